@@ -2,7 +2,7 @@ pub(crate) mod function {
     use std::{borrow::Cow, ffi::OsString};
 
     use anyhow::{bail, Context};
-    use gix::{prelude::ObjectIdExt, traverse::commit::simple::Sorting};
+    use gix::traverse::commit::simple::Sorting;
 
     use crate::OutputFormat;
 
@@ -32,19 +32,20 @@ pub(crate) mod function {
             .ancestors()
             .sorting(Sorting::ByCommitTimeNewestFirst)
             .all()?;
+        let shortener = repo.id_shortener()?;
         for commit in commits {
             let commit = commit?;
             writeln!(
                 out,
                 "{} {} {} {}",
-                commit.id().shorten_or_id(),
+                shortener.shorten_or_id(commit.id),
                 commit.commit_time.expect("traversal with date"),
                 commit.parent_ids.len(),
                 graph.commit_by_id(commit.id).map_or_else(
                     || Cow::Borrowed("<NOT IN GRAPH-CACHE>"),
                     |c| Cow::Owned(format!(
                         "{} {}",
-                        c.root_tree_id().to_owned().attach(&repo).shorten_or_id(),
+                        shortener.shorten_or_id(c.root_tree_id()),
                         c.generation()
                     ))
                 )
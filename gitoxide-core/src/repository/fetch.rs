@@ -290,6 +290,9 @@ pub(crate) mod function {
             }?;
         }
         consume_skipped_tags(&mut skipped_due_to_implicit_tag, &mut out)?;
+        for edit in &update_refs.pruned {
+            writeln!(out, "\t{} [pruned]", edit.name)?;
+        }
         if !map.fixes.is_empty() {
             writeln!(
                 err,
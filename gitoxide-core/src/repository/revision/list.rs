@@ -67,6 +67,7 @@ pub(crate) mod function {
         progress.init(None, gix::progress::count("commits"));
         progress.set_name("traverse".into());
 
+        let shortener = repo.id_shortener()?;
         let start = std::time::Instant::now();
         for commit in commits {
             if gix::interrupt::is_triggered() {
@@ -101,7 +102,7 @@ pub(crate) mod function {
                     writeln!(
                         out,
                         "{} {} {}",
-                        commit.id().shorten_or_id(),
+                        shortener.shorten_or_id(commit.id),
                         commit.commit_time.expect("traversal with date"),
                         commit.parent_ids.len()
                     )?;
@@ -36,6 +36,16 @@ pub fn decode(data: &[u8]) -> Result<(Vec, &[u8]), decode::Error> {
         buf.push(u64::from_be_bytes(bit_num.try_into().unwrap()))
     }
 
+    let mut word_index = 0;
+    while let Some(word) = buf.get(word_index) {
+        word_index += 1 + (word >> (1 + access::RLW_RUNNING_BITS)) as usize;
+    }
+    if word_index > buf.len() {
+        return Err(Error::Corrupt {
+            message: "literal words extend past the end of the bit data",
+        });
+    }
+
     let (rlw, data) = decode::u32(data).ok_or(Error::Corrupt {
         message: "eof while reading run length width",
     })?;
@@ -87,6 +97,29 @@ mod access {
             Some(())
         }
 
+        /// Return all bits uncompressed as words, with the bit at `index` being stored in `words[index / 64]` at
+        /// `1 << (index % 64)`, and trailing zero words removed.
+        ///
+        /// This is useful to perform boolean operations between multiple bitmaps.
+        pub fn to_words(&self) -> std::vec::Vec<u64> {
+            let mut out = std::vec::Vec::with_capacity((self.num_bits() + 63) / 64);
+            let mut iter = self.bits.iter();
+            while let Some(word) = iter.next() {
+                let run_word = if rlw_runbit_is_set(word) { u64::MAX } else { 0 };
+                out.extend(std::iter::repeat(run_word).take(rlw_running_len(word) as usize));
+                for _ in 0..rlw_literal_words(word) {
+                    let word = iter
+                        .next()
+                        .expect("BUG: ran out of words while going through uncompressed portion");
+                    out.push(*word);
+                }
+            }
+            while out.last() == Some(&0) {
+                out.pop();
+            }
+            out
+        }
+
         /// The amount of bits we are currently holding.
         pub fn num_bits(&self) -> usize {
             self.num_bits.try_into().expect("we are not on 16 bit systems")
@@ -113,8 +146,84 @@ mod access {
         w & 1 == 1
     }
 
-    const RLW_RUNNING_BITS: u64 = 4 * 8;
-    const RLW_LARGEST_RUNNING_COUNT: u64 = (1 << RLW_RUNNING_BITS) - 1;
+    pub(super) const RLW_RUNNING_BITS: u64 = 4 * 8;
+    pub(super) const RLW_LARGEST_RUNNING_COUNT: u64 = (1 << RLW_RUNNING_BITS) - 1;
+    pub(super) const RLW_LARGEST_LITERAL_COUNT: u64 = (1 << (64 - 1 - RLW_RUNNING_BITS)) - 1;
+}
+
+mod encode {
+    use super::{
+        access::{RLW_LARGEST_LITERAL_COUNT, RLW_LARGEST_RUNNING_COUNT, RLW_RUNNING_BITS},
+        Vec,
+    };
+
+    impl Vec {
+        /// Create a new compressed bitmap with all bits at the indices produced by `set_bits` being set, in any order.
+        ///
+        /// The amount of bits will be one past the highest set bit, just like git does it.
+        pub fn from_set_bits(set_bits: impl IntoIterator<Item = usize>) -> Self {
+            let mut words = std::vec::Vec::<u64>::new();
+            let mut num_bits = 0;
+            for bit in set_bits {
+                let word_index = bit / 64;
+                if word_index >= words.len() {
+                    words.resize(word_index + 1, 0);
+                }
+                words[word_index] |= 1 << (bit % 64);
+                num_bits = num_bits.max(bit + 1);
+            }
+
+            let mut bits = std::vec::Vec::with_capacity(words.len() + 1);
+            let mut words = words.as_slice();
+            let rlw = loop {
+                let run_bit = words.first() == Some(&u64::MAX);
+                let run_word = if run_bit { u64::MAX } else { 0 };
+                let running_len = words
+                    .iter()
+                    .take(RLW_LARGEST_RUNNING_COUNT as usize)
+                    .take_while(|w| **w == run_word)
+                    .count();
+                words = &words[running_len..];
+                let literal_len = words
+                    .iter()
+                    .take(RLW_LARGEST_LITERAL_COUNT as usize)
+                    .take_while(|w| **w != 0 && **w != u64::MAX)
+                    .count();
+
+                let rlw = bits.len();
+                bits.push(
+                    u64::from(run_bit) | ((running_len as u64) << 1) | ((literal_len as u64) << (1 + RLW_RUNNING_BITS)),
+                );
+                bits.extend_from_slice(&words[..literal_len]);
+                words = &words[literal_len..];
+                if words.is_empty() {
+                    break rlw;
+                }
+            };
+
+            Vec {
+                num_bits: num_bits.try_into().expect("we don't have more than 4 billion bits"),
+                bits,
+                rlw: rlw as u64,
+            }
+        }
+
+        /// Write this bitmap to `out` in the format understood by [`decode()`](super::decode()).
+        pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+            let num_words: u32 = self
+                .bits
+                .len()
+                .try_into()
+                .expect("we don't have more than 4 billion words");
+            out.write_all(&self.num_bits.to_be_bytes())?;
+            out.write_all(&num_words.to_be_bytes())?;
+            for word in &self.bits {
+                out.write_all(&word.to_be_bytes())?;
+            }
+            let rlw: u32 = self.rlw.try_into().expect("rlw is an offset into our words");
+            out.write_all(&rlw.to_be_bytes())
+        }
+    }
 }
 
 /// A growable collection of u64 that are seen as stream of individual bits.
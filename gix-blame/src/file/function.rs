@@ -0,0 +1,366 @@
+use std::{num::NonZeroU32, ops::Range};
+
+use bstr::{BStr, ByteSlice};
+use gix_hash::ObjectId;
+use gix_hashtable::hash_map::Entry;
+use gix_object::FindExt;
+use imara_diff::{intern::InternedInput, sources::byte_lines_with_terminator};
+
+use crate::{
+    file::{fuzzy, Error},
+    BlameEntry, Options, Outcome,
+};
+
+/// Blame the file at `file_path` as it is in the commit `suspect`, i.e. find the commit that last changed each of its lines,
+/// by walking the commits reachable from `suspect` with objects obtained from `odb`.
+///
+/// Commits are visited by descending commit time, and each of them passes the lines it didn't change on to its parents.
+/// If it has more than one parent, each parent is tried in order and receives the lines it has in common with the commit,
+/// while lines that differ from all parents are blamed on the commit.
+///
+/// Lines changed by a commit in [`ignore_revs`](Options::ignore_revs) are passed on to the most similar line in its parents
+/// instead, using the same heuristics as `git blame --ignore-rev`. Only lines without such a match remain with the ignored commit.
+///
+/// `file_path` is a slash-separated path relative to the root of the tree of `suspect`, and it is an error if it doesn't
+/// point to a file there.
+pub fn file(
+    odb: &impl gix_object::Find,
+    suspect: ObjectId,
+    file_path: &BStr,
+    options: Options,
+) -> Result<Outcome, Error> {
+    let mut buf = Vec::new();
+    let blob_id = blob_id_by_path(odb, &suspect, file_path, &mut buf)?.ok_or_else(|| Error::FileMissing {
+        file_path: file_path.to_owned(),
+        commit_id: suspect,
+    })?;
+    let blob = odb.find_blob(&blob_id, &mut buf)?.data.to_owned();
+    let num_lines = byte_lines_with_terminator(&blob).count() as u32;
+    if num_lines == 0 {
+        return Ok(Outcome {
+            entries: Vec::new(),
+            blob,
+        });
+    }
+
+    let mut state = State {
+        queue: gix_revwalk::PriorityQueue::new(),
+        suspects_by_commit: Default::default(),
+        blob_ids: Default::default(),
+    };
+    state.blob_ids.insert(suspect, Some(blob_id));
+    state.pass_to(
+        odb,
+        suspect,
+        vec![Suspect {
+            start_in_blamed_file: 0,
+            start_in_suspect: 0,
+            len: num_lines,
+        }],
+        &mut buf,
+    )?;
+
+    let mut entries = Vec::new();
+    let mut parent_buf = Vec::new();
+    while let Some(commit_id) = state.queue.pop_value() {
+        let mut suspects = state
+            .suspects_by_commit
+            .remove(&commit_id)
+            .expect("commits are queued once for each set of suspects");
+        suspects.sort_by_key(|s| s.start_in_suspect);
+        let blob_id = state.blob_ids[&commit_id].expect("only commits that have the file are queued");
+
+        let parent_ids: Vec<_> = odb.find_commit_iter(&commit_id, &mut buf)?.parent_ids().collect();
+        let mut parents = Vec::with_capacity(parent_ids.len());
+        for parent_id in parent_ids {
+            if let Some(parent_blob_id) = state.blob_id(odb, &parent_id, file_path, &mut buf)? {
+                parents.push((parent_id, parent_blob_id));
+            }
+        }
+
+        if let Some(&(parent_id, _)) = parents.iter().find(|(_, parent_blob_id)| *parent_blob_id == blob_id) {
+            state.pass_to(odb, parent_id, suspects, &mut buf)?;
+            continue;
+        }
+
+        if !parents.is_empty() {
+            let data = odb.find_blob(&blob_id, &mut buf)?.data.to_owned();
+            for (idx, &(parent_id, parent_blob_id)) in parents.iter().enumerate() {
+                if parents[..idx].iter().any(|(_, id)| *id == parent_blob_id) {
+                    continue;
+                }
+                let parent_data = odb.find_blob(&parent_blob_id, &mut parent_buf)?.data;
+                let hunks = diff(options.diff_algorithm, parent_data, &data);
+                let (passed, remaining) = split_by_hunks(suspects, &hunks, None);
+                suspects = remaining;
+                state.pass_to(odb, parent_id, passed, &mut buf)?;
+                if suspects.is_empty() {
+                    break;
+                }
+            }
+
+            if !suspects.is_empty() && options.ignore_revs.contains(&commit_id) {
+                let fingerprints = fuzzy::fingerprints(&data);
+                for &(parent_id, parent_blob_id) in &parents {
+                    let parent_data = odb.find_blob(&parent_blob_id, &mut parent_buf)?.data;
+                    let hunks = diff(options.diff_algorithm, parent_data, &data);
+                    let mut parent_fingerprints = fuzzy::fingerprints(parent_data);
+                    let guesses: Vec<_> = hunks
+                        .iter()
+                        .map(|hunk| {
+                            fuzzy::guess_line_blames(
+                                &mut parent_fingerprints,
+                                &fingerprints,
+                                hunk.before.clone(),
+                                hunk.after.clone(),
+                            )
+                        })
+                        .collect();
+                    let (passed, remaining) = split_by_hunks(suspects, &hunks, Some(&guesses));
+                    suspects = remaining;
+                    state.pass_to(odb, parent_id, passed, &mut buf)?;
+                    if suspects.is_empty() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        entries.extend(suspects.into_iter().map(|s| BlameEntry {
+            start_in_blamed_file: s.start_in_blamed_file,
+            start_in_source_file: s.start_in_suspect,
+            len: NonZeroU32::new(s.len).expect("suspects are never empty"),
+            commit_id,
+        }));
+    }
+
+    Ok(Outcome {
+        entries: coalesce(entries),
+        blob,
+    })
+}
+
+/// Lines of the blamed file that still need to be blamed, along with their position in the version of the file
+/// as it is in the commit that is suspected to have changed them.
+#[derive(Debug, Clone, Copy)]
+struct Suspect {
+    /// The first line in the blamed file.
+    start_in_blamed_file: u32,
+    /// The first line in the version of the file in the suspected commit.
+    start_in_suspect: u32,
+    len: u32,
+}
+
+struct State {
+    /// Commits with suspects, ordered by their commit time so children are processed before their parents.
+    queue: gix_revwalk::PriorityQueue<gix_date::SecondsSinceUnixEpoch, ObjectId>,
+    /// The lines each queued commit is suspected to have changed.
+    suspects_by_commit: gix_hashtable::HashMap<ObjectId, Vec<Suspect>>,
+    /// The id of the blamed file in each commit we have seen, or `None` if it doesn't exist there.
+    blob_ids: gix_hashtable::HashMap<ObjectId, Option<ObjectId>>,
+}
+
+impl State {
+    fn blob_id(
+        &mut self,
+        odb: &impl gix_object::Find,
+        commit_id: &ObjectId,
+        file_path: &BStr,
+        buf: &mut Vec<u8>,
+    ) -> Result<Option<ObjectId>, Error> {
+        Ok(match self.blob_ids.get(commit_id) {
+            Some(blob_id) => *blob_id,
+            None => {
+                let blob_id = blob_id_by_path(odb, commit_id, file_path, buf)?;
+                self.blob_ids.insert(*commit_id, blob_id);
+                blob_id
+            }
+        })
+    }
+
+    /// Make `commit_id` a suspect for `suspects`, and queue it unless it's already queued.
+    fn pass_to(
+        &mut self,
+        odb: &impl gix_object::Find,
+        commit_id: ObjectId,
+        suspects: Vec<Suspect>,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        if suspects.is_empty() {
+            return Ok(());
+        }
+        match self.suspects_by_commit.entry(commit_id) {
+            Entry::Occupied(mut entry) => entry.get_mut().extend(suspects),
+            Entry::Vacant(entry) => {
+                let time = odb.find_commit_iter(&commit_id, buf)?.committer()?.time.seconds;
+                self.queue.insert(time, commit_id);
+                entry.insert(suspects);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A change between the version of the file in a parent, `before`, and the version in its child, `after`.
+struct Hunk {
+    before: Range<u32>,
+    after: Range<u32>,
+}
+
+fn diff(algorithm: imara_diff::Algorithm, before: &[u8], after: &[u8]) -> Vec<Hunk> {
+    let input = InternedInput::new(byte_lines_with_terminator(before), byte_lines_with_terminator(after));
+    let mut hunks = Vec::new();
+    imara_diff::diff(algorithm, &input, |before, after| hunks.push(Hunk { before, after }));
+    hunks
+}
+
+/// A range of lines in the version of the file of a child, which is either unchanged in the parent, or changed by a hunk.
+enum Region {
+    /// The lines are the same in the parent, where they are at an `offset` to the lines in the child.
+    Unchanged { lines: Range<u32>, offset: i64 },
+    /// The lines were changed by the hunk at `hunk_idx`.
+    Changed { lines: Range<u32>, hunk_idx: usize },
+}
+
+impl Region {
+    fn lines(&self) -> &Range<u32> {
+        match self {
+            Region::Unchanged { lines, .. } | Region::Changed { lines, .. } => lines,
+        }
+    }
+}
+
+/// Split `suspects` of a child along `hunks` of its diff with a parent, and return the suspects to pass on to the parent,
+/// along with the suspects that remain with the child.
+///
+/// Lines that are unchanged in the parent are always passed on. Lines changed by a hunk remain unless `guesses` contains
+/// a matching line in the parent for them, with one list of guesses for each line of each hunk.
+fn split_by_hunks(
+    suspects: Vec<Suspect>,
+    hunks: &[Hunk],
+    guesses: Option<&[Vec<Option<u32>>]>,
+) -> (Vec<Suspect>, Vec<Suspect>) {
+    let mut regions = Vec::with_capacity(hunks.len() * 2 + 1);
+    let mut start = 0;
+    let mut offset = 0;
+    for (hunk_idx, hunk) in hunks.iter().enumerate() {
+        regions.push(Region::Unchanged {
+            lines: start..hunk.after.start,
+            offset: hunk.before.start as i64 - hunk.after.start as i64,
+        });
+        regions.push(Region::Changed {
+            lines: hunk.after.clone(),
+            hunk_idx,
+        });
+        start = hunk.after.end;
+        offset = hunk.before.end as i64 - hunk.after.end as i64;
+    }
+    regions.push(Region::Unchanged {
+        lines: start..u32::MAX,
+        offset,
+    });
+
+    let mut passed = Vec::new();
+    let mut remaining = Vec::new();
+    for suspect in suspects {
+        let suspect_end = suspect.start_in_suspect + suspect.len;
+        let first_region = regions.partition_point(|r| r.lines().end <= suspect.start_in_suspect);
+        for region in regions[first_region..]
+            .iter()
+            .take_while(|r| r.lines().start < suspect_end)
+        {
+            let start = region.lines().start.max(suspect.start_in_suspect);
+            let end = region.lines().end.min(suspect_end);
+            if start >= end {
+                continue;
+            }
+            let start_in_blamed_file = suspect.start_in_blamed_file + (start - suspect.start_in_suspect);
+            match region {
+                Region::Unchanged { offset, .. } => passed.push(Suspect {
+                    start_in_blamed_file,
+                    start_in_suspect: (start as i64 + offset) as u32,
+                    len: end - start,
+                }),
+                Region::Changed { lines, hunk_idx } => match guesses {
+                    None => remaining.push(Suspect {
+                        start_in_blamed_file,
+                        start_in_suspect: start,
+                        len: end - start,
+                    }),
+                    Some(guesses) => {
+                        let guesses = &guesses[*hunk_idx];
+                        let guess_of = |line: u32| guesses[(line - lines.start) as usize];
+                        let mut run_start = start;
+                        for line in start..end {
+                            let is_end_of_run = line + 1 == end
+                                || match (guess_of(line), guess_of(line + 1)) {
+                                    (Some(a), Some(b)) => a + 1 != b,
+                                    (None, None) => false,
+                                    _ => true,
+                                };
+                            if !is_end_of_run {
+                                continue;
+                            }
+                            let run = Suspect {
+                                start_in_blamed_file: start_in_blamed_file + (run_start - start),
+                                start_in_suspect: run_start,
+                                len: line + 1 - run_start,
+                            };
+                            match guess_of(run_start) {
+                                Some(start_in_parent) => passed.push(Suspect {
+                                    start_in_suspect: start_in_parent,
+                                    ..run
+                                }),
+                                None => remaining.push(run),
+                            }
+                            run_start = line + 1;
+                        }
+                    }
+                },
+            }
+        }
+    }
+    (passed, remaining)
+}
+
+/// Sort `entries` by their position in the blamed file, and merge adjacent ones of the same commit that are adjacent
+/// in the source file as well.
+fn coalesce(mut entries: Vec<BlameEntry>) -> Vec<BlameEntry> {
+    entries.sort_by_key(|e| e.start_in_blamed_file);
+    let mut out: Vec<BlameEntry> = Vec::with_capacity(entries.len());
+    for entry in entries {
+        match out.last_mut() {
+            Some(last)
+                if last.commit_id == entry.commit_id
+                    && last.start_in_blamed_file + last.len.get() == entry.start_in_blamed_file
+                    && last.start_in_source_file + last.len.get() == entry.start_in_source_file =>
+            {
+                last.len = last.len.saturating_add(entry.len.get());
+            }
+            _ => out.push(entry),
+        }
+    }
+    out
+}
+
+/// Return the id of the blob at the slash-separated `path` in the tree of `commit_id`, or `None` if there is no such blob.
+fn blob_id_by_path(
+    odb: &impl gix_object::Find,
+    commit_id: &ObjectId,
+    path: &BStr,
+    buf: &mut Vec<u8>,
+) -> Result<Option<ObjectId>, Error> {
+    let mut id = odb.find_commit_iter(commit_id, buf)?.tree_id()?;
+    let mut components = path.split_str("/").peekable();
+    while let Some(component) = components.next() {
+        let is_dir = components.peek().is_some();
+        let Some(entry) = odb.find_tree(&id, buf)?.bisect_entry(component.as_bstr(), is_dir) else {
+            return Ok(None);
+        };
+        if !is_dir && !entry.mode.is_blob_or_symlink() {
+            return Ok(None);
+        }
+        id = entry.oid.to_owned();
+    }
+    Ok(Some(id))
+}
@@ -0,0 +1,291 @@
+//! Match lines changed by an ignored commit with the lines they most likely originate from in its parent,
+//! using the same heuristics as [`git`](https://github.com/git/git/blob/v2.45.0/blame.c#L330).
+use std::ops::Range;
+
+use imara_diff::sources::byte_lines_with_terminator;
+
+/// A multi-set of pairs of adjacent characters in a line, with whitespace normalized and letters lower-cased.
+#[derive(Debug, Clone, Default)]
+pub(super) struct Fingerprint(Vec<(u16, u32)>);
+
+impl Fingerprint {
+    fn from_line(line: &[u8]) -> Self {
+        let normalize = |b: u8| {
+            // `\x0b` is whitespace to C's `isspace()`, but not to Rust.
+            if b.is_ascii_whitespace() || b == b'\x0b' {
+                0
+            } else {
+                b.to_ascii_lowercase() as u16
+            }
+        };
+        let mut pairs = Vec::with_capacity(line.len() + 1);
+        let mut previous = 0;
+        for current in line.iter().copied().map(normalize).chain(Some(0)) {
+            let pair = previous | (current << 8);
+            previous = current;
+            if pair != 0 {
+                pairs.push(pair);
+            }
+        }
+        pairs.sort_unstable();
+
+        let mut out: Vec<(u16, u32)> = Vec::with_capacity(pairs.len());
+        for pair in pairs {
+            match out.last_mut() {
+                Some((last, count)) if *last == pair => *count += 1,
+                _ => out.push((pair, 1)),
+            }
+        }
+        Fingerprint(out)
+    }
+
+    /// Return the amount of pairs that `self` and `other` have in common.
+    fn similarity(&self, other: &Fingerprint) -> u32 {
+        let (mut a, mut b) = (self.0.iter().peekable(), other.0.iter().peekable());
+        let mut out = 0;
+        while let (Some((pair_a, count_a)), Some((pair_b, count_b))) = (a.peek(), b.peek()) {
+            match pair_a.cmp(pair_b) {
+                std::cmp::Ordering::Less => {
+                    a.next();
+                }
+                std::cmp::Ordering::Greater => {
+                    b.next();
+                }
+                std::cmp::Ordering::Equal => {
+                    out += count_a.min(count_b);
+                    a.next();
+                    b.next();
+                }
+            }
+        }
+        out
+    }
+
+    /// Remove all pairs of `other` from `self`, so they can't be matched again.
+    fn subtract(&mut self, other: &Fingerprint) {
+        self.0.retain_mut(|(pair, count)| {
+            if let Ok(idx) = other.0.binary_search_by_key(pair, |(pair, _)| *pair) {
+                let other_count = other.0[idx].1;
+                if *count <= other_count {
+                    return false;
+                }
+                *count -= other_count;
+            }
+            true
+        })
+    }
+}
+
+/// Return the fingerprints of all lines in `data`.
+pub(super) fn fingerprints(data: &[u8]) -> Vec<Fingerprint> {
+    byte_lines_with_terminator(data).map(Fingerprint::from_line).collect()
+}
+
+/// For each line in `after` of the version of the file in a child, return the line in its parent it most likely originates from,
+/// or `None` if there is no similar line, with `before` being the lines the parent has in place of `after`.
+///
+/// Lines are first matched with the lines in `before`, and lines without a match are then matched with any line in the parent.
+/// The fingerprints of the parent lines in `parent` are updated so that the same parts of a line aren't matched twice.
+pub(super) fn guess_line_blames(
+    parent: &mut [Fingerprint],
+    child: &[Fingerprint],
+    before: Range<u32>,
+    after: Range<u32>,
+) -> Vec<Option<u32>> {
+    let matches = find_matching_lines(parent, child, before, after.clone());
+    after
+        .enumerate()
+        .map(|(idx, line)| {
+            matches
+                .as_ref()
+                .and_then(|matches| matches[idx])
+                .or_else(|| scan_parent(parent, &child[line as usize], line))
+        })
+        .collect()
+}
+
+/// Return the line in `parent` that is most similar to `line`, preferring the one closest to `line_idx`, but only
+/// if it is similar enough.
+fn scan_parent(parent: &[Fingerprint], line: &Fingerprint, line_idx: u32) -> Option<u32> {
+    const MIN_SIMILARITY: u32 = 10;
+    let mut best: Option<(u32, u32)> = None;
+    for (idx, candidate) in parent.iter().enumerate() {
+        let idx = idx as u32;
+        let similarity = line.similarity(candidate);
+        let best_similarity = best.map_or(MIN_SIMILARITY, |(similarity, _)| similarity);
+        if similarity < best_similarity {
+            continue;
+        }
+        if let Some((_, best_idx)) = best {
+            if similarity == best_similarity && best_idx.abs_diff(line_idx) < idx.abs_diff(line_idx) {
+                continue;
+            }
+        }
+        best = Some((similarity, idx));
+    }
+    best.map(|(_, idx)| idx)
+}
+
+const CERTAINTY_NOT_CALCULATED: i64 = -1;
+const CERTAIN_NOTHING_MATCHES: i64 = -2;
+
+/// Match lines in `after` of `child` with lines in `before` of `parent`, or return `None` if `before` is empty.
+///
+/// Lines are matched by similarity, weighted by how close they are to the position the line would have if `after` was
+/// stretched to the size of `before`. The line with the most certain match is matched first, and the lines before and after it
+/// are then matched recursively with the lines before and after its match, so that matches are never out of order.
+fn find_matching_lines(
+    parent: &mut [Fingerprint],
+    child: &[Fingerprint],
+    before: Range<u32>,
+    after: Range<u32>,
+) -> Option<Vec<Option<u32>>> {
+    let (start_a, len_a) = (before.start as i64, before.len() as i64);
+    let (start_b, len_b) = (after.start as i64, after.len() as i64);
+    if len_a == 0 {
+        return None;
+    }
+    let max_distance_a = 10.min(len_a - 1);
+    let max_distance_b = ((2 * max_distance_a + 1) * len_b - 1) / len_a;
+
+    let mut state = Matcher {
+        parent,
+        child,
+        start_b,
+        len_a,
+        len_b,
+        start_a,
+        max_distance_a,
+        max_distance_b,
+        similarities: vec![-1; (len_b * (2 * max_distance_a + 1)) as usize],
+        certainties: vec![CERTAINTY_NOT_CALCULATED; len_b as usize],
+        second_best: vec![-1; len_b as usize],
+        best: vec![-1; len_b as usize],
+    };
+    state.find(start_a, start_b, len_a, len_b);
+    Some(
+        state
+            .best
+            .into_iter()
+            .map(|line| (line >= 0).then_some(line as u32))
+            .collect(),
+    )
+}
+
+/// The state of matching the lines of a hunk, with all line numbers being absolute, and all per-line data
+/// indexed by the position of the line in the hunk's child lines.
+struct Matcher<'a> {
+    parent: &'a mut [Fingerprint],
+    child: &'a [Fingerprint],
+    start_a: i64,
+    len_a: i64,
+    start_b: i64,
+    len_b: i64,
+    max_distance_a: i64,
+    max_distance_b: i64,
+    /// The weighted similarity of each child line with the parent lines around its closest line, or -1 if unknown.
+    similarities: Vec<i64>,
+    certainties: Vec<i64>,
+    second_best: Vec<i64>,
+    best: Vec<i64>,
+}
+
+impl Matcher<'_> {
+    /// The parent line that is closest to child `line` if the child lines were stretched to the size of the parent lines.
+    fn closest_line_a(&self, line: i64) -> i64 {
+        ((line - self.start_b) * 2 + 1) * self.len_a / (self.len_b * 2) + self.start_a
+    }
+
+    fn similarity_idx(&self, line_a: i64, line_b: i64) -> usize {
+        ((line_b - self.start_b) * (2 * self.max_distance_a + 1) + line_a - self.closest_line_a(line_b)
+            + self.max_distance_a) as usize
+    }
+
+    /// Find the best and second best match for child `line_b` among the parent lines starting at `start_a`, unless known.
+    fn find_best_match(&mut self, start_a: i64, len_a: i64, line_b: i64) {
+        let idx_b = (line_b - self.start_b) as usize;
+        if self.certainties[idx_b] != CERTAINTY_NOT_CALCULATED {
+            return;
+        }
+        let closest = self.closest_line_a(line_b);
+        let search =
+            (closest - self.max_distance_a).max(start_a)..(closest + self.max_distance_a + 1).min(start_a + len_a);
+
+        let (mut best, mut best_line) = (0, start_a);
+        let (mut second_best, mut second_best_line) = (0, start_a);
+        for line_a in search {
+            let idx = self.similarity_idx(line_a, line_b);
+            if self.similarities[idx] == -1 {
+                self.similarities[idx] = self.child[line_b as usize].similarity(&self.parent[line_a as usize]) as i64
+                    * (1000 - (line_a - closest).abs());
+            }
+            let similarity = self.similarities[idx];
+            if similarity > best {
+                (second_best, second_best_line) = (best, best_line);
+                (best, best_line) = (similarity, line_a);
+            } else if similarity > second_best {
+                (second_best, second_best_line) = (similarity, line_a);
+            }
+        }
+
+        if best == 0 {
+            self.certainties[idx_b] = CERTAIN_NOTHING_MATCHES;
+            self.best[idx_b] = -1;
+        } else {
+            self.certainties[idx_b] = best * 2 - second_best;
+            self.best[idx_b] = best_line;
+            self.second_best[idx_b] = second_best_line;
+        }
+    }
+
+    fn find(&mut self, start_a: i64, start_b: i64, len_a: i64, len_b: i64) {
+        let mut most_certain: Option<(i64, i64)> = None;
+        for line_b in start_b..start_b + len_b {
+            self.find_best_match(start_a, len_a, line_b);
+            let certainty = self.certainties[(line_b - self.start_b) as usize];
+            if certainty > most_certain.map_or(-1, |(certainty, _)| certainty) {
+                most_certain = Some((certainty, line_b));
+            }
+        }
+        let Some((_, line_b)) = most_certain else {
+            return;
+        };
+        let idx_b = line_b - self.start_b;
+        let line_a = self.best[idx_b as usize];
+        self.parent[line_a as usize].subtract(&self.child[line_b as usize]);
+
+        // Forget everything that may have been affected by the changed fingerprint, or by the match itself.
+        let invalidate =
+            (line_b - self.max_distance_b).max(start_b)..(line_b + self.max_distance_b + 1).min(start_b + len_b);
+        for other_b in invalidate.clone() {
+            if (line_a - self.closest_line_a(other_b)).abs() <= self.max_distance_a {
+                let idx = self.similarity_idx(line_a, other_b);
+                self.similarities[idx] = -1;
+            }
+        }
+        for other_b in invalidate.start..line_b {
+            let idx = (other_b - self.start_b) as usize;
+            if self.certainties[idx] >= 0 && (self.best[idx] >= line_a || self.second_best[idx] >= line_a) {
+                self.certainties[idx] = CERTAINTY_NOT_CALCULATED;
+            }
+        }
+        for other_b in line_b + 1..invalidate.end {
+            let idx = (other_b - self.start_b) as usize;
+            if self.certainties[idx] >= 0 && (self.best[idx] <= line_a || self.second_best[idx] <= line_a) {
+                self.certainties[idx] = CERTAINTY_NOT_CALCULATED;
+            }
+        }
+
+        if line_b > start_b {
+            self.find(start_a, start_b, line_a + 1 - start_a, line_b - start_b);
+        }
+        if line_b + 1 < start_b + len_b {
+            self.find(
+                line_a,
+                line_b + 1,
+                start_a + len_a - line_a,
+                start_b + len_b - line_b - 1,
+            );
+        }
+    }
+}
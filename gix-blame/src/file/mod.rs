@@ -0,0 +1,19 @@
+use bstr::BString;
+use gix_hash::ObjectId;
+
+/// The error returned by [`file()`](crate::file()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The file '{file_path}' does not exist in commit {commit_id}")]
+    FileMissing { file_path: BString, commit_id: ObjectId },
+    #[error(transparent)]
+    FindObject(#[from] gix_object::find::existing_object::Error),
+    #[error(transparent)]
+    FindObjectIter(#[from] gix_object::find::existing_iter::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+}
+
+pub(super) mod function;
+mod fuzzy;
@@ -0,0 +1,42 @@
+use bstr::{BString, ByteSlice};
+use gix_hash::ObjectId;
+
+/// The error returned by [`parse()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Line {line_number} does not contain a full {object_hash} object id: '{line}'")]
+    InvalidObjectId {
+        line_number: usize,
+        line: BString,
+        object_hash: gix_hash::Kind,
+    },
+}
+
+/// Parse `input`, the content of a file like the ones configured with `blame.ignoreRevsFile`, into the ids of the
+/// revisions to ignore, with `object_hash` being the kind of hash used in the repository.
+///
+/// Each line contains one full hexadecimal object id and may be surrounded by whitespace. Everything after a `#` is a comment,
+/// and lines that are empty without it are skipped. Anything else is an error, just like it is for `git`.
+///
+/// Note that the ids aren't validated any further. `git` peels them to commits and ignores those that can't be peeled.
+pub fn parse(input: &[u8], object_hash: gix_hash::Kind) -> Result<Vec<ObjectId>, Error> {
+    let mut out = Vec::new();
+    for (line_idx, line) in input.lines().enumerate() {
+        let line = line.find_byte(b'#').map_or(line, |pos| &line[..pos]).trim();
+        if line.is_empty() {
+            continue;
+        }
+        match ObjectId::from_hex(line) {
+            Ok(id) if id.kind() == object_hash => out.push(id),
+            _ => {
+                return Err(Error::InvalidObjectId {
+                    line_number: line_idx + 1,
+                    line: line.into(),
+                    object_hash,
+                })
+            }
+        }
+    }
+    Ok(out)
+}
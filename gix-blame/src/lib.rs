@@ -0,0 +1,68 @@
+//! Blame a file, i.e. find the commit that last changed each of its lines, similar to `git blame`.
+//!
+//! ### Deviation
+//!
+//! * Renames aren't followed, so the history of a file ends in the commit that added it at its current path.
+//! * Only a single path is tracked, so lines moved or copied from other files are blamed on the commit that moved them.
+//! * Ambiguous changes may be placed differently in the diff than `git` places them, so lines within them can be blamed differently.
+#![deny(rust_2018_idioms, missing_docs)]
+#![forbid(unsafe_code)]
+
+use std::num::NonZeroU32;
+
+use gix_hash::ObjectId;
+
+///
+#[allow(clippy::empty_docs)]
+pub mod file;
+pub use file::function::file;
+
+///
+#[allow(clippy::empty_docs)]
+pub mod ignore_revs;
+
+/// Options to control how [`file()`] assigns blame.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// Commits whose changes shouldn't be blamed on them, like `git blame --ignore-rev`.
+    ///
+    /// Lines changed by one of these commits are attributed to the most similar line in its parent instead,
+    /// and only remain with the commit if no such line could be found.
+    pub ignore_revs: gix_hashtable::HashSet<ObjectId>,
+    /// The algorithm to use when diffing each version of the file with the version in its parents.
+    ///
+    /// Defaults to [`imara_diff::Algorithm::Myers`].
+    pub diff_algorithm: imara_diff::Algorithm,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            ignore_revs: Default::default(),
+            diff_algorithm: imara_diff::Algorithm::Myers,
+        }
+    }
+}
+
+/// The outcome of [`file()`].
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// Ranges of lines in the blamed file along with the commit that last changed them, sorted by their position
+    /// in the blamed file and covering each of its lines exactly once.
+    pub entries: Vec<BlameEntry>,
+    /// The content of the blamed file.
+    pub blob: Vec<u8>,
+}
+
+/// A range of consecutive lines in the blamed file that were last changed by the same commit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlameEntry {
+    /// The 0-based index of the first line of this range in the blamed file.
+    pub start_in_blamed_file: u32,
+    /// The 0-based index of the first line of this range in the file as it was in [`commit_id`](Self::commit_id).
+    pub start_in_source_file: u32,
+    /// The amount of lines in this range.
+    pub len: NonZeroU32,
+    /// The commit that last changed the lines in this range.
+    pub commit_id: ObjectId,
+}
@@ -0,0 +1,158 @@
+use std::path::Path;
+
+use gix_blame::{BlameEntry, Options};
+use gix_hash::ObjectId;
+use gix_testtools::bstr::ByteSlice;
+
+#[test]
+fn without_ignored_revisions() -> crate::Result {
+    let (dir, odb) = fixture()?;
+    let head = rev(&dir, "rename")?;
+    assert_blame_matches_baseline(&odb, head, "file.rs", Options::default(), &dir.join("file.baseline"))?;
+    assert_blame_matches_baseline(
+        &odb,
+        head,
+        "nested/dir/file.rs",
+        Options::default(),
+        &dir.join("nested.baseline"),
+    )?;
+    assert_blame_matches_baseline(
+        &odb,
+        rev(&dir, "merge")?,
+        "file.rs",
+        Options::default(),
+        &dir.join("file-at-merge.baseline"),
+    )
+}
+
+#[test]
+fn ignored_revisions_pass_their_lines_to_similar_lines_in_their_parents() -> crate::Result {
+    let (dir, odb) = fixture()?;
+    let head = rev(&dir, "rename")?;
+    assert_blame_matches_baseline(
+        &odb,
+        head,
+        "file.rs",
+        ignoring(&dir, &["reformat"])?,
+        &dir.join("file-ignore-reformat.baseline"),
+    )?;
+    assert_blame_matches_baseline(
+        &odb,
+        rev(&dir, "c4")?,
+        "file.rs",
+        ignoring(&dir, &["merge", "reformat"])?,
+        &dir.join("file-at-c4-ignore-merge-and-reformat.baseline"),
+    )
+}
+
+#[test]
+fn ignored_merges_pass_their_lines_to_all_parents() -> crate::Result {
+    let (dir, odb) = fixture()?;
+    let merge = rev(&dir, "merge")?;
+    assert_blame_matches_baseline(
+        &odb,
+        merge,
+        "file.rs",
+        ignoring(&dir, &["merge"])?,
+        &dir.join("file-at-merge-ignore-merge.baseline"),
+    )
+}
+
+#[test]
+fn ignored_revisions_from_file() -> crate::Result {
+    let (dir, odb) = fixture()?;
+    let options = Options {
+        ignore_revs: gix_blame::ignore_revs::parse(&std::fs::read(dir.join("ignore-revs"))?, gix_hash::Kind::Sha1)?
+            .into_iter()
+            .collect(),
+        ..Default::default()
+    };
+    assert_blame_matches_baseline(
+        &odb,
+        rev(&dir, "rename")?,
+        "file.rs",
+        options,
+        &dir.join("file-ignore-revs-file.baseline"),
+    )
+}
+
+#[test]
+fn missing_file_is_an_error() -> crate::Result {
+    let (dir, odb) = fixture()?;
+    let head = rev(&dir, "rename")?;
+    for path in ["does-not-exist", "nested/dir", "nested/file.rs", "file.rs/child"] {
+        let err = gix_blame::file(&odb, head, path.into(), Options::default()).unwrap_err();
+        assert!(
+            matches!(err, gix_blame::file::Error::FileMissing { ref file_path, commit_id } if file_path == path && commit_id == head),
+            "{err:?}"
+        );
+    }
+    Ok(())
+}
+
+fn fixture() -> crate::Result<(std::path::PathBuf, gix_odb::Handle)> {
+    let dir = gix_testtools::scripted_fixture_read_only("make_blame_repo.sh")?;
+    let odb = gix_odb::at(dir.join(".git/objects"))?;
+    Ok((dir, odb))
+}
+
+/// Return the commit that `name` was recorded as by the fixture script.
+fn rev(dir: &Path, name: &str) -> crate::Result<ObjectId> {
+    Ok(ObjectId::from_hex(std::fs::read(dir.join(".git").join(name))?.trim())?)
+}
+
+fn ignoring(dir: &Path, names: &[&str]) -> crate::Result<Options> {
+    Ok(Options {
+        ignore_revs: names.iter().map(|name| rev(dir, name)).collect::<Result<_, _>>()?,
+        ..Default::default()
+    })
+}
+
+fn assert_blame_matches_baseline(
+    odb: &gix_odb::Handle,
+    suspect: ObjectId,
+    file_path: &str,
+    options: Options,
+    baseline: &Path,
+) -> crate::Result {
+    let outcome = gix_blame::file(odb, suspect, file_path.into(), options)?;
+    let expected = parse_baseline(baseline)?;
+    assert_eq!(outcome.blob.lines().count(), expected.len(), "the whole file is blamed");
+    assert_eq!(lines(&outcome.entries), expected, "{}", baseline.display());
+    Ok(())
+}
+
+/// Return the commit and the 0-based line in its version of the file for each line of the blamed file.
+fn lines(entries: &[BlameEntry]) -> Vec<(ObjectId, u32)> {
+    let mut out = Vec::new();
+    for entry in entries {
+        assert_eq!(
+            entry.start_in_blamed_file as usize,
+            out.len(),
+            "entries are sorted and contiguous"
+        );
+        out.extend((0..entry.len.get()).map(|offset| (entry.commit_id, entry.start_in_source_file + offset)));
+    }
+    out
+}
+
+/// Parse the output of `git blame --porcelain` in the same format as [`lines()`].
+fn parse_baseline(path: &Path) -> crate::Result<Vec<(ObjectId, u32)>> {
+    let mut out = Vec::new();
+    for line in std::fs::read(path)?.lines() {
+        if line.starts_with(b"\t") {
+            continue;
+        }
+        let mut tokens = line.split_str(" ");
+        let (Some(id), Some(source_line), Some(blamed_line)) = (tokens.next(), tokens.next(), tokens.next()) else {
+            continue;
+        };
+        let Ok(id) = ObjectId::from_hex(id) else {
+            continue;
+        };
+        let blamed_line: usize = blamed_line.to_str()?.parse()?;
+        assert_eq!(blamed_line, out.len() + 1, "lines are listed in order");
+        out.push((id, source_line.to_str()?.parse::<u32>()? - 1));
+    }
+    Ok(out)
+}
@@ -0,0 +1,147 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function tick () {
+  if test -z "${tick+set}"
+  then
+    tick=1112911993
+  else
+    tick=$(($tick + 60))
+  fi
+  GIT_COMMITTER_DATE="$tick -0700"
+  GIT_AUTHOR_DATE="$tick -0700"
+  export GIT_COMMITTER_DATE GIT_AUTHOR_DATE
+}
+
+function commit() {
+  tick
+  git add -A
+  git commit -q -m "$1"
+  git rev-parse HEAD > ".git/$1"
+}
+
+git init -q
+git checkout -q -b main
+
+mkdir -p nested/dir
+cat <<'EOF' >file.rs
+fn main() {
+    let numbers = vec![1, 2, 3];
+    let total: i32 = numbers.iter().sum();
+    println!("total: {}", total);
+}
+
+fn helper(value: i32) -> i32 {
+    value * 2
+}
+EOF
+cp file.rs nested/dir/file.rs
+commit c1
+
+cat <<'EOF' >file.rs
+fn main() {
+    let numbers = vec![1, 2, 3, 4];
+    let total: i32 = numbers.iter().sum();
+    println!("the total is: {}", total);
+}
+
+fn helper(value: i32) -> i32 {
+    value * 2
+}
+
+fn unrelated() {}
+EOF
+commit c2
+
+git checkout -q -b feature
+cat <<'EOF' >file.rs
+fn main() {
+    let numbers = vec![1, 2, 3, 4];
+    let total: i32 = numbers.iter().sum();
+    println!("the total is: {}", total);
+}
+
+fn helper(value: i32) -> i32 {
+    value * 3
+}
+
+fn unrelated() {}
+EOF
+commit f1
+
+git checkout -q main
+echo "// a comment" >> nested/dir/file.rs
+sed -i.bak 's/fn unrelated() {}/fn unrelated() { }/' file.rs && rm file.rs.bak
+commit c3
+
+# A merge that also changes a line that neither of its parents changed.
+tick
+git merge -q --no-ff --no-commit feature
+sed -i.bak 's/let total: i32/let total: i64/' file.rs && rm file.rs.bak
+git commit -q -am "merge feature"
+git rev-parse HEAD > .git/merge
+
+# A reformatting commit that also changes a line and adds a new one.
+cat <<'EOF' >file.rs
+fn main()
+{
+  let numbers = vec![1, 2, 3, 4];
+  let total: i64 = numbers.iter().sum();
+  println!("the total is: {}", total);
+  println!("a brand new line that is entirely different");
+}
+
+fn helper(value: i32) -> i32
+{
+  value * 3
+}
+
+fn unrelated() {}
+EOF
+commit reformat
+
+cat <<'EOF' >file.rs
+fn main()
+{
+  let numbers = vec![1, 2, 3, 4, 5];
+  let total: i64 = numbers.iter().sum();
+  println!("the total is: {}", total);
+  println!("a brand new line that is entirely different");
+}
+
+fn helper(value: i32) -> i32
+{
+  value * 3
+}
+
+fn unrelated() {}
+EOF
+commit c4
+
+# A commit that renames a variable on multiple lines, and moves a function.
+cat <<'EOF' >file.rs
+fn unrelated() {}
+
+fn main()
+{
+  let values = vec![1, 2, 3, 4, 5];
+  let total: i64 = values.iter().sum();
+  println!("the total is: {}", total);
+  println!("a brand new line that is entirely different");
+}
+
+fn helper(value: i32) -> i32
+{
+  value * 3
+}
+EOF
+commit rename
+
+printf '%s\n' "# formatting only" "$(cat .git/reformat)" "" "  $(cat .git/rename)  # trailing comment" > ignore-revs
+git blame --porcelain file.rs > file.baseline
+git blame --porcelain --ignore-rev "$(cat .git/reformat)" file.rs > file-ignore-reformat.baseline
+git blame --porcelain --ignore-revs-file ignore-revs file.rs > file-ignore-revs-file.baseline
+git blame --porcelain "$(cat .git/merge)" -- file.rs > file-at-merge.baseline
+git blame --porcelain --ignore-rev "$(cat .git/merge)" "$(cat .git/merge)" -- file.rs > file-at-merge-ignore-merge.baseline
+git blame --porcelain --ignore-rev "$(cat .git/merge)" --ignore-rev "$(cat .git/reformat)" "$(cat .git/c4)" -- file.rs > file-at-c4-ignore-merge-and-reformat.baseline
+git blame --porcelain nested/dir/file.rs > nested.baseline
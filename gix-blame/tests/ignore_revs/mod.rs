@@ -0,0 +1,35 @@
+use gix_blame::ignore_revs::{parse, Error};
+use gix_hash::ObjectId;
+
+#[test]
+fn comments_whitespace_and_empty_lines_are_skipped() -> crate::Result {
+    let input = b"# a comment\n\n  2b5a351f9da7016f424dcadab1650e5896fe9b88  \n\t61fc4097d0a2f710d71e579b5a118a41aaa3a231 # reason\r\n   \n#\n";
+    assert_eq!(
+        parse(input, gix_hash::Kind::Sha1)?,
+        vec![
+            ObjectId::from_hex(b"2b5a351f9da7016f424dcadab1650e5896fe9b88")?,
+            ObjectId::from_hex(b"61fc4097d0a2f710d71e579b5a118a41aaa3a231")?,
+        ]
+    );
+    assert!(parse(b"", gix_hash::Kind::Sha1)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn anything_but_full_object_ids_is_an_error() {
+    for (input, expected_line_number) in [
+        (&b"2b5a351f"[..], 1),
+        (b"# comment\nHEAD", 2),
+        (
+            b"2b5a351f9da7016f424dcadab1650e5896fe9b88 61fc4097d0a2f710d71e579b5a118a41aaa3a231",
+            1,
+        ),
+        (b"\n\n2b5a351f9da7016f424dcadab1650e5896fe9b8g", 3),
+    ] {
+        let err = parse(input, gix_hash::Kind::Sha1).unwrap_err();
+        assert!(
+            matches!(err, Error::InvalidObjectId { line_number, .. } if line_number == expected_line_number),
+            "{err:?}"
+        );
+    }
+}
@@ -0,0 +1,27 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use gix_chunk::file::Index;
+use libfuzzer_sys::fuzz_target;
+use std::hint::black_box;
+
+#[derive(Debug, Arbitrary)]
+struct Ctx<'a> {
+    toc_offset: u8,
+    num_chunks: u8,
+    data: &'a [u8],
+}
+
+fn fuzz(ctx: Ctx<'_>) {
+    let Ok(index) = Index::from_bytes(ctx.data, ctx.toc_offset as usize, ctx.num_chunks as u32) else {
+        return;
+    };
+    assert!(index.highest_offset() <= ctx.data.len() as u64);
+    for id in [*b"OIDF", *b"OIDL", *b"CDAT", *b"PNAM", *b"OOFF"] {
+        _ = black_box(index.data_by_id(ctx.data, id));
+    }
+}
+
+fuzz_target!(|ctx: Ctx<'_>| {
+    fuzz(ctx);
+});
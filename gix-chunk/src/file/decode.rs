@@ -14,6 +14,8 @@ mod error {
             offset: crate::file::Offset,
             file_length: u64,
         },
+        #[error("The chunk offset {offset} points into the table of contents which ends at {toc_end}")]
+        ChunkOffsetInToc { offset: crate::file::Offset, toc_end: u64 },
         #[error("All chunk offsets must be incrementing.")]
         NonIncrementalChunkOffsets,
         #[error("The chunk of kind {:?} was encountered more than once", std::str::from_utf8(kind.as_ref()).unwrap_or("<non-ascii>"))]
@@ -38,7 +40,7 @@ impl file::Index {
 
         let data_len: u64 = data.len() as u64;
         let mut chunks = Vec::with_capacity(num_chunks as usize);
-        let mut toc_entry = &data[toc_offset..];
+        let mut toc_entry = data.get(toc_offset..).unwrap_or_default();
         let expected_min_size = (num_chunks as usize + 1) * file::Index::ENTRY_SIZE;
         if toc_entry.len() < expected_min_size {
             return Err(Error::TocTooSmall {
@@ -46,6 +48,8 @@ impl file::Index {
                 actual: toc_entry.len(),
             });
         }
+        // Chunks can't overlap with the table of contents, which is followed by the first chunk.
+        let toc_end = (toc_offset + expected_min_size) as u64;
 
         for _ in 0..num_chunks {
             let (kind, offset) = toc_entry.split_at(4);
@@ -58,6 +62,9 @@ impl file::Index {
             }
 
             let offset = be_u64(offset);
+            if offset < toc_end {
+                return Err(Error::ChunkOffsetInToc { offset, toc_end });
+            }
             if offset > data_len {
                 return Err(Error::ChunkSizeOutOfBounds {
                     offset,
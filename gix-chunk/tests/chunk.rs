@@ -0,0 +1,175 @@
+use std::io::Write;
+
+use gix_chunk::file::{decode::Error, Index};
+
+const HEADER_LEN: usize = 8;
+const NUM_CHUNKS: u32 = 3;
+
+/// Write a file with a header, a table of contents and three chunks, one of which has an id unknown to readers.
+fn file() -> Vec<u8> {
+    let mut out = b"HEADER!!".to_vec();
+    let mut index = Index::for_writing();
+    index.plan_chunk(*b"AAAA", 4);
+    index.plan_chunk(*b"?UNK", 2);
+    index.plan_chunk(*b"BBBB", 3);
+    let mut chunks = index
+        .into_write(&mut out, HEADER_LEN)
+        .expect("in-memory writes succeed");
+    while let Some(id) = chunks.next_chunk() {
+        let data: &[u8] = match &id {
+            b"AAAA" => b"aaaa",
+            b"?UNK" => b"??",
+            b"BBBB" => b"bbb",
+            _ => unreachable!("only planned chunks are written"),
+        };
+        chunks.write_all(data).unwrap();
+    }
+    out.extend_from_slice(b"TRAILING-CHECKSUM");
+    out
+}
+
+fn toc_entry_offset(entry: usize) -> usize {
+    HEADER_LEN + entry * Index::ENTRY_SIZE + 4
+}
+
+fn set_offset(data: &mut [u8], entry: usize, offset: u64) {
+    let pos = toc_entry_offset(entry);
+    data[pos..pos + 8].copy_from_slice(&offset.to_be_bytes());
+}
+
+fn get_offset(data: &[u8], entry: usize) -> u64 {
+    let pos = toc_entry_offset(entry);
+    u64::from_be_bytes(data[pos..pos + 8].try_into().unwrap())
+}
+
+#[test]
+fn written_chunks_can_be_read_by_id_including_unknown_ones() -> Result<(), Box<dyn std::error::Error>> {
+    let data = file();
+    let index = Index::from_bytes(&data, HEADER_LEN, NUM_CHUNKS)?;
+    assert_eq!(index.data_by_id(&data, *b"AAAA")?, b"aaaa");
+    assert_eq!(index.data_by_id(&data, *b"BBBB")?, b"bbb");
+    assert_eq!(
+        index.data_by_id(&data, *b"?UNK")?,
+        b"??",
+        "ids aren't interpreted, so unknown ones are available as well"
+    );
+    assert!(
+        index.data_by_id(&data, *b"CCCC").is_err(),
+        "missing chunks are an error"
+    );
+    assert_eq!(
+        index.highest_offset() as usize,
+        data.len() - b"TRAILING-CHECKSUM".len(),
+        "the trailer follows the last chunk"
+    );
+    Ok(())
+}
+
+mod malformed_toc {
+    use super::*;
+
+    #[test]
+    fn toc_past_the_end_of_the_data() {
+        let data = file();
+        assert!(matches!(
+            Index::from_bytes(&data, data.len() + 1, NUM_CHUNKS),
+            Err(Error::TocTooSmall { actual: 0, .. })
+        ));
+        assert!(matches!(
+            Index::from_bytes(&data[..HEADER_LEN + 10], HEADER_LEN, NUM_CHUNKS),
+            Err(Error::TocTooSmall { actual: 10, .. })
+        ));
+    }
+
+    #[test]
+    fn no_chunks() {
+        assert!(matches!(Index::from_bytes(&file(), HEADER_LEN, 0), Err(Error::Empty)));
+    }
+
+    #[test]
+    fn sentinel_too_early_or_missing() {
+        let mut data = file();
+        let second_id = HEADER_LEN + Index::ENTRY_SIZE;
+        data[second_id..second_id + 4].copy_from_slice(&gix_chunk::SENTINEL);
+        assert!(matches!(
+            Index::from_bytes(&data, HEADER_LEN, NUM_CHUNKS),
+            Err(Error::EarlySentinelValue)
+        ));
+        let data = file();
+        assert!(matches!(
+            Index::from_bytes(&data, HEADER_LEN, NUM_CHUNKS - 1),
+            Err(Error::MissingSentinelValue { actual }) if &actual == b"BBBB"
+        ));
+    }
+
+    #[test]
+    fn duplicate_ids() {
+        let mut data = file();
+        let second_id = HEADER_LEN + Index::ENTRY_SIZE;
+        data[second_id..second_id + 4].copy_from_slice(b"AAAA");
+        assert!(matches!(
+            Index::from_bytes(&data, HEADER_LEN, NUM_CHUNKS),
+            Err(Error::DuplicateChunk { kind }) if &kind == b"AAAA"
+        ));
+    }
+
+    #[test]
+    fn offsets_that_are_not_increasing() {
+        let mut data = file();
+        let first = get_offset(&data, 0);
+        set_offset(&mut data, 1, first);
+        assert!(matches!(
+            Index::from_bytes(&data, HEADER_LEN, NUM_CHUNKS),
+            Err(Error::NonIncrementalChunkOffsets)
+        ));
+    }
+
+    #[test]
+    fn offsets_pointing_into_the_toc() {
+        let mut data = file();
+        set_offset(&mut data, 0, HEADER_LEN as u64);
+        assert!(matches!(
+            Index::from_bytes(&data, HEADER_LEN, NUM_CHUNKS),
+            Err(Error::ChunkOffsetInToc { offset: 8, toc_end: 56 })
+        ));
+    }
+
+    #[test]
+    fn offsets_past_the_end_of_the_data() {
+        let mut data = file();
+        let past_the_end = data.len() as u64 + 1;
+        set_offset(&mut data, NUM_CHUNKS as usize, past_the_end);
+        assert!(matches!(
+            Index::from_bytes(&data, HEADER_LEN, NUM_CHUNKS),
+            Err(Error::ChunkSizeOutOfBounds { .. })
+        ));
+    }
+
+    #[test]
+    fn arbitrary_corruptions_never_panic() {
+        let original = file();
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        for _ in 0..10_000 {
+            let mut data = original.clone();
+            for _ in 0..(next() % 4 + 1) {
+                let pos = next() as usize % data.len();
+                data[pos] = next() as u8;
+            }
+            data.truncate(next() as usize % (data.len() + 1));
+            let toc_offset = next() as usize % (data.len() + 2);
+            let num_chunks = (next() % 6) as u32;
+            if let Ok(index) = Index::from_bytes(&data, toc_offset, num_chunks) {
+                for id in [*b"AAAA", *b"?UNK", *b"BBBB"] {
+                    let _ = index.data_by_id(&data, id);
+                }
+                assert!(index.highest_offset() as usize <= data.len());
+            }
+        }
+    }
+}
@@ -444,3 +444,31 @@ mod spawn {
         }
     }
 }
+
+mod git_trace {
+    use gix_trace::git_trace::{Subscriber, GIT_TRACE};
+
+    #[test]
+    fn spawned_commands_are_traced_like_git_does() -> crate::Result {
+        let dir = gix_testtools::tempfile::tempdir()?;
+        let trace_file = dir.path().join("trace");
+        let subscriber =
+            Subscriber::from_env(|name| (name == GIT_TRACE).then(|| trace_file.clone().into())).expect("tracing is on");
+        let status = tracing_core::dispatcher::with_default(&tracing_core::Dispatch::new(subscriber), || {
+            gix_command::prepare("git")
+                .arg("--version")
+                .stdout(std::process::Stdio::null())
+                .spawn()?
+                .wait()
+        })?;
+        assert!(status.success());
+
+        let trace = std::fs::read_to_string(&trace_file)?;
+        assert_eq!(trace.lines().count(), 1, "{trace}");
+        assert!(
+            trace.ends_with(" trace: run_command: cmd=\"git\" \"--version\"\n"),
+            "the spawned command is traced just like `git` does: {trace}"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+//! Changed-path Bloom filters, as stored in the `BIDX` and `BDAT` chunks of commit-graph files.
+//!
+//! The filter of a commit contains all paths that changed compared to its first parent, along with all of their
+//! leading directories. If a path isn't contained in the filter, the commit definitely didn't change it, which allows
+//! to skip computing tree diffs for most commits in path-limited traversals.
+//!
+//! See [the `git` documentation](https://git-scm.com/docs/commit-graph-format#_chunk_data) for details.
+use bstr::{BStr, ByteSlice};
+
+/// The settings used to create all Bloom filters of a commit-graph file, as read from the header of its `BDAT` chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Settings {
+    /// The version of the murmur3 hash used to compute [keys](Key), either 1 or 2.
+    ///
+    /// Version 1 is what `git` wrote by default for a long time, and it treats bytes above `0x7f` as signed
+    /// just like `git` does on most platforms.
+    pub hash_version: u32,
+    /// The amount of bits to set for each path, and the number of hashes in each [`Key`].
+    pub num_hashes: u32,
+    /// The amount of bits per changed path that `git` used to size the filters.
+    pub bits_per_entry: u32,
+}
+
+/// The key of a single path, to [test for its presence](Filter::contains()) in filters created with the same [`Settings`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Key {
+    hashes: Vec<u32>,
+}
+
+impl Key {
+    /// Compute the key for `path`, which is relative to the repository root and doesn't end with a slash, for use with
+    /// filters using `settings`.
+    pub fn new(path: &BStr, settings: &Settings) -> Self {
+        const SEED0: u32 = 0x293a_e76f;
+        const SEED1: u32 = 0x7e64_6e2c;
+        let hash0 = murmur3(SEED0, path, settings.hash_version);
+        let hash1 = murmur3(SEED1, path, settings.hash_version);
+        Key {
+            hashes: (0..settings.num_hashes)
+                .map(|idx| hash0.wrapping_add(idx.wrapping_mul(hash1)))
+                .collect(),
+        }
+    }
+
+    /// Compute the keys for `path` and each of its leading directories, for use with filters using `settings`.
+    ///
+    /// A commit can only have changed `path` if all of these keys are [contained](Filter::contains()) in its filter.
+    /// Trailing slashes are ignored, and an empty `path` has no keys as the root is always assumed to have changed.
+    pub fn for_path_and_leading_directories(path: &BStr, settings: &Settings) -> Vec<Key> {
+        let path = path.trim_end_with(|c| c == '/').as_bstr();
+        if path.is_empty() {
+            return Vec::new();
+        }
+        std::iter::once(path)
+            .chain(path.rfind_iter("/").map(|pos| path[..pos].as_bstr()))
+            .map(|path| Key::new(path, settings))
+            .collect()
+    }
+}
+
+/// The changed-path Bloom filter of a commit.
+#[derive(Debug, Clone, Copy)]
+pub struct Filter<'a> {
+    data: &'a [u8],
+    settings: Settings,
+}
+
+impl<'a> Filter<'a> {
+    pub(crate) fn new(data: &'a [u8], settings: Settings) -> Self {
+        Filter { data, settings }
+    }
+
+    /// The settings with which this filter was created, and which must be used to create the [keys](Key) to test.
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Return `false` if the path of `key` definitely didn't change in the commit, or `true` if it may have changed.
+    ///
+    /// Note that empty filters as well as the filters of commits with too many changes, which have all bits set,
+    /// always return `true`.
+    pub fn contains(&self, key: &Key) -> bool {
+        let num_bits = self.data.len() as u64 * 8;
+        if num_bits == 0 {
+            return true;
+        }
+        key.hashes.iter().all(|hash| {
+            let bit = u64::from(*hash) % num_bits;
+            self.data[(bit / 8) as usize] & (1 << (bit % 8)) != 0
+        })
+    }
+}
+
+/// Compute the 32 bit murmur3 hash of `data` with `seed`, treating bytes as signed if `hash_version` is 1
+/// to produce the same hashes as `git`.
+pub(crate) fn murmur3(seed: u32, data: &[u8], hash_version: u32) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+    const R1: u32 = 15;
+    const R2: u32 = 13;
+    const M: u32 = 5;
+    const N: u32 = 0xe654_6b64;
+
+    let byte = |b: u8| -> u32 {
+        if hash_version == 1 {
+            b as i8 as u32
+        } else {
+            u32::from(b)
+        }
+    };
+    let mix = |k: u32| k.wrapping_mul(C1).rotate_left(R1).wrapping_mul(C2);
+
+    let mut hash = seed;
+    let blocks = data.chunks_exact(4);
+    let tail = blocks.remainder();
+    for block in blocks {
+        let k = byte(block[0]) | (byte(block[1]) << 8) | (byte(block[2]) << 16) | (byte(block[3]) << 24);
+        hash ^= mix(k);
+        hash = hash.rotate_left(R2).wrapping_mul(M).wrapping_add(N);
+    }
+    if !tail.is_empty() {
+        let k = tail
+            .iter()
+            .enumerate()
+            .fold(0, |k, (idx, b)| k ^ (byte(*b) << (8 * idx)));
+        hash ^= mix(k);
+    }
+
+    hash ^= data.len() as u32;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2_ae35);
+    hash ^ (hash >> 16)
+}
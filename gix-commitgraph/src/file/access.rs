@@ -4,7 +4,8 @@ use std::{
 };
 
 use crate::{
-    file::{self, commit::Commit, COMMIT_DATA_ENTRY_SIZE_SANS_HASH},
+    bloom,
+    file::{self, commit::Commit, COMMIT_DATA_ENTRY_SIZE_SANS_HASH, GENERATION_DATA_OVERFLOW_MASK},
     File,
 };
 
@@ -15,6 +16,11 @@ impl File {
         self.base_graph_count
     }
 
+    /// The settings of the changed-path Bloom filters of all commits in this file, or `None` if there are no such filters.
+    pub fn bloom_filter_settings(&self) -> Option<&bloom::Settings> {
+        self.bloom_filter_settings.as_ref()
+    }
+
     /// Returns the commit data for the commit located at the given lexicographical position.
     ///
     /// `pos` must range from 0 to `self.num_commits()`.
@@ -26,6 +32,11 @@ impl File {
         Commit::new(self, pos)
     }
 
+    /// Return `true` if this file stores corrected commit dates, also known as generation numbers v2, for its commits.
+    pub fn has_generation_data(&self) -> bool {
+        self.generation_data_offset.is_some()
+    }
+
     /// The kind of hash used in this File.
     ///
     /// Note that it is always conforming to the hash used in the owning repository.
@@ -127,6 +138,31 @@ impl File {
         &self.data[start..][..entry_size]
     }
 
+    /// Returns the offset of the corrected commit date from the commit date of the commit at `pos`, as stored in the
+    /// Generation Data (GDA2) chunk and possibly the Generation Data Overflow (GDO2) chunk.
+    pub(crate) fn generation_data_offset(&self, pos: file::Position) -> Option<u64> {
+        let start = self.generation_data_offset? + pos.0 as usize * 4;
+        let raw = u32::from_be_bytes(self.data[start..][..4].try_into().unwrap());
+        if raw & GENERATION_DATA_OVERFLOW_MASK == 0 {
+            return Some(raw.into());
+        }
+        let overflow = &self.data[self.generation_data_overflow_range.clone()?];
+        let start = (raw & !GENERATION_DATA_OVERFLOW_MASK) as usize * 8;
+        Some(u64::from_be_bytes(overflow.get(start..start + 8)?.try_into().unwrap()))
+    }
+
+    /// Returns the changed-path Bloom filter of the commit at `pos`, as stored in the Bloom Filter Data (BDAT) chunk
+    /// at the range found in the Bloom Filter Index (BIDX) chunk, or `None` if there is no valid filter.
+    pub(crate) fn bloom_filter(&self, pos: file::Position) -> Option<bloom::Filter<'_>> {
+        let index_offset = self.bloom_filter_index_offset?;
+        let read_end = |pos: usize| u32::from_be_bytes(self.data[index_offset + pos * 4..][..4].try_into().unwrap());
+        let pos = pos.0 as usize;
+        let start = if pos == 0 { 0 } else { read_end(pos - 1) } as usize;
+        let end = read_end(pos) as usize;
+        let filter_data = self.data[self.bloom_filter_data_range.clone()?].get(start..end)?;
+        Some(bloom::Filter::new(filter_data, self.bloom_filter_settings?))
+    }
+
     /// Returns the byte slice for this file's entire Extra Edge List (EDGE) chunk.
     pub(crate) fn extra_edges_data(&self) -> Option<&[u8]> {
         Some(&self.data[self.extra_edges_list_range.clone()?])
@@ -74,6 +74,24 @@ impl<'a> Commit<'a> {
         self.generation
     }
 
+    /// Returns the corrected commit date of this commit, also known as generation number v2, if the owning file
+    /// [has generation data](File::has_generation_data()).
+    ///
+    /// It's the largest of the committer timestamp and the corrected commit dates of all parents plus one, and is
+    /// the preferred generation number as it's more likely to allow stopping traversals early.
+    /// Dates that would overflow from a corrupt offset saturate at `u64::MAX`.
+    pub fn corrected_commit_date(&self) -> Option<u64> {
+        self.file
+            .generation_data_offset(self.pos)
+            .map(|offset| self.commit_timestamp.saturating_add(offset))
+    }
+
+    /// Returns the changed-path Bloom filter of this commit, which contains all paths that changed compared to its
+    /// first parent, or `None` if the owning file has no [Bloom filters](File::bloom_filter_settings()) or if it is invalid.
+    pub fn changed_paths_filter(&self) -> Option<crate::bloom::Filter<'a>> {
+        self.file.bloom_filter(self.pos)
+    }
+
     /// Returns an iterator over the parent positions for lookup in the owning [Graph][crate::Graph].
     pub fn iter_parents(self) -> Parents<'a> {
         // I didn't find a combinator approach that a) was as strict as ParentIterator, b) supported
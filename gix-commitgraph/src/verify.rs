@@ -40,6 +40,12 @@ pub enum Error<E: std::error::Error + 'static> {
         expected: u32,
         id: gix_hash::ObjectId,
     },
+    #[error("Commit {id}'s corrected commit date should be {expected} but is {actual}")]
+    CorrectedCommitDate {
+        actual: u64,
+        expected: u64,
+        id: gix_hash::ObjectId,
+    },
     #[error(
         "Commit {id} has parent position {parent_pos} that is out of range (should be in range 0-{max_valid_pos})"
     )]
@@ -130,6 +136,7 @@ impl Graph {
             let file_stats = file
                 .traverse(|commit| {
                     let mut max_parent_generation = 0u32;
+                    let mut min_corrected_commit_date = Some(1u64);
                     for parent_pos in commit.iter_parents() {
                         let parent_pos = parent_pos.map_err(Error::Commit)?;
                         if parent_pos >= next_file_start_pos {
@@ -141,6 +148,9 @@ impl Graph {
                         }
                         let parent = self.commit_at(parent_pos);
                         max_parent_generation = max(max_parent_generation, parent.generation());
+                        min_corrected_commit_date = min_corrected_commit_date
+                            .zip(parent.corrected_commit_date())
+                            .map(|(a, b)| max(a, b + 1));
                     }
 
                     // If the max parent generation is GENERATION_NUMBER_MAX, then this commit's
@@ -154,6 +164,17 @@ impl Graph {
                         });
                     }
 
+                    if let Some((actual, min_date)) = commit.corrected_commit_date().zip(min_corrected_commit_date) {
+                        let expected = max(commit.committer_timestamp(), min_date);
+                        if actual != expected {
+                            return Err(Error::CorrectedCommitDate {
+                                actual,
+                                expected,
+                                id: commit.id().into(),
+                            });
+                        }
+                    }
+
                     processor(commit).map_err(Error::Processor)?;
 
                     Ok(())
@@ -0,0 +1,301 @@
+use std::{
+    cmp::{max, min},
+    io::Write,
+};
+
+use crate::{
+    file::{
+        BASE_GRAPHS_LIST_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
+        EXTENDED_EDGES_LIST_CHUNK_ID, EXTENDED_EDGES_MASK, FAN_LEN, GENERATION_DATA_CHUNK_ID,
+        GENERATION_DATA_OVERFLOW_CHUNK_ID, GENERATION_DATA_OVERFLOW_MASK, HEADER_LEN, LAST_EXTENDED_EDGE_MASK,
+        NO_PARENT, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
+    },
+    write::{Commit, Error, Options, Outcome},
+    File, Graph, Position, GENERATION_NUMBER_MAX, MAX_COMMITS,
+};
+
+/// The largest offset of a corrected commit date to its commit date that is stored directly in the generation data chunk.
+const GENERATION_DATA_OFFSET_MAX: u64 = 0x7fff_ffff;
+
+/// Writing
+impl File {
+    /// Write a commit-graph file containing all `commits` to `out`, with `base` being the graph whose files are the
+    /// base graphs of the written file in case it's a layer of a split commit-graph chain.
+    ///
+    /// Commits that are already contained in `base` are skipped, and all parents of `commits` must be part of either
+    /// `commits` or `base`.
+    /// Generation numbers, i.e. topological levels and optionally corrected commit dates, are computed for all commits.
+    pub fn write_from_commits(
+        mut commits: Vec<Commit>,
+        base: Option<&Graph>,
+        out: &mut dyn std::io::Write,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let object_hash = options.object_hash;
+        let base_files = base.map_or(&[][..], |graph| graph.files.as_slice());
+        if base_files.len() > usize::from(u8::MAX) {
+            return Err(Error::TooManyFiles(base_files.len() + 1));
+        }
+        if let Some(file) = base_files.iter().find(|file| file.object_hash() != object_hash) {
+            return Err(Error::HashMismatch {
+                actual: file.object_hash(),
+                expected: object_hash,
+            });
+        }
+
+        commits.sort_by_key(|commit| commit.id);
+        commits.dedup_by(|a, b| a.id == b.id);
+        if let Some(base) = base {
+            commits.retain(|commit| base.lookup(commit.id).is_none());
+        }
+        let num_base_commits = base.map_or(0, Graph::num_commits);
+        let num_commits = num_base_commits as u64 + commits.len() as u64;
+        if num_commits > u64::from(MAX_COMMITS) {
+            return Err(Error::TooManyCommits(num_commits));
+        }
+        let generation_data = options.generation_data && base_files.iter().all(File::has_generation_data);
+
+        let mut parent_positions = Vec::with_capacity(commits.len());
+        let mut parents_start = Vec::with_capacity(commits.len() + 1);
+        for commit in &commits {
+            parents_start.push(parent_positions.len());
+            for parent_id in &commit.parent_ids {
+                let pos = commits
+                    .binary_search_by(|c| c.id.cmp(parent_id))
+                    .ok()
+                    .map(|pos| num_base_commits + pos as u32)
+                    .or_else(|| base.and_then(|base| base.lookup(parent_id)).map(|pos| pos.0))
+                    .ok_or(Error::MissingParent {
+                        id: commit.id,
+                        parent_id: *parent_id,
+                    })?;
+                parent_positions.push(pos);
+            }
+        }
+        parents_start.push(parent_positions.len());
+        let parents_of = |idx: usize| &parent_positions[parents_start[idx]..parents_start[idx + 1]];
+
+        let generations = compute_generations(&commits, parents_of, base, num_base_commits);
+
+        let num_overflows = if generation_data {
+            generations
+                .iter()
+                .filter(|g| g.corrected_commit_date_offset > GENERATION_DATA_OFFSET_MAX)
+                .count()
+        } else {
+            0
+        };
+        let num_extra_edges: usize = (0..commits.len())
+            .map(|idx| parents_of(idx).len())
+            .filter(|num_parents| *num_parents > 2)
+            .map(|num_parents| num_parents - 1)
+            .sum();
+
+        let hash_len = object_hash.len_in_bytes();
+        let mut cf = gix_chunk::file::Index::for_writing();
+        cf.plan_chunk(OID_FAN_CHUNK_ID, (FAN_LEN * 4) as u64);
+        cf.plan_chunk(OID_LOOKUP_CHUNK_ID, (commits.len() * hash_len) as u64);
+        cf.plan_chunk(
+            COMMIT_DATA_CHUNK_ID,
+            (commits.len() * (hash_len + COMMIT_DATA_ENTRY_SIZE_SANS_HASH)) as u64,
+        );
+        if generation_data {
+            cf.plan_chunk(GENERATION_DATA_CHUNK_ID, (commits.len() * 4) as u64);
+            if num_overflows > 0 {
+                cf.plan_chunk(GENERATION_DATA_OVERFLOW_CHUNK_ID, (num_overflows * 8) as u64);
+            }
+        }
+        if num_extra_edges > 0 {
+            cf.plan_chunk(EXTENDED_EDGES_LIST_CHUNK_ID, (num_extra_edges * 4) as u64);
+        }
+        if !base_files.is_empty() {
+            cf.plan_chunk(BASE_GRAPHS_LIST_CHUNK_ID, (base_files.len() * hash_len) as u64);
+        }
+
+        let mut out = gix_features::hash::Write::new(out, object_hash);
+        out.write_all(SIGNATURE)?;
+        out.write_all(&[1 /* version */, object_hash as u8])?;
+        out.write_all(&[
+            cf.num_chunks().try_into().expect("BUG: wrote more than 256 chunks"),
+            base_files.len() as u8,
+        ])?;
+
+        let mut chunk_write = cf.into_write(&mut out, HEADER_LEN)?;
+        while let Some(chunk_to_write) = chunk_write.next_chunk() {
+            match chunk_to_write {
+                OID_FAN_CHUNK_ID => {
+                    let mut fan = [0u32; FAN_LEN];
+                    for commit in &commits {
+                        fan[usize::from(commit.id.first_byte())] += 1;
+                    }
+                    let mut count = 0;
+                    for entry in fan {
+                        count += entry;
+                        chunk_write.write_all(&count.to_be_bytes())?;
+                    }
+                }
+                OID_LOOKUP_CHUNK_ID => {
+                    for commit in &commits {
+                        chunk_write.write_all(commit.id.as_slice())?;
+                    }
+                }
+                COMMIT_DATA_CHUNK_ID => {
+                    let mut extra_edge_index = 0u32;
+                    for (idx, (commit, generation)) in commits.iter().zip(&generations).enumerate() {
+                        chunk_write.write_all(commit.tree_id.as_slice())?;
+                        let parents = parents_of(idx);
+                        let (parent1, parent2) = match parents {
+                            [] => (NO_PARENT, NO_PARENT),
+                            [first] => (*first, NO_PARENT),
+                            [first, second] => (*first, *second),
+                            [first, rest @ ..] => {
+                                let edge = EXTENDED_EDGES_MASK | extra_edge_index;
+                                extra_edge_index += rest.len() as u32;
+                                (*first, edge)
+                            }
+                        };
+                        chunk_write.write_all(&parent1.to_be_bytes())?;
+                        chunk_write.write_all(&parent2.to_be_bytes())?;
+                        let generation_and_time_high_bits =
+                            (generation.topological_level << 2) | ((commit.commit_time >> 32) & 0x3) as u32;
+                        chunk_write.write_all(&generation_and_time_high_bits.to_be_bytes())?;
+                        chunk_write.write_all(&(commit.commit_time as u32).to_be_bytes())?;
+                    }
+                }
+                GENERATION_DATA_CHUNK_ID => {
+                    let mut overflow_index = 0u32;
+                    for generation in &generations {
+                        let offset = generation.corrected_commit_date_offset;
+                        let value = if offset > GENERATION_DATA_OFFSET_MAX {
+                            overflow_index += 1;
+                            GENERATION_DATA_OVERFLOW_MASK | (overflow_index - 1)
+                        } else {
+                            offset as u32
+                        };
+                        chunk_write.write_all(&value.to_be_bytes())?;
+                    }
+                }
+                GENERATION_DATA_OVERFLOW_CHUNK_ID => {
+                    for generation in generations
+                        .iter()
+                        .filter(|g| g.corrected_commit_date_offset > GENERATION_DATA_OFFSET_MAX)
+                    {
+                        chunk_write.write_all(&generation.corrected_commit_date_offset.to_be_bytes())?;
+                    }
+                }
+                EXTENDED_EDGES_LIST_CHUNK_ID => {
+                    for idx in 0..commits.len() {
+                        if let [_first, rest @ ..] = parents_of(idx) {
+                            if rest.len() < 2 {
+                                continue;
+                            }
+                            for (edge_idx, pos) in rest.iter().enumerate() {
+                                let edge = if edge_idx + 1 == rest.len() {
+                                    pos | LAST_EXTENDED_EDGE_MASK
+                                } else {
+                                    *pos
+                                };
+                                chunk_write.write_all(&edge.to_be_bytes())?;
+                            }
+                        }
+                    }
+                }
+                BASE_GRAPHS_LIST_CHUNK_ID => {
+                    for file in base_files {
+                        chunk_write.write_all(file.checksum().as_bytes())?;
+                    }
+                }
+                unknown => unreachable!("BUG: forgot to implement chunk {:?}", std::str::from_utf8(&unknown)),
+            }
+        }
+
+        let checksum: gix_hash::ObjectId = out.hash.digest();
+        out.inner.write_all(checksum.as_slice())?;
+        Ok(Outcome {
+            checksum,
+            num_commits: commits.len() as u32,
+            generation_data,
+        })
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+struct Generation {
+    topological_level: u32,
+    corrected_commit_date_offset: u64,
+    corrected_commit_date: u64,
+}
+
+/// Compute the generation numbers of all `commits` in a depth-first traversal of their parents, which may be in `base`.
+fn compute_generations<'a>(
+    commits: &[Commit],
+    parents_of: impl Fn(usize) -> &'a [u32],
+    base: Option<&Graph>,
+    num_base_commits: u32,
+) -> Vec<Generation> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Unvisited,
+        Expanded,
+        Done,
+    }
+    let mut generations = vec![Generation::default(); commits.len()];
+    let mut states = vec![State::Unvisited; commits.len()];
+    let mut stack = Vec::new();
+    let parent_generation = |generations: &[Generation], pos: u32| -> (u32, u64) {
+        match pos.checked_sub(num_base_commits) {
+            Some(idx) => {
+                let generation = generations[idx as usize];
+                (generation.topological_level, generation.corrected_commit_date)
+            }
+            None => {
+                let commit = base
+                    .expect("positions below the base are in the base")
+                    .commit_at(Position(pos));
+                (commit.generation(), commit.corrected_commit_date().unwrap_or_default())
+            }
+        }
+    };
+
+    for root in 0..commits.len() {
+        stack.push(root);
+        while let Some(&idx) = stack.last() {
+            match states[idx] {
+                State::Done => {
+                    stack.pop();
+                    continue;
+                }
+                State::Unvisited => {
+                    states[idx] = State::Expanded;
+                    // Parents that are expanded but not done yet form a cycle, which can only happen with hash collisions,
+                    // and they are ignored to remain finite.
+                    stack.extend(
+                        parents_of(idx)
+                            .iter()
+                            .filter_map(|pos| pos.checked_sub(num_base_commits).map(|idx| idx as usize))
+                            .filter(|parent_idx| states[*parent_idx] == State::Unvisited),
+                    );
+                    continue;
+                }
+                State::Expanded => {}
+            }
+            stack.pop();
+
+            let (mut max_level, mut max_corrected_commit_date) = (0, 0);
+            for &pos in parents_of(idx) {
+                let (level, corrected_commit_date) = parent_generation(&generations, pos);
+                max_level = max(max_level, level);
+                max_corrected_commit_date = max(max_corrected_commit_date, corrected_commit_date);
+            }
+            let commit_time = commits[idx].commit_time;
+            let corrected_commit_date = max(commit_time, max_corrected_commit_date + 1);
+            generations[idx] = Generation {
+                topological_level: min(max_level.saturating_add(1), GENERATION_NUMBER_MAX),
+                corrected_commit_date_offset: corrected_commit_date - commit_time,
+                corrected_commit_date,
+            };
+            states[idx] = State::Done;
+        }
+    }
+    generations
+}
@@ -0,0 +1,252 @@
+/// How to write the commit-graph of an object database with [`to_info_dir()`](super::to_info_dir()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Write all commits into the single `info/commit-graph` file, and remove the split commit-graph chain if present.
+    Single,
+    /// Write all commits that aren't in the split commit-graph chain yet into a new layer in `info/commit-graphs/`,
+    /// possibly merging it with existing layers.
+    ///
+    /// The commits of a single `info/commit-graph` file are always merged into the new layer, and the file is removed.
+    Split(Split),
+}
+
+/// Configure how layers of a split commit-graph chain are merged, like `git commit-graph write --split[=<strategy>]` does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Split {
+    /// Control if and how layers are merged.
+    pub strategy: Strategy,
+    /// Merge the topmost existing layer into the new layer if it contains at most this many times the commits of
+    /// the new layer, and repeat with the next layer. Defaults to 2, just like `splitIndex.sizeMultiple` in `git`.
+    pub size_multiple: u32,
+    /// If set, keep merging existing layers into the new layer while it contains more than this amount of commits.
+    pub max_commits: Option<u32>,
+}
+
+impl Default for Split {
+    fn default() -> Self {
+        Split {
+            strategy: Strategy::default(),
+            size_multiple: 2,
+            max_commits: None,
+        }
+    }
+}
+
+/// The strategy to use when merging the layers of a split commit-graph chain.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Merge layers according to [`Split::size_multiple`] and [`Split::max_commits`].
+    #[default]
+    Merge,
+    /// Never merge existing layers, and always add a new layer.
+    NoMerge,
+    /// Merge all existing layers into a single new layer.
+    Replace,
+}
+
+pub(super) mod function {
+    use std::{
+        io::Write,
+        path::{Path, PathBuf},
+    };
+
+    use gix_lock::acquire::Fail;
+
+    use super::{Mode, Split, Strategy};
+    use crate::{
+        file,
+        write::{Commit, Error, Options, Outcome},
+        File, Graph,
+    };
+
+    /// Write the commit-graph of an object database at `info_dir`, i.e. `.git/objects/info`, to contain `commits` according
+    /// to `mode`, and with the given `options`.
+    ///
+    /// In [split mode](Mode::Split), `commits` that are already part of the existing chain are skipped, and the new layer
+    /// is written only if there is something to write, returning `None` otherwise.
+    ///
+    /// All files are written to temporary files first, and the commit-graph chain file is written last, so that readers
+    /// will always see a consistent commit-graph. Layer files that aren't referenced by the chain anymore are removed.
+    pub fn to_info_dir(
+        info_dir: &Path,
+        commits: Vec<Commit>,
+        mode: Mode,
+        options: Options,
+    ) -> Result<Option<Outcome>, Error> {
+        let graphs_dir = info_dir.join("commit-graphs");
+        let chain_path = graphs_dir.join("commit-graph-chain");
+        let single_path = info_dir.join("commit-graph");
+        match mode {
+            Mode::Single => {
+                let mut lock = gix_lock::File::acquire_to_update_resource(&single_path, Fail::Immediately, None)?;
+                let mut out = std::io::BufWriter::new(&mut lock);
+                let outcome = File::write_from_commits(commits, None, &mut out, options)?;
+                out.flush()?;
+                drop(out);
+                lock.commit()?;
+
+                remove_if_present(&chain_path)?;
+                remove_unreferenced_layers(&graphs_dir, &[])?;
+                Ok(Some(outcome))
+            }
+            Mode::Split(split) => {
+                std::fs::create_dir_all(&graphs_dir)?;
+                let chain_lock = gix_lock::File::acquire_to_update_resource(&chain_path, Fail::Immediately, None)?;
+                let layers = if chain_path.is_file() {
+                    Graph::from_commit_graphs_dir(&graphs_dir)?
+                } else {
+                    Graph { files: Vec::new() }
+                };
+                let single = single_path
+                    .is_file()
+                    .then(|| {
+                        File::at(&single_path).map_err(|err| crate::init::Error::File {
+                            err,
+                            path: single_path.clone(),
+                        })
+                    })
+                    .transpose()?;
+                if let Some(file) = layers
+                    .files
+                    .iter()
+                    .chain(single.as_ref())
+                    .find(|f| f.object_hash() != options.object_hash)
+                {
+                    return Err(Error::HashMismatch {
+                        actual: file.object_hash(),
+                        expected: options.object_hash,
+                    });
+                }
+
+                let mut commits: Vec<_> = commits
+                    .into_iter()
+                    .filter(|commit| layers.lookup(commit.id).is_none())
+                    .collect();
+                commits.sort_by_key(|commit| commit.id);
+                commits.dedup_by(|a, b| a.id == b.id);
+
+                let num_new_commits = commits.len() as u64 + single.as_ref().map_or(0, |f| f.num_commits().into());
+                let num_layers_to_keep = num_layers_to_keep(&layers.files, num_new_commits, split);
+                if commits.is_empty() && single.is_none() && num_layers_to_keep == layers.files.len() {
+                    return Ok(None);
+                }
+
+                for file in &layers.files[num_layers_to_keep..] {
+                    for commit in file.iter_commits() {
+                        commits.push(to_write_commit(commit, |pos| layers.id_at(pos))?);
+                    }
+                }
+                if let Some(single) = &single {
+                    for commit in single.iter_commits() {
+                        commits.push(to_write_commit(commit, |pos| single.id_at(file::Position(pos.0)))?);
+                    }
+                }
+
+                let mut files = layers.files;
+                files.truncate(num_layers_to_keep);
+                let base = Graph { files };
+                let mut layer = gix_tempfile::new(
+                    &graphs_dir,
+                    gix_tempfile::ContainingDirectory::Exists,
+                    gix_tempfile::AutoRemove::Tempfile,
+                )?;
+                let mut out = std::io::BufWriter::new(&mut layer);
+                let outcome =
+                    File::write_from_commits(commits, (!base.files.is_empty()).then_some(&base), &mut out, options)?;
+                out.flush()?;
+                drop(out);
+                let layer_path = graphs_dir.join(format!("graph-{}.graph", outcome.checksum));
+                layer.persist(&layer_path).map_err(|err| Error::Persist {
+                    source: err.error,
+                    path: layer_path,
+                })?;
+
+                let chain: Vec<_> = base
+                    .files
+                    .iter()
+                    .map(|file| file.checksum().to_owned())
+                    .chain(Some(outcome.checksum))
+                    .collect();
+                drop(base);
+                let mut chain_lock = chain_lock;
+                for checksum in &chain {
+                    writeln!(chain_lock, "{checksum}")?;
+                }
+                chain_lock.commit()?;
+
+                drop(single);
+                remove_if_present(&single_path)?;
+                remove_unreferenced_layers(&graphs_dir, &chain)?;
+                Ok(Some(outcome))
+            }
+        }
+    }
+
+    /// Return the amount of bottom-most `layers` to keep when adding a layer with `num_commits`.
+    fn num_layers_to_keep(layers: &[File], mut num_commits: u64, split: Split) -> usize {
+        match split.strategy {
+            Strategy::Replace => 0,
+            Strategy::NoMerge => layers.len(),
+            Strategy::Merge => {
+                let mut num_layers = layers.len();
+                while let Some(top) = num_layers.checked_sub(1).map(|idx| &layers[idx]) {
+                    let top_commits = u64::from(top.num_commits());
+                    let exceeds_max_commits = split.max_commits.into_iter().any(|max| num_commits > u64::from(max));
+                    if top_commits <= u64::from(split.size_multiple) * num_commits || exceeds_max_commits {
+                        num_commits += top_commits;
+                        num_layers -= 1;
+                    } else {
+                        break;
+                    }
+                }
+                num_layers
+            }
+        }
+    }
+
+    fn to_write_commit<'a>(
+        commit: file::Commit<'a>,
+        id_at: impl Fn(crate::Position) -> &'a gix_hash::oid,
+    ) -> Result<Commit, Error> {
+        Ok(Commit {
+            id: commit.id().to_owned(),
+            tree_id: commit.root_tree_id().to_owned(),
+            parent_ids: commit
+                .iter_parents()
+                .map(|pos| pos.map(|pos| id_at(pos).to_owned()))
+                .collect::<Result<_, _>>()?,
+            commit_time: commit.committer_timestamp(),
+        })
+    }
+
+    fn remove_if_present(path: &Path) -> std::io::Result<()> {
+        match std::fs::remove_file(path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    /// Remove all layer files in `graphs_dir` whose checksum isn't in `chain`.
+    fn remove_unreferenced_layers(graphs_dir: &Path, chain: &[gix_hash::ObjectId]) -> std::io::Result<()> {
+        let entries = match std::fs::read_dir(graphs_dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err),
+        };
+        for entry in entries {
+            let path: PathBuf = entry?.path();
+            let Some(hex) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix("graph-"))
+                .and_then(|name| name.strip_suffix(".graph"))
+            else {
+                continue;
+            };
+            if !chain.iter().any(|checksum| checksum.to_string() == hex) {
+                remove_if_present(&path)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+//! Write commit-graph files, either a single one or layers of a split commit-graph chain.
+//!
+//! Use [`File::write_from_commits()`](crate::File::write_from_commits()) to write a single file to any output, or
+//! [`to_info_dir()`] to write or update the commit-graph of an object database.
+use std::path::PathBuf;
+
+pub use info_dir::function::to_info_dir;
+
+/// A commit to write into a commit-graph file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    /// The id of the commit.
+    pub id: gix_hash::ObjectId,
+    /// The id of the tree the commit points to.
+    pub tree_id: gix_hash::ObjectId,
+    /// The ids of all parents, in order.
+    pub parent_ids: Vec<gix_hash::ObjectId>,
+    /// The committer timestamp in seconds since the unix epoch.
+    ///
+    /// Note that only the lower 34 bits are stored.
+    pub commit_time: u64,
+}
+
+/// Options for writing commit-graph files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// The kind of hash to use for all ids, which must match the hash of all commits and base graphs.
+    pub object_hash: gix_hash::Kind,
+    /// If `true`, store corrected commit dates (generation numbers v2) along with topological levels, as `git` does by
+    /// default with `commitGraph.generationVersion = 2`.
+    ///
+    /// Note that it's ignored if the base graph lacks this data, as the data would be incomplete otherwise.
+    pub generation_data: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            object_hash: gix_hash::Kind::Sha1,
+            generation_data: true,
+        }
+    }
+}
+
+/// The result of writing a commit-graph file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The checksum of the written file, which is also the hash in the name of files that are part of a chain.
+    pub checksum: gix_hash::ObjectId,
+    /// The amount of commits in the written file.
+    pub num_commits: u32,
+    /// `true` if corrected commit dates were written.
+    pub generation_data: bool,
+}
+
+/// The error returned when writing commit-graph files.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Could not write commit-graph file to '{}'", .path.display())]
+    Persist { source: std::io::Error, path: PathBuf },
+    #[error("Commit {id} has parent {parent_id} which is neither part of the commits to write nor of the base graph")]
+    MissingParent {
+        id: gix_hash::ObjectId,
+        parent_id: gix_hash::ObjectId,
+    },
+    #[error(
+        "Commit-graph files can store at most {} commits, but there are {0}",
+        crate::MAX_COMMITS
+    )]
+    TooManyCommits(u64),
+    #[error("Commit-graph chains can have at most 256 files, but it would have {0}")]
+    TooManyFiles(usize),
+    #[error("The commit-graph uses hash {actual:?}, but {expected:?} should be written")]
+    HashMismatch {
+        actual: gix_hash::Kind,
+        expected: gix_hash::Kind,
+    },
+    #[error(transparent)]
+    Commit(#[from] crate::file::commit::Error),
+    #[error("Could not open the existing commit-graph")]
+    Open(#[from] crate::init::Error),
+    #[error(transparent)]
+    LockAcquire(#[from] gix_lock::acquire::Error),
+    #[error(transparent)]
+    LockCommit(#[from] gix_lock::commit::Error<gix_lock::File>),
+}
+
+mod file;
+///
+#[allow(clippy::empty_docs)]
+pub mod info_dir;
@@ -0,0 +1,124 @@
+use std::{path::Path, process::Command};
+
+use gix_commitgraph::{bloom::Key, Graph};
+use gix_testtools::scripted_fixture_read_only;
+
+#[test]
+fn filters_written_by_git_contain_all_changed_paths_and_their_leading_directories() {
+    let repo = scripted_fixture_read_only("changed_paths.sh").unwrap();
+    let graph = Graph::from_info_dir(&repo.join(".git").join("objects").join("info")).unwrap();
+
+    let mut definitely_not = 0;
+    for id in graph.iter_ids() {
+        let commit = graph.commit_by_id(id).expect("present");
+        let filter = commit
+            .changed_paths_filter()
+            .expect("git writes filters for all commits");
+        let settings = filter.settings();
+        assert_eq!(settings.num_hashes, 7, "git's defaults");
+        assert_eq!(settings.bits_per_entry, 10);
+        assert!(matches!(settings.hash_version, 1 | 2));
+
+        let changed = changed_paths(&repo, id);
+        for path in &changed {
+            for key in Key::for_path_and_leading_directories(path.as_str().into(), settings) {
+                assert!(
+                    filter.contains(&key),
+                    "{id}: {path} or one of its leading directories changed"
+                );
+            }
+        }
+        for path in [
+            "a",
+            "dir",
+            "dir/sub/c",
+            "other/d",
+            "dir/ü-umlaut",
+            "many/1",
+            "does-not-exist",
+        ] {
+            if !changed
+                .iter()
+                .any(|changed| changed == path || changed.starts_with(&format!("{path}/")))
+            {
+                definitely_not += usize::from(!filter.contains(&Key::new(path.into(), settings)));
+            }
+        }
+    }
+    assert!(
+        definitely_not > 40,
+        "most paths that didn't change are definitely not contained, got {definitely_not}"
+    );
+}
+
+#[test]
+fn filters_of_commits_with_too_many_changes_contain_everything() {
+    let repo = scripted_fixture_read_only("changed_paths.sh").unwrap();
+    let graph = Graph::from_info_dir(&repo.join(".git").join("objects").join("info")).unwrap();
+    let id = rev_parse(&repo, "main~1");
+    let filter = graph
+        .commit_by_id(id)
+        .expect("present")
+        .changed_paths_filter()
+        .expect("present");
+    for path in ["a", "many/1", "does-not-exist"] {
+        assert!(filter.contains(&Key::new(path.into(), filter.settings())));
+    }
+}
+
+#[test]
+fn keys_for_leading_directories() {
+    let settings = gix_commitgraph::bloom::Settings {
+        hash_version: 2,
+        num_hashes: 7,
+        bits_per_entry: 10,
+    };
+    assert_eq!(
+        Key::for_path_and_leading_directories("a/b/c/".into(), &settings),
+        ["a/b/c", "a/b", "a"]
+            .into_iter()
+            .map(|path| Key::new(path.into(), &settings))
+            .collect::<Vec<_>>(),
+        "trailing slashes are ignored"
+    );
+    assert!(Key::for_path_and_leading_directories("".into(), &settings).is_empty());
+}
+
+fn git(repo: &Path) -> Command {
+    let mut cmd = Command::new("git");
+    cmd.arg("-C").arg(repo).env_remove("GIT_DIR");
+    cmd
+}
+
+fn rev_parse(repo: &Path, spec: &str) -> gix_hash::ObjectId {
+    let out = git(repo).args(["rev-parse", spec]).output().unwrap();
+    assert!(out.status.success());
+    gix_hash::ObjectId::from_hex(std::str::from_utf8(&out.stdout).unwrap().trim().as_bytes()).unwrap()
+}
+
+/// Return all paths that changed in `id` compared to its first parent, or all paths if it's a root commit.
+fn changed_paths(repo: &Path, id: &gix_hash::oid) -> Vec<String> {
+    let has_parent = git(repo)
+        .args(["rev-parse", "--verify", "--quiet", &format!("{id}^1")])
+        .output()
+        .unwrap()
+        .status
+        .success();
+    let out = if has_parent {
+        git(repo)
+            .args(["diff", "-z", "--name-only", &format!("{id}^1"), &id.to_string()])
+            .output()
+    } else {
+        git(repo)
+            .args(["ls-tree", "-z", "-r", "--name-only", &id.to_string()])
+            .output()
+    }
+    .unwrap();
+    assert!(out.status.success());
+    String::from_utf8(out.stdout)
+        .unwrap()
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(ToOwned::to_owned)
+        .collect()
+}
@@ -0,0 +1,32 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+mkdir -p dir/sub other
+echo a > a
+echo b > dir/b
+echo c > dir/sub/c
+echo d > other/d
+git add . && git commit -q -m "root"
+
+echo a2 >> a && git commit -q -am "change a"
+echo c2 >> dir/sub/c && git commit -q -am "change dir/sub/c"
+echo d2 >> other/d && git commit -q -am "change other/d"
+echo ü > "dir/ü-umlaut" && git add . && git commit -q -m "add path with bytes above 0x7f"
+git rm -q dir/b && git commit -q -m "remove dir/b"
+
+git checkout -q -b side
+echo side >> other/d && git commit -q -am "change other/d on side"
+git checkout -q main
+echo main >> a && git commit -q -am "change a on main"
+git merge -q --no-ff -m "merge side" side
+
+mkdir many
+for i in $(seq 600); do echo $i > many/$i; done
+git add many && git commit -q -m "too many changes for a filter"
+
+git commit -q --allow-empty -m "no changes"
+
+git commit-graph write --no-progress --reachable --changed-paths
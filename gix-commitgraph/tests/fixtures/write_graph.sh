@@ -0,0 +1,55 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function tick () {
+  if test -z "${tick+set}"
+  then
+    tick=1112911993
+  else
+    tick=$(($tick + 60))
+  fi
+  GIT_COMMITTER_DATE="$tick -0700"
+  GIT_AUTHOR_DATE="$tick -0700"
+  export GIT_COMMITTER_DATE GIT_AUTHOR_DATE
+}
+
+function commit() {
+  local message=${1:?first argument is the commit message}
+  tick
+  git commit -q --allow-empty -m "$message"
+  git tag "$message"
+}
+
+git init -q
+git config commitGraph.generationVersion 2
+
+git checkout -q -b main
+commit c1
+commit c2
+commit c3
+
+git checkout -q -b side c1
+commit s1
+commit s2
+
+git checkout -q main
+tick
+git merge -q --no-ff -m merge side
+git tag merge
+
+for name in o1 o2 o3; do
+  git checkout -q -b branch-$name c2
+  commit $name
+done
+git checkout -q main
+tick
+git merge -q --no-ff -m octopus branch-o1 branch-o2 branch-o3 >/dev/null
+git tag octopus
+
+# a commit far in the future followed by one at the epoch needs the generation data overflow chunk
+GIT_COMMITTER_DATE="@4147483646 +0000" git commit -q --allow-empty -m future
+git tag future
+GIT_COMMITTER_DATE="@0 +0000" git commit -q --allow-empty -m old
+git tag old
+
+git commit-graph write --no-progress --reachable
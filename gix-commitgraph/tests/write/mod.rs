@@ -0,0 +1,333 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use gix_commitgraph::{
+    write,
+    write::info_dir::{Mode, Split, Strategy},
+    Graph,
+};
+use gix_testtools::scripted_fixture_writable;
+
+#[test]
+fn single_file_is_accepted_by_git_and_reads_like_the_one_written_by_git() {
+    let dir = scripted_fixture_writable("write_graph.sh").unwrap();
+    let info_dir = info_dir(dir.path());
+    let native = Graph::from_info_dir(&info_dir).unwrap();
+    let native_data = std::fs::read(info_dir.join("commit-graph")).unwrap();
+
+    let outcome = write::to_info_dir(&info_dir, commits(&native, &[]), Mode::Single, Default::default())
+        .unwrap()
+        .expect("always written");
+    assert_eq!(outcome.num_commits, native.num_commits());
+    assert!(outcome.generation_data);
+    assert_eq!(
+        std::fs::read(info_dir.join("commit-graph")).unwrap(),
+        native_data,
+        "we write the same chunks in the same order as git"
+    );
+    git_verify(dir.path());
+
+    let ours = Graph::from_info_dir(&info_dir).unwrap();
+    assert_same_commits(&native, &ours);
+    let old = ours.commit_by_id(rev_parse(dir.path(), "old")).expect("present");
+    assert_eq!(old.committer_timestamp(), 0);
+    assert_eq!(
+        old.corrected_commit_date(),
+        Some(4147483647),
+        "the offset needs the overflow chunk"
+    );
+}
+
+#[test]
+fn generation_version_1_does_not_write_corrected_commit_dates() {
+    let dir = scripted_fixture_writable("write_graph.sh").unwrap();
+    let info_dir = info_dir(dir.path());
+    let native = Graph::from_info_dir(&info_dir).unwrap();
+    let outcome = write::to_info_dir(
+        &info_dir,
+        commits(&native, &[]),
+        Mode::Single,
+        write::Options {
+            generation_data: false,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+    .expect("always written");
+    assert!(!outcome.generation_data);
+    git_verify(dir.path());
+
+    let ours = Graph::from_info_dir(&info_dir).unwrap();
+    for id in ours.iter_ids() {
+        let commit = ours.commit_by_id(id).expect("present");
+        assert_eq!(commit.corrected_commit_date(), None);
+        assert_eq!(
+            commit.generation(),
+            native.commit_by_id(id).expect("present").generation()
+        );
+    }
+}
+
+#[test]
+fn split_chain_layers_are_added_on_top_of_git_layers_and_merged_like_git() {
+    let dir = scripted_fixture_writable("write_graph.sh").unwrap();
+    let repo = dir.path();
+    let info_dir = info_dir(repo);
+    let native = Graph::from_info_dir(&info_dir).unwrap();
+    let all = commits(&native, &[]);
+    std::fs::remove_file(info_dir.join("commit-graph")).unwrap();
+
+    git_write_split_layer(repo, rev_parse(repo, "merge"));
+    assert_eq!(layer_sizes(&info_dir), [6]);
+
+    let no_merge = Mode::Split(Split {
+        strategy: Strategy::NoMerge,
+        ..Default::default()
+    });
+    let outcome = write::to_info_dir(&info_dir, all.clone(), no_merge, Default::default())
+        .unwrap()
+        .expect("new commits");
+    assert_eq!(outcome.num_commits, 6, "all commits above the first layer");
+    assert_eq!(layer_sizes(&info_dir), [6, 6]);
+    git_verify(repo);
+
+    std::fs::remove_dir_all(info_dir.join("commit-graphs")).unwrap();
+    git_write_split_layer(repo, rev_parse(repo, "merge"));
+    let merge_by_policy = Mode::Split(Split::default());
+    write::to_info_dir(
+        &info_dir,
+        commits(&native, &[rev_parse(repo, "octopus")]),
+        merge_by_policy,
+        Default::default(),
+    )
+    .unwrap();
+    assert_eq!(layer_sizes(&info_dir), [10], "6 <= 2 * 4, so the layers are merged");
+    write::to_info_dir(
+        &info_dir,
+        commits(&native, &[rev_parse(repo, "future")]),
+        merge_by_policy,
+        Default::default(),
+    )
+    .unwrap();
+    assert_eq!(layer_sizes(&info_dir), [10, 1], "10 > 2 * 1");
+    git_verify(repo);
+
+    write::to_info_dir(
+        &info_dir,
+        all.clone(),
+        Mode::Split(Split {
+            size_multiple: 1,
+            ..Default::default()
+        }),
+        Default::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        layer_sizes(&info_dir),
+        [10, 2],
+        "the layer below has as many commits as the new one and is merged"
+    );
+    git_verify(repo);
+    assert_same_commits(&native, &Graph::from_info_dir(&info_dir).unwrap());
+
+    assert_eq!(
+        write::to_info_dir(&info_dir, all.clone(), merge_by_policy, Default::default()).unwrap(),
+        None,
+        "nothing to write"
+    );
+    std::fs::remove_dir_all(info_dir.join("commit-graphs")).unwrap();
+    git_write_split_layer(repo, rev_parse(repo, "merge"));
+    write::to_info_dir(
+        &info_dir,
+        commits(&native, &[rev_parse(repo, "octopus")]),
+        Mode::Split(Split {
+            size_multiple: 1,
+            max_commits: Some(3),
+            ..Default::default()
+        }),
+        Default::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        layer_sizes(&info_dir),
+        [10],
+        "layers are merged while the new layer has more than the maximum amount of commits"
+    );
+
+    write::to_info_dir(
+        &info_dir,
+        all.clone(),
+        Mode::Split(Split {
+            strategy: Strategy::Replace,
+            ..Default::default()
+        }),
+        Default::default(),
+    )
+    .unwrap();
+    assert_eq!(layer_sizes(&info_dir), [12]);
+    assert_eq!(
+        layer_files(&info_dir).len(),
+        1,
+        "layers that aren't part of the chain anymore are removed"
+    );
+    git_verify(repo);
+    assert_same_commits(&native, &Graph::from_info_dir(&info_dir).unwrap());
+
+    write::to_info_dir(&info_dir, all, Mode::Single, Default::default()).unwrap();
+    assert!(info_dir.join("commit-graph").is_file());
+    assert!(!info_dir.join("commit-graphs").join("commit-graph-chain").exists());
+    assert_eq!(layer_files(&info_dir).len(), 0);
+    git_verify(repo);
+
+    let outcome = write::to_info_dir(&info_dir, Vec::new(), merge_by_policy, Default::default())
+        .unwrap()
+        .expect("the single file is always merged");
+    assert_eq!(outcome.num_commits, 12);
+    assert!(!info_dir.join("commit-graph").exists());
+    assert_eq!(layer_sizes(&info_dir), [12]);
+    git_verify(repo);
+}
+
+#[test]
+fn missing_parents_are_an_error() {
+    let dir = scripted_fixture_writable("write_graph.sh").unwrap();
+    let info_dir = info_dir(dir.path());
+    let native = Graph::from_info_dir(&info_dir).unwrap();
+    let root = rev_parse(dir.path(), "c1");
+    let commits: Vec<_> = commits(&native, &[]).into_iter().filter(|c| c.id != root).collect();
+
+    let err = write::to_info_dir(&info_dir, commits, Mode::Single, Default::default()).unwrap_err();
+    assert!(
+        matches!(err, write::Error::MissingParent { parent_id, .. } if parent_id == root),
+        "{err:?}"
+    );
+}
+
+fn info_dir(repo: &Path) -> PathBuf {
+    repo.join(".git").join("objects").join("info")
+}
+
+/// Return the commits reachable from `tips` in `graph`, or all of them if `tips` is empty.
+fn commits(graph: &Graph, tips: &[gix_hash::ObjectId]) -> Vec<write::Commit> {
+    let to_commit = |id: &gix_hash::oid| {
+        let commit = graph.commit_by_id(id).expect("present");
+        write::Commit {
+            id: id.to_owned(),
+            tree_id: commit.root_tree_id().to_owned(),
+            parent_ids: commit
+                .iter_parents()
+                .map(|pos| graph.id_at(pos.expect("valid")).to_owned())
+                .collect(),
+            commit_time: commit.committer_timestamp(),
+        }
+    };
+    if tips.is_empty() {
+        return graph.iter_ids().map(to_commit).collect();
+    }
+    let mut out = Vec::<write::Commit>::new();
+    let mut queue = tips.to_vec();
+    while let Some(id) = queue.pop() {
+        if out.iter().any(|c| c.id == id) {
+            continue;
+        }
+        let commit = to_commit(&id);
+        queue.extend(commit.parent_ids.iter().copied());
+        out.push(commit);
+    }
+    out
+}
+
+fn assert_same_commits(expected: &Graph, actual: &Graph) {
+    assert_eq!(actual.num_commits(), expected.num_commits());
+    for id in expected.iter_ids() {
+        let expected_commit = expected.commit_by_id(id).expect("present");
+        let actual_commit = actual.commit_by_id(id).expect("present in both");
+        assert_eq!(actual_commit.root_tree_id(), expected_commit.root_tree_id());
+        assert_eq!(
+            actual_commit.committer_timestamp(),
+            expected_commit.committer_timestamp()
+        );
+        assert_eq!(actual_commit.generation(), expected_commit.generation(), "{id}");
+        assert_eq!(
+            actual_commit.corrected_commit_date(),
+            expected_commit.corrected_commit_date(),
+            "{id}"
+        );
+        assert!(expected_commit.corrected_commit_date().is_some(), "git writes them");
+        assert_eq!(
+            actual_commit
+                .iter_parents()
+                .map(|pos| actual.id_at(pos.expect("valid")))
+                .collect::<Vec<_>>(),
+            expected_commit
+                .iter_parents()
+                .map(|pos| expected.id_at(pos.expect("valid")))
+                .collect::<Vec<_>>()
+        );
+    }
+}
+
+/// Return the amount of commits in each layer of the commit-graph chain, from the bottom to the top.
+fn layer_sizes(info_dir: &Path) -> Vec<u32> {
+    let graphs_dir = info_dir.join("commit-graphs");
+    std::fs::read_to_string(graphs_dir.join("commit-graph-chain"))
+        .unwrap()
+        .lines()
+        .map(|hash| {
+            gix_commitgraph::File::at(graphs_dir.join(format!("graph-{hash}.graph")))
+                .unwrap()
+                .num_commits()
+        })
+        .collect()
+}
+
+fn layer_files(info_dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(info_dir.join("commit-graphs"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension() == Some("graph".as_ref()))
+        .collect()
+}
+
+fn git(repo: &Path) -> Command {
+    let mut cmd = Command::new("git");
+    cmd.arg("-C").arg(repo).env_remove("GIT_DIR");
+    cmd
+}
+
+fn rev_parse(repo: &Path, spec: &str) -> gix_hash::ObjectId {
+    let out = git(repo).args(["rev-parse", spec]).output().unwrap();
+    assert!(out.status.success());
+    gix_hash::ObjectId::from_hex(std::str::from_utf8(&out.stdout).unwrap().trim().as_bytes()).unwrap()
+}
+
+fn git_write_split_layer(repo: &Path, tip: gix_hash::ObjectId) {
+    let mut child = git(repo)
+        .args([
+            "commit-graph",
+            "write",
+            "--no-progress",
+            "--split=no-merge",
+            "--stdin-commits",
+        ])
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    writeln!(child.stdin.take().expect("piped"), "{tip}").unwrap();
+    assert!(child.wait().unwrap().success());
+}
+
+fn git_verify(repo: &Path) {
+    let out = git(repo)
+        .args(["commit-graph", "verify", "--no-progress"])
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "git commit-graph verify failed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
@@ -516,6 +516,12 @@ impl<'event> File<'event> {
         if entries.is_empty() {
             Err(lookup::existing::Error::KeyMissing)
         } else {
+            for entry in &entries {
+                self.sections
+                    .get_mut(&entry.section_id)
+                    .expect("known section-id")
+                    .spans = None;
+            }
             Ok(MultiValueMut {
                 section: &mut self.sections,
                 key,
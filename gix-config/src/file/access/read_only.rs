@@ -226,6 +226,29 @@ impl<'event> File<'event> {
             .map_err(lookup::Error::FailedConversion)
     }
 
+    /// Return `true` if `key` is set in any section, even if only implicitly like `a` in `[core]\n\ta`.
+    ///
+    /// Unlike [`raw_value()`](Self::raw_value()), this doesn't allocate, and invalid keys are never contained.
+    #[must_use]
+    pub fn contains_key(&self, key: impl AsKey) -> bool {
+        self.contains_key_filter(key, &mut |_| true)
+    }
+
+    /// Like [`contains_key()`](Self::contains_key()), but only considers sections that pass `filter`.
+    #[must_use]
+    pub fn contains_key_filter(&self, key: impl AsKey, filter: &mut MetadataFilter) -> bool {
+        let Some(key) = key.try_as_key() else {
+            return false;
+        };
+        match self.section_ids_by_name_and_subname(key.section_name, key.subsection_name) {
+            Ok(mut ids) => ids.any(|id| {
+                let section = &self.sections[&id];
+                filter(section.meta()) && section.contains_value_name(key.value_name)
+            }),
+            Err(_) => false,
+        }
+    }
+
     /// Returns the last found immutable section with a given `name` and optional `subsection_name`.
     pub fn section(
         &self,
@@ -424,6 +447,51 @@ impl<'event> File<'event> {
         })
     }
 
+    /// Return an iterator over the key-value pairs of all sections in order of occurrence, along with the section
+    /// they are contained in and their location in the file they came from, if [recorded](crate::file::init::Options::spans).
+    ///
+    /// Key-value pairs that are overridden by a later one of the same key are marked as [shadowed](file::section::Entry::is_shadowed),
+    /// which also applies to all but the last value of multi-valued keys.
+    pub fn entries(&self) -> impl Iterator<Item = file::section::Entry<'_, 'event>> + '_ {
+        self.section_order.iter().flat_map(move |id| {
+            let section = &self.sections[id];
+            let spans = section.spans().map(|spans| spans.values.as_slice()).unwrap_or_default();
+            let key_values = section.body.key_values();
+            let is_shadowed: Vec<_> = key_values
+                .iter()
+                .enumerate()
+                .map(|(idx, (name, _))| {
+                    key_values[idx + 1..].iter().any(|(other, _)| other == name)
+                        || self.is_shadowed_by_later_section(*id, name.as_ref())
+                })
+                .collect();
+            key_values
+                .into_iter()
+                .zip(is_shadowed)
+                .enumerate()
+                .map(move |(idx, ((name, value), is_shadowed))| file::section::Entry {
+                    section,
+                    name,
+                    value,
+                    span: spans.get(idx).copied(),
+                    is_shadowed,
+                })
+        })
+    }
+
+    /// Return `true` if a section with the same name and subsection as the one with `id` contains `value_name`
+    /// and is seen before it by single-value lookups.
+    fn is_shadowed_by_later_section(&self, id: SectionId, value_name: &str) -> bool {
+        let header = &self.sections[&id].header;
+        match self.section_ids_by_name_and_subname(header.name.as_ref(), header.subsection_name()) {
+            Ok(ids) => ids
+                .skip_while(|other| *other != id)
+                .skip(1)
+                .any(|other| self.sections[&other].contains_value_name(value_name)),
+            Err(_) => false,
+        }
+    }
+
     /// Return all events which are in front of the first of our sections, or `None` if there are none.
     pub fn frontmatter(&self) -> Option<impl Iterator<Item = &Event<'event>>> {
         (!self.frontmatter_events.is_empty()).then(|| self.frontmatter_events.iter())
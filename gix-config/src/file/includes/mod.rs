@@ -38,25 +38,18 @@ impl File<'static> {
 }
 
 pub(crate) fn resolve(config: &mut File<'static>, buf: &mut Vec<u8>, options: init::Options<'_>) -> Result<(), Error> {
-    resolve_includes_recursive(config, 0, buf, options)
+    resolve_includes_recursive(config, 0, &mut Vec::new(), buf, options)
 }
 
+/// Follow all includes of `target_config` at `depth`, with `chain` being the canonicalized paths of all files that were
+/// included to get here, starting with the including file if it has a path.
 fn resolve_includes_recursive(
     target_config: &mut File<'static>,
     depth: u8,
+    chain: &mut Vec<PathBuf>,
     buf: &mut Vec<u8>,
     options: init::Options<'_>,
 ) -> Result<(), Error> {
-    if depth == options.includes.max_depth {
-        return if options.includes.err_on_max_depth_exceeded {
-            Err(Error::IncludeDepthExceeded {
-                max_depth: options.includes.max_depth,
-            })
-        } else {
-            Ok(())
-        };
-    }
-
     let mut section_ids_and_include_paths = Vec::new();
     for (id, section) in target_config
         .section_order
@@ -77,13 +70,14 @@ fn resolve_includes_recursive(
         }
     }
 
-    append_followed_includes_recursively(section_ids_and_include_paths, target_config, depth, options, buf)
+    append_followed_includes_recursively(section_ids_and_include_paths, target_config, depth, chain, options, buf)
 }
 
 fn append_followed_includes_recursively(
     section_ids_and_include_paths: Vec<(SectionId, crate::Path<'_>)>,
     target_config: &mut File<'static>,
     depth: u8,
+    chain: &mut Vec<PathBuf>,
     options: init::Options<'_>,
     buf: &mut Vec<u8>,
 ) -> Result<(), Error> {
@@ -97,6 +91,25 @@ fn append_followed_includes_recursively(
         if !config_path.is_file() {
             continue;
         }
+        // Like `git`, the depth is checked only when a file would actually be included, so conditional includes
+        // that don't match and paths that don't exist don't count.
+        // Files of the top-level configuration may have different paths, so the chain starts with the one that includes.
+        let chain_len = chain.len();
+        if depth == 0 {
+            chain.extend(target_config_path.map(canonicalized));
+        }
+        if depth == options.includes.max_depth {
+            if options.includes.err_on_max_depth_exceeded {
+                let mut chain = std::mem::take(chain);
+                chain.push(canonicalized(&config_path));
+                return Err(Error::IncludeDepthExceeded {
+                    max_depth: options.includes.max_depth,
+                    chain,
+                });
+            }
+            chain.truncate(chain_len);
+            continue;
+        }
 
         buf.clear();
         std::io::copy(
@@ -124,13 +137,21 @@ fn append_followed_includes_recursively(
                 init::Error::Interpolate(err) => Error::Interpolate(err),
                 init::Error::Includes(_) => unreachable!("BUG: {:?} not possible due to no-follow options", err),
             })?;
-        resolve_includes_recursive(&mut include_config, depth + 1, buf, options)?;
+        chain.push(canonicalized(include_config.meta().path.as_deref().expect("set above")));
+        let res = resolve_includes_recursive(&mut include_config, depth + 1, chain, buf, options);
+        chain.truncate(chain_len);
+        res?;
 
         target_config.append_or_insert(include_config, Some(section_id));
     }
     Ok(())
 }
 
+/// Return `path` with all symlinks resolved so that files are recognized in the include chain no matter how they are referred to.
+fn canonicalized(path: &Path) -> PathBuf {
+    gix_path::realpath(path).unwrap_or_else(|_| path.to_owned())
+}
+
 fn detach_include_paths(
     include_paths: &mut Vec<(SectionId, crate::Path<'static>)>,
     section: &file::Section<'_>,
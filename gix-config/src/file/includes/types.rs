@@ -13,8 +13,13 @@ pub enum Error {
     Parse(#[from] parse::Error),
     #[error(transparent)]
     Interpolate(#[from] interpolate::Error),
-    #[error("The maximum allowed length {} of the file include chain built by following nested resolve_includes is exceeded", .max_depth)]
-    IncludeDepthExceeded { max_depth: u8 },
+    #[error("The maximum allowed length {max_depth} of the file include chain is exceeded while including:{}", describe_chain(.chain))]
+    IncludeDepthExceeded {
+        max_depth: u8,
+        /// The canonicalized paths of all files in the include chain, starting with the including file if it has a path,
+        /// and ending with the file that couldn't be included anymore.
+        chain: Vec<PathBuf>,
+    },
     #[error("Include paths from environment variables must not be relative as no config file paths exists as root")]
     MissingConfigPath,
     #[error("The git directory must be provided to support `gitdir:` conditional includes")]
@@ -23,10 +28,31 @@ pub enum Error {
     Realpath(#[from] gix_path::realpath::Error),
 }
 
+/// List all files in `chain` on their own line, and point out the file that includes itself if there is a cycle.
+fn describe_chain(chain: &[PathBuf]) -> String {
+    let mut out = String::new();
+    for (idx, path) in chain.iter().enumerate() {
+        out.push_str(if idx == 0 { "\n\t" } else { "\n\t-> " });
+        out.push_str(&path.display().to_string());
+    }
+    if let Some((last, previous)) = chain.split_last() {
+        if previous.contains(last) {
+            out.push_str(&format!(
+                "\nThe chain is a cycle as '{}' includes itself, directly or indirectly.",
+                last.display()
+            ));
+        }
+    }
+    out
+}
+
 /// Options to handle includes, like `include.path` or `includeIf.<condition>.path`,
 #[derive(Clone, Copy)]
 pub struct Options<'a> {
-    /// The maximum allowed length of the file include chain built by following nested resolve_includes where base level is depth = 0.
+    /// The maximum allowed length of the file include chain built by following nested includes, which is the amount
+    /// of files that can be included from each other, starting at the including file.
+    ///
+    /// `git` allows up to 10, so a file included by 10 other files is read while the file included by it is not.
     pub max_depth: u8,
     /// When max depth is exceeded while following nested includes,
     /// return an error if true or silently stop following resolve_includes.
@@ -38,16 +38,26 @@ impl<'a> File<'a> {
         options: Options<'_>,
     ) -> Result<Self, Error> {
         let meta = meta.into();
-        Ok(Self::from_parse_events_no_includes(
-            parse::Events::from_bytes(input, options.to_event_filter())?,
-            meta,
-        ))
+        let mut spans = Vec::new();
+        let events = parse::Events::from_bytes_with_spans(
+            input,
+            options.to_event_filter(),
+            options.spans.then_some(&mut spans),
+        )?;
+        Ok(Self::from_parse_events_and_spans_no_includes(events, spans, meta))
     }
 
     /// Instantiate a new `File` from given `events`, associating each section and their values with
     /// `meta`-data.
-    pub fn from_parse_events_no_includes(
+    pub fn from_parse_events_no_includes(events: parse::Events<'a>, meta: impl Into<OwnShared<Metadata>>) -> Self {
+        Self::from_parse_events_and_spans_no_includes(events, Vec::new(), meta)
+    }
+
+    /// Like [`from_parse_events_no_includes()`](Self::from_parse_events_no_includes()), but also associate each section
+    /// with its location in `spans`, which are only used if there is one for each section.
+    fn from_parse_events_and_spans_no_includes(
         parse::Events { frontmatter, sections }: parse::Events<'a>,
+        spans: Vec<parse::section::Spans>,
         meta: impl Into<OwnShared<Metadata>>,
     ) -> Self {
         let meta = meta.into();
@@ -55,6 +65,7 @@ impl<'a> File<'a> {
 
         this.frontmatter_events = frontmatter;
 
+        let mut spans = (spans.len() == sections.len()).then(|| spans.into_iter());
         this.sections.reserve(sections.len());
         this.section_order.reserve(sections.len());
         for section in sections {
@@ -63,6 +74,7 @@ impl<'a> File<'a> {
                 body: section::Body(section.events),
                 meta: OwnShared::clone(&meta),
                 id: Default::default(),
+                spans: spans.as_mut().and_then(Iterator::next),
             });
         }
         this
@@ -78,10 +90,14 @@ impl File<'static> {
         meta: impl Into<OwnShared<Metadata>>,
         options: Options<'_>,
     ) -> Result<Self, Error> {
-        let mut config = Self::from_parse_events_no_includes(
-            parse::Events::from_bytes_owned(input_and_buf, options.to_event_filter()).map_err(Error::from)?,
-            meta,
-        );
+        let mut spans = Vec::new();
+        let events = parse::Events::from_bytes_owned_with_spans(
+            input_and_buf,
+            options.to_event_filter(),
+            options.spans.then_some(&mut spans),
+        )
+        .map_err(Error::from)?;
+        let mut config = Self::from_parse_events_and_spans_no_includes(events, spans, meta);
 
         includes::resolve(&mut config, input_and_buf, options).map_err(Error::from)?;
         Ok(config)
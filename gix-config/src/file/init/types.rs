@@ -26,6 +26,11 @@ pub struct Options<'a> {
     ///
     /// That way it's possible to pass multiple files and read as many as possible, to have 'something' instead of nothing.
     pub ignore_io_errors: bool,
+    /// If true, the location of each section and each key-value pair in its file is recorded, to be obtained with
+    /// [`Section::spans()`][crate::file::Section::spans()] and [`File::entries()`][crate::File::entries()].
+    ///
+    /// This is off by default as it costs additional memory for each section.
+    pub spans: bool,
 }
 
 impl Options<'_> {
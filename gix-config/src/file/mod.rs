@@ -78,6 +78,7 @@ pub struct Section<'a> {
     body: section::Body<'a>,
     meta: OwnShared<Metadata>,
     id: SectionId,
+    spans: Option<crate::parse::section::Spans>,
 }
 
 /// A function to filter metadata, returning `true` if the corresponding but omitted value can be used.
@@ -125,6 +125,39 @@ impl<'event> Body<'event> {
         &self.0
     }
 
+    /// Return all value names along with their normalized value in order of occurrence, with the value being `None`
+    /// if there is no key-value separator.
+    pub(crate) fn key_values(&self) -> Vec<(&ValueName<'event>, Option<Cow<'_, BStr>>)> {
+        let mut out = Vec::new();
+        let mut key = None;
+        let mut has_separator = false;
+        let mut concatenated_value = BString::default();
+        for event in &self.0 {
+            match event {
+                Event::SectionValueName(name) => {
+                    key = Some(name);
+                    has_separator = false;
+                }
+                Event::KeyValueSeparator => has_separator = true,
+                Event::Value(v) => {
+                    if let Some(key) = key.take() {
+                        out.push((key, has_separator.then(|| normalize_bstr(v.as_ref()))));
+                    }
+                }
+                Event::ValueNotDone(v) => concatenated_value.push_str(v.as_ref()),
+                Event::ValueDone(v) => {
+                    concatenated_value.push_str(v.as_ref());
+                    let value = normalize_bstring(std::mem::take(&mut concatenated_value));
+                    if let Some(key) = key.take() {
+                        out.push((key, Some(value)));
+                    }
+                }
+                _ => (),
+            }
+        }
+        out
+    }
+
     /// Returns the range containing the value events for the `value_name`, with value range being `None` if there is
     /// no key-value separator and only a 'fake' Value event with an empty string in side.
     /// If the value is not found, `None` is returned.
@@ -27,6 +27,24 @@ impl<'a> Deref for Section<'a> {
     }
 }
 
+/// A key-value pair along with the section it's contained in, as returned by [`File::entries()`](crate::File::entries()).
+#[derive(Clone, Debug)]
+pub struct Entry<'a, 'event> {
+    /// The section containing the key-value pair, which also knows where it came from with [`Section::meta()`].
+    pub section: &'a Section<'event>,
+    /// The name of the key, like `url` in `remote.origin.url`.
+    pub name: &'a section::ValueName<'event>,
+    /// The normalized value, or `None` if the value is implicit like `a` in `[core]\n\ta`, which typically means `true`.
+    pub value: Option<Cow<'a, BStr>>,
+    /// The location of the key-value pair in the file at [`meta().path`](Metadata::path), if [known](Section::spans()).
+    pub span: Option<crate::parse::Span>,
+    /// If `true`, a later key-value pair with the same key overrides this one, so single-value lookups like
+    /// [`File::raw_value()`](crate::File::raw_value()) won't see it.
+    ///
+    /// This is useful to find keys that are defined multiple times, possibly in different files.
+    pub is_shadowed: bool,
+}
+
 /// Instantiation and conversion
 impl<'a> Section<'a> {
     /// Create a new section with the given `name` and optional, `subsection`, `meta`-data and an empty body.
@@ -40,6 +58,7 @@ impl<'a> Section<'a> {
             body: Default::default(),
             meta: meta.into(),
             id: SectionId::default(),
+            spans: None,
         })
     }
 }
@@ -138,8 +157,18 @@ impl<'a> Section<'a> {
         &self.meta
     }
 
+    /// Return the location of our header and our key-value pairs in the file at [`meta().path`](Metadata::path),
+    /// or `None` if this section wasn't parsed with [`spans`](crate::file::init::Options::spans) enabled or was obtained
+    /// for mutation since.
+    pub fn spans(&self) -> Option<&section::Spans> {
+        self.spans.as_ref()
+    }
+
     /// Returns a mutable version of this section for adjustment of values.
+    ///
+    /// Note that this discards our [spans](Self::spans()) as they can't be kept in sync with the changes.
     pub fn to_mut(&mut self, newline: SmallVec<[u8; 2]>) -> SectionMut<'_, 'a> {
+        self.spans = None;
         SectionMut::new(self, newline)
     }
 }
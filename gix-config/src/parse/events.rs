@@ -227,7 +227,16 @@ impl Events<'static> {
         input: &'a [u8],
         filter: Option<fn(&Event<'a>) -> bool>,
     ) -> Result<Events<'static>, parse::Error> {
-        from_bytes(input, &|e| e.to_owned(), filter)
+        from_bytes(input, &|e| e.to_owned(), filter, None)
+    }
+
+    /// Like [`from_bytes_owned()`](Self::from_bytes_owned()), but if `spans` is set, push the location of each section onto it.
+    pub(crate) fn from_bytes_owned_with_spans<'a>(
+        input: &'a [u8],
+        filter: Option<fn(&Event<'a>) -> bool>,
+        spans: Option<&mut Vec<parse::section::Spans>>,
+    ) -> Result<Events<'static>, parse::Error> {
+        from_bytes(input, &|e| e.to_owned(), filter, spans)
     }
 }
 
@@ -239,7 +248,16 @@ impl<'a> Events<'a> {
     ///
     /// Use `filter` to only include those events for which it returns true.
     pub fn from_bytes(input: &'a [u8], filter: Option<fn(&Event<'a>) -> bool>) -> Result<Events<'a>, parse::Error> {
-        from_bytes(input, &std::convert::identity, filter)
+        from_bytes(input, &std::convert::identity, filter, None)
+    }
+
+    /// Like [`from_bytes()`](Self::from_bytes()), but if `spans` is set, push the location of each section onto it.
+    pub(crate) fn from_bytes_with_spans(
+        input: &'a [u8],
+        filter: Option<fn(&Event<'a>) -> bool>,
+        spans: Option<&mut Vec<parse::section::Spans>>,
+    ) -> Result<Events<'a>, parse::Error> {
+        from_bytes(input, &std::convert::identity, filter, spans)
     }
 
     /// Attempt to zero-copy parse the provided `input` string.
@@ -288,33 +306,62 @@ fn from_bytes<'a, 'b>(
     input: &'a [u8],
     convert: &dyn Fn(Event<'a>) -> Event<'b>,
     filter: Option<fn(&Event<'a>) -> bool>,
+    mut section_spans: Option<&mut Vec<parse::section::Spans>>,
 ) -> Result<Events<'b>, parse::Error> {
     let mut header = None;
     let mut events = Vec::with_capacity(256);
     let mut frontmatter = FrontMatterEvents::default();
     let mut sections = Vec::new();
-    parse::from_bytes(input, &mut |e: Event<'_>| match e {
-        Event::SectionHeader(next_header) => {
-            match header.take() {
-                None => {
-                    frontmatter = std::mem::take(&mut events).into_iter().collect();
+    let mut spans = parse::section::Spans::default();
+    let mut key_start = 0;
+    let mut key_value_end = 0;
+    parse::nom::from_bytes_with_spans(input, &mut |e: Event<'_>, span| {
+        if section_spans.is_some() {
+            match &e {
+                Event::SectionValueName(_) => {
+                    key_start = span.start;
+                    key_value_end = span.end;
                 }
-                Some(prev_header) => {
-                    sections.push(parse::Section {
-                        header: prev_header,
-                        events: std::mem::take(&mut events),
+                Event::KeyValueSeparator | Event::ValueNotDone(_) => key_value_end = span.end,
+                Event::Value(value) | Event::ValueDone(value) => {
+                    if !value.is_empty() {
+                        key_value_end = span.end;
+                    }
+                    spans.values.push(parse::Span {
+                        start: key_start,
+                        end: key_value_end,
                     });
                 }
-            };
-            header = match convert(Event::SectionHeader(next_header)) {
-                Event::SectionHeader(h) => h,
-                _ => unreachable!("BUG: convert must not change the event type, just the lifetime"),
+                _ => {}
             }
-            .into();
         }
-        event => {
-            if filter.map_or(true, |f| f(&event)) {
-                events.push(convert(event))
+        match e {
+            Event::SectionHeader(next_header) => {
+                match header.take() {
+                    None => {
+                        frontmatter = std::mem::take(&mut events).into_iter().collect();
+                    }
+                    Some(prev_header) => {
+                        sections.push(parse::Section {
+                            header: prev_header,
+                            events: std::mem::take(&mut events),
+                        });
+                        if let Some(section_spans) = section_spans.as_mut() {
+                            section_spans.push(std::mem::take(&mut spans));
+                        }
+                    }
+                };
+                spans.header = span;
+                header = match convert(Event::SectionHeader(next_header)) {
+                    Event::SectionHeader(h) => h,
+                    _ => unreachable!("BUG: convert must not change the event type, just the lifetime"),
+                }
+                .into();
+            }
+            event => {
+                if filter.map_or(true, |f| f(&event)) {
+                    events.push(convert(event))
+                }
             }
         }
     })?;
@@ -328,6 +375,9 @@ fn from_bytes<'a, 'b>(
                 header: prev_header,
                 events: std::mem::take(&mut events),
             });
+            if let Some(section_spans) = section_spans {
+                section_spans.push(spans);
+            }
         }
     }
     Ok(Events { frontmatter, sections })
@@ -93,6 +93,22 @@ pub struct Section<'a> {
     pub events: Vec<Event<'a>>,
 }
 
+/// The location of a parsed item in the input, as byte offsets.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct Span {
+    /// The offset of the first byte of the item.
+    pub start: usize,
+    /// The offset one past the last byte of the item.
+    pub end: usize,
+}
+
+impl Span {
+    /// Return the byte range of this span, to be used for indexing into the parsed input.
+    pub fn to_range(&self) -> std::ops::Range<usize> {
+        self.start..self.end
+    }
+}
+
 /// A parsed comment containing the comment marker and comment.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
 pub struct Comment<'a> {
@@ -9,35 +9,53 @@ use winnow::{
     token::{one_of, take_till, take_while},
 };
 
-use crate::parse::{error::ParseNode, section, Comment, Error, Event};
+use crate::parse::{error::ParseNode, section, Comment, Error, Event, Span};
 
 /// Attempt to zero-copy parse the provided bytes, passing results to `dispatch`.
-pub fn from_bytes<'i>(mut input: &'i [u8], dispatch: &mut dyn FnMut(Event<'i>)) -> Result<(), Error> {
+pub fn from_bytes<'i>(input: &'i [u8], dispatch: &mut dyn FnMut(Event<'i>)) -> Result<(), Error> {
+    from_bytes_with_spans(input, &mut |event, _span| dispatch(event))
+}
+
+/// Like [`from_bytes()`], but also pass the location of each event in `input` to `dispatch`.
+pub(crate) fn from_bytes_with_spans<'i>(
+    mut input: &'i [u8],
+    dispatch: &mut dyn FnMut(Event<'i>, Span),
+) -> Result<(), Error> {
     let start = input.checkpoint();
+    let input_len = input.len();
 
     let bom = unicode_bom::Bom::from(input);
     input.next_slice(bom.len());
 
-    repeat(
-        0..,
-        alt((
-            comment.map(Event::Comment),
-            take_spaces1.map(|whitespace| Event::Whitespace(Cow::Borrowed(whitespace))),
-            |i: &mut &'i [u8]| {
-                let newline = take_newlines1.parse_next(i)?;
-                let o = Event::Newline(Cow::Borrowed(newline));
-                Ok(o)
+    let mut event_start = bom.len();
+    let dispatch = &mut |event, remaining: usize| {
+        let end = input_len - remaining;
+        dispatch(
+            event,
+            Span {
+                start: event_start,
+                end,
             },
-        )),
-    )
-    .fold(|| (), |_acc, event| dispatch(event))
+        );
+        event_start = end;
+    };
+
+    while let Some(event) = opt(alt((
+        comment.map(Event::Comment),
+        take_spaces1.map(|whitespace| Event::Whitespace(Cow::Borrowed(whitespace))),
+        |i: &mut &'i [u8]| {
+            let newline = take_newlines1.parse_next(i)?;
+            let o = Event::Newline(Cow::Borrowed(newline));
+            Ok(o)
+        },
+    )))
     .parse_next(&mut input)
-    // I don't think this can panic. many0 errors if the child parser returns
-    // a success where the input was not consumed, but alt will only return Ok
-    // if one of its children succeed. However, all of it's children are
-    // guaranteed to consume something if they succeed, so the Ok(i) == i case
-    // can never occur.
-    .expect("many0(alt(...)) panicked. Likely a bug in one of the children parsers.");
+    // I don't think this can panic. `opt` turns all recoverable errors into `None`, and none
+    // of the children of `alt` can fail in any other way.
+    .expect("opt(alt(...)) panicked. Likely a bug in one of the children parsers.")
+    {
+        dispatch(event, input.len());
+    }
 
     if input.is_empty() {
         return Ok(());
@@ -93,14 +111,14 @@ mod tests;
 fn section<'i>(
     i: &mut &'i [u8],
     node: &mut ParseNode,
-    dispatch: &mut dyn FnMut(Event<'i>),
+    dispatch: &mut dyn FnMut(Event<'i>, usize),
 ) -> PResult<(), NomError<&'i [u8]>> {
     let start = i.checkpoint();
     let header = section_header(i).map_err(|e| {
         i.reset(&start);
         e
     })?;
-    dispatch(Event::SectionHeader(header));
+    dispatch(Event::SectionHeader(header), i.len());
 
     // This would usually be a many0(alt(...)), the manual loop allows us to
     // optimize vec insertions
@@ -108,17 +126,17 @@ fn section<'i>(
         let start = i.checkpoint();
 
         if let Some(v) = opt(take_spaces1).parse_next(i)? {
-            dispatch(Event::Whitespace(Cow::Borrowed(v.as_bstr())));
+            dispatch(Event::Whitespace(Cow::Borrowed(v.as_bstr())), i.len());
         }
 
         if let Some(v) = opt(take_newlines1).parse_next(i)? {
-            dispatch(Event::Newline(Cow::Borrowed(v.as_bstr())));
+            dispatch(Event::Newline(Cow::Borrowed(v.as_bstr())), i.len());
         }
 
         key_value_pair(i, node, dispatch)?;
 
         if let Some(comment) = opt(comment).parse_next(i)? {
-            dispatch(Event::Comment(comment));
+            dispatch(Event::Comment(comment), i.len());
         }
 
         if i.offset_from(&start) == 0 {
@@ -204,14 +222,17 @@ fn is_subsection_unescaped_char(c: u8) -> bool {
 fn key_value_pair<'i>(
     i: &mut &'i [u8],
     node: &mut ParseNode,
-    dispatch: &mut dyn FnMut(Event<'i>),
+    dispatch: &mut dyn FnMut(Event<'i>, usize),
 ) -> PResult<(), NomError<&'i [u8]>> {
     *node = ParseNode::Name;
     if let Some(name) = opt(config_name).parse_next(i)? {
-        dispatch(Event::SectionValueName(section::ValueName(Cow::Borrowed(name))));
+        dispatch(
+            Event::SectionValueName(section::ValueName(Cow::Borrowed(name))),
+            i.len(),
+        );
 
         if let Some(whitespace) = opt(take_spaces1).parse_next(i)? {
-            dispatch(Event::Whitespace(Cow::Borrowed(whitespace)));
+            dispatch(Event::Whitespace(Cow::Borrowed(whitespace)), i.len());
         }
 
         *node = ParseNode::Value;
@@ -233,25 +254,25 @@ fn config_name<'i>(i: &mut &'i [u8]) -> PResult<&'i BStr, NomError<&'i [u8]>> {
         .parse_next(i)
 }
 
-fn config_value<'i>(i: &mut &'i [u8], dispatch: &mut dyn FnMut(Event<'i>)) -> PResult<(), NomError<&'i [u8]>> {
+fn config_value<'i>(i: &mut &'i [u8], dispatch: &mut dyn FnMut(Event<'i>, usize)) -> PResult<(), NomError<&'i [u8]>> {
     if opt('=').parse_next(i)?.is_some() {
-        dispatch(Event::KeyValueSeparator);
+        dispatch(Event::KeyValueSeparator, i.len());
         if let Some(whitespace) = opt(take_spaces1).parse_next(i)? {
-            dispatch(Event::Whitespace(Cow::Borrowed(whitespace)));
+            dispatch(Event::Whitespace(Cow::Borrowed(whitespace)), i.len());
         }
         value_impl(i, dispatch)
     } else {
         // This is a special way of denoting 'empty' values which a lot of code depends on.
         // Hence, rather to fix this everywhere else, leave it here and fix it where it matters, namely
         // when it's about differentiating between a missing key-value separator, and one followed by emptiness.
-        dispatch(Event::Value(Cow::Borrowed("".into())));
+        dispatch(Event::Value(Cow::Borrowed("".into())), i.len());
         Ok(())
     }
 }
 
 /// Handles parsing of known-to-be values. This function handles both single
 /// line values as well as values that are continuations.
-fn value_impl<'i>(i: &mut &'i [u8], dispatch: &mut dyn FnMut(Event<'i>)) -> PResult<(), NomError<&'i [u8]>> {
+fn value_impl<'i>(i: &mut &'i [u8], dispatch: &mut dyn FnMut(Event<'i>, usize)) -> PResult<(), NomError<&'i [u8]>> {
     let start_checkpoint = i.checkpoint();
     let mut value_start_checkpoint = i.checkpoint();
     let mut value_end = None;
@@ -300,12 +321,12 @@ fn value_impl<'i>(i: &mut &'i [u8], dispatch: &mut dyn FnMut(Event<'i>)) -> PRes
                             i.reset(&value_start_checkpoint);
 
                             let value = i.next_slice(escape_index).as_bstr();
-                            dispatch(Event::ValueNotDone(Cow::Borrowed(value)));
+                            dispatch(Event::ValueNotDone(Cow::Borrowed(value)), i.len());
 
                             i.next_token();
 
                             let nl = i.next_slice(consumed).as_bstr();
-                            dispatch(Event::Newline(Cow::Borrowed(nl)));
+                            dispatch(Event::Newline(Cow::Borrowed(nl)), i.len());
 
                             value_start_checkpoint = i.checkpoint();
                             value_end = None;
@@ -333,7 +354,7 @@ fn value_impl<'i>(i: &mut &'i [u8], dispatch: &mut dyn FnMut(Event<'i>)) -> PRes
         None => {
             let last_value_index = i.offset_from(&value_start_checkpoint);
             if last_value_index == 0 {
-                dispatch(Event::Value(Cow::Borrowed("".into())));
+                dispatch(Event::Value(Cow::Borrowed("".into())), i.len());
                 return Ok(());
             } else {
                 last_value_index
@@ -352,9 +373,9 @@ fn value_impl<'i>(i: &mut &'i [u8], dispatch: &mut dyn FnMut(Event<'i>)) -> PRes
     let remainder_value = i.next_slice(value_end_no_trailing_whitespace);
 
     if partial_value_found {
-        dispatch(Event::ValueDone(Cow::Borrowed(remainder_value.as_bstr())));
+        dispatch(Event::ValueDone(Cow::Borrowed(remainder_value.as_bstr())), i.len());
     } else {
-        dispatch(Event::Value(Cow::Borrowed(remainder_value.as_bstr())));
+        dispatch(Event::Value(Cow::Borrowed(remainder_value.as_bstr())), i.len());
     }
 
     Ok(())
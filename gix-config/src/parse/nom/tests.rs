@@ -180,7 +180,7 @@ mod section {
     fn section<'a>(mut i: &'a [u8], node: &mut ParseNode) -> winnow::IResult<&'a [u8], Section<'a>> {
         let mut header = None;
         let mut events = Vec::new();
-        super::section(&mut i, node, &mut |e| match &header {
+        super::section(&mut i, node, &mut |e, _| match &header {
             None => {
                 header = Some(e);
             }
@@ -514,7 +514,7 @@ mod value_continuation {
     };
 
     pub fn value_impl<'a>(mut i: &'a [u8], events: &mut Vec<Event<'a>>) -> winnow::IResult<&'a [u8], ()> {
-        super::value_impl(&mut i, &mut |e| events.push(e)).map(|_| (i, ()))
+        super::value_impl(&mut i, &mut |e, _| events.push(e)).map(|_| (i, ()))
     }
 
     #[test]
@@ -783,7 +783,7 @@ mod key_value_pair {
         node: &mut ParseNode,
         events: &mut Vec<Event<'a>>,
     ) -> winnow::IResult<&'a [u8], ()> {
-        super::key_value_pair(&mut i, node, &mut |e| events.push(e)).map(|_| (i, ()))
+        super::key_value_pair(&mut i, node, &mut |e, _| events.push(e)).map(|_| (i, ()))
     }
 
     #[test]
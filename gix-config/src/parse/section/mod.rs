@@ -2,7 +2,7 @@ use std::{borrow::Cow, fmt::Display};
 
 use bstr::BStr;
 
-use crate::parse::{Event, Section};
+use crate::parse::{Event, Section, Span};
 
 ///
 #[allow(clippy::empty_docs)]
@@ -24,6 +24,17 @@ pub struct Header<'a> {
     pub(crate) subsection_name: Option<Cow<'a, BStr>>,
 }
 
+/// The location of a section header and its key-value pairs in the parsed input.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct Spans {
+    /// The location of the header, like `[remote "origin"]`.
+    pub header: Span,
+    /// The location of each key-value pair in order of occurrence, from the first byte of the key to the last byte of the value.
+    ///
+    /// Implicit values, like `a` in `[core]\n\ta`, end where their key ends.
+    pub values: Vec<Span>,
+}
+
 impl Section<'_> {
     /// Turn this instance into a fully owned one with `'static` lifetime.
     #[must_use]
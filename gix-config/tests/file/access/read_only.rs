@@ -412,3 +412,178 @@ fn overrides_with_implicit_booleans_work_across_sections() {
     let config = File::try_from(config).unwrap();
     assert_eq!(config.boolean("a.b"), Some(Ok(true)), "empty implicit booleans ");
 }
+
+#[test]
+fn entries_in_multi_file_cascade() -> crate::Result {
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let files = [
+        (
+            "system",
+            gix_config::Source::System,
+            "[core]\n\tbare = false\n\tautocrlf = input\n[remote \"origin\"]\n\turl = a\n",
+        ),
+        (
+            "global",
+            gix_config::Source::User,
+            "; comment\n[Core]\n\tBARE\n[remote \"Origin\"]\n\turl = b\n\tpush = x \\\n y # comment\n",
+        ),
+        (
+            "local",
+            gix_config::Source::Local,
+            "[core]\n\tbare = true\n[remote \"origin\"]\n\tfetch = one\n\tfetch = two\n\turl = c\n",
+        ),
+    ];
+    let mut metas = Vec::new();
+    for (name, source, content) in files {
+        let path = dir.path().join(name);
+        std::fs::write(&path, content)?;
+        metas.push(Metadata::try_from_path(path, source)?);
+    }
+    let config = File::from_paths_metadata(
+        metas,
+        init::Options {
+            spans: true,
+            ..Default::default()
+        },
+    )?
+    .expect("non-empty");
+
+    let actual: Vec<_> = config
+        .entries()
+        .map(|entry| {
+            let path = entry.section.meta().path.as_deref().expect("loaded from file");
+            let content = std::fs::read(path).expect("readable");
+            (
+                path.file_name()
+                    .expect("present")
+                    .to_str()
+                    .expect("valid UTF-8")
+                    .to_owned(),
+                entry.section.meta().source,
+                entry.section.header().subsection_name().map(ToOwned::to_owned),
+                entry.name.to_string(),
+                entry.value.map(Cow::into_owned),
+                BStr::new(&content[entry.span.expect("parsed").to_range()]).to_owned(),
+                entry.is_shadowed,
+            )
+        })
+        .collect();
+    use gix_config::Source::*;
+    let expected = [
+        ("system", System, None, "bare", Some("false"), "bare = false", true),
+        (
+            "system",
+            System,
+            None,
+            "autocrlf",
+            Some("input"),
+            "autocrlf = input",
+            false,
+        ),
+        ("system", System, Some("origin"), "url", Some("a"), "url = a", true),
+        ("global", User, None, "BARE", None, "BARE", true),
+        ("global", User, Some("Origin"), "url", Some("b"), "url = b", false),
+        (
+            "global",
+            User,
+            Some("Origin"),
+            "push",
+            Some("x  y"),
+            "push = x \\\n y",
+            false,
+        ),
+        ("local", Local, None, "bare", Some("true"), "bare = true", false),
+        (
+            "local",
+            Local,
+            Some("origin"),
+            "fetch",
+            Some("one"),
+            "fetch = one",
+            true,
+        ),
+        (
+            "local",
+            Local,
+            Some("origin"),
+            "fetch",
+            Some("two"),
+            "fetch = two",
+            false,
+        ),
+        ("local", Local, Some("origin"), "url", Some("c"), "url = c", false),
+    ]
+    .map(|(file, source, subsection, name, value, span, is_shadowed)| {
+        (
+            file.to_owned(),
+            source,
+            subsection.map(Into::into),
+            name.to_owned(),
+            value.map(Into::into),
+            span.into(),
+            is_shadowed,
+        )
+    });
+    assert_eq!(
+        actual, expected,
+        "files and sections are in order of occurrence, section and key names are case-insensitive, but subsections are not"
+    );
+
+    assert_eq!(
+        config.sections_by_name("remote").expect("present").count(),
+        3,
+        "it's possible to look at a subset of sections"
+    );
+    Ok(())
+}
+
+#[test]
+fn spans_are_discarded_on_mutation() -> crate::Result {
+    let input = "\u{feff}[a]\n\tb = 1\n\tb = 2\n[c \"d\"]\n\te = 3\n";
+    assert!(
+        File::try_from(input)?.entries().all(|entry| entry.span.is_none()),
+        "spans are only recorded if requested"
+    );
+    let mut config = File::from_bytes_no_includes(
+        input.as_bytes(),
+        Metadata::api(),
+        init::Options {
+            spans: true,
+            ..Default::default()
+        },
+    )?;
+    let spans: Vec<_> = config
+        .entries()
+        .map(|entry| entry.span.map(|span| span.to_range()))
+        .collect();
+    assert_eq!(spans, [Some(8..13), Some(15..20), Some(30..35)], "the BOM counts");
+    assert_eq!(
+        config
+            .sections()
+            .next()
+            .and_then(|s| s.spans())
+            .map(|spans| spans.header.to_range()),
+        Some(3..6)
+    );
+
+    config.raw_values_mut(&"a.b")?.set_all("x");
+    config.section_mut("c", Some("d".into()))?;
+    assert!(
+        config.entries().all(|entry| entry.span.is_none()),
+        "sections may change when obtained for mutation, so their location isn't known anymore"
+    );
+    Ok(())
+}
+
+#[test]
+fn contains_key() -> crate::Result {
+    let config = File::try_from("[core]\n\ta = 1\n\tb\n[remote \"origin\"]\n\turl = x\n")?;
+    for key in ["core.a", "core.b", "CORE.A", "remote.origin.url"] {
+        assert!(config.contains_key(key), "{key}");
+    }
+    for key in ["core.c", "remote.Origin.url", "remote.url", "other.a", "invalid"] {
+        assert!(!config.contains_key(key), "{key}");
+    }
+    assert!(!config.contains_key_filter("core.a", &mut |meta| meta.source != gix_config::Source::Api));
+    Ok(())
+}
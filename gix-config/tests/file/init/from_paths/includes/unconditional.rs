@@ -161,7 +161,8 @@ fn respect_max_depth() -> crate::Result {
     assert!(matches!(
         config.unwrap_err(),
         from_paths::Error::Init(init::Error::Includes(includes::Error::IncludeDepthExceeded {
-            max_depth: 2
+            max_depth: 2,
+            ..
         }))
     ));
 
@@ -176,12 +177,65 @@ fn respect_max_depth() -> crate::Result {
     assert!(matches!(
         config.unwrap_err(),
         from_paths::Error::Init(init::Error::Includes(includes::Error::IncludeDepthExceeded {
-            max_depth: 0
+            max_depth: 0,
+            ..
         }))
     ));
     Ok(())
 }
 
+#[test]
+fn depth_limit_is_the_one_of_git_and_ignores_conditional_includes_that_dont_apply() -> crate::Result {
+    let dir = tempdir()?;
+
+    // Each file includes the next one, so 0 starts a chain of 11 includes, and 1 one of 10, just like `git` allows.
+    let last = 11;
+    let path = |i: usize| dir.path().join(i.to_string());
+    for i in 0..last {
+        fs::write(
+            path(i),
+            format!(
+                "[core]\n  i = {i}\n[include]\n  path = {}",
+                escape_backslashes(path(i + 1))
+            ),
+        )?;
+    }
+    fs::write(
+        path(last),
+        format!(
+            "[core]\n  i = {last}\n[includeIf \"gitdir:/never/matches/\"]\n  path = {}",
+            escape_backslashes(path(0))
+        ),
+    )?;
+
+    let config = File::from_paths_metadata(into_meta(vec![path(1)]), follow_options())?.expect("non-empty");
+    assert_eq!(
+        config.integers("core.i"),
+        Some(Ok((1..=11).collect())),
+        "10 includes are allowed, and conditional includes that don't match don't count"
+    );
+
+    let err = File::from_paths_metadata(into_meta(vec![path(0)]), follow_options()).unwrap_err();
+    let from_paths::Error::Init(init::Error::Includes(includes::Error::IncludeDepthExceeded { max_depth, chain })) =
+        &err
+    else {
+        panic!("unexpected error: {err:?}")
+    };
+    assert_eq!(*max_depth, 10);
+    assert_eq!(
+        chain,
+        &(0..=last)
+            .map(|i| path(i).canonicalize())
+            .collect::<Result<Vec<_>, _>>()?,
+        "the 11th include fails like in `git`"
+    );
+    assert!(
+        !err.to_string().contains("cycle"),
+        "there is no cycle, the chain is just too long"
+    );
+    Ok(())
+}
+
 #[test]
 fn simple() -> crate::Result {
     let dir = tempdir()?;
@@ -257,13 +311,31 @@ fn cycle_detection() -> crate::Result {
         },
         ..Default::default()
     };
-    let config = File::from_paths_metadata(into_meta(vec![a_path.clone()]), options);
-    assert!(matches!(
-        config.unwrap_err(),
-        from_paths::Error::Init(init::Error::Includes(includes::Error::IncludeDepthExceeded {
-            max_depth: 4
-        }))
-    ));
+    let err = File::from_paths_metadata(into_meta(vec![a_path.clone()]), options).unwrap_err();
+    let from_paths::Error::Init(init::Error::Includes(includes::Error::IncludeDepthExceeded { max_depth, chain })) =
+        &err
+    else {
+        panic!("unexpected error: {err:?}")
+    };
+    assert_eq!(*max_depth, 4);
+    let (a, b) = (a_path.canonicalize()?, b_path.canonicalize()?);
+    assert_eq!(
+        chain,
+        &[a.clone(), b.clone(), a.clone(), b.clone(), a.clone(), b.clone()],
+        "the chain starts with the including file and ends with the one that exceeds the limit"
+    );
+    let inner = std::error::Error::source(&err)
+        .and_then(std::error::Error::source)
+        .map_or_else(|| err.to_string(), ToString::to_string);
+    assert_eq!(
+        inner,
+        format!(
+            "The maximum allowed length 4 of the file include chain is exceeded while including:\n\t{a}\n\t-> {b}\n\t-> {a}\n\t-> {b}\n\t-> {a}\n\t-> {b}\nThe chain is a cycle as '{b}' includes itself, directly or indirectly.",
+            a = a.display(),
+            b = b.display()
+        ),
+        "the message shows all files and points out the cycle"
+    );
 
     let options = init::Options {
         includes: includes::Options {
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use gix_hash::ObjectId;
+use gix_object::{tree::EntryKind, WriteTo};
+
+/// An in-memory object database.
+#[derive(Default)]
+struct Odb(HashMap<ObjectId, (gix_object::Kind, Vec<u8>)>);
+
+impl Odb {
+    fn write(&mut self, kind: gix_object::Kind, data: Vec<u8>) -> ObjectId {
+        let id = gix_object::compute_hash(gix_hash::Kind::Sha1, kind, &data);
+        self.0.insert(id, (kind, data));
+        id
+    }
+}
+
+impl gix_object::Find for Odb {
+    fn try_find<'a>(
+        &self,
+        id: &gix_hash::oid,
+        buffer: &'a mut Vec<u8>,
+    ) -> Result<Option<gix_object::Data<'a>>, gix_object::find::Error> {
+        Ok(self.0.get(id).map(|(kind, data)| {
+            buffer.clear();
+            buffer.extend_from_slice(data);
+            gix_object::Data { kind: *kind, data: buffer }
+        }))
+    }
+}
+
+/// Write a tree with `fanout` files and `fanout` subdirectories per level, down to `depth`, and return its id.
+/// The file at `changed_path` gets different content.
+fn write_tree(odb: &mut Odb, depth: usize, fanout: usize, path: &str, changed_path: &str) -> ObjectId {
+    let mut tree = gix_object::Tree::empty();
+    if depth > 0 {
+        for i in 0..fanout {
+            let name = format!("d{i}");
+            let oid = write_tree(odb, depth - 1, fanout, &format!("{path}/{name}"), changed_path);
+            tree.entries.push(gix_object::tree::Entry {
+                mode: EntryKind::Tree.into(),
+                filename: name.into(),
+                oid,
+            });
+        }
+    }
+    for i in 0..fanout {
+        let name = format!("f{i}");
+        let file_path = format!("{path}/{name}");
+        let mut content = file_path.clone().into_bytes();
+        if file_path == changed_path {
+            content.extend_from_slice(b" changed");
+        }
+        tree.entries.push(gix_object::tree::Entry {
+            mode: EntryKind::Blob.into(),
+            filename: name.into(),
+            oid: odb.write(gix_object::Kind::Blob, content),
+        });
+    }
+    let mut buf = Vec::new();
+    tree.write_to(&mut buf).expect("writing to memory works");
+    odb.write(gix_object::Kind::Tree, buf)
+}
+
+struct Count(usize);
+
+impl gix_diff::tree::Visit for Count {
+    fn pop_front_tracked_path_and_set_current(&mut self) {}
+    fn push_back_tracked_path_component(&mut self, _component: &gix_object::bstr::BStr) {}
+    fn push_path_component(&mut self, _component: &gix_object::bstr::BStr) {}
+    fn pop_path_component(&mut self) {}
+    fn visit(&mut self, _change: gix_diff::tree::visit::Change) -> gix_diff::tree::visit::Action {
+        self.0 += 1;
+        gix_diff::tree::visit::Action::Continue
+    }
+}
+
+/// With a single changed file, the time it takes to diff should grow with the depth of the tree, not with its size.
+fn depth_first_with_one_change(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tree::depth_first(one change)");
+    let fanout = 10;
+    for depth in [1, 2, 3, 4] {
+        let mut odb = Odb::default();
+        let changed_path = format!("{}/f0", "/d0".repeat(depth));
+        let lhs = write_tree(&mut odb, depth, fanout, "", "");
+        let rhs = write_tree(&mut odb, depth, fanout, "", &changed_path);
+        let num_files = (0..=depth).map(|level| fanout.pow(level as u32 + 1)).sum::<usize>();
+
+        let mut state = gix_diff::tree::depth_first::State::default();
+        group.bench_with_input(BenchmarkId::from_parameter(num_files), &(lhs, rhs), |b, (lhs, rhs)| {
+            b.iter(|| {
+                let mut count = Count(0);
+                gix_diff::tree::depth_first(Some(lhs), Some(rhs), &mut state, &odb, &mut count)
+                    .expect("all objects are present");
+                assert_eq!(count.0, depth + 1, "all parent trees and the file itself changed");
+                black_box(count.0)
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, depth_first_with_one_change);
+criterion_main!(benches);
@@ -1,6 +1,6 @@
 //! For using text diffs, please have a look at the [`imara-diff` documentation](https://docs.rs/imara-diff),
 //! maintained by [Pascal Kuthe](https://github.com/pascalkuthe).
-use std::{collections::HashMap, path::PathBuf};
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use bstr::BString;
 pub use imara_diff::*;
@@ -13,6 +13,21 @@ pub mod pipeline;
 #[allow(clippy::empty_docs)]
 pub mod platform;
 
+///
+#[allow(clippy::empty_docs)]
+pub mod unified_diff;
+pub use unified_diff::UnifiedDiff;
+
+///
+#[allow(clippy::empty_docs)]
+pub mod whitespace;
+
+/// Return `true` if `buf` is considered binary, which like in `git` is the case if it contains a null-byte within the first 8000 bytes.
+pub fn is_binary_buf(buf: &[u8]) -> bool {
+    let buf = &buf[..buf.len().min(8000)];
+    buf.contains(&0)
+}
+
 /// Information about the diff performed to detect similarity.
 #[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
 pub struct DiffLineStats {
@@ -58,6 +73,11 @@ pub struct Driver {
     /// Note that it's invoked with a shell if arguments are given.
     /// Further, if present, it will always be executed, whether `is_binary` is set or not.
     pub binary_to_text_command: Option<BString>,
+    /// If `true`, the output of the `binary_to_text_command` for objects in the object database will be stored in and retrieved from
+    /// the [binary-to-text cache](Pipeline::binary_to_text_cache), if one is set.
+    ///
+    /// This is the equivalent of `diff.<driver>.cachetextconv`.
+    pub cache_binary_to_text: bool,
     /// `Some(true)` if this driver deals with binary files, which means that a `binary_to_text_command` should be used to convert binary
     /// into a textual representation.
     /// Without such a command, anything that is considered binary is not diffed, but only the size of its data is made available.
@@ -83,6 +103,9 @@ pub struct Pipeline {
     pub worktree_filter: gix_filter::Pipeline,
     /// Options affecting the way we read files.
     pub options: pipeline::Options,
+    /// A cache for the output of binary-to-text conversions of objects, used by drivers that
+    /// [ask for it](Driver::cache_binary_to_text).
+    pub binary_to_text_cache: Option<Arc<dyn pipeline::BinaryToTextCache>>,
     /// Drivers to help customize the conversion behaviour depending on the location of items.
     drivers: Vec<Driver>,
     /// Pre-configured attributes to obtain additional diff-related information.
@@ -11,7 +11,7 @@ use gix_filter::{
 };
 use gix_object::tree::EntryKind;
 
-use crate::blob::{Driver, Pipeline, ResourceKind};
+use crate::blob::{is_binary_buf, Driver, Pipeline, ResourceKind};
 
 /// A way to access roots for different kinds of resources that are possibly located and accessible in a worktree.
 #[derive(Clone, Debug, Default)]
@@ -61,6 +61,22 @@ pub struct Outcome {
     pub driver_index: Option<usize>,
     /// The data itself, suitable for diffing, and if the object or worktree item is present at all.
     pub data: Option<Data>,
+    /// If `true`, the data is the output of a [binary-to-text](Driver::binary_to_text_command) conversion, either
+    /// as produced by the program or as obtained from the [cache](BinaryToTextCache), and not the actual content.
+    pub is_derived: bool,
+}
+
+/// A cache for the output of [binary-to-text](Driver::binary_to_text_command) conversions of objects in the object database,
+/// used for drivers with [`cache_binary_to_text`](Driver::cache_binary_to_text) set.
+///
+/// As the cache is merely an optimization, implementations are expected to deal with their errors themselves.
+/// They should also consider stored conversions invalid once the command of the driver changes, which is what `git` does.
+pub trait BinaryToTextCache: Send + Sync {
+    /// Write the output of the conversion of the object with `id` by `driver` into the empty `out` and return `true`,
+    /// or return `false` if it isn't known.
+    fn get(&self, driver: &Driver, id: &gix_hash::oid, out: &mut Vec<u8>) -> bool;
+    /// Store `data` as output of the conversion of the object with `id` by `driver`.
+    fn put(&self, driver: &Driver, id: &gix_hash::oid, data: &[u8]);
 }
 
 /// Options for use in a [`Pipeline`].
@@ -172,6 +188,7 @@ impl Pipeline {
             worktree_filter,
             drivers,
             options,
+            binary_to_text_cache: None,
             attrs: {
                 let mut out = gix_filter::attributes::search::Outcome::default();
                 out.initialize_with_selection(&Default::default(), Some("diff"));
@@ -214,6 +231,11 @@ impl Pipeline {
     ///
     /// As these files are ultimately named tempfiles, they will be leaked unless the [gix_tempfile] is configured with
     /// a signal handler. If they leak, they would remain in the system's `$TMP` directory.
+    ///
+    /// ### About Caching
+    ///
+    /// The output of the binary-to-text conversion of objects in the object database is taken from and stored in the
+    /// [binary-to-text cache](Pipeline::binary_to_text_cache) if the driver [wants that](Driver::cache_binary_to_text).
     #[allow(clippy::too_many_arguments)]
     pub fn convert_to_diffable(
         &mut self,
@@ -226,8 +248,8 @@ impl Pipeline {
         convert: Mode,
         out: &mut Vec<u8>,
     ) -> Result<Outcome, convert_to_diffable::Error> {
-        let is_symlink = match mode {
-            EntryKind::Link if self.options.fs.symlink => true,
+        let is_link = match mode {
+            EntryKind::Link => true,
             EntryKind::Blob | EntryKind::BlobExecutable => false,
             _ => {
                 return Err(convert_to_diffable::Error::InvalidEntryKind {
@@ -246,6 +268,7 @@ impl Pipeline {
             .as_bstr()
             .and_then(|name| self.drivers.binary_search_by(|d| d.name.as_bstr().cmp(name)).ok());
         let driver = driver_index.map(|idx| &self.drivers[idx]);
+        let mut is_derived = false;
         let mut is_binary = if let Some(driver) = driver {
             driver
                 .is_binary
@@ -258,7 +281,7 @@ impl Pipeline {
                 self.path.clear();
                 self.path.push(root);
                 self.path.push(gix_path::from_bstr(rela_path));
-                let data = if is_symlink {
+                let data = if is_link && self.options.fs.symlink {
                     let target = none_if_missing(std::fs::read_link(&self.path)).map_err(|err| {
                         convert_to_diffable::Error::ReadLink {
                             rela_path: rela_path.to_owned(),
@@ -269,6 +292,15 @@ impl Pipeline {
                         out.extend_from_slice(gix_path::into_bstr(target).as_ref());
                         Data::Buffer
                     })
+                } else if is_link {
+                    // Without symlink support, links are checked out as files containing the link target,
+                    // which is never subject to filters or drivers.
+                    none_if_missing(std::fs::File::open(&self.path).and_then(|mut file| file.read_to_end(out)))
+                        .map_err(|err| convert_to_diffable::Error::ReadLink {
+                            rela_path: rela_path.to_owned(),
+                            source: err,
+                        })?
+                        .map(|_| Data::Buffer)
                 } else {
                     let need_size_only = is_binary == Some(true);
                     let size_in_bytes = (need_size_only
@@ -305,6 +337,7 @@ impl Pipeline {
                                         None
                                     } else {
                                         run_cmd(rela_path, cmd, out)?;
+                                        is_derived = true;
                                         Some(Data::Buffer)
                                     }
                                 }
@@ -373,7 +406,11 @@ impl Pipeline {
                         }
                     }
                 };
-                Ok(Outcome { driver_index, data })
+                Ok(Outcome {
+                    driver_index,
+                    data,
+                    is_derived,
+                })
             }
             None => {
                 let data = if id.is_null() {
@@ -389,8 +426,18 @@ impl Pipeline {
                     {
                         is_binary = Some(true);
                     };
+                    let binary_to_text_cache = driver
+                        .filter(|d| d.cache_binary_to_text && d.binary_to_text_command.is_some())
+                        .filter(|_| {
+                            convert == Mode::ToGitUnlessBinaryToTextIsPresent
+                                || (!is_link && convert == Mode::ToWorktreeAndBinaryToText)
+                        })
+                        .and_then(|d| self.binary_to_text_cache.as_deref().map(|cache| (d, cache)));
                     let data = if is_binary == Some(true) {
                         Data::Binary { size: header.size }
+                    } else if matches!(binary_to_text_cache, Some((driver, cache)) if cache.get(driver, id, out)) {
+                        is_derived = true;
+                        Data::Buffer
                     } else {
                         objects
                             .try_find(id, out)
@@ -453,6 +500,10 @@ impl Pipeline {
                                     })?;
                                     out.clear();
                                     run_cmd(rela_path, cmd, out)?;
+                                    is_derived = true;
+                                    if let Some((driver, cache)) = binary_to_text_cache {
+                                        cache.put(driver, id, out);
+                                    }
                                 }
                                 None => {
                                     match res {
@@ -490,17 +541,16 @@ impl Pipeline {
                     };
                     Some(data)
                 };
-                Ok(Outcome { driver_index, data })
+                Ok(Outcome {
+                    driver_index,
+                    data,
+                    is_derived,
+                })
             }
         }
     }
 }
 
-fn is_binary_buf(buf: &[u8]) -> bool {
-    let buf = &buf[..buf.len().min(8000)];
-    buf.contains(&0)
-}
-
 fn none_if_missing<T>(res: std::io::Result<T>) -> std::io::Result<Option<T>> {
     match res {
         Ok(data) => Ok(Some(data)),
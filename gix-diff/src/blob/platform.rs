@@ -79,6 +79,9 @@ pub struct Resource<'a> {
     pub driver_index: Option<usize>,
     /// The data itself, suitable for diffing, and if the object or worktree item is present at all.
     pub data: resource::Data<'a>,
+    /// If `true`, the data is the output of a [binary-to-text](super::Driver::binary_to_text_command) conversion
+    /// and not the actual content of the resource.
+    pub is_derived: bool,
     /// The kind of the resource we are looking at. Only possible values are `Blob`, `BlobExecutable` and `Link`.
     pub mode: gix_object::tree::EntryKind,
     /// The location of the resource, relative to the working tree.
@@ -105,6 +108,7 @@ pub mod resource {
                     pipeline::Data::Buffer => Data::Buffer(&value.buffer),
                     pipeline::Data::Binary { size } => Data::Binary { size },
                 }),
+                is_derived: value.conversion.is_derived,
                 mode: value.mode,
                 rela_path: key.location.as_ref(),
                 id: &key.id,
@@ -386,6 +390,9 @@ impl Platform {
     ///
     /// If one of the resources is binary, the operation reports an error as such resources don't make their data available
     /// which is required for the external diff to run.
+    ///
+    /// Resources that are [derived](Resource::is_derived) are passed with the output of their binary-to-text conversion,
+    /// whereas `git` would pass their actual content.
     pub fn prepare_diff_command(
         &self,
         diff_command: BString,
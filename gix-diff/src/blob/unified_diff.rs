@@ -0,0 +1,221 @@
+use std::ops::Range;
+
+use bstr::{BString, ByteSlice};
+use imara_diff::{intern::InternedInput, Sink};
+
+use crate::blob::whitespace;
+
+/// Options for use with [`UnifiedDiff`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// The amount of unchanged lines to show before and after each change, similar to `git diff -U<n>`.
+    ///
+    /// Changes that are separated by no more than twice this amount of lines are merged into the same hunk.
+    pub context_size: u32,
+    /// The amount of unchanged lines between changes which additionally allows them to be merged into the same hunk,
+    /// similar to `git diff --inter-hunk-context=<n>`.
+    pub inter_hunk_context: u32,
+    /// If `true`, each hunk header is followed by the closest line before the hunk that looks like the start of a function,
+    /// using the built-in heuristic of `git` which matches all lines starting with a letter, `_` or `$`.
+    pub function_names: bool,
+    /// If `true`, changes whose lines are all [blank](whitespace::Options::is_blank()) are omitted unless they are close to
+    /// other changes, similar to `git diff --ignore-blank-lines`.
+    pub ignore_blank_lines: bool,
+    /// The whitespace options that were used to compare the lines of the input, which determines which lines are blank.
+    pub whitespace: whitespace::Options,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            context_size: 3,
+            inter_hunk_context: 0,
+            function_names: true,
+            ignore_blank_lines: false,
+            whitespace: Default::default(),
+        }
+    }
+}
+
+/// A [`Sink`] that produces a patch in the unified diff format exactly like `git diff` would, excluding any file headers.
+///
+/// The input is expected to be interned lines *with* their line terminator, as produced by
+/// [`byte_lines_with_terminator()`](imara_diff::sources::byte_lines_with_terminator()), which allows to
+/// indicate missing newlines at the end of the file.
+///
+/// Like `git`, unchanged lines are shown as they are in the new version, which matters if whitespace is ignored.
+pub struct UnifiedDiff<'a> {
+    before: Vec<&'a [u8]>,
+    after: Vec<&'a [u8]>,
+    options: Options,
+
+    /// All changes, in order, along with a flag that is `true` if they can be ignored as they only affect blank lines.
+    changes: Vec<(Range<u32>, Range<u32>, bool)>,
+    out: BString,
+}
+
+impl<'a> UnifiedDiff<'a> {
+    /// Create a new instance to produce a patch from `input`, configured with `options`, which appends to `out`.
+    pub fn new(input: &'a InternedInput<&'a [u8]>, out: BString, options: Options) -> Self {
+        let lines = |tokens: &[imara_diff::intern::Token]| tokens.iter().map(|&token| input.interner[token]).collect();
+        Self::from_lines(lines(&input.before), lines(&input.after), out, options)
+    }
+
+    /// Create a new instance to produce a patch from the original `before` and `after` lines with their line terminators,
+    /// configured with `options`, which appends to `out`.
+    ///
+    /// This is useful if the input was interned with tokens that don't retain the original of each line, like the
+    /// [lines that ignore whitespace](whitespace::lines()).
+    pub fn from_lines(before: Vec<&'a [u8]>, after: Vec<&'a [u8]>, out: BString, options: Options) -> Self {
+        UnifiedDiff {
+            before,
+            after,
+            options,
+            changes: Vec::new(),
+            out,
+        }
+    }
+
+    fn push_lines(&mut self, tokens: Range<u32>, from_after: bool, prefix: u8) {
+        let lines = if from_after { &self.after } else { &self.before };
+        for line in &lines[tokens.start as usize..tokens.end as usize] {
+            self.out.push(prefix);
+            self.out.extend_from_slice(line);
+            if line.last() != Some(&b'\n') {
+                self.out.extend_from_slice(b"\n\\ No newline at end of file\n");
+            }
+        }
+    }
+
+    /// Return the indices of the first and the last of `changes` that make up the next hunk, or `None` if there is none,
+    /// just like `xdl_get_hunk()` in `git`.
+    fn next_hunk(&self, changes: &[(Range<u32>, Range<u32>, bool)]) -> Option<(usize, usize)> {
+        let max_common = 2 * self.options.context_size + self.options.inter_hunk_context;
+        let max_ignorable = self.options.context_size;
+
+        // Skip ignorable changes that are too far from the next change.
+        let mut first = 0;
+        for (idx, (before, _, _)) in changes.iter().enumerate().take_while(|(_, change)| change.2) {
+            if changes
+                .get(idx + 1)
+                .map_or(true, |next| next.0.start - before.end >= max_ignorable)
+            {
+                first = idx + 1;
+            }
+        }
+        if first == changes.len() {
+            return None;
+        }
+
+        let mut last = first;
+        let mut ignored = 0;
+        for idx in first + 1..changes.len() {
+            let (prev, (before, after, ignorable)) = (&changes[idx - 1], &changes[idx]);
+            let distance = before.start - prev.0.end;
+            if distance > max_common {
+                break;
+            }
+            if distance < max_ignorable && (!ignorable || last == idx - 1) {
+                last = idx;
+                ignored = 0;
+            } else if distance < max_ignorable && *ignorable {
+                ignored += after.len() as u32;
+            } else if last != idx - 1 && before.start + ignored - changes[last].0.end > max_common {
+                break;
+            } else if !ignorable {
+                last = idx;
+                ignored = 0;
+            } else {
+                ignored += after.len() as u32;
+            }
+        }
+        Some((first, last))
+    }
+
+    fn write_hunk(&mut self, changes: &[(Range<u32>, Range<u32>, bool)]) {
+        let context_size = self.options.context_size;
+        let (first, last) = (&changes[0], &changes[changes.len() - 1]);
+        let (before_start, after_start) = (
+            first.0.start.saturating_sub(context_size),
+            first.1.start.saturating_sub(context_size),
+        );
+        let trailing_context = context_size
+            .min(self.before.len() as u32 - last.0.end)
+            .min(self.after.len() as u32 - last.1.end);
+        let (before_end, after_end) = (last.0.end + trailing_context, last.1.end + trailing_context);
+
+        self.out.extend_from_slice(b"@@ -");
+        push_range(&mut self.out, before_start, before_end - before_start);
+        self.out.extend_from_slice(b" +");
+        push_range(&mut self.out, after_start, after_end - after_start);
+        self.out.extend_from_slice(b" @@");
+        if let Some(name) = self
+            .options
+            .function_names
+            .then(|| self.function_name_before(before_start))
+            .flatten()
+        {
+            self.out.push(b' ');
+            self.out.extend_from_slice(name);
+        }
+        self.out.push(b'\n');
+
+        self.push_lines(after_start..first.1.start, true, b' ');
+        let mut pos = (first.0.start, first.1.start);
+        for (before, after, _) in changes {
+            let common = (before.start - pos.0).min(after.start - pos.1);
+            self.push_lines(pos.1..pos.1 + common, true, b' ');
+            self.push_lines(before.clone(), false, b'-');
+            self.push_lines(after.clone(), true, b'+');
+            pos = (before.end, after.end);
+        }
+        self.push_lines(last.1.end..after_end, true, b' ');
+    }
+
+    fn function_name_before(&self, line: u32) -> Option<&'a [u8]> {
+        const MAX_LEN: usize = 80;
+        self.before[..line as usize].iter().rev().find_map(|&line| {
+            matches!(line.first(), Some(b) if b.is_ascii_alphabetic() || *b == b'_' || *b == b'$')
+                .then(|| line[..line.len().min(MAX_LEN)].trim_end())
+        })
+    }
+}
+
+impl Sink for UnifiedDiff<'_> {
+    type Out = BString;
+
+    fn process_change(&mut self, before: Range<u32>, after: Range<u32>) {
+        let ignorable = self.options.ignore_blank_lines && {
+            let whitespace = self.options.whitespace;
+            let is_blank = |lines: &[&[u8]], range: &Range<u32>| {
+                lines[range.start as usize..range.end as usize]
+                    .iter()
+                    .all(|line| whitespace.is_blank(line))
+            };
+            is_blank(&self.before, &before) && is_blank(&self.after, &after)
+        };
+        self.changes.push((before, after, ignorable));
+    }
+
+    fn finish(mut self) -> Self::Out {
+        let changes = std::mem::take(&mut self.changes);
+        let mut remaining = changes.as_slice();
+        while let Some((first, last)) = self.next_hunk(remaining) {
+            self.write_hunk(&remaining[first..=last]);
+            remaining = &remaining[last + 1..];
+        }
+        self.out
+    }
+}
+
+/// Write the `start` and `len` of a hunk like `git` does, which omits the length if it's `1`,
+/// and uses the line before the hunk if it's empty.
+fn push_range(out: &mut BString, start: u32, len: u32) {
+    use std::io::Write;
+    match len {
+        0 => write!(out, "{start},0"),
+        1 => write!(out, "{}", start + 1),
+        _ => write!(out, "{},{len}", start + 1),
+    }
+    .expect("writing to memory never fails");
+}
@@ -0,0 +1,159 @@
+use std::{
+    borrow::Cow,
+    hash::{Hash, Hasher},
+};
+
+use imara_diff::{intern::TokenSource, sources::ByteLines};
+
+/// Options to control which whitespace differences are ignored when comparing lines, similar to the respective options of `git diff`.
+///
+/// The line terminator is ignored as soon as any whitespace is ignored.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// Ignore all whitespace, similar to `git diff -w`.
+    pub ignore_all_space: bool,
+    /// Ignore changes in the amount of whitespace, so any run of whitespace is equal to a single space, and whitespace
+    /// at the end of the line is ignored, similar to `git diff -b`.
+    pub ignore_space_change: bool,
+    /// Ignore changes in whitespace at the end of lines, similar to `git diff --ignore-space-at-eol`.
+    pub ignore_space_at_eol: bool,
+    /// Ignore a carriage-return at the end of lines, similar to `git diff --ignore-cr-at-eol`.
+    pub ignore_cr_at_eol: bool,
+}
+
+impl Options {
+    /// Return `true` if any kind of whitespace is ignored.
+    pub fn is_active(&self) -> bool {
+        self.ignore_all_space || self.ignore_space_change || self.ignore_space_at_eol || self.ignore_cr_at_eol
+    }
+
+    /// Return `true` if `line` is considered blank by `git diff --ignore-blank-lines`, which is the case if it only
+    /// consists of its line terminator, or of whitespace if any whitespace [is ignored](Self::is_active()).
+    pub fn is_blank(&self, line: &[u8]) -> bool {
+        if self.is_active() {
+            line.iter().all(|b| is_space(*b))
+        } else {
+            line.len() <= 1
+        }
+    }
+
+    /// Return `line`, including its line terminator, in the form that is used to compare it to other lines.
+    pub fn normalize<'a>(&self, line: &'a [u8]) -> Cow<'a, [u8]> {
+        if !self.is_active() {
+            return Cow::Borrowed(line);
+        }
+        let (content, has_terminator) = match line.strip_suffix(b"\n") {
+            Some(content) => (content, true),
+            None => (line, false),
+        };
+        if !(self.ignore_all_space || self.ignore_space_change || self.ignore_space_at_eol) {
+            // A carriage-return of an incomplete last line is not ignored, just like in `git`.
+            return Cow::Borrowed(if has_terminator {
+                content.strip_suffix(b"\r").unwrap_or(content)
+            } else {
+                content
+            });
+        }
+
+        let mut out = Vec::with_capacity(content.len());
+        let mut pos = 0;
+        while pos < content.len() {
+            if !is_space(content[pos]) {
+                out.push(content[pos]);
+                pos += 1;
+                continue;
+            }
+            let start = pos;
+            while pos < content.len() && is_space(content[pos]) {
+                pos += 1;
+            }
+            let at_eol = pos == content.len();
+            if self.ignore_all_space || at_eol {
+                continue;
+            }
+            if self.ignore_space_change {
+                out.push(b' ');
+            } else {
+                out.extend_from_slice(&content[start..pos]);
+            }
+        }
+        Cow::Owned(out)
+    }
+}
+
+/// A line that compares and hashes by its form [normalized](Options::normalize()) according to the whitespace [`Options`],
+/// while retaining the original for display.
+#[derive(Debug, Clone)]
+pub struct Line<'a> {
+    /// The original line, including its line terminator.
+    pub data: &'a [u8],
+    normalized: Cow<'a, [u8]>,
+}
+
+impl PartialEq for Line<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.normalized == other.normalized
+    }
+}
+
+impl Eq for Line<'_> {}
+
+impl Hash for Line<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.normalized.hash(state);
+    }
+}
+
+/// A [`TokenSource`] for the lines of a buffer, including their line terminator, which compare according to whitespace [`Options`].
+///
+/// Note that the interner keeps only one of the lines that compare equal, so to display the original lines, obtain them separately,
+/// for instance with [`byte_lines_with_terminator()`](imara_diff::sources::byte_lines_with_terminator()).
+#[derive(Debug, Copy, Clone)]
+pub struct Lines<'a> {
+    data: &'a [u8],
+    options: Options,
+}
+
+/// Return a [`TokenSource`] for the lines in `data` which compare according to `options`.
+pub fn lines(data: &[u8], options: Options) -> Lines<'_> {
+    Lines { data, options }
+}
+
+impl<'a> TokenSource for Lines<'a> {
+    type Token = Line<'a>;
+    type Tokenizer = Tokenizer<'a>;
+
+    fn tokenize(&self) -> Self::Tokenizer {
+        Tokenizer {
+            lines: imara_diff::sources::byte_lines_with_terminator(self.data),
+            options: self.options,
+        }
+    }
+
+    fn estimate_tokens(&self) -> u32 {
+        imara_diff::sources::byte_lines_with_terminator(self.data).estimate_tokens()
+    }
+}
+
+/// The iterator over the [lines](Line) of [`Lines`].
+pub struct Tokenizer<'a> {
+    lines: ByteLines<'a, true>,
+    options: Options,
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Line<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let data = self.lines.next()?;
+        Some(Line {
+            data,
+            normalized: self.options.normalize(data),
+        })
+    }
+}
+
+/// Like `isspace()` in C, as used by `git`.
+fn is_space(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\n' | b'\r' | 0x0b | 0x0c)
+}
@@ -34,6 +34,8 @@ impl<'a> tree::Changes<'a> {
     /// * To obtain progress, implement it within the `delegate`.
     /// * Tree entries are expected to be ordered using [`tree-entry-comparison`][git_cmp_c] (the same [in Rust][git_cmp_rs])
     /// * it does a breadth first iteration as buffer space only fits two trees, the current one on the one we compare with.
+    ///   Use [`depth_first()`](tree::depth_first()) to receive changes in path order instead.
+    /// * subtrees with the same id on both sides are not traversed as they can't contain changes.
     /// * does not do rename tracking but attempts to reduce allocations to zero (so performance is mostly determined
     ///   by the delegate implementation which should be as specific as possible. Rename tracking can be computed on top of the changes
     ///   received by the `delegate`.
@@ -109,7 +111,7 @@ impl<'a> tree::Changes<'a> {
     }
 }
 
-fn compare(a: &EntryRef<'_>, b: &EntryRef<'_>) -> std::cmp::Ordering {
+pub(super) fn compare(a: &EntryRef<'_>, b: &EntryRef<'_>) -> std::cmp::Ordering {
     let common = a.filename.len().min(b.filename.len());
     a.filename[..common].cmp(&b.filename[..common]).then_with(|| {
         let a = a.filename.get(common).or_else(|| a.mode.is_tree().then_some(&b'/'));
@@ -251,16 +253,20 @@ fn handle_lhs_and_rhs_with_equal_filenames<R: tree::Visit>(
 ) -> Result<(), Error> {
     match (lhs.mode.is_tree(), rhs.mode.is_tree()) {
         (true, true) => {
+            if lhs.oid == rhs.oid {
+                // Identical trees have no changes, there is no need to traverse them.
+                delegate.push_path_component(lhs.filename);
+                return Ok(());
+            }
             delegate.push_back_tracked_path_component(lhs.filename);
-            if lhs.oid != rhs.oid
-                && delegate
-                    .visit(Change::Modification {
-                        previous_entry_mode: lhs.mode,
-                        previous_oid: lhs.oid.to_owned(),
-                        entry_mode: rhs.mode,
-                        oid: rhs.oid.to_owned(),
-                    })
-                    .cancelled()
+            if delegate
+                .visit(Change::Modification {
+                    previous_entry_mode: lhs.mode,
+                    previous_oid: lhs.oid.to_owned(),
+                    entry_mode: rhs.mode,
+                    oid: rhs.oid.to_owned(),
+                })
+                .cancelled()
             {
                 return Err(Error::Cancelled);
             }
@@ -313,7 +319,7 @@ fn handle_lhs_and_rhs_with_equal_filenames<R: tree::Visit>(
         (false, false) => {
             delegate.push_path_component(lhs.filename);
             debug_assert!(lhs.mode.is_no_tree() && lhs.mode.is_no_tree());
-            if lhs.oid != rhs.oid
+            if (lhs.oid != rhs.oid || lhs.mode != rhs.mode)
                 && delegate
                     .visit(Change::Modification {
                         previous_entry_mode: lhs.mode,
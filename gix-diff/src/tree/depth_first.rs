@@ -0,0 +1,225 @@
+/// The state required to run [`depth_first()`](super::depth_first()), to be instantiated with `State::default()`.
+///
+/// It keeps one pair of buffers per level of depth that is currently traversed, to be reused across runs.
+#[derive(Default, Clone)]
+pub struct State {
+    buffers: Vec<Vec<u8>>,
+}
+
+impl State {
+    fn take_buffer(&mut self) -> Vec<u8> {
+        let mut buf = self.buffers.pop().unwrap_or_default();
+        buf.clear();
+        buf
+    }
+}
+
+pub(super) mod function {
+    use gix_hash::oid;
+    use gix_object::{tree::EntryRef, FindExt, TreeRefIter};
+
+    use super::State;
+    use crate::{
+        tree,
+        tree::{
+            changes::{compare, Error},
+            visit::Change,
+        },
+    };
+
+    /// Calculate the changes that would need to be applied to the tree with id `lhs` to get the tree with id `rhs`,
+    /// using `objects` to obtain trees as needed for traversal, and pass them to `delegate` in the order of their paths.
+    /// If `lhs` or `rhs` are `None`, they are treated as empty trees.
+    ///
+    /// * The `state` may be reused across runs to avoid allocations.
+    /// * `delegate` receives the changes through [`Visit::visit()`](tree::Visit::visit()), and the path is tracked solely
+    ///   with [`push_path_component()`](tree::Visit::push_path_component()) and
+    ///   [`pop_path_component()`](tree::Visit::pop_path_component()), which makes implementations of
+    ///   [`Recorder`](tree::Recorder) usable here as well. Returning [`tree::visit::Action::Cancel`] stops the traversal
+    ///   with [`Error::Cancelled`].
+    ///
+    /// # Notes
+    ///
+    /// * In contrast to [`Changes::needed_to_obtain()`](tree::Changes::needed_to_obtain()), this traversal is depth-first.
+    ///   This makes it suitable for diffing huge trees as changes are streamed in path order, without queueing subtrees
+    ///   for later traversal. Only the two trees of each level of depth that is currently traversed are kept in memory.
+    /// * Subtrees with the same id on both sides are skipped without being looked up, and if `lhs` and `rhs` are equal no
+    ///   object is looked up at all.
+    /// * Changes to trees are emitted before the changes of their contents.
+    /// * The recursion depth is limited by the depth of the trees that are compared.
+    pub fn depth_first<R>(
+        lhs: Option<&oid>,
+        rhs: Option<&oid>,
+        state: &mut State,
+        objects: impl gix_object::Find,
+        delegate: &mut R,
+    ) -> Result<(), Error>
+    where
+        R: tree::Visit,
+    {
+        if lhs == rhs {
+            return Ok(());
+        }
+        diff_level(lhs, rhs, state, &objects, delegate)
+    }
+
+    fn diff_level<R: tree::Visit>(
+        lhs: Option<&oid>,
+        rhs: Option<&oid>,
+        state: &mut State,
+        objects: &dyn gix_object::Find,
+        delegate: &mut R,
+    ) -> Result<(), Error> {
+        let mut lhs_buf = state.take_buffer();
+        let mut rhs_buf = state.take_buffer();
+        let res = diff_trees(lhs, rhs, &mut lhs_buf, &mut rhs_buf, state, objects, delegate);
+        state.buffers.push(lhs_buf);
+        state.buffers.push(rhs_buf);
+        res
+    }
+
+    fn diff_trees<R: tree::Visit>(
+        lhs: Option<&oid>,
+        rhs: Option<&oid>,
+        lhs_buf: &mut Vec<u8>,
+        rhs_buf: &mut Vec<u8>,
+        state: &mut State,
+        objects: &dyn gix_object::Find,
+        delegate: &mut R,
+    ) -> Result<(), Error> {
+        let mut lhs_entries = match lhs {
+            Some(id) => objects.find_tree_iter(id, lhs_buf)?,
+            None => TreeRefIter::from_bytes(&[]),
+        };
+        let mut rhs_entries = match rhs {
+            Some(id) => objects.find_tree_iter(id, rhs_buf)?,
+            None => TreeRefIter::from_bytes(&[]),
+        };
+
+        let mut lhs = lhs_entries.next().transpose()?;
+        let mut rhs = rhs_entries.next().transpose()?;
+        loop {
+            match (lhs.take(), rhs.take()) {
+                (None, None) => break,
+                (Some(l), None) => {
+                    deletion(l, state, objects, delegate)?;
+                    lhs = lhs_entries.next().transpose()?;
+                }
+                (None, Some(r)) => {
+                    addition(r, state, objects, delegate)?;
+                    rhs = rhs_entries.next().transpose()?;
+                }
+                (Some(l), Some(r)) => {
+                    use std::cmp::Ordering::*;
+                    match compare(&l, &r) {
+                        Less => {
+                            deletion(l, state, objects, delegate)?;
+                            lhs = lhs_entries.next().transpose()?;
+                            rhs = Some(r);
+                        }
+                        Greater => {
+                            addition(r, state, objects, delegate)?;
+                            rhs = rhs_entries.next().transpose()?;
+                            lhs = Some(l);
+                        }
+                        Equal => {
+                            modification(l, r, state, objects, delegate)?;
+                            lhs = lhs_entries.next().transpose()?;
+                            rhs = rhs_entries.next().transpose()?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn deletion<R: tree::Visit>(
+        entry: EntryRef<'_>,
+        state: &mut State,
+        objects: &dyn gix_object::Find,
+        delegate: &mut R,
+    ) -> Result<(), Error> {
+        delegate.push_path_component(entry.filename);
+        let res = visit(
+            delegate,
+            Change::Deletion {
+                entry_mode: entry.mode,
+                oid: entry.oid.to_owned(),
+            },
+        )
+        .and_then(|()| {
+            if entry.mode.is_tree() {
+                diff_level(Some(entry.oid), None, state, objects, delegate)
+            } else {
+                Ok(())
+            }
+        });
+        delegate.pop_path_component();
+        res
+    }
+
+    fn addition<R: tree::Visit>(
+        entry: EntryRef<'_>,
+        state: &mut State,
+        objects: &dyn gix_object::Find,
+        delegate: &mut R,
+    ) -> Result<(), Error> {
+        delegate.push_path_component(entry.filename);
+        let res = visit(
+            delegate,
+            Change::Addition {
+                entry_mode: entry.mode,
+                oid: entry.oid.to_owned(),
+            },
+        )
+        .and_then(|()| {
+            if entry.mode.is_tree() {
+                diff_level(None, Some(entry.oid), state, objects, delegate)
+            } else {
+                Ok(())
+            }
+        });
+        delegate.pop_path_component();
+        res
+    }
+
+    fn modification<R: tree::Visit>(
+        lhs: EntryRef<'_>,
+        rhs: EntryRef<'_>,
+        state: &mut State,
+        objects: &dyn gix_object::Find,
+        delegate: &mut R,
+    ) -> Result<(), Error> {
+        if lhs.oid == rhs.oid && lhs.mode == rhs.mode {
+            return Ok(());
+        }
+        delegate.push_path_component(lhs.filename);
+        let res = visit(
+            delegate,
+            Change::Modification {
+                previous_entry_mode: lhs.mode,
+                previous_oid: lhs.oid.to_owned(),
+                entry_mode: rhs.mode,
+                oid: rhs.oid.to_owned(),
+            },
+        )
+        .and_then(|()| {
+            if lhs.mode.is_tree() && rhs.mode.is_tree() && lhs.oid != rhs.oid {
+                diff_level(Some(lhs.oid), Some(rhs.oid), state, objects, delegate)
+            } else {
+                Ok(())
+            }
+        });
+        delegate.pop_path_component();
+        res
+    }
+
+    fn visit<R: tree::Visit>(delegate: &mut R, change: Change) -> Result<(), Error> {
+        if delegate.visit(change).cancelled() {
+            Err(Error::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -37,6 +37,11 @@ where
 #[allow(clippy::empty_docs)]
 pub mod changes;
 
+///
+#[allow(clippy::empty_docs)]
+pub mod depth_first;
+pub use depth_first::function::depth_first;
+
 ///
 #[allow(clippy::empty_docs)]
 pub mod visit;
@@ -435,6 +435,68 @@ pub(crate) mod convert_to_diffable {
         Ok(())
     }
 
+    #[test]
+    fn links_checked_out_as_files_are_not_filtered() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let filter = gix_filter::Pipeline::new(
+            Default::default(),
+            gix_filter::pipeline::Options {
+                eol_config: eol::Configuration {
+                    auto_crlf: AutoCrlf::Enabled,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        let mut options = default_options();
+        options.fs.symlink = false;
+        let mut filter = gix_diff::blob::Pipeline::new(
+            WorktreeRoots {
+                old_root: Some(tmp.path().to_owned()),
+                new_root: None,
+            },
+            filter,
+            vec![],
+            options,
+        );
+
+        let does_not_matter = gix_hash::Kind::Sha1.null();
+        let mut buf = Vec::new();
+        let link_name = "link";
+        let link_content = "a\r\nb";
+        std::fs::write(tmp.path().join(link_name), link_content.as_bytes())?;
+        let out = filter.convert_to_diffable(
+            &does_not_matter,
+            EntryKind::Link,
+            link_name.into(),
+            ResourceKind::OldOrSource,
+            &mut |_, _| {},
+            &gix_object::find::Never,
+            pipeline::Mode::ToGit,
+            &mut buf,
+        )?;
+        assert!(out.driver_index.is_none());
+        assert_eq!(out.data, Some(pipeline::Data::Buffer));
+        assert_eq!(
+            buf.as_bstr(),
+            link_content,
+            "without symlink support, the file content is the link target, which is never converted back to git"
+        );
+
+        let out = filter.convert_to_diffable(
+            &does_not_matter,
+            EntryKind::Link,
+            "missing".into(),
+            ResourceKind::OldOrSource,
+            &mut |_, _| {},
+            &gix_object::find::Never,
+            pipeline::Mode::ToGit,
+            &mut buf,
+        )?;
+        assert_eq!(out.data, None, "missing files are detected as such");
+        Ok(())
+    }
+
     #[test]
     fn binary_by_buffer_inspection() -> crate::Result {
         let tmp = gix_testtools::tempfile::TempDir::new()?;
@@ -968,6 +1030,172 @@ pub(crate) mod convert_to_diffable {
         Ok(())
     }
 
+    #[test]
+    fn with_driver_and_binary_to_text_cache() -> crate::Result {
+        #[derive(Default)]
+        struct MemoryCache(
+            std::sync::Mutex<std::collections::HashMap<(gix_object::bstr::BString, gix_hash::ObjectId), Vec<u8>>>,
+        );
+        impl pipeline::BinaryToTextCache for MemoryCache {
+            fn get(&self, driver: &gix_diff::blob::Driver, id: &gix_hash::oid, out: &mut Vec<u8>) -> bool {
+                match self.0.lock().unwrap().get(&(driver.name.clone(), id.to_owned())) {
+                    Some(data) => {
+                        out.extend_from_slice(data);
+                        true
+                    }
+                    None => false,
+                }
+            }
+
+            fn put(&self, driver: &gix_diff::blob::Driver, id: &gix_hash::oid, data: &[u8]) {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .insert((driver.name.clone(), id.to_owned()), data.to_owned());
+            }
+        }
+
+        let root = gix_testtools::scripted_fixture_read_only_standalone("make_blob_repo.sh")?;
+        let command = "echo to-text; cat <";
+        let mut attributes = gix_worktree::Stack::new(
+            &root,
+            gix_worktree::stack::State::AttributesStack(gix_worktree::stack::state::Attributes::new(
+                Default::default(),
+                None,
+                attributes::Source::WorktreeThenIdMapping,
+                Default::default(),
+            )),
+            gix_worktree::glob::pattern::Case::Sensitive,
+            Vec::new(),
+            Vec::new(),
+        );
+        let mut filter = gix_diff::blob::Pipeline::new(
+            WorktreeRoots {
+                old_root: Some(root.clone()),
+                new_root: None,
+            },
+            gix_filter::Pipeline::default(),
+            vec![
+                gix_diff::blob::Driver {
+                    name: "a".into(),
+                    binary_to_text_command: Some(command.into()),
+                    cache_binary_to_text: true,
+                    ..Default::default()
+                },
+                gix_diff::blob::Driver {
+                    name: "d".into(),
+                    binary_to_text_command: Some(command.into()),
+                    ..Default::default()
+                },
+            ],
+            default_options(),
+        );
+        let cache = std::sync::Arc::new(MemoryCache::default());
+        filter.binary_to_text_cache = Some(cache.clone());
+
+        let mut db = ObjectDb::default();
+        let mut buf = Vec::new();
+        let id = db.insert("a\n");
+        let a = attributes.at_entry("a", None, &gix_object::find::Never)?;
+        let convert = |filter: &mut gix_diff::blob::Pipeline,
+                       id: &gix_hash::oid,
+                       kind: ResourceKind,
+                       mode: pipeline::Mode,
+                       buf: &mut Vec<u8>|
+         -> crate::Result<pipeline::Outcome> {
+            Ok(filter.convert_to_diffable(
+                id,
+                EntryKind::Blob,
+                "a".into(),
+                kind,
+                &mut |_, out| {
+                    let _ = a.matching_attributes(out);
+                },
+                &db,
+                mode,
+                buf,
+            )?)
+        };
+
+        let out = convert(
+            &mut filter,
+            &id,
+            ResourceKind::NewOrDestination,
+            pipeline::Mode::ToWorktreeAndBinaryToText,
+            &mut buf,
+        )?;
+        assert_eq!(out.data, Some(pipeline::Data::Buffer));
+        assert!(out.is_derived, "the converted data isn't the actual content");
+        assert_eq!(buf.as_bstr(), "to-text\na\n", "filter was applied");
+        assert_eq!(cache.0.lock().unwrap().len(), 1, "the conversion was stored");
+
+        for value in cache.0.lock().unwrap().values_mut() {
+            *value = b"cached\n".to_vec();
+        }
+        for mode in [
+            pipeline::Mode::ToWorktreeAndBinaryToText,
+            pipeline::Mode::ToGitUnlessBinaryToTextIsPresent,
+        ] {
+            let out = convert(&mut filter, &id, ResourceKind::NewOrDestination, mode, &mut buf)?;
+            assert_eq!(out.data, Some(pipeline::Data::Buffer));
+            assert!(out.is_derived);
+            assert_eq!(
+                buf.as_bstr(),
+                "cached\n",
+                "the cached conversion is used instead of running the program"
+            );
+        }
+
+        let out = convert(
+            &mut filter,
+            &id,
+            ResourceKind::NewOrDestination,
+            pipeline::Mode::ToGit,
+            &mut buf,
+        )?;
+        assert!(!out.is_derived);
+        assert_eq!(buf.as_bstr(), "a\n", "no conversion, no cache");
+
+        let null = gix_hash::Kind::Sha1.null();
+        let out = convert(
+            &mut filter,
+            &null,
+            ResourceKind::OldOrSource,
+            pipeline::Mode::ToWorktreeAndBinaryToText,
+            &mut buf,
+        )?;
+        assert!(out.is_derived);
+        assert_eq!(
+            buf.as_bstr(),
+            "to-text\na\n",
+            "worktree files are always converted as they have no id to serve as key"
+        );
+        assert_eq!(cache.0.lock().unwrap().len(), 1, "nothing new was stored");
+
+        let d = attributes.at_entry("d", None, &gix_object::find::Never)?;
+        let id = db.insert("d-in-db");
+        let out = filter.convert_to_diffable(
+            &id,
+            EntryKind::Blob,
+            "d".into(),
+            ResourceKind::NewOrDestination,
+            &mut |_, out| {
+                let _ = d.matching_attributes(out);
+            },
+            &db,
+            pipeline::Mode::ToWorktreeAndBinaryToText,
+            &mut buf,
+        )?;
+        assert!(out.is_derived);
+        assert_eq!(buf.as_bstr(), "to-text\nd-in-db");
+        assert_eq!(
+            cache.0.lock().unwrap().len(),
+            1,
+            "drivers that don't want caching don't use the cache"
+        );
+        Ok(())
+    }
+
     pub(crate) fn default_options() -> Options {
         Options {
             large_file_threshold_bytes: 0,
@@ -976,6 +1204,7 @@ pub(crate) mod convert_to_diffable {
                 ignore_case: false,
                 executable_bit: true,
                 symlink: true,
+                long_paths: false,
             },
         }
     }
@@ -0,0 +1,193 @@
+use gix_diff::blob::{
+    intern::InternedInput,
+    unified_diff::{self, Options},
+    Algorithm, UnifiedDiff,
+};
+
+fn diff(old: &str, new: &str, options: Options) -> String {
+    let input = InternedInput::new(
+        gix_diff::blob::sources::byte_lines_with_terminator(old.as_bytes()),
+        gix_diff::blob::sources::byte_lines_with_terminator(new.as_bytes()),
+    );
+    gix_diff::blob::diff(
+        Algorithm::Myers,
+        &input,
+        UnifiedDiff::new(&input, Default::default(), options),
+    )
+    .to_string()
+}
+
+const OLD: &str = "fn a() {\n  1\n  2\n  3\n  4\n  5\n  6\n  7\n  8\n  9\n  10\n}\n";
+const NEW: &str = "fn a() {\n  1\n  two\n  3\n  4\n  5\n  6\n  7\n  8\n  9\n  ten\n}\n";
+
+#[test]
+fn close_changes_share_a_hunk() {
+    assert_eq!(
+        diff(OLD, NEW, Options::default()),
+        "@@ -1,6 +1,6 @@\n fn a() {\n   1\n-  2\n+  two\n   3\n   4\n   5\n@@ -8,5 +8,5 @@ fn a() {\n   7\n   8\n   9\n-  10\n+  ten\n }\n"
+    );
+}
+
+#[test]
+fn smaller_context_splits_hunks_and_shows_function_names() {
+    let options = Options {
+        context_size: 1,
+        ..Default::default()
+    };
+    assert_eq!(
+        diff(OLD, NEW, options),
+        "@@ -2,3 +2,3 @@ fn a() {\n   1\n-  2\n+  two\n   3\n@@ -10,3 +10,3 @@ fn a() {\n   9\n-  10\n+  ten\n }\n"
+    );
+    assert_eq!(
+        diff(
+            OLD,
+            NEW,
+            Options {
+                function_names: false,
+                ..options
+            }
+        ),
+        "@@ -2,3 +2,3 @@\n   1\n-  2\n+  two\n   3\n@@ -10,3 +10,3 @@\n   9\n-  10\n+  ten\n }\n"
+    );
+}
+
+#[test]
+fn missing_newline_at_end_of_file() {
+    assert_eq!(
+        diff("a\nb", "a\nc\n", Options::default()),
+        "@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+c\n"
+    );
+}
+
+#[test]
+fn empty_sides_and_zero_context() {
+    assert_eq!(diff("", "x\ny\n", Options::default()), "@@ -0,0 +1,2 @@\n+x\n+y\n");
+    assert_eq!(diff("x\ny\n", "", Options::default()), "@@ -1,2 +0,0 @@\n-x\n-y\n");
+    assert_eq!(
+        diff(
+            "a\nb\nc\n",
+            "a\nc\n",
+            Options {
+                context_size: 0,
+                ..Default::default()
+            }
+        ),
+        "@@ -2 +1,0 @@ a\n-b\n"
+    );
+    assert_eq!(diff("same\n", "same\n", unified_diff::Options::default()), "");
+}
+
+mod whitespace {
+    use gix_diff::blob::{
+        intern::InternedInput,
+        sources::byte_lines_with_terminator,
+        unified_diff::Options,
+        whitespace::{self, lines},
+        Algorithm, UnifiedDiff,
+    };
+
+    fn diff(old: &[u8], new: &[u8], options: Options) -> String {
+        let input = InternedInput::new(lines(old, options.whitespace), lines(new, options.whitespace));
+        gix_diff::blob::diff(
+            Algorithm::Myers,
+            &input,
+            UnifiedDiff::from_lines(
+                byte_lines_with_terminator(old).collect(),
+                byte_lines_with_terminator(new).collect(),
+                Default::default(),
+                options,
+            ),
+        )
+        .to_string()
+    }
+
+    /// Parse `args` of `git diff` as used in the fixture script into `Options`.
+    fn options_from_args(args: &str) -> Options {
+        let mut options = Options::default();
+        for arg in args.split(' ').filter(|arg| !arg.is_empty()) {
+            match arg {
+                "-w" => options.whitespace.ignore_all_space = true,
+                "-b" => options.whitespace.ignore_space_change = true,
+                "--ignore-space-at-eol" => options.whitespace.ignore_space_at_eol = true,
+                "--ignore-cr-at-eol" => options.whitespace.ignore_cr_at_eol = true,
+                "--ignore-blank-lines" => options.ignore_blank_lines = true,
+                "-U1" => options.context_size = 1,
+                "--inter-hunk-context=2" => options.inter_hunk_context = 2,
+                unknown => unreachable!("unknown argument: {unknown}"),
+            }
+        }
+        options
+    }
+
+    #[test]
+    fn baseline() -> gix_testtools::Result {
+        let dir = gix_testtools::scripted_fixture_read_only_standalone("make_whitespace_diff_baselines.sh")?;
+        let mut count = 0;
+        for name in ["indentation", "blank-lines", "line-endings"] {
+            let old = std::fs::read(dir.join(format!("{name}.old")))?;
+            let new = std::fs::read(dir.join(format!("{name}.new")))?;
+            for args in [
+                "",
+                "-w",
+                "-b",
+                "--ignore-space-at-eol",
+                "--ignore-cr-at-eol",
+                "--ignore-blank-lines",
+                "-w --ignore-blank-lines",
+                "-U1",
+                "-U1 --inter-hunk-context=2",
+                "-U1 -b --ignore-blank-lines",
+            ] {
+                let baseline_name = format!("{name}.{}.baseline", args.replace(' ', "_").replace('=', "-"));
+                let expected = std::fs::read_to_string(dir.join(&baseline_name))?;
+                assert_eq!(
+                    diff(&old, &new, options_from_args(args)),
+                    expected,
+                    "{baseline_name}: the output matches the one of `git diff {args}`"
+                );
+                count += 1;
+            }
+        }
+        assert_eq!(count, 30, "all baselines were checked");
+        Ok(())
+    }
+
+    #[test]
+    fn normalize() {
+        let all = whitespace::Options {
+            ignore_all_space: true,
+            ..Default::default()
+        };
+        let change = whitespace::Options {
+            ignore_space_change: true,
+            ..Default::default()
+        };
+        let eol = whitespace::Options {
+            ignore_space_at_eol: true,
+            ..Default::default()
+        };
+        let cr = whitespace::Options {
+            ignore_cr_at_eol: true,
+            ..Default::default()
+        };
+        let line = b"\t a  b \r\n";
+        assert_eq!(
+            whitespace::Options::default().normalize(line).as_ref(),
+            line,
+            "nothing is ignored by default"
+        );
+        assert_eq!(all.normalize(line).as_ref(), b"ab");
+        assert_eq!(change.normalize(line).as_ref(), b" a b");
+        assert_eq!(eol.normalize(line).as_ref(), b"\t a  b");
+        assert_eq!(cr.normalize(line).as_ref(), b"\t a  b ");
+        assert_eq!(
+            cr.normalize(b"a\r").as_ref(),
+            b"a\r",
+            "the carriage-return of an incomplete line is kept"
+        );
+
+        assert!(!whitespace::Options::default().is_blank(b" \n"));
+        assert!(whitespace::Options::default().is_blank(b"\n"));
+        assert!(change.is_blank(b" \t\r\n"));
+    }
+}
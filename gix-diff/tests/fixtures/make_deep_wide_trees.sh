@@ -0,0 +1,28 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config core.autocrlf false
+
+for a in $(seq 0 9); do
+  for b in $(seq 0 9); do
+    mkdir -p "d$a/d$b"
+    for c in $(seq 0 9); do
+      echo "$a$b$c" > "d$a/d$b/f$c"
+    done
+  done
+done
+git add . && git commit -q -m "base"
+git tag base
+
+echo changed >> d3/d4/f5
+rm d0/d0/f0
+mkdir -p d9/new/deeper
+echo new > d9/new/deeper/file
+echo "sorts before the directory of the same name" > d2/d2.x
+rm -r d5/d5
+echo "a file where a directory used to be" > d5/d5
+git add . && git commit -q -m "few changes"
+git tag changes
+
+git diff-tree -r -t --no-renames --raw base changes > changes.baseline
@@ -0,0 +1,32 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+# Write the hunks of `git diff` between `$1.old` and `$1.new` with the given arguments to `$1.<arguments>.baseline`,
+# omitting the file headers.
+function baseline() {
+  local name=${1:?need name}
+  shift
+  local out
+  out="$name.$(echo "$*" | tr ' =' '_-').baseline"
+  { git diff --no-index --no-color "$@" -- "$name.old" "$name.new" || test $? -eq 1; } | sed -n '/^@@/,$p' >"$out"
+}
+
+function all_baselines() {
+  local name=${1:?need name}
+  for args in "" "-w" "-b" "--ignore-space-at-eol" "--ignore-cr-at-eol" "--ignore-blank-lines" "-w --ignore-blank-lines" "-U1" "-U1 --inter-hunk-context=2" "-U1 -b --ignore-blank-lines"; do
+    # shellcheck disable=SC2086
+    baseline "$name" $args
+  done
+}
+
+printf 'fn main() {\n    let a = 1;\n    let b = 2;\n    if a < b {\n        println!("less");\n    }\n    let c = a + b;\n    println!("{c}");\n    let d = c * 2;\n    println!("{d}");\n    let e = d - 1;\n    println!("{e}");\n}\n' >indentation.old
+printf 'fn main() {\n\tlet a = 1;\n    let  b  =  2;   \n    if a < b {\n        println!("more");\n    }\r\n    let c = a+b;\n    println!("{c}");\n    let d = c * 2; \n    println!("{d}");\n\tlet e = d - 1;\n    println!( "{e}" );\n}\n' >indentation.new
+all_baselines indentation
+
+printf 'one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\neleven\ntwelve\nthirteen\nfourteen\nfifteen\n' >blank-lines.old
+printf 'one\n\ntwo\nthree\nfour\n  \nfive\nsix\nSEVEN\neight\nnine\nten\neleven\n\n\ntwelve\nthirteen\nfourteen\nFIFTEEN\n\n' >blank-lines.new
+all_baselines blank-lines
+
+printf 'a\r\nb\r\nc\r\nd\ne\nf\ng\nh\r\n' >line-endings.old
+printf 'a\nb\r\nc\nd\r\ne \nf\ng\nh\r' >line-endings.new
+all_baselines line-endings
@@ -702,5 +702,222 @@ mod changes {
             );
             Ok(())
         }
+
+        #[test]
+        fn mode_only_changes_are_modifications() -> crate::Result {
+            let id = hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+            let tree_with_mode = |kind: EntryKind| -> crate::Result<Vec<u8>> {
+                let tree = gix_object::Tree {
+                    entries: vec![gix_object::tree::Entry {
+                        mode: kind.into(),
+                        filename: "file".into(),
+                        oid: id,
+                    }],
+                };
+                let mut buf = Vec::new();
+                gix_object::WriteTo::write_to(&tree, &mut buf)?;
+                Ok(buf)
+            };
+            let (lhs, rhs) = (tree_with_mode(EntryKind::Blob)?, tree_with_mode(EntryKind::BlobExecutable)?);
+
+            let mut recorder = gix_diff::tree::Recorder::default();
+            gix_diff::tree::Changes::from(TreeRefIter::from_bytes(&lhs)).needed_to_obtain(
+                TreeRefIter::from_bytes(&rhs),
+                gix_diff::tree::State::default(),
+                &gix_object::find::Never,
+                &mut recorder,
+            )?;
+            assert_eq!(
+                recorder.records,
+                vec![Modification {
+                    previous_entry_mode: EntryKind::Blob.into(),
+                    previous_oid: id,
+                    entry_mode: EntryKind::BlobExecutable.into(),
+                    oid: id,
+                    path: "file".into()
+                }],
+                "the executable bit changed, but not the content"
+            );
+            Ok(())
+        }
+    }
+}
+
+mod depth_first {
+    use std::cell::Cell;
+
+    use gix_diff::tree::{recorder, visit};
+    use gix_hash::ObjectId;
+    use gix_object::{bstr::ByteSlice, FindExt};
+
+    /// An object database that counts all lookups.
+    struct CountingFind {
+        inner: gix_odb::Handle,
+        lookups: Cell<usize>,
+    }
+
+    impl gix_object::Find for CountingFind {
+        fn try_find<'a>(
+            &self,
+            id: &gix_hash::oid,
+            buffer: &'a mut Vec<u8>,
+        ) -> Result<Option<gix_object::Data<'a>>, gix_object::find::Error> {
+            self.lookups.set(self.lookups.get() + 1);
+            self.inner.try_find(id, buffer)
+        }
+    }
+
+    fn db() -> crate::Result<(CountingFind, std::path::PathBuf)> {
+        let root = gix_testtools::scripted_fixture_read_only_standalone("make_deep_wide_trees.sh")?;
+        let inner = gix_odb::at(root.join(".git").join("objects"))?;
+        Ok((
+            CountingFind {
+                inner,
+                lookups: Cell::new(0),
+            },
+            root,
+        ))
+    }
+
+    fn tree_of_tag(db: &CountingFind, root: &std::path::Path, tag: &str) -> crate::Result<ObjectId> {
+        let commit_id = ObjectId::from_hex(
+            std::fs::read(root.join(".git").join("refs").join("tags").join(tag))?
+                .as_bstr()
+                .trim(),
+        )?;
+        let tree_id = db.inner.find_commit(&commit_id, &mut Vec::new())?.tree();
+        db.lookups.set(0);
+        Ok(tree_id)
+    }
+
+    fn status_and_path(change: &recorder::Change) -> String {
+        match change {
+            recorder::Change::Addition { path, .. } => format!("A {path}"),
+            recorder::Change::Deletion { path, .. } => format!("D {path}"),
+            recorder::Change::Modification { path, .. } => format!("M {path}"),
+        }
+    }
+
+    #[test]
+    fn changes_are_emitted_in_path_order_like_git() -> crate::Result {
+        let (db, root) = db()?;
+        let (base, changes) = (tree_of_tag(&db, &root, "base")?, tree_of_tag(&db, &root, "changes")?);
+        let mut recorder = gix_diff::tree::Recorder::default();
+        gix_diff::tree::depth_first(Some(&base), Some(&changes), &mut Default::default(), &db, &mut recorder)?;
+
+        let expected: Vec<_> = std::fs::read_to_string(root.join("changes.baseline"))?
+            .lines()
+            .map(|line| {
+                let (info, path) = line.split_once('\t').expect("tab-separated path");
+                format!("{} {path}", info.rsplit(' ').next().expect("status"))
+            })
+            .collect();
+        assert_eq!(
+            recorder.records.iter().map(status_and_path).collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(
+            db.lookups.get(),
+            2 + 2 * 5 + 2 * 2 + 3,
+            "both root trees, the pairs of the 5 changed top-level trees and 2 changed subtrees, \
+             along with the 3 trees that were only deleted or added - unchanged subtrees aren't looked up"
+        );
+
+        let mut breadth_first = gix_diff::tree::Recorder::default();
+        let mut buf = Vec::new();
+        let mut buf2 = Vec::new();
+        gix_diff::tree::Changes::from(db.inner.find_tree_iter(&base, &mut buf)?).needed_to_obtain(
+            db.inner.find_tree_iter(&changes, &mut buf2)?,
+            gix_diff::tree::State::default(),
+            &db.inner,
+            &mut breadth_first,
+        )?;
+        let mut breadth_first: Vec<_> = breadth_first.records.iter().map(status_and_path).collect();
+        breadth_first.sort();
+        let mut depth_first: Vec<_> = recorder.records.iter().map(status_and_path).collect();
+        depth_first.sort();
+        assert_eq!(depth_first, breadth_first, "both traversals see the same changes");
+        Ok(())
+    }
+
+    #[test]
+    fn from_and_to_empty_trees() -> crate::Result {
+        let (db, root) = db()?;
+        let base = tree_of_tag(&db, &root, "base")?;
+        for (lhs, rhs, expected_status) in [(None, Some(&base), 'A'), (Some(&base), None, 'D')] {
+            let mut recorder = gix_diff::tree::Recorder::default();
+            gix_diff::tree::depth_first(
+                lhs.map(AsRef::as_ref),
+                rhs.map(AsRef::as_ref),
+                &mut Default::default(),
+                &db,
+                &mut recorder,
+            )?;
+            assert_eq!(
+                recorder.records.len(),
+                10 + 10 * 10 + 10 * 10 * 10,
+                "all trees and files"
+            );
+            assert!(recorder
+                .records
+                .iter()
+                .all(|change| status_and_path(change).starts_with(expected_status)));
+            let paths: Vec<_> = recorder
+                .records
+                .iter()
+                .map(|change| match change {
+                    recorder::Change::Addition { path, .. }
+                    | recorder::Change::Deletion { path, .. }
+                    | recorder::Change::Modification { path, .. } => path.clone(),
+                })
+                .collect();
+            assert_eq!(&paths[..3], ["d0", "d0/d0", "d0/d0/f0"]);
+            assert!(paths.windows(2).all(|w| w[0] < w[1]), "paths are sorted");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn equal_trees_are_not_looked_up() -> crate::Result {
+        let (db, root) = db()?;
+        let base = tree_of_tag(&db, &root, "base")?;
+        let mut recorder = gix_diff::tree::Recorder::default();
+        gix_diff::tree::depth_first(Some(&base), Some(&base), &mut Default::default(), &db, &mut recorder)?;
+        assert!(recorder.records.is_empty());
+        assert_eq!(db.lookups.get(), 0, "the root trees are equal, nothing to do");
+        Ok(())
+    }
+
+    #[test]
+    fn cancellation() -> crate::Result {
+        struct CancelAfterFirst(gix_diff::tree::Recorder);
+        impl gix_diff::tree::Visit for CancelAfterFirst {
+            fn pop_front_tracked_path_and_set_current(&mut self) {
+                unreachable!("not used in depth-first mode")
+            }
+            fn push_back_tracked_path_component(&mut self, _component: &gix_object::bstr::BStr) {
+                unreachable!("not used in depth-first mode")
+            }
+            fn push_path_component(&mut self, component: &gix_object::bstr::BStr) {
+                self.0.push_path_component(component);
+            }
+            fn pop_path_component(&mut self) {
+                self.0.pop_path_component();
+            }
+            fn visit(&mut self, change: visit::Change) -> visit::Action {
+                self.0.visit(change);
+                visit::Action::Cancel
+            }
+        }
+
+        let (db, root) = db()?;
+        let (base, changes) = (tree_of_tag(&db, &root, "base")?, tree_of_tag(&db, &root, "changes")?);
+        let mut delegate = CancelAfterFirst(Default::default());
+        let err = gix_diff::tree::depth_first(Some(&base), Some(&changes), &mut Default::default(), &db, &mut delegate)
+            .unwrap_err();
+        assert!(matches!(err, gix_diff::tree::changes::Error::Cancelled));
+        assert_eq!(delegate.0.records.len(), 1, "only the first change was seen");
+        assert_eq!(delegate.0.path(), "", "paths are unwound");
+        Ok(())
     }
 }
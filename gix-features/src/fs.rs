@@ -19,6 +19,38 @@ mod shared {
             thread_name: &'static str,
         },
     }
+
+    /// Order two directory entries by name as if directories had a trailing slash, which makes the traversal
+    /// yield paths in the order of their full path.
+    ///
+    /// This matters as `a-b` sorts before `a/c` by path, even though `a` sorts before `a-b` by file name.
+    pub(super) fn cmp_entries_by_path(
+        a_name: &std::ffi::OsStr,
+        a_is_dir: bool,
+        b_name: &std::ffi::OsStr,
+        b_is_dir: bool,
+    ) -> std::cmp::Ordering {
+        let a = file_name_bytes(a_name);
+        let b = file_name_bytes(b_name);
+        a.iter()
+            .chain(a_is_dir.then_some(&b'/'))
+            .cmp(b.iter().chain(b_is_dir.then_some(&b'/')))
+    }
+
+    fn file_name_bytes(name: &std::ffi::OsStr) -> std::borrow::Cow<'_, [u8]> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            name.as_bytes().into()
+        }
+        #[cfg(not(unix))]
+        {
+            match name.to_string_lossy() {
+                std::borrow::Cow::Borrowed(name) => name.as_bytes().into(),
+                std::borrow::Cow::Owned(name) => name.into_bytes().into(),
+            }
+        }
+    }
 }
 
 #[cfg(any(feature = "walkdir", feature = "fs-walkdir-parallel", feature = "fs-read-dir"))]
@@ -244,14 +276,27 @@ pub mod walkdir {
         }
     }
 
-    /// Instantiate a new directory iterator which will not skip hidden files and is sorted
+    /// Instantiate a new directory iterator which will not skip hidden files and is sorted by path, i.e. as if
+    /// directories had a trailing slash.
     ///
     /// Use `precompose_unicode` to represent the `core.precomposeUnicode` configuration option.
     pub fn walkdir_sorted_new(root: &Path, parallelism: Parallelism, precompose_unicode: bool) -> WalkDir {
         WalkDir {
             inner: WalkDirImpl::new(root)
                 .skip_hidden(false)
-                .sort(true)
+                .process_read_dir(|_depth, _path, _state, children| {
+                    children.sort_by(|a, b| match (a, b) {
+                        (Ok(a), Ok(b)) => super::shared::cmp_entries_by_path(
+                            &a.file_name,
+                            a.file_type.is_dir(),
+                            &b.file_name,
+                            b.file_type.is_dir(),
+                        ),
+                        (Ok(_), Err(_)) => std::cmp::Ordering::Less,
+                        (Err(_), Ok(_)) => std::cmp::Ordering::Greater,
+                        (Err(_), Err(_)) => std::cmp::Ordering::Equal,
+                    });
+                })
                 .parallelism(parallelism.into())
                 .into(),
             precompose_unicode,
@@ -337,12 +382,22 @@ pub mod walkdir {
         }
     }
 
-    /// Instantiate a new directory iterator which will not skip hidden files and is sorted, with the given level of `parallelism`.
+    /// Instantiate a new directory iterator which will not skip hidden files and is sorted by path, i.e. as if
+    /// directories had a trailing slash, with the given level of `parallelism`.
     ///
     /// Use `precompose_unicode` to represent the `core.precomposeUnicode` configuration option.
     pub fn walkdir_sorted_new(root: &Path, _: Parallelism, precompose_unicode: bool) -> WalkDir {
         WalkDir {
-            inner: WalkDirImpl::new(root).sort_by_file_name().into(),
+            inner: WalkDirImpl::new(root)
+                .sort_by(|a, b| {
+                    super::shared::cmp_entries_by_path(
+                        a.file_name(),
+                        a.file_type().is_dir(),
+                        b.file_name(),
+                        b.file_type().is_dir(),
+                    )
+                })
+                .into(),
             precompose_unicode,
         }
     }
@@ -26,6 +26,37 @@ pub enum RoundTripCheck<'a> {
     },
 }
 
+/// Describes if converting line endings to `git` and back to the worktree would reproduce the original content.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum RoundTrip {
+    /// Converting to `git` and checking the result out again would yield the same line endings.
+    #[default]
+    Safe,
+    /// `CRLF` line endings would be replaced by `LF` in the worktree next time the file is checked out.
+    IrreversibleCrLfToLf,
+    /// `LF` line endings would be replaced by `CRLF` in the worktree next time the file is checked out.
+    IrreversibleLfToCrLf,
+}
+
+impl RoundTrip {
+    /// Return `true` if the conversion is reversible.
+    pub fn is_safe(&self) -> bool {
+        matches!(self, RoundTrip::Safe)
+    }
+}
+
+/// The outcome of [convert_to_git()][super::convert_to_git()].
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Outcome {
+    /// If `true`, the output buffer was written with the converted content.
+    /// Otherwise nothing had to be done and the input can be used as is.
+    pub changed: bool,
+    /// Whether or not the conversion can be undone by checking out the result.
+    ///
+    /// It's determined even if no [round-trip check](Options::round_trip_check) was requested.
+    pub round_trip: RoundTrip,
+}
+
 /// The error returned by [convert_to_git()][super::convert_to_git()].
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
@@ -48,13 +79,14 @@ pub(crate) mod function {
     use crate::{
         clear_and_set_capacity,
         eol::{
-            convert_to_git::{Error, IndexObjectFn, Options, RoundTripCheck},
+            convert_to_git::{Error, IndexObjectFn, Options, Outcome, RoundTrip, RoundTripCheck},
             AttributesDigest, Stats,
         },
     };
 
     /// Given a `src` buffer, change it `git` (`\n`) line endings and store the result in `buf`.
-    /// Return `true` if `buf` was written or `false` if nothing had to be done.
+    /// Return an outcome whose `changed` field is `true` if `buf` was written or `false` if nothing had to be done,
+    /// along with information about whether checking out the result would restore the original line endings.
     /// Depending on the state in `buf`, `index_object` is called to write the version of `src` as stored in the index
     /// into the buffer and if it is a blob, or return `Ok(None)` if no such object exists.
     /// If renormalization is desired, let it return `Ok(None)` at all times to not let it have any influence over the
@@ -69,9 +101,9 @@ pub(crate) mod function {
             round_trip_check,
             config,
         }: Options<'_>,
-    ) -> Result<bool, Error> {
+    ) -> Result<Outcome, Error> {
         if digest == AttributesDigest::Binary || src.is_empty() {
-            return Ok(false);
+            return Ok(Outcome::default());
         }
 
         let stats = Stats::from_bytes(src);
@@ -79,7 +111,7 @@ pub(crate) mod function {
         if digest.is_auto_text() {
             // In this mode, we are supposed to figure out ourselves if we should convert or not.
             if stats.is_binary() {
-                return Ok(false);
+                return Ok(Outcome::default());
             }
 
             if let Some(()) = index_object(buf).map_err(Error::FetchObjectFromIndex)? {
@@ -94,57 +126,56 @@ pub(crate) mod function {
             }
         }
 
-        if let Some(round_trip_check) = round_trip_check {
-            let mut new_stats = stats;
-            // simulate to-git conversion/git-add
-            if convert_crlf_to_lf {
-                new_stats.lone_lf += new_stats.crlf;
-                new_stats.crlf = 0;
-            }
-            // simulate worktree checkout
-            if new_stats.will_convert_lf_to_crlf(digest, config) {
-                new_stats.crlf += new_stats.lone_lf;
-                new_stats.lone_lf = 0;
-            }
-            if stats.crlf > 0 && new_stats.crlf == 0 {
-                // CRLF would not be restored by checkout
-                match round_trip_check {
-                    RoundTripCheck::Fail { rela_path } => {
-                        return Err(Error::RoundTrip {
-                            msg: "CRLF would be replaced by LF",
-                            path: rela_path.to_owned(),
-                        })
-                    }
-                    #[allow(unused_variables)]
-                    RoundTripCheck::Warn { rela_path } => {
-                        gix_trace::warn!(
-                            "in the working copy of '{}', CRLF will be replaced by LF next time git touches it",
-                            rela_path.display()
-                        )
-                    }
+        let mut new_stats = stats;
+        // simulate to-git conversion/git-add
+        if convert_crlf_to_lf {
+            new_stats.lone_lf += new_stats.crlf;
+            new_stats.crlf = 0;
+        }
+        // simulate worktree checkout
+        if new_stats.will_convert_lf_to_crlf(digest, config) {
+            new_stats.crlf += new_stats.lone_lf;
+            new_stats.lone_lf = 0;
+        }
+        let round_trip = if stats.crlf > 0 && new_stats.crlf == 0 {
+            // CRLF would not be restored by checkout
+            RoundTrip::IrreversibleCrLfToLf
+        } else if stats.lone_lf > 0 && new_stats.lone_lf == 0 {
+            // CRLF would be added by checkout
+            RoundTrip::IrreversibleLfToCrLf
+        } else {
+            RoundTrip::Safe
+        };
+
+        if let Some(round_trip_check) = round_trip_check.filter(|_| !round_trip.is_safe()) {
+            let crlf_to_lf = round_trip == RoundTrip::IrreversibleCrLfToLf;
+            match round_trip_check {
+                RoundTripCheck::Fail { rela_path } => {
+                    return Err(Error::RoundTrip {
+                        msg: if crlf_to_lf {
+                            "CRLF would be replaced by LF"
+                        } else {
+                            "LF would be replaced by CRLF"
+                        },
+                        path: rela_path.to_owned(),
+                    })
                 }
-            } else if stats.lone_lf > 0 && new_stats.lone_lf == 0 {
-                // CRLF would be added by checkout
-                match round_trip_check {
-                    RoundTripCheck::Fail { rela_path } => {
-                        return Err(Error::RoundTrip {
-                            msg: "LF would be replaced by CRLF",
-                            path: rela_path.to_owned(),
-                        })
-                    }
-                    #[allow(unused_variables)]
-                    RoundTripCheck::Warn { rela_path } => {
-                        gix_trace::warn!(
-                            "in the working copy of '{}', LF will be replaced by CRLF next time git touches it",
-                            rela_path.display()
-                        )
-                    }
+                #[allow(unused_variables)]
+                RoundTripCheck::Warn { rela_path } => {
+                    let (from, to) = if crlf_to_lf { ("CRLF", "LF") } else { ("LF", "CRLF") };
+                    gix_trace::warn!(
+                        "in the working copy of '{}', {from} will be replaced by {to} next time git touches it",
+                        rela_path.display()
+                    )
                 }
             }
         }
 
         if !convert_crlf_to_lf {
-            return Ok(false);
+            return Ok(Outcome {
+                changed: false,
+                round_trip,
+            });
         }
 
         clear_and_set_capacity(buf, src.len() - stats.crlf)?;
@@ -158,6 +189,9 @@ pub(crate) mod function {
                 }
             }
         }
-        Ok(true)
+        Ok(Outcome {
+            changed: true,
+            round_trip,
+        })
     }
 }
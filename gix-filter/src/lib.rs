@@ -47,6 +47,8 @@ pub struct Pipeline {
     processes: driver::State,
     /// A utility to handle multiple buffers to keep results of various filters.
     bufs: gix_utils::Buffers,
+    /// The round-trip safety of the last end-of-line conversion to `git`, if one was performed.
+    crlf_round_trip: Option<eol::convert_to_git::RoundTrip>,
 }
 
 /// A declaration of a driver program.
@@ -52,8 +52,11 @@ pub mod to_worktree {
     #[derive(Debug, thiserror::Error)]
     #[allow(missing_docs)]
     pub enum Error {
-        #[error(transparent)]
-        Worktree(#[from] crate::worktree::encode_to_worktree::Error),
+        #[error("Could not encode '{rela_path}' for the working tree")]
+        Worktree {
+            rela_path: bstr::BString,
+            source: crate::worktree::encode_to_worktree::Error,
+        },
         #[error(transparent)]
         Driver(#[from] crate::driver::apply::Error),
         #[error(transparent)]
@@ -94,18 +97,9 @@ impl Pipeline {
             self.options.eol_config,
         )?;
 
+        self.crlf_round_trip = None;
         let mut in_buffer = false;
-        // this is just an approximation, but it's as good as it gets without reading the actual input.
-        let would_convert_eol = eol::convert_to_git(
-            b"\r\n",
-            digest,
-            &mut self.bufs.dest,
-            &mut |_| Ok(None),
-            eol::convert_to_git::Options {
-                round_trip_check: None,
-                config: self.options.eol_config,
-            },
-        )?;
+        let would_convert_eol = would_convert_eol(digest, &mut self.bufs.dest, self.options.eol_config)?;
 
         if let Some(driver) = driver {
             if let Some(mut read) = self.processes.apply(
@@ -145,17 +139,23 @@ impl Pipeline {
             self.bufs.swap();
         }
 
-        if eol::convert_to_git(
-            &self.bufs.src,
-            digest,
-            &mut self.bufs.dest,
-            &mut |buf| index_object(buf),
-            eol::convert_to_git::Options {
-                round_trip_check: self.options.crlf_roundtrip_check.to_eol_roundtrip_check(rela_path),
-                config: self.options.eol_config,
-            },
-        )? {
-            self.bufs.swap();
+        if in_buffer {
+            let eol = eol::convert_to_git(
+                &self.bufs.src,
+                digest,
+                &mut self.bufs.dest,
+                &mut |buf| index_object(buf),
+                eol::convert_to_git::Options {
+                    round_trip_check: self.options.crlf_roundtrip_check.to_eol_roundtrip_check(rela_path),
+                    config: self.options.eol_config,
+                },
+            )?;
+            if would_convert_eol {
+                self.crlf_round_trip = Some(eol.round_trip);
+            }
+            if eol.changed {
+                self.bufs.swap();
+            }
         }
 
         if apply_ident_filter && ident::undo(&self.bufs.src, &mut self.bufs.dest)? {
@@ -168,6 +168,39 @@ impl Pipeline {
         })
     }
 
+    /// Return `true` if [`convert_to_git()`](Self::convert_to_git()) may change the content of a file at `rela_path`
+    /// based on its `attributes`, and thus its length, without reading it.
+    ///
+    /// This is the case if a filter driver, the `ident` filter, a `working-tree-encoding` or end-of-line conversions
+    /// apply. Note that the latter only change files that actually contain `CRLF`, which isn't known without reading them.
+    #[allow(clippy::result_large_err)]
+    pub fn may_change_when_converting_to_git(
+        &mut self,
+        rela_path: &Path,
+        attributes: &mut dyn FnMut(&BStr, &mut gix_attributes::search::Outcome),
+    ) -> Result<bool, to_git::Error> {
+        let bstr_path = gix_path::into_bstr(rela_path);
+        let Configuration {
+            driver,
+            digest,
+            _attr_digest: _,
+            encoding,
+            apply_ident_filter,
+        } = Configuration::at_path(
+            bstr_path.as_ref(),
+            &self.options.drivers,
+            &mut self.attrs,
+            attributes,
+            self.options.eol_config,
+        )?;
+        Ok(driver
+            .into_iter()
+            .any(|driver| driver.clean.is_some() || driver.process.is_some())
+            || apply_ident_filter
+            || encoding.is_some()
+            || would_convert_eol(digest, &mut self.bufs.dest, self.options.eol_config)?)
+    }
+
     /// Convert a `src` buffer located at `rela_path` (in the index) from what's in `git` to the worktree representation,
     /// asking for `attributes` with `rela_path` as first argument to configure the operation automatically.
     /// `can_delay` defines if long-running processes can delay their response, and if they *choose* to the caller has to
@@ -209,7 +242,15 @@ impl Pipeline {
 
         if let Some(encoding) = encoding {
             let (src, dest) = bufs.src_and_dest();
-            worktree::encode_to_worktree(src, encoding, dest)?;
+            if self.options.encodings_with_roundtrip_check.contains(&encoding) {
+                worktree::encode_to_worktree_with_round_trip_check(src, encoding, dest)
+            } else {
+                worktree::encode_to_worktree(src, encoding, dest)
+            }
+            .map_err(|source| to_worktree::Error::Worktree {
+                rela_path: rela_path.to_owned(),
+                source,
+            })?;
             bufs.swap();
         }
 
@@ -233,6 +274,25 @@ impl Pipeline {
     }
 }
 
+// this is just an approximation, but it's as good as it gets without reading the actual input.
+fn would_convert_eol(
+    digest: eol::AttributesDigest,
+    buf: &mut Vec<u8>,
+    config: eol::Configuration,
+) -> Result<bool, eol::convert_to_git::Error> {
+    Ok(eol::convert_to_git(
+        b"\r\n",
+        digest,
+        buf,
+        &mut |_| Ok(None),
+        eol::convert_to_git::Options {
+            round_trip_check: None,
+            config,
+        },
+    )?
+    .changed)
+}
+
 /// The result of a conversion with zero or more filters to be stored in git.
 pub enum ToGitOutcome<'pipeline, R> {
     /// The original input wasn't changed and the reader is still available for consumption.
@@ -25,7 +25,8 @@ pub struct Options {
     pub eol_config: eol::Configuration,
     /// How to perform round-trip checks during end-of-line conversions to git.
     pub crlf_roundtrip_check: CrlfRoundTripCheck,
-    /// All worktree encodings for round-trip checks should be performed.
+    /// All worktree encodings for which round-trip checks should be performed, when converting to git and to the worktree,
+    /// the equivalent of `core.checkRoundtripEncoding`.
     pub encodings_with_roundtrip_check: Vec<&'static encoding_rs::Encoding>,
     /// The object hash to use when applying the `ident` filter.
     pub object_hash: gix_hash::Kind,
@@ -65,6 +66,7 @@ impl Pipeline {
             processes: driver::State::new(context),
             options,
             bufs: Default::default(),
+            crlf_round_trip: None,
         }
     }
 
@@ -103,6 +105,15 @@ impl Pipeline {
         &mut self.options
     }
 
+    /// Return the round-trip safety of the end-of-line conversion performed by the last call to
+    /// [`convert_to_git()`][Self::convert_to_git()], or `None` if no such conversion was performed, and reset it.
+    ///
+    /// This allows to learn which files would have their line endings changed the next time they are checked out,
+    /// the kind of information `git` emits as warnings if `core.safecrlf` is `warn`.
+    pub fn take_crlf_round_trip(&mut self) -> Option<eol::convert_to_git::RoundTrip> {
+        self.crlf_round_trip.take()
+    }
+
     /// Return our double-buffers for reuse by the caller.
     pub fn buffers_mut(&mut self) -> &mut gix_utils::Buffers {
         &mut self.bufs
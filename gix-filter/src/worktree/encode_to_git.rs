@@ -1,7 +1,7 @@
 /// Whether or not to perform round-trip checks.
 #[derive(Debug, Copy, Clone)]
 pub enum RoundTripCheck {
-    /// Assure that we can losslessly convert the UTF-8 result back to the original encoding or fail with an error.
+    /// Assure that we can losslessly convert the result back to the original encoding or fail with an error.
     Fail,
     /// Do not check if the encoding is round-trippable.
     Skip,
@@ -1,4 +1,5 @@
-/// The error returned by [`encode_to_worktree()][super::encode_to_worktree()].
+/// The error returned by [`encode_to_worktree()][super::encode_to_worktree()] and
+/// [`encode_to_worktree_with_round_trip_check()][super::encode_to_worktree_with_round_trip_check()].
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
 pub enum Error {
@@ -11,30 +12,110 @@ pub enum Error {
         character: char,
         worktree_encoding: &'static str,
     },
+    #[error("Encoding from 'UTF-8' to '{worktree_encoding}' and back is not the same, the first byte that differs is at offset {offset}")]
+    RoundTrip {
+        worktree_encoding: &'static str,
+        offset: usize,
+    },
 }
 
 pub(crate) mod function {
+    use std::borrow::Cow;
+
     use encoding_rs::EncoderResult;
 
     use super::Error;
+    use crate::worktree::encode_to_git::RoundTripCheck;
 
     /// Encode `src_utf8`, which is assumed to be UTF-8 encoded, according to `worktree_encoding` for placement in the working directory,
     /// and write it to `buf`, possibly resizing it.
     /// Note that the encoding is always applied, there is no conditional even if `worktree_encoding` and the `src` encoding are the same.
+    ///
+    /// ### Deviation
+    ///
+    /// UTF-16 is written without byte order mark, while `git` writes one for `working-tree-encoding=UTF-16`. This is because
+    /// `encoding_rs` resolves the `UTF-16` label to `UTF-16LE`, so both can't be told apart here.
     pub fn encode_to_worktree(
         src_utf8: &[u8],
         worktree_encoding: &'static encoding_rs::Encoding,
         buf: &mut Vec<u8>,
     ) -> Result<(), Error> {
+        encode_to_worktree_inner(src_utf8, worktree_encoding, buf, RoundTripCheck::Skip)
+    }
+
+    /// Like [`encode_to_worktree()`], but decode the result again and compare it to `src_utf8`, returning an error with the offset
+    /// of the first byte that didn't survive the round-trip. This also applies to input that isn't valid UTF-8, which is otherwise
+    /// rejected without offset.
+    pub fn encode_to_worktree_with_round_trip_check(
+        src_utf8: &[u8],
+        worktree_encoding: &'static encoding_rs::Encoding,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        encode_to_worktree_inner(src_utf8, worktree_encoding, buf, RoundTripCheck::Fail)
+    }
+
+    fn encode_to_worktree_inner(
+        src_utf8: &[u8],
+        worktree_encoding: &'static encoding_rs::Encoding,
+        buf: &mut Vec<u8>,
+        round_trip: RoundTripCheck,
+    ) -> Result<(), Error> {
+        let src = match round_trip {
+            RoundTripCheck::Fail => String::from_utf8_lossy(src_utf8),
+            RoundTripCheck::Skip => Cow::Borrowed(std::str::from_utf8(src_utf8)?),
+        };
+        if worktree_encoding == encoding_rs::UTF_16LE || worktree_encoding == encoding_rs::UTF_16BE {
+            // `encoding_rs` can only decode UTF-16, and would encode to UTF-8 instead.
+            encode_to_utf16(&src, worktree_encoding == encoding_rs::UTF_16LE, buf)?;
+        } else {
+            encode(&src, worktree_encoding, buf)?;
+        }
+
+        match round_trip {
+            RoundTripCheck::Fail => {
+                let (decoded, _had_errors) = worktree_encoding.decode_without_bom_handling(buf);
+                let decoded = decoded.as_bytes();
+                if decoded != src_utf8 {
+                    let offset = decoded
+                        .iter()
+                        .zip(src_utf8)
+                        .position(|(a, b)| a != b)
+                        .unwrap_or_else(|| decoded.len().min(src_utf8.len()));
+                    return Err(Error::RoundTrip {
+                        worktree_encoding: worktree_encoding.name(),
+                        offset,
+                    });
+                }
+            }
+            RoundTripCheck::Skip => {}
+        }
+        Ok(())
+    }
+
+    fn encode_to_utf16(src: &str, little_endian: bool, buf: &mut Vec<u8>) -> Result<(), Error> {
+        let buf_len = src
+            .len()
+            .checked_mul(2)
+            .ok_or(Error::Overflow { input_len: src.len() })?;
+        buf.clear();
+        buf.reserve(buf_len);
+        for unit in src.encode_utf16() {
+            buf.extend_from_slice(&if little_endian {
+                unit.to_le_bytes()
+            } else {
+                unit.to_be_bytes()
+            });
+        }
+        Ok(())
+    }
+
+    fn encode(src: &str, worktree_encoding: &'static encoding_rs::Encoding, buf: &mut Vec<u8>) -> Result<(), Error> {
         let mut encoder = worktree_encoding.new_encoder();
         let buf_len = encoder
-            .max_buffer_length_from_utf8_if_no_unmappables(src_utf8.len())
-            .ok_or(Error::Overflow {
-                input_len: src_utf8.len(),
-            })?;
+            .max_buffer_length_from_utf8_if_no_unmappables(src.len())
+            .ok_or(Error::Overflow { input_len: src.len() })?;
         buf.clear();
         buf.resize(buf_len, 0);
-        let src = std::str::from_utf8(src_utf8)?;
         let (res, read, written) = encoder.encode_from_utf8_without_replacement(src, buf, true);
         match res {
             EncoderResult::InputEmpty => {
@@ -42,7 +123,7 @@ pub(crate) mod function {
                     buf_len >= written,
                     "encoding_rs estimates the maximum amount of bytes written correctly"
                 );
-                assert_eq!(read, src_utf8.len(), "input buffer should be fully consumed");
+                assert_eq!(read, src.len(), "input buffer should be fully consumed");
                 buf.truncate(written);
             }
             EncoderResult::OutputFull => {
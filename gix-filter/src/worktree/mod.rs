@@ -16,4 +16,4 @@ pub use encode_to_git::function::encode_to_git;
 ///
 #[allow(clippy::empty_docs)]
 pub mod encode_to_worktree;
-pub use encode_to_worktree::function::encode_to_worktree;
+pub use encode_to_worktree::function::{encode_to_worktree, encode_to_worktree_with_round_trip_check};
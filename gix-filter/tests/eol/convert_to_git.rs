@@ -1,7 +1,10 @@
 use std::path::Path;
 
 use bstr::{ByteSlice, ByteVec};
-use gix_filter::{eol, eol::AttributesDigest};
+use gix_filter::{
+    eol,
+    eol::{convert_to_git::RoundTrip, AttributesDigest},
+};
 
 #[test]
 fn with_binary_attribute_is_never_converted() {
@@ -13,7 +16,8 @@ fn with_binary_attribute_is_never_converted() {
         &mut no_call,
         Default::default(),
     )
-    .expect("no error");
+    .expect("no error")
+    .changed;
     assert!(!changed, "the user marked it as binary so it's never being touched");
 }
 
@@ -27,7 +31,8 @@ fn no_crlf_means_no_work() -> crate::Result {
         &mut no_call,
         Default::default(),
     )
-    .expect("no error");
+    .expect("no error")
+    .changed;
     assert!(!changed);
 
     let changed = eol::convert_to_git(
@@ -37,7 +42,8 @@ fn no_crlf_means_no_work() -> crate::Result {
         &mut no_object_in_index,
         Default::default(),
     )
-    .expect("no error");
+    .expect("no error")
+    .changed;
     assert!(!changed, "in auto-mode, the object is queried in the index as well.");
     Ok(())
 }
@@ -52,7 +58,8 @@ fn detected_as_binary() -> crate::Result {
         &mut no_call,
         Default::default(),
     )
-    .expect("no error");
+    .expect("no error")
+    .changed;
     assert!(
         !changed,
         "in auto-mode, we have a heuristic to see if the buffer is binary"
@@ -70,7 +77,8 @@ fn fast_conversion_by_stripping_cr() -> crate::Result {
         &mut no_call,
         Default::default(),
     )
-    .expect("no error");
+    .expect("no error")
+    .changed;
     assert!(changed);
     assert_eq!(buf.as_bstr(), "a\nb\nc", "here carriage returns can just be stripped");
     Ok(())
@@ -86,7 +94,8 @@ fn slower_conversion_due_to_lone_cr() -> crate::Result {
         &mut no_call,
         Default::default(),
     )
-    .expect("no error");
+    .expect("no error")
+    .changed;
     assert!(changed);
     assert_eq!(
         buf.as_bstr(),
@@ -112,7 +121,8 @@ fn crlf_in_index_prevents_conversion_to_lf() -> crate::Result {
         },
         Default::default(),
     )
-    .expect("no error");
+    .expect("no error")
+    .changed;
     assert!(called, "in auto mode, the index is queried as well");
     assert!(
         !changed,
@@ -155,7 +165,8 @@ fn round_trip_check() -> crate::Result {
                 }),
                 config: Default::default(),
             },
-        )?;
+        )?
+        .changed;
         assert!(
             changed,
             "in warn mode, we will get a result even though it won't round-trip"
@@ -164,6 +175,87 @@ fn round_trip_check() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn round_trip_outcome_with_mixed_line_endings() -> crate::Result {
+    let mut buf = Vec::new();
+    for (input, digest, expected) in [
+        (&b"a\r\nb\r\n"[..], AttributesDigest::TextCrlf, RoundTrip::Safe),
+        (b"a\nb\n", AttributesDigest::TextInput, RoundTrip::Safe),
+        (b"a\nb\r\n", AttributesDigest::TextCrlf, RoundTrip::IrreversibleLfToCrLf),
+        (
+            b"a\nb\r\n",
+            AttributesDigest::TextInput,
+            RoundTrip::IrreversibleCrLfToLf,
+        ),
+        (
+            b"a\r\nb\r\n",
+            AttributesDigest::TextInput,
+            RoundTrip::IrreversibleCrLfToLf,
+        ),
+        (b"a\nb\r\n", AttributesDigest::Binary, RoundTrip::Safe),
+    ] {
+        let out = eol::convert_to_git(input, digest, &mut buf, &mut no_call, Default::default())?;
+        assert_eq!(out.round_trip, expected, "{input:?} with {digest:?}");
+    }
+
+    let err = eol::convert_to_git(
+        b"a\nb\r\n",
+        AttributesDigest::TextInput,
+        &mut buf,
+        &mut no_call,
+        eol::convert_to_git::Options {
+            round_trip_check: Some(eol::convert_to_git::RoundTripCheck::Fail {
+                rela_path: Path::new("dir/mixed.txt"),
+            }),
+            config: Default::default(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "CRLF would be replaced by LF in 'dir/mixed.txt'",
+        "the failure names the path"
+    );
+    Ok(())
+}
+
+#[test]
+fn round_trip_outcome_with_lone_cr() -> crate::Result {
+    let mut buf = Vec::new();
+    for (input, digest, expected, expected_output) in [
+        (&b"a\rb\rc"[..], AttributesDigest::TextCrlf, RoundTrip::Safe, None),
+        (b"a\rb\rc", AttributesDigest::TextInput, RoundTrip::Safe, None),
+        (
+            b"a\rb\r\nc",
+            AttributesDigest::TextCrlf,
+            RoundTrip::Safe,
+            Some("a\rb\nc"),
+        ),
+        (
+            b"a\rb\r\nc",
+            AttributesDigest::TextInput,
+            RoundTrip::IrreversibleCrLfToLf,
+            Some("a\rb\nc"),
+        ),
+        (
+            b"a\r\rb\nc\r\n",
+            AttributesDigest::TextCrlf,
+            RoundTrip::IrreversibleLfToCrLf,
+            Some("a\r\rb\nc\n"),
+        ),
+        // in auto-mode, lone CRs make the file binary so it's never touched
+        (b"a\rb\r\nc\n", AttributesDigest::TextAutoCrlf, RoundTrip::Safe, None),
+    ] {
+        let out = eol::convert_to_git(input, digest, &mut buf, &mut no_object_in_index, Default::default())?;
+        assert_eq!(out.round_trip, expected, "{input:?} with {digest:?}");
+        assert_eq!(out.changed, expected_output.is_some());
+        if let Some(expected_output) = expected_output {
+            assert_eq!(buf.as_bstr(), expected_output, "lone CRs are never touched");
+        }
+    }
+    Ok(())
+}
+
 #[allow(clippy::ptr_arg)]
 fn no_call(_buf: &mut Vec<u8>) -> Result<Option<()>, Box<dyn std::error::Error + Send + Sync>> {
     unreachable!("index function will not be called")
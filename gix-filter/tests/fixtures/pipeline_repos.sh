@@ -16,3 +16,9 @@ EOF
 * filter=arrow
 EOF
 )
+
+(mkdir utf16 && cd utf16
+  cat <<EOF > .gitattributes
+*.txt working-tree-encoding=UTF-16LE
+EOF
+)
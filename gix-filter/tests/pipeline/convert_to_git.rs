@@ -37,6 +37,62 @@ fn no_driver_but_filter_with_autocrlf() -> gix_testtools::Result {
     Ok(())
 }
 
+#[test]
+fn crlf_round_trip_is_available_per_conversion() -> gix_testtools::Result {
+    for (check, expected_err) in [
+        (CrlfRoundTripCheck::Skip, None),
+        (CrlfRoundTripCheck::Warn, None),
+        (
+            CrlfRoundTripCheck::Fail,
+            Some("CRLF would be replaced by LF in 'mixed.txt'"),
+        ),
+    ] {
+        let (_cache, mut pipe) = pipeline("no-filter", || {
+            (
+                vec![],
+                Vec::new(),
+                check,
+                eol::Configuration {
+                    auto_crlf: eol::AutoCrlf::Input,
+                    eol: None,
+                },
+            )
+        })?;
+
+        let res = pipe.convert_to_git(
+            "a\nb\r\n".as_bytes(),
+            Path::new("mixed.txt"),
+            &mut |_path, _attrs| {},
+            &mut no_object_in_index,
+        );
+        match expected_err {
+            Some(expected) => {
+                assert_eq!(res.err().expect("round-trip check fails").to_string(), expected);
+                continue;
+            }
+            None => {
+                let out = res?;
+                assert_eq!(out.as_bytes().expect("converted in memory").as_bstr(), "a\nb\n");
+            }
+        }
+        assert_eq!(
+            pipe.take_crlf_round_trip(),
+            Some(eol::convert_to_git::RoundTrip::IrreversibleCrLfToLf),
+            "CRLF won't come back on checkout, independently of the check mode"
+        );
+        assert_eq!(pipe.take_crlf_round_trip(), None, "the value is reset when taken");
+
+        pipe.convert_to_git(
+            "a\nb\n".as_bytes(),
+            Path::new("lf.txt"),
+            &mut |_path, _attrs| {},
+            &mut no_object_in_index,
+        )?;
+        assert_eq!(pipe.take_crlf_round_trip(), Some(eol::convert_to_git::RoundTrip::Safe));
+    }
+    Ok(())
+}
+
 #[test]
 fn all_stages_mean_streaming_is_impossible() -> gix_testtools::Result {
     let (mut cache, mut pipe) = pipeline("all-filters", || {
@@ -128,6 +184,35 @@ fn no_filter_means_reader_is_returned_unchanged() -> gix_testtools::Result {
     Ok(())
 }
 
+#[test]
+fn may_change_when_converting_to_git() -> gix_testtools::Result {
+    for (name, drivers, eol_config, expected) in [
+        ("no-filters", vec![], eol::Configuration::default(), false),
+        (
+            "no-filters",
+            vec![],
+            eol::Configuration {
+                auto_crlf: eol::AutoCrlf::Enabled,
+                eol: None,
+            },
+            true,
+        ),
+        ("driver-only", vec![driver_with_process()], Default::default(), true),
+        ("driver-only", vec![], Default::default(), false),
+        ("all-filters", vec![], Default::default(), true),
+    ] {
+        let (mut cache, mut pipe) = pipeline(name, || (drivers, Vec::new(), CrlfRoundTripCheck::Skip, eol_config))?;
+        let actual = pipe.may_change_when_converting_to_git(Path::new("any.txt"), &mut |path, attrs| {
+            cache
+                .at_entry(path, None, &gix_object::find::Never)
+                .expect("cannot fail")
+                .matching_attributes(attrs);
+        })?;
+        assert_eq!(actual, expected, "{name}, {eol_config:?}");
+    }
+    Ok(())
+}
+
 #[allow(clippy::ptr_arg)]
 fn no_call(_buf: &mut Vec<u8>) -> Result<Option<()>, Box<dyn std::error::Error + Send + Sync>> {
     unreachable!("index function will not be called")
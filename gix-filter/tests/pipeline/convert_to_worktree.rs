@@ -98,3 +98,51 @@ fn no_filter() -> gix_testtools::Result {
     assert_eq!(actual.as_ptr(), input.as_ptr(), "…which means it's exactly the same");
     Ok(())
 }
+
+#[test]
+fn working_tree_encoding_round_trip_failures_name_the_path() -> gix_testtools::Result {
+    let (mut cache, mut pipe) = pipeline("utf16", || {
+        (
+            vec![],
+            vec![encoding_rs::UTF_16LE],
+            CrlfRoundTripCheck::Skip,
+            Default::default(),
+        )
+    })?;
+    let mut attributes = |path: &bstr::BStr, attrs: &mut gix_attributes::search::Outcome| {
+        cache
+            .at_entry(path, None, &gix_object::find::Never)
+            .expect("cannot fail")
+            .matching_attributes(attrs);
+    };
+
+    {
+        let out = pipe.convert_to_worktree(
+            "hällo".as_bytes(),
+            "good.txt".into(),
+            &mut attributes,
+            gix_filter::driver::apply::Delay::Forbid,
+        )?;
+        assert_eq!(
+            out.as_bytes().expect("no driver").as_bstr(),
+            b"h\0\xe4\0l\0l\0o\0".as_bstr(),
+            "the content is encoded as UTF-16LE"
+        );
+    }
+
+    let err = pipe
+        .convert_to_worktree(
+            b"ok \xed\xa0\x80",
+            "bad.txt".into(),
+            &mut attributes,
+            gix_filter::driver::apply::Delay::Forbid,
+        )
+        .err()
+        .expect("the unpaired surrogate can't be represented");
+    assert_eq!(err.to_string(), "Could not encode 'bad.txt' for the working tree");
+    assert_eq!(
+        std::error::Error::source(&err).expect("present").to_string(),
+        "Encoding from 'UTF-8' to 'UTF-16LE' and back is not the same, the first byte that differs is at offset 3"
+    );
+    Ok(())
+}
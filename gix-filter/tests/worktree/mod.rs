@@ -96,4 +96,48 @@ mod encode_to_worktree {
         assert_eq!(re_encoded.as_bstr(), input, "this should be round-trippable too");
         Ok(())
     }
+
+    #[test]
+    fn utf16_is_written_as_such() -> crate::Result {
+        let input = "hällo";
+        for (encoding, expected) in [
+            (encoding_rs::UTF_16LE, b"h\0\xe4\0l\0l\0o\0"),
+            (encoding_rs::UTF_16BE, b"\0h\0\xe4\0l\0l\0o"),
+        ] {
+            let mut buf = Vec::new();
+            worktree::encode_to_worktree(input.as_bytes(), encoding, &mut buf)?;
+            assert_eq!(buf.as_bstr(), expected.as_bstr(), "{}", encoding.name());
+            worktree::encode_to_worktree_with_round_trip_check(input.as_bytes(), encoding, &mut buf)?;
+            assert_eq!(buf.as_bstr(), expected.as_bstr(), "{}", encoding.name());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_failures_point_to_the_first_lossy_byte() {
+        // An unpaired surrogate encoded like UTF-8 isn't valid UTF-8 and can't be represented in UTF-16.
+        let input = b"ok \xed\xa0\x80 not ok";
+        let mut buf = Vec::new();
+        let err =
+            worktree::encode_to_worktree_with_round_trip_check(input, encoding_rs::UTF_16LE, &mut buf).unwrap_err();
+        assert!(matches!(
+            err,
+            worktree::encode_to_worktree::Error::RoundTrip {
+                worktree_encoding: "UTF-16LE",
+                offset: 3
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Encoding from 'UTF-8' to 'UTF-16LE' and back is not the same, the first byte that differs is at offset 3"
+        );
+
+        assert!(
+            matches!(
+                worktree::encode_to_worktree(input, encoding_rs::UTF_16LE, &mut buf),
+                Err(worktree::encode_to_worktree::Error::InputAsUtf8(_))
+            ),
+            "without the check, the input is rejected as well, just without details"
+        );
+    }
 }
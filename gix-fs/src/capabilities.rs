@@ -11,6 +11,7 @@ impl Default for Capabilities {
             ignore_case: true,
             executable_bit: false,
             symlink: false,
+            long_paths: false,
         }
     }
 }
@@ -23,6 +24,7 @@ impl Default for Capabilities {
             ignore_case: true,
             executable_bit: true,
             symlink: true,
+            long_paths: false,
         }
     }
 }
@@ -35,6 +37,7 @@ impl Default for Capabilities {
             ignore_case: false,
             executable_bit: true,
             symlink: true,
+            long_paths: false,
         }
     }
 }
@@ -52,6 +55,8 @@ impl Capabilities {
             ignore_case: Self::probe_ignore_case(git_dir).unwrap_or(ctx.ignore_case),
             precompose_unicode: Self::probe_precompose_unicode(git_dir).unwrap_or(ctx.precompose_unicode),
             executable_bit: Self::probe_file_mode(git_dir).unwrap_or(ctx.executable_bit),
+            // This is a choice, not a property of the filesystem.
+            long_paths: ctx.long_paths,
         }
     }
 
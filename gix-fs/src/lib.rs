@@ -29,6 +29,10 @@ pub struct Capabilities {
     /// If true, the file system supports symbolic links and we should try to create them. Otherwise symbolic links will be checked
     /// out as files which contain the link as text.
     pub symlink: bool,
+    /// If true, paths on Windows are converted into their extended-length form before using them with the filesystem, so they
+    /// may exceed the classic `MAX_PATH` limit. If false, paths to files that exceed the limit can't be written.
+    /// This is controlled by `core.longpaths`, and has no effect on other platforms.
+    pub long_paths: bool,
 }
 mod capabilities;
 
@@ -48,6 +52,8 @@ pub use read_dir::function::read_dir;
 #[allow(clippy::empty_docs)]
 pub mod dir;
 
+pub mod long_path;
+
 /// Like [`std::env::current_dir()`], but it will `precompose_unicode` if that value is true, if the current directory
 /// is valid unicode and if there are decomposed unicode codepoints.
 ///
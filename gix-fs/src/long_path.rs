@@ -0,0 +1,125 @@
+//! Support for paths that exceed the classic `MAX_PATH` limit on Windows.
+//!
+//! Win32 APIs refuse paths longer than [`MAX_PATH`] characters unless they are passed in their *extended-length* form,
+//! i.e. prefixed with `\\?\`. Such paths are passed to the filesystem verbatim, which is why they must be absolute
+//! and can't contain `.` or `..` components or forward slashes.
+//!
+//! Converted paths are only meant to be used for the actual filesystem calls, while the original path should be kept
+//! for display. On all other platforms, the functions in this module don't alter their input.
+use std::{borrow::Cow, path::Path};
+
+/// The maximum length of a path in characters, including the terminating null character, that Win32 APIs accept
+/// for paths to files unless the path is in its extended-length form.
+pub const MAX_PATH: usize = 260;
+
+/// The maximum length of a path to a directory that is to be created, which is less than [`MAX_PATH`] as Windows
+/// reserves space for a file name in 8.3 notation in the directory.
+pub const MAX_DIR_PATH: usize = MAX_PATH - 12;
+
+/// Return `true` if `path` is too long to be used as path to a file with Win32 APIs, unless it was converted with
+/// [`to_extended_length()`] first. Relative paths are considered as if they were joined with the current working directory.
+///
+/// This is always `false` on platforms other than Windows.
+pub fn exceeds_limit(path: &Path) -> bool {
+    #[cfg(windows)]
+    {
+        length_in_characters(path) >= MAX_PATH
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+/// Return `path` in its extended-length form on Windows to be used in filesystem calls if `force` is `true` or if it
+/// is long enough to possibly exceed the limits for paths to files or directories.
+/// Relative paths are made absolute using the current working directory first, and the result is normalized.
+///
+/// Paths that are already in their extended-length form are returned unchanged, and on platforms other than Windows,
+/// `path` is always returned unchanged.
+pub fn to_extended_length(path: &Path, force: bool) -> std::io::Result<Cow<'_, Path>> {
+    #[cfg(windows)]
+    {
+        if !force && length_in_characters(path) < MAX_DIR_PATH {
+            return Ok(Cow::Borrowed(path));
+        }
+        windows::to_extended_length(path)
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = force;
+        Ok(Cow::Borrowed(path))
+    }
+}
+
+#[cfg(windows)]
+fn length_in_characters(path: &Path) -> usize {
+    use std::os::windows::ffi::OsStrExt;
+    let len = path.as_os_str().encode_wide().count() + 1 /* null character */;
+    if path.is_absolute() {
+        len
+    } else {
+        std::env::current_dir().map_or(0, |cwd| cwd.as_os_str().encode_wide().count() + 1 /* separator */) + len
+    }
+}
+
+#[cfg(windows)]
+mod windows {
+    use std::{
+        borrow::Cow,
+        ffi::{OsStr, OsString},
+        path::{Component, Path, Prefix},
+    };
+
+    pub fn to_extended_length(path: &Path) -> std::io::Result<Cow<'_, Path>> {
+        let absolute_path;
+        let path = if path.is_absolute() {
+            path
+        } else {
+            absolute_path = std::env::current_dir()?.join(path);
+            absolute_path.as_path()
+        };
+
+        let mut components = path.components();
+        let mut out = OsString::with_capacity(path.as_os_str().len() + 8);
+        match components.next() {
+            Some(Component::Prefix(prefix)) => match prefix.kind() {
+                Prefix::Disk(_) => {
+                    out.push(r"\\?\");
+                    out.push(prefix.as_os_str());
+                }
+                Prefix::UNC(server, share) => {
+                    out.push(r"\\?\UNC\");
+                    out.push(server);
+                    out.push(r"\");
+                    out.push(share);
+                }
+                Prefix::Verbatim(_) | Prefix::VerbatimUNC(..) | Prefix::VerbatimDisk(_) | Prefix::DeviceNS(_) => {
+                    return Ok(Cow::Owned(path.to_owned()))
+                }
+            },
+            // Paths that are rooted, but lack a drive, can't be expressed without knowing the drive.
+            _ => return Ok(Cow::Owned(path.to_owned())),
+        }
+
+        let mut normalized: Vec<&OsStr> = Vec::new();
+        for component in components {
+            match component {
+                Component::Prefix(_) | Component::RootDir | Component::CurDir => {}
+                Component::ParentDir => {
+                    normalized.pop();
+                }
+                Component::Normal(name) => normalized.push(name),
+            }
+        }
+        if normalized.is_empty() {
+            out.push(r"\");
+        }
+        for name in normalized {
+            out.push(r"\");
+            out.push(name);
+        }
+        Ok(Cow::Owned(out.into()))
+    }
+}
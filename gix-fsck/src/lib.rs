@@ -7,6 +7,9 @@ use gix_hash::ObjectId;
 use gix_hashtable::HashSet;
 use gix_object::{tree::EntryKind, Exists, FindExt, Kind};
 
+/// Validation of tree objects.
+pub mod tree;
+
 /// Perform a connectivity check.
 pub struct Connectivity<T, F>
 where
@@ -0,0 +1,45 @@
+use gix_object::{
+    bstr::{BString, ByteSlice},
+    tree::EntryMode,
+};
+
+/// A problem with an entry of a tree, as `git fsck` would report it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// The mode of the entry is zero-padded, like `040000`, which `git` calls `zeroPaddedFilemode`.
+    ZeroPaddedMode {
+        /// The name of the entry.
+        filename: BString,
+        /// The mode as parsed.
+        mode: EntryMode,
+    },
+    /// The mode of the entry isn't the canonical mode of its kind, like `100664`, which `git` calls `badFilemode`.
+    ///
+    /// Note that `git fsck` only reports `100664` with `--strict` as it was written by early versions of `git`.
+    NonstandardMode {
+        /// The name of the entry.
+        filename: BString,
+        /// The mode as parsed, whose [normalized](EntryMode::normalized()) version is what should have been written.
+        mode: EntryMode,
+    },
+}
+
+/// Check the entries of the tree whose serialized form is `data` and return all problems in order of occurrence.
+///
+/// Note that nonstandard modes are tolerated when reading trees, which is why these problems don't prevent their use.
+pub fn check_entries(data: &[u8]) -> Result<Vec<Problem>, gix_object::decode::Error> {
+    let tree = gix_object::TreeRef::from_bytes(data)?;
+    let mut rest = data;
+    let mut problems = Vec::new();
+    for entry in &tree.entries {
+        let raw_mode = &rest[..rest.find_byte(b' ').expect("the tree was parsed successfully")];
+        rest = &rest[raw_mode.len() + 1 + entry.filename.len() + 1 + entry.oid.as_bytes().len()..];
+        let (filename, mode) = (entry.filename.to_owned(), entry.mode);
+        if raw_mode.len() > 1 && raw_mode[0] == b'0' {
+            problems.push(Problem::ZeroPaddedMode { filename, mode });
+        } else if !mode.is_standard() {
+            problems.push(Problem::NonstandardMode { filename, mode });
+        }
+    }
+    Ok(problems)
+}
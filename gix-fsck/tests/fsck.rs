@@ -5,3 +5,4 @@ pub fn hex_to_id(hex: &str) -> ObjectId {
 }
 
 mod connectivity;
+mod tree;
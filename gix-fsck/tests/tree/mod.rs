@@ -0,0 +1,42 @@
+use gix_fsck::tree::{check_entries, Problem};
+use gix_object::tree::EntryMode;
+
+fn tree(entries: &[(&str, &str)]) -> Vec<u8> {
+    let mut data = Vec::new();
+    for (mode, filename) in entries {
+        data.extend_from_slice(format!("{mode} {filename}\0").as_bytes());
+        data.extend_from_slice(&[1; 20]);
+    }
+    data
+}
+
+#[test]
+fn nonstandard_modes_are_flagged() -> Result<(), Box<dyn std::error::Error>> {
+    let data = tree(&[
+        ("040000", "a"),
+        ("100664", "b"),
+        ("100644", "c"),
+        ("40000", "d"),
+        ("100600", "e"),
+    ]);
+    assert_eq!(
+        check_entries(&data)?,
+        [
+            Problem::ZeroPaddedMode {
+                filename: "a".into(),
+                mode: EntryMode(0o40000),
+            },
+            Problem::NonstandardMode {
+                filename: "b".into(),
+                mode: EntryMode(0o100664),
+            },
+            Problem::NonstandardMode {
+                filename: "e".into(),
+                mode: EntryMode(0o100600),
+            },
+        ]
+    );
+    assert_eq!(check_entries(&tree(&[("100755", "x"), ("120000", "y")]))?, []);
+    assert!(check_entries(b"100644 x").is_err(), "invalid trees can't be checked");
+    Ok(())
+}
@@ -5,8 +5,12 @@ bitflags! {
     #[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
     pub struct Mode: u8 {
         /// Let globs like `*` and `?` not match the slash `/` literal, which is useful when matching paths.
+        ///
+        /// This is the equivalent of `WM_PATHNAME` in `git`.
         const NO_MATCH_SLASH_LITERAL = 1 << 0;
         /// Match case insensitively for ascii characters only.
+        ///
+        /// This is the equivalent of `WM_CASEFOLD` in `git`.
         const IGNORE_CASE = 1 << 1;
     }
 }
@@ -273,7 +277,7 @@ pub(crate) mod function {
                                             }
                                         }
                                         b"blank" => {
-                                            if t_ch.is_ascii_whitespace() {
+                                            if matches!(t_ch, b' ' | b'\t') {
                                                 matched = true;
                                             }
                                         }
@@ -309,7 +313,8 @@ pub(crate) mod function {
                                             }
                                         }
                                         b"space" => {
-                                            if t_ch == b' ' {
+                                            // Like git, which doesn't consider `\v` and `\f` as space.
+                                            if matches!(t_ch, b' ' | b'\t' | b'\n' | b'\r') {
                                                 matched = true;
                                             }
                                         }
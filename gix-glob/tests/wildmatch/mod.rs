@@ -206,6 +206,15 @@ fn corpus() {
         (1,1,1,1, "a", "[B-a]"),
         (0,1,0,1, "z", "[Z-y]"),
         (1,1,1,1, "Z", "[Z-y]"),
+        // Character classes are the ones of `git`, which differ from `libc` for whitespace.
+        (1,1,1,1, "\t", "[[:blank:]]"),
+        (0,0,0,0, "\n", "[[:blank:]]"),
+        (1,1,1,1, "\t", "[[:space:]]"),
+        (1,1,1,1, "\n", "[[:space:]]"),
+        (1,1,1,1, "\r", "[[:space:]]"),
+        (0,0,0,0, "\x0b", "[[:space:]]"),
+        (0,0,0,0, "\x0c", "[[:space:]]"),
+        (0,0,0,0, "\t", "[[:graph:]]"),
     ];
 
     let mut failures = Vec::new();
@@ -0,0 +1,95 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use gix_hash::ObjectId;
+use gix_hashtable::{HashSet, ObjectIdSet};
+
+const NUM_IDS: usize = 10_000_000;
+
+fn ids() -> Vec<ObjectId> {
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    (0..NUM_IDS)
+        .map(|_| {
+            let mut bytes = [0u8; 20];
+            for chunk in bytes.chunks_mut(8) {
+                chunk.copy_from_slice(&next().to_ne_bytes()[..chunk.len()]);
+            }
+            ObjectId::from(bytes)
+        })
+        .collect()
+}
+
+/// The amount of bytes `set` allocated, assuming hashbrown keeps at most 7 of 8 buckets occupied and uses one control
+/// byte per bucket.
+fn hash_set_heap_size(set: &HashSet<ObjectId>) -> usize {
+    let buckets = (set.capacity() * 8 / 7).next_power_of_two();
+    buckets * (std::mem::size_of::<ObjectId>() + 1)
+}
+
+fn insert_and_lookup(c: &mut Criterion) {
+    let ids = ids();
+    {
+        let mut hash_set = HashSet::default();
+        let mut oid_set = ObjectIdSet::default();
+        let mut lossy_set = ObjectIdSet::lossy(NUM_IDS / 8);
+        for id in &ids {
+            hash_set.insert(*id);
+            oid_set.insert(id);
+            lossy_set.insert(id);
+        }
+        eprintln!(
+            "heap size for {NUM_IDS} ids: HashSet<ObjectId>: {}MB, ObjectIdSet: {}MB, ObjectIdSet::lossy({}): {}MB",
+            hash_set_heap_size(&hash_set) / 1024 / 1024,
+            oid_set.heap_size() / 1024 / 1024,
+            NUM_IDS / 8,
+            lossy_set.heap_size() / 1024 / 1024,
+        );
+    }
+
+    let mut group = c.benchmark_group("insert 10M ids twice");
+    group.sample_size(10);
+    group.bench_function("HashSet<ObjectId>", |b| {
+        b.iter_batched(
+            HashSet::<ObjectId>::default,
+            |mut set| {
+                for id in ids.iter().chain(&ids) {
+                    black_box(set.insert(*id));
+                }
+                set
+            },
+            BatchSize::PerIteration,
+        )
+    });
+    group.bench_function("ObjectIdSet", |b| {
+        b.iter_batched(
+            ObjectIdSet::default,
+            |mut set| {
+                for id in ids.iter().chain(&ids) {
+                    black_box(set.insert(id));
+                }
+                set
+            },
+            BatchSize::PerIteration,
+        )
+    });
+    group.bench_function("ObjectIdSet::lossy(10M/8)", |b| {
+        b.iter_batched(
+            || ObjectIdSet::lossy(NUM_IDS / 8),
+            |mut set| {
+                for id in ids.iter().chain(&ids) {
+                    black_box(set.insert(id));
+                }
+                set
+            },
+            BatchSize::PerIteration,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, insert_and_lookup);
+criterion_main!(benches);
@@ -7,6 +7,9 @@
 use gix_hash::ObjectId;
 pub use hashbrown::{hash_map, hash_set, raw, Equivalent};
 
+pub mod oid_set;
+pub use oid_set::ObjectIdSet;
+
 /// thread-safe types
 pub mod sync {
     /// A map for associating data with object ids in a thread-safe fashion. It should scale well up to 256 threads.
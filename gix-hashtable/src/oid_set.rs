@@ -0,0 +1,379 @@
+//! A set of object ids optimized for tracking visited objects in traversals of huge repositories.
+use gix_hash::{oid, ObjectId};
+
+/// Determines how an [`ObjectIdSet`] trades exactness for memory.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Mode {
+    /// Remember every inserted id, growing the set as needed.
+    #[default]
+    Exact,
+    /// Never grow beyond `max_entries` stored ids, and use a Bloom filter to quickly learn that an id is new.
+    ///
+    /// If the set is full, ids that were inserted before may be forgotten, so [`insert()`](ObjectIdSet::insert()) may
+    /// claim that an id is new even though it was seen. It never claims that a new id was seen though, which makes this
+    /// mode suitable for traversals that can tolerate visiting objects more than once.
+    Lossy {
+        /// The maximum amount of ids to remember exactly, which is rounded up to the next power of two.
+        max_entries: usize,
+    },
+}
+
+/// A set of object ids, similar to a [`HashSet<ObjectId>`](crate::HashSet), but using less memory per id.
+///
+/// In [exact mode](Mode::Exact), ids are kept in buckets selected by their leading bits, each of which holds a sorted
+/// block of ids and a small unsorted overflow which is merged into the sorted block once it's full.
+/// That way, each id uses little more than its own size, compared to the 1.2 to 2.3 times its size needed by a `HashSet`,
+/// at the cost of much slower insertions and lookups, which is why it's only worth using if memory is the main concern.
+///
+/// In [lossy mode](Mode::Lossy), a table of bounded size along with a Bloom filter is used instead.
+#[derive(Default, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ObjectIdSet {
+    inner: Inner,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum Inner {
+    Exact(Buckets),
+    Lossy(Lossy),
+}
+
+impl Default for Inner {
+    fn default() -> Self {
+        Inner::Exact(Buckets::default())
+    }
+}
+
+/// Initialization
+impl ObjectIdSet {
+    /// Create an empty set in [lossy mode](Mode::Lossy), which remembers up to `max_entries` ids exactly.
+    pub fn lossy(max_entries: usize) -> Self {
+        ObjectIdSet {
+            inner: Inner::Lossy(Lossy::new(max_entries)),
+        }
+    }
+}
+
+/// Access and modification
+impl ObjectIdSet {
+    /// Insert `id` and return `true` if it wasn't present yet, or `false` if it was.
+    ///
+    /// In [lossy mode](Mode::Lossy), `true` may also be returned for ids that were inserted before.
+    pub fn insert(&mut self, id: &oid) -> bool {
+        match &mut self.inner {
+            Inner::Exact(buckets) => buckets.insert(id),
+            Inner::Lossy(lossy) => lossy.insert(id),
+        }
+    }
+
+    /// Return `true` if `id` is contained in the set.
+    ///
+    /// In [lossy mode](Mode::Lossy), `false` may also be returned for ids that were inserted before.
+    pub fn contains(&self, id: &oid) -> bool {
+        match &self.inner {
+            Inner::Exact(buckets) => buckets.contains(id),
+            Inner::Lossy(lossy) => lossy.contains(id),
+        }
+    }
+
+    /// Return the amount of ids stored in the set.
+    ///
+    /// In [lossy mode](Mode::Lossy), this is the amount of ids that are currently remembered.
+    pub fn len(&self) -> usize {
+        match &self.inner {
+            Inner::Exact(buckets) => buckets.len,
+            Inner::Lossy(lossy) => lossy.len,
+        }
+    }
+
+    /// Return `true` if there is no id stored in the set.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the mode the set operates in.
+    pub fn mode(&self) -> Mode {
+        match &self.inner {
+            Inner::Exact(_) => Mode::Exact,
+            Inner::Lossy(lossy) => Mode::Lossy {
+                max_entries: lossy.max_entries,
+            },
+        }
+    }
+
+    /// Remove all ids.
+    pub fn clear(&mut self) {
+        match &mut self.inner {
+            Inner::Exact(buckets) => *buckets = Buckets::default(),
+            Inner::Lossy(lossy) => lossy.clear(),
+        }
+    }
+
+    /// Return the amount of bytes allocated on the heap to hold the set.
+    pub fn heap_size(&self) -> usize {
+        match &self.inner {
+            Inner::Exact(buckets) => buckets.heap_size(),
+            Inner::Lossy(lossy) => lossy.heap_size(),
+        }
+    }
+}
+
+/// The amount of unsorted ids in a bucket after which they are merged into its sorted block.
+const MAX_UNSORTED: usize = 16;
+/// The average amount of ids per bucket after which the amount of buckets is doubled.
+const MAX_AVERAGE_BUCKET_LEN: usize = 256;
+
+#[derive(Default, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Buckets {
+    /// The amount of leading bits of an id that determine its bucket.
+    prefix_bits: u32,
+    /// `2^prefix_bits` buckets, or none if nothing was inserted yet.
+    buckets: Vec<Bucket>,
+    /// The amount of ids in all buckets.
+    len: usize,
+}
+
+#[derive(Default, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Bucket {
+    /// The ids in this bucket, the first `num_sorted` of which are sorted.
+    ids: Vec<ObjectId>,
+    /// The amount of sorted ids at the beginning of `ids`.
+    num_sorted: usize,
+}
+
+impl Buckets {
+    fn insert(&mut self, id: &oid) -> bool {
+        if self.buckets.is_empty() {
+            self.buckets.push(Bucket::default());
+        }
+        let bucket_index = bucket_index(id, self.prefix_bits);
+        if !self.buckets[bucket_index].insert(id) {
+            return false;
+        }
+        self.len += 1;
+        if self.len > self.buckets.len() * MAX_AVERAGE_BUCKET_LEN {
+            self.split();
+        }
+        true
+    }
+
+    fn contains(&self, id: &oid) -> bool {
+        self.buckets
+            .get(bucket_index(id, self.prefix_bits))
+            .into_iter()
+            .any(|bucket| bucket.contains(id))
+    }
+
+    /// Double the amount of buckets by splitting each one in two, one bucket at a time to keep the peak memory usage low.
+    fn split(&mut self) {
+        let buckets = std::mem::take(&mut self.buckets);
+        self.buckets.reserve_exact(buckets.len() * 2);
+        for mut bucket in buckets {
+            bucket.sort();
+            let pos = bucket
+                .ids
+                .partition_point(|id| bucket_index(id, self.prefix_bits + 1) % 2 == 0);
+            for ids in [&bucket.ids[..pos], &bucket.ids[pos..]] {
+                let mut new_ids = Vec::with_capacity(ids.len() + MAX_UNSORTED);
+                new_ids.extend_from_slice(ids);
+                self.buckets.push(Bucket {
+                    num_sorted: new_ids.len(),
+                    ids: new_ids,
+                });
+            }
+        }
+        self.prefix_bits += 1;
+    }
+
+    fn heap_size(&self) -> usize {
+        self.buckets.capacity() * std::mem::size_of::<Bucket>()
+            + self
+                .buckets
+                .iter()
+                .map(|bucket| bucket.ids.capacity() * std::mem::size_of::<ObjectId>())
+                .sum::<usize>()
+    }
+}
+
+impl Bucket {
+    fn insert(&mut self, id: &oid) -> bool {
+        if self.contains(id) {
+            return false;
+        }
+        if self.ids.len() == self.ids.capacity() {
+            // Grow in smaller steps than `Vec` would, as doubling the capacity wastes a lot of memory.
+            self.ids.reserve_exact((self.ids.len() / 8).max(MAX_UNSORTED));
+        }
+        self.ids.push(id.to_owned());
+        if self.ids.len() - self.num_sorted >= MAX_UNSORTED {
+            self.sort();
+        }
+        true
+    }
+
+    fn contains(&self, id: &oid) -> bool {
+        let (sorted, unsorted) = self.ids.split_at(self.num_sorted);
+        sorted.binary_search_by(|probe| probe.as_ref().cmp(id)).is_ok()
+            || unsorted.iter().any(|probe| probe.as_bytes() == id.as_bytes())
+    }
+
+    /// Sort the unsorted ids and merge them into the sorted ones.
+    fn sort(&mut self) {
+        let num_unsorted = self.ids.len() - self.num_sorted;
+        debug_assert!(num_unsorted <= MAX_UNSORTED, "we sort as soon as the overflow is full");
+        // Keep the few unsorted ids on the stack to avoid allocating for each merge.
+        let mut unsorted = [gix_hash::Kind::Sha1.null(); MAX_UNSORTED];
+        let unsorted = &mut unsorted[..num_unsorted];
+        unsorted.copy_from_slice(&self.ids[self.num_sorted..]);
+        unsorted.sort_unstable();
+        let (mut sorted_end, mut unsorted_end) = (self.num_sorted, num_unsorted);
+        // Merge from the back so that each id is moved only once.
+        for dest in (0..self.ids.len()).rev() {
+            if unsorted_end == 0 {
+                break;
+            }
+            if sorted_end > 0 && self.ids[sorted_end - 1] > unsorted[unsorted_end - 1] {
+                sorted_end -= 1;
+                self.ids[dest] = self.ids[sorted_end];
+            } else {
+                unsorted_end -= 1;
+                self.ids[dest] = unsorted[unsorted_end];
+            }
+        }
+        self.num_sorted = self.ids.len();
+    }
+}
+
+/// Return the index of the bucket of `id` if the first `prefix_bits` bits of ids determine their bucket.
+fn bucket_index(id: &oid, prefix_bits: u32) -> usize {
+    if prefix_bits == 0 {
+        return 0;
+    }
+    let prefix = u64::from_be_bytes(id.as_bytes()[..8].try_into().expect("ids are at least 8 bytes long"));
+    (prefix >> (64 - prefix_bits)) as usize
+}
+
+/// The maximum amount of slots to probe in lossy mode before an existing entry is evicted.
+const LOSSY_PROBE_LIMIT: usize = 16;
+/// The amount of Bloom filter bits per slot in lossy mode.
+const BLOOM_BITS_PER_SLOT: usize = 16;
+/// The amount of bits to set and test per id in the Bloom filter.
+const BLOOM_NUM_HASHES: u64 = 4;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Lossy {
+    max_entries: usize,
+    /// A power of two amount of slots, each of which may hold an id.
+    slots: Vec<Option<ObjectId>>,
+    /// The amount of slots that hold an id.
+    len: usize,
+    /// The bits of the Bloom filter, which knows all ids in `slots`, along with ids that were evicted from it.
+    bloom: Vec<u64>,
+}
+
+impl Lossy {
+    fn new(max_entries: usize) -> Self {
+        Lossy {
+            max_entries,
+            slots: Vec::new(),
+            len: 0,
+            bloom: Vec::new(),
+        }
+    }
+
+    fn insert(&mut self, id: &oid) -> bool {
+        if self.slots.is_empty() {
+            let num_slots = self.max_entries.max(1).next_power_of_two();
+            self.slots = vec![None; num_slots];
+            self.bloom = vec![0; (num_slots * BLOOM_BITS_PER_SLOT / 64).max(1)];
+        }
+        let maybe_present = self.bloom_insert(id);
+        let mut empty_slot = None;
+        for slot in self.probe_sequence(id) {
+            match &self.slots[slot] {
+                Some(stored) if maybe_present && stored.as_bytes() == id.as_bytes() => return false,
+                Some(_) => {}
+                None => {
+                    empty_slot = Some(slot);
+                    break;
+                }
+            }
+        }
+        match empty_slot {
+            Some(slot) => {
+                self.slots[slot] = Some(id.to_owned());
+                self.len += 1;
+            }
+            None => {
+                let slot = self.probe_sequence(id).next().expect("at least one slot");
+                self.slots[slot] = Some(id.to_owned());
+            }
+        }
+        true
+    }
+
+    fn contains(&self, id: &oid) -> bool {
+        if self.slots.is_empty() || !self.bloom_contains(id) {
+            return false;
+        }
+        for slot in self.probe_sequence(id) {
+            match &self.slots[slot] {
+                Some(stored) if stored.as_bytes() == id.as_bytes() => return true,
+                Some(_) => {}
+                None => break,
+            }
+        }
+        false
+    }
+
+    fn clear(&mut self) {
+        self.slots.iter_mut().for_each(|slot| *slot = None);
+        self.bloom.iter_mut().for_each(|bits| *bits = 0);
+        self.len = 0;
+    }
+
+    fn heap_size(&self) -> usize {
+        self.slots.capacity() * std::mem::size_of::<Option<ObjectId>>()
+            + self.bloom.capacity() * std::mem::size_of::<u64>()
+    }
+
+    fn probe_sequence(&self, id: &oid) -> impl Iterator<Item = usize> {
+        let mask = self.slots.len() - 1;
+        let home = u64::from_ne_bytes(id.as_bytes()[..8].try_into().expect("ids are at least 8 bytes long")) as usize;
+        (0..LOSSY_PROBE_LIMIT.min(self.slots.len())).map(move |offset| (home + offset) & mask)
+    }
+
+    /// Set the Bloom filter bits for `id` and return `true` if all of them were set before, i.e. `id` may have been
+    /// inserted before.
+    fn bloom_insert(&mut self, id: &oid) -> bool {
+        let mut was_set = true;
+        for bit in bloom_bits(id, self.bloom.len() * 64) {
+            let (word, mask) = (bit / 64, 1 << (bit % 64));
+            was_set &= self.bloom[word] & mask != 0;
+            self.bloom[word] |= mask;
+        }
+        was_set
+    }
+
+    fn bloom_contains(&self, id: &oid) -> bool {
+        bloom_bits(id, self.bloom.len() * 64).all(|bit| self.bloom[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+}
+
+/// Use double hashing on bytes of `id` that aren't used to find its slot to produce the bits to use in a Bloom filter
+/// of `num_bits` bits.
+fn bloom_bits(id: &oid, num_bits: usize) -> impl Iterator<Item = usize> {
+    let id = id.as_bytes();
+    let h1 = u64::from(u32::from_ne_bytes(
+        id[12..16].try_into().expect("ids are at least 20 bytes long"),
+    ));
+    let h2 = u64::from(u32::from_ne_bytes(
+        id[16..20].try_into().expect("ids are at least 20 bytes long"),
+    )) | 1;
+    (0..BLOOM_NUM_HASHES).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % num_bits as u64) as usize)
+}
@@ -19,3 +19,99 @@ mod hash {
         }
     }
 }
+
+mod oid_set {
+    use gix_hash::ObjectId;
+    use gix_hashtable::{oid_set::Mode, ObjectIdSet};
+
+    /// Produce `count` distinct pseudo-random ids.
+    fn ids(count: usize) -> Vec<ObjectId> {
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let mut ids: Vec<_> = (0..count)
+            .map(|_| {
+                let mut bytes = [0u8; 20];
+                for chunk in bytes.chunks_mut(8) {
+                    chunk.copy_from_slice(&next().to_ne_bytes()[..chunk.len()]);
+                }
+                ObjectId::from(bytes)
+            })
+            .collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), count, "ids are distinct");
+        ids
+    }
+
+    #[test]
+    fn exact_mode_remembers_all_ids() {
+        let ids = ids(100_000);
+        let mut set = ObjectIdSet::default();
+        assert!(set.is_empty());
+        assert_eq!(set.mode(), Mode::Exact);
+        for id in &ids {
+            assert!(set.insert(id), "{id} is new");
+        }
+        assert_eq!(set.len(), ids.len());
+        for id in &ids {
+            assert!(!set.insert(id), "{id} was seen");
+            assert!(set.contains(id));
+        }
+        assert_eq!(set.len(), ids.len(), "duplicates aren't stored");
+        assert!(!set.contains(&gix_hash::Kind::Sha1.null()));
+        assert!(
+            set.heap_size() < ids.len() * std::mem::size_of::<ObjectId>() * 5 / 4,
+            "each id needs little more than its own size: {}",
+            set.heap_size()
+        );
+
+        set.clear();
+        assert!(set.is_empty());
+        assert!(!set.contains(&ids[0]));
+        assert!(set.insert(&ids[0]));
+    }
+
+    #[test]
+    fn lossy_mode_never_claims_that_new_ids_were_seen() {
+        let ids = ids(100_000);
+        let mut set = ObjectIdSet::lossy(1000);
+        assert_eq!(set.mode(), Mode::Lossy { max_entries: 1000 });
+        for id in &ids {
+            assert!(set.insert(id), "{id} is new, even though the set can't hold it");
+            assert!(!set.insert(id), "the most recently inserted id is always remembered");
+            assert!(set.contains(id));
+        }
+        assert_eq!(set.len(), 1024, "the set doesn't grow beyond its maximum size");
+        let heap_size = set.heap_size();
+        assert_eq!(
+            heap_size,
+            1024 * std::mem::size_of::<Option<ObjectId>>() + 1024 * 16 / 8,
+            "slots for ids and the bloom filter"
+        );
+
+        let remembered = ids.iter().filter(|id| set.contains(id)).count();
+        assert!(
+            remembered <= 1024 && remembered > 512,
+            "many of the ids are still remembered, but not all: {remembered}"
+        );
+        assert_eq!(set.heap_size(), heap_size, "the memory usage is bounded");
+    }
+
+    #[test]
+    fn lossy_mode_is_exact_while_there_is_enough_space() {
+        let ids = ids(500);
+        let mut set = ObjectIdSet::lossy(1000);
+        for id in &ids {
+            assert!(set.insert(id));
+        }
+        for id in &ids {
+            assert!(!set.insert(id), "{id} was seen");
+        }
+        assert_eq!(set.len(), ids.len());
+    }
+}
@@ -0,0 +1,57 @@
+use std::time::Instant;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use gix_index::{entry, File, Snapshot, State};
+
+const NUM_ENTRIES: usize = 100_000;
+const QUERIES_PER_THREAD: usize = 10_000;
+
+fn snapshot() -> (Snapshot, Vec<Vec<u8>>) {
+    let mut state = State::new(gix_hash::Kind::Sha1);
+    let paths: Vec<_> = (0..NUM_ENTRIES)
+        .map(|idx| format!("dir-{:03}/sub-{:02}/file-{idx:06}", idx % 997, idx % 13).into_bytes())
+        .collect();
+    for path in &paths {
+        state.dangerously_push_entry(
+            Default::default(),
+            gix_hash::Kind::Sha1.null(),
+            entry::Flags::empty(),
+            entry::Mode::FILE,
+            path.as_slice().into(),
+        );
+    }
+    state.sort_entries();
+    (Snapshot::from(File::from_state(state, "index")), paths)
+}
+
+/// Run `QUERIES_PER_THREAD` lookups on each of `threads` threads, all sharing the same snapshot, so that the time per
+/// query stays the same no matter how many threads are used.
+fn concurrent_lookups(c: &mut Criterion) {
+    let (snapshot, paths) = snapshot();
+    let mut group = c.benchmark_group("Snapshot::find()");
+    for threads in [1, 2, 4, 8] {
+        group.throughput(Throughput::Elements((threads * QUERIES_PER_THREAD) as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(threads), &threads, |b, &threads| {
+            b.iter_custom(|iterations| {
+                let start = Instant::now();
+                std::thread::scope(|scope| {
+                    for thread in 0..threads {
+                        let snapshot = snapshot.clone();
+                        let paths = &paths;
+                        scope.spawn(move || {
+                            for _ in 0..iterations {
+                                for path in paths.iter().skip(thread).step_by(threads).take(QUERIES_PER_THREAD) {
+                                    black_box(snapshot.find(path.as_slice().into()));
+                                }
+                            }
+                        });
+                    }
+                });
+                start.elapsed()
+            });
+        });
+    }
+}
+
+criterion_group!(benches, concurrent_lookups);
+criterion_main!(benches);
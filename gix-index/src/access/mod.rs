@@ -17,6 +17,12 @@ impl State {
         self.version
     }
 
+    /// Set the `version` to use when writing this state, which is adjusted to the entries when writing
+    /// as described in [`write::Options::version`](crate::write::Options::version).
+    pub fn set_version(&mut self, version: Version) {
+        self.version = version;
+    }
+
     /// Returns time at which the state was created, indicating its freshness compared to other files on disk.
     pub fn timestamp(&self) -> FileTime {
         self.timestamp
@@ -40,6 +46,17 @@ impl State {
     pub fn entries(&self) -> &[Entry] {
         &self.entries
     }
+
+    /// Return `true` if at least one of our entries has a timestamp with a nanosecond part.
+    ///
+    /// If none has, the index was likely written by a `git` that doesn't store nanoseconds, or on a filesystem
+    /// with coarse timestamps.
+    pub fn has_nanosecond_timestamps(&self) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.stat.mtime.nsecs != 0 || entry.stat.ctime.nsecs != 0)
+    }
+
     /// Return our path backing, the place which keeps all paths one after another, with entries storing only the range to access them.
     pub fn path_backing(&self) -> &PathStorageRef {
         &self.path_backing
@@ -496,6 +513,75 @@ impl State {
         });
     }
 
+    /// Insert a new unconflicted entry containing `stat`, `id`, `flags` and `mode` at `path` while keeping entries sorted,
+    /// or replace the entry that is already present at `path`. Return the index of the inserted or updated entry.
+    ///
+    /// If `path` was conflicted, all of its stages collapse into the one unconflicted entry, just like `git add` resolves conflicts.
+    /// The stage bits of `flags` are ignored, and as trees computed previously are now out of date, the `tree` extension is removed.
+    ///
+    /// Entries that can't coexist with an entry at `path` are removed, which are those inside of a directory at `path` and
+    /// those at one of the leading directories of `path`, just like `git` replaces them when adding files.
+    pub fn upsert_entry(
+        &mut self,
+        stat: entry::Stat,
+        id: gix_hash::ObjectId,
+        mut flags: entry::Flags,
+        mode: entry::Mode,
+        path: &BStr,
+    ) -> usize {
+        flags.remove(entry::Flags::STAGE_MASK);
+        self.tree = None;
+        self.remove_directory_file_conflicts(path);
+        match self.entry_range(path) {
+            Some(range) => {
+                let idx = range.start;
+                self.entries.drain(idx + 1..range.end);
+                let entry = &mut self.entries[idx];
+                entry.stat = stat;
+                entry.id = id;
+                entry.flags = flags;
+                entry.mode = mode;
+                idx
+            }
+            None => {
+                let idx = self
+                    .entry_index_by_path(path)
+                    .expect_err("BUG: entry_range() would have found the entry");
+                let path = {
+                    let path_start = self.path_backing.len();
+                    self.path_backing.push_str(path);
+                    path_start..self.path_backing.len()
+                };
+                self.entries.insert(
+                    idx,
+                    Entry {
+                        stat,
+                        id,
+                        flags,
+                        mode,
+                        path,
+                    },
+                );
+                idx
+            }
+        }
+    }
+
+    /// Remove all entries below the directory `path`, and all entries at leading directories of `path`,
+    /// as neither can exist if `path` is a file.
+    fn remove_directory_file_conflicts(&mut self, path: &BStr) {
+        let mut directory = path.to_owned();
+        directory.push_byte(b'/');
+        if let Some(range) = self.prefixed_entries_range(directory.as_ref()) {
+            self.entries.drain(range);
+        }
+        for leading_dir_end in path.find_iter(b"/") {
+            if let Some(range) = self.entry_range(path[..leading_dir_end].as_bstr()) {
+                self.entries.drain(range);
+            }
+        }
+    }
+
     /// Unconditionally sort entries as needed to perform lookups quickly.
     pub fn sort_entries(&mut self) {
         let path_backing = &self.path_backing;
@@ -542,6 +628,10 @@ impl State {
     pub fn tree(&self) -> Option<&extension::Tree> {
         self.tree.as_ref()
     }
+    /// Remove the `tree` extension and return it, which is useful as it would be outdated after changing entries.
+    pub fn remove_tree(&mut self) -> Option<extension::Tree> {
+        self.tree.take()
+    }
     /// Access the `link` extension.
     pub fn link(&self) -> Option<&extension::Link> {
         self.link.as_ref()
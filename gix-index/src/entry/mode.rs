@@ -17,6 +17,39 @@ impl Mode {
         gix_object::tree::EntryMode::try_from(self.bits()).ok()
     }
 
+    /// Determine the mode an entry should have if it was created from the file system version ([`std::fs::symlink_metadata`])
+    /// in `stat`, similar to what `git` does when adding a file to the index.
+    ///
+    /// `previous` is the mode of an entry that is already present at the same path, if there is one.
+    /// It's used to retain what the filesystem can't represent:
+    ///
+    /// * if `has_symlinks` is false and a normal file is on disk where a symlink was tracked, the symlink mode is kept.
+    /// * if `executable_bit` is false the executable bit is taken from `previous`, or assumed to be unset
+    ///   if there is no such entry.
+    ///
+    /// Directories are assumed to be submodules, and `None` is returned if `stat` is neither a file, a symlink nor a directory.
+    pub fn from_fs(
+        stat: &crate::fs::Metadata,
+        has_symlinks: bool,
+        executable_bit: bool,
+        previous: Option<Mode>,
+    ) -> Option<Mode> {
+        Some(if stat.is_dir() {
+            Mode::COMMIT
+        } else if stat.is_symlink() {
+            Mode::SYMLINK
+        } else if stat.is_file() {
+            match previous {
+                Some(Mode::SYMLINK) if !has_symlinks => Mode::SYMLINK,
+                Some(mode @ (Mode::FILE | Mode::FILE_EXECUTABLE)) if !executable_bit => mode,
+                _ if executable_bit && stat.is_executable() => Mode::FILE_EXECUTABLE,
+                _ => Mode::FILE,
+            }
+        } else {
+            return None;
+        })
+    }
+
     /// Compares this mode to the file system version ([`std::fs::symlink_metadata`])
     /// and returns the change needed to update this mode to match the file.
     ///
@@ -69,7 +102,7 @@ impl Mode {
 
 impl From<gix_object::tree::EntryMode> for Mode {
     fn from(value: gix_object::tree::EntryMode) -> Self {
-        Self::from_bits_truncate(value.0 as u32)
+        Self::from_bits_truncate(value.normalized().0 as u32)
     }
 }
 
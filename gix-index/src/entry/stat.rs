@@ -14,6 +14,9 @@ impl Stat {
     /// The index `timestamp` marks the point in time before which we definitely resolved the racy git problem
     /// for all index entries so any index entries that changed afterwards will need to be examined for
     /// changes by actually reading the file from disk at least once.
+    ///
+    /// Nanoseconds are only used to tell apart timestamps within the same second if both have them, as a zero
+    /// nanosecond part typically indicates a filesystem with coarse timestamps.
     pub fn is_racy(
         &self,
         timestamp: FileTime,
@@ -23,7 +26,9 @@ impl Stat {
     ) -> bool {
         match timestamp.unix_seconds().cmp(&(self.mtime.secs as i64)) {
             Ordering::Less => true,
-            Ordering::Equal if use_nsec && check_stat => timestamp.nanoseconds() <= self.mtime.nsecs,
+            Ordering::Equal if use_nsec && check_stat && timestamp.nanoseconds() != 0 && self.mtime.nsecs != 0 => {
+                timestamp.nanoseconds() <= self.mtime.nsecs
+            }
             Ordering::Equal => true,
             Ordering::Greater => false,
         }
@@ -56,11 +61,11 @@ impl Stat {
             return false;
         }
 
-        if trust_ctime {
+        if trust_ctime && check_stat {
             if self.ctime.secs != other.ctime.secs {
                 return false;
             }
-            if check_stat && use_nsec && self.ctime.nsecs != other.ctime.nsecs {
+            if use_nsec && self.ctime.nsecs != other.ctime.nsecs {
                 return false;
             }
         }
@@ -167,12 +172,13 @@ impl PartialOrd<FileTime> for Time {
 pub struct Options {
     /// If true, a files creation time is taken into consideration when checking if a file changed.
     /// Can be set to false in case other tools alter the creation time in ways that interfere with our operation.
+    /// It's only used if [`check_stat`](Self::check_stat) is `true` as well.
     ///
     /// Default `true`.
     pub trust_ctime: bool,
     /// If true, all stat fields will be used when checking for up-to-date'ness of the entry. Otherwise
-    /// nano-second parts of mtime and ctime,uid, gid, inode and device number _will not_ be used, leaving only
-    /// the whole-second part of ctime and mtime and the file size to be checked.
+    /// ctime, the nano-second part of mtime, uid, gid, inode and device number _will not_ be used, leaving only
+    /// the whole-second part of mtime and the file size to be checked, just like `git` does with `core.checkStat=minimal`.
     ///
     /// Default `true`.
     pub check_stat: bool,
@@ -189,6 +195,19 @@ pub struct Options {
     pub use_stdev: bool,
 }
 
+impl Options {
+    /// Adjust these options to the entries of `index`, turning off the comparison of nanoseconds if none of its entries
+    /// has them, as is the case for indices written by a `git` that didn't store them.
+    ///
+    /// Otherwise, every entry would appear changed when compared to fresh stat information that has nanoseconds.
+    pub fn for_index(mut self, index: &crate::State) -> Self {
+        if self.use_nsec && !index.has_nanosecond_timestamps() {
+            self.use_nsec = false;
+        }
+        self
+    }
+}
+
 impl Default for Options {
     fn default() -> Self {
         Self {
@@ -1,8 +1,39 @@
+use bstr::BStr;
+
 use crate::{entry, Entry, State};
 
 impl Entry {
     /// Serialize ourselves to `out` with path access via `state`, without padding.
     pub fn write_to(&self, mut out: impl std::io::Write, state: &State) -> std::io::Result<()> {
+        let path = self.path(state);
+        self.write_fields_to(&mut out, path)?;
+        out.write_all(path)?;
+        out.write_all(b"\0")
+    }
+
+    /// Serialize ourselves to `out` with path access via `state` as done in index V4, which stores our path as
+    /// the amount of bytes to remove from the end of `previous_path` followed by the bytes to append to it.
+    ///
+    /// `previous_path` is the path of the entry written before this one, or empty if there is none.
+    pub fn write_to_with_path_compression(
+        &self,
+        mut out: impl std::io::Write,
+        state: &State,
+        previous_path: &BStr,
+    ) -> std::io::Result<()> {
+        let path = self.path(state);
+        self.write_fields_to(&mut out, path)?;
+        let common_prefix_len = path
+            .iter()
+            .zip(previous_path.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        write_var_int(&mut out, (previous_path.len() - common_prefix_len) as u64)?;
+        out.write_all(&path[common_prefix_len..])?;
+        out.write_all(b"\0")
+    }
+
+    fn write_fields_to(&self, mut out: impl std::io::Write, path: &BStr) -> std::io::Result<()> {
         let stat = self.stat;
         out.write_all(&stat.ctime.secs.to_be_bytes())?;
         out.write_all(&stat.ctime.nsecs.to_be_bytes())?;
@@ -15,7 +46,6 @@ impl Entry {
         out.write_all(&stat.gid.to_be_bytes())?;
         out.write_all(&stat.size.to_be_bytes())?;
         out.write_all(self.id.as_bytes())?;
-        let path = self.path(state);
         let path_len: u16 = if path.len() >= entry::Flags::PATH_LEN.bits() as usize {
             entry::Flags::PATH_LEN.bits() as u16
         } else {
@@ -31,7 +61,22 @@ impl Entry {
                     .to_be_bytes(),
             )?;
         }
-        out.write_all(path)?;
-        out.write_all(b"\0")
+        Ok(())
+    }
+}
+
+/// Write `value` as variable-length integer in the encoding that is also used for offsets of delta-bases in packs,
+/// which is the inverse of [`gix_features::decode::leb64()`].
+fn write_var_int(mut out: impl std::io::Write, mut value: u64) -> std::io::Result<()> {
+    let mut buf = [0u8; 10];
+    let mut pos = buf.len() - 1;
+    buf[pos] = (value & 0x7f) as u8;
+    value >>= 7;
+    while value != 0 {
+        value -= 1;
+        pos -= 1;
+        buf[pos] = 0x80 | (value & 0x7f) as u8;
+        value >>= 7;
     }
+    out.write_all(&buf[pos..])
 }
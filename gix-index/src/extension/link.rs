@@ -1,6 +1,8 @@
 use crate::{
+    entry,
     extension::{Link, Signature},
     util::split_at_pos,
+    Entry, State,
 };
 
 /// The signature of the link extension.
@@ -90,13 +92,17 @@ impl Link {
                 ..options
             },
         )?;
+        shared_index.checksum = Some(self.shared_index_checksum);
 
         if let Some(bitmaps) = self.bitmaps {
+            // The shared index is kept as is to write only changes later, so its entries are merged into copies.
+            let mut shared_entries = shared_index.entries.clone();
+            let mut shared_path_backing = shared_index.path_backing.clone();
             let mut split_entry_index = 0;
 
             let mut err = None;
             bitmaps.replace.for_each_set_bit(|replace_index| {
-                let shared_entry = match shared_index.entries.get_mut(replace_index) {
+                let shared_entry = match shared_entries.get_mut(replace_index) {
                     Some(e) => e,
                     None => {
                         err = decode::Error::Corrupt("replace bitmap length exceeds shared index length - more entries in bitmap than found in shared index").into();
@@ -138,19 +144,17 @@ impl Link {
 
             let split_index_path_backing = std::mem::take(&mut split_index.path_backing);
             for mut split_entry in split_index.entries.drain(split_entry_index..) {
-                let start = shared_index.path_backing.len();
+                let start = shared_path_backing.len();
                 let split_index_path = split_entry.path.clone();
 
                 split_entry.path = start..start + split_entry.path.len();
-                shared_index.entries.push(split_entry);
+                shared_entries.push(split_entry);
 
-                shared_index
-                    .path_backing
-                    .extend_from_slice(&split_index_path_backing[split_index_path]);
+                shared_path_backing.extend_from_slice(&split_index_path_backing[split_index_path]);
             }
 
             bitmaps.delete.for_each_set_bit(|delete_index| {
-                let shared_entry = match shared_index.entries.get_mut(delete_index) {
+                let shared_entry = match shared_entries.get_mut(delete_index) {
                     Some(e) => e,
                     None => {
                         err = decode::Error::Corrupt("delete bitmap length exceeds shared index length - more entries in bitmap than found in shared index").into();
@@ -164,17 +168,133 @@ impl Link {
                 return Err(err.into());
             }
 
-            shared_index
-                .entries
-                .retain(|e| !e.flags.contains(crate::entry::Flags::REMOVE));
-
-            let mut shared_entries = std::mem::take(&mut shared_index.entries);
-            shared_entries.sort_by(|a, b| a.cmp(b, &shared_index.state));
+            shared_entries.retain(|e| !e.flags.contains(crate::entry::Flags::REMOVE));
+            shared_entries.sort_by(|a, b| {
+                Entry::cmp_filepaths(a.path_in(&shared_path_backing), b.path_in(&shared_path_backing))
+                    .then_with(|| a.stage().cmp(&b.stage()))
+            });
 
             split_index.entries = shared_entries;
-            split_index.path_backing = std::mem::take(&mut shared_index.path_backing);
+            split_index.path_backing = shared_path_backing;
         }
+        split_index.shared_index = Some(Box::new(shared_index));
 
         Ok(())
     }
 }
+
+impl Link {
+    /// Serialize this instance to `out`.
+    pub fn write_to(&self, mut out: impl std::io::Write) -> Result<(), std::io::Error> {
+        let mut buf = Vec::with_capacity(self.shared_index_checksum.as_slice().len() + 64);
+        buf.extend_from_slice(self.shared_index_checksum.as_slice());
+        if let Some(bitmaps) = &self.bitmaps {
+            bitmaps.delete.write_to(&mut buf)?;
+            bitmaps.replace.write_to(&mut buf)?;
+        }
+
+        out.write_all(&SIGNATURE)?;
+        out.write_all(&(u32::try_from(buf.len()).expect("less than 4GB link extension")).to_be_bytes())?;
+        out.write_all(&buf)?;
+        Ok(())
+    }
+}
+
+/// Produce a state that only contains the changes of `state` compared to the `shared` index stored as `sharedindex.<shared_index_checksum>`,
+/// along with a link extension that points to it.
+///
+/// Entries of `shared` that changed in `state` in any way, including their stat information only, are stored as replacements with
+/// empty paths, entries of `shared` that don't exist in `state` anymore are marked for deletion, and all entries of `state` that
+/// are not in `shared` are added with their paths.
+pub(crate) fn split(state: &State, shared: &State, shared_index_checksum: gix_hash::ObjectId) -> State {
+    let mut replacements = Vec::new();
+    let mut additions = Vec::new();
+    let mut replace = Vec::new();
+    let mut delete = Vec::new();
+
+    let mut entries = state
+        .entries()
+        .iter()
+        .filter(|e| !e.flags.contains(entry::Flags::REMOVE))
+        .peekable();
+    for (shared_index, shared_entry) in shared.entries().iter().enumerate() {
+        let shared_path = shared_entry.path(shared);
+        loop {
+            let Some(entry) = entries.peek() else {
+                delete.push(shared_index);
+                break;
+            };
+            match Entry::cmp_filepaths(entry.path(state), shared_path)
+                .then_with(|| entry.stage().cmp(&shared_entry.stage()))
+            {
+                std::cmp::Ordering::Less => {
+                    additions.push(*entry);
+                    entries.next();
+                }
+                std::cmp::Ordering::Equal => {
+                    if !is_same_at_rest(entry, shared_entry) {
+                        replace.push(shared_index);
+                        replacements.push(*entry);
+                    }
+                    entries.next();
+                    break;
+                }
+                std::cmp::Ordering::Greater => {
+                    delete.push(shared_index);
+                    break;
+                }
+            }
+        }
+    }
+    additions.extend(entries);
+
+    let mut path_backing = Vec::new();
+    let entries = replacements
+        .into_iter()
+        .map(|e| Entry {
+            path: 0..0,
+            ..e.clone()
+        })
+        .chain(additions.into_iter().map(|e| {
+            let path = e.path(state);
+            let start = path_backing.len();
+            path_backing.extend_from_slice(path);
+            Entry {
+                path: start..path_backing.len(),
+                ..e.clone()
+            }
+        }))
+        .collect();
+
+    State {
+        object_hash: state.object_hash,
+        timestamp: state.timestamp,
+        version: state.version,
+        entries,
+        path_backing,
+        is_sparse: state.is_sparse,
+        end_of_index_at_decode_time: false,
+        offset_table_at_decode_time: false,
+        tree: state.tree.clone(),
+        link: Some(Link {
+            shared_index_checksum,
+            bitmaps: Some(Bitmaps {
+                delete: gix_bitmap::ewah::Vec::from_set_bits(delete),
+                replace: gix_bitmap::ewah::Vec::from_set_bits(replace),
+            }),
+        }),
+        resolve_undo: state.resolve_undo.clone(),
+        untracked: state.untracked.clone(),
+        fs_monitor: None,
+    }
+}
+
+/// Return `true` if `a` and `b` would be serialized the same way, ignoring their paths.
+fn is_same_at_rest(a: &Entry, b: &Entry) -> bool {
+    let at_rest = entry::Flags::STAGE_MASK
+        | entry::Flags::EXTENDED
+        | entry::Flags::ASSUME_VALID
+        | entry::Flags::INTENT_TO_ADD
+        | entry::Flags::SKIP_WORKTREE;
+    a.stat == b.stat && a.id == b.id && a.mode == b.mode && (a.flags & at_rest) == (b.flags & at_rest)
+}
@@ -96,7 +96,12 @@ impl File {
         };
 
         let (state, checksum) = State::from_bytes(&data, mtime, object_hash, options)?;
-        let mut file = File { state, path, checksum };
+        let mut file = File {
+            state,
+            path,
+            checksum,
+            shared_index: None,
+        };
         if let Some(mut link) = file.link.take() {
             link.dissolve_into(&mut file, object_hash, skip_hash, options)?;
         }
@@ -112,6 +117,7 @@ impl File {
             state,
             path: path.into(),
             checksum: None,
+            shared_index: None,
         }
     }
 }
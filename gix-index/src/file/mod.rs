@@ -63,6 +63,13 @@ mod access {
         pub fn checksum(&self) -> Option<gix_hash::ObjectId> {
             self.checksum
         }
+
+        /// Return the shared index if this index was read from a split index, as configured with `core.splitIndex`.
+        ///
+        /// Its entries are already merged into ours, but it's kept to be able to write the index back in split form.
+        pub fn shared_index(&self) -> Option<&File> {
+            self.shared_index.as_deref()
+        }
     }
 }
 
@@ -1,6 +1,6 @@
 use gix_features::hash;
 
-use crate::{write, File, Version};
+use crate::{extension, write, File, Version};
 
 /// The error produced by [`File::write()`].
 #[derive(Debug, thiserror::Error)]
@@ -17,21 +17,39 @@ pub enum Error {
 impl File {
     /// Write the index to `out` with `options`, to be readable by [`File::at()`], returning the version that was actually written
     /// to retain all information of this index.
+    ///
+    /// If this index was read from a split index and [`options.split_index`](write::Options::split_index) is
+    /// [`Preserve`](write::SplitIndex::Preserve), only the changes compared to the [shared index](File::shared_index())
+    /// will be written.
     pub fn write_to(
         &self,
         mut out: impl std::io::Write,
         options: write::Options,
     ) -> std::io::Result<(Version, gix_hash::ObjectId)> {
         let _span = gix_features::trace::detail!("gix_index::File::write_to()", skip_hash = options.skip_hash);
+        let split_state;
+        let state = match (options.split_index, self.shared_index.as_deref()) {
+            (write::SplitIndex::Preserve, Some(shared)) => {
+                split_state = extension::link::split(
+                    &self.state,
+                    &shared.state,
+                    shared
+                        .checksum
+                        .expect("shared index checksum is always set when reading it"),
+                );
+                &split_state
+            }
+            _ => &self.state,
+        };
         let (version, hash) = if options.skip_hash {
             let out: &mut dyn std::io::Write = &mut out;
-            let version = self.state.write_to(out, options)?;
+            let version = state.write_to(out, options)?;
             (version, self.state.object_hash.null())
         } else {
             let mut hasher = hash::Write::new(&mut out, self.state.object_hash);
             let out: &mut dyn std::io::Write = &mut hasher;
-            let version = self.state.write_to(out, options)?;
-            (version, gix_hash::ObjectId::from(hasher.hash.digest()))
+            let version = state.write_to(out, options)?;
+            (version, hasher.hash.digest())
         };
         out.write_all(hash.as_slice())?;
         Ok((version, hash))
@@ -40,6 +58,7 @@ impl File {
     /// Write ourselves to the path we were read from after acquiring a lock, using `options`.
     ///
     /// Note that the hash produced will be stored which is why we need to be mutable.
+    /// If a split index is squashed, the link to the shared index is removed as well.
     pub fn write(&mut self, options: write::Options) -> Result<(), Error> {
         let _span = gix_features::trace::detail!("gix_index::File::write()", path = ?self.path);
         let mut lock = std::io::BufWriter::with_capacity(
@@ -53,6 +72,9 @@ impl File {
         };
         self.state.version = version;
         self.checksum = Some(digest);
+        if options.split_index == write::SplitIndex::Squash {
+            self.shared_index = None;
+        }
         Ok(())
     }
 }
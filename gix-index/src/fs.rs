@@ -166,6 +166,20 @@ impl Metadata {
         gix_fs::is_executable(&self.0)
     }
 
+    /// Return the permission bits of the file, like `0o644`, which on windows only indicate if it's executable.
+    pub fn permissions(&self) -> u32 {
+        #[cfg(not(windows))]
+        {
+            self.0.st_mode as u32 & 0o7777
+        }
+        #[cfg(windows)]
+        if self.is_executable() {
+            0o755
+        } else {
+            0o644
+        }
+    }
+
     /// Return `true` if the file's is a symbolic link.
     pub fn is_symlink(&self) -> bool {
         #[cfg(not(windows))]
@@ -44,6 +44,10 @@ pub mod write;
 
 pub mod fs;
 
+///
+#[allow(clippy::empty_docs)]
+pub mod snapshot;
+
 /// All known versions of a git index file.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -81,6 +85,24 @@ pub struct File {
     pub(crate) path: PathBuf,
     /// The checksum of all bytes prior to the checksum itself.
     pub(crate) checksum: Option<gix_hash::ObjectId>,
+    /// The shared index as it was read from disk, if this index was read from a split index.
+    ///
+    /// It's kept to be able to write the index back in split form.
+    pub(crate) shared_index: Option<Box<File>>,
+}
+
+/// An immutable view of an index [`File`] that can be shared cheaply across threads, along with the information needed
+/// to learn if the file on disk changed since it was read.
+///
+/// All entries, their paths and the extensions are kept in a few contiguous allocations that are never mutated, so any
+/// amount of threads can query a snapshot concurrently without locking. Changes are made to a [copy](Snapshot::into_file())
+/// of the underlying file, which can be turned into a new snapshot once done.
+#[derive(Clone)]
+pub struct Snapshot {
+    /// The index as it was read from disk.
+    file: std::sync::Arc<File>,
+    /// Information about the file on disk right before it was read, or `None` if it wasn't read from disk.
+    stat: Option<snapshot::Stat>,
 }
 
 /// The type to use and store paths to all entries.
@@ -244,7 +266,7 @@ pub(crate) mod util {
 
 #[test]
 fn size_of_entry() {
-    assert_eq!(std::mem::size_of::<crate::Entry>(), 80);
+    assert_eq!(std::mem::size_of::<crate::Entry>(), 96);
 
     // the reason we have our own time is half the size.
     assert_eq!(std::mem::size_of::<crate::entry::stat::Time>(), 8);
@@ -0,0 +1,139 @@
+use std::{
+    io::{Read, Seek, SeekFrom},
+    ops::Deref,
+    path::PathBuf,
+    sync::Arc,
+};
+
+use bstr::BStr;
+
+use crate::{decode, file, Entry, File, Snapshot};
+
+/// Information about an index file on disk, sufficient to detect if it was changed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) struct Stat {
+    modified: Option<std::time::SystemTime>,
+    len: u64,
+    /// As indices are always written to a lock file which then replaces the original, a new inode indicates
+    /// a change even if the modification time and size are the same.
+    #[cfg(unix)]
+    inode: u64,
+}
+
+impl Stat {
+    fn from_metadata(meta: &std::fs::Metadata) -> Self {
+        Stat {
+            modified: meta.modified().ok(),
+            len: meta.len(),
+            #[cfg(unix)]
+            inode: std::os::unix::fs::MetadataExt::ino(meta),
+        }
+    }
+}
+
+/// Initialization
+impl Snapshot {
+    /// Read the index file at `path` like [`File::at()`] does, and remember enough about the file on disk to be able
+    /// to tell if [it changed](Snapshot::is_outdated()) later.
+    pub fn at(
+        path: impl Into<PathBuf>,
+        object_hash: gix_hash::Kind,
+        skip_hash: bool,
+        options: decode::Options,
+    ) -> Result<Self, file::init::Error> {
+        let path = path.into();
+        // Obtain the metadata before reading so a change that happens in the meantime is detected later.
+        let stat = Stat::from_metadata(&std::fs::metadata(&path)?);
+        let file = File::at(path, object_hash, skip_hash, options)?;
+        Ok(Snapshot {
+            file: Arc::new(file),
+            stat: Some(stat),
+        })
+    }
+
+    /// Read the index from disk again if [it changed](Snapshot::is_outdated()), and return the new snapshot,
+    /// or `None` if this one is still current.
+    pub fn reload_if_outdated(
+        &self,
+        skip_hash: bool,
+        options: decode::Options,
+    ) -> Result<Option<Self>, file::init::Error> {
+        if !self.is_outdated()? {
+            return Ok(None);
+        }
+        Self::at(self.file.path(), self.file.object_hash(), skip_hash, options).map(Some)
+    }
+}
+
+impl From<File> for Snapshot {
+    /// Note that snapshots created from a file that was never read or written can't tell if their file on disk changed,
+    /// and thus are always [outdated](Snapshot::is_outdated()).
+    fn from(file: File) -> Self {
+        Snapshot {
+            file: Arc::new(file),
+            stat: None,
+        }
+    }
+}
+
+/// Access
+impl Snapshot {
+    /// Return the entry at `path` at stage 0, or at stage 2 in case of a merge conflict, just like
+    /// [`State::entry_by_path()`](crate::State::entry_by_path()).
+    ///
+    /// All other queries, like [`prefixed_entries()`](crate::State::prefixed_entries()), are available through
+    /// the underlying [`File`] as well.
+    pub fn find(&self, path: &BStr) -> Option<&Entry> {
+        self.file.entry_by_path(path)
+    }
+
+    /// Return `true` if the index file on disk isn't the one this snapshot was read from anymore, or was removed.
+    ///
+    /// The metadata of the file is compared first, and if it changed, the checksum at the end of the file is compared to ours
+    /// so that merely touching the file doesn't cause it to be considered outdated. If either of these is unknown,
+    /// the file is considered outdated to be on the safe side.
+    pub fn is_outdated(&self) -> std::io::Result<bool> {
+        let mut file = match std::fs::File::open(self.file.path()) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(true),
+            Err(err) => return Err(err),
+        };
+        let meta = file.metadata()?;
+        if self.stat == Some(Stat::from_metadata(&meta)) {
+            return Ok(false);
+        }
+        let Some(checksum) = self.file.checksum() else {
+            return Ok(true);
+        };
+        let hash_len = checksum.kind().len_in_bytes();
+        if meta.len() < hash_len as u64 {
+            return Ok(true);
+        }
+        let mut buf = [0; gix_hash::Kind::longest().len_in_bytes()];
+        file.seek(SeekFrom::End(-(hash_len as i64)))?;
+        file.read_exact(&mut buf[..hash_len])?;
+        Ok(checksum.as_slice() != &buf[..hash_len])
+    }
+
+    /// Return the underlying file for modification, which is only copied if this snapshot is shared with other
+    /// instances. Use [`Snapshot::from()`] to turn it into a new snapshot once done.
+    pub fn into_file(self) -> File {
+        Arc::try_unwrap(self.file).unwrap_or_else(|file| (*file).clone())
+    }
+}
+
+impl Deref for Snapshot {
+    type Target = File;
+
+    fn deref(&self) -> &Self::Target {
+        &self.file
+    }
+}
+
+impl std::fmt::Debug for Snapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Snapshot")
+            .field("file", &self.file)
+            .finish_non_exhaustive()
+    }
+}
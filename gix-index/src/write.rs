@@ -43,19 +43,50 @@ impl Extensions {
     }
 }
 
+/// Determine how to write an index that was read from a split index, i.e. one that links to a shared index.
+///
+/// This is only respected when writing via [`File::write()`](crate::File::write()) and
+/// [`File::write_to()`](crate::File::write_to()), as only [files](crate::File) know about the shared index they were read from.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SplitIndex {
+    /// Write all entries into a single index, permanently merging the shared index into it.
+    ///
+    /// This is what git does if `core.splitIndex` is `false`.
+    #[default]
+    Squash,
+    /// Write only the entries that differ from the shared index the index was read from, along with a `link` extension that
+    /// refers to the unchanged shared index.
+    ///
+    /// This is what git does if `core.splitIndex` is `true`.
+    /// If the index wasn't read from a split index, this is the same as [`Squash`](SplitIndex::Squash).
+    Preserve,
+}
+
 /// The options for use when [writing an index][State::write_to()].
 ///
-/// Note that default options write either index V2 or V3 depending on the content of the entries.
+/// Note that default options write the version the index was read with, but use either index V2 or V3
+/// depending on the content of the entries if it isn't V4.
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Options {
     /// Configures which extensions to write.
     pub extensions: Extensions,
+    /// The version to write, or `None` to write the [version of the index](State::version()) itself.
+    ///
+    /// Just like in `git`, V2 and V3 are adjusted to the version that suffices to store the entries, so V2 is upgraded to V3
+    /// if there are entries with extended flags, and V3 is downgraded to V2 if there are none.
+    /// V4 is always written as is.
+    ///
+    /// This value is typically controlled by `index.version` or the `GIT_INDEX_VERSION` environment variable
+    /// for newly created indices.
+    pub version: Option<Version>,
     /// Set the trailing hash of the produced index to all zeroes to save some time.
     ///
     /// This value is typically controlled by `index.skipHash` and is respected when the index is written
     /// via [`File::write()`](crate::File::write()) and [`File::write_to()`](crate::File::write_to()).
     /// Note that
     pub skip_hash: bool,
+    /// Configure how to write indices that were read from a split index.
+    pub split_index: SplitIndex,
 }
 
 impl State {
@@ -65,11 +96,13 @@ impl State {
         out: impl std::io::Write,
         Options {
             extensions,
+            version,
             skip_hash: _,
+            split_index: _,
         }: Options,
     ) -> std::io::Result<Version> {
         let _span = gix_features::trace::detail!("gix_index::State::write()");
-        let version = self.detect_required_version();
+        let version = self.version_to_write(version.unwrap_or(self.version));
 
         let mut write = CountBytes::new(out);
         let num_entries: u32 = self
@@ -86,7 +119,7 @@ impl State {
             .expect("definitely not too many entries");
 
         let offset_to_entries = header(&mut write, version, num_entries - removed_entries)?;
-        let offset_to_extensions = entries(&mut write, self, offset_to_entries)?;
+        let offset_to_extensions = entries(&mut write, self, version, offset_to_entries)?;
         let (extension_toc, out) = self.write_extensions(write, offset_to_extensions, extensions)?;
 
         if num_entries > 0
@@ -112,6 +145,10 @@ impl State {
     {
         type WriteExtFn<'a> = &'a dyn Fn(&mut dyn std::io::Write) -> Option<std::io::Result<extension::Signature>>;
         let extensions: &[WriteExtFn<'_>] = &[
+            &|write| {
+                self.link()
+                    .map(|link| link.write_to(write).map(|_| extension::link::SIGNATURE))
+            },
             &|write| {
                 extensions
                     .should_write(extension::tree::SIGNATURE)
@@ -138,11 +175,15 @@ impl State {
 }
 
 impl State {
-    fn detect_required_version(&self) -> Version {
-        self.entries
-            .iter()
-            .find_map(|e| e.flags.contains(entry::Flags::EXTENDED).then_some(Version::V3))
-            .unwrap_or(Version::V2)
+    fn version_to_write(&self, desired: Version) -> Version {
+        match desired {
+            Version::V4 => Version::V4,
+            Version::V2 | Version::V3 => self
+                .entries
+                .iter()
+                .find_map(|e| e.flags.contains(entry::Flags::EXTENDED).then_some(Version::V3))
+                .unwrap_or(Version::V2),
+        }
     }
 }
 
@@ -164,19 +205,33 @@ fn header<T: std::io::Write>(
     Ok(out.count)
 }
 
-fn entries<T: std::io::Write>(out: &mut CountBytes<T>, state: &State, header_size: u32) -> Result<u32, std::io::Error> {
+fn entries<T: std::io::Write>(
+    out: &mut CountBytes<T>,
+    state: &State,
+    version: Version,
+    header_size: u32,
+) -> Result<u32, std::io::Error> {
+    let mut previous_path = Default::default();
     for entry in state.entries() {
         if entry.flags.contains(entry::Flags::REMOVE) {
             continue;
         }
-        entry.write_to(&mut *out, state)?;
-        match (out.count - header_size) % 8 {
-            0 => {}
-            n => {
-                let eight_null_bytes = [0u8; 8];
-                out.write_all(&eight_null_bytes[n as usize..])?;
+        match version {
+            Version::V2 | Version::V3 => {
+                entry.write_to(&mut *out, state)?;
+                match (out.count - header_size) % 8 {
+                    0 => {}
+                    n => {
+                        let eight_null_bytes = [0u8; 8];
+                        out.write_all(&eight_null_bytes[n as usize..])?;
+                    }
+                };
             }
-        };
+            Version::V4 => {
+                entry.write_to_with_path_compression(&mut *out, state, previous_path)?;
+                previous_path = entry.path(state);
+            }
+        }
     }
 
     Ok(out.count)
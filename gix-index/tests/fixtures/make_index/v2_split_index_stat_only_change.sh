@@ -0,0 +1,19 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+export GIT_INDEX_VERSION=2
+git init -q
+git config index.threads 1
+git config splitIndex.maxPercentChange 100
+
+echo a > a
+echo b > b
+echo c > c
+git add .
+git commit -q -m "initial"
+
+git update-index --split-index
+
+# only the stat information of 'b' changes, which forces it into the split index as replacement
+touch -t 202001010000 b
+git update-index b
@@ -1,6 +1,6 @@
 use crate::index::Fixture;
 use bstr::{BString, ByteSlice};
-use gix_index::entry::Stage;
+use gix_index::entry::{Flags, Mode, Stage};
 
 fn icase_fixture() -> gix_index::File {
     Fixture::Generated("v2_icase_name_clashes").open()
@@ -269,6 +269,70 @@ fn sort_entries() {
     check_prefix(&file, "a", &["a", "an initially incorrectly ordered entry"]);
 }
 
+#[test]
+fn upsert_entry() {
+    let mut file = Fixture::Generated("v4_more_files_IEOT").open();
+    let num_entries = file.entries().len();
+    let entry = file.entry(0).clone();
+
+    let ita = Flags::INTENT_TO_ADD | Flags::EXTENDED;
+    let new_entry_path = "d/b-new".into();
+    let idx = file.upsert_entry(entry.stat, entry.id, ita, Mode::FILE, new_entry_path);
+    assert_eq!(file.entries().len(), num_entries + 1);
+    assert!(file.verify_entries().is_ok(), "entries are inserted at their sorted position");
+    assert_eq!(file.entry(idx).path(&file), new_entry_path);
+    assert!(file.tree().is_none(), "the tree extension is out of date and removed");
+
+    let id = gix_hash::ObjectId::empty_blob(gix_hash::Kind::Sha1);
+    let same_idx = file.upsert_entry(entry.stat, id, Flags::empty(), Mode::FILE_EXECUTABLE, new_entry_path);
+    assert_eq!(same_idx, idx, "existing entries are updated in place");
+    assert_eq!(file.entries().len(), num_entries + 1);
+    let updated = file.entry(idx);
+    assert_eq!(updated.id, id);
+    assert_eq!(updated.mode, Mode::FILE_EXECUTABLE);
+    assert_eq!(updated.flags, Flags::empty(), "the intent-to-add flag collapses");
+
+    let mut file = Fixture::Loose("conflicting-file").open();
+    let idx = file.upsert_entry(entry.stat, id, Flags::STAGE_MASK, Mode::FILE, "file".into());
+    assert_eq!(file.entries().len(), 1, "all stages collapse into one");
+    assert_eq!(file.entry(idx).stage(), Stage::Unconflicted, "stage bits of flags are ignored");
+    assert_eq!(file.entry_by_path("file".into()).map(|e| e.id), Some(id));
+}
+
+#[test]
+fn upsert_entry_replaces_directory_file_conflicts() {
+    let mut file = Fixture::Generated("v4_more_files_IEOT").open();
+    let num_entries = file.entries().len();
+    let entry = file.entry(0).clone();
+
+    file.upsert_entry(entry.stat, entry.id, Flags::empty(), Mode::FILE, "d/last".into());
+    assert_eq!(
+        file.entries().len(),
+        num_entries - 3 + 1,
+        "a file replaces all entries in the directory of the same name"
+    );
+    assert!(file.prefixed_entries("d/last/".into()).is_none());
+    assert!(file.entry_by_path("d/last".into()).is_some());
+    assert!(file.verify_entries().is_ok());
+
+    file.upsert_entry(entry.stat, entry.id, Flags::empty(), Mode::FILE, "a/b".into());
+    assert_eq!(
+        file.entries().len(),
+        num_entries - 3 + 1,
+        "a file in a directory replaces the file that was in its place"
+    );
+    assert!(file.entry_by_path("a".into()).is_none());
+    assert!(file.entry_by_path("a/b".into()).is_some());
+
+    file.upsert_entry(entry.stat, entry.id, Flags::empty(), Mode::FILE, "d/last/x/y".into());
+    assert!(
+        file.entry_by_path("d/last".into()).is_none(),
+        "files at any leading directory are replaced"
+    );
+    assert_eq!(file.entries().len(), num_entries - 3 + 1);
+    assert!(file.verify_entries().is_ok());
+}
+
 #[test]
 fn prefixed_entries() {
     let mut file = Fixture::Generated("v4_more_files_IEOT").open();
@@ -12,3 +12,59 @@ fn apply() {
         Mode::SYMLINK
     );
 }
+
+#[test]
+fn from_fs() -> std::io::Result<()> {
+    let tmp = gix_testtools::tempfile::tempdir()?;
+    let file = tmp.path().join("file");
+    std::fs::write(&file, b"content")?;
+    let stat = gix_index::fs::Metadata::from_path_no_follow(&file)?;
+
+    assert_eq!(Mode::from_fs(&stat, true, true, None), Some(Mode::FILE));
+    assert_eq!(
+        Mode::from_fs(&stat, false, true, Some(Mode::SYMLINK)),
+        Some(Mode::SYMLINK),
+        "without symlink support, tracked symlinks stay symlinks"
+    );
+    assert_eq!(
+        Mode::from_fs(&stat, true, false, Some(Mode::FILE_EXECUTABLE)),
+        Some(Mode::FILE_EXECUTABLE),
+        "without support for the executable bit, the previous mode is retained"
+    );
+    assert_eq!(
+        Mode::from_fs(&stat, true, true, Some(Mode::FILE_EXECUTABLE)),
+        Some(Mode::FILE),
+        "otherwise the filesystem is authoritative"
+    );
+
+    let stat = gix_index::fs::Metadata::from_path_no_follow(tmp.path())?;
+    assert_eq!(
+        Mode::from_fs(&stat, true, true, None),
+        Some(Mode::COMMIT),
+        "directories are assumed to be submodules"
+    );
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o755))?;
+        let stat = gix_index::fs::Metadata::from_path_no_follow(&file)?;
+        assert_eq!(Mode::from_fs(&stat, true, true, None), Some(Mode::FILE_EXECUTABLE));
+        assert_eq!(
+            Mode::from_fs(&stat, true, false, None),
+            Some(Mode::FILE),
+            "new files are never executable if the executable bit isn't trusted"
+        );
+        assert_eq!(
+            Mode::from_fs(&stat, true, false, Some(Mode::FILE)),
+            Some(Mode::FILE),
+            "and the previous mode is kept"
+        );
+
+        let link = tmp.path().join("link");
+        std::os::unix::fs::symlink("file", &link)?;
+        let stat = gix_index::fs::Metadata::from_path_no_follow(&link)?;
+        assert_eq!(Mode::from_fs(&stat, true, true, None), Some(Mode::SYMLINK));
+    }
+    Ok(())
+}
@@ -237,6 +237,148 @@ mod matches {
             "size is different => mismatch (check_stat=false)"
         );
     }
+
+    #[test]
+    fn check_stat_minimal_ignores_ctime_even_if_trusted() {
+        let stat1 = Stat {
+            mtime: Time { secs: 1, nsecs: 2 },
+            ctime: Time { secs: 1, nsecs: 2 },
+            ..Default::default()
+        };
+        let mut stat2 = stat1;
+        stat2.ctime = Time { secs: 3, nsecs: 4 };
+        assert!(
+            stat1.matches(
+                &stat2,
+                Options {
+                    trust_ctime: true,
+                    check_stat: false,
+                    use_nsec: true,
+                    ..Default::default()
+                },
+            ),
+            "like git, ctime is only compared if check_stat=true"
+        );
+    }
+
+    #[test]
+    fn each_option_combination() {
+        let base = Stat {
+            mtime: Time { secs: 10, nsecs: 20 },
+            ctime: Time { secs: 30, nsecs: 40 },
+            dev: 1,
+            ino: 2,
+            uid: 3,
+            gid: 4,
+            size: 5,
+        };
+        for (name, change, is_compared) in [
+            (
+                "mtime secs",
+                (|s: &mut Stat| s.mtime.secs += 1) as fn(&mut Stat),
+                (|_: Options| true) as fn(Options) -> bool,
+            ),
+            ("mtime nsecs", |s| s.mtime.nsecs += 1, |o| o.check_stat && o.use_nsec),
+            ("ctime secs", |s| s.ctime.secs += 1, |o| o.check_stat && o.trust_ctime),
+            (
+                "ctime nsecs",
+                |s| s.ctime.nsecs += 1,
+                |o| o.check_stat && o.trust_ctime && o.use_nsec,
+            ),
+            ("dev", |s| s.dev += 1, |o| o.check_stat && o.use_stdev),
+            ("ino", |s| s.ino += 1, |o| o.check_stat),
+            ("uid", |s| s.uid += 1, |o| o.check_stat),
+            ("gid", |s| s.gid += 1, |o| o.check_stat),
+            ("size", |s| s.size += 1, |_| true),
+        ] {
+            let mut changed = base;
+            change(&mut changed);
+            for bits in 0..16u8 {
+                let opts = Options {
+                    trust_ctime: bits & 1 != 0,
+                    check_stat: bits & 2 != 0,
+                    use_nsec: bits & 4 != 0,
+                    use_stdev: bits & 8 != 0,
+                };
+                assert!(base.matches(&base, opts), "identical stats always match with {opts:?}");
+                assert_eq!(
+                    base.matches(&changed, opts),
+                    !is_compared(opts),
+                    "a change in {name} is noticed only if the field is compared with {opts:?}"
+                );
+            }
+        }
+    }
+}
+
+mod for_index {
+    use gix_index::entry::{
+        stat::{Options, Time},
+        Flags, Mode, Stat,
+    };
+
+    fn index_with_stats(stats: impl IntoIterator<Item = Stat>) -> gix_index::State {
+        let mut index = gix_index::State::new(gix_hash::Kind::Sha1);
+        for (idx, stat) in stats.into_iter().enumerate() {
+            index.dangerously_push_entry(
+                stat,
+                gix_hash::Kind::Sha1.null(),
+                Flags::empty(),
+                Mode::FILE,
+                format!("file{idx}").as_str().into(),
+            );
+        }
+        index
+    }
+
+    #[test]
+    fn nsecs_are_not_used_if_no_entry_has_them() {
+        let opts = Options {
+            use_nsec: true,
+            ..Default::default()
+        };
+        let seconds_only = Stat {
+            mtime: Time { secs: 1, nsecs: 0 },
+            ctime: Time { secs: 2, nsecs: 0 },
+            ..Default::default()
+        };
+        let index = index_with_stats([seconds_only, seconds_only]);
+        assert!(!index.has_nanosecond_timestamps());
+        assert!(
+            !opts.for_index(&index).use_nsec,
+            "an index written without nanoseconds would otherwise make every entry look modified"
+        );
+
+        let fresh = Stat {
+            mtime: Time { secs: 1, nsecs: 500 },
+            ctime: Time { secs: 2, nsecs: 600 },
+            ..Default::default()
+        };
+        assert!(seconds_only.matches(&fresh, opts.for_index(&index)));
+        assert!(!seconds_only.matches(&fresh, opts));
+    }
+
+    #[test]
+    fn nsecs_are_used_if_one_entry_has_them() {
+        let opts = Options {
+            use_nsec: true,
+            ..Default::default()
+        };
+        let index = index_with_stats([
+            Stat::default(),
+            Stat {
+                ctime: Time { secs: 2, nsecs: 1 },
+                ..Default::default()
+            },
+        ]);
+        assert!(index.has_nanosecond_timestamps());
+        assert_eq!(opts.for_index(&index), opts);
+        assert_eq!(
+            Options::default().for_index(&index_with_stats(None)),
+            Options::default(),
+            "nsecs are never turned on"
+        );
+    }
 }
 
 #[test]
@@ -307,3 +449,39 @@ fn is_racy() {
         "entry with mtime before (seconds) timestamp is not racy (use_nsec=true)"
     );
 }
+
+#[test]
+fn is_racy_with_coarse_timestamps_or_minimal_stat_checks() {
+    let nsec = Options {
+        use_nsec: true,
+        ..Default::default()
+    };
+    let coarse = Stat {
+        mtime: Time { secs: 1, nsecs: 0 },
+        ..Default::default()
+    };
+    assert!(
+        coarse.is_racy(FileTime::from_unix_time(1, 20), nsec),
+        "without nanoseconds in the entry, it might have been modified after the timestamp within the same second"
+    );
+    let fine = Stat {
+        mtime: Time { secs: 1, nsecs: 10 },
+        ..Default::default()
+    };
+    assert!(
+        fine.is_racy(FileTime::from_unix_time(1, 0), nsec),
+        "without nanoseconds in the timestamp, the entry might have been modified after it within the same second"
+    );
+    let minimal = Options {
+        check_stat: false,
+        ..nsec
+    };
+    assert!(
+        fine.is_racy(FileTime::from_unix_time(1, 20), minimal),
+        "nanoseconds aren't used with check_stat=false, so the same second is racy"
+    );
+    assert!(
+        !fine.is_racy(FileTime::from_unix_time(2, 0), minimal),
+        "an entry modified in an earlier second is never racy"
+    );
+}
@@ -351,7 +351,11 @@ fn split_index_and_regular_index_of_same_content_are_indeed_the_same() {
 
     assert!(
         split.link().is_none(),
-        "link extension is dissolved, merging the shared index permanently into the split one (for now)"
+        "link extension is dissolved, merging the shared index into the split one"
+    );
+    assert!(
+        split.shared_index().is_some(),
+        "the shared index is kept to be able to write the split index back"
     );
 
     let regular = verify(
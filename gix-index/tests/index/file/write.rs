@@ -1,3 +1,6 @@
+use std::path::Path;
+
+use bstr::BString;
 use filetime::FileTime;
 use gix_index::{entry, extension, write, write::Options, State, Version};
 
@@ -20,6 +23,7 @@ fn roundtrips() -> crate::Result {
         (Generated("V2_empty"), only_tree_ext()),
         (Generated("v2_more_files"), only_tree_ext()),
         (Generated("v2_all_file_kinds"), only_tree_ext()),
+        (Generated("v4_more_files"), only_tree_ext()),
     ];
 
     for (fixture, options) in input {
@@ -47,7 +51,9 @@ fn skip_hash() -> crate::Result {
     expected.set_path(&path);
     expected.write(Options {
         extensions: Default::default(),
+        version: None,
         skip_hash: false,
+        split_index: Default::default(),
     })?;
 
     let actual = gix_index::File::at(
@@ -64,7 +70,9 @@ fn skip_hash() -> crate::Result {
 
     expected.write(Options {
         extensions: Default::default(),
+        version: None,
         skip_hash: true,
+        split_index: Default::default(),
     })?;
 
     let actual = gix_index::File::at(
@@ -133,9 +141,8 @@ fn state_comparisons_with_various_extension_configurations() {
         Generated("v2_more_files"),
         Generated("v2_all_file_kinds"),
         Generated("v2_split_index"),
-        // TODO: this fails because git allows to configure the index version while gitoxide doesn't
-        //       the fixture artificially sets the version to V4 and gitoxide writes it back out as the lowest required version, V2
-        // Generated("v4_more_files_IEOT"),
+        Generated("v4_more_files_IEOT"),
+        Generated("v4_more_files"),
         Generated("v3_skip_worktree"),
         Generated("v3_added_files"),
         Generated("v3_sparse_index_non_cone"),
@@ -181,6 +188,108 @@ fn extended_flags_automatically_upgrade_the_version_to_avoid_data_loss() -> crat
     Ok(())
 }
 
+#[test]
+fn version_is_adjusted_like_git_does() -> crate::Result {
+    let mut index = Generated("v2").open();
+    for (desired, expected) in [
+        (None, Version::V2),
+        (Some(Version::V2), Version::V2),
+        (Some(Version::V3), Version::V2),
+        (Some(Version::V4), Version::V4),
+    ] {
+        let mut buf = Vec::new();
+        let (actual, _digest) = index.write_to(&mut buf, version_options(desired))?;
+        assert_eq!(actual, expected, "V3 is only used with extended flags, {desired:?}");
+    }
+
+    index.entries_mut()[0].flags.insert(entry::Flags::EXTENDED);
+    for (desired, expected) in [
+        (None, Version::V3),
+        (Some(Version::V2), Version::V3),
+        (Some(Version::V3), Version::V3),
+        (Some(Version::V4), Version::V4),
+    ] {
+        let mut buf = Vec::new();
+        let (actual, _digest) = index.write_to(&mut buf, version_options(desired))?;
+        assert_eq!(actual, expected, "V2 can't store extended flags, {desired:?}");
+    }
+
+    let index = Generated("v4_more_files").open();
+    assert_eq!(index.version(), Version::V4);
+    let mut buf = Vec::new();
+    let (actual, _digest) = index.write_to(&mut buf, Default::default())?;
+    assert_eq!(actual, Version::V4, "the version of the index is kept by default");
+    Ok(())
+}
+
+#[test]
+fn v4_roundtrips_to_identical_entries() -> crate::Result {
+    for fixture in [
+        Loose("extended-flags"),
+        Loose("conflicting-file"),
+        Loose("very-long-path"),
+        Generated("v2"),
+        Generated("v2_more_files"),
+        Generated("v2_all_file_kinds"),
+        Generated("v2_deeper_tree"),
+        Generated("v3_added_files"),
+        Generated("v3_skip_worktree"),
+        Generated("v3_sparse_index"),
+    ] {
+        let expected = fixture.open();
+        let name = fixture.to_name();
+        let mut out = Vec::new();
+        let (actual_version, _digest) = expected.write_to(&mut out, version_options(Some(Version::V4)))?;
+        assert_eq!(actual_version, Version::V4);
+
+        let (actual, _) = State::from_bytes(&out, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+        assert_eq!(actual.version(), Version::V4, "{name}");
+        compare_entries(&actual, &expected, name);
+
+        out.clear();
+        let (downgraded_version, _digest) =
+            gix_index::File::from_state(actual, "unused").write_to(&mut out, version_options(Some(Version::V2)))?;
+        assert_ne!(downgraded_version, Version::V4, "{name}: V4 can be downgraded as well");
+        let (actual, _) = State::from_bytes(&out, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+        compare_entries(&actual, &expected, name);
+    }
+    Ok(())
+}
+
+#[test]
+fn v4_written_by_us_can_be_read_by_git() -> crate::Result {
+    let dir = gix_testtools::scripted_fixture_writable_standalone("make_index/v2_more_files.sh")?;
+    let index_path = dir.path().join(".git").join("index");
+    let mut index = gix_index::File::at(&index_path, gix_hash::Kind::Sha1, false, Default::default())?;
+    assert_eq!(index.version(), Version::V2);
+    let expected_ls_files = git_ls_files(dir.path())?;
+
+    index.write(version_options(Some(Version::V4)))?;
+    assert_eq!(index.version(), Version::V4, "the written version is remembered");
+    assert_eq!(
+        &std::fs::read(&index_path)?[4..8],
+        &4_u32.to_be_bytes(),
+        "the file on disk is V4"
+    );
+    assert_eq!(
+        git_ls_files(dir.path())?,
+        expected_ls_files,
+        "git sees the same entries in our V4 index"
+    );
+
+    let out = std::process::Command::new("git")
+        .args(["status", "--porcelain", "--untracked-files=no"])
+        .current_dir(dir.path())
+        .output()?;
+    assert!(out.status.success());
+    assert_eq!(
+        out.stdout.as_slice(),
+        b"",
+        "stat information was stored correctly as well"
+    );
+    Ok(())
+}
+
 #[test]
 fn remove_flag_is_respected() -> crate::Result {
     let mut index = Generated("v4_more_files_IEOT").open();
@@ -210,6 +319,140 @@ fn remove_flag_is_respected() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn split_index_roundtrips_when_preserved() -> crate::Result {
+    for (script, index_path) in [
+        ("v2_split_vs_regular_index.sh", "split/.git/index"),
+        ("v2_split_index_stat_only_change.sh", ".git/index"),
+    ] {
+        let dir = gix_testtools::scripted_fixture_writable_standalone(&format!("make_index/{script}"))?;
+        let index_path = dir.path().join(index_path);
+        let worktree = index_path.parent().and_then(Path::parent).expect("index in .git dir");
+        let expected = gix_index::File::at(&index_path, gix_hash::Kind::Sha1, false, Default::default())?;
+        assert!(expected.shared_index().is_some(), "{script}: fixture has a split index");
+        let expected_ls_files = git_ls_files(worktree)?;
+
+        let mut index = expected.clone();
+        index.write(split_options(write::SplitIndex::Preserve))?;
+        assert!(
+            index.shared_index().is_some(),
+            "{script}: the link to the shared index is kept"
+        );
+
+        let actual = gix_index::File::at(&index_path, gix_hash::Kind::Sha1, false, Default::default())?;
+        assert_eq!(
+            actual.shared_index().and_then(gix_index::File::checksum),
+            expected.shared_index().and_then(gix_index::File::checksum),
+            "{script}: the same shared index is used"
+        );
+        compare_entries(&actual, &expected, script);
+        assert_eq!(
+            git_ls_files(worktree)?,
+            expected_ls_files,
+            "{script}: git sees the same entries in our split index"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn split_index_with_stat_only_change_is_written_as_replacement() -> crate::Result {
+    let dir = gix_testtools::scripted_fixture_writable_standalone("make_index/v2_split_index.sh")?;
+    let index_path = dir.path().join(".git").join("index");
+    let mut index = gix_index::File::at(&index_path, gix_hash::Kind::Sha1, false, Default::default())?;
+    assert_eq!(index.entries().len(), 1);
+    index.entries_mut()[0].stat.mtime.secs += 1;
+    let expected_stat = index.entries()[0].stat;
+
+    let mut out_bytes = Vec::new();
+    index.write_to(&mut out_bytes, split_options(write::SplitIndex::Preserve))?;
+    let (split, _) = State::from_bytes(&out_bytes, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+    assert_eq!(split.entries().len(), 1, "just the replacement is stored");
+    assert!(
+        split.entries()[0].path(&split).is_empty(),
+        "replacements don't store a path"
+    );
+    let bitmaps = split
+        .link()
+        .and_then(|link| link.bitmaps.as_ref())
+        .expect("bitmaps are always written");
+    assert_eq!(set_bits(&bitmaps.replace), [0]);
+    assert_eq!(set_bits(&bitmaps.delete), [] as [usize; 0]);
+
+    index.write(split_options(write::SplitIndex::Preserve))?;
+    let actual = gix_index::File::at(&index_path, gix_hash::Kind::Sha1, false, Default::default())?;
+    assert_eq!(actual.entries().len(), 1);
+    assert_eq!(actual.entries()[0].stat, expected_stat, "the new stat was applied");
+    assert_eq!(actual.entries()[0].path(&actual), "a");
+    Ok(())
+}
+
+#[test]
+fn split_index_is_squashed_by_default() -> crate::Result {
+    let dir = gix_testtools::scripted_fixture_writable_standalone("make_index/v2_split_vs_regular_index.sh")?;
+    let index_path = dir.path().join("split/.git/index");
+    let expected = gix_index::File::at(&index_path, gix_hash::Kind::Sha1, false, Default::default())?;
+
+    let mut index = expected.clone();
+    index.write(Default::default())?;
+    assert!(index.shared_index().is_none(), "the split index was merged");
+
+    let actual = gix_index::File::at(&index_path, gix_hash::Kind::Sha1, false, Default::default())?;
+    assert!(actual.shared_index().is_none());
+    assert!(actual.link().is_none());
+    compare_entries(&actual, &expected, "split");
+    Ok(())
+}
+
+fn split_options(split_index: write::SplitIndex) -> Options {
+    Options {
+        extensions: Default::default(),
+        version: None,
+        skip_hash: false,
+        split_index,
+    }
+}
+
+fn version_options(version: Option<Version>) -> Options {
+    Options {
+        version,
+        ..Default::default()
+    }
+}
+
+fn git_ls_files(worktree: &Path) -> crate::Result<BString> {
+    let out = std::process::Command::new("git")
+        .args(["ls-files", "--stage"])
+        .current_dir(worktree)
+        .output()?;
+    assert!(out.status.success(), "git can read the index");
+    Ok(out.stdout.into())
+}
+
+fn set_bits(bitmap: &gix_bitmap::ewah::Vec) -> Vec<usize> {
+    let mut out = Vec::new();
+    bitmap.for_each_set_bit(|idx| {
+        out.push(idx);
+        Some(())
+    });
+    out
+}
+
+fn compare_entries(actual: &State, expected: &State, fixture: &str) {
+    assert_eq!(
+        actual.entries().len(),
+        expected.entries().len(),
+        "{fixture}: entry count"
+    );
+    for (a, e) in actual.entries().iter().zip(expected.entries()) {
+        assert_eq!(a.path(actual), e.path(expected), "{fixture}: path");
+        assert_eq!(a.stat, e.stat, "{fixture}: stat");
+        assert_eq!(a.id, e.id, "{fixture}: id");
+        assert_eq!(a.flags, e.flags, "{fixture}: flags");
+        assert_eq!(a.mode, e.mode, "{fixture}: mode");
+    }
+}
+
 fn compare_states_against_baseline(
     actual: &State,
     actual_version: Version,
@@ -294,13 +537,17 @@ fn only_tree_ext() -> Options {
             end_of_index_entry: false,
             tree_cache: true,
         },
+        version: None,
         skip_hash: false,
+        split_index: Default::default(),
     }
 }
 
 fn options_with(extensions: write::Extensions) -> Options {
     Options {
         extensions,
+        version: None,
         skip_hash: false,
+        split_index: Default::default(),
     }
 }
@@ -0,0 +1,90 @@
+use bstr::ByteSlice;
+use gix_index::{File, Snapshot};
+
+fn snapshot_of_writable_fixture() -> crate::Result<(gix_testtools::tempfile::TempDir, Snapshot)> {
+    let dir = gix_testtools::scripted_fixture_writable_standalone("make_index/v2_more_files.sh")?;
+    let snapshot = Snapshot::at(
+        dir.path().join(".git").join("index"),
+        gix_hash::Kind::Sha1,
+        false,
+        Default::default(),
+    )?;
+    Ok((dir, snapshot))
+}
+
+#[test]
+fn is_send_and_sync() {
+    fn assert_send_and_sync<T: Send + Sync>() {}
+    assert_send_and_sync::<Snapshot>();
+}
+
+#[test]
+fn concurrent_queries_while_reloading() -> crate::Result {
+    let (_dir, snapshot) = snapshot_of_writable_fixture()?;
+    assert!(!snapshot.is_outdated()?, "nothing changed yet");
+    let paths: Vec<_> = snapshot
+        .entries()
+        .iter()
+        .map(|entry| entry.path(&snapshot).to_owned())
+        .collect();
+    assert_eq!(paths.len(), 6);
+
+    let reloaded = std::thread::scope(|scope| -> crate::Result<Snapshot> {
+        for _ in 0..8 {
+            let snapshot = snapshot.clone();
+            let paths = &paths;
+            scope.spawn(move || {
+                for _ in 0..1000 {
+                    for path in paths {
+                        let entry = snapshot.find(path.as_bstr()).expect("each path is present");
+                        assert_eq!(entry.path(&snapshot), path);
+                    }
+                    assert_eq!(
+                        snapshot.prefixed_entries("d/".into()).map(<[_]>::len),
+                        Some(3),
+                        "prefix queries work as well"
+                    );
+                }
+            });
+        }
+
+        let mut file = snapshot.clone().into_file();
+        file.remove_entries(|_, path, _| path == "a");
+        file.write(Default::default())?;
+        assert!(snapshot.is_outdated()?, "the file on disk was replaced");
+        Ok(snapshot
+            .reload_if_outdated(false, Default::default())?
+            .expect("outdated snapshots are reloaded"))
+    })?;
+
+    assert_eq!(reloaded.entries().len(), 5);
+    assert!(reloaded.find("a".into()).is_none());
+    assert!(
+        snapshot.find("a".into()).is_some(),
+        "the previous snapshot is unchanged"
+    );
+    assert!(!reloaded.is_outdated()?);
+    assert!(reloaded.reload_if_outdated(false, Default::default())?.is_none());
+    Ok(())
+}
+
+#[test]
+fn touching_the_file_does_not_make_it_outdated() -> crate::Result {
+    let (_dir, snapshot) = snapshot_of_writable_fixture()?;
+    filetime::set_file_mtime(snapshot.path(), filetime::FileTime::from_unix_time(42, 0))?;
+    assert!(
+        !snapshot.is_outdated()?,
+        "the checksum is compared if the metadata changed, and it's still the same"
+    );
+
+    std::fs::remove_file(snapshot.path())?;
+    assert!(snapshot.is_outdated()?, "removed files are outdated");
+    Ok(())
+}
+
+#[test]
+fn snapshots_of_files_that_were_never_on_disk_are_outdated() -> crate::Result {
+    let file = File::from_state(gix_index::State::new(gix_hash::Kind::Sha1), "does-not-exist");
+    assert!(Snapshot::from(file).is_outdated()?);
+    Ok(())
+}
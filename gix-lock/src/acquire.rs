@@ -46,14 +46,22 @@ impl From<Duration> for Fail {
 pub enum Error {
     #[error("Another IO error occurred while obtaining the lock")]
     Io(#[from] std::io::Error),
-    #[error("The lock for resource '{resource_path}' could not be obtained {mode} after {attempts} attempt(s). The lockfile at '{resource_path}{}' might need manual deletion.", super::DOT_LOCK_SUFFIX)]
+    #[error("The lock for resource '{resource_path}' could not be obtained {mode} after {attempts} attempt(s). The lockfile at '{resource_path}{}' might need manual deletion.{}", super::DOT_LOCK_SUFFIX, describe_holder(*.holder))]
     PermanentlyLocked {
         resource_path: PathBuf,
         mode: Fail,
         attempts: usize,
+        /// The ID of the process that created the lock file, which is only known if it is a [`Marker`] of `gix-lock`.
+        holder: Option<u32>,
     },
 }
 
+fn describe_holder(holder: Option<u32>) -> String {
+    holder
+        .map(|pid| format!(" It is likely held by the process with ID {pid}."))
+        .unwrap_or_default()
+}
+
 impl File {
     /// Create a writable lock file with failure `mode` whose content will eventually overwrite the given resource `at_path`.
     ///
@@ -111,6 +119,9 @@ impl Marker {
     ///
     /// Note that permissions will be set to `0o666`, which usually results in `0o644` after passing a default umask, on Unix systems.
     ///
+    /// The lock file contains the ID of the current process, which allows others that fail to acquire the lock to
+    /// [know](Error::PermanentlyLocked::holder) which process is holding it.
+    ///
     /// ### Warning of potential resource leak
     ///
     /// Please note that the underlying file will remain if destructors don't run, as is the case when interrupting the application.
@@ -122,11 +133,12 @@ impl Marker {
         boundary_directory: Option<PathBuf>,
     ) -> Result<Marker, Error> {
         let (lock_path, handle) = lock_with_mode(at_path.as_ref(), mode, boundary_directory, &|p, d, c| {
-            if let Some(permissions) = default_permissions() {
-                gix_tempfile::mark_at_with_permissions(p, d, c, permissions)
+            let handle = if let Some(permissions) = default_permissions() {
+                gix_tempfile::writable_at_with_permissions(p, d, c, permissions)
             } else {
-                gix_tempfile::mark_at(p, d, c)
-            }
+                gix_tempfile::writable_at(p, d, c)
+            }?;
+            mark_with_holder(handle)
         })?;
         Ok(Marker {
             created_from_file: false,
@@ -143,7 +155,7 @@ impl Marker {
         make_permissions: impl Fn() -> std::fs::Permissions,
     ) -> Result<Marker, Error> {
         let (lock_path, handle) = lock_with_mode(at_path.as_ref(), mode, boundary_directory, &|p, d, c| {
-            gix_tempfile::mark_at_with_permissions(p, d, c, make_permissions())
+            mark_with_holder(gix_tempfile::writable_at_with_permissions(p, d, c, make_permissions())?)
         })?;
         Ok(Marker {
             created_from_file: false,
@@ -153,6 +165,28 @@ impl Marker {
     }
 }
 
+const HOLDER_PREFIX: &str = "pid ";
+
+/// Write the ID of the current process into the lock file of `handle` and close it.
+fn mark_with_holder(
+    mut handle: gix_tempfile::Handle<gix_tempfile::handle::Writable>,
+) -> std::io::Result<gix_tempfile::Handle<gix_tempfile::handle::Closed>> {
+    use std::io::Write;
+    handle.with_mut(|tf| writeln!(tf, "{HOLDER_PREFIX}{}", std::process::id()))??;
+    handle.close()
+}
+
+/// Read the ID of the process holding the lock at `lock_path`, if it was written by [`mark_with_holder()`].
+fn read_holder(lock_path: &Path) -> Option<u32> {
+    let content = std::fs::read(lock_path).ok()?;
+    std::str::from_utf8(&content)
+        .ok()?
+        .strip_prefix(HOLDER_PREFIX)?
+        .strip_suffix('\n')?
+        .parse()
+        .ok()
+}
+
 fn dir_cleanup(boundary: Option<PathBuf>) -> (ContainingDirectory, AutoRemove) {
     match boundary {
         None => (ContainingDirectory::Exists, AutoRemove::Tempfile),
@@ -173,7 +207,7 @@ fn lock_with_mode<T>(
     let (directory, cleanup) = dir_cleanup(boundary_directory);
     let lock_path = add_lock_suffix(resource);
     let mut attempts = 1;
-    match mode {
+    let res = match mode {
         Fail::Immediately => try_lock(&lock_path, directory, cleanup),
         Fail::AfterDurationWithBackoff(time) => {
             for wait in backoff::Exponential::default_with_random().until_no_remaining(time) {
@@ -195,16 +229,17 @@ fn lock_with_mode<T>(
             }
             try_lock(&lock_path, directory, cleanup)
         }
-    }
-    .map(|v| (lock_path, v))
-    .map_err(|err| match err.kind() {
-        AlreadyExists => Error::PermanentlyLocked {
+    };
+    match res {
+        Ok(v) => Ok((lock_path, v)),
+        Err(err) if err.kind() == AlreadyExists => Err(Error::PermanentlyLocked {
             resource_path: resource.into(),
             mode,
             attempts,
-        },
-        _ => Error::Io(err),
-    })
+            holder: read_holder(&lock_path),
+        }),
+        Err(err) => Err(Error::Io(err)),
+    }
 }
 
 fn add_lock_suffix(resource_path: &Path) -> PathBuf {
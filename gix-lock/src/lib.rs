@@ -8,6 +8,7 @@
 //! * consistent naming of lock files
 //! * block the thread (with timeout) or fail immediately if a lock cannot be obtained right away
 //! * commit lock files to atomically put them into the location of the originally locked file
+//! * markers record the ID of the process holding them so those failing to acquire the lock can learn who holds it
 //!
 //! # Limitations
 //!
@@ -153,3 +153,83 @@ mod acquire {
         Ok(())
     }
 }
+
+mod contention {
+    use std::{
+        io::{Read, Seek, Write},
+        time::Duration,
+    };
+
+    use gix_lock::acquire::Fail;
+
+    #[test]
+    fn threads_take_turns_when_waiting_for_the_lock() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let resource = dir.path().join("counter");
+        std::fs::write(&resource, "0")?;
+        let num_increments = 25;
+        std::thread::scope(|scope| -> crate::Result {
+            let threads: Vec<_> = (0..2)
+                .map(|_| {
+                    scope.spawn(|| -> std::io::Result<()> {
+                        for _ in 0..num_increments {
+                            let mut file = gix_lock::File::acquire_to_update_resource(
+                                &resource,
+                                Fail::AfterDurationWithBackoff(Duration::from_secs(60)),
+                                None,
+                            )
+                            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+                            let count: usize = std::fs::read_to_string(&resource)?
+                                .parse()
+                                .expect("only we write the counter");
+                            std::thread::yield_now();
+                            write!(file, "{}", count + 1)?;
+                            file.commit().map_err(|err| err.error)?;
+                        }
+                        Ok(())
+                    })
+                })
+                .collect();
+            for thread in threads {
+                thread.join().expect("no panic")?;
+            }
+            Ok(())
+        })?;
+        assert_eq!(
+            std::fs::read_to_string(&resource)?,
+            (2 * num_increments).to_string(),
+            "no increment was lost as only one thread could update the counter at a time"
+        );
+        assert!(!resource.with_extension("lock").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn locks_are_removed_when_their_holder_panics() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let resource = dir.path().join("resource");
+        let lock_path = resource.with_extension("lock");
+        let res = std::thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    let mut file = gix_lock::File::acquire_to_update_resource(&resource, Fail::Immediately, None)
+                        .expect("not locked");
+                    file.write_all(b"partial").expect("writable");
+                    assert!(lock_path.is_file());
+                    panic!("interrupted while holding the lock");
+                })
+                .join()
+        });
+        assert!(res.is_err(), "the thread panicked");
+        assert!(!lock_path.exists(), "the lock was removed while unwinding");
+        assert!(!resource.exists(), "the resource wasn't created");
+
+        let mut file = gix_lock::File::acquire_to_update_resource(&resource, Fail::Immediately, None)?;
+        file.write_all(b"complete")?;
+        file.rewind()?;
+        let mut buf = String::new();
+        file.read_to_string(&mut buf)?;
+        assert_eq!(buf, "complete", "the lock can be acquired again");
+        Ok(())
+    }
+}
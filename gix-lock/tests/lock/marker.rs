@@ -41,6 +41,46 @@ mod acquire {
         assert!(err_str.contains("the-resource.lock"), "it mentions the lockfile itself");
         Ok(())
     }
+
+    #[test]
+    fn the_process_holding_the_lock_is_reported() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let resource = dir.path().join("the-resource");
+        let _guard = gix_lock::Marker::acquire_to_hold_resource(&resource, Fail::Immediately, None)?;
+        let err = gix_lock::Marker::acquire_to_hold_resource(&resource, Fail::Immediately, None)
+            .expect_err("the lock is taken");
+        assert!(
+            matches!(err, gix_lock::acquire::Error::PermanentlyLocked { holder: Some(pid), .. } if pid == std::process::id()),
+            "markers record the ID of the process that holds them"
+        );
+        assert!(err.to_string().ends_with(&format!(
+            "It is likely held by the process with ID {}.",
+            std::process::id()
+        )));
+
+        let err = gix_lock::File::acquire_to_update_resource(&resource, Fail::Immediately, None)
+            .expect_err("the lock is taken, no matter how it is acquired");
+        assert!(matches!(
+            err,
+            gix_lock::acquire::Error::PermanentlyLocked { holder: Some(_), .. }
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn the_holder_of_lock_files_is_unknown_as_they_contain_the_new_resource() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let resource = dir.path().join("the-resource");
+        let mut file = gix_lock::File::acquire_to_update_resource(&resource, Fail::Immediately, None)?;
+        std::io::Write::write_all(&mut file, b"new content")?;
+        let err = gix_lock::Marker::acquire_to_hold_resource(&resource, Fail::Immediately, None)
+            .expect_err("the lock is taken");
+        assert!(matches!(
+            err,
+            gix_lock::acquire::Error::PermanentlyLocked { holder: None, .. }
+        ));
+        Ok(())
+    }
 }
 mod commit {
     use gix_lock::acquire::Fail;
@@ -0,0 +1,38 @@
+/// What to do when having to pick a side to resolve a conflict.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ResolveWith {
+    /// Chose the ancestor to resolve a conflict, which is what happens when merging the ancestors of a merge-base.
+    Ancestor,
+    /// Chose our side to resolve a conflict.
+    Ours,
+    /// Chose their side to resolve a conflict.
+    Theirs,
+}
+
+/// Tell the caller of [`merge()`](function::merge()) which side was picked.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Pick {
+    /// Chose the ancestor.
+    Ancestor,
+    /// Chose our side.
+    Ours,
+    /// Chose their side.
+    Theirs,
+}
+
+pub(super) mod function {
+    use crate::blob::builtin_driver::binary::{Pick, ResolveWith};
+    use crate::blob::Resolution;
+
+    /// As this algorithm doesn't look at the actual data, it returns a choice solely based on `on_conflict`.
+    /// If `on_conflict` is `None`, *ours* is picked but the result is still a conflict, as binary files can't
+    /// be merged automatically.
+    pub fn merge(on_conflict: Option<ResolveWith>) -> (Pick, Resolution) {
+        match on_conflict {
+            None => (Pick::Ours, Resolution::Conflict),
+            Some(ResolveWith::Ours) => (Pick::Ours, Resolution::Complete),
+            Some(ResolveWith::Theirs) => (Pick::Theirs, Resolution::Complete),
+            Some(ResolveWith::Ancestor) => (Pick::Ancestor, Resolution::Complete),
+        }
+    }
+}
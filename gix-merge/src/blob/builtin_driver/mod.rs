@@ -0,0 +1,18 @@
+use crate::blob::BuiltinDriver;
+
+///
+#[allow(clippy::empty_docs)]
+pub mod binary;
+pub use binary::function::merge as binary;
+
+///
+#[allow(clippy::empty_docs)]
+pub mod text;
+pub use text::function::merge as text;
+
+impl BuiltinDriver {
+    /// Return `true` if this driver merges line-by-line, which is only possible if none of the inputs is binary.
+    pub fn is_line_based(&self) -> bool {
+        matches!(self, BuiltinDriver::Text | BuiltinDriver::Union)
+    }
+}
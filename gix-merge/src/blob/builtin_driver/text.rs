@@ -0,0 +1,441 @@
+use bstr::BStr;
+
+/// The way the built-in [text driver](crate::blob::BuiltinDriver::Text) will express merge-conflicts in the resulting file.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ConflictStyle {
+    /// Only show the zealously minified conflicting lines of the local changes and the incoming (other) changes,
+    /// hiding the base version entirely.
+    ///
+    /// ```text
+    /// line1-changed-by-both
+    /// <<<<<<< local
+    /// line2-to-be-changed-in-incoming
+    /// =======
+    /// line2-changed
+    /// >>>>>>> incoming
+    /// ```
+    #[default]
+    Merge,
+    /// Show non-minimized hunks of local changes, the base, and the incoming (other) changes.
+    ///
+    /// This mode does not hide any information.
+    ///
+    /// ```text
+    /// <<<<<<< local
+    /// line1-changed-by-both
+    /// line2-to-be-changed-in-incoming
+    /// ||||||| 9a8d80c
+    /// line1-to-be-changed-by-both
+    /// line2-to-be-changed-in-incoming
+    /// =======
+    /// line1-changed-by-both
+    /// line2-changed
+    /// >>>>>>> incoming
+    /// ```
+    Diff3,
+}
+
+/// The set of labels to annotate conflict markers with.
+///
+/// That way it becomes clearer where the content of conflicts are originating from.
+#[derive(Default, Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Labels<'a> {
+    /// The label for the common ancestor, shown with the [`Diff3`](ConflictStyle::Diff3) style only.
+    pub ancestor: Option<&'a BStr>,
+    /// The label for the *current* (or *ours*) side.
+    pub current: Option<&'a BStr>,
+    /// The label for the *other* (or *theirs*) side.
+    pub other: Option<&'a BStr>,
+}
+
+/// The default amount of characters making up conflict markers, like `<<<<<<<`.
+pub const DEFAULT_MARKER_SIZE: usize = 7;
+
+/// Define how to resolve conflicts, or if they should be kept.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Conflict {
+    /// Keep the conflict by marking it in the source file.
+    Keep {
+        /// How to visualize conflicts in merged files.
+        style: ConflictStyle,
+        /// The amount of markers to draw, defaults to [`DEFAULT_MARKER_SIZE`].
+        marker_size: usize,
+    },
+    /// Chose our side to resolve a conflict.
+    ResolveWithOurs,
+    /// Chose their side to resolve a conflict.
+    ResolveWithTheirs,
+    /// Place our and their lines one after another, in any order, which is what the
+    /// [union driver](crate::blob::BuiltinDriver::Union) does.
+    ResolveWithUnion,
+}
+
+impl Default for Conflict {
+    fn default() -> Self {
+        Conflict::Keep {
+            style: Default::default(),
+            marker_size: DEFAULT_MARKER_SIZE,
+        }
+    }
+}
+
+/// Options for the builtin [text driver](crate::blob::BuiltinDriver::Text).
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Options {
+    /// Determine how the diff will be performed.
+    /// Defaults to [`imara_diff::Algorithm::Myers`].
+    pub diff_algorithm: imara_diff::Algorithm,
+    /// Decide what to do to automatically resolve conflicts, or to keep them.
+    pub conflict: Conflict,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            conflict: Default::default(),
+            diff_algorithm: imara_diff::Algorithm::Myers,
+        }
+    }
+}
+
+pub(super) mod function {
+    use std::ops::Range;
+
+    use imara_diff::{
+        intern::{Interner, Token},
+        sources::byte_lines_with_terminator,
+    };
+
+    use crate::blob::{
+        builtin_driver::text::{Conflict, ConflictStyle, Labels, Options},
+        Resolution,
+    };
+
+    /// Merge `current` and `other` with `ancestor` as base line-by-line, and write the result into `out`,
+    /// using `labels` to annotate conflict markers, and `opts` to control how conflicts are handled.
+    ///
+    /// Unless conflicts are shown with the [`Diff3`](ConflictStyle::Diff3) style, conflicts are *zealously*
+    /// minimized just like `git` does, so lines that both sides have in common are moved out of the conflict,
+    /// while conflicts that are only separated by up to three lines are combined into one.
+    ///
+    /// Note that the inputs are assumed to be text, it's up to the caller to merge binary data
+    /// with the [binary driver](crate::blob::builtin_driver::binary()) instead.
+    pub fn merge(
+        out: &mut Vec<u8>,
+        labels: Labels<'_>,
+        ancestor: &[u8],
+        current: &[u8],
+        other: &[u8],
+        opts: Options,
+    ) -> Resolution {
+        let mut interner = Interner::new(ancestor.len() / 16);
+        let mut tokenize = |data| {
+            byte_lines_with_terminator(data)
+                .map(|line| interner.intern(line))
+                .collect::<Vec<_>>()
+        };
+        let ancestor = tokenize(ancestor);
+        let current = tokenize(current);
+        let other = tokenize(other);
+        let input = Input {
+            ancestor: &ancestor,
+            current: &current,
+            other: &other,
+            interner: &interner,
+        };
+
+        let current_hunks = hunks(
+            opts.diff_algorithm,
+            input.ancestor,
+            input.current,
+            interner.num_tokens(),
+        );
+        let other_hunks = hunks(opts.diff_algorithm, input.ancestor, input.other, interner.num_tokens());
+        let mut items = items(&input, &current_hunks, &other_hunks);
+        let zealous = !matches!(
+            opts.conflict,
+            Conflict::Keep {
+                style: ConflictStyle::Diff3,
+                ..
+            }
+        );
+        if zealous {
+            items = simplify_non_conflicts(refine_conflicts(&input, items, opts.diff_algorithm));
+        }
+
+        let mut resolution = Resolution::Complete;
+        for item in items {
+            match item {
+                Item::Common(lines) | Item::Current(lines) | Item::Identical(lines) => {
+                    input.write(out, input.current, lines, false);
+                }
+                Item::Other(lines) => input.write(out, input.other, lines, false),
+                Item::Conflict {
+                    ancestor,
+                    current,
+                    other,
+                } => match opts.conflict {
+                    Conflict::Keep { style, marker_size } => {
+                        resolution = Resolution::Conflict;
+                        write_marker(out, b'<', marker_size, labels.current);
+                        input.write(out, input.current, current, true);
+                        if style == ConflictStyle::Diff3 {
+                            write_marker(out, b'|', marker_size, labels.ancestor);
+                            input.write(out, input.ancestor, ancestor, true);
+                        }
+                        write_marker(out, b'=', marker_size, None);
+                        input.write(out, input.other, other, true);
+                        write_marker(out, b'>', marker_size, labels.other);
+                    }
+                    Conflict::ResolveWithOurs => input.write(out, input.current, current, false),
+                    Conflict::ResolveWithTheirs => input.write(out, input.other, other, false),
+                    Conflict::ResolveWithUnion => {
+                        input.write(out, input.current, current, true);
+                        input.write(out, input.other, other, false);
+                    }
+                },
+            }
+        }
+        resolution
+    }
+
+    struct Input<'a, 'data> {
+        ancestor: &'a [Token],
+        current: &'a [Token],
+        other: &'a [Token],
+        interner: &'a Interner<&'data [u8]>,
+    }
+
+    impl Input<'_, '_> {
+        /// Write `lines` of `tokens` to `out`, and add a newline if the last one doesn't have one and `add_newline` is `true`.
+        fn write(&self, out: &mut Vec<u8>, tokens: &[Token], lines: Range<u32>, add_newline: bool) {
+            let tokens = &tokens[lines.start as usize..lines.end as usize];
+            for token in tokens {
+                out.extend_from_slice(self.interner[*token]);
+            }
+            if add_newline && matches!(tokens.last(), Some(token) if self.interner[*token].last() != Some(&b'\n')) {
+                out.push(b'\n');
+            }
+        }
+    }
+
+    fn write_marker(out: &mut Vec<u8>, marker: u8, size: usize, label: Option<&bstr::BStr>) {
+        out.extend(std::iter::repeat(marker).take(size));
+        if let Some(label) = label {
+            out.push(b' ');
+            out.extend_from_slice(label);
+        }
+        out.push(b'\n');
+    }
+
+    /// A change in the *current* or *other* version, compared to the ancestor.
+    struct Hunk {
+        ancestor: Range<u32>,
+        side: Range<u32>,
+    }
+
+    fn hunks(algorithm: imara_diff::Algorithm, before: &[Token], after: &[Token], num_tokens: u32) -> Vec<Hunk> {
+        let mut out = Vec::new();
+        imara_diff::diff_with_tokens(algorithm, before, after, num_tokens, |ancestor, side| {
+            out.push(Hunk { ancestor, side })
+        });
+        out
+    }
+
+    /// A section of the merged file, with ranges of lines referring to the *current*, *other* or *ancestor* version.
+    #[derive(Debug, Clone)]
+    enum Item {
+        /// Lines that are unchanged, or that were changed in the same way by both sides.
+        Common(Range<u32>),
+        /// Lines only changed in the *current* version.
+        Current(Range<u32>),
+        /// Lines only changed in the *other* version.
+        Other(Range<u32>),
+        /// A conflict that turned out to have the same lines on both sides after all.
+        Identical(Range<u32>),
+        /// Lines that were changed differently on both sides.
+        Conflict {
+            ancestor: Range<u32>,
+            current: Range<u32>,
+            other: Range<u32>,
+        },
+    }
+
+    /// Combine the changes of both sides into regions, which are conflicting if the changes of both sides overlap or touch.
+    fn items(input: &Input<'_, '_>, current: &[Hunk], other: &[Hunk]) -> Vec<Item> {
+        let mut out = Vec::new();
+        let (mut current_hunks, mut other_hunks) = (current.iter().peekable(), other.iter().peekable());
+        let (mut current_delta, mut other_delta) = (0_i64, 0_i64);
+        let mut current_pos = 0;
+        let offset = |pos: u32, delta: i64| (pos as i64 + delta) as u32;
+        loop {
+            let start = match (current_hunks.peek(), other_hunks.peek()) {
+                (Some(a), Some(b)) => a.ancestor.start.min(b.ancestor.start),
+                (Some(a), None) => a.ancestor.start,
+                (None, Some(b)) => b.ancestor.start,
+                (None, None) => break,
+            };
+            let current_start = offset(start, current_delta);
+            let other_start = offset(start, other_delta);
+
+            let mut end = start;
+            let (mut current_count, mut other_count) = (0, 0);
+            let mut first_pair = None;
+            loop {
+                let mut absorbed = false;
+                for (hunks, delta, count) in [
+                    (&mut current_hunks, &mut current_delta, &mut current_count),
+                    (&mut other_hunks, &mut other_delta, &mut other_count),
+                ] {
+                    if let Some(hunk) = hunks.next_if(|hunk| hunk.ancestor.start <= end) {
+                        end = end.max(hunk.ancestor.end);
+                        *delta = hunk.side.end as i64 - hunk.ancestor.end as i64;
+                        *count += 1;
+                        absorbed = true;
+                        first_pair = match first_pair {
+                            None => Some((hunk, None)),
+                            Some((first, None)) => Some((first, Some(hunk))),
+                            pair => pair,
+                        };
+                    }
+                }
+                if !absorbed {
+                    break;
+                }
+            }
+            let current_lines = current_start..offset(end, current_delta);
+            let other_lines = other_start..offset(end, other_delta);
+
+            if current_pos < current_lines.start {
+                out.push(Item::Common(current_pos..current_lines.start));
+            }
+            current_pos = current_lines.end;
+            out.push(match (current_count, other_count) {
+                (_, 0) => Item::Current(current_lines),
+                (0, _) => Item::Other(other_lines),
+                (1, 1)
+                    if matches!(first_pair, Some((a, Some(b))) if a.ancestor == b.ancestor)
+                        && input.current[range(&current_lines)] == input.other[range(&other_lines)] =>
+                {
+                    Item::Common(current_lines)
+                }
+                _ => Item::Conflict {
+                    ancestor: start..end,
+                    current: current_lines,
+                    other: other_lines,
+                },
+            });
+        }
+        if (current_pos as usize) < input.current.len() {
+            out.push(Item::Common(current_pos..input.current.len() as u32));
+        }
+        out
+    }
+
+    /// Diff both sides of each conflict to only leave the lines that actually differ in conflict.
+    fn refine_conflicts(input: &Input<'_, '_>, items: Vec<Item>, algorithm: imara_diff::Algorithm) -> Vec<Item> {
+        let mut out = Vec::with_capacity(items.len());
+        for item in items {
+            let Item::Conflict {
+                ancestor,
+                current,
+                other,
+            } = item
+            else {
+                out.push(item);
+                continue;
+            };
+            if current.is_empty() || other.is_empty() {
+                out.push(Item::Conflict {
+                    ancestor,
+                    current,
+                    other,
+                });
+                continue;
+            }
+            let hunks = hunks(
+                algorithm,
+                &input.current[range(&current)],
+                &input.other[range(&other)],
+                input.interner.num_tokens(),
+            );
+            if hunks.is_empty() {
+                out.push(Item::Identical(current));
+                continue;
+            }
+            let mut pos = current.start;
+            for hunk in hunks {
+                let hunk_start = current.start + hunk.ancestor.start;
+                if pos < hunk_start {
+                    out.push(Item::Common(pos..hunk_start));
+                }
+                pos = current.start + hunk.ancestor.end;
+                out.push(Item::Conflict {
+                    ancestor: ancestor.clone(),
+                    current: hunk_start..pos,
+                    other: other.start + hunk.side.start..other.start + hunk.side.end,
+                });
+            }
+            if pos < current.end {
+                out.push(Item::Common(pos..current.end));
+            }
+        }
+        out
+    }
+
+    /// Combine conflicts that are separated by no more than three lines, as these lines are unlikely to be
+    /// helpful on their own.
+    fn simplify_non_conflicts(items: Vec<Item>) -> Vec<Item> {
+        const MAX_COMMON_LINES: u32 = 3;
+        let mut out: Vec<Item> = Vec::with_capacity(items.len());
+        for item in items {
+            let Item::Conflict {
+                ancestor,
+                current,
+                other,
+            } = item
+            else {
+                out.push(item);
+                continue;
+            };
+            let num_common = out
+                .iter()
+                .rev()
+                .take_while(|item| matches!(item, Item::Common(_)))
+                .count();
+            let common_lines: u32 = out[out.len() - num_common..]
+                .iter()
+                .map(|item| match item {
+                    Item::Common(lines) => lines.len() as u32,
+                    _ => unreachable!("only common lines are counted"),
+                })
+                .sum();
+            let previous_conflict = out
+                .len()
+                .checked_sub(num_common + 1)
+                .filter(|_| common_lines <= MAX_COMMON_LINES);
+            match previous_conflict.map(|idx| &mut out[idx]) {
+                Some(Item::Conflict {
+                    ancestor: prev_ancestor,
+                    current: prev_current,
+                    other: prev_other,
+                }) => {
+                    prev_ancestor.end = ancestor.end.max(prev_ancestor.end);
+                    prev_current.end = current.end;
+                    prev_other.end = other.end;
+                    out.truncate(out.len() - num_common);
+                }
+                _ => out.push(Item::Conflict {
+                    ancestor,
+                    current,
+                    other,
+                }),
+            }
+        }
+        out
+    }
+
+    fn range(lines: &Range<u32>) -> Range<usize> {
+        lines.start as usize..lines.end as usize
+    }
+}
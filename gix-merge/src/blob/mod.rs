@@ -0,0 +1,106 @@
+//! Merge three versions of a blob, using either one of the [built-in drivers](BuiltinDriver) or a [user-defined driver](Driver)
+//! as selected by the `merge` attribute.
+use bstr::BString;
+
+///
+#[allow(clippy::empty_docs)]
+pub mod builtin_driver;
+
+///
+#[allow(clippy::empty_docs)]
+pub mod platform;
+
+/// A way to classify the outcome of a merge.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Resolution {
+    /// Everything could be resolved during the merge, and the result is ready to be used.
+    Complete,
+    /// There were conflicts which are marked in the result, or which were resolved in favor of one side
+    /// without being able to tell if that's correct, as is the case with binary files.
+    Conflict,
+}
+
+/// A merge driver that is built into `gitoxide`, selected by name with the `merge` attribute.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum BuiltinDriver {
+    /// Perform a 3-way line-by-line merge, marking conflicts in the result. This is the default.
+    #[default]
+    Text,
+    /// Take the *current* (or *ours*) version and report a conflict,
+    /// as binary files can't be merged automatically.
+    Binary,
+    /// Like [`Text`](Self::Text), but resolve conflicts by using the lines of both sides, *ours* first, without
+    /// conflict markers.
+    Union,
+    /// Take the *current* (or *ours*) version, which is considered the correct result.
+    ///
+    /// ### Deviation
+    ///
+    /// In `git`, `merge=ours` only works if a driver named `ours` is configured, typically with `merge.ours.driver true`.
+    /// Here it's built-in, but a user-defined driver of the same name still takes precedence.
+    Ours,
+}
+
+impl BuiltinDriver {
+    /// All built-in drivers, for use in iteration.
+    pub const ALL: [BuiltinDriver; 4] = [
+        BuiltinDriver::Text,
+        BuiltinDriver::Binary,
+        BuiltinDriver::Union,
+        BuiltinDriver::Ours,
+    ];
+
+    /// Return the name of this driver as it would be used with the `merge` attribute.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BuiltinDriver::Text => "text",
+            BuiltinDriver::Binary => "binary",
+            BuiltinDriver::Union => "union",
+            BuiltinDriver::Ours => "ours",
+        }
+    }
+
+    /// Return the built-in driver with the given `name`, or `None` if there is none.
+    pub fn by_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().find(|driver| driver.as_str() == name).copied()
+    }
+}
+
+/// A user-defined merge driver, configured in the `[merge "<name>"]` section of the git configuration.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Driver {
+    /// The name of the driver, as referred to by `merge=<name>` in `.gitattributes` and in `[merge "<name>"]` in the git configuration.
+    pub name: BString,
+    /// A human-readable name for the driver, as set in `merge.<name>.name`.
+    pub display_name: BString,
+    /// The command to execute to perform the merge, as set in `merge.<name>.driver`.
+    ///
+    /// It's executed with a shell after substituting the following tokens:
+    ///
+    /// * `%O` - the path to a temporary file with the content of the ancestor.
+    /// * `%A` - the path to a temporary file with the *current* (or *ours*) version, which is also
+    ///   where the result of the merge is expected to be written to.
+    /// * `%B` - the path to a temporary file with the *other* (or *theirs*) version.
+    /// * `%L` - the size of the conflict markers to use.
+    /// * `%P` - the path of the merged file relative to the root of the worktree.
+    /// * `%%` - a literal `%`.
+    ///
+    /// The command exits with code `0` if it could merge cleanly, or with any other code if there are conflicts.
+    pub command: BString,
+    /// If set, the name of the driver to use when merging the ancestors of the merge-base,
+    /// which can be a built-in or a user-defined driver, as set in `merge.<name>.recursive`.
+    pub recursive: Option<BString>,
+}
+
+/// A utility to merge blobs with the driver selected for each path by the `merge` attribute, which may be one of the
+/// [built-in drivers](BuiltinDriver) or a [user-defined driver](Driver).
+pub struct Platform {
+    /// All user-defined drivers, sorted by name.
+    drivers: Vec<Driver>,
+    /// A way to access `.gitattributes`.
+    pub attr_stack: gix_worktree::Stack,
+    /// Options to alter how merges should be performed.
+    pub options: platform::Options,
+    /// Pre-configured attributes to obtain the `merge` and `conflict-marker-size` attributes.
+    attrs: gix_worktree::attributes::search::Outcome,
+}
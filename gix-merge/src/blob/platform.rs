@@ -0,0 +1,369 @@
+use std::io::{Read, Write};
+
+use bstr::{BStr, BString, ByteSlice};
+
+use crate::blob::{builtin_driver, BuiltinDriver, Driver, Platform, Resolution};
+
+/// The three versions of a blob to merge, as passed to [`Platform::merge()`].
+///
+/// Note that the data is expected to be in a state suitable for merging, i.e. it has been converted
+/// with the *to-git* filters as needed.
+#[derive(Debug, Copy, Clone)]
+pub struct Input<'a> {
+    /// The common ancestor of `current` and `other`, or an empty buffer if there is none.
+    pub ancestor: &'a [u8],
+    /// The *current* (or *ours*) version.
+    pub current: &'a [u8],
+    /// The *other* (or *theirs*) version.
+    pub other: &'a [u8],
+}
+
+/// Options for use in [`Platform::new()`].
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    /// The name of the driver to use if the `merge` attribute isn't specified for a path, as configured by `merge.default`.
+    /// If `None`, the [text driver](BuiltinDriver::Text) is used.
+    pub default_driver: Option<BString>,
+    /// The options to use with the built-in [text driver](BuiltinDriver::Text), whose conflict marker size
+    /// may be overridden for each path with the `conflict-marker-size` attribute.
+    pub text: builtin_driver::text::Options,
+    /// What to do when binary files conflict. If `None`, *ours* is chosen, but the merge is still considered conflicting.
+    pub resolve_binary_with: Option<builtin_driver::binary::ResolveWith>,
+    /// If `true`, we are merging the ancestors of the merge-base to produce a virtual merge-base,
+    /// which makes user-defined drivers use the driver named in their `recursive` field, and resolves
+    /// binary conflicts with the ancestor.
+    pub is_virtual_ancestor: bool,
+    /// Information to pass to user-defined merge drivers through the environment, with its `worktree_dir` also
+    /// being used as working directory for the driver if set.
+    pub command_context: gix_command::Context,
+}
+
+/// The driver to use for a particular path, as returned by [`Platform::driver_for()`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum DriverChoice {
+    /// Use the given built-in driver.
+    BuiltIn(BuiltinDriver),
+    /// Use the user-defined driver at the given index in [`Platform::drivers()`].
+    Index(usize),
+}
+
+/// The merge-related settings for a path as obtained by [`Platform::driver_for()`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Selection {
+    /// The driver to use for merging.
+    pub driver: DriverChoice,
+    /// The amount of characters to use for conflict markers.
+    pub marker_size: usize,
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod merge {
+    use bstr::BString;
+
+    /// The error returned by [Platform::merge()](super::Platform::merge()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Failed to obtain attributes for '{rela_path}'")]
+        Attributes { rela_path: BString, source: std::io::Error },
+        #[error("The merge driver '{name}' for '{rela_path}' has no command configured in merge.{name}.driver")]
+        MissingDriverCommand { name: BString, rela_path: BString },
+        #[error(
+            "Tempfile to store content of '{rela_path}' for passing to merge driver '{name}' could not be created"
+        )]
+        CreateTempfile {
+            name: BString,
+            rela_path: BString,
+            source: std::io::Error,
+        },
+        #[error("Could not write content of '{rela_path}' to tempfile for passing to merge driver '{name}'")]
+        WriteTempfile {
+            name: BString,
+            rela_path: BString,
+            source: std::io::Error,
+        },
+        #[error("Failed to run merge driver '{name}' with command '{command}'")]
+        SpawnDriver {
+            name: BString,
+            command: BString,
+            source: std::io::Error,
+        },
+        #[error("Failed to read the result of merge driver '{name}' for '{rela_path}'")]
+        ReadResult {
+            name: BString,
+            rela_path: BString,
+            source: std::io::Error,
+        },
+    }
+}
+
+/// Lifecycle
+impl Platform {
+    /// Create a new instance with user-defined merge `drivers`, an `attr_stack` to obtain the `merge` and `conflict-marker-size`
+    /// attributes for each path, and `options` to further configure the merge.
+    pub fn new(mut drivers: Vec<Driver>, attr_stack: gix_worktree::Stack, options: Options) -> Self {
+        drivers.sort_by(|a, b| a.name.cmp(&b.name));
+        Platform {
+            drivers,
+            attr_stack,
+            attrs: {
+                let mut out = gix_worktree::attributes::search::Outcome::default();
+                out.initialize_with_selection(&Default::default(), ["merge", "conflict-marker-size"]);
+                out
+            },
+            options,
+        }
+    }
+}
+
+/// Access
+impl Platform {
+    /// Return all user-defined drivers that this instance was initialized with, sorted by name.
+    pub fn drivers(&self) -> &[Driver] {
+        &self.drivers
+    }
+
+    /// Return the driver and conflict marker size to use for `rela_path`, based on its attributes
+    /// which are looked up using `objects` if needed.
+    ///
+    /// The driver is chosen like `git` does, so a `merge` attribute that is set uses the [text driver](BuiltinDriver::Text),
+    /// and an unset one uses the [binary driver](BuiltinDriver::Binary). If it's unspecified, the driver in
+    /// [`Options::default_driver`] is used, if set. Named drivers are looked up among the user-defined drivers first, then
+    /// among the built-in ones, falling back to the text driver if there is no driver of that name.
+    ///
+    /// If [`Options::is_virtual_ancestor`] is set, the driver named by the `recursive` field of a user-defined driver is used instead.
+    pub fn driver_for(&mut self, rela_path: &BStr, objects: &dyn gix_object::Find) -> Result<Selection, merge::Error> {
+        let platform = self
+            .attr_stack
+            .at_entry(rela_path, None, objects)
+            .map_err(|err| merge::Error::Attributes {
+                rela_path: rela_path.to_owned(),
+                source: err,
+            })?;
+        platform.matching_attributes(&mut self.attrs);
+        let mut attrs = self.attrs.iter_selected();
+        let merge_attr = attrs.next().expect("pre-initialized with 'merge'");
+        let marker_size_attr = attrs.next().expect("pre-initialized with 'conflict-marker-size'");
+
+        let mut driver = match merge_attr.assignment.state {
+            gix_worktree::attributes::StateRef::Set => DriverChoice::BuiltIn(BuiltinDriver::Text),
+            gix_worktree::attributes::StateRef::Unset => DriverChoice::BuiltIn(BuiltinDriver::Binary),
+            gix_worktree::attributes::StateRef::Value(name) => self.driver_by_name(name.as_bstr()),
+            gix_worktree::attributes::StateRef::Unspecified => match self.options.default_driver.as_ref() {
+                Some(name) => self.driver_by_name(name.as_bstr()),
+                None => DriverChoice::BuiltIn(BuiltinDriver::Text),
+            },
+        };
+        if self.options.is_virtual_ancestor {
+            if let DriverChoice::Index(idx) = driver {
+                if let Some(name) = self.drivers[idx].recursive.as_ref() {
+                    driver = self.driver_by_name(name.as_bstr());
+                }
+            }
+        }
+
+        let marker_size = match marker_size_attr.assignment.state {
+            gix_worktree::attributes::StateRef::Value(value) => value
+                .as_bstr()
+                .to_str()
+                .ok()
+                .and_then(|value| value.parse::<usize>().ok())
+                .filter(|size| *size > 0),
+            _ => None,
+        }
+        .unwrap_or(match self.options.text.conflict {
+            builtin_driver::text::Conflict::Keep { marker_size, .. } => marker_size,
+            _ => builtin_driver::text::DEFAULT_MARKER_SIZE,
+        });
+        Ok(Selection { driver, marker_size })
+    }
+
+    fn driver_by_name(&self, name: &BStr) -> DriverChoice {
+        self.drivers
+            .binary_search_by(|driver| driver.name.as_bstr().cmp(name))
+            .ok()
+            .map(DriverChoice::Index)
+            .or_else(|| {
+                name.to_str()
+                    .ok()
+                    .and_then(BuiltinDriver::by_name)
+                    .map(DriverChoice::BuiltIn)
+            })
+            .unwrap_or(DriverChoice::BuiltIn(BuiltinDriver::Text))
+    }
+}
+
+/// Merging
+impl Platform {
+    /// Merge `input` of the blob at `rela_path` with the driver selected by its attributes as returned by
+    /// [`driver_for()`](Self::driver_for()), and write the result into `out`, returning the way the merge was resolved.
+    /// `labels` are used to annotate conflict markers, and `objects` is used to read attributes from the
+    /// object database if needed.
+    ///
+    /// Just like in `git`, the [text](BuiltinDriver::Text) and [union](BuiltinDriver::Union) drivers fall back to the
+    /// [binary driver](BuiltinDriver::Binary) if any of the inputs is binary. User-defined drivers are run with the
+    /// three versions written to temporary files, and their result is expected in the file of the *current* version.
+    /// They are considered to have conflicts if their exit code is non-zero.
+    pub fn merge(
+        &mut self,
+        out: &mut Vec<u8>,
+        rela_path: &BStr,
+        input: Input<'_>,
+        labels: builtin_driver::text::Labels<'_>,
+        objects: &dyn gix_object::Find,
+    ) -> Result<Resolution, merge::Error> {
+        let Selection { driver, marker_size } = self.driver_for(rela_path, objects)?;
+        match driver {
+            DriverChoice::BuiltIn(driver) => Ok(self.merge_builtin(out, driver, input, labels, marker_size)),
+            DriverChoice::Index(idx) => merge_with_user_driver(
+                out,
+                &self.drivers[idx],
+                rela_path,
+                input,
+                marker_size,
+                &self.options.command_context,
+            ),
+        }
+    }
+
+    fn merge_builtin(
+        &self,
+        out: &mut Vec<u8>,
+        driver: BuiltinDriver,
+        input: Input<'_>,
+        labels: builtin_driver::text::Labels<'_>,
+        marker_size: usize,
+    ) -> Resolution {
+        let is_binary = driver.is_line_based()
+            && [input.ancestor, input.current, input.other]
+                .iter()
+                .any(|data| gix_diff::blob::is_binary_buf(data));
+        match driver {
+            BuiltinDriver::Text | BuiltinDriver::Union if !is_binary => {
+                let mut opts = self.options.text;
+                opts.conflict = match (driver, opts.conflict) {
+                    (BuiltinDriver::Union, _) => builtin_driver::text::Conflict::ResolveWithUnion,
+                    (_, builtin_driver::text::Conflict::Keep { style, .. }) => {
+                        builtin_driver::text::Conflict::Keep { style, marker_size }
+                    }
+                    (_, conflict) => conflict,
+                };
+                builtin_driver::text(out, labels, input.ancestor, input.current, input.other, opts)
+            }
+            BuiltinDriver::Ours => {
+                out.extend_from_slice(input.current);
+                Resolution::Complete
+            }
+            BuiltinDriver::Text | BuiltinDriver::Union | BuiltinDriver::Binary => {
+                let (pick, resolution) = builtin_driver::binary(if self.options.is_virtual_ancestor {
+                    Some(builtin_driver::binary::ResolveWith::Ancestor)
+                } else {
+                    self.options.resolve_binary_with
+                });
+                out.extend_from_slice(match pick {
+                    builtin_driver::binary::Pick::Ancestor => input.ancestor,
+                    builtin_driver::binary::Pick::Ours => input.current,
+                    builtin_driver::binary::Pick::Theirs => input.other,
+                });
+                resolution
+            }
+        }
+    }
+}
+
+fn merge_with_user_driver(
+    out: &mut Vec<u8>,
+    driver: &Driver,
+    rela_path: &BStr,
+    input: Input<'_>,
+    marker_size: usize,
+    context: &gix_command::Context,
+) -> Result<Resolution, merge::Error> {
+    if driver.command.is_empty() {
+        return Err(merge::Error::MissingDriverCommand {
+            name: driver.name.clone(),
+            rela_path: rela_path.to_owned(),
+        });
+    }
+    let write_tempfile = |data: &[u8]| {
+        let mut tmp = gix_tempfile::new(
+            std::env::temp_dir(),
+            gix_tempfile::ContainingDirectory::Exists,
+            gix_tempfile::AutoRemove::Tempfile,
+        )
+        .map_err(|err| merge::Error::CreateTempfile {
+            name: driver.name.clone(),
+            rela_path: rela_path.to_owned(),
+            source: err,
+        })?;
+        tmp.write_all(data)
+            .and_then(|_| tmp.close())
+            .and_then(|tmp| {
+                tmp.take().ok_or_else(|| {
+                    std::io::Error::new(std::io::ErrorKind::NotFound, "tempfile was removed prematurely")
+                })
+            })
+            .map_err(|err| merge::Error::WriteTempfile {
+                name: driver.name.clone(),
+                rela_path: rela_path.to_owned(),
+                source: err,
+            })
+    };
+    let ancestor = write_tempfile(input.ancestor)?;
+    let current = write_tempfile(input.current)?;
+    let other = write_tempfile(input.other)?;
+    let tempfile_path = |path: &std::path::Path| gix_quote::single(gix_path::into_bstr(path).as_ref());
+
+    let mut command = BString::default();
+    let mut rest = driver.command.as_bytes();
+    while let Some(pos) = rest.find_byte(b'%') {
+        command.extend_from_slice(&rest[..pos]);
+        rest = &rest[pos + 1..];
+        let substitution = match rest.first() {
+            Some(b'O') => tempfile_path(&ancestor),
+            Some(b'A') => tempfile_path(&current),
+            Some(b'B') => tempfile_path(&other),
+            Some(b'L') => marker_size.to_string().into(),
+            Some(b'P') => gix_quote::single(rela_path),
+            Some(b'%') => "%".into(),
+            _ => {
+                command.push(b'%');
+                continue;
+            }
+        };
+        command.extend_from_slice(&substitution);
+        rest = &rest[1..];
+    }
+    command.extend_from_slice(rest);
+
+    let mut cmd: std::process::Command = gix_command::prepare(gix_path::from_bstring(command.clone()))
+        .with_shell()
+        .with_context(context.clone())
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::inherit())
+        .stderr(std::process::Stdio::inherit())
+        .into();
+    if let Some(worktree_dir) = context.worktree_dir.as_ref() {
+        cmd.current_dir(worktree_dir);
+    }
+    gix_trace::debug!(cmd = ?cmd, "Running merge driver");
+    let status = cmd.status().map_err(|err| merge::Error::SpawnDriver {
+        name: driver.name.clone(),
+        command,
+        source: err,
+    })?;
+
+    std::fs::File::open(&current)
+        .and_then(|mut file| file.read_to_end(out))
+        .map_err(|err| merge::Error::ReadResult {
+            name: driver.name.clone(),
+            rela_path: rela_path.to_owned(),
+            source: err,
+        })?;
+    Ok(if status.success() {
+        Resolution::Complete
+    } else {
+        Resolution::Conflict
+    })
+}
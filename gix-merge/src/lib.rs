@@ -0,0 +1,12 @@
+//! Implement merge algorithms for all kinds of git objects, starting with blobs whose merge-driver is selected by `git-attributes`.
+#![deny(rust_2018_idioms, missing_docs)]
+#![forbid(unsafe_code)]
+
+///
+#[allow(clippy::empty_docs)]
+pub mod blob;
+
+///
+#[allow(clippy::empty_docs)]
+pub mod tree;
+pub use tree::function::tree;
@@ -0,0 +1,766 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use gix_hash::{oid, ObjectId};
+use gix_index::entry::Stage;
+use gix_object::{
+    tree::{EntryKind, EntryMode},
+    FindExt,
+};
+
+use crate::{
+    blob::{builtin_driver::text::Labels, platform::Input, Resolution},
+    tree::{
+        renames::{self, Item, Items},
+        Conflict, DirectoryRenames, Entry, Error, Options, Outcome, Side,
+    },
+};
+
+/// Merge the trees `ours` and `theirs` with `base` as their common ancestor, detecting renamed files and directories
+/// on each side as configured in `options`, and return the merged tree along with all conflicts and the entries
+/// that `git` would record in the index.
+///
+/// `labels` annotate conflict markers and name the files that have to be moved out of the way, while `objects` provides
+/// the trees and blobs to merge. All new objects are written with `write_object`. `diff_resource_cache` is used to detect
+/// renames, and `blob_merge` merges the content of files that were changed on both sides.
+///
+/// ### Deviation
+///
+/// * Symbolic links and submodules that were changed on both sides always conflict, keeping the version of *ours*.
+/// * Conflicts are only classified, the messages that `git` prints for them aren't produced.
+#[allow(clippy::too_many_arguments)]
+pub fn tree<E>(
+    base: &oid,
+    ours: &oid,
+    theirs: &oid,
+    labels: Labels<'_>,
+    objects: &impl gix_object::FindObjectOrHeader,
+    mut write_object: impl FnMut(&dyn gix_object::WriteTo) -> Result<ObjectId, E>,
+    diff_resource_cache: &mut gix_diff::blob::Platform,
+    blob_merge: &mut crate::blob::Platform,
+    options: Options,
+) -> Result<Outcome, Error>
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+{
+    let mut buf = Vec::new();
+    let base_items = flatten(base, objects, &mut buf)?;
+    let ours_items = flatten(ours, objects, &mut buf)?;
+    let theirs_items = flatten(theirs, objects, &mut buf)?;
+
+    let (ours_renames, theirs_renames) = match options.rewrites {
+        Some(rewrites) => (
+            renames::files(&base_items, &ours_items, rewrites, objects, diff_resource_cache)?,
+            renames::files(&base_items, &theirs_items, rewrites, objects, diff_resource_cache)?,
+        ),
+        None => Default::default(),
+    };
+    let (mut ours_dirs, mut theirs_dirs) = match options.directory_renames {
+        DirectoryRenames::Disabled => Default::default(),
+        DirectoryRenames::Apply | DirectoryRenames::Conflict => (
+            renames::directories(&ours_items, &ours_renames),
+            renames::directories(&theirs_items, &theirs_renames),
+        ),
+    };
+    // A directory that was renamed by both sides doesn't take anything along, its files are merged like any other.
+    let renamed_by_both: Vec<_> = ours_dirs
+        .keys()
+        .filter(|dir| theirs_dirs.contains_key(*dir))
+        .cloned()
+        .collect();
+    for dir in renamed_by_both {
+        ours_dirs.remove(&dir);
+        theirs_dirs.remove(&dir);
+    }
+
+    let mut claims = BTreeMap::<BString, Vec<Claim>>::new();
+    let mut merge = Merge {
+        labels,
+        objects,
+        write_object: &mut |object| write_object(object).map_err(|err| Error::WriteObject(err.into())),
+        blob_merge,
+        directory_renames: options.directory_renames,
+        written: Default::default(),
+        buf,
+        occupied: base_items
+            .keys()
+            .chain(ours_items.keys())
+            .chain(theirs_items.keys())
+            .cloned()
+            .collect(),
+        tree: Default::default(),
+        entries: Vec::new(),
+        conflicts: Vec::new(),
+    };
+
+    for (path, base_item) in &base_items {
+        let ours = Fate::of(path, &ours_items, &ours_renames, &theirs_dirs);
+        let theirs = Fate::of(path, &theirs_items, &theirs_renames, &ours_dirs);
+        match (ours, theirs) {
+            (
+                Fate::Renamed {
+                    to: ours_destination,
+                    item: ours,
+                    ..
+                },
+                Fate::Renamed {
+                    to: theirs_destination,
+                    item: theirs,
+                    ..
+                },
+            ) if ours_destination != theirs_destination => {
+                let (ours, theirs) = if file_type(ours.mode) == file_type(theirs.mode) {
+                    let merged = merge.content(path.as_ref(), Some(*base_item), ours, theirs)?.0;
+                    (merged, merged)
+                } else {
+                    (ours, theirs)
+                };
+                merge.stage(path, Stage::Base, *base_item);
+                merge.conflicts.push(Conflict::RenameRenameOneToTwo {
+                    source: path.clone(),
+                    ours: ours_destination.clone(),
+                    theirs: theirs_destination.clone(),
+                });
+                for (side, destination, merged) in [
+                    (Side::Ours, ours_destination, ours),
+                    (Side::Theirs, theirs_destination, theirs),
+                ] {
+                    claims
+                        .entry(destination)
+                        .or_default()
+                        .push(Claim::RenameRenameOneToTwo {
+                            side,
+                            source: path.clone(),
+                            merged,
+                        });
+                }
+            }
+            (Fate::Renamed { to, item, .. }, Fate::Deleted) | (Fate::Deleted, Fate::Renamed { to, item, .. }) => {
+                let side = if ours_renames.contains_key(path) {
+                    Side::Ours
+                } else {
+                    Side::Theirs
+                };
+                claims.entry(to).or_default().push(Claim::RenameDelete {
+                    side,
+                    source: path.clone(),
+                    base: *base_item,
+                    renamed: item,
+                });
+            }
+            (ours, theirs) => {
+                let (to, renamed, moved) = match (&ours, &theirs) {
+                    (Fate::Renamed { to, .. }, Fate::Renamed { .. }) => (to.clone(), None, None),
+                    (Fate::Renamed { to, moved, .. }, _) => (
+                        to.clone(),
+                        Some((Side::Ours, path.clone())),
+                        moved.clone().map(|moved| (Side::Ours, moved)),
+                    ),
+                    (_, Fate::Renamed { to, moved, .. }) => (
+                        to.clone(),
+                        Some((Side::Theirs, path.clone())),
+                        moved.clone().map(|moved| (Side::Theirs, moved)),
+                    ),
+                    _ => (path.clone(), None, None),
+                };
+                claims.entry(to).or_default().push(Claim::Merge {
+                    base: Some(*base_item),
+                    ours: ours.item(),
+                    theirs: theirs.item(),
+                    renamed,
+                    moved,
+                });
+            }
+        }
+    }
+
+    let mut added = BTreeMap::<BString, (Option<Item>, Option<Item>, Option<(Side, BString)>)>::new();
+    for (side, items, renames, other_dirs) in [
+        (Side::Ours, &ours_items, &ours_renames, &theirs_dirs),
+        (Side::Theirs, &theirs_items, &theirs_renames, &ours_dirs),
+    ] {
+        let destinations: BTreeSet<_> = renames.values().collect();
+        for (path, item) in items
+            .iter()
+            .filter(|(path, _)| !base_items.contains_key(*path) && !destinations.contains(path))
+        {
+            let (to, moved) = match renames::relocate(path.as_ref(), other_dirs) {
+                Some(to) => (to, Some((side, path.clone()))),
+                None => (path.clone(), None),
+            };
+            let (ours, theirs, previously_moved) = added.entry(to).or_default();
+            match side {
+                Side::Ours => *ours = Some(*item),
+                Side::Theirs => *theirs = Some(*item),
+            }
+            if previously_moved.is_none() {
+                *previously_moved = moved;
+            }
+        }
+    }
+    for (path, (ours, theirs, moved)) in added {
+        claims.entry(path).or_default().push(Claim::Merge {
+            base: None,
+            ours,
+            theirs,
+            renamed: None,
+            moved,
+        });
+    }
+
+    merge.occupied.extend(claims.keys().cloned());
+    for (path, mut claims) in claims {
+        if claims.len() == 1 {
+            merge.resolve(path, claims.pop().expect("one claim"))?;
+        } else {
+            merge.resolve_collision(path, claims)?;
+        }
+    }
+    merge.move_files_out_of_the_way_of_directories(&ours_items);
+
+    let tree = write_tree(&merge.tree, merge.write_object)?;
+    let mut entries = merge.entries;
+    entries.sort();
+    Ok(Outcome {
+        tree,
+        conflicts: merge.conflicts,
+        entries,
+    })
+}
+
+/// What happened to a path of the base on one side.
+enum Fate {
+    /// The path is still present, possibly with changes.
+    Kept(Item),
+    /// The path was deleted.
+    Deleted,
+    /// The path was renamed to `to`, with `moved` being the original destination if it was moved
+    /// into a directory renamed by the other side.
+    Renamed {
+        to: BString,
+        item: Item,
+        moved: Option<BString>,
+    },
+}
+
+impl Fate {
+    fn of(
+        path: &BString,
+        items: &Items,
+        renames: &BTreeMap<BString, BString>,
+        other_dirs: &BTreeMap<BString, BString>,
+    ) -> Self {
+        match renames.get(path) {
+            Some(destination) => {
+                let item = items[destination];
+                match renames::relocate(destination.as_ref(), other_dirs) {
+                    Some(to) => Fate::Renamed {
+                        to,
+                        item,
+                        moved: Some(destination.clone()),
+                    },
+                    None => Fate::Renamed {
+                        to: destination.clone(),
+                        item,
+                        moved: None,
+                    },
+                }
+            }
+            None => items.get(path).copied().map_or(Fate::Deleted, Fate::Kept),
+        }
+    }
+
+    fn item(&self) -> Option<Item> {
+        match self {
+            Fate::Kept(item) | Fate::Renamed { item, .. } => Some(*item),
+            Fate::Deleted => None,
+        }
+    }
+}
+
+/// The version that one or both sides want to see at a path of the merged tree.
+enum Claim {
+    /// Merge the three versions of a path that may have been renamed or moved into a renamed directory.
+    Merge {
+        base: Option<Item>,
+        ours: Option<Item>,
+        theirs: Option<Item>,
+        /// The side that renamed the file, and its path in the base.
+        renamed: Option<(Side, BString)>,
+        /// The side that added the file to a directory renamed by the other side, and the path it was added at.
+        moved: Option<(Side, BString)>,
+    },
+    /// A file renamed by `side` from `source`, but deleted by the other side.
+    RenameDelete {
+        side: Side,
+        source: BString,
+        base: Item,
+        renamed: Item,
+    },
+    /// The destination of `side` for a file that both sides renamed differently, with the merged content.
+    RenameRenameOneToTwo { side: Side, source: BString, merged: Item },
+}
+
+impl Claim {
+    /// The side that claims the path all by itself, if any.
+    fn side(&self) -> Option<Side> {
+        match self {
+            Claim::Merge {
+                base,
+                ours,
+                theirs,
+                renamed,
+                moved,
+            } => renamed
+                .as_ref()
+                .or(moved.as_ref())
+                .map(|(side, _)| *side)
+                .or(match (base, ours, theirs) {
+                    (None, Some(_), None) => Some(Side::Ours),
+                    (None, None, Some(_)) => Some(Side::Theirs),
+                    _ => None,
+                }),
+            Claim::RenameDelete { side, .. } | Claim::RenameRenameOneToTwo { side, .. } => Some(*side),
+        }
+    }
+
+    /// The path that the claiming side renamed to the claimed path, if any.
+    fn renamed_from(&self) -> Option<&BString> {
+        match self {
+            Claim::Merge { renamed, .. } => renamed.as_ref().map(|(_, source)| source),
+            Claim::RenameDelete { source, .. } | Claim::RenameRenameOneToTwo { source, .. } => Some(source),
+        }
+    }
+}
+
+/// The state of the merge, along with everything needed to merge individual paths.
+struct Merge<'a, Find> {
+    labels: Labels<'a>,
+    objects: &'a Find,
+    write_object: &'a mut dyn FnMut(&dyn gix_object::WriteTo) -> Result<ObjectId, Error>,
+    blob_merge: &'a mut crate::blob::Platform,
+    directory_renames: DirectoryRenames,
+    /// Blobs written during the merge, kept to be able to merge them again without reading them back.
+    written: HashMap<ObjectId, Vec<u8>>,
+    buf: Vec<u8>,
+    /// All paths known to any side or the merge result, to be able to find unused paths.
+    occupied: BTreeSet<BString>,
+    tree: BTreeMap<BString, Item>,
+    entries: Vec<Entry>,
+    conflicts: Vec<Conflict>,
+}
+
+impl<'a, Find> Merge<'a, Find>
+where
+    Find: gix_object::Find,
+{
+    fn resolve(&mut self, path: BString, claim: Claim) -> Result<(), Error> {
+        match claim {
+            Claim::Merge {
+                base,
+                ours,
+                theirs,
+                renamed: _,
+                moved,
+            } => {
+                let clean = self.versions(&path, base, ours, theirs)?;
+                if let (Some((added_by, original)), DirectoryRenames::Conflict) = (moved, self.directory_renames) {
+                    if clean {
+                        if let Some(item) = self.tree.get(&path).copied() {
+                            self.entries.retain(|entry| entry.path != path);
+                            self.stage(&path, added_by.stage(), item);
+                        }
+                    }
+                    self.conflicts.push(Conflict::DirectoryRename {
+                        path: original,
+                        new_path: path,
+                        added_by,
+                    });
+                }
+            }
+            Claim::RenameDelete {
+                side,
+                source,
+                base,
+                renamed,
+            } => {
+                self.tree.insert(path.clone(), renamed);
+                self.stage(&path, Stage::Base, base);
+                self.stage(&path, side.stage(), renamed);
+                self.conflicts.push(Conflict::RenameDelete {
+                    source,
+                    destination: path,
+                    renamed_by: side,
+                });
+            }
+            Claim::RenameRenameOneToTwo {
+                side,
+                source: _,
+                merged,
+            } => {
+                self.tree.insert(path.clone(), merged);
+                self.stage(&path, side.stage(), merged);
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolve multiple `claims` for the same `path` by merging each of them on their own, and then merging the results
+    /// of each side without a base version.
+    fn resolve_collision(&mut self, path: BString, claims: Vec<Claim>) -> Result<(), Error> {
+        let (mut ours, mut theirs) = (None, None);
+        let (mut ours_source, mut theirs_source) = (None, None);
+        for claim in claims {
+            let side = claim.side();
+            let source = claim.renamed_from().cloned();
+            let item = self.contribution(path.as_ref(), claim)?;
+            let (version, renamed_from) = match side {
+                Some(Side::Ours) => (&mut ours, &mut ours_source),
+                Some(Side::Theirs) => (&mut theirs, &mut theirs_source),
+                None if ours.is_none() => (&mut ours, &mut ours_source),
+                None => (&mut theirs, &mut theirs_source),
+            };
+            if version.is_none() {
+                *version = item;
+                *renamed_from = source;
+            }
+        }
+        let clean = self.versions(&path, None, ours, theirs)?;
+        if let (false, Some(ours_source), Some(theirs_source)) = (clean, ours_source, theirs_source) {
+            if let Some(Conflict::AddAdd { .. }) = self.conflicts.last() {
+                self.conflicts.pop();
+                self.conflicts.push(Conflict::RenameRenameTwoToOne {
+                    ours_source,
+                    theirs_source,
+                    destination: path,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the version of `path` that `claim` would produce, ignoring all conflicts.
+    fn contribution(&mut self, path: &BStr, claim: Claim) -> Result<Option<Item>, Error> {
+        Ok(match claim {
+            Claim::Merge { base, ours, theirs, .. } => {
+                if ours == theirs || theirs == base {
+                    ours
+                } else if ours == base {
+                    theirs
+                } else {
+                    match (ours, theirs) {
+                        (Some(ours), Some(theirs)) if file_type(ours.mode) == file_type(theirs.mode) => {
+                            Some(self.content(path, base, ours, theirs)?.0)
+                        }
+                        (ours, theirs) => ours.or(theirs),
+                    }
+                }
+            }
+            Claim::RenameDelete { renamed, .. } => Some(renamed),
+            Claim::RenameRenameOneToTwo { merged, .. } => Some(merged),
+        })
+    }
+
+    /// Merge the `base`, `ours` and `theirs` version of `path`, record the result and return `true` if it was clean.
+    fn versions(
+        &mut self,
+        path: &BString,
+        base: Option<Item>,
+        ours: Option<Item>,
+        theirs: Option<Item>,
+    ) -> Result<bool, Error> {
+        if ours == theirs || theirs == base {
+            self.clean(path, ours);
+            return Ok(true);
+        }
+        if ours == base {
+            self.clean(path, theirs);
+            return Ok(true);
+        }
+        let (ours, theirs) = match (ours, theirs) {
+            (Some(ours), Some(theirs)) => (ours, theirs),
+            (ours, theirs) => {
+                let (deleted_by, kept_by, kept) = match (ours, theirs) {
+                    (Some(ours), _) => (Side::Theirs, Side::Ours, ours),
+                    (_, Some(theirs)) => (Side::Ours, Side::Theirs, theirs),
+                    (None, None) => unreachable!("both sides deleting a path is handled above"),
+                };
+                self.tree.insert(path.clone(), kept);
+                if let Some(base) = base {
+                    self.stage(path, Stage::Base, base);
+                }
+                self.stage(path, kept_by.stage(), kept);
+                self.conflicts.push(Conflict::ModifyDelete {
+                    path: path.clone(),
+                    deleted_by,
+                });
+                return Ok(false);
+            }
+        };
+
+        if file_type(ours.mode) != file_type(theirs.mode) {
+            self.distinct_types(path, base, ours, theirs);
+            return Ok(false);
+        }
+        let (merged, content_is_clean, mode_is_clean) = self.content(path.as_ref(), base, ours, theirs)?;
+        if content_is_clean && mode_is_clean {
+            self.clean(path, Some(merged));
+            return Ok(true);
+        }
+        self.tree.insert(path.clone(), merged);
+        if let Some(base) = base {
+            self.stage(path, Stage::Base, base);
+        }
+        self.stage(path, Stage::Ours, ours);
+        self.stage(path, Stage::Theirs, theirs);
+        self.conflicts.push(match (content_is_clean, base) {
+            (false, Some(_)) => Conflict::Content { path: path.clone() },
+            (false, None) => Conflict::AddAdd { path: path.clone() },
+            (true, _) => Conflict::Mode {
+                path: path.clone(),
+                ours: ours.mode,
+                theirs: theirs.mode,
+            },
+        });
+        Ok(false)
+    }
+
+    /// Merge the content and mode of `ours` and `theirs`, which must be of the same type, and return the merged version
+    /// along with `true` if the content and mode could be merged cleanly, respectively.
+    fn content(
+        &mut self,
+        path: &BStr,
+        base: Option<Item>,
+        ours: Item,
+        theirs: Item,
+    ) -> Result<(Item, bool, bool), Error> {
+        let base_mode = base.map(|base| base.mode);
+        let (mode, mode_is_clean) = if ours.mode == theirs.mode || Some(ours.mode) == base_mode {
+            (theirs.mode, true)
+        } else {
+            (ours.mode, Some(theirs.mode) == base_mode)
+        };
+
+        let base_id = base.map(|base| base.id);
+        let (id, content_is_clean) = if ours.id == theirs.id || Some(theirs.id) == base_id {
+            (ours.id, true)
+        } else if Some(ours.id) == base_id {
+            (theirs.id, true)
+        } else if ours.mode.is_blob() {
+            let ancestor = match base {
+                Some(base) if base.mode.is_blob() => self.blob(base.id)?,
+                _ => Vec::new(),
+            };
+            let current = self.blob(ours.id)?;
+            let other = self.blob(theirs.id)?;
+            let mut out = Vec::new();
+            let resolution = self.blob_merge.merge(
+                &mut out,
+                path,
+                Input {
+                    ancestor: &ancestor,
+                    current: &current,
+                    other: &other,
+                },
+                self.labels,
+                self.objects,
+            )?;
+            let id = (self.write_object)(&gix_object::BlobRef { data: &out })?;
+            self.written.insert(id, out);
+            (id, resolution == Resolution::Complete)
+        } else {
+            (ours.id, false)
+        };
+        Ok((Item { mode, id }, content_is_clean, mode_is_clean))
+    }
+
+    /// Keep `ours` and `theirs` of different types by moving the regular file, or both if there is none, to a new path.
+    fn distinct_types(&mut self, path: &BString, base: Option<Item>, ours: Item, theirs: Item) {
+        let (move_ours, move_theirs) = match (ours.mode.is_blob(), theirs.mode.is_blob()) {
+            (true, _) => (true, false),
+            (false, true) => (false, true),
+            (false, false) => (true, true),
+        };
+        let ours_path = if move_ours {
+            self.unused_path(path.as_ref(), Side::Ours)
+        } else {
+            path.clone()
+        };
+        let theirs_path = if move_theirs {
+            self.unused_path(path.as_ref(), Side::Theirs)
+        } else {
+            path.clone()
+        };
+        for (side, item, item_path) in [(Side::Ours, ours, &ours_path), (Side::Theirs, theirs, &theirs_path)] {
+            self.tree.insert(item_path.clone(), item);
+            if let Some(base) = base.filter(|base| file_type(base.mode) == file_type(item.mode)) {
+                self.stage(item_path, Stage::Base, base);
+            }
+            self.stage(item_path, side.stage(), item);
+        }
+        self.conflicts.push(Conflict::DistinctTypes {
+            path: path.clone(),
+            ours: ours_path,
+            theirs: theirs_path,
+        });
+    }
+
+    /// Move all files of the merged tree that are in the way of a directory to an unused path.
+    fn move_files_out_of_the_way_of_directories(&mut self, ours: &Items) {
+        let files_in_the_way: Vec<_> = self
+            .tree
+            .keys()
+            .filter(|path| {
+                let mut dir = (*path).clone();
+                dir.push_byte(b'/');
+                matches!(self.tree.range(dir.clone()..).next(), Some((path, _)) if path.starts_with(&dir))
+            })
+            .cloned()
+            .collect();
+        for path in files_in_the_way {
+            let file_by = if ours.contains_key(&path) {
+                Side::Ours
+            } else {
+                Side::Theirs
+            };
+            let new_path = self.unused_path(path.as_ref(), file_by);
+            let item = self.tree.remove(&path).expect("present");
+            self.tree.insert(new_path.clone(), item);
+            for entry in self.entries.iter_mut().filter(|entry| entry.path == path) {
+                entry.path = new_path.clone();
+                if entry.stage == Stage::Unconflicted {
+                    entry.stage = file_by.stage();
+                }
+            }
+            self.conflicts.push(Conflict::FileDirectory {
+                path,
+                new_path,
+                file_by,
+            });
+        }
+    }
+
+    /// Return a path like `<path>~<label>` which isn't used by anything else, and mark it as used.
+    fn unused_path(&mut self, path: &BStr, side: Side) -> BString {
+        let label = match side {
+            Side::Ours => self.labels.current.unwrap_or("ours".into()),
+            Side::Theirs => self.labels.other.unwrap_or("theirs".into()),
+        };
+        let mut out = path.to_owned();
+        out.push_byte(b'~');
+        out.extend(label.iter().map(|b| if *b == b'/' { b'_' } else { *b }));
+        let len = out.len();
+        let mut suffix = 0;
+        while self.occupied.contains(&out) || self.tree.contains_key(&out) {
+            out.truncate(len);
+            out.push_str(format!("_{suffix}"));
+            suffix += 1;
+        }
+        self.occupied.insert(out.clone());
+        out
+    }
+
+    fn blob(&mut self, id: ObjectId) -> Result<Vec<u8>, Error> {
+        Ok(match self.written.get(&id) {
+            Some(data) => data.clone(),
+            None => self.objects.find_blob(&id, &mut self.buf)?.data.to_vec(),
+        })
+    }
+
+    fn clean(&mut self, path: &BString, item: Option<Item>) {
+        if let Some(item) = item {
+            self.tree.insert(path.clone(), item);
+            self.stage(path, Stage::Unconflicted, item);
+        }
+    }
+
+    fn stage(&mut self, path: &BString, stage: Stage, item: Item) {
+        self.entries.push(Entry {
+            path: path.clone(),
+            stage,
+            mode: item.mode,
+            id: item.id,
+        });
+    }
+}
+
+/// Return the kind of an entry without distinguishing between executable and non-executable files.
+fn file_type(mode: EntryMode) -> EntryKind {
+    match mode.kind() {
+        EntryKind::BlobExecutable => EntryKind::Blob,
+        kind => kind,
+    }
+}
+
+/// Return all non-tree entries of the tree with `id`, recursively.
+fn flatten(id: &oid, objects: &impl gix_object::Find, buf: &mut Vec<u8>) -> Result<Items, Error> {
+    let mut out = Items::new();
+    if id.to_owned().is_empty_tree() {
+        return Ok(out);
+    }
+    let mut queue = vec![(id.to_owned(), BString::default())];
+    while let Some((id, prefix)) = queue.pop() {
+        for entry in objects.find_tree(&id, buf)?.entries {
+            let mut path = prefix.clone();
+            if !path.is_empty() {
+                path.push_byte(b'/');
+            }
+            path.extend_from_slice(entry.filename);
+            if entry.mode.is_tree() {
+                queue.push((entry.oid.to_owned(), path));
+            } else {
+                out.insert(
+                    path,
+                    Item {
+                        mode: entry.mode,
+                        id: entry.oid.to_owned(),
+                    },
+                );
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Write `items` as nested trees and return the id of the root tree.
+fn write_tree(
+    items: &BTreeMap<BString, Item>,
+    write_object: &mut dyn FnMut(&dyn gix_object::WriteTo) -> Result<ObjectId, Error>,
+) -> Result<ObjectId, Error> {
+    #[derive(Default)]
+    struct Dir {
+        files: Vec<gix_object::tree::Entry>,
+        dirs: BTreeMap<BString, Dir>,
+    }
+
+    fn write(
+        dir: Dir,
+        write_object: &mut dyn FnMut(&dyn gix_object::WriteTo) -> Result<ObjectId, Error>,
+    ) -> Result<ObjectId, Error> {
+        let mut entries = dir.files;
+        for (filename, dir) in dir.dirs {
+            entries.push(gix_object::tree::Entry {
+                mode: EntryKind::Tree.into(),
+                filename,
+                oid: write(dir, write_object)?,
+            });
+        }
+        entries.sort();
+        write_object(&gix_object::Tree { entries })
+    }
+
+    let mut root = Dir::default();
+    for (path, item) in items {
+        let mut components: Vec<_> = path.split_str("/").collect();
+        let filename = components.pop().expect("paths are never empty");
+        let dir = components.into_iter().fold(&mut root, |dir, component| {
+            dir.dirs.entry(component.into()).or_default()
+        });
+        dir.files.push(gix_object::tree::Entry {
+            mode: item.mode,
+            filename: filename.into(),
+            oid: item.id,
+        });
+    }
+    write(root, write_object)
+}
@@ -0,0 +1,213 @@
+//! Merge three trees like `git merge-ort` does, detecting renames of files and directories on either side, and classify
+//! the conflicts that couldn't be resolved so they can be presented to the user or recorded in the index.
+use bstr::BString;
+use gix_hash::ObjectId;
+use gix_object::tree::EntryMode;
+
+pub(super) mod function;
+mod renames;
+
+/// Decide what to do with paths added by one side into a directory that was renamed by the other side, as configured
+/// with `merge.directoryRenames`.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum DirectoryRenames {
+    /// Don't detect renamed directories, so added paths stay where they were added, which is `merge.directoryRenames=false`.
+    Disabled,
+    /// Move added paths into the renamed directory and consider it resolved, which is `merge.directoryRenames=true`.
+    Apply,
+    /// Move added paths into the renamed directory, but report a [`Conflict::DirectoryRename`] so the user can confirm
+    /// the new location, which is `merge.directoryRenames=conflict` and the default.
+    #[default]
+    Conflict,
+}
+
+/// Options for use in [`tree()`](crate::tree()).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Options {
+    /// The way to detect renamed files on each side, or `None` to not detect renames at all, which also turns off the
+    /// detection of renamed directories.
+    ///
+    /// Copies are never tracked, just like in `git`.
+    pub rewrites: Option<gix_diff::Rewrites>,
+    /// What to do with paths added into directories that were renamed by the other side.
+    pub directory_renames: DirectoryRenames,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            rewrites: Some(Default::default()),
+            directory_renames: Default::default(),
+        }
+    }
+}
+
+/// One side of the merge.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Side {
+    /// The *current* (or *ours*) side.
+    Ours,
+    /// The *other* (or *theirs*) side.
+    Theirs,
+}
+
+impl Side {
+    /// Return the stage at which entries of this side are recorded in the index when they conflict.
+    pub fn stage(&self) -> gix_index::entry::Stage {
+        match self {
+            Side::Ours => gix_index::entry::Stage::Ours,
+            Side::Theirs => gix_index::entry::Stage::Theirs,
+        }
+    }
+}
+
+/// A conflict that couldn't be resolved automatically, typed after the kinds of conflicts that `git merge-ort` reports.
+///
+/// All paths are relative to the root of the merged tree.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Conflict {
+    /// Both sides changed `path` in ways that couldn't be merged, leaving conflict markers in its merged content.
+    ///
+    /// If it was renamed by one side, `path` is the destination and the base version is the one of the source.
+    Content {
+        /// The path of the conflicting file.
+        path: BString,
+    },
+    /// Both sides added `path` with different content, or renamed another file to `path` while the other side added it.
+    AddAdd {
+        /// The path added by both sides.
+        path: BString,
+    },
+    /// `path` was modified by one side, but deleted by the side in `deleted_by`. The modified version is kept.
+    ModifyDelete {
+        /// The path of the modified file.
+        path: BString,
+        /// The side that deleted the file.
+        deleted_by: Side,
+    },
+    /// `source` was renamed to `destination` by the side in `renamed_by`, but deleted by the other side.
+    /// The renamed version is kept at `destination`.
+    RenameDelete {
+        /// The path of the file before it was renamed.
+        source: BString,
+        /// The path the file was renamed to.
+        destination: BString,
+        /// The side that renamed the file.
+        renamed_by: Side,
+    },
+    /// `source` was renamed to `ours` by our side and to `theirs` by their side, which is a *rename/rename(1to2)*.
+    ///
+    /// The merged content is kept at both destinations.
+    RenameRenameOneToTwo {
+        /// The path of the file before it was renamed.
+        source: BString,
+        /// The path our side renamed the file to.
+        ours: BString,
+        /// The path their side renamed the file to.
+        theirs: BString,
+    },
+    /// Our side renamed `ours_source`, and their side renamed `theirs_source`, both to `destination`,
+    /// which is a *rename/rename(2to1)*.
+    ///
+    /// The content of each renamed file is merged with the version of the other side first, and both are merged again
+    /// without a base version at `destination`.
+    RenameRenameTwoToOne {
+        /// The path of the file our side renamed to `destination`.
+        ours_source: BString,
+        /// The path of the file their side renamed to `destination`.
+        theirs_source: BString,
+        /// The path both sides renamed a file to.
+        destination: BString,
+    },
+    /// `path` was added by the side in `added_by` to a directory that was renamed by the other side, so it was moved
+    /// to `new_path` within the renamed directory.
+    ///
+    /// This is only reported with [`DirectoryRenames::Conflict`], as the new location is merely a suggestion.
+    DirectoryRename {
+        /// The path that was added.
+        path: BString,
+        /// The path the added file was moved to.
+        new_path: BString,
+        /// The side that added the file.
+        added_by: Side,
+    },
+    /// Both sides changed the mode of `path` differently, so it's unclear whether or not it should be executable.
+    /// The mode of our side is kept.
+    Mode {
+        /// The path of the file with conflicting modes.
+        path: BString,
+        /// The mode of our side.
+        ours: EntryMode,
+        /// The mode of their side.
+        theirs: EntryMode,
+    },
+    /// Both sides changed `path` to entries of different types, like a file and a symbolic link, which can't be merged.
+    ///
+    /// To keep both, the regular file is moved to `<path>~<label>`, or both of them if none is a regular file.
+    DistinctTypes {
+        /// The path that had different types on each side.
+        path: BString,
+        /// The path at which the entry of our side is kept.
+        ours: BString,
+        /// The path at which the entry of their side is kept.
+        theirs: BString,
+    },
+    /// `path` is a file on the side in `file_by`, but a directory on the other side, so the file was moved to `new_path`.
+    FileDirectory {
+        /// The path that is both a file and a directory.
+        path: BString,
+        /// The path the file was moved to.
+        new_path: BString,
+        /// The side with the file.
+        file_by: Side,
+    },
+}
+
+/// An entry of the merge result as it would be recorded in the index.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Entry {
+    /// The path of the entry relative to the root of the merged tree.
+    pub path: BString,
+    /// The stage of the entry, which is [`Unconflicted`](gix_index::entry::Stage::Unconflicted) for all paths that could be merged.
+    pub stage: gix_index::entry::Stage,
+    /// The mode of the entry.
+    pub mode: EntryMode,
+    /// The id of the object the entry points to.
+    pub id: ObjectId,
+}
+
+/// The result of [`tree()`](crate::tree()).
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The merged tree, which is fully resolved if there are no [conflicts](Self::conflicts).
+    ///
+    /// Otherwise, conflicting files contain conflict markers, and entries that couldn't be merged are kept
+    /// at the locations mentioned in the respective conflicts.
+    pub tree: ObjectId,
+    /// All conflicts that couldn't be resolved automatically.
+    pub conflicts: Vec<Conflict>,
+    /// All entries of the merge result sorted by path and stage, just like `git` would record them in the index, with
+    /// the base, our and their version of conflicting paths at stages 1, 2 and 3 respectively.
+    pub entries: Vec<Entry>,
+}
+
+impl Outcome {
+    /// Return `true` if there is at least one conflict.
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+}
+
+/// The error returned by [`tree()`](crate::tree()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindObject(#[from] gix_object::find::existing_object::Error),
+    #[error("Could not detect renamed files")]
+    TrackRewrites(#[source] Box<gix_diff::rewrites::tracker::emit::Error>),
+    #[error(transparent)]
+    MergeBlob(#[from] crate::blob::platform::merge::Error),
+    #[error("Could not write the merged object")]
+    WriteObject(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+}
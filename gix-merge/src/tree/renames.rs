@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use gix_diff::tree::visit::Change;
+use gix_hash::ObjectId;
+use gix_object::tree::EntryMode;
+
+use crate::tree::Error;
+
+/// A non-tree entry of a tree, like a file, symbolic link or submodule.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(super) struct Item {
+    pub mode: EntryMode,
+    pub id: ObjectId,
+}
+
+/// All non-tree entries of a tree, recursively, by their path.
+pub(super) type Items = BTreeMap<BString, Item>;
+
+/// Return a map of all paths of `base` that were renamed in `side`, to their new path, using `rewrites` to find renames
+/// among the files that were deleted and added. Only renames are tracked, never copies.
+pub(super) fn files(
+    base: &Items,
+    side: &Items,
+    rewrites: gix_diff::Rewrites,
+    objects: &impl gix_object::FindObjectOrHeader,
+    diff_resource_cache: &mut gix_diff::blob::Platform,
+) -> Result<BTreeMap<BString, BString>, Error> {
+    let mut tracker = gix_diff::rewrites::Tracker::<Change>::new(gix_diff::Rewrites {
+        copies: None,
+        ..rewrites
+    });
+    for (path, item) in base.iter().filter(|(path, _)| !side.contains_key(*path)) {
+        tracker.try_push_change(
+            Change::Deletion {
+                entry_mode: item.mode,
+                oid: item.id,
+            },
+            path.as_ref(),
+        );
+    }
+    for (path, item) in side.iter().filter(|(path, _)| !base.contains_key(*path)) {
+        tracker.try_push_change(
+            Change::Addition {
+                entry_mode: item.mode,
+                oid: item.id,
+            },
+            path.as_ref(),
+        );
+    }
+
+    let mut renames = BTreeMap::new();
+    tracker
+        .emit(
+            |destination, source| {
+                if let Some(source) = source {
+                    renames.insert(source.location.to_owned(), destination.location.to_owned());
+                }
+                gix_diff::tree::visit::Action::Continue
+            },
+            diff_resource_cache,
+            objects,
+            |_push| Ok::<_, std::convert::Infallible>(()),
+        )
+        .map_err(|err| Error::TrackRewrites(Box::new(err)))?;
+    Ok(renames)
+}
+
+/// Return a map of directories of `base` that don't exist in `side` anymore, to the directory they were renamed to,
+/// as indicated by the `renames` of their files in `side`.
+///
+/// Like `git`, each rename of a file votes for its directory, and all its parent directories with the same name, to be
+/// renamed to the directory of its destination. The directory with the most votes wins, and if there is a tie,
+/// the directory isn't considered renamed.
+pub(super) fn directories(side: &Items, renames: &BTreeMap<BString, BString>) -> BTreeMap<BString, BString> {
+    let mut votes = BTreeMap::<&BStr, BTreeMap<&BStr, usize>>::new();
+    for (source, destination) in renames {
+        let (mut old_dir, mut new_dir) = (parent(source.as_ref()), parent(destination.as_ref()));
+        while let Some(old) = old_dir {
+            let new = new_dir.unwrap_or_default();
+            if old == new {
+                break;
+            }
+            if !contains_dir(side, old) {
+                *votes.entry(old).or_default().entry(new).or_default() += 1;
+            }
+            if file_name(old) != file_name(new) {
+                break;
+            }
+            old_dir = parent(old);
+            new_dir = parent(new);
+        }
+    }
+
+    votes
+        .into_iter()
+        .filter_map(|(old, candidates)| {
+            let max = candidates.values().copied().max()?;
+            let mut winners = candidates.into_iter().filter(|(_, count)| *count == max);
+            let (new, _) = winners.next()?;
+            winners.next().is_none().then(|| (old.to_owned(), new.to_owned()))
+        })
+        .collect()
+}
+
+/// Return the path that `path` would have if it was moved along with the closest of its parent directories that was
+/// renamed according to `directories`, or `None` if none of them was renamed.
+pub(super) fn relocate(path: &BStr, directories: &BTreeMap<BString, BString>) -> Option<BString> {
+    let mut dir = parent(path);
+    while let Some(old) = dir {
+        if let Some(new) = directories.get(old) {
+            let rest = &path[old.len() + 1..];
+            let mut out = new.clone();
+            if !out.is_empty() {
+                out.push_byte(b'/');
+            }
+            out.extend_from_slice(rest);
+            return Some(out);
+        }
+        dir = parent(old);
+    }
+    None
+}
+
+fn contains_dir(items: &Items, dir: &BStr) -> bool {
+    let mut prefix = BString::from(dir);
+    prefix.push_byte(b'/');
+    matches!(items.range(prefix.clone()..).next(), Some((path, _)) if path.starts_with(&prefix))
+}
+
+fn parent(path: &BStr) -> Option<&BStr> {
+    path.rfind_byte(b'/').map(|pos| path[..pos].as_bstr())
+}
+
+fn file_name(path: &BStr) -> &BStr {
+    path.rfind_byte(b'/').map_or(path, |pos| path[pos + 1..].as_bstr())
+}
@@ -0,0 +1,115 @@
+use gix_merge::blob::{builtin_driver, Resolution};
+
+#[test]
+fn binary() {
+    assert_eq!(
+        builtin_driver::binary(None),
+        (builtin_driver::binary::Pick::Ours, Resolution::Conflict),
+        "by default it picks ours and marks it as conflict"
+    );
+    assert_eq!(
+        builtin_driver::binary(Some(builtin_driver::binary::ResolveWith::Ancestor)),
+        (builtin_driver::binary::Pick::Ancestor, Resolution::Complete),
+        "Otherwise we can pick anything and it will mark it as complete"
+    );
+    assert_eq!(
+        builtin_driver::binary(Some(builtin_driver::binary::ResolveWith::Ours)),
+        (builtin_driver::binary::Pick::Ours, Resolution::Complete)
+    );
+    assert_eq!(
+        builtin_driver::binary(Some(builtin_driver::binary::ResolveWith::Theirs)),
+        (builtin_driver::binary::Pick::Theirs, Resolution::Complete)
+    );
+}
+
+mod text {
+    use bstr::ByteSlice;
+    use gix_merge::blob::{
+        builtin_driver,
+        builtin_driver::text::{Conflict, ConflictStyle},
+        Resolution,
+    };
+
+    #[test]
+    fn run_baseline() -> crate::Result {
+        let root = gix_testtools::scripted_fixture_read_only("text-baseline.sh")?;
+        let cases = std::fs::read_to_string(root.join("baseline.cases"))?;
+        let mut out = Vec::new();
+        let mut num_cases = 0;
+        for case in cases.lines() {
+            let mut words = case.split(' ');
+            let (Some(name), Some(variant), Some(num_conflicts), None) =
+                (words.next(), words.next(), words.next(), words.next())
+            else {
+                unreachable!("invalid baseline line: {case:?}")
+            };
+            let dir = root.join(name);
+            let conflict = match variant {
+                "merge" => Conflict::Keep {
+                    style: ConflictStyle::Merge,
+                    marker_size: builtin_driver::text::DEFAULT_MARKER_SIZE,
+                },
+                "diff3" => Conflict::Keep {
+                    style: ConflictStyle::Diff3,
+                    marker_size: builtin_driver::text::DEFAULT_MARKER_SIZE,
+                },
+                "union" => Conflict::ResolveWithUnion,
+                "ours" => Conflict::ResolveWithOurs,
+                "theirs" => Conflict::ResolveWithTheirs,
+                unknown => unreachable!("unknown variant: {unknown}"),
+            };
+
+            out.clear();
+            let resolution = builtin_driver::text(
+                &mut out,
+                builtin_driver::text::Labels {
+                    ancestor: Some("base".into()),
+                    current: Some("ours".into()),
+                    other: Some("theirs".into()),
+                },
+                &std::fs::read(dir.join("base"))?,
+                &std::fs::read(dir.join("ours"))?,
+                &std::fs::read(dir.join("theirs"))?,
+                builtin_driver::text::Options {
+                    conflict,
+                    ..Default::default()
+                },
+            );
+            let expected = std::fs::read(dir.join(format!("result-{variant}")))?;
+            assert_eq!(out.as_bstr(), expected.as_bstr(), "{name}: {variant}");
+            assert_eq!(
+                resolution,
+                if num_conflicts == "0" {
+                    Resolution::Complete
+                } else {
+                    Resolution::Conflict
+                },
+                "{name}: {variant}"
+            );
+            num_cases += 1;
+        }
+        assert_eq!(num_cases, 60, "all cases were run");
+        Ok(())
+    }
+
+    #[test]
+    fn marker_size_and_missing_labels() {
+        let mut out = Vec::new();
+        let resolution = builtin_driver::text(
+            &mut out,
+            Default::default(),
+            b"a\nb\nc\n",
+            b"a\nours\nc\n",
+            b"a\ntheirs\nc\n",
+            builtin_driver::text::Options {
+                conflict: Conflict::Keep {
+                    style: ConflictStyle::Diff3,
+                    marker_size: 3,
+                },
+                ..Default::default()
+            },
+        );
+        assert_eq!(resolution, Resolution::Conflict);
+        assert_eq!(out.as_bstr(), "a\n<<<\nours\n|||\nb\n===\ntheirs\n>>>\nc\n");
+    }
+}
@@ -0,0 +1,318 @@
+use bstr::ByteSlice;
+use gix_merge::blob::{
+    builtin_driver::{self, binary::ResolveWith},
+    platform::{DriverChoice, Input, Options},
+    BuiltinDriver, Driver, Platform, Resolution,
+};
+
+#[test]
+fn driver_selection_by_attribute() -> crate::Result {
+    let mut platform = new_platform(Default::default())?;
+    for (path, expected) in [
+        ("set", DriverChoice::BuiltIn(BuiltinDriver::Text)),
+        ("unset", DriverChoice::BuiltIn(BuiltinDriver::Binary)),
+        ("unspecified", DriverChoice::BuiltIn(BuiltinDriver::Text)),
+        ("union", DriverChoice::BuiltIn(BuiltinDriver::Union)),
+        ("binary", DriverChoice::BuiltIn(BuiltinDriver::Binary)),
+        ("ours", DriverChoice::BuiltIn(BuiltinDriver::Ours)),
+        ("custom", DriverChoice::Index(0)),
+        ("with-recursive", DriverChoice::Index(1)),
+        ("missing", DriverChoice::BuiltIn(BuiltinDriver::Text)),
+    ] {
+        assert_eq!(
+            platform.driver_for(path.into(), &gix_object::find::Never)?.driver,
+            expected,
+            "{path}"
+        );
+    }
+    assert_eq!(
+        platform
+            .drivers()
+            .iter()
+            .map(|d| d.name.to_str_lossy())
+            .collect::<Vec<_>>(),
+        ["custom", "with-recursive"],
+        "drivers are sorted by name"
+    );
+    Ok(())
+}
+
+#[test]
+fn driver_selection_with_default_and_virtual_ancestor() -> crate::Result {
+    let mut platform = new_platform(Options {
+        default_driver: Some("union".into()),
+        ..Default::default()
+    })?;
+    let driver_for = |platform: &mut Platform, path: &str| {
+        platform
+            .driver_for(path.into(), &gix_object::find::Never)
+            .map(|selection| selection.driver)
+    };
+    assert_eq!(
+        driver_for(&mut platform, "unspecified")?,
+        DriverChoice::BuiltIn(BuiltinDriver::Union),
+        "merge.default is used if the attribute isn't specified"
+    );
+    assert_eq!(
+        driver_for(&mut platform, "set")?,
+        DriverChoice::BuiltIn(BuiltinDriver::Text),
+        "a set attribute always means the text driver"
+    );
+
+    platform.options.default_driver = Some("custom".into());
+    assert_eq!(driver_for(&mut platform, "unspecified")?, DriverChoice::Index(0));
+
+    assert_eq!(driver_for(&mut platform, "with-recursive")?, DriverChoice::Index(1));
+    platform.options.is_virtual_ancestor = true;
+    assert_eq!(
+        driver_for(&mut platform, "with-recursive")?,
+        DriverChoice::Index(0),
+        "virtual ancestors are merged with the recursive driver"
+    );
+    assert_eq!(
+        driver_for(&mut platform, "custom")?,
+        DriverChoice::Index(0),
+        "without a recursive driver, the driver itself is used"
+    );
+    Ok(())
+}
+
+#[test]
+fn marker_size_by_attribute() -> crate::Result {
+    let mut platform = new_platform(Default::default())?;
+    for (path, expected) in [("marker-size", 10), ("invalid-marker-size", 7), ("set", 7)] {
+        assert_eq!(
+            platform.driver_for(path.into(), &gix_object::find::Never)?.marker_size,
+            expected,
+            "{path}"
+        );
+    }
+
+    let mut out = Vec::new();
+    let res = platform.merge(
+        &mut out,
+        "marker-size".into(),
+        Input {
+            ancestor: b"a\n",
+            current: b"b\n",
+            other: b"c\n",
+        },
+        Default::default(),
+        &gix_object::find::Never,
+    )?;
+    assert_eq!(res, Resolution::Conflict);
+    assert_eq!(out.as_bstr(), "<<<<<<<<<<\nb\n==========\nc\n>>>>>>>>>>\n");
+    Ok(())
+}
+
+#[test]
+fn builtin_drivers() -> crate::Result {
+    let mut platform = new_platform(Default::default())?;
+    let input = Input {
+        ancestor: b"a\n",
+        current: b"b\n",
+        other: b"c\n",
+    };
+    let mut out = Vec::new();
+    let mut merge = |platform: &mut Platform, path: &str, input: Input<'_>| -> crate::Result<_> {
+        out.clear();
+        let res = platform.merge(
+            &mut out,
+            path.into(),
+            input,
+            builtin_driver::text::Labels {
+                ancestor: None,
+                current: Some("ours".into()),
+                other: Some("theirs".into()),
+            },
+            &gix_object::find::Never,
+        )?;
+        Ok((res, out.as_bstr().to_owned()))
+    };
+
+    assert_eq!(
+        merge(&mut platform, "set", input)?,
+        (
+            Resolution::Conflict,
+            "<<<<<<< ours\nb\n=======\nc\n>>>>>>> theirs\n".into()
+        )
+    );
+    assert_eq!(
+        merge(&mut platform, "union", input)?,
+        (Resolution::Complete, "b\nc\n".into())
+    );
+    assert_eq!(
+        merge(&mut platform, "ours", input)?,
+        (Resolution::Complete, "b\n".into())
+    );
+    assert_eq!(
+        merge(&mut platform, "unset", input)?,
+        (Resolution::Conflict, "b\n".into()),
+        "binary merges keep our version, but conflict"
+    );
+    assert_eq!(
+        merge(&mut platform, "binary", input)?,
+        (Resolution::Conflict, "b\n".into())
+    );
+
+    let binary_input = Input {
+        ancestor: b"a\0",
+        current: b"b\n",
+        other: b"c\n",
+    };
+    assert_eq!(
+        merge(&mut platform, "union", binary_input)?,
+        (Resolution::Conflict, "b\n".into()),
+        "line-based drivers fall back to the binary driver if any input is binary"
+    );
+
+    platform.options.resolve_binary_with = Some(ResolveWith::Theirs);
+    assert_eq!(
+        merge(&mut platform, "binary", input)?,
+        (Resolution::Complete, "c\n".into())
+    );
+    platform.options.is_virtual_ancestor = true;
+    assert_eq!(
+        merge(&mut platform, "binary", input)?,
+        (Resolution::Complete, "a\n".into()),
+        "binary merges of virtual ancestors take the ancestor"
+    );
+    Ok(())
+}
+
+#[test]
+fn user_defined_driver() -> crate::Result {
+    let mut platform = new_platform(Default::default())?;
+    let input = Input {
+        ancestor: b"ancestor\n",
+        current: b"current\n",
+        other: b"other\n",
+    };
+    let mut out = Vec::new();
+    let res = platform.merge(
+        &mut out,
+        "custom".into(),
+        input,
+        Default::default(),
+        &gix_object::find::Never,
+    )?;
+    assert_eq!(res, Resolution::Complete, "the driver exited with 0");
+    assert_eq!(
+        out.as_bstr(),
+        "path: custom, marker-size: 7\nancestor\ncurrent\nother\n",
+        "the result is read from the file of the current version"
+    );
+
+    out.clear();
+    let res = platform.merge(
+        &mut out,
+        "custom-conflict".into(),
+        input,
+        Default::default(),
+        &gix_object::find::Never,
+    )?;
+    assert_eq!(res, Resolution::Conflict, "the driver exited with 1");
+    assert_eq!(
+        out.as_bstr(),
+        "path: custom-conflict, marker-size: 7\nancestor\ncurrent\nother\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn user_defined_driver_with_escaped_percent_sign() -> crate::Result {
+    let mut platform = new_platform_with_drivers(
+        vec![Driver {
+            name: "custom".into(),
+            command: "echo '%%O %%P %' >%A".into(),
+            ..Default::default()
+        }],
+        Default::default(),
+    )?;
+    let mut out = Vec::new();
+    let res = platform.merge(
+        &mut out,
+        "custom".into(),
+        Input {
+            ancestor: b"ancestor\n",
+            current: b"current\n",
+            other: b"other\n",
+        },
+        Default::default(),
+        &gix_object::find::Never,
+    )?;
+    assert_eq!(res, Resolution::Complete);
+    assert_eq!(
+        out.as_bstr(),
+        "%O %P %\n",
+        "`%%` is a literal `%` which isn't substituted, just like a lone `%`"
+    );
+    Ok(())
+}
+
+#[test]
+fn user_defined_driver_without_command() -> crate::Result {
+    let mut platform = new_platform_with_drivers(
+        vec![Driver {
+            name: "custom".into(),
+            ..Default::default()
+        }],
+        Default::default(),
+    )?;
+    let err = platform
+        .merge(
+            &mut Vec::new(),
+            "custom".into(),
+            Input {
+                ancestor: b"",
+                current: b"",
+                other: b"",
+            },
+            Default::default(),
+            &gix_object::find::Never,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "The merge driver 'custom' for 'custom' has no command configured in merge.custom.driver"
+    );
+    Ok(())
+}
+
+fn new_platform(options: Options) -> crate::Result<Platform> {
+    new_platform_with_drivers(
+        vec![
+            Driver {
+                name: "with-recursive".into(),
+                display_name: "a driver with recursive driver".into(),
+                command: "false".into(),
+                recursive: Some("custom".into()),
+            },
+            Driver {
+                name: "custom".into(),
+                display_name: "a fake driver".into(),
+                command: "./fake-driver.sh %O %A %B %L %P".into(),
+                recursive: None,
+            },
+        ],
+        options,
+    )
+}
+
+fn new_platform_with_drivers(drivers: Vec<Driver>, mut options: Options) -> crate::Result<Platform> {
+    let root = gix_testtools::scripted_fixture_read_only("make_driver_repo.sh")?;
+    let attributes = gix_worktree::Stack::new(
+        &root,
+        gix_worktree::stack::State::AttributesStack(gix_worktree::stack::state::Attributes::new(
+            Default::default(),
+            None,
+            gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+            Default::default(),
+        )),
+        gix_worktree::glob::pattern::Case::Sensitive,
+        Vec::new(),
+        Vec::new(),
+    );
+    options.command_context.worktree_dir = Some(root);
+    Ok(Platform::new(drivers, attributes, options))
+}
@@ -0,0 +1,32 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+cat <<EOF >.gitattributes
+union merge=union
+binary merge=binary
+unset -merge
+set merge
+ours merge=ours
+custom merge=custom
+custom-conflict merge=custom
+with-recursive merge=with-recursive
+missing merge=missing
+marker-size conflict-marker-size=10
+invalid-marker-size conflict-marker-size=-1
+EOF
+
+cat <<'EOF' >fake-driver.sh
+#!/bin/sh
+{
+  echo "path: $5, marker-size: $4"
+  cat "$1" "$2" "$3"
+} > "$2.tmp" && mv "$2.tmp" "$2"
+case "$5" in
+  *conflict*) exit 1;;
+esac
+EOF
+chmod +x fake-driver.sh
+
+git add . && git commit -q -m "init"
@@ -0,0 +1,114 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function baseline() {
+  local dir=${1:?the case directory}
+  (cd "$dir"
+    for variant in merge diff3 union ours theirs; do
+      local opts=()
+      case "$variant" in
+        merge) ;;
+        *) opts=("--$variant") ;;
+      esac
+      local conflicts=0
+      git merge-file -p ${opts[@]+"${opts[@]}"} -L ours -L base -L theirs ours base theirs > "result-$variant" || conflicts=$?
+      echo "$dir $variant $conflicts" >> ../baseline.cases
+    done
+  )
+}
+
+mkdir no-conflict
+(cd no-conflict
+  printf 'one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\n' > base
+  printf 'one\ntwo-ours\nthree\nfour\nfive\nsix\nseven\neight\n' > ours
+  printf 'one\ntwo\nthree\nfour\nfive\nsix\nseven\neight-theirs\n' > theirs
+)
+baseline no-conflict
+
+mkdir simple-conflict
+(cd simple-conflict
+  printf 'one\ntwo\nthree\nfour\nfive\n' > base
+  printf 'one\ntwo\nthree-ours\nfour\nfive\n' > ours
+  printf 'one\ntwo\nthree-theirs\nfour\nfive\n' > theirs
+)
+baseline simple-conflict
+
+mkdir common-lines-in-conflict
+(cd common-lines-in-conflict
+  printf 'one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\n' > base
+  printf 'one\ntwo\nthree-ours\nfour-both\nfive-both\nsix-ours\nseven\neight\n' > ours
+  printf 'one\ntwo\nthree-theirs\nfour-both\nfive-both\nsix-theirs\nseven\neight\n' > theirs
+)
+baseline common-lines-in-conflict
+
+mkdir common-lines-beyond-threshold
+(cd common-lines-beyond-threshold
+  printf 'one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\n' > base
+  printf 'one\ntwo-ours\nthree-both\nfour-both\nfive-both\nsix-both\nseven-ours\neight\nnine\nten\n' > ours
+  printf 'one\ntwo-theirs\nthree-both\nfour-both\nfive-both\nsix-both\nseven-theirs\neight\nnine\nten\n' > theirs
+)
+baseline common-lines-beyond-threshold
+
+mkdir close-conflicts
+(cd close-conflicts
+  printf 'one\ntwo\nthree\nfour\nfive\nsix\nseven\n' > base
+  printf 'one\ntwo-ours\nthree\nfour\nfive-ours\nsix\nseven\n' > ours
+  printf 'one\ntwo-theirs\nthree\nfour\nfive-theirs\nsix\nseven\n' > theirs
+)
+baseline close-conflicts
+
+mkdir distant-conflicts
+(cd distant-conflicts
+  printf 'one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\n' > base
+  printf 'one\ntwo-ours\nthree\nfour\nfive\nsix\nseven\neight-ours\nnine\nten\n' > ours
+  printf 'one\ntwo-theirs\nthree\nfour\nfive\nsix\nseven\neight-theirs\nnine\nten\n' > theirs
+)
+baseline distant-conflicts
+
+mkdir identical-changes
+(cd identical-changes
+  printf 'one\ntwo\nthree\nfour\nfive\n' > base
+  printf 'one\ntwo-both\nthree\nfour-ours\nfive\n' > ours
+  printf 'one\ntwo-both\nthree\nfour\nfive-theirs\n' > theirs
+)
+baseline identical-changes
+
+mkdir insertions-at-same-position
+(cd insertions-at-same-position
+  printf 'one\ntwo\nthree\n' > base
+  printf 'one\ntwo\nours-a\nours-b\nthree\n' > ours
+  printf 'one\ntwo\ntheirs-a\nthree\n' > theirs
+)
+baseline insertions-at-same-position
+
+mkdir deletion-and-modification
+(cd deletion-and-modification
+  printf 'one\ntwo\nthree\nfour\nfive\n' > base
+  printf 'one\nfour\nfive\n' > ours
+  printf 'one\ntwo\nthree-theirs\nfour\nfive\n' > theirs
+)
+baseline deletion-and-modification
+
+mkdir missing-newline-at-end
+(cd missing-newline-at-end
+  printf 'one\ntwo\nthree' > base
+  printf 'one\ntwo\nthree-ours' > ours
+  printf 'one\ntwo\nthree-theirs' > theirs
+)
+baseline missing-newline-at-end
+
+mkdir empty-ancestor
+(cd empty-ancestor
+  touch base
+  printf 'one\ntwo\nthree\n' > ours
+  printf 'one\nzwei\nthree\n' > theirs
+)
+baseline empty-ancestor
+
+mkdir one-sided-changes-around-conflict
+(cd one-sided-changes-around-conflict
+  printf 'one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\neleven\ntwelve\n' > base
+  printf 'one-ours\ntwo\nthree\nfour\nfive\nsix-ours\nseven\neight\nnine\nten\neleven\ntwelve\n' > ours
+  printf 'one\ntwo\nthree\nfour\nfive\nsix-theirs\nseven\neight\nnine\nten\neleven\ntwelve-theirs\n' > theirs
+)
+baseline one-sided-changes-around-conflict
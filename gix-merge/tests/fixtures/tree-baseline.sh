@@ -0,0 +1,147 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+# Each scenario is a repository with the branches `ours` and `theirs` forked from `base`.
+# `git merge` is run with `ours` checked out as detached `HEAD` for each value of `merge.directoryRenames`, and the
+# resulting index is recorded in `<scenario>.<value>.index`, along with the conflict messages in `<scenario>.<value>.messages`.
+# If the merge succeeds, the merged tree is recorded in `<scenario>.<value>.tree`.
+
+function scenario() {
+  local name=${1:?scenario name}
+  git init -q "$name"
+  (cd "$name" && git checkout -q -b base && git commit -q --allow-empty -m "empty")
+}
+
+function commit() {
+  git add -A . && git commit -q -m "${1:?message}"
+}
+
+function fork() {
+  git checkout -q -b "${1:?branch}" base
+}
+
+function baseline() {
+  local name=${1:?scenario name}
+  for dir_renames in false true conflict; do
+    (cd "$name"
+      git checkout -q --detach ours
+      if git -c merge.directoryRenames=$dir_renames merge --no-edit -q theirs >"../$name.$dir_renames.messages" 2>&1; then
+        git rev-parse HEAD^{tree} >"../$name.$dir_renames.tree"
+      fi
+      git ls-files --stage >"../$name.$dir_renames.index"
+      git reset -q --hard ours
+    )
+  done
+}
+
+scenario clean
+(cd clean
+  echo a >a && echo b >b && mkdir dir && echo c >dir/c && commit base
+  fork ours && echo a-ours >a && echo d >dir/d && commit ours
+  fork theirs && echo b-theirs >b && rm dir/c && commit theirs
+)
+baseline clean
+
+scenario content
+(cd content
+  printf '1\n2\n3\n' >a && commit base
+  fork ours && printf '1\nours\n3\n' >a && commit ours
+  fork theirs && printf '1\ntheirs\n3\n' >a && commit theirs
+)
+baseline content
+
+scenario modify-delete
+(cd modify-delete
+  echo a >a && echo b >b && commit base
+  fork ours && echo a-ours >a && rm b && commit ours
+  fork theirs && rm a && echo b-theirs >b && commit theirs
+)
+baseline modify-delete
+
+scenario add-add
+(cd add-add
+  echo base >unrelated && commit base
+  fork ours && echo ours >a && echo same >b && commit ours
+  fork theirs && echo theirs >a && echo same >b && commit theirs
+)
+baseline add-add
+
+scenario rename-modify
+(cd rename-modify
+  seq 10 >a && commit base
+  fork ours && git mv a b && commit ours
+  fork theirs && seq 11 >a && commit theirs
+)
+baseline rename-modify
+
+scenario rename-modify-conflict
+(cd rename-modify-conflict
+  seq 10 >a && commit base
+  fork ours && git mv a b && { seq 4; echo ours; seq 6 10; } >b && commit ours
+  fork theirs && { seq 4; echo theirs; seq 6 10; } >a && commit theirs
+)
+baseline rename-modify-conflict
+
+scenario rename-delete
+(cd rename-delete
+  seq 10 >a && seq 20 >b && commit base
+  fork ours && git mv a a-renamed && rm b && commit ours
+  fork theirs && rm a && git mv b b-renamed && commit theirs
+)
+baseline rename-delete
+
+scenario rename-rename-1-to-2
+(cd rename-rename-1-to-2
+  seq 10 >a && commit base
+  fork ours && git mv a b && commit ours
+  fork theirs && git mv a c && commit theirs
+)
+baseline rename-rename-1-to-2
+
+scenario rename-rename-2-to-1
+(cd rename-rename-2-to-1
+  seq 10 >a && seq 20 30 >b && commit base
+  fork ours && git mv a c && commit ours
+  fork theirs && git mv b c && commit theirs
+)
+baseline rename-rename-2-to-1
+
+scenario directory-rename
+(cd directory-rename
+  mkdir dir && seq 10 >dir/x && seq 20 30 >dir/y && seq 40 50 >dir/z && commit base
+  fork ours && git mv dir new-dir && commit ours
+  fork theirs && seq 60 70 >dir/added && mkdir dir/sub && echo sub >dir/sub/file && commit theirs
+)
+baseline directory-rename
+
+scenario directory-rename-theirs
+(cd directory-rename-theirs
+  mkdir dir && seq 10 >dir/x && seq 20 30 >dir/y && commit base
+  fork ours && seq 60 70 >dir/added && commit ours
+  fork theirs && mkdir -p other/new && git mv dir/x dir/y other/new/ && commit theirs
+)
+baseline directory-rename-theirs
+
+scenario directory-rename-majority
+(cd directory-rename-majority
+  mkdir dir && seq 10 >dir/x && seq 20 30 >dir/y && seq 40 50 >dir/z && commit base
+  fork ours && mkdir a b && git mv dir/x a/x && git mv dir/y a/y && git mv dir/z b/z && commit ours
+  fork theirs && seq 60 70 >dir/added && commit theirs
+)
+baseline directory-rename-majority
+
+scenario distinct-types
+(cd distinct-types
+  echo a >a && echo b >b && commit base
+  fork ours && rm a && ln -s target a && echo b-ours >b && commit ours
+  fork theirs && echo a-theirs >a && rm b && ln -s target b && commit theirs
+)
+baseline distinct-types
+
+scenario mode
+(cd mode
+  echo base >unrelated && commit base
+  fork ours && echo same >a && chmod +x a && commit ours
+  fork theirs && echo same >a && commit theirs
+)
+baseline mode
@@ -0,0 +1,325 @@
+use std::path::Path;
+
+use bstr::{BString, ByteSlice};
+use gix_merge::tree::{Conflict, DirectoryRenames, Entry, Options, Outcome, Side};
+use gix_object::{
+    tree::{EntryKind, EntryMode},
+    FindExt,
+};
+use gix_odb::Write;
+
+#[test]
+fn entries_and_trees_match_git() -> crate::Result {
+    let root = gix_testtools::scripted_fixture_read_only("tree-baseline.sh")?;
+    for name in [
+        "clean",
+        "content",
+        "modify-delete",
+        "add-add",
+        "rename-modify",
+        "rename-modify-conflict",
+        "rename-delete",
+        "rename-rename-1-to-2",
+        "rename-rename-2-to-1",
+        "directory-rename",
+        "directory-rename-theirs",
+        "directory-rename-majority",
+        "distinct-types",
+        "mode",
+    ] {
+        for (value, directory_renames) in [
+            ("false", DirectoryRenames::Disabled),
+            ("true", DirectoryRenames::Apply),
+            ("conflict", DirectoryRenames::Conflict),
+        ] {
+            let outcome = merge(&root, name, directory_renames)?;
+            let baseline = format!("{name}.{value}");
+            assert_eq!(
+                outcome.entries,
+                parse_index(&std::fs::read(root.join(format!("{baseline}.index")))?),
+                "{baseline}: the index matches the one of git"
+            );
+            match std::fs::read(root.join(format!("{baseline}.tree"))) {
+                Ok(tree) => {
+                    assert!(
+                        !outcome.has_conflicts(),
+                        "{baseline}: git merged cleanly, but we saw {:?}",
+                        outcome.conflicts
+                    );
+                    assert_eq!(
+                        outcome.tree,
+                        gix_hash::ObjectId::from_hex(tree.trim())?,
+                        "{baseline}: the merged tree is the one of git"
+                    );
+                }
+                Err(_) => assert!(outcome.has_conflicts(), "{baseline}: git saw conflicts, we didn't"),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn conflicts_are_classified() -> crate::Result {
+    let root = gix_testtools::scripted_fixture_read_only("tree-baseline.sh")?;
+    let link = EntryMode::from(EntryKind::Link);
+    for (name, directory_renames, expected) in [
+        ("clean", DirectoryRenames::Conflict, vec![]),
+        (
+            "content",
+            DirectoryRenames::Conflict,
+            vec![Conflict::Content { path: "a".into() }],
+        ),
+        (
+            "modify-delete",
+            DirectoryRenames::Conflict,
+            vec![
+                Conflict::ModifyDelete {
+                    path: "a".into(),
+                    deleted_by: Side::Theirs,
+                },
+                Conflict::ModifyDelete {
+                    path: "b".into(),
+                    deleted_by: Side::Ours,
+                },
+            ],
+        ),
+        (
+            "add-add",
+            DirectoryRenames::Conflict,
+            vec![Conflict::AddAdd { path: "a".into() }],
+        ),
+        ("rename-modify", DirectoryRenames::Conflict, vec![]),
+        (
+            "rename-modify-conflict",
+            DirectoryRenames::Conflict,
+            vec![Conflict::Content { path: "b".into() }],
+        ),
+        (
+            "rename-delete",
+            DirectoryRenames::Conflict,
+            vec![
+                Conflict::RenameDelete {
+                    source: "a".into(),
+                    destination: "a-renamed".into(),
+                    renamed_by: Side::Ours,
+                },
+                Conflict::RenameDelete {
+                    source: "b".into(),
+                    destination: "b-renamed".into(),
+                    renamed_by: Side::Theirs,
+                },
+            ],
+        ),
+        (
+            "rename-rename-1-to-2",
+            DirectoryRenames::Conflict,
+            vec![Conflict::RenameRenameOneToTwo {
+                source: "a".into(),
+                ours: "b".into(),
+                theirs: "c".into(),
+            }],
+        ),
+        (
+            "rename-rename-2-to-1",
+            DirectoryRenames::Conflict,
+            vec![Conflict::RenameRenameTwoToOne {
+                ours_source: "a".into(),
+                theirs_source: "b".into(),
+                destination: "c".into(),
+            }],
+        ),
+        ("directory-rename", DirectoryRenames::Disabled, vec![]),
+        ("directory-rename", DirectoryRenames::Apply, vec![]),
+        (
+            "directory-rename",
+            DirectoryRenames::Conflict,
+            vec![
+                Conflict::DirectoryRename {
+                    path: "dir/added".into(),
+                    new_path: "new-dir/added".into(),
+                    added_by: Side::Theirs,
+                },
+                Conflict::DirectoryRename {
+                    path: "dir/sub/file".into(),
+                    new_path: "new-dir/sub/file".into(),
+                    added_by: Side::Theirs,
+                },
+            ],
+        ),
+        (
+            "directory-rename-theirs",
+            DirectoryRenames::Conflict,
+            vec![Conflict::DirectoryRename {
+                path: "dir/added".into(),
+                new_path: "other/new/added".into(),
+                added_by: Side::Ours,
+            }],
+        ),
+        (
+            "directory-rename-majority",
+            DirectoryRenames::Conflict,
+            vec![Conflict::DirectoryRename {
+                path: "dir/added".into(),
+                new_path: "a/added".into(),
+                added_by: Side::Theirs,
+            }],
+        ),
+        (
+            "distinct-types",
+            DirectoryRenames::Conflict,
+            vec![
+                Conflict::DistinctTypes {
+                    path: "a".into(),
+                    ours: "a".into(),
+                    theirs: "a~theirs".into(),
+                },
+                Conflict::DistinctTypes {
+                    path: "b".into(),
+                    ours: "b~HEAD".into(),
+                    theirs: "b".into(),
+                },
+            ],
+        ),
+        (
+            "mode",
+            DirectoryRenames::Conflict,
+            vec![Conflict::Mode {
+                path: "a".into(),
+                ours: EntryKind::BlobExecutable.into(),
+                theirs: EntryKind::Blob.into(),
+            }],
+        ),
+    ] {
+        let outcome = merge(&root, name, directory_renames)?;
+        assert_eq!(outcome.conflicts, expected, "{name} with {directory_renames:?}");
+        if name == "distinct-types" {
+            assert_eq!(
+                outcome
+                    .entries
+                    .iter()
+                    .filter(|entry| entry.mode == link)
+                    .map(|entry| entry.path.clone())
+                    .collect::<Vec<_>>(),
+                ["a", "b"],
+                "the symbolic links stay in place as they can't be renamed"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn without_rename_tracking_renames_are_deletions_and_additions() -> crate::Result {
+    let root = gix_testtools::scripted_fixture_read_only("tree-baseline.sh")?;
+    let outcome = merge_with_options(
+        &root,
+        "rename-modify",
+        Options {
+            rewrites: None,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        outcome.conflicts,
+        [Conflict::ModifyDelete {
+            path: "a".into(),
+            deleted_by: Side::Ours,
+        }]
+    );
+    Ok(())
+}
+
+fn merge(root: &Path, name: &str, directory_renames: DirectoryRenames) -> crate::Result<Outcome> {
+    merge_with_options(
+        root,
+        name,
+        Options {
+            directory_renames,
+            ..Default::default()
+        },
+    )
+}
+
+fn merge_with_options(root: &Path, name: &str, options: Options) -> crate::Result<Outcome> {
+    let repo = root.join(name);
+    let odb = gix_odb::at(repo.join(".git/objects"))?;
+    let sink = gix_odb::sink(gix_hash::Kind::Sha1);
+    let mut buf = Vec::new();
+    let mut tree_of = |branch: &str| -> crate::Result<gix_hash::ObjectId> {
+        let commit = gix_hash::ObjectId::from_hex(std::fs::read(repo.join(".git/refs/heads").join(branch))?.trim())?;
+        Ok(odb.find_commit(&commit, &mut buf)?.tree())
+    };
+    let (base, ours, theirs) = (tree_of("base")?, tree_of("ours")?, tree_of("theirs")?);
+
+    Ok(gix_merge::tree(
+        &base,
+        &ours,
+        &theirs,
+        gix_merge::blob::builtin_driver::text::Labels {
+            ancestor: None,
+            current: Some("HEAD".into()),
+            other: Some("theirs".into()),
+        },
+        &odb,
+        |object| sink.write(object),
+        &mut new_diff_platform(&repo),
+        &mut gix_merge::blob::Platform::new(Vec::new(), attributes(&repo), Default::default()),
+        options,
+    )?)
+}
+
+fn parse_index(baseline: &[u8]) -> Vec<Entry> {
+    baseline
+        .lines()
+        .map(|line| {
+            let (info, path) = line.split_once_str("\t").expect("tab before path");
+            let mut tokens = info.split_str(" ");
+            let mode = EntryMode::from_bytes(tokens.next().expect("mode")).expect("valid mode");
+            let id = gix_hash::ObjectId::from_hex(tokens.next().expect("id")).expect("valid id");
+            let stage = match tokens.next().expect("stage") {
+                b"0" => gix_index::entry::Stage::Unconflicted,
+                b"1" => gix_index::entry::Stage::Base,
+                b"2" => gix_index::entry::Stage::Ours,
+                b"3" => gix_index::entry::Stage::Theirs,
+                stage => unreachable!("invalid stage {stage:?}"),
+            };
+            Entry {
+                path: BString::from(path),
+                stage,
+                mode,
+                id,
+            }
+        })
+        .collect()
+}
+
+fn attributes(root: &Path) -> gix_worktree::Stack {
+    gix_worktree::Stack::new(
+        root,
+        gix_worktree::stack::State::AttributesStack(gix_worktree::stack::state::Attributes::new(
+            Default::default(),
+            None,
+            gix_worktree::stack::state::attributes::Source::IdMapping,
+            Default::default(),
+        )),
+        gix_worktree::glob::pattern::Case::Sensitive,
+        Vec::new(),
+        Vec::new(),
+    )
+}
+
+fn new_diff_platform(root: &Path) -> gix_diff::blob::Platform {
+    let filter = gix_diff::blob::Pipeline::new(
+        Default::default(),
+        gix_filter::Pipeline::default(),
+        Vec::new(),
+        Default::default(),
+    );
+    gix_diff::blob::Platform::new(
+        Default::default(),
+        filter,
+        gix_diff::blob::pipeline::Mode::ToGit,
+        attributes(root),
+    )
+}
@@ -167,6 +167,54 @@ impl EntryMode {
         }
         .into()
     }
+
+    /// Parse the octal representation of a mode as found in tree objects, like `100644` or `40000`, or return `None`
+    /// if `mode` isn't octal or doesn't represent any known kind of entry.
+    ///
+    /// Like `git`, nonstandard modes are tolerated, which includes blobs with unusual permissions like `100664` and
+    /// zero-padded modes like `040000`. Use [`normalized()`](Self::normalized()) to obtain the mode `git` writes instead.
+    pub fn from_bytes(mode: &[u8]) -> Option<Self> {
+        if mode.is_empty() {
+            return None;
+        }
+        ref_iter::mode_from_octal_digits(mode).and_then(|mode| EntryMode::try_from(mode).ok())
+    }
+
+    /// Return the canonical mode of our [kind](Self::kind()), which drops all nonstandard permission bits, as used
+    /// when writing trees.
+    pub const fn normalized(&self) -> EntryMode {
+        EntryMode(self.kind() as u16)
+    }
+
+    /// Return `true` if this mode is the canonical mode of its [kind](Self::kind()), i.e. it doesn't change
+    /// when [normalized](Self::normalized()).
+    pub const fn is_standard(&self) -> bool {
+        self.0 == self.kind() as u16
+    }
+
+    /// Create the mode of a blob from the `permissions` of a file in the worktree, like `0o755`, for adding it.
+    ///
+    /// If `file_mode` is `false`, like with `core.fileMode=false`, the executable bit of the file isn't trusted
+    /// and the blob is never executable.
+    pub const fn from_file_permissions(permissions: u32, file_mode: bool) -> Self {
+        EntryMode(if file_mode && permissions & 0o100 == 0o100 {
+            EntryKind::BlobExecutable
+        } else {
+            EntryKind::Blob
+        } as u16)
+    }
+
+    /// Return the permissions of a file to create for this mode on checkout, which are `0o755` for executable blobs
+    /// and `0o644` for all other blobs, or `None` if this isn't a blob.
+    ///
+    /// If `file_mode` is `false`, like with `core.fileMode=false`, files are never made executable.
+    pub const fn to_file_permissions(&self, file_mode: bool) -> Option<u32> {
+        match self.kind() {
+            EntryKind::BlobExecutable if file_mode => Some(0o755),
+            EntryKind::Blob | EntryKind::BlobExecutable => Some(0o644),
+            EntryKind::Tree | EntryKind::Link | EntryKind::Commit => None,
+        }
+    }
 }
 
 /// An element of a [`TreeRef`][crate::TreeRef::entries].
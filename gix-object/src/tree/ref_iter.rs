@@ -93,20 +93,21 @@ impl<'a> TryFrom<&'a [u8]> for tree::EntryMode {
 }
 
 fn mode_from_decimal(i: &[u8]) -> Option<(u32, &[u8])> {
+    let spacer_pos = i.iter().position(|b| *b == b' ')?;
+    let mode = mode_from_octal_digits(&i[..spacer_pos])?;
+    Some((mode, &i[spacer_pos + 1..]))
+}
+
+/// Parse `digits` as octal number, or return `None` if they aren't octal or too large to be a mode.
+pub(crate) fn mode_from_octal_digits(digits: &[u8]) -> Option<u32> {
     let mut mode = 0u32;
-    let mut spacer_pos = 1;
-    for b in i.iter().take_while(|b| **b != b' ') {
-        if *b < b'0' || *b > b'7' {
+    for b in digits {
+        if *b < b'0' || *b > b'7' || mode > u32::from(u16::MAX) {
             return None;
         }
         mode = (mode << 3) + (b - b'0') as u32;
-        spacer_pos += 1;
-    }
-    if i.len() < spacer_pos {
-        return None;
     }
-    let (_, i) = i.split_at(spacer_pos);
-    Some((mode, i))
+    (mode <= u32::from(u16::MAX)).then_some(mode)
 }
 
 impl TryFrom<u32> for tree::EntryMode {
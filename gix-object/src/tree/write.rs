@@ -24,7 +24,7 @@ impl From<Error> for io::Error {
 
 /// Serialization
 impl crate::WriteTo for Tree {
-    /// Serialize this tree to `out` in the git internal format.
+    /// Serialize this tree to `out` in the git internal format, with all entry modes [normalized](crate::tree::EntryMode::normalized()).
     fn write_to(&self, out: &mut dyn io::Write) -> io::Result<()> {
         debug_assert_eq!(
             &{
@@ -37,7 +37,7 @@ impl crate::WriteTo for Tree {
         );
         let mut buf = Default::default();
         for Entry { mode, filename, oid } in &self.entries {
-            out.write_all(mode.as_bytes(&mut buf))?;
+            out.write_all(mode.normalized().as_bytes(&mut buf))?;
             out.write_all(SPACE)?;
 
             if filename.find_byte(b'\n').is_some() {
@@ -63,7 +63,7 @@ impl crate::WriteTo for Tree {
         self.entries
             .iter()
             .map(|Entry { mode, filename, oid }| {
-                (mode.as_bytes(&mut buf).len() + 1 + filename.len() + 1 + oid.as_bytes().len()) as u64
+                (mode.normalized().as_bytes(&mut buf).len() + 1 + filename.len() + 1 + oid.as_bytes().len()) as u64
             })
             .sum()
     }
@@ -71,7 +71,7 @@ impl crate::WriteTo for Tree {
 
 /// Serialization
 impl<'a> crate::WriteTo for TreeRef<'a> {
-    /// Serialize this tree to `out` in the git internal format.
+    /// Serialize this tree to `out` in the git internal format, keeping nonstandard entry modes as they are.
     fn write_to(&self, out: &mut dyn io::Write) -> io::Result<()> {
         debug_assert_eq!(
             &{
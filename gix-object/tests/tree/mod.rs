@@ -273,4 +273,116 @@ mod entry_mode {
             assert_eq!(mode.as_bytes(&mut buf), expected)
         }
     }
+
+    #[test]
+    fn from_bytes() {
+        for (input, expected) in [
+            (&b"100644"[..], Some(EntryKind::Blob.into())),
+            (b"40000", Some(EntryKind::Tree.into())),
+            (b"040000", Some(EntryKind::Tree.into())),
+            (b"100664", Some(EntryMode(0o100664))),
+            (b"0100644", Some(EntryKind::Blob.into())),
+            (b"160000", Some(EntryKind::Commit.into())),
+            (b"", None),
+            (b"100844", None),
+            (b"100644 ", None),
+            (b"60000", None),
+            (b"1000000000644", None),
+        ] {
+            assert_eq!(EntryMode::from_bytes(input), expected, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn normalization() {
+        for (mode, expected) in [
+            (0o100664, EntryKind::Blob),
+            (0o100640, EntryKind::Blob),
+            (0o100775, EntryKind::BlobExecutable),
+            (0o040755, EntryKind::Tree),
+        ] {
+            let mode = EntryMode(mode);
+            assert!(!mode.is_standard());
+            assert_eq!(mode.normalized(), expected.into());
+            assert!(mode.normalized().is_standard());
+        }
+        for kind in [
+            EntryKind::Tree,
+            EntryKind::Blob,
+            EntryKind::BlobExecutable,
+            EntryKind::Link,
+            EntryKind::Commit,
+        ] {
+            assert!(EntryMode::from(kind).is_standard(), "{kind:?}");
+        }
+    }
+
+    #[test]
+    fn file_permissions() {
+        assert_eq!(
+            EntryMode::from_file_permissions(0o755, true),
+            EntryKind::BlobExecutable.into()
+        );
+        assert_eq!(EntryMode::from_file_permissions(0o664, true), EntryKind::Blob.into());
+        assert_eq!(
+            EntryMode::from_file_permissions(0o755, false),
+            EntryKind::Blob.into(),
+            "without trusting the file mode, files are never executable"
+        );
+
+        assert_eq!(
+            EntryMode::from(EntryKind::BlobExecutable).to_file_permissions(true),
+            Some(0o755)
+        );
+        assert_eq!(
+            EntryMode::from(EntryKind::BlobExecutable).to_file_permissions(false),
+            Some(0o644)
+        );
+        assert_eq!(EntryMode(0o100664).to_file_permissions(true), Some(0o644));
+        assert_eq!(EntryMode::from(EntryKind::Link).to_file_permissions(true), None);
+        assert_eq!(EntryMode::from(EntryKind::Tree).to_file_permissions(true), None);
+    }
+}
+
+mod nonstandard_modes {
+    use gix_object::{
+        bstr::ByteSlice,
+        tree::{EntryKind, EntryMode},
+        Tree, TreeRef, WriteTo,
+    };
+
+    /// A tree with a group-writable file as written by early versions of `git`.
+    fn tree_with_group_writable_file() -> Vec<u8> {
+        let mut data = b"100664 a-file\0".to_vec();
+        data.extend_from_slice(&[1; 20]);
+        data.extend_from_slice(b"40000 dir\0");
+        data.extend_from_slice(&[2; 20]);
+        data
+    }
+
+    #[test]
+    fn are_tolerated_when_reading_and_normalized_when_writing() -> crate::Result {
+        let data = tree_with_group_writable_file();
+        let tree = TreeRef::from_bytes(&data)?;
+        let mode = tree.entries[0].mode;
+        assert_eq!(mode, EntryMode(0o100664), "nonstandard modes are read as they are");
+        assert!(mode.is_blob() && !mode.is_executable() && !mode.is_standard());
+        assert_eq!(mode.kind(), EntryKind::Blob);
+
+        let mut out = Vec::new();
+        tree.write_to(&mut out)?;
+        assert_eq!(
+            out.as_bstr(),
+            data.as_bstr(),
+            "borrowed trees are written as they were read"
+        );
+
+        let tree: Tree = tree.into();
+        out.clear();
+        tree.write_to(&mut out)?;
+        assert_eq!(&out[..7], b"100644 ", "owned trees are written with normalized modes");
+        assert_eq!(out[7..].as_bstr(), data[7..].as_bstr());
+        assert_eq!(tree.size(), out.len() as u64);
+        Ok(())
+    }
 }
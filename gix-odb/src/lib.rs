@@ -30,6 +30,8 @@ pub use store_impls::{dynamic as store, loose};
 
 pub mod alternate;
 
+pub mod maintenance;
+
 /// A way to access objects along with pre-configured thread-local caches for packed base objects as well as objects themselves.
 ///
 /// By default, no cache will be used.
@@ -142,6 +144,10 @@ pub struct Store {
     object_hash: gix_hash::Kind,
     /// Ignore alternates
     ignore_alternates: bool,
+    /// The cache of windows to use when mapping pack data files, or `None` to map them entirely.
+    pack_windows: Option<Arc<gix_pack::data::window::Cache>>,
+    /// The hash kind to additionally compute for written loose objects.
+    compat_object_hash: Option<gix_hash::Kind>,
 }
 
 /// Create a new cached handle to the object store with support for additional options.
@@ -0,0 +1,132 @@
+//! Cheaply estimate if an object database would benefit from maintenance, similar to what `git gc --auto` does.
+use std::path::Path;
+
+use crate::Store;
+
+/// The limits above which maintenance is advised.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Thresholds {
+    /// The approximate amount of loose objects above which maintenance is advised, as configured with `gc.auto`.
+    ///
+    /// If `0`, maintenance is never advised, not even if there are too many packs.
+    pub loose_objects: usize,
+    /// The amount of packs without a `.keep` file above which maintenance is advised, as configured with `gc.autoPackLimit`.
+    ///
+    /// If `0`, the amount of packs isn't considered.
+    pub packs: usize,
+}
+
+impl Default for Thresholds {
+    /// Use the same defaults as git.
+    fn default() -> Self {
+        Thresholds {
+            loose_objects: 6700,
+            packs: 50,
+        }
+    }
+}
+
+/// The reason for advising maintenance.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Reason {
+    /// The estimated amount of loose objects is above [`Thresholds::loose_objects`].
+    TooManyLooseObjects,
+    /// The amount of packs without a `.keep` file is above [`Thresholds::packs`].
+    TooManyPacks,
+}
+
+/// The outcome of [`gc_needed()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GcAdvice {
+    /// The amount of loose objects, extrapolated from the amount of loose objects in a single fan-out directory.
+    pub estimated_loose_objects: usize,
+    /// The amount of packs that aren't marked to be kept with a `.keep` file.
+    pub packs: usize,
+    /// The reasons for which maintenance is advised, which is empty if it isn't.
+    pub reasons: Vec<Reason>,
+}
+
+impl GcAdvice {
+    /// Return `true` if maintenance is advised for at least one reason.
+    pub fn is_needed(&self) -> bool {
+        !self.reasons.is_empty()
+    }
+}
+
+/// The fan-out directory to sample, the same one git uses.
+const SAMPLE_DIRECTORY: &str = "17";
+/// The amount of fan-out directories.
+const NUM_FANOUT_DIRECTORIES: usize = 256;
+
+/// Estimate if the objects in `db` (but not those of its alternates) would benefit from maintenance as their amount
+/// exceeds the given `thresholds`.
+///
+/// Just like git, the amount of loose objects is estimated by counting the objects in a single fan-out directory,
+/// while the amount of packs is counted exactly.
+pub fn gc_needed(db: &Store, thresholds: Thresholds) -> std::io::Result<GcAdvice> {
+    let mut out = GcAdvice {
+        estimated_loose_objects: 0,
+        packs: 0,
+        reasons: Vec::new(),
+    };
+    if thresholds.loose_objects == 0 {
+        return Ok(out);
+    }
+
+    let objects_dir = db.path();
+    let num_sampled = count_loose_objects(&objects_dir.join(SAMPLE_DIRECTORY), db.object_hash())?;
+    out.estimated_loose_objects = num_sampled * NUM_FANOUT_DIRECTORIES;
+    // Compare in units of the sampled directory to match git exactly.
+    let sample_limit = (thresholds.loose_objects + NUM_FANOUT_DIRECTORIES - 1) / NUM_FANOUT_DIRECTORIES;
+    if num_sampled > sample_limit {
+        out.reasons.push(Reason::TooManyLooseObjects);
+    }
+
+    out.packs = count_packs_without_keep(&objects_dir.join("pack"))?;
+    if thresholds.packs != 0 && out.packs > thresholds.packs {
+        out.reasons.push(Reason::TooManyPacks);
+    }
+    Ok(out)
+}
+
+fn count_loose_objects(fanout_dir: &Path, object_hash: gix_hash::Kind) -> std::io::Result<usize> {
+    let name_len = object_hash.len_in_hex() - 2;
+    let Some(entries) = read_dir_if_present(fanout_dir)? else {
+        return Ok(0);
+    };
+    let mut count = 0;
+    for entry in entries {
+        let name = entry?.file_name();
+        let Some(name) = name.to_str() else { continue };
+        if name.len() == name_len && name.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+fn count_packs_without_keep(pack_dir: &Path) -> std::io::Result<usize> {
+    let Some(entries) = read_dir_if_present(pack_dir)? else {
+        return Ok(0);
+    };
+    let mut count = 0;
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().map_or(true, |ext| ext != "pack") {
+            continue;
+        }
+        // Like git, only consider packs that are usable as they have an index.
+        if path.with_extension("idx").is_file() && !path.with_extension("keep").exists() {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+fn read_dir_if_present(dir: &Path) -> std::io::Result<Option<std::fs::ReadDir>> {
+    match std::fs::read_dir(dir) {
+        Ok(entries) => Ok(Some(entries)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
@@ -12,6 +12,11 @@ impl Store {
         self.object_hash
     }
 
+    /// The kind of object hash to additionally compute for written loose objects, if any.
+    pub fn compat_object_hash(&self) -> Option<gix_hash::Kind> {
+        self.compat_object_hash
+    }
+
     /// Whether or not we are allowed to use multi-pack indices
     pub fn use_multi_pack_index(&self) -> bool {
         self.use_multi_pack_index
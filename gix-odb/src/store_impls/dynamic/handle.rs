@@ -353,6 +353,8 @@ impl TryFrom<&super::Store> for super::Store {
                 use_multi_pack_index: false,
                 current_dir: s.current_dir.clone().into(),
                 ignore_alternates: false,
+                pack_windows: s.pack_windows.clone(),
+                compat_object_hash: s.compat_object_hash,
             },
         )
     }
@@ -21,6 +21,12 @@ pub struct Options {
     pub current_dir: Option<std::path::PathBuf>,
     /// Ignore alternate databases
     pub ignore_alternates: bool,
+    /// If set, pack data files are accessed through memory-mapped windows managed by this cache instead of being mapped entirely,
+    /// which limits the amount of address space used by all packs sharing it.
+    pub pack_windows: Option<Arc<gix_pack::data::window::Cache>>,
+    /// If set, loose objects are written along with their id in this hash, as recorded in the
+    /// [loose object map](crate::loose::object_map).
+    pub compat_object_hash: Option<gix_hash::Kind>,
 }
 
 impl Default for Options {
@@ -31,6 +37,8 @@ impl Default for Options {
             use_multi_pack_index: true,
             current_dir: None,
             ignore_alternates: false,
+            pack_windows: None,
+            compat_object_hash: None,
         }
     }
 }
@@ -81,6 +89,8 @@ impl Store {
             use_multi_pack_index,
             current_dir,
             ignore_alternates,
+            pack_windows,
+            compat_object_hash,
         }: Options,
     ) -> std::io::Result<Self> {
         let _span = gix_features::trace::detail!("gix_odb::Store::at()");
@@ -136,6 +146,8 @@ impl Store {
             num_handles_unstable: Default::default(),
             num_disk_state_consolidation: Default::default(),
             ignore_alternates,
+            pack_windows,
+            compat_object_hash,
         })
     }
 }
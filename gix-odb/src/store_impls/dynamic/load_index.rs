@@ -231,7 +231,9 @@ impl super::Store {
             Arc::new(
                 db_paths
                     .iter()
-                    .map(|path| crate::loose::Store::at(path, self.object_hash))
+                    .map(|path| {
+                        crate::loose::Store::at(path, self.object_hash).with_compat_object_hash(self.compat_object_hash)
+                    })
                     .collect::<Vec<_>>(),
             )
         } else {
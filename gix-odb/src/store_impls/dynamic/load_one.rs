@@ -24,16 +24,20 @@ impl super::Store {
             path: &Path,
             id: types::PackId,
             object_hash: gix_hash::Kind,
+            windows: Option<&Arc<gix_pack::data::window::Cache>>,
         ) -> std::io::Result<Arc<gix_pack::data::File>> {
-            gix_pack::data::File::at(path, object_hash)
-                .map(|mut pack| {
-                    pack.id = id.to_intrinsic_pack_id();
-                    Arc::new(pack)
-                })
-                .map_err(|err| match err {
-                    gix_pack::data::header::decode::Error::Io { source, .. } => source,
-                    other => std::io::Error::new(std::io::ErrorKind::Other, other),
-                })
+            match windows {
+                Some(windows) => gix_pack::data::File::at_with_windows(path, object_hash, windows.clone()),
+                None => gix_pack::data::File::at(path, object_hash),
+            }
+            .map(|mut pack| {
+                pack.id = id.to_intrinsic_pack_id();
+                Arc::new(pack)
+            })
+            .map_err(|err| match err {
+                gix_pack::data::header::decode::Error::Io { source, .. } => source,
+                other => std::io::Error::new(std::io::ErrorKind::Other, other),
+            })
         }
 
         let slot = &self.files[id.index];
@@ -56,9 +60,11 @@ impl super::Store {
                                 let mut files = slot.files.load_full();
                                 let files_mut = Arc::make_mut(&mut files);
                                 let pack = match files_mut {
-                                    Some(types::IndexAndPacks::Index(bundle)) => bundle
-                                        .data
-                                        .load_with_recovery(|path| load_pack(path, id, self.object_hash))?,
+                                    Some(types::IndexAndPacks::Index(bundle)) => {
+                                        bundle.data.load_with_recovery(|path| {
+                                            load_pack(path, id, self.object_hash, self.pack_windows.as_ref())
+                                        })?
+                                    }
                                     Some(types::IndexAndPacks::MultiIndex(_)) => {
                                         // something changed between us getting the lock, trigger a complete index refresh.
                                         None
@@ -100,7 +106,9 @@ impl super::Store {
                                             .data
                                             .get_mut(pack_index as usize)
                                             .expect("BUG: must set this handle to be stable")
-                                            .load_with_recovery(|path| load_pack(path, id, self.object_hash))?,
+                                            .load_with_recovery(|path| {
+                                                load_pack(path, id, self.object_hash, self.pack_windows.as_ref())
+                                            })?,
                                         None => {
                                             unreachable!("BUG: must set this handle to be stable to avoid slots to be cleared/changed")
                                         }
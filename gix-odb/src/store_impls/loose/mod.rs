@@ -12,6 +12,9 @@ pub struct Store {
     pub(crate) path: PathBuf,
     /// The kind of hash we should assume during iteration and when writing new objects.
     pub(crate) object_hash: gix_hash::Kind,
+    /// If set, the kind of hash to also compute for each written object, with the mapping recorded in the
+    /// [object map](object_map).
+    pub(crate) compat_object_hash: Option<gix_hash::Kind>,
 }
 
 /// Initialization
@@ -26,9 +29,17 @@ impl Store {
         Store {
             path: objects_directory.into(),
             object_hash,
+            compat_object_hash: None,
         }
     }
 
+    /// Also compute the id of each written object with `compat_object_hash` if it is set, and record the mapping
+    /// between both ids in the [object map](object_map), like `git` does if `extensions.compatObjectFormat` is set.
+    pub fn with_compat_object_hash(mut self, compat_object_hash: Option<gix_hash::Kind>) -> Self {
+        self.compat_object_hash = compat_object_hash;
+        self
+    }
+
     /// Return the path to our `objects` directory.
     pub fn path(&self) -> &Path {
         &self.path
@@ -38,6 +49,11 @@ impl Store {
     pub fn object_hash(&self) -> gix_hash::Kind {
         self.object_hash
     }
+
+    /// Return the kind of hash we additionally compute for each written object, if any.
+    pub fn compat_object_hash(&self) -> Option<gix_hash::Kind> {
+        self.compat_object_hash
+    }
 }
 
 fn hash_path(id: &gix_hash::oid, mut root: PathBuf) -> PathBuf {
@@ -55,6 +71,7 @@ pub mod find;
 ///
 #[allow(clippy::empty_docs)]
 pub mod iter;
+pub mod object_map;
 ///
 #[allow(clippy::empty_docs)]
 pub mod verify;
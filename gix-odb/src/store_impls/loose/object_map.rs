@@ -0,0 +1,149 @@
+//! Read and write the `loose-object-idx` file which maps the ids of objects to their ids in the compatibility hash,
+//! as used by `git` if `extensions.compatObjectFormat` is set.
+//!
+//! The file starts with a header line, followed by one line per object with its id, a space, and its compatibility id.
+use std::{
+    collections::HashMap,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use gix_hash::ObjectId;
+use gix_object::bstr::ByteSlice;
+
+/// The name of the file in the `objects` directory which holds the mapping.
+pub const FILE_NAME: &str = "loose-object-idx";
+
+const HEADER: &[u8] = b"# loose-object-idx\n";
+
+/// The error returned by [`Map::at()`] and [`Map::from_bytes()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read the loose object map at '{}'", path.display())]
+    Io { source: std::io::Error, path: PathBuf },
+    #[error("The loose object map doesn't start with the '# loose-object-idx' header")]
+    MissingHeader,
+    #[error(
+        "Line {line_number} of the loose object map is not a pair of a {object_hash} and a {compat_hash} object id"
+    )]
+    InvalidLine {
+        line_number: usize,
+        object_hash: gix_hash::Kind,
+        compat_hash: gix_hash::Kind,
+    },
+}
+
+/// A mapping between the ids of objects and their ids in the compatibility hash, which can be looked up in both directions.
+///
+/// Like in `git`, the empty tree, the empty blob and the null id are always known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Map {
+    object_hash: gix_hash::Kind,
+    compat_hash: gix_hash::Kind,
+    /// Both directions of the mapping, which can share a map as ids of different kinds are never equal.
+    ids: HashMap<ObjectId, ObjectId>,
+}
+
+/// Initialization
+impl Map {
+    /// Read the map from `objects_dir`, with `object_hash` being the hash of the repository and `compat_hash` the one
+    /// configured in `extensions.compatObjectFormat`.
+    ///
+    /// Only the objects that are always known are contained if the file doesn't exist.
+    pub fn at(objects_dir: &Path, object_hash: gix_hash::Kind, compat_hash: gix_hash::Kind) -> Result<Self, Error> {
+        let path = objects_dir.join(FILE_NAME);
+        match std::fs::read(&path) {
+            Ok(data) => Self::from_bytes(&data, object_hash, compat_hash),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::new(object_hash, compat_hash)),
+            Err(source) => Err(Error::Io { source, path }),
+        }
+    }
+
+    /// Parse the map from `data` as read from a `loose-object-idx` file, with `object_hash` being the hash of the
+    /// first id on each line and `compat_hash` the one of the second.
+    pub fn from_bytes(data: &[u8], object_hash: gix_hash::Kind, compat_hash: gix_hash::Kind) -> Result<Self, Error> {
+        let data = data.strip_prefix(HEADER).ok_or(Error::MissingHeader)?;
+        let mut map = Self::new(object_hash, compat_hash);
+        for (idx, line) in data.lines().enumerate() {
+            let invalid = || Error::InvalidLine {
+                line_number: idx + 2,
+                object_hash,
+                compat_hash,
+            };
+            let (id, compat_id) = line
+                .split_once_str(b" ")
+                .and_then(|(id, compat_id)| Some((ObjectId::from_hex(id).ok()?, ObjectId::from_hex(compat_id).ok()?)))
+                .filter(|(id, compat_id)| id.kind() == object_hash && compat_id.kind() == compat_hash)
+                .ok_or_else(invalid)?;
+            map.insert(id, compat_id);
+        }
+        Ok(map)
+    }
+
+    fn new(object_hash: gix_hash::Kind, compat_hash: gix_hash::Kind) -> Self {
+        let mut map = Map {
+            object_hash,
+            compat_hash,
+            ids: HashMap::new(),
+        };
+        map.insert(ObjectId::empty_tree(object_hash), ObjectId::empty_tree(compat_hash));
+        map.insert(ObjectId::empty_blob(object_hash), ObjectId::empty_blob(compat_hash));
+        map.insert(object_hash.null(), compat_hash.null());
+        map
+    }
+
+    fn insert(&mut self, id: ObjectId, compat_id: ObjectId) {
+        self.ids.insert(id, compat_id);
+        self.ids.insert(compat_id, id);
+    }
+}
+
+/// Access
+impl Map {
+    /// Return the id of the object with `id` in the other hash, that is its compatibility id if `id` uses the hash of the
+    /// repository, or the id it is stored under if `id` is a compatibility id, or `None` if it isn't known.
+    pub fn compat_id(&self, id: &gix_hash::oid) -> Option<ObjectId> {
+        self.ids.get(id).copied()
+    }
+
+    /// Return the kind of hash used by the repository.
+    pub fn object_hash(&self) -> gix_hash::Kind {
+        self.object_hash
+    }
+
+    /// Return the kind of hash used for compatibility ids.
+    pub fn compat_hash(&self) -> gix_hash::Kind {
+        self.compat_hash
+    }
+}
+
+/// Append all pairs of object ids and compatibility ids in `mappings` to the `loose-object-idx` file in `objects_dir`,
+/// creating it if needed.
+///
+/// Like `git`, the file is locked while writing, but not rewritten, so concurrent readers always see a valid map.
+pub fn append(
+    objects_dir: &Path,
+    mappings: impl IntoIterator<Item = (ObjectId, ObjectId)>,
+) -> Result<(), gix_lock::acquire::Error> {
+    let path = objects_dir.join(FILE_NAME);
+    let mut buf = Vec::new();
+    for (id, compat_id) in mappings {
+        writeln!(buf, "{id} {compat_id}").expect("write to memory works");
+    }
+    if buf.is_empty() {
+        return Ok(());
+    }
+
+    let _lock = gix_lock::Marker::acquire_to_hold_resource(
+        &path,
+        gix_lock::acquire::Fail::AfterDurationWithBackoff(std::time::Duration::from_secs(1)),
+        None,
+    )?;
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&path)?;
+    if file.metadata()?.len() == 0 {
+        buf.splice(0..0, HEADER.iter().copied());
+    }
+    file.write_all(&buf)?;
+    Ok(())
+}
@@ -24,6 +24,8 @@ pub enum Error {
         source: tempfile::PersistError,
         target: PathBuf,
     },
+    #[error("Could not record the compatibility id of the written object")]
+    ObjectMap(#[from] gix_lock::acquire::Error),
 }
 
 impl crate::traits::Write for Store {
@@ -95,6 +97,26 @@ impl crate::traits::Write for Store {
 
 type CompressedTempfile = deflate::Write<NamedTempFile>;
 
+/// The destination of an object, which also computes its compatibility id if needed.
+struct Dest {
+    file: hash::Write<CompressedTempfile>,
+    compat_hash: Option<hash::Hasher>,
+}
+
+impl io::Write for Dest {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.file.write(buf)?;
+        if let Some(compat_hash) = &mut self.compat_hash {
+            compat_hash.update(&buf[..written]);
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
 /// Access
 impl Store {
     /// Return the path to the object with `id`.
@@ -106,7 +128,7 @@ impl Store {
 }
 
 impl Store {
-    fn dest(&self) -> Result<hash::Write<CompressedTempfile>, Error> {
+    fn dest(&self) -> Result<Dest, Error> {
         #[cfg_attr(not(unix), allow(unused_mut))]
         let mut builder = tempfile::Builder::new();
         #[cfg(unix)]
@@ -115,22 +137,30 @@ impl Store {
             let perms = std::fs::Permissions::from_mode(0o444);
             builder.permissions(perms);
         }
-        Ok(hash::Write::new(
-            deflate::Write::new(builder.tempfile_in(&self.path).map_err(|err| Error::Io {
-                source: err,
-                message: "create named temp file in",
-                path: self.path.to_owned(),
-            })?),
-            self.object_hash,
-        ))
+        Ok(Dest {
+            file: hash::Write::new(
+                deflate::Write::new(builder.tempfile_in(&self.path).map_err(|err| Error::Io {
+                    source: err,
+                    message: "create named temp file in",
+                    path: self.path.to_owned(),
+                })?),
+                self.object_hash,
+            ),
+            compat_hash: self.compat_object_hash.map(hash::hasher),
+        })
     }
 
     fn finalize_object(
         &self,
-        hash::Write { hash, inner: file }: hash::Write<CompressedTempfile>,
+        Dest {
+            file: hash::Write { hash, inner: file },
+            compat_hash,
+        }: Dest,
     ) -> Result<gix_hash::ObjectId, Error> {
-        let id = gix_hash::ObjectId::from(hash.digest());
+        let id = hash.digest();
         let object_path = loose::hash_path(&id, self.path.clone());
+        // Like `git`, only record the compatibility id of objects that we actually add.
+        let compat_id = compat_hash.filter(|_| !object_path.is_file()).map(hash::Hasher::digest);
         let object_dir = object_path
             .parent()
             .expect("each object path has a 1 hex-bytes directory");
@@ -156,6 +186,9 @@ impl Store {
             source: err,
             target: object_path,
         })?;
+        if let Some(compat_id) = compat_id {
+            loose::object_map::append(&self.path, Some((id, compat_id)))?;
+        }
         Ok(id)
     }
 }
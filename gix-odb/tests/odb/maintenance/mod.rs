@@ -0,0 +1,100 @@
+use gix_odb::{
+    maintenance::{gc_needed, Reason, Thresholds},
+    Write,
+};
+
+/// Write the next blob whose id places it into the fan-out directory `17`, which is the one that is sampled.
+fn write_blob_into_sampled_directory(db: &gix_odb::loose::Store, blob_no: &mut usize) -> crate::Result {
+    loop {
+        *blob_no += 1;
+        let data = blob_no.to_string();
+        let id = gix_object::compute_hash(gix_hash::Kind::Sha1, gix_object::Kind::Blob, data.as_bytes());
+        if id.as_bytes()[0] == 0x17 {
+            assert_eq!(db.write_buf(gix_object::Kind::Blob, data.as_bytes())?, id);
+            return Ok(());
+        }
+    }
+}
+
+#[test]
+fn loose_objects_flip_the_advice_above_the_threshold() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let loose = gix_odb::loose::Store::at(tmp.path(), gix_hash::Kind::Sha1);
+    let db = gix_odb::at(tmp.path())?;
+    let thresholds = Thresholds {
+        loose_objects: 500,
+        packs: 0,
+    };
+
+    let advice = gc_needed(db.store_ref(), thresholds)?;
+    assert_eq!(advice.estimated_loose_objects, 0);
+    assert!(!advice.is_needed(), "there is nothing yet");
+
+    let mut blob_no = 0;
+    for num_sampled in 1..=3 {
+        write_blob_into_sampled_directory(&loose, &mut blob_no)?;
+        let advice = gc_needed(db.store_ref(), thresholds)?;
+        assert_eq!(advice.estimated_loose_objects, num_sampled * 256);
+        assert_eq!(
+            advice.is_needed(),
+            num_sampled > 2,
+            "a threshold of 500 allows for up to 2 objects in the sampled directory, just like git"
+        );
+    }
+    assert_eq!(
+        gc_needed(db.store_ref(), thresholds)?.reasons,
+        [Reason::TooManyLooseObjects]
+    );
+
+    let advice = gc_needed(
+        db.store_ref(),
+        Thresholds {
+            loose_objects: 0,
+            packs: 0,
+        },
+    )?;
+    assert!(!advice.is_needed(), "a threshold of 0 disables the check");
+    Ok(())
+}
+
+#[test]
+fn packs_with_keep_files_or_without_index_are_ignored() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let pack_dir = tmp.path().join("pack");
+    std::fs::create_dir(&pack_dir)?;
+    for name in [
+        "a.pack", "a.idx", "b.pack", "b.idx", "b.keep", "c.pack", "d.pack", "d.idx",
+    ] {
+        std::fs::write(pack_dir.join(name), [])?;
+    }
+    let db = gix_odb::at(tmp.path())?;
+
+    let advice = gc_needed(
+        db.store_ref(),
+        Thresholds {
+            packs: 2,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(advice.packs, 2, "only 'a' and 'd' are counted");
+    assert!(!advice.is_needed(), "the amount of packs must be above the threshold");
+
+    let advice = gc_needed(
+        db.store_ref(),
+        Thresholds {
+            packs: 1,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(advice.reasons, [Reason::TooManyPacks]);
+
+    let advice = gc_needed(
+        db.store_ref(),
+        Thresholds {
+            packs: 0,
+            ..Default::default()
+        },
+    )?;
+    assert!(!advice.is_needed(), "a pack limit of 0 disables the check");
+    Ok(())
+}
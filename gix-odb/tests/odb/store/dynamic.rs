@@ -962,6 +962,89 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn handles_on_many_threads_see_packs_added_concurrently() -> crate::Result {
+    fn assert_send_and_clone<T: Send + Clone + 'static>(_v: &T) {}
+
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let pack_dir = tmp.path().join("objects/pack");
+    std::fs::create_dir_all(&pack_dir)?;
+    gix_testtools::copy_recursively_into_existing_dir(fixture_path_standalone("objects/pack"), &pack_dir)?;
+    let ids_in_pack = |name: &str| -> crate::Result<Vec<ObjectId>> {
+        let index = gix_pack::index::File::at(
+            fixture_path_standalone("objects/pack").join(format!("{name}.idx")),
+            gix_hash::Kind::Sha1,
+        )?;
+        Ok(index.iter().map(|e| e.oid).collect())
+    };
+    let added_pack = "pack-a2bf8e71d8c18879e499335762dd95119d93d9f1";
+    let existing_ids = ids_in_pack("pack-c0438c19fb16422b6bbcce24387b3264416d485b")?;
+    let added_ids = ids_in_pack(added_pack)?;
+    let stem = pack_dir.join(added_pack);
+    for ext in ["idx", "pack"] {
+        std::fs::rename(stem.with_extension(ext), stem.with_extension(format!("{ext}.bak")))?;
+    }
+
+    let handle = gix_odb::at(tmp.path().join("objects"))?.into_arc()?;
+    assert_send_and_clone(&handle);
+    assert!(!handle.exists(&added_ids[0]), "the pack to add isn't visible yet");
+    let pack_added = std::sync::atomic::AtomicBool::new(false);
+    std::thread::scope(|s| -> crate::Result {
+        let mut workers = Vec::new();
+        for seed in 0..16u64 {
+            let handle = handle.clone();
+            let (existing_ids, added_ids, pack_added) = (&existing_ids, &added_ids, &pack_added);
+            workers.push(s.spawn(move || -> crate::Result {
+                let mut buf = Vec::new();
+                let mut state = seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1;
+                let mut next_index = |len: usize| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    (state % len as u64) as usize
+                };
+                for _ in 0..500 {
+                    let saw_pack_added = pack_added.load(std::sync::atomic::Ordering::SeqCst);
+                    let id = existing_ids[next_index(existing_ids.len())];
+                    handle.find(&id, &mut buf)?;
+                    if saw_pack_added {
+                        let id = added_ids[next_index(added_ids.len())];
+                        handle.find(&id, &mut buf)?;
+                    }
+                }
+                Ok(())
+            }));
+        }
+        let adder_handle = handle.clone();
+        let (stem, added_ids, pack_added) = (&stem, &added_ids, &pack_added);
+        s.spawn(move || -> crate::Result {
+            for ext in ["idx", "pack"] {
+                std::fs::rename(stem.with_extension(format!("{ext}.bak")), stem.with_extension(ext))?;
+            }
+            let mut buf = Vec::new();
+            adder_handle.find(&added_ids[0], &mut buf)?;
+            pack_added.store(true, std::sync::atomic::Ordering::SeqCst);
+            Ok(())
+        })
+        .join()
+        .expect("no panic")?;
+        for worker in workers {
+            worker.join().expect("no panic")?;
+        }
+        Ok(())
+    })?;
+
+    for id in existing_ids.iter().chain(added_ids.iter()) {
+        assert!(handle.exists(id), "all objects are visible through the original handle as well");
+    }
+    assert_eq!(
+        handle.store_ref().metrics().num_handles,
+        1,
+        "all handles of the worker threads were dropped"
+    );
+    Ok(())
+}
+
 mod verify {
     use std::sync::atomic::AtomicBool;
 
@@ -129,6 +129,92 @@ mod write {
 
         Ok(())
     }
+
+    #[test]
+    fn with_compat_object_hash_the_object_map_is_updated() -> crate::Result {
+        let dir = gix_testtools::tempfile::tempdir()?;
+        let db =
+            loose::Store::at(dir.path(), gix_hash::Kind::Sha1).with_compat_object_hash(Some(gix_hash::Kind::Sha256));
+        let mut buf = Vec::new();
+        let mut expected = Vec::new();
+        for oid in object_ids() {
+            let obj = locate_oid(oid, &mut buf);
+            assert_eq!(db.write_buf(obj.kind, obj.data)?, oid);
+            expected.push((
+                oid,
+                gix_object::compute_hash(gix_hash::Kind::Sha256, obj.kind, obj.data),
+            ));
+            assert_eq!(
+                db.write_buf(obj.kind, obj.data)?,
+                oid,
+                "objects that exist already aren't recorded again"
+            );
+        }
+
+        let map = loose::object_map::Map::at(dir.path(), gix_hash::Kind::Sha1, gix_hash::Kind::Sha256)?;
+        for (oid, compat_id) in &expected {
+            assert_eq!(map.compat_id(oid), Some(*compat_id));
+            assert_eq!(
+                map.compat_id(compat_id),
+                Some(*oid),
+                "the mapping works in both directions"
+            );
+        }
+        let mut expected_file = String::from("# loose-object-idx\n");
+        for (oid, compat_id) in expected {
+            expected_file.push_str(&format!("{oid} {compat_id}\n"));
+        }
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join(loose::object_map::FILE_NAME))?,
+            expected_file
+        );
+        Ok(())
+    }
+}
+
+mod object_map {
+    use gix_hash::Kind;
+    use gix_odb::loose::object_map::{Error, Map};
+
+    use crate::hex_to_id;
+
+    #[test]
+    fn a_missing_file_knows_only_the_well_known_objects() -> crate::Result {
+        let dir = gix_testtools::tempfile::tempdir()?;
+        let map = Map::at(dir.path(), Kind::Sha1, Kind::Sha256)?;
+        assert_eq!(
+            map.compat_id(&gix_hash::ObjectId::empty_blob(Kind::Sha1)),
+            Some(gix_hash::ObjectId::empty_blob(Kind::Sha256))
+        );
+        assert_eq!(
+            map.compat_id(&gix_hash::ObjectId::empty_tree(Kind::Sha256)),
+            Some(gix_hash::ObjectId::empty_tree(Kind::Sha1))
+        );
+        assert_eq!(map.compat_id(&Kind::Sha1.null()), Some(Kind::Sha256.null()));
+        assert_eq!(
+            map.compat_id(&hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab980")),
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_files_are_rejected() {
+        assert!(matches!(
+            Map::from_bytes(b"", Kind::Sha1, Kind::Sha256),
+            Err(Error::MissingHeader)
+        ));
+        let err = Map::from_bytes(
+            b"# loose-object-idx\n37d4e6c5c48ba0d245164c4e10d5f41140cab980 37d4e6c5c48ba0d245164c4e10d5f41140cab980\n",
+            Kind::Sha1,
+            Kind::Sha256,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Line 2 of the loose object map is not a pair of a SHA1 and a SHA256 object id"
+        );
+    }
 }
 
 mod contains {
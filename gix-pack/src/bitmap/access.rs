@@ -0,0 +1,188 @@
+use gix_hash::{oid, ObjectId};
+
+use crate::bitmap::{Bitmap, File};
+
+/// Access
+impl File {
+    /// The path at which the bitmap file was opened.
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    /// The checksum of the pack this bitmap belongs to.
+    pub fn pack_checksum(&self) -> &oid {
+        &self.pack_checksum
+    }
+
+    /// The amount of commits that have a bitmap.
+    pub fn num_commits(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return the bitmap with a bit set for each object of `kind` in the pack.
+    pub fn objects_of_kind(&self, kind: gix_object::Kind) -> &Bitmap {
+        match kind {
+            gix_object::Kind::Commit => &self.commits,
+            gix_object::Kind::Tree => &self.trees,
+            gix_object::Kind::Blob => &self.blobs,
+            gix_object::Kind::Tag => &self.tags,
+        }
+    }
+
+    /// Return the kind of the object at `bit`, or `None` if the bit is out of bounds.
+    pub fn kind_at_bit(&self, bit: usize) -> Option<gix_object::Kind> {
+        use gix_object::Kind::*;
+        [Commit, Tree, Blob, Tag]
+            .into_iter()
+            .find(|kind| self.objects_of_kind(*kind).contains(bit))
+    }
+
+    /// Return the bitmap of all objects reachable from the commit with `id`, including the commit itself,
+    /// or `None` if there is no bitmap for it.
+    pub fn commit_bitmap(&self, id: &oid) -> Option<Bitmap> {
+        let mut entry_index = *self.entry_by_id.get(id)?;
+        let mut bitmap = Bitmap::default();
+        loop {
+            let entry = &self.entries[entry_index];
+            bitmap.xor(&Bitmap::from(&entry.bitmap));
+            if entry.xor_offset == 0 {
+                break;
+            }
+            entry_index -= usize::from(entry.xor_offset);
+        }
+        Some(bitmap)
+    }
+
+    /// Return the bit representing `id` in the pack described by `index`, or `None` if the object isn't in the pack.
+    pub fn bit_of(&self, index: &crate::index::File, id: &oid) -> Option<usize> {
+        index
+            .lookup(id)
+            .map(|entry_index| self.bit_by_index[entry_index as usize] as usize)
+    }
+
+    /// Return the id of the object at `bit` in the pack described by `index`.
+    ///
+    /// # Panics
+    ///
+    /// If `bit` is out of bounds.
+    pub fn id_at_bit<'a>(&self, index: &'a crate::index::File, bit: usize) -> &'a oid {
+        index.oid_at_index(self.index_by_bit[bit])
+    }
+
+    /// Return the hash of the path at which the object at `bit` was seen when the pack was written, as computed by
+    /// [`name_hash()`](crate::bitmap::name_hash()), or `None` if there is no name-hash cache or the bit is out of bounds.
+    ///
+    /// Objects without a path, like commits, have a hash of 0.
+    pub fn name_hash(&self, bit: usize) -> Option<u32> {
+        let entry_index = *self.index_by_bit.get(bit)?;
+        self.name_hashes.as_ref().map(|hashes| hashes[entry_index as usize])
+    }
+
+    /// Return the ids of all objects whose bit is set in `bitmap`, in pack order, using `index` to look them up.
+    pub fn ids<'a>(&'a self, index: &'a crate::index::File, bitmap: &'a Bitmap) -> impl Iterator<Item = ObjectId> + 'a {
+        bitmap.iter().map(move |bit| self.id_at_bit(index, bit).to_owned())
+    }
+}
+
+/// Access
+impl Bitmap {
+    /// Return `true` if `bit` is set.
+    pub fn contains(&self, bit: usize) -> bool {
+        matches!(self.words.get(bit / 64), Some(word) if word & (1 << (bit % 64)) != 0)
+    }
+
+    /// Set `bit` and return `true` if it wasn't set before.
+    pub fn insert(&mut self, bit: usize) -> bool {
+        let word_index = bit / 64;
+        if word_index >= self.words.len() {
+            self.words.resize(word_index + 1, 0);
+        }
+        let word = &mut self.words[word_index];
+        let mask = 1 << (bit % 64);
+        let is_new = *word & mask == 0;
+        *word |= mask;
+        is_new
+    }
+
+    /// Return the amount of set bits.
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Return `true` if no bit is set.
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// Iterate over all set bits in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(word_index, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(word_index * 64 + bit)
+            })
+        })
+    }
+}
+
+/// Boolean operations
+impl Bitmap {
+    /// Set all bits that are set in `other`.
+    pub fn union_with(&mut self, other: &Bitmap) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word |= other;
+        }
+    }
+
+    /// Clear all bits that aren't set in `other`.
+    pub fn intersect_with(&mut self, other: &Bitmap) {
+        self.words.truncate(other.words.len());
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word &= other;
+        }
+    }
+
+    /// Clear all bits that are set in `other`.
+    pub fn subtract(&mut self, other: &Bitmap) {
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word &= !other;
+        }
+    }
+
+    pub(crate) fn xor(&mut self, other: &Bitmap) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word ^= other;
+        }
+    }
+}
+
+impl PartialEq for Bitmap {
+    fn eq(&self, other: &Self) -> bool {
+        fn trimmed(words: &[u64]) -> &[u64] {
+            let len = words.iter().rposition(|word| *word != 0).map_or(0, |pos| pos + 1);
+            &words[..len]
+        }
+        trimmed(&self.words) == trimmed(&other.words)
+    }
+}
+
+impl Eq for Bitmap {}
+
+impl From<&gix_bitmap::ewah::Vec> for Bitmap {
+    fn from(bitmap: &gix_bitmap::ewah::Vec) -> Self {
+        Bitmap {
+            words: bitmap.to_words(),
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use std::path::Path;
+
+use gix_hash::ObjectId;
+
+use crate::bitmap::{Bitmap, Entry, File, OPT_FULL_DAG, OPT_HASH_CACHE, OPT_LOOKUP_TABLE, SIGNATURE};
+
+/// Returned by [`bitmap::File::at()`](File::at()).
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not open pack bitmap file at '{path}'")]
+    Io {
+        source: std::io::Error,
+        path: std::path::PathBuf,
+    },
+    #[error("{message}")]
+    Corrupt { message: String },
+    #[error("Unsupported bitmap version: {version}")]
+    UnsupportedVersion { version: u16 },
+    #[error("The bitmap belongs to the pack with checksum {actual}, but the pack index belongs to the pack with checksum {expected}")]
+    PackMismatch { expected: ObjectId, actual: ObjectId },
+    #[error("Could not decode the {kind} bitmap")]
+    BitmapDecode {
+        kind: &'static str,
+        source: gix_bitmap::ewah::decode::Error,
+    },
+}
+
+const HEADER_LEN: usize = SIGNATURE.len() + 2 /* version */ + 2 /* options */ + 4 /* entry count */;
+/// The longest chain of bitmaps that are XORed with each other that `git` will write.
+const MAX_XOR_OFFSET: u8 = 160;
+
+/// Instantiation
+impl File {
+    /// Open the bitmap file at the given `path`, which belongs to the pack whose `index` is given.
+    ///
+    /// Only bitmaps for a single pack are supported, those of multi-pack indices are not.
+    pub fn at(path: impl AsRef<Path>, index: &crate::index::File) -> Result<File, Error> {
+        Self::at_inner(path.as_ref(), index)
+    }
+
+    fn at_inner(path: &Path, index: &crate::index::File) -> Result<File, Error> {
+        let data = crate::mmap::read_only(path).map_err(|source| Error::Io {
+            source,
+            path: path.to_owned(),
+        })?;
+        let hash_len = index.object_hash().len_in_bytes();
+        let num_objects = index.num_objects() as usize;
+        if data.len() < HEADER_LEN + hash_len * 2 {
+            return Err(corrupt(format!(
+                "Pack bitmap of size {} is too small for even an empty bitmap",
+                data.len()
+            )));
+        }
+        let (header, d) = data.split_at(HEADER_LEN);
+        if &header[..SIGNATURE.len()] != SIGNATURE {
+            return Err(corrupt("Pack bitmap does not start with the BITM signature".into()));
+        }
+        let version = u16::from_be_bytes([header[4], header[5]]);
+        if version != 1 {
+            return Err(Error::UnsupportedVersion { version });
+        }
+        let options = u16::from_be_bytes([header[6], header[7]]);
+        if options & OPT_FULL_DAG == 0 {
+            return Err(corrupt(
+                "Pack bitmaps that don't contain all reachable objects are not supported".into(),
+            ));
+        }
+        let num_entries = crate::read_u32(&header[8..]) as usize;
+        let (pack_checksum, d) = d.split_at(hash_len);
+        let pack_checksum = ObjectId::from_bytes_or_panic(pack_checksum);
+        if pack_checksum != index.pack_checksum() {
+            return Err(Error::PackMismatch {
+                expected: index.pack_checksum(),
+                actual: pack_checksum,
+            });
+        }
+
+        let mut end = data.len() - hash_len;
+        let name_hashes = if options & OPT_HASH_CACHE != 0 {
+            let cache_len = num_objects * 4;
+            let start = end
+                .checked_sub(cache_len)
+                .filter(|start| *start >= HEADER_LEN + hash_len)
+                .ok_or_else(|| corrupt("Pack bitmap is too short to fit the name-hash cache".into()))?;
+            let hashes = data[start..end].chunks_exact(4).map(crate::read_u32).collect();
+            end = start;
+            Some(hashes)
+        } else {
+            None
+        };
+        if options & OPT_LOOKUP_TABLE != 0 {
+            // The lookup table allows to load bitmaps lazily, which we don't do as all of them are decoded right away.
+            let table_len = num_entries * (4 + 8 + 4);
+            end = end
+                .checked_sub(table_len)
+                .filter(|end| *end >= HEADER_LEN + hash_len)
+                .ok_or_else(|| corrupt("Pack bitmap is too short to fit the lookup table".into()))?;
+        }
+        let d = &d[..end - HEADER_LEN - hash_len];
+
+        let mut types = Vec::with_capacity(4);
+        let mut d = d;
+        for kind in ["commit", "tree", "blob", "tag"] {
+            let (bitmap, rest) = gix_bitmap::ewah::decode(d).map_err(|source| Error::BitmapDecode { kind, source })?;
+            if !bits_are_in_bounds(&bitmap, num_objects) {
+                return Err(corrupt(format!(
+                    "The {kind} bitmap has bits set beyond the {num_objects} objects in the pack"
+                )));
+            }
+            types.push(Bitmap::from(&bitmap));
+            d = rest;
+        }
+
+        let mut entries = Vec::with_capacity(num_entries);
+        let mut entry_by_id = gix_hashtable::HashMap::default();
+        for entry_index in 0..num_entries {
+            if d.len() < 6 {
+                return Err(corrupt(format!(
+                    "Pack bitmap is truncated in the header of commit bitmap {entry_index}"
+                )));
+            }
+            let (entry_header, rest) = d.split_at(6);
+            let position = crate::read_u32(&entry_header[..4]);
+            let xor_offset = entry_header[4];
+            if position >= index.num_objects() {
+                return Err(corrupt(format!(
+                    "Commit bitmap {entry_index} refers to object {position}, but there are only {num_objects} objects"
+                )));
+            }
+            if xor_offset > MAX_XOR_OFFSET || usize::from(xor_offset) > entry_index {
+                return Err(corrupt(format!(
+                    "Commit bitmap {entry_index} has an invalid XOR offset of {xor_offset}"
+                )));
+            }
+            let (bitmap, rest) =
+                gix_bitmap::ewah::decode(rest).map_err(|source| Error::BitmapDecode { kind: "commit", source })?;
+            if !bits_are_in_bounds(&bitmap, num_objects) {
+                return Err(corrupt(format!(
+                    "Commit bitmap {entry_index} has bits set beyond the {num_objects} objects in the pack"
+                )));
+            }
+            d = rest;
+            entry_by_id.insert(index.oid_at_index(position).to_owned(), entry_index);
+            entries.push(Entry { xor_offset, bitmap });
+        }
+        if !d.is_empty() {
+            return Err(corrupt(format!(
+                "Pack bitmap has {} unexpected bytes after the last commit bitmap",
+                d.len()
+            )));
+        }
+
+        let mut index_by_bit: Vec<_> = (0..index.num_objects()).collect();
+        index_by_bit.sort_by_key(|entry_index| index.pack_offset_at_index(*entry_index));
+        let mut bit_by_index = vec![0; num_objects];
+        for (bit, entry_index) in index_by_bit.iter().enumerate() {
+            bit_by_index[*entry_index as usize] = bit as u32;
+        }
+
+        let tags = types.pop().expect("four type bitmaps");
+        let blobs = types.pop().expect("four type bitmaps");
+        let trees = types.pop().expect("four type bitmaps");
+        let commits = types.pop().expect("four type bitmaps");
+        Ok(File {
+            path: path.to_owned(),
+            pack_checksum,
+            commits,
+            trees,
+            blobs,
+            tags,
+            entries,
+            entry_by_id,
+            name_hashes,
+            index_by_bit,
+            bit_by_index,
+        })
+    }
+}
+
+/// Return `true` if all bits set in `bitmap` correspond to one of the `num_objects` objects in the pack.
+///
+/// As bitmaps are combined with XOR, this also holds for all bitmaps that are computed from them.
+fn bits_are_in_bounds(bitmap: &gix_bitmap::ewah::Vec, num_objects: usize) -> bool {
+    bitmap
+        .for_each_set_bit(|bit| (bit < num_objects).then_some(()))
+        .is_some()
+}
+
+fn corrupt(message: String) -> Error {
+    Error::Corrupt { message }
+}
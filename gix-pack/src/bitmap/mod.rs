@@ -0,0 +1,72 @@
+use gix_hash::ObjectId;
+
+/// The flag indicating that bitmaps include all objects reachable from the commit, which is always set.
+const OPT_FULL_DAG: u16 = 0x1;
+/// The flag indicating that the name-hash cache extension is present.
+const OPT_HASH_CACHE: u16 = 0x4;
+/// The flag indicating that the lookup table extension is present.
+const OPT_LOOKUP_TABLE: u16 = 0x10;
+
+const SIGNATURE: &[u8] = b"BITM";
+
+/// A bitmap index as stored in a `.bitmap` file next to a pack and its index, to quickly learn which objects of the pack are
+/// reachable from a selection of commits.
+///
+/// Each bit corresponds to an object in the pack, in the order in which the objects are stored in the pack, as opposed to the
+/// order of the [pack index](crate::index::File) that is sorted by object id. Thus, the pack index that belongs to the bitmap
+/// is needed to associate bits with objects and has to be passed to all methods that need to do so.
+pub struct File {
+    path: std::path::PathBuf,
+    pack_checksum: ObjectId,
+    commits: Bitmap,
+    trees: Bitmap,
+    blobs: Bitmap,
+    tags: Bitmap,
+    /// All commit bitmaps in the order they are stored in the file, as their XOR-bases are referred to relative to that.
+    entries: Vec<Entry>,
+    /// Map a commit id to its entry in `entries`.
+    entry_by_id: gix_hashtable::HashMap<ObjectId, usize>,
+    /// The name-hash of each object, sorted like the pack index.
+    name_hashes: Option<Vec<u32>>,
+    /// Map a bit, which is a position in the pack, to the position of the object in the pack index.
+    index_by_bit: Vec<crate::index::EntryIndex>,
+    /// Map the position of an object in the pack index to its bit.
+    bit_by_index: Vec<u32>,
+}
+
+/// A bitmap of a single commit as stored in the bitmap file.
+struct Entry {
+    /// The amount of entries before this one at which to find the bitmap to XOR this one with, or 0 if it is stored as is.
+    xor_offset: u8,
+    bitmap: gix_bitmap::ewah::Vec,
+}
+
+/// An uncompressed bitmap in which each bit represents an object of the pack the [bitmap file](File) belongs to.
+///
+/// Bitmaps are equal if the same bits are set, no matter how many bits they can hold.
+#[derive(Default, Clone, Debug)]
+pub struct Bitmap {
+    /// The bit at `index` is stored in `words[index / 64]` at `1 << (index % 64)`.
+    words: Vec<u64>,
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod init;
+
+mod access;
+
+///
+#[allow(clippy::empty_docs)]
+pub mod reachable;
+pub use reachable::Reachable;
+
+/// Compute the hash of `path` like `git` does, for comparison with the hashes stored in the name-hash cache,
+/// see [`File::name_hash()`].
+///
+/// Objects with similar paths have similar hashes, which is used to find good delta bases among them.
+pub fn name_hash(path: &[u8]) -> u32 {
+    path.iter()
+        .filter(|b| !matches!(b, b' ' | b'\t' | b'\n' | b'\r'))
+        .fold(0, |hash: u32, b| (hash >> 2).wrapping_add(u32::from(*b) << 24))
+}
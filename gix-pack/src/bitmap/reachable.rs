@@ -0,0 +1,128 @@
+use gix_hash::ObjectId;
+use gix_object::FindExt;
+
+use crate::bitmap::{Bitmap, File};
+
+/// The error returned by [`File::reachable()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Find(#[from] gix_object::find::existing::Error),
+    #[error(transparent)]
+    Decode(#[from] gix_object::decode::Error),
+}
+
+/// A set of objects, represented as [`Bitmap`] for objects in the pack the [bitmap file](File) belongs to, and as ids of objects
+/// that are stored elsewhere.
+#[derive(Default, Clone, Debug)]
+pub struct Reachable {
+    /// All objects that are in the pack.
+    pub in_pack: Bitmap,
+    /// All objects that are not in the pack, along with their kind.
+    pub extended: gix_hashtable::HashMap<ObjectId, gix_object::Kind>,
+}
+
+impl Reachable {
+    /// Return the amount of objects.
+    pub fn len(&self) -> usize {
+        self.in_pack.len() + self.extended.len()
+    }
+
+    /// Return `true` if there are no objects.
+    pub fn is_empty(&self) -> bool {
+        self.in_pack.is_empty() && self.extended.is_empty()
+    }
+
+    /// Remove all objects that are also contained in `other`.
+    pub fn subtract(&mut self, other: &Reachable) {
+        self.in_pack.subtract(&other.in_pack);
+        self.extended.retain(|id, _| !other.extended.contains_key(id));
+    }
+
+    /// Remove all objects of `kind`, using `bitmap` to learn the kind of objects in the pack.
+    pub fn remove_kind(&mut self, bitmap: &File, kind: gix_object::Kind) {
+        self.in_pack.subtract(bitmap.objects_of_kind(kind));
+        self.extended.retain(|_, extended_kind| *extended_kind != kind);
+    }
+
+    /// Return the ids of all objects, first those in the pack in pack order, followed by all others in no particular order,
+    /// using `bitmap` and its `index` to look them up.
+    pub fn ids<'a>(&'a self, bitmap: &'a File, index: &'a crate::index::File) -> impl Iterator<Item = ObjectId> + 'a {
+        bitmap.ids(index, &self.in_pack).chain(self.extended.keys().copied())
+    }
+
+    fn contains(&self, bit: Option<usize>, id: &gix_hash::oid) -> bool {
+        match bit {
+            Some(bit) => self.in_pack.contains(bit),
+            None => self.extended.contains_key(id),
+        }
+    }
+
+    fn insert(&mut self, bit: Option<usize>, id: &gix_hash::oid, kind: gix_object::Kind) {
+        match bit {
+            Some(bit) => {
+                self.in_pack.insert(bit);
+            }
+            None => {
+                self.extended.insert(id.to_owned(), kind);
+            }
+        }
+    }
+}
+
+impl File {
+    /// Return all objects reachable from `tips`, including the tips themselves, with `index` belonging to the pack of this bitmap.
+    ///
+    /// Bitmaps of commits are used whenever possible, while all objects not covered by them are found by walking the object graph
+    /// with `objects`, until a commit with bitmap is found. Thus, objects not contained in the pack are found as well.
+    /// Note that submodule commits are never included.
+    ///
+    /// To learn which objects need to be sent to a remote that wants some commits and has others, compute the objects
+    /// reachable from the commits it wants and [subtract](Reachable::subtract()) the ones reachable from the commits it has.
+    pub fn reachable(
+        &self,
+        index: &crate::index::File,
+        tips: impl IntoIterator<Item = ObjectId>,
+        objects: &dyn gix_object::Find,
+    ) -> Result<Reachable, Error> {
+        let mut out = Reachable::default();
+        let mut buf = Vec::new();
+        let mut stack: Vec<_> = tips.into_iter().collect();
+        while let Some(id) = stack.pop() {
+            let bit = self.bit_of(index, &id);
+            if out.contains(bit, &id) {
+                continue;
+            }
+            if let Some(bitmap) = self.commit_bitmap(&id) {
+                out.in_pack.union_with(&bitmap);
+                continue;
+            }
+            let object = objects.find(&id, &mut buf)?;
+            out.insert(bit, &id, object.kind);
+            match object.kind {
+                gix_object::Kind::Commit => {
+                    let mut commit = gix_object::CommitRefIter::from_bytes(object.data);
+                    stack.push(commit.tree_id()?);
+                    stack.extend(commit.parent_ids());
+                }
+                gix_object::Kind::Tree => {
+                    for entry in gix_object::TreeRefIter::from_bytes(object.data) {
+                        let entry = entry?;
+                        if entry.mode.is_tree() {
+                            stack.push(entry.oid.to_owned());
+                        } else if !entry.mode.is_commit() {
+                            // Blobs don't lead anywhere, so there is no need to read them.
+                            out.insert(self.bit_of(index, entry.oid), entry.oid, gix_object::Kind::Blob);
+                        }
+                    }
+                }
+                gix_object::Kind::Tag => {
+                    stack.push(gix_object::TagRefIter::from_bytes(object.data).target_id()?);
+                }
+                gix_object::Kind::Blob => {}
+            }
+        }
+        Ok(out)
+    }
+}
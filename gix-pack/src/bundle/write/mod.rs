@@ -269,6 +269,7 @@ impl crate::Bundle {
             iteration_mode: _,
             index_version: index_kind,
             object_hash,
+            compat_object_hash,
         }: Options,
         data_file: SharedTempFile,
         mut pack_entries_iter: Box<dyn Iterator<Item = Result<data::input::Entry, data::input::Error>> + 'a>,
@@ -297,6 +298,7 @@ impl crate::Bundle {
                     should_interrupt,
                     object_hash,
                     pack_version,
+                    compat_object_hash,
                 )?;
                 drop(pack_entries_iter);
 
@@ -353,6 +355,7 @@ impl crate::Bundle {
                     should_interrupt,
                     object_hash,
                     pack_version,
+                    compat_object_hash,
                 )?,
                 data_path: None,
                 index_path: None,
@@ -14,6 +14,9 @@ pub struct Options {
     pub index_version: crate::index::Version,
     /// The kind of hash to use when writing the bundle.
     pub object_hash: gix_hash::Kind,
+    /// If set, also compute the id of each object with this hash, to be returned in
+    /// [`compat_ids`](crate::index::write::Outcome::compat_ids).
+    pub compat_object_hash: Option<gix_hash::Kind>,
 }
 
 impl Default for Options {
@@ -24,6 +27,7 @@ impl Default for Options {
             iteration_mode: crate::data::input::Mode::Verify,
             index_version: Default::default(),
             object_hash: Default::default(),
+            compat_object_hash: None,
         }
     }
 }
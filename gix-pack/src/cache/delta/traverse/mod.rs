@@ -30,6 +30,17 @@ pub enum Error {
     EntryType(#[from] crate::data::entry::decode::Error),
     #[error("One of the object inspectors failed")]
     Inspect(#[from] Box<dyn std::error::Error + Send + Sync>),
+    #[error("The delta at {pack_offset} expects a base of {expected} bytes, but it has {actual} bytes")]
+    BaseSizeMismatch {
+        pack_offset: crate::data::Offset,
+        expected: u64,
+        actual: u64,
+    },
+    #[error("Failed to apply the delta at {pack_offset}")]
+    DeltaApply {
+        source: crate::data::delta::apply::Error,
+        pack_offset: crate::data::Offset,
+    },
     #[error("Interrupted")]
     Interrupted,
     #[error(
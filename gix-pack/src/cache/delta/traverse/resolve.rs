@@ -177,18 +177,7 @@ where
 
         for mut child in base.into_child_iter() {
             let (mut child_entry, entry_end) = decompress_from_resolver(child.entry_slice(), delta_bytes)?;
-            let (base_size, consumed) = data::delta::decode_header_size(delta_bytes);
-            let mut header_ofs = consumed;
-            assert_eq!(
-                base_bytes.len(),
-                base_size as usize,
-                "recorded base size in delta does match the actual one"
-            );
-            let (result_size, consumed) = data::delta::decode_header_size(&delta_bytes[consumed..]);
-            header_ofs += consumed;
-
-            fully_resolved_delta_bytes.resize(result_size as usize, 0);
-            data::delta::apply(&base_bytes, fully_resolved_delta_bytes, &delta_bytes[header_ofs..]);
+            apply_delta(child.offset(), &base_bytes, delta_bytes, fully_resolved_delta_bytes)?;
 
             // FIXME: this actually invalidates the "pack_offset()" computation, which is not obvious to consumers
             //        at all
@@ -351,23 +340,12 @@ where
                                 for mut child in base.into_child_iter() {
                                     let (mut child_entry, entry_end) =
                                         decompress_from_resolver(child.entry_slice(), &mut delta_bytes)?;
-                                    let (base_size, consumed) = data::delta::decode_header_size(&delta_bytes);
-                                    let mut header_ofs = consumed;
-                                    assert_eq!(
-                                        base_bytes.len(),
-                                        base_size as usize,
-                                        "recorded base size in delta does match the actual one"
-                                    );
-                                    let (result_size, consumed) =
-                                        data::delta::decode_header_size(&delta_bytes[consumed..]);
-                                    header_ofs += consumed;
-
-                                    fully_resolved_delta_bytes.resize(result_size as usize, 0);
-                                    data::delta::apply(
+                                    apply_delta(
+                                        child.offset(),
                                         &base_bytes,
+                                        &delta_bytes,
                                         &mut fully_resolved_delta_bytes,
-                                        &delta_bytes[header_ofs..],
-                                    );
+                                    )?;
 
                                     // FIXME: this actually invalidates the "pack_offset()" computation, which is not obvious to consumers
                                     //        at all
@@ -466,3 +444,20 @@ fn decompress_all_at_once_with(
     })?;
     Ok(())
 }
+
+/// Apply the decompressed `delta` of the entry at `pack_offset` to `base` and write the result into `out`.
+fn apply_delta(pack_offset: data::Offset, base: &[u8], delta: &[u8], out: &mut Vec<u8>) -> Result<(), Error> {
+    let (base_size, consumed) = data::delta::decode_header_size(delta);
+    let mut header_ofs = consumed;
+    if base.len() as u64 != base_size {
+        return Err(Error::BaseSizeMismatch {
+            pack_offset,
+            expected: base_size,
+            actual: base.len() as u64,
+        });
+    }
+    let (result_size, consumed) = data::delta::decode_header_size(&delta[header_ofs..]);
+    header_ofs += consumed;
+    data::delta::apply_to_vec(base, out, &delta[header_ofs..], result_size)
+        .map_err(|source| Error::DeltaApply { source, pack_offset })
+}
@@ -1,3 +1,23 @@
+///
+#[allow(clippy::empty_docs)]
+pub mod apply {
+    /// Returned when applying a delta with [`apply()`](super::apply()) or [`apply_to_vec()`](super::apply_to_vec()).
+    #[derive(thiserror::Error, Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Encountered unsupported delta instruction 0 at offset {offset}")]
+        UnsupportedInstruction { offset: usize },
+        #[error("The delta instruction at offset {offset} is truncated")]
+        TruncatedInstruction { offset: usize },
+        #[error("Delta copies {size} bytes at offset {offset} of its base, which has only {base_size} bytes")]
+        CopyOutOfBounds { offset: u64, size: u64, base_size: usize },
+        #[error("Delta produces more bytes than the {expected} bytes announced in its header")]
+        ResultTooLarge { expected: u64 },
+        #[error("Delta produced {actual} bytes, but {expected} bytes were announced in its header")]
+        ResultSizeMismatch { expected: u64, actual: u64 },
+    }
+}
+
 /// Given the decompressed pack delta `d`, decode a size in bytes (either the base object size or the result object size)
 /// Equivalent to [this canonical git function](https://github.com/git/git/blob/311531c9de557d25ac087c1637818bd2aad6eb3a/delta.h#L89)
 pub fn decode_header_size(d: &[u8]) -> (u64, usize) {
@@ -15,56 +35,106 @@ pub fn decode_header_size(d: &[u8]) -> (u64, usize) {
     (size, consumed)
 }
 
-pub fn apply(base: &[u8], mut target: &mut [u8], data: &[u8]) {
+/// Apply the delta instructions in `data`, i.e. the delta without its size header, to `base` and write the result into `target`,
+/// which must have exactly the size of the result.
+///
+/// Malformed instructions, copies outside of `base` or results that don't fill `target` exactly are an error.
+pub fn apply(base: &[u8], mut target: &mut [u8], data: &[u8]) -> Result<(), apply::Error> {
+    let expected = target.len() as u64;
+    for_each_chunk(base, data, |chunk| {
+        if chunk.len() > target.len() {
+            return Err(apply::Error::ResultTooLarge { expected });
+        }
+        let (out, rest) = std::mem::take(&mut target).split_at_mut(chunk.len());
+        out.copy_from_slice(chunk);
+        target = rest;
+        Ok(())
+    })?;
+    if !target.is_empty() {
+        return Err(apply::Error::ResultSizeMismatch {
+            expected,
+            actual: expected - target.len() as u64,
+        });
+    }
+    Ok(())
+}
+
+/// Like [`apply()`], but write the result into `out` after clearing it, which must end up with `result_size` bytes.
+///
+/// As `result_size` is read from untrusted delta headers, `out` only grows with the data actually produced by the delta,
+/// so that bogus sizes cause an error instead of a huge allocation.
+pub fn apply_to_vec(base: &[u8], out: &mut Vec<u8>, data: &[u8], result_size: u64) -> Result<(), apply::Error> {
+    out.clear();
+    out.reserve((result_size as usize).min(base.len() + data.len()));
+    for_each_chunk(base, data, |chunk| {
+        if (out.len() + chunk.len()) as u64 > result_size {
+            return Err(apply::Error::ResultTooLarge { expected: result_size });
+        }
+        out.extend_from_slice(chunk);
+        Ok(())
+    })?;
+    if out.len() as u64 != result_size {
+        return Err(apply::Error::ResultSizeMismatch {
+            expected: result_size,
+            actual: out.len() as u64,
+        });
+    }
+    Ok(())
+}
+
+/// Decode all instructions in `data` and call `write` with each chunk of bytes they produce, either copied from `base`
+/// or inserted from `data`.
+fn for_each_chunk(
+    base: &[u8],
+    data: &[u8],
+    mut write: impl FnMut(&[u8]) -> Result<(), apply::Error>,
+) -> Result<(), apply::Error> {
     let mut i = 0;
-    while let Some(cmd) = data.get(i) {
+    while let Some(&cmd) = data.get(i) {
+        let cmd_offset = i;
         i += 1;
+        let next_byte = |i: &mut usize| -> Result<u32, apply::Error> {
+            let byte = *data
+                .get(*i)
+                .ok_or(apply::Error::TruncatedInstruction { offset: cmd_offset })?;
+            *i += 1;
+            Ok(byte as u32)
+        };
         match cmd {
             cmd if cmd & 0b1000_0000 != 0 => {
                 let (mut ofs, mut size): (u32, u32) = (0, 0);
-                if cmd & 0b0000_0001 != 0 {
-                    ofs = data[i] as u32;
-                    i += 1;
-                }
-                if cmd & 0b0000_0010 != 0 {
-                    ofs |= (data[i] as u32) << 8;
-                    i += 1;
-                }
-                if cmd & 0b0000_0100 != 0 {
-                    ofs |= (data[i] as u32) << 16;
-                    i += 1;
-                }
-                if cmd & 0b0000_1000 != 0 {
-                    ofs |= (data[i] as u32) << 24;
-                    i += 1;
-                }
-                if cmd & 0b0001_0000 != 0 {
-                    size = data[i] as u32;
-                    i += 1;
-                }
-                if cmd & 0b0010_0000 != 0 {
-                    size |= (data[i] as u32) << 8;
-                    i += 1;
+                for (bit, shift) in [(0b0000_0001, 0), (0b0000_0010, 8), (0b0000_0100, 16), (0b0000_1000, 24)] {
+                    if cmd & bit != 0 {
+                        ofs |= next_byte(&mut i)? << shift;
+                    }
                 }
-                if cmd & 0b0100_0000 != 0 {
-                    size |= (data[i] as u32) << 16;
-                    i += 1;
+                for (bit, shift) in [(0b0001_0000, 0), (0b0010_0000, 8), (0b0100_0000, 16)] {
+                    if cmd & bit != 0 {
+                        size |= next_byte(&mut i)? << shift;
+                    }
                 }
                 if size == 0 {
                     size = 0x10000; // 65536
                 }
-                let ofs = ofs as usize;
-                std::io::Write::write(&mut target, &base[ofs..ofs + size as usize])
-                    .expect("delta copy from base: byte slices must match");
+                let chunk = (ofs as usize)
+                    .checked_add(size as usize)
+                    .and_then(|end| base.get(ofs as usize..end))
+                    .ok_or(apply::Error::CopyOutOfBounds {
+                        offset: ofs as u64,
+                        size: size as u64,
+                        base_size: base.len(),
+                    })?;
+                write(chunk)?;
             }
-            0 => panic!("encountered unsupported command code: 0"),
+            0 => return Err(apply::Error::UnsupportedInstruction { offset: cmd_offset }),
             size => {
-                std::io::Write::write(&mut target, &data[i..i + *size as usize])
-                    .expect("delta copy data: slice sizes to match up");
-                i += *size as usize;
+                let chunk = data
+                    .get(i..i + size as usize)
+                    .ok_or(apply::Error::TruncatedInstruction { offset: cmd_offset })?;
+                write(chunk)?;
+                i += size as usize;
             }
         }
     }
-    assert_eq!(i, data.len());
-    assert_eq!(target.len(), 0);
+    Ok(())
 }
@@ -20,6 +20,9 @@ pub enum ResolvedBase {
     OutOfPack { kind: gix_object::Kind, end: usize },
 }
 
+/// The maximum size of an entry header, with the decompressed size and the base distance or id of the longest hash.
+const MAX_ENTRY_HEADER_LEN: usize = 64;
+
 #[derive(Debug)]
 struct Delta {
     data: Range<usize>,
@@ -97,18 +100,21 @@ impl File {
         );
 
         self.decompress_entry_from_data_offset(entry.data_offset, inflate, out)
-            .map_err(Into::into)
     }
 
     /// Obtain the [`Entry`][crate::data::Entry] at the given `offset` into the pack.
     ///
     /// The `offset` is typically obtained from the pack index file.
-    pub fn entry(&self, offset: data::Offset) -> Result<data::Entry, data::entry::decode::Error> {
+    pub fn entry(&self, offset: data::Offset) -> Result<data::Entry, Error> {
         let pack_offset: usize = offset.try_into().expect("offset representable by machine");
-        assert!(pack_offset <= self.data.len(), "offset out of bounds");
+        if pack_offset > self.data.len() {
+            return Err(data::window::out_of_bounds(offset..offset, self.data.len() as u64).into());
+        }
 
-        let object_data = &self.data[pack_offset..];
-        data::Entry::from_bytes(object_data, offset, self.hash_len)
+        let object_data = self
+            .data
+            .slice(pack_offset..(pack_offset + MAX_ENTRY_HEADER_LEN).min(self.data.len()))?;
+        Ok(data::Entry::from_bytes(&object_data, offset, self.hash_len)?)
     }
 
     /// Decompress the object expected at the given data offset, sans pack header. This information is only
@@ -121,14 +127,9 @@ impl File {
         data_offset: data::Offset,
         inflate: &mut zlib::Inflate,
         out: &mut [u8],
-    ) -> Result<usize, zlib::inflate::Error> {
-        let offset: usize = data_offset.try_into().expect("offset representable by machine");
-        assert!(offset < self.data.len(), "entry offset out of bounds");
-
-        inflate.reset();
-        inflate
-            .once(&self.data[offset..], out)
-            .map(|(_status, consumed_in, _consumed_out)| consumed_in)
+    ) -> Result<usize, Error> {
+        self.decompress_entry_from_data_offset_2(data_offset, inflate, out)
+            .map(|(consumed_in, _consumed_out)| consumed_in)
     }
 
     /// Like `decompress_entry_from_data_offset`, but returns consumed input and output.
@@ -137,14 +138,28 @@ impl File {
         data_offset: data::Offset,
         inflate: &mut zlib::Inflate,
         out: &mut [u8],
-    ) -> Result<(usize, usize), zlib::inflate::Error> {
-        let offset: usize = data_offset.try_into().expect("offset representable by machine");
-        assert!(offset < self.data.len(), "entry offset out of bounds");
+    ) -> Result<(usize, usize), Error> {
+        let mut offset: usize = data_offset.try_into().expect("offset representable by machine");
 
         inflate.reset();
-        inflate
-            .once(&self.data[offset..], out)
-            .map(|(_status, consumed_in, consumed_out)| (consumed_in, consumed_out))
+        let (mut total_in, mut total_out) = (0, 0);
+        // Windows may end before the compressed stream does, so keep feeding what follows until the stream ends
+        // or no progress is made, which is also when the input wasn't consumed entirely.
+        loop {
+            let input = self.data.slice_from(offset)?;
+            let (status, consumed_in, consumed_out) = inflate.once(&input, &mut out[total_out..])?;
+            total_in += consumed_in;
+            total_out += consumed_out;
+            offset += consumed_in;
+            if status == zlib::Status::StreamEnd
+                || consumed_in < input.len()
+                || offset >= self.data.len()
+                || (consumed_in == 0 && consumed_out == 0)
+            {
+                break;
+            }
+        }
+        Ok((total_in, total_out))
     }
 
     /// Decode an entry, resolving delta's as needed, while growing the `out` vector if there is not enough
@@ -295,12 +310,12 @@ impl File {
                 let (base_size, offset) = delta::decode_header_size(instructions);
                 let mut bytes_consumed_by_header = offset;
                 biggest_result_size = biggest_result_size.max(base_size);
-                delta.base_size = base_size.try_into().expect("base size fits into usize");
+                delta.base_size = base_size.try_into().map_err(|_| Error::OutOfMemory)?;
 
                 let (result_size, offset) = delta::decode_header_size(&instructions[offset..]);
                 bytes_consumed_by_header += offset;
                 biggest_result_size = biggest_result_size.max(result_size);
-                delta.result_size = result_size.try_into().expect("result size fits into usize");
+                delta.result_size = result_size.try_into().map_err(|_| Error::OutOfMemory)?;
 
                 // the absolute location into the instructions buffer, so we keep track of the end point of the last
                 delta.data.start = relative_delta_start + bytes_consumed_by_header;
@@ -374,7 +389,7 @@ impl File {
             if delta_idx + 1 == chain_len {
                 last_result_size = Some(result_size);
             }
-            delta::apply(&source_buf[..base_size], &mut target_buf[..result_size], data);
+            delta::apply(&source_buf[..base_size], &mut target_buf[..result_size], data)?;
             // use the target as source for the next delta
             std::mem::swap(&mut source_buf, &mut target_buf);
         }
@@ -19,8 +19,12 @@ pub enum Error {
     DeltaBaseUnresolved(gix_hash::ObjectId),
     #[error(transparent)]
     EntryType(#[from] crate::data::entry::decode::Error),
+    #[error("Failed to apply a delta of the chain")]
+    DeltaApply(#[from] crate::data::delta::apply::Error),
     #[error("Entry too large to fit in memory")]
     OutOfMemory,
+    #[error("Could not read pack data")]
+    Io(#[from] std::io::Error),
 }
 
 impl From<TryReserveError> for Error {
@@ -9,25 +9,52 @@ impl data::File {
     /// The `object_hash` is a way to read (and write) the same file format with different hashes, as the hash kind
     /// isn't stored within the file format itself.
     pub fn at(path: impl AsRef<Path>, object_hash: gix_hash::Kind) -> Result<data::File, data::header::decode::Error> {
-        Self::at_inner(path.as_ref(), object_hash)
+        Self::at_inner(path.as_ref(), object_hash, None)
     }
 
-    fn at_inner(path: &Path, object_hash: gix_hash::Kind) -> Result<data::File, data::header::decode::Error> {
+    /// Like [`at()`](Self::at()), but instead of mapping the data file as a whole, access it through windows
+    /// managed by `windows`, which is typically shared by all packs of a repository.
+    pub fn at_with_windows(
+        path: impl AsRef<Path>,
+        object_hash: gix_hash::Kind,
+        windows: std::sync::Arc<data::window::Cache>,
+    ) -> Result<data::File, data::header::decode::Error> {
+        Self::at_inner(path.as_ref(), object_hash, Some(windows))
+    }
+
+    fn at_inner(
+        path: &Path,
+        object_hash: gix_hash::Kind,
+        windows: Option<std::sync::Arc<data::window::Cache>>,
+    ) -> Result<data::File, data::header::decode::Error> {
         use crate::data::header::N32_SIZE;
         let hash_len = object_hash.len_in_bytes();
 
-        let data = crate::mmap::read_only(path).map_err(|e| data::header::decode::Error::Io {
+        let io_err = |e| data::header::decode::Error::Io {
             source: e,
             path: path.to_owned(),
-        })?;
+        };
+        let data = match windows {
+            Some(windows) => data::Data::Windowed(
+                std::fs::File::open(path)
+                    .and_then(|file| data::window::Windows::new(file, windows))
+                    .map_err(io_err)?,
+            ),
+            None => data::Data::Mapped(crate::mmap::read_only(path).map_err(io_err)?),
+        };
         let pack_len = data.len();
         if pack_len < N32_SIZE * 3 + hash_len {
             return Err(data::header::decode::Error::Corrupt(format!(
                 "Pack data of size {pack_len} is too small for even an empty pack with shortest hash"
             )));
         }
-        let (kind, num_objects) =
-            data::header::decode(&data[..12].try_into().expect("enough data after previous check"))?;
+        let (kind, num_objects) = data::header::decode(
+            &data.slice(0..12).map_err(io_err)?[..]
+                .try_into()
+                .expect("enough data after previous check"),
+        )?;
+        let checksum =
+            gix_hash::ObjectId::from_bytes_or_panic(&data.slice(pack_len - hash_len..pack_len).map_err(io_err)?);
         Ok(data::File {
             data,
             path: path.to_owned(),
@@ -36,6 +63,7 @@ impl data::File {
             num_objects,
             hash_len,
             object_hash,
+            checksum,
         })
     }
 }
@@ -7,5 +7,7 @@ pub mod verify;
 #[allow(clippy::empty_docs)]
 pub mod decode;
 
+pub mod window;
+
 /// The bytes used as header in a pack data file.
 pub type Header = [u8; 12];
@@ -2,7 +2,7 @@ use std::sync::atomic::AtomicBool;
 
 use gix_features::progress::Progress;
 
-use crate::data::File;
+use crate::data::{Data, File};
 
 ///
 #[allow(clippy::empty_docs)]
@@ -15,7 +15,7 @@ pub mod checksum {
 impl File {
     /// The checksum in the trailer of this pack data file
     pub fn checksum(&self) -> gix_hash::ObjectId {
-        gix_hash::ObjectId::from_bytes_or_panic(&self.data[self.data.len() - self.hash_len..])
+        self.checksum
     }
 
     /// Verifies that the checksum of the packfile over all bytes preceding it indeed matches the actual checksum,
@@ -31,13 +31,47 @@ impl File {
         progress: &mut dyn Progress,
         should_interrupt: &AtomicBool,
     ) -> Result<gix_hash::ObjectId, checksum::Error> {
-        crate::verify::checksum_on_disk_or_mmap(
-            self.path(),
-            &self.data,
-            self.checksum(),
-            self.object_hash,
-            progress,
-            should_interrupt,
-        )
+        match &self.data {
+            Data::Mapped(map) => crate::verify::checksum_on_disk_or_mmap(
+                self.path(),
+                map,
+                self.checksum(),
+                self.object_hash,
+                progress,
+                should_interrupt,
+            ),
+            Data::Windowed(_) => {
+                let expected = self.checksum();
+                let actual = match gix_features::hash::bytes_of_file(
+                    self.path(),
+                    self.pack_end() as u64,
+                    self.object_hash,
+                    progress,
+                    should_interrupt,
+                ) {
+                    Ok(id) => id,
+                    Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {
+                        return Err(checksum::Error::Interrupted)
+                    }
+                    Err(_io_err) => {
+                        let mut hasher = gix_features::hash::hasher(self.object_hash);
+                        let mut offset = 0;
+                        while offset < self.pack_end() {
+                            let data = self.data.slice_from(offset)?;
+                            let data = &data[..data.len().min(self.pack_end() - offset)];
+                            hasher.update(data);
+                            progress.inc_by(data.len());
+                            offset += data.len();
+                        }
+                        hasher.digest()
+                    }
+                };
+                if actual == expected {
+                    Ok(actual)
+                } else {
+                    Err(checksum::Error::Mismatch { actual, expected })
+                }
+            }
+        }
     }
 }
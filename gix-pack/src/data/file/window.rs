@@ -0,0 +1,361 @@
+//! Access to pack data through memory-mapped windows of limited size.
+//!
+//! Mapping packs in their entirety is fastest, but may exhaust the address space on 32-bit systems or if many large packs are open.
+//! With windows, only the portions of a pack that are accessed are mapped, and the total of mapped bytes is kept within a budget
+//! shared by all packs using the same [`Cache`], similar to what `git` does with `core.packedGitWindowSize` and `core.packedGitLimit`.
+use std::{
+    ops::{Deref, Range},
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Weak,
+    },
+};
+
+use parking_lot::Mutex;
+
+const MIB: usize = 1024 * 1024;
+
+/// Configuration for windowed access to pack data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// The size of each window in bytes, the equivalent of `core.packedGitWindowSize`.
+    ///
+    /// Windows start at multiples of this size, and are enlarged if data would otherwise cross their end.
+    pub window_size: usize,
+    /// The total amount of bytes mapped by all windows of all packs sharing a [`Cache`], the equivalent of `core.packedGitLimit`.
+    ///
+    /// It is exceeded temporarily if all windows are in use while another one is needed.
+    pub limit: usize,
+}
+
+impl Default for Options {
+    /// The defaults of `git`, which depend on the pointer width of the target.
+    fn default() -> Self {
+        #[cfg(target_pointer_width = "64")]
+        let options = Options {
+            window_size: 1024 * MIB,
+            limit: 32 * 1024 * 1024 * MIB,
+        };
+        #[cfg(not(target_pointer_width = "64"))]
+        let options = Options {
+            window_size: 32 * MIB,
+            limit: 256 * MIB,
+        };
+        options
+    }
+}
+
+/// A cache of mapped windows shared by all packs using it, which unmaps the least recently used windows to keep the total amount
+/// of mapped bytes within [`Options::limit`].
+///
+/// Windows are reference-counted, so windows that are still read from by another thread are never unmapped.
+/// Each pack keeps its own windows, so accessing them only locks the pack, while all packs are only visited to
+/// find windows to unmap.
+pub struct Cache {
+    options: Options,
+    /// The total amount of bytes mapped by the windows of all packs.
+    mapped_bytes: AtomicUsize,
+    /// A counter which is incremented with each access to a window, to know which windows were used least recently.
+    clock: AtomicU64,
+    /// The windows of all packs using this cache.
+    packs: Mutex<Vec<Weak<PackWindows>>>,
+}
+
+/// The windows of a single pack, sorted by their offset.
+#[derive(Default)]
+struct PackWindows(Mutex<Vec<CachedWindow>>);
+
+struct CachedWindow {
+    window: Arc<Window>,
+    last_used: u64,
+}
+
+/// A portion of a pack data file, mapped into memory.
+pub(crate) struct Window {
+    /// The offset into the pack at which `map` starts.
+    offset: u64,
+    map: memmap2::Mmap,
+}
+
+impl Window {
+    fn contains(&self, range: &Range<u64>) -> bool {
+        self.offset <= range.start && range.end <= self.offset + self.map.len() as u64
+    }
+}
+
+/// Lifecycle
+impl Cache {
+    /// Create a new instance which maps windows as configured by `options`.
+    pub fn new(options: Options) -> Self {
+        Cache {
+            options: Options {
+                window_size: options.window_size.max(1),
+                ..options
+            },
+            mapped_bytes: AtomicUsize::new(0),
+            clock: AtomicU64::new(0),
+            packs: Default::default(),
+        }
+    }
+}
+
+/// Access
+impl Cache {
+    /// The options this instance was created with.
+    pub fn options(&self) -> Options {
+        self.options
+    }
+
+    /// The total amount of bytes currently mapped by all windows.
+    pub fn mapped_bytes(&self) -> usize {
+        self.mapped_bytes.load(Ordering::Relaxed)
+    }
+
+    /// The amount of windows that are currently mapped.
+    pub fn num_windows(&self) -> usize {
+        self.packs
+            .lock()
+            .iter()
+            .filter_map(Weak::upgrade)
+            .map(|pack| pack.0.lock().len())
+            .sum()
+    }
+}
+
+impl std::fmt::Debug for Cache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cache")
+            .field("options", &self.options)
+            .field("mapped_bytes", &self.mapped_bytes())
+            .finish()
+    }
+}
+
+impl Cache {
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Unmap the least recently used window of all packs that isn't in use, and return `true` if there was one.
+    ///
+    /// Must not be called while the windows of a pack are locked.
+    fn unmap_least_recently_used(&self) -> bool {
+        let mut packs = self.packs.lock();
+        packs.retain(|pack| pack.strong_count() > 0);
+        let mut least_recently_used: Option<(u64, Arc<PackWindows>)> = None;
+        for pack in packs.iter().filter_map(Weak::upgrade) {
+            let oldest = pack
+                .0
+                .lock()
+                .iter()
+                .filter(|cached| Arc::strong_count(&cached.window) == 1)
+                .map(|cached| cached.last_used)
+                .min();
+            if let Some(last_used) = oldest {
+                if least_recently_used
+                    .as_ref()
+                    .map_or(true, |(least_recently_used, _)| last_used < *least_recently_used)
+                {
+                    least_recently_used = Some((last_used, pack));
+                }
+            }
+        }
+        let Some((last_used, pack)) = least_recently_used else {
+            return false;
+        };
+        let mut windows = pack.0.lock();
+        // The window may have been used again in the meantime, in which case it's kept and we won't try any further.
+        let Some(idx) = windows
+            .iter()
+            .position(|cached| cached.last_used == last_used && Arc::strong_count(&cached.window) == 1)
+        else {
+            return false;
+        };
+        let cached = windows.remove(idx);
+        self.mapped_bytes.fetch_sub(cached.window.map.len(), Ordering::Relaxed);
+        true
+    }
+}
+
+/// The windows of a single pack data file.
+pub(crate) struct Windows {
+    file: std::fs::File,
+    len: u64,
+    windows: Arc<PackWindows>,
+    cache: Arc<Cache>,
+}
+
+impl Windows {
+    pub(crate) fn new(file: std::fs::File, cache: Arc<Cache>) -> std::io::Result<Self> {
+        let windows = Arc::new(PackWindows::default());
+        {
+            let mut packs = cache.packs.lock();
+            packs.retain(|pack| pack.strong_count() > 0);
+            packs.push(Arc::downgrade(&windows));
+        }
+        Ok(Windows {
+            len: file.metadata()?.len(),
+            file,
+            windows,
+            cache,
+        })
+    }
+
+    pub(crate) fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Return the bytes in `range` as a single slice.
+    pub(crate) fn slice(&self, range: Range<u64>) -> std::io::Result<Slice<'static>> {
+        let window = self.window(range.clone())?;
+        let start = (range.start - window.offset) as usize;
+        let end = (range.end - window.offset) as usize;
+        Ok(Slice(Inner::Window {
+            window,
+            range: start..end,
+        }))
+    }
+
+    /// Return the bytes from `offset` to the end of the window containing it, which may be less than the remaining pack data.
+    pub(crate) fn slice_from(&self, offset: u64) -> std::io::Result<Slice<'static>> {
+        if offset >= self.len {
+            return Err(out_of_bounds(offset..offset + 1, self.len));
+        }
+        let window = self.window(offset..offset + 1)?;
+        let start = (offset - window.offset) as usize;
+        let end = window.map.len();
+        Ok(Slice(Inner::Window {
+            window,
+            range: start..end,
+        }))
+    }
+
+    /// Return a window that contains `range`, reusing a mapped one or mapping a new one.
+    ///
+    /// Fail if `range` isn't within the pack, or if no window can be mapped even after unmapping all unused ones.
+    fn window(&self, range: Range<u64>) -> std::io::Result<Arc<Window>> {
+        if range.start > range.end || range.end > self.len {
+            return Err(out_of_bounds(range, self.len));
+        }
+        let window_size = self.cache.options.window_size as u64;
+        let offset = range.start - range.start % window_size;
+        if let Some(window) = self.find(&mut self.windows.0.lock(), offset, &range) {
+            return Ok(window);
+        }
+
+        let end = (offset + window_size).max(range.end).min(self.len);
+        let len: usize = (end - offset).try_into().map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::OutOfMemory,
+                format!("A window of {} bytes doesn't fit into memory", end - offset),
+            )
+        })?;
+        while self.cache.mapped_bytes() + len > self.cache.options.limit && self.cache.unmap_least_recently_used() {}
+        let map = loop {
+            match self.map(offset, len) {
+                Ok(map) => break map,
+                Err(err) => {
+                    if !self.cache.unmap_least_recently_used() {
+                        return Err(std::io::Error::new(
+                            err.kind(),
+                            format!("Failed to map {len} bytes of pack data at offset {offset}: {err}"),
+                        ));
+                    }
+                }
+            }
+        };
+
+        let mut windows = self.windows.0.lock();
+        // Another thread may have mapped a suitable window in the meantime, which is used instead.
+        if let Some(window) = self.find(&mut windows, offset, &range) {
+            return Ok(window);
+        }
+        let window = Arc::new(Window { offset, map });
+        self.cache.mapped_bytes.fetch_add(len, Ordering::Relaxed);
+        let idx = windows.partition_point(|cached| cached.window.offset < offset);
+        windows.insert(
+            idx,
+            CachedWindow {
+                window: window.clone(),
+                last_used: self.cache.tick(),
+            },
+        );
+        Ok(window)
+    }
+
+    /// Find a window among the sorted `windows` of this pack which starts at `offset` and contains `range`.
+    fn find(&self, windows: &mut [CachedWindow], offset: u64, range: &Range<u64>) -> Option<Arc<Window>> {
+        let first = windows.partition_point(|cached| cached.window.offset < offset);
+        let cached = windows[first..]
+            .iter_mut()
+            .take_while(|cached| cached.window.offset == offset)
+            .find(|cached| cached.window.contains(range))?;
+        cached.last_used = self.cache.tick();
+        Some(cached.window.clone())
+    }
+
+    fn map(&self, offset: u64, len: usize) -> std::io::Result<memmap2::Mmap> {
+        // SAFETY: we have to take the risk of somebody changing the file underneath. Git never writes into the same file.
+        #[allow(unsafe_code)]
+        unsafe {
+            memmap2::MmapOptions::new()
+                .offset(offset)
+                .len(len)
+                .map_copy_read_only(&self.file)
+        }
+    }
+}
+
+/// Return the error for a `range` that isn't within a pack of `len` bytes.
+pub(crate) fn out_of_bounds(range: Range<u64>, len: u64) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::UnexpectedEof,
+        format!("Range {range:?} is out of bounds of pack with {len} bytes"),
+    )
+}
+
+impl Drop for Windows {
+    fn drop(&mut self) {
+        let windows = std::mem::take(&mut *self.windows.0.lock());
+        let unmapped_bytes: usize = windows.iter().map(|cached| cached.window.map.len()).sum();
+        self.cache.mapped_bytes.fetch_sub(unmapped_bytes, Ordering::Relaxed);
+    }
+}
+
+/// A contiguous portion of pack data, which is either borrowed from a pack that is mapped as a whole,
+/// or keeps the window it is contained in mapped for as long as it lives.
+pub struct Slice<'a>(Inner<'a>);
+
+enum Inner<'a> {
+    Borrowed(&'a [u8]),
+    Window { window: Arc<Window>, range: Range<usize> },
+}
+
+impl<'a> From<&'a [u8]> for Slice<'a> {
+    fn from(data: &'a [u8]) -> Self {
+        Slice(Inner::Borrowed(data))
+    }
+}
+
+impl Deref for Slice<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        match &self.0 {
+            Inner::Borrowed(data) => data,
+            Inner::Window { window, range } => &window.map[range.clone()],
+        }
+    }
+}
+
+impl AsRef<[u8]> for Slice<'_> {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl std::fmt::Debug for Slice<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Slice").field(&self.len()).finish()
+    }
+}
@@ -0,0 +1,454 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+};
+
+use gix_features::zlib;
+use gix_hash::ObjectId;
+
+use crate::data::{self, entry::Header, input};
+
+/// An object decoded from a pack entry, as produced by [`EntriesToObjectsIter`] or [`in_parallel()`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DecodedEntry {
+    /// The first byte of the entry in the pack, at which its header can be read.
+    pub pack_offset: data::Offset,
+    /// The id of the decoded object.
+    pub id: ObjectId,
+    /// The kind of the decoded object, which for delta entries is the kind of their base object.
+    pub kind: gix_object::Kind,
+    /// The size of the decoded object in bytes.
+    pub object_size: u64,
+    /// The amount of bytes used to encode the entry header.
+    pub header_size: u16,
+    /// The amount of bytes the compressed portion of the entry takes, i.e. the portion behind the header.
+    pub compressed_size: u64,
+    /// The amount of bytes the entry takes when inflated, which for delta entries is the size of the delta.
+    pub decompressed_size: u64,
+    /// The amount of deltas that had to be applied to obtain the object, with 0 indicating an undeltified object.
+    pub chain_length: u16,
+    /// The CRC32 over the complete entry, if it was computed by the producer of the entries.
+    pub crc32: Option<u32>,
+}
+
+impl DecodedEntry {
+    /// The amount of bytes this entry takes in the pack data file.
+    pub fn bytes_in_pack(&self) -> u64 {
+        self.header_size as u64 + self.compressed_size
+    }
+}
+
+/// Options for use in [`EntriesToObjectsIter::new()`].
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The amount of bytes of decoded objects to keep in memory, to serve as base for delta objects that follow later in the pack.
+    pub base_cache_budget_in_bytes: usize,
+    /// If set, objects evicted from the in-memory cache are written to a temporary file in this directory, so they can still
+    /// serve as base for deltas even after long chains or with bases far away from their deltas.
+    ///
+    /// If `None`, the default, evicted objects are dropped and deltas referring to them cause an error.
+    /// Note that up to the size of all decoded objects in the pack may be written to the spill file.
+    pub spill_directory: Option<PathBuf>,
+    /// The kind of hash to use when computing object ids, and to expect in ref-delta entries.
+    pub object_hash: gix_hash::Kind,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            base_cache_budget_in_bytes: 64 * 1024 * 1024,
+            spill_directory: None,
+            object_hash: gix_hash::Kind::Sha1,
+        }
+    }
+}
+
+/// The error returned as part of the iteration of [`EntriesToObjectsIter`] and by [`in_parallel()`].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Input(#[from] input::Error),
+    #[error("The entry at offset {pack_offset} doesn't have its compressed data, which is needed to decode it")]
+    MissingCompressedData { pack_offset: data::Offset },
+    #[error("Failed to decompress entry at offset {pack_offset}")]
+    ZlibInflate {
+        source: zlib::inflate::Error,
+        pack_offset: data::Offset,
+    },
+    #[error("{distance} is not a valid base distance for the delta at pack offset {pack_offset}")]
+    BaseOffset { pack_offset: data::Offset, distance: u64 },
+    #[error("The delta at offset {pack_offset} refers to {base_id} which wasn't seen before in the pack")]
+    UnresolvedRefDelta {
+        pack_offset: data::Offset,
+        base_id: ObjectId,
+    },
+    #[error("The base at offset {base_pack_offset} of the delta at offset {pack_offset} was evicted from the cache and couldn't be spilled to disk")]
+    EvictedBase {
+        pack_offset: data::Offset,
+        base_pack_offset: data::Offset,
+    },
+    #[error("The delta at offset {pack_offset} expects a base of {expected} bytes, but it has {actual} bytes")]
+    BaseSizeMismatch {
+        pack_offset: data::Offset,
+        expected: u64,
+        actual: u64,
+    },
+    #[error("Failed to apply the delta at offset {pack_offset}")]
+    DeltaApply {
+        source: data::delta::apply::Error,
+        pack_offset: data::Offset,
+    },
+    #[error("Could not write or read back objects evicted from the base cache")]
+    Spill(#[source] std::io::Error),
+    #[error(transparent)]
+    Parallel(#[from] crate::index::write::Error),
+}
+
+/// An iterator that decodes all [entries](input::Entry) of a pack front-to-back, resolving each delta against objects decoded
+/// earlier, to produce a [`DecodedEntry`] for each of them.
+///
+/// This doesn't require a pack index, so it works with packs straight from the wire, as long as entries keep their
+/// [compressed data](input::EntryDataMode::keep()). Ref-deltas are supported if their base is part of the pack.
+///
+/// As there is no way to know which objects will be used as base, all decoded objects are kept in a cache
+/// whose memory is bounded by [`Options::base_cache_budget_in_bytes`], and objects that don't fit anymore are spilled
+/// to disk if [`Options::spill_directory`] is set.
+pub struct EntriesToObjectsIter<I> {
+    /// The iterator providing the pack entries to decode.
+    pub inner: I,
+    cache: BaseCache,
+    object_hash: gix_hash::Kind,
+    /// The kind and chain length of all objects seen so far, by pack offset.
+    kind_and_chain_length_by_offset: HashMap<data::Offset, (gix_object::Kind, u16)>,
+    /// The pack offset of all objects seen so far, for lookup of ref-delta bases.
+    offset_by_id: gix_hashtable::HashMap<ObjectId, data::Offset>,
+    inflate: zlib::Inflate,
+    decompressed: Vec<u8>,
+    base: Vec<u8>,
+    had_error: bool,
+}
+
+impl<I> EntriesToObjectsIter<I>
+where
+    I: Iterator<Item = Result<input::Entry, input::Error>>,
+{
+    /// Create a new instance to decode all entries from `iter` configured with `options`.
+    pub fn new(iter: I, options: Options) -> Self {
+        EntriesToObjectsIter {
+            inner: iter,
+            cache: BaseCache::new(options.base_cache_budget_in_bytes, options.spill_directory),
+            object_hash: options.object_hash,
+            kind_and_chain_length_by_offset: Default::default(),
+            offset_by_id: Default::default(),
+            inflate: Default::default(),
+            decompressed: Vec::new(),
+            base: Vec::new(),
+            had_error: false,
+        }
+    }
+
+    fn next_inner(&mut self, entry: input::Entry) -> Result<DecodedEntry, Error> {
+        let pack_offset = entry.pack_offset;
+        let compressed = entry
+            .compressed
+            .as_deref()
+            .ok_or(Error::MissingCompressedData { pack_offset })?;
+        self.decompressed.resize(entry.decompressed_size as usize, 0);
+        self.inflate.reset();
+        self.inflate
+            .once(compressed, &mut self.decompressed)
+            .map_err(|source| Error::ZlibInflate { source, pack_offset })?;
+
+        let base_pack_offset = match entry.header {
+            Header::OfsDelta { base_distance } => Some(
+                Header::verified_base_pack_offset(pack_offset, base_distance).ok_or(Error::BaseOffset {
+                    pack_offset,
+                    distance: base_distance,
+                })?,
+            ),
+            Header::RefDelta { base_id } => Some(
+                *self
+                    .offset_by_id
+                    .get(&base_id)
+                    .ok_or(Error::UnresolvedRefDelta { pack_offset, base_id })?,
+            ),
+            Header::Tree | Header::Blob | Header::Commit | Header::Tag => None,
+        };
+
+        let (kind, chain_length, object) = match base_pack_offset {
+            None => (
+                entry.header.as_kind().expect("non-delta"),
+                0,
+                std::mem::take(&mut self.decompressed),
+            ),
+            Some(base_pack_offset) => {
+                let (base_kind, base_chain_length) = *self
+                    .kind_and_chain_length_by_offset
+                    .get(&base_pack_offset)
+                    .ok_or(Error::EvictedBase {
+                        pack_offset,
+                        base_pack_offset,
+                    })?;
+                if !self.cache.get(base_pack_offset, &mut self.base)? {
+                    return Err(Error::EvictedBase {
+                        pack_offset,
+                        base_pack_offset,
+                    });
+                }
+                let delta = &self.decompressed;
+                let (base_size, consumed) = data::delta::decode_header_size(delta);
+                let mut header_ofs = consumed;
+                if self.base.len() as u64 != base_size {
+                    return Err(Error::BaseSizeMismatch {
+                        pack_offset,
+                        expected: base_size,
+                        actual: self.base.len() as u64,
+                    });
+                }
+                let (result_size, consumed) = data::delta::decode_header_size(&delta[consumed..]);
+                header_ofs += consumed;
+
+                let mut object = Vec::new();
+                data::delta::apply_to_vec(&self.base, &mut object, &delta[header_ofs..], result_size)
+                    .map_err(|source| Error::DeltaApply { source, pack_offset })?;
+                (base_kind, base_chain_length + 1, object)
+            }
+        };
+
+        let id = gix_object::compute_hash(self.object_hash, kind, &object);
+        let object_size = object.len() as u64;
+        self.kind_and_chain_length_by_offset
+            .insert(pack_offset, (kind, chain_length));
+        self.offset_by_id.insert(id, pack_offset);
+        self.cache.put(pack_offset, object)?;
+
+        Ok(DecodedEntry {
+            pack_offset,
+            id,
+            kind,
+            object_size,
+            header_size: entry.header_size,
+            compressed_size: entry.compressed_size,
+            decompressed_size: entry.decompressed_size,
+            chain_length,
+            crc32: entry.crc32,
+        })
+    }
+}
+
+impl<I> Iterator for EntriesToObjectsIter<I>
+where
+    I: Iterator<Item = Result<input::Entry, input::Error>>,
+{
+    type Item = Result<DecodedEntry, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.had_error {
+            return None;
+        }
+        let res = match self.inner.next()? {
+            Ok(entry) => self.next_inner(entry),
+            Err(err) => Err(err.into()),
+        };
+        self.had_error = res.is_err();
+        Some(res)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// Decode all `entries` of a pack and resolve their deltas using up to `thread_limit` threads, and return a
+/// [`DecodedEntry`] for each of them, sorted by pack offset.
+///
+/// Entries are first collected into a tree of deltas whose roots are distributed across threads, which requires
+/// random access to the pack. Thus `make_resolver()` is called after all entries were seen, and its resolver
+/// has to provide all bytes of a pack entry. It's the same engine that is used to create pack indices, see
+/// [`index::File::write_data_iter_to_stream()`](crate::index::File::write_data_iter_to_stream()) for details on the parameters.
+///
+/// Note that ref-deltas aren't supported here, use the [`LookupRefDeltaObjectsIter`](input::LookupRefDeltaObjectsIter)
+/// to resolve them beforehand.
+pub fn in_parallel<F, F2, R>(
+    entries: &mut dyn Iterator<Item = Result<input::Entry, input::Error>>,
+    make_resolver: F,
+    thread_limit: Option<usize>,
+    progress: &mut dyn gix_features::progress::DynNestedProgress,
+    should_interrupt: &std::sync::atomic::AtomicBool,
+    object_hash: gix_hash::Kind,
+) -> Result<Vec<DecodedEntry>, Error>
+where
+    F: FnOnce() -> std::io::Result<(F2, R)>,
+    R: Send + Sync,
+    F2: for<'r> Fn(data::EntryRange, &'r R) -> Option<&'r [u8]> + Send + Clone,
+{
+    use crate::{cache::delta::traverse, index::write::ProgressId};
+
+    let crate::index::write::EntriesTree {
+        tree, pack_entries_end, ..
+    } = crate::index::write::tree_from_entries(entries, progress, |entry| DecodedEntry {
+        pack_offset: entry.pack_offset,
+        id: object_hash.null(),
+        kind: gix_object::Kind::Blob,
+        object_size: 0,
+        header_size: entry.header_size,
+        compressed_size: entry.compressed_size,
+        decompressed_size: entry.decompressed_size,
+        chain_length: 0,
+        crc32: entry.crc32,
+    })?;
+
+    let (resolver, pack) = make_resolver().map_err(crate::index::write::Error::from)?;
+    let traverse::Outcome { roots, children } = tree
+        .traverse(
+            resolver,
+            &pack,
+            pack_entries_end,
+            |data: &mut DecodedEntry,
+             _progress,
+             traverse::Context {
+                 entry,
+                 decompressed,
+                 level,
+                 ..
+             }| {
+                let kind = entry.header.as_kind().expect("base object as source of iteration");
+                data.id = gix_object::compute_hash(object_hash, kind, decompressed);
+                data.kind = kind;
+                data.object_size = decompressed.len() as u64;
+                data.chain_length = level;
+                Ok::<_, Error>(())
+            },
+            traverse::Options {
+                object_progress: Box::new(
+                    progress.add_child_with_id("Resolving".into(), ProgressId::ResolveObjects.into()),
+                ),
+                size_progress: &mut progress.add_child_with_id("Decoding".into(), ProgressId::DecodedBytes.into()),
+                thread_limit,
+                should_interrupt,
+                object_hash,
+            },
+        )
+        .map_err(crate::index::write::Error::from)?;
+
+    let mut entries: Vec<_> = roots.into_iter().chain(children).map(|item| item.data).collect();
+    entries.sort_by_key(|e| e.pack_offset);
+    Ok(entries)
+}
+
+impl crate::index::traverse::Statistics {
+    /// Compute statistics from all `entries` of a pack with a size of `pack_size` bytes, which are the same as the ones
+    /// produced when [traversing](crate::index::File::traverse()) the pack with its index.
+    pub fn from_decoded_entries<'a>(entries: impl IntoIterator<Item = &'a DecodedEntry>, pack_size: u64) -> Self {
+        let mut stats = Self {
+            pack_size,
+            ..Default::default()
+        };
+        let mut num_objects = 0;
+        for entry in entries {
+            stats.add_object(
+                entry.kind,
+                entry.chain_length,
+                entry.decompressed_size,
+                entry.compressed_size,
+                entry.object_size,
+            );
+            num_objects += 1;
+        }
+        stats.finalize_average(num_objects);
+        stats
+    }
+}
+
+/// A cache for decoded objects which keeps up to a budget of bytes in memory, and spills the oldest objects to disk.
+struct BaseCache {
+    budget: usize,
+    in_memory: HashMap<data::Offset, Vec<u8>>,
+    in_memory_bytes: usize,
+    insertion_order: VecDeque<data::Offset>,
+    spill_directory: Option<PathBuf>,
+    spill: Option<Spill>,
+}
+
+struct Spill {
+    file: gix_tempfile::Handle<gix_tempfile::handle::Writable>,
+    len: u64,
+    range_by_offset: HashMap<data::Offset, (u64, usize)>,
+}
+
+impl BaseCache {
+    fn new(budget: usize, spill_directory: Option<PathBuf>) -> Self {
+        BaseCache {
+            budget,
+            in_memory: Default::default(),
+            in_memory_bytes: 0,
+            insertion_order: Default::default(),
+            spill_directory,
+            spill: None,
+        }
+    }
+
+    fn put(&mut self, offset: data::Offset, object: Vec<u8>) -> Result<(), Error> {
+        self.in_memory_bytes += object.len();
+        self.in_memory.insert(offset, object);
+        self.insertion_order.push_back(offset);
+
+        while self.in_memory_bytes > self.budget {
+            let Some(evicted_offset) = self.insertion_order.pop_front() else {
+                break;
+            };
+            let object = self.in_memory.remove(&evicted_offset).expect("kept in sync");
+            self.in_memory_bytes -= object.len();
+            self.spill(evicted_offset, &object)?;
+        }
+        Ok(())
+    }
+
+    fn spill(&mut self, offset: data::Offset, object: &[u8]) -> Result<(), Error> {
+        let Some(directory) = self.spill_directory.as_ref() else {
+            return Ok(());
+        };
+        let spill = match self.spill.as_mut() {
+            Some(spill) => spill,
+            None => self.spill.insert(Spill {
+                file: gix_tempfile::new(
+                    directory,
+                    gix_tempfile::ContainingDirectory::Exists,
+                    gix_tempfile::AutoRemove::Tempfile,
+                )
+                .map_err(Error::Spill)?,
+                len: 0,
+                range_by_offset: Default::default(),
+            }),
+        };
+        spill.file.seek(SeekFrom::Start(spill.len)).map_err(Error::Spill)?;
+        spill.file.write_all(object).map_err(Error::Spill)?;
+        spill.range_by_offset.insert(offset, (spill.len, object.len()));
+        spill.len += object.len() as u64;
+        Ok(())
+    }
+
+    /// Write the object at `offset` into `out` and return `true`, or return `false` if it isn't available anymore.
+    fn get(&mut self, offset: data::Offset, out: &mut Vec<u8>) -> Result<bool, Error> {
+        if let Some(object) = self.in_memory.get(&offset) {
+            out.clear();
+            out.extend_from_slice(object);
+            return Ok(true);
+        }
+        let Some((start, len)) = self
+            .spill
+            .as_ref()
+            .and_then(|spill| spill.range_by_offset.get(&offset).copied())
+        else {
+            return Ok(false);
+        };
+        let spill = self.spill.as_mut().expect("present if range was found");
+        out.resize(len, 0);
+        spill.file.seek(SeekFrom::Start(start)).map_err(Error::Spill)?;
+        spill.file.read_exact(out).map_err(Error::Spill)?;
+        Ok(true)
+    }
+}
@@ -39,3 +39,8 @@ pub use lookup_ref_delta_objects::LookupRefDeltaObjectsIter;
 
 mod entries_to_bytes;
 pub use entries_to_bytes::EntriesToBytesIter;
+
+///
+#[allow(clippy::empty_docs)]
+pub mod entries_to_objects;
+pub use entries_to_objects::{DecodedEntry, EntriesToObjectsIter};
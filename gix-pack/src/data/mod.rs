@@ -24,7 +24,7 @@ pub struct Entry {
 }
 
 mod file;
-pub use file::{decode, verify, Header};
+pub use file::{decode, verify, window, Header};
 ///
 #[allow(clippy::empty_docs)]
 pub mod header;
@@ -64,7 +64,7 @@ pub enum Version {
 
 /// A pack data file
 pub struct File {
-    data: Mmap,
+    data: Data,
     path: std::path::PathBuf,
     /// A value to represent this pack uniquely when used with cache lookup, or a way to identify this pack by its location on disk.
     /// The same location on disk should yield the same id.
@@ -80,6 +80,56 @@ pub struct File {
     /// based on their configuration.
     hash_len: usize,
     object_hash: gix_hash::Kind,
+    /// The checksum in the trailer of the pack, read once when opening it.
+    checksum: gix_hash::ObjectId,
+}
+
+/// The way the bytes of a pack data file are accessed.
+pub(crate) enum Data {
+    /// The whole file is mapped at once.
+    Mapped(Mmap),
+    /// Portions of the file are mapped on demand.
+    Windowed(window::Windows),
+}
+
+impl Data {
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Data::Mapped(map) => map.len(),
+            Data::Windowed(windows) => windows.len().try_into().expect("pack size fits into usize"),
+        }
+    }
+
+    /// Return the bytes in `range`, or `None` if it's not within the pack or couldn't be mapped.
+    fn get(&self, range: std::ops::Range<usize>) -> Option<window::Slice<'_>> {
+        self.slice(range).ok()
+    }
+
+    /// Return the bytes in `range`, or fail if it's not within the pack or if it couldn't be mapped.
+    pub(crate) fn slice(&self, range: std::ops::Range<usize>) -> std::io::Result<window::Slice<'_>> {
+        match self {
+            Data::Mapped(map) => map
+                .get(range.clone())
+                .map(Into::into)
+                .ok_or_else(|| window::out_of_bounds(range.start as u64..range.end as u64, map.len() as u64)),
+            Data::Windowed(windows) => windows.slice(range.start as u64..range.end as u64),
+        }
+    }
+
+    /// Return the bytes starting at `offset` which are available without mapping more data, which are all remaining ones
+    /// if the pack is mapped as a whole.
+    ///
+    /// Fail if `offset` isn't within the pack, or if the data couldn't be mapped.
+    pub(crate) fn slice_from(&self, offset: usize) -> std::io::Result<window::Slice<'_>> {
+        match self {
+            Data::Mapped(map) => map
+                .get(offset..)
+                .filter(|data| !data.is_empty())
+                .map(Into::into)
+                .ok_or_else(|| window::out_of_bounds(offset as u64..offset as u64 + 1, map.len() as u64)),
+            Data::Windowed(windows) => windows.slice_from(offset as u64),
+        }
+    }
 }
 
 /// Information about the pack data file itself
@@ -110,25 +160,46 @@ impl File {
         &self.path
     }
 
-    /// Returns the pack data at the given slice if its range is contained in the mapped pack data
-    pub fn entry_slice(&self, slice: EntryRange) -> Option<&[u8]> {
+    /// Returns the pack data at the given slice if its range is contained in the pack data.
+    ///
+    /// If the pack is accessed through [windows](window), the returned slice keeps the window it is contained in mapped.
+    pub fn entry_slice(&self, slice: EntryRange) -> Option<window::Slice<'_>> {
         let entry_end: usize = slice.end.try_into().expect("end of pack fits into usize");
         let entry_start = slice.start as usize;
         self.data.get(entry_start..entry_end)
     }
 
+    /// Return all pack data if the pack is mapped as a whole, or `None` if it's accessed through [windows](window).
+    pub(crate) fn mapped_data(&self) -> Option<&[u8]> {
+        match &self.data {
+            Data::Mapped(map) => Some(map),
+            Data::Windowed(_) => None,
+        }
+    }
+
     /// Returns the CRC32 of the pack data indicated by `pack_offset` and the `size` of the mapped data.
     ///
     /// _Note:_ finding the right size is only possible by decompressing
     /// the pack entry beforehand, or by using the (to be sorted) offsets stored in an index file.
     ///
-    /// # Panics
-    ///
-    /// If `pack_offset` or `size` are pointing to a range outside of the mapped pack data.
-    pub fn entry_crc32(&self, pack_offset: Offset, size: usize) -> u32 {
-        let pack_offset: usize = pack_offset.try_into().expect("pack_size fits into usize");
-        gix_features::hash::crc32(&self.data[pack_offset..pack_offset + size])
+    /// Fail if `pack_offset` or `size` are pointing to a range outside of the pack data, or if it couldn't be mapped.
+    pub fn entry_crc32(&self, pack_offset: Offset, size: usize) -> std::io::Result<u32> {
+        let start: usize = pack_offset.try_into().expect("pack_size fits into usize");
+        let end = start + size;
+        if end > self.data.len() {
+            return Err(window::out_of_bounds(pack_offset..end as u64, self.data.len() as u64));
+        }
+        let mut crc32 = 0;
+        let mut offset = start;
+        while offset < end {
+            let data = self.data.slice_from(offset)?;
+            let data = &data[..data.len().min(end - offset)];
+            crc32 = gix_features::hash::crc32_update(crc32, data);
+            offset += data.len();
+        }
+        Ok(crc32)
     }
 }
 
-pub(crate) mod delta;
+/// Decoding and applying the instructions of delta objects.
+pub mod delta;
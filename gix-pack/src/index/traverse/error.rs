@@ -8,6 +8,8 @@ pub enum Error<E: std::error::Error + Send + Sync + 'static> {
     Processor(#[source] E),
     #[error("Index file, pack file or object verification failed")]
     VerifyChecksum(#[from] index::verify::checksum::Error),
+    #[error("The pack data file could not be mapped entirely")]
+    MapPackData(#[source] std::io::Error),
     #[error("The pack delta tree index could not be built")]
     Tree(#[from] crate::cache::delta::from_offsets::Error),
     #[error("The tree traversal failed")]
@@ -46,6 +46,49 @@ impl Default for Statistics {
     }
 }
 
+impl Statistics {
+    /// Account for an object of `kind` at the given delta-chain `level`, whose entry is `compressed_size` bytes in the pack and
+    /// `decompressed_size` bytes when inflated, which decodes into an object of `object_size` bytes.
+    pub(crate) fn add_object(
+        &mut self,
+        kind: gix_object::Kind,
+        level: u16,
+        decompressed_size: u64,
+        compressed_size: u64,
+        object_size: u64,
+    ) {
+        self.total_compressed_entries_size += compressed_size;
+        self.total_decompressed_entries_size += decompressed_size;
+        self.total_object_size += object_size;
+        *self.objects_per_chain_length.entry(level as u32).or_insert(0) += 1;
+
+        let average = &mut self.average;
+        average.decompressed_size += decompressed_size;
+        average.compressed_size += compressed_size as usize;
+        average.object_size += object_size;
+        average.num_deltas += level as u32;
+        use gix_object::Kind::*;
+        match kind {
+            Blob => self.num_blobs += 1,
+            Tree => self.num_trees += 1,
+            Tag => self.num_tags += 1,
+            Commit => self.num_commits += 1,
+        };
+    }
+
+    /// Turn the sums in `average` into averages, after `num_objects` were [added](Self::add_object()).
+    pub(crate) fn finalize_average(&mut self, num_objects: usize) {
+        if num_objects == 0 {
+            return;
+        }
+        let average = &mut self.average;
+        average.decompressed_size /= num_objects as u64;
+        average.compressed_size /= num_objects;
+        average.object_size /= num_objects as u64;
+        average.num_deltas /= num_objects as u32;
+    }
+}
+
 /// The ways to validate decoded objects before passing them to the processor.
 #[derive(Default, Debug, PartialEq, Eq, Hash, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -96,6 +96,15 @@ impl index::File {
                 }
             },
             || -> Result<_, Error<_>> {
+                // Entries are resolved by borrowing from the pack data, so packs accessed through windows are mapped entirely.
+                let entire_pack;
+                let pack_data: &[u8] = match pack.mapped_data() {
+                    Some(data) => data,
+                    None => {
+                        entire_pack = crate::mmap::read_only(pack.path()).map_err(Error::MapPackData)?;
+                        &entire_pack
+                    }
+                };
                 let sorted_entries = index_entries_sorted_by_offset_ascending(
                     self,
                     &mut progress.add_child_with_id(
@@ -113,8 +122,8 @@ impl index::File {
                     self.object_hash,
                 )?;
                 let mut outcome = digest_statistics(tree.traverse(
-                    |slice, pack| pack.entry_slice(slice),
-                    pack,
+                    |slice, data| data.get(slice.start as usize..slice.end as usize),
+                    &pack_data,
                     pack.pack_end() as u64,
                     move |data,
                           progress,
@@ -139,10 +148,11 @@ impl index::File {
                                 // TODO: Fix this - we overwrite the header of 'data' which also changes the computed entry size,
                                 // causing index and pack to seemingly mismatch. This is surprising, and should be done differently.
                                 // debug_assert_eq!(&data.index_entry.pack_offset, &pack_entry.pack_offset());
-                                gix_features::hash::crc32(
-                                    pack.entry_slice(data.index_entry.pack_offset..entry_end)
+                                Ok(gix_features::hash::crc32(
+                                    pack_data
+                                        .get(data.index_entry.pack_offset as usize..entry_end as usize)
                                         .expect("slice pointing into the pack (by now data is verified)"),
-                                )
+                                ))
                             },
                             progress,
                             &mut processor,
@@ -201,31 +211,15 @@ impl From<crate::index::Entry> for Entry {
 
 fn digest_statistics(traverse::Outcome { roots, children }: traverse::Outcome<Entry>) -> index::traverse::Statistics {
     let mut res = index::traverse::Statistics::default();
-    let average = &mut res.average;
     for item in roots.iter().chain(children.iter()) {
-        res.total_compressed_entries_size += item.data.compressed_size;
-        res.total_decompressed_entries_size += item.data.decompressed_size;
-        res.total_object_size += item.data.object_size;
-        *res.objects_per_chain_length.entry(item.data.level as u32).or_insert(0) += 1;
-
-        average.decompressed_size += item.data.decompressed_size;
-        average.compressed_size += item.data.compressed_size as usize;
-        average.object_size += item.data.object_size;
-        average.num_deltas += item.data.level as u32;
-        use gix_object::Kind::*;
-        match item.data.object_kind {
-            Blob => res.num_blobs += 1,
-            Tree => res.num_trees += 1,
-            Tag => res.num_tags += 1,
-            Commit => res.num_commits += 1,
-        };
+        res.add_object(
+            item.data.object_kind,
+            item.data.level,
+            item.data.decompressed_size,
+            item.data.compressed_size,
+            item.data.object_size,
+        );
     }
-
-    let num_nodes = roots.len() + children.len();
-    average.decompressed_size /= num_nodes as u64;
-    average.compressed_size /= num_nodes;
-    average.object_size /= num_nodes as u64;
-    average.num_deltas /= num_nodes as u32;
-
+    res.finalize_average(roots.len() + children.len());
     res
 }
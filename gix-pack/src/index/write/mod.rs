@@ -10,6 +10,7 @@ mod error;
 pub(crate) struct TreeEntry {
     pub id: gix_hash::ObjectId,
     pub crc32: u32,
+    pub compat_id: Option<gix_hash::ObjectId>,
 }
 
 /// Information gathered while executing [`write_data_iter_to_stream()`][crate::index::File::write_data_iter_to_stream]
@@ -25,6 +26,9 @@ pub struct Outcome {
     pub data_hash: gix_hash::ObjectId,
     /// The amount of objects that were verified, always the amount of objects in the pack.
     pub num_objects: u32,
+    /// Pairs of the id of each object and its id in the compatibility hash, sorted by object id, or empty if no
+    /// compatibility hash was given.
+    pub compat_ids: Vec<(gix_hash::ObjectId, gix_hash::ObjectId)>,
 }
 
 /// The progress ids used in [`write_data_iter_from_stream()`][crate::index::File::write_data_iter_to_stream()].
@@ -73,6 +77,8 @@ impl crate::index::File {
     /// * `object_hash` defines what kind of object hash we write into the index file.
     /// * `pack_version` is the version of the underlying pack for which `entries` are read. It's used in case none of these objects are provided
     ///    to compute a pack-hash.
+    /// * `compat_object_hash`, if set, is the hash to additionally compute the id of each object with, to be returned in
+    ///    [`Outcome::compat_ids`].
     ///
     /// # Remarks
     ///
@@ -92,6 +98,7 @@ impl crate::index::File {
         should_interrupt: &AtomicBool,
         object_hash: gix_hash::Kind,
         pack_version: crate::data::Version,
+        compat_object_hash: Option<gix_hash::Kind>,
     ) -> Result<Outcome, Error>
     where
         F: FnOnce() -> io::Result<(F2, R)>,
@@ -101,83 +108,24 @@ impl crate::index::File {
         if version != crate::index::Version::default() {
             return Err(Error::Unsupported(version));
         }
-        let mut num_objects: usize = 0;
-        let mut last_seen_trailer = None;
-        let (anticipated_num_objects, upper_bound) = entries.size_hint();
-        let worst_case_num_objects_after_thin_pack_resolution = upper_bound.unwrap_or(anticipated_num_objects);
-        let mut tree = Tree::with_capacity(worst_case_num_objects_after_thin_pack_resolution)?;
         let indexing_start = std::time::Instant::now();
 
         root_progress.init(Some(4), progress::steps());
-        let mut objects_progress = root_progress.add_child_with_id("indexing".into(), ProgressId::IndexObjects.into());
-        objects_progress.init(Some(anticipated_num_objects), progress::count("objects"));
-        let mut decompressed_progress =
-            root_progress.add_child_with_id("decompressing".into(), ProgressId::DecompressedBytes.into());
-        decompressed_progress.init(None, progress::bytes());
-        let mut pack_entries_end: u64 = 0;
-
-        for entry in entries {
-            let crate::data::input::Entry {
-                header,
-                pack_offset,
+        let EntriesTree {
+            tree,
+            num_objects,
+            pack_entries_end,
+            last_seen_trailer,
+        } = tree_from_entries(entries, root_progress, |entry| {
+            let crc32 = entry
+                .crc32
+                .expect("crc32 to be computed by the iterator. Caller assures correct configuration.");
+            TreeEntry {
+                id: object_hash.null(),
                 crc32,
-                header_size,
-                compressed: _,
-                compressed_size,
-                decompressed_size,
-                trailer,
-            } = entry?;
-
-            decompressed_progress.inc_by(decompressed_size as usize);
-
-            let entry_len = header_size as u64 + compressed_size;
-            pack_entries_end = pack_offset + entry_len;
-
-            let crc32 = crc32.expect("crc32 to be computed by the iterator. Caller assures correct configuration.");
-
-            use crate::data::entry::Header::*;
-            match header {
-                Tree | Blob | Commit | Tag => {
-                    tree.add_root(
-                        pack_offset,
-                        TreeEntry {
-                            id: object_hash.null(),
-                            crc32,
-                        },
-                    )?;
-                }
-                RefDelta { .. } => return Err(Error::IteratorInvariantNoRefDelta),
-                OfsDelta { base_distance } => {
-                    let base_pack_offset =
-                        crate::data::entry::Header::verified_base_pack_offset(pack_offset, base_distance).ok_or(
-                            Error::IteratorInvariantBaseOffset {
-                                pack_offset,
-                                distance: base_distance,
-                            },
-                        )?;
-                    tree.add_child(
-                        base_pack_offset,
-                        pack_offset,
-                        TreeEntry {
-                            id: object_hash.null(),
-                            crc32,
-                        },
-                    )?;
-                }
-            };
-            last_seen_trailer = trailer;
-            num_objects += 1;
-            objects_progress.inc();
-        }
-        let num_objects: u32 = num_objects
-            .try_into()
-            .map_err(|_| Error::IteratorInvariantTooManyObjects(num_objects))?;
-
-        objects_progress.show_throughput(indexing_start);
-        decompressed_progress.show_throughput(indexing_start);
-        drop(objects_progress);
-        drop(decompressed_progress);
-
+                compat_id: None,
+            }
+        })?;
         root_progress.inc();
 
         let (resolver, pack) = make_resolver()?;
@@ -193,7 +141,7 @@ impl crate::index::File {
                      decompressed: bytes,
                      ..
                  }| {
-                    modify_base(data, entry, bytes, version.hash());
+                    modify_base(data, entry, bytes, version.hash(), compat_object_hash);
                     Ok::<_, Error>(())
                 },
                 traverse::Options {
@@ -227,10 +175,14 @@ impl crate::index::File {
                 let header = crate::data::header::encode(pack_version, 0);
                 let mut hasher = gix_features::hash::hasher(object_hash);
                 hasher.update(&header);
-                gix_hash::ObjectId::from(hasher.digest())
+                hasher.digest()
             }
             None => return Err(Error::IteratorInvariantTrailer),
         };
+        let compat_ids = sorted_pack_offsets_by_oid
+            .iter()
+            .filter_map(|item| item.data.compat_id.map(|compat_id| (item.data.id, compat_id)))
+            .collect();
         let index_hash = crate::index::encode::write_to(
             out,
             sorted_pack_offsets_by_oid,
@@ -249,12 +201,95 @@ impl crate::index::File {
             index_hash,
             data_hash: pack_hash,
             num_objects,
+            compat_ids,
         })
     }
 }
 
-fn modify_base(entry: &mut TreeEntry, pack_entry: &crate::data::Entry, decompressed: &[u8], hash: gix_hash::Kind) {
+/// A delta-tree built from all entries of a pack, along with information about the pack itself.
+pub(crate) struct EntriesTree<T> {
+    pub tree: Tree<T>,
+    pub num_objects: u32,
+    /// One past the last byte of the last entry in the pack.
+    pub pack_entries_end: u64,
+    /// The trailer of the last entry, which is the hash over the whole pack.
+    pub last_seen_trailer: Option<gix_hash::ObjectId>,
+}
+
+/// Consume all `entries` into a tree to prepare for resolving all deltas in parallel, producing the data to associate with
+/// each entry by calling `make_data(entry)`.
+///
+/// Ref-deltas aren't supported and must have been resolved beforehand.
+pub(crate) fn tree_from_entries<T>(
+    entries: &mut dyn Iterator<Item = Result<crate::data::input::Entry, crate::data::input::Error>>,
+    root_progress: &mut dyn DynNestedProgress,
+    mut make_data: impl FnMut(&crate::data::input::Entry) -> T,
+) -> Result<EntriesTree<T>, Error> {
+    let mut num_objects: usize = 0;
+    let mut last_seen_trailer = None;
+    let (anticipated_num_objects, upper_bound) = entries.size_hint();
+    let worst_case_num_objects_after_thin_pack_resolution = upper_bound.unwrap_or(anticipated_num_objects);
+    let mut tree = Tree::with_capacity(worst_case_num_objects_after_thin_pack_resolution)?;
+    let indexing_start = std::time::Instant::now();
+
+    let mut objects_progress = root_progress.add_child_with_id("indexing".into(), ProgressId::IndexObjects.into());
+    objects_progress.init(Some(anticipated_num_objects), progress::count("objects"));
+    let mut decompressed_progress =
+        root_progress.add_child_with_id("decompressing".into(), ProgressId::DecompressedBytes.into());
+    decompressed_progress.init(None, progress::bytes());
+    let mut pack_entries_end: u64 = 0;
+
+    for entry in entries {
+        let entry = entry?;
+        decompressed_progress.inc_by(entry.decompressed_size as usize);
+
+        let pack_offset = entry.pack_offset;
+        pack_entries_end = pack_offset + entry.bytes_in_pack();
+
+        use crate::data::entry::Header::*;
+        match entry.header {
+            Tree | Blob | Commit | Tag => {
+                tree.add_root(pack_offset, make_data(&entry))?;
+            }
+            RefDelta { .. } => return Err(Error::IteratorInvariantNoRefDelta),
+            OfsDelta { base_distance } => {
+                let base_pack_offset =
+                    crate::data::entry::Header::verified_base_pack_offset(pack_offset, base_distance).ok_or(
+                        Error::IteratorInvariantBaseOffset {
+                            pack_offset,
+                            distance: base_distance,
+                        },
+                    )?;
+                tree.add_child(base_pack_offset, pack_offset, make_data(&entry))?;
+            }
+        };
+        last_seen_trailer = entry.trailer;
+        num_objects += 1;
+        objects_progress.inc();
+    }
+    let num_objects: u32 = num_objects
+        .try_into()
+        .map_err(|_| Error::IteratorInvariantTooManyObjects(num_objects))?;
+
+    objects_progress.show_throughput(indexing_start);
+    decompressed_progress.show_throughput(indexing_start);
+    Ok(EntriesTree {
+        tree,
+        num_objects,
+        pack_entries_end,
+        last_seen_trailer,
+    })
+}
+
+fn modify_base(
+    entry: &mut TreeEntry,
+    pack_entry: &crate::data::Entry,
+    decompressed: &[u8],
+    hash: gix_hash::Kind,
+    compat_hash: Option<gix_hash::Kind>,
+) {
     let object_kind = pack_entry.header.as_kind().expect("base object as source of iteration");
     let id = gix_object::compute_hash(hash, object_kind, decompressed);
     entry.id = id;
+    entry.compat_id = compat_hash.map(|compat_hash| gix_object::compute_hash(compat_hash, object_kind, decompressed));
 }
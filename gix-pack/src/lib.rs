@@ -18,6 +18,10 @@
 #![cfg_attr(all(doc, feature = "document-features"), feature(doc_cfg, doc_auto_cfg))]
 #![deny(missing_docs, rust_2018_idioms, unsafe_code)]
 
+///
+#[allow(clippy::empty_docs)]
+pub mod bitmap;
+
 ///
 #[allow(clippy::empty_docs)]
 pub mod bundle;
@@ -282,6 +282,7 @@ impl File {
                         match err {
                             Processor(err) => Processor(integrity::Error::IndexIntegrity(err)),
                             VerifyChecksum(err) => VerifyChecksum(err),
+                            MapPackData(err) => MapPackData(err),
                             Tree(err) => Tree(err),
                             TreeTraversal(err) => TreeTraversal(err),
                             PackDecode { id, offset, source } => PackDecode { id, offset, source },
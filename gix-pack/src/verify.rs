@@ -11,6 +11,8 @@ pub mod checksum {
     pub enum Error {
         #[error("Interrupted by user")]
         Interrupted,
+        #[error("Could not read the data to checksum")]
+        Io(#[from] std::io::Error),
         #[error("index checksum mismatch: expected {expected}, got {actual}")]
         Mismatch {
             expected: gix_hash::ObjectId,
@@ -53,7 +55,7 @@ pub fn checksum_on_disk_or_mmap(
             hasher.update(&data[..data_len_without_trailer]);
             progress.inc_by(data_len_without_trailer);
             progress.show_throughput(start);
-            gix_hash::ObjectId::from(hasher.digest())
+            hasher.digest()
         }
     };
 
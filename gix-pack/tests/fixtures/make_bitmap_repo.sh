@@ -0,0 +1,56 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config pack.writeBitmapLookupTable true
+
+git checkout -q -b main
+mkdir -p dir/sub
+# Enough commits for bitmaps to be stored as XOR of others.
+for round in $(seq 120); do
+  echo "$round" > "file-$((round % 5))"
+  echo "$round" > "dir/$((round % 3))"
+  seq "$round" > dir/sub/numbers
+  git add .
+  git commit -qm "$round"
+  if [ "$round" = 60 ]; then
+    git tag -m "the middle" middle
+  fi
+done
+
+git checkout -q -b side HEAD~5
+for round in $(seq 3); do
+  echo "side $round" > side-file
+  git add side-file
+  git commit -qm "side $round"
+done
+git checkout -q main
+git merge -q --no-edit side
+
+git repack -adbq
+
+# These commits and their objects are loose, so there are no bitmaps for them.
+git checkout -q -b after
+for round in $(seq 3); do
+  echo "after $round" > "dir/sub/after"
+  git add dir
+  git commit -qm "after $round"
+done
+git tag -m "after everything" latest
+
+function baseline() {
+  local name=${1:?the name of the baseline}
+  shift
+  git rev-list --objects --no-object-names "$@" -- > "$name.baseline"
+}
+
+baseline main main
+baseline main-not-middle main --not middle
+baseline after-not-side after --not side
+baseline latest-not-main latest --not main
+baseline side-not-after side --not after
+git rev-list --objects main -- > main-with-names.baseline
+
+for name in main middle side after latest; do
+  echo "$name $(git rev-parse "$name")"
+done > refs.baseline
@@ -0,0 +1,291 @@
+use std::{collections::BTreeSet, path::Path};
+
+use gix_hash::ObjectId;
+use gix_object::FindExt;
+use gix_pack::bitmap;
+
+struct Fixture {
+    dir: std::path::PathBuf,
+    index: gix_pack::index::File,
+    bitmap: bitmap::File,
+    odb: gix_odb::Handle,
+}
+
+fn fixture() -> crate::Result<Fixture> {
+    let dir = crate::scripted_fixture_read_only("make_bitmap_repo.sh")?;
+    let pack_dir = dir.join(".git/objects/pack");
+    let bitmap_path = std::fs::read_dir(&pack_dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .find(|path| path.extension() == Some("bitmap".as_ref()))
+        .expect("the fixture writes a bitmap");
+    let index = gix_pack::index::File::at(bitmap_path.with_extension("idx"), gix_hash::Kind::Sha1)?;
+    let bitmap = bitmap::File::at(&bitmap_path, &index)?;
+    let odb = gix_odb::at(dir.join(".git/objects"))?;
+    Ok(Fixture {
+        dir,
+        index,
+        bitmap,
+        odb,
+    })
+}
+
+impl Fixture {
+    fn baseline(&self, name: &str) -> crate::Result<BTreeSet<ObjectId>> {
+        let data = std::fs::read_to_string(self.dir.join(format!("{name}.baseline")))?;
+        Ok(data.lines().map(crate::hex_to_id).collect())
+    }
+
+    fn rev(&self, name: &str) -> crate::Result<ObjectId> {
+        let data = std::fs::read_to_string(self.dir.join("refs.baseline"))?;
+        Ok(data
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(' '))
+            .map(crate::hex_to_id)
+            .expect("ref is listed"))
+    }
+
+    fn objects_to_send(&self, wants: &str, haves: &str) -> crate::Result<BTreeSet<ObjectId>> {
+        let mut objects = self.bitmap.reachable(&self.index, Some(self.rev(wants)?), &self.odb)?;
+        objects.subtract(&self.bitmap.reachable(&self.index, Some(self.rev(haves)?), &self.odb)?);
+        let ids: BTreeSet<_> = objects.ids(&self.bitmap, &self.index).collect();
+        assert_eq!(ids.len(), objects.len(), "each object is only listed once");
+        Ok(ids)
+    }
+
+    /// Find all objects reachable from `tips` by walking the object graph, without using bitmaps.
+    fn walk(&self, tips: impl IntoIterator<Item = ObjectId>) -> crate::Result<BTreeSet<ObjectId>> {
+        let mut out = BTreeSet::new();
+        let mut stack: Vec<_> = tips.into_iter().collect();
+        let mut buf = Vec::new();
+        while let Some(id) = stack.pop() {
+            if !out.insert(id) {
+                continue;
+            }
+            let object = self.odb.find(&id, &mut buf)?;
+            match object.kind {
+                gix_object::Kind::Commit => {
+                    let mut commit = gix_object::CommitRefIter::from_bytes(object.data);
+                    stack.push(commit.tree_id()?);
+                    stack.extend(commit.parent_ids());
+                }
+                gix_object::Kind::Tree => {
+                    for entry in gix_object::TreeRefIter::from_bytes(object.data) {
+                        stack.push(entry?.oid.to_owned());
+                    }
+                }
+                gix_object::Kind::Tag => stack.push(gix_object::TagRefIter::from_bytes(object.data).target_id()?),
+                gix_object::Kind::Blob => {}
+            }
+        }
+        Ok(out)
+    }
+}
+
+fn kind_of(odb: &gix_odb::Handle, id: &ObjectId) -> gix_object::Kind {
+    odb.find(id, &mut Vec::new()).expect("object exists").kind
+}
+
+#[test]
+fn type_bitmaps_partition_all_objects_in_the_pack() -> crate::Result {
+    let fix = fixture()?;
+    assert!(fix.bitmap.num_commits() > 1, "there are some commits with bitmap");
+    assert_eq!(fix.bitmap.pack_checksum(), fix.index.pack_checksum());
+
+    let mut all = bitmap::Bitmap::default();
+    let mut total = 0;
+    for kind in [
+        gix_object::Kind::Commit,
+        gix_object::Kind::Tree,
+        gix_object::Kind::Blob,
+        gix_object::Kind::Tag,
+    ] {
+        let objects = fix.bitmap.objects_of_kind(kind);
+        assert!(!objects.is_empty(), "there are objects of each kind");
+        for id in fix.bitmap.ids(&fix.index, objects) {
+            assert_eq!(kind_of(&fix.odb, &id), kind);
+        }
+        total += objects.len();
+        all.union_with(objects);
+    }
+    assert_eq!(
+        total,
+        fix.index.num_objects() as usize,
+        "each object has exactly one kind…"
+    );
+    assert_eq!(all.len(), total, "…and none has more than one");
+
+    for bit in [0, total / 2, total - 1] {
+        let id = fix.bitmap.id_at_bit(&fix.index, bit).to_owned();
+        assert_eq!(fix.bitmap.bit_of(&fix.index, &id), Some(bit));
+        assert_eq!(fix.bitmap.kind_at_bit(bit), Some(kind_of(&fix.odb, &id)));
+    }
+    assert_eq!(fix.bitmap.kind_at_bit(total), None);
+    Ok(())
+}
+
+#[test]
+fn commit_bitmaps_contain_all_reachable_objects() -> crate::Result {
+    let fix = fixture()?;
+    let mut seen = 0;
+    for id in fix.baseline("main")? {
+        let Some(bitmap) = fix.bitmap.commit_bitmap(&id) else {
+            continue;
+        };
+        seen += 1;
+        assert_eq!(
+            fix.bitmap.ids(&fix.index, &bitmap).collect::<BTreeSet<_>>(),
+            fix.walk(Some(id))?,
+            "{id}: bitmaps that are XORed with others are resolved as well"
+        );
+    }
+    assert_eq!(seen, fix.bitmap.num_commits(), "all commits with bitmaps are on main");
+    Ok(())
+}
+
+#[test]
+fn reachable_objects_match_the_ones_found_by_walking_the_graph_and_git() -> crate::Result {
+    let fix = fixture()?;
+    let main = fix.bitmap.reachable(&fix.index, Some(fix.rev("main")?), &fix.odb)?;
+    assert!(
+        main.extended.is_empty(),
+        "all objects reachable from main are in the pack"
+    );
+    assert_eq!(
+        main.ids(&fix.bitmap, &fix.index).collect::<BTreeSet<_>>(),
+        fix.baseline("main")?
+    );
+
+    let latest = fix.bitmap.reachable(&fix.index, Some(fix.rev("latest")?), &fix.odb)?;
+    assert_eq!(
+        latest.extended.len(),
+        3 * 5 + 1,
+        "the loose commits with their three trees and blob each, and the annotated tag are found by walking"
+    );
+    assert_eq!(
+        latest.ids(&fix.bitmap, &fix.index).collect::<BTreeSet<_>>(),
+        fix.walk(Some(fix.rev("latest")?))?
+    );
+
+    for (wants, haves, baseline) in [
+        ("main", "middle", "main-not-middle"),
+        ("after", "side", "after-not-side"),
+        ("latest", "main", "latest-not-main"),
+        ("side", "after", "side-not-after"),
+    ] {
+        let expected = fix.baseline(baseline)?;
+        let walked: BTreeSet<_> = fix
+            .walk(Some(fix.rev(wants)?))?
+            .difference(&fix.walk(Some(fix.rev(haves)?))?)
+            .copied()
+            .collect();
+        assert_eq!(walked, expected, "{baseline}: our walk matches the one of git");
+        assert_eq!(
+            fix.objects_to_send(wants, haves)?,
+            expected,
+            "{baseline}: bitmaps yield the same result"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn objects_of_a_kind_can_be_removed() -> crate::Result {
+    let fix = fixture()?;
+    let mut objects = fix.bitmap.reachable(&fix.index, Some(fix.rev("latest")?), &fix.odb)?;
+    let expected: BTreeSet<_> = objects
+        .ids(&fix.bitmap, &fix.index)
+        .filter(|id| kind_of(&fix.odb, id) != gix_object::Kind::Blob)
+        .collect();
+    objects.remove_kind(&fix.bitmap, gix_object::Kind::Blob);
+    assert_eq!(objects.ids(&fix.bitmap, &fix.index).collect::<BTreeSet<_>>(), expected);
+    Ok(())
+}
+
+#[test]
+fn name_hashes_are_the_ones_of_the_paths_objects_were_found_at() -> crate::Result {
+    let fix = fixture()?;
+    let baseline = std::fs::read_to_string(fix.dir.join("main-with-names.baseline"))?;
+    let mut checked = 0;
+    for line in baseline.lines() {
+        let (id, path) = line.split_once(' ').unwrap_or((line, ""));
+        if !matches!(path, "" | "dir" | "dir/sub" | "dir/sub/numbers") {
+            // Other blobs exist at multiple paths, and the one that was used for the hash isn't known.
+            continue;
+        }
+        checked += 1;
+        let bit = fix
+            .bitmap
+            .bit_of(&fix.index, &crate::hex_to_id(id))
+            .expect("all objects are in the pack");
+        assert_eq!(
+            fix.bitmap.name_hash(bit),
+            Some(bitmap::name_hash(path.as_bytes())),
+            "{line}"
+        );
+    }
+    assert!(checked > 20, "all commits and trees, and some blobs");
+    assert_ne!(bitmap::name_hash(b"dir/sub/numbers"), 0);
+    assert_eq!(
+        bitmap::name_hash(b"a b\n"),
+        bitmap::name_hash(b"ab"),
+        "whitespace is ignored"
+    );
+    Ok(())
+}
+
+#[test]
+fn bitmaps_of_other_packs_are_rejected() -> crate::Result {
+    let fix = fixture()?;
+    let other_index =
+        gix_pack::index::File::at(crate::fixture_path(crate::pack::SMALL_PACK_INDEX), gix_hash::Kind::Sha1)?;
+    assert!(matches!(
+        bitmap::File::at(fix.bitmap.path(), &other_index),
+        Err(bitmap::init::Error::PackMismatch { .. })
+    ));
+    assert!(matches!(
+        bitmap::File::at(Path::new("does-not-exist.bitmap"), &fix.index),
+        Err(bitmap::init::Error::Io { .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn bitmaps_with_bits_beyond_the_last_object_are_rejected() -> crate::Result {
+    let fix = fixture()?;
+    let num_objects = fix.index.num_objects();
+    let ewah_with_bit = |bit: u32| {
+        let mut out = Vec::new();
+        out.extend_from_slice(&(bit + 1).to_be_bytes());
+        out.extend_from_slice(&2u32.to_be_bytes());
+        let marker = (u64::from(bit / 64) << 1) | (1 << 33);
+        out.extend_from_slice(&marker.to_be_bytes());
+        out.extend_from_slice(&(1u64 << (bit % 64)).to_be_bytes());
+        out.extend_from_slice(&0u32.to_be_bytes());
+        out
+    };
+
+    let mut data = b"BITM".to_vec();
+    data.extend_from_slice(&1u16.to_be_bytes());
+    data.extend_from_slice(&1u16.to_be_bytes());
+    data.extend_from_slice(&0u32.to_be_bytes());
+    data.extend_from_slice(fix.index.pack_checksum().as_slice());
+    data.extend(ewah_with_bit(num_objects));
+    for _ in 0..3 {
+        data.extend(ewah_with_bit(0));
+    }
+    data.extend_from_slice(gix_hash::Kind::Sha1.null().as_slice());
+
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let path = tmp.path().join("corrupt.bitmap");
+    std::fs::write(&path, &data)?;
+    let err = bitmap::File::at(&path, &fix.index)
+        .err()
+        .expect("bits must refer to objects");
+    assert_eq!(
+        err.to_string(),
+        format!("The commit bitmap has bits set beyond the {num_objects} objects in the pack")
+    );
+    Ok(())
+}
@@ -90,7 +90,7 @@ mod locate {
 mod write_to_directory {
     use std::{fs, path::Path, sync::atomic::AtomicBool};
 
-    use gix_features::progress;
+    use gix_features::{progress, zlib};
     use gix_odb::pack;
     use gix_testtools::tempfile::TempDir;
 
@@ -106,6 +106,7 @@ mod write_to_directory {
                 index_hash: gix_hash::ObjectId::from_hex(b"544a7204a55f6e9cacccf8f6e191ea8f83575de3")?,
                 data_hash: gix_hash::ObjectId::from_hex(b"0f3ea84cd1bba10c2a03d736a460635082833e59")?,
                 num_objects: 42,
+                compat_ids: Vec::new(),
             },
             pack_version: pack::data::Version::V2,
             index_path: None,
@@ -117,7 +118,7 @@ mod write_to_directory {
 
     #[test]
     fn without_providing_one() -> Result<(), Box<dyn std::error::Error>> {
-        let res = write_pack(None::<&Path>, SMALL_PACK)?;
+        let res = write_pack(None::<&Path>, SMALL_PACK, None)?;
         assert_eq!(res, expected_outcome()?);
         assert_eq!(
             res.index.index_hash,
@@ -130,7 +131,7 @@ mod write_to_directory {
     #[test]
     fn given_a_directory() -> Result<(), Box<dyn std::error::Error>> {
         let dir = TempDir::new()?;
-        let mut res = write_pack(Some(&dir), SMALL_PACK)?;
+        let mut res = write_pack(Some(&dir), SMALL_PACK, None)?;
         let (index_path, data_path, keep_path) = (res.index_path.take(), res.data_path.take(), res.keep_path.take());
         assert_eq!(res, expected_outcome()?);
         let mut sorted_entries = fs::read_dir(&dir)?.filter_map(Result::ok).collect::<Vec<_>>();
@@ -154,6 +155,30 @@ mod write_to_directory {
         Ok(())
     }
 
+    #[test]
+    fn with_compat_object_hash() -> Result<(), Box<dyn std::error::Error>> {
+        let res = write_pack(None::<&Path>, SMALL_PACK, Some(gix_hash::Kind::Sha256))?;
+        assert_eq!(res.index.compat_ids.len(), 42, "every object has a compatibility id");
+        assert!(
+            res.index.compat_ids.windows(2).all(|w| w[0].0 < w[1].0),
+            "sorted by object id"
+        );
+
+        let bundle = pack::Bundle::at(fixture_path(SMALL_PACK_INDEX), gix_hash::Kind::Sha1)?;
+        let mut buf = Vec::new();
+        for (id, compat_id) in res.index.compat_ids {
+            let (object, _location) = bundle
+                .find(&id, &mut buf, &mut zlib::Inflate::default(), &mut pack::cache::Never)?
+                .expect("object present");
+            assert_eq!(
+                compat_id,
+                gix_object::compute_hash(gix_hash::Kind::Sha256, object.kind, object.data),
+                "the compatibility id is the hash of the same object with the compatibility hash"
+            );
+        }
+        Ok(())
+    }
+
     fn file_name(entry: &fs::DirEntry) -> String {
         entry.path().file_name().unwrap().to_str().unwrap().to_owned()
     }
@@ -161,6 +186,7 @@ mod write_to_directory {
     fn write_pack(
         directory: Option<impl AsRef<Path>>,
         pack_file: &str,
+        compat_object_hash: Option<gix_hash::Kind>,
     ) -> Result<pack::bundle::write::Outcome, Box<dyn std::error::Error>> {
         let pack_file = fs::File::open(fixture_path(pack_file))?;
         static SHOULD_INTERRUPT: AtomicBool = AtomicBool::new(false);
@@ -176,6 +202,7 @@ mod write_to_directory {
                 iteration_mode: pack::data::input::Mode::Verify,
                 index_version: pack::index::Version::V2,
                 object_hash: gix_hash::Kind::Sha1,
+                compat_object_hash,
             },
         )
         .map_err(Into::into)
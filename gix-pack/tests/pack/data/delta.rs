@@ -0,0 +1,51 @@
+use gix_pack::data::delta;
+
+const BASE: &[u8] = b"hello world";
+
+#[test]
+fn copy_and_insert_instructions_produce_the_result() -> crate::Result {
+    // copy 5 bytes from offset 6, then insert "!".
+    let instructions = b"\x91\x06\x05\x01!";
+    let mut target = [0; 6];
+    delta::apply(BASE, &mut target, instructions)?;
+    assert_eq!(&target, b"world!");
+
+    let mut out = Vec::new();
+    delta::apply_to_vec(BASE, &mut out, instructions, 6)?;
+    assert_eq!(out, b"world!");
+    Ok(())
+}
+
+#[test]
+fn corrupt_deltas_cause_an_error_instead_of_a_panic() {
+    for (instructions, result_size) in [
+        (&b"\x00"[..], 1),
+        (b"\x05abc", 5),
+        (b"\x91\x06", 5),
+        (b"\x91\x08\x05", 5),
+        (b"\x90", 0x10000),
+        (b"\x91\x06\x05\x01!", 5),
+        (b"\x91\x06\x05\x01!", 7),
+    ] {
+        let mut target = vec![0; result_size];
+        assert!(
+            delta::apply(BASE, &mut target, instructions).is_err(),
+            "{instructions:?} with {result_size} bytes"
+        );
+        assert!(
+            delta::apply_to_vec(BASE, &mut Vec::new(), instructions, result_size as u64).is_err(),
+            "{instructions:?} with {result_size} bytes"
+        );
+    }
+}
+
+#[test]
+fn bogus_result_sizes_do_not_cause_huge_allocations() {
+    let mut out = Vec::new();
+    let err = delta::apply_to_vec(BASE, &mut out, b"\x01!", u64::MAX).unwrap_err();
+    assert!(
+        matches!(err, delta::apply::Error::ResultSizeMismatch { actual: 1, .. }),
+        "{err:?}"
+    );
+    assert!(out.capacity() < 1024, "only the produced data is allocated");
+}
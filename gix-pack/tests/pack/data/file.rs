@@ -41,6 +41,139 @@ mod method {
     }
 }
 
+mod windows {
+    use std::sync::{atomic::AtomicBool, Arc};
+
+    use gix_features::progress;
+    use gix_pack::{
+        cache,
+        data::{decode::entry::ResolvedBase, window},
+    };
+
+    use crate::pack::{fixture_path, INDEX_V1, PACK_FOR_INDEX_V1, V2_PACKS_AND_INDICES};
+
+    #[test]
+    fn tiny_windows_decode_all_objects_like_an_entirely_mapped_pack() -> crate::Result {
+        for (index_path, pack_path) in V2_PACKS_AND_INDICES.iter().chain(Some(&(INDEX_V1, PACK_FOR_INDEX_V1))) {
+            let index = gix_pack::index::File::at(fixture_path(index_path), gix_hash::Kind::Sha1)?;
+            let entire_pack = gix_pack::data::File::at(fixture_path(pack_path), gix_hash::Kind::Sha1)?;
+            for window_size in [1, 7, 64, 1000, 4096] {
+                // Windows grow beyond their size to contain entry headers, which are read with up to 64 bytes.
+                let limit = 3 * (window_size + 64);
+                let cache = Arc::new(window::Cache::new(window::Options { window_size, limit }));
+                let pack = gix_pack::data::File::at_with_windows(
+                    fixture_path(pack_path),
+                    gix_hash::Kind::Sha1,
+                    cache.clone(),
+                )?;
+                assert_eq!(pack.data_len(), entire_pack.data_len());
+                assert_eq!(pack.checksum(), entire_pack.checksum());
+                assert_eq!(
+                    pack.verify_checksum(&mut progress::Discard, &AtomicBool::default())?,
+                    entire_pack.checksum()
+                );
+
+                let mut offsets = index.sorted_offsets();
+                offsets.push(pack.pack_end() as u64);
+                for entry in index.iter() {
+                    let (mut expected, mut actual) = (Vec::new(), Vec::new());
+                    let expected_outcome = decode(&entire_pack, &index, entry.pack_offset, &mut expected)?;
+                    let actual_outcome = decode(&pack, &index, entry.pack_offset, &mut actual)?;
+                    assert_eq!(actual_outcome, expected_outcome);
+                    assert_eq!(actual, expected, "{pack_path}: {window_size}: {}", entry.oid);
+                    assert!(
+                        cache.mapped_bytes() <= limit,
+                        "unused windows are unmapped to stay within the limit"
+                    );
+
+                    let entry_end = offsets[offsets.binary_search(&entry.pack_offset).expect("present") + 1];
+                    let range = entry.pack_offset..entry_end;
+                    assert_eq!(
+                        pack.entry_slice(range.clone()).as_deref(),
+                        entire_pack.entry_slice(range.clone()).as_deref(),
+                        "entries spanning multiple windows are mapped into one"
+                    );
+                    let crc32 = pack.entry_crc32(entry.pack_offset, (entry_end - entry.pack_offset) as usize)?;
+                    assert_eq!(
+                        crc32,
+                        entire_pack.entry_crc32(entry.pack_offset, (entry_end - entry.pack_offset) as usize)?
+                    );
+                    if let Some(expected) = entry.crc32 {
+                        assert_eq!(crc32, expected);
+                    }
+                }
+                drop(pack);
+                assert_eq!(cache.num_windows(), 0, "all windows of a pack are unmapped with it");
+                assert_eq!(cache.mapped_bytes(), 0);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn windows_in_use_are_not_unmapped() -> crate::Result {
+        let (_, pack_path) = V2_PACKS_AND_INDICES[0];
+        let cache = Arc::new(window::Cache::new(window::Options {
+            window_size: 64,
+            limit: 64,
+        }));
+        let pack = gix_pack::data::File::at_with_windows(fixture_path(pack_path), gix_hash::Kind::Sha1, cache.clone())?;
+        let first = pack.entry_slice(0..64).expect("in bounds");
+        let expected_first = first.to_vec();
+        let second = pack.entry_slice(64..128).expect("in bounds");
+        assert_eq!(
+            cache.num_windows(),
+            2,
+            "the limit is exceeded as the first window is still in use"
+        );
+        assert_eq!(&*first, expected_first.as_slice());
+        drop((first, second));
+
+        let _third = pack.entry_slice(128..192).expect("in bounds");
+        assert_eq!(cache.num_windows(), 1, "unused windows are unmapped to make room");
+        assert_eq!(cache.mapped_bytes(), 64);
+        Ok(())
+    }
+
+    #[test]
+    fn out_of_bounds_access_fails_instead_of_panicking() -> crate::Result {
+        let (_, pack_path) = V2_PACKS_AND_INDICES[0];
+        let entire_pack = gix_pack::data::File::at(fixture_path(pack_path), gix_hash::Kind::Sha1)?;
+        let cache = Arc::new(window::Cache::new(window::Options {
+            window_size: 64,
+            limit: 64,
+        }));
+        let pack = gix_pack::data::File::at_with_windows(fixture_path(pack_path), gix_hash::Kind::Sha1, cache)?;
+        for pack in [&entire_pack, &pack] {
+            let len = pack.data_len() as u64;
+            assert!(pack.entry(len + 1).is_err());
+            assert!(pack.entry_crc32(len - 1, 2).is_err());
+            assert!(pack.entry_slice(len - 1..len + 1).is_none());
+        }
+        Ok(())
+    }
+
+    fn decode(
+        pack: &gix_pack::data::File,
+        index: &gix_pack::index::File,
+        pack_offset: u64,
+        out: &mut Vec<u8>,
+    ) -> Result<gix_pack::data::decode::entry::Outcome, gix_pack::data::decode::Error> {
+        let entry = pack.entry(pack_offset)?;
+        pack.decode_entry(
+            entry,
+            out,
+            &mut Default::default(),
+            &|id, _out| {
+                index
+                    .lookup(id)
+                    .map(|idx| ResolvedBase::InPack(pack.entry(index.pack_offset_at_index(idx)).expect("valid")))
+            },
+            &mut cache::Never,
+        )
+    }
+}
+
 /// All hardcoded offsets are obtained via `git pack-verify --verbose  tests/fixtures/packs/pack-a2bf8e71d8c18879e499335762dd95119d93d9f1.idx`
 mod decode_entry {
     use bstr::ByteSlice;
@@ -210,3 +210,257 @@ mod lookup_ref_delta_objects {
         }
     }
 }
+
+mod entries_to_objects {
+    use std::sync::atomic::AtomicBool;
+
+    use gix_features::progress;
+    use gix_pack::{
+        data::{
+            entry::Header,
+            input,
+            input::{entries_to_objects, DecodedEntry, EntriesToObjectsIter},
+        },
+        index,
+    };
+
+    use crate::{
+        fixture_path,
+        pack::{
+            INDEX_V1, INDEX_V2, PACK_FOR_INDEX_V1, PACK_FOR_INDEX_V2, SMALL_PACK, SMALL_PACK_INDEX,
+            V2_PACKS_AND_INDICES,
+        },
+    };
+
+    const ALL_PACKS_AND_INDICES: &[(&str, &str)] = &[
+        (SMALL_PACK_INDEX, SMALL_PACK),
+        (INDEX_V1, PACK_FOR_INDEX_V1),
+        (INDEX_V2, PACK_FOR_INDEX_V2),
+    ];
+
+    fn index_statistics(idx: &index::File, pack: &gix_pack::data::File) -> crate::Result<index::traverse::Statistics> {
+        Ok(idx
+            .verify_integrity(
+                Some(index::verify::PackContext {
+                    data: pack,
+                    options: index::verify::integrity::Options::default(),
+                }),
+                &mut progress::Discard,
+                &AtomicBool::new(false),
+            )?
+            .pack_traverse_statistics
+            .expect("pack was traversed"))
+    }
+
+    fn slice_map(entry: gix_pack::data::EntryRange, map: &memmap2::Mmap) -> Option<&[u8]> {
+        map.get(entry.start as usize..entry.end as usize)
+    }
+
+    fn slice_vec(entry: gix_pack::data::EntryRange, pack: &Vec<u8>) -> Option<&[u8]> {
+        pack.get(entry.start as usize..entry.end as usize)
+    }
+
+    fn assert_entries_match_index(entries: &[DecodedEntry], idx: &index::File) {
+        let mut index_entries: Vec<_> = idx.iter().collect();
+        index_entries.sort_by_key(|e| e.pack_offset);
+        assert_eq!(entries.len(), index_entries.len());
+        for (entry, index_entry) in entries.iter().zip(index_entries) {
+            assert_eq!(entry.pack_offset, index_entry.pack_offset);
+            assert_eq!(entry.id, index_entry.oid);
+            if index_entry.crc32.is_some() {
+                assert_eq!(entry.crc32, index_entry.crc32);
+            }
+        }
+    }
+
+    #[test]
+    fn sequential_statistics_are_the_same_as_with_index() -> crate::Result {
+        for (index_path, pack_path) in ALL_PACKS_AND_INDICES {
+            let idx = index::File::at(fixture_path(index_path), gix_hash::Kind::Sha1)?;
+            let pack = gix_pack::data::File::at(fixture_path(pack_path), gix_hash::Kind::Sha1)?;
+            let expected = index_statistics(&idx, &pack)?;
+
+            for base_cache_budget_in_bytes in [usize::MAX, 1024, 0] {
+                let entries = EntriesToObjectsIter::new(
+                    pack.streaming_iter()?,
+                    entries_to_objects::Options {
+                        base_cache_budget_in_bytes,
+                        spill_directory: Some(std::env::temp_dir()),
+                        ..Default::default()
+                    },
+                )
+                .collect::<Result<Vec<_>, _>>()?;
+                assert_entries_match_index(&entries, &idx);
+                assert_eq!(
+                    index::traverse::Statistics::from_decoded_entries(&entries, pack.data_len() as u64),
+                    expected,
+                    "{pack_path} with a budget of {base_cache_budget_in_bytes} bytes"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn parallel_statistics_are_the_same_as_with_index() -> crate::Result {
+        // ref-deltas, as in the V1 pack, have to be resolved beforehand.
+        for (index_path, pack_path) in V2_PACKS_AND_INDICES {
+            let idx = index::File::at(fixture_path(index_path), gix_hash::Kind::Sha1)?;
+            let pack = gix_pack::data::File::at(fixture_path(pack_path), gix_hash::Kind::Sha1)?;
+
+            let entries = entries_to_objects::in_parallel(
+                &mut pack.streaming_iter()?,
+                || {
+                    let file = std::fs::File::open(fixture_path(pack_path))?;
+                    let map = unsafe { memmap2::MmapOptions::new().map_copy_read_only(&file)? };
+                    Ok((slice_map, map))
+                },
+                None,
+                &mut progress::Discard,
+                &AtomicBool::new(false),
+                gix_hash::Kind::Sha1,
+            )?;
+            assert_entries_match_index(&entries, &idx);
+            assert_eq!(
+                index::traverse::Statistics::from_decoded_entries(&entries, pack.data_len() as u64),
+                index_statistics(&idx, &pack)?,
+                "{pack_path}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn evicted_bases_without_spilling_cause_an_error() -> crate::Result {
+        let pack = gix_pack::data::File::at(fixture_path(SMALL_PACK), gix_hash::Kind::Sha1)?;
+        let err = EntriesToObjectsIter::new(
+            pack.streaming_iter()?,
+            entries_to_objects::Options {
+                base_cache_budget_in_bytes: 0,
+                ..Default::default()
+            },
+        )
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_err();
+        assert!(
+            matches!(err, entries_to_objects::Error::EvictedBase { .. }),
+            "the pack has deltas, but their bases can't be obtained anymore, as spilling is disabled by default"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn deltas_with_mismatching_base_size_cause_an_error() -> crate::Result {
+        let base = input::Entry::from_data_obj(
+            &gix_object::Data {
+                kind: gix_object::Kind::Blob,
+                data: b"abc",
+            },
+            12,
+        )?;
+        // Claim a base of 5 bytes and produce 3 bytes by inserting them.
+        let delta_data = b"\x05\x03\x03xyz";
+        let mut delta = input::Entry::from_data_obj(
+            &gix_object::Data {
+                kind: gix_object::Kind::Blob,
+                data: delta_data,
+            },
+            base.pack_offset + base.bytes_in_pack(),
+        )?;
+        delta.header = Header::OfsDelta {
+            base_distance: delta.pack_offset - base.pack_offset,
+        };
+
+        let err = EntriesToObjectsIter::new(vec![Ok(base), Ok(delta)].into_iter(), Default::default())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                entries_to_objects::Error::BaseSizeMismatch {
+                    expected: 5,
+                    actual: 3,
+                    ..
+                }
+            ),
+            "malformed packs are an error, not a panic: {err:?}"
+        );
+        Ok(())
+    }
+
+    fn base_and_delta(delta_data: &[u8]) -> crate::Result<(input::Entry, input::Entry)> {
+        let base = input::Entry::from_data_obj(
+            &gix_object::Data {
+                kind: gix_object::Kind::Blob,
+                data: b"abc",
+            },
+            12,
+        )?;
+        let mut delta = input::Entry::from_data_obj(
+            &gix_object::Data {
+                kind: gix_object::Kind::Blob,
+                data: delta_data,
+            },
+            base.pack_offset + base.bytes_in_pack(),
+        )?;
+        delta.header = Header::OfsDelta {
+            base_distance: delta.pack_offset - base.pack_offset,
+        };
+        delta.header_size = delta.header.size(delta.decompressed_size) as u16;
+        Ok((base, delta))
+    }
+
+    /// Deltas claiming a base of 3 bytes, with instructions that can't be applied to it.
+    const CORRUPT_DELTAS: &[&[u8]] = &[
+        b"\x03\x03\x00",
+        b"\x03\x05\x91\x00\x05",
+        b"\x03\x03\x05ab",
+        b"\x03\x04\x02xy",
+        b"\x03\xff\xff\xff\xff\xff\xff\xff\xff\x01\x02xy",
+    ];
+
+    #[test]
+    fn corrupt_deltas_cause_an_error() -> crate::Result {
+        for delta_data in CORRUPT_DELTAS {
+            let (base, delta) = base_and_delta(delta_data)?;
+            let err = EntriesToObjectsIter::new(vec![Ok(base), Ok(delta)].into_iter(), Default::default())
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap_err();
+            assert!(
+                matches!(err, entries_to_objects::Error::DeltaApply { .. }),
+                "malformed deltas are an error, not a panic: {err:?}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn corrupt_deltas_cause_an_error_when_resolved_in_parallel() -> crate::Result {
+        for delta_data in CORRUPT_DELTAS {
+            let (base, delta) = base_and_delta(delta_data)?;
+            let mut pack = vec![0; base.pack_offset as usize];
+            for entry in [&base, &delta] {
+                entry.header.write_to(entry.decompressed_size, &mut pack)?;
+                pack.extend_from_slice(entry.compressed.as_deref().expect("kept"));
+            }
+
+            let err = entries_to_objects::in_parallel(
+                &mut vec![Ok(base), Ok(delta)].into_iter(),
+                || Ok((slice_vec, pack)),
+                None,
+                &mut progress::Discard,
+                &AtomicBool::new(false),
+                gix_hash::Kind::Sha1,
+            )
+            .unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    entries_to_objects::Error::Parallel(index::write::Error::TreeTraversal(_))
+                ) && format!("{err:?}").contains("DeltaApply"),
+                "malformed deltas are an error, not a panic: {err:?}"
+            );
+        }
+        Ok(())
+    }
+}
@@ -158,6 +158,7 @@ mod version {
                     &AtomicBool::new(false),
                     gix_hash::Kind::Sha1,
                     pack_version,
+                    None,
                 )?;
 
                 let expected = fs::read(fixture_path(index_path))?;
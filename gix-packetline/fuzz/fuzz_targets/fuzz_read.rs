@@ -0,0 +1,28 @@
+#![no_main]
+
+use gix_packetline::{decode, PacketLineRef, StreamingPeekableIter};
+use libfuzzer_sys::fuzz_target;
+use std::hint::black_box;
+
+fn fuzz(data: &[u8]) {
+    let mut buf = data;
+    while let Ok(decode::Stream::Complete { line, bytes_consumed }) = decode::streaming(buf) {
+        assert!(bytes_consumed <= buf.len());
+        _ = black_box(line.decode_band());
+        buf = &buf[bytes_consumed..];
+    }
+
+    let mut rd = StreamingPeekableIter::new(data, &[PacketLineRef::Flush], false);
+    while let Some(Ok(Ok(line))) = rd.read_line() {
+        _ = black_box(line.as_text());
+    }
+
+    let mut rd = StreamingPeekableIter::new(data, &[PacketLineRef::Flush], false);
+    if let Ok(stats) = rd.demultiplex_sidebands(std::io::sink(), std::io::sink(), std::io::sink()) {
+        assert!(stats.data + stats.progress + stats.error <= data.len() as u64);
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    fuzz(data);
+});
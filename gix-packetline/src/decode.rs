@@ -91,7 +91,7 @@ pub fn hex_prefix(four_bytes: &[u8]) -> Result<PacketLineOrWantedSize<'_>, Error
 
 /// Obtain a `PacketLine` from `data` after assuring `data` is small enough to fit.
 pub fn to_data_line(data: &[u8]) -> Result<PacketLineRef<'_>, Error> {
-    if data.len() > MAX_LINE_LEN {
+    if data.len() > MAX_DATA_LEN {
         return Err(Error::DataLengthLimitExceeded {
             length_in_bytes: data.len(),
         });
@@ -28,6 +28,27 @@ pub fn band_to_write(kind: Channel, data: &[u8], out: impl io::Write) -> io::Res
     prefixed_data_to_write(&[kind as u8], data, out)
 }
 
+/// Write `data` of `kind` to `out` using side-band encoding, split into as many packet lines as needed for each of them
+/// to carry no more than `max_data_len` bytes of `data`, or as much as fits into a packet line if that's less.
+///
+/// This is useful as the `side-band` capability limits packet lines to 1000 bytes, while `side-band-64k` allows
+/// for the largest possible packet lines.
+pub fn bands_to_write(
+    kind: Channel,
+    mut data: &[u8],
+    max_data_len: usize,
+    mut out: impl io::Write,
+) -> io::Result<usize> {
+    let max_data_len = max_data_len.clamp(1, MAX_DATA_LEN - 1);
+    let mut written = 0;
+    while !data.is_empty() {
+        let (chunk, rest) = data.split_at(data.len().min(max_data_len));
+        written += band_to_write(kind, chunk, &mut out)?;
+        data = rest;
+    }
+    Ok(written)
+}
+
 /// Write a `data` message to `out`.
 pub fn data_to_write(data: &[u8], out: impl io::Write) -> io::Result<usize> {
     prefixed_data_to_write(&[], data, out)
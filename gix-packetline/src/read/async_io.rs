@@ -1,13 +1,13 @@
 use std::io;
 
 use bstr::ByteSlice;
-use futures_io::AsyncRead;
-use futures_lite::AsyncReadExt;
+use futures_io::{AsyncRead, AsyncWrite};
+use futures_lite::{AsyncReadExt, AsyncWriteExt};
 
 use crate::{
     decode,
-    read::{ExhaustiveOutcome, ProgressAction, WithSidebands},
-    PacketLineRef, StreamingPeekableIter, MAX_LINE_LEN, U16_HEX_BYTES,
+    read::{Demultiplexed, ExhaustiveOutcome, ProgressAction, WithSidebands},
+    BandRef, PacketLineRef, StreamingPeekableIter, MAX_LINE_LEN, U16_HEX_BYTES,
 };
 
 /// Non-IO methods
@@ -165,6 +165,30 @@ where
         }
     }
 
+    /// Read all packet lines up to the next delimiter and decode each of them as side-band, writing the data band to `data`,
+    /// the progress band to `progress` and the error band to `error` as is, and return the amount of bytes seen on each band.
+    ///
+    /// Unlike [`as_read_with_sidebands()`][StreamingPeekableIter::as_read_with_sidebands()], the error band doesn't stop
+    /// the iteration, which is useful to observe or forward all traffic.
+    /// Lines that aren't valid side-band lines fail with [`InvalidData`][io::ErrorKind::InvalidData].
+    pub async fn demultiplex_sidebands(
+        &mut self,
+        mut data: impl AsyncWrite + Unpin,
+        mut progress: impl AsyncWrite + Unpin,
+        mut error: impl AsyncWrite + Unpin,
+    ) -> io::Result<Demultiplexed> {
+        let mut out = Demultiplexed::default();
+        while let Some(line) = self.read_line().await {
+            let line = line?.map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            match out.record(line)? {
+                BandRef::Data(band) => data.write_all(band).await?,
+                BandRef::Progress(band) => progress.write_all(band).await?,
+                BandRef::Error(band) => error.write_all(band).await?,
+            }
+        }
+        Ok(out)
+    }
+
     /// Same as [`as_read_with_sidebands(…)`][StreamingPeekableIter::as_read_with_sidebands()], but for channels without side band support.
     ///
     /// Due to the preconfigured function type this method can be called without 'turbofish'.
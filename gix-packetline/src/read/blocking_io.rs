@@ -4,8 +4,8 @@ use bstr::ByteSlice;
 
 use crate::{
     decode,
-    read::{ExhaustiveOutcome, ProgressAction, WithSidebands},
-    PacketLineRef, StreamingPeekableIter, MAX_LINE_LEN, U16_HEX_BYTES,
+    read::{Demultiplexed, ExhaustiveOutcome, ProgressAction, WithSidebands},
+    BandRef, PacketLineRef, StreamingPeekableIter, MAX_LINE_LEN, U16_HEX_BYTES,
 };
 
 /// Non-IO methods
@@ -158,6 +158,30 @@ where
         }
     }
 
+    /// Read all packet lines up to the next delimiter and decode each of them as side-band, writing the data band to `data`,
+    /// the progress band to `progress` and the error band to `error` as is, and return the amount of bytes seen on each band.
+    ///
+    /// Unlike [`as_read_with_sidebands()`][StreamingPeekableIter::as_read_with_sidebands()], the error band doesn't stop
+    /// the iteration, which is useful to observe or forward all traffic.
+    /// Lines that aren't valid side-band lines fail with [`InvalidData`][io::ErrorKind::InvalidData].
+    pub fn demultiplex_sidebands(
+        &mut self,
+        mut data: impl io::Write,
+        mut progress: impl io::Write,
+        mut error: impl io::Write,
+    ) -> io::Result<Demultiplexed> {
+        let mut out = Demultiplexed::default();
+        while let Some(line) = self.read_line() {
+            let line = line?.map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            match out.record(line)? {
+                BandRef::Data(band) => data.write_all(band)?,
+                BandRef::Progress(band) => progress.write_all(band)?,
+                BandRef::Error(band) => error.write_all(band)?,
+            }
+        }
+        Ok(out)
+    }
+
     /// Return this instance as implementor of [`Read`][io::Read] assuming side bands to be used in all received packet lines.
     /// Each invocation of [`read_line()`][io::BufRead::read_line()] returns a packet line.
     ///
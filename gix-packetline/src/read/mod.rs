@@ -125,4 +125,4 @@ mod async_io;
 
 mod sidebands;
 #[cfg(any(feature = "blocking-io", feature = "async-io"))]
-pub use sidebands::WithSidebands;
+pub use sidebands::{Demultiplexed, WithSidebands};
@@ -7,3 +7,33 @@ pub use blocking_io::WithSidebands;
 mod async_io;
 #[cfg(all(not(feature = "blocking-io"), feature = "async-io"))]
 pub use async_io::WithSidebands;
+
+/// The amount of bytes received on each band, as returned by
+/// [`StreamingPeekableIter::demultiplex_sidebands()`](crate::StreamingPeekableIter::demultiplex_sidebands()).
+#[cfg(any(feature = "blocking-io", feature = "async-io"))]
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Demultiplexed {
+    /// The amount of bytes received on the data band.
+    pub data: u64,
+    /// The amount of bytes received on the progress band.
+    pub progress: u64,
+    /// The amount of bytes received on the error band.
+    pub error: u64,
+}
+
+#[cfg(any(feature = "blocking-io", feature = "async-io"))]
+impl Demultiplexed {
+    /// Decode `line` as side-band and count its bytes, or fail if it isn't a valid side-band line.
+    pub(crate) fn record<'a>(&mut self, line: crate::PacketLineRef<'a>) -> std::io::Result<crate::BandRef<'a>> {
+        let band = line
+            .decode_band()
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        let (count, data) = match band {
+            crate::BandRef::Data(data) => (&mut self.data, data),
+            crate::BandRef::Progress(data) => (&mut self.progress, data),
+            crate::BandRef::Error(data) => (&mut self.error, data),
+        };
+        *count += data.len() as u64;
+        Ok(band)
+    }
+}
@@ -130,6 +130,44 @@ mod streaming {
         );
     }
 
+    #[test]
+    fn error_on_partially_invalid_hex() {
+        for input in [&b"000g"[..], b"-001", b"00 5", b"0x05"] {
+            assert!(
+                matches!(streaming(input), Err(decode::Error::HexDecode { .. })),
+                "{input:?} isn't a valid length"
+            );
+        }
+    }
+
+    #[test]
+    fn lengths_below_the_length_of_the_prefix_are_special_or_invalid() -> crate::Result {
+        assert_complete(streaming(b"0001"), 4, PacketLineRef::Delimiter)?;
+        assert_complete(streaming(b"0002"), 4, PacketLineRef::ResponseEnd)?;
+        assert_err_display(streaming(b"0003"), "Received an invalid line of length 3");
+        Ok(())
+    }
+
+    #[test]
+    fn the_largest_line_is_the_last_one_to_be_accepted() -> crate::Result {
+        let mut line = b"fff0".to_vec();
+        line.resize(65520, b'a');
+        assert_complete(streaming(&line), 65520, PacketLineRef::Data(&line[4..]))?;
+        assert_err_display(
+            streaming(b"fff1"),
+            "The data received claims to be larger than the maximum allowed size: got 65521, exceeds 65516",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn oversized_data_lines_are_rejected() {
+        assert_err_display(
+            decode::to_data_line(&[0; 65517]),
+            "The data received claims to be larger than the maximum allowed size: got 65517, exceeds 65516",
+        );
+    }
+
     #[test]
     fn error_on_empty_line() {
         assert_err_display(streaming(b"0004"), "Received an invalid empty line");
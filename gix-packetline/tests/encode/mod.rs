@@ -77,6 +77,40 @@ mod error {
     }
 }
 
+mod bands_to_write {
+    use bstr::ByteSlice;
+    use gix_packetline::{encode::bands_to_write, Channel};
+
+    #[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
+    async fn data_is_split_into_lines_of_the_given_size() -> crate::Result {
+        let mut out = Vec::new();
+        let res = bands_to_write(Channel::Progress, b"hello", 2, &mut out).await?;
+        assert_eq!(res, 3 * 4 + 3 + 5);
+        assert_eq!(out.as_bstr(), b"0007\x02he0007\x02ll0006\x02o".as_bstr());
+        Ok(())
+    }
+
+    #[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
+    async fn the_line_size_is_limited_to_what_fits_into_a_packet_line() -> crate::Result {
+        let mut out = Vec::new();
+        let data = vec![b'a'; 65516];
+        bands_to_write(Channel::Data, &data, usize::MAX, &mut out).await?;
+        assert_eq!(
+            out.len(),
+            4 + 65516 + 4 + 1 + 1,
+            "two lines are needed as the band takes one byte"
+        );
+        assert_eq!(&out[..4], b"fff0");
+        assert_eq!(&out[out.len() - 6..], b"0006\x01a");
+
+        out.clear();
+        let res = bands_to_write(Channel::Data, &[], 1000, &mut out).await?;
+        assert_eq!(res, 0, "nothing is written without data");
+        assert!(out.is_empty());
+        Ok(())
+    }
+}
+
 mod flush_delim_response_end {
     use bstr::ByteSlice;
     use gix_packetline::encode::{delim_to_write, flush_to_write, response_end_to_write};
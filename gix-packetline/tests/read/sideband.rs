@@ -5,7 +5,10 @@ use bstr::{BString, ByteSlice};
 #[cfg(all(not(feature = "blocking-io"), feature = "async-io"))]
 use futures_lite::io::AsyncReadExt;
 use gix_odb::pack;
-use gix_packetline::{read::ProgressAction, PacketLineRef};
+use gix_packetline::{
+    read::{Demultiplexed, ProgressAction},
+    PacketLineRef,
+};
 
 use crate::read::streaming_peek_iter::fixture_bytes;
 
@@ -272,3 +275,74 @@ async fn handling_of_err_lines() {
         "An error can also be the reason, which is not distinguishable from an EOF"
     );
 }
+
+#[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
+async fn demultiplex_sidebands_into_separate_sinks() -> crate::Result {
+    let buf = fixture_bytes("v1/01-clone.combined-output");
+    let mut rd = gix_packetline::StreamingPeekableIter::new(&buf[..], &[PacketLineRef::Flush], false);
+    let mut out = Vec::new();
+    rd.as_read().read_to_end(&mut out).await?;
+    rd.read_line().await.expect("NAK line")??;
+
+    let (mut data, mut progress, mut error) = (Vec::new(), Vec::new(), Vec::new());
+    let stats = rd.demultiplex_sidebands(&mut data, &mut progress, &mut error).await?;
+    assert_eq!(
+        stats,
+        Demultiplexed {
+            data: data.len() as u64,
+            progress: progress.len() as u64,
+            error: 0
+        }
+    );
+    assert_eq!(&data[..4], b"PACK", "the data band contains the pack");
+    assert!(progress.starts_with(b"Enumerating objects: 3, done.\n"));
+    assert_eq!(rd.stopped_at(), Some(PacketLineRef::Flush));
+    Ok(())
+}
+
+#[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
+async fn demultiplex_sidebands_continues_after_errors() -> crate::Result {
+    let input = b"0008\x01one0009\x03oops0008\x02two0008\x01six0000";
+    let mut rd = gix_packetline::StreamingPeekableIter::new(&input[..], &[PacketLineRef::Flush], false);
+    let (mut data, mut progress, mut error) = (Vec::new(), Vec::new(), Vec::new());
+    let stats = rd.demultiplex_sidebands(&mut data, &mut progress, &mut error).await?;
+    assert_eq!(
+        stats,
+        Demultiplexed {
+            data: 6,
+            progress: 3,
+            error: 4
+        }
+    );
+    assert_eq!(data.as_bstr(), "onesix");
+    assert_eq!(progress.as_bstr(), "two");
+    assert_eq!(error.as_bstr(), "oops");
+    Ok(())
+}
+
+#[maybe_async::test(feature = "blocking-io", async(feature = "async-io", async_std::test))]
+async fn demultiplex_sidebands_fails_on_invalid_lines() -> crate::Result {
+    for (input, expected) in [
+        (
+            &b"0008\x04bad0000"[..],
+            "attempt to decode a non-side channel line or input was malformed: 4",
+        ),
+        (
+            b"00010000",
+            "attempt to decode a non-data line into a side-channel band",
+        ),
+        (
+            b"000g",
+            "Failed to decode the first four hex bytes indicating the line length: Invalid character",
+        ),
+    ] {
+        let mut rd = gix_packetline::StreamingPeekableIter::new(input, &[PacketLineRef::Flush], false);
+        let err = rd
+            .demultiplex_sidebands(Vec::new(), Vec::new(), Vec::new())
+            .await
+            .expect_err("invalid input");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), expected);
+    }
+    Ok(())
+}
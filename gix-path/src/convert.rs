@@ -245,6 +245,41 @@ pub fn to_windows_separators<'a>(path: impl Into<Cow<'a, BStr>>) -> Cow<'a, BStr
     replace(path, b'/', b'\\')
 }
 
+/// Convert `path` as stored in `git`, i.e. in trees or the index, with slashes as separators, into a platform path
+/// for accessing the filesystem, which only converts separators on windows.
+///
+/// On unix, bytes are used verbatim so every `path` can be converted without loss. On windows, `path` must be valid UTF-8
+/// or [`Utf8Error`] is returned.
+pub fn try_from_git_path<'a>(path: impl Into<Cow<'a, BStr>>) -> Result<Cow<'a, Path>, Utf8Error> {
+    #[cfg(not(windows))]
+    {
+        try_from_bstr(path)
+    }
+    #[cfg(windows)]
+    {
+        try_from_bstr(to_windows_separators(path))
+    }
+}
+
+/// Similar to [`try_from_git_path()`], but **panics** if `path` isn't valid UTF-8 on windows.
+pub fn from_git_path<'a>(path: impl Into<Cow<'a, BStr>>) -> Cow<'a, Path> {
+    try_from_git_path(path).expect("well-formed UTF-8 on windows")
+}
+
+/// Convert the platform `path` into a path suitable for storage in `git`, with slashes as separators, which is the
+/// inverse of [`try_from_git_path()`].
+///
+/// On unix, bytes are used verbatim so every `path` can be converted without loss. On windows, `path` must be valid
+/// unicode, or [`Utf8Error`] is returned.
+pub fn try_into_git_path<'a>(path: impl Into<Cow<'a, Path>>) -> Result<Cow<'a, BStr>, Utf8Error> {
+    try_into_bstr(path).map(to_unix_separators_on_windows)
+}
+
+/// Similar to [`try_into_git_path()`], but **panics** if `path` isn't valid unicode on windows.
+pub fn into_git_path<'a>(path: impl Into<Cow<'a, Path>>) -> Cow<'a, BStr> {
+    try_into_git_path(path).expect("well-formed unicode on windows")
+}
+
 /// Resolve relative components virtually without accessing the file system, e.g. turn `a/./b/c/.././..` into `a`,
 /// without keeping intermediate `..` and `/a/../b/..` becomes `/`.
 /// If the input path was relative and ends up being the `current_dir`, `.` is returned instead of the full path to `current_dir`.
@@ -334,3 +369,60 @@ pub fn relativize_with_prefix<'a>(relative_path: &'a Path, prefix: &Path) -> Cow
         Cow::Owned(buf)
     }
 }
+
+/// Compute the path to `target` as seen from `base`, like `../other/file.txt`, lexically and without accessing the file system.
+///
+/// Both paths are assumed to be [normalized](normalize()) and either both absolute or both relative, with `base` typically
+/// being the current working directory. Trailing slashes don't matter, and if both paths are equal, `.` is returned.
+///
+/// If `target` can't be expressed relative to `base`, for instance because they are on different drives on Windows,
+/// only one of them is absolute, or `base` contains `..` components that can't be resolved lexically, `target`
+/// is returned unchanged.
+pub fn relativize<'a>(target: &'a Path, base: &Path) -> Cow<'a, Path> {
+    if target.is_absolute() != base.is_absolute() {
+        return Cow::Borrowed(target);
+    }
+    let mut target_components = target
+        .components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .peekable();
+    let mut base_components = base.components().filter(|c| !matches!(c, Component::CurDir)).peekable();
+    while let (Some(t), Some(b)) = (target_components.peek(), base_components.peek()) {
+        if t != b {
+            break;
+        }
+        target_components.next();
+        base_components.next();
+    }
+
+    let is_root = |c: &Component<'_>| matches!(c, Component::Prefix(_) | Component::RootDir);
+    if matches!(target_components.peek(), Some(c) if is_root(c))
+        || matches!(base_components.peek(), Some(c) if is_root(c))
+    {
+        return Cow::Borrowed(target);
+    }
+    let mut buf = PathBuf::new();
+    for component in base_components {
+        if component == Component::ParentDir {
+            return Cow::Borrowed(target);
+        }
+        buf.push(Component::ParentDir);
+    }
+    buf.extend(target_components);
+    if buf.as_os_str().is_empty() {
+        Cow::Borrowed(Path::new("."))
+    } else {
+        Cow::Owned(buf)
+    }
+}
+
+/// Like [`relativize()`], but return `target` unchanged if it is shorter than the path relative to `base`,
+/// which makes it suitable for displaying paths to the user just like `git` does.
+pub fn relativize_for_display<'a>(target: &'a Path, base: &Path) -> Cow<'a, Path> {
+    let relative = relativize(target, base);
+    if relative.as_os_str().len() <= target.as_os_str().len() {
+        relative
+    } else {
+        Cow::Borrowed(target)
+    }
+}
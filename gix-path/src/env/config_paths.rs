@@ -0,0 +1,100 @@
+use std::path::PathBuf;
+
+/// The scope of a configuration file as returned by [`config_paths()`][function::config_paths()],
+/// in order of ascending precedence.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Scope {
+    /// A special configuration file that ships with the git installation, and is thus tied to the used git binary.
+    Installation,
+    /// The configuration file shared for the entire system.
+    System,
+    /// The configuration files unique for each user of the system.
+    Global,
+}
+
+/// A configuration file as returned by [`config_paths()`][function::config_paths()].
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Entry {
+    /// The scope the configuration file belongs to.
+    pub scope: Scope,
+    /// The location of the configuration file, which may not exist.
+    pub path: PathBuf,
+    /// If `true`, `path` was a file at the time it was returned.
+    pub exists: bool,
+}
+
+pub(crate) mod function {
+    use std::{
+        ffi::{OsStr, OsString},
+        path::PathBuf,
+    };
+
+    use super::{Entry, Scope};
+
+    /// Return the locations of all configuration files that aren't specific to a repository, in the order in which a loader
+    /// should apply them, i.e. in order of ascending precedence, obtaining environment variables with `var_os(<name>)`.
+    ///
+    /// These are
+    ///
+    /// * the configuration of the git [installation][super::super::installation_config()],
+    /// * the system configuration at `etc/gitconfig` below the [system prefix][super::super::system_prefix()],
+    ///   or the path in `GIT_CONFIG_SYSTEM`,
+    /// * the global configuration in the [xdg config directory][super::super::xdg_config()] and in `$HOME/.gitconfig`,
+    ///   or the single path in `GIT_CONFIG_GLOBAL`.
+    ///
+    /// The installation and system configuration are omitted if `GIT_CONFIG_NOSYSTEM` is true.
+    /// Paths that would be returned more than once are only returned at their position of highest precedence,
+    /// which is the case if the installation configuration also is the system or the global configuration for instance.
+    ///
+    /// Note that `HOME` should be obtained with [`var()`][super::super::var()] to work correctly on Windows.
+    ///
+    /// ### Performance
+    ///
+    /// Unless `GIT_CONFIG_NOSYSTEM` is set, this invokes the git binary once per process which is slow on windows.
+    pub fn config_paths(var_os: &mut dyn FnMut(&str) -> Option<OsString>) -> Vec<Entry> {
+        let mut paths = Vec::<(Scope, PathBuf)>::new();
+        if !matches!(var_os("GIT_CONFIG_NOSYSTEM"), Some(v) if is_true(&v)) {
+            if let Some(path) = super::super::installation_config() {
+                paths.push((Scope::Installation, path.to_owned()));
+            }
+            if let Some(path) = var_os("GIT_CONFIG_SYSTEM")
+                .map(PathBuf::from)
+                .or_else(|| super::super::system_prefix().map(|p| p.join("etc").join("gitconfig")))
+            {
+                paths.push((Scope::System, path));
+            }
+        }
+        match var_os("GIT_CONFIG_GLOBAL") {
+            Some(path) => paths.push((Scope::Global, path.into())),
+            None => {
+                if let Some(path) = super::super::xdg_config("config", var_os) {
+                    paths.push((Scope::Global, path));
+                }
+                if let Some(home) = var_os("HOME") {
+                    paths.push((Scope::Global, PathBuf::from(home).join(".gitconfig")));
+                }
+            }
+        }
+
+        let mut out = Vec::with_capacity(paths.len());
+        for (idx, (scope, path)) in paths.iter().enumerate() {
+            if paths[idx + 1..].iter().any(|(_, later)| later == path) {
+                continue;
+            }
+            out.push(Entry {
+                scope: *scope,
+                exists: path.is_file(),
+                path: path.clone(),
+            });
+        }
+        out
+    }
+
+    /// Interpret `value` like `git` interprets boolean environment variables, considering anything unknown as `false`.
+    fn is_true(value: &OsStr) -> bool {
+        let Some(value) = value.to_str() else { return false };
+        let value = value.trim();
+        ["true", "yes", "on"].iter().any(|v| v.eq_ignore_ascii_case(value))
+            || matches!(value.parse::<i64>(), Ok(v) if v != 0)
+    }
+}
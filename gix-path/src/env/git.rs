@@ -16,7 +16,10 @@ pub(crate) fn install_config_path() -> Option<&'static BStr> {
         if let Some(mut exec_path) = std::env::var_os("EXEPATH").map(std::path::PathBuf::from) {
             exec_path.push("etc");
             exec_path.push("gitconfig");
-            return crate::os_string_into_bstring(exec_path.into()).ok();
+            // `git` reports this path with slashes as well.
+            return crate::try_into_git_path(exec_path)
+                .ok()
+                .map(std::borrow::Cow::into_owned);
         }
         let mut cmd = Command::new(if cfg!(windows) { "git.exe" } else { "git" });
         cmd.args(["config", "-l", "--show-origin"])
@@ -7,6 +7,11 @@ use bstr::{BString, ByteSlice};
 
 mod git;
 
+///
+#[allow(clippy::empty_docs)]
+pub mod config_paths;
+pub use config_paths::function::config_paths;
+
 /// Return the location at which installation specific git configuration file can be found, or `None`
 /// if the binary could not be executed or its results could not be parsed.
 ///
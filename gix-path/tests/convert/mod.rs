@@ -98,3 +98,145 @@ mod relativize_with_prefix {
         assert_eq!(r("", ""), "", "empty stays empty");
     }
 }
+
+mod relativize {
+    use std::path::Path;
+
+    fn r(target: &str, base: &str) -> String {
+        gix_path::to_unix_separators_on_windows(
+            gix_path::os_str_into_bstr(gix_path::relativize(target.as_ref(), base.as_ref()).as_os_str())
+                .expect("no illformed UTF-8"),
+        )
+        .to_string()
+    }
+
+    fn d(target: &str, base: &str) -> String {
+        gix_path::to_unix_separators_on_windows(
+            gix_path::os_str_into_bstr(gix_path::relativize_for_display(target.as_ref(), base.as_ref()).as_os_str())
+                .expect("no illformed UTF-8"),
+        )
+        .to_string()
+    }
+
+    #[test]
+    fn equal_paths_yield_the_current_directory() {
+        assert_eq!(r("/a/b", "/a/b"), ".");
+        assert_eq!(r("/a/b/", "/a/b"), ".", "trailing slashes don't matter");
+        assert_eq!(r("/a/b", "/a/b/"), ".");
+        assert_eq!(r("/", "/"), ".");
+        assert_eq!(r("a", "./a"), ".", "leading '.' components are ignored");
+        assert_eq!(r("", ""), ".");
+    }
+
+    #[test]
+    fn target_inside_of_base() {
+        assert_eq!(r("/a/b/c", "/a"), "b/c");
+        assert_eq!(r("/a/b/c/", "/a/"), "b/c");
+        assert_eq!(r("/a/b", "/"), "a/b");
+        assert_eq!(r("a/b", ""), "a/b", "an empty relative base is the current directory");
+    }
+
+    #[test]
+    fn base_inside_of_target() {
+        assert_eq!(r("/a", "/a/b/c"), "../..");
+        assert_eq!(r("/a/", "/a/b/c/"), "../..");
+        assert_eq!(r("/", "/a"), "..");
+        assert_eq!(r("", "a/b"), "../..");
+    }
+
+    #[test]
+    fn siblings_and_disjoint_paths() {
+        assert_eq!(r("/a/other/file.txt", "/a/b"), "../other/file.txt");
+        assert_eq!(r("/x/y", "/a/b"), "../../x/y");
+        assert_eq!(
+            r("/a/bc", "/a/b"),
+            "../bc",
+            "components are compared as a whole, not as strings"
+        );
+        assert_eq!(r("x/y", "a"), "../x/y");
+    }
+
+    #[test]
+    fn unrepresentable_targets_are_returned_unchanged() {
+        let abs = if cfg!(windows) { "c:/a" } else { "/a" };
+        assert_eq!(r(abs, "b"), abs, "absolute target with relative base");
+        assert_eq!(r("b", abs), "b", "relative target with absolute base");
+        assert_eq!(
+            r("a/b", "../c"),
+            "a/b",
+            "the name of the parent directory of the base isn't known"
+        );
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn different_drives_fall_back_to_the_absolute_path() {
+        assert_eq!(r("d:\\a\\b", "c:\\a"), "d:/a/b");
+        assert_eq!(r("c:\\a\\b", "c:\\a"), "b");
+    }
+
+    #[test]
+    fn display_prefers_the_shorter_form() {
+        assert_eq!(d("/a/b/other/file.txt", "/a/b/c"), "../other/file.txt");
+        assert_eq!(
+            d("/x", "/a/b/c/d"),
+            "/x",
+            "the absolute path is shorter than '../../../../x'"
+        );
+        assert_eq!(d("/a/b", "/a/b"), ".");
+        assert_eq!(
+            gix_path::relativize_for_display(Path::new("/a/b"), Path::new("/a")),
+            Path::new("b")
+        );
+    }
+}
+
+mod git_path {
+    use bstr::{BStr, ByteSlice};
+    use gix_path::{into_git_path, try_from_git_path, try_into_git_path};
+
+    #[test]
+    fn separators_are_converted_on_windows_only() {
+        let path = try_from_git_path(b"a/b/c".as_bstr()).expect("valid UTF-8");
+        #[cfg(windows)]
+        assert_eq!(path.to_str(), Some("a\\b\\c"));
+        #[cfg(not(windows))]
+        assert_eq!(path.to_str(), Some("a/b/c"));
+        assert_eq!(into_git_path(path).as_ref(), b"a/b/c".as_bstr(), "slashes are restored");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn arbitrary_bytes_round_trip_verbatim() {
+        let mut rng = fastrand::Rng::with_seed(42);
+        for _ in 0..1000 {
+            let len = rng.usize(0..64);
+            let bytes: Vec<u8> = (0..len)
+                .map(|_| if rng.bool() { b'/' } else { rng.u8(1..=255) })
+                .collect();
+            let path = try_from_git_path(bytes.as_bstr()).expect("unix paths can be any bytes");
+            assert_eq!(
+                try_into_git_path(path).expect("and they convert back"),
+                bytes.as_bstr(),
+                "conversions are lossless, even with ill-formed UTF-8"
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn ill_formed_utf8_is_an_error_on_windows() {
+        let path: &BStr = b"a/\xff/b".as_bstr();
+        assert!(try_from_git_path(path).is_err(), "windows paths must be valid unicode");
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn ill_formed_utf8_is_fine_on_unix() {
+        let path: &BStr = b"a/\xff/b".as_bstr();
+        assert_eq!(
+            try_into_git_path(try_from_git_path(path).expect("any bytes")).expect("any bytes"),
+            path
+        );
+    }
+}
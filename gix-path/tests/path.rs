@@ -40,3 +40,103 @@ mod xdg_config_path {
     }
 }
 mod util;
+
+mod config_paths {
+    use std::{ffi::OsString, path::Path};
+
+    use gix_path::env::config_paths::{Entry, Scope};
+
+    fn env<'a>(vars: &'a [(&'a str, &'a Path)]) -> impl FnMut(&str) -> Option<OsString> + 'a {
+        move |name| {
+            vars.iter()
+                .find_map(|(key, value)| (*key == name).then(|| value.as_os_str().to_owned()))
+        }
+    }
+
+    #[test]
+    fn global_paths_in_order_of_precedence_without_system_configuration() -> crate::Result {
+        let home = tempfile::tempdir()?;
+        std::fs::write(home.path().join(".gitconfig"), b"")?;
+        let actual = gix_path::env::config_paths(&mut env(&[
+            ("GIT_CONFIG_NOSYSTEM", Path::new("1")),
+            ("HOME", home.path()),
+        ]));
+        assert_eq!(
+            actual,
+            vec![
+                Entry {
+                    scope: Scope::Global,
+                    path: home.path().join(".config").join("git").join("config"),
+                    exists: false,
+                },
+                Entry {
+                    scope: Scope::Global,
+                    path: home.path().join(".gitconfig"),
+                    exists: true,
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn overrides_replace_system_and_global_paths() {
+        let actual = gix_path::env::config_paths(&mut env(&[
+            ("GIT_CONFIG_SYSTEM", Path::new("system-override")),
+            ("GIT_CONFIG_GLOBAL", Path::new("global-override")),
+            ("HOME", Path::new("home")),
+            ("XDG_CONFIG_HOME", Path::new("xdg")),
+        ]));
+        let without_installation: Vec<_> = actual
+            .iter()
+            .filter(|e| e.scope != Scope::Installation)
+            .map(|e| (e.scope, e.path.as_path(), e.exists))
+            .collect();
+        assert_eq!(
+            without_installation,
+            [
+                (Scope::System, Path::new("system-override"), false),
+                (Scope::Global, Path::new("global-override"), false)
+            ]
+        );
+        assert!(
+            actual.windows(2).all(|w| w[0].scope <= w[1].scope),
+            "entries are ordered by precedence"
+        );
+    }
+
+    #[test]
+    fn nosystem_is_a_boolean() {
+        for (value, expect_system) in [
+            ("0", true),
+            ("false", true),
+            ("", true),
+            ("true", false),
+            ("Yes", false),
+        ] {
+            let actual = gix_path::env::config_paths(&mut env(&[
+                ("GIT_CONFIG_NOSYSTEM", Path::new(value)),
+                ("GIT_CONFIG_SYSTEM", Path::new("system")),
+            ]));
+            assert_eq!(
+                actual.iter().any(|e| e.scope == Scope::System),
+                expect_system,
+                "{value:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn duplicate_paths_are_only_returned_at_their_highest_precedence() {
+        let actual = gix_path::env::config_paths(&mut env(&[
+            ("GIT_CONFIG_SYSTEM", Path::new("shared")),
+            ("GIT_CONFIG_GLOBAL", Path::new("shared")),
+        ]));
+        let shared: Vec<_> = actual
+            .iter()
+            .filter(|e| e.path == Path::new("shared"))
+            .map(|e| e.scope)
+            .collect();
+        assert_eq!(shared, [Scope::Global]);
+    }
+}
@@ -61,6 +61,15 @@ pub mod ls_refs;
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
 pub use ls_refs::function::ls_refs;
 
+///
+#[allow(clippy::empty_docs)]
+#[cfg(feature = "blocking-server")]
+pub mod receive_pack;
+///
+#[allow(clippy::empty_docs)]
+#[cfg(feature = "blocking-server")]
+pub mod upload_pack;
+
 mod util;
 pub use util::agent;
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
@@ -0,0 +1,99 @@
+use std::io;
+
+use bstr::{BString, ByteSlice};
+use gix_packetline::{PacketLineRef, StreamingPeekableIter};
+
+use crate::receive_pack::{Command, Request};
+
+/// The error returned by [`request()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Failed to read from the client")]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    PacketlineDecode(#[from] gix_packetline::decode::Error),
+    #[error("Expected a data line, but got a special packetline")]
+    ExpectedDataLine,
+    #[error("Could not parse command line {line:?}, expected '<old-id> <new-id> <refname>'")]
+    MalformedCommand { line: BString },
+    #[error("Could not parse object id in line {line:?}")]
+    InvalidObjectId { line: BString, source: gix_hash::decode::Error },
+    #[error("Push certificates are not supported")]
+    PushCertificateUnsupported,
+}
+
+/// Read the request of a client from `reader`, which is expected to be positioned just after the
+/// [ref advertisement](super::encode::advertisement()) was sent.
+///
+/// This reads the command list along with the capabilities on its first line and possibly leading `shallow` lines up to the first flush packet,
+/// followed by a section of push options if the client selected the `push-options` capability.
+/// Afterwards the reader inside of `reader` is positioned at the beginning of the pack, if the [request expects one](Request::expects_pack()).
+///
+/// If the client disconnects without sending any command, as is the case for `git ls-remote`, an empty request is returned.
+pub fn request<T: io::Read>(reader: &mut StreamingPeekableIter<T>) -> Result<Request, Error> {
+    let mut out = Request::default();
+    reader.reset_with(&[PacketLineRef::Flush]);
+    let mut is_first_line = true;
+    while let Some(line) = reader.read_line() {
+        let line = match line {
+            Ok(line) => line?,
+            Err(err) if is_first_line && err.kind() == io::ErrorKind::UnexpectedEof => return Ok(out),
+            Err(err) => return Err(err.into()),
+        };
+        is_first_line = false;
+        let line = line.as_bstr().ok_or(Error::ExpectedDataLine)?;
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+
+        if let Some(id) = line.strip_prefix(b"shallow ") {
+            out.shallow.push(parse_id(id, line)?);
+            continue;
+        }
+        if line.starts_with(b"push-cert") {
+            return Err(Error::PushCertificateUnsupported);
+        }
+
+        let command = match line.split_once_str(b"\0") {
+            Some((command, capabilities)) if out.commands.is_empty() => {
+                out.capabilities = capabilities
+                    .split(|b| *b == b' ')
+                    .filter(|cap| !cap.is_empty())
+                    .map(Into::into)
+                    .collect();
+                command
+            }
+            _ => line,
+        };
+        out.commands.push(parse_command(command)?);
+    }
+
+    if !out.commands.is_empty() && out.has_capability("push-options") {
+        reader.reset_with(&[PacketLineRef::Flush]);
+        while let Some(line) = reader.read_line() {
+            let line = line??;
+            let line = line.as_bstr().ok_or(Error::ExpectedDataLine)?;
+            out.push_options
+                .push(line.strip_suffix(b"\n").unwrap_or(line).as_bstr().to_owned());
+        }
+    }
+    Ok(out)
+}
+
+fn parse_command(line: &[u8]) -> Result<Command, Error> {
+    let mut tokens = line.splitn(3, |b| *b == b' ');
+    match (tokens.next(), tokens.next(), tokens.next()) {
+        (Some(old_id), Some(new_id), Some(refname)) if !refname.is_empty() => Ok(Command {
+            old_id: parse_id(old_id, line)?,
+            new_id: parse_id(new_id, line)?,
+            refname: refname.into(),
+        }),
+        _ => Err(Error::MalformedCommand { line: line.into() }),
+    }
+}
+
+fn parse_id(hex: &[u8], line: &[u8]) -> Result<gix_hash::ObjectId, Error> {
+    gix_hash::ObjectId::from_hex(hex).map_err(|source| Error::InvalidObjectId {
+        line: line.into(),
+        source,
+    })
+}
@@ -0,0 +1,85 @@
+use std::io;
+
+use bstr::{BStr, BString, ByteVec};
+
+use crate::receive_pack::Report;
+
+/// Write the reference advertisement of a `git-receive-pack` server to `out`, with each of `refs` being the
+/// peeled id along with the full name of a reference, followed by a flush packet.
+///
+/// The `capabilities` are sent along with the first line, and are amended with `object-format` according to `object_hash` and
+/// `agent`, which identifies the server and is passed through [`agent()`](crate::agent()).
+/// If there is no reference, a placeholder line with the name `capabilities^{}` is written instead so the client learns about our capabilities.
+pub fn advertisement<'a>(
+    refs: impl IntoIterator<Item = (gix_hash::ObjectId, &'a BStr)>,
+    capabilities: &[&str],
+    object_hash: gix_hash::Kind,
+    agent: &str,
+    mut out: impl io::Write,
+) -> io::Result<()> {
+    let mut capabilities_line = BString::default();
+    for cap in capabilities {
+        if !capabilities_line.is_empty() {
+            capabilities_line.push_byte(b' ');
+        }
+        capabilities_line.push_str(cap);
+    }
+    capabilities_line.push_str(" object-format=");
+    capabilities_line.push_str(match object_hash {
+        gix_hash::Kind::Sha1 => "sha1",
+        gix_hash::Kind::Sha256 => "sha256",
+    });
+    capabilities_line.push_str(format!(" agent={}", crate::agent(agent)));
+
+    let mut line = BString::default();
+    let mut refs = refs.into_iter().peekable();
+    if refs.peek().is_none() {
+        line.push_str(object_hash.null().to_hex().to_string());
+        line.push_str(" capabilities^{}\0");
+        line.push_str(&capabilities_line);
+        gix_packetline::encode::text_to_write(&line, &mut out)?;
+    }
+    for (idx, (id, name)) in refs.enumerate() {
+        line.clear();
+        line.push_str(id.to_hex().to_string());
+        line.push_byte(b' ');
+        line.push_str(name);
+        if idx == 0 {
+            line.push_byte(0);
+            line.push_str(&capabilities_line);
+        }
+        gix_packetline::encode::text_to_write(&line, &mut out)?;
+    }
+    gix_packetline::encode::flush_to_write(&mut out)?;
+    out.flush()
+}
+
+/// Write `report` to `out` in the format of `report-status` and `report-status-v2`, followed by a flush packet.
+///
+/// Note that we don't produce `option` lines, which makes both formats the same.
+pub fn report(report: &Report, mut out: impl io::Write) -> io::Result<()> {
+    let mut line = BString::from("unpack ");
+    match &report.unpack_error {
+        None => line.push_str("ok"),
+        Some(err) => line.push_str(err),
+    }
+    gix_packetline::encode::text_to_write(&line, &mut out)?;
+    for status in &report.commands {
+        line.clear();
+        match &status.rejection {
+            None => {
+                line.push_str("ok ");
+                line.push_str(&status.refname);
+            }
+            Some(reason) => {
+                line.push_str("ng ");
+                line.push_str(&status.refname);
+                line.push_byte(b' ');
+                line.push_str(reason);
+            }
+        }
+        gix_packetline::encode::text_to_write(&line, &mut out)?;
+    }
+    gix_packetline::encode::flush_to_write(&mut out)?;
+    out.flush()
+}
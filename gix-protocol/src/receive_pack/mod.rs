@@ -0,0 +1,131 @@
+use bstr::{BStr, BString, ByteSlice};
+
+/// The capabilities a `git-receive-pack` server implemented with this module may advertise, next to `agent` and `object-format`
+/// which are added by [`encode::advertisement()`].
+///
+/// Note that `side-band-64k` and `atomic` aren't supported.
+pub const CAPABILITIES: &[&str] = &[
+    "report-status",
+    "report-status-v2",
+    "delete-refs",
+    "quiet",
+    "ofs-delta",
+    "push-options",
+];
+
+/// The kind of change a [`Command`] describes.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum CommandKind {
+    /// The reference doesn't exist yet and is to be created.
+    Create,
+    /// The reference exists and is to be set to another value.
+    Update,
+    /// The reference exists and is to be deleted.
+    Delete,
+}
+
+/// A single reference update as sent by the client, like `<old-id> <new-id> <refname>`.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Command {
+    /// The value the client believes the reference to have, or the null id if it is to be created.
+    pub old_id: gix_hash::ObjectId,
+    /// The value the reference should have after the update, or the null id if it is to be deleted.
+    pub new_id: gix_hash::ObjectId,
+    /// The full name of the reference to update, like `refs/heads/main`.
+    pub refname: BString,
+}
+
+impl Command {
+    /// Return the kind of change this command represents.
+    pub fn kind(&self) -> CommandKind {
+        if self.old_id.is_null() {
+            CommandKind::Create
+        } else if self.new_id.is_null() {
+            CommandKind::Delete
+        } else {
+            CommandKind::Update
+        }
+    }
+}
+
+/// The version of the status report a client asked for.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum ReportStatus {
+    /// The `report-status` capability.
+    V1,
+    /// The `report-status-v2` capability.
+    V2,
+}
+
+/// Everything the client sent before the pack, as [decoded](decode::request()) from packetlines.
+#[derive(Default, PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Request {
+    /// The reference updates the client would like to perform, in order.
+    pub commands: Vec<Command>,
+    /// The capabilities the client selected, as sent on the first command line, like `report-status` or `agent=git/2.0`.
+    pub capabilities: Vec<BString>,
+    /// The ids of the shallow boundary commits of the client, sent as `shallow <id>` lines.
+    pub shallow: Vec<gix_hash::ObjectId>,
+    /// Push options as passed with `git push -o <option>`, only available if the `push-options` capability was selected.
+    pub push_options: Vec<BString>,
+}
+
+impl Request {
+    /// Return `true` if the client selected the capability `name`, with or without a value.
+    pub fn has_capability(&self, name: &str) -> bool {
+        self.capability(name).is_some()
+    }
+
+    /// Return the value of the capability `name` if it was selected by the client, or an empty value if it is a flag.
+    pub fn capability(&self, name: &str) -> Option<&BStr> {
+        self.capabilities.iter().find_map(|cap| {
+            let (cap_name, value) = cap.split_once_str("=").unwrap_or((cap.as_slice(), &[]));
+            (cap_name == name.as_bytes()).then(|| value.as_bstr())
+        })
+    }
+
+    /// Return the version of the status report the client wants to receive, if any.
+    pub fn report_status(&self) -> Option<ReportStatus> {
+        if self.has_capability("report-status-v2") {
+            Some(ReportStatus::V2)
+        } else if self.has_capability("report-status") {
+            Some(ReportStatus::V1)
+        } else {
+            None
+        }
+    }
+
+    /// Return `true` if the client will send a pack, which is the case unless all commands are deletions.
+    pub fn expects_pack(&self) -> bool {
+        self.commands.iter().any(|cmd| cmd.kind() != CommandKind::Delete)
+    }
+}
+
+/// The result of a single [`Command`], to be sent back to the client as part of the [`Report`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommandStatus {
+    /// The name of the reference the command was for.
+    pub refname: BString,
+    /// `None` if the reference was updated, or the reason for rejecting the update, like `non-fast-forward`.
+    pub rejection: Option<BString>,
+}
+
+/// The status report to send to the client after processing all commands if it asked for it.
+#[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Report {
+    /// `None` if the pack was received successfully or if there was none, or an error message otherwise.
+    pub unpack_error: Option<BString>,
+    /// The result of each command, in the order they were received.
+    pub commands: Vec<CommandStatus>,
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod decode;
+///
+#[allow(clippy::empty_docs)]
+pub mod encode;
@@ -0,0 +1,224 @@
+use std::io;
+
+use bstr::{BStr, BString, ByteSlice};
+use gix_packetline::{PacketLineRef, StreamingPeekableIter};
+
+use crate::upload_pack::{Command, Deepen, FetchRequest, LsRefsRequest};
+
+/// The error returned by [`command()`], [`wants()`] and [`haves()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Failed to read from the client")]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    PacketlineDecode(#[from] gix_packetline::decode::Error),
+    #[error("Expected a data line, but got a special packetline")]
+    ExpectedDataLine,
+    #[error("Expected a line like 'command=<name>', got {line:?}")]
+    MissingCommand { line: BString },
+    #[error("The command {command:?} is unknown")]
+    UnknownCommand { command: BString },
+    #[error("Unexpected line {line:?} in the arguments of {command:?}")]
+    UnexpectedArgument { command: &'static str, line: BString },
+    #[error("Could not parse object id in line {line:?}")]
+    InvalidObjectId {
+        line: BString,
+        source: gix_hash::decode::Error,
+    },
+    #[error("Could not parse number in line {line:?}")]
+    InvalidNumber { line: BString },
+}
+
+/// Read the next protocol V2 command of a client from `reader`, or `None` if the client indicated it's done by sending
+/// a flush packet or disconnecting.
+///
+/// Note that `reader` doesn't have to be positioned at a flush packet, as it will be reset before reading.
+pub fn command<T: io::Read>(reader: &mut StreamingPeekableIter<T>) -> Result<Option<Command>, Error> {
+    reader.reset_with(&[PacketLineRef::Flush]);
+    let first = match reader.read_line() {
+        None => return Ok(None),
+        Some(Err(err)) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Some(line) => line??,
+    };
+    let line = trimmed(first.as_bstr().ok_or(Error::ExpectedDataLine)?);
+    let command = line
+        .strip_prefix(b"command=")
+        .ok_or_else(|| Error::MissingCommand { line: line.into() })?;
+    let mut command = match command {
+        b"ls-refs" => Command::LsRefs(LsRefsRequest::default()),
+        b"fetch" => Command::Fetch(FetchRequest::default()),
+        _ => {
+            return Err(Error::UnknownCommand {
+                command: command.into(),
+            })
+        }
+    };
+
+    let mut in_arguments = false;
+    while let Some(line) = reader.read_line() {
+        let line = line??;
+        if line == PacketLineRef::Delimiter {
+            in_arguments = true;
+            continue;
+        }
+        let line = trimmed(line.as_bstr().ok_or(Error::ExpectedDataLine)?);
+        match &mut command {
+            Command::LsRefs(req) if !in_arguments => req.capabilities.push(line.into()),
+            Command::Fetch(req) if !in_arguments => req.capabilities.push(line.into()),
+            Command::LsRefs(req) => {
+                if line == "symrefs" {
+                    req.symrefs = true;
+                } else if line == "peel" {
+                    req.peel = true;
+                } else if line == "unborn" {
+                    req.unborn = true;
+                } else if let Some(prefix) = line.strip_prefix(b"ref-prefix ") {
+                    req.ref_prefixes.push(prefix.into());
+                } else {
+                    return Err(Error::UnexpectedArgument {
+                        command: "ls-refs",
+                        line: line.into(),
+                    });
+                }
+            }
+            Command::Fetch(req) => {
+                if !parse_fetch_argument(line, req)? {
+                    return Err(Error::UnexpectedArgument {
+                        command: "fetch",
+                        line: line.into(),
+                    });
+                }
+            }
+        }
+    }
+    Ok(Some(command))
+}
+
+/// Read the first part of the request of a protocol V0 or V1 client from `reader`, which is expected to be positioned just after the
+/// [ref advertisement](super::encode::advertisement()) was sent.
+///
+/// This reads `want` lines, with the capabilities on the first one, along with `shallow`, `deepen*` and `filter` lines up to the
+/// first flush packet. Afterwards, the [`haves()`] of the client can be read.
+///
+/// If the client doesn't want anything, as is the case for `git ls-remote`, a request without `wants` is returned.
+pub fn wants<T: io::Read>(reader: &mut StreamingPeekableIter<T>) -> Result<FetchRequest, Error> {
+    let mut out = FetchRequest::default();
+    reader.reset_with(&[PacketLineRef::Flush]);
+    while let Some(line) = reader.read_line() {
+        let line = match line {
+            Ok(line) => line?,
+            Err(err) if out.wants.is_empty() && err.kind() == io::ErrorKind::UnexpectedEof => return Ok(out),
+            Err(err) => return Err(err.into()),
+        };
+        let line = trimmed(line.as_bstr().ok_or(Error::ExpectedDataLine)?);
+        if let Some(rest) = line.strip_prefix(b"want ") {
+            let mut tokens = rest.split(|b| *b == b' ');
+            out.wants.push(parse_id(tokens.next().unwrap_or_default(), line)?);
+            if out.wants.len() == 1 {
+                out.capabilities = tokens.filter(|cap| !cap.is_empty()).map(Into::into).collect();
+                for (cap, flag) in [
+                    ("thin-pack", &mut out.thin_pack),
+                    ("no-progress", &mut out.no_progress),
+                    ("include-tag", &mut out.include_tag),
+                    ("ofs-delta", &mut out.ofs_delta),
+                ] {
+                    *flag = out.capabilities.iter().any(|c| c == cap);
+                }
+            }
+        } else if line.starts_with(b"have ") || line == "done" || !parse_fetch_argument(line, &mut out)? {
+            return Err(Error::UnexpectedArgument {
+                command: "upload-pack",
+                line: line.into(),
+            });
+        }
+    }
+    Ok(out)
+}
+
+/// The `have` lines of a single negotiation round of a protocol V0 or V1 client, as returned by [`haves()`].
+#[derive(Default, PartialEq, Eq, Debug, Clone)]
+pub struct Haves {
+    /// The objects the client has.
+    pub ids: Vec<gix_hash::ObjectId>,
+    /// If `true`, the client sent `done` instead of a flush packet to conclude the negotiation.
+    pub done: bool,
+}
+
+/// Read the `have` lines of the next negotiation round of a protocol V0 or V1 client from `reader`, which ends with
+/// a flush packet or with `done`, or return `None` if the client disconnected.
+pub fn haves<T: io::Read>(reader: &mut StreamingPeekableIter<T>) -> Result<Option<Haves>, Error> {
+    let mut out = Haves::default();
+    reader.reset_with(&[PacketLineRef::Flush]);
+    let mut saw_line = false;
+    while let Some(line) = reader.read_line() {
+        let line = match line {
+            Ok(line) => line?,
+            Err(err) if !saw_line && err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        saw_line = true;
+        let line = trimmed(line.as_bstr().ok_or(Error::ExpectedDataLine)?);
+        if let Some(id) = line.strip_prefix(b"have ") {
+            out.ids.push(parse_id(id, line)?);
+        } else if line == "done" {
+            out.done = true;
+            break;
+        } else {
+            return Err(Error::UnexpectedArgument {
+                command: "upload-pack",
+                line: line.into(),
+            });
+        }
+    }
+    Ok(Some(out))
+}
+
+/// Parse a single argument of the `fetch` command into `req`, and return `false` if it is unknown.
+fn parse_fetch_argument(line: &BStr, req: &mut FetchRequest) -> Result<bool, Error> {
+    let (name, value) = line
+        .split_once_str(" ")
+        .map_or((line.as_bytes(), None), |(name, value)| (name, Some(value)));
+    match (name, value) {
+        (b"want", Some(id)) => req.wants.push(parse_id(id, line)?),
+        (b"want-ref", Some(name)) => req.want_refs.push(name.into()),
+        (b"have", Some(id)) => req.haves.push(parse_id(id, line)?),
+        (b"shallow", Some(id)) => req.shallow.push(parse_id(id, line)?),
+        (b"done", None) => req.done = true,
+        (b"thin-pack", None) => req.thin_pack = true,
+        (b"no-progress", None) => req.no_progress = true,
+        (b"include-tag", None) => req.include_tag = true,
+        (b"ofs-delta", None) => req.ofs_delta = true,
+        (b"sideband-all", None) => req.sideband_all = true,
+        (b"deepen", Some(depth)) => {
+            req.deepen.get_or_insert_with(Default::default).depth = Some(parse_number(depth, line)?)
+        }
+        (b"deepen-relative", None) => req.deepen.get_or_insert_with(Default::default).relative = true,
+        (b"deepen-since", Some(since)) => {
+            req.deepen.get_or_insert_with(Default::default).since = Some(parse_number(since, line)?)
+        }
+        (b"deepen-not", Some(name)) => req.deepen.get_or_insert_with(Deepen::default).not.push(name.into()),
+        (b"filter", Some(spec)) => req.filter = Some(spec.into()),
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+fn trimmed(line: &[u8]) -> &BStr {
+    line.strip_suffix(b"\n").unwrap_or(line).as_bstr()
+}
+
+fn parse_id(hex: &[u8], line: &[u8]) -> Result<gix_hash::ObjectId, Error> {
+    gix_hash::ObjectId::from_hex(hex).map_err(|source| Error::InvalidObjectId {
+        line: line.into(),
+        source,
+    })
+}
+
+fn parse_number<N: std::str::FromStr>(number: &[u8], line: &[u8]) -> Result<N, Error> {
+    number
+        .to_str()
+        .ok()
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(|| Error::InvalidNumber { line: line.into() })
+}
@@ -0,0 +1,273 @@
+use std::io;
+
+use bstr::{BString, ByteVec};
+use gix_packetline::Channel;
+
+use crate::upload_pack::{LsRefsRequest, Ref};
+
+/// Write the protocol V0 and V1 reference advertisement of a `git-upload-pack` server to `out`, followed by a flush packet.
+///
+/// Each of `refs` is written with the id it points to, followed by a line with its peeled id if it is an annotated tag.
+/// Unborn references aren't advertised, but symbolic references are communicated with `symref=<name>:<target>` capabilities.
+/// The `capabilities` are sent along with the first line, and are amended with `object-format` according to `object_hash` and
+/// `agent`, which identifies the server and is passed through [`agent()`](crate::agent()).
+/// If there is no reference, a placeholder line with the name `capabilities^{}` is written instead so the client learns about our capabilities.
+pub fn advertisement(
+    refs: &[Ref],
+    capabilities: &[&str],
+    object_hash: gix_hash::Kind,
+    agent: &str,
+    mut out: impl io::Write,
+) -> io::Result<()> {
+    let mut capabilities_line = BString::default();
+    for cap in capabilities {
+        capabilities_line.push_str(cap);
+        capabilities_line.push_byte(b' ');
+    }
+    for r in refs.iter().filter(|r| r.id.is_some()) {
+        if let Some(target) = &r.symref_target {
+            capabilities_line.push_str("symref=");
+            capabilities_line.push_str(&r.name);
+            capabilities_line.push_byte(b':');
+            capabilities_line.push_str(target);
+            capabilities_line.push_byte(b' ');
+        }
+    }
+    capabilities_line.push_str("object-format=");
+    capabilities_line.push_str(object_format(object_hash));
+    capabilities_line.push_str(format!(" agent={}", crate::agent(agent)));
+
+    let mut line = BString::default();
+    let mut is_first = true;
+    for r in refs {
+        let Some(id) = r.id else { continue };
+        line.clear();
+        line.push_str(id.to_hex().to_string());
+        line.push_byte(b' ');
+        line.push_str(&r.name);
+        if is_first {
+            line.push_byte(0);
+            line.push_str(&capabilities_line);
+            is_first = false;
+        }
+        gix_packetline::encode::text_to_write(&line, &mut out)?;
+        if let Some(peeled) = r.peeled {
+            line.clear();
+            line.push_str(peeled.to_hex().to_string());
+            line.push_byte(b' ');
+            line.push_str(&r.name);
+            line.push_str("^{}");
+            gix_packetline::encode::text_to_write(&line, &mut out)?;
+        }
+    }
+    if is_first {
+        line.clear();
+        line.push_str(object_hash.null().to_hex().to_string());
+        line.push_str(" capabilities^{}\0");
+        line.push_str(&capabilities_line);
+        gix_packetline::encode::text_to_write(&line, &mut out)?;
+    }
+    gix_packetline::encode::flush_to_write(&mut out)?;
+    out.flush()
+}
+
+/// Write the protocol V2 capability advertisement of a `git-upload-pack` server to `out`, followed by a flush packet.
+///
+/// `capabilities` are written one per line, like `ls-refs=unborn` or `fetch=filter`, after `version 2` and `agent`,
+/// which identifies the server and is passed through [`agent()`](crate::agent()), and before the `object-format` matching `object_hash`.
+pub fn capabilities_v2(
+    capabilities: &[&str],
+    object_hash: gix_hash::Kind,
+    agent: &str,
+    mut out: impl io::Write,
+) -> io::Result<()> {
+    gix_packetline::encode::text_to_write(b"version 2", &mut out)?;
+    gix_packetline::encode::text_to_write(format!("agent={}", crate::agent(agent)).as_bytes(), &mut out)?;
+    for cap in capabilities {
+        gix_packetline::encode::text_to_write(cap.as_bytes(), &mut out)?;
+    }
+    gix_packetline::encode::text_to_write(
+        format!("object-format={}", object_format(object_hash)).as_bytes(),
+        &mut out,
+    )?;
+    gix_packetline::encode::flush_to_write(&mut out)?;
+    out.flush()
+}
+
+/// Write the response to the protocol V2 `ls-refs` command to `out` as configured by `request`, followed by a flush packet.
+///
+/// Only those of `refs` that [match](LsRefsRequest::matches()) the ref-prefixes of `request` are written,
+/// and unborn references are only written if requested.
+pub fn ls_refs(refs: &[Ref], request: &LsRefsRequest, mut out: impl io::Write) -> io::Result<()> {
+    let mut line = BString::default();
+    for r in refs.iter().filter(|r| request.matches(r.name.as_ref())) {
+        line.clear();
+        match r.id {
+            Some(id) => line.push_str(id.to_hex().to_string()),
+            None if request.unborn && r.symref_target.is_some() => line.push_str("unborn"),
+            None => continue,
+        }
+        line.push_byte(b' ');
+        line.push_str(&r.name);
+        if let Some(target) = r.symref_target.as_ref().filter(|_| request.symrefs || r.id.is_none()) {
+            line.push_str(" symref-target:");
+            line.push_str(target);
+        }
+        if let Some(peeled) = r.peeled.filter(|_| request.peel) {
+            line.push_str(" peeled:");
+            line.push_str(peeled.to_hex().to_string());
+        }
+        gix_packetline::encode::text_to_write(&line, &mut out)?;
+    }
+    gix_packetline::encode::flush_to_write(&mut out)?;
+    out.flush()
+}
+
+/// Write the `acknowledgments` section of the response to the protocol V2 `fetch` command to `out`, with an `ACK` for each of
+/// the `common` objects or a `NAK` if there is none.
+///
+/// If `ready` is `true`, the `packfile` section has to follow as the section is terminated with a delimiter packet,
+/// otherwise a flush packet ends the response and the client is expected to continue the negotiation.
+pub fn acknowledgments(common: &[gix_hash::ObjectId], ready: bool, mut out: impl io::Write) -> io::Result<()> {
+    gix_packetline::encode::text_to_write(b"acknowledgments", &mut out)?;
+    if common.is_empty() {
+        gix_packetline::encode::text_to_write(b"NAK", &mut out)?;
+    }
+    for id in common {
+        gix_packetline::encode::text_to_write(format!("ACK {id}").as_bytes(), &mut out)?;
+    }
+    if ready {
+        gix_packetline::encode::text_to_write(b"ready", &mut out)?;
+        gix_packetline::encode::delim_to_write(&mut out)?;
+    } else {
+        gix_packetline::encode::flush_to_write(&mut out)?;
+    }
+    out.flush()
+}
+
+/// Write the `wanted-refs` section of the response to the protocol V2 `fetch` command to `out`, which tells the client
+/// which object each of the references it passed as `want-ref` resolved to.
+///
+/// It is terminated with a delimiter packet as the `packfile` section has to follow.
+pub fn wanted_refs(refs: &[(gix_hash::ObjectId, BString)], mut out: impl io::Write) -> io::Result<()> {
+    gix_packetline::encode::text_to_write(b"wanted-refs", &mut out)?;
+    for (id, name) in refs {
+        let mut line = BString::from(id.to_hex().to_string());
+        line.push_byte(b' ');
+        line.push_str(name);
+        gix_packetline::encode::text_to_write(&line, &mut out)?;
+    }
+    gix_packetline::encode::delim_to_write(&mut out)?;
+    out.flush()
+}
+
+/// Write an `ACK <id>` line to `out`, as sent by a protocol V0 or V1 server for the first object it has in common with the client.
+pub fn ack(id: &gix_hash::oid, mut out: impl io::Write) -> io::Result<()> {
+    gix_packetline::encode::text_to_write(format!("ACK {id}").as_bytes(), &mut out)?;
+    out.flush()
+}
+
+/// Write a `NAK` line to `out`, as sent by a protocol V0 or V1 server if it has no object in common with the client.
+pub fn nak(mut out: impl io::Write) -> io::Result<()> {
+    gix_packetline::encode::text_to_write(b"NAK", &mut out)?;
+    out.flush()
+}
+
+/// Write the header of the `packfile` section of the response to the protocol V2 `fetch` command to `out`,
+/// which is followed by the pack written through a [`Sideband::large()`] writer.
+pub fn packfile_section(mut out: impl io::Write) -> io::Result<()> {
+    gix_packetline::encode::text_to_write(b"packfile", &mut out).map(|_| ())
+}
+
+/// Write an `ERR <message>` packet to `out` to tell the client why its request can't be served.
+pub fn error(message: &str, mut out: impl io::Write) -> io::Result<()> {
+    gix_packetline::encode::error_to_write(message.as_bytes(), &mut out)?;
+    out.flush()
+}
+
+/// A writer for the pack sent to the client, which multiplexes it with progress messages if side-bands are used.
+///
+/// Call [`finish()`](Sideband::finish()) once the pack was written to write a flush packet if side-bands are used.
+pub struct Sideband<W> {
+    inner: W,
+    max_data_len: Option<usize>,
+}
+
+impl<W: io::Write> Sideband<W> {
+    /// Write the pack to `inner` as is, without side-bands, which also means progress can't be sent.
+    pub fn plain(inner: W) -> Self {
+        Sideband {
+            inner,
+            max_data_len: None,
+        }
+    }
+
+    /// Multiplex the pack and progress using the `side-band-64k` capability, as is always the case in protocol V2.
+    pub fn large(inner: W) -> Self {
+        Sideband {
+            inner,
+            max_data_len: Some(65515),
+        }
+    }
+
+    /// Multiplex the pack and progress using the `side-band` capability, with its limit of 1000 bytes per packet.
+    pub fn small(inner: W) -> Self {
+        Sideband {
+            inner,
+            max_data_len: Some(999),
+        }
+    }
+
+    /// Return `true` if side-bands are used and [`progress()`](Self::progress()) can be communicated.
+    pub fn is_multiplexed(&self) -> bool {
+        self.max_data_len.is_some()
+    }
+
+    /// Send the progress `message` to the client if side-bands are used, or do nothing otherwise.
+    pub fn progress(&mut self, message: &[u8]) -> io::Result<()> {
+        self.write_band(Channel::Progress, message)
+    }
+
+    /// Send the error `message` to the client if side-bands are used, or do nothing otherwise.
+    pub fn error(&mut self, message: &[u8]) -> io::Result<()> {
+        self.write_band(Channel::Error, message)
+    }
+
+    /// Write a flush packet if side-bands are used and return the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.is_multiplexed() {
+            gix_packetline::encode::flush_to_write(&mut self.inner)?;
+        }
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    fn write_band(&mut self, channel: Channel, data: &[u8]) -> io::Result<()> {
+        let Some(max_data_len) = self.max_data_len else {
+            return Ok(());
+        };
+        gix_packetline::encode::bands_to_write(channel, data, max_data_len, &mut self.inner).map(|_| ())
+    }
+}
+
+impl<W: io::Write> io::Write for Sideband<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.is_multiplexed() {
+            self.write_band(Channel::Data, buf)?;
+            Ok(buf.len())
+        } else {
+            self.inner.write(buf)
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn object_format(object_hash: gix_hash::Kind) -> &'static str {
+    match object_hash {
+        gix_hash::Kind::Sha1 => "sha1",
+        gix_hash::Kind::Sha256 => "sha256",
+    }
+}
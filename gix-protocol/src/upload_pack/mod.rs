@@ -0,0 +1,126 @@
+use bstr::{BStr, BString, ByteSlice};
+
+/// The capabilities a `git-upload-pack` server implemented with this module may advertise in protocol V0 and V1,
+/// next to `agent`, `object-format` and `symref` which are added by [`encode::advertisement()`].
+///
+/// Note that `multi_ack`, `multi_ack_detailed`, `shallow` and `deepen-*` aren't supported.
+pub const CAPABILITIES: &[&str] = &[
+    "thin-pack",
+    "side-band",
+    "side-band-64k",
+    "ofs-delta",
+    "no-progress",
+    "include-tag",
+];
+
+/// A reference as advertised by a `git-upload-pack` server.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ref {
+    /// The full name of the reference, like `refs/heads/main` or `HEAD`.
+    pub name: BString,
+    /// The id the reference points to after following symbolic references, or `None` if it is an unborn symbolic reference.
+    pub id: Option<gix_hash::ObjectId>,
+    /// The object the reference ultimately points to after peeling annotated tags, if it's different from `id`.
+    pub peeled: Option<gix_hash::ObjectId>,
+    /// The name of the reference this one points to if it is a symbolic reference.
+    pub symref_target: Option<BString>,
+}
+
+/// The arguments of the protocol V2 `ls-refs` command.
+#[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LsRefsRequest {
+    /// The capabilities sent along with the command, like `agent=git/2.0` or `object-format=sha1`.
+    pub capabilities: Vec<BString>,
+    /// If `true`, the targets of symbolic references should be listed.
+    pub symrefs: bool,
+    /// If `true`, the objects annotated tags point to should be listed.
+    pub peel: bool,
+    /// If `true`, symbolic references which point to a reference that doesn't exist yet should be listed.
+    pub unborn: bool,
+    /// If not empty, only references starting with one of these prefixes should be listed.
+    pub ref_prefixes: Vec<BString>,
+}
+
+impl LsRefsRequest {
+    /// Return `true` if the reference `name` should be listed according to [`ref_prefixes`](Self::ref_prefixes).
+    pub fn matches(&self, name: &BStr) -> bool {
+        self.ref_prefixes.is_empty() || self.ref_prefixes.iter().any(|prefix| name.starts_with(prefix))
+    }
+}
+
+/// Arguments to make the pack shallow, with history limited in different ways.
+#[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Deepen {
+    /// Only send commits up to this depth from the tips, as passed with `deepen <depth>`.
+    pub depth: Option<u32>,
+    /// If `true`, `depth` is relative to the current shallow boundary of the client, as passed with `deepen-relative`.
+    pub relative: bool,
+    /// Only send commits that are newer than this time, as passed with `deepen-since <seconds>`.
+    pub since: Option<gix_date::SecondsSinceUnixEpoch>,
+    /// Don't send commits reachable from these references, as passed with `deepen-not <ref>`.
+    pub not: Vec<BString>,
+}
+
+/// Everything a client sends to obtain a pack, as [decoded](decode::command()) from the protocol V2 `fetch` command or
+/// from the [wants](decode::wants()) and [haves](decode::haves()) of protocol V0 and V1.
+#[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FetchRequest {
+    /// In V2, the capabilities sent along with the command, or in V0 and V1, the capabilities on the first `want` line.
+    pub capabilities: Vec<BString>,
+    /// The objects the client wants.
+    pub wants: Vec<gix_hash::ObjectId>,
+    /// The full names of references the client wants, which are resolved by the server. Only available in V2.
+    pub want_refs: Vec<BString>,
+    /// The objects the client has, to let the server find common commits.
+    pub haves: Vec<gix_hash::ObjectId>,
+    /// If `true`, the client is done with negotiation and the server should send the pack.
+    pub done: bool,
+    /// If `true`, the pack may contain deltas against objects that aren't in the pack, but which the client has.
+    pub thin_pack: bool,
+    /// If `true`, no progress should be sent.
+    pub no_progress: bool,
+    /// If `true`, annotated tags pointing to objects in the pack should be sent as well.
+    pub include_tag: bool,
+    /// If `true`, the client understands deltas with offsets to their base object.
+    pub ofs_delta: bool,
+    /// If `true`, all sections of the response should be multiplexed. Only available in V2.
+    pub sideband_all: bool,
+    /// The commits the client has without their parents, making it a shallow repository.
+    pub shallow: Vec<gix_hash::ObjectId>,
+    /// How the history of the pack should be limited, if at all.
+    pub deepen: Option<Deepen>,
+    /// The object filter specification, like `blob:none`, to receive a partial pack.
+    pub filter: Option<BString>,
+}
+
+impl FetchRequest {
+    /// Return `true` if the client selected the capability `name`, with or without a value.
+    pub fn has_capability(&self, name: &str) -> bool {
+        self.capabilities.iter().any(|cap| {
+            cap.split_once_str("=")
+                .map_or(cap.as_slice(), |(cap_name, _value)| cap_name)
+                == name.as_bytes()
+        })
+    }
+}
+
+/// A command sent by the client in protocol V2.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Command {
+    /// The client wants to list references.
+    LsRefs(LsRefsRequest),
+    /// The client wants to negotiate and receive a pack.
+    Fetch(FetchRequest),
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod decode;
+///
+#[allow(clippy::empty_docs)]
+pub mod encode;
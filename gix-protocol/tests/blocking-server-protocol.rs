@@ -0,0 +1,4 @@
+type Result = std::result::Result<(), Box<dyn std::error::Error>>;
+
+mod receive_pack;
+mod upload_pack;
@@ -0,0 +1,198 @@
+use bstr::ByteSlice;
+use gix_hash::ObjectId;
+use gix_packetline::{PacketLineRef, StreamingPeekableIter};
+use gix_protocol::receive_pack::{decode, encode, Command, CommandKind, CommandStatus, Report, ReportStatus};
+
+fn id(hex: &str) -> ObjectId {
+    ObjectId::from_hex(hex.as_bytes()).expect("valid hex")
+}
+
+const A: &str = "1111111111111111111111111111111111111111";
+const B: &str = "2222222222222222222222222222222222222222";
+const NULL: &str = "0000000000000000000000000000000000000000";
+
+fn packetlines(lines: &[Option<&str>]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for line in lines {
+        match line {
+            Some(line) => gix_packetline::encode::data_to_write(line.as_bytes(), &mut out),
+            None => gix_packetline::encode::flush_to_write(&mut out),
+        }
+        .expect("in-memory writes always work");
+    }
+    out
+}
+
+mod request {
+    use super::*;
+
+    #[test]
+    fn commands_with_capabilities_shallow_lines_and_push_options_followed_by_a_pack() -> crate::Result {
+        let mut input = packetlines(&[
+            Some(&format!("shallow {A}")),
+            Some(&format!(
+                "{A} {B} refs/heads/main\0 report-status-v2 side-band-64k push-options agent=git/2.45.0\n"
+            )),
+            Some(&format!("{NULL} {B} refs/heads/new\n")),
+            Some(&format!("{B} {NULL} refs/tags/gone")),
+            None,
+            Some("ci.skip\n"),
+            Some("key=value"),
+            None,
+        ]);
+        input.extend_from_slice(b"PACK");
+
+        let mut reader = StreamingPeekableIter::new(input.as_slice(), &[PacketLineRef::Flush], false);
+        let request = decode::request(&mut reader)?;
+        assert_eq!(request.shallow, vec![id(A)]);
+        assert_eq!(
+            request.commands,
+            vec![
+                Command {
+                    old_id: id(A),
+                    new_id: id(B),
+                    refname: "refs/heads/main".into()
+                },
+                Command {
+                    old_id: id(NULL),
+                    new_id: id(B),
+                    refname: "refs/heads/new".into()
+                },
+                Command {
+                    old_id: id(B),
+                    new_id: id(NULL),
+                    refname: "refs/tags/gone".into()
+                }
+            ]
+        );
+        assert_eq!(
+            request.commands.iter().map(Command::kind).collect::<Vec<_>>(),
+            [CommandKind::Update, CommandKind::Create, CommandKind::Delete]
+        );
+        assert_eq!(
+            request.capabilities,
+            ["report-status-v2", "side-band-64k", "push-options", "agent=git/2.45.0"]
+        );
+        assert_eq!(request.capability("agent").map(|v| v.to_str_lossy()), Some("git/2.45.0".into()));
+        assert!(request.has_capability("side-band-64k"));
+        assert!(!request.has_capability("side-band"));
+        assert_eq!(request.report_status(), Some(ReportStatus::V2));
+        assert_eq!(request.push_options, ["ci.skip", "key=value"]);
+        assert!(request.expects_pack());
+
+        assert_eq!(reader.into_inner(), b"PACK", "the reader is positioned right at the pack");
+        Ok(())
+    }
+
+    #[test]
+    fn deletions_only_without_push_options() -> crate::Result {
+        let input = packetlines(&[Some(&format!("{A} {NULL} refs/heads/main\0report-status")), None]);
+        let mut reader = StreamingPeekableIter::new(input.as_slice(), &[PacketLineRef::Flush], false);
+        let request = decode::request(&mut reader)?;
+        assert_eq!(request.commands.len(), 1);
+        assert_eq!(request.report_status(), Some(ReportStatus::V1));
+        assert!(request.push_options.is_empty());
+        assert!(!request.expects_pack(), "deletions don't need a pack");
+        Ok(())
+    }
+
+    #[test]
+    fn no_commands_at_all() -> crate::Result {
+        for input in [packetlines(&[None]), Vec::new()] {
+            let mut reader = StreamingPeekableIter::new(input.as_slice(), &[PacketLineRef::Flush], false);
+            let request = decode::request(&mut reader)?;
+            assert!(request.commands.is_empty(), "a flush or EOF means the client is done");
+            assert_eq!(request.report_status(), None);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_commands() {
+        for line in [
+            format!("{A} {B}"),
+            format!("{A} {B} "),
+            format!("{A} refs/heads/main"),
+            "shallow abc".to_string(),
+        ] {
+            let input = packetlines(&[Some(&line), None]);
+            let mut reader = StreamingPeekableIter::new(input.as_slice(), &[PacketLineRef::Flush], false);
+            assert!(decode::request(&mut reader).is_err(), "{line:?} is invalid");
+        }
+    }
+}
+
+mod response {
+    use super::*;
+
+    #[test]
+    fn advertisement_with_refs() -> crate::Result {
+        let mut out = Vec::new();
+        encode::advertisement(
+            [
+                (id(A), "refs/heads/main".into()),
+                (id(B), "refs/tags/v1".into()),
+            ],
+            &["report-status", "delete-refs"],
+            gix_hash::Kind::Sha1,
+            "gix-server",
+            &mut out,
+        )?;
+        assert_eq!(
+            out.as_bstr(),
+            format!(
+                "007f{A} refs/heads/main\0report-status delete-refs object-format=sha1 agent=git/gix-server\n\
+                 003a{B} refs/tags/v1\n\
+                 0000"
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn advertisement_without_refs() -> crate::Result {
+        let mut out = Vec::new();
+        encode::advertisement(None, &["report-status"], gix_hash::Kind::Sha1, "git/x", &mut out)?;
+        assert_eq!(
+            out.as_bstr(),
+            format!("006a{NULL} capabilities^{{}}\0report-status object-format=sha1 agent=git/x\n0000")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn report() -> crate::Result {
+        let mut out = Vec::new();
+        encode::report(
+            &Report {
+                unpack_error: None,
+                commands: vec![
+                    CommandStatus {
+                        refname: "refs/heads/main".into(),
+                        rejection: None,
+                    },
+                    CommandStatus {
+                        refname: "refs/heads/other".into(),
+                        rejection: Some("non-fast-forward".into()),
+                    },
+                ],
+            },
+            &mut out,
+        )?;
+        assert_eq!(
+            out.as_bstr(),
+            "000eunpack ok\n0017ok refs/heads/main\n0029ng refs/heads/other non-fast-forward\n0000"
+        );
+
+        out.clear();
+        encode::report(
+            &Report {
+                unpack_error: Some("index-pack failed".into()),
+                commands: vec![],
+            },
+            &mut out,
+        )?;
+        assert_eq!(out.as_bstr(), "001dunpack index-pack failed\n0000");
+        Ok(())
+    }
+}
@@ -0,0 +1,380 @@
+use bstr::ByteSlice;
+use gix_hash::ObjectId;
+use gix_packetline::{PacketLineRef, StreamingPeekableIter};
+use gix_protocol::upload_pack::{decode, encode, Command, Deepen, FetchRequest, LsRefsRequest, Ref};
+
+fn id(hex: &str) -> ObjectId {
+    ObjectId::from_hex(hex.as_bytes()).expect("valid hex")
+}
+
+const A: &str = "1111111111111111111111111111111111111111";
+const B: &str = "2222222222222222222222222222222222222222";
+const C: &str = "3333333333333333333333333333333333333333";
+
+/// Encode `lines` as packetlines, with `0000` and `0001` producing flush and delimiter packets.
+fn packetlines(lines: &[&str]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for line in lines {
+        match *line {
+            "0000" => gix_packetline::encode::flush_to_write(&mut out),
+            "0001" => gix_packetline::encode::delim_to_write(&mut out),
+            line => gix_packetline::encode::data_to_write(line.as_bytes(), &mut out),
+        }
+        .expect("in-memory writes always work");
+    }
+    out
+}
+
+fn reader(input: &[u8]) -> StreamingPeekableIter<&[u8]> {
+    StreamingPeekableIter::new(input, &[PacketLineRef::Flush], false)
+}
+
+mod request {
+    use super::*;
+
+    #[test]
+    fn v2_commands_until_the_client_is_done() -> crate::Result {
+        let input = packetlines(&[
+            "command=ls-refs\n",
+            "agent=git/2.45.0\n",
+            "object-format=sha1\n",
+            "0001",
+            "peel\n",
+            "symrefs\n",
+            "unborn\n",
+            "ref-prefix HEAD\n",
+            "ref-prefix refs/heads/\n",
+            "0000",
+            "command=fetch\n",
+            "agent=git/2.45.0\n",
+            "0001",
+            &format!("want {A}\n"),
+            &format!("want {B}\n"),
+            "want-ref refs/heads/main\n",
+            &format!("have {C}\n"),
+            &format!("shallow {C}\n"),
+            "thin-pack\n",
+            "no-progress\n",
+            "include-tag\n",
+            "ofs-delta\n",
+            "sideband-all\n",
+            "deepen 3\n",
+            "deepen-relative\n",
+            "deepen-since 1234\n",
+            "deepen-not refs/tags/v1\n",
+            "filter blob:none\n",
+            "done\n",
+            "0000",
+            "0000",
+        ]);
+        let mut reader = reader(&input);
+        assert_eq!(
+            decode::command(&mut reader)?,
+            Some(Command::LsRefs(LsRefsRequest {
+                capabilities: vec!["agent=git/2.45.0".into(), "object-format=sha1".into()],
+                symrefs: true,
+                peel: true,
+                unborn: true,
+                ref_prefixes: vec!["HEAD".into(), "refs/heads/".into()],
+            }))
+        );
+        let Some(Command::Fetch(fetch)) = decode::command(&mut reader)? else {
+            unreachable!("fetch follows")
+        };
+        assert_eq!(
+            fetch,
+            FetchRequest {
+                capabilities: vec!["agent=git/2.45.0".into()],
+                wants: vec![id(A), id(B)],
+                want_refs: vec!["refs/heads/main".into()],
+                haves: vec![id(C)],
+                done: true,
+                thin_pack: true,
+                no_progress: true,
+                include_tag: true,
+                ofs_delta: true,
+                sideband_all: true,
+                shallow: vec![id(C)],
+                deepen: Some(Deepen {
+                    depth: Some(3),
+                    relative: true,
+                    since: Some(1234),
+                    not: vec!["refs/tags/v1".into()],
+                }),
+                filter: Some("blob:none".into()),
+            }
+        );
+        assert!(fetch.has_capability("agent"));
+        assert!(!fetch.has_capability("object-format"));
+        assert_eq!(decode::command(&mut reader)?, None, "a flush packet ends the session");
+        assert_eq!(decode::command(&mut reader)?, None, "the client may also disconnect");
+        Ok(())
+    }
+
+    #[test]
+    fn v2_invalid_commands_and_arguments() {
+        for (lines, expected) in [
+            (
+                &["command=push\n", "0001", "0000"][..],
+                "The command \"push\" is unknown",
+            ),
+            (
+                &["want-refs\n", "0000"],
+                "Expected a line like 'command=<name>', got \"want-refs\"",
+            ),
+            (
+                &["command=ls-refs\n", "0001", "unknown\n", "0000"],
+                "Unexpected line \"unknown\" in the arguments of \"ls-refs\"",
+            ),
+            (
+                &["command=fetch\n", "0001", "have 123\n", "0000"],
+                "Could not parse object id in line \"have 123\"",
+            ),
+            (
+                &["command=fetch\n", "0001", "deepen many\n", "0000"],
+                "Could not parse number in line \"deepen many\"",
+            ),
+        ] {
+            let input = packetlines(lines);
+            assert_eq!(decode::command(&mut reader(&input)).unwrap_err().to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn v0_wants_with_capabilities_followed_by_rounds_of_haves() -> crate::Result {
+        let input = packetlines(&[
+            &format!("want {A} multi_ack side-band-64k thin-pack ofs-delta agent=git/2.45.0\n"),
+            &format!("want {B}\n"),
+            "filter blob:none\n",
+            "0000",
+            &format!("have {C}\n"),
+            &format!("have {A}\n"),
+            "0000",
+            &format!("have {B}\n"),
+            "done\n",
+        ]);
+        let mut reader = reader(&input);
+        let request = decode::wants(&mut reader)?;
+        assert_eq!(request.wants, [id(A), id(B)]);
+        assert_eq!(
+            request.capabilities,
+            [
+                "multi_ack",
+                "side-band-64k",
+                "thin-pack",
+                "ofs-delta",
+                "agent=git/2.45.0"
+            ]
+        );
+        assert!(request.has_capability("side-band-64k"));
+        assert!(request.thin_pack && request.ofs_delta);
+        assert!(!request.no_progress && !request.include_tag);
+        assert_eq!(request.filter.as_ref().map(|f| f.as_bstr()), Some("blob:none".into()));
+
+        assert_eq!(
+            decode::haves(&mut reader)?,
+            Some(decode::Haves {
+                ids: vec![id(C), id(A)],
+                done: false
+            })
+        );
+        assert_eq!(
+            decode::haves(&mut reader)?,
+            Some(decode::Haves {
+                ids: vec![id(B)],
+                done: true
+            })
+        );
+        assert_eq!(decode::haves(&mut reader)?, None, "the client disconnected");
+        Ok(())
+    }
+
+    #[test]
+    fn v0_without_wants() -> crate::Result {
+        for input in [packetlines(&["0000"]), Vec::new()] {
+            let request = decode::wants(&mut reader(&input))?;
+            assert!(request.wants.is_empty());
+        }
+
+        let input = packetlines(&[&format!("have {A}\n"), "0000"]);
+        assert_eq!(
+            decode::wants(&mut reader(&input)).unwrap_err().to_string(),
+            format!("Unexpected line \"have {A}\" in the arguments of \"upload-pack\"")
+        );
+        Ok(())
+    }
+}
+
+mod response {
+    use super::*;
+
+    fn refs() -> Vec<Ref> {
+        vec![
+            Ref {
+                name: "HEAD".into(),
+                id: Some(id(A)),
+                peeled: None,
+                symref_target: Some("refs/heads/main".into()),
+            },
+            Ref {
+                name: "refs/heads/main".into(),
+                id: Some(id(A)),
+                peeled: None,
+                symref_target: None,
+            },
+            Ref {
+                name: "refs/tags/v1".into(),
+                id: Some(id(B)),
+                peeled: Some(id(A)),
+                symref_target: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn v0_advertisement() -> crate::Result {
+        let mut out = Vec::new();
+        encode::advertisement(
+            &refs(),
+            &["thin-pack", "ofs-delta"],
+            gix_hash::Kind::Sha1,
+            "oxide-test",
+            &mut out,
+        )?;
+        assert_eq!(
+            out.as_bstr(),
+            format!(
+                "008a{A} HEAD\0thin-pack ofs-delta symref=HEAD:refs/heads/main object-format=sha1 agent=git/oxide-test\n\
+                 003d{A} refs/heads/main\n\
+                 003a{B} refs/tags/v1\n\
+                 003d{A} refs/tags/v1^{{}}\n\
+                 0000"
+            )
+        );
+
+        out.clear();
+        let unborn = Ref {
+            name: "HEAD".into(),
+            id: None,
+            peeled: None,
+            symref_target: Some("refs/heads/main".into()),
+        };
+        encode::advertisement(&[unborn], &[], gix_hash::Kind::Sha1, "oxide-test", &mut out)?;
+        assert_eq!(
+            out.as_bstr(),
+            format!(
+                "0065{} capabilities^{{}}\0object-format=sha1 agent=git/oxide-test\n0000",
+                gix_hash::Kind::Sha1.null()
+            ),
+            "unborn references aren't advertised"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn v2_capabilities_and_ls_refs() -> crate::Result {
+        let mut out = Vec::new();
+        encode::capabilities_v2(
+            &["ls-refs=unborn", "fetch"],
+            gix_hash::Kind::Sha1,
+            "oxide-test",
+            &mut out,
+        )?;
+        assert_eq!(
+            out.as_bstr(),
+            "000eversion 2\n0019agent=git/oxide-test\n0013ls-refs=unborn\n000afetch\n0017object-format=sha1\n0000"
+        );
+
+        let mut refs = refs();
+        refs[0].id = None;
+        out.clear();
+        encode::ls_refs(
+            &refs,
+            &LsRefsRequest {
+                peel: true,
+                unborn: true,
+                ..Default::default()
+            },
+            &mut out,
+        )?;
+        assert_eq!(
+            out.as_bstr(),
+            format!(
+                "002eunborn HEAD symref-target:refs/heads/main\n\
+                 003d{A} refs/heads/main\n\
+                 006a{B} refs/tags/v1 peeled:{A}\n\
+                 0000"
+            )
+        );
+
+        out.clear();
+        refs[0].id = Some(id(A));
+        encode::ls_refs(
+            &refs,
+            &LsRefsRequest {
+                symrefs: true,
+                ref_prefixes: vec!["HEAD".into(), "refs/tags/".into()],
+                ..Default::default()
+            },
+            &mut out,
+        )?;
+        assert_eq!(
+            out.as_bstr(),
+            format!(
+                "0050{A} HEAD symref-target:refs/heads/main\n\
+                 003a{B} refs/tags/v1\n\
+                 0000"
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn acknowledgments() -> crate::Result {
+        let mut out = Vec::new();
+        encode::acknowledgments(&[], false, &mut out)?;
+        assert_eq!(out.as_bstr(), "0014acknowledgments\n0008NAK\n0000");
+
+        out.clear();
+        encode::acknowledgments(&[id(A), id(B)], true, &mut out)?;
+        assert_eq!(
+            out.as_bstr(),
+            format!("0014acknowledgments\n0031ACK {A}\n0031ACK {B}\n000aready\n0001")
+        );
+
+        out.clear();
+        encode::wanted_refs(&[(id(A), "refs/heads/main".into())], &mut out)?;
+        assert_eq!(out.as_bstr(), format!("0010wanted-refs\n003d{A} refs/heads/main\n0001"));
+        Ok(())
+    }
+
+    #[test]
+    fn sideband_multiplexes_and_splits_data() -> crate::Result {
+        use std::io::Write;
+        let mut writer = encode::Sideband::small(Vec::new());
+        writer.progress(b"counting\n")?;
+        writer.write_all(&[b'x'; 1000])?;
+        let out = writer.finish()?;
+        let mut reader = reader(&out);
+        let mut bands = Vec::new();
+        while let Some(line) = reader.read_line() {
+            bands.push(match line??.decode_band()? {
+                gix_packetline::BandRef::Data(d) => ("data", d.len()),
+                gix_packetline::BandRef::Progress(d) => ("progress", d.len()),
+                gix_packetline::BandRef::Error(d) => ("error", d.len()),
+            });
+        }
+        assert_eq!(bands, [("progress", 9), ("data", 999), ("data", 1)]);
+        assert_eq!(reader.stopped_at(), Some(PacketLineRef::Flush));
+
+        let mut writer = encode::Sideband::plain(Vec::new());
+        writer.progress(b"not sent")?;
+        writer.write_all(b"PACK")?;
+        assert_eq!(
+            writer.finish()?.as_bstr(),
+            "PACK",
+            "without side-bands, the pack is written as is"
+        );
+        Ok(())
+    }
+}
@@ -98,7 +98,7 @@ mod tests {
     fn size_of_reference() {
         assert_eq!(
             std::mem::size_of::<Reference>(),
-            80,
+            104,
             "let's not let it change size undetected"
         );
     }
@@ -231,6 +231,74 @@ pub mod create_or_update {
     use crate::FullNameRef;
 }
 
+///
+#[allow(clippy::empty_docs)]
+pub mod rewrite {
+    use std::io::Write;
+
+    use crate::{log::Line, store_impl::file, FullNameRef};
+
+    impl file::Store {
+        /// Replace all entries of the reflog of `name` with `lines`, ordered from oldest to newest, or do nothing if there is no
+        /// reflog.
+        ///
+        /// Like `git reflog expire`, the reference is locked along with its reflog while it's rewritten so that no entry can be
+        /// appended concurrently, and `lock_mode` determines what happens if either of them is locked already.
+        pub fn reflog_rewrite(
+            &self,
+            name: &FullNameRef,
+            lines: &[Line],
+            lock_mode: gix_lock::acquire::Fail,
+        ) -> Result<(), Error> {
+            let reflog_path = self.reflog_path(name);
+            if !reflog_path.is_file() {
+                return Ok(());
+            }
+            let _reference_lock =
+                gix_lock::Marker::acquire_to_hold_resource(self.reference_path(name), lock_mode, None)?;
+            let mut file = gix_lock::File::acquire_to_update_resource(&reflog_path, lock_mode, None)?;
+            for line in lines {
+                write!(file, "{} {} ", line.previous_oid, line.new_oid)
+                    .and_then(|_| line.signature.write_to(&mut file))
+                    .and_then(|_| {
+                        if !line.message.is_empty() {
+                            writeln!(file, "\t{}", line.message)
+                        } else {
+                            writeln!(file)
+                        }
+                    })
+                    .map_err(|err| Error::Write {
+                        source: err,
+                        reflog_path: reflog_path.clone(),
+                    })?;
+            }
+            file.commit().map_err(|err| Error::Write {
+                source: err.error,
+                reflog_path,
+            })?;
+            Ok(())
+        }
+    }
+
+    mod error {
+        use std::path::PathBuf;
+
+        /// The error returned by [`crate::file::Store::reflog_rewrite()`].
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            #[error("Could not lock the reference or its reflog")]
+            Lock(#[from] gix_lock::acquire::Error),
+            #[error("Could not write reflog file at {reflog_path:?}")]
+            Write {
+                source: std::io::Error,
+                reflog_path: PathBuf,
+            },
+        }
+    }
+    pub use error::Error;
+}
+
 mod error {
     /// The error returned by [`crate::file::Store::reflog_iter()`].
     #[derive(Debug, thiserror::Error)]
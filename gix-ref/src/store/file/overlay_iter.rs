@@ -16,7 +16,13 @@ use crate::{
 /// equivalent packed references.
 ///
 /// All errors will be returned verbatim, while packed errors are depleted first if loose refs also error.
+///
+/// References are yielded in order of their full name, and each one is yielded only once even if it is packed while
+/// iterating. Loose references that vanish after being listed are taken from the packed snapshot this iteration started
+/// with, or from the current `packed-refs` file if the snapshot doesn't know them. If they don't exist there either, they
+/// were deleted and are skipped.
 pub struct LooseThenPacked<'p, 's> {
+    store: &'s file::Store,
     git_dir: &'s Path,
     common_dir: Option<&'s Path>,
     namespace: Option<&'s Namespace>,
@@ -110,6 +116,25 @@ impl<'p, 's> LooseThenPacked<'p, 's> {
             .map(Into::into)
             .map(|r| self.strip_namespace(r))
     }
+
+    /// Like [`Self::convert_loose()`], but if the loose reference was deleted after it was listed, possibly because
+    /// it was packed concurrently, look it up in the current `packed-refs` file instead.
+    /// Return `None` if it doesn't exist there either.
+    fn convert_loose_or_current_packed(
+        &mut self,
+        res: std::io::Result<(PathBuf, FullName)>,
+    ) -> Option<Result<Reference, Error>> {
+        let name = res.as_ref().ok().map(|(_path, name)| name.clone());
+        match self.convert_loose(res) {
+            Err(err) if err.is_vanished_loose_ref() => {
+                let name = name.expect("only listed references can vanish");
+                let packed = self.store.open_packed_buffer().ok()??;
+                let reference = packed.try_find(name.as_ref()).ok()??.into();
+                Some(Ok(self.strip_namespace(reference)))
+            }
+            res => Some(res),
+        }
+    }
 }
 
 impl<'p, 's> Iterator for LooseThenPacked<'p, 's> {
@@ -149,40 +174,61 @@ impl<'p, 's> Iterator for LooseThenPacked<'p, 's> {
                 None => git_dir.peek().map(|r| (r, IterKind::Git)),
             }
         }
-        match self.iter_packed.as_mut() {
-            Some(packed_iter) => match (
-                peek_loose(&mut self.iter_git_dir, self.iter_common_dir.as_mut()),
-                packed_iter.peek(),
-            ) {
-                (None, None) => None,
-                (None, Some(_)) | (Some(_), Some(Err(_))) => {
-                    let res = packed_iter.next().expect("peeked value exists");
-                    Some(self.convert_packed(res))
-                }
-                (Some((_, kind)), None) | (Some((Err(_), kind)), Some(_)) => {
-                    let res = self.loose_iter(kind).next().expect("prior peek");
-                    Some(self.convert_loose(res))
-                }
-                (Some((Ok((_, loose_name)), kind)), Some(Ok(packed))) => match loose_name.as_ref().cmp(packed.name) {
-                    Ordering::Less => {
-                        let res = self.loose_iter(kind).next().expect("prior peek");
-                        Some(self.convert_loose(res))
+        loop {
+            match self.iter_packed.as_mut() {
+                Some(packed_iter) => match (
+                    peek_loose(&mut self.iter_git_dir, self.iter_common_dir.as_mut()),
+                    packed_iter.peek(),
+                ) {
+                    (None, None) => return None,
+                    (None, Some(_)) | (Some(_), Some(Err(_))) => {
+                        let res = packed_iter.next().expect("peeked value exists");
+                        return Some(self.convert_packed(res));
                     }
-                    Ordering::Equal => {
-                        drop(packed_iter.next());
+                    (Some((_, kind)), None) | (Some((Err(_), kind)), Some(_)) => {
                         let res = self.loose_iter(kind).next().expect("prior peek");
-                        Some(self.convert_loose(res))
+                        match self.convert_loose_or_current_packed(res) {
+                            Some(res) => return Some(res),
+                            None => continue,
+                        }
                     }
-                    Ordering::Greater => {
-                        let res = packed_iter.next().expect("name retrieval configured");
-                        Some(self.convert_packed(res))
+                    (Some((Ok((_, loose_name)), kind)), Some(Ok(packed))) => {
+                        match loose_name.as_ref().cmp(packed.name) {
+                            Ordering::Less => {
+                                let res = self.loose_iter(kind).next().expect("prior peek");
+                                match self.convert_loose_or_current_packed(res) {
+                                    Some(res) => return Some(res),
+                                    None => continue,
+                                }
+                            }
+                            Ordering::Equal => {
+                                let res = self.loose_iter(kind).next().expect("prior peek");
+                                let packed = self.iter_packed.as_mut().expect("present").next().expect("prior peek");
+                                // The loose ref may have been packed and deleted since we listed it,
+                                // in which case our packed snapshot still has its value.
+                                return Some(match self.convert_loose(res) {
+                                    Err(err) if err.is_vanished_loose_ref() => self.convert_packed(packed),
+                                    res => res,
+                                });
+                            }
+                            Ordering::Greater => {
+                                let res = packed_iter.next().expect("name retrieval configured");
+                                return Some(self.convert_packed(res));
+                            }
+                        }
                     }
                 },
-            },
-            None => match peek_loose(&mut self.iter_git_dir, self.iter_common_dir.as_mut()) {
-                None => None,
-                Some((_, kind)) => self.loose_iter(kind).next().map(|res| self.convert_loose(res)),
-            },
+                None => match peek_loose(&mut self.iter_git_dir, self.iter_common_dir.as_mut()) {
+                    None => return None,
+                    Some((_, kind)) => {
+                        let res = self.loose_iter(kind).next().expect("prior peek");
+                        match self.convert_loose_or_current_packed(res) {
+                            Some(res) => return Some(res),
+                            None => continue,
+                        }
+                    }
+                },
+            }
         }
     }
 }
@@ -411,6 +457,7 @@ impl file::Store {
         packed: Option<&'p packed::Buffer>,
     ) -> std::io::Result<LooseThenPacked<'p, 's>> {
         Ok(LooseThenPacked {
+            store: self,
             git_dir: self.git_dir(),
             common_dir: self.common_dir(),
             iter_packed: match packed {
@@ -457,3 +504,12 @@ mod error {
     }
 }
 pub use error::Error;
+
+impl Error {
+    /// Return `true` if a loose reference was deleted after it was listed, but before it could be read.
+    ///
+    /// This happens if references are deleted or packed concurrently, and such references are skipped.
+    fn is_vanished_loose_ref(&self) -> bool {
+        matches!(self, Error::ReadFileContents { source, .. } if source.kind() == std::io::ErrorKind::NotFound)
+    }
+}
@@ -1,3 +1,5 @@
+use std::path::{Path, PathBuf};
+
 use crate::{
     store_impl::file::{transaction::PackedRefs, Transaction},
     transaction::{Change, LogChange, RefEdit, RefLog},
@@ -20,10 +22,13 @@ impl<'s, 'p> Transaction<'s, 'p> {
     /// * update the ref log
     /// * move updated refs into place
     /// * delete reflogs and empty parent directories
-    /// * delete packed refs
+    /// * delete packed refs, by rewriting the `packed-refs` file once for all edits in the transaction
     /// * delete their corresponding reference (if applicable)
     ///   along with empty parent directories
     ///
+    /// Deleting loose references only after `packed-refs` was rewritten assures that readers never see the possibly
+    /// outdated value of a packed reference that was shadowed by the loose reference that is about to be deleted.
+    ///
     /// Note that transactions will be prepared automatically as needed.
     pub fn commit<'a>(self, committer: impl Into<Option<gix_actor::SignatureRef<'a>>>) -> Result<Vec<RefEdit>, Error> {
         self.commit_inner(committer.into())
@@ -124,7 +129,7 @@ impl<'s, 'p> Transaction<'s, 'p> {
                 Change::Delete { .. } => {
                     // Reflog deletion happens first in case it fails a ref without log is less terrible than
                     // a log without a reference.
-                    let reflog_path = reflog_root.join(relative_name);
+                    let reflog_path = reflog_root.join(&relative_name);
                     if let Err(err) = std::fs::remove_file(&reflog_path) {
                         if err.kind() != std::io::ErrorKind::NotFound {
                             return Err(Error::DeleteReflog {
@@ -133,11 +138,7 @@ impl<'s, 'p> Transaction<'s, 'p> {
                             });
                         }
                     } else {
-                        gix_tempfile::remove_dir::empty_upward_until_boundary(
-                            reflog_path.parent().expect("never without parent"),
-                            &reflog_root,
-                        )
-                        .ok();
+                        remove_empty_parent_directories(&reflog_root, &relative_name);
                     }
                 }
             }
@@ -160,22 +161,42 @@ impl<'s, 'p> Transaction<'s, 'p> {
                 Change::Delete { log: mode, .. } => *mode == RefLog::AndReference,
             };
             if take_lock_and_delete {
+                // The packed-refs file was written already and doesn't contain the deleted reference anymore,
+                // so removing the loose reference now can't reveal a stale value from packed-refs to readers.
                 let lock = change.lock.take();
-                let reference_path = self.store.reference_path(change.update.name.as_ref());
-                if let Err(err) = std::fs::remove_file(reference_path) {
-                    if err.kind() != std::io::ErrorKind::NotFound {
+                let (base, relative_path) = self.store.reference_path_with_base(change.update.name.as_ref());
+                let removed = match std::fs::remove_file(base.join(&relative_path)) {
+                    Ok(()) => true,
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => false,
+                    Err(err) => {
                         return Err(Error::DeleteReference {
                             err,
                             full_name: change.name(),
-                        });
+                        })
                     }
+                };
+                // The lock lives next to the reference and has to be gone for its directory to be empty.
+                drop(lock);
+                if removed {
+                    remove_empty_parent_directories(&base, &relative_path);
                 }
-                drop(lock)
             }
         }
         Ok(updates.into_iter().map(|edit| edit.update).collect())
     }
 }
+/// Remove all empty directories leading to `relative_path` within `root`, but keep category directories like `refs/heads`
+/// just like git does. Failure is ignored as it's not critical, as empty directories don't affect the correctness of the store.
+fn remove_empty_parent_directories(root: &Path, relative_path: &Path) {
+    let boundary = root.join(relative_path.components().take(2).collect::<PathBuf>());
+    match root.join(relative_path).parent() {
+        Some(parent) if parent != boundary && parent.starts_with(&boundary) => {
+            gix_tempfile::remove_dir::empty_upward_until_boundary(parent, &boundary).ok();
+        }
+        _ => {}
+    }
+}
+
 mod error {
     use gix_object::bstr::BString;
 
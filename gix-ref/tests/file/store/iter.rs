@@ -461,3 +461,172 @@ fn overlay_partial_prefix_iter() -> crate::Result {
     assert_eq!(ref_names, vec![(b"refs/heads/main".as_bstr().to_owned(), Peeled(c1)),]);
     Ok(())
 }
+
+mod overlay_consistency {
+    use std::path::Path;
+
+    use gix_object::bstr::{BString, ByteSlice};
+
+    use crate::file::transaction::prepare_and_commit::empty_store;
+
+    const ID_A: &str = "134385f6d781b7e97062102c6a483440bfda2a03";
+    const ID_B: &str = "9902e3c3e8f0c569b4ab295ddf473e6de763e1e7";
+
+    /// Atomically write the loose ref `name`, like git does with a lock file.
+    fn write_loose(git_dir: &Path, name: &str, id: &str) -> std::io::Result<()> {
+        let path = git_dir.join(name);
+        std::fs::create_dir_all(path.parent().expect("parent"))?;
+        let lock = path.with_extension("lock");
+        std::fs::write(&lock, format!("{id}\n"))?;
+        std::fs::rename(lock, path)
+    }
+
+    /// Atomically replace the `packed-refs` file with one containing `refs`, which must be sorted.
+    fn write_packed(git_dir: &Path, refs: &[(&str, &str)]) -> std::io::Result<()> {
+        let mut buf = String::from("# pack-refs with: peeled fully-peeled sorted \n");
+        for (name, id) in refs {
+            buf.push_str(&format!("{id} {name}\n"));
+        }
+        let tmp = git_dir.join("packed-refs.tmp");
+        std::fs::write(&tmp, buf)?;
+        std::fs::rename(tmp, git_dir.join("packed-refs"))
+    }
+
+    fn names(store: &gix_ref::file::Store) -> crate::Result<Vec<(BString, gix_ref::Target)>> {
+        Ok(store
+            .iter()?
+            .all()?
+            .map(|r| r.map(|r| (r.name.as_bstr().to_owned(), r.target)))
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    #[test]
+    fn output_is_sorted_by_full_name_across_directories_and_loose_refs_win() -> crate::Result {
+        let (dir, store) = empty_store()?;
+        for name in ["refs/heads/a/c", "refs/heads/a-b", "refs/heads/a0", "refs/tags/v1"] {
+            write_loose(dir.path(), name, ID_B)?;
+        }
+        write_packed(
+            dir.path(),
+            &[
+                ("refs/heads/a-a", ID_A),
+                ("refs/heads/a-b", ID_A),
+                ("refs/heads/a/b", ID_A),
+                ("refs/heads/a/d", ID_A),
+            ],
+        )?;
+
+        let peeled = |hex: &str| gix_ref::Target::Peeled(crate::hex_to_id(hex));
+        assert_eq!(
+            names(&store)?,
+            vec![
+                ("refs/heads/a-a".into(), peeled(ID_A)),
+                ("refs/heads/a-b".into(), peeled(ID_B)),
+                ("refs/heads/a/b".into(), peeled(ID_A)),
+                ("refs/heads/a/c".into(), peeled(ID_B)),
+                ("refs/heads/a/d".into(), peeled(ID_A)),
+                ("refs/heads/a0".into(), peeled(ID_B)),
+                ("refs/tags/v1".into(), peeled(ID_B)),
+            ],
+            "'-' sorts before '/' which sorts before '0', just like in packed-refs, and loose refs win"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn loose_refs_packed_after_being_listed_are_yielded_once() -> crate::Result {
+        let (dir, store) = empty_store()?;
+        for name in ["refs/heads/a", "refs/heads/in-snapshot", "refs/heads/not-in-snapshot"] {
+            write_loose(dir.path(), name, ID_B)?;
+        }
+        write_packed(dir.path(), &[("refs/heads/in-snapshot", ID_A)])?;
+
+        let platform = store.iter()?;
+        let mut iter = platform.all()?;
+        assert_eq!(iter.next().expect("first")?.name.as_bstr(), "refs/heads/a");
+
+        // Pack everything like `git pack-refs` would, by rewriting `packed-refs` before deleting the loose refs.
+        write_packed(
+            dir.path(),
+            &[("refs/heads/in-snapshot", ID_B), ("refs/heads/not-in-snapshot", ID_B)],
+        )?;
+        std::fs::remove_file(dir.path().join("refs/heads/in-snapshot"))?;
+        std::fs::remove_file(dir.path().join("refs/heads/not-in-snapshot"))?;
+
+        let rest = iter
+            .map(|r| r.map(|r| (r.name.as_bstr().to_owned(), r.target)))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            rest,
+            vec![
+                (
+                    "refs/heads/in-snapshot".into(),
+                    gix_ref::Target::Peeled(crate::hex_to_id(ID_A))
+                ),
+                (
+                    "refs/heads/not-in-snapshot".into(),
+                    gix_ref::Target::Peeled(crate::hex_to_id(ID_B))
+                ),
+            ],
+            "the packed snapshot is used if available, otherwise the current packed-refs are consulted"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn concurrent_packing_and_unpacking_never_yields_unsorted_or_duplicate_refs() -> crate::Result {
+        let (dir, store) = empty_store()?;
+        let stable: Vec<String> = (0..20).map(|i| format!("refs/heads/stable-{i:02}")).collect();
+        let volatile: Vec<String> = (0..20).map(|i| format!("refs/heads/s/volatile-{i:02}")).collect();
+        for name in stable.iter().chain(volatile.iter()) {
+            write_loose(dir.path(), name, ID_A)?;
+        }
+        let all_packed = {
+            let mut refs: Vec<_> = stable
+                .iter()
+                .chain(volatile.iter())
+                .map(|n| (n.as_str(), ID_A))
+                .collect();
+            refs.sort();
+            refs
+        };
+        let stable_packed: Vec<_> = stable.iter().map(|n| (n.as_str(), ID_A)).collect();
+        write_packed(dir.path(), &stable_packed)?;
+
+        std::thread::scope(|scope| -> crate::Result {
+            let mutator = scope.spawn(|| -> std::io::Result<()> {
+                for _round in 0..100 {
+                    // Pack all refs, then delete the loose ones, just like `git pack-refs`.
+                    write_packed(dir.path(), &all_packed)?;
+                    for name in stable.iter().chain(volatile.iter()) {
+                        std::fs::remove_file(dir.path().join(name))?;
+                    }
+                    // Bring the loose refs back, then un-pack the volatile ones.
+                    for name in stable.iter().chain(volatile.iter()) {
+                        write_loose(dir.path(), name, ID_A)?;
+                    }
+                    write_packed(dir.path(), &stable_packed)?;
+                }
+                Ok(())
+            });
+
+            let mut iterations = 0;
+            while iterations == 0 || !mutator.is_finished() {
+                iterations += 1;
+                let names: Vec<_> = names(&store)?.into_iter().map(|(name, _)| name).collect();
+                assert!(
+                    names.windows(2).all(|w| w[0] < w[1]),
+                    "refs are sorted and yielded exactly once: {names:?}"
+                );
+                for name in &stable {
+                    assert!(
+                        names.iter().any(|n| n == name.as_bytes().as_bstr()),
+                        "{name} is always present in the packed snapshot"
+                    );
+                }
+            }
+            mutator.join().expect("no panic")?;
+            Ok(())
+        })
+    }
+}
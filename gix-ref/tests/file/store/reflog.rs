@@ -67,3 +67,39 @@ mod iter_rev {
         Ok(())
     }
 }
+
+mod rewrite {
+    use gix_lock::acquire::Fail;
+    use gix_ref::log::Line;
+
+    #[test]
+    fn keeps_only_the_given_lines_and_ignores_missing_reflogs() -> crate::Result {
+        let (_keep, store) = crate::file::store_writable("make_repo_for_reflog.sh")?;
+        let mut buf = Vec::new();
+        let lines: Vec<Line> = store
+            .reflog_iter("refs/heads/main", &mut buf)?
+            .expect("exists")
+            .map(|line| line.map(|line| line.to_owned()))
+            .collect::<Result<_, _>>()?;
+        let before = buf.clone();
+
+        store.reflog_rewrite("refs/heads/main".try_into()?, &lines, Fail::Immediately)?;
+        store.reflog_iter("refs/heads/main", &mut buf)?;
+        assert_eq!(buf, before, "lines are written exactly as git writes them");
+
+        store.reflog_rewrite("refs/heads/main".try_into()?, &lines[3..], Fail::Immediately)?;
+        let remaining: Vec<Line> = store
+            .reflog_iter("refs/heads/main", &mut buf)?
+            .expect("still exists")
+            .map(|line| line.map(|line| line.to_owned()))
+            .collect::<Result<_, _>>()?;
+        assert_eq!(remaining, lines[3..]);
+
+        store.reflog_rewrite("refs/heads/missing".try_into()?, &lines, Fail::Immediately)?;
+        assert!(
+            store.reflog_iter("refs/heads/missing", &mut buf)?.is_none(),
+            "reflogs aren't created"
+        );
+        Ok(())
+    }
+}
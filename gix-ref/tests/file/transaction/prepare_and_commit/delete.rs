@@ -457,3 +457,139 @@ fn all_contained_references_deletes_the_packed_ref_file_too() -> crate::Result {
     }
     Ok(())
 }
+
+#[test]
+fn many_packed_refs_are_deleted_in_one_transaction_along_with_their_reflogs() -> crate::Result {
+    let (_keep, store) = store_writable("make_packed_refs_for_deletion.sh")?;
+    let stale: Vec<_> = store
+        .open_packed_buffer()?
+        .expect("packed-refs")
+        .iter_prefixed("refs/heads/stale/".into())?
+        .map(|r| r.map(|r| r.name.to_owned()))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(stale.len(), 100);
+
+    let edits = store
+        .transaction()
+        .prepare(
+            stale.iter().map(|name| RefEdit {
+                change: Change::Delete {
+                    expected: PreviousValue::MustExist,
+                    log: RefLog::AndReference,
+                },
+                name: name.clone(),
+                deref: false,
+            }),
+            Fail::Immediately,
+            Fail::Immediately,
+        )?
+        .commit(committer().to_ref())?;
+    assert_eq!(edits.len(), 100);
+
+    let packed = store.open_packed_buffer()?.expect("packed-refs still present");
+    assert_eq!(
+        packed.iter()?.count(),
+        1,
+        "only the packed main branch remains, all stale branches were removed in one go"
+    );
+    for name in &stale {
+        assert!(store.try_find(name)?.is_none(), "{name} was deleted");
+    }
+    assert!(
+        !store.git_dir().join("logs/refs/heads/stale").exists(),
+        "reflogs are deleted along with their empty directory"
+    );
+    assert!(
+        store.git_dir().join("logs/refs/heads/main").is_file(),
+        "other reflogs are untouched"
+    );
+    Ok(())
+}
+
+#[test]
+fn deleting_loose_refs_removes_empty_parent_directories() -> crate::Result {
+    let (_keep, store) = store_writable("make_packed_refs_for_deletion.sh")?;
+    store
+        .transaction()
+        .prepare(
+            Some(RefEdit {
+                change: Change::Delete {
+                    expected: PreviousValue::MustExist,
+                    log: RefLog::AndReference,
+                },
+                name: "refs/heads/loose/nested".try_into()?,
+                deref: false,
+            }),
+            Fail::Immediately,
+            Fail::Immediately,
+        )?
+        .commit(committer().to_ref())?;
+
+    for dir in ["refs/heads/loose", "logs/refs/heads/loose"] {
+        assert!(!store.git_dir().join(dir).exists(), "{dir} is empty and was removed");
+    }
+    for dir in ["refs/heads", "logs/refs/heads"] {
+        assert!(
+            store.git_dir().join(dir).is_dir(),
+            "{dir} is kept, just like git does, even if it's empty"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn concurrent_readers_never_see_the_outdated_packed_value_of_a_deleted_loose_ref() -> crate::Result {
+    for _round in 0..10 {
+        let (_keep, store) = store_writable("make_packed_ref_repository_for_overlay.sh")?;
+        let name = "refs/heads/newer-as-loose";
+        let loose_id = store.find(name)?.target.into_id();
+        let packed_id = store.open_packed_buffer()?.expect("packed-refs").find(name)?.target();
+        assert_ne!(loose_id, packed_id, "the loose ref shadows an outdated packed ref");
+
+        let done = std::sync::atomic::AtomicBool::default();
+        let git_dir = store.git_dir().to_owned();
+        std::thread::scope(|scope| -> crate::Result {
+            let done = &done;
+            let reader = scope.spawn(move || -> crate::Result<usize> {
+                let store = gix_ref::file::Store::at(git_dir, Default::default());
+                let mut reads = 0;
+                loop {
+                    let is_done = done.load(std::sync::atomic::Ordering::SeqCst);
+                    if let Some(r) = store.try_find(name)? {
+                        assert_eq!(
+                            r.target.into_id(),
+                            loose_id,
+                            "the stale packed value must never be visible"
+                        );
+                    }
+                    reads += 1;
+                    if is_done {
+                        break Ok(reads);
+                    }
+                }
+            });
+
+            store
+                .transaction()
+                .prepare(
+                    Some(RefEdit {
+                        change: Change::Delete {
+                            expected: PreviousValue::MustExistAndMatch(Target::Peeled(loose_id)),
+                            log: RefLog::AndReference,
+                        },
+                        name: name.try_into()?,
+                        deref: false,
+                    }),
+                    Fail::Immediately,
+                    Fail::Immediately,
+                )?
+                .commit(committer().to_ref())?;
+            done.store(true, std::sync::atomic::Ordering::SeqCst);
+
+            assert!(reader.join().expect("no panic")? > 0);
+            Ok(())
+        })?;
+        assert!(store.try_find(name)?.is_none(), "the reference is gone entirely");
+    }
+    Ok(())
+}
@@ -0,0 +1,15 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+git checkout -q -b main
+git commit -q --allow-empty -m c1
+
+for name in $(seq 100); do
+  git branch stale/"$name"
+done
+
+git pack-refs --all --prune
+
+git branch loose/nested
@@ -0,0 +1,157 @@
+/// The outcome of [`ahead_behind()`][function::ahead_behind()].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Outcome {
+    /// The amount of commits reachable from the left tip, but not from the right tip.
+    pub ahead: usize,
+    /// The amount of commits reachable from the right tip, but not from the left tip.
+    pub behind: usize,
+    /// If `true`, the traversal was stopped early as the `limit` was reached, and `ahead` and `behind` are
+    /// lower bounds of their actual values, i.e. *at least* as many commits are ahead and behind respectively.
+    pub limit_reached: bool,
+}
+
+impl Outcome {
+    /// Return `(ahead, behind)` as tuple.
+    pub fn counts(&self) -> (usize, usize) {
+        (self.ahead, self.behind)
+    }
+}
+
+/// The error returned by [`ahead_behind()`][function::ahead_behind()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Commit {} could not be found", oid.to_hex())]
+    NotFound { oid: gix_hash::ObjectId },
+    #[error("A commit could not be looked up or decoded during traversal")]
+    Lookup(#[from] crate::graph::try_lookup_or_insert_default::Error),
+}
+
+/// A bit-field to track which of the tips can reach a commit.
+pub type Flags = u8;
+
+/// The commit is reachable from the left tip.
+const LEFT: Flags = 1 << 0;
+/// The commit is reachable from the right tip.
+const RIGHT: Flags = 1 << 1;
+/// The commit is reachable from both tips.
+const COMMON: Flags = LEFT | RIGHT;
+/// The commit was taken off the queue and its parents were queued.
+const POPPED: Flags = 1 << 2;
+
+pub(crate) mod function {
+    use gix_hash::{oid, ObjectId};
+
+    use super::{Error, Flags, Outcome, COMMON, LEFT, POPPED, RIGHT};
+    use crate::{graph, Graph, PriorityQueue};
+
+    /// Count the commits reachable from `left` but not from `right` (`ahead`) and the commits reachable
+    /// from `right` but not from `left` (`behind`), similar to `git rev-list --left-right --count left...right`.
+    ///
+    /// Both tips are traversed at once in order of generation number if a commit-graph is available to `graph`,
+    /// and in order of commit time otherwise, marking each commit with the side it is reachable from.
+    /// The traversal stops as soon as all commits left to traverse are reachable from both tips.
+    ///
+    /// If `limit` is `Some(n)`, the traversal stops once `n` commits were counted as `ahead` or `behind`, which
+    /// is indicated by [`Outcome::limit_reached`].
+    ///
+    /// Note that without generation numbers, commits with skewed commit times may be counted as being on one side
+    /// even though they are reachable from both, just like it is the case in `git`.
+    pub fn ahead_behind(
+        left: &oid,
+        right: &oid,
+        graph: &mut Graph<'_, graph::Commit<Flags>>,
+        limit: Option<usize>,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix_revision::ahead_behind()", left = %left, right = %right, limit = ?limit);
+        let mut out = Outcome::default();
+        if left == right {
+            return Ok(out);
+        }
+
+        graph.clear();
+        let mut queue = PriorityQueue::<Key, ObjectId>::new();
+        let mut uncommon_in_queue = 0_usize;
+        for (id, side) in [(left, LEFT), (right, RIGHT)] {
+            let commit = graph
+                .try_lookup_or_insert_commit(id.to_owned(), |flags| *flags |= side)?
+                .ok_or_else(|| Error::NotFound { oid: id.to_owned() })?;
+            queue.insert(key(commit), id.to_owned());
+            uncommon_in_queue += 1;
+        }
+
+        // The oldest commit counted as being on one side. Commits with the same key may still be queued, and as they
+        // could be its descendants they have to be processed to be sure it isn't reachable from the other side as well.
+        let mut oldest_counted = None::<Key>;
+        loop {
+            let has_undecided_ties =
+                matches!((queue.peek(), oldest_counted), (Some((next, _)), Some(oldest)) if *next >= oldest);
+            if uncommon_in_queue == 0 && !has_undecided_ties {
+                break;
+            }
+            if matches!(limit, Some(limit) if out.ahead + out.behind >= limit) {
+                out.limit_reached = true;
+                break;
+            }
+            let id = queue.pop_value().expect("uncommon commits or ties are still queued");
+            let commit = graph.get_mut(&id).expect("queued commits are in the graph");
+            commit.data |= POPPED;
+            let flags = commit.data & COMMON;
+            match flags {
+                LEFT => out.ahead += 1,
+                RIGHT => out.behind += 1,
+                _ => {}
+            }
+            if flags != COMMON {
+                uncommon_in_queue -= 1;
+                let key = key(commit);
+                oldest_counted = Some(oldest_counted.map_or(key, |oldest| oldest.min(key)));
+            }
+
+            let parents = commit.parents.clone();
+            for parent_id in parents {
+                match graph.get_mut(&parent_id) {
+                    Some(parent) => {
+                        let previous = parent.data;
+                        parent.data |= flags;
+                        if previous & COMMON == COMMON || parent.data & COMMON != COMMON {
+                            continue;
+                        }
+                        if previous & POPPED == 0 {
+                            uncommon_in_queue -= 1;
+                        } else {
+                            // It was counted on one side before it was known to be reachable from the other side as well,
+                            // which can happen if it has the same key as its descendants. Undo that and queue it again
+                            // to pass the knowledge on to its ancestors.
+                            match previous & COMMON {
+                                LEFT => out.ahead -= 1,
+                                RIGHT => out.behind -= 1,
+                                _ => {}
+                            }
+                            parent.data &= !POPPED;
+                            queue.insert(key(parent), parent_id);
+                        }
+                    }
+                    None => {
+                        // Missing parents are skipped, which is expected in shallow clones.
+                        if let Some(parent) = graph.try_lookup_or_insert_commit(parent_id, |data| *data = flags)? {
+                            queue.insert(key(parent), parent_id);
+                            if flags != COMMON {
+                                uncommon_in_queue += 1;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Order by generation first, and fall back to commit time for commits that aren't in the commit-graph,
+    /// which are then considered the most recent just like in `git`.
+    type Key = (u32, gix_date::SecondsSinceUnixEpoch);
+
+    fn key(commit: &graph::Commit<Flags>) -> Key {
+        (commit.generation.unwrap_or(u32::MAX), commit.commit_time)
+    }
+}
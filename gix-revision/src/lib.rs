@@ -1,4 +1,5 @@
-//! Interact with git revisions by parsing them from rev-specs and describing them in terms of reference names.
+//! Interact with git revisions by parsing them from rev-specs, describing them in terms of reference names
+//! and counting the commits they are ahead and behind of each other.
 //!
 //! ## Feature Flags
 #![cfg_attr(
@@ -8,6 +9,11 @@
 #![cfg_attr(all(doc, feature = "document-features"), feature(doc_cfg, doc_auto_cfg))]
 #![deny(missing_docs, rust_2018_idioms, unsafe_code)]
 
+///
+#[allow(clippy::empty_docs)]
+pub mod ahead_behind;
+pub use ahead_behind::function::ahead_behind;
+
 ///
 #[allow(clippy::empty_docs)]
 #[cfg(feature = "describe")]
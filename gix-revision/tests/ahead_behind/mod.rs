@@ -0,0 +1,108 @@
+use gix_object::bstr::ByteSlice;
+use gix_revision::ahead_behind::Outcome;
+
+use crate::hex_to_id;
+
+#[test]
+fn matches_git_rev_list_left_right_count() -> crate::Result {
+    let root = fixture_path();
+    for dir in [root.clone(), root.join("same-time")] {
+        assert_baseline(&dir)?;
+    }
+    Ok(())
+}
+
+fn assert_baseline(dir: &std::path::Path) -> crate::Result {
+    let store = gix_odb::at(dir.join(".git/objects"))?;
+    let baseline = std::fs::read(dir.join("baseline"))?;
+    for use_commitgraph in [false, true] {
+        let cache = use_commitgraph
+            .then(|| gix_commitgraph::Graph::from_info_dir(&store.store_ref().path().join("info")).ok())
+            .flatten();
+        assert_eq!(cache.is_some(), use_commitgraph, "the commit-graph is present");
+        let mut graph = gix_revision::Graph::new(&store, cache);
+        for line in baseline.lines() {
+            let tokens: Vec<_> = line
+                .split(u8::is_ascii_whitespace)
+                .map(|t| t.to_str().unwrap())
+                .collect();
+            let [left_name, right_name, left, right, ahead, behind] = tokens[..] else {
+                panic!("unexpected baseline line: {:?}", line.as_bstr());
+            };
+            let actual = gix_revision::ahead_behind(&hex_to_id(left), &hex_to_id(right), &mut graph, None)?;
+            assert_eq!(
+                actual,
+                Outcome {
+                    ahead: ahead.parse()?,
+                    behind: behind.parse()?,
+                    limit_reached: false
+                },
+                "{left_name}...{right_name} in {dir:?} (commit-graph: {use_commitgraph})"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn limit_yields_lower_bounds() -> crate::Result {
+    let dir = fixture_path();
+    let store = gix_odb::at(dir.join(".git/objects"))?;
+    let mut graph = gix_revision::Graph::new(&store, None);
+    let (merged, other) = ids_of(&dir, "merged", "other")?;
+
+    let actual = gix_revision::ahead_behind(&merged, &other, &mut graph, Some(3))?;
+    assert!(actual.limit_reached);
+    assert_eq!(
+        actual.ahead + actual.behind,
+        3,
+        "it stops as soon as the limit is reached"
+    );
+    assert!(
+        actual.ahead <= 5 && actual.behind <= 3,
+        "counts never exceed the actual values"
+    );
+
+    let actual = gix_revision::ahead_behind(&merged, &other, &mut graph, Some(9))?;
+    assert_eq!(
+        actual,
+        Outcome {
+            ahead: 5,
+            behind: 3,
+            limit_reached: false
+        },
+        "a limit that isn't exceeded yields exact results"
+    );
+    Ok(())
+}
+
+#[test]
+fn missing_tip_is_an_error() -> crate::Result {
+    let dir = fixture_path();
+    let store = gix_odb::at(dir.join(".git/objects"))?;
+    let mut graph = gix_revision::Graph::new(&store, None);
+    let (main, _) = ids_of(&dir, "main", "main")?;
+    let missing = gix_hash::Kind::Sha1.null();
+    let err = gix_revision::ahead_behind(&main, &missing, &mut graph, None).unwrap_err();
+    assert!(matches!(err, gix_revision::ahead_behind::Error::NotFound { oid } if oid == missing));
+    Ok(())
+}
+
+fn ids_of(
+    dir: &std::path::Path,
+    left_name: &str,
+    right_name: &str,
+) -> crate::Result<(gix_hash::ObjectId, gix_hash::ObjectId)> {
+    let baseline = std::fs::read(dir.join("baseline"))?;
+    let line = baseline
+        .lines()
+        .find(|line| line.starts_with(format!("{left_name} {right_name} ").as_bytes()))
+        .expect("pair is in baseline");
+    let mut tokens = line.split(|b| *b == b' ').skip(2);
+    let mut next_id = || hex_to_id(tokens.next().expect("id").to_str().expect("ascii"));
+    Ok((next_id(), next_id()))
+}
+
+fn fixture_path() -> std::path::PathBuf {
+    gix_testtools::scripted_fixture_read_only("make_repo_for_ahead_behind.sh").unwrap()
+}
@@ -0,0 +1,85 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function tick () {
+  if test -z "${tick+set}"
+  then
+    tick=1112911993
+  else
+    tick=$(($tick + 60))
+  fi
+  GIT_COMMITTER_DATE="$tick -0700"
+  GIT_AUTHOR_DATE="$tick -0700"
+  export GIT_COMMITTER_DATE GIT_AUTHOR_DATE
+}
+
+function commit() {
+  tick
+  git commit -q --allow-empty -m "$1"
+}
+
+git init -q
+git checkout -q -b main
+commit c1
+commit c2
+git branch ancestor
+commit c3
+
+git checkout -q -b feature
+commit f1
+commit f2
+commit f3
+
+git checkout -q main
+commit c4
+commit c5
+
+git checkout -q -b merged
+tick
+git merge -q --no-ff feature -m "merge feature"
+commit m1
+
+git checkout -q -b other ancestor
+commit o1
+tick
+git merge -q --no-ff main -m "merge main"
+commit o2
+
+git checkout -q --orphan disjoint
+git rm -rfq . 2>/dev/null || true
+commit d1
+commit d2
+
+git checkout -q main
+
+for pair in "main feature" "feature main" "ancestor main" "main ancestor" "main main" \
+            "merged feature" "feature merged" "merged main" "other merged" "merged other" \
+            "other feature" "disjoint main"; do
+  read -r left right <<<"$pair"
+  echo "$left $right $(git rev-parse "$left") $(git rev-parse "$right") $(git rev-list --left-right --count "$left...$right")" >> baseline
+done
+
+git commit-graph write --no-progress --reachable
+
+# all commits have the same time, so only their graph can be used to tell them apart.
+git init -q same-time
+(cd same-time
+  export GIT_COMMITTER_DATE="1112911993 -0700" GIT_AUTHOR_DATE="1112911993 -0700"
+  git checkout -q -b main
+  git commit -q --allow-empty -m c1
+  git commit -q --allow-empty -m c2
+  git branch ancestor
+  git commit -q --allow-empty -m c3
+  git checkout -q -b feature ancestor
+  git commit -q --allow-empty -m f1
+  git checkout -q main
+  git merge -q --no-ff feature -m "merge feature"
+  git commit -q --allow-empty -m c4
+
+  for pair in "ancestor main" "main ancestor" "feature main" "main feature" "ancestor feature"; do
+    read -r left right <<<"$pair"
+    echo "$left $right $(git rev-parse "$left") $(git rev-parse "$right") $(git rev-list --left-right --count "$left...$right")" >> baseline
+  done
+
+  git commit-graph write --no-progress --reachable
+)
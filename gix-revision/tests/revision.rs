@@ -1,3 +1,4 @@
+mod ahead_behind;
 #[cfg(feature = "describe")]
 mod describe;
 mod spec;
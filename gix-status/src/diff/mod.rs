@@ -0,0 +1,382 @@
+//! Changes between the index and the worktree along with the content of both versions, similar to `git diff`.
+use bstr::{BStr, BString};
+use gix_hash::ObjectId;
+
+use crate::index_as_worktree;
+
+mod patch;
+
+/// The error returned by [`worktree()`](function::worktree()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Status(#[from] index_as_worktree::Error),
+    #[error("Could not read '{rela_path}' from the worktree")]
+    ReadWorktree { rela_path: BString, source: std::io::Error },
+    #[error(transparent)]
+    FindObject(#[from] gix_object::find::existing_object::Error),
+    #[error("The change callback failed")]
+    Callback(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+}
+
+/// Options for use in [`worktree()`](function::worktree()).
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct Options {
+    /// Options to control how modifications are detected.
+    pub status: index_as_worktree::Options,
+    /// If `Some(…)`, produce a patch for each change, just like `git diff` would. Otherwise, only the content
+    /// of the old and new version is provided.
+    pub patch: Option<PatchOptions>,
+}
+
+pub use patch::Options as PatchOptions;
+
+pub use function::worktree;
+
+/// Tell the caller of [`worktree()`](function::worktree()) whether to continue or to stop.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Continue with the next change.
+    #[default]
+    Continue,
+    /// Stop producing changes.
+    Cancel,
+}
+
+/// One side of a [`Change`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Version<'a> {
+    /// The id of the content as it is or would be stored in `git`.
+    pub id: ObjectId,
+    /// The mode of the version, typically a file, an executable file or a symlink.
+    pub mode: gix_index::entry::Mode,
+    /// The content as it is stored in `git`, i.e. after applying all filters to worktree files, or the link target for symlinks.
+    pub data: &'a [u8],
+    /// If `true`, the content is considered binary and should not be diffed line by line.
+    ///
+    /// This is the case if the `diff` attribute is unset, or if the attribute is unspecified and the first 8000 bytes
+    /// of `data` contain a null-byte.
+    pub is_binary: bool,
+}
+
+/// A file in the worktree that differs from its version in the index, or a modified submodule.
+///
+/// `S` is the status of submodules as produced by the [`SubmoduleStatus`](crate::index_as_worktree::traits::SubmoduleStatus)
+/// implementation passed to [`worktree()`](function::worktree()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Change<'index, 'data, S = ()> {
+    /// The index entry this change is about.
+    pub entry: &'index gix_index::Entry,
+    /// The path of `entry`, relative to the worktree root.
+    pub rela_path: &'index BStr,
+    /// The version in the index, or `None` if the entry was added with `git add --intent-to-add`.
+    pub old: Option<Version<'data>>,
+    /// The version in the worktree, or `None` if it was removed from the worktree.
+    ///
+    /// Note that its mode may differ from the mode of `old` if the executable bit changed, or if the type changed,
+    /// like a file that became a symlink.
+    pub new: Option<Version<'data>>,
+    /// The complete patch for this change including its headers, as `git diff` would produce it, if
+    /// [`Options::patch`] was set.
+    ///
+    /// Type changes produce two patches, one for the deletion and one for the addition.
+    pub patch: Option<&'data BStr>,
+    /// The status of the submodule at `rela_path` if it is considered modified, which is all there is to know about it.
+    ///
+    /// If set, `old` and `new` are both the commit recorded in the index with empty `data`, and there is no `patch`.
+    pub submodule: Option<&'data S>,
+}
+
+pub(super) mod function {
+    use std::{io::Read, path::Path};
+
+    use bstr::{BStr, BString, ByteSlice};
+    use gix_diff::blob::is_binary_buf;
+    use gix_filter::pipeline::convert::ToGitOutcome;
+    use gix_hash::ObjectId;
+    use gix_object::FindExt;
+
+    use super::{Action, Change, Error, Options, Version};
+    use crate::index_as_worktree::{
+        self, traits, traits::ReadData, Change as StatusChange, Context, EntryStatus, Outcome, Recorder,
+    };
+
+    /// Compare the `index` with the `worktree` like [`index_as_worktree()`](crate::index_as_worktree()), but obtain
+    /// the content of both versions of each changed file to call `on_change` with each [`Change`] in index order.
+    /// This is what `git diff` does without arguments.
+    ///
+    /// `submodule` determines the status of submodules, which are passed to `on_change` if they are considered modified.
+    ///
+    /// Each changed file is read only once, and its content is converted to what would be stored in `git` using the filters
+    /// in `ctx`, which is what the index version is compared to. `objects` are used to obtain the index versions.
+    /// `progress` is used for the underlying status, which is configured with `options`, and `ctx` also
+    /// determines which entries to look at in the first place by means of its pathspec.
+    ///
+    /// Return the outcome of the underlying status operation.
+    ///
+    /// ### Deviation
+    ///
+    /// * Conflicting entries are skipped, whereas `git` would list them as *unmerged*.
+    /// * Diff drivers configured with the `diff` attribute, and thus text conversions, aren't supported.
+    #[allow(clippy::too_many_arguments)]
+    pub fn worktree<'index, Find, S, SE, E>(
+        index: &'index gix_index::State,
+        worktree: &Path,
+        objects: Find,
+        submodule: impl traits::SubmoduleStatus<Output = S, Error = SE> + Send + Clone,
+        progress: &mut dyn gix_features::progress::Progress,
+        ctx: Context<'_>,
+        options: Options,
+        mut on_change: impl FnMut(Change<'index, '_, S>) -> Result<Action, E>,
+    ) -> Result<Outcome, Error>
+    where
+        Find: gix_object::Find + Send + Clone,
+        S: Send,
+        SE: std::error::Error + Send + Sync + 'static,
+        E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+    {
+        let mut attr_stack = ctx.stack.clone();
+        let mut filter = ctx.filter.clone();
+        let mut recorder = Recorder { records: Vec::new() };
+        let outcome = crate::index_as_worktree(
+            index,
+            worktree,
+            &mut recorder,
+            CaptureContent,
+            submodule,
+            objects.clone(),
+            progress,
+            ctx,
+            options.status.clone(),
+        )?;
+        recorder.records.sort_by_key(|r| r.entry_index);
+
+        let mut attrs = gix_filter::attributes::search::Outcome::default();
+        attrs.initialize_with_selection(&Default::default(), Some("diff"));
+        let (mut old_buf, mut new_buf, mut patch) = (Vec::new(), Vec::new(), BString::default());
+        let fs = options.status.fs;
+        for record in recorder.records {
+            let (entry, rela_path) = (record.entry, record.relative_path);
+            let attr_platform = attr_stack
+                .at_entry(rela_path, Some(entry.mode), &objects)
+                .map_err(|err| Error::ReadWorktree {
+                    rela_path: rela_path.to_owned(),
+                    source: err,
+                })?;
+            attr_platform.matching_attributes(&mut attrs);
+            let is_binary_by_attr = match attrs.iter_selected().next().map(|attr| attr.assignment.state) {
+                Some(gix_filter::attributes::StateRef::Unset) => Some(true),
+                Some(gix_filter::attributes::StateRef::Set) => Some(false),
+                _ => None,
+            };
+
+            let (has_old, new) = match record.status {
+                EntryStatus::Conflict(_) | EntryStatus::NeedsUpdate(_) => continue,
+                EntryStatus::IntentToAdd => (false, New::ReadWorktree),
+                EntryStatus::Change(StatusChange::SubmoduleModification(ref status)) => {
+                    let gitlink = Version {
+                        id: entry.id,
+                        mode: entry.mode,
+                        data: &[],
+                        is_binary: false,
+                    };
+                    let action = on_change(Change {
+                        entry,
+                        rela_path,
+                        old: Some(gitlink),
+                        new: Some(gitlink),
+                        patch: None,
+                        submodule: Some(status),
+                    })
+                    .map_err(|err| Error::Callback(err.into()))?;
+                    if action == Action::Cancel {
+                        break;
+                    }
+                    continue;
+                }
+                EntryStatus::Change(StatusChange::Removed)
+                    if entry.flags.contains(gix_index::entry::Flags::INTENT_TO_ADD) =>
+                {
+                    continue
+                }
+                EntryStatus::Change(StatusChange::Removed) => (true, New::Missing),
+                EntryStatus::Change(StatusChange::Type) => (true, New::ReadWorktree),
+                EntryStatus::Change(StatusChange::Modification {
+                    executable_bit_changed,
+                    content_change,
+                    ..
+                }) => {
+                    let mode = if executable_bit_changed {
+                        gix_index::entry::mode::Change::ExecutableBit.apply(entry.mode)
+                    } else {
+                        entry.mode
+                    };
+                    match content_change {
+                        Some(content) => (true, New::Content { mode, content }),
+                        None => (true, New::SameContent { mode }),
+                    }
+                }
+            };
+
+            let old = if has_old {
+                let blob = objects.find_blob(&entry.id, &mut old_buf)?;
+                Some(Version {
+                    id: entry.id,
+                    mode: entry.mode,
+                    data: blob.data,
+                    is_binary: is_binary_by_attr.unwrap_or_else(|| is_binary_buf(blob.data)),
+                })
+            } else {
+                None
+            };
+            let new = match new {
+                New::Missing => None,
+                New::SameContent { mode } => old.map(|old| Version { mode, ..old }),
+                New::Content { mode, content } => {
+                    new_buf = content.data;
+                    Some(Version {
+                        id: content.id,
+                        mode,
+                        data: &new_buf,
+                        is_binary: is_binary_by_attr.unwrap_or_else(|| is_binary_buf(&new_buf)),
+                    })
+                }
+                New::ReadWorktree => {
+                    let path = worktree.join(gix_path::from_bstr(rela_path));
+                    let mode = read_worktree_file(
+                        &path,
+                        rela_path,
+                        fs,
+                        &mut filter,
+                        &mut |_path, out| {
+                            attr_platform.matching_attributes(out);
+                        },
+                        &mut |buf| {
+                            if has_old {
+                                Ok(objects.find_blob(&entry.id, buf).map(|_| Some(()))?)
+                            } else {
+                                Ok(None)
+                            }
+                        },
+                        &mut new_buf,
+                    )?;
+                    Some(Version {
+                        id: gix_object::compute_hash(entry.id.kind(), gix_object::Kind::Blob, &new_buf),
+                        mode,
+                        data: &new_buf,
+                        is_binary: is_binary_by_attr.unwrap_or_else(|| is_binary_buf(&new_buf)),
+                    })
+                }
+            };
+
+            let patch = match options.patch.as_ref() {
+                Some(patch_options) => {
+                    patch.clear();
+                    super::patch::write(&mut patch, rela_path, old.as_ref(), new.as_ref(), patch_options);
+                    Some(patch.as_bstr())
+                }
+                None => None,
+            };
+            let action = on_change(Change {
+                entry,
+                rela_path,
+                old,
+                new,
+                patch,
+                submodule: None,
+            })
+            .map_err(|err| Error::Callback(err.into()))?;
+            if action == Action::Cancel {
+                break;
+            }
+        }
+        Ok(outcome)
+    }
+
+    enum New {
+        Missing,
+        ReadWorktree,
+        SameContent {
+            mode: gix_index::entry::Mode,
+        },
+        Content {
+            mode: gix_index::entry::Mode,
+            content: Content,
+        },
+    }
+
+    /// Read the file at `path` into `buf` in the form it would have in `git`, and return its mode.
+    #[allow(clippy::too_many_arguments)]
+    fn read_worktree_file(
+        path: &Path,
+        rela_path: &BStr,
+        fs: gix_fs::Capabilities,
+        filter: &mut gix_filter::Pipeline,
+        attributes: &mut dyn FnMut(&BStr, &mut gix_filter::attributes::search::Outcome),
+        index_object: &mut gix_filter::pipeline::convert::to_git::IndexObjectFn<'_>,
+        buf: &mut Vec<u8>,
+    ) -> Result<gix_index::entry::Mode, Error> {
+        let io_err = |err| Error::ReadWorktree {
+            rela_path: rela_path.to_owned(),
+            source: err,
+        };
+        buf.clear();
+        let metadata = gix_index::fs::Metadata::from_path_no_follow(path).map_err(io_err)?;
+        if metadata.is_symlink() && fs.symlink {
+            let target = std::fs::read_link(path).map_err(io_err)?;
+            buf.extend_from_slice(&gix_path::into_bstr(target));
+            return Ok(gix_index::entry::Mode::SYMLINK);
+        }
+
+        let file = std::fs::File::open(path).map_err(io_err)?;
+        match filter
+            .convert_to_git(file, gix_path::from_bstr(rela_path).as_ref(), attributes, index_object)
+            .map_err(|err| io_err(std::io::Error::new(std::io::ErrorKind::Other, err)))?
+        {
+            ToGitOutcome::Unchanged(mut file) => file.read_to_end(buf).map(|_| ()),
+            ToGitOutcome::Process(mut stream) => stream.read_to_end(buf).map(|_| ()),
+            ToGitOutcome::Buffer(data) => {
+                buf.extend_from_slice(data);
+                Ok(())
+            }
+        }
+        .map_err(io_err)?;
+        Ok(gix_object::tree::EntryMode::from_file_permissions(metadata.permissions(), fs.executable_bit).into())
+    }
+
+    /// The content of a modified worktree file as it would be stored in `git`.
+    #[derive(Clone)]
+    struct Content {
+        id: ObjectId,
+        data: Vec<u8>,
+    }
+
+    /// Like [`HashEq`](traits::HashEq), but retain the data so it doesn't have to be read again for diffing.
+    #[derive(Clone)]
+    struct CaptureContent;
+
+    impl traits::CompareBlobs for CaptureContent {
+        type Output = Content;
+
+        fn compare_blobs<'a, 'b>(
+            &mut self,
+            entry: &gix_index::Entry,
+            _worktree_blob_size: u64,
+            data: impl ReadData<'a>,
+            _buf: &mut Vec<u8>,
+        ) -> Result<Option<Self::Output>, index_as_worktree::Error> {
+            let mut stream = data.stream_worktree_file()?;
+            let data = match stream.as_bytes() {
+                Some(buf) => buf.to_owned(),
+                None => {
+                    let mut buf = Vec::with_capacity(stream.size().unwrap_or_default() as usize);
+                    stream.read_to_end(&mut buf)?;
+                    buf
+                }
+            };
+            let id = gix_object::compute_hash(entry.id.kind(), gix_object::Kind::Blob, &data);
+            Ok((id != entry.id).then_some(Content { id, data }))
+        }
+    }
+}
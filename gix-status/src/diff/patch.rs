@@ -0,0 +1,143 @@
+use bstr::{BStr, BString, ByteSlice};
+use gix_diff::blob::{
+    intern::InternedInput, sources::byte_lines_with_terminator, unified_diff, whitespace, Algorithm, UnifiedDiff,
+};
+use std::io::Write;
+
+use super::Version;
+
+/// Options to control how patches are produced by [`worktree()`](super::function::worktree()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// The algorithm to use when diffing, typically as configured by `diff.algorithm`.
+    pub algorithm: Algorithm,
+    /// Control the context around each change and the hunk headers.
+    pub unified: unified_diff::Options,
+    /// The amount of hexadecimal characters to show of object ids, similar to `core.abbrev`.
+    pub hex_len: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            algorithm: Algorithm::Myers,
+            unified: Default::default(),
+            hex_len: 7,
+        }
+    }
+}
+
+/// Append the patch to transform `old` into `new` at `rela_path` to `out`, just like `git diff` would.
+pub(super) fn write(
+    out: &mut BString,
+    rela_path: &BStr,
+    old: Option<&Version<'_>>,
+    new: Option<&Version<'_>>,
+    opts: &Options,
+) {
+    let (a, b) = (format!("a/{rela_path}"), format!("b/{rela_path}"));
+    let res = match (old, new) {
+        (Some(old), Some(new)) if is_symlink(old) != is_symlink(new) => {
+            write(out, rela_path, Some(old), None, opts);
+            write(out, rela_path, None, Some(new), opts);
+            Ok(())
+        }
+        (Some(old), Some(new)) => (|| {
+            writeln!(out, "diff --git {a} {b}")?;
+            if old.mode != new.mode {
+                writeln!(
+                    out,
+                    "old mode {:06o}\nnew mode {:06o}",
+                    old.mode.bits(),
+                    new.mode.bits()
+                )?;
+            }
+            if old.id != new.id {
+                write!(
+                    out,
+                    "index {}..{}",
+                    old.id.to_hex_with_len(opts.hex_len),
+                    new.id.to_hex_with_len(opts.hex_len)
+                )?;
+                if old.mode == new.mode {
+                    write!(out, " {:06o}", old.mode.bits())?;
+                }
+                writeln!(out)?;
+                write_body(out, old, new, &a, &b, opts)?;
+            }
+            Ok(())
+        })(),
+        (Some(old), None) => (|| {
+            writeln!(out, "diff --git {a} {b}\ndeleted file mode {:06o}", old.mode.bits())?;
+            writeln!(
+                out,
+                "index {}..{}",
+                old.id.to_hex_with_len(opts.hex_len),
+                null_hex(old, opts)
+            )?;
+            let new = Version {
+                data: &[],
+                is_binary: false,
+                ..*old
+            };
+            write_body(out, old, &new, &a, "/dev/null", opts)
+        })(),
+        (None, Some(new)) => (|| {
+            writeln!(out, "diff --git {a} {b}\nnew file mode {:06o}", new.mode.bits())?;
+            writeln!(
+                out,
+                "index {}..{}",
+                null_hex(new, opts),
+                new.id.to_hex_with_len(opts.hex_len)
+            )?;
+            let old = Version {
+                data: &[],
+                is_binary: false,
+                ..*new
+            };
+            write_body(out, &old, new, "/dev/null", &b, opts)
+        })(),
+        (None, None) => Ok(()),
+    };
+    res.expect("writing to memory never fails");
+}
+
+fn write_body(
+    out: &mut BString,
+    old: &Version<'_>,
+    new: &Version<'_>,
+    old_name: &str,
+    new_name: &str,
+    opts: &Options,
+) -> std::io::Result<()> {
+    if old.is_binary || new.is_binary {
+        return writeln!(out, "Binary files {old_name} and {new_name} differ");
+    }
+    let input = InternedInput::new(
+        whitespace::lines(old.data, opts.unified.whitespace),
+        whitespace::lines(new.data, opts.unified.whitespace),
+    );
+    let hunks = gix_diff::blob::diff(
+        opts.algorithm,
+        &input,
+        UnifiedDiff::from_lines(
+            byte_lines_with_terminator(old.data).collect(),
+            byte_lines_with_terminator(new.data).collect(),
+            BString::default(),
+            opts.unified,
+        ),
+    );
+    if !hunks.is_empty() {
+        writeln!(out, "--- {old_name}\n+++ {new_name}")?;
+        out.extend_from_slice(hunks.as_bytes());
+    }
+    Ok(())
+}
+
+fn is_symlink(v: &Version<'_>) -> bool {
+    v.mode == gix_index::entry::Mode::SYMLINK
+}
+
+fn null_hex(v: &Version<'_>, opts: &Options) -> String {
+    v.id.kind().null().to_hex_with_len(opts.hex_len).to_string()
+}
@@ -0,0 +1,87 @@
+//! Decide how to compare the content of worktree files with their index entries once their stat information changed.
+use gix_index::Entry;
+
+/// Options to control how the content of worktree files is compared to their index entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// Files of at least this size are hashed while reading them directly from disk in chunks, instead of passing them
+    /// through the filter pipeline, if no filters apply to them.
+    ///
+    /// Defaults to 32MiB. Use `u64::MAX` to always use the filter pipeline.
+    pub large_file_threshold: u64,
+    /// If `Some(size)`, files of at least `size` bytes whose stat information changed are considered modified without
+    /// verifying their content. This corresponds to `core.bigFileThreshold`, and is `None` by default.
+    ///
+    /// Note that this only affects [`FastEq`](super::traits::FastEq), as other comparisons must produce a hash.
+    pub skip_verification_threshold: Option<u64>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            large_file_threshold: 32 * 1024 * 1024,
+            skip_verification_threshold: None,
+        }
+    }
+}
+
+/// What the attributes of an index entry say about the filters that apply when converting its worktree file to `git`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AttributeSummary {
+    /// If `true`, filters may change the content of the worktree file, and thus its length, when converting it to `git`.
+    pub filters_may_change_length: bool,
+}
+
+/// The outcome of [`decide()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Decision {
+    /// The worktree file is modified, which is known without looking at its content.
+    Modified,
+    /// The worktree file must be hashed to learn if it changed.
+    Hash(HashStrategy),
+}
+
+/// The way the content of a worktree file is hashed, as returned by [`hash_strategy()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HashStrategy {
+    /// Stream the file through filters and a reused buffer.
+    Stream,
+    /// Read the file directly from disk in chunks and hash it, bypassing the filter pipeline.
+    Direct,
+}
+
+/// Decide how to learn if the worktree file of `worktree_file_size` bytes differs from `entry`, whose stat information
+/// doesn't match the file anymore, knowing the filters that apply to it through `attributes`.
+///
+/// A size that differs from the size recorded in `entry` means the file is modified, unless filters may change its length
+/// or the recorded size was zeroed to mark a racily clean entry. Files at or above [`Options::skip_verification_threshold`]
+/// are considered modified as well. Otherwise, the file has to be hashed according to [`hash_strategy()`].
+pub fn decide(entry: &Entry, worktree_file_size: u64, attributes: AttributeSummary, options: &Options) -> Decision {
+    // make sure to account for racily smudged entries here so that they don't always keep
+    // showing up as modified even after their contents have changed again, to a potentially
+    // unmodified state. That means that we want to ignore stat.size == 0 for non_empty_blobs.
+    let size_differs =
+        u64::from(entry.stat.size) != worktree_file_size && (entry.id.is_empty_blob() || entry.stat.size != 0);
+    if size_differs && !attributes.filters_may_change_length {
+        return Decision::Modified;
+    }
+    if options
+        .skip_verification_threshold
+        .into_iter()
+        .any(|threshold| worktree_file_size >= threshold)
+    {
+        return Decision::Modified;
+    }
+    Decision::Hash(hash_strategy(worktree_file_size, attributes, options))
+}
+
+/// Decide how to hash a worktree file of `worktree_file_size` bytes, knowing the filters that apply to it through `attributes`.
+///
+/// Files to which no filter applies are read directly if they are at least [`Options::large_file_threshold`] bytes in size.
+pub fn hash_strategy(worktree_file_size: u64, attributes: AttributeSummary, options: &Options) -> HashStrategy {
+    if !attributes.filters_may_change_length && worktree_file_size >= options.large_file_threshold {
+        HashStrategy::Direct
+    } else {
+        HashStrategy::Stream
+    }
+}
@@ -1,8 +1,9 @@
 use std::{
     io,
+    io::Read,
     path::Path,
     slice::Chunks,
-    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
 };
 
 use bstr::BStr;
@@ -14,7 +15,7 @@ use gix_object::FindExt;
 use crate::index_as_worktree::Context;
 use crate::{
     index_as_worktree::{
-        traits,
+        content, traits,
         traits::{read_data::Stream, CompareBlobs, SubmoduleStatus},
         types::{Error, Options},
         Change, Conflict, EntryStatus, Outcome, VisitEntry,
@@ -25,7 +26,8 @@ use crate::{
 /// Calculates the changes that need to be applied to an `index` to match the state of the `worktree` and makes them
 /// observable in `collector`, along with information produced by `compare` which gets to see blobs that may have changes, and
 /// `submodule` which can take a look at submodules in detail to produce status information (BASE version if its conflicting).
-/// `options` are used to configure the operation.
+/// `options` are used to configure the operation, with nanoseconds only being compared if the `index` has them,
+/// see [`for_index()`](gix_index::entry::stat::Options::for_index()).
 ///
 /// Note `worktree` must be the root path of the worktree, not a path inside of the worktree.
 ///
@@ -64,7 +66,7 @@ pub fn index_as_worktree<'index, T, U, Find, E>(
         filter,
         should_interrupt,
     }: Context<'_>,
-    options: Options,
+    mut options: Options,
 ) -> Result<Outcome, Error>
 where
     T: Send,
@@ -76,6 +78,7 @@ where
     // (modified at or after the last index update) during the index update we then set those
     // entries size to 0 (see below) to ensure they keep showing up as racy and reset the timestamp.
     let timestamp = index.timestamp();
+    options.stat = options.stat.for_index(index);
     let (chunk_size, thread_limit, _) = gix_features::parallel::optimize_chunk_size_and_thread_limit(
         500, // just like git
         index.entries().len().into(),
@@ -441,6 +444,7 @@ impl<'index> State<'_, 'index> {
             odb_bytes: self.odb_bytes,
         };
         let content_change = diff.compare_blobs(entry, metadata.len(), fetch_data, &mut self.buf2)?;
+        let crlf_round_trip = self.filter.take_crlf_round_trip();
         // This file is racy clean! Set the size to 0 so we keep detecting this as the file is updated.
         if content_change.is_some() || executable_bit_changed {
             let set_entry_stat_size_zero = content_change.is_some() && racy_clean;
@@ -449,6 +453,7 @@ impl<'index> State<'_, 'index> {
                     executable_bit_changed,
                     content_change,
                     set_entry_stat_size_zero,
+                    crlf_round_trip,
                 }
                 .into(),
             ))
@@ -524,7 +529,7 @@ where
     fn stream_worktree_file(self) -> Result<Stream<'a>, Error> {
         self.buf.clear();
         // symlinks are only stored as actual symlinks if the FS supports it otherwise they are just
-        // normal files with their content equal to the linked path (so can be read normally)
+        // normal files with their content equal to the linked path (so can be read normally, but without filters)
         //
         let is_symlink = self.entry.mode == gix_index::entry::Mode::SYMLINK;
         // TODO: what to do about precompose unicode and ignore_case for symlinks
@@ -539,6 +544,15 @@ where
                 bytes: None,
                 len: None,
             }
+        } else if is_symlink {
+            // The link target is stored as is and never subject to filters.
+            std::fs::File::open(self.path)?.read_to_end(self.buf)?;
+            self.worktree_bytes.fetch_add(self.buf.len() as u64, Ordering::Relaxed);
+            Stream {
+                inner: ToGitOutcome::Buffer(self.buf),
+                bytes: None,
+                len: None,
+            }
         } else {
             self.buf.clear();
             let platform = self
@@ -570,6 +584,51 @@ where
         self.worktree_reads.fetch_add(1, Ordering::Relaxed);
         Ok(out)
     }
+
+    fn attribute_summary(&mut self) -> Result<content::AttributeSummary, Error> {
+        if self.entry.mode == gix_index::entry::Mode::SYMLINK && self.options.fs.symlink {
+            return Ok(content::AttributeSummary::default());
+        }
+        let platform = self
+            .attr_stack
+            .at_entry(self.rela_path, Some(self.entry.mode), &self.objects)?;
+        let filters_may_change_length = self
+            .filter
+            .may_change_when_converting_to_git(self.path, &mut |_path, attrs| {
+                platform.matching_attributes(attrs);
+            })
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        Ok(content::AttributeSummary {
+            filters_may_change_length,
+        })
+    }
+
+    fn content_options(&self) -> content::Options {
+        self.options.content
+    }
+
+    fn hash_worktree_file_directly(self, kind: gix_hash::Kind) -> Result<gix_hash::ObjectId, Error> {
+        if self.entry.mode == gix_index::entry::Mode::SYMLINK && self.options.fs.symlink {
+            let symlink_path = gix_path::into_bstr(std::fs::read_link(self.path)?);
+            self.worktree_reads.fetch_add(1, Ordering::Relaxed);
+            self.worktree_bytes
+                .fetch_add(symlink_path.len() as u64, Ordering::Relaxed);
+            return Ok(gix_object::compute_hash(kind, gix_object::Kind::Blob, &symlink_path));
+        }
+        let mut file = std::fs::File::open(self.path)?;
+        let len = file.metadata()?.len();
+        let id = gix_object::compute_stream_hash(
+            kind,
+            gix_object::Kind::Blob,
+            &mut file,
+            len,
+            &mut gix_features::progress::Discard,
+            &AtomicBool::default(),
+        )?;
+        self.worktree_reads.fetch_add(1, Ordering::Relaxed);
+        self.worktree_bytes.fetch_add(len, Ordering::Relaxed);
+        Ok(id)
+    }
 }
 
 struct OffsetIter<'a, T> {
@@ -5,7 +5,7 @@ use gix_hash::ObjectId;
 use gix_index as index;
 use index::Entry;
 
-use crate::index_as_worktree::Error;
+use crate::index_as_worktree::{content, Error};
 
 /// Compares the content of two blobs in some way.
 pub trait CompareBlobs {
@@ -47,6 +47,31 @@ pub trait ReadData<'a> {
 
     /// Stream a worktree file in such a manner that its content matches what would be put into git.
     fn stream_worktree_file(self) -> Result<read_data::Stream<'a>, Error>;
+
+    /// Return what the attributes of the entry say about the filters that apply to its worktree file.
+    ///
+    /// The default implementation claims that filters don't change the length of the file.
+    fn attribute_summary(&mut self) -> Result<content::AttributeSummary, Error> {
+        Ok(content::AttributeSummary::default())
+    }
+
+    /// Return the options that control how the content of the worktree file should be compared.
+    ///
+    /// The default implementation returns the default options.
+    fn content_options(&self) -> content::Options {
+        content::Options::default()
+    }
+
+    /// Hash the worktree file as blob of `kind` by reading it directly, which is only correct if no filters apply to it.
+    ///
+    /// The default implementation hashes the [stream of the worktree file](Self::stream_worktree_file()).
+    fn hash_worktree_file_directly(self, kind: gix_hash::Kind) -> Result<ObjectId, Error>
+    where
+        Self: Sized,
+    {
+        let mut buf = Vec::new();
+        hash_stream(kind, self.stream_worktree_file()?, &mut buf)
+    }
 }
 
 ///
@@ -96,6 +121,9 @@ pub mod read_data {
 
 /// Compares to blobs by comparing their size and oid, and only looks at the file if
 /// the size matches, therefore it's very fast.
+///
+/// See [`content::decide()`] for details on when files are considered modified without looking at them, and how
+/// they are hashed otherwise.
 #[derive(Clone)]
 pub struct FastEq;
 
@@ -107,18 +135,20 @@ impl CompareBlobs for FastEq {
         &mut self,
         entry: &Entry,
         worktree_file_size: u64,
-        data: impl ReadData<'a>,
+        mut data: impl ReadData<'a>,
         buf: &mut Vec<u8>,
     ) -> Result<Option<Self::Output>, Error> {
-        // make sure to account for racily smudged entries here so that they don't always keep
-        // showing up as modified even after their contents have changed again, to a potentially
-        // unmodified state. That means that we want to ignore stat.size == 0 for non_empty_blobs.
-        if entry.stat.size as u64 != worktree_file_size && (entry.id.is_empty_blob() || entry.stat.size != 0) {
-            return Ok(Some(()));
+        let attributes = data.attribute_summary()?;
+        match content::decide(entry, worktree_file_size, attributes, &data.content_options()) {
+            content::Decision::Modified => Ok(Some(())),
+            content::Decision::Hash(content::HashStrategy::Direct) => {
+                let file_hash = data.hash_worktree_file_directly(entry.id.kind())?;
+                Ok((entry.id != file_hash).then_some(()))
+            }
+            content::Decision::Hash(content::HashStrategy::Stream) => {
+                stream_hash(entry, data, buf).map(|opt| opt.map(|_| ()))
+            }
         }
-        HashEq
-            .compare_blobs(entry, worktree_file_size, data, buf)
-            .map(|opt| opt.map(|_| ()))
     }
 }
 
@@ -126,6 +156,7 @@ impl CompareBlobs for FastEq {
 ///
 /// Same as [`FastEq`] but does not contain a fast path for files with mismatched files and
 /// therefore always returns an OID that can be reused later.
+/// Large files are hashed according to [`content::hash_strategy()`].
 #[derive(Clone)]
 pub struct HashEq;
 
@@ -135,33 +166,44 @@ impl CompareBlobs for HashEq {
     fn compare_blobs<'a, 'b>(
         &mut self,
         entry: &Entry,
-        _worktree_blob_size: u64,
-        data: impl ReadData<'a>,
+        worktree_file_size: u64,
+        mut data: impl ReadData<'a>,
         buf: &mut Vec<u8>,
     ) -> Result<Option<Self::Output>, Error> {
-        let mut stream = data.stream_worktree_file()?;
-        match stream.as_bytes() {
-            Some(buffer) => {
-                let file_hash = gix_object::compute_hash(entry.id.kind(), gix_object::Kind::Blob, buffer);
-                Ok((entry.id != file_hash).then_some(file_hash))
-            }
-            None => {
-                let file_hash = match stream.size() {
-                    None => {
-                        stream.read_to_end(buf)?;
-                        gix_object::compute_hash(entry.id.kind(), gix_object::Kind::Blob, buf)
-                    }
-                    Some(len) => gix_object::compute_stream_hash(
-                        entry.id.kind(),
-                        gix_object::Kind::Blob,
-                        &mut stream,
-                        len,
-                        &mut gix_features::progress::Discard,
-                        &AtomicBool::default(),
-                    )?,
-                };
+        let attributes = data.attribute_summary()?;
+        match content::hash_strategy(worktree_file_size, attributes, &data.content_options()) {
+            content::HashStrategy::Direct => {
+                let file_hash = data.hash_worktree_file_directly(entry.id.kind())?;
                 Ok((entry.id != file_hash).then_some(file_hash))
             }
+            content::HashStrategy::Stream => stream_hash(entry, data, buf),
         }
     }
 }
+
+/// Hash the worktree file of `data` after converting it to `git`, and return its hash if it differs from the one of `entry`.
+fn stream_hash<'a>(entry: &Entry, data: impl ReadData<'a>, buf: &mut Vec<u8>) -> Result<Option<ObjectId>, Error> {
+    let file_hash = hash_stream(entry.id.kind(), data.stream_worktree_file()?, buf)?;
+    Ok((entry.id != file_hash).then_some(file_hash))
+}
+
+/// Hash all data in `stream` as blob of `kind`, using `buf` if it has to be read into memory.
+fn hash_stream(kind: gix_hash::Kind, mut stream: read_data::Stream<'_>, buf: &mut Vec<u8>) -> Result<ObjectId, Error> {
+    Ok(match stream.as_bytes() {
+        Some(buffer) => gix_object::compute_hash(kind, gix_object::Kind::Blob, buffer),
+        None => match stream.size() {
+            None => {
+                stream.read_to_end(buf)?;
+                gix_object::compute_hash(kind, gix_object::Kind::Blob, buf)
+            }
+            Some(len) => gix_object::compute_stream_hash(
+                kind,
+                gix_object::Kind::Blob,
+                &mut stream,
+                len,
+                &mut gix_features::progress::Discard,
+                &AtomicBool::default(),
+            )?,
+        },
+    })
+}
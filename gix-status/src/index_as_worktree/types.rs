@@ -31,6 +31,8 @@ pub struct Options {
     pub thread_limit: Option<usize>,
     /// Options that control how stat comparisons are made when checking if a file is fresh.
     pub stat: gix_index::entry::stat::Options,
+    /// Options that control how the content of files is compared once their stat information changed.
+    pub content: crate::index_as_worktree::content::Options,
 }
 
 /// The context for [index_as_worktree()`](crate::index_as_worktree()).
@@ -120,6 +122,11 @@ pub enum Change<T = (), U = ()> {
         /// reading it from disk to hash it. It's a performance optimization and not doing so won't change the correctness
         /// of the operation.
         set_entry_stat_size_zero: bool,
+        /// If the worktree file was read and its line endings were converted for comparison, this is the
+        /// [round-trip safety](gix_filter::eol::convert_to_git::RoundTrip) of that conversion.
+        ///
+        /// This is the information `git` presents in warnings like *CRLF will be replaced by LF*, as controlled by `core.safecrlf`.
+        crlf_round_trip: Option<gix_filter::eol::convert_to_git::RoundTrip>,
     },
     /// A submodule is initialized and checked out, and there was modification to either:
     ///
@@ -4,6 +4,7 @@
 //! * index and working tree
 //! * index and tree
 //! * find untracked files
+//! * index and working tree, along with the content of both versions (`git diff`)
 //!
 //! While also being able to check check if the working tree is dirty, quickly.
 //!
@@ -23,6 +24,9 @@ pub mod index_as_worktree_with_renames;
 #[cfg(feature = "worktree-rewrites")]
 pub use index_as_worktree_with_renames::function::index_as_worktree_with_renames;
 
+#[cfg(feature = "worktree-diff")]
+pub mod diff;
+
 /// A stack that validates we are not going through a symlink in a way that is read-only.
 ///
 /// It can efficiently validate paths when these are queried in sort-order, which leads to each component
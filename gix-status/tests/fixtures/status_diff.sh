@@ -0,0 +1,60 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+cat <<EOT >modified
+fn first() {
+  1
+  2
+  3
+  4
+  5
+  6
+  7
+  8
+  9
+  10
+}
+EOT
+printf 'no newline' > no-newline
+printf 'same content' > executable-bit
+printf 'old content\n' > executable-bit-and-content
+printf 'removed\nfile\n' > removed
+printf 'binary\0data\n' > binary
+printf 'text\n' > attr-binary.dat
+echo '*.dat -diff' > .gitattributes
+printf 'regular file\n' > file-to-symlink
+printf 'unchanged\n' > unchanged
+
+git add -A
+git commit -q -m "initial"
+
+printf 'added\ncontent\n' > intent-to-add
+touch intent-to-add-empty
+git add --intent-to-add intent-to-add intent-to-add-empty
+
+cat <<EOT >modified
+fn first() {
+  1
+  two
+  3
+  4
+  5
+  6
+  7
+  8
+  9
+  ten
+}
+EOT
+printf 'no newline, changed' > no-newline
+chmod +x executable-bit executable-bit-and-content
+printf 'new content\n' > executable-bit-and-content
+rm removed
+printf 'binary\0data, changed\n' > binary
+printf 'changed text\n' > attr-binary.dat
+rm file-to-symlink && ln -s unchanged file-to-symlink
+touch unchanged
+
+git diff > baseline.diff
@@ -0,0 +1,16 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+echo "*.txt text eol=lf" > .gitattributes
+printf 'a\nb\n' > lf.txt
+printf 'a\nb\n' > mixed.txt
+printf 'a\rb\n' > lone-cr.txt
+
+git add -A
+git commit -m "Commit"
+
+printf 'a\nb\n' > lf.txt
+printf 'a\r\nb\nc\n' > mixed.txt
+printf 'a\rb\r\nc\n' > lone-cr.txt
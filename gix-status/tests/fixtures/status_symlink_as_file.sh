@@ -0,0 +1,13 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config core.symlinks false
+
+# A filter that would alter the link target if it was applied.
+echo "* ident" > .gitattributes
+printf '$Id: target$' > link
+
+git add .gitattributes
+git update-index --add --cacheinfo "120000,$(printf '$Id: target$' | git hash-object -w --stdin),link"
+git commit -m "Commit"
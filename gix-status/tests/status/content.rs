@@ -0,0 +1,72 @@
+use gix_status::index_as_worktree::content::{
+    decide, hash_strategy, AttributeSummary, Decision, HashStrategy, Options,
+};
+
+fn entry_of_size(size: u32) -> gix_index::Entry {
+    let index = gix_index::File::at(
+        crate::fixture_path("status_changed").join(".git/index"),
+        gix_hash::Kind::Sha1,
+        false,
+        Default::default(),
+    )
+    .unwrap();
+    let mut entry = index.entries()[0].clone();
+    entry.stat.size = size;
+    entry.id = gix_hash::ObjectId::null(gix_hash::Kind::Sha1);
+    entry
+}
+
+const NO_FILTERS: AttributeSummary = AttributeSummary {
+    filters_may_change_length: false,
+};
+const FILTERS: AttributeSummary = AttributeSummary {
+    filters_may_change_length: true,
+};
+
+#[test]
+fn size_mismatch_is_a_modification_unless_filters_may_change_the_length() {
+    let opts = Options::default();
+    assert_eq!(decide(&entry_of_size(10), 11, NO_FILTERS, &opts), Decision::Modified);
+    assert_eq!(
+        decide(&entry_of_size(10), 11, FILTERS, &opts),
+        Decision::Hash(HashStrategy::Stream)
+    );
+    assert_eq!(
+        decide(&entry_of_size(10), 10, NO_FILTERS, &opts),
+        Decision::Hash(HashStrategy::Stream)
+    );
+    assert_eq!(
+        decide(&entry_of_size(0), 10, NO_FILTERS, &opts),
+        Decision::Hash(HashStrategy::Stream),
+        "a zero size marks racily clean entries, so their content has to be checked"
+    );
+}
+
+#[test]
+fn skip_verification_threshold_considers_large_files_modified() {
+    let opts = Options {
+        skip_verification_threshold: Some(10),
+        ..Default::default()
+    };
+    assert_eq!(decide(&entry_of_size(10), 10, NO_FILTERS, &opts), Decision::Modified);
+    assert_eq!(decide(&entry_of_size(10), 10, FILTERS, &opts), Decision::Modified);
+    assert_eq!(
+        decide(&entry_of_size(9), 9, NO_FILTERS, &opts),
+        Decision::Hash(HashStrategy::Stream)
+    );
+}
+
+#[test]
+fn large_files_are_read_directly_unless_filters_apply() {
+    let opts = Options {
+        large_file_threshold: 10,
+        ..Default::default()
+    };
+    assert_eq!(hash_strategy(9, NO_FILTERS, &opts), HashStrategy::Stream);
+    assert_eq!(hash_strategy(10, NO_FILTERS, &opts), HashStrategy::Direct);
+    assert_eq!(hash_strategy(10, FILTERS, &opts), HashStrategy::Stream);
+    assert_eq!(
+        decide(&entry_of_size(10), 10, NO_FILTERS, &opts),
+        Decision::Hash(HashStrategy::Direct)
+    );
+}
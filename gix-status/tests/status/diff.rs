@@ -0,0 +1,136 @@
+use std::sync::atomic::AtomicBool;
+
+use bstr::{BString, ByteSlice};
+use gix_status::{
+    diff::{Action, Options, PatchOptions},
+    index_as_worktree::Context,
+};
+
+use crate::{fixture_path, status::index_as_worktree::SubmoduleStatusMock};
+
+/// The path along with the old and new content of each change.
+type Changes = Vec<(BString, Option<BString>, Option<BString>)>;
+
+fn diff_worktree(name: &str, pathspecs: &[&str], patch: Option<PatchOptions>) -> (Changes, BString) {
+    diff_worktree_at(fixture_path(name), pathspecs, patch, false)
+}
+
+fn diff_worktree_at(
+    worktree: std::path::PathBuf,
+    pathspecs: &[&str],
+    patch: Option<PatchOptions>,
+    submodule_dirty: bool,
+) -> (Changes, BString) {
+    let git_dir = worktree.join(".git");
+    let index = gix_index::File::at(git_dir.join("index"), gix_hash::Kind::Sha1, false, Default::default()).unwrap();
+    let objects = gix_odb::at(git_dir.join("objects")).unwrap().into_arc().unwrap();
+    let search = gix_pathspec::Search::from_specs(
+        super::index_as_worktree::to_pathspecs(pathspecs),
+        None,
+        std::path::Path::new(""),
+    )
+    .expect("valid specs can be normalized");
+    let stack = gix_worktree::Stack::from_state_and_ignore_case(
+        worktree.clone(),
+        false,
+        gix_worktree::stack::State::AttributesStack(gix_worktree::stack::state::Attributes::new(
+            Default::default(),
+            None,
+            gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+            Default::default(),
+        )),
+        &index,
+        index.path_backing(),
+    );
+
+    let mut changes = Vec::new();
+    let mut patches = BString::default();
+    gix_status::diff::worktree(
+        &index,
+        &worktree,
+        objects,
+        SubmoduleStatusMock { dirty: submodule_dirty },
+        &mut gix_features::progress::Discard,
+        Context {
+            pathspec: search,
+            stack,
+            filter: Default::default(),
+            should_interrupt: &AtomicBool::default(),
+        },
+        Options {
+            status: gix_status::index_as_worktree::Options {
+                fs: gix_fs::Capabilities::probe(&git_dir),
+                stat: super::index_as_worktree::TEST_OPTIONS,
+                ..Default::default()
+            },
+            patch,
+        },
+        |change| -> Result<_, std::convert::Infallible> {
+            assert_eq!(
+                change.submodule.is_some(),
+                change.entry.mode == gix_index::entry::Mode::COMMIT,
+                "only submodules carry a submodule status"
+            );
+            changes.push((
+                change.rela_path.to_owned(),
+                change.old.map(|v| v.data.into()),
+                change.new.map(|v| v.data.into()),
+            ));
+            if let Some(patch) = change.patch {
+                patches.extend_from_slice(patch);
+            }
+            Ok(Action::Continue)
+        },
+    )
+    .unwrap();
+    (changes, patches)
+}
+
+#[test]
+fn patches_match_git_diff() {
+    let (changes, patches) = diff_worktree("status_diff", &[], Some(PatchOptions::default()));
+    let baseline = std::fs::read(fixture_path("status_diff").join("baseline.diff")).unwrap();
+    pretty_assertions::assert_eq!(patches.to_str_lossy(), baseline.to_str_lossy());
+    assert_eq!(
+        changes.len(),
+        10,
+        "the typechange is one change with two patches, and unchanged files don't show up"
+    );
+}
+
+#[test]
+fn content_of_both_versions_is_provided_without_patches() {
+    let (changes, patches) = diff_worktree("status_diff", &["no-newline", "removed", "intent-to-add"], None);
+    assert!(patches.is_empty(), "no patches are produced if not requested");
+    assert_eq!(
+        changes,
+        [
+            ("intent-to-add".into(), None, Some("added\ncontent\n".into())),
+            (
+                "no-newline".into(),
+                Some("no newline".into()),
+                Some("no newline, changed".into())
+            ),
+            ("removed".into(), Some("removed\nfile\n".into()), None),
+        ]
+    );
+}
+
+#[test]
+fn modified_submodules_are_passed_with_their_status() {
+    let (changes, patches) = diff_worktree_at(
+        fixture_path("status_submodule").join("no-change"),
+        &[],
+        Some(PatchOptions::default()),
+        true,
+    );
+    assert_eq!(
+        changes,
+        [("m1".into(), Some("".into()), Some("".into()))],
+        "the gitlink is all there is, and the submodule status describes the modification"
+    );
+    assert!(patches.is_empty(), "submodules don't have a patch");
+
+    let (changes, _patches) = diff_worktree_at(fixture_path("status_submodule").join("no-change"), &[], None, false);
+    assert!(changes.is_empty(), "unmodified submodules don't show up");
+}
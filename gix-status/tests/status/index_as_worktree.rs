@@ -11,6 +11,7 @@ use gix_status::index_as_worktree::Context;
 use gix_status::{
     index_as_worktree,
     index_as_worktree::{
+        content,
         traits::{CompareBlobs, FastEq, ReadData, SubmoduleStatus},
         Change as WorktreeChange, Conflict, EntryStatus as WorktreeEntryStatus, Options, Outcome, Record, Recorder,
     },
@@ -42,23 +43,39 @@ fn fixture_with_index(
     prepare_index: impl FnMut(&mut gix_index::State),
     expected_status: &[Expectation<'_>],
 ) -> Outcome {
-    fixture_filtered_detailed(name, "", &[], expected_status, prepare_index, false)
+    fixture_filtered_detailed(name, "", &[], expected_status, prepare_index, false, |_| {})
+}
+
+fn fixture_with_fs_capabilities(
+    name: &str,
+    prepare_fs: impl FnOnce(&mut gix_fs::Capabilities),
+    expected_status: &[Expectation<'_>],
+) -> Outcome {
+    fixture_filtered_detailed(name, "", &[], expected_status, |_| {}, false, prepare_fs)
 }
 
 fn submodule_fixture(name: &str, expected_status: &[Expectation<'_>]) -> Outcome {
-    fixture_filtered_detailed("status_submodule", name, &[], expected_status, |_| {}, false)
+    fixture_filtered_detailed("status_submodule", name, &[], expected_status, |_| {}, false, |_| {})
 }
 
 fn conflict_fixture(name: &str, expected_status: &[Expectation<'_>]) -> Outcome {
-    fixture_filtered_detailed("conflicts", name, &[], expected_status, |_| {}, false)
+    fixture_filtered_detailed("conflicts", name, &[], expected_status, |_| {}, false, |_| {})
 }
 
 fn submodule_fixture_status(name: &str, expected_status: &[Expectation<'_>], submodule_dirty: bool) -> Outcome {
-    fixture_filtered_detailed("status_submodule", name, &[], expected_status, |_| {}, submodule_dirty)
+    fixture_filtered_detailed(
+        "status_submodule",
+        name,
+        &[],
+        expected_status,
+        |_| {},
+        submodule_dirty,
+        |_| {},
+    )
 }
 
 fn fixture_filtered(name: &str, pathspecs: &[&str], expected_status: &[Expectation<'_>]) -> Outcome {
-    fixture_filtered_detailed(name, "", pathspecs, expected_status, |_| {}, false)
+    fixture_filtered_detailed(name, "", pathspecs, expected_status, |_| {}, false, |_| {})
 }
 
 fn fixture_filtered_detailed(
@@ -68,6 +85,7 @@ fn fixture_filtered_detailed(
     expected_status: &[Expectation<'_>],
     mut prepare_index: impl FnMut(&mut gix_index::State),
     submodule_dirty: bool,
+    prepare_fs: impl FnOnce(&mut gix_fs::Capabilities),
 ) -> Outcome {
     // This can easily happen in some fixtures, which can cause flakiness. It's time-dependent after all.
     fn ignore_racyclean(mut out: Outcome) -> Outcome {
@@ -97,10 +115,17 @@ fn fixture_filtered_detailed(
     let stack = gix_worktree::Stack::from_state_and_ignore_case(
         worktree.clone(),
         false,
-        gix_worktree::stack::State::AttributesStack(Default::default()),
+        gix_worktree::stack::State::AttributesStack(gix_worktree::stack::state::Attributes::new(
+            Default::default(),
+            None,
+            gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+            Default::default(),
+        )),
         &index,
         index.path_backing(),
     );
+    let mut fs = gix_fs::Capabilities::probe(&git_dir);
+    prepare_fs(&mut fs);
     let outcome = index_as_worktree(
         &index,
         &worktree,
@@ -116,7 +141,7 @@ fn fixture_filtered_detailed(
             should_interrupt: &AtomicBool::default(),
         },
         Options {
-            fs: gix_fs::Capabilities::probe(&git_dir),
+            fs,
             stat: TEST_OPTIONS,
             ..Options::default()
         },
@@ -147,10 +172,12 @@ fn deracify_status(status: EntryStatus) -> Option<EntryStatus> {
                 executable_bit_changed,
                 content_change,
                 set_entry_stat_size_zero: _,
+                crlf_round_trip,
             } => Change::Modification {
                 executable_bit_changed,
                 content_change,
                 set_entry_stat_size_zero: false,
+                crlf_round_trip,
             },
             Change::SubmoduleModification(c) => Change::SubmoduleModification(c),
         }
@@ -431,7 +458,8 @@ fn refresh() {
                     Change::Modification {
                         executable_bit_changed: true,
                         content_change: None,
-                        set_entry_stat_size_zero: false
+                        set_entry_stat_size_zero: false,
+                        crlf_round_trip: None
                     }
                     .into(),
                 ),
@@ -441,7 +469,8 @@ fn refresh() {
                     Change::Modification {
                         executable_bit_changed: false,
                         content_change: Some(()),
-                        set_entry_stat_size_zero: false
+                        set_entry_stat_size_zero: false,
+                        crlf_round_trip: None
                     }
                     .into(),
                 ),
@@ -452,7 +481,8 @@ fn refresh() {
                     Change::Modification {
                         executable_bit_changed: true,
                         content_change: Some(()),
-                        set_entry_stat_size_zero: false
+                        set_entry_stat_size_zero: false,
+                        crlf_round_trip: None
                     }
                     .into(),
                 ),
@@ -465,7 +495,8 @@ fn refresh() {
                     Change::Modification {
                         executable_bit_changed: false,
                         content_change: Some(()),
-                        set_entry_stat_size_zero: false
+                        set_entry_stat_size_zero: false,
+                        crlf_round_trip: None
                     }
                     .into(),
                 ),
@@ -475,7 +506,8 @@ fn refresh() {
                     Change::Modification {
                         executable_bit_changed: false,
                         content_change: Some(()),
-                        set_entry_stat_size_zero: false
+                        set_entry_stat_size_zero: false,
+                        crlf_round_trip: None
                     }
                     .into(),
                 ),
@@ -485,7 +517,8 @@ fn refresh() {
                     Change::Modification {
                         executable_bit_changed: false,
                         content_change: Some(()),
-                        set_entry_stat_size_zero: false
+                        set_entry_stat_size_zero: false,
+                        crlf_round_trip: None
                     }
                     .into(),
                 )
@@ -514,6 +547,7 @@ fn modified() {
                     executable_bit_changed: true,
                     content_change: None,
                     set_entry_stat_size_zero: false,
+                    crlf_round_trip: None,
                 }
                 .into(),
             ),
@@ -524,6 +558,7 @@ fn modified() {
                     executable_bit_changed: false,
                     content_change: Some(()),
                     set_entry_stat_size_zero: false,
+                    crlf_round_trip: None,
                 }
                 .into(),
             ),
@@ -535,6 +570,7 @@ fn modified() {
                     executable_bit_changed: true,
                     content_change: Some(()),
                     set_entry_stat_size_zero: false,
+                    crlf_round_trip: None,
                 }
                 .into(),
             ),
@@ -548,6 +584,7 @@ fn modified() {
                     executable_bit_changed: false,
                     content_change: Some(()),
                     set_entry_stat_size_zero: false,
+                    crlf_round_trip: None,
                 }
                 .into(),
             ),
@@ -558,6 +595,7 @@ fn modified() {
                     executable_bit_changed: false,
                     content_change: Some(()),
                     set_entry_stat_size_zero: false,
+                    crlf_round_trip: None,
                 }
                 .into(),
             ),
@@ -568,6 +606,7 @@ fn modified() {
                     executable_bit_changed: false,
                     content_change: Some(()),
                     set_entry_stat_size_zero: false,
+                    crlf_round_trip: None,
                 }
                 .into(),
             ),
@@ -576,6 +615,67 @@ fn modified() {
     assert_eq!(actual_outcome, expected_outcome,);
 }
 
+#[test]
+fn modified_with_irreversible_line_ending_conversion() {
+    let expected_outcome = Outcome {
+        entries_to_process: 4,
+        entries_processed: 4,
+        symlink_metadata_calls: 4,
+        ..Default::default()
+    };
+    let actual_outcome = fixture_with_index(
+        "status_eol",
+        |index| index.entries_mut().iter_mut().for_each(|e| e.stat = Default::default()),
+        &[
+            (
+                BStr::new(b"lone-cr.txt"),
+                2,
+                Change::Modification {
+                    executable_bit_changed: false,
+                    content_change: Some(()),
+                    set_entry_stat_size_zero: false,
+                    crlf_round_trip: Some(gix_filter::eol::convert_to_git::RoundTrip::IrreversibleCrLfToLf),
+                }
+                .into(),
+            ),
+            (
+                BStr::new(b"mixed.txt"),
+                3,
+                Change::Modification {
+                    executable_bit_changed: false,
+                    content_change: Some(()),
+                    set_entry_stat_size_zero: false,
+                    crlf_round_trip: Some(gix_filter::eol::convert_to_git::RoundTrip::IrreversibleCrLfToLf),
+                }
+                .into(),
+            ),
+        ],
+    );
+    assert_eq!(actual_outcome, expected_outcome);
+}
+
+#[test]
+fn symlinks_checked_out_as_files_compare_their_unfiltered_content() {
+    let expected_outcome = Outcome {
+        entries_to_process: 2,
+        entries_processed: 2,
+        symlink_metadata_calls: 2,
+        ..Default::default()
+    };
+    let actual_outcome = fixture_with_fs_capabilities("status_symlink_as_file", |fs| fs.symlink = false, &[]);
+    assert_eq!(actual_outcome, expected_outcome);
+
+    let actual_outcome = fixture_with_fs_capabilities(
+        "status_symlink_as_file",
+        |fs| fs.symlink = true,
+        &[(BStr::new(b"link"), 1, Change::Type.into())],
+    );
+    assert_eq!(
+        actual_outcome, expected_outcome,
+        "if symlinks are supported, a file isn't a symlink"
+    );
+}
+
 #[test]
 fn racy_git() {
     let timestamp = 940040400;
@@ -677,7 +777,7 @@ fn racy_git() {
         SubmoduleStatusMock { dirty: false },
         gix_object::find::Never,
         &mut gix_features::progress::Discard,
-        ctx,
+        ctx.clone(),
         Options {
             fs,
             stat: TEST_OPTIONS,
@@ -710,12 +810,54 @@ fn racy_git() {
             Change::Modification {
                 executable_bit_changed: false,
                 content_change: Some(()),
-                set_entry_stat_size_zero: false
+                set_entry_stat_size_zero: false,
+                crlf_round_trip: None
             }
             .into(),
         )],
         "racy change is correctly detected"
     );
+
+    let mut recorder = Recorder::default();
+    let out = index_as_worktree(
+        &index,
+        worktree,
+        &mut recorder,
+        FastEq,
+        SubmoduleStatusMock { dirty: false },
+        gix_object::find::Never,
+        &mut gix_features::progress::Discard,
+        ctx,
+        Options {
+            fs,
+            stat: TEST_OPTIONS,
+            content: content::Options {
+                large_file_threshold: 0,
+                skip_verification_threshold: None,
+            },
+            ..Options::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        out.worktree_bytes, 3,
+        "directly read files are accounted for just like streamed ones"
+    );
+    assert_eq!(
+        records_to_tuple(recorder.records),
+        &[(
+            BStr::new(b"content"),
+            0,
+            Change::Modification {
+                executable_bit_changed: false,
+                content_change: Some(()),
+                set_entry_stat_size_zero: false,
+                crlf_round_trip: None
+            }
+            .into(),
+        )],
+        "the racy change is also detected when hashing files directly"
+    );
 }
 
 fn default_pathspec() -> gix_pathspec::Search {
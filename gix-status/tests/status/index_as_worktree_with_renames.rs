@@ -110,6 +110,7 @@ fn changed_and_untracked() {
                 executable_bit_changed: false,
                 content_change: Some(()),
                 set_entry_stat_size_zero: false,
+                crlf_round_trip: None,
             }),
         }],
         None,
@@ -134,6 +135,7 @@ fn changed_and_untracked() {
                 executable_bit_changed: false,
                 content_change: Some(()),
                 set_entry_stat_size_zero: false,
+                crlf_round_trip: None,
             }),
         },
         Expectation::DirwalkEntry {
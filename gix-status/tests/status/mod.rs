@@ -1,3 +1,5 @@
+mod content;
+mod diff;
 mod index_as_worktree;
 mod index_as_worktree_with_renames;
 
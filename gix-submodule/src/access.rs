@@ -164,6 +164,36 @@ impl File {
         })
     }
 
+    /// Retrieve the `url` field of the submodule named `name` like [`url()`](Self::url()), but resolve it against
+    /// `superproject_url` if it is relative, i.e. starts with `./` or `../`, as described in [`resolve_url()`](crate::resolve_url()).
+    ///
+    /// `superproject_url` is the url of the default remote of the superproject, and it's an error if it is `None` while
+    /// the url is relative, as there is nothing to resolve it against. Absolute urls are returned as is.
+    pub fn resolved_url(
+        &self,
+        name: &BStr,
+        superproject_url: Option<&BStr>,
+    ) -> Result<gix_url::Url, config::url::Error> {
+        let url = self
+            .config
+            .string(format!("submodule.{name}.url"))
+            .filter(|url| !url.is_empty())
+            .ok_or_else(|| config::url::Error::Missing {
+                submodule: name.to_owned(),
+            })?;
+        if !crate::resolve_url::function::is_relative(url.as_ref()) {
+            return self.url(name);
+        }
+        let superproject_url = superproject_url.ok_or_else(|| config::url::Error::MissingSuperprojectUrl {
+            submodule: name.to_owned(),
+            url: url.as_ref().to_owned(),
+        })?;
+        crate::resolve_url(superproject_url, url.as_ref()).map_err(|err| config::url::Error::Resolve {
+            submodule: name.to_owned(),
+            source: err,
+        })
+    }
+
     /// Retrieve the `update` field of the submodule named `name`, if present.
     pub fn update(&self, name: &BStr) -> Result<Option<Update>, config::update::Error> {
         let value: Update = match self.config.string(format!("submodule.{name}.update")) {
@@ -185,7 +185,7 @@ pub mod update {
 pub mod url {
     use bstr::BString;
 
-    /// The error returned by [File::url()](crate::File::url).
+    /// The error returned by [File::url()](crate::File::url) and [File::resolved_url()](crate::File::resolved_url).
     #[derive(Debug, thiserror::Error)]
     #[allow(missing_docs)]
     pub enum Error {
@@ -196,6 +196,13 @@ pub mod url {
         },
         #[error("The submodule '{submodule}' was missing its 'url' field or it was empty")]
         Missing { submodule: BString },
+        #[error("The relative url '{url}' of submodule '{submodule}' can't be resolved as the superproject has no remote url")]
+        MissingSuperprojectUrl { submodule: BString, url: BString },
+        #[error("The relative url of submodule '{submodule}' could not be resolved")]
+        Resolve {
+            submodule: BString,
+            source: crate::resolve_url::Error,
+        },
     }
 }
 
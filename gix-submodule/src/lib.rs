@@ -26,6 +26,11 @@ pub mod config;
 #[allow(clippy::empty_docs)]
 pub mod is_active_platform;
 
+///
+#[allow(clippy::empty_docs)]
+pub mod resolve_url;
+pub use resolve_url::function::resolve_url;
+
 /// A platform to keep the state necessary to perform repeated active checks, created by [File::is_active_platform()].
 pub struct IsActivePlatform {
     pub(crate) search: Option<gix_pathspec::Search>,
@@ -0,0 +1,119 @@
+use bstr::BString;
+
+/// The error returned by [resolve_url()](crate::resolve_url()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The superproject url to resolve relative submodule urls against must not be empty")]
+    EmptySuperprojectUrl,
+    #[error("Cannot strip one component off url '{url}'")]
+    CannotStripComponent { url: BString },
+    #[error("The resolved url '{url}' could not be parsed")]
+    Parse {
+        url: BString,
+        source: gix_url::parse::Error,
+    },
+}
+
+pub(crate) mod function {
+    use std::borrow::Cow;
+
+    use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+    use super::Error;
+
+    /// Resolve the submodule `url` against `superproject_url`, the url of the default remote of the superproject,
+    /// if it is relative, i.e. starts with `./` or `../`, and parse the result. Absolute urls are parsed as is.
+    ///
+    /// This follows the rules of `git`, which strips one path component off `superproject_url` for each leading `../`
+    /// in `url`, or the part after the last colon if there is no slash left, to support scp-like urls.
+    /// A trailing slash of `superproject_url` is ignored, and so is a trailing slash of `url`.
+    /// If `superproject_url` is a relative path, the result will be a relative path as well.
+    pub fn resolve_url(superproject_url: &BStr, url: &BStr) -> Result<gix_url::Url, Error> {
+        let url = if is_relative(url) {
+            Cow::Owned(relative_url(superproject_url, url)?)
+        } else {
+            Cow::Borrowed(url)
+        };
+        gix_url::Url::from_bytes(url.as_ref()).map_err(|err| Error::Parse {
+            url: url.into_owned(),
+            source: err,
+        })
+    }
+
+    /// Return `true` if the submodule `url` is to be resolved against the url of the superproject.
+    pub(crate) fn is_relative(url: &BStr) -> bool {
+        url.starts_with(b"./") || url.starts_with(b"../")
+    }
+
+    fn relative_url(remote_url: &BStr, mut url: &BStr) -> Result<BString, Error> {
+        let mut remote_url = remote_url.to_owned();
+        if remote_url.is_empty() {
+            return Err(Error::EmptySuperprojectUrl);
+        }
+        if remote_url.ends_with(b"/") {
+            remote_url.pop();
+        }
+        let remote_is_relative = is_local_not_ssh(remote_url.as_ref()) && !is_absolute_path(remote_url.as_ref());
+        if remote_is_relative && !is_relative(remote_url.as_ref()) {
+            remote_url.insert_str(0, "./");
+        }
+
+        let mut colon_separated = false;
+        loop {
+            if let Some(rest) = url.strip_prefix(b"../") {
+                url = rest.as_bstr();
+                colon_separated |= chop_last_component(&mut remote_url, remote_is_relative)?;
+            } else if let Some(rest) = url.strip_prefix(b"./") {
+                url = rest.as_bstr();
+            } else {
+                break;
+            }
+        }
+
+        let mut out = remote_url;
+        out.push(if colon_separated { b':' } else { b'/' });
+        out.push_str(url);
+        if url.ends_with(b"/") {
+            out.pop();
+        }
+        if out.starts_with(b"./") {
+            out.drain(..2);
+        }
+        Ok(out)
+    }
+
+    /// Remove the last path component from `url`, or everything after the last colon, and return `true` in the latter case.
+    fn chop_last_component(url: &mut BString, is_relative: bool) -> Result<bool, Error> {
+        if let Some(pos) = url.rfind_byte(b'/') {
+            url.truncate(pos);
+            return Ok(false);
+        }
+        if let Some(pos) = url.rfind_byte(b':') {
+            url.truncate(pos);
+            return Ok(true);
+        }
+        if is_relative || *url == "." {
+            return Err(Error::CannotStripComponent { url: url.clone() });
+        }
+        *url = ".".into();
+        Ok(false)
+    }
+
+    /// Return `true` if `url` is a path, as opposed to an url with a scheme or an scp-like url.
+    fn is_local_not_ssh(url: &BStr) -> bool {
+        match url.find_byte(b':') {
+            None => true,
+            Some(colon) => url.find_byte(b'/').into_iter().any(|slash| slash < colon) || has_dos_drive_prefix(url),
+        }
+    }
+
+    fn is_absolute_path(url: &BStr) -> bool {
+        url.starts_with(b"/")
+            || (has_dos_drive_prefix(url) && url.get(2).into_iter().any(|b| matches!(b, b'/' | b'\\')))
+    }
+
+    fn has_dos_drive_prefix(url: &BStr) -> bool {
+        cfg!(windows) && url.len() >= 2 && url[0].is_ascii_alphabetic() && url[1] == b':'
+    }
+}
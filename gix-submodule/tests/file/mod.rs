@@ -236,6 +236,39 @@ mod url {
 
         assert!(matches!(submodule_url("file://"), Error::Parse { .. }));
     }
+
+    #[test]
+    fn resolved() -> crate::Result {
+        let module = submodule("[submodule.a]\n url = ../lib.git\n[submodule.b]\n url = https://example.com/b\n[submodule.c]\n url = ../../lib.git");
+        assert_eq!(module.url("a".into())?.to_bstring(), "../lib.git", "the raw url");
+        assert_eq!(
+            module
+                .resolved_url("a".into(), Some("https://example.com/org/super.git/".into()))?
+                .to_bstring(),
+            "https://example.com/org/lib.git"
+        );
+        assert_eq!(
+            module
+                .resolved_url("a".into(), Some("git@example.com:super".into()))?
+                .to_bstring(),
+            "git@example.com:lib.git",
+            "scp-like urls have their path replaced"
+        );
+        assert!(matches!(
+            module.resolved_url("a".into(), None).unwrap_err(),
+            Error::MissingSuperprojectUrl { .. }
+        ));
+        assert_eq!(
+            module.resolved_url("b".into(), None)?.to_bstring(),
+            "https://example.com/b",
+            "absolute urls don't need a superproject url"
+        );
+        assert!(matches!(
+            module.resolved_url("c".into(), Some("super".into())).unwrap_err(),
+            Error::Resolve { .. }
+        ));
+        Ok(())
+    }
 }
 
 mod update {
@@ -0,0 +1,103 @@
+use gix_submodule::{resolve_url, resolve_url::Error};
+
+/// Assert that `url` resolved against `superproject_url` is `expected`, comparing the textual result if it can't be parsed.
+fn assert_resolved(superproject_url: &str, url: &str, expected: &str) {
+    match (
+        resolve_url(superproject_url.into(), url.into()),
+        gix_url::Url::from_bytes(expected.into()),
+    ) {
+        (Ok(actual), Ok(expected_url)) => {
+            assert_eq!(actual, expected_url, "{superproject_url} + {url} = {expected}");
+        }
+        (Err(Error::Parse { url: actual, .. }), Err(_)) => {
+            assert_eq!(
+                actual, expected,
+                "{superproject_url} + {url}, even though it's not a valid url"
+            );
+        }
+        (actual, _) => unreachable!("{superproject_url} + {url}: expected {expected}, got {actual:?}"),
+    }
+}
+
+#[test]
+fn baseline_from_git() {
+    // The cases of `t0060-path-utils.sh` without an `up_path`, with `$(pwd)` replaced by `/pwd`.
+    for (superproject_url, url, expected) in [
+        ("../foo/bar", "../sub/a/b/c", "../foo/sub/a/b/c"),
+        ("../foo/bar", "../sub/a/b/c/", "../foo/sub/a/b/c"),
+        ("../foo/bar/", "../sub/a/b/c", "../foo/sub/a/b/c"),
+        ("../foo/bar", "../submodule", "../foo/submodule"),
+        ("../foo/submodule", "../submodule", "../foo/submodule"),
+        ("../foo", "../submodule", "../submodule"),
+        ("./foo/bar", "../submodule", "foo/submodule"),
+        ("./foo", "../submodule", "submodule"),
+        ("//somewhere else/repo", "../subrepo", "//somewhere else/subrepo"),
+        ("//somewhere else/repo", "../../subrepo", "//subrepo"),
+        ("//somewhere else/repo", "../../../subrepo", "/subrepo"),
+        ("//somewhere else/repo", "../../../../subrepo", "subrepo"),
+        (
+            "/pwd/subsuper_update_r",
+            "../subsubsuper_update_r",
+            "/pwd/subsubsuper_update_r",
+        ),
+        ("/pwd/super_update_r2", "../subsuper_update_r", "/pwd/subsuper_update_r"),
+        ("/pwd/.", "../.", "/pwd/."),
+        ("/pwd", "./.", "/pwd/."),
+        ("/pwd/addtest", "../repo", "/pwd/repo"),
+        ("/pwd", "./å äö", "/pwd/å äö"),
+        ("/pwd/.", "../submodule", "/pwd/submodule"),
+        ("/pwd/submodule", "../submodule", "/pwd/submodule"),
+        ("/pwd/home2/../remote", "../bundle1", "/pwd/home2/../bundle1"),
+        ("/pwd/submodule_update_repo", "./.", "/pwd/submodule_update_repo/."),
+        ("file:///tmp/repo", "../subrepo", "file:///tmp/subrepo"),
+        ("foo/bar", "../submodule", "foo/submodule"),
+        ("foo", "../submodule", "submodule"),
+        ("helper:://hostname/repo", "../subrepo", "helper:://hostname/subrepo"),
+        ("helper:://hostname/repo", "../../subrepo", "helper:://subrepo"),
+        ("helper:://hostname/repo", "../../../subrepo", "helper::/subrepo"),
+        ("helper:://hostname/repo", "../../../../subrepo", "helper::subrepo"),
+        ("helper:://hostname/repo", "../../../../../subrepo", "helper:subrepo"),
+        ("helper:://hostname/repo", "../../../../../../subrepo", ".:subrepo"),
+        ("ssh://hostname/repo", "../subrepo", "ssh://hostname/subrepo"),
+        ("ssh://hostname/repo", "../../subrepo", "ssh://subrepo"),
+        ("ssh://hostname/repo", "../../../subrepo", "ssh:/subrepo"),
+        ("ssh://hostname/repo", "../../../../subrepo", "ssh:subrepo"),
+        ("ssh://hostname/repo", "../../../../../subrepo", ".:subrepo"),
+        ("ssh://hostname:22/repo", "../subrepo", "ssh://hostname:22/subrepo"),
+        ("user@host:path/to/repo", "../subrepo", "user@host:path/to/subrepo"),
+        ("user@host:repo", "../subrepo", "user@host:subrepo"),
+        ("user@host:repo", "../../subrepo", ".:subrepo"),
+    ] {
+        assert_resolved(superproject_url, url, expected);
+    }
+}
+
+#[test]
+fn absolute_urls_are_used_as_is() -> crate::Result {
+    for url in [
+        "https://example.com/lib.git",
+        "/absolute/lib",
+        "user@host:lib.git",
+        "lib",
+    ] {
+        assert_eq!(
+            resolve_url("https://example.com/super.git".into(), url.into())?,
+            gix_url::Url::from_bytes(url.into())?
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn relative_superprojects_cannot_lose_all_of_their_components() {
+    for (superproject_url, url) in [("foo", "../../submodule"), ("./foo/bar", "../../../submodule")] {
+        assert!(matches!(
+            resolve_url(superproject_url.into(), url.into()),
+            Err(Error::CannotStripComponent { .. })
+        ));
+    }
+    assert!(matches!(
+        resolve_url("".into(), "../submodule".into()),
+        Err(Error::EmptySuperprojectUrl)
+    ));
+}
@@ -1,3 +1,4 @@
 use gix_testtools::Result;
 
 mod file;
+mod resolve_url;
@@ -0,0 +1,313 @@
+//! A subscriber which writes spans and events in the format `git` uses for `GIT_TRACE` and `GIT_TRACE_PACKET`.
+//!
+//! This allows tools built on `gitoxide` to honor the environment variables users already know from `git`,
+//! with [`init_from_env()`] being the typical way to activate it.
+use std::{
+    ffi::OsString,
+    fmt::Write as _,
+    io::Write as _,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use tracing_core::{
+    field::{Field, Visit},
+    span, Event, Metadata,
+};
+
+/// The variable to activate general tracing, which includes the spawning of programs and object database operations.
+pub const GIT_TRACE: &str = "GIT_TRACE";
+/// The variable to activate tracing of lines sent or received with the packet-line protocol.
+pub const GIT_TRACE_PACKET: &str = "GIT_TRACE_PACKET";
+
+/// The kind of trace line, derived from the target of a span or event.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Category {
+    /// The spawning of programs, like hooks, filter drivers, `ssh` or the programs behind `EXE_INFO`.
+    RunCommand,
+    /// Lines sent or received with the packet-line protocol.
+    Packet,
+    /// Operations on packs and the object database.
+    Pack,
+    /// Everything else, named after the crate that produced it.
+    Other,
+}
+
+impl Category {
+    /// Classify `target`, which typically is the module path of the instrumented code.
+    pub fn from_target(target: &str) -> Self {
+        let starts_with_any = |prefixes: &[&str]| {
+            prefixes
+                .iter()
+                .any(|prefix| target == *prefix || target.starts_with(&format!("{prefix}::")))
+        };
+        if starts_with_any(&["gix_packetline", "gix_packetline_blocking"]) {
+            Category::Packet
+        } else if starts_with_any(&[
+            "gix_command",
+            "gix_filter::driver",
+            "gix_transport::client::blocking_io::ssh",
+            "gix_transport::client::blocking_io::file",
+        ]) {
+            Category::RunCommand
+        } else if starts_with_any(&["gix_pack", "gix_odb"]) {
+            Category::Pack
+        } else {
+            Category::Other
+        }
+    }
+}
+
+/// Where trace lines are written to, as configured by the value of a `GIT_TRACE*` variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Destination {
+    /// Write to standard error, for values `1`, `2` and `true`.
+    Stderr,
+    /// Write to the file descriptor with the given number, for single-digit values other than `0`, `1` and `2`.
+    ///
+    /// It's only available on Unix, where file descriptors can be opened through `/dev/fd`. Elsewhere, these values
+    /// are rejected as unknown.
+    #[cfg(unix)]
+    Fd(u8),
+    /// Append to the file at the given absolute path.
+    File(PathBuf),
+}
+
+impl Destination {
+    /// Interpret `value` exactly like `git` does, returning `None` if tracing is turned off.
+    ///
+    /// Values that can't be interpreted turn tracing off as well, after printing a warning to standard error just like `git`.
+    pub fn from_value(name: &str, value: &OsString) -> Option<Self> {
+        let value_str = value.to_string_lossy();
+        if value_str.is_empty() || value_str == "0" || value_str.eq_ignore_ascii_case("false") {
+            return None;
+        }
+        if value_str == "1" || value_str.eq_ignore_ascii_case("true") {
+            return Some(Destination::Stderr);
+        }
+        if value_str.len() == 1 && value_str.as_bytes()[0].is_ascii_digit() {
+            let fd = value_str.as_bytes()[0] - b'0';
+            if fd == 2 {
+                return Some(Destination::Stderr);
+            }
+            #[cfg(unix)]
+            return Some(Destination::Fd(fd));
+        }
+        let path = PathBuf::from(value);
+        if path.is_absolute() {
+            return Some(Destination::File(path));
+        }
+        eprintln!(
+            "warning: unknown trace value for '{name}': {value_str}\n         \
+             If you want to trace into a file, then please set {name}\n         \
+             to an absolute pathname (starting with /)"
+        );
+        None
+    }
+}
+
+/// An output which is opened lazily when the first line is written to it.
+struct Output {
+    destination: Destination,
+    file: Mutex<Option<Result<std::fs::File, ()>>>,
+}
+
+impl Output {
+    fn new(destination: Destination) -> Self {
+        Output {
+            destination,
+            file: Mutex::new(None),
+        }
+    }
+
+    /// Write `line` with a single write so lines of concurrent writers don't interleave.
+    fn write_line(&self, line: &str) {
+        let path = match &self.destination {
+            Destination::Stderr => {
+                std::io::stderr().write_all(line.as_bytes()).ok();
+                return;
+            }
+            #[cfg(unix)]
+            Destination::Fd(fd) => PathBuf::from(format!("/dev/fd/{fd}")),
+            Destination::File(path) => path.clone(),
+        };
+        let mut file = self.file.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let file = file.get_or_insert_with(|| {
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .map_err(|err| {
+                    eprintln!(
+                        "warning: could not open '{}' for tracing: {err}\n         Defaulting to tracing on stderr...",
+                        path.display()
+                    );
+                })
+        });
+        match file {
+            Ok(file) => {
+                file.write_all(line.as_bytes()).ok();
+            }
+            Err(()) => {
+                std::io::stderr().write_all(line.as_bytes()).ok();
+            }
+        }
+    }
+}
+
+/// A [subscriber](tracing_core::Subscriber) which writes a line in `git`'s trace format for each span and event.
+///
+/// Packet-line traffic goes to the destination of [`GIT_TRACE_PACKET`], everything else to the one of [`GIT_TRACE`].
+///
+/// Note that timestamps are in UTC as there is no portable way to obtain the local time offset.
+pub struct Subscriber {
+    trace: Option<Output>,
+    packet: Option<Output>,
+    next_id: AtomicU64,
+}
+
+impl Subscriber {
+    /// Create a new instance configured by the `GIT_TRACE*` variables as obtained by `var(name)`,
+    /// or `None` if all tracing is turned off.
+    ///
+    /// Nothing is opened until the first line is written.
+    pub fn from_env(mut var: impl FnMut(&str) -> Option<OsString>) -> Option<Self> {
+        let mut output = |name: &str| {
+            var(name)
+                .and_then(|value| Destination::from_value(name, &value))
+                .map(Output::new)
+        };
+        let trace = output(GIT_TRACE);
+        let packet = output(GIT_TRACE_PACKET);
+        (trace.is_some() || packet.is_some()).then(|| Subscriber {
+            trace,
+            packet,
+            next_id: AtomicU64::new(1),
+        })
+    }
+
+    fn output(&self, category: Category) -> Option<&Output> {
+        match category {
+            Category::Packet => self.packet.as_ref(),
+            Category::RunCommand | Category::Pack | Category::Other => self.trace.as_ref(),
+        }
+    }
+
+    fn write(&self, meta: &Metadata<'_>, text: &str) {
+        let category = Category::from_target(meta.target());
+        let Some(output) = self.output(category) else {
+            return;
+        };
+        let mut line = String::new();
+        let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let secs_today = since_epoch.as_secs() % (24 * 60 * 60);
+        write!(
+            line,
+            "{:02}:{:02}:{:02}.{:06} ",
+            secs_today / 3600,
+            secs_today / 60 % 60,
+            secs_today % 60,
+            since_epoch.subsec_micros()
+        )
+        .ok();
+        if let (Some(file), Some(line_number)) = (meta.file(), meta.line()) {
+            write!(line, "{file}:{line_number} ").ok();
+        }
+        // Align like `git` does, as column 40 catches most file names.
+        while line.len() < 40 {
+            line.push(' ');
+        }
+        match category {
+            Category::Packet => line.push_str("packet: "),
+            Category::RunCommand => line.push_str("trace: run_command: "),
+            Category::Pack => line.push_str("trace: pack: "),
+            Category::Other => {
+                let krate = meta.target().split("::").next().unwrap_or_default();
+                write!(line, "trace: {krate}: ").ok();
+            }
+        }
+        line.push_str(text);
+        line.push('\n');
+        output.write_line(&line);
+    }
+}
+
+/// Collect all fields into a single line, with the `message` field first and without its name.
+#[derive(Default)]
+struct Fields {
+    message: String,
+    fields: String,
+}
+
+impl Fields {
+    fn into_text(self, name: Option<&str>) -> String {
+        let mut out = String::new();
+        for part in [name.unwrap_or_default(), &self.message, &self.fields] {
+            if part.is_empty() {
+                continue;
+            }
+            if !out.is_empty() {
+                out.push(' ');
+            }
+            out.push_str(part);
+        }
+        out
+    }
+}
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            write!(self.message, "{value:?}").ok();
+        } else {
+            if !self.fields.is_empty() {
+                self.fields.push(' ');
+            }
+            write!(self.fields, "{}={value:?}", field.name()).ok();
+        }
+    }
+}
+
+impl tracing_core::Subscriber for Subscriber {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        self.output(Category::from_target(metadata.target())).is_some()
+    }
+
+    fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+        let mut fields = Fields::default();
+        span.record(&mut fields);
+        self.write(span.metadata(), &fields.into_text(Some(span.metadata().name())));
+        span::Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        self.write(event.metadata(), &fields.into_text(None));
+    }
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+}
+
+/// Install a [`Subscriber`] as global default if any of the `GIT_TRACE*` variables turn tracing on,
+/// and return `true` if it was installed.
+///
+/// Note that this fails if a global default was already set, in which case `false` is returned as well.
+pub fn init_from_env() -> bool {
+    match Subscriber::from_env(|name| std::env::var_os(name)) {
+        Some(subscriber) => {
+            tracing_core::dispatcher::set_global_default(tracing_core::Dispatch::new(subscriber)).is_ok()
+        }
+        None => false,
+    }
+}
@@ -37,6 +37,9 @@ mod enabled;
 #[cfg(feature = "tracing")]
 pub use enabled::{field, Span};
 
+#[cfg(feature = "tracing")]
+pub mod git_trace;
+
 impl Span {
     /// Execute `f` in with this span active, consuming it.
     pub fn into_scope<T>(self, f: impl FnOnce() -> T) -> T {
@@ -44,3 +44,101 @@ fn detail() {
     detail!("hello", x = "value", y = 42);
     detail!(target: "other", "hello", x = "value", y = 42);
 }
+
+#[cfg(feature = "tracing")]
+mod git_trace {
+    use std::ffi::OsString;
+
+    use gix_trace::git_trace::{Category, Destination, Subscriber, GIT_TRACE, GIT_TRACE_PACKET};
+
+    #[test]
+    fn destinations_are_interpreted_like_git_does() {
+        let d = |value: &str| Destination::from_value("GIT_TRACE", &OsString::from(value));
+        for off in ["", "0", "false", "FALSE"] {
+            assert_eq!(d(off), None, "{off:?} turns tracing off");
+        }
+        for stderr in ["1", "2", "true", "True"] {
+            assert_eq!(d(stderr), Some(Destination::Stderr), "{stderr:?} traces to stderr");
+        }
+        #[cfg(unix)]
+        assert_eq!(d("9"), Some(Destination::Fd(9)));
+        #[cfg(not(unix))]
+        assert_eq!(d("9"), None, "file descriptors can't be opened by number");
+        let abs = if cfg!(windows) {
+            "c:\\trace.log"
+        } else {
+            "/tmp/trace.log"
+        };
+        assert_eq!(d(abs), Some(Destination::File(abs.into())));
+        assert_eq!(d("relative/trace.log"), None, "relative paths are rejected");
+        assert_eq!(d("10"), None, "only single-digit file descriptors are supported");
+    }
+
+    #[test]
+    fn categories_are_derived_from_the_target() {
+        assert_eq!(Category::from_target("gix_packetline::read"), Category::Packet);
+        assert_eq!(Category::from_target("gix_command"), Category::RunCommand);
+        assert_eq!(Category::from_target("gix_filter::driver::init"), Category::RunCommand);
+        assert_eq!(Category::from_target("gix_odb::store"), Category::Pack);
+        assert_eq!(Category::from_target("gix_pack::bundle"), Category::Pack);
+        assert_eq!(
+            Category::from_target("gix_packs"),
+            Category::Other,
+            "only full crate names match"
+        );
+        assert_eq!(Category::from_target("gix_traverse::commit"), Category::Other);
+    }
+
+    #[test]
+    fn everything_is_off_without_variables() {
+        assert!(Subscriber::from_env(|_| None).is_none());
+        assert!(Subscriber::from_env(|_| Some("0".into())).is_none());
+    }
+
+    #[test]
+    fn traversal_output_is_written_to_files_with_category_prefixes() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let trace_file = dir.path().join("trace");
+        let packet_file = dir.path().join("packet");
+        let subscriber = Subscriber::from_env(|name| match name {
+            GIT_TRACE => Some(trace_file.clone().into()),
+            GIT_TRACE_PACKET => Some(packet_file.clone().into()),
+            _ => None,
+        })
+        .expect("tracing is on");
+        assert!(!trace_file.exists(), "files are opened lazily");
+
+        let dispatch = tracing_core::Dispatch::new(subscriber);
+        tracing_core::dispatcher::with_default(&dispatch, || {
+            let _traversal = gix_trace::coarse!(target: "gix_traverse::commit", "walk", tips = 1);
+            gix_trace::debug!(target: "gix_command", cmd = "\"git\" \"upload-pack\"");
+            gix_trace::trace!(target: "gix_packetline::read", "<< version 2");
+            gix_trace::info!(target: "gix_odb::store", "loaded {} packs", 3);
+        });
+
+        let trace = std::fs::read_to_string(&trace_file)?;
+        let lines: Vec<_> = trace.lines().collect();
+        assert_eq!(lines.len(), 3, "{trace}");
+        let messages: Vec<_> = lines
+            .iter()
+            .map(|line| &line[line.find("trace: ").expect("category prefix")..])
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "trace: gix_traverse: walk tips=1",
+                "trace: run_command: cmd=\"\\\"git\\\" \\\"upload-pack\\\"\"",
+                "trace: pack: loaded 3 packs",
+            ]
+        );
+        for line in &lines {
+            assert_eq!(line.as_bytes()[2], b':', "lines start with a timestamp: {line}");
+            assert!(line.contains("trace.rs:"), "the source location follows: {line}");
+        }
+
+        let packet = std::fs::read_to_string(&packet_file)?;
+        assert!(packet.ends_with("packet: << version 2\n"), "{packet}");
+        assert_eq!(packet.lines().count(), 1);
+        Ok(())
+    }
+}
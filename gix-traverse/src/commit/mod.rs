@@ -36,12 +36,14 @@ pub struct Topo<Find, Predicate> {
     parents: Parents,
     min_gen: u32,
     buf: Vec<u8>,
+    boundary: Option<topo::iter::Boundary>,
 }
 
 pub mod topo;
 
 /// Specify how to handle commit parents during traversal.
-#[derive(Default, Copy, Clone)]
+#[derive(Default, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Parents {
     /// Traverse all parents, useful for traversing the entire ancestry.
     #[default]
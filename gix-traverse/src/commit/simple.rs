@@ -1,8 +1,7 @@
 use gix_date::SecondsSinceUnixEpoch;
 use gix_hash::ObjectId;
-use gix_hashtable::HashSet;
 use smallvec::SmallVec;
-use std::collections::VecDeque;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 
 /// Specify how to sort commits during a [simple](super::Simple) traversal.
 ///
@@ -17,6 +16,7 @@ use std::collections::VecDeque;
 ///      3----5----6----8---
 /// ```
 #[derive(Default, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Sorting {
     /// Commits are sorted as they are mentioned in the commit graph.
     ///
@@ -61,15 +61,48 @@ pub enum Error {
     ObjectDecode(#[from] gix_object::decode::Error),
 }
 
+/// A traversal like [`Simple`](super::Simple), but as state machine which leaves obtaining commit data to the caller.
+///
+/// This allows to decide when and where commits are loaded, for instance to load them in batches or asynchronously,
+/// and is how [`Simple`](super::Simple) is implemented.
+///
+/// ### Usage
+///
+/// Call [`step()`](Self::step()) until it returns [`Step::Done`](walk::Step::Done).
+/// Whenever it returns [`Step::NeedsCommits`](walk::Step::NeedsCommits), [provide](Self::provide_commit()) the data of
+/// the commits returned by [`next_candidates()`](Self::next_candidates()) in any order.
+/// Data of commits may also be provided before they are needed, for instance to prefetch them.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Walk {
+    sorting: Sorting,
+    parents: super::Parents,
+    /// All commits that were ever queued, to queue them only once.
+    seen: HashSet<ObjectId, gix_hashtable::hash::Builder>,
+    /// Commits in the order they are returned when traversing breadth-first.
+    next: VecDeque<ObjectId>,
+    /// Commits that are waiting for their data to be inserted into `queue` when sorting by date.
+    pending: Vec<ObjectId>,
+    /// Commits sorted by their commit time.
+    queue: BinaryHeap<walk::Queued>,
+    /// The data of commits that were provided, but weren't returned yet.
+    provided: HashMap<ObjectId, walk::Commit, gix_hashtable::hash::Builder>,
+}
+
+/// Types for use with a [`Walk`].
+pub mod walk;
+
 /// The state used and potentially shared by multiple graph traversals.
-#[derive(Clone)]
 pub(super) struct State {
-    next: VecDeque<ObjectId>,
-    queue: gix_revwalk::PriorityQueue<SecondsSinceUnixEpoch, ObjectId>,
+    walk: Walk,
     buf: Vec<u8>,
-    seen: HashSet<ObjectId>,
-    parents_buf: Vec<u8>,
+    /// The commit whose data is currently in `buf`.
+    buf_id: Option<ObjectId>,
     parent_ids: SmallVec<[(ObjectId, SecondsSinceUnixEpoch); 2]>,
+    /// Commits that couldn't be obtained, with the error to return once they are due.
+    failed: Vec<(ObjectId, Error)>,
+    /// The tips whose commit time wasn't obtained yet, which is always read from the object database.
+    tips: gix_hashtable::HashSet<ObjectId>,
 }
 
 ///
@@ -77,35 +110,13 @@ pub(super) struct State {
 mod init {
     use gix_date::SecondsSinceUnixEpoch;
     use gix_hash::{oid, ObjectId};
-    use gix_object::{CommitRefIter, FindExt};
+    use gix_object::CommitRefIter;
 
     use super::{
         super::{simple::Sorting, Either, Info, ParentIds, Parents, Simple},
-        collect_parents, Error, State,
+        collect_parents, walk, Error, State, Walk,
     };
 
-    impl Default for State {
-        fn default() -> Self {
-            State {
-                next: Default::default(),
-                queue: gix_revwalk::PriorityQueue::new(),
-                buf: vec![],
-                seen: Default::default(),
-                parents_buf: vec![],
-                parent_ids: Default::default(),
-            }
-        }
-    }
-
-    impl State {
-        fn clear(&mut self) {
-            self.next.clear();
-            self.queue.clear();
-            self.buf.clear();
-            self.seen.clear();
-        }
-    }
-
     /// Builder
     impl<Find, Predicate> Simple<Find, Predicate>
     where
@@ -114,37 +125,14 @@ mod init {
         /// Set the `sorting` method.
         pub fn sorting(mut self, sorting: Sorting) -> Result<Self, Error> {
             self.sorting = sorting;
-            match self.sorting {
-                Sorting::BreadthFirst => {
-                    self.queue_to_vecdeque();
-                }
-                Sorting::ByCommitTimeNewestFirst | Sorting::ByCommitTimeNewestFirstCutoffOlderThan { .. } => {
-                    let cutoff_time = self.sorting.cutoff_time();
-                    let state = &mut self.state;
-                    for commit_id in state.next.drain(..) {
-                        let commit_iter = self.objects.find_commit_iter(&commit_id, &mut state.buf)?;
-                        let time = commit_iter.committer()?.time.seconds;
-                        match cutoff_time {
-                            Some(cutoff_time) if time >= cutoff_time => {
-                                state.queue.insert(time, commit_id);
-                            }
-                            Some(_) => {}
-                            None => {
-                                state.queue.insert(time, commit_id);
-                            }
-                        }
-                    }
-                }
-            }
+            self.state.walk.configure(self.sorting, self.parents);
             Ok(self)
         }
 
         /// Change our commit parent handling mode to the given one.
         pub fn parents(mut self, mode: Parents) -> Self {
             self.parents = mode;
-            if matches!(self.parents, Parents::First) {
-                self.queue_to_vecdeque();
-            }
+            self.state.walk.configure(self.sorting, self.parents);
             self
         }
 
@@ -156,15 +144,6 @@ mod init {
             self.cache = cache;
             self
         }
-
-        fn queue_to_vecdeque(&mut self) {
-            let state = &mut self.state;
-            state.next.extend(
-                std::mem::replace(&mut state.queue, gix_revwalk::PriorityQueue::new())
-                    .into_iter_unordered()
-                    .map(|(_time, id)| id),
-            );
-        }
     }
 
     /// Lifecycle
@@ -206,18 +185,20 @@ mod init {
             find: Find,
             mut predicate: Predicate,
         ) -> Self {
-            let tips = tips.into_iter();
-            let mut state = State::default();
-            {
-                state.clear();
-                state.next.reserve(tips.size_hint().0);
-                for tip in tips.map(Into::into) {
-                    let was_inserted = state.seen.insert(tip);
-                    if was_inserted && predicate(&tip) {
-                        state.next.push_back(tip);
-                    }
-                }
-            }
+            let tips: Vec<ObjectId> = tips.into_iter().map(Into::into).collect();
+            let state = State {
+                walk: Walk::filtered(
+                    tips.iter().copied(),
+                    Default::default(),
+                    Default::default(),
+                    &mut predicate,
+                ),
+                buf: Vec::new(),
+                buf_id: None,
+                parent_ids: Default::default(),
+                failed: Vec::new(),
+                tips: tips.into_iter().collect(),
+            };
             Self {
                 objects: find,
                 cache: None,
@@ -250,14 +231,14 @@ mod init {
         type Item = Result<Info, Error>;
 
         fn next(&mut self) -> Option<Self::Item> {
-            if matches!(self.parents, Parents::First) {
-                self.next_by_topology()
-            } else {
-                match self.sorting {
-                    Sorting::BreadthFirst => self.next_by_topology(),
-                    Sorting::ByCommitTimeNewestFirst => self.next_by_commit_date(None),
-                    Sorting::ByCommitTimeNewestFirstCutoffOlderThan { seconds } => {
-                        self.next_by_commit_date(seconds.into())
+            loop {
+                match self.state.walk.step(&mut self.predicate) {
+                    walk::Step::Commit(info) => return Some(self.finalize(info)),
+                    walk::Step::Done => return None,
+                    walk::Step::NeedsCommits => {
+                        for id in self.state.walk.next_candidates() {
+                            self.provide_commit(id);
+                        }
                     }
                 }
             }
@@ -266,7 +247,7 @@ mod init {
 
     impl Sorting {
         /// If not topo sort, provide the cutoff date if present.
-        fn cutoff_time(&self) -> Option<SecondsSinceUnixEpoch> {
+        pub(super) fn cutoff_time(&self) -> Option<SecondsSinceUnixEpoch> {
             match self {
                 Sorting::ByCommitTimeNewestFirstCutoffOlderThan { seconds } => Some(*seconds),
                 _ => None,
@@ -280,125 +261,77 @@ mod init {
         Find: gix_object::Find,
         Predicate: FnMut(&oid) -> bool,
     {
-        fn next_by_commit_date(
-            &mut self,
-            cutoff_older_than: Option<SecondsSinceUnixEpoch>,
-        ) -> Option<Result<Info, Error>> {
+        /// Obtain the data of the commit with `id` and provide it to our walk, or remember why that wasn't possible.
+        fn provide_commit(&mut self, id: ObjectId) {
             let state = &mut self.state;
-
-            let (commit_time, oid) = state.queue.pop()?;
-            let mut parents: ParentIds = Default::default();
-            match super::super::find(self.cache.as_ref(), &self.objects, &oid, &mut state.buf) {
+            state.buf_id = None;
+            let mut parents = ParentIds::new();
+            let mut commit_time = None;
+            match super::super::find(self.cache.as_ref(), &self.objects, &id, &mut state.buf) {
                 Ok(Either::CachedCommit(commit)) => {
                     if !collect_parents(&mut state.parent_ids, self.cache.as_ref(), commit.iter_parents()) {
                         // drop corrupt caches and try again with ODB
                         self.cache = None;
-                        return self.next_by_commit_date(cutoff_older_than);
+                        return self.provide_commit(id);
                     }
-                    for (id, parent_commit_time) in state.parent_ids.drain(..) {
-                        parents.push(id);
-                        let was_inserted = state.seen.insert(id);
-                        if !(was_inserted && (self.predicate)(&id)) {
-                            continue;
-                        }
-
-                        match cutoff_older_than {
-                            Some(cutoff_older_than) if parent_commit_time < cutoff_older_than => continue,
-                            Some(_) | None => state.queue.insert(parent_commit_time, id),
+                    if state.tips.remove(&id) {
+                        // The commit-graph can't represent times before the unix epoch, so read the time of tips from their object.
+                        match gix_object::FindExt::find_commit_iter(&self.objects, &id, &mut state.buf)
+                            .map_err(Error::from)
+                            .and_then(|commit| Ok(commit.committer()?.time.seconds))
+                        {
+                            Ok(time) => {
+                                state.buf_id = Some(id);
+                                commit_time = Some(time);
+                            }
+                            Err(err) => state.failed.push((id, err)),
                         }
+                    } else {
+                        commit_time = Some(commit.committer_timestamp() as SecondsSinceUnixEpoch);
                     }
+                    parents.extend(state.parent_ids.drain(..).map(|(id, _time)| id));
                 }
                 Ok(Either::CommitRefIter(commit_iter)) => {
+                    state.tips.remove(&id);
+                    state.buf_id = Some(id);
                     for token in commit_iter {
                         match token {
                             Ok(gix_object::commit::ref_iter::Token::Tree { .. }) => continue,
-                            Ok(gix_object::commit::ref_iter::Token::Parent { id }) => {
-                                parents.push(id);
-                                let was_inserted = state.seen.insert(id);
-                                if !(was_inserted && (self.predicate)(&id)) {
-                                    continue;
-                                }
-
-                                let parent = self.objects.find_commit_iter(id.as_ref(), &mut state.parents_buf).ok();
-                                let parent_commit_time = parent
-                                    .and_then(|parent| parent.committer().ok().map(|committer| committer.time.seconds))
-                                    .unwrap_or_default();
-
-                                match cutoff_older_than {
-                                    Some(cutoff_older_than) if parent_commit_time < cutoff_older_than => continue,
-                                    Some(_) | None => state.queue.insert(parent_commit_time, id),
-                                }
+                            Ok(gix_object::commit::ref_iter::Token::Parent { id }) => parents.push(id),
+                            Ok(gix_object::commit::ref_iter::Token::Author { .. }) => continue,
+                            Ok(gix_object::commit::ref_iter::Token::Committer { signature }) => {
+                                commit_time = Some(signature.time.seconds);
+                                break;
                             }
                             Ok(_unused_token) => break,
-                            Err(err) => return Some(Err(err.into())),
+                            Err(err) => {
+                                state.failed.push((id, err.into()));
+                                break;
+                            }
                         }
                     }
                 }
-                Err(err) => return Some(Err(err.into())),
+                Err(err) => state.failed.push((id, err.into())),
             }
-            Some(Ok(Info {
-                id: oid,
-                parent_ids: parents,
-                commit_time: Some(commit_time),
-            }))
+            state.walk.provide_commit(id, commit_time, parents);
         }
-    }
 
-    /// Utilities
-    impl<Find, Predicate> Simple<Find, Predicate>
-    where
-        Find: gix_object::Find,
-        Predicate: FnMut(&oid) -> bool,
-    {
-        fn next_by_topology(&mut self) -> Option<Result<Info, Error>> {
+        /// Return `info` or the error that occurred when obtaining its data, and assure the commit data is available
+        /// via [`Self::commit_data()`].
+        fn finalize(&mut self, info: Info) -> Result<Info, Error> {
             let state = &mut self.state;
-            let oid = state.next.pop_front()?;
-            let mut parents: ParentIds = Default::default();
-            match super::super::find(self.cache.as_ref(), &self.objects, &oid, &mut state.buf) {
-                Ok(Either::CachedCommit(commit)) => {
-                    if !collect_parents(&mut state.parent_ids, self.cache.as_ref(), commit.iter_parents()) {
-                        // drop corrupt caches and try again with ODB
-                        self.cache = None;
-                        return self.next_by_topology();
-                    }
-
-                    for (id, _commit_time) in state.parent_ids.drain(..) {
-                        parents.push(id);
-                        let was_inserted = state.seen.insert(id);
-                        if was_inserted && (self.predicate)(&id) {
-                            state.next.push_back(id);
-                        }
-                        if matches!(self.parents, Parents::First) {
-                            break;
-                        }
-                    }
-                }
-                Ok(Either::CommitRefIter(commit_iter)) => {
-                    for token in commit_iter {
-                        match token {
-                            Ok(gix_object::commit::ref_iter::Token::Tree { .. }) => continue,
-                            Ok(gix_object::commit::ref_iter::Token::Parent { id }) => {
-                                parents.push(id);
-                                let was_inserted = state.seen.insert(id);
-                                if was_inserted && (self.predicate)(&id) {
-                                    state.next.push_back(id);
-                                }
-                                if matches!(self.parents, Parents::First) {
-                                    break;
-                                }
-                            }
-                            Ok(_a_token_past_the_parents) => break,
-                            Err(err) => return Some(Err(err.into())),
-                        }
-                    }
+            if let Some(pos) = state.failed.iter().position(|(id, _)| *id == info.id) {
+                return Err(state.failed.swap_remove(pos).1);
+            }
+            if state.buf_id != Some(info.id) && state.walk.sorts_by_commit_time() {
+                state.buf_id = None;
+                if let Either::CommitRefIter(_) =
+                    super::super::find(self.cache.as_ref(), &self.objects, &info.id, &mut state.buf)?
+                {
+                    state.buf_id = Some(info.id);
                 }
-                Err(err) => return Some(Err(err.into())),
             }
-            Some(Ok(Info {
-                id: oid,
-                parent_ids: parents,
-                commit_time: None,
-            }))
+            Ok(info)
         }
     }
 }
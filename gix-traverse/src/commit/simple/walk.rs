@@ -0,0 +1,223 @@
+use gix_date::SecondsSinceUnixEpoch;
+use gix_hash::{oid, ObjectId};
+
+use super::{Sorting, Walk};
+use crate::commit::{Info, ParentIds, Parents};
+
+/// The result of [`Walk::step()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    /// The next commit of the traversal.
+    Commit(Info),
+    /// The data of the commits returned by [`Walk::next_candidates()`] must be provided to continue the traversal.
+    NeedsCommits,
+    /// There are no more commits to traverse.
+    Done,
+}
+
+/// A commit in the queue of a [`Walk`] when sorting by commit time.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(super) struct Queued {
+    time: SecondsSinceUnixEpoch,
+    id: ObjectId,
+}
+
+impl PartialEq for Queued {
+    fn eq(&self, other: &Self) -> bool {
+        self.time == other.time
+    }
+}
+
+impl Eq for Queued {}
+
+impl PartialOrd for Queued {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Queued {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.time.cmp(&other.time)
+    }
+}
+
+/// The data of a commit that was provided to a [`Walk`].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(super) struct Commit {
+    time: SecondsSinceUnixEpoch,
+    parent_ids: Vec<ObjectId>,
+}
+
+/// Lifecycle
+impl Walk {
+    /// Create a new instance to traverse the ancestry of all `tips` in the order defined by `sorting` and `parents`.
+    ///
+    /// Each commit is returned only once, including the tips.
+    pub fn new(tips: impl IntoIterator<Item = impl Into<ObjectId>>, sorting: Sorting, parents: Parents) -> Self {
+        Self::filtered(tips, sorting, parents, |_| true)
+    }
+
+    /// Like [`new()`](Self::new()), but use `predicate` to decide whether a commit should be included in the result
+    /// as well as whether its parent commits should be traversed.
+    ///
+    /// The `predicate` is only applied to the `tips` here, and has to be passed to each call of [`step()`](Self::step())
+    /// to apply it to all other commits.
+    pub fn filtered(
+        tips: impl IntoIterator<Item = impl Into<ObjectId>>,
+        sorting: Sorting,
+        parents: Parents,
+        mut predicate: impl FnMut(&oid) -> bool,
+    ) -> Self {
+        let mut walk = Walk {
+            sorting,
+            parents,
+            seen: Default::default(),
+            next: Default::default(),
+            pending: Default::default(),
+            queue: Default::default(),
+            provided: Default::default(),
+        };
+        for tip in tips.into_iter().map(Into::into) {
+            let was_inserted = walk.seen.insert(tip);
+            if was_inserted && predicate(&tip) {
+                walk.enqueue(tip);
+            }
+        }
+        walk
+    }
+
+    /// Change the `sorting` and `parents` modes of this instance to the given ones, which affects all commits
+    /// that weren't returned yet.
+    pub fn configure(&mut self, sorting: Sorting, parents: Parents) {
+        self.sorting = sorting;
+        self.parents = parents;
+        if self.sorts_by_commit_time() {
+            self.pending.extend(self.next.drain(..));
+        } else {
+            while let Some(Queued { id, .. }) = self.queue.pop() {
+                self.next.push_back(id);
+            }
+            self.next.extend(self.pending.drain(..));
+        }
+    }
+}
+
+/// Traversal
+impl Walk {
+    /// Return the ids of all commits whose data has to be [provided](Self::provide_commit()) before the traversal can
+    /// continue, which is empty if [`step()`](Self::step()) can make progress.
+    ///
+    /// Calling it multiple times returns the same ids unless the data of commits was provided in the meantime.
+    pub fn next_candidates(&self) -> Vec<ObjectId> {
+        if self.sorts_by_commit_time() {
+            self.pending
+                .iter()
+                .filter(|id| !self.provided.contains_key(*id))
+                .copied()
+                .collect()
+        } else {
+            self.next
+                .front()
+                .filter(|id| !self.provided.contains_key(*id))
+                .copied()
+                .into_iter()
+                .collect()
+        }
+    }
+
+    /// Provide the data of the commit with `id`, namely its `commit_time` and its `parent_ids`, in any order
+    /// and even before it is needed.
+    ///
+    /// If the commit couldn't be obtained, pass `None` as `commit_time` and no `parent_ids`, which will sort it
+    /// as if it was created at the beginning of the unix epoch and end the traversal along this path.
+    pub fn provide_commit(
+        &mut self,
+        id: ObjectId,
+        commit_time: Option<SecondsSinceUnixEpoch>,
+        parent_ids: impl IntoIterator<Item = ObjectId>,
+    ) {
+        let mut parent_ids: Vec<_> = parent_ids.into_iter().collect();
+        if matches!(self.parents, Parents::First) {
+            parent_ids.truncate(1);
+        }
+        self.provided.insert(
+            id,
+            Commit {
+                time: commit_time.unwrap_or_default(),
+                parent_ids,
+            },
+        );
+    }
+
+    /// Advance the traversal and return the next commit, or [`Step::NeedsCommits`] if the data of
+    /// [more commits](Self::next_candidates()) must be provided first.
+    ///
+    /// The `predicate` decides whether a parent commit should be included in the result as well
+    /// as whether its parent commits should be traversed.
+    pub fn step(&mut self, mut predicate: impl FnMut(&oid) -> bool) -> Step {
+        let (id, commit, commit_time) = if self.sorts_by_commit_time() {
+            if self.pending.iter().any(|id| !self.provided.contains_key(id)) {
+                return Step::NeedsCommits;
+            }
+            let cutoff = self.sorting.cutoff_time();
+            for id in self.pending.drain(..) {
+                let time = self.provided[&id].time;
+                match cutoff {
+                    Some(cutoff) if time < cutoff => {
+                        self.provided.remove(&id);
+                    }
+                    Some(_) | None => self.queue.push(Queued { time, id }),
+                }
+            }
+            let Some(Queued { time, id }) = self.queue.pop() else {
+                return Step::Done;
+            };
+            let commit = self.provided.remove(&id).expect("queued commits were provided");
+            (id, commit, Some(time))
+        } else {
+            let Some(id) = self.next.front().copied() else {
+                return Step::Done;
+            };
+            let Some(commit) = self.provided.remove(&id) else {
+                return Step::NeedsCommits;
+            };
+            self.next.pop_front();
+            (id, commit, None)
+        };
+
+        for parent_id in &commit.parent_ids {
+            let was_inserted = self.seen.insert(*parent_id);
+            if was_inserted && predicate(parent_id) {
+                self.enqueue(*parent_id);
+            }
+        }
+        Step::Commit(Info {
+            id,
+            parent_ids: commit.parent_ids.into_iter().collect::<ParentIds>(),
+            commit_time,
+        })
+    }
+}
+
+/// Access
+impl Walk {
+    /// Return `true` if commits are returned by their commit time, which is when their commit time must be known
+    /// as well.
+    pub fn sorts_by_commit_time(&self) -> bool {
+        !matches!(self.parents, Parents::First) && !matches!(self.sorting, Sorting::BreadthFirst)
+    }
+}
+
+/// Utilities
+impl Walk {
+    fn enqueue(&mut self, id: ObjectId) {
+        if self.sorts_by_commit_time() {
+            self.pending.push(id);
+        } else {
+            self.next.push_back(id);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::commit::topo::iter::gen_and_commit_time;
+use crate::commit::topo::iter::{collect_parents, gen_and_commit_time, Boundary, GenAndCommitTime};
 use crate::commit::topo::{Error, Sorting, WalkFlags};
 use crate::commit::{find, Info, Parents, Topo};
 use gix_hash::{oid, ObjectId};
@@ -14,6 +14,8 @@ pub struct Builder<Find, Predicate> {
     parents: Parents,
     tips: Vec<ObjectId>,
     ends: Vec<ObjectId>,
+    symmetric: bool,
+    boundary: bool,
 }
 
 impl<Find> Builder<Find, fn(&oid) -> bool>
@@ -40,10 +42,25 @@ where
             parents: Default::default(),
             tips,
             ends,
+            symmetric: false,
+            boundary: false,
             predicate: |_| true,
         }
     }
 
+    /// Create a new `Builder` for a [`Topo`] that reads commits from a repository with `find`
+    /// and returns all commits reachable from either `left` or `right`, but not from both.
+    /// Like `git rev-list --topo-order left...right`.
+    ///
+    /// The merge-bases of `left` and `right` are computed when [building](Self::build()) the walk and used as ends.
+    /// Use [`Topo::with_flags()`] to learn which side each commit is on.
+    pub fn from_symmetric_difference(find: Find, left: impl Into<ObjectId>, right: impl Into<ObjectId>) -> Self {
+        Self {
+            symmetric: true,
+            ..Self::from_iters(find, [left.into(), right.into()], None::<Option<ObjectId>>)
+        }
+    }
+
     /// Set a `predicate` to filter out revisions from the walk. Can be used to
     /// implement e.g. filtering on paths or time. This does *not* exclude the
     /// parent(s) of a revision that is excluded. Specify a revision as an 'end'
@@ -59,6 +76,8 @@ where
             parents: self.parents,
             tips: self.tips,
             ends: self.ends,
+            symmetric: self.symmetric,
+            boundary: self.boundary,
             predicate,
         }
     }
@@ -81,6 +100,15 @@ where
         self
     }
 
+    /// If `boundary` is `true`, return the excluded parents of all returned commits after all other commits,
+    /// like `git rev-list --boundary`.
+    ///
+    /// They are marked with [`Flags::Boundary`](super::Flags::Boundary) when using [`Topo::with_flags()`].
+    pub fn boundary(mut self, boundary: bool) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
     /// Set or unset the `commit_graph` to use for the iteration.
     pub fn with_commit_graph(mut self, commit_graph: Option<gix_commitgraph::Graph>) -> Self {
         self.commit_graph = commit_graph;
@@ -90,7 +118,7 @@ where
     /// Build a new [`Topo`] instance.
     ///
     /// Note that merely building an instance is currently expensive.
-    pub fn build(self) -> Result<Topo<Find, Predicate>, Error> {
+    pub fn build(mut self) -> Result<Topo<Find, Predicate>, Error> {
         let mut w = Topo {
             commit_graph: self.commit_graph,
             find: self.find,
@@ -103,8 +131,16 @@ where
             parents: self.parents,
             min_gen: gix_commitgraph::GENERATION_NUMBER_INFINITY,
             buf: vec![],
+            boundary: self.boundary.then(|| Boundary::new(self.sorting)),
         };
 
+        let mut side_flags = Vec::new();
+        if self.symmetric {
+            let (left, right) = (self.tips[0], self.tips[1]);
+            self.ends = merge_bases(w.commit_graph.as_ref(), &w.find, left, right, &mut w.buf)?;
+            side_flags = vec![WalkFlags::SymmetricLeft, WalkFlags::SymmetricRight];
+        }
+
         // Initial flags for the states of the tips and ends. All of them are
         // seen and added to the explore and indegree queues. The ends are by
         // definition (?) uninteresting and bottom.
@@ -114,7 +150,13 @@ where
         for (id, flags) in self
             .tips
             .iter()
-            .map(|id| (id, tip_flags))
+            .enumerate()
+            .map(|(idx, id)| {
+                (
+                    id,
+                    tip_flags | side_flags.get(idx).copied().unwrap_or(WalkFlags::empty()),
+                )
+            })
             .chain(self.ends.iter().map(|id| (id, end_flags)))
         {
             *w.indegrees.entry(*id).or_default() = 1;
@@ -125,7 +167,7 @@ where
                 w.min_gen = gen;
             }
 
-            w.states.insert(*id, flags);
+            *w.states.entry(*id).or_insert(WalkFlags::empty()) |= flags;
             w.explore_queue.insert((gen, time), *id);
             w.indegree_queue.insert((gen, time), *id);
         }
@@ -150,7 +192,7 @@ where
         for id in self.tips.iter() {
             let i = w.indegrees.get(id).ok_or(Error::MissingIndegreeUnexpected)?;
 
-            if *i != 1 {
+            if *i != 1 || matches!(w.states.get(id), Some(s) if s.contains(WalkFlags::Uninteresting)) {
                 continue;
             }
 
@@ -172,3 +214,79 @@ where
         Ok(w)
     }
 }
+
+/// Compute the merge-bases of `first` and `second` by painting their ancestry until only commits reachable from
+/// both remain, and remove those reachable from other merge-bases. This is what `git merge-base --all` does.
+fn merge_bases(
+    cache: Option<&gix_commitgraph::Graph>,
+    objects: &impl gix_object::Find,
+    first: ObjectId,
+    second: ObjectId,
+    buf: &mut Vec<u8>,
+) -> Result<Vec<ObjectId>, Error> {
+    const FIRST: u8 = 1 << 0;
+    const SECOND: u8 = 1 << 1;
+    const STALE: u8 = 1 << 2;
+    const RESULT: u8 = 1 << 3;
+
+    if first == second {
+        return Ok(vec![first]);
+    }
+    let mut flags = IdMap::<u8>::default();
+    let mut queue = PriorityQueue::<GenAndCommitTime, ObjectId>::new();
+    for (id, flag) in [(first, FIRST), (second, SECOND)] {
+        *flags.entry(id).or_default() |= flag;
+        queue.insert(gen_and_commit_time(find(cache, objects, &id, buf)?)?, id);
+    }
+
+    let mut bases = Vec::new();
+    while queue.iter_unordered().any(|id| flags[id] & STALE == 0) {
+        let (_, id) = queue.pop().expect("at least one item is queued");
+        let mut paint = flags[&id] & (FIRST | SECOND | STALE);
+        if paint == FIRST | SECOND {
+            if flags[&id] & RESULT == 0 {
+                *flags.get_mut(&id).expect("present") |= RESULT;
+                bases.push(id);
+            }
+            paint |= STALE;
+        }
+        for (parent_id, gen_time) in collect_parents(cache, objects, &id, false, buf)? {
+            let parent_flags = flags.entry(parent_id).or_default();
+            if *parent_flags & paint == paint {
+                continue;
+            }
+            *parent_flags |= paint;
+            queue.insert(gen_time, parent_id);
+        }
+    }
+
+    if bases.len() < 2 {
+        return Ok(bases);
+    }
+    let mut redundant = Vec::new();
+    for (idx, base) in bases.iter().enumerate() {
+        let mut seen = gix_hashtable::HashSet::default();
+        let mut queue: Vec<_> = bases
+            .iter()
+            .enumerate()
+            .filter(|(other_idx, _)| *other_idx != idx && !redundant.contains(other_idx))
+            .map(|(_, id)| *id)
+            .collect();
+        while let Some(id) = queue.pop() {
+            if id == *base {
+                redundant.push(idx);
+                break;
+            }
+            for (parent_id, _) in collect_parents(cache, objects, &id, false, buf)? {
+                if seen.insert(parent_id) {
+                    queue.push(parent_id);
+                }
+            }
+        }
+    }
+    Ok(bases
+        .into_iter()
+        .enumerate()
+        .filter_map(|(idx, id)| (!redundant.contains(&idx)).then_some(id))
+        .collect())
+}
@@ -1,8 +1,10 @@
-use crate::commit::topo::{Error, Sorting, WalkFlags};
+use crate::commit::topo::{Error, Flags, Sorting, WalkFlags, WithFlags};
 use crate::commit::{find, Either, Info, Parents, Topo};
 use gix_hash::{oid, ObjectId};
+use gix_revwalk::graph::IdMap;
 use gix_revwalk::PriorityQueue;
 use smallvec::SmallVec;
+use std::collections::VecDeque;
 
 pub(in crate::commit) type GenAndCommitTime = (u32, i64);
 
@@ -45,6 +47,25 @@ impl Queue {
     }
 }
 
+/// The state needed to return boundary commits once all other commits were returned.
+pub(in crate::commit) struct Boundary {
+    sorting: Sorting,
+    /// Parents of returned commits in the order they were encountered, which may be boundary commits.
+    candidates: Vec<ObjectId>,
+    /// The boundary commits in the order they are returned, available once all other commits were returned.
+    commits: Option<VecDeque<Info>>,
+}
+
+impl Boundary {
+    pub(super) fn new(sorting: Sorting) -> Self {
+        Boundary {
+            sorting,
+            candidates: Vec::new(),
+            commits: None,
+        }
+    }
+}
+
 impl<Find, Predicate> Topo<Find, Predicate>
 where
     Find: gix_object::Find,
@@ -166,10 +187,10 @@ where
             }
             (flags, flags)
         } else {
-            // NOTE: git sets SEEN like we do but keeps the SYMMETRIC_LEFT and
-            // ANCENSTRY_PATH if they are set, but they have no purpose here.
-            let flags = WalkFlags::empty();
-            (flags, WalkFlags::Seen)
+            // NOTE: git sets SEEN like we do but also keeps ANCESTRY_PATH if
+            // it is set, which has no purpose here.
+            let flags = *state & (WalkFlags::SymmetricLeft | WalkFlags::SymmetricRight);
+            (flags, WalkFlags::Seen | flags)
         };
 
         for (id, _) in parents {
@@ -212,30 +233,209 @@ where
 
         Some(Ok(commit))
     }
+
+    /// Mark `commit` as shown, and its parents as candidates for boundary commits.
+    fn mark_shown(&mut self, commit: &Info) -> Flags {
+        let state = self.states.entry(commit.id).or_insert(WalkFlags::empty());
+        *state |= WalkFlags::Shown;
+        let flags = Flags::from_walk_flags(*state);
+
+        if let Some(boundary) = self.boundary.as_mut() {
+            let num_parents = match self.parents {
+                Parents::All => commit.parent_ids.len(),
+                Parents::First => 1,
+            };
+            for id in commit.parent_ids.iter().take(num_parents) {
+                let state = self.states.entry(*id).or_insert(WalkFlags::empty());
+                if state.intersects(WalkFlags::ChildShown | WalkFlags::Shown) {
+                    continue;
+                }
+                *state |= WalkFlags::ChildShown;
+                boundary.candidates.push(*id);
+            }
+        }
+        flags
+    }
+
+    fn pop_boundary_commit(&mut self) -> Option<Result<(Info, Flags), Error>> {
+        let boundary = self.boundary.as_mut()?;
+        if boundary.commits.is_none() {
+            let candidates = std::mem::take(&mut boundary.candidates);
+            let sorting = boundary.sorting;
+            match self.sort_boundary_commits(candidates, sorting) {
+                Ok(commits) => self.boundary.as_mut().expect("still set").commits = Some(commits),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+
+        let commit = self.boundary.as_mut()?.commits.as_mut()?.pop_front()?;
+        let state = self.states.get_mut(&commit.id)?;
+        *state |= WalkFlags::Shown;
+        let flags = Flags::from_walk_flags(*state);
+        Some(Ok((commit, flags)))
+    }
+
+    /// Turn the boundary `candidates` into boundary commits, and sort them topologically according to `sorting`.
+    ///
+    /// The order matches the one of `git rev-list --boundary`.
+    fn sort_boundary_commits(&mut self, candidates: Vec<ObjectId>, sorting: Sorting) -> Result<VecDeque<Info>, Error> {
+        // Like git, use the reverse order of discovery.
+        let mut commits = Vec::new();
+        for id in candidates.into_iter().rev() {
+            let state = self.states.get_mut(&id).ok_or(Error::MissingStateUnexpected)?;
+            if !state.contains(WalkFlags::ChildShown) || state.intersects(WalkFlags::Shown | WalkFlags::Boundary) {
+                continue;
+            }
+            *state |= WalkFlags::Boundary;
+
+            let commit = find(self.commit_graph.as_ref(), &self.find, &id, &mut self.buf)?;
+            let (_, time) = gen_and_commit_time(commit)?;
+            let parent_ids = self.collect_all_parents(&id)?.into_iter().map(|e| e.0).collect();
+            commits.push(Info {
+                id,
+                parent_ids,
+                commit_time: Some(time),
+            });
+        }
+
+        // Each commit has an indegree of 1 plus the amount of its children among the boundary commits.
+        let mut indegrees = IdMap::<usize>::default();
+        for commit in &commits {
+            indegrees.insert(commit.id, 1);
+        }
+        for commit in &commits {
+            for id in &commit.parent_ids {
+                if let Some(indegree) = indegrees.get_mut(id) {
+                    *indegree += 1;
+                }
+            }
+        }
+
+        let mut queue = BoundaryQueue::new(sorting);
+        let mut by_id: IdMap<Info> = IdMap::default();
+        for commit in commits {
+            if indegrees[&commit.id] == 1 {
+                queue.push(commit);
+            } else {
+                by_id.insert(commit.id, commit);
+            }
+        }
+        queue.initial_sort();
+
+        let mut out = VecDeque::new();
+        while let Some(commit) = queue.pop() {
+            for id in &commit.parent_ids {
+                let Some(indegree) = indegrees.get_mut(id).filter(|i| **i != 0) else {
+                    continue;
+                };
+                *indegree -= 1;
+                if *indegree == 1 {
+                    queue.push(by_id.remove(id).ok_or(Error::MissingIndegreeUnexpected)?);
+                }
+            }
+            *indegrees.get_mut(&commit.id).expect("present") = 0;
+            out.push_back(commit);
+        }
+        Ok(out)
+    }
 }
 
-impl<Find, Predicate> Iterator for Topo<Find, Predicate>
+impl<Find, Predicate> Topo<Find, Predicate>
 where
     Find: gix_object::Find,
     Predicate: FnMut(&oid) -> bool,
 {
-    type Item = Result<Info, Error>;
-
-    fn next(&mut self) -> Option<Self::Item> {
+    fn next_with_flags(&mut self) -> Option<Result<(Info, Flags), Error>> {
         loop {
-            match self.pop_commit()? {
-                Ok(id) => {
-                    if (self.predicate)(&id.id) {
-                        return Some(Ok(id));
+            match self.pop_commit() {
+                Some(Ok(commit)) => {
+                    if (self.predicate)(&commit.id) {
+                        let flags = self.mark_shown(&commit);
+                        return Some(Ok((commit, flags)));
                     }
                 }
-                Err(e) => return Some(Err(e)),
+                Some(Err(e)) => return Some(Err(e)),
+                None => return self.pop_boundary_commit(),
+            }
+        }
+    }
+}
+
+/// A queue like the one used by `git` to sort a list of commits topologically, with commits sorted by date
+/// or in the order they are provided.
+enum BoundaryQueue {
+    Date(PriorityQueue<(i64, std::cmp::Reverse<usize>), Info>, usize),
+    Topo(Vec<Info>),
+}
+
+impl BoundaryQueue {
+    fn new(sorting: Sorting) -> Self {
+        match sorting {
+            Sorting::DateOrder => Self::Date(PriorityQueue::new(), 0),
+            Sorting::TopoOrder => Self::Topo(Vec::new()),
+        }
+    }
+
+    fn push(&mut self, commit: Info) {
+        match self {
+            Self::Date(q, count) => {
+                *count += 1;
+                q.insert(
+                    (commit.commit_time.unwrap_or_default(), std::cmp::Reverse(*count)),
+                    commit,
+                );
             }
+            Self::Topo(q) => q.push(commit),
+        }
+    }
+
+    fn pop(&mut self) -> Option<Info> {
+        match self {
+            Self::Date(q, _) => q.pop_value(),
+            Self::Topo(q) => q.pop(),
+        }
+    }
+
+    /// Make sure the initial commits are returned in the order they were pushed.
+    fn initial_sort(&mut self) {
+        if let Self::Topo(q) = self {
+            q.reverse();
         }
     }
 }
 
-fn collect_parents<Find>(
+impl<Find, Predicate> Topo<Find, Predicate> {
+    /// Return an iterator which also returns the [`Flags`] of each commit.
+    pub fn with_flags(self) -> WithFlags<Find, Predicate> {
+        WithFlags(self)
+    }
+}
+
+impl<Find, Predicate> Iterator for Topo<Find, Predicate>
+where
+    Find: gix_object::Find,
+    Predicate: FnMut(&oid) -> bool,
+{
+    type Item = Result<Info, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_flags().map(|res| res.map(|(commit, _flags)| commit))
+    }
+}
+
+impl<Find, Predicate> Iterator for WithFlags<Find, Predicate>
+where
+    Find: gix_object::Find,
+    Predicate: FnMut(&oid) -> bool,
+{
+    type Item = Result<(Info, Flags), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next_with_flags()
+    }
+}
+
+pub(super) fn collect_parents<Find>(
     cache: Option<&gix_commitgraph::Graph>,
     f: Find,
     id: &oid,
@@ -23,7 +23,7 @@ bitflags! {
     // NOTE: The names correspond to the names of the flags in revision.h
     #[repr(transparent)]
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-    pub(super) struct WalkFlags: u8 {
+    pub(super) struct WalkFlags: u16 {
         /// Commit has been seen
         const Seen = 0b000001;
         /// Commit has been processed by the Explore walk
@@ -36,6 +36,48 @@ bitflags! {
         const Bottom = 0b010000;
         /// Parents have been processed
         const Added = 0b100000;
+        /// Commit is reachable from the left side of a symmetric difference
+        const SymmetricLeft = 0b1000000;
+        /// Commit is reachable from the right side of a symmetric difference
+        const SymmetricRight = 0b10000000;
+        /// Commit is a parent of a commit that was returned
+        const ChildShown = 0b100000000;
+        /// Commit was returned
+        const Shown = 0b1000000000;
+        /// Commit was returned as boundary commit
+        const Boundary = 0b10000000000;
+    }
+}
+
+bitflags! {
+    /// Flags to describe how a commit returned by [`WithFlags`] relates to the tips and ends of the walk.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct Flags: u8 {
+        /// The commit is reachable from the left tip of a [symmetric difference](Builder::from_symmetric_difference()),
+        /// like `<` in `git log --left-right`.
+        const Left = 0b0001;
+        /// The commit is reachable from the right tip of a [symmetric difference](Builder::from_symmetric_difference()),
+        /// like `>` in `git log --left-right`.
+        const Right = 0b0010;
+        /// The commit is an excluded parent of a returned commit, like `-` in `git log --boundary`.
+        const Boundary = 0b0100;
+        /// The commit is reachable from one of the ends, which is only the case for boundary commits.
+        const Uninteresting = 0b1000;
+    }
+}
+
+impl Flags {
+    pub(super) fn from_walk_flags(flags: WalkFlags) -> Self {
+        let mut out = Flags::empty();
+        for (walk_flag, flag) in [
+            (WalkFlags::SymmetricLeft, Flags::Left),
+            (WalkFlags::SymmetricRight, Flags::Right),
+            (WalkFlags::Boundary, Flags::Boundary),
+            (WalkFlags::Uninteresting, Flags::Uninteresting),
+        ] {
+            out.set(flag, flags.contains(walk_flag));
+        }
+        out
     }
 }
 
@@ -67,6 +109,11 @@ pub enum Sorting {
     TopoOrder,
 }
 
+/// An iterator over the commits of a [`Topo`](super::Topo) walk along with their [`Flags`].
+///
+/// Create it with [`Topo::with_flags()`](super::Topo::with_flags()).
+pub struct WithFlags<Find, Predicate>(pub(super) super::Topo<Find, Predicate>);
+
 mod init;
 pub use init::Builder;
 
@@ -1,11 +1,162 @@
 use gix_hash::{oid, ObjectId};
 use gix_traverse::commit;
 
-use crate::hex_to_id;
+use crate::fixture_repo::{Fixture, FixtureRepo};
+
+/// The histories to traverse, each built natively and, where `git` is used as oracle, also by a script.
+#[derive(Debug, Copy, Clone)]
+enum Scenario {
+    /// All commits have the same date.
+    SameDate,
+    /// Commits are a year apart, but `c1` and `c2` have the same date.
+    WithDates,
+    /// An octopus merge of two branches, with each commit a minute apart.
+    Simple,
+    /// Like [`Scenario::Simple`], but with commits of both branches alternating.
+    Intermixed,
+    /// Two merges of the same two commits in different order, merged again.
+    CrissCross,
+}
+
+impl Scenario {
+    const ALL: [Scenario; 5] = [
+        Scenario::SameDate,
+        Scenario::WithDates,
+        Scenario::Simple,
+        Scenario::Intermixed,
+        Scenario::CrissCross,
+    ];
+
+    fn build(self) -> FixtureRepo {
+        match self {
+            Scenario::SameDate => FixtureRepo::new()
+                .commit("c1")
+                .commit("c2")
+                .commit("c3")
+                .commit("c4")
+                .branch("branch1")
+                .commit("b1c1")
+                .commit("b1c2")
+                .checkout("main")
+                .commit("c5")
+                .merge("m1b1", ["branch1"]),
+            Scenario::WithDates => FixtureRepo::new()
+                .commit("c1")
+                .branch("branch1")
+                .committed_at(978393600 /* 2001-01-02 00:00:00 +0000 */)
+                .commit("b1c1")
+                .checkout("main")
+                .commit("c2")
+                .committed_at(1009929600 /* 2002-01-02 00:00:00 +0000 */)
+                .merge("m1b1", ["branch1"]),
+            Scenario::Simple => FixtureRepo::new()
+                .ticking_from(1112911993, -7 * 3600)
+                .commit("c1")
+                .commit("c2")
+                .commit("c3")
+                .commit("c4")
+                .branch("branch1")
+                .branch("branch2")
+                .commit("b2c1")
+                .commit("b2c2")
+                .checkout("branch1")
+                .commit("b1c1")
+                .commit("b1c2")
+                .checkout("main")
+                .commit("c5")
+                .merge("merge", ["branch1", "branch2"]),
+            Scenario::Intermixed => FixtureRepo::new()
+                .ticking_from(1112911993, -7 * 3600)
+                .commit("c1")
+                .commit("c2")
+                .branch("branch1")
+                .branch("branch2")
+                .commit("b2c1")
+                .checkout("branch1")
+                .commit("b1c1")
+                .checkout("branch2")
+                .commit("b2c2")
+                .checkout("branch1")
+                .commit("b1c2")
+                .checkout("main")
+                .commit("c3")
+                .merge("merge", ["branch1", "branch2"]),
+            Scenario::CrissCross => FixtureRepo::new()
+                .ticking_from(1112911993, -7 * 3600)
+                .commit("c1")
+                .branch("branch1")
+                .commit("b1c1")
+                .checkout("main")
+                .commit("c2")
+                .merge("m1", ["b1c1"])
+                .checkout("branch1")
+                .merge("m2", ["c2"])
+                .checkout("main")
+                .merge("merge", ["branch1"]),
+        }
+    }
+
+    /// The script and the name of the repository within it that creates the same history with `git`, if there is one.
+    fn script(self) -> Option<(&'static str, &'static str)> {
+        Some(match self {
+            Scenario::SameDate => ("make_traversal_repo_for_commits_same_date.sh", ""),
+            Scenario::WithDates => ("make_traversal_repo_for_commits_with_dates.sh", ""),
+            Scenario::Simple => ("make_repos.sh", "simple"),
+            Scenario::Intermixed => ("make_repos.sh", "intermixed"),
+            Scenario::CrissCross => return None,
+        })
+    }
+
+    /// Return the natively built fixture, followed by the one created by `git` if there is a script for it.
+    fn fixtures(self) -> crate::Result<Vec<Fixture>> {
+        let mut out = vec![self.build().build()?];
+        if let Some((script, repo_name)) = self.script() {
+            out.push(Fixture::from_script(script, repo_name)?);
+        }
+        Ok(out)
+    }
+}
+
+#[test]
+fn scenarios_produce_the_same_commits_as_git() -> crate::Result {
+    for scenario in Scenario::ALL {
+        let fixtures = scenario.fixtures()?;
+        if let [native, git] = fixtures.as_slice() {
+            assert_eq!(
+                native.ids(),
+                git.ids(),
+                "{scenario:?}: hashes are stable and match the ones of git"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn replacements_are_written_as_references() -> crate::Result {
+    let fixture = FixtureRepo::new()
+        .commit("c1")
+        .commit("c2")
+        .branch("branch1")
+        .commit("b1c1")
+        .replace("c2", "b1c1")
+        .build()?;
+    let refs = gix_ref::file::Store::at(fixture.git_dir().to_owned(), Default::default());
+    let replacement = refs.find(format!("refs/replace/{}", fixture.id("c2")).as_str())?;
+    assert_eq!(replacement.target, gix_ref::Target::Peeled(fixture.id("b1c1")));
+    assert_eq!(
+        refs.find("HEAD")?
+            .target
+            .try_name()
+            .map(|name| name.as_bstr().to_owned()),
+        Some("refs/heads/branch1".into()),
+        "HEAD points to the branch that was checked out last"
+    );
+    Ok(())
+}
 
 struct TraversalAssertion<'a> {
-    init_script: &'a str,
-    repo_name: &'a str,
+    scenario: Scenario,
     tips: &'a [&'a str],
     expected: &'a [&'a str],
     mode: commit::Parents,
@@ -13,14 +164,9 @@ struct TraversalAssertion<'a> {
 }
 
 impl<'a> TraversalAssertion<'a> {
-    fn new(init_script: &'a str, tips: &'a [&'a str], expected: &'a [&'a str]) -> Self {
-        Self::new_at(init_script, "", tips, expected)
-    }
-
-    fn new_at(init_script: &'a str, repo_name: &'a str, tips: &'a [&'a str], expected: &'a [&'a str]) -> Self {
+    fn new(scenario: Scenario, tips: &'a [&'a str], expected: &'a [&'a str]) -> Self {
         TraversalAssertion {
-            init_script,
-            repo_name,
+            scenario,
             tips,
             expected,
             mode: Default::default(),
@@ -40,99 +186,133 @@ impl<'a> TraversalAssertion<'a> {
 }
 
 impl TraversalAssertion<'_> {
-    fn setup(&self) -> crate::Result<(gix_odb::Handle, Vec<ObjectId>, Vec<ObjectId>)> {
-        let dir = gix_testtools::scripted_fixture_read_only_standalone(self.init_script)?;
-        let store = gix_odb::at(dir.join(self.repo_name).join(".git").join("objects"))?;
-        let tips: Vec<_> = self.tips.iter().copied().map(hex_to_id).collect();
+    fn setup(&self, fixture: &Fixture) -> crate::Result<(gix_odb::Handle, Vec<ObjectId>, Vec<ObjectId>)> {
+        let store = fixture.odb()?;
+        let tips: Vec<_> = self.tips.iter().map(|name| fixture.id(name)).collect();
         let expected: Vec<ObjectId> = tips
             .clone()
             .into_iter()
-            .chain(self.expected.iter().map(|hex_id| hex_to_id(hex_id)))
+            .chain(self.expected.iter().map(|name| fixture.id(name)))
             .collect();
         Ok((store, tips, expected))
     }
 
-    fn setup_commitgraph(&self, store: &gix_odb::Store, use_graph: bool) -> Option<gix_commitgraph::Graph> {
-        use_graph
-            .then(|| gix_commitgraph::at(store.path().join("info")))
-            .transpose()
-            .expect("graph can be loaded if it exists")
+    /// Return the commit-graphs to use for each traversal, which includes the one of `fixture` if it has one.
+    fn commit_graphs(fixture: &Fixture) -> Vec<Option<gix_commitgraph::Graph>> {
+        std::iter::once(None).chain(fixture.commit_graph().map(Some)).collect()
     }
 
-    fn check_with_predicate(&mut self, predicate: impl FnMut(&oid) -> bool + Clone) -> crate::Result<()> {
-        let (store, tips, expected) = self.setup()?;
-
-        for use_commitgraph in [false, true] {
-            let oids = commit::Simple::filtered(tips.clone(), &store, predicate.clone())
-                .sorting(self.sorting)?
-                .parents(self.mode)
-                .commit_graph(self.setup_commitgraph(store.store_ref(), use_commitgraph))
-                .map(|res| res.map(|info| info.id))
-                .collect::<Result<Vec<_>, _>>()?;
-
-            assert_eq!(oids, expected);
+    /// Check with the `predicate` returned for each fixture.
+    fn check_with_predicate<P>(&mut self, predicate: impl Fn(&Fixture) -> P) -> crate::Result<()>
+    where
+        P: FnMut(&oid) -> bool + Clone,
+    {
+        for fixture in self.scenario.fixtures()? {
+            let (store, tips, expected) = self.setup(&fixture)?;
+            let predicate = predicate(&fixture);
+
+            for graph in Self::commit_graphs(&fixture) {
+                let oids = commit::Simple::filtered(tips.clone(), &store, predicate.clone())
+                    .sorting(self.sorting)?
+                    .parents(self.mode)
+                    .commit_graph(graph)
+                    .map(|res| res.map(|info| info.id))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                assert_eq!(oids, expected);
+            }
+            assert_eq!(self.walk_out_of_order(&store, &tips, predicate)?, expected);
         }
         Ok(())
     }
 
+    /// Traverse with a [`commit::simple::Walk`], providing the commits it asks for in reverse order along with
+    /// their parents, before these are needed.
+    fn walk_out_of_order(
+        &self,
+        store: &gix_odb::Handle,
+        tips: &[ObjectId],
+        mut predicate: impl FnMut(&oid) -> bool,
+    ) -> crate::Result<Vec<ObjectId>> {
+        use gix_object::FindExt;
+        let mut walk = commit::simple::Walk::filtered(tips.iter().copied(), self.sorting, self.mode, &mut predicate);
+        let mut buf = Vec::new();
+        let mut provide = |walk: &mut commit::simple::Walk, id: ObjectId| -> crate::Result<Vec<ObjectId>> {
+            let commit = store.find_commit(&id, &mut buf)?;
+            let parents: Vec<_> = commit.parents().collect();
+            walk.provide_commit(id, Some(commit.committer.time.seconds), parents.iter().copied());
+            Ok(parents)
+        };
+        let mut oids = Vec::new();
+        loop {
+            match walk.step(&mut predicate) {
+                commit::simple::walk::Step::Commit(info) => oids.push(info.id),
+                commit::simple::walk::Step::Done => break,
+                commit::simple::walk::Step::NeedsCommits => {
+                    let candidates = walk.next_candidates();
+                    assert!(!candidates.is_empty(), "there is something to provide");
+                    assert_eq!(walk.next_candidates(), candidates, "candidates don't change by themselves");
+                    for id in candidates.into_iter().rev() {
+                        for parent_id in provide(&mut walk, id)? {
+                            provide(&mut walk, parent_id)?;
+                        }
+                    }
+                    assert!(walk.next_candidates().is_empty());
+                }
+            }
+        }
+        Ok(oids)
+    }
+
     fn check(&self) -> crate::Result {
-        let (store, tips, expected) = self.setup()?;
-
-        for use_commitgraph in [false, true] {
-            let oids = commit::Simple::new(tips.clone(), &store)
-                .sorting(self.sorting)?
-                .parents(self.mode)
-                .commit_graph(self.setup_commitgraph(store.store_ref(), use_commitgraph))
-                .map(|res| res.map(|info| info.id))
-                .collect::<Result<Vec<_>, _>>()?;
-            assert_eq!(oids, expected);
+        for fixture in self.scenario.fixtures()? {
+            let (store, tips, expected) = self.setup(&fixture)?;
+
+            for graph in Self::commit_graphs(&fixture) {
+                let oids = commit::Simple::new(tips.clone(), &store)
+                    .sorting(self.sorting)?
+                    .parents(self.mode)
+                    .commit_graph(graph)
+                    .map(|res| res.map(|info| info.id))
+                    .collect::<Result<Vec<_>, _>>()?;
+                assert_eq!(oids, expected);
+            }
+            assert_eq!(self.walk_out_of_order(&store, &tips, |_| true)?, expected);
         }
         Ok(())
     }
 }
 
+#[test]
+fn walk_can_be_sent_to_other_threads() {
+    fn assert_send<T: Send>() {}
+    assert_send::<commit::simple::Walk>();
+}
+
 mod different_date_intermixed {
     use gix_traverse::commit::simple::Sorting;
 
-    use crate::commit::simple::TraversalAssertion;
+    use crate::commit::simple::{Scenario, TraversalAssertion};
 
     #[test]
     fn head_breadth_first() -> crate::Result {
-        TraversalAssertion::new_at(
-            "make_repos.sh",
-            "intermixed",
-            &["58912d92944087dcb09dca79cdd2a937cc158bed"], /* merge */
+        TraversalAssertion::new(
+            Scenario::Intermixed,
+            &["merge"],
             // This is very different from what git does as it keeps commits together,
             // whereas we spread them out breadth-first.
-            &[
-                "2dce37be587e07caef8c4a5ab60b423b13a8536a", /* c3 */
-                "0f6632a5a7d81417488b86692b729e49c1b73056", /* b1c2 */
-                "a9c28710e058af4e5163699960234adb9fb2abc7", /* b2c2 */
-                "ad33ff2d0c4fc77d56b5fbff6f86f332fe792d83", /* c2 */
-                "77fd3c6832c0cd542f7a39f3af9250c3268db979", /* b1c1 */
-                "b648f955b930ca95352fae6f22cb593ee0244b27", /* b2c1 */
-                "65d6af66f60b8e39fd1ba6a1423178831e764ec5", /* c1 */
-            ],
+            &["c3", "b1c2", "b2c2", "c2", "b1c1", "b2c1", "c1"],
         )
         .check()
     }
 
     #[test]
     fn head_date_order() -> crate::Result {
-        TraversalAssertion::new_at(
-            "make_repos.sh",
-            "intermixed",
-            &["58912d92944087dcb09dca79cdd2a937cc158bed"], /* merge */
+        TraversalAssertion::new(
+            Scenario::Intermixed,
+            &["merge"],
             // This is exactly what git shows.
-            &[
-                "2dce37be587e07caef8c4a5ab60b423b13a8536a", /* c3 */
-                "0f6632a5a7d81417488b86692b729e49c1b73056", /* b1c2 */
-                "a9c28710e058af4e5163699960234adb9fb2abc7", /* b2c2 */
-                "77fd3c6832c0cd542f7a39f3af9250c3268db979", /* b1c1 */
-                "b648f955b930ca95352fae6f22cb593ee0244b27", /* b2c1 */
-                "ad33ff2d0c4fc77d56b5fbff6f86f332fe792d83", /* c2 */
-                "65d6af66f60b8e39fd1ba6a1423178831e764ec5", /* c1 */
-            ],
+            &["c3", "b1c2", "b2c2", "b1c1", "b2c1", "c2", "c1"],
         )
         .with_sorting(Sorting::ByCommitTimeNewestFirst)
         .check()
@@ -142,49 +322,27 @@ mod different_date_intermixed {
 mod different_date {
     use gix_traverse::commit::simple::Sorting;
 
-    use crate::commit::simple::TraversalAssertion;
+    use crate::commit::simple::{Scenario, TraversalAssertion};
 
     #[test]
     fn head_breadth_first() -> crate::Result {
-        TraversalAssertion::new_at(
-            "make_repos.sh",
-            "simple",
-            &["f49838d84281c3988eeadd988d97dd358c9f9dc4"], /* merge */
+        TraversalAssertion::new(
+            Scenario::Simple,
+            &["merge"],
             // This is very different from what git does as it keeps commits together,
             // whereas we spread them out breadth-first.
-            &[
-                "0edb95c0c0d9933d88f532ec08fcd405d0eee882", /* c5 */
-                "66a309480201c4157b0eae86da69f2d606aadbe7", /* b1c2 */
-                "48e8dac19508f4238f06c8de2b10301ce64a641c", /* b2c2 */
-                "8cb5f13b66ce52a49399a2c49f537ee2b812369c", /* c4 */
-                "80947acb398362d8236fcb8bf0f8a9dac640583f", /* b1c1 */
-                "cb6a6befc0a852ac74d74e0354e0f004af29cb79", /* b2c1 */
-                "33aa07785dd667c0196064e3be3c51dd9b4744ef", /* c3 */
-                "ad33ff2d0c4fc77d56b5fbff6f86f332fe792d83", /* c2 */
-                "65d6af66f60b8e39fd1ba6a1423178831e764ec5", /* c1 */
-            ],
+            &["c5", "b1c2", "b2c2", "c4", "b1c1", "b2c1", "c3", "c2", "c1"],
         )
         .check()
     }
 
     #[test]
     fn head_date_order() -> crate::Result {
-        TraversalAssertion::new_at(
-            "make_repos.sh",
-            "simple",
-            &["f49838d84281c3988eeadd988d97dd358c9f9dc4"], /* merge */
+        TraversalAssertion::new(
+            Scenario::Simple,
+            &["merge"],
             // This is exactly what git shows.
-            &[
-                "0edb95c0c0d9933d88f532ec08fcd405d0eee882", /* c5 */
-                "66a309480201c4157b0eae86da69f2d606aadbe7", /* b1c2 */
-                "80947acb398362d8236fcb8bf0f8a9dac640583f", /* b1c1 */
-                "48e8dac19508f4238f06c8de2b10301ce64a641c", /* b2c2 */
-                "cb6a6befc0a852ac74d74e0354e0f004af29cb79", /* b2c1 */
-                "8cb5f13b66ce52a49399a2c49f537ee2b812369c", /* c4 */
-                "33aa07785dd667c0196064e3be3c51dd9b4744ef", /* c3 */
-                "ad33ff2d0c4fc77d56b5fbff6f86f332fe792d83", /* c2 */
-                "65d6af66f60b8e39fd1ba6a1423178831e764ec5", /* c1 */
-            ],
+            &["c5", "b1c2", "b1c1", "b2c2", "b2c1", "c4", "c3", "c2", "c1"],
         )
         .with_sorting(Sorting::ByCommitTimeNewestFirst)
         .check()
@@ -195,39 +353,22 @@ mod different_date {
 mod same_date {
     use gix_traverse::commit::{simple::Sorting, Parents};
 
-    use crate::{commit::simple::TraversalAssertion, hex_to_id};
+    use crate::commit::simple::{Scenario, TraversalAssertion};
 
     #[test]
     fn c4_breadth_first() -> crate::Result {
-        TraversalAssertion::new(
-            "make_traversal_repo_for_commits_same_date.sh",
-            &["9556057aee5abb06912922e9f26c46386a816822"], /* c4 */
-            &[
-                "17d78c64cef6c33a10a604573fd2c429e477fd63", /* c3 */
-                "9902e3c3e8f0c569b4ab295ddf473e6de763e1e7", /* c2 */
-                "134385f6d781b7e97062102c6a483440bfda2a03", /* c1 */
-            ],
-        )
-        .check()
+        TraversalAssertion::new(Scenario::SameDate, &["c4"], &["c3", "c2", "c1"]).check()
     }
 
     #[test]
     fn head_breadth_first() -> crate::Result {
         TraversalAssertion::new(
-            "make_traversal_repo_for_commits_same_date.sh",
-            &["01ec18a3ebf2855708ad3c9d244306bc1fae3e9b"], /* m1b1 */
+            Scenario::SameDate,
+            &["m1b1"],
             // We always take the first parent first, then the second, and so on.
             // Deviation: git for some reason displays b1c2 *before* c5, but I think it's better
             //            to have a strict parent order.
-            &[
-                "efd9a841189668f1bab5b8ebade9cd0a1b139a37", /* c5 */
-                "ce2e8ffaa9608a26f7b21afc1db89cadb54fd353", /* b1c2 */
-                "9556057aee5abb06912922e9f26c46386a816822", /* c4 */
-                "9152eeee2328073cf23dcf8e90c949170b711659", /* b1c1 */
-                "17d78c64cef6c33a10a604573fd2c429e477fd63", /* c3 */
-                "9902e3c3e8f0c569b4ab295ddf473e6de763e1e7", /* c2 */
-                "134385f6d781b7e97062102c6a483440bfda2a03", /* c1 */
-            ],
+            &["c5", "b1c2", "c4", "b1c1", "c3", "c2", "c1"],
         )
         .check()
     }
@@ -235,17 +376,9 @@ mod same_date {
     #[test]
     fn head_date_order() -> crate::Result {
         TraversalAssertion::new(
-            "make_traversal_repo_for_commits_same_date.sh",
-            &["01ec18a3ebf2855708ad3c9d244306bc1fae3e9b"], /* m1b1 */
-            &[
-                "efd9a841189668f1bab5b8ebade9cd0a1b139a37", /* c5 */
-                "ce2e8ffaa9608a26f7b21afc1db89cadb54fd353", /* b1c2 */
-                "9556057aee5abb06912922e9f26c46386a816822", /* c4 */
-                "9152eeee2328073cf23dcf8e90c949170b711659", /* b1c1 */
-                "17d78c64cef6c33a10a604573fd2c429e477fd63", /* c3 */
-                "9902e3c3e8f0c569b4ab295ddf473e6de763e1e7", /* c2 */
-                "134385f6d781b7e97062102c6a483440bfda2a03", /* c1 */
-            ],
+            Scenario::SameDate,
+            &["m1b1"],
+            &["c5", "b1c2", "c4", "b1c1", "c3", "c2", "c1"],
         )
         .with_sorting(Sorting::ByCommitTimeNewestFirst)
         .check()
@@ -253,37 +386,17 @@ mod same_date {
 
     #[test]
     fn head_first_parent_only_breadth_first() -> crate::Result {
-        TraversalAssertion::new(
-            "make_traversal_repo_for_commits_same_date.sh",
-            &["01ec18a3ebf2855708ad3c9d244306bc1fae3e9b"], /* m1b1 */
-            &[
-                "efd9a841189668f1bab5b8ebade9cd0a1b139a37", /* c5 */
-                "9556057aee5abb06912922e9f26c46386a816822", /* c4 */
-                "17d78c64cef6c33a10a604573fd2c429e477fd63", /* c3 */
-                "9902e3c3e8f0c569b4ab295ddf473e6de763e1e7", /* c2 */
-                "134385f6d781b7e97062102c6a483440bfda2a03", /* c1 */
-            ],
-        )
-        .with_parents(Parents::First)
-        .check()
+        TraversalAssertion::new(Scenario::SameDate, &["m1b1"], &["c5", "c4", "c3", "c2", "c1"])
+            .with_parents(Parents::First)
+            .check()
     }
 
     #[test]
     fn head_c4_breadth_first() -> crate::Result {
         TraversalAssertion::new(
-            "make_traversal_repo_for_commits_same_date.sh",
-            &[
-                "01ec18a3ebf2855708ad3c9d244306bc1fae3e9b", /* m1b1 */
-                "9556057aee5abb06912922e9f26c46386a816822", /* c4 */
-            ],
-            &[
-                "efd9a841189668f1bab5b8ebade9cd0a1b139a37", /* c5 */
-                "ce2e8ffaa9608a26f7b21afc1db89cadb54fd353", /* b1c2 */
-                "17d78c64cef6c33a10a604573fd2c429e477fd63", /* c3 */
-                "9152eeee2328073cf23dcf8e90c949170b711659", /* b1c1 */
-                "9902e3c3e8f0c569b4ab295ddf473e6de763e1e7", /* c2 */
-                "134385f6d781b7e97062102c6a483440bfda2a03", /* c1 */
-            ],
+            Scenario::SameDate,
+            &["m1b1", "c4"],
+            &["c5", "b1c2", "c3", "b1c1", "c2", "c1"],
         )
         .check()
     }
@@ -294,44 +407,32 @@ mod same_date {
         // at least one of its ancestors, so this test is kind of dubious. But we do want
         // `Ancestors` to not eagerly blacklist all of a commit's ancestors when blacklisting that
         // one commit, and this test happens to check that.
-        TraversalAssertion::new(
-            "make_traversal_repo_for_commits_same_date.sh",
-            &["01ec18a3ebf2855708ad3c9d244306bc1fae3e9b"], /* m1b1 */
-            &[
-                "efd9a841189668f1bab5b8ebade9cd0a1b139a37", /* c5 */
-                "ce2e8ffaa9608a26f7b21afc1db89cadb54fd353", /* b1c2 */
-                "9556057aee5abb06912922e9f26c46386a816822", /* c4 */
-                "17d78c64cef6c33a10a604573fd2c429e477fd63", /* c3 */
-                "9902e3c3e8f0c569b4ab295ddf473e6de763e1e7", /* c2 */
-                "134385f6d781b7e97062102c6a483440bfda2a03", /* c1 */
-            ],
-        )
-        .check_with_predicate(|id| id != hex_to_id("9152eeee2328073cf23dcf8e90c949170b711659"))
+        TraversalAssertion::new(Scenario::SameDate, &["m1b1"], &["c5", "b1c2", "c4", "c3", "c2", "c1"])
+            .check_with_predicate(|fixture| {
+                let b1c1 = fixture.id("b1c1");
+                move |id: &gix_hash::oid| id != b1c1
+            })
     }
 
     #[test]
     fn predicate_only_called_once_even_if_fork_point() -> crate::Result {
         // The `self.seen` check should come before the `self.predicate` check, as we don't know how
         // expensive calling `self.predicate` may be.
-        let mut seen = false;
-        TraversalAssertion::new(
-            "make_traversal_repo_for_commits_same_date.sh",
-            &["01ec18a3ebf2855708ad3c9d244306bc1fae3e9b"], /* m1b1 */
-            &[
-                "efd9a841189668f1bab5b8ebade9cd0a1b139a37", /* c5 */
-                "ce2e8ffaa9608a26f7b21afc1db89cadb54fd353", /* b1c2 */
-                "9152eeee2328073cf23dcf8e90c949170b711659", /* b1c1 */
-            ],
+        TraversalAssertion::new(Scenario::SameDate, &["m1b1"], &["c5", "b1c2", "b1c1"]).check_with_predicate(
+            |fixture| {
+                let c4 = fixture.id("c4");
+                let mut seen = false;
+                move |id: &gix_hash::oid| {
+                    if id == c4 {
+                        assert!(!seen);
+                        seen = true;
+                        false
+                    } else {
+                        true
+                    }
+                }
+            },
         )
-        .check_with_predicate(move |id| {
-            if id == hex_to_id("9556057aee5abb06912922e9f26c46386a816822") {
-                assert!(!seen);
-                seen = true;
-                false
-            } else {
-                true
-            }
-        })
     }
 }
 
@@ -339,84 +440,111 @@ mod same_date {
 mod adjusted_dates {
     use gix_traverse::commit::{simple::Sorting, Parents, Simple};
 
-    use crate::{commit::simple::TraversalAssertion, hex_to_id};
+    use crate::commit::simple::{Scenario, TraversalAssertion};
 
     #[test]
     fn head_breadth_first() -> crate::Result {
         TraversalAssertion::new(
-            "make_traversal_repo_for_commits_with_dates.sh",
-            &["288e509293165cb5630d08f4185bdf2445bf6170"], /* m1b1 */
+            Scenario::WithDates,
+            &["m1b1"],
             // Here `git` also shows `b1c1` first, making topo-order similar to date order for some reason,
             // even though c2 *is* the first parent.
-            &[
-                "9902e3c3e8f0c569b4ab295ddf473e6de763e1e7", /* c2 */
-                "bcb05040a6925f2ff5e10d3ae1f9264f2e8c43ac", /* b1c1 */
-                "134385f6d781b7e97062102c6a483440bfda2a03", /* c1 */
-            ],
+            &["c2", "b1c1", "c1"],
         )
         .check()
     }
 
     #[test]
     fn head_date_order() -> crate::Result {
-        TraversalAssertion::new(
-            "make_traversal_repo_for_commits_with_dates.sh",
-            &["288e509293165cb5630d08f4185bdf2445bf6170"], /* m1b1 */
-            &[
-                "bcb05040a6925f2ff5e10d3ae1f9264f2e8c43ac", /* b1c1 */
-                "9902e3c3e8f0c569b4ab295ddf473e6de763e1e7", /* c2 */
-                "134385f6d781b7e97062102c6a483440bfda2a03", /* c1 */
-            ],
-        )
-        .with_sorting(Sorting::ByCommitTimeNewestFirst)
-        .check()
+        TraversalAssertion::new(Scenario::WithDates, &["m1b1"], &["b1c1", "c2", "c1"])
+            .with_sorting(Sorting::ByCommitTimeNewestFirst)
+            .check()
     }
 
     #[test]
     fn head_date_order_with_cutoff() -> crate::Result {
-        TraversalAssertion::new(
-            "make_traversal_repo_for_commits_with_dates.sh",
-            &["288e509293165cb5630d08f4185bdf2445bf6170"], /* m1b1 */
-            &["bcb05040a6925f2ff5e10d3ae1f9264f2e8c43ac"], /* b1c1 */
-        )
-        .with_sorting(Sorting::ByCommitTimeNewestFirstCutoffOlderThan {
-            seconds: 978393600, // =2001-01-02 00:00:00 +0000
-        })
-        .check()
+        TraversalAssertion::new(Scenario::WithDates, &["m1b1"], &["b1c1"])
+            .with_sorting(Sorting::ByCommitTimeNewestFirstCutoffOlderThan {
+                seconds: 978393600, // =2001-01-02 00:00:00 +0000
+            })
+            .check()
     }
 
     #[test]
     fn date_order_with_cutoff_is_applied_to_starting_position() -> crate::Result {
-        let dir =
-            gix_testtools::scripted_fixture_read_only_standalone("make_traversal_repo_for_commits_with_dates.sh")?;
-        let store = gix_odb::at(dir.join(".git").join("objects"))?;
-        let iter = Simple::new(
-            Some(hex_to_id("9902e3c3e8f0c569b4ab295ddf473e6de763e1e7" /* c2 */)),
-            &store,
-        )
-        .sorting(Sorting::ByCommitTimeNewestFirstCutoffOlderThan {
-            seconds: 978393600, // =2001-01-02 00:00:00 +0000
-        })?;
-        assert_eq!(
-            iter.count(),
-            0,
-            "initial tips that don't pass cutoff value are not returned either"
-        );
+        for fixture in Scenario::WithDates.fixtures()? {
+            let store = fixture.odb()?;
+            let iter = Simple::new(Some(fixture.id("c2")), &store).sorting(
+                Sorting::ByCommitTimeNewestFirstCutoffOlderThan {
+                    seconds: 978393600, // =2001-01-02 00:00:00 +0000
+                },
+            )?;
+            assert_eq!(
+                iter.count(),
+                0,
+                "initial tips that don't pass cutoff value are not returned either"
+            );
+        }
         Ok(())
     }
 
     #[test]
     fn head_date_order_first_parent_only() -> crate::Result {
-        TraversalAssertion::new(
-            "make_traversal_repo_for_commits_with_dates.sh",
-            &["288e509293165cb5630d08f4185bdf2445bf6170"], /* m1b1 */
-            &[
-                "9902e3c3e8f0c569b4ab295ddf473e6de763e1e7", /* c2 */
-                "134385f6d781b7e97062102c6a483440bfda2a03", /* c1 */
-            ],
-        )
-        .with_sorting(Sorting::ByCommitTimeNewestFirst)
-        .with_parents(Parents::First)
-        .check()
+        TraversalAssertion::new(Scenario::WithDates, &["m1b1"], &["c2", "c1"])
+            .with_sorting(Sorting::ByCommitTimeNewestFirst)
+            .with_parents(Parents::First)
+            .check()
+    }
+}
+
+/// A topology that is easy to describe natively, but would need a bespoke script otherwise.
+mod criss_cross {
+    use gix_traverse::commit::Parents;
+
+    use crate::commit::simple::{Scenario, TraversalAssertion};
+
+    #[test]
+    fn head_breadth_first() -> crate::Result {
+        TraversalAssertion::new(Scenario::CrissCross, &["merge"], &["m1", "m2", "c2", "b1c1", "c1"]).check()
+    }
+
+    #[test]
+    fn head_first_parent_only_breadth_first() -> crate::Result {
+        TraversalAssertion::new(Scenario::CrissCross, &["merge"], &["m1", "c2", "c1"])
+            .with_parents(Parents::First)
+            .check()
+    }
+}
+
+mod shallow {
+    use gix_traverse::commit::Simple;
+
+    use crate::fixture_repo::FixtureRepo;
+
+    #[test]
+    fn traversal_stops_at_boundary_if_the_predicate_skips_grafted_parents() -> crate::Result {
+        let fixture = FixtureRepo::new()
+            .commit("c1")
+            .commit("c2")
+            .commit("c3")
+            .shallow("c2")
+            .build()?;
+        let store = fixture.odb()?;
+        let tip = fixture.id("c3");
+        assert!(
+            Simple::new(Some(tip), &store).any(|res| res.is_err()),
+            "the parent of the boundary wasn't written"
+        );
+
+        let shallow = fixture.shallow_commits()?;
+        assert_eq!(shallow, [fixture.id("c2")]);
+        let grafted_parents = [fixture.id("c1")];
+        let ids = Simple::filtered(Some(tip), &store, |id| {
+            !grafted_parents.iter().any(|parent| *parent == id)
+        })
+        .map(|res| res.map(|info| info.id))
+        .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(ids, [tip, fixture.id("c2")]);
+        Ok(())
     }
 }
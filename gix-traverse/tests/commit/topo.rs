@@ -371,3 +371,136 @@ mod date_order {
         Ok(())
     }
 }
+
+mod left_right_and_boundary {
+    use gix_object::bstr::ByteSlice;
+    use gix_traverse::commit::topo;
+    use std::path::Path;
+
+    use crate::hex_to_id;
+
+    const MAIN: &str = "ec750f6eb14df59c860ea5683e8a34f18e0f8e49";
+    const MAIN_PARENT: &str = "f2a5f4cc02da467015f82212bc15d64229c94ef6";
+    const SIDE: &str = "441344ab9f701f7de66cfc740b34413f306aadf8";
+
+    /// Return each commit of `walk` prefixed like `git rev-list --left-right --boundary` would.
+    fn lines(
+        walk: impl Iterator<Item = Result<(gix_traverse::commit::Info, topo::Flags), topo::Error>>,
+    ) -> Vec<String> {
+        walk.map(|res| {
+            let (info, flags) = res.expect("walk works");
+            assert_eq!(
+                flags.contains(topo::Flags::Boundary),
+                flags.contains(topo::Flags::Uninteresting),
+                "only boundary commits are uninteresting"
+            );
+            let prefix = if flags.contains(topo::Flags::Boundary) {
+                "-"
+            } else if flags.contains(topo::Flags::Left) {
+                "<"
+            } else if flags.contains(topo::Flags::Right) {
+                ">"
+            } else {
+                ""
+            };
+            format!("{prefix}{}", info.id)
+        })
+        .collect()
+    }
+
+    fn baseline(dir: &Path, name: &str) -> Vec<String> {
+        std::fs::read(dir.join(format!("{name}.baseline")))
+            .expect("baseline exists")
+            .lines()
+            .map(|line| line.to_str().expect("valid UTF-8").to_owned())
+            .collect()
+    }
+
+    fn assert_symmetric_difference(
+        left: &str,
+        right: &str,
+        sorting: topo::Sorting,
+        baseline_name: &str,
+    ) -> crate::Result {
+        let dir = gix_testtools::scripted_fixture_read_only_standalone("make_repo_for_symmetric_difference.sh")?;
+        let store = gix_odb::at(dir.join(".git").join("objects"))?;
+        let expected = baseline(&dir, baseline_name);
+
+        for use_commitgraph in [false, true] {
+            let commit_graph = || {
+                use_commitgraph
+                    .then(|| gix_commitgraph::at(store.store_ref().path().join("info")))
+                    .transpose()
+            };
+            let actual = lines(
+                topo::Builder::from_symmetric_difference(&store, hex_to_id(left), hex_to_id(right))
+                    .sorting(sorting)
+                    .boundary(true)
+                    .with_commit_graph(commit_graph()?)
+                    .build()?
+                    .with_flags(),
+            );
+            assert_eq!(actual, expected, "commit-graph: {use_commitgraph}");
+
+            let without_boundary = topo::Builder::from_symmetric_difference(&store, hex_to_id(left), hex_to_id(right))
+                .sorting(sorting)
+                .with_commit_graph(commit_graph()?)
+                .build()?
+                .map(|res| res.map(|info| info.id.to_string()))
+                .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(
+                without_boundary,
+                expected
+                    .iter()
+                    .filter(|line| !line.starts_with('-'))
+                    .map(|line| line[1..].to_owned())
+                    .collect::<Vec<_>>(),
+                "boundary commits are only returned on request"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn symmetric_difference_with_two_merge_bases() -> crate::Result {
+        assert_symmetric_difference(MAIN, SIDE, topo::Sorting::TopoOrder, "symmetric-topo-order")?;
+        assert_symmetric_difference(MAIN, SIDE, topo::Sorting::DateOrder, "symmetric-date-order")?;
+        assert_symmetric_difference(SIDE, MAIN, topo::Sorting::TopoOrder, "symmetric-reversed")
+    }
+
+    #[test]
+    fn symmetric_difference_with_ancestor() -> crate::Result {
+        assert_symmetric_difference(MAIN, MAIN_PARENT, topo::Sorting::TopoOrder, "symmetric-ancestor")
+    }
+
+    #[test]
+    fn symmetric_difference_with_itself_is_empty() -> crate::Result {
+        let dir = gix_testtools::scripted_fixture_read_only_standalone("make_repo_for_symmetric_difference.sh")?;
+        let store = gix_odb::at(dir.join(".git").join("objects"))?;
+        let mut walk = topo::Builder::from_symmetric_difference(&store, hex_to_id(MAIN), hex_to_id(MAIN))
+            .boundary(true)
+            .build()?;
+        assert!(walk.next().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn boundary_with_ends() -> crate::Result {
+        let dir = gix_testtools::scripted_fixture_read_only_standalone("make_repo_for_symmetric_difference.sh")?;
+        let store = gix_odb::at(dir.join(".git").join("objects"))?;
+        for (sorting, baseline_name) in [
+            (topo::Sorting::TopoOrder, "boundary-topo-order"),
+            (topo::Sorting::DateOrder, "boundary-date-order"),
+        ] {
+            let actual = lines(
+                topo::Builder::from_iters(&store, [hex_to_id(MAIN)], Some([hex_to_id(SIDE)]))
+                    .sorting(sorting)
+                    .boundary(true)
+                    .build()?
+                    .with_flags(),
+            );
+            assert_eq!(actual, baseline(&dir, baseline_name));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,346 @@
+//! A builder for repositories with a precise commit topology, written with `gitoxide` itself.
+//!
+//! Commits are identified by their message, and all of them point to the empty tree. Identities and dates are the same
+//! that `gix-testtools` configures for fixture scripts, so a history built here has the very same hashes as one
+//! created by an equivalent script, which makes both interchangeable and allows to use `git` as oracle where needed.
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use gix_hash::ObjectId;
+use gix_object::{bstr::ByteSlice, FindExt, WriteTo};
+use gix_odb::Write;
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit},
+    Target,
+};
+
+/// The time of the author of all commits, which is `2000-01-01 00:00:00 +0000`.
+const AUTHOR_TIME: gix_date::SecondsSinceUnixEpoch = 946684800;
+/// The time of the committer of all commits, which is `2000-01-02 00:00:00 +0000`.
+const COMMITTER_TIME: gix_date::SecondsSinceUnixEpoch = 946771200;
+
+/// Describe a repository by adding commits, branches and merges one by one, similar to how it would be done with `git`,
+/// and [write](FixtureRepo::build()) it into a new directory.
+#[derive(Debug, Clone)]
+pub struct FixtureRepo {
+    commits: BTreeMap<ObjectId, gix_object::Commit>,
+    ids: BTreeMap<String, ObjectId>,
+    branches: BTreeMap<String, ObjectId>,
+    head: String,
+    author_time: gix_date::Time,
+    committer_time: gix_date::Time,
+    next_committer_time: Option<gix_date::SecondsSinceUnixEpoch>,
+    tick: bool,
+    replacements: BTreeMap<ObjectId, ObjectId>,
+    shallow: BTreeSet<ObjectId>,
+}
+
+impl Default for FixtureRepo {
+    fn default() -> Self {
+        FixtureRepo {
+            commits: Default::default(),
+            ids: Default::default(),
+            branches: Default::default(),
+            head: "main".into(),
+            author_time: gix_date::Time::new(AUTHOR_TIME, 0),
+            committer_time: gix_date::Time::new(COMMITTER_TIME, 0),
+            next_committer_time: None,
+            tick: false,
+            replacements: Default::default(),
+            shallow: Default::default(),
+        }
+    }
+}
+
+/// Building
+impl FixtureRepo {
+    /// Create an empty repository with `HEAD` pointing to the unborn `main` branch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the author and committer date to `seconds` with an `offset` in seconds, and advance both by a minute right
+    /// before each [commit](Self::commit()), but not before [merges](Self::merge()).
+    ///
+    /// This is what the `tick` function of our fixture scripts does, borrowed from `test_tick` of `git`'s own test-suite.
+    pub fn ticking_from(mut self, seconds: gix_date::SecondsSinceUnixEpoch, offset: gix_date::OffsetInSeconds) -> Self {
+        self.author_time = gix_date::Time::new(seconds, offset);
+        self.committer_time = self.author_time;
+        self.tick = true;
+        self
+    }
+
+    /// Use `seconds` as the committer date of the next commit or merge only, like `GIT_COMMITTER_DATE=… git commit` would.
+    pub fn committed_at(mut self, seconds: gix_date::SecondsSinceUnixEpoch) -> Self {
+        self.next_committer_time = Some(seconds);
+        self
+    }
+
+    /// Add an empty commit with `message` on top of the current branch.
+    pub fn commit(mut self, message: &str) -> Self {
+        if self.tick {
+            self.author_time.seconds += 60;
+            self.committer_time.seconds += 60;
+        }
+        let parents = self.branches.get(&self.head).copied().into_iter().collect();
+        self.add_commit(message, parents)
+    }
+
+    /// Merge all `others` into the current branch with a merge commit that has `message`, creating an octopus merge
+    /// if there is more than one of them.
+    ///
+    /// Each of `others` is the name of a branch or, if there is no such branch, the message of a commit, which allows
+    /// to merge commits that aren't at the tip of a branch anymore.
+    pub fn merge<'a>(self, message: &str, others: impl IntoIterator<Item = &'a str>) -> Self {
+        let parents = self
+            .branches
+            .get(&self.head)
+            .copied()
+            .into_iter()
+            .chain(others.into_iter().map(|name| self.resolve(name)))
+            .collect();
+        self.add_commit(message, parents)
+    }
+
+    /// Create the branch `name` at the tip of the current branch and check it out, like `git checkout -b <name>`.
+    pub fn branch(mut self, name: &str) -> Self {
+        let tip = self.branches.get(&self.head).copied();
+        self.branches
+            .insert(name.into(), tip.expect("cannot branch off an unborn branch"));
+        self.head = name.into();
+        self
+    }
+
+    /// Check out the existing branch `name`.
+    pub fn checkout(mut self, name: &str) -> Self {
+        assert!(self.branches.contains_key(name), "branch '{name}' doesn't exist");
+        self.head = name.into();
+        self
+    }
+
+    /// Replace the commit with message `original` by the one with message `replacement`, like `git replace` would.
+    pub fn replace(mut self, original: &str, replacement: &str) -> Self {
+        let (original, replacement) = (self.resolve(original), self.resolve(replacement));
+        self.replacements.insert(original, replacement);
+        self
+    }
+
+    /// Make the commit with `message` a shallow boundary, as if the repository was cloned with a limited depth,
+    /// so none of its ancestors is written unless it's reachable in another way.
+    pub fn shallow(mut self, message: &str) -> Self {
+        let id = self.resolve(message);
+        self.shallow.insert(id);
+        self
+    }
+
+    /// Write all objects and references into a new repository, and return it.
+    pub fn build(self) -> crate::Result<Fixture> {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let git_dir = tmp.path().join(".git");
+        std::fs::create_dir_all(git_dir.join("objects"))?;
+        std::fs::create_dir_all(git_dir.join("refs").join("heads"))?;
+
+        let odb = gix_odb::at(git_dir.join("objects"))?;
+        odb.write(&gix_object::Tree::empty())?;
+        for id in self.reachable() {
+            let written = odb.write(&self.commits[&id])?;
+            assert_eq!(written, id, "the id we computed matches the one of the written object");
+        }
+
+        let refs = gix_ref::file::Store::at(
+            git_dir.clone(),
+            gix_ref::store::init::Options {
+                write_reflog: gix_ref::store::WriteReflog::Disable,
+                ..Default::default()
+            },
+        );
+        let edit = |name: String, new: Target| RefEdit {
+            change: Change::Update {
+                log: LogChange::default(),
+                expected: PreviousValue::MustNotExist,
+                new,
+            },
+            name: name.try_into().expect("valid ref name"),
+            deref: false,
+        };
+        let edits = std::iter::once(edit(
+            "HEAD".into(),
+            Target::Symbolic(format!("refs/heads/{}", self.head).try_into()?),
+        ))
+        .chain(
+            self.branches
+                .iter()
+                .map(|(name, id)| edit(format!("refs/heads/{name}"), Target::Peeled(*id))),
+        )
+        .chain(
+            self.replacements
+                .iter()
+                .map(|(original, replacement)| edit(format!("refs/replace/{original}"), Target::Peeled(*replacement))),
+        );
+        refs.transaction()
+            .prepare(
+                edits,
+                gix_lock::acquire::Fail::Immediately,
+                gix_lock::acquire::Fail::Immediately,
+            )?
+            .commit(None)?;
+
+        if !self.shallow.is_empty() {
+            let shallow: String = self.shallow.iter().map(|id| format!("{id}\n")).collect();
+            std::fs::write(git_dir.join("shallow"), shallow)?;
+        }
+
+        Ok(Fixture {
+            _tmp: Some(tmp),
+            git_dir,
+            ids: self.ids,
+        })
+    }
+}
+
+/// Utilities
+impl FixtureRepo {
+    fn add_commit(mut self, message: &str, parents: Vec<ObjectId>) -> Self {
+        let committer_time = match self.next_committer_time.take() {
+            Some(seconds) => gix_date::Time {
+                seconds,
+                ..self.committer_time
+            },
+            None => self.committer_time,
+        };
+        let commit = gix_object::Commit {
+            tree: ObjectId::empty_tree(gix_hash::Kind::Sha1),
+            parents: parents.into(),
+            author: signature("author", self.author_time),
+            committer: signature("committer", committer_time),
+            encoding: None,
+            message: format!("{message}\n").into(),
+            extra_headers: Vec::new(),
+        };
+        let mut buf = Vec::new();
+        commit.write_to(&mut buf).expect("writing to memory works");
+        let id = gix_object::compute_hash(gix_hash::Kind::Sha1, gix_object::Kind::Commit, &buf);
+
+        let previous = self.ids.insert(message.into(), id);
+        assert!(previous.is_none(), "commit messages must be unique, '{message}' isn't");
+        self.commits.insert(id, commit);
+        self.branches.insert(self.head.clone(), id);
+        self
+    }
+
+    fn resolve(&self, name: &str) -> ObjectId {
+        self.branches
+            .get(name)
+            .or_else(|| self.ids.get(name))
+            .copied()
+            .unwrap_or_else(|| panic!("'{name}' is neither a branch nor a commit"))
+    }
+
+    /// Return all commits reachable from branches and replacements, without passing through shallow boundaries.
+    fn reachable(&self) -> BTreeSet<ObjectId> {
+        let mut out = BTreeSet::new();
+        let mut queue: Vec<_> = self
+            .branches
+            .values()
+            .chain(self.replacements.values())
+            .copied()
+            .collect();
+        while let Some(id) = queue.pop() {
+            if !out.insert(id) || self.shallow.contains(&id) {
+                continue;
+            }
+            queue.extend(self.commits[&id].parents.iter().copied());
+        }
+        out
+    }
+}
+
+fn signature(name: &str, time: gix_date::Time) -> gix_actor::Signature {
+    gix_actor::Signature {
+        name: name.into(),
+        email: format!("{name}@example.com").into(),
+        time,
+    }
+}
+
+/// A repository on disk along with the ids of all of its commits by their message.
+pub struct Fixture {
+    _tmp: Option<gix_testtools::tempfile::TempDir>,
+    git_dir: PathBuf,
+    ids: BTreeMap<String, ObjectId>,
+}
+
+impl Fixture {
+    /// Use the repository `repo_name` created by `script` as fixture, with `repo_name` being empty if the script
+    /// creates the repository in its working directory.
+    ///
+    /// This is the way to go if the behaviour of `git` itself is the oracle, for instance if its output is
+    /// recorded as baseline. Commits are identified by the first line of their message.
+    pub fn from_script(script: &str, repo_name: &str) -> crate::Result<Self> {
+        let git_dir = gix_testtools::scripted_fixture_read_only_standalone(script)?
+            .join(repo_name)
+            .join(".git");
+        let odb = gix_odb::at(git_dir.join("objects"))?;
+        let refs = gix_ref::file::Store::at(git_dir.clone(), Default::default());
+        let mut tips = Vec::new();
+        for reference in refs.iter()?.all()? {
+            if let Target::Peeled(id) = reference?.target {
+                tips.push(id);
+            }
+        }
+
+        let mut ids = BTreeMap::new();
+        let mut buf = Vec::new();
+        for info in gix_traverse::commit::Simple::new(tips, &odb) {
+            let id = info?.id;
+            let message = odb.find_commit(&id, &mut buf)?.message_summary().to_str()?.to_owned();
+            ids.insert(message, id);
+        }
+        Ok(Fixture {
+            _tmp: None,
+            git_dir,
+            ids,
+        })
+    }
+
+    /// Return the id of the commit with `message`.
+    pub fn id(&self, message: &str) -> ObjectId {
+        *self
+            .ids
+            .get(message)
+            .unwrap_or_else(|| panic!("no commit with message '{message}'"))
+    }
+
+    /// Return the ids of all commits by their message.
+    pub fn ids(&self) -> &BTreeMap<String, ObjectId> {
+        &self.ids
+    }
+
+    /// Return the `.git` directory of the repository.
+    pub fn git_dir(&self) -> &Path {
+        &self.git_dir
+    }
+
+    /// Open the object database of the repository.
+    pub fn odb(&self) -> crate::Result<gix_odb::Handle> {
+        Ok(gix_odb::at(self.git_dir.join("objects"))?)
+    }
+
+    /// Open the commit-graph of the repository, if there is one. Repositories built with [`FixtureRepo`] never have one.
+    pub fn commit_graph(&self) -> Option<gix_commitgraph::Graph> {
+        let info_dir = self.git_dir.join("objects").join("info");
+        (info_dir.join("commit-graph").is_file() || info_dir.join("commit-graphs").is_dir())
+            .then(|| gix_commitgraph::at(info_dir).expect("graph can be loaded if it exists"))
+    }
+
+    /// Return the ids of all shallow boundary commits.
+    pub fn shallow_commits(&self) -> crate::Result<Vec<ObjectId>> {
+        match std::fs::read(self.git_dir.join("shallow")) {
+            Ok(buf) => Ok(buf.lines().map(ObjectId::from_hex).collect::<Result<_, _>>()?),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
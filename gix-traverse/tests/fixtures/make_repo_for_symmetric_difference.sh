@@ -0,0 +1,80 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function tick () {
+  if test -z "${tick+set}"
+  then
+    tick=1112911993
+  else
+    tick=$(($tick + 60))
+  fi
+  GIT_COMMITTER_DATE="$tick -0700"
+  GIT_AUTHOR_DATE="$tick -0700"
+  export GIT_COMMITTER_DATE GIT_AUTHOR_DATE
+}
+
+tick
+function commit() {
+  local message=${1:?first argument is the commit message}
+  tick
+  git commit --allow-empty -m "$message"
+}
+
+function merge() {
+  tick
+  git merge "$@"
+}
+
+function optimize() {
+  git commit-graph write --no-progress --reachable
+  git repack -adq
+}
+
+function collect_baselines() {
+  git merge-base --all main side > merge-bases.baseline
+  git rev-list --topo-order --left-right --boundary main...side > symmetric-topo-order.baseline
+  git rev-list --date-order --left-right --boundary main...side > symmetric-date-order.baseline
+  git rev-list --topo-order --left-right --boundary side...main > symmetric-reversed.baseline
+  git rev-list --topo-order --left-right --boundary main...main~1 > symmetric-ancestor.baseline
+  git rev-list --topo-order --boundary main ^side > boundary-topo-order.baseline
+  git rev-list --date-order --boundary main ^side > boundary-date-order.baseline
+}
+
+git init
+git config merge.ff false
+
+git checkout -q -b main
+commit c0
+commit c1
+
+git branch side
+commit c2
+
+git checkout -q side
+commit s1
+
+# criss-cross merges lead to two merge-bases, c2 and s1
+git checkout -q main
+merge side -m m1
+git checkout -q side
+merge main~1 -m m2
+
+git checkout -q main
+commit c3
+git checkout -q -b topic
+commit t1
+commit t2
+git checkout -q main
+commit c4
+merge topic -m m3
+
+git checkout -q side
+commit s2
+git checkout -q -b feature
+commit f1
+git checkout -q side
+commit s3
+merge feature -m m4
+
+optimize
+collect_baselines
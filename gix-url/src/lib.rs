@@ -16,6 +16,8 @@ use bstr::{BStr, BString};
 #[allow(clippy::empty_docs)]
 pub mod expand_path;
 
+pub mod matching;
+
 mod scheme;
 pub use scheme::Scheme;
 mod impls;
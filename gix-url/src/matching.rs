@@ -0,0 +1,74 @@
+//! Match URLs against URL patterns like the ones in `http.<url>.*` configuration, the way `git` does.
+use crate::Url;
+
+/// Describe how specifically a URL pattern matches a URL, with more specific matches comparing greater.
+///
+/// Matches are ordered by the length of the matched host first, then the length of the matched path,
+/// and finally by whether or not a user name was matched.
+/// Of multiple equally specific matches, the last one seen should win.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Specificity {
+    /// The length of the host portion of the pattern, including a non-default port.
+    pub host_len: usize,
+    /// The length of the matched path portion of the pattern, without trailing slash.
+    pub path_len: usize,
+    /// If `true`, the pattern contained a user name which was equal to the one of the URL.
+    pub user_matched: bool,
+}
+
+/// Return how specifically `pattern` matches `url`, or `None` if it doesn't match at all.
+///
+/// A `pattern` matches if
+///
+/// * the schemes are equal,
+/// * the hosts are equal, where each `.`-separated component of the pattern that is just `*` matches any one component,
+/// * the ports are equal, taking default ports into account,
+/// * the path of the pattern is a prefix of the path of the `url` that ends at a `/`,
+/// * and the user name is equal if the pattern has one.
+pub fn specificity(pattern: &Url, url: &Url) -> Option<Specificity> {
+    if pattern.scheme != url.scheme
+        || !host_matches(pattern.host(), url.host())
+        || pattern.port_or_default() != url.port_or_default()
+    {
+        return None;
+    }
+    let user_matched = match pattern.user() {
+        Some(user) if url.user() != Some(user) => return None,
+        Some(_) => true,
+        None => false,
+    };
+    let path_len = path_match_len(&pattern.path, &url.path)?;
+    // Parsing drops default ports, so a port is only present if it's explicitly specified.
+    let host_len = pattern.host().map_or(0, str::len) + pattern.port.map_or(0, |port| 1 + port.to_string().len());
+    Some(Specificity {
+        host_len,
+        path_len,
+        user_matched,
+    })
+}
+
+fn host_matches(pattern: Option<&str>, host: Option<&str>) -> bool {
+    match (pattern, host) {
+        (Some(pattern), Some(host)) => {
+            let (mut pattern, mut host) = (pattern.split('.'), host.split('.'));
+            loop {
+                match (pattern.next(), host.next()) {
+                    (Some(pattern), Some(host)) if pattern == "*" || pattern.eq_ignore_ascii_case(host) => continue,
+                    (None, None) => return true,
+                    _ => return false,
+                }
+            }
+        }
+        (None, None) => true,
+        (Some(_), None) | (None, Some(_)) => false,
+    }
+}
+
+fn path_match_len(pattern: &[u8], path: &[u8]) -> Option<usize> {
+    let pattern = pattern.strip_suffix(b"/").unwrap_or(pattern);
+    if pattern.is_empty() {
+        return Some(1);
+    }
+    let rest = path.strip_prefix(pattern)?;
+    (rest.is_empty() || rest[0] == b'/').then_some(pattern.len())
+}
@@ -0,0 +1,86 @@
+use gix_url::matching::{specificity, Specificity};
+
+fn matches(pattern: &str, url: &str) -> Option<Specificity> {
+    let pattern = gix_url::parse(pattern.into()).expect("valid pattern");
+    let url = gix_url::parse(url.into()).expect("valid url");
+    specificity(&pattern, &url)
+}
+
+fn spec(host_len: usize, path_len: usize, user_matched: bool) -> Option<Specificity> {
+    Some(Specificity {
+        host_len,
+        path_len,
+        user_matched,
+    })
+}
+
+#[test]
+fn scheme_host_and_port_must_be_equal() {
+    let url = "https://example.com/org/repo.git";
+    assert_eq!(matches("https://example.com", url), spec(11, 1, false));
+    assert_eq!(matches("https://EXAMPLE.com/", url), spec(11, 1, false));
+    assert_eq!(matches("http://example.com", url), None, "schemes differ");
+    assert_eq!(matches("https://example.org", url), None);
+    assert_eq!(matches("https://sub.example.com", url), None);
+    assert_eq!(matches("https://example.com:443", url), spec(11, 1, false), "default ports");
+    assert_eq!(matches("https://example.com:8443", url), None);
+    assert_eq!(
+        matches("https://example.com:8443", "https://example.com:8443/repo"),
+        spec(16, 1, false),
+        "explicit ports count towards the host"
+    );
+}
+
+#[test]
+fn wildcards_match_exactly_one_host_component() {
+    let url = "https://git.internal.example.com/repo";
+    assert_eq!(matches("https://*.internal.example.com", url), spec(22, 1, false));
+    assert_eq!(matches("https://*.*.example.com", url), spec(15, 1, false));
+    assert_eq!(matches("https://*.example.com", url), None, "one component per wildcard");
+    assert_eq!(matches("https://*.git.internal.example.com", url), None);
+    assert_eq!(matches("https://g*.internal.example.com", url), None, "only entire components");
+}
+
+#[test]
+fn paths_match_as_prefix_at_component_boundaries() {
+    let url = "https://example.com/org/repo.git";
+    assert_eq!(matches("https://example.com/org", url), spec(11, 4, false));
+    assert_eq!(matches("https://example.com/org/", url), spec(11, 4, false));
+    assert_eq!(matches("https://example.com/org/repo.git", url), spec(11, 13, false));
+    assert_eq!(matches("https://example.com/or", url), None, "not at a component boundary");
+    assert_eq!(matches("https://example.com/other", url), None);
+    assert_eq!(matches("https://example.com/org/repo.git/more", url), None);
+}
+
+#[test]
+fn users_must_be_equal_if_present_in_the_pattern() {
+    let url = "https://jane@example.com/repo";
+    assert_eq!(matches("https://jane@example.com", url), spec(11, 1, true));
+    assert_eq!(matches("https://joe@example.com", url), None);
+    assert_eq!(matches("https://jane@example.com", "https://example.com/repo"), None);
+    assert_eq!(matches("https://example.com", url), spec(11, 1, false));
+}
+
+#[test]
+fn specificity_orders_by_host_then_path_then_user() {
+    let url = "https://jane@git.example.com/org/repo.git";
+    let mut patterns = vec![
+        "https://jane@git.example.com",
+        "https://git.example.com/org/repo.git",
+        "https://*.example.com/org",
+        "https://git.example.com",
+        "https://jane@*.example.com",
+    ];
+    patterns.sort_by_key(|pattern| matches(pattern, url).expect("all match"));
+    assert_eq!(
+        patterns,
+        [
+            "https://jane@*.example.com",
+            "https://*.example.com/org",
+            "https://git.example.com",
+            "https://jane@git.example.com",
+            "https://git.example.com/org/repo.git",
+        ],
+        "longer hosts win over longer paths, which win over user names"
+    );
+}
@@ -76,6 +76,10 @@ pub struct Context<Find: Clone> {
     pub filters: gix_filter::Pipeline,
     pub buf: Vec<u8>,
     pub options: Options,
+    /// Worktree-relative paths of the symbolic links created so far, which are never traversed when writing later entries.
+    ///
+    /// Keys are ASCII-lowercased on case-insensitive filesystems.
+    pub created_symlinks: BTreeSet<BString>,
 }
 
 #[derive(Clone, Copy)]
@@ -205,11 +209,24 @@ where
                         gix_filter::driver::Operation::Smudge,
                     )?,
                 );
+                if let Err(err) = entry::verify_no_symlink_in_leading_path(
+                    ctx.path_cache.base(),
+                    delayed.entry_path,
+                    &ctx.created_symlinks,
+                    ctx.options.fs.ignore_case,
+                    false,
+                ) {
+                    delayed.validated_file_path = Default::default();
+                    handle_error(err, delayed.entry_path, files, &mut out.errors, true)?;
+                    std::io::copy(&mut read, &mut std::io::sink())?;
+                    continue;
+                }
                 let (file, set_executable_after_creation) = match entry::open_file(
                     &std::mem::take(&mut delayed.validated_file_path), // mark it as seen, relevant for `unprocessed_paths`
                     destination_is_initially_empty,
                     overwrite_existing,
                     delayed.needs_executable_bit,
+                    ctx.options.fs.long_paths,
                     delayed.entry.mode,
                 ) {
                     Ok(res) => res,
@@ -292,6 +309,7 @@ pub fn checkout_entry_handle_result<'entry, Find>(
         filters,
         buf,
         options,
+        created_symlinks,
     }: &mut Context<Find>,
 ) -> Result<entry::Outcome<'entry>, checkout::Error>
 where
@@ -305,6 +323,7 @@ where
             path_cache,
             filters,
             buf,
+            created_symlinks,
         },
         *options,
     );
@@ -319,6 +338,16 @@ where
         Err(checkout::Error::Io(err)) if is_collision(&err, entry_path, collisions, files) => {
             Ok(entry::Outcome::Written { bytes: 0 })
         }
+        Err(
+            err @ (checkout::Error::WriteThroughSymlink(_)
+            | checkout::Error::Filter(gix_filter::pipeline::convert::to_worktree::Error::Worktree {
+                source: gix_filter::worktree::encode_to_worktree::Error::RoundTrip { .. },
+                ..
+            })),
+        ) => {
+            handle_error(err, entry_path, files, errors, true)?;
+            Ok(entry::Outcome::Written { bytes: 0 })
+        }
         Err(err) => handle_error(err, entry_path, files, errors, options.keep_going)
             .map(|()| entry::Outcome::Written { bytes: 0 }),
     }
@@ -1,10 +1,11 @@
 use std::{
+    collections::BTreeSet,
     fs::OpenOptions,
     io::Write,
     path::{Path, PathBuf},
 };
 
-use bstr::BStr;
+use bstr::{BStr, BString, ByteSlice};
 use gix_filter::{driver::apply::MaybeDelayed, pipeline::convert::ToWorktreeOutcome};
 use gix_index::{entry::Stat, Entry};
 use gix_object::FindExt;
@@ -16,6 +17,7 @@ pub struct Context<'a, Find> {
     pub path_cache: &'a mut Stack,
     pub filters: &'a mut gix_filter::Pipeline,
     pub buf: &'a mut Vec<u8>,
+    pub created_symlinks: &'a mut BTreeSet<BString>,
 }
 
 /// A delayed result of a long-running filter process, which is made available as stream.
@@ -61,13 +63,17 @@ pub fn checkout<'entry, Find>(
         filters,
         path_cache,
         buf,
+        created_symlinks,
     }: Context<'_, Find>,
     crate::checkout::chunk::Options {
-        fs: gix_fs::Capabilities {
-            symlink,
-            executable_bit,
-            ..
-        },
+        fs:
+            gix_fs::Capabilities {
+                symlink,
+                executable_bit,
+                ignore_case,
+                long_paths,
+                ..
+            },
         destination_is_initially_empty,
         overwrite_existing,
         filter_process_delay,
@@ -80,6 +86,25 @@ where
     let dest_relative = gix_path::try_from_bstr(entry_path).map_err(|_| crate::checkout::Error::IllformedUtf8 {
         path: entry_path.to_owned(),
     })?;
+    // Symlinks are only created at the very end, so this check is free for all other entries.
+    // It must happen before the path cache is set as it would replace symlinks in the way if overwriting is allowed,
+    // which is only acceptable for symlinks we didn't create ourselves.
+    if !created_symlinks.is_empty() {
+        verify_no_symlink_in_leading_path(
+            path_cache.base(),
+            entry_path,
+            created_symlinks,
+            ignore_case,
+            overwrite_existing,
+        )?;
+    }
+    // Fail early, before leading directories are created, and with a message that names the offending path.
+    if cfg!(windows) && !long_paths {
+        let dest = path_cache.base().join(&dest_relative);
+        if gix_fs::long_path::exceeds_limit(&dest) {
+            return Err(crate::checkout::Error::PathTooLong { path: dest });
+        }
+    }
     let path_cache = path_cache.at_path(dest_relative, Some(entry.mode), &*objects)?;
     let dest = path_cache.path();
 
@@ -107,6 +132,7 @@ where
                         destination_is_initially_empty,
                         overwrite_existing,
                         executable_bit,
+                        long_paths,
                         entry.mode,
                     )?;
                     file.write_all(buf)?;
@@ -118,6 +144,7 @@ where
                         destination_is_initially_empty,
                         overwrite_existing,
                         executable_bit,
+                        long_paths,
                         entry.mode,
                     )?;
                     let num_bytes = std::io::copy(&mut filtered, &mut file)? as usize;
@@ -148,19 +175,21 @@ where
             let symlink_destination = gix_path::try_from_byte_slice(obj.data)
                 .map_err(|_| crate::checkout::Error::IllformedUtf8 { path: obj.data.into() })?;
 
+            let io_dest = gix_fs::long_path::to_extended_length(dest, long_paths)?;
             if symlink {
-                try_op_or_unlink(dest, overwrite_existing, |p| {
+                try_op_or_unlink(&io_dest, overwrite_existing, |p| {
                     gix_fs::symlink::create(symlink_destination, p)
                 })?;
+                created_symlinks.insert(symlink_key(entry_path, ignore_case));
             } else {
-                let mut file = try_op_or_unlink(dest, overwrite_existing, |p| {
-                    open_options(p, destination_is_initially_empty, overwrite_existing).open(dest)
+                let mut file = try_op_or_unlink(&io_dest, overwrite_existing, |p| {
+                    open_options(p, destination_is_initially_empty, overwrite_existing).open(p)
                 })?;
                 file.write_all(obj.data)?;
                 file.close()?;
             }
 
-            entry.stat = Stat::from_fs(&gix_index::fs::Metadata::from_path_no_follow(dest)?)?;
+            entry.stat = Stat::from_fs(&gix_index::fs::Metadata::from_path_no_follow(&io_dest)?)?;
             obj.data.len()
         }
         gix_index::entry::Mode::DIR => {
@@ -182,6 +211,64 @@ where
     Ok(Outcome::Written { bytes: object_size })
 }
 
+fn symlink_key(rela_path: &BStr, ignore_case: bool) -> BString {
+    if ignore_case {
+        rela_path.to_ascii_lowercase().into()
+    } else {
+        rela_path.to_owned()
+    }
+}
+
+/// Assure that none of the leading components of `rela_path` below `root` is a symbolic link, as writing to
+/// `rela_path` (or deleting it) would then affect whatever the link points to, possibly outside of the worktree.
+///
+/// Symbolic links that we didn't create ourselves, i.e. that aren't in `created_symlinks`, are tolerated if
+/// `allow_preexisting` is `true` as these will be replaced with a directory when creating the leading path.
+/// Symbolic links are detected with `lstat()` on each component, which on Windows is best-effort as only reparse points
+/// that the standard library considers symbolic links will be detected.
+pub(crate) fn verify_no_symlink_in_leading_path(
+    root: &Path,
+    rela_path: &BStr,
+    created_symlinks: &BTreeSet<BString>,
+    ignore_case: bool,
+    allow_preexisting: bool,
+) -> Result<(), crate::checkout::Error> {
+    let mut path = root.to_owned();
+    let mut components = rela_path.split_str("/").peekable();
+    let mut rela_end = 0;
+    while let Some(component) = components.next() {
+        if components.peek().is_none() {
+            break;
+        }
+        rela_end += component.len();
+        path.push(
+            gix_path::try_from_byte_slice(component).map_err(|_| crate::checkout::Error::IllformedUtf8 {
+                path: rela_path.to_owned(),
+            })?,
+        );
+        match std::fs::symlink_metadata(&path) {
+            Ok(meta) if meta.file_type().is_symlink() => {
+                let rela_symlink = rela_path[..rela_end].as_bstr();
+                if !allow_preexisting || created_symlinks.contains(&symlink_key(rela_symlink, ignore_case)) {
+                    return Err(crate::checkout::Error::WriteThroughSymlink(Box::new(
+                        crate::checkout::SymlinkInPath {
+                            path: rela_path.to_owned(),
+                            symlink: path,
+                        },
+                    )));
+                }
+                // It will be replaced, and we don't want to look through it.
+                break;
+            }
+            Ok(_) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => break,
+            Err(err) => return Err(err.into()),
+        }
+        rela_end += 1;
+    }
+    Ok(())
+}
+
 /// Note that this works only because we assume to not race ourselves when symlinks are involved, and we do this by
 /// delaying symlink creation to the end and will always do that sequentially.
 /// It's still possible to fall for a race if other actors create symlinks in our path, but that's nothing to defend against.
@@ -194,6 +281,7 @@ fn try_op_or_unlink<T>(
         match op(path) {
             Ok(res) => Ok(res),
             Err(err) if gix_fs::symlink::is_collision_error(&err) => {
+                // The leading path was verified to not contain symlinks at this point, so we won't delete through one.
                 try_unlink_path_recursively(path, &std::fs::symlink_metadata(path)?)?;
                 op(path)
             }
@@ -237,20 +325,31 @@ fn open_options(path: &Path, destination_is_initially_empty: bool, overwrite_exi
     options
         .create_new(destination_is_initially_empty && !overwrite_existing)
         .create(!destination_is_initially_empty || overwrite_existing)
+        // Existing files that are longer than the new content must not keep their trailing bytes.
+        .truncate(true)
         .write(true);
     options
 }
 
+/// Open the file at `path` for writing, using its extended-length form if `long_paths` is `true` or if it's required.
 pub(crate) fn open_file(
     path: &Path,
     destination_is_initially_empty: bool,
     overwrite_existing: bool,
     fs_supports_executable_bit: bool,
+    long_paths: bool,
     entry_mode: gix_index::entry::Mode,
 ) -> std::io::Result<(std::fs::File, bool)> {
+    let path = gix_fs::long_path::to_extended_length(path, long_paths)?;
+    let path = path.as_ref();
     #[cfg_attr(windows, allow(unused_mut))]
     let mut options = open_options(path, destination_is_initially_empty, overwrite_existing);
-    let needs_executable_bit = fs_supports_executable_bit && entry_mode == gix_index::entry::Mode::FILE_EXECUTABLE;
+    let needs_executable_bit = matches!(
+        entry_mode
+            .to_tree_entry_mode()
+            .and_then(|mode| mode.to_file_permissions(fs_supports_executable_bit)),
+        Some(0o755)
+    );
     #[cfg(unix)]
     let set_executable_after_creation = if needs_executable_bit && destination_is_initially_empty {
         use std::os::unix::fs::OpenOptionsExt;
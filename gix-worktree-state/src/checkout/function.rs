@@ -74,6 +74,7 @@ where
         ),
         filters: options.filters,
         objects,
+        created_symlinks: Default::default(),
     };
 
     let chunk::Outcome {
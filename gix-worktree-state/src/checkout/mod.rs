@@ -29,6 +29,10 @@ pub struct Outcome {
     /// The encountered collisions, which can happen on a case-insensitive filesystem.
     pub collisions: Vec<Collision>,
     /// Other errors that happened during checkout.
+    ///
+    /// Entries that were refused as they would have been written through a symbolic link, or as their content wouldn't survive
+    /// the round-trip through their `working-tree-encoding`, are always recorded here, even if [`keep_going`](Options::keep_going)
+    /// is disabled.
     pub errors: Vec<ErrorRecord>,
     /// Relative paths that the process listed as 'delayed' even though we never passed them.
     pub delayed_paths_unknown: Vec<BString>,
@@ -70,12 +74,25 @@ pub struct Options {
     pub filter_process_delay: gix_filter::driver::apply::Delay,
 }
 
+/// The paths involved when refusing to write through a symbolic link, as part of [`Error::WriteThroughSymlink`].
+#[derive(Debug, Clone)]
+pub struct SymlinkInPath {
+    /// The path of the entry that was to be written, relative to the worktree.
+    pub path: BString,
+    /// The path to the symbolic link that is a leading component of `path`.
+    pub symlink: std::path::PathBuf,
+}
+
 /// The error returned by the [checkout()][crate::checkout()] function.
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
 pub enum Error {
     #[error("Could not convert path to UTF8: {}", .path)]
     IllformedUtf8 { path: BString },
+    #[error("Refusing to write '{}' as its leading component at '{}' is a symbolic link that could point outside of the worktree", .0.path, .0.symlink.display())]
+    WriteThroughSymlink(Box<SymlinkInPath>),
+    #[error("Refusing to write '{}' as it is longer than supported by the platform - set core.longPaths to true to allow it", .path.display())]
+    PathTooLong { path: std::path::PathBuf },
     #[error("The clock was off when reading file related metadata after updating a file on disk")]
     Time(#[from] std::time::SystemTimeError),
     #[error("IO error while writing blob or reading file metadata or changing filetype")]
@@ -99,5 +116,5 @@ pub enum Error {
 }
 
 mod chunk;
-mod entry;
+pub(crate) mod entry;
 pub(crate) mod function;
@@ -5,3 +5,13 @@
 #[allow(clippy::empty_docs)]
 pub mod checkout;
 pub use checkout::function::checkout;
+
+///
+#[allow(clippy::empty_docs)]
+pub mod overwrite;
+pub use overwrite::function::check_overwrites;
+
+///
+#[allow(clippy::empty_docs)]
+pub mod switch;
+pub use switch::function::switch;
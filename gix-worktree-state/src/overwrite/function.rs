@@ -0,0 +1,259 @@
+use std::{collections::BTreeSet, path::Path};
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use gix_index::{entry::Mode, Entry};
+use gix_status::index_as_worktree::{traits, Change, EntryStatus, Recorder};
+
+use crate::overwrite::{Conflicts, Context, Error, Options};
+
+/// Assure that checking out `target` over the `index`, which was created from the tree at `head`, doesn't lose local changes
+/// in the `index` or in the `worktree`, and fail with an error listing all conflicting paths otherwise.
+/// `objects` are used to look up attributes and to compare the content of files.
+///
+/// Only paths that are different between `index` and `target` are checked, and they conflict if:
+///
+/// * the `index` doesn't match `head` at that path, i.e. there are staged changes or unresolved conflicts,
+/// * or the file in the `worktree` doesn't match the `index`, although it's fine if it was deleted,
+/// * or an untracked file is in its place or in place of one of its leading directories,
+/// * or an untracked file is in a directory that would be replaced by a file.
+///
+/// Paths that don't differ between `head` and `target` are kept as they are, and can't conflict.
+/// Ignored untracked files are allowed to be overwritten if [`Context::excludes`] is set.
+///
+/// If [`Options::force`] is set, nothing is checked.
+///
+/// ### Deviation
+///
+/// Submodules are only checked to not have changes in the index, and their worktrees are assumed to be unmodified.
+pub fn check_overwrites<Find>(
+    head: &gix_index::State,
+    index: &gix_index::State,
+    target: &gix_index::State,
+    worktree: &Path,
+    objects: Find,
+    Context {
+        status: status_ctx,
+        mut excludes,
+    }: Context<'_>,
+    options: Options,
+) -> Result<(), Error>
+where
+    Find: gix_object::Find + Send + Clone,
+{
+    if options.force {
+        return Ok(());
+    }
+    let _span = gix_features::trace::coarse!("gix_worktree_state::check_overwrites()");
+
+    let mut local_changes = BTreeSet::<BString>::new();
+    let mut needs_worktree_check = BTreeSet::<BString>::new();
+    let mut new_files = Vec::<BString>::new();
+
+    let paths: BTreeSet<&BStr> = index
+        .entries()
+        .iter()
+        .map(|entry| entry.path(index))
+        .chain(target.entries().iter().map(|entry| entry.path(target)))
+        .collect();
+    for path in paths {
+        let head_entry = head.entry_by_path_and_stage(path, gix_index::entry::Stage::Unconflicted);
+        let target_entry = target.entry_by_path_and_stage(path, gix_index::entry::Stage::Unconflicted);
+        let is_unmerged = index
+            .entry_range(path)
+            .into_iter()
+            .any(|range| index.entries()[range].iter().any(|e| e.stage_raw() != 0));
+        if is_unmerged {
+            local_changes.insert(path.to_owned());
+            continue;
+        }
+        let index_entry = index.entry_by_path_and_stage(path, gix_index::entry::Stage::Unconflicted);
+        match index_entry {
+            Some(index_entry) => {
+                if same(Some(index_entry), target_entry) || same(head_entry, target_entry) {
+                    continue;
+                }
+                if same(head_entry, Some(index_entry)) {
+                    needs_worktree_check.insert(path.to_owned());
+                } else {
+                    local_changes.insert(path.to_owned());
+                }
+            }
+            None => match (head_entry, target_entry) {
+                (_, None) => {}
+                (Some(_), Some(_)) => {
+                    if !same(head_entry, target_entry) {
+                        local_changes.insert(path.to_owned());
+                    }
+                }
+                (None, Some(_)) => new_files.push(path.to_owned()),
+            },
+        }
+    }
+
+    if !needs_worktree_check.is_empty() {
+        let mut to_check = index.clone();
+        to_check.remove_entries(|_, path, _| !needs_worktree_check.contains(path));
+        let mut recorder = Recorder::default();
+        gix_status::index_as_worktree(
+            &to_check,
+            worktree,
+            &mut recorder,
+            traits::FastEq,
+            IgnoreSubmodules,
+            objects.clone(),
+            &mut gix_features::progress::Discard,
+            status_ctx,
+            options.status,
+        )?;
+        local_changes.extend(
+            recorder
+                .records
+                .into_iter()
+                .filter(|record| is_local_change(&record.status))
+                .map(|record| record.relative_path.to_owned()),
+        );
+    }
+
+    let mut untracked_overwritten = BTreeSet::<BString>::new();
+    let mut untracked_removed = BTreeSet::<BString>::new();
+    let mut is_ignored = |rela_path: &BStr, is_dir: bool| -> Result<bool, Error> {
+        let Some(excludes) = excludes.as_mut() else {
+            return Ok(false);
+        };
+        let mode = if is_dir { Mode::DIR } else { Mode::FILE };
+        Ok(excludes
+            .at_entry(rela_path, Some(mode), &objects)
+            .map_err(|err| Error::Io {
+                source: err,
+                path: worktree.join(gix_path::from_bstr(rela_path)),
+            })?
+            .is_excluded())
+    };
+    for path in new_files {
+        let mut is_blocked_by_leading_path = false;
+        for pos in path.find_iter(b"/") {
+            let leading = path[..pos].as_bstr();
+            let Some(metadata) = symlink_metadata(worktree, leading)? else {
+                break;
+            };
+            if metadata.is_dir() {
+                continue;
+            }
+            if index.entry_index_by_path(leading).is_err() && !is_ignored(leading, false)? {
+                untracked_overwritten.insert(leading.to_owned());
+            }
+            is_blocked_by_leading_path = true;
+            break;
+        }
+        if is_blocked_by_leading_path {
+            continue;
+        }
+
+        let Some(metadata) = symlink_metadata(worktree, path.as_bstr())? else {
+            continue;
+        };
+        if metadata.is_dir() {
+            collect_untracked_files(worktree, path, index, &mut is_ignored, &mut untracked_removed)?;
+        } else if !is_ignored(path.as_bstr(), false)? {
+            untracked_overwritten.insert(path);
+        }
+    }
+
+    let conflicts = Conflicts {
+        local_changes: local_changes.into_iter().collect(),
+        untracked_overwritten: untracked_overwritten.into_iter().collect(),
+        untracked_removed: untracked_removed.into_iter().collect(),
+    };
+    if conflicts.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::WouldOverwrite { conflicts })
+    }
+}
+
+pub(crate) fn same(a: Option<&Entry>, b: Option<&Entry>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.id == b.id && a.mode == b.mode,
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Deleted files are fine as nothing would be lost, just like it is when the file only needs its stat information updated.
+fn is_local_change(status: &EntryStatus<(), ()>) -> bool {
+    match status {
+        EntryStatus::Conflict(_) | EntryStatus::IntentToAdd => true,
+        EntryStatus::Change(change) => !matches!(change, Change::Removed),
+        EntryStatus::NeedsUpdate(_) => false,
+    }
+}
+
+fn symlink_metadata(worktree: &Path, rela_path: &BStr) -> Result<Option<std::fs::Metadata>, Error> {
+    let path = worktree.join(gix_path::try_from_bstr(rela_path).map_err(|_| Error::IllformedUtf8 {
+        path: rela_path.to_owned(),
+    })?);
+    match std::fs::symlink_metadata(&path) {
+        Ok(metadata) => Ok(Some(metadata)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(Error::Io { source: err, path }),
+    }
+}
+
+/// Recursively find all non-ignored files below the directory at `rela_path` that aren't tracked in `index`, without
+/// entering tracked submodules.
+fn collect_untracked_files(
+    worktree: &Path,
+    rela_path: BString,
+    index: &gix_index::State,
+    is_ignored: &mut dyn FnMut(&BStr, bool) -> Result<bool, Error>,
+    out: &mut BTreeSet<BString>,
+) -> Result<(), Error> {
+    if index.entry_index_by_path(rela_path.as_bstr()).is_ok() || is_ignored(rela_path.as_bstr(), true)? {
+        return Ok(());
+    }
+    let dir = worktree.join(
+        gix_path::try_from_bstr(rela_path.as_bstr()).map_err(|_| Error::IllformedUtf8 {
+            path: rela_path.clone(),
+        })?,
+    );
+    let entries = std::fs::read_dir(&dir).map_err(|err| Error::Io {
+        source: err,
+        path: dir.clone(),
+    })?;
+    for entry in entries {
+        let entry = entry.map_err(|err| Error::Io {
+            source: err,
+            path: dir.clone(),
+        })?;
+        let mut entry_path = rela_path.clone();
+        entry_path.push_byte(b'/');
+        entry_path.push_str(
+            gix_path::os_str_into_bstr(&entry.file_name()).map_err(|_| Error::IllformedUtf8 {
+                path: rela_path.clone(),
+            })?,
+        );
+        let file_type = entry.file_type().map_err(|err| Error::Io {
+            source: err,
+            path: entry.path(),
+        })?;
+        if file_type.is_dir() {
+            collect_untracked_files(worktree, entry_path, index, is_ignored, out)?;
+        } else if index.entry_index_by_path(entry_path.as_bstr()).is_err() && !is_ignored(entry_path.as_bstr(), false)?
+        {
+            out.insert(entry_path);
+        }
+    }
+    Ok(())
+}
+
+#[derive(Clone)]
+struct IgnoreSubmodules;
+
+impl traits::SubmoduleStatus for IgnoreSubmodules {
+    type Output = ();
+    type Error = std::convert::Infallible;
+
+    fn status(&mut self, _entry: &Entry, _rela_path: &BStr) -> Result<Option<()>, Self::Error> {
+        Ok(None)
+    }
+}
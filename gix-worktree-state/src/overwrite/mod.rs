@@ -0,0 +1,145 @@
+use std::path::Path;
+
+use bstr::{BStr, BString};
+
+/// Paths whose local state would be lost if a checkout was performed, grouped like `git` does when refusing to switch branches.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Conflicts {
+    /// Tracked files whose changes in the index or in the worktree would be overwritten or deleted.
+    ///
+    /// This also includes files whose removal was staged, and files with unresolved conflicts.
+    pub local_changes: Vec<BString>,
+    /// Untracked files or symlinks that are in the way of a tracked file, either at its location or at one of its
+    /// leading directories.
+    pub untracked_overwritten: Vec<BString>,
+    /// Untracked files inside of directories which would be removed to make room for a tracked file.
+    pub untracked_removed: Vec<BString>,
+}
+
+impl Conflicts {
+    /// Return `true` if there are no conflicting paths at all.
+    pub fn is_empty(&self) -> bool {
+        self.local_changes.is_empty() && self.untracked_overwritten.is_empty() && self.untracked_removed.is_empty()
+    }
+
+    /// Return a type to display the conflicts like [`Display`](std::fmt::Display) does, but with each path shown
+    /// relative to `current_dir` if that is shorter, just like `git` does.
+    ///
+    /// `worktree_root` is the directory the worktree-relative conflicting paths are relative to.
+    pub fn display_relative_to<'a>(
+        &'a self,
+        worktree_root: &'a Path,
+        current_dir: &'a Path,
+    ) -> impl std::fmt::Display + 'a {
+        RelativeConflicts {
+            conflicts: self,
+            worktree_root,
+            current_dir,
+        }
+    }
+
+    fn write_sections(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        mut write_path: impl FnMut(&mut std::fmt::Formatter<'_>, &BStr) -> std::fmt::Result,
+    ) -> std::fmt::Result {
+        let sections = [
+            (
+                &self.local_changes,
+                "Your local changes to the following files would be overwritten by checkout:",
+                "Please commit your changes or stash them before you switch branches.",
+            ),
+            (
+                &self.untracked_overwritten,
+                "The following untracked working tree files would be overwritten by checkout:",
+                "Please move or remove them before you switch branches.",
+            ),
+            (
+                &self.untracked_removed,
+                "The following untracked working tree files would be removed by checkout:",
+                "Please move or remove them before you switch branches.",
+            ),
+        ];
+        let mut is_first = true;
+        for (paths, title, advice) in sections.iter().filter(|(paths, _, _)| !paths.is_empty()) {
+            if !is_first {
+                f.write_str("\n")?;
+            }
+            is_first = false;
+            writeln!(f, "{title}")?;
+            for path in paths.iter() {
+                f.write_str("\t")?;
+                write_path(f, path.as_ref())?;
+                f.write_str("\n")?;
+            }
+            f.write_str(advice)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Conflicts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_sections(f, |f, path| write!(f, "{path}"))
+    }
+}
+
+struct RelativeConflicts<'a> {
+    conflicts: &'a Conflicts,
+    worktree_root: &'a Path,
+    current_dir: &'a Path,
+}
+
+impl std::fmt::Display for RelativeConflicts<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.conflicts.write_sections(f, |f, path| {
+            let path = self.worktree_root.join(gix_path::from_bstr(path));
+            write!(
+                f,
+                "{}",
+                gix_path::relativize_for_display(&path, self.current_dir).display()
+            )
+        })
+    }
+}
+
+/// The error returned by [`check_overwrites()`](crate::check_overwrites()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("{conflicts}")]
+    WouldOverwrite { conflicts: Conflicts },
+    #[error("Could not convert path to UTF8: {}", .path)]
+    IllformedUtf8 { path: BString },
+    #[error("Could not obtain the status of tracked files in the worktree")]
+    Status(#[from] gix_status::index_as_worktree::Error),
+    #[error("Could not read '{}' from the worktree", .path.display())]
+    Io {
+        source: std::io::Error,
+        path: std::path::PathBuf,
+    },
+}
+
+/// Options to configure [`check_overwrites()`](crate::check_overwrites()).
+#[derive(Clone, Default, Debug, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// If `true`, default `false`, don't check anything and allow local changes to be overwritten,
+    /// similar to `git checkout --force`.
+    pub force: bool,
+    /// Options to control how the tracked files in the worktree are compared to the index.
+    pub status: gix_status::index_as_worktree::Options,
+}
+
+/// The context for [`check_overwrites()`](crate::check_overwrites()).
+#[derive(Clone)]
+pub struct Context<'a> {
+    /// The context for obtaining the status of tracked files in the worktree.
+    ///
+    /// Its pathspec should be empty as only the paths that the checkout would change are checked.
+    pub status: gix_status::index_as_worktree::Context<'a>,
+    /// A stack configured with exclude patterns to allow untracked files to be overwritten if they are ignored, just like
+    /// `git` does by default, or `None` to protect all untracked files.
+    pub excludes: Option<gix_worktree::Stack>,
+}
+
+pub(crate) mod function;
@@ -0,0 +1,252 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
+
+use bstr::{BStr, ByteSlice};
+use gix_index::entry::{Mode, Stage, Stat};
+
+use crate::{
+    overwrite::function::same,
+    switch::{Change, Decision, Error, Options, Outcome},
+};
+
+/// Switch the `worktree` and the `index`, which was created from the tree at `head`, to the tree represented by `target`,
+/// while only touching the paths that differ between `index` and `target`.
+/// `objects` are used to look up blobs, and files are counted in `files` and their written bytes in `bytes`.
+/// If `should_interrupt` is `true`, the checkout of files will abort early.
+///
+/// Before changing anything, [`check_overwrites()`](crate::check_overwrites()) assures that no local changes would be lost,
+/// using `ctx` along with [`Options::overwrite`]. Then each path is assigned a [`Decision`]:
+///
+/// * paths that are the same in `index` and `target` are kept, just like paths that are the same in `head` and `target`,
+///   which keeps local changes,
+/// * files only in `target` are created, and files only in `index` are deleted along with all leading directories that
+///   become empty, unless one of their leading directories is a symlink, which leaves them in place,
+/// * files whose content or type changed are updated, and files that only changed their executable bit have their mode
+///   updated.
+///
+/// Files in the worktree that aren't touched keep their modification time, which is important for build systems.
+/// Finally, the `index` is updated to match `target` for all changed paths, with stat information refreshed from the
+/// worktree, and its `tree` extension is removed as it would be outdated.
+///
+/// ### Deviation
+///
+/// If [`force`](crate::overwrite::Options::force) is set, local changes are overwritten for all paths that differ between
+/// `index` and `target`, while `git` would also discard local changes to all other paths.
+#[allow(clippy::too_many_arguments, clippy::result_large_err)]
+pub fn switch<Find>(
+    head: &gix_index::State,
+    index: &mut gix_index::State,
+    target: &gix_index::State,
+    worktree: &Path,
+    objects: Find,
+    files: &dyn gix_features::progress::Count,
+    bytes: &dyn gix_features::progress::Count,
+    should_interrupt: &AtomicBool,
+    ctx: crate::overwrite::Context<'_>,
+    options: Options,
+) -> Result<Outcome, Error>
+where
+    Find: gix_object::Find + Send + Clone,
+{
+    let _span = gix_features::trace::coarse!("gix_worktree_state::switch()");
+    crate::check_overwrites(
+        head,
+        index,
+        target,
+        worktree,
+        objects.clone(),
+        ctx,
+        options.overwrite.clone(),
+    )?;
+
+    let changes = decide(head, index, target, options.overwrite.force);
+    if changes.is_empty() {
+        return Ok(Outcome::default());
+    }
+
+    let mut directories_removed = 0;
+    for change in changes.iter().filter(|change| change.decision == Decision::Delete) {
+        let path = worktree_path(worktree, change.path.as_bstr())?;
+        // Never delete through a symlink in the leading path as it might point outside of the worktree, just like `git`.
+        if crate::checkout::entry::verify_no_symlink_in_leading_path(
+            worktree,
+            change.path.as_bstr(),
+            &Default::default(),
+            options.checkout.fs.ignore_case,
+            false,
+        )
+        .is_err()
+        {
+            continue;
+        }
+        let res = match std::fs::symlink_metadata(&path) {
+            // Submodules that aren't empty are left in place, just like `git` does.
+            Ok(metadata) if metadata.is_dir() => std::fs::remove_dir(&path).or(Ok(())),
+            Ok(_) => std::fs::remove_file(&path),
+            Err(err) => Err(err),
+        };
+        match res {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(Error::Io { source: err, path }),
+        }
+        directories_removed += remove_empty_leading_directories(worktree, change.path.as_bstr());
+    }
+
+    let mut to_checkout = gix_index::State::new(target.object_hash());
+    for change in changes
+        .iter()
+        .filter(|change| matches!(change.decision, Decision::Create | Decision::UpdateContent))
+    {
+        let entry = target
+            .entry_by_path_and_stage(change.path.as_bstr(), Stage::Unconflicted)
+            .expect("created and updated paths are in target");
+        to_checkout.dangerously_push_entry(entry.stat, entry.id, entry.flags, entry.mode, change.path.as_bstr());
+    }
+    let checkout = if to_checkout.entries().is_empty() {
+        Default::default()
+    } else {
+        crate::checkout(
+            &mut to_checkout,
+            worktree,
+            objects,
+            files,
+            bytes,
+            should_interrupt,
+            crate::checkout::Options {
+                overwrite_existing: true,
+                destination_is_initially_empty: false,
+                ..options.checkout.clone()
+            },
+        )?
+    };
+
+    let touched: BTreeSet<&BStr> = changes.iter().map(|change| change.path.as_bstr()).collect();
+    index.remove_entries(|_, path, _| touched.contains(path));
+    for entry in to_checkout.entries() {
+        index.dangerously_push_entry(entry.stat, entry.id, entry.flags, entry.mode, entry.path(&to_checkout));
+    }
+    for change in changes.iter().filter(|change| change.decision == Decision::UpdateMode) {
+        let entry = target
+            .entry_by_path_and_stage(change.path.as_bstr(), Stage::Unconflicted)
+            .expect("paths with updated mode are in target");
+        let path = worktree_path(worktree, change.path.as_bstr())?;
+        if options.checkout.fs.executable_bit {
+            set_executable(&path, entry.mode == Mode::FILE_EXECUTABLE).map_err(|err| Error::Io {
+                source: err,
+                path: path.clone(),
+            })?;
+        }
+        let stat = match gix_index::fs::Metadata::from_path_no_follow(&path) {
+            Ok(metadata) => Stat::from_fs(&metadata)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Stat::default(),
+            Err(err) => return Err(Error::Io { source: err, path }),
+        };
+        index.dangerously_push_entry(stat, entry.id, entry.flags, entry.mode, change.path.as_bstr());
+    }
+    index.sort_entries();
+    index.remove_tree();
+
+    Ok(Outcome {
+        changes,
+        directories_removed,
+        checkout,
+    })
+}
+
+/// Decide what to do with each path that differs between `index` and `target`, in order.
+fn decide(head: &gix_index::State, index: &gix_index::State, target: &gix_index::State, force: bool) -> Vec<Change> {
+    let paths: BTreeSet<&BStr> = index
+        .entries()
+        .iter()
+        .map(|entry| entry.path(index))
+        .chain(target.entries().iter().map(|entry| entry.path(target)))
+        .collect();
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let target_entry = target.entry_by_path_and_stage(path, Stage::Unconflicted);
+            let is_unmerged = index
+                .entry_range(path)
+                .into_iter()
+                .any(|range| index.entries()[range].iter().any(|e| e.stage_raw() != 0));
+            let index_entry = index.entry_by_path_and_stage(path, Stage::Unconflicted);
+            if !is_unmerged && same(index_entry, target_entry) {
+                return None;
+            }
+            if !force && same(head.entry_by_path_and_stage(path, Stage::Unconflicted), target_entry) {
+                return None;
+            }
+            let decision = match (index_entry, target_entry) {
+                (_, None) => Decision::Delete,
+                (_, Some(_)) if is_unmerged => Decision::UpdateContent,
+                (None, Some(_)) => Decision::Create,
+                (Some(index_entry), Some(target_entry)) => {
+                    let only_executable_bit_changed = index_entry.id == target_entry.id
+                        && matches!(
+                            (index_entry.mode, target_entry.mode),
+                            (Mode::FILE, Mode::FILE_EXECUTABLE) | (Mode::FILE_EXECUTABLE, Mode::FILE)
+                        );
+                    if only_executable_bit_changed {
+                        Decision::UpdateMode
+                    } else {
+                        Decision::UpdateContent
+                    }
+                }
+            };
+            Some(Change {
+                path: path.to_owned(),
+                decision,
+            })
+        })
+        .collect()
+}
+
+#[allow(clippy::result_large_err)]
+fn worktree_path(worktree: &Path, rela_path: &BStr) -> Result<PathBuf, Error> {
+    Ok(
+        worktree.join(gix_path::try_from_bstr(rela_path).map_err(|_| Error::IllformedUtf8 {
+            path: rela_path.to_owned(),
+        })?),
+    )
+}
+
+/// Remove all leading directories of `rela_path` from the innermost to the outermost, until one of them isn't empty,
+/// and return the amount of removed directories.
+///
+/// The leading directories must have been verified to not contain symlinks.
+fn remove_empty_leading_directories(worktree: &Path, rela_path: &BStr) -> usize {
+    let mut num_removed = 0;
+    for pos in rela_path.rfind_iter(b"/") {
+        let Ok(dir) = gix_path::try_from_bstr(rela_path[..pos].as_bstr()) else {
+            break;
+        };
+        if std::fs::remove_dir(worktree.join(dir)).is_err() {
+            break;
+        }
+        num_removed += 1;
+    }
+    num_removed
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path, executable: bool) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut perm = std::fs::symlink_metadata(path)?.permissions();
+    let mode = perm.mode();
+    // Only allow execution for those who may read the file, just like `git` does.
+    perm.set_mode(if executable {
+        mode | ((mode & 0o444) >> 2)
+    } else {
+        mode & !0o111
+    });
+    std::fs::set_permissions(path, perm)
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path, _executable: bool) -> std::io::Result<()> {
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use bstr::BString;
+
+/// What to do with a path in the worktree and the index to switch from one tree to another.
+///
+/// Paths that don't need a change aren't listed and are kept as they are, along with their local modifications.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Decision {
+    /// Write the content of the target tree to the worktree, replacing what was there before.
+    ///
+    /// This is also used for type changes, like a file becoming a symbolic link, and for paths with unresolved conflicts.
+    UpdateContent,
+    /// Only change the executable bit of the file in the worktree, as its content stays the same.
+    UpdateMode,
+    /// Remove the path from the worktree, along with all leading directories that become empty.
+    Delete,
+    /// Write a path that didn't exist in the index before.
+    Create,
+}
+
+/// A path along with the [`Decision`] of how to change it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Change {
+    /// The path relative to the worktree root.
+    pub path: BString,
+    /// What to do with the path.
+    pub decision: Decision,
+}
+
+/// The outcome of [`switch()`](crate::switch()).
+#[derive(Debug, Default)]
+pub struct Outcome {
+    /// All paths that were changed in the worktree and in the index, in order.
+    pub changes: Vec<Change>,
+    /// The amount of directories that were removed as they became empty after deleting files.
+    pub directories_removed: usize,
+    /// The outcome of checking out all paths whose content was [updated](Decision::UpdateContent) or which
+    /// were [created](Decision::Create).
+    pub checkout: crate::checkout::Outcome,
+}
+
+/// Options to configure [`switch()`](crate::switch()).
+#[derive(Clone, Default)]
+pub struct Options {
+    /// Options to control the check for local changes, which also allow to overwrite them.
+    pub overwrite: crate::overwrite::Options,
+    /// Options to control how files are written.
+    ///
+    /// Note that [`overwrite_existing`](crate::checkout::Options::overwrite_existing) is always enabled and
+    /// [`destination_is_initially_empty`](crate::checkout::Options::destination_is_initially_empty) is always disabled,
+    /// as all paths that are written were checked before.
+    pub checkout: crate::checkout::Options,
+}
+
+/// The error returned by [`switch()`](crate::switch()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Overwrite(#[from] crate::overwrite::Error),
+    #[error(transparent)]
+    Checkout(#[from] crate::checkout::Error),
+    #[error("Could not convert path to UTF8: {}", .path)]
+    IllformedUtf8 { path: BString },
+    #[error("Could not update '{}' in the worktree", .path.display())]
+    Io {
+        source: std::io::Error,
+        path: std::path::PathBuf,
+    },
+    #[error("The clock was off when reading file related metadata after updating a file on disk")]
+    Time(#[from] std::time::SystemTimeError),
+}
+
+pub(crate) mod function;
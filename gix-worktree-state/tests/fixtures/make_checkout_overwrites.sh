@@ -0,0 +1,48 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q base
+(cd base
+  echo unchanged > unchanged
+  echo changed > changed
+  echo removed > removed
+  mkdir dir && echo a > dir/a && echo b > dir/b
+  echo file > file-becomes-dir
+  git add . && git commit -q -m "head"
+  git rev-parse @^{tree} > ../head-tree
+
+  git checkout -q -b other
+  echo other > changed
+  git rm -q removed
+  git rm -q -r dir && echo "now a file" > dir
+  git rm -q file-becomes-dir && mkdir file-becomes-dir && echo new > file-becomes-dir/new
+  echo added > added
+  mkdir new-dir && echo new > new-dir/file
+  git add . && git commit -q -m "target"
+  git rev-parse @^{tree} > ../target-tree
+  git checkout -q @{-1}
+)
+
+cp -R base clean
+
+cp -R base tracked-modified
+(cd tracked-modified
+  echo modified >> changed
+  echo staged >> removed && git add removed
+  echo modified >> unchanged
+  echo modified >> dir/a
+  rm dir/b
+)
+
+cp -R base untracked-collision
+(cd untracked-collision
+  echo untracked > added
+  echo untracked > new-dir
+)
+
+cp -R base dir-becomes-file
+(cd dir-becomes-file
+  echo untracked > dir/untracked
+  mkdir dir/nested && echo untracked > dir/nested/untracked
+  echo modified >> file-becomes-dir
+)
@@ -0,0 +1,43 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q base
+(cd base
+  echo unchanged > unchanged
+  echo staged > staged
+  echo changed > changed
+  echo removed > removed
+  echo script > script
+  mkdir dir && echo a > dir/a && echo b > dir/b
+  echo file > file-becomes-dir
+  mkdir -p deep/nested && echo only > deep/nested/only
+  mkdir kept && echo kept > kept/file
+  git add . && git commit -q -m "head"
+  git rev-parse @^{tree} > ../head-tree
+
+  git checkout -q -b other
+  echo other > changed
+  chmod +x script && git update-index --chmod=+x script
+  git rm -q removed
+  git rm -q -r dir && echo "now a file" > dir
+  git rm -q file-becomes-dir && mkdir file-becomes-dir && echo new > file-becomes-dir/new
+  git rm -q -r deep
+  echo added > added
+  mkdir new-dir && echo new > new-dir/file
+  git add . && git commit -q -m "target"
+  git rev-parse @^{tree} > ../target-tree
+  git checkout -q @{-1}
+)
+
+cp -R base clean
+
+cp -R base local-changes
+(cd local-changes
+  echo modified >> unchanged
+  echo modified >> staged && git add staged
+)
+
+cp -R base conflicting
+(cd conflicting
+  echo modified >> changed
+)
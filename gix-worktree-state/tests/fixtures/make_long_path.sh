@@ -0,0 +1,10 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+# Add a file whose path is 300 characters long to the index without ever having it on disk.
+dir=$(printf 'd%.0s' $(seq 55))
+blob=$(echo content | git hash-object -w --stdin)
+git update-index --add --cacheinfo 100644,"$blob","$dir/$dir/$dir/$dir/$dir/file-with-long-path.txt"
+git commit -q -m "a file with a long path"
@@ -0,0 +1,18 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+# A symlink pointing outside of the worktree, followed by an entry in the same tree that would be written through it.
+# Git itself can't put both into the index, but a crafted tree can contain both.
+# As symlinks are checked out last, the entry below it is a symlink as well to be sure it's written afterwards.
+# The target doesn't exist to be sure nothing happens outside of the worktree even if the protection fails.
+empty_oid=$(git hash-object -w --stdin </dev/null)
+outside_target=$(echo -n '/non-existing-gix-checkout-target' | git hash-object -w --stdin)
+inner_target=$(echo -n 'anything' | git hash-object -w --stdin)
+file_target=$(echo -n 'file' | git hash-object -w --stdin)
+
+sub_tree=$(printf "120000 blob $inner_target\tpasswd\n" | git mktree)
+root_tree=$(printf "120000 blob $outside_target\ta\n040000 tree $sub_tree\ta\n100644 blob $empty_oid\tfile\n120000 blob $file_target\tlink-to-file\n" | git mktree)
+
+git update-ref refs/heads/main "$(git commit-tree -m "init" "$root_tree")"
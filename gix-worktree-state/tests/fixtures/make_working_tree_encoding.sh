@@ -0,0 +1,19 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+echo "*.txt working-tree-encoding=UTF-16LE" > .gitattributes
+git add .gitattributes
+
+printf 'h\0\344\0l\0l\0o\0' > good.txt
+git add good.txt
+
+# An unpaired surrogate encoded like UTF-8 which can't be represented in UTF-16, and which `git add` would never produce.
+blob=$(printf 'ok \355\240\200' | git hash-object -w --stdin --no-filters)
+git update-index --add --cacheinfo 100644 "$blob" bad.txt
+
+echo "unaffected" > other
+git add other
+
+git commit -q -m "Commit"
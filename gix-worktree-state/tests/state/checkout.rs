@@ -8,7 +8,7 @@ use std::{
 };
 
 use gix_features::progress;
-use gix_object::{bstr::ByteSlice, Data};
+use gix_object::{bstr::ByteSlice, Data, FindExt};
 use gix_testtools::tempfile::TempDir;
 use gix_worktree_state::checkout::Collision;
 use once_cell::sync::Lazy;
@@ -137,6 +137,65 @@ fn writes_through_symlinks_are_prevented_even_if_overwriting_is_allowed() {
     };
 }
 
+#[test]
+fn entries_below_symlinks_created_by_checkout_are_rejected() -> crate::Result {
+    let mut opts = opts_from_probe();
+    if !opts.fs.symlink {
+        eprintln!("Skipping symlink test on filesystem that doesn't support it");
+        return Ok(());
+    }
+    let git_dir = fixture_path("make_symlink_then_file").join(".git");
+    let odb = gix_odb::at(git_dir.join("objects"))?.into_inner().into_arc()?;
+    let commit_id = gix_hash::ObjectId::from_hex(fs::read(git_dir.join("refs/heads/main"))?.trim())?;
+    let mut buf = Vec::new();
+    let tree_id = odb.find_commit(&commit_id, &mut buf)?.tree();
+
+    for overwrite_existing in [false, true] {
+        opts.overwrite_existing = overwrite_existing;
+        let mut index = gix_index::State::from_tree(&tree_id, &odb, Default::default())?;
+        assert_eq!(
+            index.entries().len(),
+            4,
+            "the crafted tree puts both 'a' and 'a/passwd' into the index"
+        );
+        let destination = gix_testtools::tempfile::tempdir_in(std::env::current_dir()?)?;
+        let outcome = gix_worktree_state::checkout(
+            &mut index,
+            destination.path(),
+            odb.clone(),
+            &progress::Discard,
+            &progress::Discard,
+            &AtomicBool::default(),
+            opts.clone(),
+        )?;
+
+        assert!(outcome.collisions.is_empty());
+        assert_eq!(
+            outcome
+                .errors
+                .iter()
+                .map(|r| r.path.to_path_lossy().into_owned())
+                .collect::<Vec<_>>(),
+            paths(["a/passwd"]),
+            "only the entry below the symlink is rejected, even without keep-going"
+        );
+        assert!(outcome.errors[0]
+            .error
+            .to_string()
+            .starts_with("Refusing to write 'a/passwd' as its leading component"));
+        assert_eq!(
+            stripped_prefix(&destination, &dir_structure(&destination)),
+            paths(["a", "file", "link-to-file"]),
+            "everything else was checked out"
+        );
+        assert!(
+            fs::symlink_metadata(destination.path().join("a"))?.is_symlink(),
+            "the symlink we created is still present and wasn't replaced by a directory"
+        );
+    }
+    Ok(())
+}
+
 #[test]
 fn delayed_driver_process() -> crate::Result {
     let mut opts = opts_from_probe();
@@ -305,6 +364,37 @@ fn allow_or_disallow_symlinks() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn working_tree_encoding_round_trip_failures_are_recorded_without_stopping_the_checkout() -> crate::Result {
+    let mut opts = opts_from_probe();
+    opts.filters.options_mut().encodings_with_roundtrip_check =
+        vec![gix_filter::worktree::encoding::for_label("UTF-16LE")?];
+    assert!(!opts.keep_going, "these failures are always recorded");
+    let (_source_tree, destination, _index, outcome) =
+        checkout_index_in_tmp_dir(opts, "make_working_tree_encoding", None)?;
+
+    assert_eq!(
+        outcome.errors.iter().map(|r| r.path.as_bstr()).collect::<Vec<_>>(),
+        ["bad.txt"]
+    );
+    let err = &outcome.errors[0].error;
+    assert_eq!(err.to_string(), "Could not encode 'bad.txt' for the working tree");
+    assert_eq!(
+        std::error::Error::source(&**err).expect("present").to_string(),
+        "Encoding from 'UTF-8' to 'UTF-16LE' and back is not the same, the first byte that differs is at offset 3"
+    );
+
+    let dest = destination.path();
+    assert!(!dest.join("bad.txt").exists(), "the file isn't written at all");
+    assert_eq!(
+        std::fs::read(dest.join("good.txt"))?.as_bstr(),
+        b"h\0\xe4\0l\0l\0o\0".as_bstr(),
+        "everything else is checked out, with the working-tree-encoding applied"
+    );
+    assert_eq!(std::fs::read(dest.join("other"))?.as_bstr(), "unaffected\n");
+    Ok(())
+}
+
 #[test]
 fn keep_going_collects_results() {
     let mut opts = opts_from_probe();
@@ -507,6 +597,38 @@ fn collisions_are_detected_on_a_case_insensitive_filesystem_even_with_delayed_fi
     }
 }
 
+#[test]
+#[cfg(windows)]
+fn long_paths_are_written_only_if_enabled() -> crate::Result {
+    let mut opts = opts_from_probe();
+    opts.fs.long_paths = false;
+    let err = checkout_index_in_tmp_dir(opts.clone(), "make_long_path", None).unwrap_err();
+    let msg = err.to_string();
+    assert!(
+        msg.contains("file-with-long-path.txt") && msg.contains("core.longPaths"),
+        "the error names the path and the way to allow it: {msg}"
+    );
+
+    opts.fs.long_paths = true;
+    let (_source_tree, destination, index, outcome) = checkout_index_in_tmp_dir(opts, "make_long_path", None)?;
+    assert_eq!(outcome.files_updated, 1);
+    assert!(outcome.errors.is_empty());
+
+    let rela_path = index.entries()[0].path(&index);
+    assert_eq!(
+        rela_path.len(),
+        303,
+        "the path is longer than MAX_PATH even without its prefix"
+    );
+    let path = destination.path().join(rela_path.to_path_lossy());
+    assert_eq!(
+        std::fs::read(gix_fs::long_path::to_extended_length(&path, true)?)?,
+        b"content\n",
+        "the file was written with its content"
+    );
+    Ok(())
+}
+
 fn multi_threaded() -> bool {
     gix_features::parallel::num_threads(None) > 1
 }
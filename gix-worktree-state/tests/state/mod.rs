@@ -1,4 +1,6 @@
 mod checkout;
+mod overwrite;
+mod switch;
 
 use std::path::{Path, PathBuf};
 
@@ -0,0 +1,141 @@
+use std::{path::Path, sync::atomic::AtomicBool};
+
+use gix_worktree_state::overwrite::{Conflicts, Context, Error, Options};
+
+use crate::fixture_path;
+
+fn check_overwrites(name: &str, force: bool) -> crate::Result<Result<(), Error>> {
+    let root = fixture_path("make_checkout_overwrites");
+    let worktree = root.join(name);
+    let git_dir = worktree.join(".git");
+    let odb = gix_odb::at(git_dir.join("objects"))?.into_inner().into_arc()?;
+    let index = gix_index::File::at(git_dir.join("index"), gix_hash::Kind::Sha1, false, Default::default())?;
+    let state_from_tree = |name: &str| -> crate::Result<gix_index::State> {
+        let hex = std::fs::read_to_string(root.join(name))?;
+        let tree = gix_hash::ObjectId::from_hex(hex.trim().as_bytes())?;
+        Ok(gix_index::State::from_tree(&tree, &odb, Default::default())?)
+    };
+    let (head, target) = (state_from_tree("head-tree")?, state_from_tree("target-tree")?);
+
+    let stack = gix_worktree::Stack::from_state_and_ignore_case(
+        worktree.clone(),
+        false,
+        gix_worktree::stack::State::AttributesStack(Default::default()),
+        &index,
+        index.path_backing(),
+    );
+    let should_interrupt = AtomicBool::default();
+    Ok(gix_worktree_state::check_overwrites(
+        &head,
+        &index,
+        &target,
+        &worktree,
+        odb.clone(),
+        Context {
+            status: gix_status::index_as_worktree::Context {
+                pathspec: gix_pathspec::Search::from_specs(None, None, Path::new(""))?,
+                stack,
+                filter: Default::default(),
+                should_interrupt: &should_interrupt,
+            },
+            excludes: None,
+        },
+        Options {
+            force,
+            status: gix_status::index_as_worktree::Options {
+                fs: gix_fs::Capabilities::probe(&git_dir),
+                ..Default::default()
+            },
+        },
+    ))
+}
+
+fn conflicts(name: &str) -> crate::Result<Conflicts> {
+    match check_overwrites(name, false)? {
+        Err(Error::WouldOverwrite { conflicts }) => Ok(conflicts),
+        res => panic!("expected conflicts, got {res:?}"),
+    }
+}
+
+#[test]
+fn unmodified_worktree_can_be_switched() -> crate::Result {
+    check_overwrites("clean", false)??;
+    Ok(())
+}
+
+#[test]
+fn tracked_modifications_in_index_and_worktree() -> crate::Result {
+    let conflicts = conflicts("tracked-modified")?;
+    assert_eq!(
+        conflicts,
+        Conflicts {
+            local_changes: vec!["changed".into(), "dir/a".into(), "removed".into()],
+            ..Default::default()
+        },
+        "all staged and unstaged changes to paths that would change are listed, but deleted files, \
+         and modifications of files that stay the same, are fine"
+    );
+    assert_eq!(
+        conflicts.to_string(),
+        "Your local changes to the following files would be overwritten by checkout:
+\tchanged
+\tdir/a
+\tremoved
+Please commit your changes or stash them before you switch branches."
+    );
+
+    check_overwrites("tracked-modified", true)?.expect("force bypasses all checks");
+    Ok(())
+}
+
+#[test]
+fn untracked_files_in_place_of_new_files_and_their_leading_directories() -> crate::Result {
+    assert_eq!(
+        conflicts("untracked-collision")?,
+        Conflicts {
+            untracked_overwritten: vec!["added".into(), "new-dir".into()],
+            ..Default::default()
+        },
+        "the tracked file in place of a new directory is removed without conflict as it's unmodified"
+    );
+    Ok(())
+}
+
+#[test]
+fn directory_becomes_file_with_untracked_files_in_it() -> crate::Result {
+    let conflicts = conflicts("dir-becomes-file")?;
+    assert_eq!(
+        conflicts,
+        Conflicts {
+            local_changes: vec!["file-becomes-dir".into()],
+            untracked_overwritten: vec![],
+            untracked_removed: vec!["dir/nested/untracked".into(), "dir/untracked".into()],
+        },
+        "tracked files in the directory are removed as they are unmodified, but the untracked ones are listed"
+    );
+    assert_eq!(
+        conflicts.to_string(),
+        "Your local changes to the following files would be overwritten by checkout:
+\tfile-becomes-dir
+Please commit your changes or stash them before you switch branches.
+The following untracked working tree files would be removed by checkout:
+\tdir/nested/untracked
+\tdir/untracked
+Please move or remove them before you switch branches."
+    );
+    #[cfg(unix)]
+    assert_eq!(
+        conflicts
+            .display_relative_to("/repo".as_ref(), "/repo/dir".as_ref())
+            .to_string(),
+        "Your local changes to the following files would be overwritten by checkout:
+\t../file-becomes-dir
+Please commit your changes or stash them before you switch branches.
+The following untracked working tree files would be removed by checkout:
+\tnested/untracked
+\tuntracked
+Please move or remove them before you switch branches.",
+        "paths can be shown relative to the current directory"
+    );
+    Ok(())
+}
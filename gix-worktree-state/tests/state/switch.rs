@@ -0,0 +1,267 @@
+use std::{path::Path, sync::atomic::AtomicBool};
+
+use bstr::ByteSlice;
+use filetime::FileTime;
+use gix_testtools::tempfile::TempDir;
+use gix_worktree_state::{
+    overwrite,
+    switch::{Change, Decision, Error, Options, Outcome},
+};
+
+struct Fixture {
+    _tmp: TempDir,
+    worktree: std::path::PathBuf,
+    index: gix_index::File,
+    outcome: Result<Outcome, Error>,
+    target: gix_index::State,
+}
+
+fn switch(name: &str, prepare: impl FnOnce(&Path) -> std::io::Result<()>) -> crate::Result<Fixture> {
+    switch_with_force(name, false, prepare)
+}
+
+fn switch_with_force(
+    name: &str,
+    force: bool,
+    prepare: impl FnOnce(&Path) -> std::io::Result<()>,
+) -> crate::Result<Fixture> {
+    let tmp = gix_testtools::scripted_fixture_writable_standalone("make_checkout_switch.sh").expect("script works");
+    let root = tmp.path();
+    let worktree = root.join(name);
+    prepare(&worktree)?;
+    let git_dir = worktree.join(".git");
+    let odb = gix_odb::at(git_dir.join("objects"))?.into_inner().into_arc()?;
+    let mut index = gix_index::File::at(git_dir.join("index"), gix_hash::Kind::Sha1, false, Default::default())?;
+    let state_from_tree = |name: &str| -> crate::Result<gix_index::State> {
+        let hex = std::fs::read_to_string(root.join(name))?;
+        let tree = gix_hash::ObjectId::from_hex(hex.trim().as_bytes())?;
+        Ok(gix_index::State::from_tree(&tree, &odb, Default::default())?)
+    };
+    let (head, target) = (state_from_tree("head-tree")?, state_from_tree("target-tree")?);
+
+    let stack = gix_worktree::Stack::from_state_and_ignore_case(
+        worktree.clone(),
+        false,
+        gix_worktree::stack::State::AttributesStack(Default::default()),
+        &index,
+        index.path_backing(),
+    );
+    let should_interrupt = AtomicBool::default();
+    let fs = gix_fs::Capabilities::probe(&git_dir);
+    let outcome = gix_worktree_state::switch(
+        &head,
+        &mut index,
+        &target,
+        &worktree,
+        odb.clone(),
+        &gix_features::progress::Discard,
+        &gix_features::progress::Discard,
+        &should_interrupt,
+        overwrite::Context {
+            status: gix_status::index_as_worktree::Context {
+                pathspec: gix_pathspec::Search::from_specs(None, None, Path::new(""))?,
+                stack,
+                filter: Default::default(),
+                should_interrupt: &should_interrupt,
+            },
+            excludes: None,
+        },
+        Options {
+            overwrite: overwrite::Options {
+                force,
+                status: gix_status::index_as_worktree::Options {
+                    fs,
+                    ..Default::default()
+                },
+            },
+            checkout: gix_worktree_state::checkout::Options {
+                fs,
+                ..Default::default()
+            },
+        },
+    );
+    Ok(Fixture {
+        _tmp: tmp,
+        worktree,
+        index,
+        outcome,
+        target,
+    })
+}
+
+fn backdate(path: &Path) -> std::io::Result<FileTime> {
+    let time = FileTime::from_unix_time(1_000_000_000, 0);
+    filetime::set_file_mtime(path, time)?;
+    Ok(time)
+}
+
+fn mtime(path: &Path) -> std::io::Result<FileTime> {
+    Ok(FileTime::from_last_modification_time(&std::fs::symlink_metadata(path)?))
+}
+
+fn change(path: &str, decision: Decision) -> Change {
+    Change {
+        path: path.into(),
+        decision,
+    }
+}
+
+#[test]
+fn only_changed_paths_are_touched_and_the_index_matches_the_target() -> crate::Result {
+    let mut old_mtime = None;
+    let fixture = switch("clean", |worktree| {
+        old_mtime = Some((
+            backdate(&worktree.join("unchanged"))?,
+            backdate(&worktree.join("kept/file"))?,
+        ));
+        Ok(())
+    })?;
+    let outcome = fixture.outcome?;
+    assert_eq!(
+        outcome.changes,
+        vec![
+            change("added", Decision::Create),
+            change("changed", Decision::UpdateContent),
+            change("deep/nested/only", Decision::Delete),
+            change("dir", Decision::Create),
+            change("dir/a", Decision::Delete),
+            change("dir/b", Decision::Delete),
+            change("file-becomes-dir", Decision::Delete),
+            change("file-becomes-dir/new", Decision::Create),
+            change("new-dir/file", Decision::Create),
+            change("removed", Decision::Delete),
+            change("script", Decision::UpdateMode),
+        ]
+    );
+    assert_eq!(
+        outcome.directories_removed, 3,
+        "`deep/nested`, `deep` and `dir` became empty and were removed"
+    );
+    assert_eq!(outcome.checkout.files_updated, 5);
+
+    let worktree = &fixture.worktree;
+    let (unchanged_mtime, kept_mtime) = old_mtime.expect("set");
+    assert_eq!(
+        mtime(&worktree.join("unchanged"))?,
+        unchanged_mtime,
+        "files that don't change are not touched at all"
+    );
+    assert_eq!(mtime(&worktree.join("kept/file"))?, kept_mtime);
+
+    assert_eq!(std::fs::read(worktree.join("changed"))?.as_bstr(), "other\n");
+    assert_eq!(std::fs::read(worktree.join("dir"))?.as_bstr(), "now a file\n");
+    assert_eq!(std::fs::read(worktree.join("file-becomes-dir/new"))?.as_bstr(), "new\n");
+    assert_eq!(std::fs::read(worktree.join("new-dir/file"))?.as_bstr(), "new\n");
+    assert!(!worktree.join("removed").exists());
+    assert!(!worktree.join("deep").exists(), "empty directories are removed");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_ne!(
+            std::fs::symlink_metadata(worktree.join("script"))?.permissions().mode() & 0o111,
+            0,
+            "the executable bit was set"
+        );
+    }
+
+    let actual: Vec<_> = fixture
+        .index
+        .entries()
+        .iter()
+        .map(|e| (e.path(&fixture.index).to_owned(), e.id, e.mode))
+        .collect();
+    let expected: Vec<_> = fixture
+        .target
+        .entries()
+        .iter()
+        .map(|e| (e.path(&fixture.target).to_owned(), e.id, e.mode))
+        .collect();
+    assert_eq!(actual, expected, "the index matches the target tree");
+    assert!(fixture.index.tree().is_none(), "the outdated tree extension is removed");
+    for path in ["added", "changed", "dir", "script"] {
+        let entry = fixture.index.entry_by_path(path.into()).expect("present");
+        let stat =
+            gix_index::entry::Stat::from_fs(&gix_index::fs::Metadata::from_path_no_follow(&worktree.join(path))?)?;
+        assert_eq!(
+            entry.stat.mtime, stat.mtime,
+            "{path}: the stat information of touched files is refreshed"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn local_changes_to_unchanged_paths_are_kept() -> crate::Result {
+    let fixture = switch("local-changes", |_| Ok(()))?;
+    let outcome = fixture.outcome?;
+    assert!(
+        outcome
+            .changes
+            .iter()
+            .all(|change| change.path != "unchanged" && change.path != "staged"),
+        "paths that are the same in both trees aren't changed"
+    );
+
+    let worktree = &fixture.worktree;
+    assert_eq!(
+        std::fs::read(worktree.join("unchanged"))?.as_bstr(),
+        "unchanged\nmodified\n"
+    );
+    let staged = fixture.index.entry_by_path("staged".into()).expect("present");
+    assert_ne!(
+        Some(staged.id),
+        fixture.target.entry_by_path("staged".into()).map(|e| e.id),
+        "the staged change is kept in the index"
+    );
+    Ok(())
+}
+
+#[test]
+fn local_changes_to_changed_paths_prevent_the_switch() -> crate::Result {
+    let fixture = switch("conflicting", |_| Ok(()))?;
+    match fixture.outcome {
+        Err(Error::Overwrite(overwrite::Error::WouldOverwrite { conflicts })) => {
+            assert_eq!(conflicts.local_changes, vec!["changed"]);
+        }
+        res => panic!("expected conflicts, got {res:?}"),
+    }
+    assert_eq!(
+        std::fs::read(fixture.worktree.join("changed"))?.as_bstr(),
+        "changed\nmodified\n",
+        "nothing was changed"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn files_are_not_deleted_through_symlinks_in_the_leading_path() -> crate::Result {
+    let mut outside = None;
+    // Force is needed as the status refuses to look through the symlink, and it's what would lose the most data.
+    let fixture = switch_with_force("clean", true, |worktree| {
+        let outside_dir = worktree.parent().expect("parent").join("outside");
+        std::fs::rename(worktree.join("deep"), &outside_dir)?;
+        std::os::unix::fs::symlink(&outside_dir, worktree.join("deep"))?;
+        outside = Some(outside_dir);
+        Ok(())
+    })?;
+    let outcome = fixture.outcome?;
+    assert!(outcome.changes.contains(&change("deep/nested/only", Decision::Delete)));
+    assert_eq!(
+        outcome.directories_removed, 1,
+        "only `dir` became empty, `deep` is a symlink and isn't looked into"
+    );
+
+    let outside = outside.expect("set");
+    assert_eq!(
+        std::fs::read(outside.join("nested/only"))?.as_bstr(),
+        "only\n",
+        "the file outside of the worktree wasn't deleted"
+    );
+    assert!(
+        std::fs::symlink_metadata(fixture.worktree.join("deep"))?.is_symlink(),
+        "the symlink itself is left alone"
+    );
+    assert!(!fixture.worktree.join("removed").exists(), "other paths are still deleted");
+    Ok(())
+}
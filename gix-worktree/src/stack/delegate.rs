@@ -170,20 +170,22 @@ fn create_leading_directory(
         return Ok(());
     }
     *mkdir_calls += 1;
-    match std::fs::create_dir(stack.current()) {
+    // Directories whose paths are too long for the classic Windows APIs are created using their extended-length form.
+    let dir = gix_fs::long_path::to_extended_length(stack.current(), false)?;
+    match std::fs::create_dir(&dir) {
         Ok(()) => Ok(()),
         Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
-            let meta = stack.current().symlink_metadata()?;
+            let meta = dir.symlink_metadata()?;
             if meta.is_dir() {
                 Ok(())
             } else if unlink_on_collision {
                 if meta.file_type().is_symlink() {
-                    gix_fs::symlink::remove(stack.current())?;
+                    gix_fs::symlink::remove(&dir)?;
                 } else {
-                    std::fs::remove_file(stack.current())?;
+                    std::fs::remove_file(&dir)?;
                 }
                 *mkdir_calls += 1;
-                std::fs::create_dir(stack.current())
+                std::fs::create_dir(&dir)
             } else {
                 Err(err)
             }
@@ -0,0 +1,46 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+fn repo() -> gix::Repository {
+    let dir = gix_testtools::scripted_fixture_read_only("make_changed_paths_many_commits_repo.sh").unwrap();
+    gix::open_opts(dir, gix::open::Options::isolated()).unwrap()
+}
+
+fn count_commits(
+    repo: &gix::Repository,
+    path: &str,
+    use_commit_graph: bool,
+) -> (usize, gix::revision::walk::ChangedPathsStatistics) {
+    let mut walk = repo
+        .rev_walk(Some(repo.head_id().unwrap().detach()))
+        .use_commit_graph(use_commit_graph)
+        .changed_paths(Some(path))
+        .all()
+        .unwrap();
+    let count = walk.by_ref().map(Result::unwrap).count();
+    (count, walk.changed_paths_statistics().expect("limited to paths"))
+}
+
+fn changed_paths(c: &mut Criterion) {
+    let repo = repo();
+    let path = "dir-7/sub/file";
+    let (with_filters, stats) = count_commits(&repo, path, true);
+    let (without_filters, _) = count_commits(&repo, path, false);
+    assert_eq!(with_filters, without_filters, "filters don't change the outcome");
+    let filtered = stats.definitely_not + stats.maybe;
+    eprintln!(
+        "{path}: {with_filters} commits, Bloom filters skipped {} of {filtered} tree comparisons ({:.1}%), {} false positives",
+        stats.definitely_not,
+        stats.definitely_not as f64 / filtered as f64 * 100.0,
+        stats.false_positive
+    );
+
+    let mut group = c.benchmark_group("rev_walk().changed_paths()");
+    group.bench_function("with Bloom filters", |b| b.iter(|| count_commits(&repo, path, true)));
+    group.bench_function("without Bloom filters", |b| {
+        b.iter(|| count_commits(&repo, path, false))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, changed_paths);
+criterion_main!(benches);
@@ -23,6 +23,8 @@ pub mod main_worktree {
         #[error("Couldn't obtain configuration for core.protect*")]
         BooleanConfig(#[from] crate::config::boolean::Error),
         #[error(transparent)]
+        IndexVersion(#[from] crate::config::key::GenericErrorWithValue),
+        #[error(transparent)]
         WriteIndex(#[from] gix_index::file::write::Error),
         #[error(transparent)]
         CheckoutOptions(#[from] crate::config::checkout_options::Error),
@@ -112,11 +114,12 @@ pub mod main_worktree {
                 }
             };
 
-            let index = gix_index::State::from_tree(&root_tree, &repo.objects, repo.config.protect_options()?)
+            let mut index = gix_index::State::from_tree(&root_tree, &repo.objects, repo.config.protect_options()?)
                 .map_err(|err| Error::IndexFromTree {
                     id: root_tree,
                     source: err,
                 })?;
+            index.set_version(repo.config.index_version()?);
             let mut index = gix_index::File::from_state(index, repo.index_path());
 
             let mut opts = repo
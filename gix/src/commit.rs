@@ -22,6 +22,14 @@ pub enum Error {
     ReferenceEdit(#[from] crate::reference::edit::Error),
 }
 
+#[cfg(feature = "blob-diff")]
+pub mod format_patch;
+
+#[cfg(feature = "blob-diff")]
+pub mod am;
+
+pub mod mailinfo;
+
 ///
 #[allow(clippy::empty_docs)]
 #[cfg(feature = "revision")]
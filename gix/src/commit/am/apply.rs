@@ -0,0 +1,568 @@
+//! Parse patches in the format of `git diff` and apply them to the files of a tree, like `git apply --cached` does.
+use std::collections::BTreeMap;
+
+use gix_hash::ObjectId;
+use gix_object::tree::{EntryKind, EntryMode};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    Repository,
+};
+
+/// The error returned when a patch can't be applied, pointing at the file and hunk that failed.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Line {line_number} of the patch is malformed: {message}")]
+    Malformed { line_number: usize, message: &'static str },
+    #[error("The patch of '{path}' has no binary data, as it was created without --binary")]
+    MissingBinaryData { path: BString },
+    #[error("The path '{path}' in the patch isn't valid")]
+    InvalidPath { path: BString },
+    #[error("'{path}' doesn't exist")]
+    MissingFile { path: BString },
+    #[error("'{path}' already exists")]
+    FileExists { path: BString },
+    #[error("Hunk {hunk} of '{path}' doesn't apply")]
+    Hunk { path: BString, hunk: usize },
+    #[error("The patch of '{path}' doesn't remove all of its content")]
+    DeletionLeavesContent { path: BString },
+    #[error("The binary patch of '{path}' doesn't apply to its content")]
+    Binary { path: BString },
+    #[error("The binary patch of '{path}' is corrupt")]
+    CorruptBinary { path: BString },
+    #[error("The patch of the submodule '{path}' doesn't result in a commit")]
+    Submodule { path: BString },
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+}
+
+/// All files of a tree by their path, with their mode and object id.
+pub(super) type Files = BTreeMap<BString, (EntryMode, ObjectId)>;
+
+/// The changes to a single file, as parsed from the `diff --git` section of a patch.
+#[derive(Default)]
+pub(super) struct FilePatch {
+    /// The path of the file before the change, or `None` if it's created.
+    old_path: Option<BString>,
+    /// The path of the file after the change, or `None` if it's deleted.
+    new_path: Option<BString>,
+    is_copy: bool,
+    old_mode: Option<EntryMode>,
+    new_mode: Option<EntryMode>,
+    /// The ids of the file before and after the change as given in the `index` line, which may be abbreviated.
+    ids: Option<(BString, BString)>,
+    content: Content,
+}
+
+#[derive(Default)]
+enum Content {
+    /// Only the path or the mode changes.
+    #[default]
+    Unchanged,
+    Hunks(Vec<Hunk>),
+    Binary(Binary),
+    /// The patch states that the content changed, but not how.
+    BinaryWithoutData,
+}
+
+struct Hunk {
+    /// The 1-based line at which the hunk starts in the old file, or the line after which it inserts if `before` is empty.
+    old_start: usize,
+    /// The lines to remove, including context, with their line terminators.
+    before: Vec<BString>,
+    /// The lines to add in place of `before`, including context.
+    after: Vec<BString>,
+}
+
+enum Binary {
+    /// The entire new content.
+    Literal(Vec<u8>),
+    /// A delta to apply to the old content, in the format used in packs.
+    Delta(Vec<u8>),
+}
+
+/// Parse all `diff --git` sections of `patch`, ignoring everything before the first and after the last of them.
+pub(super) fn parse(patch: &[u8]) -> Result<Vec<FilePatch>, Error> {
+    let mut lines = patch
+        .lines_with_terminator()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line))
+        .peekable();
+    let mut out = Vec::new();
+    while let Some((_, line)) = lines.next() {
+        let Some(names) = line.strip_prefix(b"diff --git ") else {
+            continue;
+        };
+        let mut file = FilePatch::default();
+        let (mut old_name, mut new_name) = match header_names(trim_newline(names)) {
+            Some((old, new)) => (Some(old), Some(new)),
+            None => (None, None),
+        };
+        let (mut is_created, mut is_deleted) = (false, false);
+        while let Some((line_number, line)) = lines.next_if(|(_, line)| !line.starts_with(b"diff --git ")) {
+            let malformed = |message| Error::Malformed { line_number, message };
+            let line = trim_newline(line);
+            if let Some(mode) = line.strip_prefix(b"old mode ") {
+                file.old_mode = Some(parse_mode(mode).ok_or_else(|| malformed("invalid mode"))?);
+            } else if let Some(mode) = line.strip_prefix(b"new mode ") {
+                file.new_mode = Some(parse_mode(mode).ok_or_else(|| malformed("invalid mode"))?);
+            } else if let Some(mode) = line.strip_prefix(b"deleted file mode ") {
+                file.old_mode = Some(parse_mode(mode).ok_or_else(|| malformed("invalid mode"))?);
+                is_deleted = true;
+            } else if let Some(mode) = line.strip_prefix(b"new file mode ") {
+                file.new_mode = Some(parse_mode(mode).ok_or_else(|| malformed("invalid mode"))?);
+                is_created = true;
+            } else if let Some(path) = line
+                .strip_prefix(b"rename from ")
+                .or_else(|| line.strip_prefix(b"copy from "))
+            {
+                file.is_copy = line.starts_with(b"copy");
+                old_name = Some(unquote(path).ok_or_else(|| malformed("invalid path"))?);
+            } else if let Some(path) = line
+                .strip_prefix(b"rename to ")
+                .or_else(|| line.strip_prefix(b"copy to "))
+            {
+                new_name = Some(unquote(path).ok_or_else(|| malformed("invalid path"))?);
+            } else if let Some(index) = line.strip_prefix(b"index ") {
+                let (ids, mode) = index.split_once_str(" ").unwrap_or((index, &[]));
+                let (old_id, new_id) = ids
+                    .split_once_str("..")
+                    .ok_or_else(|| malformed("invalid index line"))?;
+                file.ids = Some((old_id.into(), new_id.into()));
+                if !mode.is_empty() {
+                    let mode = parse_mode(mode).ok_or_else(|| malformed("invalid mode"))?;
+                    file.old_mode.get_or_insert(mode);
+                    file.new_mode.get_or_insert(mode);
+                }
+            } else if let Some(name) = line.strip_prefix(b"--- ") {
+                match patch_name(name).ok_or_else(|| malformed("invalid path"))? {
+                    Some(name) => old_name = Some(name),
+                    None => is_created = true,
+                }
+            } else if let Some(name) = line.strip_prefix(b"+++ ") {
+                match patch_name(name).ok_or_else(|| malformed("invalid path"))? {
+                    Some(name) => new_name = Some(name),
+                    None => is_deleted = true,
+                }
+            } else if line.starts_with(b"@@ ") {
+                let hunk = parse_hunk(line, &mut lines).ok_or_else(|| malformed("invalid hunk"))?;
+                match &mut file.content {
+                    Content::Hunks(hunks) => hunks.push(hunk),
+                    content => *content = Content::Hunks(vec![hunk]),
+                }
+            } else if line == b"GIT binary patch" {
+                let binary = parse_binary(&mut lines).ok_or_else(|| malformed("invalid binary patch"))?;
+                file.content = Content::Binary(binary);
+                // The reverse patch follows, which isn't needed.
+                parse_binary(&mut lines);
+            } else if line.starts_with(b"Binary files ") {
+                file.content = Content::BinaryWithoutData;
+            }
+        }
+        file.old_path = if is_created {
+            None
+        } else {
+            old_name.clone().or(new_name.clone())
+        };
+        file.new_path = if is_deleted { None } else { new_name.or(old_name) };
+        if file.old_path.is_none() && file.new_path.is_none() {
+            return Err(Error::Malformed {
+                line_number: 0,
+                message: "a file patch is missing its paths",
+            });
+        }
+        out.push(file);
+    }
+    Ok(out)
+}
+
+fn trim_newline(line: &[u8]) -> &[u8] {
+    line.strip_suffix(b"\n").unwrap_or(line)
+}
+
+fn parse_mode(mode: &[u8]) -> Option<EntryMode> {
+    EntryMode::from_bytes(mode)
+        .map(|mode| mode.normalized())
+        .filter(|mode| !mode.is_tree())
+}
+
+/// Parse the names of `diff --git a/name b/name` without their prefixes, which is only unambiguous if they are quoted
+/// or if both are the same.
+fn header_names(names: &[u8]) -> Option<(BString, BString)> {
+    let strip_prefix = |name: BString| -> Option<BString> { name.get(2..).map(Into::into) };
+    if names.starts_with(b"\"") {
+        let (old, rest) = unquote_prefix(names)?;
+        let new = match rest.strip_prefix(b" ")? {
+            quoted if quoted.starts_with(b"\"") => unquote(quoted)?,
+            unquoted => unquoted.into(),
+        };
+        return Some((strip_prefix(old)?, strip_prefix(new)?));
+    }
+    if let Some(pos) = names.find(b" \"") {
+        let new = unquote(&names[pos + 1..])?;
+        return Some((strip_prefix(names[..pos].into())?, strip_prefix(new)?));
+    }
+    let len = names.len().checked_sub(5)? / 2;
+    let (old, new) = (&names[2..2 + len], names.get(len + 5..)?);
+    (names.get(len + 2) == Some(&b' ') && old == new).then(|| (old.into(), new.into()))
+}
+
+/// Parse the name after `---` or `+++` without its prefix, or return `Some(None)` if it's `/dev/null`.
+fn patch_name(name: &[u8]) -> Option<Option<BString>> {
+    if name == b"/dev/null" {
+        return Some(None);
+    }
+    // Names may be followed by a tab, and possibly a timestamp.
+    let name = if name.starts_with(b"\"") {
+        unquote_prefix(name)?.0
+    } else {
+        name[..name.find_byte(b'\t').unwrap_or(name.len())].into()
+    };
+    Some(Some(name.get(2..)?.into()))
+}
+
+/// Unquote a path that may be quoted in the style of C, like `git` quotes paths with special characters.
+fn unquote(path: &[u8]) -> Option<BString> {
+    if !path.starts_with(b"\"") {
+        return Some(path.into());
+    }
+    let (out, rest) = unquote_prefix(path)?;
+    rest.is_empty().then_some(out)
+}
+
+/// Unquote the quoted string at the beginning of `input`, and return it along with everything after it.
+fn unquote_prefix(input: &[u8]) -> Option<(BString, &[u8])> {
+    let mut out = BString::default();
+    let mut pos = 1;
+    loop {
+        let b = *input.get(pos)?;
+        pos += 1;
+        match b {
+            b'"' => return Some((out, &input[pos..])),
+            b'\\' => {
+                let escaped = *input.get(pos)?;
+                pos += 1;
+                out.push(match escaped {
+                    b'a' => 0x07,
+                    b'b' => 0x08,
+                    b't' => b'\t',
+                    b'n' => b'\n',
+                    b'v' => 0x0b,
+                    b'f' => 0x0c,
+                    b'r' => b'\r',
+                    b'"' | b'\\' => escaped,
+                    b'0'..=b'3' => {
+                        let digits = input.get(pos..pos + 2)?;
+                        pos += 2;
+                        let octal = |b: u8| b.is_ascii_digit().then(|| b - b'0').filter(|b| *b < 8);
+                        ((escaped - b'0') << 6) | (octal(digits[0])? << 3) | octal(digits[1])?
+                    }
+                    _ => return None,
+                });
+            }
+            _ => out.push(b),
+        }
+    }
+}
+
+/// Parse the hunk starting with the `header` line like `@@ -1,2 +1,3 @@` along with as many of the following `lines`
+/// as its header announces.
+fn parse_hunk<'a>(
+    header: &[u8],
+    lines: &mut std::iter::Peekable<impl Iterator<Item = (usize, &'a [u8])>>,
+) -> Option<Hunk> {
+    let range = |range: &[u8], sign: u8| -> Option<(usize, usize)> {
+        let range = range.strip_prefix(&[sign])?.to_str().ok()?;
+        Some(match range.split_once(',') {
+            Some((start, len)) => (start.parse().ok()?, len.parse().ok()?),
+            None => (range.parse().ok()?, 1),
+        })
+    };
+    let mut ranges = header.strip_prefix(b"@@ ")?.splitn_str(3, " ");
+    let (old_start, mut old_len) = range(ranges.next()?, b'-')?;
+    let (_new_start, mut new_len) = range(ranges.next()?, b'+')?;
+
+    let mut hunk = Hunk {
+        old_start,
+        before: Vec::new(),
+        after: Vec::new(),
+    };
+    while old_len > 0 || new_len > 0 {
+        let (_, line) = lines.next()?;
+        let (kind, content) = match line.split_first() {
+            Some((kind, content)) => (*kind, content),
+            None => return None,
+        };
+        // Editors may remove the space of empty context lines.
+        let (kind, content) = if line == b"\n" { (b' ', line) } else { (kind, content) };
+        match kind {
+            b' ' if old_len > 0 && new_len > 0 => {
+                hunk.before.push(content.into());
+                hunk.after.push(content.into());
+                old_len -= 1;
+                new_len -= 1;
+            }
+            b'-' if old_len > 0 => {
+                hunk.before.push(content.into());
+                old_len -= 1;
+            }
+            b'+' if new_len > 0 => {
+                hunk.after.push(content.into());
+                new_len -= 1;
+            }
+            _ => return None,
+        }
+        if lines.next_if(|(_, line)| line.starts_with(b"\\ ")).is_some() {
+            let images = match kind {
+                b' ' => [Some(&mut hunk.before), Some(&mut hunk.after)],
+                b'-' => [Some(&mut hunk.before), None],
+                _ => [None, Some(&mut hunk.after)],
+            };
+            for image in images.into_iter().flatten() {
+                let last = image.last_mut().expect("just pushed");
+                if last.ends_with(b"\n") {
+                    last.pop();
+                }
+            }
+        }
+    }
+    Some(hunk)
+}
+
+/// Parse a `literal` or `delta` block of a binary patch along with its base85 encoded data, up to and including
+/// the empty line after it.
+fn parse_binary<'a>(lines: &mut std::iter::Peekable<impl Iterator<Item = (usize, &'a [u8])>>) -> Option<Binary> {
+    let (_, header) = lines.next_if(|(_, line)| line.starts_with(b"literal ") || line.starts_with(b"delta "))?;
+    let (kind, size) = trim_newline(header).split_once_str(" ")?;
+    let size: usize = size.to_str().ok()?.parse().ok()?;
+
+    let mut deflated = Vec::new();
+    for (_, line) in lines.by_ref() {
+        let line = trim_newline(line);
+        if line.is_empty() {
+            break;
+        }
+        decode_85_line(line, &mut deflated)?;
+    }
+    // Don't trust the size more than the maximum compression ratio of zlib, which is about 1032:1.
+    if size > deflated.len().saturating_mul(1032) {
+        return None;
+    }
+    let mut data = vec![0; size];
+    let mut inflate = gix_features::zlib::Inflate::default();
+    let (status, consumed_in, consumed_out) = inflate.once(&deflated, &mut data).ok()?;
+    if status != gix_features::zlib::Status::StreamEnd || consumed_in != deflated.len() || consumed_out != size {
+        return None;
+    }
+    Some(if kind == b"literal" {
+        Binary::Literal(data)
+    } else {
+        Binary::Delta(data)
+    })
+}
+
+/// Decode a `line` of a binary patch, which starts with the amount of bytes it encodes, followed by these bytes
+/// in the base85 flavor of `git`.
+fn decode_85_line(line: &[u8], out: &mut Vec<u8>) -> Option<()> {
+    let (len, encoded) = line.split_first()?;
+    let len = match len {
+        b'A'..=b'Z' => len - b'A' + 1,
+        b'a'..=b'z' => len - b'a' + 27,
+        _ => return None,
+    } as usize;
+    if encoded.len() != (len + 3) / 4 * 5 {
+        return None;
+    }
+    let start = out.len();
+    for group in encoded.chunks(5) {
+        let mut acc = 0u64;
+        for b in group {
+            acc = acc * 85 + decode_85_digit(*b)? as u64;
+        }
+        out.extend_from_slice(&u32::try_from(acc).ok()?.to_be_bytes());
+    }
+    out.truncate(start + len);
+    Some(())
+}
+
+fn decode_85_digit(b: u8) -> Option<u8> {
+    const ALPHABET: &[u8; 85] =
+        b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~";
+    ALPHABET.iter().position(|a| *a == b).map(|pos| pos as u8)
+}
+
+/// Apply the changes of `patch` to `files`, writing the new content of changed files to the object database of `repo`.
+pub(super) fn apply(repo: &Repository, files: &mut Files, patch: &FilePatch) -> Result<(), Error> {
+    for path in patch.old_path.iter().chain(&patch.new_path) {
+        validate_path(path.as_ref())?;
+    }
+    let old = match &patch.old_path {
+        Some(path) => Some(
+            *files
+                .get(path)
+                .ok_or_else(|| Error::MissingFile { path: path.clone() })?,
+        ),
+        None => None,
+    };
+    let path = patch
+        .new_path
+        .as_ref()
+        .or(patch.old_path.as_ref())
+        .expect("one path is always set");
+    if let Some(new_path) = &patch.new_path {
+        if patch.old_path.as_ref() != Some(new_path) && collides(files, new_path.as_ref()) {
+            return Err(Error::FileExists { path: new_path.clone() });
+        }
+    }
+
+    let old_content = match old {
+        Some((mode, id)) if mode.is_commit() => format!("Subproject commit {id}\n").into_bytes(),
+        Some((_, id)) => repo.find_object(id)?.detach().data,
+        None => Vec::new(),
+    };
+    let new_content = match &patch.content {
+        Content::Unchanged => None,
+        Content::Hunks(hunks) => Some(apply_hunks(&old_content, hunks, path)?),
+        Content::Binary(binary) => {
+            if let (Some((_, old_id)), Some((expected_old_id, _))) = (old, &patch.ids) {
+                if !old_id
+                    .to_hex()
+                    .to_string()
+                    .starts_with(expected_old_id.to_str_lossy().as_ref())
+                {
+                    return Err(Error::Binary { path: path.clone() });
+                }
+            }
+            Some(apply_binary(&old_content, binary, path)?)
+        }
+        Content::BinaryWithoutData => return Err(Error::MissingBinaryData { path: path.clone() }),
+    };
+
+    let Some(new_path) = &patch.new_path else {
+        if matches!(&new_content, Some(content) if !content.is_empty()) {
+            return Err(Error::DeletionLeavesContent { path: path.clone() });
+        }
+        files.remove(patch.old_path.as_ref().expect("deletions have an old path"));
+        return Ok(());
+    };
+    if !patch.is_copy {
+        if let Some(old_path) = &patch.old_path {
+            files.remove(old_path);
+        }
+    }
+    let mode = patch
+        .new_mode
+        .or(old.map(|(mode, _)| mode))
+        .unwrap_or(EntryKind::Blob.into());
+    let id = match new_content {
+        None => match old {
+            Some((_, id)) => id,
+            None => repo.write_blob(b"")?.detach(),
+        },
+        Some(content) if mode.is_commit() => content
+            .strip_prefix(b"Subproject commit ")
+            .map(ByteSlice::trim_end)
+            .and_then(|id| ObjectId::from_hex(id).ok())
+            .ok_or_else(|| Error::Submodule { path: path.clone() })?,
+        Some(content) => repo.write_blob(content)?.detach(),
+    };
+    files.insert(new_path.clone(), (mode, id));
+    Ok(())
+}
+
+/// Paths must be relative and normalized, and may not point into `.git`.
+fn validate_path(path: &BStr) -> Result<(), Error> {
+    let is_valid = path.split_str("/").all(|component| {
+        !(component.is_empty() || component == b"." || component == b".." || component.eq_ignore_ascii_case(b".git"))
+    });
+    if is_valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidPath { path: path.into() })
+    }
+}
+
+/// Return `true` if `path` exists in `files`, or if it is a directory or a file within one of the files.
+fn collides(files: &Files, path: &BStr) -> bool {
+    let mut dir = BString::from(path);
+    dir.push(b'/');
+    let is_directory = matches!(files.range(dir.clone()..).next(), Some((file, _)) if file.starts_with(&dir));
+    let mut parent = path;
+    is_directory
+        || files.contains_key(path)
+        || std::iter::from_fn(|| {
+            parent = parent[..parent.rfind_byte(b'/')?].as_bstr();
+            Some(parent)
+        })
+        .any(|parent| files.contains_key(parent))
+}
+
+/// Apply all `hunks` to `content`, each at the position closest to where it is expected that its context matches,
+/// but not before the previous hunk.
+fn apply_hunks(content: &[u8], hunks: &[Hunk], path: &BString) -> Result<Vec<u8>, Error> {
+    let lines: Vec<&[u8]> = content.lines_with_terminator().collect();
+    let mut out = Vec::with_capacity(content.len());
+    let mut consumed = 0;
+    let mut offset = 0isize;
+    for (idx, hunk) in hunks.iter().enumerate() {
+        let start = if hunk.before.is_empty() {
+            hunk.old_start
+        } else {
+            hunk.old_start.saturating_sub(1)
+        };
+        let expected = (start as isize + offset).clamp(consumed as isize, lines.len() as isize) as usize;
+        let at = find_hunk(&lines, consumed, expected, &hunk.before).ok_or_else(|| Error::Hunk {
+            path: path.clone(),
+            hunk: idx + 1,
+        })?;
+        offset = at as isize - start as isize;
+        for line in &lines[consumed..at] {
+            out.extend_from_slice(line);
+        }
+        for line in &hunk.after {
+            out.extend_from_slice(line);
+        }
+        consumed = at + hunk.before.len();
+    }
+    for line in &lines[consumed..] {
+        out.extend_from_slice(line);
+    }
+    Ok(out)
+}
+
+/// Return the line index at or after `min` closest to `expected` at which `before` matches `lines`.
+fn find_hunk(lines: &[&[u8]], min: usize, expected: usize, before: &[BString]) -> Option<usize> {
+    let matches_at = |at: usize| {
+        lines.len() >= at + before.len() && lines[at..].iter().zip(before).all(|(line, expected)| *line == expected)
+    };
+    (0..=lines.len()).find_map(|distance| {
+        [
+            expected.checked_sub(distance).filter(|at| *at >= min),
+            Some(expected + distance),
+        ]
+        .into_iter()
+        .flatten()
+        .find(|at| matches_at(*at))
+    })
+}
+
+fn apply_binary(content: &[u8], binary: &Binary, path: &BString) -> Result<Vec<u8>, Error> {
+    match binary {
+        Binary::Literal(data) => Ok(data.clone()),
+        Binary::Delta(delta) => {
+            let (base_size, consumed) = gix_pack::data::delta::decode_header_size(delta);
+            if base_size != content.len() as u64 {
+                return Err(Error::Binary { path: path.clone() });
+            }
+            let delta = &delta[consumed..];
+            let (result_size, consumed) = gix_pack::data::delta::decode_header_size(delta);
+            let mut out = Vec::new();
+            gix_pack::data::delta::apply_to_vec(content, &mut out, &delta[consumed..], result_size)
+                .map_err(|_| Error::CorruptBinary { path: path.clone() })?;
+            Ok(out)
+        }
+    }
+}
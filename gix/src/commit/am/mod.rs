@@ -0,0 +1,173 @@
+//! Apply patches from emails as commits, just like `git am` does.
+//!
+//! This works with mailboxes written by [`format_patch`](super::format_patch) as well as with those of `git format-patch`.
+use std::collections::BTreeMap;
+
+use gix_hash::ObjectId;
+
+use super::mailinfo;
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    Repository,
+};
+
+pub mod apply;
+
+/// Options for use in [`Repository::apply_mailbox()`].
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// Control how the author, the commit message and the patch are extracted from each email.
+    pub mailinfo: mailinfo::Options,
+}
+
+/// The error returned by [`Repository::apply_mailbox()`].
+///
+/// The `patch` of the failing email is its 1-based position in the mailbox, just like `git am` counts them.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read the mailbox")]
+    Read(#[from] std::io::Error),
+    #[error("Could not parse the email of patch {patch}")]
+    Mail { patch: usize, source: mailinfo::Error },
+    #[error("Patch {patch} doesn't contain any changes: {subject}")]
+    EmptyPatch { patch: usize, subject: BString },
+    #[error("Patch {patch} failed to apply: {subject}")]
+    Apply {
+        patch: usize,
+        subject: BString,
+        source: apply::Error,
+    },
+    #[error(transparent)]
+    FindHead(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    PeelHead(#[from] crate::head::peel::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToTree(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    TraverseTree(#[from] gix_traverse::tree::breadthfirst::Error),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    Commit(#[from] crate::commit::Error),
+}
+
+impl Repository {
+    /// Read all emails from `mbox`, configured by `options`, and apply the patch in each of them as a new commit on top of `HEAD`,
+    /// like `git am` does, returning the ids of all created commits in order.
+    ///
+    /// `mbox` may be a mailbox or a single email. The author and the commit message are taken from each email, while
+    /// the committer is the one [configured](Repository::committer()) in this repository. `HEAD` is moved to each
+    /// new commit as it is created, or born with the first one if it is unborn.
+    ///
+    /// The first patch that can't be parsed or applied stops the operation with an error that points at the patch,
+    /// and for patches that don't apply, at the file and hunk that failed. The commits of all previous patches remain,
+    /// just like `git am` keeps them when it stops.
+    ///
+    /// ### Deviation
+    ///
+    /// * Only the object database and `HEAD` are changed, while the index and the worktree are left untouched,
+    ///   similar to [`commit()`](Repository::commit()).
+    /// * Hunks are applied where their context matches exactly, which is the position closest to where they are
+    ///   expected, but there is no fuzzy matching with less context as with `git am -C<n>`.
+    /// * There is no three-way merge to fall back to if a patch doesn't apply, as with `git am --3way`.
+    /// * The reflog message is `commit: <subject>`, while `git` writes `am: <subject>`.
+    pub fn apply_mailbox(&self, mut mbox: impl std::io::Read, options: Options) -> Result<Vec<ObjectId>, Error> {
+        let mut buf = Vec::new();
+        mbox.read_to_end(&mut buf)?;
+
+        let mut parent = self.head()?.try_peel_to_id_in_place()?.map(crate::Id::detach);
+        let mut files = match parent {
+            Some(id) => self
+                .find_object(id)?
+                .peel_to_tree()?
+                .traverse()
+                .breadthfirst
+                .files()?
+                .into_iter()
+                .filter(|entry| !entry.mode.is_tree())
+                .map(|entry| (entry.filepath, (entry.mode, entry.oid)))
+                .collect(),
+            None => apply::Files::default(),
+        };
+        let committer = self
+            .committer()
+            .ok_or(crate::commit::Error::CommitterMissing)?
+            .map_err(crate::commit::Error::from)?
+            .to_owned();
+
+        let mut out = Vec::new();
+        for (idx, mail) in mailinfo::split(&buf).enumerate() {
+            let patch = idx + 1;
+            let mail = mailinfo::parse(mail, options.mailinfo).map_err(|source| Error::Mail { patch, source })?;
+            let apply_error = |source| Error::Apply {
+                patch,
+                subject: mail.subject.clone(),
+                source,
+            };
+            let file_patches = apply::parse(&mail.patch).map_err(apply_error)?;
+            if file_patches.is_empty() {
+                return Err(Error::EmptyPatch {
+                    patch,
+                    subject: mail.subject,
+                });
+            }
+            for file_patch in &file_patches {
+                apply::apply(self, &mut files, file_patch).map_err(apply_error)?;
+            }
+
+            let tree = write_tree(self, &files)?;
+            let id = self
+                .commit_as(
+                    &committer,
+                    &mail.author,
+                    "HEAD",
+                    mail.message.to_str_lossy(),
+                    tree,
+                    parent,
+                )?
+                .detach();
+            out.push(id);
+            parent = Some(id);
+        }
+        Ok(out)
+    }
+}
+
+/// Write the trees needed to hold all `files`, and return the id of the root tree.
+fn write_tree(repo: &Repository, files: &apply::Files) -> Result<ObjectId, crate::object::write::Error> {
+    #[derive(Default)]
+    struct Dir<'a> {
+        entries: Vec<gix_object::tree::Entry>,
+        dirs: BTreeMap<&'a BStr, Dir<'a>>,
+    }
+    fn write(repo: &Repository, dir: Dir<'_>) -> Result<ObjectId, crate::object::write::Error> {
+        let mut tree = gix_object::Tree { entries: dir.entries };
+        for (name, dir) in dir.dirs {
+            tree.entries.push(gix_object::tree::Entry {
+                mode: gix_object::tree::EntryKind::Tree.into(),
+                filename: name.into(),
+                oid: write(repo, dir)?,
+            });
+        }
+        tree.entries.sort();
+        Ok(repo.write_object(&tree)?.detach())
+    }
+
+    let mut root = Dir::default();
+    for (path, (mode, id)) in files {
+        let mut components: Vec<_> = path.split_str("/").map(ByteSlice::as_bstr).collect();
+        let filename = components.pop().expect("split always yields one component");
+        let dir = components
+            .into_iter()
+            .fold(&mut root, |dir, component| dir.dirs.entry(component).or_default());
+        dir.entries.push(gix_object::tree::Entry {
+            mode: *mode,
+            filename: filename.into(),
+            oid: *id,
+        });
+    }
+    write(repo, root)
+}
@@ -0,0 +1,700 @@
+//! Produce the diffstat, the summary and the patches of all changes of a commit, like `git format-patch` does.
+use std::{borrow::Cow, io::Write, ops::Range};
+
+use gix_diff::blob::{platform::prepare_diff::Operation, unified_diff, Algorithm, ResourceKind, Sink, UnifiedDiff};
+use gix_hash::ObjectId;
+use gix_object::tree::{EntryKind, EntryMode};
+
+use super::Error;
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    object::tree::diff::{change::Event, Action},
+    Repository,
+};
+
+/// One side of a change.
+struct Side {
+    path: BString,
+    mode: EntryMode,
+    id: ObjectId,
+}
+
+/// A change to a single file, along with its content diff.
+struct Change {
+    /// The state before the change, or `None` if the file was added.
+    old: Option<Side>,
+    /// The state after the change, or `None` if the file was deleted.
+    new: Option<Side>,
+    /// The similarity in percent and `true` if it's a copy, if the file was renamed or copied.
+    rewrite: Option<(u32, bool)>,
+    content: Content,
+    /// The content of the deletion and the addition if the type of the file changed, which are shown separately.
+    type_change: Option<Box<(Content, Content)>>,
+}
+
+enum Content {
+    Text {
+        insertions: usize,
+        removals: usize,
+        /// The hunks in unified diff format.
+        hunks: BString,
+    },
+    Binary {
+        old: Vec<u8>,
+        new: Vec<u8>,
+    },
+}
+
+/// Append the diffstat, summary and patches of all changes between `old_tree` and `new_tree` to `out`, or do nothing
+/// if there are no changes.
+pub(super) fn write(
+    repo: &Repository,
+    old_tree: &crate::Tree<'_>,
+    new_tree: &crate::Tree<'_>,
+    options: &super::Options,
+    out: &mut BString,
+) -> Result<(), Error> {
+    let mut changes = changes(repo, old_tree, new_tree, options.unified)?;
+    if changes.is_empty() {
+        return Ok(());
+    }
+    changes.sort_by(|a, b| a.path().cmp(b.path()));
+
+    out.push_str("---\n");
+    write_stat(&changes, options.stat_width, out);
+    write_summary(&changes, out);
+    out.push(b'\n');
+    let shortener = repo.id_shortener()?;
+    for change in &changes {
+        match (&change.old, &change.new, &change.type_change) {
+            (Some(old), Some(new), Some(contents)) => {
+                let (old_content, new_content) = &**contents;
+                write_patch(repo, &shortener, Some(old), None, None, old_content, out)?;
+                write_patch(repo, &shortener, None, Some(new), None, new_content, out)?;
+            }
+            (old, new, _) => write_patch(
+                repo,
+                &shortener,
+                old.as_ref(),
+                new.as_ref(),
+                change.rewrite,
+                &change.content,
+                out,
+            )?,
+        }
+    }
+    Ok(())
+}
+
+fn changes(
+    repo: &Repository,
+    old_tree: &crate::Tree<'_>,
+    new_tree: &crate::Tree<'_>,
+    unified: unified_diff::Options,
+) -> Result<Vec<Change>, Error> {
+    let mut resource_cache = repo.diff_resource_cache(gix_diff::blob::pipeline::Mode::ToGit, Default::default())?;
+    resource_cache.options.skip_internal_diff_if_external_is_configured = false;
+    let mut changes = Vec::new();
+    let mut err = None;
+    old_tree
+        .changes()?
+        .track_path()
+        .for_each_to_obtain_tree(new_tree, |change| {
+            let side = |path: &BStr, mode: EntryMode, id: ObjectId| Side {
+                path: path.to_owned(),
+                mode,
+                id,
+            };
+            let (old, new, rewrite) = match change.event {
+                Event::Addition { entry_mode, id } => (None, Some(side(change.location, entry_mode, id.detach())), None),
+                Event::Deletion { entry_mode, id } => (Some(side(change.location, entry_mode, id.detach())), None, None),
+                Event::Modification {
+                    previous_entry_mode,
+                    previous_id,
+                    entry_mode,
+                    id,
+                } => (
+                    Some(side(change.location, previous_entry_mode, previous_id.detach())),
+                    Some(side(change.location, entry_mode, id.detach())),
+                    None,
+                ),
+                Event::Rewrite {
+                    source_location,
+                    source_entry_mode,
+                    source_id,
+                    diff,
+                    entry_mode,
+                    id,
+                    copy,
+                } => (
+                    Some(side(source_location, source_entry_mode, source_id.detach())),
+                    Some(side(change.location, entry_mode, id.detach())),
+                    Some((diff.map_or(100, |diff| (diff.similarity * 100.0) as u32), copy)),
+                ),
+            };
+            let old = old.filter(|side| !side.mode.is_tree());
+            let new = new.filter(|side| !side.mode.is_tree());
+            if old.is_none() && new.is_none() {
+                return Ok::<_, Error>(Action::Continue);
+            }
+            // Like `git`, only consider changes between files, links and submodules as type change, not the executable bit.
+            let is_type_change = matches!((&old, &new), (Some(old), Some(new)) if file_type(old.mode) != file_type(new.mode))
+                && rewrite.is_none();
+            let mut content = |old: Option<&Side>, new: Option<&Side>| {
+                content(repo, &mut resource_cache, old, new, unified)
+            };
+            let res = content(old.as_ref(), new.as_ref()).and_then(|content_of_pair| {
+                let type_change = if is_type_change {
+                    Some(Box::new((content(old.as_ref(), None)?, content(None, new.as_ref())?)))
+                } else {
+                    None
+                };
+                Ok((content_of_pair, type_change))
+            });
+            match res {
+                Ok((content, type_change)) => changes.push(Change {
+                    old,
+                    new,
+                    rewrite,
+                    content,
+                    type_change,
+                }),
+                Err(e) => {
+                    err = Some(e);
+                    return Ok(Action::Cancel);
+                }
+            }
+            Ok(Action::Continue)
+        })?;
+    match err {
+        Some(err) => Err(err),
+        None => Ok(changes),
+    }
+}
+
+fn file_type(mode: EntryMode) -> EntryKind {
+    match mode.kind() {
+        EntryKind::BlobExecutable => EntryKind::Blob,
+        kind => kind,
+    }
+}
+
+fn content(
+    repo: &Repository,
+    resource_cache: &mut gix_diff::blob::Platform,
+    old: Option<&Side>,
+    new: Option<&Side>,
+    unified: unified_diff::Options,
+) -> Result<Content, Error> {
+    if [old, new].iter().flatten().any(|side| side.mode.is_commit()) {
+        let [old, new] = [old, new].map(|side| -> Result<_, Error> {
+            Ok(match side {
+                Some(side) if side.mode.is_commit() => format!("Subproject commit {}\n", side.id).into_bytes(),
+                Some(side) => repo.find_object(side.id)?.detach().data,
+                None => Vec::new(),
+            })
+        });
+        return Ok(text_content(&old?, &new?, Algorithm::Myers, unified));
+    }
+    let kind_of =
+        |side: Option<&Side>, other: Option<&Side>| side.or(other).expect("at least one side is set").mode.kind();
+    for (side, other, kind) in [
+        (old, new, ResourceKind::OldOrSource),
+        (new, old, ResourceKind::NewOrDestination),
+    ] {
+        resource_cache.set_resource(
+            side.map_or_else(|| repo.object_hash().null(), |side| side.id),
+            kind_of(side, other),
+            side.or(other).expect("at least one side is set").path.as_ref(),
+            kind,
+            &repo.objects,
+        )?;
+    }
+    let outcome = resource_cache.prepare_diff()?;
+    Ok(match outcome.operation {
+        Operation::InternalDiff { algorithm } => text_content(
+            outcome.old.data.as_slice().unwrap_or_default(),
+            outcome.new.data.as_slice().unwrap_or_default(),
+            algorithm,
+            unified,
+        ),
+        Operation::ExternalCommand { .. } => unreachable!("we disabled external commands"),
+        Operation::SourceOrDestinationIsBinary => {
+            let [old, new] = [old, new].map(|side| -> Result<_, Error> {
+                Ok(match side {
+                    Some(side) => repo.find_object(side.id)?.detach().data,
+                    None => Vec::new(),
+                })
+            });
+            Content::Binary { old: old?, new: new? }
+        }
+    })
+}
+
+fn text_content(old: &[u8], new: &[u8], algorithm: Algorithm, unified: unified_diff::Options) -> Content {
+    let input = gix_diff::blob::intern::InternedInput::new(
+        gix_diff::blob::whitespace::lines(old, unified.whitespace),
+        gix_diff::blob::whitespace::lines(new, unified.whitespace),
+    );
+    let (hunks, insertions, removals) = gix_diff::blob::diff(
+        algorithm,
+        &input,
+        Counter {
+            inner: UnifiedDiff::from_lines(
+                gix_diff::blob::sources::byte_lines_with_terminator(old).collect(),
+                gix_diff::blob::sources::byte_lines_with_terminator(new).collect(),
+                BString::default(),
+                unified,
+            ),
+            insertions: 0,
+            removals: 0,
+        },
+    );
+    Content::Text {
+        insertions,
+        removals,
+        hunks,
+    }
+}
+
+/// A sink which counts inserted and removed lines while passing all changes on to `inner`.
+struct Counter<S> {
+    inner: S,
+    insertions: usize,
+    removals: usize,
+}
+
+impl<S: Sink> Sink for Counter<S> {
+    type Out = (S::Out, usize, usize);
+
+    fn process_change(&mut self, before: Range<u32>, after: Range<u32>) {
+        self.removals += before.len();
+        self.insertions += after.len();
+        self.inner.process_change(before, after);
+    }
+
+    fn finish(self) -> Self::Out {
+        (self.inner.finish(), self.insertions, self.removals)
+    }
+}
+
+impl Change {
+    /// The path by which changes are sorted, which is the destination of rewrites.
+    fn path(&self) -> &BStr {
+        self.new
+            .as_ref()
+            .or(self.old.as_ref())
+            .expect("one side is set")
+            .path
+            .as_ref()
+    }
+
+    /// The name as displayed in the diffstat, which shows both paths of renames.
+    fn stat_name(&self) -> BString {
+        match (&self.old, &self.new, self.rewrite) {
+            (Some(old), Some(new), Some(_)) => rewrite_name(old.path.as_ref(), new.path.as_ref()),
+            _ => quote_path(self.path()).into_owned(),
+        }
+    }
+
+    /// Changes without any changed lines are only displayed if they are interesting, like renames or mode changes.
+    fn is_interesting(&self) -> bool {
+        match (&self.old, &self.new) {
+            (Some(old), Some(new)) => old.id != new.id || old.mode != new.mode || old.path != new.path,
+            _ => true,
+        }
+    }
+}
+
+fn write_stat(changes: &[Change], max_width: usize, out: &mut BString) {
+    let shown: Vec<_> = changes
+        .iter()
+        .filter(|c| {
+            c.is_interesting()
+                || !matches!(
+                    c.content,
+                    Content::Text {
+                        insertions: 0,
+                        removals: 0,
+                        ..
+                    }
+                )
+        })
+        .map(|c| (c, c.stat_name()))
+        .collect();
+    let (mut max_len, mut max_change, mut bin_width, mut number_width) = (0, 0, 0, 0);
+    for (change, name) in &shown {
+        max_len = max_len.max(display_width(name.as_ref()));
+        match &change.content {
+            Content::Binary { old, new } => {
+                bin_width = bin_width.max(14 + decimal_width(old.len()) + decimal_width(new.len()));
+                number_width = 3;
+            }
+            Content::Text {
+                insertions, removals, ..
+            } => max_change = max_change.max(insertions + removals),
+        }
+    }
+
+    let mut width = max_width;
+    number_width = number_width.max(decimal_width(max_change));
+    width = width.max(16 + 6 + number_width);
+    let mut graph_width = if max_change + 4 > bin_width {
+        max_change
+    } else {
+        bin_width - 4
+    };
+    let mut name_width = max_len;
+    if name_width + number_width + 6 + graph_width > width {
+        if graph_width + number_width + 6 > width * 3 / 8 {
+            graph_width = (width * 3 / 8).saturating_sub(number_width + 6).max(6);
+        }
+        if name_width > width.saturating_sub(number_width + 6 + graph_width) {
+            name_width = width.saturating_sub(number_width + 6 + graph_width);
+        } else {
+            graph_width = width - number_width - 6 - name_width;
+        }
+    }
+
+    for (change, name) in &shown {
+        let mut name: &BStr = name.as_ref();
+        let mut prefix = "";
+        let mut len = name_width;
+        if name_width < display_width(name) {
+            prefix = "...";
+            len = len.saturating_sub(3);
+            let skip = display_width(name).saturating_sub(len);
+            let start = name.char_indices().nth(skip).map_or(name.len(), |(start, _, _)| start);
+            name = name[start..].as_bstr();
+            if let Some(slash) = name.find_byte(b'/') {
+                name = name[slash..].as_bstr();
+            }
+        }
+        let padding = len.saturating_sub(display_width(name));
+        out.push_str(format!(" {prefix}{name}{:padding$} | ", ""));
+        match &change.content {
+            Content::Binary { old, new } => {
+                out.push_str(format!("{:>number_width$}", "Bin"));
+                if old.len() + new.len() > 0 {
+                    out.push_str(format!(" {} -> {} bytes", old.len(), new.len()));
+                }
+            }
+            Content::Text {
+                insertions, removals, ..
+            } => {
+                let (mut add, mut del) = (*insertions, *removals);
+                if graph_width <= max_change {
+                    let mut total = scale_linear(add + del, graph_width, max_change);
+                    if total < 2 && add > 0 && del > 0 {
+                        total = 2;
+                    }
+                    if add < del {
+                        add = scale_linear(add, graph_width, max_change);
+                        del = total - add;
+                    } else {
+                        del = scale_linear(del, graph_width, max_change);
+                        add = total - del;
+                    }
+                }
+                let changed = insertions + removals;
+                out.push_str(format!(
+                    "{changed:>number_width$}{}{}{}",
+                    if changed > 0 { " " } else { "" },
+                    "+".repeat(add),
+                    "-".repeat(del)
+                ));
+            }
+        }
+        out.push(b'\n');
+    }
+
+    let (insertions, removals) = shown.iter().fold((0, 0), |(i, r), (change, _)| match change.content {
+        Content::Text {
+            insertions, removals, ..
+        } => (i + insertions, r + removals),
+        Content::Binary { .. } => (i, r),
+    });
+    let files = shown.len();
+    out.push_str(format!(" {files} file{} changed", if files == 1 { "" } else { "s" }));
+    if insertions > 0 || removals == 0 {
+        out.push_str(format!(
+            ", {insertions} insertion{}(+)",
+            if insertions == 1 { "" } else { "s" }
+        ));
+    }
+    if removals > 0 || insertions == 0 {
+        out.push_str(format!(
+            ", {removals} deletion{}(-)",
+            if removals == 1 { "" } else { "s" }
+        ));
+    }
+    out.push(b'\n');
+}
+
+/// Scale `it` linearly so that `max_change` fits into `width`, but never return less than 1 for changes.
+fn scale_linear(it: usize, width: usize, max_change: usize) -> usize {
+    if it == 0 {
+        return 0;
+    }
+    1 + it * (width - 1) / max_change
+}
+
+fn decimal_width(n: usize) -> usize {
+    n.to_string().len()
+}
+
+fn display_width(name: &BStr) -> usize {
+    name.chars().count()
+}
+
+fn write_summary(changes: &[Change], out: &mut BString) {
+    for change in changes {
+        match (&change.old, &change.new, change.rewrite) {
+            (None, Some(new), _) => {
+                out.push_str(format!(
+                    " create mode {:06o} {}\n",
+                    new.mode.0,
+                    quote_path(new.path.as_ref())
+                ));
+            }
+            (Some(old), None, _) => {
+                out.push_str(format!(
+                    " delete mode {:06o} {}\n",
+                    old.mode.0,
+                    quote_path(old.path.as_ref())
+                ));
+            }
+            (Some(old), Some(new), rewrite) => {
+                if let Some((similarity, copy)) = rewrite {
+                    out.push_str(format!(
+                        " {} {} ({similarity}%)\n",
+                        if copy { "copy" } else { "rename" },
+                        rewrite_name(old.path.as_ref(), new.path.as_ref())
+                    ));
+                }
+                if old.mode != new.mode {
+                    out.push_str(format!(" mode change {:06o} => {:06o}", old.mode.0, new.mode.0));
+                    if rewrite.is_none() {
+                        out.push_str(format!(" {}", quote_path(new.path.as_ref())));
+                    }
+                    out.push(b'\n');
+                }
+            }
+            (None, None, _) => unreachable!("one side is always set"),
+        }
+    }
+}
+
+fn write_patch(
+    repo: &Repository,
+    shortener: &crate::id::Shortener<'_>,
+    old: Option<&Side>,
+    new: Option<&Side>,
+    rewrite: Option<(u32, bool)>,
+    content: &Content,
+    out: &mut BString,
+) -> Result<(), Error> {
+    let a = quote_prefixed("a/", old.or(new).expect("one side is set").path.as_ref());
+    let b = quote_prefixed("b/", new.or(old).expect("one side is set").path.as_ref());
+    out.push_str(format!("diff --git {a} {b}\n"));
+    match (old, new) {
+        (None, Some(new)) => out.push_str(format!("new file mode {:06o}\n", new.mode.0)),
+        (Some(old), None) => out.push_str(format!("deleted file mode {:06o}\n", old.mode.0)),
+        (Some(old), Some(new)) => {
+            if old.mode != new.mode {
+                out.push_str(format!("old mode {:06o}\nnew mode {:06o}\n", old.mode.0, new.mode.0));
+            }
+            if let Some((similarity, copy)) = rewrite {
+                let kind = if copy { "copy" } else { "rename" };
+                out.push_str(format!(
+                    "similarity index {similarity}%\n{kind} from {}\n{kind} to {}\n",
+                    quote_path(old.path.as_ref()),
+                    quote_path(new.path.as_ref())
+                ));
+            }
+        }
+        (None, None) => unreachable!("one side is always set"),
+    }
+
+    let old_id = old.map(|side| side.id);
+    let new_id = new.map(|side| side.id);
+    if old_id == new_id {
+        return Ok(());
+    }
+    // Binary patches need full ids to be applicable.
+    let is_binary = matches!(content, Content::Binary { .. });
+    let hex = |id: Option<ObjectId>| -> Result<String, Error> {
+        let null = repo.object_hash().null();
+        Ok(match id {
+            Some(id) if is_binary => id.to_string(),
+            Some(id) => shortener.shorten(id)?.to_string(),
+            None if is_binary => null.to_string(),
+            None => null.to_hex_with_len(shortener.hex_len()).to_string(),
+        })
+    };
+    out.push_str(format!("index {}..{}", hex(old_id)?, hex(new_id)?));
+    match (old, new) {
+        (Some(old), Some(new)) if old.mode == new.mode => out.push_str(format!(" {:06o}\n", old.mode.0)),
+        _ => out.push(b'\n'),
+    }
+
+    match content {
+        Content::Binary {
+            old: old_data,
+            new: new_data,
+        } => {
+            out.push_str("GIT binary patch\n");
+            write_binary_literal(new_data, out);
+            write_binary_literal(old_data, out);
+        }
+        Content::Text { hunks, .. } => {
+            if !hunks.is_empty() {
+                let old_label = if old.is_some() { a } else { "/dev/null".into() };
+                let new_label = if new.is_some() { b } else { "/dev/null".into() };
+                for (marker, label) in [("---", old_label), ("+++", new_label)] {
+                    let tab = if label.contains(&b' ') { "\t" } else { "" };
+                    out.push_str(format!("{marker} {label}{tab}\n"));
+                }
+                out.extend_from_slice(hunks);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write `data` as deflated literal in base85, followed by an empty line, just like `git diff --binary` does.
+fn write_binary_literal(data: &[u8], out: &mut BString) {
+    let mut deflate = gix_features::zlib::stream::deflate::Write::new(Vec::new());
+    deflate.write_all(data).expect("writing to memory never fails");
+    deflate.flush().expect("writing to memory never fails");
+    let deflated = deflate.into_inner();
+
+    out.push_str(format!("literal {}\n", data.len()));
+    for chunk in deflated.chunks(52) {
+        let len = chunk.len() as u8;
+        out.push(if len <= 26 { b'A' + len - 1 } else { b'a' + len - 27 });
+        encode_85(chunk, out);
+        out.push(b'\n');
+    }
+    out.push(b'\n');
+}
+
+/// Encode `data` in the base85 flavor of `git`, with a trailing group of less than 4 bytes padded with zeroes.
+fn encode_85(data: &[u8], out: &mut BString) {
+    const ALPHABET: &[u8; 85] =
+        b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~";
+    for group in data.chunks(4) {
+        let mut acc = group
+            .iter()
+            .chain(std::iter::repeat(&0))
+            .take(4)
+            .fold(0u32, |acc, b| (acc << 8) | u32::from(*b));
+        let mut encoded = [0; 5];
+        for byte in encoded.iter_mut().rev() {
+            *byte = ALPHABET[(acc % 85) as usize];
+            acc /= 85;
+        }
+        out.extend_from_slice(&encoded);
+    }
+}
+
+/// The name of a rename as shown by `git`, which factors out common leading and trailing directories like `a/{b => c}/d`.
+fn rewrite_name(old: &BStr, new: &BStr) -> BString {
+    let (quoted_old, quoted_new) = (quote_path(old), quote_path(new));
+    if matches!(quoted_old, Cow::Owned(_)) || matches!(quoted_new, Cow::Owned(_)) {
+        return format!("{quoted_old} => {quoted_new}").into();
+    }
+
+    let mut prefix_len = 0;
+    for (pos, (a, b)) in old.iter().zip(new.iter()).enumerate() {
+        if a != b {
+            break;
+        }
+        if *a == b'/' {
+            prefix_len = pos + 1;
+        }
+    }
+    // The common suffix may reach into the common prefix by its trailing slash, so both can share it.
+    let min_start = prefix_len as isize - isize::from(prefix_len > 0);
+    // Like in `git`, the comparison starts at the terminating NUL of both paths.
+    let at = |path: &BStr, pos: isize| path.get(pos as usize).copied().unwrap_or(0);
+    let mut suffix_len = 0;
+    let (mut a, mut b) = (old.len() as isize, new.len() as isize);
+    while a >= min_start && b >= min_start && at(old, a) == at(new, b) {
+        if at(old, a) == b'/' {
+            suffix_len = old.len() - a as usize;
+        }
+        a -= 1;
+        b -= 1;
+    }
+
+    let old_mid = old.len().saturating_sub(prefix_len + suffix_len);
+    let new_mid = new.len().saturating_sub(prefix_len + suffix_len);
+    let mut out = BString::default();
+    if prefix_len + suffix_len > 0 {
+        out.push_str(&old[..prefix_len]);
+        out.push(b'{');
+    }
+    out.push_str(&old[prefix_len..][..old_mid]);
+    out.push_str(" => ");
+    out.push_str(&new[prefix_len..][..new_mid]);
+    if prefix_len + suffix_len > 0 {
+        out.push(b'}');
+        out.push_str(&old[old.len() - suffix_len..]);
+    }
+    out
+}
+
+/// Return `path` in double quotes with special characters escaped if needed, similar to `quote_c_style()` of `git`
+/// with `core.quotePath` enabled.
+fn quote_path(path: &BStr) -> Cow<'_, BStr> {
+    if !path.iter().copied().any(needs_quoting) {
+        return Cow::Borrowed(path);
+    }
+    let mut out = BString::from("\"");
+    push_quoted(path, &mut out);
+    out.push(b'"');
+    Cow::Owned(out)
+}
+
+/// Like [`quote_path()`], but put `prefix` before `path`, and inside the quotes if there are any.
+fn quote_prefixed(prefix: &str, path: &BStr) -> BString {
+    if !path.iter().copied().any(needs_quoting) {
+        let mut out = BString::from(prefix);
+        out.push_str(path);
+        return out;
+    }
+    let mut out = BString::from(format!("\"{prefix}"));
+    push_quoted(path, &mut out);
+    out.push(b'"');
+    out
+}
+
+fn needs_quoting(b: u8) -> bool {
+    !(0x20..0x7f).contains(&b) || b == b'"' || b == b'\\'
+}
+
+fn push_quoted(path: &BStr, out: &mut BString) {
+    for &b in path.iter() {
+        let escaped = match b {
+            0x07 => b'a',
+            0x08 => b'b',
+            b'\t' => b't',
+            b'\n' => b'n',
+            0x0b => b'v',
+            0x0c => b'f',
+            b'\r' => b'r',
+            b'"' | b'\\' => b,
+            _ if needs_quoting(b) => {
+                out.push_str(format!("\\{b:03o}"));
+                continue;
+            }
+            _ => {
+                out.push(b);
+                continue;
+            }
+        };
+        out.push(b'\\');
+        out.push(escaped);
+    }
+}
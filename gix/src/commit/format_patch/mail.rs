@@ -0,0 +1,136 @@
+//! Utilities to produce email headers exactly like `git` does, which is mostly a port of the respective functions in `pretty.c`.
+use crate::bstr::{BString, ByteSlice, ByteVec};
+
+/// The maximum length of header lines as recommended by RFC 2047.
+pub(super) const MAX_HEADER_LENGTH: usize = 78;
+
+/// The context in which RFC 2047 encoding is used, as it affects which characters have to be encoded.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(super) enum Rfc2047 {
+    Subject,
+    Address,
+}
+
+/// Like `isspace()` of `git`, which doesn't consider vertical tabs and form feeds to be whitespace.
+pub(super) fn is_space(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\n' | b'\r')
+}
+
+/// Return `true` if `text` must be encoded according to RFC 2047 to be used in a header.
+pub(super) fn needs_rfc2047_encoding(text: &[u8]) -> bool {
+    text.iter().any(|b| !b.is_ascii() || *b == b'\n') || text.find(b"=?").is_some()
+}
+
+fn is_rfc2047_special(b: u8, kind: Rfc2047) -> bool {
+    if !b.is_ascii() || b.is_ascii_control() || is_space(b) || matches!(b, b'=' | b'?' | b'_') {
+        return true;
+    }
+    match kind {
+        Rfc2047::Subject => false,
+        Rfc2047::Address => !(b.is_ascii_alphanumeric() || matches!(b, b'!' | b'*' | b'+' | b'-' | b'/')),
+    }
+}
+
+/// Append `text` to `out` as `Q`-encoded words, each short enough to keep lines within the limits of RFC 2047.
+pub(super) fn add_rfc2047(out: &mut BString, text: &[u8], kind: Rfc2047) {
+    const MAX_ENCODED_LENGTH: usize = 76;
+    const START: &str = "=?UTF-8?q?";
+    let mut line_len = last_line_len(out) + START.len();
+    out.push_str(START);
+    let mut text = text;
+    while let Some(&first) = text.first() {
+        let is_special = is_rfc2047_special(first, kind);
+        let char_len = match crate::bstr::decode_utf8(text) {
+            (Some(_), len) => len,
+            (None, _) => 1,
+        };
+        let encoded_len = if is_special { 3 * char_len } else { 1 };
+        if line_len + encoded_len + 2 > MAX_ENCODED_LENGTH {
+            out.push_str("?=\n ");
+            out.push_str(START);
+            line_len = START.len() + 1;
+        }
+        if is_special {
+            for b in &text[..char_len] {
+                out.push_str(format!("={b:02X}"));
+            }
+        } else {
+            out.push(first);
+        }
+        line_len += encoded_len;
+        text = &text[char_len..];
+    }
+    out.push_str("?=");
+}
+
+/// Return `true` if `name` contains characters that are special in RFC 822 addresses.
+pub(super) fn needs_rfc822_quoting(name: &[u8]) -> bool {
+    name.iter().any(|b| b"()<>[]:;@,.\"\\".contains(b))
+}
+
+/// Return `name` in double quotes, with quotes and backslashes escaped.
+pub(super) fn rfc822_quoted(name: &[u8]) -> BString {
+    let mut out = BString::from("\"");
+    for &b in name {
+        if matches!(b, b'"' | b'\\') {
+            out.push(b'\\');
+        }
+        out.push(b);
+    }
+    out.push(b'"');
+    out
+}
+
+/// Append `text` to `out`, breaking lines at whitespace so they don't exceed `width` if possible.
+///
+/// The first line is indented by `indent1`, or is assumed to be preceded by `-indent1` characters already if negative.
+/// All following lines are indented by `indent2`. It's a port of `strbuf_add_wrapped_text()`, which counts
+/// bytes as we only use it for text that doesn't need RFC 2047 encoding.
+pub(super) fn add_wrapped(out: &mut BString, text: &[u8], indent1: isize, indent2: usize, width: usize) {
+    let width = width as isize;
+    let mut bol = 0;
+    let mut pos = 0;
+    let mut indent = indent1.max(0) as usize;
+    let mut w = indent1.abs();
+    let mut space = (indent1 < 0).then_some(0);
+    loop {
+        let c = text.get(pos).copied();
+        if matches!(c, Some(c) if !is_space(c)) {
+            w += 1;
+            pos += 1;
+            continue;
+        }
+        if w <= width || space.is_none() {
+            if c.is_none() && pos == bol {
+                return;
+            }
+            let start = match space {
+                Some(space) => space,
+                None => {
+                    out.extend(std::iter::repeat(b' ').take(indent));
+                    bol
+                }
+            };
+            out.extend_from_slice(&text[start..pos]);
+            let Some(c) = c else { return };
+            space = Some(pos);
+            if c == b'\t' {
+                w |= 0x07;
+            }
+            w += 1;
+            pos += 1;
+        } else {
+            out.push(b'\n');
+            let space = space.take().expect("checked");
+            pos = space + usize::from(matches!(text.get(space), Some(&c) if is_space(c)));
+            bol = pos;
+            indent = indent2;
+            w = indent2 as isize;
+        }
+    }
+}
+
+/// Return the length of the last line in `out`, which is empty if `out` ends with a newline.
+pub(super) fn last_line_len(out: &[u8]) -> usize {
+    out.len() - out.rfind_byte(b'\n').map_or(0, |pos| pos + 1)
+}
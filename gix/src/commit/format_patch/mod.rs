@@ -0,0 +1,223 @@
+//! Render commits as patches in the mbox format, just like `git format-patch` does, for sending them by email
+//! or applying them with `git am`.
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    Commit,
+};
+
+mod diff;
+mod mail;
+
+/// Options for use in [`Commit::format_patch()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The text in brackets before the subject, like `PATCH` or `RFC PATCH`, the equivalent of `format.subjectPrefix`.
+    ///
+    /// If empty and there is no `number`, there are no brackets at all.
+    pub subject_prefix: BString,
+    /// The number of this patch and the total amount of patches in its series, displayed like `[PATCH 1/2]`,
+    /// or `None` to display `[PATCH]` as `git` does if there is only a single patch.
+    pub number: Option<(usize, usize)>,
+    /// The text after the `-- ` line at the end of each patch, or `None` to omit it, the equivalent of `format.signature`.
+    ///
+    /// `git` uses its version by default, which we do as well.
+    pub signature: Option<BString>,
+    /// The maximum width of the diffstat, which is 72 for patches by default.
+    pub stat_width: usize,
+    /// Control the context around each change and the hunk headers.
+    pub unified: gix_diff::blob::unified_diff::Options,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            subject_prefix: "PATCH".into(),
+            number: None,
+            signature: Some(concat!("gix ", env!("CARGO_PKG_VERSION")).into()),
+            stat_width: 72,
+            unified: Default::default(),
+        }
+    }
+}
+
+/// The error returned by [`Commit::format_patch()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    ParentKind(#[from] crate::object::try_into::Error),
+    #[error(transparent)]
+    Tree(#[from] crate::object::commit::Error),
+    #[error(transparent)]
+    Rewrites(#[from] crate::diff::new_rewrites::Error),
+    #[error(transparent)]
+    ResourceCache(#[from] crate::repository::diff::resource_cache::Error),
+    #[error(transparent)]
+    TreeDiff(#[from] crate::object::tree::diff::for_each::Error),
+    #[error(transparent)]
+    SetResource(#[from] gix_diff::blob::platform::set_resource::Error),
+    #[error(transparent)]
+    PrepareDiff(#[from] gix_diff::blob::platform::prepare_diff::Error),
+    #[error(transparent)]
+    ShortenId(#[from] crate::id::shorten::Error),
+}
+
+/// Return the name `git format-patch` would give the file containing the patch with `number` for a commit with `message`,
+/// like `0001-Fix-the-frobnicator.patch`.
+///
+/// Like `git`, only the first non-empty line of `message` is used, which is sanitized by replacing all runs of characters
+/// other than ASCII letters, digits, `.` and `_` with a single `-`. The name is truncated to at most 64 characters including
+/// the `.patch` suffix.
+pub fn file_name(number: usize, message: &BStr) -> String {
+    const MAX_LEN: usize = 64;
+    const SUFFIX: &str = ".patch";
+    let mut name = format!("{number:04}-");
+    let start = name.len();
+    let mut needs_dash = false;
+    let subject = message.lines().find(|line| !line.trim().is_empty()).unwrap_or_default();
+    let mut bytes = subject.iter().copied().peekable();
+    while let Some(b) = bytes.next() {
+        if b.is_ascii_alphanumeric() || b == b'.' || b == b'_' {
+            if needs_dash && name.len() > start {
+                name.push('-');
+            }
+            needs_dash = false;
+            name.push(b as char);
+            if b == b'.' {
+                while bytes.next_if_eq(&b'.').is_some() {}
+            }
+        } else {
+            needs_dash = true;
+        }
+    }
+    while name.len() > start && name.ends_with(['.', '-']) {
+        name.pop();
+    }
+    name.truncate(MAX_LEN - SUFFIX.len() - 1);
+    name.push_str(SUFFIX);
+    name
+}
+
+impl Commit<'_> {
+    /// Render this commit as a patch in the mbox format like `git format-patch --stdout` would, configured by `options`.
+    ///
+    /// It starts with a `From <id> Mon Sep 17 00:00:00 2001` line followed by `From`, `Date` and `Subject` headers,
+    /// where the author is encoded according to RFC 2047 if needed. Then the commit message follows, and after a `---`
+    /// separator the diffstat, a summary of created, deleted and renamed files, and patches with extended headers
+    /// in the unified format, with binary files as `GIT binary patch`. The patch is applicable with `git am`.
+    ///
+    /// The diff is performed against the first parent, or against the empty tree for root commits, and uses rename tracking
+    /// and the diff algorithm as configured.
+    ///
+    /// ### Deviation
+    ///
+    /// * Merge commits are diffed against their first parent, while `git format-patch` skips them entirely.
+    /// * Commits without changes are rendered without diff, while `git format-patch` skips them unless `--always` is given.
+    /// * Commit messages are assumed to be encoded in UTF-8, and aren't re-encoded if the commit indicates another encoding.
+    /// * Binary patches are always written as literals, while `git` uses deltas if they are smaller.
+    pub fn format_patch(&self, options: &Options) -> Result<BString, Error> {
+        let commit = self.decode()?;
+        let mut out = BString::from(format!("From {} Mon Sep 17 00:00:00 2001\n", self.id));
+        write_author(commit.author, &mut out);
+
+        let (title, body) = split_message(commit.message);
+        out.push_str("Subject: ");
+        match options.number {
+            Some((number, total)) => {
+                let width = total.to_string().len();
+                let space = if options.subject_prefix.is_empty() { "" } else { " " };
+                out.push_str(format!(
+                    "[{prefix}{space}{number:0width$}/{total}] ",
+                    prefix = options.subject_prefix
+                ));
+            }
+            None if !options.subject_prefix.is_empty() => {
+                out.push_str(format!("[{}] ", options.subject_prefix));
+            }
+            None => {}
+        }
+        if mail::needs_rfc2047_encoding(&title) {
+            mail::add_rfc2047(&mut out, &title, mail::Rfc2047::Subject);
+        } else {
+            let indent = mail::last_line_len(&out) as isize;
+            mail::add_wrapped(&mut out, &title, -indent, 1, mail::MAX_HEADER_LENGTH);
+        }
+        out.push(b'\n');
+        if !commit.message.is_ascii() {
+            out.push_str(
+                "MIME-Version: 1.0\nContent-Type: text/plain; charset=UTF-8\nContent-Transfer-Encoding: 8bit\n",
+            );
+        }
+        out.push(b'\n');
+        if !body.is_empty() {
+            out.push_str(body.trim_end());
+            out.push(b'\n');
+        }
+
+        let tree = self.tree()?;
+        let parent_tree = match self.parent_ids().next() {
+            Some(parent) => parent.object()?.try_into_commit()?.tree()?,
+            None => self.repo.empty_tree(),
+        };
+        diff::write(self.repo, &parent_tree, &tree, options, &mut out)?;
+
+        if let Some(signature) = options.signature.as_ref().filter(|s| !s.is_empty()) {
+            out.push_str("-- \n");
+            out.push_str(signature);
+            if !signature.ends_with(b"\n") {
+                out.push(b'\n');
+            }
+            out.push(b'\n');
+        }
+        Ok(out)
+    }
+}
+
+/// Write the `From` and `Date` headers for `author`.
+fn write_author(author: gix_actor::SignatureRef<'_>, out: &mut BString) {
+    let mut max_len = mail::MAX_HEADER_LENGTH;
+    out.push_str("From: ");
+    if mail::needs_rfc2047_encoding(author.name) {
+        mail::add_rfc2047(out, author.name, mail::Rfc2047::Address);
+        max_len = 76;
+    } else if mail::needs_rfc822_quoting(author.name) {
+        mail::add_wrapped(out, &mail::rfc822_quoted(author.name), -6, 1, max_len);
+    } else {
+        mail::add_wrapped(out, author.name, -6, 1, max_len);
+    }
+    if max_len < mail::last_line_len(out) + " <".len() + author.email.len() + ">".len() {
+        out.push(b'\n');
+    }
+    out.push_str(format!(" <{}>\n", author.email));
+    out.push_str(format!(
+        "Date: {}\n",
+        author.time.format(gix_date::time::format::GIT_RFC2822)
+    ));
+}
+
+/// Split `message` into its title, with all lines of the first paragraph joined by spaces, and the rest of the message,
+/// with all leading empty lines and trailing whitespace of each line removed.
+fn split_message(message: &BStr) -> (BString, BString) {
+    let mut lines = message
+        .lines_with_terminator()
+        .map(|line| line.trim_end_with(|c| mail::is_space(c as u8)))
+        .skip_while(|line| line.is_empty())
+        .peekable();
+    let mut title = BString::default();
+    while let Some(line) = lines.next_if(|line| !line.is_empty()) {
+        if !title.is_empty() {
+            title.push(b' ');
+        }
+        title.push_str(line);
+    }
+    let mut body = BString::default();
+    for line in lines.skip_while(|line| line.is_empty()) {
+        body.push_str(line);
+        body.push(b'\n');
+    }
+    (title, body)
+}
@@ -0,0 +1,123 @@
+//! Decoding of the transfer encodings and character sets used in emails, as far as they are needed to read patches.
+use crate::bstr::{BString, ByteSlice, ByteVec};
+
+/// Decode `value` of a header, replacing all RFC 2047 encoded words with their decoded content.
+///
+/// Whitespace between two encoded words is removed, while anything else is kept as is.
+pub(super) fn header(value: &[u8]) -> BString {
+    let mut out = BString::default();
+    let mut rest = value;
+    let mut after_encoded_word = false;
+    while let Some(start) = rest.find(b"=?") {
+        let (before, word) = rest.split_at(start);
+        let Some((decoded, consumed)) = encoded_word(word) else {
+            out.push_str(&rest[..start + 2]);
+            rest = &rest[start + 2..];
+            after_encoded_word = false;
+            continue;
+        };
+        if !(after_encoded_word && before.iter().all(u8::is_ascii_whitespace)) {
+            out.push_str(before);
+        }
+        out.push_str(decoded);
+        rest = &word[consumed..];
+        after_encoded_word = true;
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Decode the encoded word like `=?UTF-8?q?J=C3=B6hn?=` at the beginning of `input`, and return it along with the amount
+/// of bytes it occupies, or `None` if it isn't well-formed.
+fn encoded_word(input: &[u8]) -> Option<(Vec<u8>, usize)> {
+    let input = input.strip_prefix(b"=?")?;
+    let charset_end = input.find_byte(b'?')?;
+    let (charset, rest) = input.split_at(charset_end);
+    let (encoding, rest) = match rest.get(1..3)? {
+        [encoding, b'?'] => (encoding.to_ascii_lowercase(), &rest[3..]),
+        _ => return None,
+    };
+    let text_end = rest.find(b"?=")?;
+    let text = &rest[..text_end];
+    let decoded = match encoding {
+        b'q' => quoted_printable(text, true),
+        b'b' => base64(text),
+        _ => return None,
+    };
+    let consumed = 2 + charset_end + 3 + text_end + 2;
+    Some((to_utf8(charset, decoded), consumed))
+}
+
+/// Decode `input` that is encoded as quoted-printable, where `_` represents a space if `in_header` is `true`.
+pub(super) fn quoted_printable(input: &[u8], in_header: bool) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut bytes = input.iter().copied().enumerate();
+    while let Some((idx, b)) = bytes.next() {
+        match b {
+            b'=' => {
+                if input[idx + 1..].starts_with(b"\n") {
+                    bytes.next();
+                    continue;
+                }
+                match input.get(idx + 1..idx + 3).and_then(hex_byte) {
+                    Some(decoded) => {
+                        out.push(decoded);
+                        bytes.nth(1);
+                    }
+                    None => out.push(b),
+                }
+            }
+            b'_' if in_header => out.push(b' '),
+            _ => out.push(b),
+        }
+    }
+    out
+}
+
+fn hex_byte(hex: &[u8]) -> Option<u8> {
+    let hex = std::str::from_utf8(hex).ok()?;
+    u8::from_str_radix(hex, 16).ok()
+}
+
+/// Decode `input` that is encoded as base64, ignoring whitespace and stopping at the first padding character.
+pub(super) fn base64(input: &[u8]) -> Vec<u8> {
+    fn value(b: u8) -> Option<u32> {
+        Some(match b {
+            b'A'..=b'Z' => b - b'A',
+            b'a'..=b'z' => b - b'a' + 26,
+            b'0'..=b'9' => b - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        } as u32)
+    }
+    let mut out = Vec::with_capacity(input.len() / 4 * 3);
+    let mut acc = 0u32;
+    let mut bits = 0;
+    for &b in input.iter().take_while(|b| **b != b'=') {
+        let Some(value) = value(b) else { continue };
+        acc = (acc << 6) | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    out
+}
+
+/// Convert `data` in `charset` to UTF-8, which is only done for Latin-1 as all other charsets are assumed to be compatible.
+pub(super) fn to_utf8(charset: &[u8], data: Vec<u8>) -> Vec<u8> {
+    let is_latin1 = [b"iso-8859-1".as_slice(), b"latin1", b"latin-1"]
+        .iter()
+        .any(|name| charset.eq_ignore_ascii_case(name));
+    if !is_latin1 || data.is_ascii() {
+        return data;
+    }
+    let mut out = Vec::with_capacity(data.len() * 2);
+    for b in data {
+        out.push_char(b as char);
+    }
+    out
+}
@@ -0,0 +1,558 @@
+//! Split mailboxes into emails and parse emails with patches into the information needed to recreate their commits,
+//! just like `git mailsplit` and `git mailinfo` do for `git am`.
+//!
+//! This is the inverse of [`format_patch`](super::format_patch), and works with the output of `git format-patch` as well.
+use crate::bstr::{BStr, BString, ByteSlice, ByteVec};
+
+mod decode;
+
+/// Options for use in [`parse()`].
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// If `true`, discard everything in the body before a scissors line like `-- >8 --`, the equivalent of `--scissors`.
+    pub scissors: bool,
+    /// If `true`, keep the subject as is instead of removing prefixes like `Re:` or `[PATCH 1/2]`, the equivalent of `-k`.
+    pub keep_subject: bool,
+    /// If `true`, only remove bracketed prefixes of the subject that contain `PATCH`, and keep all others, the equivalent of `-b`.
+    pub keep_non_patch_brackets: bool,
+}
+
+/// The information extracted from an email by [`parse()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mail {
+    /// The author of the patch, from the `From` and `Date` headers, or from the same headers at the beginning of the body.
+    ///
+    /// If there is no date, the current time is used.
+    pub author: gix_actor::Signature,
+    /// The subject with all prefixes removed, which is the title of the commit message.
+    pub subject: BString,
+    /// The commit message made of the subject and the body up to the start of the patch, with whitespace cleaned up.
+    pub message: BString,
+    /// Everything from the `---` line or the first diff on, including the diffstat and the actual diff.
+    ///
+    /// It's empty if the email didn't contain a patch.
+    pub patch: BString,
+}
+
+/// The error returned by [`parse()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The email doesn't have a valid email address in its `From` header")]
+    MissingEmail,
+    #[error("The date of the email could not be parsed")]
+    Date(#[from] gix_date::parse::Error),
+}
+
+/// Split `mbox` into the emails it contains, which is done at each line starting with `From ` that ends with a date.
+///
+/// If `mbox` doesn't start with such a line, it's considered to be a single email.
+pub fn split(mbox: &[u8]) -> Split<'_> {
+    let start = mbox.iter().take_while(|b| b.is_ascii_whitespace()).count();
+    Split {
+        data: &mbox[start..],
+        is_mbox: matches!(mbox[start..].lines_with_terminator().next(), Some(line) if is_from_line(line)),
+    }
+}
+
+/// An iterator over all emails in a mailbox, created by [`split()`].
+pub struct Split<'a> {
+    data: &'a [u8],
+    is_mbox: bool,
+}
+
+impl<'a> Iterator for Split<'a> {
+    type Item = &'a BStr;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+        if !self.is_mbox {
+            return Some(std::mem::take(&mut self.data).as_bstr());
+        }
+        let mut lines = self.data.lines_with_terminator();
+        let from_line = lines.next().expect("non-empty");
+        let start = from_line.len();
+        let mut end = start;
+        for line in lines {
+            if is_from_line(line) {
+                break;
+            }
+            end += line.len();
+        }
+        let mail = &self.data[start..end];
+        self.data = &self.data[end..];
+        Some(mail.as_bstr())
+    }
+}
+
+/// Return `true` if `line` separates emails in a mailbox, like `From 1234abcd Mon Sep 17 00:00:00 2001`.
+fn is_from_line(line: &[u8]) -> bool {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    if line.len() < 19 || !line.starts_with(b"From ") {
+        return false;
+    }
+    let Some(colon) = line[5..].rfind_byte(b':').map(|pos| pos + 5) else {
+        return false;
+    };
+    let is_digit = |pos: Option<usize>| matches!(pos.and_then(|pos| line.get(pos)), Some(b) if b.is_ascii_digit());
+    let digits_around_colon = [colon.checked_sub(4), colon.checked_sub(2), colon.checked_sub(1)]
+        .into_iter()
+        .chain([Some(colon + 1), Some(colon + 2)])
+        .all(is_digit);
+    let year = line
+        .get(colon + 3..)
+        .and_then(|year| year.trim_start().split(|b| !b.is_ascii_digit()).next())
+        .and_then(|year| year.to_str().ok()?.parse::<u32>().ok());
+    digits_around_colon && matches!(year, Some(year) if year > 90)
+}
+
+/// The headers of an email, or at the beginning of its body, that we are interested in.
+#[derive(Default)]
+struct Headers {
+    from: Option<BString>,
+    subject: Option<BString>,
+    date: Option<BString>,
+}
+
+impl Headers {
+    /// Set the header `line` if it's one of ours and wasn't set yet, and return `true` if it was one of ours.
+    fn set(&mut self, line: &[u8]) -> bool {
+        let Some((name, value)) = split_header(line) else {
+            return false;
+        };
+        let field = if name.eq_ignore_ascii_case(b"from") {
+            &mut self.from
+        } else if name.eq_ignore_ascii_case(b"subject") {
+            &mut self.subject
+        } else if name.eq_ignore_ascii_case(b"date") {
+            &mut self.date
+        } else {
+            return false;
+        };
+        if field.is_none() {
+            *field = Some(decode::header(value));
+        }
+        true
+    }
+}
+
+fn split_header(line: &[u8]) -> Option<(&[u8], &[u8])> {
+    let colon = line.find_byte(b':')?;
+    let name = &line[..colon];
+    let is_valid_name = !name.is_empty() && name.iter().all(u8::is_ascii_graphic);
+    is_valid_name.then(|| (name, line[colon + 1..].trim()))
+}
+
+/// Parse the email `mail` with a patch as produced by `git format-patch`, configured by `options`, into the author,
+/// the commit message and the patch, like `git mailinfo` does.
+///
+/// Encoded words in headers are decoded according to RFC 2047, and bodies encoded as quoted-printable or base64 are decoded.
+/// `From`, `Subject` and `Date` headers at the beginning of the body override those of the email, and the commit message
+/// ends where the patch starts, which is at the `---` line before the diffstat or at the first diff.
+///
+/// ### Deviation
+///
+/// * Multi-part messages aren't supported, so patches that are sent as attachment aren't found.
+/// * Only Latin-1 is converted to UTF-8, while all other character sets are assumed to be compatible with UTF-8.
+pub fn parse(mail: &[u8], options: Options) -> Result<Mail, Error> {
+    let mail = mail.replace(b"\r\n", b"\n");
+    let mut lines = mail.lines_with_terminator().peekable();
+    let mut consumed = lines.next_if(|line| is_from_line(line)).map_or(0, <[u8]>::len);
+
+    let mut headers = Headers::default();
+    let mut transfer_encoding = BString::default();
+    let mut charset = BString::default();
+    let mut body_start = mail.len();
+    while let Some(line) = lines.next() {
+        consumed += line.len();
+        if line == b"\n" {
+            body_start = consumed;
+            break;
+        }
+        let mut header = line.trim_end().to_owned();
+        // Unfold continuation lines, which start with whitespace that is replaced with a single space.
+        while let Some(continuation) = lines.next_if(|line| matches!(line.first(), Some(b' ' | b'\t'))) {
+            consumed += continuation.len();
+            header.truncate(header.trim_end().len());
+            header.push(b' ');
+            header.extend_from_slice(continuation[1..].trim_end_with(|c| c == '\n'));
+        }
+        if !headers.set(&header) {
+            if let Some((name, value)) = split_header(&header) {
+                if name.eq_ignore_ascii_case(b"content-transfer-encoding") {
+                    transfer_encoding = value.to_ascii_lowercase().into();
+                } else if name.eq_ignore_ascii_case(b"content-type") {
+                    charset = content_type_charset(value).unwrap_or_default();
+                }
+            }
+        }
+    }
+
+    let body = &mail[body_start.min(mail.len())..];
+    let body = match transfer_encoding.as_slice() {
+        b"quoted-printable" => decode::quoted_printable(body, false),
+        b"base64" => decode::base64(body),
+        _ => body.to_vec(),
+    };
+    let Body {
+        headers: inbody,
+        message,
+        patch,
+    } = Body::parse(&body, options);
+    let message = decode::to_utf8(&charset, message.into());
+
+    let from = inbody.from.or(headers.from).unwrap_or_default();
+    let (name, email) = name_and_email(&from);
+    if email.is_empty() {
+        return Err(Error::MissingEmail);
+    }
+    let time = match inbody.date.or(headers.date) {
+        Some(date) => gix_date::parse(date.to_str_lossy().trim(), None)?,
+        None => gix_date::Time::now_local_or_utc(),
+    };
+    let mut subject = inbody.subject.or(headers.subject).unwrap_or_default();
+    if !options.keep_subject {
+        subject = cleanup_subject(&subject, options.keep_non_patch_brackets);
+    }
+
+    let mut full_message = subject.clone();
+    full_message.push_str("\n\n");
+    full_message.push_str(message);
+    Ok(Mail {
+        author: gix_actor::Signature { name, email, time },
+        subject,
+        message: strip_space(&full_message),
+        patch: patch.into(),
+    })
+}
+
+/// The body of an email, split into its parts.
+struct Body {
+    headers: Headers,
+    message: BString,
+    patch: Vec<u8>,
+}
+
+impl Body {
+    /// Read headers at the beginning of `body`, which end at the first empty line, and the message up to the start of the patch.
+    fn parse(body: &[u8], options: Options) -> Self {
+        let mut out = Body {
+            headers: Headers::default(),
+            message: BString::default(),
+            patch: Vec::new(),
+        };
+        let mut header_stage = true;
+        let mut header = BString::default();
+        let mut offset = 0;
+        for line in body.lines_with_terminator() {
+            let line_start = offset;
+            offset += line.len();
+            let is_empty = line == b"\n";
+            if header_stage {
+                if is_empty {
+                    if !header.is_empty() {
+                        out.headers.set(&std::mem::take(&mut header));
+                        header_stage = false;
+                    }
+                    continue;
+                }
+                let is_continuation = matches!(line.first(), Some(b' ' | b'\t'));
+                if !header.is_empty() && is_continuation && !(options.scissors && is_scissors_line(line)) {
+                    header.push_str(line.trim_end_with(|c| c == '\n'));
+                    continue;
+                }
+                if !header.is_empty() {
+                    out.headers.set(&std::mem::take(&mut header));
+                }
+                if let Some(subject) = line
+                    .strip_prefix(b"[PATCH]")
+                    .filter(|rest| matches!(rest.first(), Some(b) if b.is_ascii_whitespace()))
+                {
+                    let mut value = BString::from("Subject: [PATCH]");
+                    value.push_str(subject.trim_end());
+                    out.headers.set(&value);
+                    continue;
+                }
+                if matches!(line.strip_prefix(b">"), Some(line) if is_from_line(line)) {
+                    continue;
+                }
+                let is_known_header = matches!(split_header(line), Some((name, _)) if [b"from".as_slice(), b"subject", b"date"]
+                    .iter()
+                    .any(|known| name.eq_ignore_ascii_case(known)));
+                if is_known_header {
+                    header.push_str(line.trim_end_with(|c| c == '\n'));
+                    continue;
+                }
+                header_stage = false;
+            }
+            if options.scissors && is_scissors_line(line) {
+                out.message.clear();
+                out.headers = Headers::default();
+                header_stage = true;
+                continue;
+            }
+            if is_patch_start(line) {
+                out.patch = body[line_start..].to_vec();
+                break;
+            }
+            out.message.push_str(line);
+        }
+        if !header.is_empty() {
+            out.headers.set(&header);
+        }
+        out
+    }
+}
+
+/// Return `true` if `line` starts the patch, either as `---` separator before the diffstat, or as the header of a diff.
+fn is_patch_start(line: &[u8]) -> bool {
+    if line.starts_with(b"diff -") || line.starts_with(b"Index: ") {
+        return true;
+    }
+    if line.len() < 4 || !line.starts_with(b"---") {
+        return false;
+    }
+    if line[3] == b' ' && !matches!(line.get(4), Some(b) if b.is_ascii_whitespace()) {
+        return true;
+    }
+    matches!(
+        line[3..].iter().find(|b| !(b.is_ascii_whitespace() && **b != b'\n')),
+        Some(b'\n')
+    )
+}
+
+/// Return `true` if `line` is a line like `-- >8 --` which marks that everything before it should be discarded.
+///
+/// The perforation has to be at least 8 bytes wide, cover more than a third of the line, and be made of more than
+/// half of dashes and scissors.
+fn is_scissors_line(line: &[u8]) -> bool {
+    let (mut scissors, mut gap, mut perforation) = (0, 0, 0);
+    let mut in_perforation = false;
+    let (mut first_non_blank, mut last_non_blank) = (None, None);
+    let mut pos = 0;
+    while pos < line.len() {
+        let b = line[pos];
+        if b.is_ascii_whitespace() {
+            if in_perforation {
+                perforation += 1;
+                gap += 1;
+            }
+            pos += 1;
+            continue;
+        }
+        last_non_blank = Some(pos);
+        first_non_blank.get_or_insert(pos);
+        if b == b'-' {
+            in_perforation = true;
+            perforation += 1;
+        } else if [b">8", b"8<", b">%", b"%<"]
+            .iter()
+            .any(|mark| line[pos..].starts_with(*mark))
+        {
+            in_perforation = true;
+            perforation += 2;
+            scissors += 2;
+            last_non_blank = Some(pos + 1);
+            pos += 1;
+        } else {
+            in_perforation = false;
+        }
+        pos += 1;
+    }
+    let visible = match (first_non_blank, last_non_blank) {
+        (Some(first), Some(last)) => last - first + 1,
+        _ => 0,
+    };
+    scissors > 0 && visible >= 8 && visible < perforation * 3 && gap * 2 < perforation
+}
+
+/// Extract the `charset` parameter from the value of a `Content-Type` header.
+fn content_type_charset(value: &[u8]) -> Option<BString> {
+    let lowercase = value.to_ascii_lowercase();
+    let start = lowercase.find(b"charset=")? + "charset=".len();
+    let value = &value[start..];
+    let value = match value.strip_prefix(b"\"") {
+        Some(quoted) => &quoted[..quoted.find_byte(b'"').unwrap_or(quoted.len())],
+        None => &value[..value.find_byteset(b"; \t").unwrap_or(value.len())],
+    };
+    Some(value.into())
+}
+
+/// Extract name and email from the value of a `From` header, which can be `Name <email>`, `email (Name)` or just the email.
+fn name_and_email(from: &[u8]) -> (BString, BString) {
+    let mut f = unquote_quoted_pair(from);
+    let Some(mut at) = f.find_byte(b'@') else {
+        let Some(bra) = from.find_byte(b'<') else {
+            return Default::default();
+        };
+        let Some(ket) = from[bra..].find_byte(b'>').map(|pos| pos + bra) else {
+            return Default::default();
+        };
+        let email = BString::from(&from[bra + 1..ket]);
+        let name = from[..bra].trim().into();
+        return (sane_name(name, &email), email);
+    };
+    while at > 0 {
+        let b = f[at - 1];
+        if b.is_ascii_whitespace() {
+            break;
+        }
+        if b == b'<' {
+            f[at - 1] = b' ';
+            break;
+        }
+        at -= 1;
+    }
+    let email_len = f[at..].find_byteset(b" \n\t\r\x0b\x0c>").unwrap_or(f.len() - at);
+    let email = BString::from(&f[at..at + email_len]);
+    let removed_len = email_len + usize::from(at + email_len < f.len());
+    f.drain(at..at + removed_len);
+
+    let mut name: BString = cleanup_space(&f).trim().into();
+    if name.len() > 1 && name.starts_with(b"(") && name.ends_with(b")") {
+        name = name[1..name.len() - 1].into();
+    }
+    (sane_name(name, &email), email)
+}
+
+/// Use the email as name if `name` is too short, too long, or looks like an address itself.
+fn sane_name(name: BString, email: &[u8]) -> BString {
+    if name.len() < 3 || name.len() > 60 || name.find_byteset(b"@<>").is_some() {
+        email.into()
+    } else {
+        name
+    }
+}
+
+/// Remove the quotes of quoted strings, and all backslashes that escape the following character in quoted strings and comments.
+fn unquote_quoted_pair(input: &[u8]) -> BString {
+    fn comment(input: &[u8], mut pos: usize, out: &mut BString) -> usize {
+        out.push(b'(');
+        let mut depth = 1;
+        while let Some(&b) = input.get(pos) {
+            pos += 1;
+            match b {
+                b'\\' => match input.get(pos) {
+                    Some(&escaped) => {
+                        out.push(escaped);
+                        pos += 1;
+                    }
+                    None => break,
+                },
+                b'(' => {
+                    out.push(b);
+                    depth += 1;
+                }
+                b')' => {
+                    out.push(b);
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => out.push(b),
+            }
+        }
+        pos
+    }
+    fn quoted_string(input: &[u8], mut pos: usize, out: &mut BString) -> usize {
+        while let Some(&b) = input.get(pos) {
+            pos += 1;
+            match b {
+                b'"' => break,
+                b'\\' => match input.get(pos) {
+                    Some(&escaped) => {
+                        out.push(escaped);
+                        pos += 1;
+                    }
+                    None => break,
+                },
+                b'(' => pos = comment(input, pos, out),
+                _ => out.push(b),
+            }
+        }
+        pos
+    }
+
+    let mut out = BString::default();
+    let mut pos = 0;
+    while let Some(&b) = input.get(pos) {
+        pos += 1;
+        match b {
+            b'"' => pos = quoted_string(input, pos, &mut out),
+            b'(' => pos = comment(input, pos, &mut out),
+            _ => out.push(b),
+        }
+    }
+    out
+}
+
+/// Replace each run of whitespace in `text` with a single space.
+fn cleanup_space(text: &[u8]) -> BString {
+    let mut out = BString::default();
+    for b in text.iter().copied() {
+        if b.is_ascii_whitespace() {
+            if out.last() != Some(&b' ') {
+                out.push(b' ');
+            }
+        } else {
+            out.push(b);
+        }
+    }
+    out
+}
+
+/// Remove prefixes like `Re:`, `[PATCH 1/2]` and whitespace from the beginning of `subject`, trim it
+/// and collapse all whitespace.
+fn cleanup_subject(subject: &[u8], keep_non_patch_brackets: bool) -> BString {
+    let mut out = BString::from(subject);
+    let mut at = 0;
+    while at < out.len() {
+        match out[at] {
+            b'r' | b'R' if out.len() > at + 3 && out[at + 1].eq_ignore_ascii_case(&b'e') && out[at + 2] == b':' => {
+                out.drain(at..at + 3);
+            }
+            b' ' | b'\t' | b':' => {
+                out.remove(at);
+            }
+            b'[' => {
+                let Some(len) = out[at..].find_byte(b']').map(|pos| pos + 1) else {
+                    break;
+                };
+                if !keep_non_patch_brackets || (len >= 7 && out[at..at + len].find(b"PATCH").is_some()) {
+                    out.drain(at..at + len);
+                } else {
+                    at += len;
+                    if matches!(out.get(at), Some(b) if b.is_ascii_whitespace()) {
+                        at += 1;
+                    }
+                }
+            }
+            _ => break,
+        }
+    }
+    cleanup_space(out.trim())
+}
+
+/// Remove trailing whitespace from all lines, as well as empty lines at the beginning and the end of `text`,
+/// and collapse consecutive empty lines into one.
+fn strip_space(text: &[u8]) -> BString {
+    let mut out = BString::default();
+    let mut empty_lines = 0;
+    for line in text.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            empty_lines += 1;
+            continue;
+        }
+        if !out.is_empty() && empty_lines > 0 {
+            out.push(b'\n');
+        }
+        empty_lines = 0;
+        out.push_str(line);
+        out.push(b'\n');
+    }
+    out
+}
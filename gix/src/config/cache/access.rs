@@ -79,6 +79,18 @@ impl Cache {
             if let Some(textconv) = section.value(config::tree::Diff::DRIVER_TEXTCONV.name) {
                 driver.binary_to_text_command = textconv.into_owned().into();
             }
+            if let Some(cache) = section.value_implicit(config::tree::Diff::DRIVER_CACHE_TEXTCONV.name) {
+                driver.cache_binary_to_text = config::tree::Diff::DRIVER_CACHE_TEXTCONV
+                    .enrich_error(cache.map_or(Ok(true), |value| {
+                        gix_config::Boolean::try_from(value.as_ref()).map(|b| b.0)
+                    }))
+                    .with_lenient_default(self.lenient_config)
+                    .map_err(|err| config::diff::drivers::Error {
+                        name: driver.name.clone(),
+                        attribute: "cachetextconv",
+                        source: Box::new(err),
+                    })?;
+            }
             if let Some(algorithm) = section.value("algorithm") {
                 driver.algorithm = config::tree::Diff::DRIVER_ALGORITHM
                     .try_into_algorithm(algorithm)
@@ -100,6 +112,68 @@ impl Cache {
         Ok(out)
     }
 
+    #[cfg(feature = "blame")]
+    pub(crate) fn blame_ignore_revs_files(&self) -> Result<Vec<PathBuf>, gix_config::path::interpolate::Error> {
+        let install_dir = crate::path::install_dir().ok();
+        let home = self.home_dir();
+        let ctx = config::cache::interpolate_context(install_dir.as_deref(), home.as_deref());
+        let mut out = Vec::new();
+        for path in self
+            .resolved
+            .strings_filter(
+                config::tree::Blame::IGNORE_REVS_FILE,
+                &mut self.filter_config_section.clone(),
+            )
+            .into_iter()
+            .flatten()
+        {
+            if path.is_empty() {
+                out.clear();
+                continue;
+            }
+            out.push(gix_config::Path::from(path).interpolate(ctx)?.into_owned());
+        }
+        Ok(out)
+    }
+
+    #[cfg(feature = "merge")]
+    pub(crate) fn merge_drivers(&self) -> Vec<gix_merge::blob::Driver> {
+        let mut out = Vec::<gix_merge::blob::Driver>::new();
+        for section in self
+            .resolved
+            .sections_by_name("merge")
+            .into_iter()
+            .flatten()
+            .filter(|s| (self.filter_config_section)(s.meta()))
+        {
+            let Some(name) = section.header().subsection_name().filter(|n| !n.is_empty()) else {
+                continue;
+            };
+
+            let driver = match out.iter_mut().find(|d| d.name == name) {
+                Some(existing) => existing,
+                None => {
+                    out.push(gix_merge::blob::Driver {
+                        name: name.into(),
+                        ..Default::default()
+                    });
+                    out.last_mut().expect("just pushed")
+                }
+            };
+
+            if let Some(display_name) = section.value(config::tree::Merge::DRIVER_NAME.name) {
+                driver.display_name = display_name.into_owned();
+            }
+            if let Some(command) = section.value(config::tree::Merge::DRIVER_COMMAND.name) {
+                driver.command = command.into_owned();
+            }
+            if let Some(recursive) = section.value(config::tree::Merge::DRIVER_RECURSIVE.name) {
+                driver.recursive = Some(recursive.into_owned());
+            }
+        }
+        out
+    }
+
     #[cfg(feature = "blob-diff")]
     pub(crate) fn diff_pipeline_options(
         &self,
@@ -178,6 +252,17 @@ impl Cache {
         })
     }
 
+    pub(crate) fn may_read_changed_paths(&self) -> Result<bool, config::boolean::Error> {
+        const DEFAULT: bool = true;
+        self.resolved
+            .boolean(config::tree::CommitGraph::READ_CHANGED_PATHS)
+            .map_or(Ok(DEFAULT), |res| {
+                config::tree::CommitGraph::READ_CHANGED_PATHS
+                    .enrich_error(res)
+                    .with_lenient_default_value(self.lenient_config, DEFAULT)
+            })
+    }
+
     /// Returns (file-timeout, pack-refs timeout)
     pub(crate) fn lock_timeout(
         &self,
@@ -243,6 +328,7 @@ impl Cache {
             ignore_case: boolean(self, "core.ignoreCase", &Core::IGNORE_CASE, false)?,
             executable_bit: boolean(self, "core.fileMode", &Core::FILE_MODE, true)?,
             symlink: boolean(self, "core.symlinks", &Core::SYMLINKS, true)?,
+            long_paths: boolean(self, "core.longPaths", &Core::LONG_PATHS, false)?,
         })
     }
 
@@ -263,6 +349,18 @@ impl Cache {
         })
     }
 
+    /// The version of the index format to use for newly created indices, as configured by `index.version`.
+    #[cfg(feature = "index")]
+    pub(crate) fn index_version(&self) -> Result<gix_index::Version, config::key::GenericErrorWithValue> {
+        Ok(self
+            .apply_leniency(
+                self.resolved
+                    .string(config::tree::Index::VERSION)
+                    .map(|v| config::tree::Index::VERSION.try_into_index_version(v)),
+            )?
+            .unwrap_or(gix_index::Version::V2))
+    }
+
     #[cfg(feature = "index")]
     pub(crate) fn protect_options(&self) -> Result<gix_validate::path::component::Options, config::boolean::Error> {
         const IS_WINDOWS: bool = cfg!(windows);
@@ -2,7 +2,10 @@
 
 use super::{util, Error};
 use crate::config::cache::util::{ApplyLeniency, ApplyLeniencyDefaultValue};
-use crate::config::tree::{gitoxide, Core, Extensions};
+use crate::config::{
+    tree::{gitoxide, Core},
+    RepositoryFormat,
+};
 
 /// A utility to deal with the cyclic dependency between the ref store and the configuration. The ref-store needs the
 /// object hash kind, and the configuration needs the current branch name to resolve conditional includes with `onbranch`.
@@ -12,7 +15,7 @@ pub(crate) struct StageOne {
 
     pub is_bare: bool,
     pub lossy: Option<bool>,
-    pub object_hash: gix_hash::Kind,
+    pub repository_format: RepositoryFormat,
     pub reflog: Option<gix_ref::store::WriteReflog>,
     pub precompose_unicode: bool,
     pub protect_windows: bool,
@@ -40,29 +43,8 @@ impl StageOne {
         // Note that we assume the repo is bare by default unless we are told otherwise. This is relevant if
         // the repo doesn't have a configuration file.
         let is_bare = util::config_bool(&config, &Core::BARE, "core.bare", true, lenient)?;
-        let repo_format_version = config
-            .integer("core.repositoryFormatVersion")
-            .map(|version| Core::REPOSITORY_FORMAT_VERSION.try_into_usize(version))
-            .transpose()?
-            .unwrap_or_default();
-        let object_hash = (repo_format_version != 1)
-            .then_some(Ok(gix_hash::Kind::Sha1))
-            .or_else(|| {
-                config
-                    .string(Extensions::OBJECT_FORMAT)
-                    .map(|format| Extensions::OBJECT_FORMAT.try_into_object_format(format))
-            })
-            .transpose()?
-            .unwrap_or(gix_hash::Kind::Sha1);
-
-        let extension_worktree = util::config_bool(
-            &config,
-            &Extensions::WORKTREE_CONFIG,
-            "extensions.worktreeConfig",
-            false,
-            lenient,
-        )?;
-        if extension_worktree {
+        let repository_format = RepositoryFormat::from_config(&config, lenient)?;
+        if repository_format.worktree_config {
             let worktree_config = load_config(
                 git_dir.join("config.worktree"),
                 &mut buf,
@@ -96,7 +78,7 @@ impl StageOne {
             buf,
             is_bare,
             lossy,
-            object_hash,
+            repository_format,
             reflog,
             precompose_unicode,
             protect_windows,
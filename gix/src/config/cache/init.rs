@@ -25,7 +25,7 @@ impl Cache {
             mut buf,
             lossy,
             is_bare,
-            object_hash,
+            repository_format,
             reflog: _,
             precompose_unicode: _,
             protect_windows: _,
@@ -141,7 +141,8 @@ impl Cache {
             globals
         };
 
-        let hex_len = util::parse_core_abbrev(&config, object_hash).with_leniency(lenient_config)?;
+        let hex_len =
+            util::parse_core_abbrev(&config, repository_format.object_format).with_leniency(lenient_config)?;
 
         use util::config_bool;
         let reflog = util::query_refupdates(&config, lenient_config)?;
@@ -158,11 +159,14 @@ impl Cache {
         let object_kind_hint = util::disambiguate_hint(&config, lenient_config)?;
         let (static_pack_cache_limit_bytes, pack_cache_bytes, object_cache_bytes) =
             util::parse_object_caches(&config, lenient_config, filter_config_section)?;
+        let pack_windows = util::parse_pack_windows(&config, lenient_config, filter_config_section)?;
         // NOTE: When adding a new initial cache, consider adjusting `reread_values_and_clear_caches()` as well.
         Ok(Cache {
             resolved: config.into(),
             use_multi_pack_index,
-            object_hash,
+            pack_windows,
+            object_hash: repository_format.object_format,
+            repository_format,
             #[cfg(feature = "revision")]
             object_kind_hint,
             static_pack_cache_limit_bytes,
@@ -173,6 +177,7 @@ impl Cache {
             is_bare,
             ignore_case,
             hex_len,
+            auto_hex_len: Default::default(),
             filter_config_section,
             environment,
             lenient_config,
@@ -229,6 +234,7 @@ impl Cache {
         let refs_namespace = util::query_refs_namespace(config, self.lenient_config)?;
 
         self.hex_len = hex_len;
+        self.auto_hex_len = Default::default();
         self.ignore_case = ignore_case;
         self.reflog = reflog;
         self.refs_namespace = refs_namespace;
@@ -550,6 +556,15 @@ fn apply_environment_overrides(
                 (env(key), key.name)
             }],
         ),
+        (
+            "index",
+            None,
+            git_prefix,
+            &[{
+                let key = &config::tree::Index::VERSION;
+                (env(key), key.name)
+            }],
+        ),
         #[cfg(feature = "blob-diff")]
         (
             "diff",
@@ -103,6 +103,34 @@ pub(crate) fn parse_object_caches(
     Ok((static_pack_cache_limit, pack_cache_bytes, object_cache_bytes))
 }
 
+/// Return the options for mapping pack data files in windows, or `None` if they should be mapped entirely.
+///
+/// Windows are used if one of the values is configured, or on 32-bit systems where the address space is scarce.
+pub(crate) fn parse_pack_windows(
+    config: &gix_config::File<'static>,
+    lenient: bool,
+    mut filter_config_section: fn(&gix_config::file::Metadata) -> bool,
+) -> Result<Option<gix_pack::data::window::Options>, Error> {
+    let window_size = config
+        .integer_filter("core.packedGitWindowSize", &mut filter_config_section)
+        .map(|res| Core::PACKED_GIT_WINDOW_SIZE.try_into_usize(res))
+        .transpose()
+        .with_leniency(lenient)?;
+    let limit = config
+        .integer_filter("core.packedGitLimit", &mut filter_config_section)
+        .map(|res| Core::PACKED_GIT_LIMIT.try_into_usize(res))
+        .transpose()
+        .with_leniency(lenient)?;
+    if window_size.is_none() && limit.is_none() && cfg!(target_pointer_width = "64") {
+        return Ok(None);
+    }
+    let defaults = gix_pack::data::window::Options::default();
+    Ok(Some(gix_pack::data::window::Options {
+        window_size: window_size.unwrap_or(defaults.window_size),
+        limit: limit.unwrap_or(defaults.limit),
+    }))
+}
+
 pub(crate) fn parse_core_abbrev(
     config: &gix_config::File<'static>,
     object_hash: gix_hash::Kind,
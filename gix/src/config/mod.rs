@@ -15,6 +15,11 @@ pub mod overrides;
 pub mod tree;
 pub use tree::root::Tree;
 
+///
+#[allow(clippy::empty_docs)]
+pub mod repository_format;
+pub use repository_format::RepositoryFormat;
+
 /// A platform to access configuration values as read from disk.
 ///
 /// Note that these values won't update even if the underlying file(s) change.
@@ -80,6 +85,8 @@ pub enum Error {
     ConfigTypedString(#[from] key::GenericErrorWithValue),
     #[error(transparent)]
     RefsNamespace(#[from] refs_namespace::Error),
+    #[error(transparent)]
+    RepositoryFormat(#[from] repository_format::Error),
     #[error("Cannot handle objects formatted as {:?}", .name)]
     UnsupportedObjectFormat { name: BString },
     #[error(transparent)]
@@ -587,12 +594,18 @@ pub(crate) struct Cache {
     pub resolved: crate::Config,
     /// The hex-length to assume when shortening object ids. If `None`, it should be computed based on the approximate object count.
     pub hex_len: Option<usize>,
+    /// The hex-length computed from the approximate object count if `hex_len` is `None`, lazily initialized.
+    pub(crate) auto_hex_len: OnceCell<usize>,
     /// true if the repository is designated as 'bare', without work tree.
     pub is_bare: bool,
     /// The type of hash to use.
     pub object_hash: gix_hash::Kind,
+    /// The format of the repository along with the extensions it uses.
+    pub repository_format: RepositoryFormat,
     /// If true, multi-pack indices, whether present or not, may be used by the object database.
     pub use_multi_pack_index: bool,
+    /// The options for mapping pack data files in windows, or `None` if they are mapped entirely.
+    pub pack_windows: Option<gix_pack::data::window::Options>,
     /// The representation of `core.logallrefupdates`, or `None` if the variable wasn't set.
     pub reflog: Option<gix_ref::store::WriteReflog>,
     /// The representation of `gitoxide.core.refsNamespace`, or `None` if the variable wasn't set.
@@ -0,0 +1,165 @@
+use crate::{
+    bstr::{BString, ByteSlice},
+    config::{
+        cache::util,
+        tree::{Core, Extensions},
+    },
+};
+
+/// The backend used to store references, as configured with `extensions.refStorage`.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum RefStorage {
+    /// References are stored in loose files and the `packed-refs` file.
+    #[default]
+    Files,
+}
+
+/// The format of a repository as defined by `core.repositoryFormatVersion` and the `extensions.*` it enables, which
+/// are validated when opening it.
+///
+/// Like `git`, extensions that change how the repository must be read are only recognized if the version is `1`, while
+/// extensions that merely restrict what may be done with it, like `extensions.preciousObjects`, are also recognized with
+/// version `0`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RepositoryFormat {
+    /// The value of `core.repositoryFormatVersion`, which is `0` or `1`.
+    pub version: u8,
+    /// The hash used to identify objects, as configured with `extensions.objectFormat`.
+    pub object_format: gix_hash::Kind,
+    /// The hash to additionally identify written objects with, as configured with `extensions.compatObjectFormat`,
+    /// or `None` if it isn't set or is the same as [`object_format`](Self::object_format).
+    pub compat_object_format: Option<gix_hash::Kind>,
+    /// The backend storing references, as configured with `extensions.refStorage`.
+    pub ref_storage: RefStorage,
+    /// If `true`, the worktree-specific configuration in `config.worktree` is read, as configured with `extensions.worktreeConfig`.
+    pub worktree_config: bool,
+    /// If `true`, objects must never be deleted from the object database, as configured with `extensions.preciousObjects`.
+    pub precious_objects: bool,
+    /// The name of the remote to obtain missing objects from, as configured with `extensions.partialClone`.
+    pub partial_clone: Option<BString>,
+}
+
+impl Default for RepositoryFormat {
+    fn default() -> Self {
+        RepositoryFormat {
+            version: 0,
+            object_format: gix_hash::Kind::Sha1,
+            compat_object_format: None,
+            ref_storage: RefStorage::Files,
+            worktree_config: false,
+            precious_objects: false,
+            partial_clone: None,
+        }
+    }
+}
+
+/// The error returned by [`RepositoryFormat::from_config()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Expected repository format version 0 or 1, found {version}")]
+    UnsupportedVersion { version: usize },
+    #[error("Unknown repository extension '{name}' found")]
+    UnknownExtension { name: BString },
+}
+
+impl RepositoryFormat {
+    /// Read the repository format from `config`, the configuration of the repository without the configuration of its worktree,
+    /// and fail if it can't be handled.
+    ///
+    /// If `lenient` is `true`, invalid boolean values of extensions are ignored, but unknown or unsupported extensions
+    /// still cause an error as the repository can't be read correctly without them.
+    pub fn from_config(config: &gix_config::File<'_>, lenient: bool) -> Result<Self, crate::config::Error> {
+        let version = config
+            .integer("core.repositoryFormatVersion")
+            .map(|version| Core::REPOSITORY_FORMAT_VERSION.try_into_usize(version))
+            .transpose()?
+            .unwrap_or_default();
+        let version = match version {
+            0 | 1 => version as u8,
+            _ => return Err(Error::UnsupportedVersion { version }.into()),
+        };
+
+        let mut format = RepositoryFormat {
+            version,
+            ..Default::default()
+        };
+        for section in config.sections_by_name("extensions").into_iter().flatten() {
+            for name in section.value_names() {
+                let full_name: BString = match section.header().subsection_name() {
+                    Some(subsection) => format!("{subsection}.{name}").into(),
+                    None => name.as_ref().into(),
+                };
+                let key = full_name.to_ascii_lowercase();
+                // Extensions understood by `git` at version 0 are always honored.
+                match key.as_bytes() {
+                    b"noop" => continue,
+                    b"worktreeconfig" => {
+                        format.worktree_config = util::config_bool(
+                            config,
+                            &Extensions::WORKTREE_CONFIG,
+                            "extensions.worktreeConfig",
+                            false,
+                            lenient,
+                        )?;
+                        continue;
+                    }
+                    b"preciousobjects" => {
+                        format.precious_objects = util::config_bool(
+                            config,
+                            &Extensions::PRECIOUS_OBJECTS,
+                            "extensions.preciousObjects",
+                            false,
+                            lenient,
+                        )?;
+                        continue;
+                    }
+                    b"partialclone" => {
+                        format.partial_clone = config
+                            .string(Extensions::PARTIAL_CLONE)
+                            .map(std::borrow::Cow::into_owned)
+                            .filter(|name| !name.is_empty());
+                        continue;
+                    }
+                    _ => {}
+                }
+                // All others are ignored with version 0, as repositories created before they existed may contain them.
+                if version == 0 {
+                    continue;
+                }
+                match key.as_bytes() {
+                    b"noop-v1" => {}
+                    b"objectformat" => {
+                        if let Some(object_format) = config
+                            .string(Extensions::OBJECT_FORMAT)
+                            .map(|value| Extensions::OBJECT_FORMAT.try_into_object_format(value))
+                            .transpose()?
+                        {
+                            format.object_format = object_format;
+                        }
+                    }
+                    b"refstorage" => {
+                        if let Some(ref_storage) = config
+                            .string(Extensions::REF_STORAGE)
+                            .map(|value| Extensions::REF_STORAGE.try_into_ref_storage(value))
+                            .transpose()?
+                        {
+                            format.ref_storage = ref_storage;
+                        }
+                    }
+                    b"compatobjectformat" => {
+                        format.compat_object_format = config
+                            .string(Extensions::COMPAT_OBJECT_FORMAT)
+                            .map(|value| Extensions::COMPAT_OBJECT_FORMAT.try_into_compat_object_format(value))
+                            .transpose()?;
+                    }
+                    _ => return Err(Error::UnknownExtension { name: full_name }.into()),
+                }
+            }
+        }
+        if format.compat_object_format == Some(format.object_format) {
+            format.compat_object_format = None;
+        }
+        Ok(format)
+    }
+}
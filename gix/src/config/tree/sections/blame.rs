@@ -0,0 +1,19 @@
+use crate::config::{
+    tree::{keys, Blame, Key, Section},
+    Tree,
+};
+
+impl Blame {
+    /// The `blame.ignoreRevsFile` key.
+    pub const IGNORE_REVS_FILE: keys::Path = keys::Path::new_path("ignoreRevsFile", &Tree::BLAME);
+}
+
+impl Section for Blame {
+    fn name(&self) -> &str {
+        "blame"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::IGNORE_REVS_FILE]
+    }
+}
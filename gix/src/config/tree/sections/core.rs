@@ -6,6 +6,12 @@ use crate::{
 impl Core {
     /// The `core.abbrev` key.
     pub const ABBREV: Abbrev = Abbrev::new_with_validate("abbrev", &config::Tree::CORE, validate::Abbrev);
+    /// The `core.alternateRefsCommand` key.
+    pub const ALTERNATE_REFS_COMMAND: keys::Program =
+        keys::Program::new_program("alternateRefsCommand", &config::Tree::CORE);
+    /// The `core.alternateRefsPrefixes` key.
+    pub const ALTERNATE_REFS_PREFIXES: keys::String =
+        keys::String::new_string("alternateRefsPrefixes", &config::Tree::CORE);
     /// The `core.bare` key.
     pub const BARE: keys::Boolean = keys::Boolean::new_boolean("bare", &config::Tree::CORE);
     /// The `core.bigFileThreshold` key.
@@ -36,6 +42,9 @@ impl Core {
         keys::LockTimeout::new_lock_timeout("packedRefsTimeout", &config::Tree::CORE);
     /// The `core.multiPackIndex` key.
     pub const MULTIPACK_INDEX: keys::Boolean = keys::Boolean::new_boolean("multiPackIndex", &config::Tree::CORE);
+    /// The `core.longPaths` key.
+    pub const LONG_PATHS: keys::Boolean =
+        keys::Boolean::new_boolean("longPaths", &config::Tree::CORE).with_note("only has an effect on Windows");
     /// The `core.logAllRefUpdates` key.
     pub const LOG_ALL_REF_UPDATES: LogAllRefUpdates =
         LogAllRefUpdates::new_with_validate("logAllRefUpdates", &config::Tree::CORE, validate::LogAllRefUpdates);
@@ -44,6 +53,14 @@ impl Core {
     /// Needs application to use [`env::args_os`][crate::env::args_os()] to conform all input paths before they are used.
     pub const PRECOMPOSE_UNICODE: keys::Boolean = keys::Boolean::new_boolean("precomposeUnicode", &config::Tree::CORE)
         .with_note("application needs to conform all program input by using gix::env::args_os()");
+    /// The `core.packedGitLimit` key.
+    pub const PACKED_GIT_LIMIT: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("packedGitLimit", &config::Tree::CORE)
+            .with_note("enables windowed mapping of packs, which is always used on 32-bit systems");
+    /// The `core.packedGitWindowSize` key.
+    pub const PACKED_GIT_WINDOW_SIZE: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("packedGitWindowSize", &config::Tree::CORE)
+            .with_note("enables windowed mapping of packs, which is always used on 32-bit systems");
     /// The `core.protectHFS` key.
     pub const PROTECT_HFS: keys::Boolean = keys::Boolean::new_boolean("protectHFS", &config::Tree::CORE);
     /// The `core.protectNTFS` key.
@@ -51,6 +68,8 @@ impl Core {
     /// The `core.repositoryFormatVersion` key.
     pub const REPOSITORY_FORMAT_VERSION: keys::UnsignedInteger =
         keys::UnsignedInteger::new_unsigned_integer("repositoryFormatVersion", &config::Tree::CORE);
+    /// The `core.splitIndex` key.
+    pub const SPLIT_INDEX: keys::Boolean = keys::Boolean::new_boolean("splitIndex", &config::Tree::CORE);
     /// The `core.symlinks` key.
     pub const SYMLINKS: keys::Boolean = keys::Boolean::new_boolean("symlinks", &config::Tree::CORE);
     /// The `core.trustCTime` key.
@@ -103,6 +122,8 @@ impl Section for Core {
     fn keys(&self) -> &[&dyn Key] {
         &[
             &Self::ABBREV,
+            &Self::ALTERNATE_REFS_COMMAND,
+            &Self::ALTERNATE_REFS_PREFIXES,
             &Self::BARE,
             &Self::BIG_FILE_THRESHOLD,
             &Self::CHECK_STAT,
@@ -115,8 +136,12 @@ impl Section for Core {
             &Self::PACKED_REFS_TIMEOUT,
             &Self::MULTIPACK_INDEX,
             &Self::LOG_ALL_REF_UPDATES,
+            &Self::LONG_PATHS,
+            &Self::PACKED_GIT_LIMIT,
+            &Self::PACKED_GIT_WINDOW_SIZE,
             &Self::PRECOMPOSE_UNICODE,
             &Self::REPOSITORY_FORMAT_VERSION,
+            &Self::SPLIT_INDEX,
             &Self::SYMLINKS,
             &Self::TRUST_C_TIME,
             &Self::WORKTREE,
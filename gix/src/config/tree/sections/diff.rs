@@ -17,6 +17,12 @@ impl Diff {
     );
     /// The `diff.renames` key.
     pub const RENAMES: Renames = Renames::new_renames("renames", &config::Tree::DIFF);
+    /// The `diff.context` key.
+    pub const CONTEXT: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("context", &config::Tree::DIFF);
+    /// The `diff.interHunkContext` key.
+    pub const INTER_HUNK_CONTEXT: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("interHunkContext", &config::Tree::DIFF);
 
     /// The `diff.<driver>.command` key.
     pub const DRIVER_COMMAND: keys::Program = keys::Program::new_program("command", &config::Tree::DIFF)
@@ -24,6 +30,9 @@ impl Diff {
     /// The `diff.<driver>.textconv` key.
     pub const DRIVER_TEXTCONV: keys::Program = keys::Program::new_program("textconv", &config::Tree::DIFF)
         .with_subsection_requirement(Some(SubSectionRequirement::Parameter("driver")));
+    /// The `diff.<driver>.cachetextconv` key.
+    pub const DRIVER_CACHE_TEXTCONV: keys::Boolean = keys::Boolean::new_boolean("cachetextconv", &config::Tree::DIFF)
+        .with_subsection_requirement(Some(SubSectionRequirement::Parameter("driver")));
     /// The `diff.<driver>.algorithm` key.
     pub const DRIVER_ALGORITHM: Algorithm =
         Algorithm::new_with_validate("algorithm", &config::Tree::DIFF, validate::Algorithm)
@@ -35,6 +44,11 @@ impl Diff {
     /// The `diff.external` key.
     pub const EXTERNAL: keys::Program =
         keys::Program::new_program("external", &config::Tree::DIFF).with_environment_override("GIT_EXTERNAL_DIFF");
+
+    /// The `diff.ignoreSubmodules` key.
+    pub const IGNORE_SUBMODULES: IgnoreSubmodules =
+        IgnoreSubmodules::new_with_validate("ignoreSubmodules", &config::Tree::DIFF, validate::IgnoreSubmodules)
+            .with_note("It overrides the 'ignore' configuration of individual submodules, just like in git");
 }
 
 impl Section for Diff {
@@ -47,11 +61,15 @@ impl Section for Diff {
             &Self::ALGORITHM,
             &Self::RENAME_LIMIT,
             &Self::RENAMES,
+            &Self::CONTEXT,
+            &Self::INTER_HUNK_CONTEXT,
             &Self::DRIVER_COMMAND,
             &Self::DRIVER_TEXTCONV,
+            &Self::DRIVER_CACHE_TEXTCONV,
             &Self::DRIVER_ALGORITHM,
             &Self::DRIVER_BINARY,
             &Self::EXTERNAL,
+            &Self::IGNORE_SUBMODULES,
         ]
     }
 }
@@ -65,6 +83,9 @@ pub type Renames = keys::Any<validate::Renames>;
 /// The `diff.<driver>.binary` key.
 pub type Binary = keys::Any<validate::Binary>;
 
+/// The `diff.ignoreSubmodules` key.
+pub type IgnoreSubmodules = keys::Any<validate::IgnoreSubmodules>;
+
 mod algorithm {
     use std::borrow::Cow;
 
@@ -129,6 +150,26 @@ mod binary {
     }
 }
 
+mod ignore_submodules {
+    use std::borrow::Cow;
+
+    use crate::{
+        bstr::BStr,
+        config::{key::GenericErrorWithValue, tree::diff::IgnoreSubmodules},
+    };
+
+    impl IgnoreSubmodules {
+        /// Convert `value` into the portion of the submodule status to ignore.
+        pub fn try_into_ignore(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<gix_submodule::config::Ignore, GenericErrorWithValue> {
+            gix_submodule::config::Ignore::try_from(value.as_ref())
+                .map_err(|()| GenericErrorWithValue::from_value(self, value.into_owned()))
+        }
+    }
+}
+
 mod renames {
     use crate::{
         bstr::ByteSlice,
@@ -195,4 +236,12 @@ mod validate {
             Ok(())
         }
     }
+
+    pub struct IgnoreSubmodules;
+    impl keys::Validate for IgnoreSubmodules {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            Diff::IGNORE_SUBMODULES.try_into_ignore(value.into())?;
+            Ok(())
+        }
+    }
 }
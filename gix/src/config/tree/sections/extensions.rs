@@ -11,11 +11,34 @@ impl Extensions {
         ObjectFormat::new_with_validate("objectFormat", &config::Tree::EXTENSIONS, validate::ObjectFormat).with_note(
             "Support for SHA256 is prepared but not fully implemented yet. For now we abort when encountered",
         );
+    /// The `extensions.compatObjectFormat` key.
+    pub const COMPAT_OBJECT_FORMAT: CompatObjectFormat = CompatObjectFormat::new_with_validate(
+        "compatObjectFormat",
+        &config::Tree::EXTENSIONS,
+        validate::CompatObjectFormat,
+    )
+    .with_note("Compatibility ids are recorded for written loose objects, but object contents aren't translated");
+    /// The `extensions.refStorage` key.
+    pub const REF_STORAGE: RefStorage =
+        RefStorage::new_with_validate("refStorage", &config::Tree::EXTENSIONS, validate::RefStorage)
+            .with_note("Only the 'files' backend is supported");
+    /// The `extensions.preciousObjects` key.
+    pub const PRECIOUS_OBJECTS: keys::Boolean =
+        keys::Boolean::new_boolean("preciousObjects", &config::Tree::EXTENSIONS);
+    /// The `extensions.partialClone` key.
+    pub const PARTIAL_CLONE: keys::RemoteName =
+        keys::RemoteName::new_remote_name("partialClone", &config::Tree::EXTENSIONS);
 }
 
-/// The `core.checkStat` key.
+/// The `extensions.objectFormat` key.
 pub type ObjectFormat = keys::Any<validate::ObjectFormat>;
 
+/// The `extensions.compatObjectFormat` key.
+pub type CompatObjectFormat = keys::Any<validate::CompatObjectFormat>;
+
+/// The `extensions.refStorage` key.
+pub type RefStorage = keys::Any<validate::RefStorage>;
+
 mod object_format {
     use std::borrow::Cow;
 
@@ -35,13 +58,64 @@ mod object_format {
     }
 }
 
+mod compat_object_format {
+    use std::borrow::Cow;
+
+    use crate::{bstr::BStr, config, config::tree::sections::extensions::CompatObjectFormat};
+
+    impl CompatObjectFormat {
+        pub fn try_into_compat_object_format(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<gix_hash::Kind, config::key::GenericErrorWithValue> {
+            if value.as_ref().eq_ignore_ascii_case(b"sha1") {
+                Ok(gix_hash::Kind::Sha1)
+            } else if value.as_ref().eq_ignore_ascii_case(b"sha256") {
+                Ok(gix_hash::Kind::Sha256)
+            } else {
+                Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned()))
+            }
+        }
+    }
+}
+
+mod ref_storage {
+    use std::borrow::Cow;
+
+    use crate::{
+        bstr::BStr,
+        config,
+        config::{repository_format, tree::sections::extensions::RefStorage},
+    };
+
+    impl RefStorage {
+        pub fn try_into_ref_storage(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<repository_format::RefStorage, config::key::GenericErrorWithValue> {
+            if value.as_ref() == "files" {
+                Ok(repository_format::RefStorage::Files)
+            } else {
+                Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned()))
+            }
+        }
+    }
+}
+
 impl Section for Extensions {
     fn name(&self) -> &str {
         "extensions"
     }
 
     fn keys(&self) -> &[&dyn Key] {
-        &[&Self::OBJECT_FORMAT, &Self::WORKTREE_CONFIG]
+        &[
+            &Self::OBJECT_FORMAT,
+            &Self::WORKTREE_CONFIG,
+            &Self::COMPAT_OBJECT_FORMAT,
+            &Self::REF_STORAGE,
+            &Self::PRECIOUS_OBJECTS,
+            &Self::PARTIAL_CLONE,
+        ]
     }
 }
 
@@ -56,4 +130,22 @@ mod validate {
             Ok(())
         }
     }
+
+    pub struct CompatObjectFormat;
+
+    impl keys::Validate for CompatObjectFormat {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Extensions::COMPAT_OBJECT_FORMAT.try_into_compat_object_format(value.into())?;
+            Ok(())
+        }
+    }
+
+    pub struct RefStorage;
+
+    impl keys::Validate for RefStorage {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Extensions::REF_STORAGE.try_into_ref_storage(value.into())?;
+            Ok(())
+        }
+    }
 }
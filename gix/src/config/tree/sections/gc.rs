@@ -0,0 +1,117 @@
+use crate::{
+    config,
+    config::tree::{keys, Gc, Key, Section},
+};
+
+impl Gc {
+    /// The `gc.auto` key.
+    pub const AUTO: Limit = Limit::new_with_validate("auto", &config::Tree::GC, validate::Limit)
+        .with_note("a value of 0 or less disables automatic maintenance");
+    /// The `gc.autoPackLimit` key.
+    pub const AUTO_PACK_LIMIT: Limit = Limit::new_with_validate("autoPackLimit", &config::Tree::GC, validate::Limit)
+        .with_note("a value of 0 or less disables checking the amount of packs");
+    /// The `gc.reflogExpire` key, which is also used as `gc.<pattern>.reflogExpire` for references matching `<pattern>`.
+    pub const REFLOG_EXPIRE: Expiry = Expiry::new_with_validate("reflogExpire", &config::Tree::GC, validate::Expiry)
+        .with_subsection_requirement(None);
+    /// The `gc.reflogExpireUnreachable` key, which is also used as `gc.<pattern>.reflogExpireUnreachable` for references
+    /// matching `<pattern>`.
+    pub const REFLOG_EXPIRE_UNREACHABLE: Expiry =
+        Expiry::new_with_validate("reflogExpireUnreachable", &config::Tree::GC, validate::Expiry)
+            .with_subsection_requirement(None);
+}
+
+/// The `gc.auto` and `gc.autoPackLimit` keys.
+pub type Limit = keys::Any<validate::Limit>;
+
+/// The `gc.reflogExpire` and `gc.reflogExpireUnreachable` keys.
+pub type Expiry = keys::Any<validate::Expiry>;
+
+mod limit {
+    use crate::config::tree::gc::Limit;
+
+    impl Limit {
+        /// Return the limit after decoding it from `value`, with `0` meaning that it's disabled.
+        pub fn try_into_limit(
+            &'static self,
+            value: Result<i64, gix_config::value::Error>,
+        ) -> Result<usize, crate::config::key::GenericError> {
+            match value {
+                Ok(v) if v <= 0 => Ok(0),
+                Ok(v) => Ok(v.try_into().unwrap_or(usize::MAX)),
+                Err(err) => Err(crate::config::key::GenericError::from(self).with_source(err)),
+            }
+        }
+    }
+}
+
+mod expiry {
+    use std::borrow::Cow;
+
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        config::tree::gc::Expiry,
+        reference::log::expire::Cutoff,
+    };
+
+    impl Expiry {
+        /// Return the point in time before which reflog entries expire after decoding it from `value`, with `now` being
+        /// the reference time for relative dates like `90.days.ago`.
+        pub fn try_into_cutoff(
+            &'static self,
+            value: Cow<'_, BStr>,
+            now: Option<std::time::SystemTime>,
+        ) -> Result<Cutoff, crate::config::time::Error> {
+            let invalid = || crate::config::time::Error::from_value(self, value.clone().into_owned());
+            let date = value.to_str().map_err(|_| invalid())?;
+            Ok(match date {
+                "never" | "false" => Cutoff::Never,
+                "all" | "now" => Cutoff::Always,
+                _ => Cutoff::OlderThan(
+                    gix_date::parse(date, now)
+                        .or_else(|_| gix_date::parse(&date.replace('.', " "), now))
+                        .map_err(|err| invalid().with_source(err))?
+                        .seconds,
+                ),
+            })
+        }
+    }
+}
+
+impl Section for Gc {
+    fn name(&self) -> &str {
+        "gc"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[
+            &Self::AUTO,
+            &Self::AUTO_PACK_LIMIT,
+            &Self::REFLOG_EXPIRE,
+            &Self::REFLOG_EXPIRE_UNREACHABLE,
+        ]
+    }
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod validate {
+    use crate::{bstr::BStr, config::tree::keys};
+
+    pub struct Expiry;
+    impl keys::Validate for Expiry {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            crate::config::tree::Gc::REFLOG_EXPIRE.try_into_cutoff(value.into(), Some(std::time::SystemTime::now()))?;
+            Ok(())
+        }
+    }
+
+    pub struct Limit;
+    impl keys::Validate for Limit {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            gix_config::Integer::try_from(value)?
+                .to_decimal()
+                .ok_or_else(|| format!("integer {value} cannot be represented as integer"))?;
+            Ok(())
+        }
+    }
+}
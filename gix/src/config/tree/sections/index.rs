@@ -10,11 +10,56 @@ impl Index {
     /// The `index.skipHash` key.
     pub const SKIP_HASH: keys::Boolean = keys::Boolean::new_boolean("skipHash", &config::Tree::INDEX)
         .with_deviation("also used to skip the hash when reading, even if a hash exists in the index file");
+    /// The `index.version` key.
+    pub const VERSION: IndexVersion =
+        IndexVersion::new_with_validate("version", &config::Tree::INDEX, validate::IndexVersion)
+            .with_environment_override("GIT_INDEX_VERSION");
 }
 
 /// The `index.threads` key.
 pub type IndexThreads = keys::Any<validate::IndexThreads>;
 
+/// The `index.version` key.
+pub type IndexVersion = keys::Any<validate::IndexVersion>;
+
+mod index_version {
+    use std::borrow::Cow;
+
+    use crate::{
+        bstr::BStr,
+        config::{key::GenericErrorWithValue, tree::index::IndexVersion},
+    };
+
+    impl IndexVersion {
+        /// Parse `value` into the version number of the index format to write new indices with, which is 2, 3 or 4.
+        pub(crate) fn try_into_index_version_number(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<u8, GenericErrorWithValue> {
+            gix_config::Integer::try_from(value.as_ref())
+                .ok()
+                .and_then(|i| i.to_decimal())
+                .and_then(|i| u8::try_from(i).ok())
+                .filter(|v| (2..=4).contains(v))
+                .ok_or_else(|| GenericErrorWithValue::from_value(self, value.into_owned()))
+        }
+
+        /// Parse `value` into the version of the index format to write new indices with.
+        #[cfg(feature = "index")]
+        pub fn try_into_index_version(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<gix_index::Version, GenericErrorWithValue> {
+            Ok(match self.try_into_index_version_number(value)? {
+                2 => gix_index::Version::V2,
+                3 => gix_index::Version::V3,
+                4 => gix_index::Version::V4,
+                _ => unreachable!("BUG: the range was validated"),
+            })
+        }
+    }
+}
+
 mod index_threads {
     use std::borrow::Cow;
 
@@ -50,7 +95,7 @@ impl Section for Index {
     }
 
     fn keys(&self) -> &[&dyn Key] {
-        &[&Self::THREADS, &Self::SKIP_HASH]
+        &[&Self::THREADS, &Self::SKIP_HASH, &Self::VERSION]
     }
 }
 
@@ -64,4 +109,12 @@ mod validate {
             Ok(())
         }
     }
+
+    pub struct IndexVersion;
+    impl keys::Validate for IndexVersion {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Index::VERSION.try_into_index_version_number(value.into())?;
+            Ok(())
+        }
+    }
 }
@@ -0,0 +1,84 @@
+use crate::config::{
+    tree::{keys, Key, Merge, Section, SubSectionRequirement},
+    Tree,
+};
+
+impl Merge {
+    /// The `merge.default` key.
+    pub const DEFAULT: keys::String = keys::String::new_string("default", &Tree::MERGE);
+    /// The `merge.directoryRenames` key.
+    pub const DIRECTORY_RENAMES: DirectoryRenames =
+        DirectoryRenames::new_with_validate("directoryRenames", &Tree::MERGE, validate::DirectoryRenames);
+    /// The `merge.<driver>.name` key.
+    pub const DRIVER_NAME: keys::String = keys::String::new_string("name", &Tree::MERGE)
+        .with_subsection_requirement(Some(SubSectionRequirement::Parameter("driver")));
+    /// The `merge.<driver>.driver` key.
+    pub const DRIVER_COMMAND: keys::Program = keys::Program::new_program("driver", &Tree::MERGE)
+        .with_subsection_requirement(Some(SubSectionRequirement::Parameter("driver")));
+    /// The `merge.<driver>.recursive` key.
+    pub const DRIVER_RECURSIVE: keys::String = keys::String::new_string("recursive", &Tree::MERGE)
+        .with_subsection_requirement(Some(SubSectionRequirement::Parameter("driver")));
+}
+
+impl Section for Merge {
+    fn name(&self) -> &str {
+        "merge"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[
+            &Self::DEFAULT,
+            &Self::DIRECTORY_RENAMES,
+            &Self::DRIVER_NAME,
+            &Self::DRIVER_COMMAND,
+            &Self::DRIVER_RECURSIVE,
+        ]
+    }
+}
+
+/// The `merge.directoryRenames` key.
+pub type DirectoryRenames = keys::Any<validate::DirectoryRenames>;
+
+mod directory_renames {
+    use crate::{
+        bstr::ByteSlice,
+        config::{key::GenericError, tree::sections::merge::DirectoryRenames},
+    };
+
+    impl DirectoryRenames {
+        /// Try to convert the configuration into the way paths added to renamed directories should be handled.
+        /// Use `value` and if it's an error, interpret the boolean as string.
+        pub fn try_into_directory_renames(
+            &'static self,
+            value: Result<bool, gix_config::value::Error>,
+        ) -> Result<gix_merge::tree::DirectoryRenames, GenericError> {
+            Ok(match value {
+                Ok(true) => gix_merge::tree::DirectoryRenames::Apply,
+                Ok(false) => gix_merge::tree::DirectoryRenames::Disabled,
+                Err(err) => {
+                    let value = &err.input;
+                    match value.as_bytes() {
+                        b"conflict" => gix_merge::tree::DirectoryRenames::Conflict,
+                        _ => return Err(GenericError::from_value(self, value.clone()).with_source(err)),
+                    }
+                }
+            })
+        }
+    }
+}
+
+mod validate {
+    use crate::{
+        bstr::BStr,
+        config::tree::{keys, Merge},
+    };
+
+    pub struct DirectoryRenames;
+    impl keys::Validate for DirectoryRenames {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            let boolean = gix_config::Boolean::try_from(value).map(|b| b.0);
+            Merge::DIRECTORY_RENAMES.try_into_directory_renames(boolean)?;
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{
+    config,
+    config::tree::{keys, Key, Receive, Section},
+};
+
+impl Receive {
+    /// The `receive.denyCurrentBranch` key.
+    pub const DENY_CURRENT_BRANCH: Deny =
+        Deny::new_with_validate("denyCurrentBranch", &config::Tree::RECEIVE, validate::Deny)
+            .with_note("'updateInstead' is refused like 'refuse' as updating the worktree isn't supported yet");
+    /// The `receive.denyDeleteCurrent` key.
+    pub const DENY_DELETE_CURRENT: Deny =
+        Deny::new_with_validate("denyDeleteCurrent", &config::Tree::RECEIVE, validate::Deny);
+    /// The `receive.denyDeletes` key.
+    pub const DENY_DELETES: keys::Boolean = keys::Boolean::new_boolean("denyDeletes", &config::Tree::RECEIVE);
+    /// The `receive.denyNonFastForwards` key.
+    pub const DENY_NON_FAST_FORWARDS: keys::Boolean =
+        keys::Boolean::new_boolean("denyNonFastForwards", &config::Tree::RECEIVE);
+    /// The `receive.hideRefs` key, which may be specified multiple times.
+    pub const HIDE_REFS: keys::Any = keys::Any::new("hideRefs", &config::Tree::RECEIVE);
+}
+
+impl Section for Receive {
+    fn name(&self) -> &str {
+        "receive"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[
+            &Self::DENY_CURRENT_BRANCH,
+            &Self::DENY_DELETE_CURRENT,
+            &Self::DENY_DELETES,
+            &Self::DENY_NON_FAST_FORWARDS,
+            &Self::HIDE_REFS,
+        ]
+    }
+}
+
+/// The `receive.denyCurrentBranch` and `receive.denyDeleteCurrent` key types.
+pub type Deny = keys::Any<validate::Deny>;
+
+mod deny {
+    use std::borrow::Cow;
+
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        config,
+        config::tree::receive::Deny,
+        receive_pack,
+    };
+
+    impl Deny {
+        /// Try to interpret `value` as one of the values of `receive.denyCurrentBranch` or `receive.denyDeleteCurrent`.
+        pub fn try_into_deny(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<receive_pack::Deny, config::key::GenericErrorWithValue> {
+            Ok(match value.as_ref().as_bytes() {
+                b"ignore" => receive_pack::Deny::Ignore,
+                b"warn" => receive_pack::Deny::Warn,
+                b"refuse" => receive_pack::Deny::Refuse,
+                b"updateInstead" => receive_pack::Deny::UpdateInstead,
+                _ => match gix_config::Boolean::try_from(value.as_ref()) {
+                    Ok(gix_config::Boolean(true)) => receive_pack::Deny::Refuse,
+                    Ok(gix_config::Boolean(false)) => receive_pack::Deny::Ignore,
+                    Err(_) => return Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned())),
+                },
+            })
+        }
+    }
+}
+
+mod validate {
+    pub struct Deny;
+    use std::{borrow::Cow, error::Error};
+
+    use crate::{bstr::BStr, config::tree::keys::Validate};
+
+    impl Validate for Deny {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+            super::Receive::DENY_CURRENT_BRANCH.try_into_deny(Cow::Borrowed(value))?;
+            Ok(())
+        }
+    }
+}
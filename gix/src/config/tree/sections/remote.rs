@@ -23,6 +23,18 @@ impl Remote {
     /// The `remote.<name>.push` key
     pub const PUSH: keys::PushRefSpec =
         keys::PushRefSpec::new_push_refspec("push", &config::Tree::REMOTE).with_subsection_requirement(NAME_PARAMETER);
+    /// The `remote.<name>.prune` key
+    pub const PRUNE: keys::Boolean =
+        keys::Boolean::new_boolean("prune", &config::Tree::REMOTE).with_subsection_requirement(NAME_PARAMETER);
+    /// The `remote.<name>.pruneTags` key
+    pub const PRUNE_TAGS: keys::Boolean =
+        keys::Boolean::new_boolean("pruneTags", &config::Tree::REMOTE).with_subsection_requirement(NAME_PARAMETER);
+    /// The `remote.<name>.promisor` key
+    pub const PROMISOR: keys::Boolean =
+        keys::Boolean::new_boolean("promisor", &config::Tree::REMOTE).with_subsection_requirement(NAME_PARAMETER);
+    /// The `remote.<name>.partialCloneFilter` key
+    pub const PARTIAL_CLONE_FILTER: keys::String = keys::String::new_string("partialCloneFilter", &config::Tree::REMOTE)
+        .with_subsection_requirement(NAME_PARAMETER);
     /// The `remote.<name>.proxy` key
     pub const PROXY: keys::String =
         keys::String::new_string("proxy", &config::Tree::REMOTE).with_subsection_requirement(NAME_PARAMETER);
@@ -46,6 +58,10 @@ impl Section for Remote {
             &Self::PUSH_URL,
             &Self::FETCH,
             &Self::PUSH,
+            &Self::PRUNE,
+            &Self::PRUNE_TAGS,
+            &Self::PROMISOR,
+            &Self::PARTIAL_CLONE_FILTER,
             &Self::PROXY,
             &Self::PROXY_AUTH_METHOD,
         ]
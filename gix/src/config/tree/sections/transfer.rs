@@ -0,0 +1,19 @@
+use crate::{
+    config,
+    config::tree::{keys, Key, Section, Transfer},
+};
+
+impl Transfer {
+    /// The `transfer.hideRefs` key, which may be specified multiple times.
+    pub const HIDE_REFS: keys::Any = keys::Any::new("hideRefs", &config::Tree::TRANSFER);
+}
+
+impl Section for Transfer {
+    fn name(&self) -> &str {
+        "transfer"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::HIDE_REFS]
+    }
+}
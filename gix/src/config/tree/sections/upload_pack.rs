@@ -0,0 +1,36 @@
+use crate::{
+    config,
+    config::tree::{keys, Key, Section, UploadPack},
+};
+
+impl UploadPack {
+    /// The `uploadpack.hideRefs` key, which may be specified multiple times.
+    pub const HIDE_REFS: keys::Any = keys::Any::new("hideRefs", &config::Tree::UPLOAD_PACK);
+    /// The `uploadpack.allowTipSHA1InWant` key.
+    pub const ALLOW_TIP_SHA1_IN_WANT: keys::Boolean =
+        keys::Boolean::new_boolean("allowTipSHA1InWant", &config::Tree::UPLOAD_PACK);
+    /// The `uploadpack.allowReachableSHA1InWant` key.
+    pub const ALLOW_REACHABLE_SHA1_IN_WANT: keys::Boolean =
+        keys::Boolean::new_boolean("allowReachableSHA1InWant", &config::Tree::UPLOAD_PACK);
+    /// The `uploadpack.allowAnySHA1InWant` key.
+    pub const ALLOW_ANY_SHA1_IN_WANT: keys::Boolean =
+        keys::Boolean::new_boolean("allowAnySHA1InWant", &config::Tree::UPLOAD_PACK);
+    /// The `uploadpack.allowFilter` key.
+    pub const ALLOW_FILTER: keys::Boolean = keys::Boolean::new_boolean("allowFilter", &config::Tree::UPLOAD_PACK);
+}
+
+impl Section for UploadPack {
+    fn name(&self) -> &str {
+        "uploadpack"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[
+            &Self::HIDE_REFS,
+            &Self::ALLOW_TIP_SHA1_IN_WANT,
+            &Self::ALLOW_REACHABLE_SHA1_IN_WANT,
+            &Self::ALLOW_ANY_SHA1_IN_WANT,
+            &Self::ALLOW_FILTER,
+        ]
+    }
+}
@@ -24,6 +24,23 @@ pub enum Error {
     DirectoryNotEmpty { path: PathBuf },
     #[error("Could not create directory at '{}'", .path.display())]
     CreateDirectory { source: std::io::Error, path: PathBuf },
+    #[error("Could not read the git directory location from '{}'", .path.display())]
+    ReadGitDirFile {
+        source: gix_discover::path::from_gitdir_file::Error,
+        path: PathBuf,
+    },
+    #[error("Could not move the existing git directory at '{}' to '{}'", .source_path.display(), .path.display())]
+    MoveGitDir {
+        source: std::io::Error,
+        source_path: PathBuf,
+        path: PathBuf,
+    },
+    #[error("Could not copy template at '{}' to '{}'", .source_path.display(), .path.display())]
+    CopyTemplate {
+        source: std::io::Error,
+        source_path: PathBuf,
+        path: PathBuf,
+    },
 }
 
 /// The kind of repository to create.
@@ -84,17 +101,18 @@ impl<'a> Drop for PathCursor<'a> {
     }
 }
 
-fn write_file(data: &[u8], path: &Path) -> Result<(), Error> {
-    let mut file = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .append(false)
-        .open(path)
-        .map_err(|e| Error::IoOpen {
-            source: e,
-            path: path.to_owned(),
-        })?;
+/// Write `data` to `path` unless it already exists, to never destroy existing data when reinitializing.
+fn write_file_if_new(data: &[u8], path: &Path) -> Result<(), Error> {
+    let mut file = match OpenOptions::new().write(true).create_new(true).open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => return Ok(()),
+        Err(err) => {
+            return Err(Error::IoOpen {
+                source: err,
+                path: path.to_owned(),
+            })
+        }
+    };
     file.write_all(data).map_err(|e| Error::IoWrite {
         source: e,
         path: path.to_owned(),
@@ -109,19 +127,33 @@ fn create_dir(p: &Path) -> Result<(), Error> {
 }
 
 /// Options for use in [`into()`];
-#[derive(Copy, Clone, Default)]
+#[derive(Clone, Default)]
 pub struct Options {
     /// If true, and the kind of repository to create has a worktree, then the destination directory must be empty.
     ///
-    /// By default repos with worktree can be initialized into a non-empty repository as long as there is no `.git` directory.
+    /// By default repos with worktree can be initialized into a non-empty repository, and an existing repository is reinitialized.
     pub destination_must_be_empty: bool,
     /// If set, use these filesystem capabilities to populate the respective git-config fields.
     /// If `None`, the directory will be probed.
     pub fs_capabilities: Option<gix_fs::Capabilities>,
+    /// If set, copy the contents of this directory into the git directory instead of using the built-in templates,
+    /// similar to `git init --template=<dir>`. An empty path disables templates entirely.
+    ///
+    /// If `None`, the built-in templates are used.
+    pub template_dir: Option<PathBuf>,
+    /// If set and the repository has a worktree, place the git directory at this path and write a `.git` file into the
+    /// worktree which points to it, similar to `git init --separate-git-dir=<dir>`.
+    ///
+    /// If the repository already exists, its git directory is moved to this path, just like `git` does when reinitializing.
+    pub separate_git_dir: Option<PathBuf>,
 }
 
 /// Create a new `.git` repository of `kind` within the possibly non-existing `directory`
 /// and return its path.
+///
+/// If there already is a repository, it will be reinitialized similar to `git init`, which creates all missing files and
+/// directories, but never overwrites existing ones.
+///
 /// Note that this is a simple template-based initialization routine which should be accompanied with additional corrections
 /// to respect git configuration, which is accomplished by [its callers][crate::ThreadSafeRepository::init_opts()]
 /// that return a [Repository][crate::Repository].
@@ -129,15 +161,26 @@ pub struct Options {
 pub fn into(
     directory: impl Into<PathBuf>,
     kind: Kind,
+    options: Options,
+) -> Result<gix_discover::repository::Path, Error> {
+    into_inner(directory.into(), kind, options).map(|(path, _reinitialized)| path)
+}
+
+/// Like [`into()`], but also return `true` if an existing repository was reinitialized.
+pub(crate) fn into_inner(
+    directory: PathBuf,
+    kind: Kind,
     Options {
         fs_capabilities,
         destination_must_be_empty,
+        template_dir,
+        separate_git_dir,
     }: Options,
-) -> Result<gix_discover::repository::Path, Error> {
-    let mut dot_git = directory.into();
+) -> Result<(gix_discover::repository::Path, bool), Error> {
+    let mut dot_git = directory;
     let bare = matches!(kind, Kind::Bare);
 
-    if bare || destination_must_be_empty {
+    if destination_must_be_empty || (bare && gix_discover::is_git(&dot_git).is_err()) {
         let num_entries_in_dot_git = fs::read_dir(&dot_git)
             .or_else(|err| {
                 if err.kind() == std::io::ErrorKind::NotFound {
@@ -156,37 +199,53 @@ pub fn into(
         }
     }
 
+    let mut work_dir = None;
     if !bare {
+        work_dir = Some(dot_git.clone());
         dot_git.push(DOT_GIT_DIR);
 
-        if dot_git.is_dir() {
-            return Err(Error::DirectoryExists { path: dot_git });
+        if let Some(git_dir) = separate_git_dir {
+            let git_dir = if git_dir.is_relative() {
+                std::env::current_dir()?.join(git_dir)
+            } else {
+                git_dir.to_owned()
+            };
+            let existing_git_dir = if dot_git.is_dir() {
+                Some(dot_git.clone())
+            } else if dot_git.is_file() {
+                Some(read_gitdir_file(&dot_git)?)
+            } else {
+                None
+            };
+            match existing_git_dir {
+                Some(existing_git_dir) if existing_git_dir != git_dir => fs::rename(&existing_git_dir, &git_dir)
+                    .map_err(|err| Error::MoveGitDir {
+                        source: err,
+                        source_path: existing_git_dir,
+                        path: git_dir.clone(),
+                    })?,
+                Some(_) => {}
+                None => create_dir(&git_dir)?,
+            }
+            create_dir(work_dir.as_deref().expect("set for non-bare repositories"))?;
+            let mut content = b"gitdir: ".to_vec();
+            content.extend_from_slice(&gix_path::into_bstr(git_dir.as_path()));
+            content.push(b'\n');
+            std::fs::write(&dot_git, content).map_err(|err| Error::IoWrite {
+                source: err,
+                path: dot_git.clone(),
+            })?;
+            dot_git = git_dir;
+        } else if dot_git.is_file() {
+            dot_git = read_gitdir_file(&dot_git)?;
         }
     };
     create_dir(&dot_git)?;
+    let reinitialized = dot_git.join("HEAD").is_file();
 
-    {
-        let mut cursor = NewDir(&mut dot_git).at("info")?;
-        write_file(TPL_INFO_EXCLUDE, PathCursor(cursor.as_mut()).at("exclude"))?;
-    }
-
-    {
-        let mut cursor = NewDir(&mut dot_git).at("hooks")?;
-        for (tpl, filename) in &[
-            (TPL_HOOKS_DOCS_URL, "docs.url"),
-            (TPL_HOOKS_PREPARE_COMMIT_MSG, "prepare-commit-msg.sample"),
-            (TPL_HOOKS_PRE_REBASE, "pre-rebase.sample"),
-            (TPL_HOOKS_PRE_PUSH, "pre-push.sample"),
-            (TPL_HOOKS_PRE_COMMIT, "pre-commit.sample"),
-            (TPL_HOOKS_PRE_MERGE_COMMIT, "pre-merge-commit.sample"),
-            (TPL_HOOKS_PRE_APPLYPATCH, "pre-applypatch.sample"),
-            (TPL_HOOKS_POST_UPDATE, "post-update.sample"),
-            (TPL_HOOKS_FSMONITOR_WATCHMAN, "fsmonitor-watchman.sample"),
-            (TPL_HOOKS_COMMIT_MSG, "commit-msg.sample"),
-            (TPL_HOOKS_APPLYPATCH_MSG, "applypatch-msg.sample"),
-        ] {
-            write_file(tpl, PathCursor(cursor.as_mut()).at(filename))?;
-        }
+    match template_dir {
+        Some(template_dir) => copy_templates(&template_dir, &dot_git)?,
+        None => write_builtin_templates(&mut dot_git)?,
     }
 
     {
@@ -201,14 +260,14 @@ pub fn into(
         create_dir(PathCursor(cursor.as_mut()).at("tags"))?;
     }
 
-    for (tpl, filename) in &[(TPL_HEAD, "HEAD"), (TPL_DESCRIPTION, "description")] {
-        write_file(tpl, PathCursor(&mut dot_git).at(filename))?;
-    }
+    write_file_if_new(TPL_HEAD, PathCursor(&mut dot_git).at("HEAD"))?;
 
     let caps = {
-        let mut config = gix_config::File::default();
-        let caps = {
-            let caps = fs_capabilities.unwrap_or_else(|| gix_fs::Capabilities::probe(&dot_git));
+        let caps = fs_capabilities.unwrap_or_else(|| gix_fs::Capabilities::probe(&dot_git));
+        let mut cursor = PathCursor(&mut dot_git);
+        let config_path = cursor.at("config");
+        if !config_path.is_file() {
+            let mut config = gix_config::File::default();
             let mut core = config.new_section("core", None).expect("valid section name");
 
             core.push(key("repositoryformatversion"), Some("0".into()));
@@ -218,27 +277,129 @@ pub fn into(
             core.push(key("symlinks"), Some(bool(caps.symlink).into()));
             core.push(key("ignorecase"), Some(bool(caps.ignore_case).into()));
             core.push(key("precomposeunicode"), Some(bool(caps.precompose_unicode).into()));
-            caps
-        };
-        let mut cursor = PathCursor(&mut dot_git);
-        let config_path = cursor.at("config");
-        std::fs::write(config_path, config.to_bstring()).map_err(|err| Error::IoWrite {
-            source: err,
-            path: config_path.to_owned(),
-        })?;
+            std::fs::write(config_path, config.to_bstring()).map_err(|err| Error::IoWrite {
+                source: err,
+                path: config_path.to_owned(),
+            })?;
+        }
         caps
     };
 
-    Ok(gix_discover::repository::Path::from_dot_git_dir(
-        dot_git,
-        if bare {
-            gix_discover::repository::Kind::PossiblyBare
-        } else {
-            gix_discover::repository::Kind::WorkTree { linked_git_dir: None }
+    let path = match work_dir {
+        Some(work_dir) if work_dir.join(DOT_GIT_DIR) != dot_git => gix_discover::repository::Path::LinkedWorkTree {
+            work_dir,
+            git_dir: dot_git,
         },
-        &gix_fs::current_dir(caps.precompose_unicode)?,
-    )
-    .expect("by now the `dot_git` dir is valid as we have accessed it"))
+        _ => gix_discover::repository::Path::from_dot_git_dir(
+            dot_git,
+            if bare {
+                gix_discover::repository::Kind::PossiblyBare
+            } else {
+                gix_discover::repository::Kind::WorkTree { linked_git_dir: None }
+            },
+            &gix_fs::current_dir(caps.precompose_unicode)?,
+        )
+        .expect("by now the `dot_git` dir is valid as we have accessed it"),
+    };
+    Ok((path, reinitialized))
+}
+
+fn read_gitdir_file(path: &Path) -> Result<PathBuf, Error> {
+    gix_discover::path::from_gitdir_file(path).map_err(|err| Error::ReadGitDirFile {
+        source: err,
+        path: path.to_owned(),
+    })
+}
+
+/// Write the templates that are built into the library into `dot_git`, without overwriting existing files.
+pub(crate) fn write_builtin_templates(dot_git: &mut PathBuf) -> Result<(), Error> {
+    {
+        let mut cursor = NewDir(dot_git).at("info")?;
+        write_file_if_new(TPL_INFO_EXCLUDE, PathCursor(cursor.as_mut()).at("exclude"))?;
+    }
+
+    {
+        let mut cursor = NewDir(dot_git).at("hooks")?;
+        for (tpl, filename) in &[
+            (TPL_HOOKS_DOCS_URL, "docs.url"),
+            (TPL_HOOKS_PREPARE_COMMIT_MSG, "prepare-commit-msg.sample"),
+            (TPL_HOOKS_PRE_REBASE, "pre-rebase.sample"),
+            (TPL_HOOKS_PRE_PUSH, "pre-push.sample"),
+            (TPL_HOOKS_PRE_COMMIT, "pre-commit.sample"),
+            (TPL_HOOKS_PRE_MERGE_COMMIT, "pre-merge-commit.sample"),
+            (TPL_HOOKS_PRE_APPLYPATCH, "pre-applypatch.sample"),
+            (TPL_HOOKS_POST_UPDATE, "post-update.sample"),
+            (TPL_HOOKS_FSMONITOR_WATCHMAN, "fsmonitor-watchman.sample"),
+            (TPL_HOOKS_COMMIT_MSG, "commit-msg.sample"),
+            (TPL_HOOKS_APPLYPATCH_MSG, "applypatch-msg.sample"),
+        ] {
+            write_file_if_new(tpl, PathCursor(cursor.as_mut()).at(filename))?;
+        }
+    }
+
+    write_file_if_new(TPL_DESCRIPTION, PathCursor(dot_git).at("description"))
+}
+
+/// Copy the contents of `template_dir` into `dot_git` recursively, without overwriting existing files.
+///
+/// Similar to `git`, entries starting with a dot are skipped, and nothing happens if `template_dir` is empty or doesn't exist.
+///
+/// # Deviation
+///
+/// A `config` file in the template directory isn't merged into the repository configuration, but is ignored.
+pub(crate) fn copy_templates(template_dir: &Path, dot_git: &Path) -> Result<(), Error> {
+    if template_dir.as_os_str().is_empty() {
+        return Ok(());
+    }
+    let entries = match fs::read_dir(template_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            gix_trace::warn!("templates not found in {}", template_dir.display());
+            return Ok(());
+        }
+        Err(err) => {
+            return Err(Error::IoOpen {
+                source: err,
+                path: template_dir.to_owned(),
+            })
+        }
+    };
+    let is_top_level = true;
+    copy_template_entries(entries, dot_git, is_top_level)
+}
+
+fn copy_template_entries(entries: fs::ReadDir, dest: &Path, is_top_level: bool) -> Result<(), Error> {
+    for entry in entries {
+        let entry = entry.map_err(|err| Error::IoOpen {
+            source: err,
+            path: dest.to_owned(),
+        })?;
+        let name = entry.file_name();
+        if name.to_string_lossy().starts_with('.') || (is_top_level && name == "config") {
+            continue;
+        }
+        let source_path = entry.path();
+        let dest_path = dest.join(&name);
+        let copy_err = |err: std::io::Error| Error::CopyTemplate {
+            source: err,
+            source_path: source_path.clone(),
+            path: dest_path.clone(),
+        };
+        let file_type = entry.file_type().map_err(copy_err)?;
+        if file_type.is_dir() {
+            create_dir(&dest_path)?;
+            let entries = fs::read_dir(&source_path).map_err(copy_err)?;
+            copy_template_entries(entries, &dest_path, false)?;
+        } else if dest_path.symlink_metadata().is_ok() {
+            continue;
+        } else if file_type.is_symlink() {
+            let target = fs::read_link(&source_path).map_err(copy_err)?;
+            gix_fs::symlink::create(&target, &dest_path).map_err(copy_err)?;
+        } else {
+            fs::copy(&source_path, &dest_path).map_err(copy_err)?;
+        }
+    }
+    Ok(())
 }
 
 fn key(name: &'static str) -> section::ValueName<'static> {
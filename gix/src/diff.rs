@@ -17,10 +17,13 @@ pub mod rename {
     }
 }
 
+#[cfg(feature = "blob-diff")]
+pub mod binary_to_text_cache;
+
 ///
 #[cfg(feature = "blob-diff")]
 mod utils {
-    use gix_diff::{rewrites::Copies, Rewrites};
+    use gix_diff::{blob::unified_diff, rewrites::Copies, Rewrites};
 
     use crate::{
         config::{cache::util::ApplyLeniency, tree::Diff},
@@ -42,6 +45,18 @@ mod utils {
         }
     }
 
+    ///
+    #[allow(clippy::empty_docs)]
+    pub mod new_unified_diff_options {
+        /// The error returned by [`new_unified_diff_options()`](super::new_unified_diff_options()).
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            #[error(transparent)]
+            ConfigDiffContext(#[from] crate::config::unsigned_integer::Error),
+        }
+    }
+
     ///
     #[allow(clippy::empty_docs)]
     pub mod resource_cache {
@@ -100,6 +115,34 @@ mod utils {
         .into())
     }
 
+    /// Create options for producing unified diffs by reading `diff.context` and `diff.interHunkContext` from the `config`uration,
+    /// while being `lenient` or not.
+    ///
+    /// Values that aren't configured are defaulted like `git` does, and whitespace differences aren't ignored as there is
+    /// no configuration for that.
+    #[allow(clippy::result_large_err)]
+    pub fn new_unified_diff_options(
+        config: &gix_config::File<'static>,
+        lenient: bool,
+    ) -> Result<unified_diff::Options, new_unified_diff_options::Error> {
+        let default = unified_diff::Options::default();
+        Ok(unified_diff::Options {
+            context_size: config
+                .integer("diff.context")
+                .map(|value| Diff::CONTEXT.try_into_u32(value))
+                .transpose()
+                .with_leniency(lenient)?
+                .unwrap_or(default.context_size),
+            inter_hunk_context: config
+                .integer("diff.interHunkContext")
+                .map(|value| Diff::INTER_HUNK_CONTEXT.try_into_u32(value))
+                .transpose()
+                .with_leniency(lenient)?
+                .unwrap_or(default.inter_hunk_context),
+            ..default
+        })
+    }
+
     /// Return a low-level utility to efficiently prepare a blob-level diff operation between two resources,
     /// and cache these diffable versions so that matrix-like MxN diffs are efficient.
     ///
@@ -134,4 +177,4 @@ mod utils {
     }
 }
 #[cfg(feature = "blob-diff")]
-pub use utils::{new_rewrites, resource_cache};
+pub use utils::{new_rewrites, new_unified_diff_options, resource_cache};
@@ -0,0 +1,263 @@
+//! A cache for the output of binary-to-text conversion programs, stored in notes at `refs/notes/textconv/<driver>`
+//! just like `git` does it for drivers with `diff.<driver>.cachetextconv` set.
+use std::{collections::BTreeMap, sync::Mutex};
+
+use gix_hash::ObjectId;
+use gix_object::{
+    bstr::{BString, ByteSlice},
+    tree::EntryKind,
+    FindExt,
+};
+use gix_ref::transaction::PreviousValue;
+
+use crate::{bstr::BStr, Repository};
+
+/// The prefix of the notes references of each driver.
+const REF_PREFIX: &str = "refs/notes/textconv/";
+
+/// A [binary-to-text cache](gix_diff::blob::pipeline::BinaryToTextCache) which is compatible with the one of `git`,
+/// for use in [`Pipeline::binary_to_text_cache`](gix_diff::blob::Pipeline::binary_to_text_cache).
+///
+/// Conversions already cached by `git` or a previous [write](Notes::write()) are available right away, while new
+/// conversions are only written as objects, to become visible to others once [`Notes::write()`] is called.
+pub struct Notes {
+    state: Mutex<State>,
+}
+
+struct State {
+    objects: gix_odb::HandleArc,
+    drivers: Vec<Driver>,
+}
+
+/// The cache of a single driver.
+struct Driver {
+    name: BString,
+    /// The conversion command, as the cache is only valid for the command that produced it.
+    command: BString,
+    /// The name of the notes reference.
+    ref_name: gix_ref::FullName,
+    /// The tree with all notes as written by the last commit, if it was valid for `command`.
+    tree: Option<ObjectId>,
+    /// Conversions which aren't yet part of `tree`, as blob ids by the id of the object they were converted from.
+    added: BTreeMap<ObjectId, ObjectId>,
+}
+
+impl Driver {
+    fn is_valid_for(&self, driver: &gix_diff::blob::Driver) -> bool {
+        self.name == driver.name && driver.binary_to_text_command.as_ref() == Some(&self.command)
+    }
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod init {
+    /// The error returned by [Repository::diff_binary_to_text_cache()](crate::Repository::diff_binary_to_text_cache()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        DiffDrivers(#[from] crate::config::diff::drivers::Error),
+        #[error("Could not create a thread-safe handle to the object database")]
+        OpenObjectDatabase(#[from] std::io::Error),
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::Error),
+    }
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod write {
+    /// The error returned by [Notes::write()](super::Notes::write()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindExistingTree(#[from] gix_object::find::existing_iter::Error),
+        #[error(transparent)]
+        DecodeTree(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        Commit(#[from] crate::commit::Error),
+    }
+}
+
+/// Lifecycle
+impl Notes {
+    pub(crate) fn new(repo: &Repository) -> Result<Self, init::Error> {
+        let mut drivers = Vec::new();
+        for driver in repo.config.diff_drivers()? {
+            let Some(command) = driver.binary_to_text_command.filter(|_| driver.cache_binary_to_text) else {
+                continue;
+            };
+            let Ok(ref_name) = gix_ref::FullName::try_from(format!("{REF_PREFIX}{}", driver.name)) else {
+                continue;
+            };
+            let tree = repo
+                .try_find_reference(&ref_name)?
+                .and_then(|mut reference| reference.peel_to_id_in_place().ok())
+                .and_then(|id| id.object().ok()?.try_into_commit().ok())
+                .filter(|commit| {
+                    // Like `git`, use the notes only if the subject of their commit matches the current command.
+                    matches!(commit.message(), Ok(message) if message.summary().trim() == command.as_slice())
+                })
+                .and_then(|commit| commit.tree_id().ok())
+                .map(crate::Id::detach);
+            drivers.push(Driver {
+                name: driver.name,
+                command,
+                ref_name,
+                tree,
+                added: Default::default(),
+            });
+        }
+        Ok(Notes {
+            state: Mutex::new(State {
+                objects: repo.objects.clone().into_arc()?,
+                drivers,
+            }),
+        })
+    }
+}
+
+impl Notes {
+    /// Commit all conversions that were added since the last call as notes of the respective driver, just like `git` would,
+    /// and return the amount of updated notes references.
+    ///
+    /// Objects and references are written to `repo`, which is assumed to be the repository this instance was created from.
+    pub fn write(&self, repo: &Repository) -> Result<usize, write::Error> {
+        let mut state = self.state.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let State { objects, drivers } = &mut *state;
+        let mut num_updated_refs = 0;
+        for driver in drivers.iter_mut().filter(|d| !d.added.is_empty()) {
+            let mut notes = BTreeMap::new();
+            if let Some(tree) = driver.tree {
+                collect_notes(objects, tree, &mut BString::default(), &mut notes)?;
+            }
+            notes.extend(
+                std::mem::take(&mut driver.added)
+                    .into_iter()
+                    .map(|(id, note)| (BString::from(id.to_hex().to_string()), note)),
+            );
+            // As we write no fan-out, the entries are sorted like a tree wants them.
+            let tree = gix_object::Tree {
+                entries: notes
+                    .into_iter()
+                    .map(|(filename, oid)| gix_object::tree::Entry {
+                        mode: EntryKind::Blob.into(),
+                        filename,
+                        oid,
+                    })
+                    .collect(),
+            };
+            let tree_id = repo.write_object(&tree).map_err(crate::commit::Error::from)?.detach();
+
+            let committer = repo
+                .committer()
+                .ok_or(crate::commit::Error::CommitterMissing)?
+                .map_err(crate::commit::Error::from)?;
+            let commit = gix_object::Commit {
+                tree: tree_id,
+                parents: Default::default(),
+                author: committer.into(),
+                committer: committer.into(),
+                encoding: None,
+                message: driver.command.clone(),
+                extra_headers: Default::default(),
+            };
+            let commit_id = repo.write_object(&commit).map_err(crate::commit::Error::from)?;
+            repo.reference(
+                driver.ref_name.clone(),
+                commit_id,
+                PreviousValue::Any,
+                "update notes cache",
+            )
+            .map_err(crate::commit::Error::from)?;
+            driver.tree = Some(tree_id);
+            num_updated_refs += 1;
+        }
+        Ok(num_updated_refs)
+    }
+}
+
+impl gix_diff::blob::pipeline::BinaryToTextCache for Notes {
+    fn get(&self, driver: &gix_diff::blob::Driver, id: &gix_hash::oid, out: &mut Vec<u8>) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let State { objects, drivers } = &mut *state;
+        let Some(driver) = drivers.iter().find(|d| d.is_valid_for(driver)) else {
+            return false;
+        };
+        let Some(note) = driver
+            .added
+            .get(id)
+            .copied()
+            .or_else(|| find_note(objects, driver.tree?, id))
+        else {
+            return false;
+        };
+        match objects.find_blob(&note, out) {
+            Ok(_) => true,
+            Err(_) => {
+                out.clear();
+                false
+            }
+        }
+    }
+
+    fn put(&self, driver: &gix_diff::blob::Driver, id: &gix_hash::oid, data: &[u8]) {
+        use gix_odb::Write;
+
+        let mut state = self.state.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let State { objects, drivers } = &mut *state;
+        let Some(driver) = drivers.iter_mut().find(|d| d.is_valid_for(driver)) else {
+            return;
+        };
+        if let Ok(note) = objects.write_buf(gix_object::Kind::Blob, data) {
+            driver.added.insert(id.to_owned(), note);
+        }
+    }
+}
+
+/// Find the note for `id` in the notes `tree`, which may use any amount of fan-out directories.
+fn find_note(objects: &gix_odb::HandleArc, mut tree: ObjectId, id: &gix_hash::oid) -> Option<ObjectId> {
+    let hex = id.to_hex().to_string();
+    let mut remaining: &BStr = hex.as_bytes().as_bstr();
+    let mut buf = Vec::new();
+    loop {
+        let mut subtree = None;
+        for entry in objects.find_tree_iter(&tree, &mut buf).ok()? {
+            let entry = entry.ok()?;
+            if entry.mode.is_blob() && entry.filename == remaining {
+                return Some(entry.oid.to_owned());
+            }
+            if entry.mode.is_tree() && remaining.len() > 2 && entry.filename == remaining[..2].as_bstr() {
+                subtree = Some(entry.oid.to_owned());
+            }
+        }
+        tree = subtree?;
+        remaining = remaining[2..].as_bstr();
+    }
+}
+
+/// Put all notes in `tree` into `out`, keyed by the full hexadecimal id of the object they belong to.
+fn collect_notes(
+    objects: &gix_odb::HandleArc,
+    tree: ObjectId,
+    prefix: &mut BString,
+    out: &mut BTreeMap<BString, ObjectId>,
+) -> Result<(), write::Error> {
+    let mut buf = Vec::new();
+    let entries: Vec<_> = objects
+        .find_tree_iter(&tree, &mut buf)?
+        .map(|entry| entry.map(gix_object::tree::EntryRef::into))
+        .collect::<Result<Vec<gix_object::tree::Entry>, _>>()?;
+    for entry in entries {
+        let prefix_len = prefix.len();
+        prefix.extend_from_slice(&entry.filename);
+        if entry.mode.is_tree() {
+            collect_notes(objects, entry.oid, prefix, out)?;
+        } else if entry.mode.is_blob() {
+            out.insert(prefix.clone(), entry.oid);
+        }
+        prefix.truncate(prefix_len);
+    }
+    Ok(())
+}
@@ -1,10 +1,14 @@
 #![allow(clippy::result_large_err)]
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub use gix_discover::*;
 use gix_macros::momo;
 
-use crate::{bstr::BString, ThreadSafeRepository};
+use crate::{
+    bstr::BString,
+    config::tree::{Core, Key},
+    ThreadSafeRepository,
+};
 
 /// The error returned by [`crate::discover()`].
 #[derive(Debug, thiserror::Error)]
@@ -37,15 +41,24 @@ impl ThreadSafeRepository {
         directory: impl AsRef<Path>,
         options: upwards::Options<'_>,
         trust_map: gix_sec::trust::Mapping<crate::open::Options>,
+    ) -> Result<Self, Error> {
+        Self::discover_with_worktree_override(directory.as_ref(), options, trust_map, None)
+    }
+
+    fn discover_with_worktree_override(
+        directory: &Path,
+        options: upwards::Options<'_>,
+        trust_map: gix_sec::trust::Mapping<crate::open::Options>,
+        worktree_dir_override: Option<PathBuf>,
     ) -> Result<Self, Error> {
         let _span = gix_trace::coarse!("ThreadSafeRepository::discover()");
-        let (path, trust) = upwards_opts(directory.as_ref(), options)?;
+        let (path, trust) = upwards_opts(directory, options)?;
         let (git_dir, worktree_dir) = path.into_repository_and_work_tree_directories();
         let mut options = trust_map.into_value_by_level(trust);
         options.git_dir_trust = trust.into();
         // Note that we will adjust the `current_dir` later so it matches the value of `core.precomposeUnicode`.
         options.current_dir = Some(gix_fs::current_dir(false).map_err(upwards::Error::CurrentDir)?);
-        Self::open_from_paths(git_dir, worktree_dir, options).map_err(Into::into)
+        Self::open_from_paths(git_dir, worktree_dir, worktree_dir_override, options).map_err(Into::into)
     }
 
     /// Try to open a git repository directly from the environment.
@@ -61,6 +74,7 @@ impl ThreadSafeRepository {
     ///
     /// - `GIT_DISCOVERY_ACROSS_FILESYSTEM`
     /// - `GIT_CEILING_DIRECTORIES`
+    /// - `GIT_WORK_TREE`, which takes precedence over `core.worktree` and the worktree implied by the discovered repository.
     ///
     /// Finally, use the `trust_map` to determine which of our own repository options to use
     /// based on the trust level of the effective repository directory.
@@ -94,6 +108,7 @@ impl ThreadSafeRepository {
         }
 
         options = apply_additional_environment(options.apply_environment());
-        Self::discover_opts(directory, options, trust_map)
+        let worktree_dir_override = std::env::var_os(Core::WORKTREE.the_environment_override()).map(PathBuf::from);
+        Self::discover_with_worktree_override(directory.as_ref(), options, trust_map, worktree_dir_override)
     }
 }
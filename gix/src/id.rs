@@ -41,27 +41,68 @@ impl<'repo> Id<'repo> {
     }
 
     /// Turn this object id into a shortened id with a length in hex as configured by `core.abbrev`.
+    ///
+    /// Use [`Repository::id_shortener()`](crate::Repository::id_shortener()) to shorten many ids.
     pub fn shorten(&self) -> Result<gix_hash::Prefix, shorten::Error> {
-        let hex_len = self.repo.config.hex_len.map_or_else(
-            || self.repo.objects.packed_object_count().map(calculate_auto_hex_len),
-            Ok,
-        )?;
+        self.repo.id_shortener()?.shorten(self.inner)
+    }
+
+    /// Turn this object id into a shortened id with a length in hex as configured by `core.abbrev`, or default
+    /// to a prefix which equals our id in the unlikely error case.
+    pub fn shorten_or_id(&self) -> gix_hash::Prefix {
+        self.shorten().unwrap_or_else(|_| self.inner.into())
+    }
+}
+
+/// A utility to shorten object ids for display as configured by `core.abbrev`, see
+/// [`Repository::id_shortener()`](crate::Repository::id_shortener()).
+#[derive(Clone, Copy)]
+pub struct Shortener<'repo> {
+    repo: &'repo crate::Repository,
+    hex_len: usize,
+}
+
+impl<'repo> Shortener<'repo> {
+    pub(crate) fn new(repo: &'repo crate::Repository) -> Result<Self, shorten::Error> {
+        let hex_len = match repo.config.hex_len {
+            Some(hex_len) => hex_len,
+            None => *repo
+                .config
+                .auto_hex_len
+                .get_or_try_init(|| repo.objects.packed_object_count().map(calculate_auto_hex_len))?,
+        };
+        Ok(Shortener { repo, hex_len })
+    }
+
+    /// Return the least amount of hexadecimal characters of shortened ids.
+    pub fn hex_len(&self) -> usize {
+        self.hex_len
+    }
 
-        let prefix = gix_odb::store::prefix::disambiguate::Candidate::new(self.inner, hex_len)
+    /// Shorten `id` to [`hex_len()`](Self::hex_len()) characters, or more if this is needed for it to be unambiguous.
+    /// Fail if `id` doesn't exist in the object database.
+    pub fn shorten(&self, id: impl Into<ObjectId>) -> Result<gix_hash::Prefix, shorten::Error> {
+        let id = id.into();
+        let prefix = gix_odb::store::prefix::disambiguate::Candidate::new(id, self.hex_len.min(id.kind().len_in_hex()))
             .expect("BUG: internal hex-len must always be valid");
         self.repo
             .objects
             .disambiguate_prefix(prefix)?
-            .ok_or(shorten::Error::NotFound { oid: self.inner })
+            .ok_or(shorten::Error::NotFound { oid: id })
     }
 
-    /// Turn this object id into a shortened id with a length in hex as configured by `core.abbrev`, or default
-    /// to a prefix which equals our id in the unlikely error case.
-    pub fn shorten_or_id(&self) -> gix_hash::Prefix {
-        self.shorten().unwrap_or_else(|_| self.inner.into())
+    /// Like [`shorten()`](Self::shorten()), but default to a prefix which equals `id` in the unlikely error case,
+    /// which makes it suitable for display.
+    pub fn shorten_or_id(&self, id: impl Into<ObjectId>) -> gix_hash::Prefix {
+        let id = id.into();
+        self.shorten(id).unwrap_or_else(|_| id.into())
     }
 }
 
+/// Compute the amount of hexadecimal characters to abbreviate object ids to just like `git` does for `core.abbrev=auto`.
+///
+/// With `2^n` objects we can expect a collision after `2^(n/2)` objects, and as each hexadecimal character holds 4 bits,
+/// we divide the amount of bits of the object count by two and round up, but use at least 7 characters.
 fn calculate_auto_hex_len(num_packed_objects: u64) -> usize {
     let mut len = 64 - num_packed_objects.leading_zeros();
     len = (len + 1) / 2;
@@ -199,7 +240,7 @@ mod tests {
     #[test]
     fn size_of_oid() {
         let actual = std::mem::size_of::<Id<'_>>();
-        let ceiling = 32;
+        let ceiling = 48;
         assert!(
             actual <= ceiling,
             "size of oid shouldn't change without notice: {actual} <= {ceiling}"
@@ -0,0 +1,288 @@
+//! Determine the identity of authors and committers the way `git` does when creating commits.
+use std::ffi::OsString;
+
+use crate::{
+    bstr::{BString, ByteSlice, ByteVec},
+    config,
+    config::tree::{gitoxide, keys, Author, Committer, Key, User},
+};
+
+/// The reason for needing an identity, which determines the environment variables and configuration keys to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Purpose {
+    /// The identity of the author of a commit, configured with `GIT_AUTHOR_*` and `author.*`.
+    Author,
+    /// The identity of the committer of a commit, configured with `GIT_COMMITTER_*` and `committer.*`.
+    Committer,
+}
+
+impl std::fmt::Display for Purpose {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Purpose::Author => "Author",
+            Purpose::Committer => "Committer",
+        })
+    }
+}
+
+/// Provide access to the environment of the process, to allow faking it for deterministic results.
+pub trait Environment {
+    /// Return the value of the environment variable `name`, or `None` if it isn't set.
+    fn var(&self, name: &str) -> Option<OsString>;
+    /// Return the login name of the current user, like `jdoe`.
+    fn user_name(&self) -> Option<BString>;
+    /// Return the full name of the current user, like `Jane Doe`, as configured on the system.
+    fn full_name(&self) -> Option<BString>;
+    /// Return the name of the host, which should be fully qualified if possible, like `laptop.example.com`.
+    fn host_name(&self) -> Option<BString>;
+}
+
+/// The [`Environment`] of the current process.
+///
+/// ### Deviation
+///
+/// * The login name is obtained from the `USER` or `LOGNAME` environment variables, or `USERNAME` on Windows.
+/// * The full name is only read from `/etc/passwd` on unix systems.
+/// * The host name isn't qualified with its domain by resolving it, so it's only fully qualified if configured that way.
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Process;
+
+impl Environment for Process {
+    fn var(&self, name: &str) -> Option<OsString> {
+        std::env::var_os(name)
+    }
+
+    fn user_name(&self) -> Option<BString> {
+        let vars: &[&str] = if cfg!(windows) {
+            &["USERNAME"]
+        } else {
+            &["USER", "LOGNAME"]
+        };
+        vars.iter()
+            .filter_map(|name| self.var(name))
+            .find_map(|name| gix_path::os_string_into_bstring(name).ok())
+            .filter(|name| !name.is_empty())
+    }
+
+    fn full_name(&self) -> Option<BString> {
+        if cfg!(windows) {
+            return None;
+        }
+        let user_name = self.user_name()?;
+        let passwd = std::fs::read("/etc/passwd").ok()?;
+        let gecos = passwd.lines().find_map(|line| {
+            let mut fields = line.split_str(":");
+            (fields.next()? == user_name.as_slice())
+                .then(|| fields.nth(3))
+                .flatten()
+        })?;
+        Some(expand_gecos(gecos, user_name.as_ref()))
+    }
+
+    fn host_name(&self) -> Option<BString> {
+        if cfg!(windows) {
+            let host = gix_path::os_string_into_bstring(self.var("COMPUTERNAME")?).ok()?;
+            return Some(
+                match self
+                    .var("USERDNSDOMAIN")
+                    .and_then(|domain| gix_path::os_string_into_bstring(domain).ok())
+                {
+                    Some(domain) if !domain.is_empty() => {
+                        let mut qualified = host;
+                        qualified.push_byte(b'.');
+                        qualified.push_str(&domain);
+                        qualified
+                    }
+                    _ => host,
+                },
+            );
+        }
+        ["/proc/sys/kernel/hostname", "/etc/hostname"]
+            .iter()
+            .filter_map(|path| std::fs::read(path).ok())
+            .map(|name| name.trim().as_bstr().to_owned())
+            .find(|name| !name.is_empty())
+    }
+}
+
+/// The advice `git` gives when it can't determine an identity.
+pub const GUIDANCE: &str = "*** Please tell me who you are.
+
+Run
+
+  git config --global user.email \"you@example.com\"
+  git config --global user.name \"Your Name\"
+
+to set your account's default identity.
+Omit --global to set the identity only in this repository.";
+
+///
+#[allow(clippy::empty_docs)]
+pub mod resolve {
+    use crate::{bstr::BString, config, identity::Purpose};
+
+    /// The error returned by [`resolve()`](super::resolve()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("{purpose} identity unknown: {reason}\n\n{}", super::GUIDANCE)]
+        MissingName { purpose: Purpose, reason: BString },
+        #[error("{purpose} identity unknown: {reason}\n\n{}", super::GUIDANCE)]
+        MissingEmail { purpose: Purpose, reason: BString },
+        #[error(transparent)]
+        Time(#[from] config::time::Error),
+        #[error(transparent)]
+        ConfigBoolean(#[from] config::boolean::Error),
+    }
+}
+
+/// Determine the identity for `purpose` from `config` and `env` like `git` does when creating a commit, with the first
+/// available value being used:
+///
+/// * The name is taken from `GIT_(AUTHOR|COMMITTER)_NAME`, `(author|committer).name`, `user.name`,
+///   `gitoxide.(author|committer).nameFallback`, or the [full name](Environment::full_name()) of the user.
+/// * The email is taken from `GIT_(AUTHOR|COMMITTER)_EMAIL`, `(author|committer).email`, `user.email`,
+///   `gitoxide.(author|committer).emailFallback`, the `EMAIL` environment variable, or is created from the
+///   [login name](Environment::user_name()) and [host name](Environment::host_name()) like `jdoe@laptop.example.com`.
+/// * The time is parsed from `GIT_(AUTHOR|COMMITTER)_DATE`, or is the current time.
+///
+/// Empty values in `(author|committer).*` and in `EMAIL` are ignored, but an empty `user.email` is used as is.
+/// An empty name is an error, just like an email created from a host name that isn't fully qualified, which
+/// is when `git` would show `(none)` as its domain.
+/// With `user.useConfigOnly`, the name and email must be configured in `user.*`, `author.*` or `committer.*` if they
+/// aren't provided by the `GIT_*` environment variables, and no guessing takes place.
+/// The name is resolved before the email, so if both are unusable, the error is about the name.
+pub fn resolve(
+    config: &gix_config::File<'_>,
+    env: &dyn Environment,
+    purpose: Purpose,
+) -> Result<gix_actor::Signature, resolve::Error> {
+    let (name_key, email_key, name_fallback, email_fallback, date_key) = match purpose {
+        Purpose::Author => (
+            &Author::NAME,
+            &Author::EMAIL,
+            &gitoxide::Author::NAME_FALLBACK,
+            &gitoxide::Author::EMAIL_FALLBACK,
+            &gitoxide::Commit::AUTHOR_DATE,
+        ),
+        Purpose::Committer => (
+            &Committer::NAME,
+            &Committer::EMAIL,
+            &gitoxide::Committer::NAME_FALLBACK,
+            &gitoxide::Committer::EMAIL_FALLBACK,
+            &gitoxide::Commit::COMMITTER_DATE,
+        ),
+    };
+    let var = |key: &dyn Key| {
+        key.environment_override()
+            .and_then(|name| env.var(name))
+            .and_then(|value| gix_path::os_string_into_bstring(value).ok())
+    };
+    let string = |key: &keys::Any| config.string(key).map(std::borrow::Cow::into_owned);
+    let non_empty = |value: Option<BString>| value.filter(|v| !v.is_empty());
+    let use_config_only = config
+        .boolean(&User::USE_CONFIG_ONLY)
+        .map(|v| User::USE_CONFIG_ONLY.enrich_error(v))
+        .transpose()?
+        .unwrap_or(false);
+
+    let name = match var(name_fallback)
+        .or_else(|| non_empty(string(name_key)))
+        .or_else(|| string(&User::NAME))
+        .or_else(|| string(name_fallback))
+    {
+        Some(name) => name,
+        None => {
+            let is_configured = [&User::NAME, &Author::NAME, &Committer::NAME]
+                .iter()
+                .any(|key| config.string(*key).is_some());
+            if use_config_only && !is_configured {
+                return Err(resolve::Error::MissingName {
+                    purpose,
+                    reason: "no name was given and auto-detection is disabled".into(),
+                });
+            }
+            env.full_name().ok_or_else(|| resolve::Error::MissingName {
+                purpose,
+                reason: "unable to auto-detect name".into(),
+            })?
+        }
+    };
+    if name.is_empty() {
+        return Err(resolve::Error::MissingName {
+            purpose,
+            reason: "empty ident name not allowed".into(),
+        });
+    }
+
+    let email = match var(email_fallback)
+        .or_else(|| non_empty(string(email_key)))
+        .or_else(|| string(&User::EMAIL))
+        .or_else(|| string(email_fallback))
+    {
+        Some(email) => email,
+        None => {
+            let is_configured = [&User::EMAIL, &Author::EMAIL, &Committer::EMAIL]
+                .iter()
+                .any(|key| config.string(*key).is_some());
+            if use_config_only && !is_configured {
+                return Err(resolve::Error::MissingEmail {
+                    purpose,
+                    reason: "no email was given and auto-detection is disabled".into(),
+                });
+            }
+            match non_empty(env.var("EMAIL").and_then(|v| gix_path::os_string_into_bstring(v).ok())) {
+                Some(email) => email,
+                None => {
+                    let mut email = env.user_name().unwrap_or_else(|| "unknown".into());
+                    email.push_byte(b'@');
+                    match env.host_name().filter(|host| host.contains(&b'.')) {
+                        Some(host) => email.push_str(&host),
+                        None => {
+                            match env.host_name() {
+                                Some(host) => {
+                                    email.push_str(host);
+                                    email.push_str(".(none)");
+                                }
+                                None => email.push_str("(none)"),
+                            }
+                            return Err(resolve::Error::MissingEmail {
+                                purpose,
+                                reason: format!("unable to auto-detect email address (got '{email}')").into(),
+                            });
+                        }
+                    }
+                    email
+                }
+            }
+        }
+    };
+
+    let time = match var(date_key) {
+        Some(date) => date_key
+            .try_into_time(date.as_bstr().into(), std::time::SystemTime::now().into())
+            .map_err(|err| config::time::Error::from(date_key).with_source(err))?,
+        None => gix_date::Time::now_local_or_utc(),
+    };
+    Ok(gix_actor::Signature { name, email, time })
+}
+
+/// Turn the `gecos` field of `/etc/passwd` into a full name, which ends at the first `,` and where `&` is
+/// the capitalized `user_name`.
+fn expand_gecos(gecos: &[u8], user_name: &crate::bstr::BStr) -> BString {
+    let gecos = gecos.split_str(",").next().unwrap_or_default();
+    let mut out = BString::default();
+    for token in gecos.split_inclusive(|b| *b == b'&') {
+        match token.strip_suffix(b"&") {
+            Some(prefix) => {
+                out.push_str(prefix);
+                if let Some((first, rest)) = user_name.split_first() {
+                    out.push_byte(first.to_ascii_uppercase());
+                    out.push_str(rest);
+                }
+            }
+            None => out.push_str(token),
+        }
+    }
+    out
+}
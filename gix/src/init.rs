@@ -1,5 +1,8 @@
 #![allow(clippy::result_large_err)]
-use std::{borrow::Cow, path::Path};
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+};
 
 use gix_macros::momo;
 use gix_ref::{
@@ -8,7 +11,11 @@ use gix_ref::{
     FullName, Target,
 };
 
-use crate::{bstr::BString, config::tree::Init, ThreadSafeRepository};
+use crate::{
+    bstr::BString,
+    config::tree::{Init, Key},
+    ThreadSafeRepository,
+};
 
 /// The name of the branch to use if non is configured via git configuration.
 ///
@@ -34,13 +41,15 @@ pub enum Error {
     },
     #[error("Could not edit HEAD reference with new default name")]
     EditHeadForDefaultBranch(#[from] crate::reference::edit::Error),
+    #[error("Could not interpolate the template directory configured in init.templateDir")]
+    TemplateDirInterpolation(#[from] gix_config::path::interpolate::Error),
 }
 
 impl ThreadSafeRepository {
     /// Create a repository with work-tree within `directory`, creating intermediate directories as needed.
     ///
-    /// Fails without action if there is already a `.git` repository inside of `directory`, but
-    /// won't mind if the `directory` otherwise is non-empty.
+    /// If there already is a `.git` repository inside of `directory`, it will be reinitialized without destroying anything,
+    /// and the `directory` may otherwise be non-empty.
     #[momo]
     pub fn init(
         directory: impl AsRef<Path>,
@@ -58,20 +67,53 @@ impl ThreadSafeRepository {
     ///
     /// Instead of naming the default branch `master`, we name it `main` unless configured explicitly using the `init.defaultBranch`
     /// configuration key.
+    ///
+    /// If [`template_dir`](crate::create::Options::template_dir) isn't set, the templates are taken from the directory
+    /// in `GIT_TEMPLATE_DIR` or `init.templateDir`, and the built-in templates are used otherwise.
     #[momo]
     pub fn init_opts(
         directory: impl AsRef<Path>,
         kind: crate::create::Kind,
-        create_options: crate::create::Options,
+        mut create_options: crate::create::Options,
         mut open_options: crate::open::Options,
     ) -> Result<Self, Error> {
-        let path = crate::create::into(directory.as_ref(), kind, create_options)?;
+        // Templates depend on configuration, which is only available once the repository is opened.
+        let apply_configured_templates = create_options.template_dir.is_none();
+        if apply_configured_templates {
+            create_options.template_dir = Some(PathBuf::new());
+        }
+        let (path, reinitialized) = crate::create::into_inner(directory.as_ref().into(), kind, create_options)?;
         let (git_dir, worktree_dir) = path.into_repository_and_work_tree_directories();
         open_options.git_dir_trust = Some(gix_sec::Trust::Full);
         // The repo will use `core.precomposeUnicode` to adjust the value as needed.
         open_options.current_dir = gix_fs::current_dir(false)?.into();
-        let repo = ThreadSafeRepository::open_from_paths(git_dir, worktree_dir, open_options)?;
+        let repo = ThreadSafeRepository::open_from_paths(git_dir, worktree_dir, None, open_options)?;
 
+        if apply_configured_templates {
+            let template_dir: Option<std::path::PathBuf> = match repo
+                .linked_worktree_options
+                .permissions
+                .env
+                .git_prefix
+                .check_opt(Init::TEMPLATE_DIR.the_environment_override())
+                .and_then(std::env::var_os)
+            {
+                Some(dir) => Some(dir.into()),
+                None => repo
+                    .config
+                    .trusted_file_path(Init::TEMPLATE_DIR)
+                    .transpose()?
+                    .map(Cow::into_owned),
+            };
+            match template_dir {
+                Some(template_dir) => crate::create::copy_templates(&template_dir, repo.git_dir())?,
+                None => crate::create::write_builtin_templates(&mut repo.git_dir().to_owned())?,
+            }
+        }
+
+        if reinitialized {
+            return Ok(repo);
+        }
         let branch_name = repo
             .config
             .resolved
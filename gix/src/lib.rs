@@ -95,6 +95,8 @@
 pub use gix_actor as actor;
 #[cfg(feature = "attributes")]
 pub use gix_attributes as attrs;
+#[cfg(feature = "blame")]
+pub use gix_blame as blame;
 #[cfg(feature = "command")]
 pub use gix_command as command;
 pub use gix_commitgraph as commitgraph;
@@ -120,6 +122,8 @@ pub use gix_ignore as ignore;
 #[cfg(feature = "index")]
 pub use gix_index as index;
 pub use gix_lock as lock;
+#[cfg(feature = "merge")]
+pub use gix_merge as merge;
 #[cfg(feature = "credentials")]
 pub use gix_negotiate as negotiate;
 pub use gix_object as objs;
@@ -201,6 +205,15 @@ pub mod progress;
 #[allow(clippy::empty_docs)]
 pub mod push;
 
+///
+#[allow(clippy::empty_docs)]
+#[cfg(feature = "blocking-network-server")]
+pub mod receive_pack;
+///
+#[allow(clippy::empty_docs)]
+#[cfg(feature = "blocking-network-server")]
+pub mod upload_pack;
+
 ///
 #[allow(clippy::empty_docs)]
 pub mod diff;
@@ -346,6 +359,8 @@ pub mod discover;
 
 pub mod env;
 
+pub mod identity;
+
 #[cfg(feature = "attributes")]
 fn is_dir_to_mode(is_dir: bool) -> gix_index::entry::Mode {
     if is_dir {
@@ -88,12 +88,18 @@ impl<'a, 'old> Platform<'a, 'old> {
             tracked: self.rewrites.map(rewrites::Tracker::new),
             err: None,
         };
-        match gix_diff::tree::Changes::from(TreeRefIter::from_bytes(&self.lhs.data)).needed_to_obtain(
-            TreeRefIter::from_bytes(&other.data),
-            &mut self.state,
-            &repo.objects,
-            &mut delegate,
-        ) {
+        // Equal trees can't have changes, so there is no need to look at them.
+        let res = if self.lhs.id == other.id {
+            Ok(())
+        } else {
+            gix_diff::tree::Changes::from(TreeRefIter::from_bytes(&self.lhs.data)).needed_to_obtain(
+                TreeRefIter::from_bytes(&other.data),
+                &mut self.state,
+                &repo.objects,
+                &mut delegate,
+            )
+        };
+        match res {
             Ok(()) => {
                 let outcome = Outcome {
                     rewrites: delegate.process_tracked_changes(resource_cache)?,
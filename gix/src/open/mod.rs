@@ -52,7 +52,7 @@ pub enum Error {
     },
     #[error(transparent)]
     Io(#[from] std::io::Error),
-    #[error("The git directory at '{}' is considered unsafe as it's not owned by the current user.", .path.display())]
+    #[error("detected dubious ownership in repository at '{}'\nTo add an exception for this directory, call:\n\n\tgit config --global --add safe.directory {}", .path.display(), .path.display())]
     UnsafeGitDir { path: PathBuf },
     #[error(transparent)]
     EnvironmentAccessDenied(#[from] gix_sec::permission::Error<std::path::PathBuf>),
@@ -105,6 +105,11 @@ impl Options {
     /// Use this to mimic `git`s way of handling untrusted repositories. Note that `gitoxide` solves
     /// this by not using configuration from untrusted sources and by generally being secured against
     /// doctored input files which at worst could cause out-of-memory at the time of writing.
+    ///
+    /// Like `git`, repositories that aren't owned by the current user can still be opened if they are
+    /// listed in `safe.directory` of the system or global configuration, which may also be `*` to allow all repositories,
+    /// or a path ending in `/*` to allow all repositories below it.
+    /// The configuration of the repository itself is never used for this.
     pub fn bail_if_untrusted(mut self, toggle: bool) -> Self {
         self.bail_if_untrusted = toggle;
         self
@@ -6,6 +6,7 @@ use gix_macros::momo;
 
 use super::{Error, Options};
 use crate::{
+    bstr::ByteSlice,
     config,
     config::{
         cache::interpolate_context,
@@ -97,7 +98,7 @@ impl ThreadSafeRepository {
             options.git_dir_trust = gix_sec::Trust::from_path_ownership(&git_dir)?.into();
         }
         options.current_dir = Some(cwd);
-        ThreadSafeRepository::open_from_paths(git_dir, worktree_dir, options)
+        ThreadSafeRepository::open_from_paths(git_dir, worktree_dir, None, options)
     }
 
     /// Try to open a git repository in `fallback_directory` (can be worktree or `.git` directory) only if there is no override
@@ -108,6 +109,13 @@ impl ThreadSafeRepository {
     ///
     /// Note that this will read various `GIT_*` environment variables to check for overrides, and is probably most useful when implementing
     /// custom hooks.
+    ///
+    /// The worktree is determined in the same order as `git` does it:
+    ///
+    /// - `GIT_WORK_TREE`, relative to the current working directory
+    /// - `core.worktree`, relative to the `.git` directory
+    /// - the worktree implied by a `.git` file, like in linked worktrees and submodules
+    /// - the parent directory of the `.git` directory, unless the repository is bare.
     // TODO: tests, with hooks, GIT_QUARANTINE for ref-log and transaction control (needs gix-sec support to remove write access in gix-ref)
     // TODO: The following vars should end up as overrides of the respective configuration values (see git-config).
     //       GIT_PROXY_SSL_CERT, GIT_PROXY_SSL_KEY, GIT_PROXY_SSL_CERT_PASSWORD_PROTECTED.
@@ -143,17 +151,20 @@ impl ThreadSafeRepository {
         let (git_dir, worktree_dir) = gix_discover::repository::Path::from_dot_git_dir(path, path_kind, &cwd)
             .expect("we have sanitized path with is_git()")
             .into_repository_and_work_tree_directories();
-        let worktree_dir = worktree_dir.or(overrides.worktree_dir);
 
         let git_dir_trust = gix_sec::Trust::from_path_ownership(&git_dir)?;
         let mut options = trust_map.into_value_by_level(git_dir_trust);
         options.current_dir = Some(cwd);
-        ThreadSafeRepository::open_from_paths(git_dir, worktree_dir, options)
+        ThreadSafeRepository::open_from_paths(git_dir, worktree_dir, overrides.worktree_dir, options)
     }
 
+    /// Open the repository at `git_dir` with the `worktree_dir` as implied by its location, if any.
+    /// `worktree_dir_override` is typically obtained from `GIT_WORK_TREE` and takes precedence over
+    /// `core.worktree` and `worktree_dir`.
     pub(crate) fn open_from_paths(
         mut git_dir: PathBuf,
         mut worktree_dir: Option<PathBuf>,
+        worktree_dir_override: Option<PathBuf>,
         mut options: Options,
     ) -> Result<Self, Error> {
         let _span = gix_trace::detail!("open_from_paths()");
@@ -215,18 +226,20 @@ impl ThreadSafeRepository {
 
         let mut refs = {
             let reflog = repo_config.reflog.unwrap_or(gix_ref::store::WriteReflog::Disable);
-            let object_hash = repo_config.object_hash;
+            let format = &repo_config.repository_format;
             let ref_store_init_opts = gix_ref::store::init::Options {
                 write_reflog: reflog,
-                object_hash,
+                object_hash: format.object_format,
                 precompose_unicode: repo_config.precompose_unicode,
                 prohibit_windows_device_names: repo_config.protect_windows,
             };
-            match &common_dir {
-                Some(common_dir) => {
-                    crate::RefStore::for_linked_worktree(git_dir.to_owned(), common_dir.into(), ref_store_init_opts)
-                }
-                None => crate::RefStore::at(git_dir.to_owned(), ref_store_init_opts),
+            match format.ref_storage {
+                config::repository_format::RefStorage::Files => match &common_dir {
+                    Some(common_dir) => {
+                        crate::RefStore::for_linked_worktree(git_dir.to_owned(), common_dir.into(), ref_store_init_opts)
+                    }
+                    None => crate::RefStore::at(git_dir.to_owned(), ref_store_init_opts),
+                },
             }
         };
         let head = refs.find("HEAD").ok();
@@ -249,18 +262,10 @@ impl ThreadSafeRepository {
             cli_config_overrides,
         )?;
 
-        if bail_if_untrusted && git_dir_trust != gix_sec::Trust::Full {
-            check_safe_directories(
-                &git_dir,
-                git_install_dir.as_deref(),
-                current_dir,
-                home.as_deref(),
-                &config,
-            )?;
-        }
-
-        // core.worktree might be used to overwrite the worktree directory
-        if !config.is_bare {
+        // The environment has the final say, then core.worktree might be used to overwrite the worktree directory.
+        if let Some(wt) = worktree_dir_override {
+            worktree_dir = gix_path::normalize(current_dir.join(wt).into(), current_dir).map(Cow::into_owned);
+        } else if !config.is_bare {
             if let Some(wt) = config.resolved.path_filter(Core::WORKTREE, &mut filter_config_section) {
                 let wt_clone = wt.clone();
                 let wt_path = wt
@@ -297,6 +302,17 @@ impl ThreadSafeRepository {
             None => {}
         }
 
+        if bail_if_untrusted && git_dir_trust != gix_sec::Trust::Full {
+            check_safe_directories(
+                &git_dir,
+                worktree_dir.as_deref(),
+                git_install_dir.as_deref(),
+                current_dir,
+                home.as_deref(),
+                &config,
+            )?;
+        }
+
         refs.write_reflog = config::cache::util::reflog_or_default(config.reflog, worktree_dir.is_some());
         refs.namespace.clone_from(&config.refs_namespace);
         let replacements = replacement_objects_refs_prefix(&config.resolved, lenient_config, filter_config_section)?
@@ -328,6 +344,10 @@ impl ThreadSafeRepository {
                     use_multi_pack_index: config.use_multi_pack_index,
                     current_dir: current_dir.to_owned().into(),
                     ignore_alternates: false,
+                    pack_windows: config
+                        .pack_windows
+                        .map(|options| std::sync::Arc::new(gix_pack::data::window::Cache::new(options))),
+                    compat_object_hash: config.repository_format.compat_object_format,
                 },
             )?),
             common_dir,
@@ -341,6 +361,8 @@ impl ThreadSafeRepository {
             shallow_commits: gix_fs::SharedFileSnapshotMut::new().into(),
             #[cfg(feature = "attributes")]
             modules: gix_fs::SharedFileSnapshotMut::new().into(),
+            #[cfg(feature = "blocking-network-server")]
+            pack_bitmap: gix_fs::SharedFileSnapshotMut::new().into(),
         })
     }
 }
@@ -370,18 +392,28 @@ fn replacement_objects_refs_prefix(
     Ok(ref_base.into())
 }
 
+/// Fail unless the repository is listed in `safe.directory` of the system or global configuration, like `git` does
+/// for repositories owned by other users.
+///
+/// Like in `git`, the worktree is used for comparison if there is one, but we also accept the `git_dir` if it's
+/// listed exactly. Entries are either `*` to allow all repositories, a path with a trailing `/*` to allow all repositories below it,
+/// or the path to the repository itself. An empty entry resets the list.
 fn check_safe_directories(
     git_dir: &std::path::Path,
+    worktree_dir: Option<&std::path::Path>,
     git_install_dir: Option<&std::path::Path>,
     current_dir: &std::path::Path,
     home: Option<&std::path::Path>,
     config: &config::Cache,
 ) -> Result<(), Error> {
-    let mut is_safe = false;
-    let git_dir = match gix_path::realpath_opts(git_dir, current_dir, gix_path::realpath::MAX_SYMLINKS) {
-        Ok(p) => p,
-        Err(_) => git_dir.to_owned(),
+    let realpath = |path: &std::path::Path| {
+        gix_path::realpath_opts(path, current_dir, gix_path::realpath::MAX_SYMLINKS).unwrap_or_else(|_| path.to_owned())
     };
+    let git_dir = realpath(git_dir);
+    let worktree_dir = worktree_dir.map(realpath);
+    let candidates: Vec<_> = worktree_dir.iter().chain(Some(&git_dir)).collect();
+
+    let mut is_safe = false;
     for safe_dir in config
         .resolved
         .strings_filter(Safe::DIRECTORY, &mut Safe::directory_filter)
@@ -395,22 +427,31 @@ fn check_safe_directories(
             is_safe = false;
             continue;
         }
-        if !is_safe {
-            let safe_dir = match gix_config::Path::from(std::borrow::Cow::Borrowed(safe_dir.as_ref()))
-                .interpolate(interpolate_context(git_install_dir, home))
-            {
-                Ok(path) => path,
-                Err(_) => gix_path::from_bstr(safe_dir),
-            };
-            if safe_dir == git_dir {
-                is_safe = true;
-                continue;
-            }
+        if is_safe {
+            continue;
         }
+        let (safe_dir, is_prefix) = match safe_dir.strip_suffix(b"/*") {
+            Some(prefix) => (prefix.as_bstr(), true),
+            None => (safe_dir.as_ref(), false),
+        };
+        let safe_dir = match gix_config::Path::from(Cow::Borrowed(safe_dir))
+            .interpolate(interpolate_context(git_install_dir, home))
+        {
+            Ok(path) => path,
+            Err(_) => gix_path::from_bstr(safe_dir),
+        };
+        let safe_dir = realpath(&safe_dir);
+        is_safe = if is_prefix {
+            candidates[0].starts_with(&safe_dir) && *candidates[0] != safe_dir
+        } else {
+            candidates.iter().any(|candidate| **candidate == safe_dir)
+        };
     }
     if is_safe {
         Ok(())
     } else {
-        Err(Error::UnsafeGitDir { path: git_dir })
+        Err(Error::UnsafeGitDir {
+            path: worktree_dir.unwrap_or(git_dir),
+        })
     }
 }
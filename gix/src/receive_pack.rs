@@ -0,0 +1,92 @@
+//! Types to serve `git push` with [`Repository::receive_pack()`](crate::Repository::receive_pack()).
+use crate::bstr::BString;
+
+/// All possible values of `receive.denyCurrentBranch` and `receive.denyDeleteCurrent`.
+#[derive(Default, Copy, Clone, PartialOrd, PartialEq, Ord, Eq, Hash, Debug)]
+pub enum Deny {
+    /// Allow the change, which is also selected with `false`.
+    Ignore,
+    /// Allow the change, but warn about it.
+    Warn,
+    /// Refuse the change, which is also selected with `true`.
+    #[default]
+    Refuse,
+    /// Update the worktree along with the current branch, which isn't supported and treated like [`Refuse`](Deny::Refuse).
+    UpdateInstead,
+}
+
+/// Options for use in [`Repository::receive_pack()`](crate::Repository::receive_pack()).
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    /// If `true`, the reference advertisement isn't sent as it was obtained with
+    /// [`Repository::receive_pack_advertisement()`](crate::Repository::receive_pack_advertisement()) in a previous request already.
+    ///
+    /// This is the case for stateless transports like HTTP.
+    pub stateless_rpc: bool,
+    /// If `true`, the `pre-receive`, `update` and `post-receive` hooks in `$GIT_DIR/hooks` will be invoked if they exist.
+    pub run_hooks: bool,
+}
+
+/// The outcome of [`Repository::receive_pack()`](crate::Repository::receive_pack()).
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The request as sent by the client, with all commands it wanted to execute.
+    pub request: gix_protocol::receive_pack::Request,
+    /// The result of the operation for each of the commands in `request`, in the same order, along with the result of receiving the pack.
+    ///
+    /// It's sent to the client if it asked for it.
+    pub report: gix_protocol::receive_pack::Report,
+    /// Information about the pack that was received, or `None` if there was no pack or if it could not be received.
+    pub pack: Option<gix_pack::bundle::write::Outcome>,
+    /// The combined output of all hooks that ran, as it can't be communicated to the client.
+    pub hook_output: BString,
+}
+
+/// The error returned by [`Repository::receive_pack()`](crate::Repository::receive_pack()).
+///
+/// Note that rejected commands and errors while receiving the pack are not an error, but are communicated to the client
+/// and are available in [`Outcome::report`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Advertisement(#[from] advertisement::Error),
+    #[error("Could not read the request of the client")]
+    DecodeRequest(#[from] gix_protocol::receive_pack::decode::Error),
+    #[error("Could not write the status report to the client")]
+    WriteReport(#[source] std::io::Error),
+    #[error(transparent)]
+    ConfigBoolean(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    ConfigDeny(#[from] crate::config::key::GenericErrorWithValue),
+    #[error(transparent)]
+    LockTimeoutConfiguration(#[from] crate::config::lock_timeout::Error),
+    #[error(transparent)]
+    ParseCommitterTime(#[from] crate::config::time::Error),
+    #[error(transparent)]
+    HeadName(#[from] crate::reference::find::existing::Error),
+    #[error("Could not open the object database of the quarantine directory")]
+    Quarantine(#[source] std::io::Error),
+    #[error("Could not run the '{name}' hook")]
+    Hook { name: &'static str, source: std::io::Error },
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod advertisement {
+    /// The error returned by [`Repository::receive_pack_advertisement()`](crate::Repository::receive_pack_advertisement()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Iter(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        IterInit(#[from] crate::reference::iter::init::Error),
+        #[error(transparent)]
+        IterItem(Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        Follow(#[from] gix_ref::file::find::existing::Error),
+        #[error("Could not write the reference advertisement")]
+        Io(#[from] std::io::Error),
+    }
+}
@@ -0,0 +1,417 @@
+use std::cmp::Ordering;
+
+use gix_object::bstr::{BStr, BString, ByteSlice};
+
+use super::{expand::Error, Atom, DateField, DateFormat, Format, IdFormat, NameFormat, Part, SortKey, UpstreamFormat};
+use crate::{bstr::ByteVec, Id, Reference};
+
+impl Format {
+    /// Expand this format for `reference` and return the result.
+    pub fn expand(&self, reference: &Reference<'_>) -> Result<BString, Error> {
+        let mut out = BString::default();
+        self.expand_into(reference, &mut out)?;
+        Ok(out)
+    }
+
+    /// Expand this format for `reference` and append the result to `out`.
+    pub fn expand_into(&self, reference: &Reference<'_>, out: &mut BString) -> Result<(), Error> {
+        let mut state = State::new(reference)?;
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => out.extend_from_slice(text),
+                Part::Atom(atom) => state.write(*atom, out)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Sort `references` by `keys` just like `git for-each-ref --sort` does, i.e. the last key is the primary one,
+/// and references which compare equal are ordered by their full name.
+///
+/// If `keys` is empty, `references` are sorted by their full name.
+pub fn sort(references: &mut Vec<Reference<'_>>, keys: &[SortKey]) -> Result<(), Error> {
+    let mut keyed = Vec::with_capacity(references.len());
+    for reference in references.drain(..) {
+        let mut state = State::new(&reference)?;
+        let values = keys
+            .iter()
+            .rev()
+            .map(|key| state.sort_value(key.atom))
+            .collect::<Result<Vec<_>, _>>()?;
+        keyed.push((values, reference));
+    }
+    keyed.sort_by(|(a_values, a), (b_values, b)| {
+        keys.iter()
+            .rev()
+            .zip(a_values.iter().zip(b_values))
+            .map(|(key, (a, b))| {
+                let ordering = a.cmp(b, key.version);
+                if key.descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| a.name().as_bstr().cmp(b.name().as_bstr()))
+    });
+    references.extend(keyed.into_iter().map(|(_, reference)| reference));
+    Ok(())
+}
+
+enum SortValue {
+    Text(BString),
+    Time(gix_date::SecondsSinceUnixEpoch),
+}
+
+impl SortValue {
+    fn cmp(&self, other: &SortValue, version: bool) -> Ordering {
+        match (self, other) {
+            (SortValue::Time(a), SortValue::Time(b)) => a.cmp(b),
+            (SortValue::Text(a), SortValue::Text(b)) if version => version_cmp(a.as_ref(), b.as_ref()),
+            (SortValue::Text(a), SortValue::Text(b)) => a.cmp(b),
+            (SortValue::Time(_), SortValue::Text(_)) => Ordering::Less,
+            (SortValue::Text(_), SortValue::Time(_)) => Ordering::Greater,
+        }
+    }
+}
+
+/// Everything needed to expand atoms for a single reference, with the object it points to loaded on demand.
+struct State<'a, 'repo> {
+    reference: &'a Reference<'repo>,
+    id: Option<Id<'repo>>,
+    object: Option<crate::Object<'repo>>,
+}
+
+impl<'a, 'repo> State<'a, 'repo> {
+    fn new(reference: &'a Reference<'repo>) -> Result<Self, Error> {
+        Ok(State {
+            reference,
+            id: target_id(reference)?,
+            object: None,
+        })
+    }
+
+    fn repo(&self) -> &'repo crate::Repository {
+        self.reference.repo
+    }
+
+    fn object(&mut self) -> Result<Option<&crate::Object<'repo>>, Error> {
+        let Some(id) = self.id else { return Ok(None) };
+        if self.object.is_none() {
+            self.object = Some(id.object()?);
+        }
+        Ok(self.object.as_ref())
+    }
+
+    fn sort_value(&mut self, atom: Atom) -> Result<SortValue, Error> {
+        Ok(match atom {
+            Atom::Date { field, .. } => SortValue::Time(self.time(field)?.map_or(0, |time| time.seconds)),
+            _ => {
+                let mut out = BString::default();
+                self.write(atom, &mut out)?;
+                SortValue::Text(out)
+            }
+        })
+    }
+
+    fn write(&mut self, atom: Atom, out: &mut BString) -> Result<(), Error> {
+        match atom {
+            Atom::RefName(format) => self.write_name(self.reference.name().as_bstr(), format, out),
+            Atom::ObjectName(format) => {
+                let Some(id) = self.id else { return Ok(()) };
+                match format {
+                    IdFormat::Full => out.push_str(id.to_hex().to_string()),
+                    IdFormat::Short(None) => out.push_str(id.shorten()?.to_string()),
+                    IdFormat::Short(Some(hex_len)) => {
+                        let hex_len = hex_len.clamp(4, id.kind().len_in_hex());
+                        let candidate = gix_odb::store::prefix::disambiguate::Candidate::new(id.detach(), hex_len)
+                            .expect("hex-len is clamped to be valid");
+                        let prefix = self
+                            .repo()
+                            .objects
+                            .disambiguate_prefix(candidate)
+                            .map_err(crate::id::shorten::Error::from)?
+                            .ok_or(crate::id::shorten::Error::NotFound { oid: id.detach() })?;
+                        out.push_str(prefix.to_string());
+                    }
+                }
+            }
+            Atom::ObjectType => {
+                if let Some(object) = self.object()? {
+                    out.push_str(object.kind.as_bytes());
+                }
+            }
+            Atom::Date { field, format } => {
+                if let Some(time) = self.time(field)? {
+                    out.push_str(match format {
+                        DateFormat::Default => time.format(gix_date::time::format::DEFAULT),
+                        DateFormat::Iso8601 => time.format(gix_date::time::format::ISO8601),
+                        DateFormat::Iso8601Strict => time.format(gix_date::time::format::ISO8601_STRICT),
+                        DateFormat::Rfc2822 => time.format(gix_date::time::format::GIT_RFC2822),
+                        DateFormat::Short => time.format(gix_date::time::format::SHORT),
+                        DateFormat::Unix => time.format(gix_date::time::format::UNIX),
+                        DateFormat::Raw => time.format(gix_date::time::format::RAW),
+                    });
+                }
+            }
+            Atom::Subject | Atom::Body => {
+                if let Some(message) = self.message()? {
+                    let (subject, body) = split_message(message.as_ref());
+                    if atom == Atom::Subject {
+                        for (pos, byte) in subject.iter().enumerate() {
+                            match byte {
+                                b'\r' if subject.get(pos + 1) == Some(&b'\n') => {}
+                                b'\n' => out.push(b' '),
+                                _ => out.push(*byte),
+                            }
+                        }
+                    } else {
+                        out.extend_from_slice(body);
+                    }
+                }
+            }
+            Atom::Upstream(format) => self.write_upstream(format, out)?,
+        }
+        Ok(())
+    }
+
+    fn write_name(&self, name: &BStr, format: NameFormat, out: &mut BString) {
+        match format {
+            NameFormat::Full => out.extend_from_slice(name),
+            NameFormat::Short => out.extend_from_slice(&shorten_unambiguously(self.repo(), name)),
+            NameFormat::LStrip(n) => {
+                let components: Vec<_> = name.split_str("/").collect();
+                let skip = num_components_to_strip(components.len(), n);
+                out.extend_from_slice(&components[skip.min(components.len())..].join(&b'/'));
+            }
+            NameFormat::RStrip(n) => {
+                let components: Vec<_> = name.split_str("/").collect();
+                let keep = components
+                    .len()
+                    .saturating_sub(num_components_to_strip(components.len(), n));
+                out.extend_from_slice(&components[..keep].join(&b'/'));
+            }
+        }
+    }
+
+    fn write_upstream(&self, format: UpstreamFormat, out: &mut BString) -> Result<(), Error> {
+        if !matches!(self.reference.name().category(), Some(gix_ref::Category::LocalBranch)) {
+            return Ok(());
+        }
+        let Some(upstream_name) = self
+            .reference
+            .remote_tracking_ref_name(crate::remote::Direction::Fetch)
+            .transpose()?
+        else {
+            return Ok(());
+        };
+        let name_format = match format {
+            UpstreamFormat::Name(name_format) => name_format,
+            UpstreamFormat::Track | UpstreamFormat::TrackShort => {
+                let upstream_id = match self.repo().try_find_reference(upstream_name.as_ref())? {
+                    Some(upstream) => target_id(&upstream)?,
+                    None => None,
+                };
+                let (Some(ours), Some(theirs)) = (self.id, upstream_id) else {
+                    if format == UpstreamFormat::Track {
+                        out.push_str("[gone]");
+                    }
+                    return Ok(());
+                };
+                let (ahead, behind) =
+                    gix_revision::ahead_behind(&ours, &theirs, &mut self.repo().revision_graph(), None)?.counts();
+                out.push_str(match (format, ahead, behind) {
+                    (UpstreamFormat::Track, 0, 0) => String::new(),
+                    (UpstreamFormat::Track, ahead, 0) => format!("[ahead {ahead}]"),
+                    (UpstreamFormat::Track, 0, behind) => format!("[behind {behind}]"),
+                    (UpstreamFormat::Track, ahead, behind) => format!("[ahead {ahead}, behind {behind}]"),
+                    (_, 0, 0) => "=".into(),
+                    (_, _, 0) => ">".into(),
+                    (_, 0, _) => "<".into(),
+                    (_, _, _) => "<>".into(),
+                });
+                return Ok(());
+            }
+        };
+        self.write_name(upstream_name.as_bstr(), name_format, out);
+        Ok(())
+    }
+
+    fn time(&mut self, field: DateField) -> Result<Option<gix_date::Time>, Error> {
+        let Some(object) = self.object()? else { return Ok(None) };
+        Ok(match object.kind {
+            gix_object::Kind::Commit => {
+                let commit = gix_object::CommitRef::from_bytes(&object.data)?;
+                match field {
+                    DateField::Committer => Some(commit.committer.time),
+                    DateField::Author => Some(commit.author.time),
+                    DateField::Tagger => None,
+                }
+            }
+            gix_object::Kind::Tag => match field {
+                DateField::Tagger => gix_object::TagRef::from_bytes(&object.data)?
+                    .tagger
+                    .map(|tagger| tagger.time),
+                DateField::Committer | DateField::Author => None,
+            },
+            gix_object::Kind::Tree | gix_object::Kind::Blob => None,
+        })
+    }
+
+    fn message(&mut self) -> Result<Option<BString>, Error> {
+        let Some(object) = self.object()? else { return Ok(None) };
+        Ok(match object.kind {
+            gix_object::Kind::Commit => Some(gix_object::CommitRef::from_bytes(&object.data)?.message.to_owned()),
+            gix_object::Kind::Tag => Some(gix_object::TagRef::from_bytes(&object.data)?.message.to_owned()),
+            gix_object::Kind::Tree | gix_object::Kind::Blob => None,
+        })
+    }
+}
+
+/// Follow symbolic references until an object id is found, or return `None` if the chain ends in a reference that doesn't exist.
+fn target_id<'repo>(reference: &Reference<'repo>) -> Result<Option<Id<'repo>>, Error> {
+    if let Some(id) = reference.try_id() {
+        return Ok(Some(id));
+    }
+    let mut current = match reference.follow() {
+        Some(Ok(next)) => next,
+        Some(Err(gix_ref::file::find::existing::Error::NotFound { .. })) | None => return Ok(None),
+        Some(Err(err)) => return Err(err.into()),
+    };
+    // Like git, give up on chains that are suspiciously long.
+    for _ in 0..5 {
+        if let Some(id) = current.try_id() {
+            return Ok(Some(id));
+        }
+        current = match current.follow() {
+            Some(Ok(next)) => next,
+            Some(Err(gix_ref::file::find::existing::Error::NotFound { .. })) | None => return Ok(None),
+            Some(Err(err)) => return Err(err.into()),
+        };
+    }
+    Ok(None)
+}
+
+/// Split `message` into its subject and body exactly like `git` does.
+fn split_message(message: &[u8]) -> (&[u8], &[u8]) {
+    let start = message.iter().take_while(|b| **b == b'\n').count();
+    let message = &message[start..];
+    let subject_end = message
+        .find("\n\n")
+        .or_else(|| message.find("\r\n\r\n"))
+        .unwrap_or(message.len());
+    let mut subject = &message[..subject_end];
+    while let Some(rest) = subject.strip_suffix(b"\n").or_else(|| subject.strip_suffix(b"\r")) {
+        subject = rest;
+    }
+    let body = &message[subject_end..];
+    let body_start = body.iter().take_while(|b| matches!(b, b'\n' | b'\r')).count();
+    (subject, &body[body_start..])
+}
+
+/// Return the amount of leading (or trailing) components of `num_components` to remove for `n` as used in `lstrip=<n>`.
+fn num_components_to_strip(num_components: usize, n: isize) -> usize {
+    if n >= 0 {
+        n as usize
+    } else {
+        num_components.saturating_sub(n.unsigned_abs())
+    }
+}
+
+/// Shorten the full reference `name` like `git` does with `core.warnAmbiguousRefs` enabled, i.e. to the shortest name
+/// which, when expanded with the rules of `git rev-parse`, doesn't refer to any other reference.
+fn shorten_unambiguously(repo: &crate::Repository, name: &BStr) -> BString {
+    const RULES: &[(&str, &str)] = &[
+        ("", ""),
+        ("refs/", ""),
+        ("refs/tags/", ""),
+        ("refs/heads/", ""),
+        ("refs/remotes/", ""),
+        ("refs/remotes/", "/HEAD"),
+    ];
+    let exists = |candidate: &BString| -> bool {
+        match repo.refs.try_find(candidate.as_bstr()) {
+            Ok(Some(found)) => found.name.as_bstr() == candidate,
+            Ok(None) | Err(_) => false,
+        }
+    };
+    // Like `git`, never shorten to the first rule as it always matches, nor to the last one as its `HEAD` suffix is never
+    // matched by the `scanf()` pattern `git` uses. Both are considered when checking for ambiguity though.
+    for (rule_idx, (prefix, suffix)) in RULES.iter().enumerate().take(RULES.len() - 1).skip(1).rev() {
+        let Some(short) = name
+            .strip_prefix(prefix.as_bytes())
+            .and_then(|rest| rest.strip_suffix(suffix.as_bytes()))
+            .filter(|short| !short.is_empty())
+        else {
+            continue;
+        };
+        let is_ambiguous = RULES
+            .iter()
+            .enumerate()
+            .filter(|(other_idx, _)| *other_idx != rule_idx)
+            .any(|(_, (prefix, suffix))| {
+                let mut candidate = BString::from(*prefix);
+                candidate.extend_from_slice(short);
+                candidate.push_str(suffix);
+                exists(&candidate)
+            });
+        if !is_ambiguous {
+            return short.into();
+        }
+    }
+    name.to_owned()
+}
+
+/// Compare `a` and `b` so that numbers within them are ordered by their value, just like `strverscmp(3)` does.
+fn version_cmp(a: &[u8], b: &[u8]) -> Ordering {
+    // States, each with three entries for the class of the next byte.
+    const S_N: usize = 0; // normal
+    const S_I: usize = 3; // comparing integral part
+    const S_F: usize = 6; // comparing fractional part, i.e. numbers with leading zeroes
+    const S_Z: usize = 9; // idem but with leading zeroes only
+    const NEXT_STATE: [usize; 12] = [S_N, S_I, S_Z, S_N, S_I, S_I, S_N, S_F, S_F, S_N, S_F, S_Z];
+    // How to compare after the first difference, indexed by state and the classes of both bytes.
+    const CMP: i8 = 2;
+    const LEN: i8 = 3;
+    #[rustfmt::skip]
+    const RESULT: [i8; 36] = [
+        CMP, CMP, CMP, CMP, LEN, CMP, CMP, CMP, CMP,
+        CMP, -1, -1, 1, LEN, LEN, 1, LEN, LEN,
+        CMP, CMP, CMP, CMP, CMP, CMP, CMP, CMP, CMP,
+        CMP, 1, 1, -1, CMP, CMP, -1, CMP, CMP,
+    ];
+    let at = |s: &[u8], idx: usize| s.get(idx).copied().unwrap_or(0);
+    let class = |byte: u8| usize::from(byte == b'0') + usize::from(byte.is_ascii_digit());
+
+    let mut idx = 0;
+    let (mut ca, mut cb) = (at(a, 0), at(b, 0));
+    let mut state = S_N + class(ca);
+    while ca == cb {
+        if ca == 0 {
+            return Ordering::Equal;
+        }
+        state = NEXT_STATE[state];
+        idx += 1;
+        ca = at(a, idx);
+        cb = at(b, idx);
+        state += class(ca);
+    }
+    match RESULT[state * 3 + class(cb)] {
+        CMP => ca.cmp(&cb),
+        LEN => {
+            let mut idx = idx;
+            loop {
+                let (da, db) = (at(a, idx).is_ascii_digit(), at(b, idx).is_ascii_digit());
+                match (da, db) {
+                    (true, true) => idx += 1,
+                    (true, false) => return Ordering::Greater,
+                    (false, true) => return Ordering::Less,
+                    (false, false) => return ca.cmp(&cb),
+                }
+            }
+        }
+        result => result.cmp(&0),
+    }
+}
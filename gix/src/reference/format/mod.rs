@@ -0,0 +1,326 @@
+//! Expand `git for-each-ref`-style formats like `%(refname:short) %(objectname:short) %(subject)` for references,
+//! and sort references by the same atoms.
+//!
+//! Objects are only decoded if an atom needs information beyond the reference itself, and at most once per
+//! [expansion](Format::expand()).
+use gix_object::bstr::{BStr, BString, ByteSlice};
+
+mod function;
+pub use function::sort;
+
+/// A parsed format, like `%(refname) %(objecttype)%00`, ready to be [expanded](Format::expand()) for references.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Format {
+    /// The literal text and atoms in the order they appear in the format.
+    pub parts: Vec<Part>,
+}
+
+/// A part of a [`Format`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Part {
+    /// Text to copy verbatim, with `%%` and `%xx` hexadecimal escapes already resolved.
+    Literal(BString),
+    /// A placeholder to substitute, written as `%(<atom>)`.
+    Atom(Atom),
+}
+
+/// A placeholder of a [`Format`] or the value to [sort by](SortKey).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Atom {
+    /// `%(refname)`, the name of the reference.
+    RefName(NameFormat),
+    /// `%(objectname)`, the id of the object the reference points to, after following symbolic references.
+    ObjectName(IdFormat),
+    /// `%(objecttype)`, the kind of the object the reference points to.
+    ObjectType,
+    /// `%(committerdate)`, `%(authordate)` or `%(taggerdate)` of the object the reference points to,
+    /// which is empty if the object doesn't have such a field.
+    Date {
+        /// The signature to take the time from.
+        field: DateField,
+        /// How to format the time.
+        format: DateFormat,
+    },
+    /// `%(subject)`, the first paragraph of the message of a commit or tag, folded into a single line.
+    Subject,
+    /// `%(body)`, the message of a commit or tag after the subject.
+    Body,
+    /// `%(upstream)`, the remote-tracking branch of a local branch as configured with `branch.<name>.remote|merge`.
+    Upstream(UpstreamFormat),
+}
+
+/// How to display a reference name.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NameFormat {
+    /// The full name, like `refs/heads/main`.
+    Full,
+    /// `:short`, the shortest name that unambiguously refers to the reference, like `main`.
+    Short,
+    /// `:lstrip=<n>` or `:strip=<n>`, the name without the first `n` components, or with only the last `-n`
+    /// components if negative.
+    LStrip(isize),
+    /// `:rstrip=<n>`, the name without the last `n` components, or with only the first `-n` components if negative.
+    RStrip(isize),
+}
+
+/// How to display an object id.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IdFormat {
+    /// The full hexadecimal id.
+    Full,
+    /// `:short` or `:short=<n>`, the shortest unambiguous prefix of at least `n` characters,
+    /// or as many as configured with `core.abbrev` if `None`.
+    Short(Option<usize>),
+}
+
+/// The signature of an object to take a date from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DateField {
+    /// The committer of a commit.
+    Committer,
+    /// The author of a commit.
+    Author,
+    /// The tagger of an annotated tag.
+    Tagger,
+}
+
+/// How to format a date, named like the `--date` modes of `git`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DateFormat {
+    /// No modifier, like `Thu Aug 18 12:45:06 2022 +0800`.
+    Default,
+    /// `:iso` or `:iso8601`, like `2022-08-18 12:45:06 +0800`.
+    Iso8601,
+    /// `:iso-strict` or `:iso8601-strict`, like `2022-08-18T12:45:06+08:00`.
+    Iso8601Strict,
+    /// `:rfc` or `:rfc2822`, like `Thu, 18 Aug 2022 12:45:06 +0800`.
+    Rfc2822,
+    /// `:short`, like `2022-08-18`.
+    Short,
+    /// `:unix`, the seconds since the unix epoch.
+    Unix,
+    /// `:raw`, the seconds since the unix epoch followed by the offset, like `1660797906 +0800`.
+    Raw,
+}
+
+/// How to display the upstream of a branch.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UpstreamFormat {
+    /// The name of the remote-tracking branch.
+    Name(NameFormat),
+    /// `:track`, like `[ahead 1, behind 2]`, or `[gone]` if the remote-tracking branch doesn't exist.
+    Track,
+    /// `:trackshort`, one of `>`, `<`, `<>` or `=`.
+    TrackShort,
+}
+
+/// A key to [sort] references by, like `-committerdate` or `version:refname`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SortKey {
+    /// The value to compare.
+    pub atom: Atom,
+    /// If `true`, sort in descending order, written with a leading `-`.
+    pub descending: bool,
+    /// If `true`, compare values as version numbers so `v1.10` sorts after `v1.9`, written with a `version:` or `v:` prefix.
+    pub version: bool,
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod parse {
+    use gix_object::bstr::BString;
+
+    /// The error returned by [`Format::parse()`](super::Format::parse()) and [`SortKey::parse()`](super::SortKey::parse()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The atom starting at byte {position} isn't terminated with ')'")]
+        UnterminatedAtom { position: usize },
+        #[error("Unknown field name: {name}")]
+        UnknownAtom { name: BString },
+        #[error("Unrecognized modifier of %({name}): {modifier}")]
+        UnknownModifier { name: BString, modifier: BString },
+        #[error("Sort keys can't be empty")]
+        EmptySortKey,
+    }
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod expand {
+    /// The error returned by [`Format::expand()`](super::Format::expand()) and [`sort()`](super::sort()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FollowSymbolic(#[from] gix_ref::file::find::existing::Error),
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        DecodeObject(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        ShortenId(#[from] crate::id::shorten::Error),
+        #[error(transparent)]
+        UpstreamName(#[from] crate::repository::branch_remote_tracking_ref_name::Error),
+        #[error(transparent)]
+        AheadBehind(#[from] gix_revision::ahead_behind::Error),
+    }
+}
+
+impl Format {
+    /// Parse `input`, where `%(<atom>)` is a placeholder, `%%` is a literal `%` and `%xx` is the byte with
+    /// the hexadecimal value `xx`.
+    pub fn parse(input: &BStr) -> Result<Self, parse::Error> {
+        let mut parts = Vec::new();
+        let mut literal = BString::default();
+        let mut pos = 0;
+        while pos < input.len() {
+            let byte = input[pos];
+            if byte != b'%' {
+                literal.push(byte);
+                pos += 1;
+                continue;
+            }
+            let rest = &input[pos + 1..];
+            if rest.first() == Some(&b'%') {
+                literal.push(b'%');
+                pos += 2;
+            } else if rest.first() == Some(&b'(') {
+                let end = rest
+                    .find_byte(b')')
+                    .ok_or(parse::Error::UnterminatedAtom { position: pos })?;
+                if !literal.is_empty() {
+                    parts.push(Part::Literal(std::mem::take(&mut literal)));
+                }
+                parts.push(Part::Atom(Atom::parse(rest[1..end].as_bstr())?));
+                pos += 1 + end + 1;
+            } else if let Some(byte) = rest.get(..2).and_then(|hex| {
+                std::str::from_utf8(hex)
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            }) {
+                literal.push(byte);
+                pos += 3;
+            } else {
+                literal.push(b'%');
+                pos += 1;
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        Ok(Format { parts })
+    }
+}
+
+impl Atom {
+    /// Parse `input` like `refname:short` or `committerdate:iso`, i.e. the name of the atom without the surrounding `%(…)`.
+    pub fn parse(input: &BStr) -> Result<Self, parse::Error> {
+        let (name, modifier) = match input.find_byte(b':') {
+            Some(pos) => (input[..pos].as_bstr(), Some(input[pos + 1..].as_bstr())),
+            None => (input, None),
+        };
+        let unknown_modifier = || parse::Error::UnknownModifier {
+            name: name.to_owned(),
+            modifier: modifier.map(ToOwned::to_owned).unwrap_or_default(),
+        };
+        let no_modifier = |atom: Atom| match modifier {
+            None => Ok(atom),
+            Some(_) => Err(unknown_modifier()),
+        };
+        Ok(match name.as_bytes() {
+            b"refname" => Atom::RefName(NameFormat::parse(modifier).ok_or_else(unknown_modifier)?),
+            b"objectname" => Atom::ObjectName(match modifier.map(|m| m.as_bytes()) {
+                None => IdFormat::Full,
+                Some(b"short") => IdFormat::Short(None),
+                Some(m) => IdFormat::Short(Some(
+                    m.strip_prefix(b"short=")
+                        .and_then(parse_number)
+                        .and_then(|n| usize::try_from(n).ok())
+                        .ok_or_else(unknown_modifier)?,
+                )),
+            }),
+            b"objecttype" => no_modifier(Atom::ObjectType)?,
+            b"committerdate" | b"authordate" | b"taggerdate" => Atom::Date {
+                field: match name.as_bytes() {
+                    b"committerdate" => DateField::Committer,
+                    b"authordate" => DateField::Author,
+                    _ => DateField::Tagger,
+                },
+                format: DateFormat::parse(modifier).ok_or_else(unknown_modifier)?,
+            },
+            b"subject" => no_modifier(Atom::Subject)?,
+            b"body" => no_modifier(Atom::Body)?,
+            b"upstream" => Atom::Upstream(match modifier.map(|m| m.as_bytes()) {
+                Some(b"track") => UpstreamFormat::Track,
+                Some(b"trackshort") => UpstreamFormat::TrackShort,
+                _ => UpstreamFormat::Name(NameFormat::parse(modifier).ok_or_else(unknown_modifier)?),
+            }),
+            _ => return Err(parse::Error::UnknownAtom { name: name.to_owned() }),
+        })
+    }
+}
+
+impl NameFormat {
+    fn parse(modifier: Option<&BStr>) -> Option<Self> {
+        let Some(modifier) = modifier else {
+            return Some(NameFormat::Full);
+        };
+        if modifier == "short" {
+            return Some(NameFormat::Short);
+        }
+        if let Some(n) = modifier
+            .strip_prefix(b"lstrip=")
+            .or_else(|| modifier.strip_prefix(b"strip="))
+        {
+            return parse_number(n).map(NameFormat::LStrip);
+        }
+        modifier
+            .strip_prefix(b"rstrip=")
+            .and_then(parse_number)
+            .map(NameFormat::RStrip)
+    }
+}
+
+impl DateFormat {
+    fn parse(modifier: Option<&BStr>) -> Option<Self> {
+        Some(match modifier.map(|m| m.as_bytes()) {
+            None | Some(b"default") => DateFormat::Default,
+            Some(b"iso" | b"iso8601") => DateFormat::Iso8601,
+            Some(b"iso-strict" | b"iso8601-strict") => DateFormat::Iso8601Strict,
+            Some(b"rfc" | b"rfc2822") => DateFormat::Rfc2822,
+            Some(b"short") => DateFormat::Short,
+            Some(b"unix") => DateFormat::Unix,
+            Some(b"raw") => DateFormat::Raw,
+            Some(_) => return None,
+        })
+    }
+}
+
+impl SortKey {
+    /// Parse `input` like `refname`, `-committerdate` or `version:refname`, just like the values of `--sort` of `git for-each-ref`.
+    pub fn parse(input: &BStr) -> Result<Self, parse::Error> {
+        let (descending, input) = match input.strip_prefix(b"-") {
+            Some(rest) => (true, rest),
+            None => (false, input.as_bytes()),
+        };
+        let (version, input) = match input.strip_prefix(b"version:").or_else(|| input.strip_prefix(b"v:")) {
+            Some(rest) => (true, rest),
+            None => (false, input),
+        };
+        if input.is_empty() {
+            return Err(parse::Error::EmptySortKey);
+        }
+        Ok(SortKey {
+            atom: Atom::parse(input.as_bstr())?,
+            descending,
+            version,
+        })
+    }
+}
+
+fn parse_number(input: &[u8]) -> Option<isize> {
+    std::str::from_utf8(input).ok()?.parse().ok()
+}
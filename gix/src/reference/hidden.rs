@@ -0,0 +1,84 @@
+//! Hide references from the advertisement of servers, as configured with `transfer.hideRefs`.
+use crate::bstr::{BStr, BString};
+
+/// The service whose references are hidden, which determines the service-specific configuration
+/// that is used in addition to `transfer.hideRefs`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Service {
+    /// `git-upload-pack`, which is additionally configured with `uploadpack.hideRefs`.
+    UploadPack,
+    /// `git-receive-pack`, which is additionally configured with `receive.hideRefs`.
+    ReceivePack,
+}
+
+impl Service {
+    /// Return the name of the configuration section with the `hideRefs` key of this service.
+    pub fn section_name(&self) -> &'static str {
+        match self {
+            Service::UploadPack => "uploadpack",
+            Service::ReceivePack => "receive",
+        }
+    }
+}
+
+/// Decide which references are hidden using patterns like the ones of `transfer.hideRefs`, which is typically obtained
+/// with [`Repository::hidden_references()`](crate::Repository::hidden_references()).
+///
+/// Each pattern hides references that have the same name or have it as prefix up to a `/`, like `refs/changes` hides
+/// `refs/changes/42/1`, but not `refs/changes-log`.
+/// A leading `!` negates the pattern to expose matching references again, and a leading `^` matches the full reference name
+/// instead of the one with its namespace stripped. If both are used, `!` must come first.
+/// The last matching pattern decides, and references that no pattern matches are visible.
+#[derive(Default, Debug, Clone, Eq, PartialEq)]
+pub struct Matcher {
+    patterns: Vec<BString>,
+}
+
+impl Matcher {
+    /// Create a new instance from `patterns`, in the order in which they were configured.
+    pub fn new(patterns: impl IntoIterator<Item = impl Into<BString>>) -> Self {
+        Matcher {
+            patterns: patterns
+                .into_iter()
+                .map(|pattern| {
+                    let mut pattern = pattern.into();
+                    while pattern.last() == Some(&b'/') {
+                        pattern.pop();
+                    }
+                    pattern
+                })
+                .collect(),
+        }
+    }
+
+    /// Return `true` if there are no patterns, so no reference will be hidden.
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Return `true` if the reference is hidden.
+    ///
+    /// `name` is the name of the reference with the current namespace stripped, or `None` if it is outside of the current namespace,
+    /// so that only patterns starting with `^` can match it. `full_name` is the name of the reference including its namespace.
+    /// Both are the same if no namespace is set.
+    pub fn is_hidden(&self, name: Option<&BStr>, full_name: &BStr) -> bool {
+        for pattern in self.patterns.iter().rev() {
+            let (negated, pattern) = match pattern.strip_prefix(b"!") {
+                Some(pattern) => (true, pattern),
+                None => (false, pattern.as_slice()),
+            };
+            let (subject, pattern) = match pattern.strip_prefix(b"^") {
+                Some(pattern) => (Some(full_name), pattern),
+                None => (name, pattern),
+            };
+            let matches = match subject.and_then(|subject| subject.strip_prefix(pattern)) {
+                Some(rest) => rest.is_empty() || rest.starts_with(b"/"),
+                None => false,
+            };
+            if matches {
+                return !negated;
+            }
+        }
+        false
+    }
+}
@@ -40,3 +40,87 @@ pub(crate) fn commit_type_by_parents(count: usize) -> Option<&'static str> {
         _two_or_more => "merge",
     })
 }
+
+///
+#[allow(clippy::empty_docs)]
+pub mod expire {
+    /// Determine which entries of a reflog expire based on their age, as configured with `gc.reflogExpire` and
+    /// `gc.reflogExpireUnreachable`.
+    #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+    pub enum Cutoff {
+        /// No entry expires, configured with `never` or `false`.
+        Never,
+        /// All entries expire, configured with `all` or `now`.
+        Always,
+        /// Entries expire if they were created before the given time in seconds since the Unix epoch.
+        OlderThan(gix_date::SecondsSinceUnixEpoch),
+    }
+
+    impl Cutoff {
+        /// Return `true` if an entry created at `seconds` since the Unix epoch expires.
+        pub fn expires(&self, seconds: gix_date::SecondsSinceUnixEpoch) -> bool {
+            match self {
+                Cutoff::Never => false,
+                Cutoff::Always => true,
+                Cutoff::OlderThan(cutoff) => seconds < *cutoff,
+            }
+        }
+    }
+
+    /// Options for use in [`Repository::expire_reflogs()`](crate::Repository::expire_reflogs()).
+    #[derive(Default, Debug, Copy, Clone)]
+    pub struct Options {
+        /// If set, entries older than this expire, overriding `gc.reflogExpire` and `gc.<pattern>.reflogExpire`.
+        pub expire: Option<Cutoff>,
+        /// If set, entries older than this expire if they aren't reachable from the tip of their reference, overriding
+        /// `gc.reflogExpireUnreachable` and `gc.<pattern>.reflogExpireUnreachable`.
+        pub expire_unreachable: Option<Cutoff>,
+        /// If `true`, the previous id of each remaining entry is set to the new id of the remaining entry before it, like
+        /// `git reflog expire --rewrite` does, so each entry continues where the previous one left off.
+        pub rewrite: bool,
+        /// If `true`, no reflog is changed, and the outcome only tells which entries would be dropped.
+        pub dry_run: bool,
+    }
+
+    /// The outcome of [`Repository::expire_reflogs()`](crate::Repository::expire_reflogs()).
+    #[derive(Default, Debug, Clone)]
+    pub struct Outcome {
+        /// All reflogs that were inspected, along with the entries that were dropped from them.
+        pub logs: Vec<Log>,
+    }
+
+    /// A single reflog inspected by [`Repository::expire_reflogs()`](crate::Repository::expire_reflogs()).
+    #[derive(Debug, Clone)]
+    pub struct Log {
+        /// The name of the reference the reflog belongs to.
+        pub name: gix_ref::FullName,
+        /// The entries that were dropped, or would be dropped in a dry-run, from oldest to newest.
+        pub dropped: Vec<gix_ref::log::Line>,
+        /// The amount of entries that remain.
+        pub kept: usize,
+    }
+
+    /// The error returned by [`Repository::expire_reflogs()`](crate::Repository::expire_reflogs()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Config(#[from] crate::config::time::Error),
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::Error),
+        #[error(transparent)]
+        IterReferences(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        InitIterReferences(#[from] crate::reference::iter::init::Error),
+        #[error(transparent)]
+        Reference(Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        ReadReflog(#[from] gix_ref::file::log::Error),
+        #[error(transparent)]
+        DecodeReflog(#[from] gix_ref::file::log::iter::decode::Error),
+        #[error(transparent)]
+        RewriteReflog(#[from] gix_ref::file::log::rewrite::Error),
+        #[error(transparent)]
+        AheadBehind(#[from] gix_revision::ahead_behind::Error),
+    }
+}
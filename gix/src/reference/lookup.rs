@@ -0,0 +1,193 @@
+//! Find references by the object they point to, for decorating commits with the references pointing at them
+//! or to learn which references contain a commit.
+use std::collections::BTreeMap;
+
+use gix_hash::{oid, ObjectId};
+use gix_ref::{FullName, FullNameRef};
+
+/// A reference pointing at an object, directly or through annotated tags.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The full name of the reference, like `refs/tags/v1.0`.
+    pub name: FullName,
+    /// The object the reference points to after following symbolic references, which is an annotated tag
+    /// if the reference was peeled.
+    pub target: ObjectId,
+    /// The object that `target` ultimately points to after following all annotated tags, which is `target`
+    /// itself if it isn't a tag.
+    pub peeled: ObjectId,
+}
+
+impl Entry {
+    /// Return `true` if the reference points to [`peeled`](Self::peeled) only indirectly through annotated tags.
+    pub fn is_peeled(&self) -> bool {
+        self.target != self.peeled
+    }
+}
+
+/// An index from objects to the references pointing at them, directly or through annotated tags.
+///
+/// It's built once from all or some references and can be [updated](Self::update()) when references change.
+#[derive(Default, Debug, Clone)]
+pub struct ByTarget {
+    entries: BTreeMap<FullName, Entry>,
+    names_by_id: gix_hashtable::HashMap<ObjectId, Vec<FullName>>,
+}
+
+/// The error returned by [`Repository::references_by_target()`](crate::Repository::references_by_target()) and
+/// the methods of [`ByTarget`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    OpenPackedRefs(#[from] crate::reference::iter::Error),
+    #[error(transparent)]
+    IterInit(#[from] crate::reference::iter::init::Error),
+    #[error("Could not read a reference")]
+    Iter(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error("Could not follow a symbolic reference")]
+    Follow(#[from] gix_ref::file::find::existing::Error),
+    #[error(transparent)]
+    Find(#[from] crate::reference::find::Error),
+    #[error(transparent)]
+    Peel(#[from] crate::reference::peel::Error),
+    #[error(transparent)]
+    FindCommit(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    AheadBehind(#[from] gix_revision::ahead_behind::Error),
+}
+
+/// Lifecycle
+impl ByTarget {
+    /// Create a new instance from `references`, typically all references of a repository.
+    ///
+    /// Symbolic references are followed and annotated tags are peeled, so each reference is found by the object
+    /// it points to as well as by the object it was peeled to.
+    pub fn from_references<'repo>(
+        references: impl IntoIterator<Item = crate::Reference<'repo>>,
+    ) -> Result<Self, Error> {
+        let mut out = ByTarget::default();
+        for reference in references {
+            out.insert(reference)?;
+        }
+        Ok(out)
+    }
+}
+
+/// Access
+impl ByTarget {
+    /// Return all references pointing to `id`, directly or through annotated tags, sorted by name.
+    pub fn get<'a>(&'a self, id: &oid) -> impl Iterator<Item = &'a Entry> + 'a {
+        self.names_by_id
+            .get(id)
+            .into_iter()
+            .flatten()
+            .map(move |name| &self.entries[name])
+    }
+
+    /// Return the entry for the reference with `name`, if it is known.
+    pub fn by_name(&self, name: &FullNameRef) -> Option<&Entry> {
+        self.entries.get(name)
+    }
+
+    /// Return all entries, sorted by reference name.
+    pub fn iter(&self) -> impl Iterator<Item = &Entry> + '_ {
+        self.entries.values()
+    }
+
+    /// Return the amount of references in the index.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return `true` if there are no references in the index.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Return all references that pass `filter` and whose peeled commit contains `commit`, i.e. from which `commit` is reachable,
+    /// sorted by name.
+    ///
+    /// This is how `git branch --contains` finds its branches, and references that don't point to commits are ignored.
+    /// Reachability is determined with [`gix_revision::ahead_behind()`], which uses the commit-graph if it is available.
+    pub fn containing<'a>(
+        &'a self,
+        repo: &crate::Repository,
+        commit: &oid,
+        mut filter: impl FnMut(&Entry) -> bool,
+    ) -> Result<Vec<&'a Entry>, Error> {
+        let mut graph = repo.revision_graph();
+        let mut out = Vec::new();
+        for entry in self.entries.values().filter(|entry| filter(entry)) {
+            if repo.find_header(entry.peeled)?.kind() != gix_object::Kind::Commit {
+                continue;
+            }
+            if gix_revision::ahead_behind(commit, &entry.peeled, &mut graph, None)?.ahead == 0 {
+                out.push(entry);
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Mutation
+impl ByTarget {
+    /// Refresh the entries of all references with the given `names` in `repo`, removing the ones that don't exist anymore.
+    pub fn update<'a>(
+        &mut self,
+        repo: &crate::Repository,
+        names: impl IntoIterator<Item = &'a FullNameRef>,
+    ) -> Result<(), Error> {
+        for name in names {
+            self.remove(name);
+            if let Some(reference) = repo.try_find_reference(name)? {
+                self.insert(reference)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Add `reference`, replacing a previous entry of the same name.
+    pub fn insert(&mut self, mut reference: crate::Reference<'_>) -> Result<(), Error> {
+        let name = reference.name().to_owned();
+        self.remove(name.as_ref());
+
+        let target = {
+            // Note that `follow()` also peels references with known peeled targets, which is why it's only used for symbolic ones.
+            let mut leaf = None;
+            while let gix_ref::TargetRef::Symbolic(_) = leaf.as_ref().unwrap_or(&reference).target() {
+                let next = match leaf.as_ref().unwrap_or(&reference).follow() {
+                    Some(next) => next?,
+                    None => break,
+                };
+                leaf = Some(next);
+            }
+            match leaf.as_ref().unwrap_or(&reference).try_id() {
+                Some(id) => id.detach(),
+                None => return Ok(()),
+            }
+        };
+        let peeled = reference.peel_to_id_in_place()?.detach();
+        for id in std::iter::once(target).chain((target != peeled).then_some(peeled)) {
+            let names = self.names_by_id.entry(id).or_default();
+            let pos = names.binary_search(&name).unwrap_or_else(|pos| pos);
+            names.insert(pos, name.clone());
+        }
+        self.entries.insert(name.clone(), Entry { name, target, peeled });
+        Ok(())
+    }
+
+    /// Remove the reference named `name` and return its entry, if it was present.
+    pub fn remove(&mut self, name: &FullNameRef) -> Option<Entry> {
+        let entry = self.entries.remove(name)?;
+        for id in [entry.target, entry.peeled] {
+            if let Some(names) = self.names_by_id.get_mut(&id) {
+                names.retain(|n| n.as_ref() != name);
+                if names.is_empty() {
+                    self.names_by_id.remove(&id);
+                }
+            }
+        }
+        Some(entry)
+    }
+}
@@ -5,9 +5,15 @@ use gix_ref::file::ReferenceExt;
 
 use crate::{Id, Reference};
 
+#[cfg(feature = "revision")]
+pub mod format;
+pub mod hidden;
 pub mod iter;
 ///
 #[allow(clippy::empty_docs)]
+pub mod lookup;
+///
+#[allow(clippy::empty_docs)]
 pub mod remote;
 
 mod errors;
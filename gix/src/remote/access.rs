@@ -27,6 +27,18 @@ impl<'repo> Remote<'repo> {
         self.fetch_tags
     }
 
+    /// Return whether local tracking refs that don't exist on the remote anymore are deleted when fetching,
+    /// or `None` if this wasn't configured for this remote, in which case `fetch.prune` is used.
+    pub fn prune(&self) -> Option<bool> {
+        self.prune
+    }
+
+    /// Return whether local tags that don't exist on the remote anymore are deleted when fetching,
+    /// or `None` if this wasn't configured for this remote, in which case `fetch.pruneTags` is used.
+    pub fn prune_tags(&self) -> Option<bool> {
+        self.prune_tags
+    }
+
     /// Return the url used for the given `direction` with rewrites from `url.<base>.insteadOf|pushInsteadOf`, unless the instance
     /// was created with one of the `_without_url_rewrite()` methods.
     /// For pushing, this is the `remote.<name>.pushUrl` or the `remote.<name>.url` used for fetching, and for fetching it's
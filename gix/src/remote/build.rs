@@ -33,6 +33,20 @@ impl Remote<'_> {
         self
     }
 
+    /// Configure whether local tracking refs that don't exist on the remote anymore are deleted when fetching,
+    /// overriding `fetch.prune`.
+    pub fn with_prune(mut self, prune: bool) -> Self {
+        self.prune = Some(prune);
+        self
+    }
+
+    /// Configure whether local tags that don't exist on the remote anymore are deleted when fetching,
+    /// overriding `fetch.pruneTags`. This only has an effect if [pruning](Self::with_prune()) is enabled as well.
+    pub fn with_prune_tags(mut self, prune_tags: bool) -> Self {
+        self.prune_tags = Some(prune_tags);
+        self
+    }
+
     fn push_url_inner(
         mut self,
         push_url: gix_url::Url,
@@ -43,6 +43,19 @@ pub enum Error {
     RejectShallowRemoteConfig(#[from] config::boolean::Error),
     #[error("Receiving objects from shallow remotes is prohibited due to the value of `clone.rejectShallow`")]
     RejectShallowRemote,
+    #[error("Could not obtain configuration to learn if the remote is a promisor remote")]
+    PromisorConfig(#[source] config::boolean::Error),
+    #[error("The filter configured in `remote.<name>.partialCloneFilter` is invalid")]
+    PartialCloneFilter(#[from] crate::remote::fetch::filter::parse::Error),
+    #[error("Failed to write .promisor file at \"{}\"", path.display())]
+    WritePromisorFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Failed to record the compatibility ids of the received objects")]
+    WriteObjectMap(#[source] gix_lock::acquire::Error),
+    #[error("Could not obtain configuration to learn if refs should be pruned")]
+    PruneConfig(#[source] config::boolean::Error),
     #[error(transparent)]
     NegotiationAlgorithmConfig(#[from] config::key::GenericErrorWithValue),
     #[error("Failed to read remaining bytes in stream")]
@@ -87,6 +87,11 @@ pub mod outcome {
         pub graph: gix_negotiate::IdMap,
         /// Additional information for each round of negotiation.
         pub rounds: Vec<negotiate::Round>,
+        /// The commits that were used as tips for the negotiation, sorted by id.
+        ///
+        /// These are the commits of our own references and those advertised by alternate object databases, excluding
+        /// those whose immediate history isn't present locally if a promisor remote is configured.
+        pub tips: Vec<gix_hash::ObjectId>,
     }
 
     ///
@@ -195,6 +200,7 @@ where
             reflog_message: None,
             write_packed_refs: WritePackedRefs::Never,
             shallow: Default::default(),
+            filter: None,
         })
     }
 }
@@ -226,6 +232,7 @@ where
     reflog_message: Option<RefLogMessage>,
     write_packed_refs: WritePackedRefs,
     shallow: remote::fetch::Shallow,
+    filter: Option<remote::fetch::filter::Spec>,
 }
 
 /// Builder
@@ -267,6 +274,15 @@ where
         self.shallow = shallow;
         self
     }
+
+    /// Ask the remote to omit objects as described by `filter`, like `git fetch --filter` does, to create or extend a partial clone.
+    ///
+    /// If unset, `remote.<name>.partialCloneFilter` is used if the remote is a promisor remote.
+    /// Like `git`, the filter is ignored if the server doesn't support it.
+    pub fn with_filter(mut self, filter: remote::fetch::filter::Spec) -> Self {
+        self.filter = Some(filter);
+        self
+    }
 }
 
 impl<'remote, 'repo, T> Drop for Prepare<'remote, 'repo, T>
@@ -25,6 +25,10 @@ pub enum Error {
     ObtainRefDuringIteration(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
     #[error(transparent)]
     LoadIndex(#[from] gix_odb::store::load_index::Error),
+    #[error(transparent)]
+    PromisorConfig(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    OpenShallow(#[from] crate::shallow::open::Error),
 }
 
 #[must_use]
@@ -40,6 +44,8 @@ pub(crate) enum Action {
     MustNegotiate {
         /// Each `ref_map.mapping` has a slot here which is `true` if we have the object the remote ref points to, locally.
         remote_ref_target_known: Vec<bool>,
+        /// The commits that were passed to the negotiator as tips, sorted by id.
+        tips: Vec<gix_hash::ObjectId>,
     },
 }
 
@@ -61,6 +67,11 @@ pub(crate) enum Action {
 /// Finally, we also mark tips in the `negotiator` in one go to avoid traversing all refs twice, since we naturally encounter all tips during
 /// our own walk.
 ///
+/// Tips are the commits of our own references, along with the ones advertised by each alternate object database, as configured by
+/// `core.alternateRefsCommand` and `core.alternateRefsPrefixes`.
+/// If the repository has a promisor remote, objects may be missing locally, so tips whose immediate history isn't present are skipped
+/// to avoid claiming history we don't have.
+///
 /// Return whether we should negotiate, along with a queue for later use.
 pub(crate) fn mark_complete_and_common_ref(
     repo: &crate::Repository,
@@ -140,8 +151,11 @@ pub(crate) fn mark_complete_and_common_ref(
     // color our commits as complete as identified by references, unconditionally
     // (`git` is conditional here based on `deepen`, but it doesn't make sense and it's hard to extract from history when that happened).
     let mut queue = Queue::new();
-    mark_all_refs_in_repo(repo, graph, &mut queue, Flags::COMPLETE)?;
-    mark_alternate_complete(repo, graph, &mut queue)?;
+    let mut probe = has_promisor_remote(repo)?
+        .then(|| repo.shallow_commits().map(HistoryProbe::new))
+        .transpose()?;
+    mark_all_refs_in_repo(repo, graph, &mut queue, Flags::COMPLETE, probe.as_mut())?;
+    mark_alternate_complete(repo, graph, &mut queue, probe.as_mut())?;
     // Keep track of the tips, which happen to be on our queue right, before we traverse the graph with cutoff.
     let tips = if let Some(cutoff) = cutoff_date {
         let tips = Cow::Owned(queue.clone());
@@ -182,8 +196,11 @@ pub(crate) fn mark_complete_and_common_ref(
         Ok(())
     })?;
 
+    let mut tips: Vec<_> = tips.iter_unordered().copied().collect();
+    tips.sort();
     Ok(Action::MustNegotiate {
         remote_ref_target_known,
+        tips,
     })
 }
 
@@ -289,11 +306,33 @@ fn mark_all_refs_in_repo(
     graph: &mut gix_negotiate::Graph<'_>,
     queue: &mut Queue,
     mark: Flags,
+    probe: Option<&mut HistoryProbe>,
 ) -> Result<(), Error> {
     let _span = gix_trace::detail!("mark_all_refs");
+    let mut tips = Vec::new();
     for local_ref in repo.references()?.all()?.peeled() {
-        let local_ref = local_ref?;
-        let id = local_ref.id().detach();
+        tips.push(local_ref?.id().detach());
+    }
+    mark_tips(repo, tips, graph, queue, mark, probe)
+}
+
+/// Mark all commits in `tips` with `mark` and put them onto `queue` unless they have been marked as complete already,
+/// while skipping those whose history is incomplete according to `probe`.
+fn mark_tips(
+    repo: &crate::Repository,
+    tips: impl IntoIterator<Item = gix_hash::ObjectId>,
+    graph: &mut gix_negotiate::Graph<'_>,
+    queue: &mut Queue,
+    mark: Flags,
+    mut probe: Option<&mut HistoryProbe>,
+) -> Result<(), Error> {
+    for id in tips {
+        if let Some(probe) = probe.as_deref_mut() {
+            if !probe.has_immediate_history(repo, id) {
+                gix_trace::debug!("skipping tip {id} as its history is incomplete");
+                continue;
+            }
+        }
         let mut is_complete = false;
         if let Some(commit) = graph
             .try_lookup_or_insert_commit(id, |md| {
@@ -312,6 +351,7 @@ fn mark_alternate_complete(
     repo: &crate::Repository,
     graph: &mut gix_negotiate::Graph<'_>,
     queue: &mut Queue,
+    mut probe: Option<&mut HistoryProbe>,
 ) -> Result<(), Error> {
     let alternates = repo.objects.store_ref().alternate_db_paths()?;
     let _span = gix_trace::detail!("mark_alternate_refs", num_odb = alternates.len());
@@ -321,11 +361,167 @@ fn mark_alternate_complete(
             .nth(1)
             .and_then(|git_dir| crate::open_opts(git_dir, repo.options.clone()).ok())
     }) {
-        mark_all_refs_in_repo(&alternate_repo, graph, queue, Flags::ALTERNATE | Flags::COMPLETE)?;
+        let tips = alternate_ref_tips(repo, &alternate_repo)?;
+        mark_tips(
+            repo,
+            tips,
+            graph,
+            queue,
+            Flags::ALTERNATE | Flags::COMPLETE,
+            probe.as_deref_mut(),
+        )?;
     }
     Ok(())
 }
 
+/// Return the peeled ids of all references advertised by `alternate`, as configured in `repo`.
+///
+/// If `core.alternateRefsCommand` is set, it's run with the path to the `alternate` repository as argument, and each line it prints
+/// up to the first one that isn't an object id is used. Just like in `git`, failing commands are ignored.
+/// Otherwise, all references of `alternate` are used, or only those that start with one of the whitespace-separated prefixes
+/// in `core.alternateRefsPrefixes`.
+fn alternate_ref_tips(
+    repo: &crate::Repository,
+    alternate: &crate::Repository,
+) -> Result<Vec<gix_hash::ObjectId>, Error> {
+    use crate::{
+        bstr::ByteSlice,
+        config::tree::{Core, Key},
+    };
+
+    let mut tips = Vec::new();
+    if let Some(program) = repo
+        .config_snapshot()
+        .trusted_program(Core::ALTERNATE_REFS_COMMAND.logical_name().as_str())
+    {
+        let Some(stdout) = run_alternate_refs_command(&program, alternate.git_dir()) else {
+            return Ok(tips);
+        };
+        tips.extend(
+            stdout
+                .lines()
+                .map_while(|line| gix_hash::ObjectId::from_hex(line).ok())
+                .filter_map(|id| {
+                    alternate
+                        .find_object(id)
+                        .ok()?
+                        .peel_tags_to_end()
+                        .ok()
+                        .map(|object| object.id)
+                }),
+        );
+        return Ok(tips);
+    }
+
+    let prefixes = repo
+        .config
+        .resolved
+        .string_filter(Core::ALTERNATE_REFS_PREFIXES, &mut repo.filter_config_section());
+    let platform = alternate.references()?;
+    match prefixes
+        .as_ref()
+        .map(|prefixes| prefixes.fields_with(char::is_whitespace))
+    {
+        Some(prefixes) => {
+            for prefix in prefixes.filter(|prefix| !prefix.is_empty()) {
+                for reference in platform.prefixed(gix_path::from_byte_slice(prefix))?.peeled() {
+                    tips.push(reference?.id().detach());
+                }
+            }
+        }
+        None => {
+            for reference in platform.all()?.peeled() {
+                tips.push(reference?.id().detach());
+            }
+        }
+    }
+    Ok(tips)
+}
+
+/// Run `program`, through the shell if it needs one, with `git_dir` as its only argument and return its output,
+/// or `None` if it couldn't be run or failed.
+fn run_alternate_refs_command(program: &std::ffi::OsStr, git_dir: &std::path::Path) -> Option<Vec<u8>> {
+    let output = gix_command::prepare(program)
+        .with_shell()
+        .arg(git_dir)
+        .spawn()
+        .ok()?
+        .wait_with_output()
+        .ok()?;
+    output.status.success().then_some(output.stdout)
+}
+
+/// Return `true` if `repo` may be missing objects as they can be fetched lazily from a promisor remote, which is
+/// the case if `extensions.partialClone` is set, or if any remote is configured with `remote.<name>.promisor`.
+fn has_promisor_remote(repo: &crate::Repository) -> Result<bool, Error> {
+    use crate::config::tree::Remote;
+
+    if repo.config.repository_format.partial_clone.is_some() {
+        return Ok(true);
+    }
+    for name in repo.remote_names() {
+        let is_promisor = repo
+            .config
+            .resolved
+            .boolean_filter(
+                format!("remote.{name}.{}", Remote::PROMISOR.name).as_str(),
+                &mut repo.filter_config_section(),
+            )
+            .map(|value| Remote::PROMISOR.enrich_error(value))
+            .transpose()?
+            .unwrap_or(false);
+        if is_promisor {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Check if the recent history of tips is available locally, which may not be the case when objects are fetched
+/// lazily from a promisor remote.
+struct HistoryProbe {
+    shallow: Option<crate::shallow::Commits>,
+    buf: Vec<u8>,
+}
+
+impl HistoryProbe {
+    /// The amount of generations of parents that have to be present for a tip to be usable.
+    const DEPTH: usize = 2;
+
+    fn new(shallow: Option<crate::shallow::Commits>) -> Self {
+        HistoryProbe {
+            shallow,
+            buf: Vec::new(),
+        }
+    }
+
+    /// Return `true` if the commit at `id` and its ancestors up to [`Self::DEPTH`] generations are present in `repo`,
+    /// without looking past shallow boundaries.
+    fn has_immediate_history(&mut self, repo: &crate::Repository, id: gix_hash::ObjectId) -> bool {
+        let mut generation = vec![id];
+        for depth in 0..=Self::DEPTH {
+            let mut parents = Vec::new();
+            for id in generation {
+                let Ok(Some((data, _location))) = repo.objects.try_find(&id, &mut self.buf) else {
+                    return false;
+                };
+                let is_shallow = matches!(&self.shallow, Some(shallow) if shallow.binary_search(&id).is_ok());
+                if depth == Self::DEPTH || is_shallow {
+                    continue;
+                }
+                match data.kind {
+                    gix_object::Kind::Commit => {
+                        parents.extend(gix_object::CommitRefIter::from_bytes(data.data).parent_ids())
+                    }
+                    _ => continue,
+                }
+            }
+            generation = parents;
+        }
+        true
+    }
+}
+
 /// Negotiate the nth `round` with `negotiator` sending `haves_to_send` after possibly making the known common commits
 /// as sent by the remote known to `negotiator` using `previous_response` if this isn't the first round.
 /// All `haves` are added to `arguments` accordingly.
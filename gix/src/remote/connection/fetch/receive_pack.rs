@@ -10,9 +10,10 @@ use gix_protocol::{
 };
 
 use crate::{
+    bstr::BStr,
     config::{
         cache::util::ApplyLeniency,
-        tree::{Clone, Fetch, Key},
+        tree::{Clone, Fetch, Key, Remote},
     },
     remote,
     remote::{
@@ -126,6 +127,26 @@ where
             arguments.use_include_tag();
         }
         let (shallow_commits, mut shallow_lock) = add_shallow_args(&mut arguments, &self.shallow, repo)?;
+        let remote_name = con.remote.name().map(remote::Name::as_bstr);
+        let is_promisor = match remote_name {
+            Some(name) => is_promisor_remote(repo, name)?,
+            None => false,
+        };
+        let filter = match self.filter.take() {
+            Some(filter) => Some(filter),
+            None => match remote_name {
+                Some(name) if is_promisor => partial_clone_filter(repo, name)?,
+                _ => None,
+            },
+        };
+        // Like `git`, ignore the filter if the server can't handle it.
+        let uses_filter = match &filter {
+            Some(filter) if arguments.can_use_filter() => {
+                arguments.filter(&filter.to_string());
+                true
+            }
+            _ => false,
+        };
 
         if self.ref_map.object_hash != repo.object_hash() {
             return Err(Error::IncompatibleObjectHash {
@@ -174,6 +195,7 @@ where
             }
             negotiate::Action::MustNegotiate {
                 remote_ref_target_known,
+                tips,
             } => {
                 negotiate::add_wants(
                     repo,
@@ -272,6 +294,7 @@ where
                     index_version: config::pack_index_version(repo)?,
                     iteration_mode: gix_pack::data::input::Mode::Verify,
                     object_hash: con.remote.repo.object_hash(),
+                    compat_object_hash: repo.config.repository_format.compat_object_format,
                 };
 
                 let write_pack_bundle = if matches!(self.dry_run, fetch::DryRun::No) {
@@ -313,6 +336,22 @@ where
                 };
                 drop(reader);
 
+                if is_promisor || uses_filter {
+                    // Mark the pack as received from a promisor so objects it refers to may legitimately be missing.
+                    if let Some(data_path) = write_pack_bundle.as_ref().and_then(|bundle| bundle.data_path.as_ref()) {
+                        let path = data_path.with_extension("promisor");
+                        std::fs::write(&path, b"").map_err(|err| Error::WritePromisorFile { path, source: err })?;
+                    }
+                }
+
+                if let Some(bundle) = write_pack_bundle.as_ref() {
+                    gix_odb::loose::object_map::append(
+                        repo.objects.store_ref().path(),
+                        bundle.index.compat_ids.iter().copied(),
+                    )
+                    .map_err(Error::WriteObjectMap)?;
+                }
+
                 if matches!(protocol_version, gix_protocol::transport::Protocol::V2) {
                     gix_protocol::indicate_end_of_interaction(&mut con.transport, con.trace)
                         .await
@@ -324,11 +363,44 @@ where
                         crate::shallow::write(shallow_lock, shallow_commits, previous_response.shallow_updates())?;
                     }
                 }
-                (write_pack_bundle, Some(outcome::Negotiate { graph, rounds }))
+                (
+                    write_pack_bundle,
+                    Some(outcome::Negotiate {
+                        graph,
+                        rounds,
+                        tips: tips.clone(),
+                    }),
+                )
             }
         };
 
-        let update_refs = refs::update(
+        let prune = |remote_value: Option<bool>, key: &'static crate::config::tree::keys::Boolean| {
+            remote_value
+                .map(Ok)
+                .or_else(|| {
+                    repo.config
+                        .resolved
+                        .boolean_filter(key.logical_name().as_str(), &mut repo.filter_config_section())
+                        .map(|value| key.enrich_error(value))
+                })
+                .transpose()
+                .map(|value| value.unwrap_or(false))
+                .map_err(Error::PruneConfig)
+        };
+        let pruned = if prune(con.remote.prune, &Fetch::PRUNE)? {
+            refs::prune(
+                repo,
+                &self.ref_map.remote_refs,
+                con.remote.refspecs(remote::Direction::Fetch),
+                &self.ref_map.extra_refspecs,
+                con.remote.fetch_tags,
+                prune(con.remote.prune_tags, &Fetch::PRUNE_TAGS)?,
+                self.dry_run,
+            )?
+        } else {
+            Vec::new()
+        };
+        let mut update_refs = refs::update(
             repo,
             self.reflog_message
                 .take()
@@ -340,6 +412,7 @@ where
             self.dry_run,
             self.write_packed_refs,
         )?;
+        update_refs.pruned = pruned;
 
         if let Some(bundle) = write_pack_bundle.as_mut() {
             if !update_refs.edits.is_empty() || bundle.index.num_objects == 0 {
@@ -368,6 +441,38 @@ where
     }
 }
 
+/// Return `true` if the remote named `name` is a promisor remote, i.e. it's the remote configured in `extensions.partialClone`
+/// or `remote.<name>.promisor` is set.
+fn is_promisor_remote(repo: &Repository, name: &BStr) -> Result<bool, Error> {
+    if repo.config.repository_format.partial_clone.as_ref().map(AsRef::as_ref) == Some(name) {
+        return Ok(true);
+    }
+    Ok(repo
+        .config
+        .resolved
+        .boolean_filter(
+            format!("remote.{name}.{}", Remote::PROMISOR.name).as_str(),
+            &mut repo.filter_config_section(),
+        )
+        .map(|value| Remote::PROMISOR.enrich_error(value))
+        .transpose()
+        .map_err(Error::PromisorConfig)?
+        .unwrap_or(false))
+}
+
+/// Return the filter configured in `remote.<name>.partialCloneFilter`, if set.
+fn partial_clone_filter(repo: &Repository, name: &BStr) -> Result<Option<fetch::filter::Spec>, Error> {
+    Ok(repo
+        .config
+        .resolved
+        .string_filter(
+            format!("remote.{name}.{}", Remote::PARTIAL_CLONE_FILTER.name).as_str(),
+            &mut repo.filter_config_section(),
+        )
+        .map(|spec| fetch::filter::parse(spec.as_ref()))
+        .transpose()?)
+}
+
 fn acquire_shallow_lock(repo: &Repository) -> Result<gix_lock::File, Error> {
     gix_lock::File::acquire_to_update_resource(repo.shallow_file(), gix_lock::acquire::Fail::Immediately, None)
         .map_err(Into::into)
@@ -1,5 +1,8 @@
 #![allow(clippy::result_large_err)]
-use std::{collections::BTreeMap, path::PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+};
 
 use gix_object::Exists;
 use gix_ref::{
@@ -8,6 +11,7 @@ use gix_ref::{
 };
 
 use crate::{
+    bstr::{BStr, ByteSlice},
     ext::ObjectIdExt,
     remote::{
         fetch,
@@ -23,6 +27,8 @@ use crate::{
 #[allow(clippy::empty_docs)]
 pub mod update;
 
+pub mod summary;
+
 /// Information about the update of a single reference, corresponding the respective entry in [`RefMap::mappings`][crate::remote::fetch::RefMap::mappings].
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Update {
@@ -338,7 +344,161 @@ pub(crate) fn update(
         fetch::DryRun::Yes => edits,
     };
 
-    Ok(update::Outcome { edits, updates })
+    Ok(update::Outcome {
+        edits,
+        updates,
+        pruned: Vec::new(),
+    })
+}
+
+/// Delete all local refs in `repo` that are matched by the destination of a pattern refspec in `refspecs` or `extra_refspecs`,
+/// but whose source doesn't exist in `remote_refs` anymore, and return the edits that were performed.
+/// If `prune_tags` is `true`, `refs/tags/*:refs/tags/*` is considered as well, but only if `fetch_tags` makes the remote
+/// advertise its tags.
+/// If `dry_run` is true, the deletions won't actually be applied.
+///
+/// Local refs are never pruned if…
+///
+/// * …they are the destination of a refspec without pattern, as these were requested explicitly
+/// * …their source on the remote is excluded by a negative refspec
+/// * …they are currently checked out
+/// * …they are symbolic, like `refs/remotes/origin/HEAD`, just like `git` never prunes them
+///
+/// All deletions are performed in a single transaction, which also deletes the reflogs of the pruned refs.
+///
+/// ### Deviation
+///
+/// `git` adds `refs/tags/*:refs/tags/*` to the fetch refspecs if `prune_tags` is set, fetching all tags. Here we only prune
+/// tags if they are fetched anyway, i.e. unless `fetch_tags` is [`None`](fetch::Tags::None).
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn prune(
+    repo: &Repository,
+    remote_refs: &[gix_protocol::handshake::Ref],
+    refspecs: &[gix_refspec::RefSpec],
+    extra_refspecs: &[gix_refspec::RefSpec],
+    fetch_tags: fetch::Tags,
+    prune_tags: bool,
+    dry_run: fetch::DryRun,
+) -> Result<Vec<RefEdit>, update::Error> {
+    use gix_refspec::{instruction::Fetch, Instruction};
+    let _span = gix_trace::detail!("prune_refs()", remote_refs = remote_refs.len());
+
+    let tag_refspec = fetch_tags
+        .to_refspec()
+        .filter(|_| prune_tags)
+        .map(|spec| spec.to_owned());
+    let specs = || {
+        refspecs
+            .iter()
+            .chain(extra_refspecs)
+            .chain(tag_refspec.as_ref())
+            .map(|spec| spec.to_ref().instruction())
+    };
+    let mut patterns = Vec::new();
+    let mut explicit_destinations = BTreeSet::new();
+    let mut excluded = Vec::new();
+    for instruction in specs() {
+        match instruction {
+            Instruction::Fetch(Fetch::AndUpdate { src, dst, .. }) => {
+                if dst.contains(&b'*') {
+                    patterns.push((src, dst));
+                } else {
+                    explicit_destinations.insert(dst);
+                }
+            }
+            Instruction::Fetch(Fetch::Exclude { src }) => excluded.push(src),
+            _ => {}
+        }
+    }
+    if patterns.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let remote_names: BTreeSet<&BStr> = remote_refs.iter().map(|r| r.unpack().0).collect();
+    let checked_out_branches = worktree_branches(repo)?;
+    let platform = repo.references()?;
+    let mut seen = BTreeSet::new();
+    let mut edits = Vec::new();
+    for (_, dst) in &patterns {
+        let dst_prefix = &dst[..dst.find_byte(b'*').expect("pattern")];
+        let dir = &dst_prefix[..dst_prefix.rfind_byte(b'/').map_or(0, |pos| pos + 1)];
+        let dir = gix_path::from_bstr(dir.as_bstr());
+        for reference in platform.prefixed(dir)? {
+            let reference = reference.map_err(update::Error::IterReferences)?;
+            let name = reference.name().as_bstr();
+            if !seen.insert(name.to_owned()) || explicit_destinations.contains(name) {
+                continue;
+            }
+            let mut sources = patterns
+                .iter()
+                .filter_map(|(src, dst)| {
+                    glob_match(dst, name).map(|matched| {
+                        let (prefix, suffix) = src.split_at(src.find_byte(b'*').expect("pattern"));
+                        let mut src = prefix.to_owned();
+                        src.extend_from_slice(matched);
+                        src.extend_from_slice(&suffix[1..]);
+                        src
+                    })
+                })
+                .peekable();
+            if sources.peek().is_none() {
+                continue;
+            }
+            let is_stale = sources.all(|src| {
+                !remote_names.contains(src.as_bstr())
+                    && !excluded.iter().any(|pattern| {
+                        pattern.as_bytes() == src.as_slice() || glob_match(pattern, src.as_bstr()).is_some()
+                    })
+            });
+            if !is_stale
+                || checked_out_branches.contains_key(reference.name())
+                || reference.target().try_name().is_some()
+            {
+                continue;
+            }
+            edits.push(RefEdit {
+                change: Change::Delete {
+                    expected: PreviousValue::MustExistAndMatch(reference.target().into_owned()),
+                    log: RefLog::AndReference,
+                },
+                name: reference.name().to_owned(),
+                deref: false,
+            });
+        }
+    }
+
+    if edits.is_empty() {
+        return Ok(edits);
+    }
+    Ok(match dry_run {
+        fetch::DryRun::No => {
+            let _span = gix_trace::detail!("apply", edits = edits.len());
+            let (file_lock_fail, packed_refs_lock_fail) = repo
+                .config
+                .lock_timeout()
+                .map_err(crate::reference::edit::Error::from)?;
+            repo.refs
+                .transaction()
+                .packed_refs(gix_ref::file::transaction::PackedRefs::DeletionsOnly)
+                .prepare(edits, file_lock_fail, packed_refs_lock_fail)
+                .map_err(crate::reference::edit::Error::from)?
+                .commit(
+                    repo.committer()
+                        .transpose()
+                        .map_err(crate::reference::edit::Error::ParseCommitterTime)?,
+                )
+                .map_err(crate::reference::edit::Error::from)?
+        }
+        fetch::DryRun::Yes => edits,
+    })
+}
+
+/// Return the portion of `name` matched by the single `*` in `pattern`, or `None` if `pattern` doesn't match.
+fn glob_match<'a>(pattern: &BStr, name: &'a BStr) -> Option<&'a BStr> {
+    let pos = pattern.find_byte(b'*')?;
+    let (prefix, suffix) = (&pattern[..pos], &pattern[pos + 1..]);
+    (name.len() >= prefix.len() + suffix.len() && name.starts_with(prefix) && name.ends_with(suffix))
+        .then(|| &name[prefix.len()..name.len() - suffix.len()])
 }
 
 /// Figure out if target of `edit` points to a reference that doesn't exist in `repo` and won't exist as it's not in any of `edits`.
@@ -0,0 +1,243 @@
+//! Produce the information `git fetch` displays for each updated reference, without deciding on how it's presented.
+use std::borrow::Cow;
+
+use gix_ref::{
+    transaction::{Change, PreviousValue, RefEdit},
+    Target,
+};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    remote::{fetch, fetch::refs::update},
+    Repository,
+};
+
+/// The character in the first column of each line, indicating what happened to the reference.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Flag {
+    /// The local reference was fast-forwarded.
+    FastForward,
+    /// The local reference was forcefully set to a commit that isn't a descendant of its previous one.
+    Forced,
+    /// The local reference was deleted as it doesn't exist on the remote anymore.
+    Pruned,
+    /// An existing tag was forcefully updated.
+    TagUpdate,
+    /// The local reference was created, or the remote reference was only fetched without a local reference to update.
+    New,
+    /// The local reference was not updated.
+    Rejected,
+    /// The local reference already had the value of the remote reference.
+    ///
+    /// `git` only displays these with `--verbose`.
+    UpToDate,
+}
+
+impl Flag {
+    /// Return the character `git` uses for this flag.
+    pub fn as_char(&self) -> char {
+        match self {
+            Flag::FastForward => ' ',
+            Flag::Forced => '+',
+            Flag::Pruned => '-',
+            Flag::TagUpdate => 't',
+            Flag::New => '*',
+            Flag::Rejected => '!',
+            Flag::UpToDate => '=',
+        }
+    }
+}
+
+/// The summary of what happened to a reference, displayed right after the [`Flag`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Summary {
+    /// A short description like `[new branch]` or `[rejected]`, or the kind of the remote reference if it wasn't written
+    /// to a local reference.
+    Text(&'static str),
+    /// The local reference was changed from `previous` to `new`.
+    Range {
+        /// The shortened id the local reference pointed to before.
+        previous: gix_hash::Prefix,
+        /// The shortened id the local reference points to now.
+        new: gix_hash::Prefix,
+        /// If `true`, `new` isn't a descendant of `previous`, which is displayed with three dots instead of two.
+        forced: bool,
+    },
+}
+
+impl std::fmt::Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Summary::Text(text) => f.write_str(text),
+            Summary::Range { previous, new, forced } => {
+                write!(f, "{previous}{}{new}", if *forced { "..." } else { ".." })
+            }
+        }
+    }
+}
+
+/// A single line of the summary `git fetch` prints after updating references, split into its parts.
+///
+/// `git` displays it as `<flag> <summary> <remote> -> <local>  (<reason>)`, with `summary` and `remote` padded to the width
+/// of the widest entry. Coloring and alignment are left to the caller.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    /// What happened to the reference.
+    pub flag: Flag,
+    /// A description of the change, or the range of ids that the local reference moved through.
+    pub summary: Summary,
+    /// The shortened name of the remote reference, or `(none)` if the local reference was pruned.
+    pub remote: BString,
+    /// The shortened name of the local reference, or `FETCH_HEAD` if there was no local reference to update.
+    pub local: BString,
+    /// Additional information in parentheses, typically the reason for a rejection.
+    pub reason: Option<Cow<'static, str>>,
+}
+
+impl update::Outcome {
+    /// Produce one [`Line`] for each pruned reference and each update, in the order `git fetch` would display them,
+    /// using the `mappings` that were used to produce this outcome.
+    ///
+    /// Ids are shortened with [`Repository::id_shortener()`] of `repo`, which should be the repository that was fetched into.
+    /// Note that lines with [`Flag::UpToDate`] are contained as well, and tags that were implicitly included but not sent
+    /// by the remote are skipped, just like `git` does.
+    ///
+    /// ### Deviation
+    ///
+    /// As there is no implementation of `push` yet, there is no equivalent for the summary `git push` displays.
+    pub fn summary_lines(
+        &self,
+        repo: &Repository,
+        mappings: &[fetch::Mapping],
+    ) -> Result<Vec<Line>, crate::id::shorten::Error> {
+        let shortener = repo.id_shortener()?;
+        let mut lines = Vec::with_capacity(self.pruned.len() + self.updates.len());
+        for edit in &self.pruned {
+            lines.push(Line {
+                flag: Flag::Pruned,
+                summary: Summary::Text("[deleted]"),
+                remote: "(none)".into(),
+                local: prettify(edit.name.as_bstr()).into(),
+                reason: None,
+            });
+        }
+
+        for (update, mapping) in self.updates.iter().zip(mappings) {
+            let remote_name: BString = match mapping.remote.as_name() {
+                Some(name) => name.to_owned(),
+                None => mapping
+                    .remote
+                    .as_id()
+                    .map(|id| id.to_hex().to_string().into())
+                    .unwrap_or_default(),
+            };
+            let remote = if remote_name == "HEAD" {
+                BString::default()
+            } else {
+                prettify(remote_name.as_ref()).into()
+            };
+            let Some(local_name) = mapping.local.as_ref() else {
+                if matches!(update.mode, update::Mode::ImplicitTagNotSentByRemote) {
+                    continue;
+                }
+                let kind = if remote_name.starts_with(b"refs/heads/") {
+                    "branch"
+                } else if remote_name.starts_with(b"refs/tags/") {
+                    "tag"
+                } else if remote_name.starts_with(b"refs/remotes/") {
+                    "remote-tracking branch"
+                } else if remote_name == "HEAD" {
+                    "branch"
+                } else {
+                    ""
+                };
+                lines.push(Line {
+                    flag: Flag::New,
+                    summary: Summary::Text(kind),
+                    remote: if remote.is_empty() { "HEAD".into() } else { remote },
+                    local: "FETCH_HEAD".into(),
+                    reason: None,
+                });
+                continue;
+            };
+            let is_tag = local_name.starts_with(b"refs/tags/");
+            let rejected = |reason: Cow<'static, str>| (Flag::Rejected, Summary::Text("[rejected]"), Some(reason));
+            let (flag, summary, reason) = match &update.mode {
+                update::Mode::ImplicitTagNotSentByRemote => continue,
+                update::Mode::NoChangeNeeded => (Flag::UpToDate, Summary::Text("[up to date]"), None),
+                update::Mode::FastForward | update::Mode::Forced => {
+                    let forced = matches!(update.mode, update::Mode::Forced);
+                    match update
+                        .edit_index
+                        .and_then(|idx| self.edits.get(idx))
+                        .and_then(previous_and_new_id)
+                    {
+                        Some(_) if is_tag && forced => (Flag::TagUpdate, Summary::Text("[tag update]"), None),
+                        Some((previous, new)) => (
+                            if forced { Flag::Forced } else { Flag::FastForward },
+                            Summary::Range {
+                                previous: shortener.shorten(previous)?,
+                                new: shortener.shorten(new)?,
+                                forced,
+                            },
+                            forced.then_some(Cow::Borrowed("forced update")),
+                        ),
+                        // The local reference was symbolic or unborn, which `git` displays like a new reference.
+                        None => (Flag::New, new_ref_summary(remote_name.as_ref()), None),
+                    }
+                }
+                update::Mode::New => (Flag::New, new_ref_summary(remote_name.as_ref()), None),
+                update::Mode::RejectedSourceObjectNotFound { id } => rejected(format!("{id} not found").into()),
+                update::Mode::RejectedTagUpdate => rejected("would clobber existing tag".into()),
+                update::Mode::RejectedNonFastForward => rejected("non-fast-forward".into()),
+                update::Mode::RejectedToReplaceWithUnborn => rejected("refusing to replace with unborn ref".into()),
+                update::Mode::RejectedCurrentlyCheckedOut { worktree_dirs } => rejected(
+                    if worktree_dirs.iter().any(|dir| Some(dir.as_path()) == repo.work_dir()) {
+                        "can't fetch in current branch".into()
+                    } else {
+                        "checked out in another worktree".into()
+                    },
+                ),
+            };
+            lines.push(Line {
+                flag,
+                summary,
+                remote,
+                local: prettify(local_name.as_ref()).into(),
+                reason,
+            });
+        }
+        Ok(lines)
+    }
+}
+
+fn previous_and_new_id(edit: &RefEdit) -> Option<(gix_hash::ObjectId, gix_hash::ObjectId)> {
+    match &edit.change {
+        Change::Update {
+            expected: PreviousValue::MustExistAndMatch(Target::Peeled(previous)),
+            new: Target::Peeled(new),
+            ..
+        } => Some((*previous, *new)),
+        _ => None,
+    }
+}
+
+/// Like `git`, classify new references by the name of their source on the remote.
+fn new_ref_summary(remote_name: &BStr) -> Summary {
+    Summary::Text(if remote_name.starts_with(b"refs/tags/") {
+        "[new tag]"
+    } else if remote_name.starts_with(b"refs/heads/") {
+        "[new branch]"
+    } else {
+        "[new ref]"
+    })
+}
+
+/// Strip the prefixes `git` strips when displaying reference names.
+fn prettify(name: &BStr) -> &BStr {
+    ["refs/heads/", "refs/tags/", "refs/remotes/"]
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix.as_bytes()))
+        .unwrap_or(name)
+        .as_bstr()
+}
@@ -23,6 +23,12 @@ mod error {
         PeelToId(#[from] crate::reference::peel::Error),
         #[error("Failed to follow a symbolic reference to assure worktree isn't affected")]
         FollowSymref(#[from] gix_ref::file::find::existing::Error),
+        #[error("Could not open the packed-refs file to find references to prune")]
+        OpenPackedRefs(#[from] crate::reference::iter::Error),
+        #[error("Could not iterate references to find those to prune")]
+        IterReferencesInit(#[from] crate::reference::iter::init::Error),
+        #[error("A reference could not be read while finding those to prune")]
+        IterReferences(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
     }
 }
 
@@ -37,6 +43,10 @@ pub struct Outcome {
     /// Use [`iter_mapping_updates()`][Self::iter_mapping_updates()] to recombine the update information with ref-edits and their
     /// mapping.
     pub updates: Vec<super::Update>,
+    /// All edits that deleted local refs because they don't exist on the remote anymore, if pruning was enabled.
+    ///
+    /// These are listed separately as they don't correspond to any mapping.
+    pub pruned: Vec<gix_ref::transaction::RefEdit>,
 }
 
 /// Describe the way a ref was updated, with particular focus on how the (peeled) target commit was affected.
@@ -12,6 +12,8 @@ pub mod negotiate {
     };
 }
 
+pub mod filter;
+
 #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
 pub use super::connection::fetch::{
     outcome, prepare, refs, Error, Outcome, Prepare, ProgressId, RefLogMessage, Status,
@@ -0,0 +1,392 @@
+//! Object filters as used by partial clones, like `blob:none`, which tell the server which objects to omit from a pack.
+use crate::bstr::{BStr, BString, ByteSlice, ByteVec};
+
+/// A parsed object filter specification, as passed to `git clone --filter` or sent by clients as `filter` argument of a fetch.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Spec {
+    /// `blob:none`, which omits all blobs.
+    BlobNone,
+    /// `blob:limit=<n>[kmg]`, which omits all blobs whose size is at least the given amount of bytes.
+    BlobLimit(u64),
+    /// `tree:<depth>`, which omits all trees and blobs whose depth from the root tree is at least the given one.
+    ///
+    /// The root tree has a depth of `0`, which is why `tree:0` omits all trees and blobs.
+    TreeDepth(u64),
+    /// `object:type=<type>`, which omits all objects that aren't of the given kind.
+    ObjectType(gix_object::Kind),
+    /// `sparse:oid=<blob-ish>`, which omits all blobs that don't match the sparse-checkout patterns in the blob at the given revision.
+    ///
+    /// Note that the revision is resolved by the side that evaluates the filter, which typically is the server, and that
+    /// it must be a full object id or a `<rev>:<path>` specification of a blob.
+    SparseOid(BString),
+    /// `combine:<spec>+<spec>…`, which omits all objects that are omitted by any of the given filters.
+    Combine(Vec<Spec>),
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod parse {
+    use crate::bstr::BString;
+
+    /// The error returned by [`parse()`](super::parse()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("invalid filter-spec '{spec}'")]
+        Invalid { spec: BString },
+        #[error("expected 'tree:<depth>', got '{spec}'")]
+        TreeDepth { spec: BString },
+        #[error("'{name}' for 'object:type=<type>' is not a valid object type")]
+        ObjectType { name: BString },
+        #[error("sparse:path filters support has been dropped")]
+        SparsePath,
+        #[error("expected something after combine:")]
+        CombineEmpty,
+        #[error("must escape char in sub-filter-spec: '{}'", *byte as char)]
+        CombineUnescaped { byte: u8 },
+    }
+}
+
+/// Parse `spec` like `git` does, with sizes and depths allowing a `k`, `m` or `g` suffix, and the sub-filters of `combine:`
+/// being percent-encoded.
+pub fn parse(spec: &BStr) -> Result<Spec, parse::Error> {
+    use parse::Error;
+    let invalid = || Error::Invalid { spec: spec.to_owned() };
+    Ok(if spec == "blob:none" {
+        Spec::BlobNone
+    } else if let Some(limit) = spec.strip_prefix(b"blob:limit=") {
+        Spec::BlobLimit(parse_unsigned(limit).ok_or_else(invalid)?)
+    } else if let Some(depth) = spec.strip_prefix(b"tree:") {
+        Spec::TreeDepth(parse_unsigned(depth).ok_or_else(|| Error::TreeDepth { spec: spec.to_owned() })?)
+    } else if let Some(name) = spec.strip_prefix(b"object:type=") {
+        Spec::ObjectType(gix_object::Kind::from_bytes(name).map_err(|_| Error::ObjectType { name: name.into() })?)
+    } else if let Some(revision) = spec.strip_prefix(b"sparse:oid=") {
+        Spec::SparseOid(revision.into())
+    } else if spec.starts_with(b"sparse:path=") {
+        return Err(Error::SparsePath);
+    } else if let Some(specs) = spec.strip_prefix(b"combine:") {
+        if specs.is_empty() {
+            return Err(Error::CombineEmpty);
+        }
+        Spec::Combine(
+            specs
+                .split_str("+")
+                .map(|spec| {
+                    if let Some(&byte) = spec.iter().find(|byte| is_reserved(**byte)) {
+                        return Err(Error::CombineUnescaped { byte });
+                    }
+                    parse(percent_decode(spec).as_ref())
+                })
+                .collect::<Result<_, _>>()?,
+        )
+    } else {
+        return Err(invalid());
+    })
+}
+
+impl Spec {
+    /// Serialize this instance into the canonical form that is sent to the server.
+    ///
+    /// Like `git`, sizes and depths are written in full without suffix, so `blob:limit=1k` becomes `blob:limit=1024`.
+    /// The sub-filters of `combine:` are percent-encoded as needed.
+    pub fn to_bstring(&self) -> BString {
+        let mut out = BString::default();
+        self.write_to(&mut out);
+        out
+    }
+
+    fn write_to(&self, out: &mut BString) {
+        match self {
+            Spec::BlobNone => out.push_str("blob:none"),
+            Spec::BlobLimit(limit) => out.push_str(format!("blob:limit={limit}")),
+            Spec::TreeDepth(depth) => out.push_str(format!("tree:{depth}")),
+            Spec::ObjectType(kind) => {
+                out.push_str("object:type=");
+                out.push_str(kind.as_bytes());
+            }
+            Spec::SparseOid(revision) => {
+                out.push_str("sparse:oid=");
+                out.push_str(revision);
+            }
+            Spec::Combine(specs) => {
+                out.push_str("combine:");
+                for (idx, spec) in specs.iter().enumerate() {
+                    if idx != 0 {
+                        out.push(b'+');
+                    }
+                    for &byte in spec.to_bstring().iter() {
+                        if byte <= b' ' || byte >= 0x80 || byte == b'%' || byte == b'+' || is_reserved(byte) {
+                            out.push_str(format!("%{byte:02x}"));
+                        } else {
+                            out.push(byte);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Spec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.to_bstring(), f)
+    }
+}
+
+/// Characters that must be percent-encoded in sub-filters of `combine:`.
+fn is_reserved(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\n' | 0x0b | 0x0c | b'\r') || b"~`!@#$^&*()[]{}\\;'\",<>?".contains(&byte)
+}
+
+/// Decode `%XX` sequences in `input`, keeping all other bytes including invalid sequences as they are.
+fn percent_decode(input: &[u8]) -> BString {
+    let hex = |byte: u8| (byte as char).to_digit(16).map(|digit| digit as u8);
+    let mut out = BString::default();
+    let mut idx = 0;
+    while idx < input.len() {
+        match (
+            input[idx],
+            input.get(idx + 1).copied().and_then(hex),
+            input.get(idx + 2).copied().and_then(hex),
+        ) {
+            (b'%', Some(high), Some(low)) => {
+                out.push(high << 4 | low);
+                idx += 3;
+            }
+            (byte, _, _) => {
+                out.push(byte);
+                idx += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Parse `value` like `git_parse_ulong()` does, allowing leading whitespace, a leading `+`, hexadecimal numbers with `0x` prefix,
+/// octal numbers with `0` prefix, and a case-insensitive `k`, `m` or `g` suffix to multiply the value with the respective power of 1024.
+fn parse_unsigned(value: &[u8]) -> Option<u64> {
+    if value.contains(&b'-') {
+        return None;
+    }
+    let mut value = value.trim_start_with(|c| c.is_ascii_whitespace() || c == '\x0b');
+    value = value.strip_prefix(b"+").unwrap_or(value);
+    let (radix, digits) = match value {
+        [b'0', b'x' | b'X', next, ..] if next.is_ascii_hexdigit() => (16, &value[2..]),
+        [b'0', ..] => (8, value),
+        _ => (10, value),
+    };
+    let num_digits = digits
+        .iter()
+        .take_while(|byte| (**byte as char).is_digit(radix))
+        .count();
+    if num_digits == 0 {
+        return None;
+    }
+    let number = digits[..num_digits].iter().try_fold(0u64, |acc, byte| {
+        acc.checked_mul(radix as u64)?
+            .checked_add((*byte as char).to_digit(radix).expect("checked") as u64)
+    })?;
+    let factor: u64 = match digits[num_digits..].to_ascii_lowercase().as_slice() {
+        b"" => 1,
+        b"k" => 1024,
+        b"m" => 1024 * 1024,
+        b"g" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    number.checked_mul(factor)
+}
+
+#[cfg(feature = "revision")]
+pub use evaluate::{Decision, Filter, Object};
+
+///
+#[allow(clippy::empty_docs)]
+#[cfg(feature = "revision")]
+pub mod init {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Filter::new()`](super::Filter::new()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not resolve the revision of the sparse filter")]
+        RevParse(#[from] crate::revision::spec::parse::single::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error("The sparse filter revision '{revision}' must be an object id or a '<rev>:<path>' blob specification")]
+        SparseInvalidRevision { revision: BString },
+        #[error("The sparse filter revision '{revision}' must point to a blob, but points to a {actual}")]
+        SparseNotABlob {
+            revision: BString,
+            actual: gix_object::Kind,
+        },
+    }
+}
+
+#[cfg(feature = "revision")]
+mod evaluate {
+    use super::{init, Spec};
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        Repository,
+    };
+
+    /// An object that is encountered while enumerating the objects reachable from a set of tips.
+    #[derive(Debug, Copy, Clone)]
+    pub struct Object<'a> {
+        /// The id of the object.
+        pub id: &'a gix_hash::oid,
+        /// The kind of the object.
+        pub kind: gix_object::Kind,
+        /// The depth of the object, with root trees having a depth of `0` and their entries having a depth of `1`.
+        ///
+        /// Objects that aren't reached through a tree have a depth of `0` as well.
+        pub depth: usize,
+        /// The path of the object relative to its root tree, or an empty path if it isn't reached through a tree.
+        pub path: &'a BStr,
+    }
+
+    /// What to do with an [`Object`] according to a [`Filter`].
+    #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+    pub enum Decision {
+        /// Include the object.
+        Include,
+        /// Omit the object, but visit its entries if it's a tree as they may be included.
+        Omit,
+        /// Omit the tree along with all objects reachable from it.
+        OmitTree,
+    }
+
+    /// Decide which objects to omit according to a [`Spec`] while enumerating objects, similar to `git rev-list --filter`.
+    ///
+    /// Note that it's up to the caller to include objects that were explicitly asked for, like `git` does with blobs.
+    pub struct Filter<'repo> {
+        repo: &'repo Repository,
+        filter: Resolved,
+    }
+
+    enum Resolved {
+        BlobNone,
+        BlobLimit(u64),
+        TreeDepth(u64),
+        ObjectType(gix_object::Kind),
+        Sparse(Vec<gix_glob::Pattern>),
+        Combine(Vec<Resolved>),
+    }
+
+    impl<'repo> Filter<'repo> {
+        /// Create a new instance to evaluate `spec` on objects in `repo`, reading the patterns of `sparse:oid` filters right away.
+        pub fn new(spec: &Spec, repo: &'repo Repository) -> Result<Self, init::Error> {
+            Ok(Filter {
+                repo,
+                filter: resolve(spec, repo)?,
+            })
+        }
+
+        /// Decide what to do with `object`, reading the size of blobs from the object database if needed.
+        pub fn decide(&self, object: Object<'_>) -> Result<Decision, crate::object::find::existing::Error> {
+            self.decide_inner(&self.filter, object)
+        }
+
+        fn decide_inner(
+            &self,
+            filter: &Resolved,
+            object: Object<'_>,
+        ) -> Result<Decision, crate::object::find::existing::Error> {
+            use gix_object::Kind;
+            let omit_if = |omit: bool| if omit { Decision::Omit } else { Decision::Include };
+            Ok(match filter {
+                Resolved::BlobNone => omit_if(object.kind == Kind::Blob),
+                Resolved::BlobLimit(limit) => {
+                    omit_if(object.kind == Kind::Blob && self.repo.find_header(object.id)?.size() >= *limit)
+                }
+                Resolved::TreeDepth(depth) => match object.kind {
+                    Kind::Tree if object.depth as u64 >= *depth => Decision::OmitTree,
+                    Kind::Blob if object.depth as u64 >= *depth => Decision::Omit,
+                    _ => Decision::Include,
+                },
+                Resolved::ObjectType(kind) => omit_if(object.kind != *kind),
+                Resolved::Sparse(patterns) => {
+                    omit_if(object.kind == Kind::Blob && !is_sparse_match(patterns, object.path))
+                }
+                Resolved::Combine(filters) => {
+                    let mut decision = Decision::Include;
+                    for filter in filters {
+                        match self.decide_inner(filter, object)? {
+                            Decision::Include => {}
+                            Decision::Omit => decision = Decision::Omit,
+                            Decision::OmitTree => return Ok(Decision::OmitTree),
+                        }
+                    }
+                    decision
+                }
+            })
+        }
+    }
+
+    fn resolve(spec: &Spec, repo: &Repository) -> Result<Resolved, init::Error> {
+        Ok(match spec {
+            Spec::BlobNone => Resolved::BlobNone,
+            Spec::BlobLimit(limit) => Resolved::BlobLimit(*limit),
+            Spec::TreeDepth(depth) => Resolved::TreeDepth(*depth),
+            Spec::ObjectType(kind) => Resolved::ObjectType(*kind),
+            Spec::SparseOid(revision) => {
+                let id = match gix_hash::ObjectId::from_hex(revision) {
+                    Ok(id) if id.kind() == repo.object_hash() => id,
+                    _ if matches!(revision.find_byte(b':'), Some(pos) if pos > 0) => {
+                        repo.rev_parse_single(revision.as_bstr())?.detach()
+                    }
+                    _ => {
+                        return Err(init::Error::SparseInvalidRevision {
+                            revision: revision.clone(),
+                        })
+                    }
+                };
+                let object = repo.find_object(id)?;
+                if object.kind != gix_object::Kind::Blob {
+                    return Err(init::Error::SparseNotABlob {
+                        revision: revision.clone(),
+                        actual: object.kind,
+                    });
+                }
+                Resolved::Sparse(
+                    object
+                        .data
+                        .lines()
+                        .filter(|line| !line.starts_with(b"#"))
+                        .filter_map(|line| gix_glob::parse(line.trim_end_with(|c| c == ' ')))
+                        .collect(),
+                )
+            }
+            Spec::Combine(specs) => {
+                Resolved::Combine(specs.iter().map(|spec| resolve(spec, repo)).collect::<Result<_, _>>()?)
+            }
+        })
+    }
+
+    /// Return `true` if the blob at `path` matches `patterns`, or if the closest of its parent directories that matches
+    /// any pattern is included, just like `git` does for sparse checkouts.
+    fn is_sparse_match(patterns: &[gix_glob::Pattern], path: &BStr) -> bool {
+        let mut path = path;
+        let mut is_dir = false;
+        loop {
+            let basename_pos = path.rfind_byte(b'/').map(|pos| pos + 1);
+            if let Some(pattern) = patterns.iter().rev().find(|pattern| {
+                pattern.matches_repo_relative_path(
+                    path,
+                    basename_pos,
+                    Some(is_dir),
+                    gix_glob::pattern::Case::Sensitive,
+                    gix_glob::wildmatch::Mode::NO_MATCH_SLASH_LITERAL,
+                )
+            }) {
+                return !pattern.is_negative();
+            }
+            if path.is_empty() {
+                return false;
+            }
+            path = path[..basename_pos.map_or(0, |pos| pos - 1)].as_bstr();
+            is_dir = true;
+        }
+    }
+}
@@ -34,6 +34,8 @@ impl<'repo> Remote<'repo> {
         push_specs: Vec<RefSpec>,
         should_rewrite_urls: bool,
         fetch_tags: remote::fetch::Tags,
+        prune: Option<bool>,
+        prune_tags: Option<bool>,
         repo: &'repo Repository,
     ) -> Result<Self, Error> {
         debug_assert!(
@@ -52,6 +54,8 @@ impl<'repo> Remote<'repo> {
             fetch_specs,
             push_specs,
             fetch_tags,
+            prune,
+            prune_tags,
             repo,
         })
     }
@@ -89,6 +93,8 @@ impl<'repo> Remote<'repo> {
             fetch_specs: Vec::new(),
             push_specs: Vec::new(),
             fetch_tags: Default::default(),
+            prune: None,
+            prune_tags: None,
             repo,
         })
     }
@@ -56,6 +56,8 @@ impl Remote<'_> {
                 config::tree::Remote::FETCH.name,
                 config::tree::Remote::PUSH.name,
                 config::tree::Remote::TAG_OPT.name,
+                config::tree::Remote::PRUNE.name,
+                config::tree::Remote::PRUNE_TAGS.name,
             ];
             for id in section_ids {
                 let mut section = config.section_mut_by_id(id).expect("just queried");
@@ -94,6 +96,14 @@ impl Remote<'_> {
                 .into(),
             );
         }
+        for (key, value) in [
+            (config::tree::Remote::PRUNE.name, self.prune),
+            (config::tree::Remote::PRUNE_TAGS.name, self.prune_tags),
+        ] {
+            if let Some(value) = value {
+                section.push(as_key(key), Some(BStr::new(if value { "true" } else { "false" })));
+            }
+        }
         for (key, spec) in self
             .fetch_specs
             .iter()
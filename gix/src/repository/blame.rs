@@ -0,0 +1,83 @@
+use gix_hash::ObjectId;
+
+use crate::{bstr::BStr, Repository};
+
+///
+#[allow(clippy::empty_docs)]
+pub mod blame_ignore_revs {
+    use std::path::PathBuf;
+
+    /// The error returned by [Repository::blame_ignore_revs()](crate::Repository::blame_ignore_revs()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        PathInterpolation(#[from] gix_config::path::interpolate::Error),
+        #[error("Could not read the revisions to ignore from '{}'", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+        #[error("Could not parse the revisions to ignore in '{}'", path.display())]
+        Parse {
+            source: gix_blame::ignore_revs::Error,
+            path: PathBuf,
+        },
+    }
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod blame_file {
+    /// The error returned by [Repository::blame_file()](crate::Repository::blame_file()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        IgnoreRevs(#[from] super::blame_ignore_revs::Error),
+        #[error(transparent)]
+        Blame(#[from] gix_blame::file::Error),
+    }
+}
+
+/// Blame-utilities
+impl Repository {
+    /// Return the commits to ignore when blaming files, as listed in the files configured with `blame.ignoreRevsFile`,
+    /// in the format understood by [`gix_blame::ignore_revs::parse()`].
+    ///
+    /// Relative paths are relative to the working tree, or to the `.git` directory if there is none, and an empty value
+    /// discards all files configured before it. Just like `git` does, listed objects are peeled to commits, and those that don't exist
+    /// or can't be peeled are skipped.
+    pub fn blame_ignore_revs(&self) -> Result<gix_hashtable::HashSet<ObjectId>, blame_ignore_revs::Error> {
+        let mut out = gix_hashtable::HashSet::default();
+        let base = self.work_dir().unwrap_or_else(|| self.git_dir());
+        for path in self.config.blame_ignore_revs_files()? {
+            let path = base.join(path);
+            let data = std::fs::read(&path).map_err(|source| blame_ignore_revs::Error::Io {
+                source,
+                path: path.clone(),
+            })?;
+            let ids = gix_blame::ignore_revs::parse(&data, self.object_hash())
+                .map_err(|source| blame_ignore_revs::Error::Parse { source, path })?;
+            out.extend(ids.into_iter().filter_map(|id| {
+                self.try_find_object(id)
+                    .ok()
+                    .flatten()
+                    .and_then(|object| object.peel_to_kind(gix_object::Kind::Commit).ok())
+                    .map(|commit| commit.id)
+            }));
+        }
+        Ok(out)
+    }
+
+    /// Blame the file at the slash-separated `file_path` as it is in the commit `suspect`, i.e. find the commit that last changed
+    /// each of its lines, with `options` to control the algorithm.
+    ///
+    /// The [configured revisions to ignore](Self::blame_ignore_revs()) are added to [`ignore_revs`](gix_blame::Options::ignore_revs).
+    pub fn blame_file(
+        &self,
+        file_path: &BStr,
+        suspect: impl Into<ObjectId>,
+        mut options: gix_blame::Options,
+    ) -> Result<gix_blame::Outcome, blame_file::Error> {
+        options.ignore_revs.extend(self.blame_ignore_revs()?);
+        Ok(gix_blame::file(&self.objects, suspect.into(), file_path, options)?)
+    }
+}
@@ -120,6 +120,11 @@ impl crate::Repository {
     pub fn object_hash(&self) -> gix_hash::Kind {
         self.config.object_hash
     }
+
+    /// The format of the repository as defined by `core.repositoryFormatVersion`, along with the extensions it uses.
+    pub fn repository_format(&self) -> &crate::config::RepositoryFormat {
+        &self.config.repository_format
+    }
 }
 
 mod branch;
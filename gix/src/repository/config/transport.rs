@@ -101,19 +101,11 @@ impl crate::Repository {
                     }
 
                     fn ssl_version(
-                        config: &gix_config::File<'static>,
-                        key_str: &'static str,
+                        value: Option<Cow<'_, BStr>>,
                         key: &'static config::tree::http::SslVersion,
-                        mut filter: fn(&gix_config::file::Metadata) -> bool,
                         lenient: bool,
                     ) -> Result<Option<SslVersion>, config::transport::Error> {
-                        debug_assert_eq!(
-                            key_str,
-                            key.logical_name(),
-                            "BUG: hardcoded and generated key names must match"
-                        );
-                        config
-                            .string_filter(key_str, &mut filter)
+                        value
                             .filter(|v| !v.is_empty())
                             .map(|v| {
                                 key.try_into_ssl_version(v)
@@ -145,11 +137,11 @@ impl crate::Repository {
                     let config = &self.config.resolved;
                     let mut trusted_only = self.filter_config_section();
                     let lenient = self.config.lenient_config;
+                    let http = http_scoped::Sections::new(config, &url, &mut trusted_only);
                     opts.extra_headers = {
                         let key = "http.extraHeader";
                         debug_assert_eq!(key, &config::tree::Http::EXTRA_HEADER.logical_name());
-                        config
-                            .strings_filter(key, &mut trusted_only)
+                        http.values(config::tree::Http::EXTRA_HEADER.name)
                             .map(|values| config::tree::Http::EXTRA_HEADER.try_into_extra_header(values))
                             .transpose()
                             .map_err(|err| config::transport::Error::IllformedUtf8 {
@@ -160,14 +152,12 @@ impl crate::Repository {
                     };
 
                     opts.follow_redirects = {
-                        let key = "http.followRedirects";
-
                         config::tree::Http::FOLLOW_REDIRECTS
                             .try_into_follow_redirects(
-                                config.string_filter(key, &mut trusted_only).unwrap_or_default(),
+                                http.value(config::tree::Http::FOLLOW_REDIRECTS.name)
+                                    .unwrap_or_default(),
                                 || {
-                                    config
-                                        .boolean_filter(key, &mut trusted_only)
+                                    http.boolean(config::tree::Http::FOLLOW_REDIRECTS.name)
                                         .transpose()
                                         .with_leniency(lenient)
                                 },
@@ -175,15 +165,15 @@ impl crate::Repository {
                             .map_err(config::transport::http::Error::InvalidFollowRedirects)?
                     };
 
-                    opts.low_speed_time_seconds = config
-                        .integer_filter("http.lowSpeedTime", &mut trusted_only)
+                    opts.low_speed_time_seconds = http
+                        .integer(config::tree::Http::LOW_SPEED_TIME.name)
                         .map(|value| config::tree::Http::LOW_SPEED_TIME.try_into_u64(value))
                         .transpose()
                         .with_leniency(lenient)
                         .map_err(config::transport::http::Error::from)?
                         .unwrap_or_default();
-                    opts.low_speed_limit_bytes_per_second = config
-                        .integer_filter("http.lowSpeedLimit", &mut trusted_only)
+                    opts.low_speed_limit_bytes_per_second = http
+                        .integer(config::tree::Http::LOW_SPEED_LIMIT.name)
                         .map(|value| config::tree::Http::LOW_SPEED_LIMIT.try_into_u32(value))
                         .transpose()
                         .with_leniency(lenient)
@@ -202,8 +192,8 @@ impl crate::Repository {
                             .or_else(|| {
                                 let key = "http.proxy";
                                 debug_assert_eq!(key, config::tree::Http::PROXY.logical_name());
-                                let http_proxy = config
-                                    .string_filter(key, &mut trusted_only)
+                                let http_proxy = http
+                                    .value(config::tree::Http::PROXY.name)
                                     .map(|v| (v, cow_bstr(key), &config::tree::Http::PROXY))
                                     .or_else(|| {
                                         let key = "gitoxide.http.proxy";
@@ -266,7 +256,7 @@ impl crate::Repository {
                                     .or_else(|| {
                                         let key = "http.proxyAuthMethod";
                                         debug_assert_eq!(key, config::tree::Http::PROXY_AUTH_METHOD.logical_name());
-                                        config.string_filter(key, &mut trusted_only).map(|v| {
+                                        http.value(config::tree::Http::PROXY_AUTH_METHOD.name).map(|v| {
                                             (v, Cow::Borrowed(key.into()), &config::tree::Http::PROXY_AUTH_METHOD)
                                         })
                                     })
@@ -304,8 +294,8 @@ impl crate::Repository {
                     };
                     {
                         let key = "http.userAgent";
-                        opts.user_agent = config
-                            .string_filter(key, &mut trusted_only)
+                        opts.user_agent = http
+                            .value(config::tree::Http::USER_AGENT.name)
                             .and_then(|v| {
                                 try_cow_to_string(
                                     v,
@@ -320,9 +310,8 @@ impl crate::Repository {
                     }
 
                     {
-                        let key = "http.version";
-                        opts.http_version = config
-                            .string_filter(key, &mut trusted_only)
+                        opts.http_version = http
+                            .value(config::tree::Http::VERSION.name)
                             .map(|v| {
                                 config::tree::Http::VERSION
                                     .try_into_http_version(v)
@@ -339,9 +328,7 @@ impl crate::Repository {
                     }
 
                     let may_use_cainfo = {
-                        let key = "http.schannelUseSSLCAInfo";
-                        config
-                            .boolean_filter(key, &mut trusted_only)
+                        http.boolean(config::tree::Http::SCHANNEL_USE_SSL_CA_INFO.name)
                             .map(|value| config::tree::Http::SCHANNEL_USE_SSL_CA_INFO.enrich_error(value))
                             .transpose()
                             .with_leniency(lenient)
@@ -352,8 +339,8 @@ impl crate::Repository {
                     if may_use_cainfo {
                         let key = "http.sslCAInfo";
                         debug_assert_eq!(key, config::tree::Http::SSL_CA_INFO.logical_name());
-                        opts.ssl_ca_info = config
-                            .path_filter(key, &mut trusted_only)
+                        opts.ssl_ca_info = http
+                            .path(config::tree::Http::SSL_CA_INFO.name)
                             .map(|p| {
                                 use crate::config::cache::interpolate_context;
                                 p.interpolate(interpolate_context(
@@ -369,26 +356,20 @@ impl crate::Repository {
 
                     {
                         opts.ssl_version = ssl_version(
-                            config,
-                            "http.sslVersion",
+                            http.value(config::tree::Http::SSL_VERSION.name),
                             &config::tree::Http::SSL_VERSION,
-                            trusted_only,
                             lenient,
                         )?
                         .map(|v| SslVersionRangeInclusive { min: v, max: v });
                         let min_max = ssl_version(
-                            config,
-                            "gitoxide.http.sslVersionMin",
+                            config.string_filter(gitoxide::Http::SSL_VERSION_MIN, &mut trusted_only),
                             &gitoxide::Http::SSL_VERSION_MIN,
-                            trusted_only,
                             lenient,
                         )
                         .and_then(|min| {
                             ssl_version(
-                                config,
-                                "gitoxide.http.sslVersionMax",
+                                config.string_filter(gitoxide::Http::SSL_VERSION_MAX, &mut trusted_only),
                                 &gitoxide::Http::SSL_VERSION_MAX,
-                                trusted_only,
                                 lenient,
                             )
                             .map(|max| min.and_then(|min| max.map(|max| (min, max))))
@@ -416,9 +397,8 @@ impl crate::Repository {
                         if ssl_no_verify {
                             opts.ssl_verify = false;
                         } else {
-                            let key = "http.sslVerify";
-                            opts.ssl_verify = config
-                                .boolean_filter(key, &mut trusted_only)
+                            opts.ssl_verify = http
+                                .boolean(config::tree::Http::SSL_VERIFY.name)
                                 .map(|value| config::tree::Http::SSL_VERIFY.enrich_error(value))
                                 .transpose()
                                 .with_leniency(lenient)
@@ -429,9 +409,8 @@ impl crate::Repository {
 
                     #[cfg(feature = "blocking-http-transport-curl")]
                     {
-                        let key = "http.schannelCheckRevoke";
-                        let schannel_check_revoke = config
-                            .boolean_filter(key, &mut trusted_only)
+                        let schannel_check_revoke = http
+                            .boolean(config::tree::Http::SCHANNEL_CHECK_REVOKE.name)
                             .map(|value| config::tree::Http::SCHANNEL_CHECK_REVOKE.enrich_error(value))
                             .transpose()
                             .with_leniency(lenient)
@@ -448,3 +427,84 @@ impl crate::Repository {
         }
     }
 }
+
+#[cfg(any(
+    feature = "blocking-http-transport-reqwest",
+    feature = "blocking-http-transport-curl"
+))]
+mod http_scoped {
+    use std::borrow::Cow;
+
+    use crate::bstr::BStr;
+
+    /// All `[http]` and `[http "<url>"]` sections that apply to a URL, as ordered from least to most specific.
+    ///
+    /// ### Deviation
+    ///
+    /// Multi-valued keys like `http.extraHeader` accumulate the values of all applicable sections, starting with the least
+    /// specific one, whereas `git` only considers values of sections at least as specific as the ones seen before.
+    pub(super) struct Sections<'a> {
+        sections: Vec<&'a gix_config::file::Section<'static>>,
+    }
+
+    impl<'a> Sections<'a> {
+        pub fn new(
+            config: &'a gix_config::File<'static>,
+            url: &gix_url::Url,
+            filter: &mut gix_config::file::MetadataFilter,
+        ) -> Self {
+            let mut sections: Vec<_> = config
+                .sections_by_name("http")
+                .into_iter()
+                .flatten()
+                .filter(|section| filter(section.meta()))
+                .filter_map(|section| match section.header().subsection_name() {
+                    None => Some((Default::default(), section)),
+                    Some(pattern) => gix_url::parse(pattern)
+                        .ok()
+                        .and_then(|pattern| gix_url::matching::specificity(&pattern, url))
+                        .map(|specificity| (specificity, section)),
+                })
+                .collect();
+            // A stable sort assures that later sections win over equally specific ones.
+            sections.sort_by_key(|(specificity, _)| *specificity);
+            Sections {
+                sections: sections.into_iter().map(|(_, section)| section).collect(),
+            }
+        }
+
+        /// Return the value of `name` in the most specific section that has it.
+        pub fn value(&self, name: &str) -> Option<Cow<'a, BStr>> {
+            self.sections.iter().rev().find_map(|section| section.value(name))
+        }
+
+        /// Return all values of `name`, or `None` if there is none.
+        pub fn values(&self, name: &str) -> Option<Vec<Cow<'a, BStr>>> {
+            let values: Vec<_> = self.sections.iter().flat_map(|section| section.values(name)).collect();
+            (!values.is_empty()).then_some(values)
+        }
+
+        pub fn boolean(&self, name: &str) -> Option<Result<bool, gix_config::value::Error>> {
+            self.sections
+                .iter()
+                .rev()
+                .find_map(|section| section.value_implicit(name))
+                .map(|value| match value {
+                    Some(value) => gix_config::Boolean::try_from(value).map(Into::into),
+                    None => Ok(true),
+                })
+        }
+
+        pub fn integer(&self, name: &str) -> Option<Result<i64, gix_config::value::Error>> {
+            let value = self.value(name)?;
+            Some(gix_config::Integer::try_from(value.as_ref()).and_then(|int| {
+                int.to_decimal()
+                    .ok_or_else(|| gix_config::value::Error::new("Integer overflow", value.into_owned()))
+            }))
+        }
+
+        pub fn path(&self, name: &str) -> Option<gix_config::Path<'a>> {
+            self.value(name).map(gix_config::Path::from)
+        }
+    }
+}
@@ -49,4 +49,26 @@ impl Repository {
             worktree_roots,
         )?)
     }
+
+    /// Return options for producing unified diffs with the amount of context configured by `diff.context` and
+    /// `diff.interHunkContext`, for instance for use in [`format_patch::Options`](crate::commit::format_patch::Options).
+    ///
+    /// Whitespace differences can be ignored by adjusting the returned options.
+    #[allow(clippy::result_large_err)]
+    pub fn diff_unified_options(
+        &self,
+    ) -> Result<gix_diff::blob::unified_diff::Options, crate::diff::new_unified_diff_options::Error> {
+        crate::diff::new_unified_diff_options(&self.config.resolved, self.config.lenient_config)
+    }
+
+    /// Create a cache for the output of binary-to-text conversions of diff drivers which have `diff.<driver>.cachetextconv`
+    /// set, for use in [`Pipeline::binary_to_text_cache`](gix_diff::blob::Pipeline::binary_to_text_cache).
+    ///
+    /// It reads and writes the notes `git` uses for the same purpose, so both can share the cached conversions. Note that
+    /// new conversions have to be [written](crate::diff::binary_to_text_cache::Notes::write()) to be retained.
+    pub fn diff_binary_to_text_cache(
+        &self,
+    ) -> Result<crate::diff::binary_to_text_cache::Notes, crate::diff::binary_to_text_cache::init::Error> {
+        crate::diff::binary_to_text_cache::Notes::new(self)
+    }
 }
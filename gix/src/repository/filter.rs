@@ -51,7 +51,7 @@ impl Repository {
                 Ok,
             )?)?;
             let cache = self.attributes_only(&index, gix_worktree::stack::state::attributes::Source::IdMapping)?;
-            (cache, IndexPersistedOrInMemory::InMemory(index))
+            (cache, IndexPersistedOrInMemory::InMemory(Box::new(index)))
         } else {
             let index = self.index_or_empty()?;
             let cache = self.attributes_only(
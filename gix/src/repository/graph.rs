@@ -31,4 +31,81 @@ impl crate::Repository {
     pub fn commit_graph(&self) -> Result<gix_commitgraph::Graph, gix_commitgraph::init::Error> {
         gix_commitgraph::at(self.objects.store_ref().path().join("info"))
     }
+
+    /// Write the commit-graph of this repository's object database so that it contains all commits reachable from
+    /// all references and `HEAD`, in the given `mode`, and return information about the written file, or `None` if
+    /// nothing had to be written.
+    ///
+    /// In [split mode](gix_commitgraph::write::info_dir::Mode::Split), only commits that aren't in the existing commit-graph
+    /// yet are traversed and written into a new layer, which makes updating the commit-graph after a fetch cheap.
+    /// Corrected commit dates are written unless `commitGraph.generationVersion` is `1`.
+    ///
+    /// Note that shallow repositories are not supported, just like in `git`.
+    pub fn write_commit_graph(
+        &self,
+        mode: gix_commitgraph::write::info_dir::Mode,
+    ) -> Result<Option<gix_commitgraph::write::Outcome>, super::write_commit_graph::Error> {
+        use super::write_commit_graph::Error;
+        use crate::config::{cache::util::ApplyLeniency, tree::CommitGraph};
+
+        if self.is_shallow() {
+            return Err(Error::Shallow);
+        }
+        let generation_data = self
+            .config
+            .resolved
+            .integer_filter(CommitGraph::GENERATION_VERSION, &mut self.filter_config_section())
+            .map(|value| CommitGraph::GENERATION_VERSION.try_into_generation_data(value))
+            .transpose()
+            .with_leniency(self.options.lenient_config)?
+            .unwrap_or(true);
+        let existing = match mode {
+            gix_commitgraph::write::info_dir::Mode::Split(_) => self.commit_graph().ok(),
+            gix_commitgraph::write::info_dir::Mode::Single => None,
+        };
+
+        let mut tips = Vec::new();
+        for reference in self.references()?.all()?.peeled() {
+            let reference = reference.map_err(Error::ReferenceIter)?;
+            if let Some(id) = reference.try_id() {
+                tips.push(id.detach());
+            }
+        }
+        tips.extend(self.head_id().ok().map(crate::Id::detach));
+
+        let mut seen = gix_hashtable::HashSet::default();
+        let mut commits = Vec::new();
+        let mut queue = Vec::new();
+        for tip in tips {
+            if self.find_header(tip)?.kind() == gix_object::Kind::Commit {
+                queue.push(tip);
+            }
+        }
+        while let Some(id) = queue.pop() {
+            if !seen.insert(id) || existing.iter().any(|graph| graph.lookup(id).is_some()) {
+                continue;
+            }
+            let commit = self.find_object(id)?.try_into_commit()?;
+            let commit = commit.decode()?;
+            let parent_ids: Vec<_> = commit.parents().collect();
+            queue.extend(parent_ids.iter().copied());
+            commits.push(gix_commitgraph::write::Commit {
+                id,
+                tree_id: commit.tree(),
+                parent_ids,
+                commit_time: commit.committer().time.seconds.max(0) as u64,
+            });
+        }
+        drop(existing);
+
+        Ok(gix_commitgraph::write::to_info_dir(
+            &self.objects.store_ref().path().join("info"),
+            commits,
+            mode,
+            gix_commitgraph::write::Options {
+                object_hash: self.object_hash(),
+                generation_data,
+            },
+        )?)
+    }
 }
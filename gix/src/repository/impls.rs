@@ -12,6 +12,8 @@ impl Clone for crate::Repository {
             self.shallow_commits.clone(),
             #[cfg(feature = "attributes")]
             self.modules.clone(),
+            #[cfg(feature = "blocking-network-server")]
+            self.pack_bitmap.clone(),
         )
     }
 }
@@ -48,6 +50,8 @@ impl From<&crate::ThreadSafeRepository> for crate::Repository {
             repo.shallow_commits.clone(),
             #[cfg(feature = "attributes")]
             repo.modules.clone(),
+            #[cfg(feature = "blocking-network-server")]
+            repo.pack_bitmap.clone(),
         )
     }
 }
@@ -66,6 +70,8 @@ impl From<crate::ThreadSafeRepository> for crate::Repository {
             repo.shallow_commits,
             #[cfg(feature = "attributes")]
             repo.modules.clone(),
+            #[cfg(feature = "blocking-network-server")]
+            repo.pack_bitmap,
         )
     }
 }
@@ -84,6 +90,8 @@ impl From<crate::Repository> for crate::ThreadSafeRepository {
             #[cfg(feature = "attributes")]
             modules: r.modules,
             shallow_commits: r.shallow_commits,
+            #[cfg(feature = "blocking-network-server")]
+            pack_bitmap: r.pack_bitmap,
         }
     }
 }
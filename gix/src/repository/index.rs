@@ -61,13 +61,21 @@ impl crate::Repository {
     }
 
     /// Return the shared worktree index if present, or return a new empty one which has an association to the place where the index would be.
+    ///
+    /// A new index will be written with the version configured by `index.version`.
     pub fn index_or_empty(&self) -> Result<worktree::Index, worktree::open_index::Error> {
-        Ok(self.try_index()?.unwrap_or_else(|| {
-            worktree::Index::new(gix_fs::FileSnapshot::new(gix_index::File::from_state(
-                gix_index::State::new(self.object_hash()),
-                self.index_path(),
-            )))
-        }))
+        if let Some(index) = self.try_index()? {
+            return Ok(index);
+        }
+        let mut state = gix_index::State::new(self.object_hash());
+        state.set_version(
+            self.config
+                .index_version()
+                .map_err(worktree::open_index::Error::ConfigIndexVersion)?,
+        );
+        Ok(worktree::Index::new(gix_fs::FileSnapshot::new(
+            gix_index::File::from_state(state, self.index_path()),
+        )))
     }
 
     /// Return a shared worktree index which is updated automatically if the in-memory snapshot has become stale as the underlying file
@@ -105,24 +113,104 @@ impl crate::Repository {
             Some(index) => IndexPersistedOrInMemory::Persisted(index),
             None => {
                 let tree = self.head_commit()?.tree_id()?;
-                IndexPersistedOrInMemory::InMemory(self.index_from_tree(&tree)?)
+                IndexPersistedOrInMemory::InMemory(Box::new(self.index_from_tree(&tree)?))
             }
         })
     }
 
     /// Create new index-file, which would live at the correct location, in memory from the given `tree`.
     ///
+    /// It will be written with the version configured by `index.version`.
+    ///
     /// Note that this is an expensive operation as it requires recursively traversing the entire tree to unpack it into the index.
     pub fn index_from_tree(&self, tree: &gix_hash::oid) -> Result<gix_index::File, super::index_from_tree::Error> {
-        Ok(gix_index::File::from_state(
+        let mut state =
             gix_index::State::from_tree(tree, &self.objects, self.config.protect_options()?).map_err(|err| {
                 super::index_from_tree::Error::IndexFromTree {
                     id: tree.into(),
                     source: err,
                 }
-            })?,
-            self.git_dir().join("index"),
-        ))
+            })?;
+        state.set_version(self.config.index_version()?);
+        Ok(gix_index::File::from_state(state, self.git_dir().join("index")))
+    }
+
+    /// Add the file at `rela_path` in the working tree to `index`, replacing the entry that may already exist there,
+    /// and return the position of the added entry in `index`.
+    ///
+    /// With [`Mode::Content`](super::add_to_index::Mode::Content), the file is converted with the
+    /// [filter pipeline](Self::filter_pipeline()) and written as blob. With
+    /// [`Mode::IntentToAdd`](super::add_to_index::Mode::IntentToAdd), an *intent-to-add* entry pointing to the empty blob
+    /// is recorded instead, without any stat information just like `git` does. This makes the file show up as added in status,
+    /// with its whole content still to be staged.
+    ///
+    /// The entry's mode is derived from the file on disk, but if `core.fileMode` is `false` the executable bit of a previously
+    /// tracked entry at `rela_path` is retained, and new files won't be executable. The same goes for symlinks if `core.symlinks` is `false`.
+    ///
+    /// Note that `index` isn't written, and directories, including submodules, can't be added.
+    #[cfg(feature = "attributes")]
+    pub fn add_to_index(
+        &self,
+        index: &mut gix_index::State,
+        rela_path: &crate::bstr::BStr,
+        mode: super::add_to_index::Mode,
+    ) -> Result<usize, super::add_to_index::Error> {
+        use super::add_to_index::{Error, Mode};
+        use gix_index::entry::{Flags, Mode as EntryMode, Stage, Stat};
+
+        let path = self.work_dir_path(rela_path).ok_or(Error::MissingWorkDir)?;
+        let io_err = |source| Error::Io {
+            path: path.clone(),
+            source,
+        };
+        let metadata = gix_index::fs::Metadata::from_path_no_follow(&path).map_err(io_err)?;
+        let existing = index.entry_index_by_path_and_stage(rela_path, Stage::Unconflicted);
+        if let Some(idx) =
+            existing.filter(|idx| mode == Mode::IntentToAdd && !index.entry(*idx).flags.contains(Flags::INTENT_TO_ADD))
+        {
+            return Ok(idx);
+        }
+
+        let capabilities = self.config.fs_capabilities()?;
+        let previous_mode = index.entry_by_path(rela_path).map(|entry| entry.mode);
+        let entry_mode = EntryMode::from_fs(
+            &metadata,
+            capabilities.symlink,
+            capabilities.executable_bit,
+            previous_mode,
+        )
+        .filter(|mode| *mode != EntryMode::COMMIT)
+        .ok_or_else(|| Error::UnsupportedKind {
+            rela_path: rela_path.to_owned(),
+        })?;
+        let (stat, id, flags) = match mode {
+            Mode::IntentToAdd => (
+                Stat::default(),
+                gix_hash::ObjectId::empty_blob(self.object_hash()),
+                Flags::INTENT_TO_ADD | Flags::EXTENDED,
+            ),
+            Mode::Content => {
+                let data = if metadata.is_symlink() {
+                    let target = std::fs::read_link(&path).map_err(io_err)?;
+                    gix_path::into_bstr(target).into_owned().into()
+                } else if entry_mode == EntryMode::SYMLINK {
+                    std::fs::read(&path).map_err(io_err)?
+                } else {
+                    let file = std::fs::File::open(&path).map_err(io_err)?;
+                    let (mut pipeline, _) = self.filter_pipeline(None)?;
+                    let mut converted = pipeline.convert_to_git(file, &gix_path::from_bstr(rela_path), index)?;
+                    let mut buf = Vec::new();
+                    std::io::Read::read_to_end(&mut converted, &mut buf).map_err(io_err)?;
+                    buf
+                };
+                (
+                    Stat::from_fs(&metadata)?,
+                    self.write_blob(data)?.detach(),
+                    Flags::empty(),
+                )
+            }
+        };
+        Ok(index.upsert_entry(stat, id, flags, entry_mode, rela_path))
     }
 }
 
@@ -144,7 +232,7 @@ impl IndexPersistedOrInMemory {
     pub fn into_owned(self) -> gix_index::File {
         match self {
             IndexPersistedOrInMemory::Persisted(i) => gix_index::File::clone(&i),
-            IndexPersistedOrInMemory::InMemory(i) => i,
+            IndexPersistedOrInMemory::InMemory(i) => *i,
         }
     }
 }
@@ -12,6 +12,7 @@ impl crate::Repository {
         #[cfg(feature = "index")] index: crate::worktree::IndexStorage,
         shallow_commits: crate::shallow::CommitsStorage,
         #[cfg(feature = "attributes")] modules: crate::submodule::ModulesFileStorage,
+        #[cfg(feature = "blocking-network-server")] pack_bitmap: crate::upload_pack::PackBitmapStorage,
     ) -> Self {
         setup_objects(&mut objects, &config);
         crate::Repository {
@@ -27,6 +28,8 @@ impl crate::Repository {
             shallow_commits,
             #[cfg(feature = "attributes")]
             modules,
+            #[cfg(feature = "blocking-network-server")]
+            pack_bitmap,
         }
     }
 
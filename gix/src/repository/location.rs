@@ -2,6 +2,8 @@ use std::path::{Path, PathBuf};
 
 use gix_path::realpath::MAX_SYMLINKS;
 
+use crate::bstr::{BStr, BString};
+
 impl crate::Repository {
     /// Return the path to the repository itself, containing objects, references, configuration, and more.
     ///
@@ -48,11 +50,40 @@ impl crate::Repository {
     }
 
     /// Return the work tree containing all checked out files, if there is one.
+    ///
+    /// It's resolved from `GIT_WORK_TREE` if the repository was opened with environment overrides, `core.worktree`,
+    /// the location of the `.git` file of linked worktrees or submodules, or is the parent directory of the `.git` directory.
+    /// It's `None` for bare repositories, which don't have a worktree.
     #[doc(alias = "workdir", alias = "git2")]
     pub fn work_dir(&self) -> Option<&std::path::Path> {
         self.work_tree.as_deref()
     }
 
+    /// Return the path to `rela_path` within the [work tree](Self::work_dir()), or `None` if there is no work tree.
+    ///
+    /// `rela_path` is relative to the root of the work tree and uses slashes as separators, like paths in the index or in trees.
+    pub fn work_dir_path(&self, rela_path: impl AsRef<BStr>) -> Option<PathBuf> {
+        let work_dir = self.work_dir()?;
+        Some(
+            self.current_dir()
+                .join(work_dir)
+                .join(gix_path::from_bstr(rela_path.as_ref())),
+        )
+    }
+
+    /// Turn `path`, which is either absolute or relative to the [current working directory](Self::current_dir()), into a path
+    /// relative to the root of the [work tree](Self::work_dir()) with slashes as separators.
+    ///
+    /// Return `None` if there is no work tree, or if `path` isn't inside of it.
+    /// Note that symlinks are not resolved, so both paths are expected to use the same prefix.
+    pub fn work_dir_relative_path(&self, path: &Path) -> Option<BString> {
+        let cwd = self.current_dir();
+        let work_dir = gix_path::normalize(cwd.join(self.work_dir()?).into(), cwd)?;
+        let path = gix_path::normalize(cwd.join(path).into(), cwd)?;
+        let rela_path = path.strip_prefix(work_dir.as_ref()).ok()?;
+        Some(gix_path::to_unix_separators_on_windows(gix_path::into_bstr(rela_path)).into_owned())
+    }
+
     // TODO: tests, respect precomposeUnicode
     /// The directory of the binary path of the current process.
     pub fn install_dir(&self) -> std::io::Result<PathBuf> {
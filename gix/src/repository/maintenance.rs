@@ -0,0 +1,290 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use gix_hash::ObjectId;
+use gix_revision::{ahead_behind::Flags, graph::Commit, Graph};
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    config::{
+        cache::util::ApplyLeniency,
+        tree::{gc, Gc, Key},
+    },
+    reference::log::expire::{Cutoff, Error, Log, Options, Outcome},
+};
+
+impl crate::Repository {
+    /// Estimate if the object database would benefit from maintenance, like `git gc --auto` does before deciding
+    /// to do any work.
+    ///
+    /// The thresholds for the amount of loose objects and packs are configured with `gc.auto` and `gc.autoPackLimit`
+    /// respectively, and default to the ones used by git.
+    pub fn gc_advice(&self) -> Result<gix_odb::maintenance::GcAdvice, super::gc_advice::Error> {
+        let mut thresholds = gix_odb::maintenance::Thresholds::default();
+        for (key, threshold) in [
+            (&Gc::AUTO, &mut thresholds.loose_objects),
+            (&Gc::AUTO_PACK_LIMIT, &mut thresholds.packs),
+        ] {
+            if let Some(value) = self
+                .config
+                .resolved
+                .integer_filter(key, &mut self.filter_config_section())
+                .map(|value| key.try_into_limit(value))
+                .transpose()
+                .with_leniency(self.options.lenient_config)?
+            {
+                *threshold = value;
+            }
+        }
+        Ok(gix_odb::maintenance::gc_needed(self.objects.store_ref(), thresholds)?)
+    }
+
+    /// Return an error if objects must not be deleted from the object database as `extensions.preciousObjects` is set,
+    /// for instance because it is shared with other repositories as their alternate.
+    ///
+    /// Operations that delete objects, like pruning unreachable objects or deleting packs after repacking them,
+    /// must call this first and not run at all if it fails, just like `git gc` and `git repack -d` refuse to.
+    pub fn ensure_objects_may_be_deleted(&self) -> Result<(), super::precious_objects::Error> {
+        if self.config.repository_format.precious_objects {
+            return Err(super::precious_objects::Error);
+        }
+        Ok(())
+    }
+
+    /// Drop old entries from the reflogs of all references and `HEAD`, like `git reflog expire --all` does.
+    ///
+    /// An entry expires if it's older than `gc.reflogExpire`, which defaults to 90 days. It also expires if it's older than
+    /// `gc.reflogExpireUnreachable`, which defaults to 30 days, and the commit it moved from or to doesn't exist or isn't
+    /// reachable from the tip of the reference anymore. For `HEAD`, commits reachable from any other reference are reachable.
+    /// If the tip isn't a commit, all entries older than `gc.reflogExpireUnreachable` expire.
+    ///
+    /// References matching `<pattern>` use `gc.<pattern>.reflogExpire` and `gc.<pattern>.reflogExpireUnreachable` instead,
+    /// with the first matching pattern winning. Like in `git`, if only one of them is set for a pattern the other one
+    /// never expires entries, and the reflog of `refs/stash` never expires unless a pattern matches it.
+    /// The configuration is overridden by [`Options::expire`] and [`Options::expire_unreachable`].
+    pub fn expire_reflogs(&self, options: Options) -> Result<Outcome, Error> {
+        let config = ExpiryConfig::new(self)?;
+        let mut references = Vec::new();
+        for reference in self
+            .try_find_reference("HEAD")?
+            .into_iter()
+            .map(Ok)
+            .chain(self.references()?.all()?)
+        {
+            let mut reference = reference.map_err(Error::Reference)?;
+            if !reference.log_exists() {
+                continue;
+            }
+            let name = reference.name().to_owned();
+            let tip = reference
+                .peel_to_id_in_place()
+                .ok()
+                .map(crate::Id::detach)
+                .filter(|id| self.is_commit(*id));
+            references.push((name, tip));
+        }
+
+        let other_tips: Vec<_> = references
+            .iter()
+            .filter(|(name, _)| name.as_bstr() != "HEAD")
+            .filter_map(|(_, tip)| *tip)
+            .collect();
+        let mut reachability = Reachability::new(self);
+        let mut out = Outcome::default();
+        let mut buf = Vec::new();
+        for (name, tip) in &references {
+            let (config_expire, config_expire_unreachable) = config.cutoffs(name.as_bstr());
+            let expire = options.expire.unwrap_or(config_expire);
+            let expire_unreachable = options.expire_unreachable.unwrap_or(config_expire_unreachable);
+            let is_head = name.as_bstr() == "HEAD";
+
+            let mut kept: Vec<gix_ref::log::Line> = Vec::new();
+            let mut log = Log {
+                name: name.clone(),
+                dropped: Vec::new(),
+                kept: 0,
+            };
+            let mut changed = false;
+            let Some(lines) = self.refs.reflog_iter(name.as_ref(), &mut buf)? else {
+                continue;
+            };
+            for line in lines {
+                let line = line?.to_owned();
+                // Like `git`, rewritten entries are checked for expiry with the previous id they would be written with.
+                let previous_oid = if options.rewrite {
+                    kept.last()
+                        .map_or_else(|| line.previous_oid.kind().null(), |kept| kept.new_oid)
+                } else {
+                    line.previous_oid
+                };
+                let seconds = line.signature.time.seconds;
+                let expires = expire.expires(seconds)
+                    || (expire_unreachable.expires(seconds) && {
+                        let tips = match tip {
+                            _ if is_head => Some(other_tips.as_slice()),
+                            Some(tip) => Some(std::slice::from_ref(tip)),
+                            None => None,
+                        };
+                        match tips {
+                            Some(tips) => {
+                                !reachability.is_reachable(self, previous_oid, tips)?
+                                    || !reachability.is_reachable(self, line.new_oid, tips)?
+                            }
+                            None => true,
+                        }
+                    });
+                if expires {
+                    log.dropped.push(line);
+                    changed = true;
+                } else {
+                    changed |= line.previous_oid != previous_oid;
+                    kept.push(gix_ref::log::Line { previous_oid, ..line });
+                }
+            }
+            log.kept = kept.len();
+            if changed && !options.dry_run {
+                self.refs
+                    .reflog_rewrite(name.as_ref(), &kept, gix_lock::acquire::Fail::Immediately)?;
+            }
+            out.logs.push(log);
+        }
+        Ok(out)
+    }
+
+    fn is_commit(&self, id: ObjectId) -> bool {
+        matches!(self.try_find_header(id), Ok(Some(header)) if header.kind() == gix_object::Kind::Commit)
+    }
+}
+
+/// The configuration of reflog expiry, with patterns in order of their appearance.
+struct ExpiryConfig {
+    expire: Cutoff,
+    expire_unreachable: Cutoff,
+    patterns: Vec<(BString, Option<Cutoff>, Option<Cutoff>)>,
+}
+
+impl ExpiryConfig {
+    fn new(repo: &crate::Repository) -> Result<Self, crate::config::time::Error> {
+        let now = std::time::SystemTime::now();
+        let seconds_ago = |seconds: gix_date::SecondsSinceUnixEpoch| {
+            let now = now
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs() as gix_date::SecondsSinceUnixEpoch);
+            Cutoff::OlderThan(now - seconds)
+        };
+        let lenient = repo.options.lenient_config;
+        let cutoff = |key: &'static gc::Expiry, value: Option<std::borrow::Cow<'_, crate::bstr::BStr>>| {
+            value
+                .map(|value| key.try_into_cutoff(value, Some(now)))
+                .transpose()
+                .with_leniency(lenient)
+        };
+
+        let mut filter = repo.filter_config_section();
+        let config = &repo.config.resolved;
+        let mut out = ExpiryConfig {
+            expire: cutoff(
+                &Gc::REFLOG_EXPIRE,
+                config.string_filter(&Gc::REFLOG_EXPIRE, &mut filter),
+            )?
+            .unwrap_or_else(|| seconds_ago(90 * 24 * 60 * 60)),
+            expire_unreachable: cutoff(
+                &Gc::REFLOG_EXPIRE_UNREACHABLE,
+                config.string_filter(&Gc::REFLOG_EXPIRE_UNREACHABLE, &mut filter),
+            )?
+            .unwrap_or_else(|| seconds_ago(30 * 24 * 60 * 60)),
+            patterns: Vec::new(),
+        };
+        for section in config
+            .sections_by_name_and_filter("gc", &mut filter)
+            .into_iter()
+            .flatten()
+        {
+            let Some(pattern) = section.header().subsection_name() else {
+                continue;
+            };
+            let expire = cutoff(&Gc::REFLOG_EXPIRE, section.value(Gc::REFLOG_EXPIRE.name()))?;
+            let expire_unreachable = cutoff(
+                &Gc::REFLOG_EXPIRE_UNREACHABLE,
+                section.value(Gc::REFLOG_EXPIRE_UNREACHABLE.name()),
+            )?;
+            if expire.is_none() && expire_unreachable.is_none() {
+                continue;
+            }
+            let index = match out.patterns.iter().position(|(existing, _, _)| existing == pattern) {
+                Some(index) => index,
+                None => {
+                    out.patterns.push((pattern.to_owned(), None, None));
+                    out.patterns.len() - 1
+                }
+            };
+            let entry = &mut out.patterns[index];
+            entry.1 = expire.or(entry.1);
+            entry.2 = expire_unreachable.or(entry.2);
+        }
+        Ok(out)
+    }
+
+    /// Return the cutoffs for all entries and unreachable entries of the reflog of the reference named `name`.
+    fn cutoffs(&self, name: &crate::bstr::BStr) -> (Cutoff, Cutoff) {
+        if let Some((_, expire, expire_unreachable)) = self
+            .patterns
+            .iter()
+            .find(|(pattern, _, _)| gix_glob::wildmatch(pattern.as_bstr(), name, gix_glob::wildmatch::Mode::empty()))
+        {
+            return (
+                expire.unwrap_or(Cutoff::Never),
+                expire_unreachable.unwrap_or(Cutoff::Never),
+            );
+        }
+        if name == "refs/stash" {
+            return (Cutoff::Never, Cutoff::Never);
+        }
+        (self.expire, self.expire_unreachable)
+    }
+}
+
+/// Answers if commits are reachable from the tips of references, sharing the commit graph and all answers across reflogs.
+struct Reachability<'repo> {
+    graph: Graph<'repo, Commit<Flags>>,
+    /// Whether the commit in the first field is reachable from the tip in the second one.
+    known: HashMap<(ObjectId, ObjectId), bool>,
+}
+
+impl<'repo> Reachability<'repo> {
+    fn new(repo: &'repo crate::Repository) -> Self {
+        Reachability {
+            graph: repo.revision_graph(),
+            known: HashMap::new(),
+        }
+    }
+
+    /// Return `true` if `id` is null, or a commit or a tag pointing to one which is reachable from any of `tips`.
+    ///
+    /// Reachability is determined with [`gix_revision::ahead_behind()`], which uses the commit-graph if it is available.
+    fn is_reachable(&mut self, repo: &crate::Repository, id: ObjectId, tips: &[ObjectId]) -> Result<bool, Error> {
+        if id.is_null() {
+            return Ok(true);
+        }
+        let commit = match repo
+            .try_find_object(id)
+            .ok()
+            .flatten()
+            .map(crate::Object::peel_tags_to_end)
+        {
+            Some(Ok(object)) if object.kind == gix_object::Kind::Commit => object.id,
+            _ => return Ok(false),
+        };
+        for tip in tips {
+            let reachable = match self.known.entry((commit, *tip)) {
+                Entry::Occupied(entry) => *entry.get(),
+                Entry::Vacant(entry) => {
+                    *entry.insert(gix_revision::ahead_behind(&commit, tip, &mut self.graph, None)?.ahead == 0)
+                }
+            };
+            if reachable {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
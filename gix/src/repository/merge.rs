@@ -0,0 +1,53 @@
+use crate::{config::tree::Merge, Repository};
+
+///
+#[allow(clippy::empty_docs)]
+pub mod blob_merge_platform {
+    /// The error returned by [Repository::blob_merge_platform()](super::Repository::blob_merge_platform()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Index(#[from] crate::repository::index_or_load_from_head::Error),
+        #[error(transparent)]
+        AttributeStack(#[from] crate::config::attribute_stack::Error),
+        #[error(transparent)]
+        CommandContext(#[from] crate::config::command_context::Error),
+    }
+}
+
+/// Merge-utilities
+impl Repository {
+    /// Create a platform to merge blobs with the driver selected by their `merge` attribute, configured with all
+    /// user-defined drivers in `merge.<driver>.*` and the driver in `merge.default` for paths without `merge` attribute.
+    ///
+    /// Attributes are obtained from the current `HEAD` index, and from the worktree if there is one.
+    /// User-defined drivers are run in the root of the worktree, if present.
+    pub fn blob_merge_platform(&self) -> Result<gix_merge::blob::Platform, blob_merge_platform::Error> {
+        let index = self.index_or_load_from_head()?;
+        let attributes = self
+            .attributes_only(
+                &index,
+                if self.work_dir().is_some() {
+                    gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping
+                } else {
+                    gix_worktree::stack::state::attributes::Source::IdMapping
+                },
+            )?
+            .inner;
+        let options = gix_merge::blob::platform::Options {
+            default_driver: self
+                .config
+                .resolved
+                .string(Merge::DEFAULT)
+                .map(std::borrow::Cow::into_owned),
+            command_context: self.command_context()?,
+            ..Default::default()
+        };
+        Ok(gix_merge::blob::Platform::new(
+            self.config.merge_drivers(),
+            attributes,
+            options,
+        ))
+    }
+}
@@ -38,6 +38,10 @@ impl crate::Repository {
 
 #[cfg(any(feature = "attributes", feature = "excludes"))]
 pub mod attributes;
+///
+#[allow(clippy::empty_docs)]
+#[cfg(feature = "blame")]
+pub mod blame;
 mod cache;
 mod config;
 ///
@@ -57,12 +61,21 @@ pub(crate) mod identity;
 mod impls;
 #[cfg(feature = "index")]
 mod index;
+///
+#[allow(clippy::empty_docs)]
+#[cfg(feature = "merge")]
+pub mod merge;
 pub(crate) mod init;
 mod kind;
 mod location;
 #[cfg(feature = "mailmap")]
 mod mailmap;
+mod maintenance;
 mod object;
+#[cfg(feature = "blocking-network-server")]
+mod receive_pack;
+#[cfg(feature = "blocking-network-server")]
+mod upload_pack;
 #[cfg(feature = "attributes")]
 mod pathspec;
 mod reference;
@@ -91,6 +104,55 @@ pub mod index_from_tree {
         },
         #[error("Couldn't obtain configuration for core.protect*")]
         BooleanConfig(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        IndexVersion(#[from] crate::config::key::GenericErrorWithValue),
+    }
+}
+
+///
+#[allow(clippy::empty_docs)]
+#[cfg(feature = "attributes")]
+pub mod add_to_index {
+    use crate::bstr::BString;
+
+    /// Determines how [Repository::add_to_index()](crate::Repository::add_to_index()) records a worktree file.
+    #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+    pub enum Mode {
+        /// Pass the file through the filter pipeline, write it as blob and record it in the index,
+        /// like `git add` does.
+        ///
+        /// This also turns *intent-to-add* entries into regular ones.
+        Content,
+        /// Only record that the file will be added later by means of an *intent-to-add* entry that points to the empty blob,
+        /// like `git add --intent-to-add` does.
+        ///
+        /// Paths that are already tracked are left untouched.
+        IntentToAdd,
+    }
+
+    /// The error returned by [Repository::add_to_index()](crate::Repository::add_to_index()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("A working tree is required to add files to the index")]
+        MissingWorkDir,
+        #[error("Could not obtain metadata or read '{}'", path.display())]
+        Io {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error("'{rela_path}' is neither a file nor a symlink and can't be added")]
+        UnsupportedKind { rela_path: BString },
+        #[error(transparent)]
+        FilesystemCapabilities(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        Stat(#[from] std::time::SystemTimeError),
+        #[error(transparent)]
+        FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+        #[error(transparent)]
+        ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+        #[error(transparent)]
+        WriteBlob(#[from] crate::object::write::Error),
     }
 }
 
@@ -185,6 +247,57 @@ pub mod worktree_stream {
     }
 }
 
+///
+#[allow(clippy::empty_docs)]
+pub mod precious_objects {
+    /// The error returned by [`Repository::ensure_objects_may_be_deleted()`][crate::Repository::ensure_objects_may_be_deleted()].
+    #[derive(Debug, thiserror::Error)]
+    #[error("Objects must not be deleted as 'extensions.preciousObjects' is set")]
+    pub struct Error;
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod gc_advice {
+    /// The error returned by [`Repository::gc_advice()`][crate::Repository::gc_advice()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Threshold(#[from] crate::config::key::GenericError),
+        #[error("Could not count loose objects or packs")]
+        Io(#[from] std::io::Error),
+    }
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod write_commit_graph {
+    /// The error returned by [`Repository::write_commit_graph()`][crate::Repository::write_commit_graph()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Commit-graphs can't be written for shallow repositories")]
+        Shallow,
+        #[error(transparent)]
+        GenerationVersion(#[from] crate::config::key::GenericError),
+        #[error(transparent)]
+        References(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        ReferencesInit(#[from] crate::reference::iter::init::Error),
+        #[error("Could not iterate or peel a reference")]
+        ReferenceIter(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        ObjectKind(#[from] crate::object::try_into::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        Write(#[from] gix_commitgraph::write::Error),
+    }
+}
+
 ///
 #[cfg(feature = "worktree-archive")]
 pub mod worktree_archive {
@@ -75,6 +75,28 @@ impl crate::Repository {
         }
     }
 
+    /// Return the id of the object with `id` in the other hash if `extensions.compatObjectFormat` is set, that is
+    /// its compatibility id if `id` is an object id of this repository, or its object id if `id` is a compatibility id.
+    ///
+    /// `None` is returned if the extension isn't set or if the mapping isn't known, which is the case for objects
+    /// that were written without the extension being set.
+    ///
+    /// # Performance
+    ///
+    /// The mapping is read from disk on each call, use [`gix_odb::loose::object_map::Map`] directly for many lookups.
+    #[momo]
+    pub fn compat_id(
+        &self,
+        id: impl AsRef<gix_hash::oid>,
+    ) -> Result<Option<ObjectId>, gix_odb::loose::object_map::Error> {
+        let Some(compat_hash) = self.config.repository_format.compat_object_format else {
+            return Ok(None);
+        };
+        let map =
+            gix_odb::loose::object_map::Map::at(self.objects.store_ref().path(), self.object_hash(), compat_hash)?;
+        Ok(map.compat_id(id.as_ref()))
+    }
+
     /// Obtain information about an object without fully decoding it, or `None` if the object doesn't exist.
     ///
     /// Note that despite being cheaper than [`Self::try_find_object()`], there is still some effort traversing delta-chains.
@@ -352,3 +374,14 @@ impl crate::Repository {
         }
     }
 }
+
+/// Object id abbreviation
+impl crate::Repository {
+    /// Return a utility to shorten object ids for display as configured by `core.abbrev`.
+    ///
+    /// If `core.abbrev` is `auto` or unset, the amount of characters scales with the approximate amount of objects
+    /// in the object database just like in `git`, which is computed only once.
+    pub fn id_shortener(&self) -> Result<crate::id::Shortener<'_>, crate::id::shorten::Error> {
+        crate::id::Shortener::new(self)
+    }
+}
@@ -0,0 +1,540 @@
+use std::{
+    io::{BufRead, Read, Write},
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
+
+use gix_hash::ObjectId;
+use gix_protocol::receive_pack::{decode, encode, CommandKind, CommandStatus, Report, CAPABILITIES};
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    Target, TargetRef,
+};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    config::tree::Receive,
+    receive_pack::{advertisement, Deny, Error, Options, Outcome},
+    Repository,
+};
+
+/// Serving `git push`
+impl Repository {
+    /// Write the reference advertisement of `git-receive-pack` to `out`, which is the first thing sent to a client that wants to push.
+    ///
+    /// All references are advertised with the id they point to, after following symbolic references. Annotated tags are not peeled.
+    /// References hidden with `transfer.hideRefs` or `receive.hideRefs` aren't advertised.
+    pub fn receive_pack_advertisement(&self, out: impl Write) -> Result<(), advertisement::Error> {
+        let hidden = self.hidden_references(crate::reference::hidden::Service::ReceivePack);
+        let platform = self.references()?;
+        let mut refs = Vec::new();
+        for reference in platform.all()? {
+            let mut reference = reference.map_err(advertisement::Error::IterItem)?;
+            if hidden.is_hidden(
+                Some(reference.name().as_bstr()),
+                full_name(self, reference.name().as_bstr()).as_ref(),
+            ) {
+                continue;
+            }
+            // Follow symbolic references the way git does, and ignore those which are dangling or too deep.
+            let mut id = None;
+            for _ in 0..5 {
+                match reference.target() {
+                    TargetRef::Peeled(target) => {
+                        id = Some(target.to_owned());
+                        break;
+                    }
+                    TargetRef::Symbolic(_) => match reference.follow() {
+                        Some(Ok(next)) => reference = next,
+                        Some(Err(gix_ref::file::find::existing::Error::NotFound { .. })) | None => break,
+                        Some(Err(err)) => return Err(err.into()),
+                    },
+                }
+            }
+            if let Some(id) = id {
+                refs.push((id, reference.name().as_bstr().to_owned()));
+            }
+        }
+        encode::advertisement(
+            refs.iter().map(|(id, name)| (*id, name.as_bstr())),
+            CAPABILITIES,
+            self.object_hash(),
+            crate::env::agent(),
+            out,
+        )?;
+        Ok(())
+    }
+
+    /// Serve a client that pushes to this repository by reading its request from `input` and writing the responses to `output`,
+    /// just like `git-receive-pack` would. The reference advertisement is written first, unless [`Options::stateless_rpc`] is set.
+    ///
+    /// The pack sent by the client is written into a quarantine directory first, and is only moved into the object database
+    /// once the `pre-receive` hook accepted it, if [hooks are run](Options::run_hooks). Otherwise it's removed.
+    /// Commands are then checked for the following, in order, and are rejected unless all checks pass:
+    ///
+    /// * The reference name must be valid and start with `refs/`, and all objects it should point to must be present.
+    /// * `receive.denyDeletes` refuses the deletion of branches.
+    /// * `receive.denyDeleteCurrent` refuses the deletion of the branch `HEAD` points to, by default.
+    /// * `receive.denyCurrentBranch` refuses updates to the branch `HEAD` points to in repositories with a worktree, by default.
+    /// * `receive.denyNonFastForwards` refuses updates of branches to a commit that doesn't have the current commit as ancestor.
+    /// * The `pre-receive` hook declines all commands, and the `update` hook declines individual ones.
+    ///
+    /// Accepted commands are applied individually, each in its own transaction, and must still find the reference at the value
+    /// the client expected. Afterwards the `post-receive` hook is invoked with all commands that were applied.
+    /// Finally the status report is sent to the client if it asked for it.
+    ///
+    /// Hooks see the commands on `stdin` like `<old-id> <new-id> <refname>` and push options in `GIT_PUSH_OPTION_<n>` variables.
+    /// They are run in the `$GIT_DIR`, and their output is collected in [`Outcome::hook_output`].
+    ///
+    /// ### Deviation
+    ///
+    /// * The hooks directory is always `$GIT_DIR/hooks` as `core.hooksPath` isn't respected.
+    /// * Progress and hook messages aren't sent to the client as `side-band-64k` isn't supported.
+    /// * `receive.denyCurrentBranch=updateInstead` is treated like `refuse`.
+    pub fn receive_pack(
+        &self,
+        mut input: impl Read,
+        mut output: impl Write,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        if !options.stateless_rpc {
+            self.receive_pack_advertisement(&mut output)?;
+        }
+        let mut reader = gix_protocol::transport::packetline::StreamingPeekableIter::new(
+            &mut input,
+            &[gix_protocol::transport::packetline::PacketLineRef::Flush],
+            false,
+        );
+        let request = decode::request(&mut reader)?;
+        let mut input = std::io::BufReader::new(reader.into_inner());
+        let mut outcome = Outcome {
+            report: Report {
+                unpack_error: None,
+                commands: request
+                    .commands
+                    .iter()
+                    .map(|cmd| CommandStatus {
+                        refname: cmd.refname.clone(),
+                        rejection: None,
+                    })
+                    .collect(),
+            },
+            request,
+            pack: None,
+            hook_output: BString::default(),
+        };
+        if outcome.request.commands.is_empty() {
+            return Ok(outcome);
+        }
+
+        let mut quarantine = None;
+        if outcome.request.expects_pack() {
+            match self.receive_pack_into_quarantine(&mut input) {
+                Ok((q, pack)) => {
+                    quarantine = Some(q);
+                    outcome.pack = Some(pack);
+                }
+                Err(err) => outcome.report.unpack_error = Some(err.to_string().into()),
+            }
+            if outcome.report.unpack_error.is_some() {
+                reject_all(&mut outcome.report.commands, "unpacker error");
+            }
+        }
+
+        self.check_commands(&mut outcome, quarantine.as_ref())?;
+
+        let push_options = &outcome.request.push_options;
+        if options.run_hooks && has_pending(&outcome.report.commands) {
+            let stdin = hook_input(&outcome);
+            let declined = !self.run_hook(
+                "pre-receive",
+                &[],
+                &stdin,
+                quarantine.as_ref(),
+                push_options,
+                &mut outcome.hook_output,
+            )?;
+            if declined {
+                reject_all(&mut outcome.report.commands, "pre-receive hook declined");
+            }
+        }
+        if options.run_hooks {
+            for (cmd, status) in outcome.request.commands.iter().zip(outcome.report.commands.iter_mut()) {
+                if status.rejection.is_some() {
+                    continue;
+                }
+                let args = [
+                    cmd.refname.to_str_lossy().into_owned(),
+                    cmd.old_id.to_string(),
+                    cmd.new_id.to_string(),
+                ];
+                if !self.run_hook(
+                    "update",
+                    &args,
+                    &[],
+                    quarantine.as_ref(),
+                    push_options,
+                    &mut outcome.hook_output,
+                )? {
+                    status.rejection = Some("hook declined".into());
+                }
+            }
+        }
+
+        if let Some(quarantine) = quarantine.take() {
+            if has_pending(&outcome.report.commands) {
+                if let Err(err) = quarantine.migrate(&self.objects.store_ref().path().join("pack")) {
+                    outcome.report.unpack_error =
+                        Some(format!("unable to migrate objects to permanent storage: {err}").into());
+                    reject_all(
+                        &mut outcome.report.commands,
+                        "unable to migrate objects to permanent storage",
+                    );
+                }
+            }
+        }
+
+        let committer = match self.committer().transpose()? {
+            Some(committer) => committer.to_owned(),
+            None => gix_actor::Signature {
+                name: "gitoxide".into(),
+                email: "gitoxide@localhost".into(),
+                time: gix_date::Time::now_local_or_utc(),
+            },
+        };
+        let (file_lock_fail, packed_refs_lock_fail) = self.config.lock_timeout()?;
+        for (cmd, status) in outcome.request.commands.iter().zip(outcome.report.commands.iter_mut()) {
+            if status.rejection.is_some() {
+                continue;
+            }
+            let Ok(name) = gix_ref::FullName::try_from(cmd.refname.clone()) else {
+                status.rejection = Some("funny refname".into());
+                continue;
+            };
+            let change = match cmd.kind() {
+                CommandKind::Delete => Change::Delete {
+                    expected: PreviousValue::MustExistAndMatch(Target::Peeled(cmd.old_id)),
+                    log: RefLog::AndReference,
+                },
+                CommandKind::Create | CommandKind::Update => Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: "push".into(),
+                    },
+                    expected: if cmd.old_id.is_null() {
+                        PreviousValue::MustNotExist
+                    } else {
+                        PreviousValue::MustExistAndMatch(Target::Peeled(cmd.old_id))
+                    },
+                    new: Target::Peeled(cmd.new_id),
+                },
+            };
+            let res = self
+                .refs
+                .transaction()
+                .prepare(
+                    Some(RefEdit {
+                        change,
+                        name,
+                        deref: false,
+                    }),
+                    file_lock_fail,
+                    packed_refs_lock_fail,
+                )
+                .map_err(|err| err.to_string())
+                .and_then(|t| t.commit(committer.to_ref()).map_err(|err| err.to_string()));
+            if let Err(_err) = res {
+                gix_trace::debug!("failed to update ref {}: {_err}", cmd.refname);
+                status.rejection = Some("failed to update ref".into());
+            }
+        }
+        if let Some(keep_path) = outcome.pack.as_ref().and_then(|pack| pack.keep_path.as_ref()) {
+            let keep_path = self
+                .objects
+                .store_ref()
+                .path()
+                .join("pack")
+                .join(keep_path.file_name().expect("keep files have a name"));
+            std::fs::remove_file(keep_path).ok();
+        }
+
+        if options.run_hooks && outcome.report.commands.iter().any(|status| status.rejection.is_none()) {
+            let stdin = hook_input(&outcome);
+            self.run_hook(
+                "post-receive",
+                &[],
+                &stdin,
+                None,
+                &outcome.request.push_options,
+                &mut outcome.hook_output,
+            )?;
+        }
+
+        if outcome.request.report_status().is_some() {
+            encode::report(&outcome.report, &mut output).map_err(Error::WriteReport)?;
+        }
+        Ok(outcome)
+    }
+
+    fn receive_pack_into_quarantine(
+        &self,
+        pack: &mut dyn BufRead,
+    ) -> Result<(Quarantine, gix_pack::bundle::write::Outcome), Box<dyn std::error::Error + Send + Sync + 'static>>
+    {
+        let objects_dir = gix_path::realpath(self.objects.store_ref().path())?;
+        let quarantine = Quarantine::new(&objects_dir)?;
+        let outcome = gix_pack::Bundle::write_to_directory(
+            pack,
+            Some(&quarantine.path.join("pack")),
+            &mut gix_features::progress::Discard,
+            &AtomicBool::default(),
+            Some(self.objects.clone()),
+            gix_pack::bundle::write::Options {
+                object_hash: self.object_hash(),
+                ..Default::default()
+            },
+        )?;
+        Ok((quarantine, outcome))
+    }
+
+    fn check_commands(&self, outcome: &mut Outcome, quarantine: Option<&Quarantine>) -> Result<(), Error> {
+        // Objects are looked up in the quarantine as well, as they aren't migrated yet.
+        let repo = match quarantine {
+            Some(quarantine) => {
+                let mut repo = self.clone();
+                repo.objects = gix_odb::at(&quarantine.path).map_err(Error::Quarantine)?;
+                std::borrow::Cow::Owned(repo)
+            }
+            None => std::borrow::Cow::Borrowed(self),
+        };
+        let config = &self.config.resolved;
+        let deny_deletes = config
+            .boolean("receive.denyDeletes")
+            .map(|v| Receive::DENY_DELETES.enrich_error(v))
+            .transpose()?
+            .unwrap_or(false);
+        let deny_non_fast_forwards = config
+            .boolean("receive.denyNonFastForwards")
+            .map(|v| Receive::DENY_NON_FAST_FORWARDS.enrich_error(v))
+            .transpose()?
+            .unwrap_or(false);
+        let deny_current_branch = config
+            .string("receive.denyCurrentBranch")
+            .map(|v| Receive::DENY_CURRENT_BRANCH.try_into_deny(v))
+            .transpose()?
+            .unwrap_or_default();
+        let deny_delete_current = config
+            .string("receive.denyDeleteCurrent")
+            .map(|v| Receive::DENY_DELETE_CURRENT.try_into_deny(v))
+            .transpose()?
+            .unwrap_or_default();
+        let head_name = self.head_name()?;
+        let is_bare = self.is_bare();
+        let hidden = self.hidden_references(crate::reference::hidden::Service::ReceivePack);
+        let mut graph = repo.revision_graph();
+
+        for (cmd, status) in outcome.request.commands.iter().zip(outcome.report.commands.iter_mut()) {
+            if status.rejection.is_some() {
+                continue;
+            }
+            let is_branch = cmd.refname.starts_with(b"refs/heads/");
+            let is_head = matches!(&head_name, Some(name) if name.as_bstr() == cmd.refname.as_bstr());
+            let rejection = if !cmd.refname.starts_with(b"refs/")
+                || gix_validate::reference::name(cmd.refname.as_bstr()).is_err()
+            {
+                Some("funny refname")
+            } else if hidden.is_hidden(
+                Some(cmd.refname.as_bstr()),
+                full_name(self, cmd.refname.as_bstr()).as_ref(),
+            ) {
+                Some("deny updating a hidden ref")
+            } else if !cmd.new_id.is_null() && !repo.has_object(cmd.new_id) {
+                Some("missing necessary objects")
+            } else {
+                match cmd.kind() {
+                    CommandKind::Delete if deny_deletes && is_branch => Some("deletion prohibited"),
+                    CommandKind::Delete if is_head && deny(deny_delete_current, "deleting the current branch") => {
+                        Some("deletion of the current branch prohibited")
+                    }
+                    CommandKind::Create | CommandKind::Update
+                        if is_head && !is_bare && deny(deny_current_branch, "updating the current branch") =>
+                    {
+                        Some("branch is currently checked out")
+                    }
+                    CommandKind::Update
+                        if deny_non_fast_forwards && is_branch && !is_ancestor(&mut graph, cmd.old_id, cmd.new_id) =>
+                    {
+                        Some("non-fast-forward")
+                    }
+                    _ => None,
+                }
+            };
+            status.rejection = rejection.map(Into::into);
+        }
+        Ok(())
+    }
+
+    /// Run the hook `name` with `args` and `stdin` if it exists, and return `true` if it succeeded or doesn't exist.
+    fn run_hook(
+        &self,
+        name: &'static str,
+        args: &[String],
+        stdin: &[u8],
+        quarantine: Option<&Quarantine>,
+        push_options: &[BString],
+        hook_output: &mut BString,
+    ) -> Result<bool, Error> {
+        let path = self.git_dir().join("hooks").join(name);
+        if !is_executable(&path) {
+            return Ok(true);
+        }
+        let mut cmd = std::process::Command::new(&path);
+        cmd.args(args)
+            .current_dir(self.git_dir())
+            .env("GIT_DIR", ".")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped());
+        if let Some(quarantine) = quarantine {
+            cmd.env("GIT_QUARANTINE_PATH", &quarantine.path)
+                .env("GIT_OBJECT_DIRECTORY", &quarantine.path)
+                .env("GIT_ALTERNATE_OBJECT_DIRECTORIES", &quarantine.objects_dir);
+        }
+        if !push_options.is_empty() {
+            cmd.env("GIT_PUSH_OPTION_COUNT", push_options.len().to_string());
+            for (idx, option) in push_options.iter().enumerate() {
+                cmd.env(format!("GIT_PUSH_OPTION_{idx}"), option.to_os_str_lossy());
+            }
+        }
+        gix_trace::debug!("running hook: {cmd:?}");
+        let to_err = |source| Error::Hook { name, source };
+        let mut child = cmd.spawn().map_err(to_err)?;
+        let mut child_stdin = child.stdin.take().expect("configured");
+        // Hooks may not read their input, which isn't an error.
+        child_stdin.write_all(stdin).ok();
+        drop(child_stdin);
+        let res = child.wait_with_output().map_err(to_err)?;
+        hook_output.push_str(&res.stdout);
+        hook_output.push_str(&res.stderr);
+        Ok(res.status.success())
+    }
+}
+
+/// A directory in the object database into which the pack is received, to make its objects available only once they are accepted.
+/// It's removed when dropped.
+struct Quarantine {
+    path: PathBuf,
+    objects_dir: PathBuf,
+}
+
+impl Quarantine {
+    fn new(objects_dir: &Path) -> std::io::Result<Self> {
+        let path = objects_dir.join(format!(
+            "incoming-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_nanos())
+        ));
+        std::fs::create_dir_all(path.join("pack"))?;
+        let quarantine = Quarantine {
+            path,
+            objects_dir: objects_dir.to_owned(),
+        };
+        // The quarantine sees all objects of the repository, which is also required to see them in hooks.
+        std::fs::create_dir(quarantine.path.join("info"))?;
+        let mut alternates = gix_path::into_bstr(objects_dir).into_owned();
+        alternates.push_byte(b'\n');
+        std::fs::write(quarantine.path.join("info").join("alternates"), alternates)?;
+        Ok(quarantine)
+    }
+
+    /// Move all pack files into `pack_dir`, with the index last so the pack is complete once it's discovered.
+    fn migrate(self, pack_dir: &Path) -> std::io::Result<()> {
+        let mut files = std::fs::read_dir(self.path.join("pack"))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        files.sort_by_key(|path| matches!(path.extension(), Some(ext) if ext == "idx"));
+        for file in files {
+            std::fs::rename(&file, pack_dir.join(file.file_name().expect("files have names")))?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Quarantine {
+    fn drop(&mut self) {
+        std::fs::remove_dir_all(&self.path).ok();
+    }
+}
+
+/// Return `name` with the namespace of `repo` prepended, if there is one.
+fn full_name(repo: &Repository, name: &BStr) -> BString {
+    let mut full_name = repo.namespace().map(|ns| ns.as_bstr().to_owned()).unwrap_or_default();
+    full_name.push_str(name);
+    full_name
+}
+
+fn deny(deny: Deny, _action: &str) -> bool {
+    match deny {
+        Deny::Ignore => false,
+        Deny::Warn => {
+            gix_trace::warn!("{_action}: allowed by configuration");
+            false
+        }
+        Deny::Refuse | Deny::UpdateInstead => true,
+    }
+}
+
+/// Return `true` if `ancestor` is reachable from `descendant`, which is the case if no commit is only reachable from `ancestor`.
+///
+/// Commits that can't be found or traversed are never considered ancestors.
+fn is_ancestor(
+    graph: &mut gix_revision::Graph<'_, gix_revision::graph::Commit<gix_revision::ahead_behind::Flags>>,
+    ancestor: ObjectId,
+    descendant: ObjectId,
+) -> bool {
+    matches!(
+        gix_revision::ahead_behind(&ancestor, &descendant, graph, None),
+        Ok(outcome) if outcome.ahead == 0
+    )
+}
+
+fn reject_all(commands: &mut [CommandStatus], reason: &str) {
+    for status in commands.iter_mut().filter(|status| status.rejection.is_none()) {
+        status.rejection = Some(reason.into());
+    }
+}
+
+fn has_pending(commands: &[CommandStatus]) -> bool {
+    commands.iter().any(|status| status.rejection.is_none())
+}
+
+/// The lines `<old-id> <new-id> <refname>` of all commands that weren't rejected.
+fn hook_input(outcome: &Outcome) -> Vec<u8> {
+    let mut out = Vec::new();
+    for (cmd, status) in outcome.request.commands.iter().zip(&outcome.report.commands) {
+        if status.rejection.is_none() {
+            out.extend_from_slice(format!("{} {} ", cmd.old_id, cmd.new_id).as_bytes());
+            out.extend_from_slice(cmd.refname.as_ref());
+            out.push(b'\n');
+        }
+    }
+    out
+}
+
+fn is_executable(path: &Path) -> bool {
+    let Ok(meta) = path.metadata() else {
+        return false;
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        meta.is_file() && meta.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        meta.is_file()
+    }
+}
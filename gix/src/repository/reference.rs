@@ -69,6 +69,28 @@ impl crate::Repository {
         Ok(self.refs.namespace.replace(namespace))
     }
 
+    /// Return a matcher for the references to hide from clients of `service`, as configured by `transfer.hideRefs` along with
+    /// `uploadpack.hideRefs` or `receive.hideRefs`.
+    ///
+    /// Patterns of both keys are used in the order in which they appear in the configuration, so later ones override earlier ones
+    /// no matter which of the keys they were set with.
+    pub fn hidden_references(&self, service: reference::hidden::Service) -> reference::hidden::Matcher {
+        let service = service.section_name();
+        reference::hidden::Matcher::new(
+            self.config
+                .resolved
+                .sections()
+                .filter(|section| {
+                    let header = section.header();
+                    header.subsection_name().is_none()
+                        && (header.name().eq_ignore_ascii_case(b"transfer")
+                            || header.name().eq_ignore_ascii_case(service.as_bytes()))
+                })
+                .flat_map(|section| section.values("hideRefs"))
+                .map(std::borrow::Cow::into_owned),
+        )
+    }
+
     // TODO: more tests or usage
     /// Create a new reference with `name`, like `refs/heads/branch`, pointing to `target`, adhering to `constraint`
     /// during creation and writing `log_message` into the reflog. Note that a ref-log will be written even if `log_message` is empty.
@@ -242,6 +264,17 @@ impl crate::Repository {
         })
     }
 
+    /// Return an index of all references by the objects they point to, directly or through annotated tags, to decorate
+    /// objects with the references pointing at them or to learn which references contain a commit.
+    pub fn references_by_target(&self) -> Result<reference::lookup::ByTarget, reference::lookup::Error> {
+        let platform = self.references()?;
+        let references = platform
+            .all()?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(reference::lookup::Error::Iter)?;
+        reference::lookup::ByTarget::from_references(references)
+    }
+
     /// Try to find the reference named `name`, like `main`, `heads/branch`, `HEAD` or `origin/other`, and return it.
     ///
     /// Otherwise return `None` if the reference wasn't found.
@@ -182,6 +182,21 @@ impl crate::Repository {
             Some(Err(err)) => return Some(Err(err)),
             None => Default::default(),
         };
+        let mut config_bool = |key: &'static config::tree::keys::Boolean| {
+            config
+                .boolean_filter(format!("remote.{}.{}", name_or_url, key.name), &mut filter)
+                .map(|value| key.enrich_error(value))
+                .transpose()
+                .map_err(find::Error::from)
+        };
+        let prune = match config_bool(&config::tree::Remote::PRUNE) {
+            Ok(v) => v,
+            Err(err) => return Some(Err(err)),
+        };
+        let prune_tags = match config_bool(&config::tree::Remote::PRUNE_TAGS) {
+            Ok(v) => v,
+            Err(err) => return Some(Err(err)),
+        };
 
         match (url, fetch_specs, push_url, push_specs) {
             (None, None, None, None) => None,
@@ -217,6 +232,8 @@ impl crate::Repository {
                         push_specs,
                         rewrite_urls,
                         fetch_tags,
+                        prune,
+                        prune_tags,
                         self,
                     )
                     .map_err(Into::into),
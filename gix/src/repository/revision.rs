@@ -35,6 +35,21 @@ impl crate::Repository {
             .ok_or(revision::spec::parse::single::Error::RangedRev { spec: spec.into() })
     }
 
+    /// Trace the range of `lines` of the file at `location` in the commit at `tip` through history, like `git log -L` does,
+    /// and return an iterator over all commits that changed these lines.
+    ///
+    /// `lines` is zero-based and exclusive, so `git log -L 10,20:<location>` corresponds to `9..20`.
+    /// Renames of the file are followed, and the traversal ends once all tracked lines were added.
+    #[cfg(feature = "blob-diff")]
+    pub fn line_log(
+        &self,
+        tip: impl Into<gix_hash::ObjectId>,
+        location: &BStr,
+        lines: std::ops::Range<u32>,
+    ) -> Result<revision::line_log::Iter<'_>, revision::line_log::init::Error> {
+        revision::line_log::Iter::new(self, tip.into(), location, lines)
+    }
+
     /// Create the baseline for a revision walk by initializing it with the `tips` to start iterating on.
     ///
     /// It can be configured further before starting the actual walk.
@@ -0,0 +1,716 @@
+use std::{
+    io::{Read, Write},
+    sync::atomic::AtomicBool,
+};
+
+use gix_hash::ObjectId;
+use gix_protocol::{
+    transport::{
+        packetline::{PacketLineRef, StreamingPeekableIter},
+        Protocol,
+    },
+    upload_pack::{decode, encode, Command, FetchRequest, Ref, CAPABILITIES},
+};
+use gix_ref::TargetRef;
+
+use crate::{
+    bstr::{BString, ByteSlice, ByteVec},
+    config::tree::{keys, UploadPack},
+    remote::fetch::filter,
+    upload_pack::{advertisement, Error, Fetch, Options, Outcome},
+    Repository,
+};
+
+/// Serving `git fetch` and `git clone`
+impl Repository {
+    /// Write what `git-upload-pack` initially sends to a client that wants to fetch using protocol `version` to `out`.
+    ///
+    /// In protocol V2, these are only the capabilities as the client lists references with the `ls-refs` command.
+    /// Otherwise, all references are advertised along with the peeled ids of annotated tags, except for those hidden
+    /// with `transfer.hideRefs` or `uploadpack.hideRefs`.
+    pub fn upload_pack_advertisement(&self, version: Protocol, out: impl Write) -> Result<(), advertisement::Error> {
+        let policy = Policy::from_config(self)?;
+        let refs = match version {
+            Protocol::V2 => Refs::default(),
+            Protocol::V0 | Protocol::V1 => self.upload_pack_refs()?,
+        };
+        self.write_upload_pack_advertisement(version, &refs, &policy, out)?;
+        Ok(())
+    }
+
+    /// Serve a client that fetches from this repository by reading its requests from `input` and writing the responses to `output`,
+    /// just like `git-upload-pack` would. The advertisement is written first, unless [`Options::stateless_rpc`] is set.
+    ///
+    /// The client may only want objects that references point to, or objects allowed by the following configuration:
+    ///
+    /// * `uploadpack.allowTipSHA1InWant` also allows objects pointed to by hidden references.
+    /// * `uploadpack.allowReachableSHA1InWant` also allows commits reachable from any reference.
+    /// * `uploadpack.allowAnySHA1InWant` allows all objects in the object database.
+    ///
+    /// Each `have` of the client that is a commit we have as well is acknowledged, and the pack contains all objects reachable
+    /// from the wanted objects which aren't reachable from these common commits.
+    /// With `uploadpack.allowFilter`, the client may ask for a partial pack by sending an object [filter](filter::Spec).
+    ///
+    /// ### Deviation
+    ///
+    /// * Shallow clones and fetches are rejected.
+    /// * Object filters can't be restricted with `uploadpackfilter.*`.
+    /// * In protocol V0 and V1, `multi_ack` and `multi_ack_detailed` aren't supported, and in V2, `ready` is never sent.
+    /// * Packs aren't thin and contain only base objects and deltas copied from existing packs.
+    pub fn upload_pack(&self, input: impl Read, mut output: impl Write, options: Options) -> Result<Outcome, Error> {
+        let policy = Policy::from_config(self).map_err(advertisement::Error::from)?;
+        let refs = self.upload_pack_refs()?;
+        if !options.stateless_rpc {
+            self.write_upload_pack_advertisement(options.version, &refs, &policy, &mut output)
+                .map_err(advertisement::Error::from)?;
+        }
+        let mut reader = StreamingPeekableIter::new(input, &[PacketLineRef::Flush], false);
+        let mut outcome = Outcome::default();
+        let res = match options.version {
+            Protocol::V2 => self.upload_pack_v2(&mut reader, &mut output, &refs, &policy, options, &mut outcome),
+            Protocol::V0 | Protocol::V1 => self.upload_pack_v0(&mut reader, &mut output, &refs, &policy, &mut outcome),
+        };
+        if let Err(
+            err @ (Error::DecodeRequest(_)
+            | Error::NotOurRef { .. }
+            | Error::UnknownWantedRef { .. }
+            | Error::ShallowUnsupported
+            | Error::FilterUnsupported { .. }
+            | Error::InvalidFilter(_)),
+        ) = &res
+        {
+            encode::error(&err.to_string(), &mut output).ok();
+        }
+        res.map(|()| outcome)
+    }
+
+    fn upload_pack_v2<R: Read>(
+        &self,
+        reader: &mut StreamingPeekableIter<R>,
+        mut out: impl Write,
+        refs: &Refs,
+        policy: &Policy,
+        options: Options,
+        outcome: &mut Outcome,
+    ) -> Result<(), Error> {
+        while let Some(command) = decode::command(reader)? {
+            match command {
+                Command::LsRefs(request) => {
+                    encode::ls_refs(&refs.visible, &request, &mut out)?;
+                    outcome.ls_refs += 1;
+                }
+                Command::Fetch(request) => {
+                    check_request(&request, policy)?;
+                    let mut wanted_refs = Vec::new();
+                    for name in &request.want_refs {
+                        let id = refs
+                            .visible
+                            .iter()
+                            .find(|r| r.name == *name)
+                            .and_then(|r| r.id)
+                            .ok_or_else(|| Error::UnknownWantedRef { name: name.clone() })?;
+                        wanted_refs.push((id, name.clone()));
+                    }
+                    self.check_wants(&request.wants, refs, policy)?;
+                    let common = self.common_commits(&request.haves);
+                    if !request.done {
+                        encode::acknowledgments(&common, false, &mut out)?;
+                        outcome.fetches.push(Fetch {
+                            request,
+                            common,
+                            pack_objects: None,
+                        });
+                    } else {
+                        if !wanted_refs.is_empty() {
+                            encode::wanted_refs(&wanted_refs, &mut out)?;
+                        }
+                        encode::packfile_section(&mut out)?;
+                        let wants: Vec<_> = request
+                            .wants
+                            .iter()
+                            .copied()
+                            .chain(wanted_refs.iter().map(|(id, _)| *id))
+                            .collect();
+                        let mut sideband = encode::Sideband::large(&mut out);
+                        let num_objects = self.write_upload_pack(&wants, &common, &request, refs, &mut sideband)?;
+                        sideband.finish()?;
+                        outcome.fetches.push(Fetch {
+                            request,
+                            common,
+                            pack_objects: Some(num_objects),
+                        });
+                    }
+                }
+            }
+            if options.stateless_rpc {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn upload_pack_v0<R: Read>(
+        &self,
+        reader: &mut StreamingPeekableIter<R>,
+        mut out: impl Write,
+        refs: &Refs,
+        policy: &Policy,
+        outcome: &mut Outcome,
+    ) -> Result<(), Error> {
+        let mut request = decode::wants(reader)?;
+        if request.wants.is_empty() {
+            return Ok(());
+        }
+        check_request(&request, policy)?;
+        self.check_wants(&request.wants, refs, policy)?;
+
+        let mut common = Vec::new();
+        loop {
+            let Some(haves) = decode::haves(reader)? else {
+                outcome.fetches.push(Fetch {
+                    request,
+                    common,
+                    pack_objects: None,
+                });
+                return Ok(());
+            };
+            for id in self.common_commits(&haves.ids) {
+                if !common.contains(&id) {
+                    // Without `multi_ack`, only the first common commit is acknowledged.
+                    if common.is_empty() {
+                        encode::ack(&id, &mut out)?;
+                    }
+                    common.push(id);
+                }
+            }
+            request.haves.extend(haves.ids);
+            if common.is_empty() {
+                encode::nak(&mut out)?;
+            }
+            if haves.done {
+                request.done = true;
+                break;
+            }
+        }
+
+        let mut sideband = if request.has_capability("side-band-64k") {
+            encode::Sideband::large(&mut out)
+        } else if request.has_capability("side-band") {
+            encode::Sideband::small(&mut out)
+        } else {
+            encode::Sideband::plain(&mut out)
+        };
+        let num_objects = self.write_upload_pack(&request.wants, &common, &request, refs, &mut sideband)?;
+        sideband.finish()?;
+        outcome.fetches.push(Fetch {
+            request,
+            common,
+            pack_objects: Some(num_objects),
+        });
+        Ok(())
+    }
+
+    fn write_upload_pack_advertisement(
+        &self,
+        version: Protocol,
+        refs: &Refs,
+        policy: &Policy,
+        mut out: impl Write,
+    ) -> std::io::Result<()> {
+        match version {
+            Protocol::V2 => encode::capabilities_v2(
+                &[
+                    "ls-refs=unborn",
+                    if policy.allow_filter { "fetch=filter" } else { "fetch" },
+                ],
+                self.object_hash(),
+                crate::env::agent(),
+                out,
+            ),
+            Protocol::V0 | Protocol::V1 => {
+                if version == Protocol::V1 {
+                    gix_protocol::transport::packetline::encode::text_to_write(b"version 1", &mut out)?;
+                }
+                let mut capabilities = CAPABILITIES.to_vec();
+                for (enabled, capability) in [
+                    (policy.allow_tip_sha1_in_want, "allow-tip-sha1-in-want"),
+                    (policy.allow_reachable_sha1_in_want, "allow-reachable-sha1-in-want"),
+                    (policy.allow_filter, "filter"),
+                ] {
+                    if enabled {
+                        capabilities.push(capability);
+                    }
+                }
+                encode::advertisement(
+                    &refs.visible,
+                    &capabilities,
+                    self.object_hash(),
+                    crate::env::agent(),
+                    out,
+                )
+            }
+        }
+    }
+
+    /// Collect `HEAD` and all references, along with the tips of hidden references.
+    fn upload_pack_refs(&self) -> Result<Refs, advertisement::Error> {
+        let hidden = self.hidden_references(crate::reference::hidden::Service::UploadPack);
+        let namespace = self.namespace().map(gix_ref::Namespace::as_bstr);
+
+        let mut out = Refs::default();
+        let head = self.try_find_reference("HEAD")?;
+        let platform = self.references()?;
+        for reference in head.map(Ok).into_iter().chain(platform.all()?) {
+            let mut reference = reference.map_err(advertisement::Error::IterItem)?;
+            let name = reference.name().as_bstr().to_owned();
+            let symref_target = match reference.target() {
+                TargetRef::Symbolic(target) => {
+                    let target = target.as_bstr();
+                    Some(
+                        namespace
+                            .and_then(|ns| target.strip_prefix(ns.as_bytes()))
+                            .unwrap_or(target)
+                            .into(),
+                    )
+                }
+                TargetRef::Peeled(_) => None,
+            };
+            // Follow symbolic references the way git does, and ignore those which are dangling or too deep.
+            let mut id = None;
+            for _ in 0..5 {
+                match reference.target() {
+                    TargetRef::Peeled(target) => {
+                        id = Some(target.to_owned());
+                        break;
+                    }
+                    TargetRef::Symbolic(_) => match reference.follow() {
+                        Some(Ok(next)) => reference = next,
+                        Some(Err(gix_ref::file::find::existing::Error::NotFound { .. })) | None => break,
+                        Some(Err(err)) => return Err(err.into()),
+                    },
+                }
+            }
+            // Only `HEAD` is advertised as unborn.
+            if id.is_none() && name != "HEAD" {
+                continue;
+            }
+            let peeled = match id {
+                Some(id)
+                    if self.try_find_header(id).ok().flatten().map(|h| h.kind()) == Some(gix_object::Kind::Tag) =>
+                {
+                    Some(self.find_object(id)?.peel_tags_to_end()?.id)
+                }
+                _ => None,
+            };
+
+            let mut full_name = namespace.map(ToOwned::to_owned).unwrap_or_default();
+            full_name.push_str(&name);
+            if hidden.is_hidden(Some(name.as_ref()), full_name.as_ref()) {
+                out.hidden_tips.extend(id.into_iter().chain(peeled));
+                continue;
+            }
+            out.visible.push(Ref {
+                name,
+                id,
+                peeled,
+                symref_target,
+            });
+        }
+        Ok(out)
+    }
+
+    fn check_wants(&self, wants: &[ObjectId], refs: &Refs, policy: &Policy) -> Result<(), Error> {
+        let mut reachable = None;
+        for id in wants {
+            let is_advertised = refs
+                .visible
+                .iter()
+                .any(|r| r.id.as_ref() == Some(id) || r.peeled.as_ref() == Some(id));
+            let is_allowed = is_advertised
+                || (policy.allow_any_sha1_in_want && self.has_object(id))
+                || (policy.allow_tip_sha1_in_want && refs.hidden_tips.contains(id))
+                || (policy.allow_reachable_sha1_in_want && {
+                    if reachable.is_none() {
+                        let tips = refs
+                            .visible
+                            .iter()
+                            .flat_map(|r| r.peeled.or(r.id))
+                            .chain(refs.hidden_tips.iter().copied());
+                        reachable = Some(self.reachable_commits(tips)?);
+                    }
+                    reachable.as_ref().map_or(false, |commits| commits.contains(id))
+                });
+            if !is_allowed {
+                return Err(Error::NotOurRef { id: *id });
+            }
+        }
+        Ok(())
+    }
+
+    /// Return those of `haves` which are commits in our object database.
+    fn common_commits(&self, haves: &[ObjectId]) -> Vec<ObjectId> {
+        haves
+            .iter()
+            .filter(|id| {
+                self.try_find_header(**id)
+                    .ok()
+                    .flatten()
+                    .map_or(false, |header| header.kind() == gix_object::Kind::Commit)
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Return all commits reachable from those of `tips` that are commits.
+    fn reachable_commits(
+        &self,
+        tips: impl IntoIterator<Item = ObjectId>,
+    ) -> Result<gix_hashtable::HashSet<ObjectId>, Error> {
+        let tips: Vec<_> = tips
+            .into_iter()
+            .filter(|id| {
+                self.try_find_header(*id)
+                    .ok()
+                    .flatten()
+                    .map_or(false, |header| header.kind() == gix_object::Kind::Commit)
+            })
+            .collect();
+        let mut out = gix_hashtable::HashSet::default();
+        if tips.is_empty() {
+            return Ok(out);
+        }
+        for info in self.rev_walk(tips).all()? {
+            out.insert(info?.id);
+        }
+        Ok(out)
+    }
+
+    /// Find all objects reachable from `wants` that aren't reachable from `common` and write them as pack to `out`,
+    /// returning the amount of objects in the pack.
+    ///
+    /// If there is a pack bitmap, it's used to find the objects. Otherwise, the commits are walked and only the trees of `common`
+    /// commits are considered to be present on the client.
+    fn write_upload_pack(
+        &self,
+        wants: &[ObjectId],
+        common: &[ObjectId],
+        request: &FetchRequest,
+        refs: &Refs,
+        out: &mut encode::Sideband<impl Write>,
+    ) -> Result<usize, Error> {
+        let spec = request
+            .filter
+            .as_ref()
+            .map(|spec| filter::parse(spec.as_ref()))
+            .transpose()?;
+        let filter = spec.as_ref().map(|spec| filter::Filter::new(spec, self)).transpose()?;
+        let filter = filter.as_ref();
+        let mut objects = Vec::new();
+        let mut seen = gix_hashtable::HashSet::default();
+        let mut descended = gix_hashtable::HashMap::default();
+        // Bitmaps know the kind of each object, but not its size or path.
+        let bitmap = match spec {
+            None | Some(filter::Spec::BlobNone) => self.pack_bitmap(),
+            Some(_) => None,
+        };
+        if let Some(pack_bitmap) = bitmap {
+            let crate::upload_pack::PackBitmap { index, bitmap } = &**pack_bitmap;
+            let mut reachable = bitmap.reachable(index, wants.iter().copied(), &self.objects)?;
+            reachable.subtract(&bitmap.reachable(index, common.iter().copied(), &self.objects)?);
+            if spec.is_some() {
+                reachable.remove_kind(bitmap, gix_object::Kind::Blob);
+                // Blobs that are explicitly wanted are sent nonetheless.
+                for want in wants {
+                    if self.find_header(*want)?.kind() == gix_object::Kind::Blob && seen.insert(*want) {
+                        objects.push(*want);
+                    }
+                }
+            }
+            for id in reachable.ids(bitmap, index) {
+                seen.insert(id);
+                objects.push(id);
+            }
+        } else {
+            // The client has all trees of the common commits, which are the ones most likely to share objects with the ones we send.
+            let mut ignored = Vec::new();
+            for id in common {
+                let tree = gix_object::CommitRefIter::from_bytes(&self.find_object(*id)?.data).tree_id()?;
+                self.collect_tree_objects(tree, None, &mut seen, &mut descended, &mut ignored)?;
+            }
+            let uninteresting = self.reachable_commits(common.iter().copied())?;
+
+            let mut commits = Vec::new();
+            for want in wants {
+                let mut id = *want;
+                loop {
+                    let object = self.find_object(id)?;
+                    match object.kind {
+                        gix_object::Kind::Commit => {
+                            if !uninteresting.contains(&id) {
+                                commits.push(id);
+                            }
+                            break;
+                        }
+                        gix_object::Kind::Tree => {
+                            self.collect_tree_objects(id, filter, &mut seen, &mut descended, &mut objects)?;
+                            break;
+                        }
+                        gix_object::Kind::Tag => {
+                            if seen.insert(id) && is_included(filter, &id, gix_object::Kind::Tag)? {
+                                objects.push(id);
+                            }
+                            id = gix_object::TagRefIter::from_bytes(&object.data).target_id()?;
+                        }
+                        gix_object::Kind::Blob => {
+                            if seen.insert(id) {
+                                objects.push(id);
+                            }
+                            break;
+                        }
+                    }
+                }
+            }
+            if !commits.is_empty() {
+                for info in self.rev_walk(commits).selected(move |id| !uninteresting.contains(id))? {
+                    let id = info?.id;
+                    if !seen.insert(id) {
+                        continue;
+                    }
+                    if is_included(filter, &id, gix_object::Kind::Commit)? {
+                        objects.push(id);
+                    }
+                    let tree = gix_object::CommitRefIter::from_bytes(&self.find_object(id)?.data).tree_id()?;
+                    self.collect_tree_objects(tree, filter, &mut seen, &mut descended, &mut objects)?;
+                }
+            }
+        }
+        if request.include_tag {
+            for r in &refs.visible {
+                let (Some(tag), Some(peeled)) = (r.id, r.peeled) else {
+                    continue;
+                };
+                if !seen.contains(&peeled) || seen.contains(&tag) {
+                    continue;
+                }
+                // Nested tags are sent as well, up to the object that is in the pack already.
+                let mut id = tag;
+                while seen.insert(id) {
+                    objects.push(id);
+                    let object = self.find_object(id)?;
+                    if object.kind != gix_object::Kind::Tag {
+                        break;
+                    }
+                    id = gix_object::TagRefIter::from_bytes(&object.data).target_id()?;
+                }
+            }
+        }
+
+        if !request.no_progress {
+            out.progress(format!("Enumerating objects: {}, done.\n", objects.len()).as_bytes())?;
+        }
+        let num_objects = objects.len();
+        let mut db = self.objects.clone().into_arc().map_err(Error::OpenStore)?;
+        // Entries are copied from packs by location, which requires packs to stay available.
+        db.prevent_pack_unload();
+        let (counts, _) = gix_pack::data::output::count::objects_unthreaded(
+            &db,
+            &mut objects.into_iter().map(Ok),
+            &gix_features::progress::Discard,
+            &AtomicBool::new(false),
+            gix_pack::data::output::count::objects::ObjectExpansion::AsIs,
+        )
+        .map_err(|err| Error::GeneratePack(err.into()))?;
+        let entries = gix_features::parallel::InOrderIter::from(gix_pack::data::output::entry::iter_from_counts(
+            counts,
+            db,
+            Box::new(gix_features::progress::Discard),
+            gix_pack::data::output::entry::iter_from_counts::Options {
+                thread_limit: None,
+                mode: gix_pack::data::output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+                allow_thin_pack: false,
+                chunk_size: 1000,
+                version: gix_pack::data::Version::V2,
+            },
+        ));
+        for written in gix_pack::data::output::bytes::FromEntriesIter::new(
+            entries,
+            &mut *out,
+            num_objects as u32,
+            gix_pack::data::Version::V2,
+            self.object_hash(),
+        ) {
+            written.map_err(|err| Error::GeneratePack(err.into()))?;
+        }
+        if !request.no_progress {
+            out.progress(format!("Total {num_objects}\n").as_bytes())?;
+        }
+        Ok(num_objects)
+    }
+
+    /// Open the bitmap of the first pack that has one, along with the index of the pack, or return `None` if there is none
+    /// or if it can't be read, just like `git` which ignores all but one bitmap and continues without it if it's broken.
+    ///
+    /// The bitmap is shared by all clones of this repository and only loaded again once the pack directory changes.
+    fn pack_bitmap(&self) -> Option<gix_fs::SharedFileSnapshot<crate::upload_pack::PackBitmap>> {
+        let pack_dir = self.objects.store_ref().path().join("pack");
+        self.pack_bitmap
+            .recent_snapshot(
+                || pack_dir.metadata().ok().and_then(|m| m.modified().ok()),
+                || -> Result<_, std::convert::Infallible> {
+                    let Ok(entries) = std::fs::read_dir(&pack_dir) else {
+                        return Ok(None);
+                    };
+                    let mut paths: Vec<_> = entries
+                        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                        .filter(|path| path.extension() == Some("bitmap".as_ref()))
+                        .collect();
+                    paths.sort();
+                    Ok(paths.into_iter().find_map(|path| {
+                        let index = gix_pack::index::File::at(path.with_extension("idx"), self.object_hash()).ok()?;
+                        let bitmap = gix_pack::bitmap::File::at(&path, &index).ok()?;
+                        Some(crate::upload_pack::PackBitmap { index, bitmap })
+                    }))
+                },
+            )
+            .ok()
+            .flatten()
+    }
+
+    /// Add `tree` and all trees and blobs reachable from it to `out` unless they were `seen` already or are omitted by `filter`.
+    /// Submodule commits are skipped.
+    ///
+    /// With `filter`, trees are visited again if they are reached at a smaller depth than the one they were `descended` into before,
+    /// as more of their entries may be included then.
+    fn collect_tree_objects(
+        &self,
+        tree: ObjectId,
+        filter: Option<&filter::Filter<'_>>,
+        seen: &mut gix_hashtable::HashSet<ObjectId>,
+        descended: &mut gix_hashtable::HashMap<ObjectId, usize>,
+        out: &mut Vec<ObjectId>,
+    ) -> Result<(), Error> {
+        let mut stack = vec![(tree, 0, BString::default())];
+        while let Some((id, depth, path)) = stack.pop() {
+            match filter {
+                None => {
+                    if !seen.insert(id) {
+                        continue;
+                    }
+                    out.push(id);
+                }
+                Some(filter) => {
+                    let decision = filter.decide(filter::Object {
+                        id: &id,
+                        kind: gix_object::Kind::Tree,
+                        depth,
+                        path: path.as_ref(),
+                    })?;
+                    if decision == filter::Decision::OmitTree
+                        || matches!(descended.get(&id), Some(previous) if *previous <= depth)
+                    {
+                        continue;
+                    }
+                    descended.insert(id, depth);
+                    if decision == filter::Decision::Include && seen.insert(id) {
+                        out.push(id);
+                    }
+                }
+            }
+            let tree = self.find_object(id)?;
+            for entry in gix_object::TreeRefIter::from_bytes(&tree.data) {
+                let entry = entry?;
+                if entry.mode.is_commit() || (!entry.mode.is_tree() && seen.contains(entry.oid)) {
+                    continue;
+                }
+                let mut entry_path = BString::default();
+                if filter.is_some() {
+                    entry_path.push_str(&path);
+                    if !entry_path.is_empty() {
+                        entry_path.push(b'/');
+                    }
+                    entry_path.push_str(entry.filename);
+                }
+                if entry.mode.is_tree() {
+                    stack.push((entry.oid.to_owned(), depth + 1, entry_path));
+                    continue;
+                }
+                let is_included = match filter {
+                    None => true,
+                    Some(filter) => {
+                        filter.decide(filter::Object {
+                            id: entry.oid,
+                            kind: gix_object::Kind::Blob,
+                            depth: depth + 1,
+                            path: entry_path.as_ref(),
+                        })? == filter::Decision::Include
+                    }
+                };
+                if is_included && seen.insert(entry.oid.to_owned()) {
+                    out.push(entry.oid.to_owned());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The references to advertise, along with the objects hidden references point to.
+#[derive(Default)]
+struct Refs {
+    visible: Vec<Ref>,
+    hidden_tips: Vec<ObjectId>,
+}
+
+/// What the client is allowed to ask for, as configured with `uploadpack.*`.
+struct Policy {
+    allow_tip_sha1_in_want: bool,
+    allow_reachable_sha1_in_want: bool,
+    allow_any_sha1_in_want: bool,
+    allow_filter: bool,
+}
+
+impl Policy {
+    fn from_config(repo: &Repository) -> Result<Self, crate::config::boolean::Error> {
+        let boolean = |key: &'static keys::Boolean| -> Result<bool, crate::config::boolean::Error> {
+            Ok(repo
+                .config
+                .resolved
+                .boolean(key)
+                .map(|v| key.enrich_error(v))
+                .transpose()?
+                .unwrap_or(false))
+        };
+        Ok(Policy {
+            allow_tip_sha1_in_want: boolean(&UploadPack::ALLOW_TIP_SHA1_IN_WANT)?,
+            allow_reachable_sha1_in_want: boolean(&UploadPack::ALLOW_REACHABLE_SHA1_IN_WANT)?,
+            allow_any_sha1_in_want: boolean(&UploadPack::ALLOW_ANY_SHA1_IN_WANT)?,
+            allow_filter: boolean(&UploadPack::ALLOW_FILTER)?,
+        })
+    }
+}
+
+fn check_request(request: &FetchRequest, policy: &Policy) -> Result<(), Error> {
+    if !request.shallow.is_empty() || request.deepen.is_some() {
+        return Err(Error::ShallowUnsupported);
+    }
+    match &request.filter {
+        Some(spec) if !policy.allow_filter => Err(Error::FilterUnsupported { spec: spec.clone() }),
+        Some(spec) => filter::parse(spec.as_ref()).map(|_| ()).map_err(Into::into),
+        None => Ok(()),
+    }
+}
+
+/// Return `true` if the object `id` of `kind` that isn't reached through a tree is included by `filter`, if there is one.
+fn is_included(filter: Option<&filter::Filter<'_>>, id: &gix_hash::oid, kind: gix_object::Kind) -> Result<bool, Error> {
+    Ok(match filter {
+        None => true,
+        Some(filter) => {
+            filter.decide(filter::Object {
+                id,
+                kind,
+                depth: 0,
+                path: Default::default(),
+            })? == filter::Decision::Include
+        }
+    })
+}
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use crate::{worktree, Worktree};
 
 /// Interact with individual worktrees and their information.
@@ -25,6 +27,194 @@ impl crate::Repository {
         res.sort_by(|a, b| a.git_dir.cmp(&b.git_dir));
         Ok(res)
     }
+
+    /// Create a new linked worktree at `path` with `commit` checked out as detached `HEAD`, and return it as repository,
+    /// similar to `git worktree add --detach <path> <commit>`.
+    ///
+    /// `path` must not exist or be an empty directory, and it must not be registered as worktree unless its checkout is missing
+    /// and [`force`](worktree::add::Options::force) is set. Its private git dir is named after the last component of `path`,
+    /// with a number appended to make it unique if needed.
+    ///
+    /// If the operation fails, all files created so far are removed again.
+    #[cfg(feature = "worktree-mutation")]
+    pub fn worktree_add(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        commit: impl Into<gix_hash::ObjectId>,
+        options: worktree::add::Options,
+    ) -> Result<crate::Repository, worktree::add::Error> {
+        use worktree::add::Error;
+
+        use crate::bstr::{BString, ByteSlice, ByteVec};
+        let path = path.as_ref();
+        let path = if path.is_relative() {
+            let cwd = std::env::current_dir().map_err(Error::CurrentDir)?;
+            let path = cwd.join(path);
+            gix_path::normalize(path.as_path().into(), &cwd)
+                .map(std::borrow::Cow::into_owned)
+                .unwrap_or(path)
+        } else {
+            path.to_owned()
+        };
+        let is_empty_dir = || matches!(std::fs::read_dir(&path).map(|mut iter| iter.next().is_none()), Ok(true));
+        if path.symlink_metadata().is_ok() && !is_empty_dir() {
+            return Err(Error::DestinationExists { path });
+        }
+        let io_err = |path: &std::path::Path| {
+            let path = path.to_owned();
+            move |source| Error::Io { source, path }
+        };
+        for registered in self.worktrees().map_err(io_err(&self.common_dir().join("worktrees")))? {
+            if registered.base().ok().as_deref() != Some(path.as_path()) {
+                continue;
+            }
+            if registered.is_locked() {
+                return Err(Error::RegisteredLocked { path });
+            }
+            if !options.force {
+                return Err(Error::Registered { path });
+            }
+            std::fs::remove_dir_all(registered.git_dir()).map_err(io_err(registered.git_dir()))?;
+        }
+
+        let commit = self
+            .find_object(commit)?
+            .peel_to_kind(gix_object::Kind::Commit)?
+            .into_commit();
+        let tree_id = commit.tree_id()?;
+
+        let worktrees_dir = self.common_dir().join("worktrees");
+        std::fs::create_dir_all(&worktrees_dir).map_err(io_err(&worktrees_dir))?;
+        let name = worktree_name(&path);
+        let mut git_dir = worktrees_dir.join(gix_path::from_bstr(name.as_bstr()));
+        let mut counter = 0;
+        loop {
+            match std::fs::create_dir(&git_dir) {
+                Ok(()) => break,
+                Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                    counter += 1;
+                    git_dir = worktrees_dir.join(gix_path::from_bstr(format!("{name}{counter}").as_bytes().as_bstr()));
+                }
+                Err(err) => return Err(io_err(&git_dir)(err)),
+            }
+        }
+        let created_worktree_dir = !path.is_dir();
+
+        let res = (|| {
+            let write =
+                |path: std::path::PathBuf, contents: &[u8]| std::fs::write(&path, contents).map_err(io_err(&path));
+            // Protect the worktree from being pruned while it's initialized, just like `git` does.
+            write(git_dir.join("locked"), b"initializing")?;
+            std::fs::create_dir_all(&path).map_err(io_err(&path))?;
+            let mut dot_git = gix_path::into_bstr(path.join(gix_discover::DOT_GIT_DIR)).into_owned();
+            dot_git.push(b'\n');
+            write(git_dir.join("gitdir"), &dot_git)?;
+            write(git_dir.join("commondir"), b"../..\n")?;
+            write(git_dir.join("HEAD"), format!("{}\n", commit.id).as_bytes())?;
+            let mut git_dir_pointer = BString::from("gitdir: ");
+            git_dir_pointer.push_str(gix_path::into_bstr(git_dir.as_path()).as_ref());
+            git_dir_pointer.push(b'\n');
+            write(path.join(gix_discover::DOT_GIT_DIR), &git_dir_pointer)?;
+
+            let repo = worktree::Proxy::new(self, git_dir.clone()).into_repo()?;
+            let mut index = repo.index_from_tree(&tree_id)?;
+            let mut opts = repo
+                .config
+                .checkout_options(&repo, gix_worktree::stack::state::attributes::Source::IdMapping)?;
+            opts.destination_is_initially_empty = true;
+            gix_worktree_state::checkout(
+                &mut index,
+                &path,
+                repo.objects.clone().into_arc().map_err(Error::OpenArcOdb)?,
+                &gix_features::progress::Discard,
+                &gix_features::progress::Discard,
+                &std::sync::atomic::AtomicBool::default(),
+                opts,
+            )?;
+            index.write(Default::default())?;
+
+            let locked = git_dir.join("locked");
+            match &options.lock_reason {
+                Some(reason) => {
+                    let mut contents = reason.clone();
+                    if !contents.is_empty() && !contents.ends_with(b"\n") {
+                        contents.push(b'\n');
+                    }
+                    write(locked, &contents)?;
+                }
+                None => std::fs::remove_file(&locked).map_err(io_err(&locked))?,
+            }
+            Ok(repo)
+        })();
+        if res.is_err() {
+            std::fs::remove_dir_all(&git_dir).ok();
+            std::fs::remove_dir(&worktrees_dir).ok();
+            if created_worktree_dir {
+                std::fs::remove_dir_all(&path).ok();
+            } else if let Ok(entries) = std::fs::read_dir(&path) {
+                for entry in entries.flatten() {
+                    let entry_path = entry.path();
+                    if matches!(entry.file_type().map(|kind| kind.is_dir()), Ok(true)) {
+                        std::fs::remove_dir_all(entry_path).ok();
+                    } else {
+                        std::fs::remove_file(entry_path).ok();
+                    }
+                }
+            }
+        }
+        res
+    }
+
+    /// Delete the administrative files of linked worktrees whose checkout doesn't exist anymore, similar to `git worktree prune`,
+    /// and return information about each of them. Locked worktrees are never pruned.
+    ///
+    /// Note that unlike `git`, entries are pruned independently of their age.
+    pub fn prune_worktrees(&self, options: worktree::prune::Options) -> std::io::Result<Vec<worktree::prune::Pruned>> {
+        use worktree::prune::{Pruned, Reason};
+        let mut res = Vec::new();
+        let worktrees_dir = self.common_dir().join("worktrees");
+        let iter = match std::fs::read_dir(&worktrees_dir) {
+            Ok(iter) => iter,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(res),
+            Err(err) => return Err(err),
+        };
+        for entry in iter {
+            let entry = entry?;
+            let worktree_git_dir = entry.path();
+            let proxy = worktree::Proxy::new(self, worktree_git_dir.clone());
+            let reason = if !worktree_git_dir.is_dir() {
+                Reason::NotADirectory
+            } else if proxy.is_locked() {
+                continue;
+            } else if !worktree_git_dir.join("gitdir").is_file() {
+                Reason::MissingGitdirFile
+            } else {
+                match proxy.base() {
+                    Ok(base) if base.as_os_str().is_empty() => Reason::InvalidGitdirFile,
+                    Ok(base) if base.exists() => continue,
+                    Ok(_) => Reason::MissingWorktree,
+                    Err(_) => Reason::InvalidGitdirFile,
+                }
+            };
+            if !options.dry_run {
+                if reason == Reason::NotADirectory {
+                    std::fs::remove_file(&worktree_git_dir)?;
+                } else {
+                    std::fs::remove_dir_all(&worktree_git_dir)?;
+                }
+            }
+            res.push(Pruned {
+                id: gix_path::into_bstr(PathBuf::from(entry.file_name())).into_owned(),
+                reason,
+            });
+        }
+        if !options.dry_run {
+            std::fs::remove_dir(&worktrees_dir).ok();
+        }
+        res.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(res)
+    }
+
     /// Return the repository owning the main worktree, typically from a linked worktree.
     ///
     /// Note that it might be the one that is currently open if this repository doesn't point to a linked worktree.
@@ -139,3 +329,42 @@ impl crate::Repository {
         Ok(())
     }
 }
+
+/// Derive the name of the private git dir of a worktree at `path` similar to how `git` does it, by turning its last component
+/// into a valid reference name component.
+#[cfg(feature = "worktree-mutation")]
+fn worktree_name(path: &std::path::Path) -> crate::bstr::BString {
+    use crate::bstr::BString;
+
+    let name = path
+        .file_name()
+        .map(|name| {
+            gix_path::os_str_into_bstr(name)
+                .map_or_else(|_| name.to_string_lossy().into_owned().into(), ToOwned::to_owned)
+        })
+        .unwrap_or_default();
+    let mut out = BString::default();
+    for &byte in name.iter() {
+        let is_invalid = byte < 0x20 || byte == 0x7f || b" ~^:?*[\\".contains(&byte);
+        match byte {
+            b'.' if out.is_empty() || out.ends_with(b".") => {}
+            _ if is_invalid => {
+                if !out.ends_with(b"-") {
+                    out.push(b'-');
+                }
+            }
+            _ => out.push(byte),
+        }
+    }
+    while out.ends_with(b".lock") {
+        let len = out.len() - ".lock".len();
+        out.truncate(len);
+    }
+    while out.ends_with(b".") {
+        out.pop();
+    }
+    if out.is_empty() {
+        out = "worktree".into();
+    }
+    out
+}
@@ -0,0 +1,579 @@
+//! Trace a range of lines of a file through history, like `git log -L` does.
+//!
+//! Starting with the ranges of lines in a file at a tip commit, history is traversed to find all commits that changed
+//! these lines. The ranges are mapped to the version of the file in each parent so they can grow, shrink or move as lines
+//! are added or removed, and renames are followed. The traversal ends where no tracked lines are left, which is typically
+//! when the file, or all tracked lines, were added.
+use std::ops::Range;
+
+use gix_hash::ObjectId;
+use gix_hashtable::HashMap;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    object::tree::diff::{change::Event, Action},
+    Repository,
+};
+
+/// A change of lines of a file, with `before` being the range of lines in the previous version of the file and `after`
+/// the range of lines in the current version. Ranges are zero-based and exclusive.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Hunk {
+    /// The lines of the previous version of the file which were removed, which is empty if lines were only added.
+    pub before: Range<u32>,
+    /// The lines of the current version of the file which were added, which is empty if lines were only removed.
+    pub after: Range<u32>,
+}
+
+/// A tracked range of lines of the current version of a file, with the range it corresponds to in the previous version
+/// and all hunks that changed it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Lines {
+    /// The range of lines in the previous version of the file, which may be empty if all tracked lines were added.
+    pub old: Range<u32>,
+    /// The tracked range of lines in the current version of the file.
+    pub new: Range<u32>,
+    /// All hunks that touch the tracked range, ordered by their position in the file.
+    pub hunks: Vec<Hunk>,
+}
+
+/// Describe how a [`Change`] affected the tracked lines.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Diff {
+    /// The file was added in the commit, so all tracked lines were added as well, which also ends their tracking.
+    Addition,
+    /// The tracked lines were changed compared to the only parent of the commit.
+    ///
+    /// Merges that changed the tracked lines are always reported as [`Diff::Merge`].
+    Modification {
+        /// The location of the file in the parent, which differs from the location in the commit if the file was renamed.
+        previous_location: BString,
+        /// The id of the blob of the file in the parent.
+        previous_id: ObjectId,
+        /// Each of the tracked ranges of lines that were changed.
+        lines: Vec<Lines>,
+    },
+    /// A merge commit whose tracked lines differ from the ones of each of its parents, so all parents are traversed.
+    ///
+    /// Like `git`, no diff is produced for these.
+    Merge,
+}
+
+/// A commit which changed the tracked lines, as returned by [`Iter`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Change {
+    /// The id of the commit which changed the tracked lines.
+    pub commit_id: ObjectId,
+    /// The location of the file in the commit.
+    pub location: BString,
+    /// The id of the blob of the file in the commit.
+    pub id: ObjectId,
+    /// All tracked ranges of lines in the version of the file in the commit, sorted and non-overlapping.
+    pub ranges: Vec<Range<u32>>,
+    /// How the tracked lines were changed.
+    pub diff: Diff,
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod init {
+    use std::ops::Range;
+
+    use crate::bstr::BString;
+
+    /// The error returned by [`Repository::line_log()`](crate::Repository::line_log()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Iter(Box<super::Error>),
+        #[error("There is no file at '{location}' in commit {commit_id}")]
+        FileNotFound {
+            location: BString,
+            commit_id: gix_hash::ObjectId,
+        },
+        #[error("The range of lines {}..{} is invalid for '{location}' as it has only {num_lines} lines", range.start, range.end)]
+        InvalidRange {
+            location: BString,
+            range: Range<u32>,
+            num_lines: u32,
+        },
+    }
+
+    impl From<super::Error> for Error {
+        fn from(err: super::Error) -> Self {
+            Error::Iter(Box::new(err))
+        }
+    }
+}
+
+/// The error returned by [`Iter`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    ObjectKind(#[from] crate::object::try_into::Error),
+    #[error(transparent)]
+    Commit(#[from] crate::object::commit::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    Rewrites(#[from] crate::diff::new_rewrites::Error),
+    #[error(transparent)]
+    ResourceCache(#[from] crate::repository::diff::resource_cache::Error),
+    #[error(transparent)]
+    TreeDiff(#[from] crate::object::tree::diff::for_each::Error),
+}
+
+/// An iterator over all commits that changed the tracked lines, created by [`Repository::line_log()`].
+///
+/// Like `git log -L`, commits are returned in topological order, and merges which didn't change the tracked lines compared
+/// to one of their parents are simplified so that only this parent is traversed.
+///
+/// Note that the iterator is eager: as topological order requires knowing all relevant commits, the whole traversal
+/// happens when the first item is requested, and all changes are kept in memory until they are returned.
+/// If the traversal fails, its error is the only item.
+pub struct Iter<'repo> {
+    repo: &'repo Repository,
+    tip: Option<(ObjectId, Tracked)>,
+    changes: std::vec::IntoIter<Change>,
+}
+
+impl<'repo> Iter<'repo> {
+    pub(crate) fn new(
+        repo: &'repo Repository,
+        tip: ObjectId,
+        location: &BStr,
+        lines: Range<u32>,
+    ) -> Result<Self, init::Error> {
+        let commit = repo
+            .find_object(tip)
+            .map_err(Error::from)?
+            .try_into_commit()
+            .map_err(Error::from)?;
+        let tree = commit.tree().map_err(Error::from)?;
+        let blob = find_blob(&tree, location)
+            .map_err(init::Error::from)?
+            .ok_or_else(|| init::Error::FileNotFound {
+                location: location.to_owned(),
+                commit_id: tip,
+            })?;
+        let num_lines = repo
+            .find_object(blob)
+            .map_err(Error::from)?
+            .data
+            .lines_with_terminator()
+            .count() as u32;
+        if lines.start >= lines.end || lines.end > num_lines {
+            return Err(init::Error::InvalidRange {
+                location: location.to_owned(),
+                range: lines,
+                num_lines,
+            });
+        }
+        Ok(Iter {
+            repo,
+            tip: Some((
+                tip,
+                Tracked {
+                    location: location.to_owned(),
+                    ranges: vec![lines],
+                },
+            )),
+            changes: Vec::new().into_iter(),
+        })
+    }
+}
+
+impl Iterator for Iter<'_> {
+    type Item = Result<Change, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((tip, tracked)) = self.tip.take() {
+            match traverse(self.repo, tip, tracked) {
+                Ok(changes) => self.changes = changes.into_iter(),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        self.changes.next().map(Ok)
+    }
+}
+
+/// The lines tracked in the version of a file at `location`.
+struct Tracked {
+    location: BString,
+    ranges: Vec<Range<u32>>,
+}
+
+/// A commit that was visited with tracked lines.
+struct Node {
+    /// The parents the tracked lines were followed into, or all parents if they weren't followed at all.
+    parents: Vec<ObjectId>,
+    /// The change to the tracked lines, if there was one.
+    change: Option<Change>,
+}
+
+/// How the tracked lines relate to the version of the file in a parent.
+enum Previous {
+    /// The file didn't exist in the parent.
+    Missing,
+    /// The file exists in the parent, and `touched` are the hunks that changed the tracked lines, which map to `ranges` in the parent.
+    Exists {
+        location: BString,
+        id: ObjectId,
+        ranges: Vec<Range<u32>>,
+        touched: Vec<Hunk>,
+    },
+}
+
+fn traverse(repo: &Repository, tip: ObjectId, tracked: Tracked) -> Result<Vec<Change>, Error> {
+    let mut queue = gix_revwalk::PriorityQueue::<gix_date::SecondsSinceUnixEpoch, ObjectId>::new();
+    let mut pending = HashMap::<ObjectId, Tracked>::default();
+    let mut nodes = HashMap::<ObjectId, Node>::default();
+    // The order in which commits were visited, which is by commit date.
+    let mut visited = Vec::new();
+    let mut resource_cache = None;
+
+    queue.insert(repo.find_object(tip)?.try_into_commit()?.time()?.seconds, tip);
+    pending.insert(tip, tracked);
+    while let Some(commit_id) = queue.pop_value() {
+        let Tracked { location, ranges } = pending.remove(&commit_id).expect("queued commits are pending");
+        let commit = repo.find_object(commit_id)?.try_into_commit()?;
+        let tree = commit.tree()?;
+        let Some(id) = find_blob(&tree, location.as_ref())? else {
+            continue;
+        };
+        let parent_ids: Vec<_> = commit.parent_ids().map(crate::Id::detach).collect();
+        let mut follow = Vec::new();
+        let (parents, diff) = if parent_ids.is_empty() {
+            (parent_ids, Some(Diff::Addition))
+        } else {
+            let data = repo.find_object(id)?.detach().data;
+            let mut previous_by_parent = Vec::new();
+            let mut unchanged_parent = None;
+            for parent_id in &parent_ids {
+                let previous = previous(
+                    repo,
+                    &tree,
+                    *parent_id,
+                    location.as_ref(),
+                    id,
+                    &data,
+                    &ranges,
+                    &mut resource_cache,
+                )?;
+                if matches!(&previous, Previous::Exists { touched, .. } if touched.is_empty()) {
+                    unchanged_parent = Some((*parent_id, previous));
+                    break;
+                }
+                previous_by_parent.push((*parent_id, previous));
+            }
+            match unchanged_parent {
+                Some((parent_id, Previous::Exists { location, ranges, .. })) => {
+                    follow.push((parent_id, location, ranges));
+                    let parents = if parent_ids.len() > 1 {
+                        vec![parent_id]
+                    } else {
+                        parent_ids
+                    };
+                    (parents, None)
+                }
+                Some((_, Previous::Missing)) => unreachable!("only existing files can be unchanged"),
+                None if parent_ids.len() > 1 => {
+                    for (parent_id, previous) in previous_by_parent {
+                        if let Previous::Exists { location, ranges, .. } = previous {
+                            follow.push((parent_id, location, ranges));
+                        }
+                    }
+                    (parent_ids, Some(Diff::Merge))
+                }
+                None => {
+                    let (parent_id, previous) = previous_by_parent.pop().expect("one parent");
+                    let diff = match previous {
+                        Previous::Missing => Diff::Addition,
+                        Previous::Exists {
+                            location: previous_location,
+                            id: previous_id,
+                            ranges: previous_ranges,
+                            touched,
+                        } => {
+                            let lines = lines_of_ranges(&ranges, &touched);
+                            follow.push((parent_id, previous_location.clone(), previous_ranges));
+                            Diff::Modification {
+                                previous_location,
+                                previous_id,
+                                lines,
+                            }
+                        }
+                    };
+                    (parent_ids, Some(diff))
+                }
+            }
+        };
+
+        for (parent_id, location, ranges) in follow {
+            if ranges.is_empty() || nodes.contains_key(&parent_id) {
+                continue;
+            }
+            match pending.get_mut(&parent_id) {
+                Some(tracked) if tracked.location == location => {
+                    tracked.ranges = normalize(tracked.ranges.iter().cloned().chain(ranges).collect());
+                }
+                Some(_) => {}
+                None => {
+                    queue.insert(
+                        repo.find_object(parent_id)?.try_into_commit()?.time()?.seconds,
+                        parent_id,
+                    );
+                    pending.insert(parent_id, Tracked { location, ranges });
+                }
+            }
+        }
+        visited.push(commit_id);
+        nodes.insert(
+            commit_id,
+            Node {
+                parents,
+                change: diff.map(|diff| Change {
+                    commit_id,
+                    location,
+                    id,
+                    ranges,
+                    diff,
+                }),
+            },
+        );
+    }
+    Ok(sort_topologically(visited, nodes))
+}
+
+/// Find the version of the file at `location` in the parent at `parent_id` of the commit with `tree`, where it is at `id`
+/// with `data`, and map the tracked `ranges` into it.
+#[allow(clippy::too_many_arguments)]
+fn previous(
+    repo: &Repository,
+    tree: &crate::Tree<'_>,
+    parent_id: ObjectId,
+    location: &BStr,
+    id: ObjectId,
+    data: &[u8],
+    ranges: &[Range<u32>],
+    resource_cache: &mut Option<gix_diff::blob::Platform>,
+) -> Result<Previous, Error> {
+    let parent_tree = repo.find_object(parent_id)?.try_into_commit()?.tree()?;
+    let (previous_location, previous_id) = match find_blob(&parent_tree, location)? {
+        Some(previous_id) => (location.to_owned(), previous_id),
+        None => {
+            let resource_cache = match resource_cache {
+                Some(cache) => cache,
+                None => resource_cache
+                    .insert(repo.diff_resource_cache(gix_diff::blob::pipeline::Mode::ToGit, Default::default())?),
+            };
+            let mut source = None;
+            parent_tree
+                .changes()?
+                .track_path()
+                .for_each_to_obtain_tree_with_cache(tree, resource_cache, |change| {
+                    if change.location != location {
+                        return Ok::<_, std::convert::Infallible>(Action::Continue);
+                    }
+                    if let Event::Rewrite {
+                        source_location,
+                        source_id,
+                        ..
+                    } = change.event
+                    {
+                        source = Some((source_location.to_owned(), source_id.detach()));
+                    }
+                    Ok(Action::Cancel)
+                })?;
+            resource_cache.clear_resource_cache();
+            match source {
+                Some(source) => source,
+                None => return Ok(Previous::Missing),
+            }
+        }
+    };
+    if previous_id == id {
+        return Ok(Previous::Exists {
+            location: previous_location,
+            id: previous_id,
+            ranges: ranges.to_vec(),
+            touched: Vec::new(),
+        });
+    }
+    let previous_data = repo.find_object(previous_id)?.detach().data;
+    let hunks = hunks(&previous_data, data);
+    let (ranges, touched) = map_ranges(ranges, &hunks);
+    Ok(Previous::Exists {
+        location: previous_location,
+        id: previous_id,
+        ranges,
+        touched,
+    })
+}
+
+fn find_blob(tree: &crate::Tree<'_>, location: &BStr) -> Result<Option<ObjectId>, Error> {
+    let mut buf = Vec::new();
+    Ok(tree
+        .lookup_entry(location.split_str("/"), &mut buf)?
+        .filter(|entry| entry.mode().is_blob_or_symlink())
+        .map(|entry| entry.object_id()))
+}
+
+/// Return all hunks that turn `old` into `new`, without context.
+///
+/// Like `git`, the Myers algorithm is always used, independently of the configured diff algorithm.
+fn hunks(old: &[u8], new: &[u8]) -> Vec<Hunk> {
+    let input = gix_diff::blob::intern::InternedInput::new(
+        gix_diff::blob::sources::byte_lines_with_terminator(old),
+        gix_diff::blob::sources::byte_lines_with_terminator(new),
+    );
+    let mut hunks = Vec::new();
+    gix_diff::blob::diff(
+        gix_diff::blob::Algorithm::Myers,
+        &input,
+        |before: Range<u32>, after: Range<u32>| hunks.push(Hunk { before, after }),
+    );
+    hunks
+}
+
+/// Map the tracked `ranges` of the new version of a file into the old version with the help of `hunks`, and return them along
+/// with the hunks that touched them.
+///
+/// Lines not touched by any hunk are moved by the amount of lines added or removed before them, while the tracked lines
+/// touched by a hunk are replaced by all lines the hunk removed.
+fn map_ranges(ranges: &[Range<u32>], hunks: &[Hunk]) -> (Vec<Range<u32>>, Vec<Hunk>) {
+    let touched: Vec<_> = hunks
+        .iter()
+        .filter(|hunk| ranges.iter().any(|range| overlaps(&hunk.after, range)))
+        .cloned()
+        .collect();
+    let untouched = ranges.iter().flat_map(|range| {
+        let mut start = range.start;
+        let mut pieces = Vec::new();
+        for hunk in touched.iter().filter(|hunk| overlaps(&hunk.after, range)) {
+            if hunk.after.start > start {
+                pieces.push(start..hunk.after.start);
+            }
+            start = start.max(hunk.after.end);
+        }
+        if start < range.end {
+            pieces.push(start..range.end);
+        }
+        pieces
+    });
+    let moved = untouched.map(|range| {
+        let offset: i64 = hunks
+            .iter()
+            .take_while(|hunk| hunk.after.start <= range.start)
+            .map(|hunk| hunk.after.len() as i64 - hunk.before.len() as i64)
+            .sum();
+        (range.start as i64 - offset) as u32..(range.end as i64 - offset) as u32
+    });
+    let ranges = normalize(moved.chain(touched.iter().map(|hunk| hunk.before.clone())).collect());
+    (ranges, touched)
+}
+
+/// Compute the previous range of lines for each of the tracked `ranges` that were changed by `touched` hunks, like `git` does
+/// to display them.
+fn lines_of_ranges(ranges: &[Range<u32>], touched: &[Hunk]) -> Vec<Lines> {
+    ranges
+        .iter()
+        .filter_map(|range| {
+            let hunks: Vec<_> = touched
+                .iter()
+                .filter(|hunk| overlaps(&hunk.after, range))
+                .cloned()
+                .collect();
+            let (first, last) = (hunks.first()?, hunks.last()?);
+            let start = first
+                .before
+                .start
+                .saturating_sub(first.after.start.saturating_sub(range.start));
+            let end = last.before.end + range.end.saturating_sub(last.after.end);
+            Some(Lines {
+                old: start..end,
+                new: range.clone(),
+                hunks,
+            })
+        })
+        .collect()
+}
+
+/// Return `true` if the lines `hunk` changed touch `range`, which for hunks that only remove lines means the removal happened
+/// in between lines of `range`.
+fn overlaps(hunk: &Range<u32>, range: &Range<u32>) -> bool {
+    hunk.start < range.end && range.start < hunk.end
+}
+
+/// Sort `ranges` and merge those that overlap or are adjacent, while removing empty ones.
+fn normalize(mut ranges: Vec<Range<u32>>) -> Vec<Range<u32>> {
+    ranges.retain(|range| !range.is_empty());
+    ranges.sort_by_key(|range| range.start);
+    let mut out: Vec<Range<u32>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match out.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => out.push(range),
+        }
+    }
+    out
+}
+
+/// Sort all changes found in `visited` commits in topological order just like `git` does by default, which prefers to show
+/// commits of the same line of history together.
+///
+/// The parents of commits with changes are rewritten to the closest commit with changes along the history the tracked lines
+/// were followed into, and unlike `git`, history that wasn't traversed isn't considered.
+fn sort_topologically(visited: Vec<ObjectId>, mut nodes: HashMap<ObjectId, Node>) -> Vec<Change> {
+    let visited: Vec<_> = visited
+        .into_iter()
+        .filter(|id| matches!(nodes.get(id), Some(node) if node.change.is_some()))
+        .collect();
+    let rewritten_parents = |id: &ObjectId| -> Vec<ObjectId> {
+        let mut out = Vec::new();
+        for parent_id in &nodes[id].parents {
+            let mut current = *parent_id;
+            let parent = loop {
+                let Some(node) = nodes.get(&current) else {
+                    break None;
+                };
+                if node.change.is_some() {
+                    break Some(current);
+                }
+                match node.parents.as_slice() {
+                    [single] => current = *single,
+                    _ => break None,
+                }
+            };
+            if let Some(parent) = parent.filter(|parent| !out.contains(parent)) {
+                out.push(parent);
+            }
+        }
+        out
+    };
+    let parents: HashMap<ObjectId, Vec<ObjectId>> = visited.iter().map(|id| (*id, rewritten_parents(id))).collect();
+
+    let mut in_degree: HashMap<ObjectId, usize> = visited.iter().map(|id| (*id, 1)).collect();
+    for parent_id in parents.values().flatten() {
+        if let Some(degree) = in_degree.get_mut(parent_id) {
+            *degree += 1;
+        }
+    }
+    let mut stack: Vec<_> = visited.iter().rev().filter(|id| in_degree[*id] == 1).copied().collect();
+    let mut out = Vec::with_capacity(visited.len());
+    while let Some(id) = stack.pop() {
+        for parent_id in &parents[&id] {
+            let degree = in_degree.get_mut(parent_id).expect("all parents are visited");
+            *degree -= 1;
+            if *degree == 1 {
+                stack.push(*parent_id);
+            }
+        }
+        out.extend(nodes.remove(&id).and_then(|node| node.change));
+    }
+    out
+}
@@ -10,6 +10,10 @@ pub use gix_revision as plumbing;
 pub mod walk;
 pub use walk::iter::Walk;
 
+///
+#[cfg(feature = "blob-diff")]
+pub mod line_log;
+
 ///
 #[cfg(feature = "revision")]
 #[allow(clippy::empty_docs)]
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::{borrow::Cow, collections::HashSet};
 
 use gix_hash::ObjectId;
 use gix_revision::spec::{parse, parse::delegate};
@@ -6,6 +6,7 @@ use smallvec::SmallVec;
 
 use super::{Delegate, Error, ObjectKindHint};
 use crate::{
+    bstr::{BStr, BString},
     ext::{ObjectIdExt, ReferenceExt},
     Repository,
 };
@@ -216,6 +217,35 @@ impl<'repo> Delegate<'repo> {
         Some(())
     }
 
+    /// Resolve `path` relative to the current working directory within the worktree if it starts with `./` or `../`,
+    /// just like git does, or return it unchanged as it's relative to the root of the worktree already.
+    fn resolve_relative_path<'a>(&self, path: &'a BStr) -> Result<Cow<'a, BStr>, Error> {
+        if !(path.starts_with(b"./") || path.starts_with(b"../") || path == "." || path == "..") {
+            return Ok(Cow::Borrowed(path));
+        }
+        let prefix: BString = self
+            .repo
+            .prefix()?
+            .map(|prefix| gix_path::to_unix_separators_on_windows(gix_path::into_bstr(prefix)).into_owned())
+            .unwrap_or_default();
+        let mut components: Vec<&[u8]> = prefix.split(|b| *b == b'/').filter(|c| !c.is_empty()).collect();
+        for component in path.split(|b| *b == b'/') {
+            match component {
+                b"" | b"." => {}
+                b".." => {
+                    if components.pop().is_none() {
+                        return Err(Error::RelativePathOutsideOfWorktree {
+                            path: path.to_owned(),
+                            prefix: prefix.clone(),
+                        });
+                    }
+                }
+                _ => components.push(component),
+            }
+        }
+        Ok(Cow::Owned(crate::bstr::join("/", components).into()))
+    }
+
     fn unset_disambiguate_call(&mut self) {
         self.last_call_was_disambiguate_prefix[self.idx] = false;
     }
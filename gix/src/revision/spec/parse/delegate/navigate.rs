@@ -91,6 +91,16 @@ impl<'repo> delegate::Navigate for Delegate<'repo> {
     fn peel_until(&mut self, kind: PeelTo<'_>) -> Option<()> {
         self.unset_disambiguate_call();
         self.follow_refs_to_objects_if_needed()?;
+        let resolved_path = match kind {
+            PeelTo::Path(path) => match self.resolve_relative_path(path) {
+                Ok(path) => Some(path),
+                Err(err) => {
+                    self.err.push(err);
+                    return None;
+                }
+            },
+            _ => None,
+        };
 
         let mut replacements = Replacements::default();
         let mut errors = Vec::new();
@@ -117,7 +127,8 @@ impl<'repo> delegate::Navigate for Delegate<'repo> {
                     }
                 }
             }
-            PeelTo::Path(path) => {
+            PeelTo::Path(_) => {
+                let path = resolved_path.as_deref().expect("set for paths");
                 let lookup_path = |obj: &ObjectId| {
                     let tree_id = peel(repo, obj, gix_object::Kind::Tree)?;
                     if path.is_empty() {
@@ -310,6 +321,14 @@ impl<'repo> delegate::Navigate for Delegate<'repo> {
             ),
         };
         self.unset_disambiguate_call();
+        let path = match self.resolve_relative_path(path) {
+            Ok(path) => path,
+            Err(err) => {
+                self.err.push(err);
+                return None;
+            }
+        };
+        let path = path.as_ref();
         match self.repo.index() {
             Ok(index) => match index.entry_by_path_and_stage(path, stage) {
                 Some(entry) => {
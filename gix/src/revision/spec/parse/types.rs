@@ -105,6 +105,10 @@ pub enum Error {
         stage_hint: Option<gix_index::entry::Stage>,
         exists: bool,
     },
+    #[error("Path {path:?} is outside of the worktree when resolved relative to the current directory at {prefix:?}")]
+    RelativePathOutsideOfWorktree { path: BString, prefix: BString },
+    #[error(transparent)]
+    Prefix(#[from] gix_path::realpath::Error),
     #[error(transparent)]
     FindHead(#[from] reference::find::existing::Error),
     #[error(transparent)]
@@ -1,8 +1,10 @@
 use gix_hash::ObjectId;
-use gix_object::FindExt;
+use gix_object::{bstr::BString, FindExt};
 
 use crate::{ext::ObjectIdExt, revision, Repository};
 
+mod changed_paths;
+
 /// The error returned by [`Platform::all()`].
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
@@ -15,6 +17,19 @@ pub enum Error {
     ConfigBoolean(#[from] crate::config::boolean::Error),
 }
 
+/// Statistics about the use of changed-path Bloom filters in a traversal limited to [changed paths](Platform::changed_paths()).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ChangedPathsStatistics {
+    /// The amount of commits without a Bloom filter, which had their trees compared to the trees of their first parent.
+    pub filter_not_present: usize,
+    /// The amount of commits whose Bloom filter showed that none of the paths changed, so their trees didn't have to be compared.
+    pub definitely_not: usize,
+    /// The amount of commits whose Bloom filter showed that one of the paths may have changed, so their trees were compared.
+    pub maybe: usize,
+    /// The amount of commits among [`maybe`](Self::maybe) that turned out not to change any path.
+    pub false_positive: usize,
+}
+
 /// Information about a commit that we obtained naturally as part of the iteration.
 #[derive(Debug, Clone)]
 pub struct Info<'repo> {
@@ -95,6 +110,7 @@ pub struct Platform<'repo> {
     pub(crate) parents: gix_traverse::commit::Parents,
     pub(crate) use_commit_graph: Option<bool>,
     pub(crate) commit_graph: Option<gix_commitgraph::Graph>,
+    pub(crate) changed_paths: Vec<BString>,
 }
 
 impl<'repo> Platform<'repo> {
@@ -106,6 +122,7 @@ impl<'repo> Platform<'repo> {
             parents: Default::default(),
             use_commit_graph: None,
             commit_graph: None,
+            changed_paths: Vec::new(),
         }
     }
 }
@@ -143,6 +160,18 @@ impl<'repo> Platform<'repo> {
         self.commit_graph = graph;
         self
     }
+
+    /// Only yield commits that changed at least one of `paths` compared to their first parent, or that contain one of
+    /// them if they have no parent, while still traversing all commits. An empty list of `paths` yields all commits.
+    ///
+    /// `paths` are relative to the repository root and match literally, so a directory matches everything below it.
+    /// Changed-path Bloom filters in the commit-graph are used to avoid comparing the trees of commits that
+    /// certainly didn't change any of the paths, unless the commit-graph [is turned off](Platform::use_commit_graph())
+    /// or `commitGraph.readChangedPaths` is `false`.
+    pub fn changed_paths(mut self, paths: impl IntoIterator<Item = impl Into<BString>>) -> Self {
+        self.changed_paths = paths.into_iter().map(Into::into).collect();
+        self
+    }
 }
 
 /// Produce the iterator
@@ -162,9 +191,20 @@ impl<'repo> Platform<'repo> {
             parents,
             use_commit_graph,
             commit_graph,
+            changed_paths,
         } = self;
+        let use_commit_graph = use_commit_graph.map_or_else(|| repo.config.may_use_commit_graph(), Ok)?;
+        let changed_paths = if changed_paths.is_empty() {
+            None
+        } else {
+            let graph = (use_commit_graph && repo.config.may_read_changed_paths()?)
+                .then(|| repo.commit_graph().ok())
+                .flatten();
+            Some(changed_paths::ChangedPaths::new(changed_paths, graph))
+        };
         Ok(revision::Walk {
             repo,
+            changed_paths,
             inner: Box::new(
                 gix_traverse::commit::Simple::filtered(tips, &repo.objects, {
                     // Note that specific shallow handling for commit-graphs isn't needed as these contain
@@ -197,12 +237,7 @@ impl<'repo> Platform<'repo> {
                 })
                 .sorting(sorting)?
                 .parents(parents)
-                .commit_graph(
-                    commit_graph.or(use_commit_graph
-                        .map_or_else(|| self.repo.config.may_use_commit_graph(), Ok)?
-                        .then(|| self.repo.commit_graph().ok())
-                        .flatten()),
-                ),
+                .commit_graph(commit_graph.or(use_commit_graph.then(|| repo.commit_graph().ok()).flatten())),
             ),
         })
     }
@@ -223,15 +258,35 @@ pub(crate) mod iter {
         pub(crate) repo: &'repo crate::Repository,
         pub(crate) inner:
             Box<dyn Iterator<Item = Result<gix_traverse::commit::Info, gix_traverse::commit::simple::Error>> + 'repo>,
+        pub(crate) changed_paths: Option<super::changed_paths::ChangedPaths>,
+    }
+
+    impl<'repo> Walk<'repo> {
+        /// Return statistics about the use of changed-path Bloom filters so far, if the walk is limited to
+        /// [changed paths](super::Platform::changed_paths()).
+        pub fn changed_paths_statistics(&self) -> Option<super::ChangedPathsStatistics> {
+            self.changed_paths.as_ref().map(|changed_paths| changed_paths.stats)
+        }
     }
 
     impl<'repo> Iterator for Walk<'repo> {
         type Item = Result<super::Info<'repo>, gix_traverse::commit::simple::Error>;
 
         fn next(&mut self) -> Option<Self::Item> {
-            self.inner
-                .next()
-                .map(|res| res.map(|info| super::Info::new(info, self.repo)))
+            loop {
+                let info = match self.inner.next()? {
+                    Ok(info) => info,
+                    Err(err) => return Some(Err(err)),
+                };
+                if let Some(changed_paths) = self.changed_paths.as_mut() {
+                    match changed_paths.is_changed(&self.repo.objects, &info) {
+                        Ok(true) => {}
+                        Ok(false) => continue,
+                        Err(err) => return Some(Err(err)),
+                    }
+                }
+                return Some(Ok(super::Info::new(info, self.repo)));
+            }
         }
     }
 }
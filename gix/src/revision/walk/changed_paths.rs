@@ -0,0 +1,141 @@
+use gix_commitgraph::bloom;
+use gix_hash::ObjectId;
+use gix_object::{
+    bstr::{BStr, BString, ByteSlice},
+    FindExt,
+};
+use gix_traverse::commit::simple::Error;
+
+use crate::revision::walk::ChangedPathsStatistics;
+
+/// Decide if commits changed any of the `paths`, using changed-path Bloom filters from `graph` where possible.
+pub(crate) struct ChangedPaths {
+    paths: Vec<BString>,
+    graph: Option<gix_commitgraph::Graph>,
+    /// The keys of each path and its leading directories, for each kind of settings we encountered.
+    keys: Vec<(bloom::Settings, Vec<Vec<bloom::Key>>)>,
+    buf: Vec<u8>,
+    pub(crate) stats: ChangedPathsStatistics,
+}
+
+impl ChangedPaths {
+    pub(crate) fn new(paths: Vec<BString>, graph: Option<gix_commitgraph::Graph>) -> Self {
+        ChangedPaths {
+            paths,
+            graph,
+            keys: Vec::new(),
+            buf: Vec::new(),
+            stats: Default::default(),
+        }
+    }
+
+    /// Return `true` if the commit described by `info` changed any of our paths compared to its first parent,
+    /// or contains any of them if it has no parent.
+    pub(crate) fn is_changed(
+        &mut self,
+        objects: &impl gix_object::Find,
+        info: &gix_traverse::commit::Info,
+    ) -> Result<bool, Error> {
+        let tree_id = self.tree_id(objects, &info.id)?;
+        let Some(parent_id) = info.parent_ids.first() else {
+            for path in &self.paths {
+                if entry_at(objects, &mut self.buf, tree_id, path.as_ref())?.is_some() {
+                    return Ok(true);
+                }
+            }
+            return Ok(false);
+        };
+
+        let filter = self
+            .graph
+            .as_ref()
+            .and_then(|graph| graph.commit_by_id(info.id))
+            .and_then(|commit| commit.changed_paths_filter());
+        let used_filter = match filter {
+            Some(filter) => {
+                let keys = keys_for(&mut self.keys, &self.paths, filter.settings());
+                if !keys
+                    .iter()
+                    .any(|path_keys| path_keys.iter().all(|key| filter.contains(key)))
+                {
+                    self.stats.definitely_not += 1;
+                    return Ok(false);
+                }
+                self.stats.maybe += 1;
+                true
+            }
+            None => {
+                self.stats.filter_not_present += 1;
+                false
+            }
+        };
+
+        let parent_tree_id = self.tree_id(objects, parent_id)?;
+        let mut changed = false;
+        for path in &self.paths {
+            if entry_at(objects, &mut self.buf, tree_id, path.as_ref())?
+                != entry_at(objects, &mut self.buf, parent_tree_id, path.as_ref())?
+            {
+                changed = true;
+                break;
+            }
+        }
+        if used_filter && !changed {
+            self.stats.false_positive += 1;
+        }
+        Ok(changed)
+    }
+
+    fn tree_id(&mut self, objects: &impl gix_object::Find, id: &gix_hash::oid) -> Result<ObjectId, Error> {
+        if let Some(commit) = self.graph.as_ref().and_then(|graph| graph.commit_by_id(id)) {
+            return Ok(commit.root_tree_id().to_owned());
+        }
+        Ok(objects.find_commit_iter(id, &mut self.buf)?.tree_id()?)
+    }
+}
+
+fn keys_for<'a>(
+    keys: &'a mut Vec<(bloom::Settings, Vec<Vec<bloom::Key>>)>,
+    paths: &[BString],
+    settings: &bloom::Settings,
+) -> &'a [Vec<bloom::Key>] {
+    let idx = match keys.iter().position(|(s, _)| s == settings) {
+        Some(idx) => idx,
+        None => {
+            keys.push((
+                *settings,
+                paths
+                    .iter()
+                    .map(|path| bloom::Key::for_path_and_leading_directories(path.as_ref(), settings))
+                    .collect(),
+            ));
+            keys.len() - 1
+        }
+    };
+    &keys[idx].1
+}
+
+/// Return the mode and id of the entry at `path` in the tree with `tree_id`, or `None` if there is no such entry.
+/// An empty `path` refers to the tree itself.
+fn entry_at(
+    objects: &impl gix_object::Find,
+    buf: &mut Vec<u8>,
+    tree_id: ObjectId,
+    path: &BStr,
+) -> Result<Option<(gix_object::tree::EntryMode, ObjectId)>, Error> {
+    let mut entry: (gix_object::tree::EntryMode, _) = (gix_object::tree::EntryKind::Tree.into(), tree_id);
+    for component in path.split_str("/").filter(|component| !component.is_empty()) {
+        if !entry.0.is_tree() {
+            return Ok(None);
+        }
+        match objects
+            .find_tree_iter(&entry.1, buf)?
+            .filter_map(Result::ok)
+            .find(|e| e.filename == component.as_bstr())
+        {
+            Some(e) => entry = (e.mode, e.oid.to_owned()),
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(entry))
+}
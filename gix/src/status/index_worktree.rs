@@ -22,6 +22,8 @@ pub enum Error {
     #[error(transparent)]
     StatOptions(#[from] config::stat_options::Error),
     #[error(transparent)]
+    BigFileThreshold(#[from] config::unsigned_integer::Error),
+    #[error(transparent)]
     ResourceCache(#[from] crate::diff::resource_cache::Error),
 }
 
@@ -81,6 +83,9 @@ impl Repository {
     /// ### Note
     ///
     /// This is a lower-level method, prefer the [`status`](Repository::status()) method for greater ease of use.
+    ///
+    /// If `core.bigFileThreshold` is set, files of at least that size whose stat information changed are considered
+    /// modified without looking at their content if `compare` supports it, like [`FastEq`](gix_status::index_as_worktree::traits::FastEq) does.
     #[allow(clippy::too_many_arguments)]
     pub fn index_worktree_status<'index, T, U, E>(
         &self,
@@ -160,6 +165,16 @@ impl Repository {
                     fs: fs_caps,
                     thread_limit: options.thread_limit,
                     stat: self.stat_options()?,
+                    content: gix_status::index_as_worktree::content::Options {
+                        skip_verification_threshold: self
+                            .config
+                            .resolved
+                            .integer("core.bigFileThreshold")
+                            .is_some()
+                            .then(|| self.config.big_file_threshold())
+                            .transpose()?,
+                        ..Default::default()
+                    },
                 },
                 dirwalk: options.dirwalk_options.map(Into::into),
                 rewrites: options.rewrites,
@@ -171,6 +186,9 @@ impl Repository {
 
 /// An implementation of a trait to use with [`Repository::index_worktree_status()`] to compute the submodule status
 /// using [Submodule::status()](crate::Submodule::status()).
+///
+/// The status of each submodule is computed only once and shared among all clones of an instance,
+/// even if it is queried multiple times.
 #[derive(Clone)]
 pub struct BuiltinSubmoduleStatus {
     mode: crate::status::Submodule,
@@ -179,6 +197,7 @@ pub struct BuiltinSubmoduleStatus {
     #[cfg(not(feature = "parallel"))]
     git_dir: std::path::PathBuf,
     submodule_paths: Vec<BString>,
+    cache: std::sync::Arc<std::sync::Mutex<std::collections::HashMap<BString, Option<crate::submodule::Status>>>>,
 }
 
 ///
@@ -212,6 +231,7 @@ mod submodule_status {
                 #[cfg(not(feature = "parallel"))]
                 git_dir: local_repo.git_dir().to_owned(),
                 submodule_paths,
+                cache: Default::default(),
             })
         }
     }
@@ -224,6 +244,8 @@ mod submodule_status {
         SubmoduleStatus(#[from] crate::submodule::status::Error),
         #[error(transparent)]
         IgnoreConfig(#[from] crate::submodule::config::Error),
+        #[error(transparent)]
+        DiffIgnoreSubmodules(#[from] crate::config::key::GenericErrorWithValue),
     }
 
     impl gix_status::index_as_worktree::traits::SubmoduleStatus for BuiltinSubmoduleStatus {
@@ -239,6 +261,22 @@ mod submodule_status {
             {
                 return Ok(None);
             }
+            if let Some(status) = self.cache.lock().expect("not poisoned").get(rela_path) {
+                return Ok(status.clone());
+            }
+            let status = self.compute_status(rela_path)?;
+            self.cache
+                .lock()
+                .expect("not poisoned")
+                .insert(rela_path.to_owned(), status.clone());
+            Ok(status)
+        }
+    }
+
+    impl BuiltinSubmoduleStatus {
+        fn compute_status(&self, rela_path: &BStr) -> Result<Option<crate::submodule::Status>, Error> {
+            use crate::config::cache::util::ApplyLeniency;
+            use crate::config::tree::Diff;
             #[cfg(feature = "parallel")]
             let repo = self.repo.to_thread_local();
             #[cfg(not(feature = "parallel"))]
@@ -252,7 +290,20 @@ mod submodule_status {
                 return Ok(None);
             };
             let (ignore, check_dirty) = match self.mode {
-                Submodule::AsConfigured { check_dirty } => (sm.ignore()?.unwrap_or_default(), check_dirty),
+                Submodule::AsConfigured { check_dirty } => {
+                    let ignore_from_diff_config = repo
+                        .config
+                        .resolved
+                        .string(Diff::IGNORE_SUBMODULES)
+                        .map(|value| Diff::IGNORE_SUBMODULES.try_into_ignore(value))
+                        .transpose()
+                        .with_leniency(repo.config.lenient_config)?;
+                    let ignore = match ignore_from_diff_config {
+                        Some(ignore) => ignore,
+                        None => sm.ignore()?.unwrap_or_default(),
+                    };
+                    (ignore, check_dirty)
+                }
                 Submodule::Given { ignore, check_dirty } => (ignore, check_dirty),
             };
             let status = sm.status(ignore, check_dirty)?;
@@ -329,6 +380,7 @@ pub mod iter {
         /// The index that was used for the operation.
         pub index: crate::worktree::IndexPersistedOrInMemory,
         skip_hash: bool,
+        split_index: crate::index::write::SplitIndex,
         changes: Option<Vec<(usize, iter::ApplyChange)>>,
     }
 
@@ -348,7 +400,7 @@ pub mod iter {
             let changes = self.changes.take()?;
             let mut index = match &self.index {
                 IndexPersistedOrInMemory::Persisted(persisted) => (***persisted).clone(),
-                IndexPersistedOrInMemory::InMemory(index) => index.clone(),
+                IndexPersistedOrInMemory::InMemory(index) => (**index).clone(),
             };
 
             let entries = index.entries_mut();
@@ -366,7 +418,9 @@ pub mod iter {
 
             Some(index.write(crate::index::write::Options {
                 extensions: Default::default(),
+                version: None,
                 skip_hash: self.skip_hash,
+                split_index: self.split_index,
             }))
         }
     }
@@ -639,6 +693,18 @@ pub mod iter {
                 .transpose()
                 .with_lenient_default(self.repo.config.lenient_config)?
                 .unwrap_or_default();
+            let split_index = match self
+                .repo
+                .config
+                .resolved
+                .boolean(crate::config::tree::Core::SPLIT_INDEX)
+                .map(|res| crate::config::tree::Core::SPLIT_INDEX.enrich_error(res))
+                .transpose()
+                .with_lenient_default(self.repo.config.lenient_config)?
+            {
+                Some(false) => crate::index::write::SplitIndex::Squash,
+                Some(true) | None => crate::index::write::SplitIndex::Preserve,
+            };
             let should_interrupt = self.should_interrupt.clone().unwrap_or_default();
             let submodule = BuiltinSubmoduleStatus::new(self.repo.clone().into_sync(), self.submodules)?;
             #[cfg(feature = "parallel")]
@@ -670,6 +736,7 @@ pub mod iter {
                                 index,
                                 changes: None,
                                 skip_hash,
+                                split_index,
                             })
                         }
                     })
@@ -705,6 +772,7 @@ pub mod iter {
                     index,
                     changes: None,
                     skip_hash,
+                    split_index,
                 };
                 let mut iter = super::Iter {
                     items: Vec::new().into_iter(),
@@ -21,6 +21,9 @@ where
 pub enum Submodule {
     /// Use the ['ignore' value](crate::Submodule::ignore) to determine which submodules
     /// participate in the status query, and to which extent.
+    ///
+    /// Just like in `git`, `diff.ignoreSubmodules` takes precedence over the `submodule.<name>.ignore` value
+    /// of `.git/config`, which in turn takes precedence over the one in `.gitmodules`.
     AsConfigured {
         /// If `true`, default `false`, the computation will stop once the first in a ladder operations
         /// ordered from cheap to expensive shows that the submodule is dirty.
@@ -52,6 +52,29 @@ pub mod is_active {
     }
 }
 
+///
+#[allow(clippy::empty_docs)]
+pub mod resolved_url {
+    use crate::bstr::BString;
+
+    /// The error returned by [Submodule::resolved_url()](crate::Submodule::resolved_url()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindHead(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        FindRemote(#[from] crate::remote::find::existing::Error),
+        #[error(transparent)]
+        Url(#[from] gix_submodule::config::url::Error),
+        #[error("The rewritten url {rewritten_url:?} failed to parse")]
+        RewrittenUrlInvalid {
+            rewritten_url: BString,
+            source: gix_url::parse::Error,
+        },
+    }
+}
+
 ///
 #[allow(clippy::empty_docs)]
 pub mod fetch_recurse {
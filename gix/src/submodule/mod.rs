@@ -102,6 +102,41 @@ impl<'repo> Submodule<'repo> {
         self.state.modules.url(self.name())
     }
 
+    /// Return the url from which to clone or update the submodule like [`url()`](Self::url()), but resolve relative urls
+    /// against the url of the superproject's default remote, and apply `url.<base>.insteadOf` rewrites to the result.
+    ///
+    /// The default remote is the one configured for the current branch, or the one found by
+    /// [`Repository::find_default_remote()`](crate::Repository::find_default_remote()). Like in `git`, its url is used
+    /// before rewrites, which are applied only to the resolved url.
+    /// Note that unlike `git`, a relative url is an error if the superproject has no such remote.
+    ///
+    /// The result is the url to pass to [`credential_helpers()`](crate::config::Snapshot::credential_helpers()) to
+    /// obtain credentials for the submodule. For details on how relative urls are resolved, see [gix_submodule::resolve_url()].
+    pub fn resolved_url(&self) -> Result<gix_url::Url, resolved_url::Error> {
+        let repo = self.state.repo;
+        let remote = match repo.head()?.into_remote(crate::remote::Direction::Fetch) {
+            Some(remote) => Some(remote?),
+            None => repo.find_default_remote(crate::remote::Direction::Fetch).transpose()?,
+        };
+        let superproject_url = remote
+            .as_ref()
+            .and_then(|remote| remote.url.as_ref())
+            .map(gix_url::Url::to_bstring);
+        let url = self
+            .state
+            .modules
+            .resolved_url(self.name(), superproject_url.as_ref().map(AsRef::as_ref))?;
+        match repo.config.url_rewrite().longest(&url, crate::remote::Direction::Fetch) {
+            Some(rewritten) => {
+                gix_url::parse(rewritten.as_ref()).map_err(|err| resolved_url::Error::RewrittenUrlInvalid {
+                    rewritten_url: rewritten,
+                    source: err,
+                })
+            }
+            None => Ok(url),
+        }
+    }
+
     /// Return the `update` field from this submodule's configuration, if present, or `None`.
     ///
     /// This method takes into consideration submodule configuration overrides.
@@ -279,7 +314,7 @@ impl<'repo> Submodule<'repo> {
 #[allow(clippy::empty_docs)]
 #[cfg(feature = "status")]
 pub mod status {
-    use super::{head_id, index_id, open, Status};
+    use super::{head_id, index_id, open, Dirtiness, Status};
     use crate::Submodule;
     use gix_submodule::config;
 
@@ -311,7 +346,8 @@ pub mod status {
         /// Use `ignore` to control the portion of the submodule status to ignore. It can be obtained from
         /// submodule configuration using the [`ignore()`](Submodule::ignore()) method.
         /// If `check_dirty` is `true`, the computation will stop once the first in a ladder operations
-        /// ordered from cheap to expensive shows that the submodule is dirty.
+        /// ordered from cheap to expensive shows that the submodule is dirty, and the status inside the submodule
+        /// stops as soon as the [kind of dirtiness](Status::dirtiness()) is known.
         /// Thus, submodules that are clean will still impose the complete set of computation, as given.
         #[doc(alias = "submodule_status", alias = "git2")]
         pub fn status(
@@ -393,8 +429,17 @@ pub mod status {
                 })
                 .into_index_worktree_iter(Vec::new())?;
             let mut changes = Vec::new();
+            let mut dirtiness = Dirtiness::default();
             for change in statuses {
-                changes.push(change?);
+                let change = change?;
+                dirtiness.record(&change);
+                changes.push(change);
+                if check_dirty
+                    && dirtiness.modified_content
+                    && (dirtiness.untracked_content || ignore == config::Ignore::Untracked)
+                {
+                    break;
+                }
             }
             status.changes = Some(changes);
             Ok(status)
@@ -418,6 +463,44 @@ pub mod status {
                 self.checked_out_head_id != self.index_id || self.changes.as_ref().map_or(false, |c| !c.is_empty());
             Some(is_dirty)
         }
+
+        /// Return the ways in which the submodule is dirty, or `None` under the same conditions as
+        /// [`is_dirty()`](Self::is_dirty()).
+        ///
+        /// Note that only what was computed can be reported, so portions of the status that were ignored or
+        /// skipped early are never considered dirty.
+        pub fn dirtiness(&self) -> Option<Dirtiness> {
+            if !self.state.worktree_checkout || !self.state.repository_exists {
+                return None;
+            }
+            let mut dirtiness = Dirtiness {
+                new_commits: self.checked_out_head_id != self.index_id,
+                ..Default::default()
+            };
+            for change in self.changes.iter().flatten() {
+                dirtiness.record(change);
+            }
+            Some(dirtiness)
+        }
+    }
+
+    impl Dirtiness {
+        /// Return `true` if the submodule is dirty in any way.
+        pub fn is_dirty(&self) -> bool {
+            self.new_commits || self.modified_content || self.untracked_content
+        }
+
+        fn record(&mut self, change: &crate::status::index_worktree::iter::Item) {
+            use crate::status::index_worktree::iter::Item;
+            match change {
+                Item::Modification { .. } | Item::Rewrite { .. } => self.modified_content = true,
+                Item::DirectoryContents { entry, .. } => {
+                    if entry.status == gix_dir::entry::Status::Untracked {
+                        self.untracked_content = true;
+                    }
+                }
+            }
+        }
     }
 
     pub(super) mod types {
@@ -447,10 +530,23 @@ pub mod status {
             /// available or repository was available.
             pub changes: Option<Vec<crate::status::index_worktree::iter::Item>>,
         }
+
+        /// The ways in which a submodule can be dirty, named like `git status` describes them.
+        ///
+        /// It's produced by [Status::dirtiness()](crate::submodule::Status::dirtiness()).
+        #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        pub struct Dirtiness {
+            /// The checked-out commit differs from the one recorded in the superproject's index, shown as `new commits`.
+            pub new_commits: bool,
+            /// Tracked files were changed, or a nested submodule is dirty, shown as `modified content`.
+            pub modified_content: bool,
+            /// There are untracked files, shown as `untracked content`.
+            pub untracked_content: bool,
+        }
     }
 }
 #[cfg(feature = "status")]
-pub use status::types::Status;
+pub use status::types::{Dirtiness, Status};
 
 /// A summary of the state of all parts forming a submodule, which allows to answer various questions about it.
 ///
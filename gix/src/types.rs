@@ -163,6 +163,8 @@ pub struct Repository {
     #[cfg(feature = "attributes")]
     pub(crate) modules: crate::submodule::ModulesFileStorage,
     pub(crate) shallow_commits: crate::shallow::CommitsStorage,
+    #[cfg(feature = "blocking-network-server")]
+    pub(crate) pack_bitmap: crate::upload_pack::PackBitmapStorage,
 }
 
 /// An instance with access to everything a git repository entails, best imagined as container implementing `Sync + Send` for _most_
@@ -193,6 +195,8 @@ pub struct ThreadSafeRepository {
     #[cfg(feature = "attributes")]
     pub(crate) modules: crate::submodule::ModulesFileStorage,
     pub(crate) shallow_commits: crate::shallow::CommitsStorage,
+    #[cfg(feature = "blocking-network-server")]
+    pub(crate) pack_bitmap: crate::upload_pack::PackBitmapStorage,
 }
 
 /// A remote which represents a way to interact with hosts for remote clones of the parent repository.
@@ -215,10 +219,11 @@ pub struct Remote<'repo> {
     pub(crate) push_specs: Vec<gix_refspec::RefSpec>,
     /// Tell us what to do with tags when fetched.
     pub(crate) fetch_tags: remote::fetch::Tags,
-    // /// Delete local tracking branches that don't exist on the remote anymore.
-    // pub(crate) prune: bool,
-    // /// Delete tags that don't exist on the remote anymore, equivalent to pruning the refspec `refs/tags/*:refs/tags/*`.
-    // pub(crate) prune_tags: bool,
+    /// Delete local tracking branches that don't exist on the remote anymore, or `None` to fall back to `fetch.prune`.
+    pub(crate) prune: Option<bool>,
+    /// Delete tags that don't exist on the remote anymore, equivalent to pruning the refspec `refs/tags/*:refs/tags/*`,
+    /// or `None` to fall back to `fetch.pruneTags`.
+    pub(crate) prune_tags: Option<bool>,
     pub(crate) repo: &'repo Repository,
 }
 
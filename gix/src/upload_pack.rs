@@ -0,0 +1,123 @@
+//! Types to serve `git fetch` and `git clone` with [`Repository::upload_pack()`](crate::Repository::upload_pack()).
+use gix_hash::ObjectId;
+
+pub(crate) type PackBitmapStorage = gix_features::threading::OwnShared<gix_fs::SharedFileSnapshotMut<PackBitmap>>;
+
+/// The bitmap used to quickly find the objects to send, along with the index of the pack it belongs to.
+pub(crate) struct PackBitmap {
+    pub index: gix_pack::index::File,
+    pub bitmap: gix_pack::bitmap::File,
+}
+
+impl std::fmt::Debug for PackBitmap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PackBitmap")
+            .field("path", &self.bitmap.path())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Options for use in [`Repository::upload_pack()`](crate::Repository::upload_pack()).
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    /// The protocol version the client asked for, with V2 being the default.
+    ///
+    /// V1 is served like V0.
+    pub version: gix_protocol::transport::Protocol,
+    /// If `true`, the advertisement isn't sent as it was obtained with
+    /// [`Repository::upload_pack_advertisement()`](crate::Repository::upload_pack_advertisement()) in a previous request already,
+    /// and only a single request is served.
+    ///
+    /// This is the case for stateless transports like HTTP.
+    pub stateless_rpc: bool,
+}
+
+/// The outcome of [`Repository::upload_pack()`](crate::Repository::upload_pack()).
+#[derive(Default, Debug, Clone)]
+pub struct Outcome {
+    /// The amount of `ls-refs` commands that were answered in protocol V2.
+    pub ls_refs: usize,
+    /// All fetch requests that were answered, along with their result.
+    pub fetches: Vec<Fetch>,
+}
+
+/// A single fetch request served by [`Repository::upload_pack()`](crate::Repository::upload_pack()).
+#[derive(Debug, Clone)]
+pub struct Fetch {
+    /// The request as sent by the client.
+    ///
+    /// In protocol V0 and V1, it contains all `have` lines of all negotiation rounds.
+    pub request: gix_protocol::upload_pack::FetchRequest,
+    /// The commits the client sent as `have` which we have as well.
+    pub common: Vec<ObjectId>,
+    /// The amount of objects in the pack that was sent, or `None` if only acknowledgements were sent in this round of negotiation.
+    pub pack_objects: Option<usize>,
+}
+
+/// The error returned by [`Repository::upload_pack()`](crate::Repository::upload_pack()).
+///
+/// Errors caused by the request of the client are also sent to the client as `ERR` packet, if possible.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Advertisement(#[from] advertisement::Error),
+    #[error("Could not read the request of the client")]
+    DecodeRequest(#[from] gix_protocol::upload_pack::decode::Error),
+    #[error("Could not write the response to the client")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    ConfigBoolean(#[from] crate::config::boolean::Error),
+    #[error("upload-pack: not our ref {id}")]
+    NotOurRef { id: ObjectId },
+    #[error("unknown ref {name}")]
+    UnknownWantedRef { name: crate::bstr::BString },
+    #[error("upload-pack: shallow clones and fetches are not supported")]
+    ShallowUnsupported,
+    #[error("upload-pack: filter '{spec}' is not supported")]
+    FilterUnsupported { spec: crate::bstr::BString },
+    #[error(transparent)]
+    InvalidFilter(#[from] crate::remote::fetch::filter::parse::Error),
+    #[error(transparent)]
+    InitFilter(#[from] crate::remote::fetch::filter::init::Error),
+    #[error("Could not traverse the commits to send")]
+    Walk(#[from] crate::revision::walk::Error),
+    #[error("Could not traverse the commits to send")]
+    WalkIter(#[from] gix_traverse::commit::simple::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    DecodeObject(#[from] gix_object::decode::Error),
+    #[error("Could not find the objects to send using the pack bitmap")]
+    Bitmap(#[from] gix_pack::bitmap::reachable::Error),
+    #[error("Could not open the object database to generate a pack")]
+    OpenStore(#[source] std::io::Error),
+    #[error("Could not generate the pack")]
+    GeneratePack(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod advertisement {
+    /// The error returned by [`Repository::upload_pack_advertisement()`](crate::Repository::upload_pack_advertisement()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Iter(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        IterInit(#[from] crate::reference::iter::init::Error),
+        #[error(transparent)]
+        IterItem(Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        Find(#[from] crate::reference::find::Error),
+        #[error(transparent)]
+        Follow(#[from] gix_ref::file::find::existing::Error),
+        #[error(transparent)]
+        PeelTag(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        ConfigBoolean(#[from] crate::config::boolean::Error),
+        #[error("Could not write the advertisement")]
+        Io(#[from] std::io::Error),
+    }
+}
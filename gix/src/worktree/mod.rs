@@ -28,7 +28,7 @@ pub enum IndexPersistedOrInMemory {
     /// A temporary index as created from the `HEAD^{tree}`, with the file path set to the place where it would be stored naturally.
     ///
     /// Note that unless saved explicitly, it will not persist.
-    InMemory(gix_index::File),
+    InMemory(Box<gix_index::File>),
 }
 
 #[cfg(feature = "index")]
@@ -41,7 +41,7 @@ impl From<Index> for IndexPersistedOrInMemory {
 #[cfg(feature = "index")]
 impl From<gix_index::File> for IndexPersistedOrInMemory {
     fn from(value: gix_index::File) -> Self {
-        IndexPersistedOrInMemory::InMemory(value)
+        IndexPersistedOrInMemory::InMemory(Box::new(value))
     }
 }
 
@@ -111,6 +111,97 @@ pub(crate) fn id(git_dir: &std::path::Path, has_common_dir: bool) -> Option<&BSt
 #[allow(clippy::empty_docs)]
 pub mod proxy;
 
+///
+#[allow(clippy::empty_docs)]
+#[cfg(feature = "worktree-mutation")]
+pub mod add {
+    use std::path::PathBuf;
+
+    use crate::bstr::BString;
+
+    /// Options for use in [`Repository::worktree_add()`](crate::Repository::worktree_add()).
+    #[derive(Default, Debug, Clone)]
+    pub struct Options {
+        /// If `true`, a worktree that is still registered at the destination but whose checkout is missing is replaced,
+        /// like `git worktree add --force` does.
+        ///
+        /// Locked worktrees are never replaced, they have to be unlocked first.
+        pub force: bool,
+        /// If set, the new worktree is locked with the given reason, which may be empty, so it can't be pruned.
+        pub lock_reason: Option<BString>,
+    }
+
+    /// The error returned by [`Repository::worktree_add()`](crate::Repository::worktree_add()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("'{}' already exists", path.display())]
+        DestinationExists { path: PathBuf },
+        #[error("'{}' is a missing but already registered worktree, use force to override, or prune or remove it to clear", path.display())]
+        Registered { path: PathBuf },
+        #[error("'{}' is a missing but locked worktree, unlock and prune or remove it to clear", path.display())]
+        RegisteredLocked { path: PathBuf },
+        #[error("Could not obtain the current working directory to make the worktree path absolute")]
+        CurrentDir(#[source] std::io::Error),
+        #[error("Could not write '{}'", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        PeelToCommit(#[from] crate::object::peel::to_kind::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        Open(#[from] crate::worktree::proxy::into_repo::Error),
+        #[error(transparent)]
+        IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+        #[error("Failed to reopen object database as Arc (only if thread-safety wasn't compiled in)")]
+        OpenArcOdb(#[source] std::io::Error),
+        #[error(transparent)]
+        CheckoutOptions(#[from] crate::config::checkout_options::Error),
+        #[error(transparent)]
+        Checkout(#[from] gix_worktree_state::checkout::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+    }
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod prune {
+    use crate::bstr::BString;
+
+    /// Options for use in [`Repository::prune_worktrees()`](crate::Repository::prune_worktrees()).
+    #[derive(Default, Debug, Copy, Clone)]
+    pub struct Options {
+        /// If `true`, nothing is deleted, and the outcome only tells what would be pruned.
+        pub dry_run: bool,
+    }
+
+    /// The reason for the administrative files of a linked worktree to be pruned.
+    #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+    pub enum Reason {
+        /// The entry in the `worktrees` directory isn't a directory.
+        NotADirectory,
+        /// The `gitdir` file pointing to the worktree doesn't exist.
+        MissingGitdirFile,
+        /// The `gitdir` file pointing to the worktree couldn't be read or is empty.
+        InvalidGitdirFile,
+        /// The `gitdir` file points to a location that doesn't exist.
+        MissingWorktree,
+    }
+
+    /// The administrative files of a linked worktree that were pruned by
+    /// [`Repository::prune_worktrees()`](crate::Repository::prune_worktrees()).
+    #[derive(Debug, Clone, Eq, PartialEq)]
+    pub struct Pruned {
+        /// The name of the worktree, which is the name of its directory within the `worktrees` directory.
+        pub id: BString,
+        /// The reason for pruning it.
+        pub reason: Reason,
+    }
+}
+
 ///
 #[cfg(feature = "index")]
 pub mod open_index {
@@ -123,6 +214,8 @@ pub mod open_index {
         #[error(transparent)]
         ConfigSkipHash(#[from] crate::config::boolean::Error),
         #[error(transparent)]
+        ConfigIndexVersion(crate::config::key::GenericErrorWithValue),
+        #[error(transparent)]
         IndexFile(#[from] gix_index::file::init::Error),
         #[error(transparent)]
         IndexCorrupt(#[from] gix_index::file::verify::Error),
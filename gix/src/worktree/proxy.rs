@@ -24,6 +24,76 @@ pub mod into_repo {
     }
 }
 
+///
+#[allow(clippy::empty_docs)]
+pub mod lock {
+    /// The error returned by [`Proxy::lock()`][super::Proxy::lock()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The worktree is already locked{}", if reason.is_empty() { String::new() } else { format!(", reason: {reason}") })]
+        AlreadyLocked { reason: crate::bstr::BString },
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod unlock {
+    /// The error returned by [`Proxy::unlock()`][super::Proxy::unlock()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The worktree is not locked")]
+        NotLocked,
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+
+///
+#[allow(clippy::empty_docs)]
+#[cfg(feature = "status")]
+pub mod remove {
+    use std::path::PathBuf;
+
+    /// Options for use in [`Proxy::remove()`][super::Proxy::remove()].
+    #[derive(Default, Debug, Copy, Clone)]
+    pub struct Options {
+        /// If `true`, the worktree is removed even if it contains modified or untracked files, or staged changes.
+        pub force: bool,
+    }
+
+    /// The error returned by [`Proxy::remove()`][super::Proxy::remove()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The worktree is locked and has to be unlocked before it can be removed")]
+        Locked,
+        #[error("'{}' contains modified or untracked files or staged changes, use force to delete it", base.display())]
+        Dirty { base: PathBuf },
+        #[error(transparent)]
+        Open(#[from] super::into_repo::Error),
+        #[error(transparent)]
+        StatusPlatform(#[from] crate::status::Error),
+        #[error(transparent)]
+        IntoIter(#[from] crate::status::index_worktree::Error),
+        #[error(transparent)]
+        Status(#[from] crate::status::index_worktree::iter::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        FindHead(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        HeadTreeId(#[from] crate::reference::head_tree_id::Error),
+        #[error(transparent)]
+        IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+        #[error("Could not delete '{}'", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+    }
+}
+
 impl<'repo> Proxy<'repo> {
     pub(crate) fn new(parent: &'repo Repository, git_dir: impl Into<PathBuf>) -> Self {
         Proxy {
@@ -44,6 +114,15 @@ impl<'repo> Proxy<'repo> {
                 format!("Required file '{}' does not exist", git_dir.display()),
             )
         })??;
+        // Newer versions of `git` may write paths relative to the private git dir.
+        let base_dot_git = if base_dot_git.is_relative() {
+            let path = self.git_dir.join(base_dot_git);
+            gix_path::normalize(path.as_path().into(), &self.git_dir)
+                .map(std::borrow::Cow::into_owned)
+                .unwrap_or(path)
+        } else {
+            base_dot_git
+        };
 
         Ok(gix_discover::path::without_dot_git_dir(base_dot_git))
     }
@@ -82,7 +161,7 @@ impl<'repo> Proxy<'repo> {
     /// a lot of information if work tree access is avoided.
     pub fn into_repo_with_possibly_inaccessible_worktree(self) -> Result<Repository, crate::open::Error> {
         let base = self.base().ok();
-        let repo = ThreadSafeRepository::open_from_paths(self.git_dir, base, self.parent.options.clone())?;
+        let repo = ThreadSafeRepository::open_from_paths(self.git_dir, base, None, self.parent.options.clone())?;
         Ok(repo.into())
     }
 
@@ -95,7 +174,96 @@ impl<'repo> Proxy<'repo> {
         if !base.is_dir() {
             return Err(into_repo::Error::MissingWorktree { base });
         }
-        let repo = ThreadSafeRepository::open_from_paths(self.git_dir, base.into(), self.parent.options.clone())?;
+        let repo = ThreadSafeRepository::open_from_paths(self.git_dir, base.into(), None, self.parent.options.clone())?;
         Ok(repo.into())
     }
+
+    /// Lock this worktree so it can't be pruned, like `git worktree lock` does, with `reason` being stored along with the lock.
+    ///
+    /// Fail if it's locked already.
+    pub fn lock(&self, reason: &BStr) -> Result<(), lock::Error> {
+        if let Some(reason) = self.lock_reason() {
+            return Err(lock::Error::AlreadyLocked { reason });
+        }
+        let mut contents = BString::from(reason);
+        if !contents.is_empty() && !contents.ends_with(b"\n") {
+            contents.push(b'\n');
+        }
+        std::fs::write(self.git_dir.join("locked"), contents)?;
+        Ok(())
+    }
+
+    /// Unlock this worktree, like `git worktree unlock` does, or fail if it isn't locked.
+    pub fn unlock(&self) -> Result<(), unlock::Error> {
+        match std::fs::remove_file(self.git_dir.join("locked")) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Err(unlock::Error::NotLocked),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Delete the checkout of this worktree along with its private git dir, like `git worktree remove` does.
+    ///
+    /// Fail if the worktree is locked, or if it contains modified or untracked files or staged changes unless
+    /// [`remove::Options::force`] is set.
+    /// If the checkout doesn't exist anymore, only the private git dir is deleted.
+    #[cfg(feature = "status")]
+    pub fn remove(self, options: remove::Options) -> Result<(), remove::Error> {
+        if self.is_locked() {
+            return Err(remove::Error::Locked);
+        }
+        let parent = self.parent;
+        let git_dir = self.git_dir.clone();
+        let base = self.base().ok().filter(|base| base.is_dir());
+        if let Some(base) = base {
+            if !options.force {
+                let repo = self.into_repo()?;
+                let is_dirty = repo
+                    .status(gix_features::progress::Discard)?
+                    .index_worktree_rewrites(None)
+                    .into_index_worktree_iter(Vec::new())?
+                    .next()
+                    .transpose()?
+                    .is_some()
+                    || has_staged_changes(&repo)?;
+                if is_dirty {
+                    return Err(remove::Error::Dirty { base });
+                }
+            }
+            std::fs::remove_dir_all(&base).map_err(|err| remove::Error::Io {
+                source: err,
+                path: base,
+            })?;
+        }
+        std::fs::remove_dir_all(&git_dir).map_err(|err| remove::Error::Io {
+            source: err,
+            path: git_dir,
+        })?;
+        // Like `git`, leave no empty `worktrees` directory behind.
+        std::fs::remove_dir(parent.common_dir().join("worktrees")).ok();
+        Ok(())
+    }
+}
+
+/// Return `true` if the index of `repo` differs from the tree at `HEAD`, which is the part of the status that
+/// the comparison of the index with the worktree doesn't cover.
+#[cfg(feature = "status")]
+fn has_staged_changes(repo: &Repository) -> Result<bool, remove::Error> {
+    let index = repo.index_or_empty()?;
+    if repo.head()?.is_unborn() {
+        return Ok(!index.entries().is_empty());
+    }
+    let head_index = repo.index_from_tree(&repo.head_tree_id()?)?;
+    Ok(index.entries().len() != head_index.entries().len()
+        || index
+            .entries()
+            .iter()
+            .zip(head_index.entries())
+            .any(|(entry, head_entry)| {
+                entry.path(&index) != head_entry.path(&head_index)
+                    || entry.id != head_entry.id
+                    || entry.mode != head_entry.mode
+                    || entry.stage_raw() != head_entry.stage_raw()
+                    || entry.flags.contains(gix_index::entry::Flags::INTENT_TO_ADD)
+            }))
 }
@@ -0,0 +1,78 @@
+use gix::config::tree::{Blame, Key};
+
+use crate::util::{named_repo, repo_opts, restricted};
+
+#[test]
+fn configured_ignore_revs_are_peeled_and_honored() -> crate::Result {
+    let repo = named_repo("make_blame_repo.sh")?;
+    let head = repo.head_id()?.detach();
+    let initial = repo.head_commit()?.parent_ids().next().expect("one parent").detach();
+    assert_eq!(
+        repo.blame_ignore_revs()?,
+        std::iter::once(head).collect(),
+        "the tag is peeled, and everything that isn't a commit is skipped"
+    );
+
+    let outcome = repo.blame_file("file.rs".into(), head, Default::default())?;
+    assert_eq!(
+        outcome.blob,
+        std::fs::read(repo.work_dir().expect("non-bare").join("file.rs"))?
+    );
+    assert_eq!(
+        outcome
+            .entries
+            .iter()
+            .map(|e| (e.commit_id, e.start_in_source_file, e.len.get()))
+            .collect::<Vec<_>>(),
+        [(initial, 0, 1), (initial, 0, 3)],
+        "the reformatting commit is ignored, and its opening brace is matched with the line it was split from"
+    );
+    Ok(())
+}
+
+#[test]
+fn empty_values_discard_previously_configured_files() -> crate::Result {
+    let repo = repo_opts(
+        "make_blame_repo.sh",
+        restricted().config_overrides([Blame::IGNORE_REVS_FILE.validated_assignment_fmt(&"")?]),
+    )?
+    .to_thread_local();
+    assert!(repo.blame_ignore_revs()?.is_empty());
+
+    let head = repo.head_id()?.detach();
+    let outcome = repo.blame_file("file.rs".into(), head, Default::default())?;
+    assert_eq!(
+        outcome
+            .entries
+            .iter()
+            .map(|e| (e.commit_id == head, e.len.get()))
+            .collect::<Vec<_>>(),
+        [(true, 3), (false, 1)],
+        "only the closing brace remains with the initial commit"
+    );
+    Ok(())
+}
+
+#[test]
+fn invalid_ignore_revs_files_are_an_error() -> crate::Result {
+    let repo = repo_opts(
+        "make_blame_repo.sh",
+        restricted().config_overrides([Blame::IGNORE_REVS_FILE.validated_assignment_fmt(&"invalid-ignore-revs")?]),
+    )?
+    .to_thread_local();
+    assert!(matches!(
+        repo.blame_ignore_revs().unwrap_err(),
+        gix::repository::blame::blame_ignore_revs::Error::Parse { .. }
+    ));
+
+    let repo = repo_opts(
+        "make_blame_repo.sh",
+        restricted().config_overrides([Blame::IGNORE_REVS_FILE.validated_assignment_fmt(&"does-not-exist")?]),
+    )?
+    .to_thread_local();
+    assert!(matches!(
+        repo.blame_ignore_revs().unwrap_err(),
+        gix::repository::blame::blame_ignore_revs::Error::Io { .. }
+    ));
+    Ok(())
+}
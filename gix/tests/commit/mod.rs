@@ -86,3 +86,283 @@ mod describe {
         Ok(())
     }
 }
+
+mod mailinfo {
+    use std::path::Path;
+
+    use gix::{
+        bstr::{BString, ByteSlice},
+        commit::mailinfo,
+    };
+
+    /// Read the output of `git mailinfo` for the email `name`, as author name, email, subject, message and patch.
+    fn git_mailinfo(dir: &Path, name: &str) -> crate::Result<(BString, BString, BString, BString, BString)> {
+        let info = std::fs::read(dir.join("mailinfo").join(format!("{name}.info")))?;
+        let field = |field: &str| -> BString {
+            info.lines()
+                .find_map(|line| line.strip_prefix(format!("{field}: ").as_bytes()))
+                .unwrap_or_default()
+                .into()
+        };
+        let subject = field("Subject");
+        let msg = std::fs::read(dir.join("mailinfo").join(format!("{name}.msg")))?;
+        let message = if msg.is_empty() {
+            format!("{subject}\n")
+        } else {
+            format!("{subject}\n\n{}", msg.as_bstr())
+        };
+        let patch = std::fs::read(dir.join("mailinfo").join(format!("{name}.patch")))?;
+        Ok((field("Author"), field("Email"), subject, message.into(), patch.into()))
+    }
+
+    #[test]
+    fn emails_of_git_format_patch_are_parsed_like_git_mailinfo_does() -> crate::Result {
+        let dir = gix_testtools::scripted_fixture_read_only("make_format_patch_repo.sh")?;
+        let repo = gix::open_opts(dir.join("repo"), crate::restricted())?;
+        let mut ids = repo
+            .head_id()?
+            .ancestors()
+            .all()?
+            .map(|info| info.map(|info| info.id))
+            .collect::<Result<Vec<_>, _>>()?;
+        ids.reverse();
+
+        let mut patches: Vec<_> = std::fs::read_dir(dir.join("patches"))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<_, _>>()?;
+        patches.sort();
+        assert_eq!(patches.len(), ids.len(), "one patch per commit");
+
+        let mbox = std::fs::read(dir.join("mbox"))?;
+        let mails: Vec<_> = mailinfo::split(&mbox).collect();
+        assert_eq!(mails.len(), patches.len(), "the mailbox contains all patches");
+
+        for ((path, id), mail_in_mbox) in patches.iter().zip(ids).zip(mails) {
+            let name = path.file_stem().expect("file").to_str().expect("valid UTF-8");
+            let mail = std::fs::read(path)?;
+            assert_eq!(
+                mailinfo::split(&mail).count(),
+                1,
+                "a single patch is a mailbox with one email"
+            );
+            let actual = mailinfo::parse(&mail, Default::default())?;
+            assert_eq!(
+                mailinfo::parse(mail_in_mbox, Default::default())?,
+                actual,
+                "{name}: it's the same whether split from a mailbox or not"
+            );
+
+            let (author, email, subject, message, patch) = git_mailinfo(&dir, name)?;
+            assert_eq!(actual.author.name, author, "{name}");
+            assert_eq!(actual.author.email, email, "{name}");
+            assert_eq!(actual.subject, subject, "{name}");
+            assert_eq!(actual.message, message, "{name}");
+            assert_eq!(actual.patch, patch, "{name}");
+
+            let commit = repo.find_object(id)?.into_commit();
+            assert_eq!(
+                actual.author,
+                commit.author()?.to_owned(),
+                "{name}: the author round-trips with its date"
+            );
+            #[cfg(feature = "blob-diff")]
+            {
+                let ours = commit.format_patch(&Default::default())?;
+                let from_ours = mailinfo::parse(&ours, Default::default())?;
+                assert_eq!(from_ours.author, actual.author, "{name}");
+                assert_eq!(
+                    from_ours.message, actual.message,
+                    "{name}: our own patches are parsed the same"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn in_body_headers_override_and_scissors_discard() -> crate::Result {
+        let dir = gix_testtools::scripted_fixture_read_only("make_format_patch_repo.sh")?;
+        let mail = std::fs::read(dir.join("mail-with-inbody-headers"))?;
+        let actual = mailinfo::parse(
+            &mail,
+            mailinfo::Options {
+                scissors: true,
+                ..Default::default()
+            },
+        )?;
+        let (author, email, subject, message, patch) = git_mailinfo(&dir, "inbody")?;
+        assert_eq!(actual.author.name, author);
+        assert_eq!(actual.author.email, email);
+        assert_eq!(actual.author.time, gix_date::parse("1112947200 +0200", None)?);
+        assert_eq!(actual.subject, subject);
+        assert_eq!(actual.message, message);
+        assert_eq!(actual.patch, patch);
+
+        let actual = mailinfo::parse(&mail, Default::default())?;
+        assert_eq!(
+            actual.author.name, "Sender",
+            "without scissors, the in-body headers are part of the message"
+        );
+        assert_eq!(actual.subject, "the subject is overridden by café");
+        assert!(actual.message.contains_str("This is discarded."));
+
+        let mail_with_brackets = b"From: A U Thor <author@example.com>\nSubject: [topic] [PATCH 1/2] x\n\n";
+        let actual = mailinfo::parse(
+            mail_with_brackets,
+            mailinfo::Options {
+                keep_non_patch_brackets: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(actual.subject, "[topic] x", "only brackets with PATCH are removed");
+        assert_eq!(actual.message, "[topic] x\n");
+        assert_eq!(actual.patch, "", "there is no patch");
+
+        let actual = mailinfo::parse(
+            &mail,
+            mailinfo::Options {
+                keep_subject: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            actual.subject,
+            "Re: [RFC PATCH v2 3/7] the subject is overridden by café"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn emails_without_address_are_rejected() {
+        let err = mailinfo::parse(b"From: Nobody\nSubject: x\n\nbody\n", Default::default()).unwrap_err();
+        assert!(matches!(err, mailinfo::Error::MissingEmail));
+    }
+}
+
+#[cfg(feature = "blob-diff")]
+mod am {
+    use gix::{bstr::ByteSlice, commit::am};
+    use gix_testtools::tempfile;
+
+    /// Return the fixture directory, the repository `name` the patches were created from along with the ids of all its commits
+    /// from the root, and a new empty repository to apply them to.
+    fn repos(
+        name: &str,
+    ) -> crate::Result<(
+        std::path::PathBuf,
+        gix::Repository,
+        Vec<gix::ObjectId>,
+        gix::Repository,
+        tempfile::TempDir,
+    )> {
+        let dir = gix_testtools::scripted_fixture_read_only("make_format_patch_repo.sh")?;
+        let source = gix::open_opts(dir.join(name), crate::restricted())?;
+        let mut ids = source
+            .head_id()?
+            .ancestors()
+            .all()?
+            .map(|info| info.map(|info| info.id))
+            .collect::<Result<Vec<_>, _>>()?;
+        ids.reverse();
+
+        let tmp = tempfile::tempdir()?;
+        let target = gix::ThreadSafeRepository::init_opts(
+            &tmp,
+            gix::create::Kind::WithWorktree,
+            Default::default(),
+            crate::restricted(),
+        )?
+        .to_thread_local();
+        Ok((dir, source, ids, target, tmp))
+    }
+
+    fn assert_same_commits(
+        source: &gix::Repository,
+        expected: &[gix::ObjectId],
+        target: &gix::Repository,
+        actual: &[gix::ObjectId],
+    ) -> crate::Result {
+        assert_eq!(actual.len(), expected.len(), "one commit per patch");
+        for (expected, actual) in expected.iter().zip(actual) {
+            let expected = source.find_object(*expected)?.into_commit();
+            let actual = target.find_object(*actual)?.into_commit();
+            assert_eq!(actual.tree_id()?, expected.tree_id()?, "the trees are identical");
+            assert_eq!(actual.author()?, expected.author()?, "the authorship is preserved");
+            assert_eq!(
+                actual.committer()?.name,
+                "gitoxide",
+                "the committer is the current user"
+            );
+        }
+        assert_eq!(
+            target.head_id()?,
+            *actual.last().expect("at least one commit"),
+            "HEAD points to the last commit"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn patches_of_git_format_patch_are_applied_as_commits() -> crate::Result {
+        let (dir, source, ids, target, _tmp) = repos("repo")?;
+        let applied = target.apply_mailbox(std::fs::File::open(dir.join("mbox"))?, Default::default())?;
+        assert_same_commits(&source, &ids, &target, &applied)
+    }
+
+    #[test]
+    fn binary_deltas_of_git_format_patch_are_applied() -> crate::Result {
+        let (dir, source, ids, target, _tmp) = repos("binary-delta")?;
+        let mbox = std::fs::read(dir.join("binary-delta.mbox"))?;
+        assert!(
+            mbox.find(b"\ndelta ").is_some(),
+            "git uses a delta for the small change"
+        );
+        let applied = target.apply_mailbox(mbox.as_slice(), Default::default())?;
+        assert_same_commits(&source, &ids, &target, &applied)
+    }
+
+    #[test]
+    fn our_own_patches_round_trip() -> crate::Result {
+        let (_dir, source, ids, target, _tmp) = repos("repo")?;
+        let mut mbox = Vec::new();
+        for id in &ids {
+            mbox.extend_from_slice(
+                &source
+                    .find_object(*id)?
+                    .into_commit()
+                    .format_patch(&Default::default())?,
+            );
+        }
+        let applied = target.apply_mailbox(mbox.as_slice(), Default::default())?;
+        assert_same_commits(&source, &ids, &target, &applied)
+    }
+
+    #[test]
+    fn the_first_failing_patch_and_hunk_is_reported() -> crate::Result {
+        let (dir, _source, _ids, target, _tmp) = repos("repo")?;
+        let mut patches: Vec<_> = std::fs::read_dir(dir.join("patches"))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<_, _>>()?;
+        patches.sort();
+        let applied = target.apply_mailbox(std::fs::File::open(&patches[0])?, Default::default())?;
+        assert_eq!(applied.len(), 1);
+
+        let mut mbox = std::fs::read(&patches[1])?;
+        mbox.extend(std::fs::read(&patches[1])?);
+        let err = target.apply_mailbox(mbox.as_slice(), Default::default()).unwrap_err();
+        assert!(
+            matches!(
+                &err,
+                am::Error::Apply { patch: 2, source: am::apply::Error::Hunk { path, hunk: 1 }, .. } if path == "a"
+            ),
+            "{err:?}"
+        );
+        let head = target.head_commit()?;
+        assert_eq!(
+            head.parent_ids().count(),
+            1,
+            "the commit of the first patch remains, just like with `git am`"
+        );
+        Ok(())
+    }
+}
@@ -203,6 +203,40 @@ mod push {
     }
 }
 
+#[cfg(feature = "blocking-network-server")]
+mod receive {
+    use crate::config::tree::bcow;
+    use gix::config::tree::Receive;
+    use gix::receive_pack::Deny;
+
+    #[test]
+    fn deny() -> crate::Result {
+        for (actual, expected) in [
+            ("ignore", Deny::Ignore),
+            ("warn", Deny::Warn),
+            ("refuse", Deny::Refuse),
+            ("updateInstead", Deny::UpdateInstead),
+            ("true", Deny::Refuse),
+            ("yes", Deny::Refuse),
+            ("false", Deny::Ignore),
+            ("off", Deny::Ignore),
+        ] {
+            assert_eq!(Receive::DENY_CURRENT_BRANCH.try_into_deny(bcow(actual))?, expected);
+            assert_eq!(Receive::DENY_DELETE_CURRENT.try_into_deny(bcow(actual))?, expected);
+        }
+
+        assert_eq!(
+            Receive::DENY_CURRENT_BRANCH
+                .try_into_deny(bcow("Refuse"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"receive.denyCurrentBranch=Refuse\" was invalid",
+            "case-sensitive comparisons"
+        );
+        Ok(())
+    }
+}
+
 mod fetch {
 
     #[test]
@@ -269,6 +303,41 @@ mod fetch {
     }
 }
 
+#[cfg(feature = "merge")]
+mod merge {
+    use gix::config::tree::{Key, Merge};
+    use gix::merge::tree::DirectoryRenames;
+
+    #[test]
+    fn directory_renames() -> crate::Result {
+        assert_eq!(
+            Merge::DIRECTORY_RENAMES.try_into_directory_renames(Ok(true))?,
+            DirectoryRenames::Apply
+        );
+        assert!(Merge::DIRECTORY_RENAMES.validate("true".into()).is_ok());
+        assert_eq!(
+            Merge::DIRECTORY_RENAMES.try_into_directory_renames(Ok(false))?,
+            DirectoryRenames::Disabled
+        );
+        assert!(Merge::DIRECTORY_RENAMES.validate("0".into()).is_ok());
+        assert_eq!(
+            Merge::DIRECTORY_RENAMES
+                .try_into_directory_renames(Err(gix_config::value::Error::new("err", "conflict")))?,
+            DirectoryRenames::Conflict
+        );
+        assert!(Merge::DIRECTORY_RENAMES.validate("conflict".into()).is_ok());
+
+        assert_eq!(
+            Merge::DIRECTORY_RENAMES
+                .try_into_directory_renames(Err(gix_config::value::Error::new("err", "foo")))
+                .unwrap_err()
+                .to_string(),
+            "The value of key \"merge.directoryRenames=foo\" was invalid"
+        );
+        Ok(())
+    }
+}
+
 #[cfg(feature = "blob-diff")]
 mod diff {
     use gix::{
@@ -363,6 +432,29 @@ mod diff {
         );
         Ok(())
     }
+
+    #[test]
+    fn ignore_submodules() -> crate::Result {
+        use gix::submodule::config::Ignore;
+        for (actual, expected) in [
+            ("all", Ignore::All),
+            ("dirty", Ignore::Dirty),
+            ("untracked", Ignore::Untracked),
+            ("none", Ignore::None),
+        ] {
+            assert_eq!(Diff::IGNORE_SUBMODULES.try_into_ignore(bcow(actual))?, expected);
+            assert!(Diff::IGNORE_SUBMODULES.validate(actual.into()).is_ok());
+        }
+        assert_eq!(
+            Diff::IGNORE_SUBMODULES
+                .try_into_ignore(bcow("some"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"diff.ignoreSubmodules=some\" was invalid"
+        );
+        assert!(Diff::IGNORE_SUBMODULES.validate("foo".into()).is_err());
+        Ok(())
+    }
 }
 
 mod core {
@@ -649,6 +741,29 @@ mod index {
             "The key \"index.threads=nothing\" was invalid"
         );
     }
+
+    #[test]
+    #[cfg(feature = "index")]
+    fn version() {
+        for (value, expected) in [
+            ("2", gix::index::Version::V2),
+            ("3", gix::index::Version::V3),
+            ("4", gix::index::Version::V4),
+        ] {
+            assert_eq!(Index::VERSION.try_into_index_version(bcow(value)).unwrap(), expected);
+            assert!(Index::VERSION.validate(value.into()).is_ok());
+        }
+        for value in ["1", "5", "-2", "four"] {
+            assert_eq!(
+                Index::VERSION
+                    .try_into_index_version(bcow(value))
+                    .unwrap_err()
+                    .to_string(),
+                format!("The key \"index.version={value}\" (possibly from GIT_INDEX_VERSION) was invalid")
+            );
+            assert!(Index::VERSION.validate(value.into()).is_err());
+        }
+    }
 }
 
 mod extensions {
@@ -678,6 +793,47 @@ mod extensions {
         assert!(Extensions::OBJECT_FORMAT.validate("invalid".into()).is_err());
         Ok(())
     }
+
+    #[test]
+    fn compat_object_format() -> crate::Result {
+        assert_eq!(
+            Extensions::COMPAT_OBJECT_FORMAT.try_into_compat_object_format(bcow("sha256"))?,
+            gix_hash::Kind::Sha256
+        );
+        assert_eq!(
+            Extensions::COMPAT_OBJECT_FORMAT.try_into_compat_object_format(bcow("SHA1"))?,
+            gix_hash::Kind::Sha1,
+            "case-insensitive"
+        );
+        assert_eq!(
+            Extensions::COMPAT_OBJECT_FORMAT
+                .try_into_compat_object_format(bcow("invalid"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"extensions.compatObjectFormat=invalid\" was invalid"
+        );
+        assert!(Extensions::COMPAT_OBJECT_FORMAT.validate("sha256".into()).is_ok());
+        assert!(Extensions::COMPAT_OBJECT_FORMAT.validate("invalid".into()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn ref_storage() -> crate::Result {
+        assert_eq!(
+            Extensions::REF_STORAGE.try_into_ref_storage(bcow("files"))?,
+            gix::config::repository_format::RefStorage::Files
+        );
+        assert_eq!(
+            Extensions::REF_STORAGE
+                .try_into_ref_storage(bcow("reftable"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"extensions.refStorage=reftable\" was invalid"
+        );
+        assert!(Extensions::REF_STORAGE.validate("files".into()).is_ok());
+        assert!(Extensions::REF_STORAGE.validate("reftable".into()).is_err());
+        Ok(())
+    }
 }
 
 mod checkout {
@@ -700,6 +856,75 @@ mod checkout {
     }
 }
 
+mod gc {
+    use gix::config::tree::{Gc, Key};
+
+    #[test]
+    fn limits() -> crate::Result {
+        for key in [&Gc::AUTO, &Gc::AUTO_PACK_LIMIT] {
+            assert!(key.validate("0".into()).is_ok());
+            assert_eq!(key.try_into_limit(Ok(0))?, 0);
+            assert!(key.validate("-1".into()).is_ok());
+            assert_eq!(key.try_into_limit(Ok(-1))?, 0, "negative values disable the limit");
+            assert!(key.validate("6700".into()).is_ok());
+            assert_eq!(key.try_into_limit(Ok(6700))?, 6700);
+            assert!(key.validate("1k".into()).is_ok());
+            assert!(key.validate("foo".into()).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn reflog_expiry() -> crate::Result {
+        use gix::reference::log::expire::Cutoff;
+        let now = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(100 * 24 * 60 * 60);
+        for key in [&Gc::REFLOG_EXPIRE, &Gc::REFLOG_EXPIRE_UNREACHABLE] {
+            for (value, expected) in [
+                ("never", Cutoff::Never),
+                ("false", Cutoff::Never),
+                ("all", Cutoff::Always),
+                ("now", Cutoff::Always),
+                ("1112912083 +0000", Cutoff::OlderThan(1112912083)),
+                ("90.days.ago", Cutoff::OlderThan(10 * 24 * 60 * 60)),
+                ("2 weeks ago", Cutoff::OlderThan(86 * 24 * 60 * 60)),
+            ] {
+                assert!(key.validate(value.into()).is_ok());
+                assert_eq!(
+                    key.try_into_cutoff(crate::config::tree::bcow(value), Some(now))?,
+                    expected,
+                    "{value}"
+                );
+            }
+            assert!(key.validate("foo".into()).is_err());
+            assert!(key
+                .try_into_cutoff(crate::config::tree::bcow("foo"), Some(now))
+                .is_err());
+        }
+        Ok(())
+    }
+}
+
+mod commit_graph {
+    use gix::config::tree::{CommitGraph, Key};
+
+    #[test]
+    fn generation_version() -> crate::Result {
+        assert!(!CommitGraph::GENERATION_VERSION.try_into_generation_data(Ok(1))?);
+        assert!(CommitGraph::GENERATION_VERSION.validate("1".into()).is_ok());
+        assert!(CommitGraph::GENERATION_VERSION.try_into_generation_data(Ok(2))?);
+        assert!(CommitGraph::GENERATION_VERSION.validate("2".into()).is_ok());
+        assert_eq!(
+            CommitGraph::GENERATION_VERSION
+                .try_into_generation_data(Ok(3))
+                .unwrap_err()
+                .to_string(),
+            "The value of key \"commitGraph.generationVersion\" was invalid"
+        );
+        assert!(CommitGraph::GENERATION_VERSION.validate("0".into()).is_err());
+        Ok(())
+    }
+}
+
 mod pack {
     use gix::config::tree::{Key, Pack};
 
@@ -21,6 +21,7 @@ fn resource_cache() -> crate::Result {
                 command: Some("command".into()),
                 algorithm: Some(Algorithm::Histogram),
                 binary_to_text_command: Some("textconv".into()),
+                cache_binary_to_text: false,
                 is_binary: None
             },
             Driver {
@@ -47,3 +48,130 @@ fn resource_cache() -> crate::Result {
     );
     Ok(())
 }
+
+#[test]
+fn new_unified_diff_options() -> crate::Result {
+    let config: gix_config::File<'static> = "[diff]\n\tcontext = 5\n\tinterHunkContext = 2\n".parse()?;
+    let options = gix::diff::new_unified_diff_options(&config, false)?;
+    assert_eq!(options.context_size, 5);
+    assert_eq!(options.inter_hunk_context, 2);
+    assert_eq!(
+        options,
+        gix::diff::blob::unified_diff::Options {
+            context_size: 5,
+            inter_hunk_context: 2,
+            ..Default::default()
+        },
+        "everything else is defaulted"
+    );
+
+    let config: gix_config::File<'static> = "[diff]\n\tcontext = -1\n".parse()?;
+    assert_eq!(
+        gix::diff::new_unified_diff_options(&config, false)
+            .unwrap_err()
+            .to_string(),
+        "The value of key \"diff.context\" could not be parsed as unsigned integer",
+        "negative values are rejected"
+    );
+    assert_eq!(
+        gix::diff::new_unified_diff_options(&config, true)?,
+        Default::default(),
+        "invalid values are ignored if lenient"
+    );
+
+    let repo = named_repo("make_diff_repo.sh")?;
+    assert_eq!(
+        repo.diff_unified_options()?,
+        Default::default(),
+        "nothing is configured, so git's defaults are used"
+    );
+    Ok(())
+}
+
+mod binary_to_text_cache {
+    use std::sync::Arc;
+
+    use gix::bstr::ByteSlice;
+    use gix_diff::blob::{pipeline, pipeline::BinaryToTextCache, platform::resource, ResourceKind};
+    use gix_object::tree::EntryKind;
+
+    use crate::util::repo_rw;
+
+    #[test]
+    fn conversions_by_git_are_used_and_new_ones_are_written_for_git() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_textconv_cache_repo.sh")?;
+        let workdir = repo.work_dir().expect("non-bare").to_owned();
+        let cache = Arc::new(repo.diff_binary_to_text_cache()?);
+        let mut platform =
+            repo.diff_resource_cache(pipeline::Mode::ToGitUnlessBinaryToTextIsPresent, Default::default())?;
+        let driver = platform.filter.drivers()[0].clone();
+        assert_eq!(driver.name, "hex");
+        assert!(driver.cache_binary_to_text, "the configuration was picked up");
+        platform.filter.binary_to_text_cache = Some(cache.clone());
+
+        let old = repo.rev_parse_single("HEAD~1:a.bin")?.detach();
+        let new = repo.rev_parse_single("HEAD:a.bin")?.detach();
+        platform.set_resource(
+            old,
+            EntryKind::Blob,
+            "a.bin".into(),
+            ResourceKind::OldOrSource,
+            &repo.objects,
+        )?;
+        platform.set_resource(
+            new,
+            EntryKind::Blob,
+            "a.bin".into(),
+            ResourceKind::NewOrDestination,
+            &repo.objects,
+        )?;
+        let outcome = platform.prepare_diff()?;
+        let expected_old = std::fs::read(workdir.join("a-v1.hex"))?;
+        let expected_new = std::fs::read(workdir.join("a-v2.hex"))?;
+        assert_eq!(
+            outcome.old.data,
+            resource::Data::Buffer(&expected_old),
+            "the conversion program doesn't exist anymore, so this can only come from the cache written by git"
+        );
+        assert_eq!(outcome.new.data, resource::Data::Buffer(&expected_new));
+        assert!(outcome.old.is_derived && outcome.new.is_derived);
+
+        let id = repo.write_blob(b"\0c")?.detach();
+        let mut buf = Vec::new();
+        assert!(!cache.get(&driver, &id, &mut buf), "not yet converted");
+        cache.put(&driver, &id, b"converted\n");
+        assert!(
+            cache.get(&driver, &id, &mut buf),
+            "conversions are available right away"
+        );
+        assert_eq!(buf.as_bstr(), "converted\n");
+
+        let mut other_driver = driver.clone();
+        other_driver.binary_to_text_command = Some("other".into());
+        assert!(
+            !cache.get(&other_driver, &old, &mut buf),
+            "the cache is invalid if the command changes"
+        );
+
+        assert_eq!(cache.write(&repo)?, 1, "one notes reference was updated");
+        assert_eq!(cache.write(&repo)?, 0, "there is nothing new to write");
+
+        for (id, expected) in [(id, &b"converted\n"[..]), (old, &expected_old), (new, &expected_new)] {
+            let out = std::process::Command::new("git")
+                .args(["notes", "--ref", "textconv/hex", "show"])
+                .arg(id.to_string())
+                .current_dir(&workdir)
+                .output()?;
+            assert!(out.status.success(), "git can read all notes");
+            assert_eq!(out.stdout.as_bstr(), expected.as_bstr());
+        }
+
+        let cache = repo.diff_binary_to_text_cache()?;
+        assert!(
+            cache.get(&driver, &id, &mut buf),
+            "written conversions are visible to new instances"
+        );
+        assert_eq!(buf.as_bstr(), "converted\n");
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+cat <<'EOF' >file.rs
+fn main() {
+    println!("hello world");
+}
+EOF
+git add file.rs && git commit -q -m "initial"
+
+cat <<'EOF' >file.rs
+fn main()
+{
+  println!("hello world");
+}
+EOF
+git commit -q -am "reformat"
+git tag -a -m "the formatting commit" reformat
+
+{
+  echo "# the annotated tag is peeled to the commit"
+  git rev-parse reformat
+  echo "# blobs and objects that don't exist are skipped"
+  git rev-parse HEAD:file.rs
+  echo "1111111111111111111111111111111111111111"
+} > .git-blame-ignore-revs
+echo "invalid" > invalid-ignore-revs
+
+git config blame.ignoreRevsFile .git-blame-ignore-revs
@@ -0,0 +1,16 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+for d in $(seq 0 19); do mkdir -p dir-$d/sub && echo 0 > dir-$d/sub/file; done
+git add . && git commit -q -m "root"
+
+# each commit changes a single file, so the history of each one has 1 in 20 commits
+for i in $(seq 1 400); do
+  echo $i >> dir-$((i % 20))/sub/file
+  git commit -q -am "commit $i"
+done
+
+git commit-graph write --no-progress --reachable --changed-paths
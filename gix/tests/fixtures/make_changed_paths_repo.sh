@@ -0,0 +1,27 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+mkdir -p dir/sub other
+echo a > a
+echo b > dir/b
+echo c > dir/sub/c
+echo d > other/d
+git add . && git commit -q -m "root"
+
+for i in $(seq 10); do
+  echo $i >> other/d && git commit -q -am "change other/d $i"
+done
+echo a2 >> a && git commit -q -am "change a"
+echo c2 >> dir/sub/c && git commit -q -am "change dir/sub/c"
+
+git checkout -q -b side
+echo side >> dir/b && git commit -q -am "change dir/b on side"
+git checkout -q main
+echo main >> other/d && git commit -q -am "change other/d on main"
+git merge -q --no-ff -m "merge side" side
+git commit -q --allow-empty -m "no changes"
+
+git commit-graph write --no-progress --reachable --changed-paths
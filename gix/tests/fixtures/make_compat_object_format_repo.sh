@@ -0,0 +1,14 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+# Write the configuration directly as not all versions of `git` know `extensions.compatObjectFormat`.
+git init -q --bare compat
+git config -f compat/config core.repositoryFormatVersion 1
+git config -f compat/config extensions.compatObjectFormat sha256
+
+# The ids `git` computes for the same content in a SHA-256 repository serve as baseline.
+git init -q --bare --object-format=sha256 sha256
+(cd sha256
+  printf 'hello world' | git hash-object --stdin > ../hello-world.sha256
+  printf 'world' | git hash-object --stdin > ../world.sha256
+)
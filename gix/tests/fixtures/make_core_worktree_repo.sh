@@ -50,3 +50,15 @@ git clone --bare --shared base bare-relative-worktree
   git config --local core.worktree ../worktree
   git status --porcelain || : > status.baseline
 )
+
+mkdir elsewhere
+git clone -q --bare --shared base deploy.git
+(cd deploy.git
+  git config core.bare false
+  git config core.worktree ../elsewhere
+)
+(export GIT_DIR=deploy.git
+  git checkout -q -f main
+  echo modified >> elsewhere/a
+  git status --porcelain > deploy.git/status.baseline
+)
@@ -0,0 +1,39 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q remote
+(cd remote
+  git checkout -q -b main
+  git commit -q --allow-empty -m "c1"
+  git branch fast-forward
+  git branch forced
+  git branch gone
+  git branch rejected
+  git tag moved
+  git tag unchanged
+)
+
+git clone -q remote local
+(cd local
+  git config remote.origin.fetch '+refs/heads/*:refs/remotes/origin/*'
+  git config --add remote.origin.fetch 'refs/heads/rejected:refs/remotes/origin/rejected-without-force'
+  git fetch -q origin
+  git config --add remote.origin.fetch 'refs/heads/main:refs/heads/local-main'
+)
+
+(cd remote
+  git checkout -q fast-forward && git commit -q --allow-empty -m "fast-forward"
+  git checkout -q forced && git commit -q --amend --allow-empty -m "forced"
+  git checkout -q rejected && git commit -q --amend --allow-empty -m "rejected"
+  git checkout -q main
+  git branch new-branch
+  git tag new-tag
+  git tag -f moved fast-forward >/dev/null
+  git branch -D gone >/dev/null
+)
+
+cp -R local local-before-fetch
+(cd local
+  git fetch -v --prune --tags origin 2>../fetch-output || :
+  grep -v '^From ' ../fetch-output > ../fetch-summary-baseline
+)
@@ -0,0 +1,68 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function commit() {
+  local message=${1:?first argument is the commit message}
+  local date=${2:?second argument is the date}
+  GIT_AUTHOR_DATE="$date" GIT_COMMITTER_DATE="$date" git commit -q --allow-empty -m "$message"
+}
+
+git init -q base
+(cd base
+  commit "first" "2020-01-01 10:00:00 +0200"
+  commit "second
+
+with a body
+that spans lines" "2021-06-15 12:30:00 -0700"
+  git tag v1.2
+  git tag v1.9
+  GIT_COMMITTER_DATE="2021-07-01 09:00:00 +0200" git tag -m "release v1.10" v1.10
+  GIT_COMMITTER_DATE="2022-01-01 08:00:00 +0100" git tag -m "annotated subject
+continued
+
+annotated body" v1.10-annotated
+  git branch other
+  git checkout -q -b feature
+  commit "feature work" "2022-03-04 05:06:07 +0530"
+  git checkout -q main
+  commit "third" "2022-02-02 02:02:02 +0100"
+)
+
+git clone -q base clone
+(cd clone
+  git branch --track behind origin/other
+  git checkout -q -b ahead-and-behind --track origin/feature
+  git reset -q --hard HEAD~1
+  commit "local" "2023-01-01 00:00:00 +0300"
+  git checkout -q main
+  commit "ahead" "2023-02-02 00:00:00 +0300"
+  git update-ref refs/heads/behind origin/other~1
+  git branch feature
+  git branch --no-track gone
+  git config branch.gone.remote origin
+  git config branch.gone.merge refs/heads/deleted
+  # make 'feature' ambiguous as a tag to exercise the shortening rules
+  git tag feature HEAD~1
+
+  function baseline() {
+    local name=${1:?first argument is the name of the baseline}
+    shift
+    git for-each-ref "$@" > "../$name.baseline"
+  }
+
+  baseline refname-variants --format='%(refname)|%(refname:short)|%(refname:lstrip=1)|%(refname:lstrip=-1)|%(refname:rstrip=1)|%(refname:rstrip=-2)|%(refname:strip=5)'
+  baseline objects --format='%(objectname) %(objectname:short) %(objectname:short=10) %(objecttype)%09%%%(refname)'
+  baseline dates --format='%(committerdate)|%(committerdate:iso)|%(authordate:iso-strict)|%(taggerdate:rfc)|%(committerdate:short)|%(taggerdate:unix)|%(authordate:raw)'
+  baseline messages --format='%(refname) [%(subject)] [%(body)]'
+  baseline upstream --format='%(refname:short) %(upstream) %(upstream:short) %(upstream:track) %(upstream:trackshort)' refs/heads
+  baseline sort-version --format='%(refname)' --sort=version:refname refs/tags
+  baseline sort-version-descending --format='%(refname)' --sort=-v:refname refs/tags
+  baseline sort-committerdate --format='%(refname)' --sort=-committerdate
+  baseline sort-multiple --format='%(refname)' --sort=refname:lstrip=-1 --sort=objecttype
+
+  git log --all --decorate=full --format='%H%x09%D' > ../decorations.baseline
+  baseline contains-main --contains main --format='%(refname)'
+  baseline contains-other --contains origin/other --format='%(refname)'
+  baseline contains-first --contains origin/other~1 --format='%(refname)'
+  baseline contains-feature --contains origin/feature --format='%(refname)'
+)
@@ -0,0 +1,88 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q repo
+cd repo
+git config core.abbrev 7
+
+mkdir -p dir/sub
+printf 'one\ntwo\nthree\n' >a
+printf 'nested\n' >dir/sub/nested
+git add .
+git commit -q -m "root"
+
+cat <<'TEXT' >a
+one
+two changed
+three
+four
+TEXT
+printf 'added\n\ttabbed\n' >"with space"
+printf 'quoted\n' >'with "quote"'
+git add .
+GIT_AUTHOR_NAME="Jöhn Dœ" git commit -q -F - <<'MSG'
+subject: with [brackets] and a long long long long long long long long long long long long subject line
+
+A body with	a tab and ünïcödé,
+and trailing whitespace   
+
+which spans paragraphs.
+MSG
+
+git mv dir/sub/nested dir/renamed
+chmod +x a && git add a
+git commit -q -m "rename and mode change"
+
+printf '\000\001\002binary\377' >bin
+git rm -q "with space"
+git add bin
+GIT_AUTHOR_NAME="Some (Body)" git commit -q -m "add binary, remove a file"
+
+git rm -q bin
+printf 'only a subject line with "quotes"\n' >>a
+git commit -q -am $'\n\ntitle spanning\ntwo lines'
+
+git format-patch -q --no-signature -o ../patches --root HEAD
+
+cd ..
+mkdir mailinfo
+for patch in patches/*.patch; do
+  name=$(basename "$patch" .patch)
+  git mailinfo "mailinfo/$name.msg" "mailinfo/$name.patch" <"$patch" >"mailinfo/$name.info"
+done
+cat patches/*.patch >mbox
+
+cat <<'EOF2' >mail-with-inbody-headers
+From: Sender <sender@example.com>
+Subject: Re: [RFC PATCH v2 3/7] =?ISO-8859-1?Q?the_subject_is_overridden_by_caf=E9?=
+Date: Thu, 7 Apr 2005 15:13:13 -0700
+Content-Type: text/plain; charset=UTF-8
+Content-Transfer-Encoding: quoted-printable
+
+This is discarded.
+
+-- >8 --
+From: "Doe, Jane" <jane@example.com>
+Subject: [PATCH] the actual
+  subject
+Date: Fri, 8 Apr 2005 10:00:00 +0200
+
+The body with an encoded caf=C3=A9 and a soft=
+ line break.
+---
+ a | 2 +-
+ 1 file changed, 1 insertion(+), 1 deletion(-)
+
+diff --git a/a b/a
+EOF2
+git mailinfo --scissors mailinfo/inbody.msg mailinfo/inbody.patch <mail-with-inbody-headers >mailinfo/inbody.info
+
+git init -q binary-delta
+(cd binary-delta
+  for i in $(seq 1 1000); do printf 'line %d\000\n' "$i"; done >bin
+  git add bin
+  git commit -q -m "add a large binary file"
+  sed 's/^line 500/changed 500/' bin >bin.tmp && mv bin.tmp bin
+  git commit -q -am "change the binary file a little"
+  git format-patch -q --stdout --root HEAD >../binary-delta.mbox
+)
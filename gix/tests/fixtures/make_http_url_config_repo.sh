@@ -0,0 +1,26 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init http-url-scoped
+(cd http-url-scoped
+  cat >shared.config <<EOF
+[http]
+  sslVerify = true
+  proxy = http://proxy.example.com:3128
+  extraHeader = "X-Everyone: 1"
+  lowSpeedLimit = 1000
+  lowSpeedTime = 30
+  userAgent = default-agent
+[http "https://*.example.com"]
+  sslVerify = false
+  extraHeader = "X-Example: 1"
+  userAgent = wildcard-agent
+EOF
+  git config include.path ../shared.config
+  git config "http.https://internal.example.com.sslCAInfo" /etc/internal-ca.pem
+  git config "http.https://internal.example.com.proxy" ""
+  git config "http.https://internal.example.com/team.lowSpeedTime" 60
+  git config "http.https://jane@internal.example.com.followRedirects" false
+  git config "http.https://internal.example.com/team/secret.git.sslVerify" true
+  git config "http.http://internal.example.com.userAgent" plain-http-agent
+)
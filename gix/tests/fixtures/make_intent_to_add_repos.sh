@@ -0,0 +1,25 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q base
+(cd base
+  echo tracked >tracked
+  echo tracked-exe >tracked-exe && chmod +x tracked-exe
+  git add . && git commit -q -m init
+
+  echo new >new
+  echo new-exe >new-exe && chmod +x new-exe
+)
+
+cp -R base intent-to-add
+(cd intent-to-add
+  git add --intent-to-add new new-exe tracked
+  git status --porcelain=v2 >.git/status-porcelain-v2
+)
+
+cp -R base intent-to-add-then-added
+(cd intent-to-add-then-added
+  git add --intent-to-add new new-exe
+  git add new-exe
+  git status --porcelain=v2 >.git/status-porcelain-v2
+)
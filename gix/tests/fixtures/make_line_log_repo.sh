@@ -0,0 +1,76 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q repo
+cd repo
+
+function tick () {
+  if test -z "${tick+set}"
+  then
+    tick=1112911993
+  else
+    tick=$(($tick + 60))
+  fi
+  GIT_COMMITTER_DATE="$tick -0700"
+  GIT_AUTHOR_DATE="$tick -0700"
+  export GIT_COMMITTER_DATE GIT_AUTHOR_DATE
+}
+
+function commit() {
+  local message=${1:?first argument is the commit message}
+  git add -A
+  tick
+  git commit -q -m "$message"
+}
+
+seq 1 10 | sed 's/^/line /' >file
+printf 'other\n' >other
+commit "root"
+
+sed -i 's/^line 2$/line 2 changed/' file
+commit "change outside of the range"
+
+sed -i 's/^line 1$/line 1\ninserted a\ninserted b/' file
+commit "insert lines before the range"
+
+sed -i 's/^line 5$/line 5 changed/' file
+commit "change within the range"
+
+git mv file renamed
+commit "rename the file"
+
+git checkout -q -b side
+sed -i 's/^line 6$/line 6 changed on side/' renamed
+commit "change within the range on a side branch"
+
+git checkout -q main
+sed -i 's/^line 10$/line 10 changed on main/' renamed
+commit "change outside of the range on main"
+tick
+git merge -q --no-ff -m "merge side" side
+
+printf 'other changed\n' >other
+commit "change another file"
+
+sed -i 's/^line 7$/line 7\nadded within the range/' renamed
+commit "add a line within the range"
+
+sed -i '/^line 4$/d' renamed
+commit "delete a line at the start of the range"
+
+sed -i 's/^line 8$/line 8\nline 8 continued/; s/^line 9$/line 9 changed/' renamed
+commit "change lines across the end of the range"
+
+git checkout -q -b other
+sed -i 's/^line 3$/line 3 changed on other/' renamed
+commit "change within the range on another branch"
+
+git checkout -q main
+sed -i 's/^line 7$/line 7 changed on main/' renamed
+commit "change within the range on main"
+tick
+git merge -q --no-ff -m "merge other" other
+
+for range in 1,4 5,9 6,6 9,9 10,12 12,13; do
+  git log -L "$range:renamed" --format=%H >"baseline-$range"
+done
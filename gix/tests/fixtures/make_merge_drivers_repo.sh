@@ -0,0 +1,57 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+git config merge.fake.name "a fake driver"
+git config merge.fake.driver "./fake-driver.sh %O %A %B %L %P"
+git config merge.ours.driver true
+
+cat <<EOF >.gitattributes
+CHANGELOG merge=union
+*.bin merge=binary
+unset -merge
+custom* merge=fake
+ours merge=ours
+EOF
+
+cat <<'EOF' >fake-driver.sh
+#!/bin/sh
+{
+  echo "path: $5, marker-size: $4"
+  cat "$1" "$2" "$3"
+} > "$2.tmp" && mv "$2.tmp" "$2"
+case "$5" in
+  *conflict*) exit 1;;
+esac
+EOF
+chmod +x fake-driver.sh
+
+function write_all() {
+  local content=${1:?the content for all files}
+  printf 'header\n%s\nfooter\n' "$content" > CHANGELOG
+  printf 'one\n%s\nthree\n' "$content" > text
+  printf 'one\n%s\nthree\n' "$content" > text-clean
+  printf 'one\n%s\nthree\n' "$content" > unset
+  printf 'bin\0%s\n' "$content" > data.bin
+  printf '%s\n' "$content" > custom
+  printf '%s\n' "$content" > custom-conflict
+  printf '%s\n' "$content" > ours
+}
+
+write_all base
+git add . && git commit -q -m "base"
+
+git checkout -q -b theirs
+write_all theirs
+printf 'one\nbase\nthree-theirs\n' > text-clean
+git commit -q -am "theirs"
+
+git checkout -q main
+write_all ours
+printf 'one-ours\nbase\nthree\n' > text-clean
+git commit -q -am "ours"
+
+git merge theirs >/dev/null || true
+git diff --name-only --diff-filter=U > conflicting-paths
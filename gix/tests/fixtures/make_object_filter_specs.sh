@@ -0,0 +1,80 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q repo
+(cd repo
+  echo a >a
+  git add a && git commit -q -m c1
+  mkdir dir && echo b >dir/b
+  git add dir && git commit -q -m c2
+  # Allow fetching with filters from this repository.
+  git config uploadpack.allowFilter true
+)
+
+# Each line is 'ok' or 'err' followed by the filter spec, as accepted by `git rev-list`.
+while IFS= read -r spec; do
+  if git -C repo rev-list --objects "--filter=$spec" HEAD >/dev/null 2>&1; then
+    echo "ok $spec"
+  else
+    echo "err $spec"
+  fi
+done >baseline <<'SPECS'
+blob:none
+blob:none2
+blob:limit=0
+blob:limit=1
+blob:limit=1k
+blob:limit=1K
+blob:limit=2m
+blob:limit=3G
+blob:limit=0x10
+blob:limit=0X1f
+blob:limit=0x
+blob:limit=010
+blob:limit=08
+blob:limit=+5
+blob:limit= 5
+blob:limit=18446744073709551615
+blob:limit=18446744073709551616
+blob:limit=17179869183g
+blob:limit=17179869184g
+blob:limit=
+blob:limit=k
+blob:limit=1kb
+blob:limit=-1
+blob:limit=1.5k
+blob:limit
+tree:0
+tree:1
+tree:1k
+tree:0x2
+tree:
+tree:x
+tree:-1
+object:type=blob
+object:type=tree
+object:type=commit
+object:type=tag
+object:type=Blob
+object:type=
+object:type=blobs
+sparse:oid=HEAD:a
+sparse:path=a
+combine:
+combine:blob:none
+combine:blob:none+tree:1
+combine:blob:none+
+combine:+blob:none
+combine:blob%3Anone+tree%3A2
+combine:blob:none+blob%3alimit%3d1k
+combine:blob:none+tree:1+object:type=tree
+combine:combine%3Ablob%3Anone%2Btree%3A1
+combine:tree:1+sparse:oid=HEAD:a
+combine:blob:limit=1k+tree:3
+combine:blob:none+tree:1~
+combine:blob:none+tree:1 
+combine:blob:none+object:type=tree%7e
+combine:blob:none+tree:%zz
+unknown
+blob:none 
+SPECS
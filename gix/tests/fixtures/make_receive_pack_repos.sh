@@ -0,0 +1,22 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q client
+(cd client
+  git commit -q --allow-empty -m c1
+  git commit -q --allow-empty -m c2
+  git branch other
+  git tag -a -m "annotated" v1
+)
+
+git clone -q --bare client server.git
+git clone -q client server-with-worktree
+
+(cd client
+  git branch rewritten other~1
+  echo content > file && git add file && git commit -q -m c3
+  git branch diverged other
+  git checkout -q diverged
+  git commit -q --allow-empty -m "diverged from other"
+  git checkout -q main
+)
@@ -0,0 +1,66 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function tick () {
+  if test -z "${tick+set}"
+  then
+    tick=1112911993
+  else
+    tick=$(($tick + 60))
+  fi
+  GIT_COMMITTER_DATE="$tick -0700"
+  GIT_AUTHOR_DATE="$tick -0700"
+  export GIT_COMMITTER_DATE GIT_AUTHOR_DATE
+}
+
+function commit() {
+  local message=${1:?first argument is the commit message}
+  echo "$message" >file
+  git add file
+  tick
+  git commit -q -m "$message"
+}
+
+git init -q repo
+(cd repo
+  commit c1
+  commit c2
+  commit c3
+  commit c4
+  tick
+  git reset -q --hard HEAD~2
+
+  tick
+  git checkout -q -b side
+  commit s1
+  tick
+  git checkout -q main
+  commit c5
+
+  echo stashed >file
+  tick
+  git stash -q
+)
+
+# Entries created up to and including 'c2' are older than this.
+total=$((1112911993 + 90))
+# Entries created up to and including the reset are older than this.
+unreachable=$((1112911993 + 270))
+
+cp -R repo total
+(cd total && git reflog expire --all --expire=$total --expire-unreachable=never)
+cp -R repo unreachable
+(cd unreachable && git reflog expire --all --expire=never --expire-unreachable=$unreachable)
+cp -R repo rewrite
+(cd rewrite && git reflog expire --all --expire=never --expire-unreachable=$unreachable --rewrite)
+cp -R repo all
+(cd all && git reflog expire --all --expire=never --expire-unreachable=now)
+
+cp -R repo config
+(cd config
+  git config gc.reflogExpire all
+  git config gc.reflogExpireUnreachable never
+  git config gc.refs/heads/m*.reflogExpire "$unreachable +0000"
+)
+cp -R config config-expired
+(cd config-expired && git reflog expire --all)
@@ -0,0 +1,19 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+# more than 2^14 objects make `git` abbreviate object ids to 8 characters.
+for i in $(seq 16385); do
+  echo "blob"
+  echo "data <<EOT"
+  echo "$i"
+  echo "EOT"
+done | git fast-import --quiet
+
+echo content > file
+git add file
+git commit -q -m c1
+
+git rev-parse --short HEAD > short-head-id
+git rev-parse --short HEAD^{tree} > short-tree-id
@@ -0,0 +1,56 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+# Write the configuration files directly as `git` refuses to work in repositories with extensions it doesn't know.
+
+git init -q unknown-extension-v0
+(cd unknown-extension-v0
+  git config -f .git/config extensions.unknownExtension true
+)
+
+git init -q unknown-extension-v1
+(cd unknown-extension-v1
+  git config -f .git/config core.repositoryFormatVersion 1
+  git config -f .git/config extensions.unknownExtension true
+)
+
+git init -q version-2
+(cd version-2
+  git config -f .git/config core.repositoryFormatVersion 2
+)
+
+git init -q known-extensions-v1
+(cd known-extensions-v1
+  git config -f .git/config core.repositoryFormatVersion 1
+  git config -f .git/config extensions.objectFormat sha1
+  git config -f .git/config extensions.refStorage files
+  git config -f .git/config extensions.partialClone origin
+  git config -f .git/config extensions.noop-v1 true
+)
+
+git init -q compat-object-format-v1
+(cd compat-object-format-v1
+  git config -f .git/config core.repositoryFormatVersion 1
+  git config -f .git/config extensions.compatObjectFormat sha256
+)
+
+git init -q compat-object-format-v0
+(cd compat-object-format-v0
+  git config -f .git/config extensions.compatObjectFormat sha256
+)
+
+git init -q reftable-v1
+(cd reftable-v1
+  git config -f .git/config core.repositoryFormatVersion 1
+  git config -f .git/config extensions.refStorage reftable
+)
+
+git init -q reftable-v0
+(cd reftable-v0
+  git config -f .git/config extensions.refStorage reftable
+)
+
+git init -q precious-objects-v0
+(cd precious-objects-v0
+  git config -f .git/config extensions.preciousObjects true
+)
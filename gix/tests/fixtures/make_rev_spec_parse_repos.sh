@@ -417,3 +417,32 @@ git init new
 (cd new
   baseline '@{1}'
 )
+
+git init paths
+(cd paths
+  mkdir -p dir/sub
+  echo a > a
+  echo b > dir/b
+  echo c > dir/sub/c
+  git add . && git commit -m "init"
+  echo changed > dir/b
+  git add dir/b
+
+  baseline "@:"
+  baseline "@:a"
+  baseline "@:dir"
+  baseline "@:dir/b"
+  baseline "@:dir/sub/c"
+  baseline "@:dir/missing"
+  baseline "@:./"
+  baseline "@:./a"
+  baseline "@:./dir/sub/c"
+  baseline "@:./dir/../a"
+  baseline "@:../a"
+  baseline "@~1:a"         # there is no parent
+  baseline ":dir/b"
+  baseline ":0:dir/b"
+  baseline ":2:dir/b"
+  baseline ":./dir/b"
+  baseline ":../dir/b"
+)
@@ -0,0 +1,37 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function write_modules() {
+  cat <<EOM >.gitmodules
+[submodule "relative"]
+  path = relative
+  url = ../lib.git
+[submodule "nested-relative"]
+  path = nested-relative
+  url = ./sub/lib
+[submodule "absolute"]
+  path = absolute
+  url = https://example.com/absolute.git
+EOM
+}
+
+git init -q with-remote
+(cd with-remote
+  write_modules
+  git remote add origin https://example.com/org/super.git
+  git config url."https://mirror.example.com/".insteadOf "https://example.com/org/"
+  git add .gitmodules && git commit -q -m "add modules"
+)
+
+git init -q scp-remote
+(cd scp-remote
+  write_modules
+  git remote add upstream git@example.com:org/super
+  git add .gitmodules && git commit -q -m "add modules"
+)
+
+git init -q without-remote
+(cd without-remote
+  write_modules
+  git add .gitmodules && git commit -q -m "add modules"
+)
@@ -0,0 +1,30 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+cat <<'EOF2' >hexdump
+#!/bin/sh
+od -An -tx1 -v "$1"
+EOF2
+chmod +x hexdump
+
+git config diff.hex.textconv "$PWD/hexdump"
+git config diff.hex.cachetextconv true
+echo "*.bin diff=hex" >.gitattributes
+
+printf '\000\001abc' >a.bin
+./hexdump a.bin >a-v1.hex
+git add .gitattributes a.bin
+git commit -q -m "c1"
+
+printf '\000\002abc' >a.bin
+printf '\000b' >b.bin
+./hexdump a.bin >a-v2.hex
+./hexdump b.bin >b.hex
+git add a.bin b.bin
+git commit -q -m "c2"
+
+# fill the cache, and remove the conversion program to be sure all conversions come from the cache
+git log -p >/dev/null
+rm hexdump
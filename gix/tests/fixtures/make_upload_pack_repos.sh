@@ -0,0 +1,21 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q server
+(cd server
+  mkdir -p dir/sub
+  echo a > a && echo b > dir/b && echo c > dir/sub/c
+  git add . && git commit -q -m c1
+  git tag -a -m "annotated" v1
+  git tag -a -m "nested" v1-nested v1
+  echo a2 > a && git commit -q -am c2
+  git branch other
+  git update-ref refs/hidden/secret "$(git commit-tree -p HEAD -m secret HEAD^{tree})"
+)
+
+git clone -q --no-local server client
+
+(cd server
+  echo c2 > dir/sub/c && git commit -q -am c3
+  git tag -a -m "new" v2
+)
@@ -54,7 +54,8 @@ mod with_overrides {
             .set("GIT_TERMINAL_PROMPT", "42")
             .set("GIT_SHALLOW_FILE", "shallow-file-env")
             .set("GIT_NAMESPACE", "namespace-env")
-            .set("GIT_EXTERNAL_DIFF", "external-diff-env");
+            .set("GIT_EXTERNAL_DIFF", "external-diff-env")
+            .set("GIT_INDEX_VERSION", "4");
         let mut opts = gix::open::Options::isolated()
             .cli_overrides([
                 "http.userAgent=agent-from-cli",
@@ -68,6 +69,7 @@ mod with_overrides {
                 "gitoxide.http.proxyAuthMethod=proxy-auth-method-cli",
                 "gitoxide.core.shallowFile=shallow-file-cli",
                 "gitoxide.core.refsNamespace=namespace-cli",
+                "index.version=2",
             ])
             .config_overrides([
                 "http.userAgent=agent-from-api",
@@ -81,6 +83,7 @@ mod with_overrides {
                 "gitoxide.http.proxyAuthMethod=proxy-auth-method-api",
                 "gitoxide.core.shallowFile=shallow-file-api",
                 "gitoxide.core.refsNamespace=namespace-api",
+                "index.version=3",
             ]);
         opts.permissions.env.git_prefix = Permission::Allow;
         opts.permissions.env.http_transport = Permission::Allow;
@@ -188,6 +191,10 @@ mod with_overrides {
                 cow_bstr("ssh-variant-env"),
             ]
         );
+        assert_eq!(
+            config.strings("index.version").expect("at least one value"),
+            [cow_bstr("2"), cow_bstr("3"), cow_bstr("4")]
+        );
         assert_eq!(
             config.strings("core.sshCommand").expect("at least one value"),
             [
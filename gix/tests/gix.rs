@@ -2,6 +2,8 @@
 mod util;
 use util::*;
 
+#[cfg(feature = "blame")]
+mod blame;
 mod clone;
 mod commit;
 mod config;
@@ -9,7 +11,10 @@ mod config;
 mod diff;
 mod head;
 mod id;
+mod identity;
 mod init;
+#[cfg(feature = "merge")]
+mod merge;
 mod object;
 mod reference;
 mod remote;
@@ -55,6 +55,42 @@ fn prefix() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn prefix_with_auto_length_scales_with_object_count() -> crate::Result {
+    let repo = crate::named_repo("make_repo_with_many_objects.sh")?;
+    let work_dir = repo.work_dir().expect("non-bare");
+    let shortener = repo.id_shortener()?;
+    assert_eq!(
+        shortener.hex_len(),
+        8,
+        "more than 2^14 objects need more than 7 characters"
+    );
+
+    for (name, id) in [
+        ("short-head-id", repo.head_id()?.detach()),
+        ("short-tree-id", repo.head_tree_id()?.detach()),
+    ] {
+        let expected = std::fs::read_to_string(work_dir.join(name))?;
+        assert_eq!(
+            shortener.shorten(id)?.to_string(),
+            expected.trim(),
+            "it's the same as `git rev-parse --short`"
+        );
+        assert_eq!(shortener.shorten_or_id(id), shortener.shorten(id)?);
+        assert_eq!(id.attach(&repo).shorten()?, shortener.shorten(id)?);
+    }
+
+    let small_repo = crate::named_repo("make_basic_repo.sh")?;
+    assert_eq!(small_repo.id_shortener()?.hex_len(), 7, "the minimum is 7 characters");
+
+    let repo = gix::open_opts(
+        work_dir,
+        gix::open::Options::isolated().config_overrides(Core::ABBREV.validated_assignment("5".into())),
+    )?;
+    assert_eq!(repo.id_shortener()?.hex_len(), 5, "configured values are used as is");
+    Ok(())
+}
+
 #[test]
 fn display_and_debug() -> crate::Result {
     let repo = crate::basic_repo()?;
@@ -134,6 +170,62 @@ mod ancestors {
         Ok(())
     }
 
+    #[test]
+    fn changed_paths() -> crate::Result {
+        let mut repo = crate::named_repo("make_changed_paths_repo.sh")?;
+        let head = repo.head_id()?.detach();
+        let messages = |repo: &gix::Repository, paths: &[&str], use_commit_graph: bool| -> crate::Result<_> {
+            let mut walk = repo
+                .rev_walk(Some(head))
+                .use_commit_graph(use_commit_graph)
+                .changed_paths(paths.iter().copied())
+                .all()?;
+            let mut out = Vec::new();
+            for info in walk.by_ref() {
+                out.push(info?.object()?.message_raw()?.to_string().trim().to_owned());
+            }
+            Ok((out, walk.changed_paths_statistics().expect("limited to paths")))
+        };
+
+        for (paths, expected) in [
+            (&["a"][..], &["change a", "root"][..]),
+            (
+                &["dir"],
+                &["merge side", "change dir/b on side", "change dir/sub/c", "root"],
+            ),
+            (&["dir/sub/c", "a"], &["change dir/sub/c", "change a", "root"]),
+            (&["dir/b"], &["merge side", "change dir/b on side", "root"]),
+            (&["does-not-exist", "dir/sub"], &["change dir/sub/c", "root"]),
+        ] {
+            let (without_filters, stats) = messages(&repo, paths, false)?;
+            assert_eq!(without_filters, expected, "{paths:?}");
+            assert_eq!(stats.maybe + stats.definitely_not, 0, "no commit-graph, no filters");
+            assert_eq!(stats.filter_not_present, 16, "all commits with parents are compared");
+
+            let (with_filters, stats) = messages(&repo, paths, true)?;
+            assert_eq!(
+                with_filters, without_filters,
+                "{paths:?}: filters don't change the outcome"
+            );
+            assert_eq!(stats.filter_not_present, 0, "every commit has a filter");
+            assert_eq!(stats.maybe + stats.definitely_not, 16);
+            assert!(
+                stats.definitely_not > 10,
+                "{paths:?}: most commits are skipped without comparing trees, got {stats:?}"
+            );
+        }
+
+        repo.config_snapshot_mut()
+            .set_value(&gix::config::tree::CommitGraph::READ_CHANGED_PATHS, "false")?;
+        let (_, stats) = messages(&repo, &["a"], true)?;
+        assert_eq!(stats.filter_not_present, 16, "filters aren't read if disabled");
+
+        let all = repo.rev_walk(Some(head)).changed_paths(None::<&str>).all()?;
+        assert_eq!(all.changed_paths_statistics(), None, "no paths means no filtering");
+        assert_eq!(all.count(), 17);
+        Ok(())
+    }
+
     #[test]
     fn filtered() -> crate::Result {
         let repo = crate::repo("make_repo_with_fork_and_dates.sh")?.to_thread_local();
@@ -0,0 +1,250 @@
+use std::ffi::OsString;
+
+use gix::{
+    bstr::BString,
+    identity::{resolve, Environment, Purpose},
+};
+
+/// An environment with a user `jdoe` on the host `laptop.example.com`.
+struct Fake {
+    vars: Vec<(&'static str, &'static str)>,
+    full_name: Option<&'static str>,
+    host_name: Option<&'static str>,
+}
+
+impl Default for Fake {
+    fn default() -> Self {
+        Fake {
+            vars: Vec::new(),
+            full_name: Some("Jane Doe"),
+            host_name: Some("laptop.example.com"),
+        }
+    }
+}
+
+impl Fake {
+    fn vars(vars: &[(&'static str, &'static str)]) -> Self {
+        Fake {
+            vars: vars.to_vec(),
+            ..Default::default()
+        }
+    }
+}
+
+impl Environment for Fake {
+    fn var(&self, name: &str) -> Option<OsString> {
+        self.vars.iter().find(|(n, _)| *n == name).map(|(_, v)| v.into())
+    }
+
+    fn user_name(&self) -> Option<BString> {
+        Some("jdoe".into())
+    }
+
+    fn full_name(&self) -> Option<BString> {
+        self.full_name.map(Into::into)
+    }
+
+    fn host_name(&self) -> Option<BString> {
+        self.host_name.map(Into::into)
+    }
+}
+
+fn config(config: &str) -> gix::config::File<'static> {
+    config.parse().expect("valid configuration")
+}
+
+fn name_and_email(config: &str, env: &Fake, purpose: Purpose) -> Result<(String, String), resolve::Error> {
+    let signature = resolve(&self::config(config), env, purpose)?;
+    Ok((signature.name.to_string(), signature.email.to_string()))
+}
+
+fn pair(name: &str, email: &str) -> (String, String) {
+    (name.into(), email.into())
+}
+
+#[test]
+fn environment_variables_take_precedence_over_configuration() -> crate::Result {
+    let config = "[user]\nname = user\nemail = user@example.com\n[author]\nname = author\nemail = author@example.com";
+    let env = Fake::vars(&[
+        ("GIT_AUTHOR_NAME", "env author"),
+        ("GIT_AUTHOR_EMAIL", "env-author@example.com"),
+        ("GIT_AUTHOR_DATE", "1700000000 +0200"),
+    ]);
+    let author = resolve(&self::config(config), &env, Purpose::Author)?;
+    assert_eq!(author.name, "env author");
+    assert_eq!(author.email, "env-author@example.com");
+    assert_eq!(author.time, gix::date::Time::new(1700000000, 7200));
+
+    assert_eq!(
+        name_and_email(config, &env, Purpose::Committer)?,
+        pair("user", "user@example.com"),
+        "the environment of the author doesn't affect the committer, which falls back to `user.*`"
+    );
+    assert_eq!(
+        name_and_email(config, &Fake::default(), Purpose::Author)?,
+        pair("author", "author@example.com")
+    );
+
+    let err = resolve(
+        &self::config(config),
+        &Fake::vars(&[("GIT_COMMITTER_DATE", "not a date")]),
+        Purpose::Committer,
+    )
+    .unwrap_err();
+    assert!(matches!(err, resolve::Error::Time(_)), "dates must be valid");
+    Ok(())
+}
+
+#[test]
+fn empty_values() -> crate::Result {
+    assert_eq!(
+        name_and_email(
+            "[author]\nname =\nemail =\n[user]\nname = user\nemail = user@example.com",
+            &Fake::default(),
+            Purpose::Author
+        )?,
+        pair("user", "user@example.com"),
+        "empty `author.*` values are ignored"
+    );
+    assert_eq!(
+        name_and_email(
+            "[user]\nname = user\nemail =",
+            &Fake::vars(&[("EMAIL", "env@example.com")]),
+            Purpose::Committer
+        )?,
+        pair("user", ""),
+        "an empty `user.email` is used as is"
+    );
+    assert_eq!(
+        name_and_email("[user]\nname = user", &Fake::vars(&[("EMAIL", "")]), Purpose::Committer)?,
+        pair("user", "jdoe@laptop.example.com"),
+        "an empty `EMAIL` is ignored"
+    );
+    assert_eq!(
+        name_and_email("", &Fake::vars(&[("GIT_COMMITTER_EMAIL", "")]), Purpose::Committer)?,
+        pair("Jane Doe", ""),
+        "empty email environment variables are used as is"
+    );
+
+    let err = name_and_email(
+        "[user]\nname =\nemail = user@example.com",
+        &Fake::default(),
+        Purpose::Author,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Author identity unknown: empty ident name not allowed\n\n{}",
+            gix::identity::GUIDANCE
+        )
+    );
+    assert!(matches!(
+        name_and_email("", &Fake::vars(&[("GIT_COMMITTER_NAME", "")]), Purpose::Committer),
+        Err(resolve::Error::MissingName { .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn identities_are_guessed_from_the_system() -> crate::Result {
+    assert_eq!(
+        name_and_email("", &Fake::default(), Purpose::Committer)?,
+        pair("Jane Doe", "jdoe@laptop.example.com")
+    );
+    assert_eq!(
+        name_and_email("", &Fake::vars(&[("EMAIL", "env@example.com")]), Purpose::Committer)?,
+        pair("Jane Doe", "env@example.com"),
+        "`EMAIL` is used before guessing"
+    );
+
+    for (host_name, guessed) in [(Some("laptop"), "jdoe@laptop.(none)"), (None, "jdoe@(none)")] {
+        let env = Fake {
+            host_name,
+            ..Default::default()
+        };
+        let err = name_and_email("[user]\nname = user", &env, Purpose::Committer).unwrap_err();
+        assert!(
+            matches!(&err, resolve::Error::MissingEmail { reason, .. }
+                if *reason == format!("unable to auto-detect email address (got '{guessed}')")),
+            "hosts without domain can't be used for email addresses: {err:?}"
+        );
+        assert!(err.to_string().starts_with("Committer identity unknown: "));
+        assert!(err.to_string().ends_with(gix::identity::GUIDANCE));
+    }
+
+    let env = Fake {
+        full_name: None,
+        ..Default::default()
+    };
+    assert!(matches!(
+        name_and_email("", &env, Purpose::Author),
+        Err(resolve::Error::MissingName { .. })
+    ));
+    let env = Fake {
+        full_name: Some(""),
+        ..Default::default()
+    };
+    assert!(
+        matches!(
+            name_and_email("", &env, Purpose::Author),
+            Err(resolve::Error::MissingName { reason, .. }) if reason == "empty ident name not allowed"
+        ),
+        "an empty full name is invalid"
+    );
+    Ok(())
+}
+
+#[test]
+fn use_config_only_prevents_guessing() -> crate::Result {
+    let err = name_and_email(
+        "[user]\nuseConfigOnly = true\nname = user",
+        &Fake::vars(&[("EMAIL", "env@example.com")]),
+        Purpose::Committer,
+    )
+    .unwrap_err();
+    assert!(
+        matches!(&err, resolve::Error::MissingEmail { reason, .. } if reason == "no email was given and auto-detection is disabled"),
+        "not even `EMAIL` is used: {err:?}"
+    );
+
+    let err = name_and_email(
+        "[user]\nuseConfigOnly = true\nemail = user@example.com",
+        &Fake::default(),
+        Purpose::Author,
+    )
+    .unwrap_err();
+    assert!(
+        matches!(&err, resolve::Error::MissingName { reason, .. } if reason == "no name was given and auto-detection is disabled")
+    );
+
+    let err = name_and_email("[user]\nuseConfigOnly = true", &Fake::default(), Purpose::Author).unwrap_err();
+    assert!(
+        matches!(&err, resolve::Error::MissingName { reason, .. } if reason == "no name was given and auto-detection is disabled"),
+        "the name is checked before the email: {err:?}"
+    );
+
+    assert_eq!(
+        name_and_email(
+            "[user]\nuseConfigOnly = true\n[committer]\nname = committer\nemail = committer@example.com",
+            &Fake::default(),
+            Purpose::Author
+        )?,
+        pair("Jane Doe", "jdoe@laptop.example.com"),
+        "configuration for the committer counts as well, and allows guessing the author"
+    );
+    assert_eq!(
+        name_and_email(
+            "[user]\nuseConfigOnly = true",
+            &Fake::vars(&[("GIT_AUTHOR_NAME", "env"), ("GIT_AUTHOR_EMAIL", "env@example.com")]),
+            Purpose::Author
+        )?,
+        pair("env", "env@example.com"),
+        "environment variables are always used"
+    );
+    assert!(matches!(
+        name_and_email("[user]\nuseConfigOnly = maybe", &Fake::default(), Purpose::Author),
+        Err(resolve::Error::ConfigBoolean(_))
+    ));
+    Ok(())
+}
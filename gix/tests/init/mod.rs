@@ -49,11 +49,42 @@ mod bare {
             .starts_with("Refusing to initialize the non-empty directory as"));
         Ok(())
     }
+
+    #[test]
+    fn reinit_of_existing_bare_repository_keeps_everything() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let repo = gix::init_bare(tmp.path())?;
+        let description = repo.git_dir().join("description");
+        std::fs::write(&description, b"changed")?;
+
+        let repo = gix::init_bare(tmp.path())?;
+        assert_eq!(repo.kind(), gix::repository::Kind::Bare);
+        assert_eq!(std::fs::read(description)?, b"changed", "existing files aren't touched");
+        crate::init::assert_native_git_accepts(repo.git_dir())?;
+        Ok(())
+    }
 }
 
+fn assert_native_git_accepts(dir: &std::path::Path) -> crate::Result {
+    let is_bare = dir.join("HEAD").is_file();
+    for args in [&["status"][..], &["fsck"]].into_iter().skip(usize::from(is_bare)) {
+        let out = std::process::Command::new("git").args(args).current_dir(dir).output()?;
+        assert!(
+            out.status.success(),
+            "git {args:?} failed in {dir:?}: {}",
+            out.stderr.to_str_lossy()
+        );
+    }
+    Ok(())
+}
+
+use gix::bstr::ByteSlice;
+
 mod non_bare {
     use gix_testtools::tempfile;
 
+    use crate::init::assert_native_git_accepts;
+
     #[test]
     fn init_bare_with_custom_branch_name() -> crate::Result {
         let tmp = tempfile::tempdir()?;
@@ -125,4 +156,191 @@ mod non_bare {
         );
         Ok(())
     }
+
+    #[test]
+    fn reinit_keeps_head_config_and_existing_files_but_restores_missing_ones() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let repo = gix::init(tmp.path())?;
+        let git_dir = repo.git_dir().to_owned();
+        std::fs::write(git_dir.join("HEAD"), b"ref: refs/heads/other\n")?;
+        std::fs::write(git_dir.join("description"), b"changed")?;
+        let config = std::fs::read(git_dir.join("config"))?;
+        std::fs::remove_dir_all(git_dir.join("hooks"))?;
+        std::fs::remove_dir_all(git_dir.join("refs").join("tags"))?;
+
+        let repo: gix::Repository = gix::ThreadSafeRepository::init_opts(
+            tmp.path(),
+            gix::create::Kind::WithWorktree,
+            gix::create::Options::default(),
+            gix::open::Options::isolated().config_overrides(["init.defaultBranch=special"]),
+        )?
+        .into();
+        assert_eq!(repo.git_dir(), git_dir);
+        assert_eq!(
+            repo.head_name()?.expect("symbolic").as_bstr(),
+            "refs/heads/other",
+            "HEAD isn't touched on reinit, even if the default branch differs"
+        );
+        assert_eq!(std::fs::read(git_dir.join("description"))?, b"changed");
+        assert_eq!(std::fs::read(git_dir.join("config"))?, config);
+        assert!(
+            git_dir.join("hooks").join("pre-commit.sample").is_file(),
+            "templates are applied"
+        );
+        assert!(
+            git_dir.join("refs").join("tags").is_dir(),
+            "missing directories are created"
+        );
+        assert_native_git_accepts(tmp.path())
+    }
+
+    #[test]
+    fn init_with_template_dir() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let template_dir = tmp.path().join("template");
+        std::fs::create_dir_all(template_dir.join("hooks"))?;
+        std::fs::create_dir_all(template_dir.join("info"))?;
+        std::fs::write(template_dir.join("hooks").join("custom-hook"), b"#!/bin/sh")?;
+        std::fs::write(template_dir.join("info").join("exclude"), b"from-template")?;
+        std::fs::write(template_dir.join(".hidden"), b"")?;
+        std::fs::write(template_dir.join("config"), b"[core]\nbare = true")?;
+
+        for use_config in [false, true] {
+            let dir = tmp.path().join(format!("repo-{use_config}"));
+            let (create_options, open_options) = if use_config {
+                (
+                    gix::create::Options::default(),
+                    gix::open::Options::isolated()
+                        .config_overrides([format!("init.templateDir={}", template_dir.display())]),
+                )
+            } else {
+                (
+                    gix::create::Options {
+                        template_dir: Some(template_dir.clone()),
+                        ..Default::default()
+                    },
+                    gix::open::Options::isolated(),
+                )
+            };
+            let repo: gix::Repository = gix::ThreadSafeRepository::init_opts(
+                &dir,
+                gix::create::Kind::WithWorktree,
+                create_options,
+                open_options,
+            )?
+            .into();
+            let git_dir = repo.git_dir();
+            assert!(git_dir.join("hooks").join("custom-hook").is_file());
+            assert!(
+                !git_dir.join("hooks").join("pre-commit.sample").exists(),
+                "built-in templates aren't used"
+            );
+            assert!(!git_dir.join("description").exists());
+            assert_eq!(std::fs::read(git_dir.join("info").join("exclude"))?, b"from-template");
+            assert!(!git_dir.join(".hidden").exists(), "hidden files are skipped");
+            assert!(!repo.is_bare(), "the configuration of the template isn't used");
+            assert_native_git_accepts(&dir)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn init_with_empty_template_dir_has_no_templates() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let repo: gix::Repository = gix::ThreadSafeRepository::init_opts(
+            tmp.path(),
+            gix::create::Kind::WithWorktree,
+            gix::create::Options {
+                template_dir: Some(Default::default()),
+                ..Default::default()
+            },
+            gix::open::Options::isolated(),
+        )?
+        .into();
+        assert!(!repo.git_dir().join("hooks").exists());
+        assert!(!repo.git_dir().join("info").exists());
+        assert!(repo.git_dir().join("objects").join("pack").is_dir());
+        assert_native_git_accepts(tmp.path())
+    }
+
+    #[test]
+    fn init_with_separate_git_dir() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let work_dir = tmp.path().join("worktree");
+        let git_dir = tmp.path().join("repo.git");
+        let repo: gix::Repository = gix::ThreadSafeRepository::init_opts(
+            &work_dir,
+            gix::create::Kind::WithWorktree,
+            gix::create::Options {
+                separate_git_dir: Some(git_dir.clone()),
+                ..Default::default()
+            },
+            gix::open::Options::isolated(),
+        )?
+        .into();
+        assert_eq!(repo.git_dir(), git_dir);
+        assert_eq!(repo.work_dir(), Some(work_dir.as_path()));
+        assert!(work_dir.join(".git").is_file(), "the worktree points to the git dir");
+        assert!(!repo.is_bare());
+
+        let reopened = gix::open(&work_dir)?;
+        assert_eq!(reopened.git_dir(), git_dir);
+        assert_eq!(reopened.work_dir(), Some(work_dir.as_path()));
+
+        let reinitialized = gix::init(&work_dir)?;
+        assert_eq!(
+            reinitialized.git_dir(),
+            git_dir,
+            "the `.git` file is followed on reinit"
+        );
+        assert_native_git_accepts(&work_dir)
+    }
+
+    #[test]
+    fn reinit_with_separate_git_dir_moves_the_existing_git_dir() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let work_dir = tmp.path().join("worktree");
+        let repo = gix::init(&work_dir)?;
+        std::fs::write(repo.git_dir().join("description"), b"moved")?;
+
+        let git_dir = tmp.path().join("repo.git");
+        let repo: gix::Repository = gix::ThreadSafeRepository::init_opts(
+            &work_dir,
+            gix::create::Kind::WithWorktree,
+            gix::create::Options {
+                separate_git_dir: Some(git_dir.clone()),
+                ..Default::default()
+            },
+            gix::open::Options::isolated(),
+        )?
+        .into();
+        assert_eq!(repo.git_dir(), git_dir);
+        assert_eq!(repo.work_dir(), Some(work_dir.as_path()));
+        assert!(work_dir.join(".git").is_file(), "the `.git` directory was replaced");
+        assert_eq!(
+            std::fs::read(git_dir.join("description"))?,
+            b"moved",
+            "the existing git directory was moved, not recreated"
+        );
+
+        let other_git_dir = tmp.path().join("other.git");
+        let repo: gix::Repository = gix::ThreadSafeRepository::init_opts(
+            &work_dir,
+            gix::create::Kind::WithWorktree,
+            gix::create::Options {
+                separate_git_dir: Some(other_git_dir.clone()),
+                ..Default::default()
+            },
+            gix::open::Options::isolated(),
+        )?
+        .into();
+        assert_eq!(
+            repo.git_dir(),
+            other_git_dir,
+            "a git directory that is pointed to by the `.git` file is moved as well"
+        );
+        assert!(!git_dir.exists());
+        assert_eq!(std::fs::read(other_git_dir.join("description"))?, b"moved");
+        assert_native_git_accepts(&work_dir)
+    }
 }
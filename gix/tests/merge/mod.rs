@@ -0,0 +1,101 @@
+use gix::bstr::ByteSlice;
+use gix::merge::blob::{builtin_driver::text::Labels, platform::Input, Resolution};
+
+use crate::util::named_repo;
+
+#[test]
+fn blob_merges_match_git_for_all_drivers() -> crate::Result {
+    let repo = named_repo("make_merge_drivers_repo.sh")?;
+    let workdir = repo.work_dir().expect("non-bare");
+    let conflicting_paths = std::fs::read_to_string(workdir.join("conflicting-paths"))?;
+
+    let head = repo.head_commit()?;
+    let base = repo
+        .find_object(head.parent_ids().next().expect("one parent"))?
+        .try_into_commit()?;
+    let theirs = repo
+        .find_reference("theirs")?
+        .peel_to_id_in_place()?
+        .object()?
+        .try_into_commit()?;
+    let trees = [base.tree()?, head.tree()?, theirs.tree()?];
+    let mut platform = repo.blob_merge_platform()?;
+    let mut out = Vec::new();
+    for path in [
+        "CHANGELOG",
+        "text",
+        "text-clean",
+        "unset",
+        "data.bin",
+        "custom",
+        "custom-conflict",
+        "ours",
+    ] {
+        let mut buf = Vec::new();
+        let versions = trees
+            .iter()
+            .map(|tree| -> crate::Result<_> {
+                let entry = tree.lookup_entry_by_path(path, &mut buf)?.expect("present");
+                Ok(repo.find_object(entry.oid())?.detach().data)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        out.clear();
+        let resolution = platform.merge(
+            &mut out,
+            path.into(),
+            Input {
+                ancestor: &versions[0],
+                current: &versions[1],
+                other: &versions[2],
+            },
+            Labels {
+                ancestor: None,
+                current: Some("HEAD".into()),
+                other: Some("theirs".into()),
+            },
+            &repo.objects,
+        )?;
+        let expected = std::fs::read(workdir.join(path))?;
+        assert_eq!(out.as_bstr(), expected.as_bstr(), "{path}");
+        assert_eq!(
+            resolution,
+            if conflicting_paths.lines().any(|line| line == path) {
+                Resolution::Conflict
+            } else {
+                Resolution::Complete
+            },
+            "{path}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn blob_merge_platform_is_configured_from_git_configuration() -> crate::Result {
+    let mut repo = named_repo("make_merge_drivers_repo.sh")?;
+    let platform = repo.blob_merge_platform()?;
+    let drivers = platform.drivers();
+    assert_eq!(drivers.len(), 2);
+    assert_eq!(drivers[0].name, "fake");
+    assert_eq!(drivers[0].display_name, "a fake driver");
+    assert_eq!(drivers[0].command, "./fake-driver.sh %O %A %B %L %P");
+    assert_eq!(drivers[0].recursive, None);
+    assert_eq!(drivers[1].name, "ours");
+    assert_eq!(drivers[1].command, "true");
+    assert_eq!(platform.options.default_driver, None);
+    assert_eq!(
+        platform.options.command_context.worktree_dir.as_deref(),
+        repo.work_dir(),
+        "drivers run in the worktree"
+    );
+
+    repo.config_snapshot_mut()
+        .set_value(&gix::config::tree::Merge::DEFAULT, "union")?;
+    assert_eq!(
+        repo.blob_merge_platform()?.options.default_driver,
+        Some("union".into()),
+        "merge.default is picked up"
+    );
+    Ok(())
+}
@@ -43,3 +43,83 @@ fn decode() -> crate::Result {
     assert_eq!(commit.decode()?.message, "c2\n");
     Ok(())
 }
+
+#[cfg(feature = "blob-diff")]
+mod format_patch {
+    use std::{io::Write, process::Stdio};
+
+    use gix::{bstr::ByteSlice, commit::format_patch};
+
+    use crate::util::restricted;
+
+    fn commits(repo: &gix::Repository) -> crate::Result<Vec<gix::ObjectId>> {
+        let mut ids = repo
+            .head_id()?
+            .ancestors()
+            .all()?
+            .map(|info| info.map(|info| info.id))
+            .collect::<Result<Vec<_>, _>>()?;
+        ids.reverse();
+        Ok(ids)
+    }
+
+    fn options(number: usize, total: usize) -> format_patch::Options {
+        format_patch::Options {
+            number: Some((number, total)),
+            signature: None,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn output_and_file_names_are_the_same_as_the_ones_of_git() -> crate::Result {
+        let dir = gix_testtools::scripted_fixture_read_only("make_format_patch_repo.sh")?;
+        let repo = gix::open_opts(dir.join("repo"), restricted())?;
+        let ids = commits(&repo)?;
+        assert_eq!(ids.len(), 5, "one patch per commit");
+        for (idx, id) in ids.iter().enumerate() {
+            let commit = repo.find_object(*id)?.into_commit();
+            let file_name = format_patch::file_name(idx + 1, commit.message_raw()?);
+            let expected = std::fs::read(dir.join("patches").join(&file_name))?;
+            let actual = commit.format_patch(&options(idx + 1, ids.len()))?;
+            assert_eq!(actual, expected.as_bstr(), "{file_name}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn patches_can_be_applied_with_git_am() -> crate::Result {
+        let dir = gix_testtools::scripted_fixture_read_only("make_format_patch_repo.sh")?;
+        let repo = gix::open_opts(dir.join("repo"), restricted())?;
+        let ids = commits(&repo)?;
+
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let git = |args: &[&str]| {
+            let mut cmd = std::process::Command::new("git");
+            cmd.current_dir(tmp.path())
+                .args(args)
+                .env("GIT_COMMITTER_NAME", "committer")
+                .env("GIT_COMMITTER_EMAIL", "committer@example.com");
+            cmd
+        };
+        assert!(git(&["init", "-q"]).status()?.success());
+        for (idx, id) in ids.iter().enumerate() {
+            let patch = repo
+                .find_object(*id)?
+                .into_commit()
+                .format_patch(&options(idx + 1, ids.len()))?;
+            let mut am = git(&["am", "-q"]).stdin(Stdio::piped()).spawn()?;
+            am.stdin.take().expect("configured").write_all(&patch)?;
+            assert!(am.wait()?.success(), "patch {} applies", idx + 1);
+
+            let tree = git(&["rev-parse", "HEAD^{tree}"]).output()?.stdout;
+            assert_eq!(
+                tree.trim().as_bstr(),
+                repo.find_object(*id)?.into_commit().tree_id()?.to_string(),
+                "the tree after applying patch {} is the one of the original commit",
+                idx + 1
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+use gix::{
+    bstr::{BString, ByteSlice},
+    reference::format::{Atom, Format, NameFormat, SortKey},
+};
+
+use crate::util::{named_subrepo_opts, restricted};
+
+fn repo() -> crate::Result<gix::Repository> {
+    Ok(named_subrepo_opts("make_for_each_ref_repo.sh", "clone", restricted())?)
+}
+
+/// Expand `format` for all references below `prefix`, sorted by `sort_keys`, just like `git for-each-ref` would.
+fn for_each_ref(
+    repo: &gix::Repository,
+    format: &str,
+    sort_keys: &[&str],
+    prefix: Option<&str>,
+) -> crate::Result<BString> {
+    let format = Format::parse(format.into())?;
+    let keys = sort_keys
+        .iter()
+        .map(|key| SortKey::parse((*key).into()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let platform = repo.references()?;
+    let mut refs = match prefix {
+        Some(prefix) => platform.prefixed(prefix)?.collect::<Result<Vec<_>, _>>()?,
+        None => platform.all()?.collect::<Result<Vec<_>, _>>()?,
+    };
+    gix::reference::format::sort(&mut refs, &keys)?;
+    let mut out = BString::default();
+    for reference in &refs {
+        format.expand_into(reference, &mut out)?;
+        out.push(b'\n');
+    }
+    Ok(out)
+}
+
+fn baseline(name: &str) -> crate::Result<BString> {
+    let path = gix_testtools::scripted_fixture_read_only("make_for_each_ref_repo.sh")?.join(format!("{name}.baseline"));
+    Ok(std::fs::read(path)?.into())
+}
+
+#[test]
+fn output_matches_git_for_each_ref() -> crate::Result {
+    let repo = repo()?;
+    for (name, format, sort_keys, prefix) in [
+        (
+            "refname-variants",
+            "%(refname)|%(refname:short)|%(refname:lstrip=1)|%(refname:lstrip=-1)|%(refname:rstrip=1)|%(refname:rstrip=-2)|%(refname:strip=5)",
+            &[][..],
+            None,
+        ),
+        (
+            "objects",
+            "%(objectname) %(objectname:short) %(objectname:short=10) %(objecttype)%09%%%(refname)",
+            &[],
+            None,
+        ),
+        (
+            "dates",
+            "%(committerdate)|%(committerdate:iso)|%(authordate:iso-strict)|%(taggerdate:rfc)|%(committerdate:short)|%(taggerdate:unix)|%(authordate:raw)",
+            &[],
+            None,
+        ),
+        ("messages", "%(refname) [%(subject)] [%(body)]", &[], None),
+        (
+            "upstream",
+            "%(refname:short) %(upstream) %(upstream:short) %(upstream:track) %(upstream:trackshort)",
+            &[],
+            Some("refs/heads/"),
+        ),
+        ("sort-version", "%(refname)", &["version:refname"], Some("refs/tags/")),
+        ("sort-version-descending", "%(refname)", &["-v:refname"], Some("refs/tags/")),
+        ("sort-committerdate", "%(refname)", &["-committerdate"], None),
+        (
+            "sort-multiple",
+            "%(refname)",
+            &["refname:lstrip=-1", "objecttype"],
+            None,
+        ),
+    ] {
+        let actual = for_each_ref(&repo, format, sort_keys, prefix)?;
+        assert_eq!(actual.as_bstr(), baseline(name)?.as_bstr(), "{name}: {format}");
+    }
+    Ok(())
+}
+
+#[test]
+fn parse() -> crate::Result {
+    assert_eq!(
+        SortKey::parse("-version:refname:short".into())?,
+        SortKey {
+            atom: Atom::RefName(NameFormat::Short),
+            descending: true,
+            version: true,
+        }
+    );
+    assert!(
+        Format::parse("%(refname".into()).is_err(),
+        "unterminated atoms are invalid"
+    );
+    assert!(Format::parse("%(unknown)".into()).is_err());
+    assert!(Format::parse("%(refname:long)".into()).is_err());
+    assert!(Format::parse("%(subject:short)".into()).is_err());
+    assert!(SortKey::parse("-".into()).is_err());
+
+    let format = Format::parse("100% %41%(objecttype)".into())?;
+    assert_eq!(
+        format.parts.len(),
+        2,
+        "the escapes are merged into the literal before the atom"
+    );
+    Ok(())
+}
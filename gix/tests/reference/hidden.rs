@@ -0,0 +1,96 @@
+use gix::reference::hidden::{Matcher, Service};
+
+#[test]
+fn matching_and_negation() {
+    let ns = "refs/namespaces/foo/";
+    for (patterns, name, expected) in [
+        (&["refs/changes"][..], "refs/changes/42/1", true),
+        (&["refs/changes"], "refs/changes", true),
+        (&["refs/changes"], "refs/changes-log", false),
+        (&["refs/changes/"], "refs/changes/42/1", true),
+        (&["refs/changes//"], "refs/changes", true),
+        (&["refs/changes/4"], "refs/changes/42/1", false),
+        (&["refs/changes", "!refs/changes/42"], "refs/changes/42/1", false),
+        (&["refs/changes", "!refs/changes/42"], "refs/changes/43/1", true),
+        (&["!refs/changes/42", "refs/changes"], "refs/changes/42/1", true),
+        (
+            &["refs/changes", "!refs/changes/42", "refs/changes/42/1"],
+            "refs/changes/42/1",
+            true,
+        ),
+        (
+            &["refs/changes", "!refs/changes/42", "refs/changes/42/1"],
+            "refs/changes/42/2",
+            false,
+        ),
+        (&["refs/heads/master"], "refs/heads/master", true),
+        (&["^refs/heads/master"], "refs/heads/master", false),
+        (&["^refs/namespaces/foo/refs/heads"], "refs/heads/master", true),
+        (&["^refs/namespaces", "!refs/heads/master"], "refs/heads/master", false),
+        (&["refs/heads", "!^refs/namespaces/foo"], "refs/heads/master", false),
+        (&["^!refs/heads"], "refs/heads/master", false),
+        (&[], "refs/heads/master", false),
+    ] {
+        let matcher = Matcher::new(patterns.iter().copied());
+        let full_name = format!("{ns}{name}");
+        assert_eq!(
+            matcher.is_hidden(Some(name.into()), full_name.as_str().into()),
+            expected,
+            "{patterns:?} on {name}"
+        );
+    }
+}
+
+#[test]
+fn references_outside_of_the_namespace_only_match_full_names() {
+    let full_name = "refs/namespaces/other/refs/heads/main".into();
+    assert!(!Matcher::new(["refs/heads"]).is_hidden(None, full_name));
+    assert!(!Matcher::new(["refs/namespaces"]).is_hidden(None, full_name));
+    assert!(Matcher::new(["^refs/namespaces/other"]).is_hidden(None, full_name));
+    assert!(!Matcher::new(["^refs/namespaces", "!^refs/namespaces/other"]).is_hidden(None, full_name));
+}
+
+#[test]
+fn configuration_is_used_in_order_of_appearance() -> crate::Result {
+    let tmp = gix_testtools::tempfile::tempdir()?;
+    let repo = gix::init_bare(tmp.path())?;
+    let config_path = repo.git_dir().join("config");
+    let mut config = std::fs::read_to_string(&config_path)?;
+    config.push_str(
+        "[transfer]\n\thideRefs = refs/changes\n\
+         [uploadpack]\n\thideRefs = !refs/changes/42/\n\
+         [receive]\n\thideRefs = refs/internal\n\
+         [transfer]\n\thideRefs = refs/changes/42/1\n\
+         [uploadPack]\n\tHIDEREFS = refs/pull\n",
+    );
+    std::fs::write(&config_path, config)?;
+    let repo = gix::open_opts(repo.git_dir(), gix::open::Options::isolated())?;
+
+    let upload_pack = repo.hidden_references(Service::UploadPack);
+    assert_eq!(
+        upload_pack,
+        Matcher::new(["refs/changes", "!refs/changes/42", "refs/changes/42/1", "refs/pull"]),
+        "transfer.hideRefs and uploadpack.hideRefs are interleaved, and names are case-insensitive"
+    );
+    for (name, expected) in [
+        ("refs/changes/42/1", true),
+        ("refs/changes/42/2", false),
+        ("refs/changes/43/1", true),
+        ("refs/internal/x", false),
+        ("refs/pull/1/head", true),
+    ] {
+        assert_eq!(
+            upload_pack.is_hidden(Some(name.into()), name.into()),
+            expected,
+            "{name}"
+        );
+    }
+
+    let receive_pack = repo.hidden_references(Service::ReceivePack);
+    assert_eq!(
+        receive_pack,
+        Matcher::new(["refs/changes", "refs/internal", "refs/changes/42/1"])
+    );
+    assert!(receive_pack.is_hidden(Some("refs/changes/42/2".into()), "refs/changes/42/2".into()));
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use std::collections::BTreeSet;
+
+use gix::{bstr::ByteSlice, reference::lookup::ByTarget};
+
+use crate::util::{hex_to_id, named_subrepo_opts, restricted};
+
+fn repo() -> crate::Result<gix::Repository> {
+    Ok(named_subrepo_opts("make_for_each_ref_repo.sh", "clone", restricted())?)
+}
+
+fn baseline(name: &str) -> crate::Result<String> {
+    let path = gix_testtools::scripted_fixture_read_only("make_for_each_ref_repo.sh")?.join(format!("{name}.baseline"));
+    Ok(std::fs::read_to_string(path)?)
+}
+
+fn names_at(index: &ByTarget, id: &gix::oid) -> BTreeSet<String> {
+    index.get(id).map(|entry| entry.name.as_bstr().to_string()).collect()
+}
+
+#[test]
+fn decorations_match_git_log() -> crate::Result {
+    let repo = repo()?;
+    let index = repo.references_by_target()?;
+    let decorations = baseline("decorations")?;
+    let mut num_commits = 0;
+    for line in decorations.lines() {
+        let (id, decoration) = line.split_once('\t').expect("id and decoration");
+        let id = hex_to_id(id);
+        let expected: BTreeSet<_> = decoration
+            .split(", ")
+            .map(|name| name.trim_start_matches("HEAD -> "))
+            .map(|name| match name.strip_prefix("tag: ") {
+                Some(tag) => {
+                    assert!(tag.starts_with("refs/tags/"));
+                    tag
+                }
+                None => name,
+            })
+            .map(ToOwned::to_owned)
+            .collect();
+        assert_eq!(names_at(&index, &id), expected, "{id}");
+        num_commits += 1;
+    }
+    assert_eq!(num_commits, 6, "all commits with decorations were checked");
+    assert_eq!(index.len(), 14, "all references are indexed");
+    Ok(())
+}
+
+#[test]
+fn annotated_tags_are_found_by_the_tag_and_the_commit() -> crate::Result {
+    let repo = repo()?;
+    let index = repo.references_by_target()?;
+    let commit = repo.rev_parse_single("v1.9")?.detach();
+    let entry = index
+        .by_name("refs/tags/v1.10".try_into()?)
+        .expect("annotated tag is present");
+    assert!(entry.is_peeled());
+    assert_eq!(entry.peeled, commit);
+    assert_eq!(repo.find_object(entry.target)?.kind, gix_object::Kind::Tag);
+    assert_eq!(
+        names_at(&index, &entry.target),
+        ["refs/tags/v1.10"].map(ToOwned::to_owned).into(),
+        "the tag object is only decorated by the reference pointing at it"
+    );
+
+    let lightweight = index.by_name("refs/tags/v1.9".try_into()?).expect("present");
+    assert!(!lightweight.is_peeled());
+    assert_eq!(lightweight.target, commit);
+
+    let symbolic = index
+        .by_name("refs/remotes/origin/HEAD".try_into()?)
+        .expect("symbolic refs are followed");
+    assert_eq!(symbolic.target, repo.rev_parse_single("origin/main")?.detach());
+    Ok(())
+}
+
+#[test]
+fn containing_matches_git_for_each_ref() -> crate::Result {
+    let repo = repo()?;
+    let index = repo.references_by_target()?;
+    for (name, rev) in [
+        ("contains-main", "main"),
+        ("contains-other", "origin/other"),
+        ("contains-first", "origin/other~1"),
+        ("contains-feature", "origin/feature"),
+    ] {
+        let commit = repo.rev_parse_single(rev)?.detach();
+        let actual: Vec<_> = index
+            .containing(&repo, &commit, |_| true)?
+            .into_iter()
+            .map(|entry| entry.name.as_bstr().to_string())
+            .collect();
+        let expected: Vec<_> = baseline(name)?.lines().map(ToOwned::to_owned).collect();
+        assert_eq!(actual, expected, "{rev}");
+    }
+
+    let commit = repo.rev_parse_single("origin/other")?.detach();
+    let branches = index.containing(&repo, &commit, |entry| {
+        entry.name.category() == Some(gix::reference::Category::LocalBranch)
+    })?;
+    assert_eq!(
+        branches
+            .iter()
+            .map(|entry| entry.name.shorten().to_str_lossy().into_owned())
+            .collect::<Vec<_>>(),
+        ["ahead-and-behind", "feature", "gone", "main"],
+        "`behind` is based on the parent of the commit"
+    );
+    Ok(())
+}
+
+#[test]
+fn updates_reflect_changed_references() -> crate::Result {
+    let dir = gix_testtools::scripted_fixture_writable("make_for_each_ref_repo.sh")?;
+    let repo = gix::open_opts(dir.path().join("clone"), restricted())?;
+    let mut index = repo.references_by_target()?;
+    let main = repo.rev_parse_single("main")?.detach();
+    let behind = repo.rev_parse_single("behind")?.detach();
+
+    let mut branch = repo.find_reference("main")?;
+    branch.set_target_id(behind, "move main")?;
+    repo.find_reference("gone")?.delete()?;
+    let new_name = "refs/heads/new";
+    repo.reference(
+        new_name,
+        main,
+        gix::refs::transaction::PreviousValue::MustNotExist,
+        "create",
+    )?;
+
+    let names = ["refs/heads/main", "refs/heads/gone", new_name]
+        .map(|name| gix::refs::FullName::try_from(name).expect("valid"));
+    index.update(&repo, names.iter().map(AsRef::as_ref))?;
+    assert_eq!(
+        names_at(&index, &main),
+        ["refs/heads/feature", "refs/heads/new"].map(ToOwned::to_owned).into()
+    );
+    assert_eq!(
+        names_at(&index, &behind),
+        ["refs/heads/behind", "refs/heads/main"].map(ToOwned::to_owned).into()
+    );
+    assert!(index.by_name("refs/heads/gone".try_into()?).is_none());
+    assert_eq!(index.len(), 14);
+    Ok(())
+}
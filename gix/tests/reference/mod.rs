@@ -101,4 +101,8 @@ fn set_target_id() {
         .starts_with("Reference \"refs/heads/main\" was supposed to exist"));
 }
 
+#[cfg(feature = "revision")]
+mod format;
+mod hidden;
+mod lookup;
 mod remote;
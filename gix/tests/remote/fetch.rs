@@ -112,30 +112,373 @@ mod blocking_and_async_io {
         Ok(())
     }
 
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn fetch_prunes_tracking_refs_of_deleted_remote_branches_only_if_enabled() -> crate::Result {
+        use gix::{
+            config::tree::Fetch as FetchKey,
+            refs::transaction::{Change, PreviousValue, RefEdit},
+        };
+
+        fn fetch(remote: &gix::Remote<'_>) -> crate::Result<fetch::refs::update::Outcome> {
+            let outcome = remote
+                .connect(Fetch)?
+                .prepare_fetch(gix::progress::Discard, Default::default())?
+                .receive(gix::progress::Discard, &AtomicBool::default())?;
+            Ok(match outcome.status {
+                Status::Change { update_refs, .. } | Status::NoPackReceived { update_refs, .. } => update_refs,
+            })
+        }
+
+        for (remote_prune, fetch_prune, expect_pruned) in [
+            (None, None, false),
+            (None, Some(true), true),
+            (Some(true), None, true),
+            (Some(false), Some(true), false),
+        ] {
+            let (mut repo, tmp) = repo_rw("two-origins");
+            if let Some(value) = fetch_prune {
+                repo.config_snapshot_mut()
+                    .set_raw_value(&FetchKey::PRUNE, if value { "true" } else { "false" })?;
+            }
+            let remote_repo = gix::open_opts(tmp.path().join("clone-as-base-with-changes"), crate::restricted())?;
+            let head_id = remote_repo.head_id()?;
+            for name in ["refs/heads/gone", "refs/heads/explicit"] {
+                remote_repo.reference(name, head_id, PreviousValue::MustNotExist, "create")?;
+            }
+
+            let mut remote = repo.find_remote("changes-on-top-of-origin")?.with_refspecs(
+                Some("refs/heads/explicit:refs/remotes/changes-on-top-of-origin/explicit"),
+                Fetch,
+            )?;
+            if let Some(value) = remote_prune {
+                remote = remote.with_prune(value);
+            }
+            let outcome = fetch(&remote)?;
+            assert!(outcome.pruned.is_empty(), "nothing to prune yet");
+            for name in [
+                "refs/remotes/changes-on-top-of-origin/gone",
+                "refs/remotes/changes-on-top-of-origin/explicit",
+            ] {
+                assert!(
+                    repo.try_find_reference(name)?.is_some(),
+                    "{name} was created by the first fetch"
+                );
+            }
+
+            let symbolic_name = "refs/remotes/changes-on-top-of-origin/HEAD";
+            repo.edit_reference(RefEdit {
+                change: Change::Update {
+                    log: Default::default(),
+                    expected: PreviousValue::MustNotExist,
+                    new: gix::refs::Target::Symbolic("refs/remotes/changes-on-top-of-origin/main".try_into()?),
+                },
+                name: symbolic_name.try_into()?,
+                deref: false,
+            })?;
+            for name in ["refs/heads/gone", "refs/heads/explicit"] {
+                remote_repo.find_reference(name)?.delete()?;
+            }
+            let outcome = fetch(&remote)?;
+            let pruned: Vec<_> = outcome
+                .pruned
+                .iter()
+                .map(|edit| edit.name.as_bstr().to_string())
+                .collect();
+            assert_eq!(
+                repo.try_find_reference("refs/remotes/changes-on-top-of-origin/gone")?
+                    .is_none(),
+                expect_pruned,
+                "{remote_prune:?} {fetch_prune:?}: the remote-level setting overrides `fetch.prune`"
+            );
+            if expect_pruned {
+                assert_eq!(pruned, ["refs/remotes/changes-on-top-of-origin/gone"]);
+                assert!(
+                    outcome
+                        .edits
+                        .iter()
+                        .all(|edit| !edit.name.as_bstr().ends_with(b"/gone")),
+                    "pruned refs are only listed separately"
+                );
+            } else {
+                assert!(pruned.is_empty());
+            }
+            assert!(
+                repo.try_find_reference("refs/remotes/changes-on-top-of-origin/explicit")?
+                    .is_some(),
+                "destinations of refspecs without pattern are never pruned"
+            );
+            assert!(
+                repo.try_find_reference("refs/remotes/changes-on-top-of-origin/main")?
+                    .is_some(),
+                "refs that still exist on the remote are kept"
+            );
+            assert!(
+                repo.try_find_reference(symbolic_name)?.is_some(),
+                "symbolic refs are never pruned, even though there is no remote branch of the same name"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn summary_lines_match_the_ones_of_git() -> crate::Result {
+        use gix::remote::fetch::refs::summary::Flag;
+
+        let tmp = gix_testtools::scripted_fixture_writable_with_args(
+            "make_fetch_summary_repos.sh",
+            None::<String>,
+            gix_testtools::Creation::ExecuteScript,
+        )?;
+        let repo = gix::open_opts(tmp.path().join("local-before-fetch"), crate::restricted())?;
+        let remote = repo
+            .find_remote("origin")?
+            .with_prune(true)
+            .with_fetch_tags(fetch::Tags::All);
+        let outcome = remote
+            .connect(Fetch)?
+            .prepare_fetch(gix::progress::Discard, Default::default())?
+            .receive(gix::progress::Discard, &AtomicBool::default())?;
+        let update_refs = match outcome.status {
+            Status::Change { update_refs, .. } | Status::NoPackReceived { update_refs, .. } => update_refs,
+        };
+        let lines = update_refs.summary_lines(&repo, &outcome.ref_map.mappings)?;
+
+        // Align like `git` does, which also accounts for lines it only displays with `--verbose`.
+        // Pruned references are displayed before the width of the remote column is known, so they use the minimum.
+        let summary_width = 2 * repo.id_shortener()?.hex_len() + 3;
+        let min_remote_width = 10;
+        let updates_remote_width = lines
+            .iter()
+            .filter(|line| line.flag != Flag::Pruned)
+            .map(|line| line.remote.len())
+            .max()
+            .unwrap_or_default()
+            .max(min_remote_width);
+        let mut actual: Vec<_> = lines
+            .iter()
+            .map(|line| {
+                let remote_width = if line.flag == Flag::Pruned {
+                    min_remote_width
+                } else {
+                    updates_remote_width
+                };
+                let mut out = format!(
+                    " {} {:<summary_width$} {:<remote_width$} -> {}",
+                    line.flag.as_char(),
+                    line.summary.to_string(),
+                    line.remote.to_string(),
+                    line.local
+                );
+                if let Some(reason) = &line.reason {
+                    out.push_str(&format!("  ({reason})"));
+                }
+                out
+            })
+            .collect();
+        let baseline = std::fs::read_to_string(tmp.path().join("fetch-summary-baseline"))?;
+        let mut expected: Vec<_> = baseline.lines().map(ToOwned::to_owned).collect();
+        assert_eq!(actual.first(), expected.first(), "pruned references come first");
+        actual.sort();
+        expected.sort();
+        assert_eq!(actual, expected, "the order of updates may differ, but each line is the same");
+        Ok(())
+    }
+
     #[test]
     #[cfg(feature = "blocking-network-client")]
     fn fetch_with_alternates_adds_tips_from_alternates() -> crate::Result<()> {
-        let tmp = gix_testtools::tempfile::TempDir::new()?;
         let remote_repo = remote::repo("base");
+        let (num_objects, tips) = clone_server_with_alternate(&remote_repo, None::<&str>)?;
+        assert_eq!(
+            tips,
+            alternate_tips(&remote_repo)?,
+            "as the clone has no references, all tips are from the alternate"
+        );
+        assert_eq!(
+            num_objects, 66,
+            "the alternate doesn't share history with the remote, so it can't reduce the pack size"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn fetch_with_alternates_sharing_history_receives_smaller_pack() -> crate::Result<()> {
+        let client = remote::repo("multi_round/client");
+        let b1 = client.find_reference("refs/heads/b1")?.peel_to_id_in_place()?.detach();
+
+        let (num_objects_without_alternate_tips, tips) =
+            clone_server_with_alternate(&client, Some("core.alternateRefsPrefixes=refs/notes/"))?;
+        assert!(
+            tips.is_empty(),
+            "the alternate has no notes, so there are no tips at all"
+        );
+
+        let (num_objects, tips) = clone_server_with_alternate(&client, None::<&str>)?;
+        assert_eq!(
+            tips,
+            alternate_tips(&client)?,
+            "all references of the alternate are used"
+        );
+        assert!(tips.contains(&b1));
+        assert!(
+            num_objects < num_objects_without_alternate_tips,
+            "the history of b1 is in the alternate and isn't sent again: {num_objects} < {num_objects_without_alternate_tips}"
+        );
+
+        for overrides in [
+            "core.alternateRefsPrefixes=refs/heads/b1 refs/notes/",
+            r#"core.alternateRefsCommand=f() { git --git-dir="$1" rev-parse refs/heads/b1; }; f"#,
+        ] {
+            let (num_objects_with_b1_only, tips) = clone_server_with_alternate(&client, Some(overrides))?;
+            assert_eq!(tips, [b1], "{overrides}: only b1 is advertised by the alternate");
+            assert_eq!(
+                num_objects_with_b1_only, num_objects,
+                "{overrides}: b1 is the only branch the remote shares with the alternate"
+            );
+        }
+
+        let (num_objects_with_failing_command, tips) =
+            clone_server_with_alternate(&client, Some("core.alternateRefsCommand=false"))?;
+        assert!(tips.is_empty(), "failing commands are ignored, like in git");
+        assert_eq!(num_objects_with_failing_command, num_objects_without_alternate_tips);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn fetch_with_promisor_skips_alternate_tips_with_missing_history() -> crate::Result<()> {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let alternate = gix::init_bare(tmp.path())?;
+        let signature = gix::actor::Signature {
+            name: "gitoxide".into(),
+            email: "gitoxide@localhost".into(),
+            time: gix::date::Time::new(0, 0),
+        };
+        let tip = alternate
+            .write_object(gix::objs::Commit {
+                tree: alternate.empty_tree().id,
+                parents: Some(hex_to_id("dc6ab1e31cbbd5b0d5da4b9ef3e51ca56e7a1bf8"))
+                    .into_iter()
+                    .collect(),
+                author: signature.clone(),
+                committer: signature,
+                encoding: None,
+                message: "the parent is missing".into(),
+                extra_headers: Vec::new(),
+            })?
+            .detach();
+        alternate.reference(
+            "refs/heads/main",
+            tip,
+            gix::refs::transaction::PreviousValue::MustNotExist,
+            "a commit without its parent",
+        )?;
+
+        let (_num_objects, tips) = clone_server_with_alternate(&alternate, None::<&str>)?;
+        assert_eq!(tips, [tip], "without a promisor, missing history isn't checked for");
+
+        let (_num_objects, tips) = clone_server_with_alternate(&alternate, Some("remote.origin.promisor=true"))?;
+        assert!(
+            tips.is_empty(),
+            "objects may be missing with a promisor remote, so tips with missing parents aren't used"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn fetch_with_filter_omits_blobs_and_marks_the_pack_as_promisor_pack() -> crate::Result<()> {
+        let server = gix_testtools::scripted_fixture_read_only("make_object_filter_specs.sh")?.join("repo");
+        let tmp = TempDir::new()?;
+        let repo = gix::init_bare(tmp.path())?;
+        let out = repo
+            .remote_at(server.as_path())?
+            .with_refspecs(Some("refs/heads/*:refs/remotes/origin/*"), Fetch)?
+            .connect(Fetch)?
+            .prepare_fetch(progress::Discard, Default::default())?
+            .with_filter(fetch::filter::Spec::BlobNone)
+            .receive(progress::Discard, &AtomicBool::default())?;
+        assert_promisor_pack_without_blobs(&repo, out)
+    }
+
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn fetch_from_promisor_remote_uses_configured_filter() -> crate::Result<()> {
+        let server = gix_testtools::scripted_fixture_read_only("make_object_filter_specs.sh")?.join("repo");
+        let tmp = TempDir::new()?;
+        let (repo, out) = gix::clone::PrepareFetch::new(
+            server.as_path(),
+            tmp.path(),
+            gix::create::Kind::Bare,
+            Default::default(),
+            gix::open::Options::isolated()
+                .config_overrides(["remote.origin.promisor=true", "remote.origin.partialCloneFilter=blob:none"]),
+        )?
+        .fetch_only(progress::Discard, &AtomicBool::default())?;
+        assert_promisor_pack_without_blobs(&repo, out)
+    }
+
+    #[cfg(feature = "blocking-network-client")]
+    fn assert_promisor_pack_without_blobs(repo: &gix::Repository, out: fetch::Outcome) -> crate::Result<()> {
+        let Status::Change { write_pack_bundle, .. } = out.status else {
+            unreachable!("the remote has objects that we don't have")
+        };
+        assert_eq!(
+            write_pack_bundle.index.num_objects, 5,
+            "two commits and three trees, but no blobs"
+        );
+        let pack = write_pack_bundle.data_path.expect("a new pack was written");
+        assert!(
+            pack.with_extension("promisor").is_file(),
+            "packs received with a filter are marked so missing objects are expected"
+        );
+        let tip = repo
+            .find_reference("refs/remotes/origin/main")
+            .or_else(|_| repo.find_reference("refs/heads/main"))?
+            .id();
+        let tree = tip.object()?.into_commit().tree()?;
+        let blob = tree.find_entry("a").expect("present").object_id();
+        assert!(!repo.has_object(blob), "blobs were filtered");
+        Ok(())
+    }
+
+    /// Return the sorted and unique commits that all references of `repo` point to.
+    #[cfg(feature = "blocking-network-client")]
+    fn alternate_tips(repo: &gix::Repository) -> crate::Result<Vec<gix::ObjectId>> {
+        let mut tips = Vec::new();
+        for reference in repo.references()?.all()?.peeled() {
+            tips.push(reference?.id().detach());
+        }
+        tips.sort();
+        tips.dedup();
+        Ok(tips)
+    }
+
+    /// Clone the `multi_round/server` repository with `alternate` as alternate object database, and return the
+    /// amount of objects in the received pack along with the tips used in the negotiation.
+    #[cfg(feature = "blocking-network-client")]
+    fn clone_server_with_alternate(
+        alternate: &gix::Repository,
+        config_overrides: impl IntoIterator<Item = impl Into<gix::bstr::BString>>,
+    ) -> crate::Result<(u32, Vec<gix::ObjectId>)> {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let alternate_objects = gix::path::realpath(alternate.objects.store_ref().path())?;
         let (_repo, out) = gix::clone::PrepareFetch::new(
             remote::repo("multi_round/server").path(),
             tmp.path(),
             gix::create::Kind::Bare,
             Default::default(),
-            gix::open::Options::isolated(),
+            gix::open::Options::isolated().config_overrides(config_overrides),
         )?
-        .configure_remote({
-            move |r| {
-                std::fs::write(
-                    r.repo().objects.store_ref().path().join("info").join("alternates"),
-                    format!(
-                        "{}\n",
-                        gix::path::realpath(remote_repo.objects.store_ref().path())?.display()
-                    )
-                    .as_bytes(),
-                )?;
-                Ok(r)
-            }
+        .configure_remote(move |r| {
+            std::fs::write(
+                r.repo().objects.store_ref().path().join("info").join("alternates"),
+                format!("{}\n", alternate_objects.display()).as_bytes(),
+            )?;
+            Ok(r)
         })
         .fetch_only(gix::progress::Discard, &AtomicBool::default())?;
 
@@ -144,20 +487,9 @@ mod blocking_and_async_io {
                 negotiate,
                 write_pack_bundle,
                 ..
-            } => {
-                assert_eq!(
-                    negotiate.rounds.len(),
-                    1,
-                    "we don't really have a way to see that tips from alternates were added, I think"
-                );
-                assert_eq!(
-                    write_pack_bundle.index.num_objects, 66,
-                    "this test just exercises code for adding alternate-repo tips to the negotiator"
-                );
-            }
-            _ => unreachable!("we get a pack as alternates are unrelated"),
+            } => Ok((write_pack_bundle.index.num_objects, negotiate.tips)),
+            _ => unreachable!("we get a pack as the remote always has a commit that the alternate doesn't have"),
         }
-        Ok(())
     }
 
     #[maybe_async::test(
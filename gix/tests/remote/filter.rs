@@ -0,0 +1,107 @@
+use gix::{
+    bstr::ByteSlice,
+    remote::fetch::filter::{parse, Spec},
+};
+
+#[test]
+fn parse_accepts_and_rejects_specs_like_git() -> crate::Result {
+    let dir = gix_testtools::scripted_fixture_read_only("make_object_filter_specs.sh")?;
+    let baseline = std::fs::read(dir.join("baseline"))?;
+    let mut count = 0;
+    for line in baseline.lines() {
+        let (status, spec) = line.split_once_str(" ").expect("status and spec");
+        let res = parse(spec.as_bstr());
+        match status {
+            b"ok" => {
+                let parsed = res.unwrap_or_else(|err| panic!("'{}' should be accepted: {err}", spec.as_bstr()));
+                assert_eq!(
+                    parse(parsed.to_bstring().as_ref())?,
+                    parsed,
+                    "the canonical form of '{}' round-trips",
+                    spec.as_bstr()
+                );
+            }
+            b"err" => assert!(res.is_err(), "'{}' should be rejected, got {res:?}", spec.as_bstr()),
+            _ => unreachable!("unknown status"),
+        }
+        count += 1;
+    }
+    assert_ne!(count, 0, "the baseline isn't empty");
+    Ok(())
+}
+
+#[test]
+fn sizes_and_depths() -> crate::Result {
+    for (spec, expected) in [
+        ("blob:limit=0", Spec::BlobLimit(0)),
+        ("blob:limit=1k", Spec::BlobLimit(1024)),
+        ("blob:limit=2M", Spec::BlobLimit(2 * 1024 * 1024)),
+        ("blob:limit=3g", Spec::BlobLimit(3 * 1024 * 1024 * 1024)),
+        ("blob:limit=0x10", Spec::BlobLimit(16)),
+        ("blob:limit=010", Spec::BlobLimit(8)),
+        ("blob:limit=18446744073709551615", Spec::BlobLimit(u64::MAX)),
+        ("tree:0", Spec::TreeDepth(0)),
+        ("tree:1k", Spec::TreeDepth(1024)),
+    ] {
+        assert_eq!(parse(spec.into())?, expected, "{spec}");
+    }
+    Ok(())
+}
+
+#[test]
+fn combine() -> crate::Result {
+    let spec = parse("combine:blob%3Anone+combine%3Atree%3A1%2Bobject%3Atype%3Dtree+sparse:oid=main:sparse".into())?;
+    assert_eq!(
+        spec,
+        Spec::Combine(vec![
+            Spec::BlobNone,
+            Spec::Combine(vec![Spec::TreeDepth(1), Spec::ObjectType(gix::object::Kind::Tree)]),
+            Spec::SparseOid("main:sparse".into()),
+        ])
+    );
+    assert_eq!(
+        spec.to_string(),
+        "combine:blob:none+combine:tree:1%2bobject:type=tree+sparse:oid=main:sparse",
+        "sub-filters are escaped like git does"
+    );
+
+    let spec = Spec::Combine(vec![Spec::SparseOid("HEAD:a b~%".into()), Spec::BlobLimit(1024)]);
+    assert_eq!(spec.to_bstring(), "combine:sparse:oid=HEAD:a%20b%7e%25+blob:limit=1024");
+    assert_eq!(parse(spec.to_bstring().as_ref())?, spec);
+    Ok(())
+}
+
+#[test]
+fn errors() {
+    for (spec, expected) in [
+        ("tree:x", "expected 'tree:<depth>', got 'tree:x'"),
+        ("object:type=Blob", "'Blob' for 'object:type=<type>' is not a valid object type"),
+        ("sparse:path=a", "sparse:path filters support has been dropped"),
+        ("combine:", "expected something after combine:"),
+        ("combine:tree:1~", "must escape char in sub-filter-spec: '~'"),
+        ("combine:blob:none+", "invalid filter-spec ''"),
+    ] {
+        let err = parse(spec.into()).expect_err(spec);
+        assert_eq!(err.to_string(), expected, "{spec}");
+    }
+}
+
+#[test]
+fn sparse_oid_must_be_an_object_id_or_a_blob_path() -> crate::Result {
+    use gix::remote::fetch::filter::{init, Filter};
+    let repo = crate::util::named_subrepo_opts("make_object_filter_specs.sh", "repo", crate::util::restricted())?;
+    let blob = repo.rev_parse_single("HEAD:a")?;
+    for revision in [blob.to_string().as_str(), "HEAD:a", "HEAD~1:a"] {
+        Filter::new(&Spec::SparseOid(revision.into()), &repo)?;
+    }
+    for revision in [":/c1", ":a", "HEAD", "a", &blob.to_string()[..7]] {
+        let err = Filter::new(&Spec::SparseOid(revision.into()), &repo)
+            .err()
+            .expect("only object ids and blob paths are accepted");
+        assert!(
+            matches!(err, init::Error::SparseInvalidRevision { .. }),
+            "{revision}: {err:?}"
+        );
+    }
+    Ok(())
+}
@@ -112,6 +112,66 @@ mod http {
         assert_eq!(http_version, Some(HttpVersion::V1_1));
     }
 
+    #[test]
+    fn url_scoped_configuration() -> crate::Result {
+        let dir = gix_testtools::scripted_fixture_read_only("make_http_url_config_repo.sh")?;
+        let mut permissions = gix::open::Permissions::isolated();
+        permissions.config.includes = true;
+        let repo = gix::open_opts(
+            dir.join("http-url-scoped"),
+            gix::open::Options::default()
+                .permissions(permissions)
+                .strict_config(true),
+        )?;
+
+        for url in ["https://github.com/org/repo", "https://example.com/repo"] {
+            let opts = http_options(&repo, None, url);
+            assert!(opts.ssl_verify, "{url}");
+            assert_eq!(opts.ssl_ca_info, None, "{url}");
+            assert_eq!(opts.proxy.as_deref(), Some("http://proxy.example.com:3128"), "{url}");
+            assert_eq!(opts.extra_headers, ["X-Everyone: 1"], "{url}");
+            assert_eq!(opts.low_speed_limit_bytes_per_second, 1000, "{url}");
+            assert_eq!(opts.low_speed_time_seconds, 30, "{url}");
+            assert_eq!(opts.user_agent.as_deref(), Some("default-agent"), "{url}");
+            assert_eq!(opts.follow_redirects, FollowRedirects::Initial, "{url}");
+        }
+
+        let opts = http_options(&repo, None, "https://internal.example.com/team/repo.git");
+        assert!(!opts.ssl_verify, "wildcards match a single host component");
+        assert_eq!(
+            opts.ssl_ca_info.as_deref(),
+            Some(std::path::Path::new("/etc/internal-ca.pem"))
+        );
+        assert_eq!(opts.proxy.as_deref(), Some(""), "empty values override as well");
+        assert_eq!(
+            opts.extra_headers,
+            ["X-Everyone: 1", "X-Example: 1"],
+            "multi-valued keys accumulate the values of all matching sections"
+        );
+        assert_eq!(opts.low_speed_time_seconds, 60, "path prefixes match");
+        assert_eq!(opts.low_speed_limit_bytes_per_second, 1000);
+        assert_eq!(opts.user_agent.as_deref(), Some("wildcard-agent"));
+        assert_eq!(
+            opts.follow_redirects,
+            FollowRedirects::Initial,
+            "sections with a user name only match URLs with the same user"
+        );
+
+        let opts = http_options(&repo, None, "https://jane@internal.example.com/team/secret.git");
+        assert!(
+            opts.ssl_verify,
+            "the longest matching host wins, and then the longest matching path"
+        );
+        assert_eq!(opts.follow_redirects, FollowRedirects::None);
+        assert_eq!(opts.low_speed_time_seconds, 60);
+
+        let opts = http_options(&repo, None, "http://internal.example.com/repo");
+        assert!(opts.ssl_verify, "the scheme must match");
+        assert_eq!(opts.user_agent.as_deref(), Some("plain-http-agent"));
+        assert_eq!(opts.proxy.as_deref(), Some("http://proxy.example.com:3128"));
+        Ok(())
+    }
+
     #[test]
     fn http_ssl_cainfo_suppressed_by_() {
         let repo = repo("http-disabled-cainfo");
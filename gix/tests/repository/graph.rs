@@ -0,0 +1,36 @@
+use gix::commitgraph::write::info_dir::{Mode, Split};
+
+#[test]
+fn write_commit_graph() -> crate::Result {
+    let (mut repo, _keep) = crate::basic_rw_repo()?;
+    let outcome = repo
+        .write_commit_graph(Mode::Split(Split::default()))?
+        .expect("there is no commit-graph yet");
+    assert_eq!(outcome.num_commits, 2, "all commits reachable from references");
+    assert!(outcome.generation_data, "generation version 2 is the default");
+
+    let graph = repo.commit_graph()?;
+    for info in repo.head_id()?.ancestors().all()? {
+        assert!(graph.lookup(info?.id).is_some());
+    }
+    assert_eq!(
+        repo.write_commit_graph(Mode::Split(Split::default()))?,
+        None,
+        "all commits are already in the commit-graph chain"
+    );
+
+    repo.config_snapshot_mut()
+        .set_value(&gix::config::tree::CommitGraph::GENERATION_VERSION, "1")?;
+    let outcome = repo.write_commit_graph(Mode::Single)?.expect("always written");
+    assert_eq!(outcome.num_commits, 2);
+    assert!(!outcome.generation_data);
+    assert!(repo
+        .objects
+        .store_ref()
+        .path()
+        .join("info")
+        .join("commit-graph")
+        .is_file());
+    assert_eq!(repo.commit_graph()?.num_commits(), 2);
+    Ok(())
+}
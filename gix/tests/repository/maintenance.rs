@@ -0,0 +1,171 @@
+use gix::bstr::ByteSlice;
+use gix::config::tree::Gc;
+use gix::odb::maintenance::Reason;
+use gix::reference::log::expire::{Cutoff, Options};
+
+#[test]
+fn gc_advice_respects_configured_thresholds() -> crate::Result {
+    let (mut repo, _keep) = crate::basic_rw_repo()?;
+    let advice = repo.gc_advice()?;
+    assert!(!advice.is_needed(), "a small repository doesn't need maintenance");
+
+    let mut blob_no = 0;
+    let mut num_sampled = advice.estimated_loose_objects / 256;
+    while num_sampled < 2 {
+        blob_no += 1;
+        let id = repo.write_blob(format!("blob {blob_no}"))?;
+        if id.as_bytes()[0] == 0x17 {
+            num_sampled += 1;
+        }
+    }
+    assert!(
+        !repo.gc_advice()?.is_needed(),
+        "two objects in the sampled directory are far from the default threshold"
+    );
+
+    repo.config_snapshot_mut().set_value(&Gc::AUTO, "1")?;
+    let advice = repo.gc_advice()?;
+    assert_eq!(advice.estimated_loose_objects, 2 * 256);
+    assert_eq!(
+        advice.reasons,
+        [Reason::TooManyLooseObjects],
+        "more than one object in the sampled directory exceeds the threshold"
+    );
+
+    repo.config_snapshot_mut().set_value(&Gc::AUTO, "-1")?;
+    assert!(!repo.gc_advice()?.is_needed(), "negative values disable the check");
+    Ok(())
+}
+
+/// Entries created up to and including the second commit are older than this.
+const TOTAL: i64 = 1112911993 + 90;
+/// Entries created up to and including the reset that made two commits unreachable are older than this.
+const UNREACHABLE: i64 = 1112911993 + 270;
+const REFLOGS: [&str; 4] = ["HEAD", "refs/heads/main", "refs/heads/side", "refs/stash"];
+
+#[test]
+fn expire_reflogs_matches_git() -> crate::Result {
+    for (expected, options) in [
+        (
+            "total",
+            Options {
+                expire: Some(Cutoff::OlderThan(TOTAL)),
+                expire_unreachable: Some(Cutoff::Never),
+                ..Default::default()
+            },
+        ),
+        (
+            "unreachable",
+            Options {
+                expire: Some(Cutoff::Never),
+                expire_unreachable: Some(Cutoff::OlderThan(UNREACHABLE)),
+                ..Default::default()
+            },
+        ),
+        (
+            "rewrite",
+            Options {
+                expire: Some(Cutoff::Never),
+                expire_unreachable: Some(Cutoff::OlderThan(UNREACHABLE)),
+                rewrite: true,
+                ..Default::default()
+            },
+        ),
+        (
+            "all",
+            Options {
+                expire: Some(Cutoff::Never),
+                expire_unreachable: Some(Cutoff::Always),
+                ..Default::default()
+            },
+        ),
+    ] {
+        let dir = gix_testtools::scripted_fixture_writable("make_reflog_expire_repo.sh")?;
+        let repo = gix::open_opts(dir.path().join("repo"), crate::restricted())?;
+        repo.expire_reflogs(options)?;
+        assert_reflogs_eq(&dir.path().join("repo"), &dir.path().join(expected), expected)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn expire_reflogs_uses_configuration_per_pattern() -> crate::Result {
+    let dir = gix_testtools::scripted_fixture_writable("make_reflog_expire_repo.sh")?;
+    let repo = gix::open_opts(dir.path().join("config"), crate::restricted())?;
+    let outcome = repo.expire_reflogs(Default::default())?;
+    assert_reflogs_eq(&dir.path().join("config"), &dir.path().join("config-expired"), "config")?;
+    assert_eq!(
+        outcome
+            .logs
+            .iter()
+            .map(|log| (log.name.as_bstr().to_string(), log.dropped.len(), log.kept))
+            .collect::<Vec<_>>(),
+        [
+            ("HEAD".into(), 10, 0),
+            ("refs/heads/main".into(), 5, 1),
+            ("refs/heads/side".into(), 2, 0),
+            ("refs/stash".into(), 0, 1)
+        ],
+        "'gc.reflogExpire=all' drops everything, except for references matching a pattern and the stash"
+    );
+    Ok(())
+}
+
+#[test]
+fn expire_reflogs_dry_run_changes_nothing() -> crate::Result {
+    let dir = gix_testtools::scripted_fixture_writable("make_reflog_expire_repo.sh")?;
+    let repo = gix::open_opts(dir.path().join("repo"), crate::restricted())?;
+    let before = reflogs(&dir.path().join("repo"))?;
+    let outcome = repo.expire_reflogs(Options {
+        expire: Some(Cutoff::Never),
+        expire_unreachable: Some(Cutoff::OlderThan(UNREACHABLE)),
+        dry_run: true,
+        ..Default::default()
+    })?;
+    assert_eq!(reflogs(&dir.path().join("repo"))?, before, "nothing was written");
+    assert_eq!(
+        outcome
+            .logs
+            .iter()
+            .map(|log| (
+                log.name.as_bstr().to_string(),
+                log.dropped
+                    .iter()
+                    .map(|line| line.message.to_str().expect("valid UTF-8"))
+                    .collect::<Vec<_>>()
+            ))
+            .collect::<Vec<_>>(),
+        [
+            (
+                "HEAD".into(),
+                vec!["commit: c3", "commit: c4", "reset: moving to HEAD~2"]
+            ),
+            (
+                "refs/heads/main".into(),
+                vec!["commit: c3", "commit: c4", "reset: moving to HEAD~2"]
+            ),
+            ("refs/heads/side".into(), Vec::<&str>::new()),
+            ("refs/stash".into(), vec![])
+        ],
+        "entries involving commits that aren't reachable anymore are dropped"
+    );
+    Ok(())
+}
+
+fn reflogs(repo: &std::path::Path) -> std::io::Result<Vec<Vec<u8>>> {
+    REFLOGS
+        .iter()
+        .map(|name| std::fs::read(repo.join(".git/logs").join(name)))
+        .collect()
+}
+
+fn assert_reflogs_eq(actual: &std::path::Path, expected: &std::path::Path, case: &str) -> crate::Result {
+    for (name, (actual, expected)) in REFLOGS.iter().zip(reflogs(actual)?.into_iter().zip(reflogs(expected)?)) {
+        assert_eq!(
+            actual.as_bstr(),
+            expected.as_bstr(),
+            "{case}: the reflog of {name} matches the one of git"
+        );
+    }
+    Ok(())
+}
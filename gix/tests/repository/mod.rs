@@ -5,16 +5,22 @@ mod config;
 mod excludes;
 #[cfg(feature = "attributes")]
 mod filter;
+mod graph;
+mod maintenance;
 mod object;
 mod open;
 #[cfg(feature = "attributes")]
 mod pathspec;
+#[cfg(feature = "blocking-network-server")]
+mod receive_pack;
 mod reference;
 mod remote;
 mod shallow;
 mod state;
 #[cfg(feature = "attributes")]
 mod submodule;
+#[cfg(feature = "blocking-network-server")]
+mod upload_pack;
 mod worktree;
 
 #[cfg(feature = "dirwalk")]
@@ -76,6 +82,37 @@ mod dirwalk {
     }
 }
 
+#[cfg(feature = "index")]
+mod index {
+    use gix::config::tree::{Index, Key};
+    use gix::index::Version;
+
+    #[test]
+    fn new_indices_use_the_configured_version() -> crate::Result {
+        let repo = crate::named_repo("make_basic_repo.sh")?;
+        let tree = repo.head_tree_id()?;
+        assert_eq!(repo.index_from_tree(&tree)?.version(), Version::V2, "the default");
+        assert_eq!(repo.index()?.version(), Version::V2);
+
+        let repo = gix::open_opts(
+            repo.git_dir(),
+            gix::open::Options::isolated().config_overrides(Index::VERSION.validated_assignment("4".into())),
+        )?;
+        let index = repo.index_from_tree(&tree)?;
+        assert_eq!(index.version(), Version::V4, "configured for new indices");
+        assert_eq!(
+            repo.index()?.version(),
+            Version::V2,
+            "existing indices keep their version, just like in git"
+        );
+
+        let mut out = Vec::new();
+        let (version, _digest) = index.write_to(&mut out, Default::default())?;
+        assert_eq!(version, Version::V4, "writing uses the version of the index by default");
+        Ok(())
+    }
+}
+
 #[test]
 fn size_in_memory() {
     let actual_size = std::mem::size_of::<Repository>();
@@ -54,6 +54,65 @@ mod write_blob {
     }
 }
 
+mod compat_id {
+    use gix::hash::ObjectId;
+
+    use crate::repository::object::empty_bare_repo;
+
+    fn baseline_id(dir: &std::path::Path, name: &str) -> crate::Result<ObjectId> {
+        Ok(ObjectId::from_hex(
+            std::fs::read_to_string(dir.join(name))?.trim().as_bytes(),
+        )?)
+    }
+
+    #[test]
+    fn written_objects_map_to_the_ids_of_git_in_a_sha256_repository() -> crate::Result {
+        let dir = gix_testtools::scripted_fixture_writable("make_compat_object_format_repo.sh")?;
+        let repo = gix::open_opts(dir.path().join("compat"), crate::restricted())?;
+        assert_eq!(
+            repo.repository_format().compat_object_format,
+            Some(gix::hash::Kind::Sha256)
+        );
+
+        let hello = repo.write_blob(b"hello world")?.detach();
+        let world = repo.write_blob_stream(std::io::Cursor::new(b"world"))?.detach();
+        let hello_sha256 = baseline_id(dir.path(), "hello-world.sha256")?;
+        let world_sha256 = baseline_id(dir.path(), "world.sha256")?;
+        assert_eq!(repo.compat_id(hello)?, Some(hello_sha256));
+        assert_eq!(repo.compat_id(world)?, Some(world_sha256));
+        assert_eq!(
+            repo.compat_id(hello_sha256)?,
+            Some(hello),
+            "compatibility ids map back to the object id"
+        );
+
+        repo.write_blob(b"hello world")?;
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("compat/objects/loose-object-idx"))?,
+            format!("# loose-object-idx\n{hello} {hello_sha256}\n{world} {world_sha256}\n"),
+            "the map is written like git does, and existing objects aren't added again"
+        );
+
+        assert_eq!(
+            repo.compat_id(ObjectId::empty_tree(gix::hash::Kind::Sha1))?,
+            Some(ObjectId::empty_tree(gix::hash::Kind::Sha256)),
+            "the empty tree is always known"
+        );
+        let unknown = ObjectId::from_hex(b"0000000000000000000000000000000000000001")?;
+        assert_eq!(repo.compat_id(unknown)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn without_compat_object_format_there_are_no_compatibility_ids() -> crate::Result {
+        let (tmp, repo) = empty_bare_repo()?;
+        let id = repo.write_blob(b"hello world")?;
+        assert_eq!(repo.compat_id(id)?, None);
+        assert!(!tmp.path().join("objects/loose-object-idx").exists());
+        Ok(())
+    }
+}
+
 #[test]
 fn writes_avoid_io_using_duplicate_check() -> crate::Result {
     let repo = crate::named_repo("make_packed_and_loose.sh")?;
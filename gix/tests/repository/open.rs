@@ -289,6 +289,136 @@ mod object_caches {
     }
 }
 
+mod repository_format {
+    use gix::config::repository_format::{Error, RefStorage};
+
+    use crate::util::named_subrepo_opts;
+
+    fn open(name: &str) -> Result<gix::Repository, gix::open::Error> {
+        named_subrepo_opts("make_repository_format_repos.sh", name, gix::open::Options::isolated())
+    }
+
+    fn format_error(name: &str) -> Error {
+        match open(name) {
+            Err(gix::open::Error::Config(gix::config::Error::RepositoryFormat(err))) => err,
+            Err(err) => panic!("unexpected error: {err:?}"),
+            Ok(_) => panic!("{name} must not be opened"),
+        }
+    }
+
+    #[test]
+    fn unknown_extensions_are_rejected_with_version_1() {
+        let err = format_error("unknown-extension-v1");
+        assert!(matches!(&err, Error::UnknownExtension { name } if name == "unknownExtension"));
+        assert_eq!(err.to_string(), "Unknown repository extension 'unknownExtension' found");
+    }
+
+    #[test]
+    fn unknown_extensions_are_ignored_with_version_0() -> crate::Result {
+        let repo = open("unknown-extension-v0")?;
+        assert_eq!(repo.repository_format().version, 0);
+        let repo = open("reftable-v0")?;
+        assert_eq!(
+            repo.repository_format().ref_storage,
+            RefStorage::Files,
+            "extensions that need version 1 are ignored like git does"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn versions_above_1_are_rejected() {
+        assert!(matches!(
+            format_error("version-2"),
+            Error::UnsupportedVersion { version: 2 }
+        ));
+    }
+
+    #[test]
+    fn known_extensions_are_recognized_with_version_1() -> crate::Result {
+        let repo = open("known-extensions-v1")?;
+        let format = repo.repository_format();
+        assert_eq!(format.version, 1);
+        assert_eq!(format.object_format, gix_hash::Kind::Sha1);
+        assert_eq!(format.compat_object_format, None);
+        assert_eq!(format.ref_storage, RefStorage::Files);
+        assert_eq!(format.partial_clone, Some("origin".into()));
+        assert!(!format.precious_objects);
+        repo.ensure_objects_may_be_deleted()?;
+        Ok(())
+    }
+
+    #[test]
+    fn compat_object_format_is_recognized_with_version_1() -> crate::Result {
+        let repo = open("compat-object-format-v1")?;
+        assert_eq!(
+            repo.repository_format().compat_object_format,
+            Some(gix_hash::Kind::Sha256)
+        );
+        assert_eq!(
+            repo.objects.store_ref().compat_object_hash(),
+            Some(gix_hash::Kind::Sha256)
+        );
+
+        let repo = open("compat-object-format-v0")?;
+        assert_eq!(
+            repo.repository_format().compat_object_format,
+            None,
+            "like git, it's ignored with version 0"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_values_of_known_extensions_are_rejected() {
+        let err = open("reftable-v1").unwrap_err();
+        assert!(matches!(
+            err,
+            gix::open::Error::Config(gix::config::Error::ConfigTypedString(_))
+        ));
+        assert_eq!(
+            std::error::Error::source(&err).expect("present").to_string(),
+            "The key \"extensions.refStorage=reftable\" was invalid"
+        );
+    }
+
+    #[test]
+    fn precious_objects_prevent_object_deletion_even_with_version_0() -> crate::Result {
+        let repo = open("precious-objects-v0")?;
+        assert!(repo.repository_format().precious_objects);
+        assert_eq!(
+            repo.ensure_objects_may_be_deleted().unwrap_err().to_string(),
+            "Objects must not be deleted as 'extensions.preciousObjects' is set"
+        );
+        Ok(())
+    }
+}
+
+mod pack_windows {
+    use crate::util::{repo_opts, restricted};
+
+    #[test]
+    fn objects_are_readable_through_small_windows() -> crate::Result {
+        let repo = repo_opts("make_packed_and_loose.sh", restricted())?.to_thread_local();
+        let windowed_repo = repo_opts(
+            "make_packed_and_loose.sh",
+            restricted().config_overrides(["core.packedGitWindowSize=100", "core.packedGitLimit=200"]),
+        )?
+        .to_thread_local();
+
+        let mut num_objects = 0;
+        for id in repo.objects.iter()?.filter_map(Result::ok) {
+            let expected = repo.find_object(id)?;
+            let actual = windowed_repo.find_object(id)?;
+            assert_eq!(actual.kind, expected.kind);
+            assert_eq!(actual.data, expected.data, "{id}");
+            num_objects += 1;
+        }
+        assert_eq!(num_objects, 9, "all packed and loose objects were checked");
+        Ok(())
+    }
+}
+
 mod worktree {
     use gix::open;
 
@@ -389,3 +519,105 @@ mod worktree {
         Ok(())
     }
 }
+
+mod safe_directory {
+    use std::path::Path;
+
+    use gix_testtools::Env;
+    use serial_test::serial;
+
+    /// Open `path` as if it was owned by another user, with `global` being the global configuration.
+    fn open_foreign(path: &Path, global: &str) -> Result<gix::Repository, gix::open::Error> {
+        let config = path.parent().expect("parent").join("global.config");
+        std::fs::write(&config, global)?;
+        let _env = Env::new()
+            .set("GIT_CONFIG_GLOBAL", config.to_str().expect("valid UTF-8"))
+            .set("GIT_CONFIG_NOSYSTEM", "1");
+        gix::open_opts(
+            path,
+            gix::open::Options::default()
+                .with(gix_sec::Trust::Reduced)
+                .bail_if_untrusted(true),
+        )
+    }
+
+    #[test]
+    #[serial]
+    fn foreign_repositories_must_be_listed() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let root = tmp.path().canonicalize()?;
+        let repo_dir = root.join("repo");
+        gix::init(&repo_dir)?;
+        let local_config = repo_dir.join(".git").join("config");
+        let mut local = std::fs::read_to_string(&local_config)?;
+        local.push_str("[safe]\n\tdirectory = *\n");
+        std::fs::write(local_config, local)?;
+
+        let err = open_foreign(&repo_dir, "").unwrap_err();
+        assert!(
+            matches!(&err, gix::open::Error::UnsafeGitDir { path } if *path == repo_dir),
+            "the worktree is the path to trust, and the repository configuration is ignored: {err:?}"
+        );
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "detected dubious ownership in repository at '{dir}'\nTo add an exception for this directory, call:\n\n\tgit config --global --add safe.directory {dir}",
+                dir = repo_dir.display()
+            )
+        );
+
+        for (global, is_safe) in [
+            (format!("[safe]\n\tdirectory = {}", repo_dir.display()), true),
+            (
+                format!("[safe]\n\tdirectory = {}", repo_dir.join(".git").display()),
+                true,
+            ),
+            (format!("[safe]\n\tdirectory = {}/*", root.display()), true),
+            (format!("[safe]\n\tdirectory = {}/*", repo_dir.display()), false),
+            (format!("[safe]\n\tdirectory = {}", root.display()), false),
+            ("[safe]\n\tdirectory = *".into(), true),
+            ("[safe]\n\tdirectory = *\n\tdirectory =".into(), false),
+            (
+                format!(
+                    "[safe]\n\tdirectory = *\n\tdirectory =\n\tdirectory = {}",
+                    repo_dir.display()
+                ),
+                true,
+            ),
+        ] {
+            let res = open_foreign(&repo_dir, &global);
+            assert_eq!(res.is_ok(), is_safe, "{global}: {res:?}");
+            if let Err(err) = res {
+                assert!(matches!(err, gix::open::Error::UnsafeGitDir { .. }));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn bare_repositories_use_their_git_dir() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let repo_dir = tmp.path().canonicalize()?.join("bare.git");
+        gix::init_bare(&repo_dir)?;
+
+        let err = open_foreign(&repo_dir, "").unwrap_err();
+        assert!(matches!(&err, gix::open::Error::UnsafeGitDir { path } if *path == repo_dir));
+        open_foreign(&repo_dir, &format!("[safe]\n\tdirectory = {}", repo_dir.display()))?;
+        Ok(())
+    }
+
+    #[test]
+    fn the_check_is_opt_in_and_fully_trusted_repositories_are_safe() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        gix::init(tmp.path())?;
+        gix::open_opts(tmp.path(), gix::open::Options::default().with(gix_sec::Trust::Reduced))?;
+        gix::open_opts(
+            tmp.path(),
+            gix::open::Options::default()
+                .with(gix_sec::Trust::Full)
+                .bail_if_untrusted(true),
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,334 @@
+use std::path::Path;
+
+use gix::receive_pack::{Options, Outcome};
+
+use crate::util::restricted;
+
+fn server(dir: &Path, name: &str, config: &[&str]) -> crate::Result<gix::Repository> {
+    Ok(gix::open_opts(
+        dir.join(name),
+        restricted().config_overrides(config.iter().copied()),
+    )?)
+}
+
+/// Return the status of each command, sorted by reference name as `git` may reorder them.
+fn rejections(outcome: &Outcome) -> Vec<(String, Option<String>)> {
+    let mut out: Vec<_> = outcome
+        .report
+        .commands
+        .iter()
+        .map(|status| {
+            (
+                status.refname.to_string(),
+                status.rejection.as_ref().map(ToString::to_string),
+            )
+        })
+        .collect();
+    out.sort();
+    out
+}
+
+fn ref_id(repo: &gix::Repository, name: &str) -> Option<gix::ObjectId> {
+    repo.try_find_reference(name)
+        .expect("valid name")
+        .map(|mut r| r.peel_to_id_in_place().expect("valid ref").detach())
+}
+
+fn client_id(dir: &Path, spec: &str) -> crate::Result<gix::ObjectId> {
+    let client = gix::open_opts(dir.join("client"), restricted())?;
+    Ok(client.rev_parse_single(spec)?.detach())
+}
+
+/// Run `git push` in the `client` repository with `args`, and serve it with `server` over a pair of named pipes.
+#[cfg(unix)]
+fn push(
+    dir: &Path,
+    server: &gix::Repository,
+    args: &[&str],
+    options: Options,
+) -> crate::Result<(Outcome, std::process::Output)> {
+    let pipes = gix_testtools::tempfile::TempDir::new()?;
+    let (client_to_server, server_to_client) = (pipes.path().join("in"), pipes.path().join("out"));
+    for fifo in [&client_to_server, &server_to_client] {
+        assert!(std::process::Command::new("mkfifo").arg(fifo).status()?.success());
+    }
+    let receive_pack = pipes.path().join("receive-pack");
+    std::fs::write(&receive_pack, "#!/bin/sh\ncat \"$OUT\" &\ncat > \"$IN\"\nwait\n")?;
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&receive_pack, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    let child = std::process::Command::new("git")
+        .current_dir(dir.join("client"))
+        .arg("push")
+        .arg(format!("--receive-pack={}", receive_pack.display()))
+        .arg(server.git_dir())
+        .args(args)
+        .env("IN", &client_to_server)
+        .env("OUT", &server_to_client)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()?;
+    let input = std::fs::File::open(&client_to_server)?;
+    let output = std::fs::OpenOptions::new().write(true).open(&server_to_client)?;
+    let outcome = server.receive_pack(input, output, options)?;
+    Ok((outcome, child.wait_with_output()?))
+}
+
+#[cfg(unix)]
+mod push_with_git {
+    use gix::receive_pack::Options;
+
+    use super::{client_id, push, ref_id, rejections, server};
+
+    fn fixture() -> crate::Result<gix_testtools::tempfile::TempDir> {
+        gix_testtools::scripted_fixture_writable("make_receive_pack_repos.sh")
+    }
+
+    fn fsck(repo: &gix::Repository) -> crate::Result {
+        let status = std::process::Command::new("git")
+            .args(["fsck", "--no-dangling"])
+            .current_dir(repo.git_dir())
+            .status()?;
+        assert!(status.success(), "the server repository is valid");
+        Ok(())
+    }
+
+    #[test]
+    fn updates_creations_and_deletions_are_applied() -> crate::Result {
+        let dir = fixture()?;
+        let server = server(dir.path(), "server.git", &[])?;
+        let (outcome, out) = push(
+            dir.path(),
+            &server,
+            &["main", "diverged", ":refs/tags/v1"],
+            Options::default(),
+        )?;
+        assert!(out.status.success(), "{out:?}");
+        assert_eq!(
+            rejections(&outcome),
+            [
+                ("refs/heads/diverged".into(), None),
+                ("refs/heads/main".into(), None),
+                ("refs/tags/v1".into(), None)
+            ]
+        );
+        assert_eq!(outcome.report.unpack_error, None);
+        assert!(outcome.pack.is_some(), "new objects were received");
+
+        assert_eq!(ref_id(&server, "main"), Some(client_id(dir.path(), "main")?));
+        assert_eq!(ref_id(&server, "diverged"), Some(client_id(dir.path(), "diverged")?));
+        assert_eq!(ref_id(&server, "refs/tags/v1"), None);
+        assert!(
+            !std::fs::read_dir(server.objects.store_ref().path())?.any(|entry| entry
+                .expect("valid")
+                .file_name()
+                .to_string_lossy()
+                .starts_with("incoming-")),
+            "the quarantine directory is removed"
+        );
+        let packs = std::fs::read_dir(server.objects.store_ref().path().join("pack"))?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert!(
+            packs.iter().all(|p| p.extension().map_or(true, |ext| ext != "keep")),
+            "keep files are removed once references are updated"
+        );
+        fsck(&server)?;
+
+        let (outcome, out) = push(dir.path(), &server, &["main"], Options::default())?;
+        assert!(out.status.success());
+        assert!(
+            outcome.request.commands.is_empty(),
+            "nothing is sent if everything is up-to-date"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn non_fast_forwards_are_rejected_if_configured() -> crate::Result {
+        let dir = fixture()?;
+        let server = server(dir.path(), "server.git", &["receive.denyNonFastForwards=true"])?;
+        let previous = ref_id(&server, "other");
+        let (outcome, out) = push(dir.path(), &server, &["+rewritten:other", "main"], Options::default())?;
+        assert!(!out.status.success(), "one ref was rejected");
+        assert_eq!(
+            rejections(&outcome),
+            [
+                ("refs/heads/main".into(), None),
+                ("refs/heads/other".into(), Some("non-fast-forward".into()))
+            ]
+        );
+        assert!(
+            String::from_utf8_lossy(&out.stderr).contains("non-fast-forward"),
+            "the reason is communicated to the client"
+        );
+        assert_eq!(ref_id(&server, "other"), previous);
+
+        let server = super::server(dir.path(), "server.git", &[])?;
+        let (outcome, out) = push(dir.path(), &server, &["+rewritten:other"], Options::default())?;
+        assert!(out.status.success());
+        assert_eq!(rejections(&outcome), [("refs/heads/other".into(), None)]);
+        assert_eq!(ref_id(&server, "other"), Some(client_id(dir.path(), "rewritten")?));
+        fsck(&server)?;
+        Ok(())
+    }
+
+    #[test]
+    fn deletions_of_branches_are_rejected_if_configured() -> crate::Result {
+        let dir = fixture()?;
+        let server = server(dir.path(), "server.git", &["receive.denyDeletes=true"])?;
+        let (outcome, out) = push(dir.path(), &server, &[":other", ":refs/tags/v1"], Options::default())?;
+        assert!(!out.status.success());
+        assert!(outcome.pack.is_none(), "deletions don't need a pack");
+        assert_eq!(
+            rejections(&outcome),
+            [
+                ("refs/heads/other".into(), Some("deletion prohibited".into())),
+                ("refs/tags/v1".into(), None)
+            ]
+        );
+        assert!(ref_id(&server, "other").is_some());
+        assert!(ref_id(&server, "refs/tags/v1").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn hidden_references_are_not_advertised_and_cannot_be_updated() -> crate::Result {
+        let dir = fixture()?;
+        let server = server(
+            dir.path(),
+            "server.git",
+            &[
+                "transfer.hideRefs=refs/heads",
+                "receive.hideRefs=!refs/heads/main",
+                "uploadpack.hideRefs=refs/heads/main",
+            ],
+        )?;
+        let mut advertisement = Vec::new();
+        server.receive_pack_advertisement(&mut advertisement)?;
+        let advertisement = String::from_utf8(advertisement)?;
+        assert!(
+            advertisement.contains("refs/heads/main") && advertisement.contains("refs/tags/v1"),
+            "receive.hideRefs exposes main again, and uploadpack.hideRefs doesn't apply: {advertisement}"
+        );
+        assert!(!advertisement.contains("refs/heads/other"), "{advertisement}");
+
+        let (outcome, out) = push(dir.path(), &server, &["main", "diverged:other"], Options::default())?;
+        assert!(!out.status.success());
+        assert_eq!(
+            rejections(&outcome),
+            [
+                ("refs/heads/main".into(), None),
+                ("refs/heads/other".into(), Some("deny updating a hidden ref".into()))
+            ]
+        );
+        assert_eq!(ref_id(&server, "main"), Some(client_id(dir.path(), "main")?));
+        assert_ne!(ref_id(&server, "other"), Some(client_id(dir.path(), "diverged")?));
+        Ok(())
+    }
+
+    #[test]
+    fn the_current_branch_is_protected_by_default() -> crate::Result {
+        let dir = fixture()?;
+        let server = server(dir.path(), "server.git", &[])?;
+        let (outcome, _out) = push(dir.path(), &server, &[":main"], Options::default())?;
+        assert_eq!(
+            rejections(&outcome),
+            [(
+                "refs/heads/main".into(),
+                Some("deletion of the current branch prohibited".into())
+            )]
+        );
+
+        let server = super::server(dir.path(), "server-with-worktree", &[])?;
+        let (outcome, out) = push(dir.path(), &server, &["main", "other"], Options::default())?;
+        assert!(!out.status.success());
+        assert_eq!(
+            rejections(&outcome),
+            [
+                ("refs/heads/main".into(), Some("branch is currently checked out".into())),
+                ("refs/heads/other".into(), None)
+            ]
+        );
+
+        let server = super::server(dir.path(), "server-with-worktree", &["receive.denyCurrentBranch=warn"])?;
+        let (outcome, out) = push(dir.path(), &server, &["main"], Options::default())?;
+        assert!(out.status.success());
+        assert_eq!(rejections(&outcome), [("refs/heads/main".into(), None)]);
+        assert_eq!(ref_id(&server, "main"), Some(client_id(dir.path(), "main")?));
+        Ok(())
+    }
+
+    #[test]
+    fn hooks_can_decline_and_see_the_quarantined_objects() -> crate::Result {
+        let dir = fixture()?;
+        let server = server(dir.path(), "server.git", &[])?;
+        let hooks = server.git_dir().join("hooks");
+        std::fs::create_dir_all(&hooks)?;
+        let write_hook = |name: &str, script: &str| -> std::io::Result<()> {
+            use std::os::unix::fs::PermissionsExt;
+            let path = hooks.join(name);
+            std::fs::write(&path, format!("#!/bin/sh\n{script}"))?;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+        };
+        write_hook(
+            "pre-receive",
+            "while read old new ref; do git cat-file -e $new || exit 2; echo \"pre-receive $ref\"; done\n\
+             echo \"option: $GIT_PUSH_OPTION_0\"\n\
+             test -n \"$GIT_QUARANTINE_PATH\" && exit 1",
+        )?;
+        write_hook("post-receive", "echo post-receive")?;
+
+        let new_commit = client_id(dir.path(), "main")?;
+        let (outcome, out) = push(
+            dir.path(),
+            &server,
+            &["-o", "value", "main"],
+            Options {
+                run_hooks: true,
+                ..Default::default()
+            },
+        )?;
+        assert!(!out.status.success());
+        assert_eq!(
+            rejections(&outcome),
+            [("refs/heads/main".into(), Some("pre-receive hook declined".into()))]
+        );
+        assert_eq!(outcome.request.push_options, ["value"]);
+        assert_eq!(outcome.hook_output, "pre-receive refs/heads/main\noption: value\n");
+        assert!(
+            !server.has_object(new_commit),
+            "the objects of declined pushes aren't migrated"
+        );
+
+        let (outcome, out) = push(dir.path(), &server, &["main"], Options::default())?;
+        assert!(out.status.success(), "hooks are only run if enabled");
+        assert_eq!(rejections(&outcome), [("refs/heads/main".into(), None)]);
+        assert_eq!(outcome.hook_output, "");
+
+        write_hook("pre-receive", "exit 0")?;
+        write_hook("update", "test $1 != refs/heads/other")?;
+        let (outcome, out) = push(
+            dir.path(),
+            &server,
+            &["diverged", "+rewritten:other"],
+            Options {
+                run_hooks: true,
+                ..Default::default()
+            },
+        )?;
+        assert!(!out.status.success());
+        assert_eq!(
+            rejections(&outcome),
+            [
+                ("refs/heads/diverged".into(), None),
+                ("refs/heads/other".into(), Some("hook declined".into()))
+            ]
+        );
+        assert_eq!(outcome.hook_output, "post-receive\n");
+        fsck(&server)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,423 @@
+use std::path::Path;
+
+use gix::{
+    protocol::transport::Protocol,
+    upload_pack::{Error, Options, Outcome},
+};
+
+use crate::util::restricted;
+
+fn server(dir: &Path, config: &[&str]) -> crate::Result<gix::Repository> {
+    Ok(gix::open_opts(
+        dir.join("server"),
+        restricted().config_overrides(config.iter().copied()),
+    )?)
+}
+
+/// Run `git <subcommand> <args>` in `cwd` using protocol `options.version`, and serve it with `server` over a pair of named pipes.
+#[cfg(unix)]
+fn serve(
+    cwd: &Path,
+    server: &gix::Repository,
+    subcommand: &str,
+    args: &[&str],
+    options: Options,
+) -> crate::Result<(Result<Outcome, Error>, std::process::Output)> {
+    let pipes = gix_testtools::tempfile::TempDir::new()?;
+    let (client_to_server, server_to_client) = (pipes.path().join("in"), pipes.path().join("out"));
+    for fifo in [&client_to_server, &server_to_client] {
+        assert!(std::process::Command::new("mkfifo").arg(fifo).status()?.success());
+    }
+    let upload_pack = pipes.path().join("upload-pack");
+    std::fs::write(&upload_pack, "#!/bin/sh\ncat \"$OUT\" &\ncat > \"$IN\"\nwait\n")?;
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&upload_pack, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    let version = match options.version {
+        Protocol::V0 => "0",
+        Protocol::V1 => "1",
+        Protocol::V2 => "2",
+    };
+    let child = std::process::Command::new("git")
+        .current_dir(cwd)
+        .args(["-c", &format!("protocol.version={version}"), subcommand])
+        .arg(format!("--upload-pack={}", upload_pack.display()))
+        .args(args)
+        .env("IN", &client_to_server)
+        .env("OUT", &server_to_client)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()?;
+    let input = std::fs::File::open(&client_to_server)?;
+    let output = std::fs::OpenOptions::new().write(true).open(&server_to_client)?;
+    let outcome = server.upload_pack(input, output, options);
+    Ok((outcome, child.wait_with_output()?))
+}
+
+#[cfg(unix)]
+mod fetch_with_git {
+    use std::path::Path;
+
+    use gix::{
+        protocol::transport::Protocol,
+        upload_pack::{Error, Options},
+    };
+
+    use super::{serve, server};
+    use crate::util::restricted;
+
+    fn fixture() -> crate::Result<gix_testtools::tempfile::TempDir> {
+        gix_testtools::scripted_fixture_writable("make_upload_pack_repos.sh")
+    }
+
+    fn options(version: Protocol) -> Options {
+        Options {
+            version,
+            ..Default::default()
+        }
+    }
+
+    fn fsck(git_dir: &Path) -> crate::Result {
+        let status = std::process::Command::new("git")
+            .args(["fsck", "--no-dangling"])
+            .current_dir(git_dir)
+            .status()?;
+        assert!(status.success(), "the fetched objects are complete");
+        Ok(())
+    }
+
+    fn id(repo: &gix::Repository, spec: &str) -> crate::Result<gix::ObjectId> {
+        Ok(repo.rev_parse_single(spec)?.detach())
+    }
+
+    #[test]
+    fn clone_with_all_protocol_versions() -> crate::Result {
+        let dir = fixture()?;
+        let server = server(dir.path(), &[])?;
+        for version in [Protocol::V2, Protocol::V1, Protocol::V0] {
+            let name = format!("clone-{version:?}");
+            let (outcome, out) = serve(
+                dir.path(),
+                &server,
+                "clone",
+                &["--no-local", server.git_dir().to_str().expect("valid UTF-8"), &name],
+                options(version),
+            )?;
+            assert!(out.status.success(), "{version:?}: {out:?}");
+            let outcome = outcome?;
+            assert_eq!(outcome.ls_refs, usize::from(version == Protocol::V2));
+            let fetch = outcome.fetches.last().expect("one pack was sent");
+            assert!(fetch.common.is_empty());
+            assert_eq!(
+                fetch.pack_objects,
+                Some(7 + 3 + 5 + 3),
+                "all objects of the 3 commits, along with 3 annotated tags"
+            );
+
+            let clone = gix::open_opts(dir.path().join(&name), restricted())?;
+            assert_eq!(id(&clone, "HEAD")?, id(&server, "HEAD")?);
+            assert_eq!(id(&clone, "refs/remotes/origin/other")?, id(&server, "other")?);
+            assert_eq!(id(&clone, "refs/tags/v1-nested")?, id(&server, "v1-nested")?);
+            assert_eq!(
+                std::fs::read(clone.work_dir().expect("non-bare").join("dir/sub/c"))?,
+                b"c2\n"
+            );
+            assert!(
+                clone.try_find_reference("refs/remotes/origin/hidden/secret")?.is_none()
+                    && !clone.has_object(id(&server, "refs/hidden/secret")?),
+                "references outside of refs/heads and refs/tags aren't fetched by default"
+            );
+            fsck(clone.git_dir())?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_negotiates_common_commits() -> crate::Result {
+        for version in [Protocol::V2, Protocol::V0] {
+            let dir = fixture()?;
+            let server = server(dir.path(), &[])?;
+            let client = dir.path().join("client");
+            let (outcome, out) = serve(
+                &client,
+                &server,
+                "fetch",
+                &[
+                    server.git_dir().to_str().expect("valid UTF-8"),
+                    "+refs/heads/*:refs/remotes/server/*",
+                ],
+                options(version),
+            )?;
+            assert!(out.status.success(), "{version:?}: {out:?}");
+            let fetch = outcome?.fetches.pop().expect("a pack was sent");
+            assert!(fetch.common.contains(&id(&server, "main~1")?), "{version:?}");
+            assert_eq!(
+                fetch.pack_objects,
+                Some(5 + 1),
+                "only the new commit, the trees leading to the changed file and the file itself are sent, \
+                 along with the tag pointing to the commit"
+            );
+            let client = gix::open_opts(client, restricted())?;
+            assert_eq!(id(&client, "refs/remotes/server/main")?, id(&server, "main")?);
+            assert_eq!(id(&client, "refs/tags/v2")?, id(&server, "v2")?);
+            fsck(client.git_dir())?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn hidden_references_are_not_advertised() -> crate::Result {
+        let dir = fixture()?;
+        for (version, config) in [
+            (Protocol::V2, "transfer.hideRefs=refs/hidden"),
+            (Protocol::V0, "uploadpack.hideRefs=refs/hidden/"),
+        ] {
+            let server = server(
+                dir.path(),
+                &[
+                    config,
+                    "uploadpack.hideRefs=refs/tags",
+                    "uploadpack.hideRefs=!refs/tags/v2",
+                ],
+            )?;
+            let (outcome, out) = serve(
+                dir.path(),
+                &server,
+                "ls-remote",
+                &[server.git_dir().to_str().expect("valid UTF-8")],
+                options(version),
+            )?;
+            assert!(out.status.success(), "{version:?}: {out:?}");
+            assert!(outcome?.fetches.is_empty());
+            let names: Vec<_> = String::from_utf8(out.stdout)?
+                .lines()
+                .map(|line| line.split_once('\t').expect("id and name").1.to_owned())
+                .collect();
+            assert_eq!(
+                names,
+                [
+                    "HEAD",
+                    "refs/heads/main",
+                    "refs/heads/other",
+                    "refs/tags/v2",
+                    "refs/tags/v2^{}"
+                ],
+                "{version:?}: the last matching pattern decides"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn unadvertised_objects_are_only_sent_if_allowed() -> crate::Result {
+        for allowed in [
+            None,
+            Some("uploadpack.allowTipSHA1InWant=true"),
+            Some("uploadpack.allowReachableSHA1InWant=true"),
+            Some("uploadpack.allowAnySHA1InWant=true"),
+        ] {
+            let dir = fixture()?;
+            let server = server(
+                dir.path(),
+                &["transfer.hideRefs=refs/hidden"]
+                    .into_iter()
+                    .chain(allowed)
+                    .collect::<Vec<_>>(),
+            )?;
+            let secret = id(&server, "refs/hidden/secret")?;
+            let client = dir.path().join("client");
+            let (outcome, out) = serve(
+                &client,
+                &server,
+                "fetch",
+                &[server.git_dir().to_str().expect("valid UTF-8"), &secret.to_string()],
+                options(Protocol::V2),
+            )?;
+            match allowed {
+                None => {
+                    assert!(!out.status.success());
+                    assert!(matches!(outcome, Err(Error::NotOurRef { id }) if id == secret));
+                    assert!(
+                        String::from_utf8_lossy(&out.stderr).contains(&format!("not our ref {secret}")),
+                        "the error is sent to the client"
+                    );
+                }
+                Some(allowed) => {
+                    assert!(out.status.success(), "{allowed}: {out:?}");
+                    assert_eq!(
+                        outcome?.fetches.pop().and_then(|f| f.pack_objects),
+                        Some(1),
+                        "{allowed}: the commit shares its tree with a commit the client has"
+                    );
+                    fsck(&client.join(".git"))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn blobs_are_omitted_with_filter_if_allowed() -> crate::Result {
+        let dir = fixture()?;
+        let server = server(dir.path(), &[])?;
+        let clone = |server: &gix::Repository, name: &str| {
+            let args = [
+                "--bare",
+                "--no-local",
+                "--filter=blob:none",
+                server.git_dir().to_str().expect("valid UTF-8"),
+                name,
+            ];
+            serve(dir.path(), server, "clone", &args, options(Protocol::V2))
+        };
+        let (outcome, out) = clone(&server, "full.git")?;
+        assert!(out.status.success(), "{out:?}");
+        let fetch = outcome?.fetches.pop().expect("a pack was sent");
+        assert_eq!(
+            fetch.request.filter, None,
+            "clients don't send filters the server doesn't support"
+        );
+        assert_eq!(fetch.pack_objects, Some(7 + 3 + 5 + 3));
+
+        let server = super::server(dir.path(), &["uploadpack.allowFilter=true"])?;
+        let (outcome, out) = clone(&server, "partial.git")?;
+        assert!(out.status.success(), "{out:?}");
+        assert_eq!(
+            outcome?.fetches.pop().and_then(|f| f.pack_objects),
+            Some(3 + 7 + 3),
+            "only commits, trees and tags are sent"
+        );
+        let partial = gix::open_opts(dir.path().join("partial.git"), restricted())?;
+        assert!(!partial.has_object(id(&server, "HEAD:a")?));
+        fsck(partial.git_dir())?;
+        Ok(())
+    }
+
+    #[test]
+    fn filters_omit_the_same_objects_as_git() -> crate::Result {
+        fn object_ids(git_dir: &Path, args: &[&str]) -> crate::Result<std::collections::BTreeSet<String>> {
+            let out = std::process::Command::new("git")
+                .args(args)
+                .current_dir(git_dir)
+                .output()?;
+            assert!(out.status.success(), "{args:?}: {out:?}");
+            Ok(String::from_utf8(out.stdout)?
+                .lines()
+                .map(|line| line.split(' ').next().expect("at least one field").to_owned())
+                .collect())
+        }
+
+        let dir = fixture()?;
+        let server = server(dir.path(), &["uploadpack.allowFilter=true"])?;
+        for (idx, (spec, canonical)) in [
+            ("blob:limit=3", "blob:limit=3"),
+            ("blob:limit=0x3", "blob:limit=3"),
+            ("tree:0", "tree:0"),
+            ("tree:1", "tree:1"),
+            ("tree:2", "tree:2"),
+            ("sparse:oid=v1:a", "sparse:oid=v1:a"),
+            ("combine:tree:2+blob:limit=3", "combine:tree:2+blob:limit=3"),
+            ("combine:sparse:oid=v1%3aa+tree:3", "combine:sparse:oid=v1%3aa+tree:3"),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let name = format!("partial-{idx}.git");
+            let filter = format!("--filter={spec}");
+            let args = [
+                "--bare",
+                "--no-local",
+                &filter,
+                server.git_dir().to_str().expect("valid UTF-8"),
+                &name,
+            ];
+            let (outcome, out) = serve(dir.path(), &server, "clone", &args, options(Protocol::V2))?;
+            assert!(out.status.success(), "{spec}: {out:?}");
+            let fetch = outcome?.fetches.pop().expect("a pack was sent");
+            assert_eq!(
+                fetch.request.filter.expect("sent"),
+                canonical,
+                "git sends canonical specs"
+            );
+
+            let expected = object_ids(
+                server.git_dir(),
+                &["rev-list", "--objects", &filter, "--branches", "--tags"],
+            )?;
+            let actual = object_ids(
+                &dir.path().join(&name),
+                &["cat-file", "--batch-all-objects", "--batch-check=%(objectname)"],
+            )?;
+            assert_eq!(actual, expected, "{spec}");
+            assert_eq!(fetch.pack_objects, Some(expected.len()), "{spec}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_filters_are_rejected() -> crate::Result {
+        let dir = fixture()?;
+        let server = server(dir.path(), &["uploadpack.allowFilter=true"])?;
+        let args = [
+            "--bare",
+            "--no-local",
+            "--filter=sparse:oid=v1:does-not-exist",
+            server.git_dir().to_str().expect("valid UTF-8"),
+            "partial.git",
+        ];
+        let (outcome, out) = serve(dir.path(), &server, "clone", &args, options(Protocol::V2))?;
+        assert!(!out.status.success());
+        assert!(matches!(outcome, Err(Error::InitFilter(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn pack_bitmaps_are_used_to_find_the_objects_to_send() -> crate::Result {
+        let dir = fixture()?;
+        let status = std::process::Command::new("git")
+            .args(["repack", "-adbq"])
+            .current_dir(dir.path().join("server"))
+            .stdin(std::process::Stdio::null())
+            .status()?;
+        assert!(status.success());
+        let server = server(dir.path(), &["uploadpack.allowFilter=true"])?;
+
+        let (outcome, out) = serve(
+            &dir.path().join("client"),
+            &server,
+            "fetch",
+            &[
+                server.git_dir().to_str().expect("valid UTF-8"),
+                "+refs/heads/*:refs/remotes/server/*",
+            ],
+            options(Protocol::V2),
+        )?;
+        assert!(out.status.success(), "{out:?}");
+        assert_eq!(
+            outcome?.fetches.pop().and_then(|f| f.pack_objects),
+            Some(5 + 1),
+            "the same objects are sent as when walking the commit graph"
+        );
+        fsck(&dir.path().join("client/.git"))?;
+
+        for (name, filter, expected) in [
+            ("full.git", None, 7 + 3 + 5 + 3),
+            ("partial.git", Some("--filter=blob:none"), 3 + 7 + 3),
+        ] {
+            let mut args = vec!["--bare", "--no-local"];
+            args.extend(filter);
+            args.extend([server.git_dir().to_str().expect("valid UTF-8"), name]);
+            let (outcome, out) = serve(dir.path(), &server, "clone", &args, options(Protocol::V2))?;
+            assert!(out.status.success(), "{out:?}");
+            assert_eq!(
+                outcome?.fetches.pop().and_then(|f| f.pack_objects),
+                Some(expected),
+                "{name}"
+            );
+            fsck(&dir.path().join(name))?;
+        }
+        Ok(())
+    }
+}
@@ -31,6 +31,221 @@ fn archive() -> crate::Result {
     Ok(())
 }
 
+#[cfg(all(feature = "worktree-mutation", feature = "status"))]
+mod mutation {
+    use std::path::{Path, PathBuf};
+
+    use gix::worktree::{add, proxy, prune};
+
+    fn git(dir: &Path, args: &[&str]) -> crate::Result<String> {
+        let out = std::process::Command::new("git").args(args).current_dir(dir).output()?;
+        assert!(
+            out.status.success(),
+            "{:?}: {}",
+            args,
+            String::from_utf8_lossy(&out.stderr)
+        );
+        Ok(String::from_utf8(out.stdout)?)
+    }
+
+    fn repo() -> crate::Result<(gix::Repository, PathBuf, gix_testtools::tempfile::TempDir)> {
+        let tmp = gix_testtools::scripted_fixture_writable_with_args(
+            "make_worktree_repo.sh",
+            None::<String>,
+            gix_testtools::Creation::ExecuteScript,
+        )?;
+        let root = tmp.path().canonicalize()?;
+        let repo = gix::open_opts(root.join("repo"), crate::restricted())?;
+        Ok((repo, root, tmp))
+    }
+
+    fn proxy<'a>(repo: &'a gix::Repository, id: &str) -> gix::worktree::Proxy<'a> {
+        repo.worktrees()
+            .expect("worktrees can be listed")
+            .into_iter()
+            .find(|wt| wt.id() == id)
+            .expect("worktree exists")
+    }
+
+    #[test]
+    fn add_checks_out_detached_head_and_is_recognized_by_git() -> crate::Result {
+        let (repo, root, _tmp) = repo()?;
+        let head_id = repo.head_id()?.detach();
+        let path = root.join("new wt.lock");
+        let wt = repo.worktree_add(&path, head_id, Default::default())?;
+
+        assert_eq!(wt.head_id()?, head_id);
+        assert!(wt.head_name()?.is_none(), "HEAD is detached");
+        assert_eq!(wt.worktree().expect("linked worktree").id(), Some("new-wt".into()));
+        assert_eq!(wt.work_dir(), Some(path.as_path()));
+        assert_eq!(std::fs::read(path.join("a"))?, b"hello\n");
+        assert!(path.join("dir").join("c").is_file());
+        assert!(!wt.worktree().expect("linked").is_locked());
+
+        assert_eq!(
+            git(&path, &["status", "--porcelain"])?,
+            "",
+            "index and worktree are consistent"
+        );
+        let list = git(&root.join("repo"), &["worktree", "list", "--porcelain"])?;
+        assert!(
+            list.contains(&format!("worktree {}\nHEAD {head_id}\ndetached\n", path.display())),
+            "{list}"
+        );
+
+        let wt = repo.worktree_add(
+            root.join("elsewhere").join("new wt"),
+            head_id,
+            add::Options {
+                lock_reason: Some("on a stick".into()),
+                ..Default::default()
+            },
+        )?;
+        let wt = wt.worktree().expect("linked");
+        assert_eq!(wt.id(), Some("new-wt1".into()), "names are made unique");
+        assert_eq!(wt.lock_reason(), Some("on a stick".into()));
+
+        assert!(matches!(
+            repo.worktree_add(root.join("wt-a"), head_id, Default::default()),
+            Err(add::Error::DestinationExists { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn add_over_missing_registered_worktree_needs_force_and_no_lock() -> crate::Result {
+        let (repo, root, _tmp) = repo()?;
+        let head_id = repo.head_id()?.detach();
+        let path = root.join("wt-deleted");
+
+        assert!(matches!(
+            repo.worktree_add(&path, head_id, Default::default()),
+            Err(add::Error::Registered { .. })
+        ));
+        assert!(!path.exists(), "nothing is left behind on failure");
+
+        proxy(&repo, "wt-deleted").lock("".into())?;
+        let force = add::Options {
+            force: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            repo.worktree_add(&path, head_id, force.clone()),
+            Err(add::Error::RegisteredLocked { .. })
+        ));
+
+        proxy(&repo, "wt-deleted").unlock()?;
+        let wt = repo.worktree_add(&path, head_id, force)?;
+        assert_eq!(
+            wt.worktree().expect("linked").id(),
+            Some("wt-deleted".into()),
+            "the previous registration was removed"
+        );
+        assert_eq!(git(&path, &["status", "--porcelain"])?, "");
+        Ok(())
+    }
+
+    #[test]
+    fn lock_unlock_and_remove() -> crate::Result {
+        let (repo, root, _tmp) = repo()?;
+        let wt = proxy(&repo, "wt-b");
+        wt.lock("on usb".into())?;
+        assert!(matches!(
+            wt.lock("again".into()),
+            Err(proxy::lock::Error::AlreadyLocked { reason }) if reason == "on usb"
+        ));
+        let list = git(&root.join("repo"), &["worktree", "list", "--porcelain"])?;
+        assert!(list.contains("locked on usb\n"), "{list}");
+        assert!(matches!(
+            wt.clone().remove(Default::default()),
+            Err(proxy::remove::Error::Locked)
+        ));
+
+        wt.unlock()?;
+        assert!(matches!(wt.unlock(), Err(proxy::unlock::Error::NotLocked)));
+
+        let base = wt.base()?;
+        std::fs::write(base.join("untracked"), b"content")?;
+        assert!(matches!(
+            wt.clone().remove(Default::default()),
+            Err(proxy::remove::Error::Dirty { .. })
+        ));
+        wt.remove(proxy::remove::Options { force: true })?;
+        assert!(!base.exists());
+        assert!(!repo.common_dir().join("worktrees").join("wt-b").exists());
+
+        let wt = proxy(&repo, "wt-a1");
+        let base = wt.base()?;
+        assert_eq!(base, root.join("prev").join("wt-a"));
+        wt.remove(Default::default())?;
+        assert!(!base.exists(), "clean worktrees can be removed without force");
+
+        let list = git(&root.join("repo"), &["worktree", "list", "--porcelain"])?;
+        assert!(!list.contains("/wt-b\n"), "{list}");
+        Ok(())
+    }
+
+    #[test]
+    fn staged_changes_prevent_removal_without_force() -> crate::Result {
+        let (repo, _root, _tmp) = repo()?;
+        let wt = proxy(&repo, "wt-b");
+        let base = wt.base()?;
+        std::fs::write(base.join("a"), b"staged\n")?;
+        git(&base, &["add", "a"])?;
+        assert_eq!(
+            git(&base, &["status", "--porcelain"])?,
+            "M  a\n",
+            "the change is only in the index, the worktree matches it"
+        );
+
+        assert!(matches!(
+            wt.clone().remove(Default::default()),
+            Err(proxy::remove::Error::Dirty { .. })
+        ));
+        assert!(base.exists(), "nothing was deleted");
+        wt.remove(proxy::remove::Options { force: true })?;
+        assert!(!base.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn prune_removes_missing_worktrees_unless_locked() -> crate::Result {
+        let (repo, root, _tmp) = repo()?;
+        std::fs::remove_dir_all(root.join("wt-b"))?;
+        std::fs::remove_dir_all(root.join("wt-c-locked"))?;
+
+        let expected = vec![
+            prune::Pruned {
+                id: "wt-b".into(),
+                reason: prune::Reason::MissingWorktree,
+            },
+            prune::Pruned {
+                id: "wt-deleted".into(),
+                reason: prune::Reason::MissingWorktree,
+            },
+        ];
+        assert_eq!(repo.prune_worktrees(prune::Options { dry_run: true })?, expected);
+        assert_eq!(repo.worktrees()?.len(), 6, "nothing changed in a dry-run");
+        assert_eq!(repo.prune_worktrees(Default::default())?, expected);
+        assert_eq!(repo.prune_worktrees(Default::default())?, vec![]);
+
+        let list = git(&root.join("repo"), &["worktree", "list", "--porcelain"])?;
+        assert!(list.contains("wt-c-locked"), "locked worktrees are kept: {list}");
+        assert!(!list.contains("prunable"), "{list}");
+        Ok(())
+    }
+
+    #[test]
+    fn relative_gitdir_files_are_resolved_against_the_private_git_dir() -> crate::Result {
+        let (repo, root, _tmp) = repo()?;
+        let wt = proxy(&repo, "wt-b");
+        std::fs::write(wt.git_dir().join("gitdir"), "../../../../wt-b/.git\n")?;
+        assert_eq!(wt.base()?, root.join("wt-b"));
+        assert_eq!(repo.prune_worktrees(Default::default())?.len(), 1, "only wt-deleted");
+        Ok(())
+    }
+}
+
 mod with_core_worktree_config {
     use std::io::BufRead;
 
@@ -129,6 +344,123 @@ mod with_core_worktree_config {
         Ok(())
     }
 
+    #[test]
+    fn elsewhere() -> crate::Result {
+        let repo = repo("deploy.git");
+        let work_dir = repo.git_dir().parent().unwrap().join("elsewhere");
+        assert_eq!(
+            repo.work_dir(),
+            Some(work_dir.as_path()),
+            "core.worktree is relative to the git dir, which doesn't need to be the parent of the worktree"
+        );
+        assert_eq!(repo.kind(), gix::repository::Kind::WorkTree { is_linked: false });
+
+        let work_dir = repo.current_dir().join(work_dir);
+        assert_eq!(
+            repo.work_dir_path("dir/c"),
+            Some(work_dir.join("dir").join("c")),
+            "paths are made absolute"
+        );
+        assert!(repo.work_dir_path("dir/c").expect("present").is_file());
+        assert_eq!(
+            repo.work_dir_relative_path(&work_dir.join("dir").join("c")),
+            Some("dir/c".into())
+        );
+        assert_eq!(
+            repo.work_dir_relative_path(&repo.git_dir().join("config")),
+            None,
+            "paths outside of the worktree can't be made relative"
+        );
+        assert_eq!(
+            bare_repo().work_dir_path("a"),
+            None,
+            "without worktree, there is nothing to resolve against"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "status")]
+    fn elsewhere_status() -> crate::Result {
+        let repo = repo("deploy.git");
+        let mut changes: Vec<_> = repo
+            .status(gix::progress::Discard)?
+            .into_index_worktree_iter(Vec::new())?
+            .map(|item| item.map(|item| item.rela_path().to_owned()))
+            .collect::<Result<_, _>>()?;
+        changes.sort();
+        assert_eq!(changes, ["a"], "the status is computed in the configured worktree");
+        assert_eq!(
+            std::fs::read_to_string(repo.git_dir().join("status.baseline"))?,
+            " M a\n",
+            "git agrees"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "worktree-mutation")]
+    fn elsewhere_checkout() -> crate::Result {
+        let dir = gix_testtools::scripted_fixture_writable("make_core_worktree_repo.sh")?;
+        let repo = gix::open_opts(dir.path().join("deploy.git"), crate::restricted())?;
+        let work_dir = repo.work_dir().expect("configured");
+        std::fs::remove_file(work_dir.join("b"))?;
+
+        let mut index = repo.open_index()?;
+        gix::worktree::state::checkout(
+            &mut index,
+            work_dir,
+            repo.objects.clone().into_arc()?,
+            &gix_features::progress::Discard,
+            &gix_features::progress::Discard,
+            &std::sync::atomic::AtomicBool::default(),
+            gix::worktree::state::checkout::Options {
+                overwrite_existing: true,
+                ..Default::default()
+            },
+        )?;
+        assert!(work_dir.join("b").is_file(), "the file was restored in the worktree");
+        assert!(
+            !dir.path().join("b").exists(),
+            "the parent of the git dir isn't touched"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn environment_takes_precedence() -> crate::Result {
+        let dir =
+            std::env::current_dir()?.join(gix_testtools::scripted_fixture_read_only("make_core_worktree_repo.sh")?);
+        let work_dir = dir.join("worktree");
+        let env = gix_testtools::Env::new()
+            .set("GIT_DIR", dir.join("deploy.git").to_str().expect("valid UTF-8"))
+            .set("GIT_WORK_TREE", work_dir.to_str().expect("valid UTF-8"));
+        let repo: gix::Repository =
+            gix::ThreadSafeRepository::open_with_environment_overrides(".", Default::default())?.into();
+        assert_eq!(
+            repo.work_dir(),
+            Some(work_dir.as_path()),
+            "GIT_WORK_TREE overrides core.worktree"
+        );
+
+        drop(env);
+
+        let _env = gix_testtools::Env::new().set("GIT_WORK_TREE", work_dir.to_str().expect("valid UTF-8"));
+        let repo: gix::Repository =
+            gix::ThreadSafeRepository::discover_with_environment_overrides(dir.join("absolute-worktree"))?.into();
+        assert_eq!(
+            repo.work_dir(),
+            Some(work_dir.as_path()),
+            "discovery also respects the environment"
+        );
+        Ok(())
+    }
+
+    fn bare_repo() -> gix::Repository {
+        repo("bare-relative-worktree")
+    }
+
     fn repo(name: &str) -> gix::Repository {
         let dir = gix_testtools::scripted_fixture_read_only("make_core_worktree_repo.sh").unwrap();
         gix::open_opts(dir.join(name), crate::restricted()).unwrap()
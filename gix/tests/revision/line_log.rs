@@ -0,0 +1,137 @@
+use gix::{
+    bstr::{BString, ByteSlice},
+    revision::line_log::{init, Diff},
+};
+
+use crate::util::named_subrepo_opts;
+
+#[test]
+fn changes_match_git() -> crate::Result {
+    let repo = repo()?;
+    let tip = repo.head_id()?;
+    for (range, lines) in [
+        ("1,4", 0..4),
+        ("5,9", 4..9),
+        ("6,6", 5..6),
+        ("9,9", 8..9),
+        ("10,12", 9..12),
+        ("12,13", 11..13),
+    ] {
+        let actual = repo
+            .line_log(tip, "renamed".into(), lines)?
+            .map(|change| {
+                let change = change?;
+                let commit = change.commit_id.to_string();
+                Ok(match change.diff {
+                    Diff::Merge => Commit {
+                        id: commit,
+                        location: None,
+                        headers: Vec::new(),
+                    },
+                    Diff::Addition => Commit {
+                        id: commit,
+                        location: Some(change.location),
+                        headers: change
+                            .ranges
+                            .iter()
+                            .map(|range| format!("@@ -0,0 +{},{} @@", range.start + 1, range.len()))
+                            .collect(),
+                    },
+                    Diff::Modification { lines, .. } => Commit {
+                        id: commit,
+                        location: Some(change.location),
+                        headers: lines
+                            .iter()
+                            .map(|lines| {
+                                format!(
+                                    "@@ -{},{} +{},{} @@",
+                                    lines.old.start + 1,
+                                    lines.old.len(),
+                                    lines.new.start + 1,
+                                    lines.new.len()
+                                )
+                            })
+                            .collect(),
+                    },
+                })
+            })
+            .collect::<Result<Vec<_>, gix::revision::line_log::Error>>()?;
+        let expected = parse_baseline(&std::fs::read(
+            repo.work_dir().expect("non-bare").join(format!("baseline-{range}")),
+        )?);
+        assert_eq!(actual, expected, "-L {range}:renamed");
+    }
+    Ok(())
+}
+
+#[test]
+fn renames_are_followed() -> crate::Result {
+    let repo = repo()?;
+    let changes = repo
+        .line_log(repo.head_id()?, "renamed".into(), 0..2)?
+        .collect::<Result<Vec<_>, _>>()?;
+    let root = changes.last().expect("at least the root commit");
+    assert_eq!(
+        root.diff,
+        Diff::Addition,
+        "the tracking ends where the lines were added"
+    );
+    assert_eq!(root.location, "file", "the file had a different name back then");
+    Ok(())
+}
+
+#[test]
+fn invalid_input_is_rejected() -> crate::Result {
+    let repo = repo()?;
+    let tip = repo.head_id()?;
+    assert!(matches!(
+        repo.line_log(tip, "missing".into(), 0..1),
+        Err(init::Error::FileNotFound { .. })
+    ));
+    for lines in [5..5, 10..100] {
+        assert!(
+            matches!(
+                repo.line_log(tip, "renamed".into(), lines.clone()),
+                Err(init::Error::InvalidRange { .. })
+            ),
+            "{lines:?} is invalid"
+        );
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Commit {
+    id: String,
+    location: Option<BString>,
+    headers: Vec<String>,
+}
+
+fn parse_baseline(baseline: &[u8]) -> Vec<Commit> {
+    let mut out = Vec::<Commit>::new();
+    for line in baseline.lines() {
+        if line.len() == 40 && line.iter().all(u8::is_ascii_hexdigit) {
+            out.push(Commit {
+                id: line.to_str_lossy().into_owned(),
+                location: None,
+                headers: Vec::new(),
+            });
+        } else if let Some(location) = line.strip_prefix(b"+++ b/") {
+            out.last_mut().expect("commit before diff").location = Some(location.into());
+        } else if line.starts_with(b"@@ ") {
+            out.last_mut()
+                .expect("commit before hunk")
+                .headers
+                .push(line.to_str_lossy().into_owned());
+        }
+    }
+    out
+}
+
+fn repo() -> crate::Result<gix::Repository> {
+    Ok(named_subrepo_opts(
+        "make_line_log_repo.sh",
+        "repo",
+        gix::open::Options::isolated(),
+    )?)
+}
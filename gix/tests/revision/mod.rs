@@ -1 +1,3 @@
+#[cfg(feature = "blob-diff")]
+mod line_log;
 mod spec;
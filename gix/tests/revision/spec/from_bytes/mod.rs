@@ -10,6 +10,7 @@ mod util;
 mod reflog;
 mod traverse;
 
+mod path;
 mod peel;
 
 mod sibling_branch {
@@ -0,0 +1,94 @@
+use crate::revision::spec::from_bytes::{parse_spec, repo};
+
+#[test]
+fn tree_paths_match_git() -> crate::Result {
+    let repo = repo("paths")?;
+    for spec in [
+        "@:",
+        "@:a",
+        "@:dir",
+        "@:dir/b",
+        "@:dir/sub/c",
+        "@:./a",
+        "@:./dir/sub/c",
+        "@:./dir/../a",
+    ] {
+        parse_spec(spec, &repo)?;
+    }
+
+    assert_eq!(
+        parse_spec("@:./dir/sub/c", &repo)?.path_and_mode().expect("set").0,
+        "dir/sub/c",
+        "relative paths are resolved against the current directory, which is the root here"
+    );
+    assert_eq!(
+        parse_spec("@:./", &repo)?,
+        parse_spec("@:", &repo)?,
+        "a relative path to the root is the root tree itself"
+    );
+    Ok(())
+}
+
+#[test]
+fn missing_tree_paths_are_distinguished_from_missing_revisions() -> crate::Result {
+    let repo = repo("paths")?;
+    let err = parse_spec("@:dir/missing", &repo).unwrap_err();
+    assert!(
+        matches!(err, gix::revision::spec::parse::Error::PathNotFound { ref path, .. } if path == "dir/missing"),
+        "{err:?}"
+    );
+    assert!(
+        err.to_string()
+            .starts_with("Could not find path \"dir/missing\" in tree "),
+        "the tree and the object it was obtained from are named separately: {err}"
+    );
+
+    let err = parse_spec("@~1:a", &repo).unwrap_err();
+    assert!(
+        !matches!(err, gix::revision::spec::parse::Error::PathNotFound { .. }),
+        "the revision isn't found, so the path isn't even looked at: {err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn relative_paths_cannot_leave_the_worktree() -> crate::Result {
+    let repo = repo("paths")?;
+    for spec in ["@:../a", ":../dir/b"] {
+        let err = parse_spec(spec, &repo).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                gix::revision::spec::parse::Error::RelativePathOutsideOfWorktree { .. }
+            ),
+            "{spec}: {err:?}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn index_paths_match_git() -> crate::Result {
+    let repo = repo("paths")?;
+    let expected = parse_spec(":dir/b", &repo)?;
+    assert_ne!(
+        expected,
+        parse_spec("@:dir/b", &repo)?,
+        "the index has a different version of the file than HEAD"
+    );
+    assert_eq!(parse_spec(":0:dir/b", &repo)?, expected);
+    let relative = parse_spec(":./dir/b", &repo)?;
+    assert_eq!(relative, expected);
+    assert_eq!(
+        relative.path_and_mode().expect("set").0,
+        "dir/b",
+        "the resolved path is made available"
+    );
+
+    let err = parse_spec(":2:dir/b", &repo).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Path \"dir/b\" did not exist in index at stage 2. It does exist at stage 0. It exists on disk"
+    );
+    Ok(())
+}
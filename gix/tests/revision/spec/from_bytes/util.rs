@@ -9,11 +9,13 @@ use gix_testtools::once_cell::sync::Lazy;
 const FIXTURE_NAME: &str = "make_rev_spec_parse_repos.sh";
 static BASELINE: Lazy<HashMap<PathBuf, HashMap<BString, Option<gix_revision::Spec>>>> = Lazy::new(|| {
     fn kind_of(spec: &BStr) -> gix_revision::spec::Kind {
+        // Paths like `@:../a` may contain dots as well, but they can't affect the kind.
+        let revision = spec.find_byte(b':').map_or(spec.as_bytes(), |pos| &spec[..pos]);
         if spec.starts_with(b"^") {
             gix_revision::spec::Kind::IncludeReachable
-        } else if spec.contains_str(b"...") {
+        } else if revision.contains_str(b"...") {
             gix_revision::spec::Kind::ReachableToMergeBase
-        } else if spec.contains_str(b"..") {
+        } else if revision.contains_str(b"..") {
             gix_revision::spec::Kind::RangeBetween
         } else if spec.ends_with(b"^!") {
             gix_revision::spec::Kind::ExcludeReachableFromParents
@@ -20,13 +20,14 @@ mod index_worktree {
     mod iter {
         use crate::status::{repo, submodule_repo};
         use gix::status::index_worktree::iter::Item;
+        use gix::status::plumbing::index_as_worktree::{Change, EntryStatus};
         use pretty_assertions::assert_eq;
 
         #[test]
         fn item_size() {
             assert_eq!(
                 std::mem::size_of::<gix::status::index_worktree::iter::Item>(),
-                264,
+                320,
                 "The size is pretty huge and goes down ideally"
             );
         }
@@ -43,6 +44,45 @@ mod index_worktree {
                 .into_index_worktree_iter(Vec::new())?;
             let items: Vec<_> = status.by_ref().filter_map(Result::ok).collect();
             assert_eq!(items.len(), 3, "1 untracked, 1 modified file, 1 submodule modification");
+
+            let submodule_status = items
+                .iter()
+                .find_map(|item| match item {
+                    Item::Modification {
+                        status: EntryStatus::Change(Change::SubmoduleModification(status)),
+                        ..
+                    } => Some(status),
+                    _ => None,
+                })
+                .expect("the submodule is modified");
+            assert_eq!(
+                submodule_status.dirtiness(),
+                Some(gix::submodule::Dirtiness {
+                    new_commits: true,
+                    modified_content: true,
+                    untracked_content: false,
+                }),
+                "the submodule was checked out at a different commit, and a file was modified"
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn submodule_modification_ignored_by_diff_configuration() -> crate::Result {
+            let repo = crate::util::named_subrepo_opts(
+                "make_submodules.sh",
+                "modified-untracked-and-submodule-head-changed-and-modified",
+                gix::open::Options::isolated().config_overrides(["diff.ignoreSubmodules=all"]),
+            )?;
+            let status = repo
+                .status(gix::progress::Discard)?
+                .into_index_worktree_iter(Vec::new())?;
+            let items: Vec<_> = status.filter_map(Result::ok).collect();
+            assert_eq!(
+                items.len(),
+                2,
+                "1 untracked, 1 modified file, as `diff.ignoreSubmodules` overrides the submodule configuration"
+            );
             Ok(())
         }
 
@@ -128,6 +168,37 @@ mod index_worktree {
             assert!(is_dirty, "this should abort the work as quickly as possible");
             Ok(())
         }
+
+        #[test]
+        fn big_file_threshold_skips_content_checks_of_changed_files() -> crate::Result {
+            let tmp = gix_testtools::scripted_fixture_writable("make_status_repos.sh")?;
+            let work_dir = tmp.path().join("untracked-only");
+            let file = work_dir.join("this");
+            std::fs::write(&file, "content")?;
+            let repo = gix::open_opts(&work_dir, gix::open::Options::isolated())?;
+            let mut index = repo.open_index()?;
+            let idx = repo.add_to_index(&mut index, "this".into(), gix::repository::add_to_index::Mode::Content)?;
+            index.entries_mut()[idx].stat.mtime = Default::default();
+            index.write(Default::default())?;
+
+            for (overrides, expected) in [(&[][..], 0), (&["core.bigFileThreshold=1"][..], 1)] {
+                let repo = gix::open_opts(
+                    &work_dir,
+                    gix::open::Options::isolated().config_overrides(overrides.iter().copied()),
+                )?;
+                let modifications = repo
+                    .status(gix::progress::Discard)?
+                    .into_index_worktree_iter(Vec::new())?
+                    .filter_map(Result::ok)
+                    .filter(|item| matches!(item, Item::Modification { rela_path, .. } if rela_path == "this"))
+                    .count();
+                assert_eq!(
+                    modifications, expected,
+                    "{overrides:?}: big files whose stat changed are considered modified without looking at them"
+                );
+            }
+            Ok(())
+        }
     }
 }
 
@@ -172,3 +243,174 @@ mod is_dirty {
         Ok(())
     }
 }
+
+mod intent_to_add {
+    use gix::bstr::{BString, ByteSlice};
+    use gix::index::entry::{Flags, Mode};
+    use gix::repository::add_to_index;
+    use gix::status::index_worktree::iter::Summary;
+    use gix_testtools::tempfile::TempDir;
+    use pretty_assertions::assert_eq;
+
+    fn writable(overrides: &[&str]) -> crate::Result<(gix::Repository, TempDir)> {
+        let tmp = gix_testtools::scripted_fixture_writable("make_intent_to_add_repos.sh")?;
+        let repo = gix::open_opts(
+            tmp.path().join("base"),
+            gix::open::Options::isolated().config_overrides(overrides.iter().copied()),
+        )?;
+        Ok((repo, tmp))
+    }
+
+    fn git_repo(tmp: &TempDir, name: &str) -> crate::Result<gix::Repository> {
+        Ok(gix::open_opts(tmp.path().join(name), gix::open::Options::isolated())?)
+    }
+
+    /// Return `(path, mode, id, intent-to-add-flags)` for each entry in `index`.
+    fn entries(index: &gix::index::File) -> Vec<(BString, Mode, gix::ObjectId, Flags)> {
+        index
+            .entries()
+            .iter()
+            .map(|e| {
+                (
+                    e.path(index).to_owned(),
+                    e.mode,
+                    e.id,
+                    e.flags & (Flags::INTENT_TO_ADD | Flags::EXTENDED),
+                )
+            })
+            .collect()
+    }
+
+    /// Return `(path, worktree-mode)` of all lines in the `git status --porcelain=v2` output stored in `repo`
+    /// which denote intent-to-add entries.
+    fn git_intent_to_add_status(repo: &gix::Repository) -> crate::Result<Vec<(BString, String)>> {
+        let status = std::fs::read(repo.git_dir().join("status-porcelain-v2"))?;
+        Ok(status
+            .lines()
+            .filter_map(|line| line.strip_prefix(b"1 .A N... 000000 000000 "))
+            .map(|line| {
+                let mut tokens = line.splitn(4, |b| *b == b' ');
+                let mode = tokens
+                    .next()
+                    .expect("worktree mode")
+                    .to_str()
+                    .expect("ascii")
+                    .to_owned();
+                let path = tokens.nth(2).expect("path").into();
+                (path, mode)
+            })
+            .collect())
+    }
+
+    /// Return `(path, mode)` of all items classified as intent-to-add by our status, along with the mode recorded in the index.
+    fn intent_to_add_status(repo: &gix::Repository) -> crate::Result<Vec<(BString, String)>> {
+        let index = repo.index()?;
+        Ok(repo
+            .status(gix::progress::Discard)?
+            .index_worktree_options_mut(|opts| {
+                opts.sorting = Some(gix::status::plumbing::index_as_worktree_with_renames::Sorting::ByPathCaseSensitive)
+            })
+            .into_index_worktree_iter(Vec::new())?
+            .filter_map(Result::ok)
+            .filter(|item| item.summary() == Some(Summary::IntentToAdd))
+            .map(|item| {
+                let path = item.rela_path().to_owned();
+                let mode = index.entry_by_path(path.as_ref()).expect("tracked").mode;
+                (path, format!("{:06o}", mode.bits()))
+            })
+            .collect())
+    }
+
+    #[test]
+    fn intent_to_add_entries_match_git() -> crate::Result {
+        let (repo, tmp) = writable(&[])?;
+        let mut index = repo.open_index()?;
+        for path in ["new", "new-exe", "tracked"] {
+            repo.add_to_index(&mut index, path.into(), add_to_index::Mode::IntentToAdd)?;
+        }
+        let expected = git_repo(&tmp, "intent-to-add")?;
+        assert_eq!(
+            entries(&index),
+            entries(&expected.open_index()?),
+            "entries point to the empty blob and tracked files are left alone"
+        );
+        let ita = index.entry_by_path("new".into()).expect("present");
+        assert_eq!(
+            ita.stat,
+            Default::default(),
+            "git doesn't record stat information either"
+        );
+
+        index.write(Default::default())?;
+        let actual = intent_to_add_status(&repo)?;
+        assert_eq!(actual, git_intent_to_add_status(&expected)?);
+        assert_eq!(actual.len(), 2, "both new files are intended to be added");
+        Ok(())
+    }
+
+    #[test]
+    fn adding_content_collapses_intent_to_add() -> crate::Result {
+        let (repo, tmp) = writable(&[])?;
+        let mut index = repo.open_index()?;
+        for path in ["new", "new-exe"] {
+            repo.add_to_index(&mut index, path.into(), add_to_index::Mode::IntentToAdd)?;
+        }
+        repo.add_to_index(&mut index, "new-exe".into(), add_to_index::Mode::Content)?;
+
+        let expected = git_repo(&tmp, "intent-to-add-then-added")?;
+        assert_eq!(entries(&index), entries(&expected.open_index()?));
+        assert_ne!(
+            index.entry_by_path("new-exe".into()).expect("present").stat,
+            Default::default(),
+            "real entries have stat information"
+        );
+
+        index.write(Default::default())?;
+        let actual = intent_to_add_status(&repo)?;
+        assert_eq!(actual, git_intent_to_add_status(&expected)?);
+        assert_eq!(actual.len(), 1, "only one file is still intended to be added");
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn executable_bit_without_core_file_mode() -> crate::Result {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (repo, _tmp) = writable(&["core.fileMode=false"])?;
+        let mut index = repo.open_index()?;
+        let idx = repo.add_to_index(&mut index, "new-exe".into(), add_to_index::Mode::IntentToAdd)?;
+        assert_eq!(
+            index.entry(idx).mode,
+            Mode::FILE,
+            "new files are never executable if the executable bit can't be trusted"
+        );
+        let idx = repo.add_to_index(&mut index, "new-exe".into(), add_to_index::Mode::Content)?;
+        assert_eq!(
+            index.entry(idx).mode,
+            Mode::FILE,
+            "the mode of the intent-to-add entry is retained"
+        );
+
+        let tracked_exe = repo.work_dir().expect("non-bare").join("tracked-exe");
+        std::fs::set_permissions(&tracked_exe, std::fs::Permissions::from_mode(0o644))?;
+        let idx = repo.add_to_index(&mut index, "tracked-exe".into(), add_to_index::Mode::Content)?;
+        assert_eq!(
+            index.entry(idx).mode,
+            Mode::FILE_EXECUTABLE,
+            "the executable bit of tracked files is retained"
+        );
+
+        let (repo, _tmp) = writable(&[])?;
+        let mut index = repo.open_index()?;
+        let tracked_exe = repo.work_dir().expect("non-bare").join("tracked-exe");
+        std::fs::set_permissions(&tracked_exe, std::fs::Permissions::from_mode(0o644))?;
+        let idx = repo.add_to_index(&mut index, "tracked-exe".into(), add_to_index::Mode::Content)?;
+        assert_eq!(
+            index.entry(idx).mode,
+            Mode::FILE,
+            "with core.fileMode, the filesystem is authoritative"
+        );
+        Ok(())
+    }
+}
@@ -154,6 +154,13 @@ mod open {
                 Some(Vec::new()),
                 "the status check ran, but there were no changes"
             );
+            assert_eq!(
+                status.dirtiness(),
+                Some(gix::submodule::Dirtiness {
+                    new_commits: true,
+                    ..Default::default()
+                })
+            );
             // make it easier to compare this as baseline
             status.changes.take();
 
@@ -228,6 +235,14 @@ mod open {
                 status_with_dirty_check, status,
                 "it cannot abort early as the only change it sees is the modification check"
             );
+            assert_eq!(
+                status.dirtiness(),
+                Some(gix::submodule::Dirtiness {
+                    new_commits: false,
+                    modified_content: true,
+                    untracked_content: true,
+                })
+            );
 
             let status = sm.status(gix::submodule::config::Ignore::Untracked, false)?;
             assert_eq!(
@@ -237,6 +252,15 @@ mod open {
             );
             assert_eq!(status.index_id, status.checked_out_head_id, "the head didn't change");
             assert_eq!(status.changes.as_ref().into_iter().flatten().count(), 1, "1 modified");
+            assert_eq!(
+                status.dirtiness(),
+                Some(gix::submodule::Dirtiness {
+                    new_commits: false,
+                    modified_content: true,
+                    untracked_content: false,
+                }),
+                "untracked files aren't looked for"
+            );
 
             Ok(())
         }
@@ -342,3 +366,85 @@ mod open {
         Ok(())
     }
 }
+
+mod resolved_url {
+    use gix::submodule::resolved_url::Error;
+
+    fn submodule_urls(name: &str) -> crate::Result<Vec<(String, Result<String, Error>)>> {
+        let repo =
+            crate::util::named_subrepo_opts("make_submodule_relative_urls.sh", name, gix::open::Options::isolated())?;
+        let urls = repo
+            .submodules()?
+            .expect("modules present")
+            .map(|sm| {
+                (
+                    sm.name().to_string(),
+                    sm.resolved_url().map(|url| url.to_bstring().to_string()),
+                )
+            })
+            .collect();
+        Ok(urls)
+    }
+
+    #[test]
+    fn relative_urls_are_resolved_against_the_default_remote_and_rewritten() -> crate::Result {
+        let urls = submodule_urls("with-remote")?;
+        let urls: Vec<_> = urls
+            .into_iter()
+            .map(|(name, url)| (name, url.expect("valid")))
+            .collect();
+        assert_eq!(
+            urls,
+            [
+                ("relative".into(), "https://mirror.example.com/lib.git".into()),
+                (
+                    "nested-relative".into(),
+                    "https://mirror.example.com/super.git/sub/lib".into()
+                ),
+                ("absolute".into(), "https://example.com/absolute.git".into()),
+            ],
+            "urls are resolved against the original remote url, and rewritten afterwards"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn scp_like_remote_urls() -> crate::Result {
+        let urls: Vec<_> = submodule_urls("scp-remote")?
+            .into_iter()
+            .map(|(_, url)| url.expect("valid"))
+            .collect();
+        assert_eq!(
+            urls,
+            [
+                "git@example.com:org/lib.git",
+                "git@example.com:org/super/sub/lib",
+                "https://example.com/absolute.git"
+            ],
+            "the only remote is used even if it's not called 'origin'"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn relative_urls_without_remote_are_an_error() -> crate::Result {
+        let urls = submodule_urls("without-remote")?;
+        assert!(matches!(
+            &urls[0].1,
+            Err(Error::Url(
+                gix::submodule::config::url::Error::MissingSuperprojectUrl { .. }
+            ))
+        ));
+        assert!(matches!(
+            &urls[1].1,
+            Err(Error::Url(
+                gix::submodule::config::url::Error::MissingSuperprojectUrl { .. }
+            ))
+        ));
+        assert_eq!(
+            urls[2].1.as_ref().expect("absolute urls don't need a remote"),
+            "https://example.com/absolute.git"
+        );
+        Ok(())
+    }
+}
@@ -70,22 +70,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "core.hideDotFiles",
         usage: Planned("Seems useful, but needs demand from windows users")
     },
-    Record {
-        config: "core.packedGitWindowSize",
-        usage: NotPlanned("an optimization for handling many large packs more efficiently seems unnecessary")
-    },
-    Record {
-        config: "core.packedGitLimit",
-        usage: NotApplicable("we target 32bit systems only and don't use a windowing mechanism")
-    },
-    Record {
-        config: "core.alternateRefsCommand",
-        usage: NotPlanned("there is no need as we can perform the required operation in-binary. This could happen though if there is a use-case and demand.")
-    },
-    Record {
-        config: "core.alternateRefsPrefixes",
-        usage: NotPlanned("seems like a niche feature, but can be implemented if there is demand")
-    },
     Record {
         config: "core.compression",
         usage: Planned("Allow to remove similar hardcoded value - passing it through will be some effort")
@@ -130,18 +114,10 @@ static GIT_CONFIG: &[Record] = &[
         config: "core.preloadIndex",
         usage: Planned("it's enabled by default and allows parallel stat checks - it's using a lot of CPU for just minor performance boosts though")
     },
-    Record {
-        config: "commitGraph.generationVersion",
-        usage: NotPlanned("couldn't find a test that would require corrected generation numbers, even `git` has no test for this.")
-    },
     Record {
         config: "commitGraph.maxNewFilters",
         usage: NotPlanned("can be considered when the underlying feature is actually used or needed")
     },
-    Record {
-        config: "commitGraph.readChangedPaths",
-        usage: NotPlanned("can be considered when the underlying feature is actually used or needed")
-    },
     Record {
         config: "index.sparse",
         usage: Planned("we can read sparse indices and support for it will be added early on")
@@ -237,14 +213,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "fetch.unpackLimit",
         usage: Planned("")
     },
-    Record {
-        config: "fetch.prune",
-        usage: Planned("")
-    },
-    Record {
-        config: "fetch.pruneTags",
-        usage: Planned("")
-    },
     Record {
         config: "fetch.writeCommitGraph",
         usage: Planned("")
@@ -278,10 +246,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "core.sparseCheckoutCone",
         usage: Planned("non-cone mode is deprecated but should still fail gracefully if encountered")
     },
-    Record {
-        config: "core.splitIndex",
-        usage: NotPlanned("we don't want to be able to create split indices, but we will read them. It's (somewhat) superseded by sparse indices")
-    },
     Record {
         config: "splitIndex.maxPercentageChange",
         usage: NotPlanned("seems like it's superseded by sparse indices")
@@ -406,10 +370,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "sparse.expectFilesOutsideOfPatterns",
         usage: NotPlanned("todo")
     },
-    Record {
-        config: "remote.<name>.promisor",
-        usage: Planned("required for big monorepos, and typically used in conjunction with sparse indices")
-    },
     Record {
         config: "remote.<name>.partialCloneFilter",
         usage: Planned("required for big monorepos, and typically used in conjunction with sparse indices")
@@ -434,10 +394,6 @@ static GIT_CONFIG: &[Record] = &[
         config: "transfer.credentialsInUrl",
         usage: Planned("currently we are likely to expose passwords in errors or in other places, and it's better to by default not do that")
     },
-    Record {
-        config: "diff.*.cachetextconv",
-        usage: NotPlanned("It seems to slow to do that, and persisting results to save a relatively cheap computation doesn't seem right")
-    },
 ];
 
 /// A programmatic way to record and display progress.
@@ -70,6 +70,8 @@ pub mod pretty {
 
         match (verbose, progress) {
             (false, false) => {
+                #[cfg(feature = "tracing")]
+                gix::trace::git_trace::init_from_env();
                 let stdout = stdout();
                 let mut stdout_lock = stdout.lock();
                 let stderr = stderr();
@@ -102,6 +104,9 @@ pub mod pretty {
         reverse_lines: bool,
         progress: &gix::progress::prodash::tree::Root,
     ) -> anyhow::Result<()> {
+        if !enable && gix::trace::git_trace::init_from_env() {
+            return Ok(());
+        }
         if enable {
             let processor = tracing_forest::Printer::new().formatter({
                 let progress = std::sync::Mutex::new(progress.add_child("tracing"));